@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# ═══════════════════════════════════════════════════════════════════════
+# Regenerate the bundled Node.js SHA-256 manifest
+#
+# Fetches nodejs.org's SHASUMS256.txt for the NODE_VERSION pinned in
+# node_provision.rs and keeps only the archives the app downloads.
+# Run this whenever NODE_VERSION is bumped and commit the result.
+#
+# Usage:
+#   ./scripts/update-node-shasums.sh
+#
+# Requirements: curl
+# ═══════════════════════════════════════════════════════════════════════
+
+set -euo pipefail
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+SRC="$ROOT/src-tauri/src/engine/n8n_engine/node_provision.rs"
+OUT="$ROOT/src-tauri/src/engine/n8n_engine/node-shasums256.txt"
+
+VERSION="$(sed -n 's/^const NODE_VERSION: &str = "\(.*\)";$/\1/p' "$SRC")"
+if [ -z "$VERSION" ]; then
+  echo "Could not find NODE_VERSION in $SRC" >&2
+  exit 1
+fi
+
+SUMS="$(curl -fsSL "https://nodejs.org/dist/v${VERSION}/SHASUMS256.txt")"
+
+{
+  echo "# SHA-256 of the Node.js v${VERSION} archives downloaded by node_provision.rs."
+  echo "# Generated by scripts/update-node-shasums.sh from nodejs.org SHASUMS256.txt — do not edit."
+  echo "$SUMS" | grep -E "  node-v${VERSION}-(darwin-arm64|darwin-x64|linux-arm64|linux-x64)\.tar\.gz$|  node-v${VERSION}-win-x64\.zip$"
+} > "$OUT"
+
+echo "Wrote $(grep -vc '^#' "$OUT") checksums for Node.js v${VERSION} to $OUT"
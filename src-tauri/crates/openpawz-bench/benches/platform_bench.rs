@@ -353,6 +353,7 @@ fn make_project(id: &str, agent_count: usize) -> Project {
                 capabilities: vec!["read_file".into(), "execute_command".into()],
             })
             .collect(),
+        budget_usd: 0.0,
         created_at: ts.clone(),
         updated_at: ts,
     }
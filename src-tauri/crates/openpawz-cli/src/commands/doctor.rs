@@ -114,28 +114,36 @@ fn check_db(store: &SessionStore) -> HealthCheck {
 }
 
 fn check_key_vault() -> HealthCheck {
-    if key_vault::is_loaded() {
-        HealthCheck {
+    if !key_vault::is_loaded() {
+        key_vault::prefetch();
+    }
+    if !key_vault::is_loaded() {
+        return HealthCheck {
+            name: "Key Vault".into(),
+            status: "warn".into(),
+            message: "Key vault not loaded — encryption may be unavailable".into(),
+        };
+    }
+    let status = key_vault::status();
+    match status.backend {
+        key_vault::VaultBackend::Keychain => HealthCheck {
             name: "Key Vault".into(),
             status: "ok".into(),
             message: "OS keychain accessible — encryption keys loaded".into(),
-        }
-    } else {
-        // Try to prefetch
-        key_vault::prefetch();
-        if key_vault::is_loaded() {
-            HealthCheck {
-                name: "Key Vault".into(),
-                status: "ok".into(),
-                message: "OS keychain accessible (loaded on demand)".into(),
-            }
-        } else {
-            HealthCheck {
-                name: "Key Vault".into(),
-                status: "warn".into(),
-                message: "OS keychain not loaded — encryption may be unavailable".into(),
-            }
-        }
+        },
+        key_vault::VaultBackend::File if status.passphrase_protected => HealthCheck {
+            name: "Key Vault".into(),
+            status: "ok".into(),
+            message: "Encrypted file store (passphrase-protected) — OS keychain unavailable".into(),
+        },
+        key_vault::VaultBackend::File => HealthCheck {
+            name: "Key Vault".into(),
+            status: "warn".into(),
+            message: format!(
+                "Encrypted file store bound to this machine — set {} for stronger protection",
+                key_vault::file_store::PASSPHRASE_ENV
+            ),
+        },
     }
 }
 
@@ -12,6 +12,7 @@ pub mod session;
 pub mod setup;
 pub mod status;
 pub mod task;
+pub mod vault;
 
 use crate::OutputFormat;
 
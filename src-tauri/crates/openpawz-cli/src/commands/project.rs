@@ -18,6 +18,9 @@ pub enum ProjectAction {
         /// Boss (orchestrator) agent ID
         #[arg(long)]
         boss: String,
+        /// Hard spending limit in USD (0 = none)
+        #[arg(long, default_value_t = 0.0)]
+        budget: f64,
     },
     /// Show project details and team
     Get {
@@ -110,7 +113,12 @@ pub fn run(
             }
             Ok(())
         }
-        ProjectAction::Create { title, goal, boss } => {
+        ProjectAction::Create {
+            title,
+            goal,
+            boss,
+            budget,
+        } => {
             let id = uuid::Uuid::new_v4()
                 .to_string()
                 .split('-')
@@ -134,6 +142,7 @@ pub fn run(
                     system_prompt: None,
                     capabilities: Vec::new(),
                 }],
+                budget_usd: budget,
                 created_at: now.clone(),
                 updated_at: now,
             };
@@ -0,0 +1,79 @@
+use crate::OutputFormat;
+use clap::{Subcommand, ValueEnum};
+use openpawz_core::engine::key_vault::{self, VaultBackend};
+
+#[derive(Subcommand)]
+pub enum VaultAction {
+    /// Show which backend stores encryption keys (OS keychain or encrypted file)
+    Status,
+    /// Move all keys to another backend and remove them from the current one
+    Migrate {
+        /// Target backend
+        #[arg(value_enum)]
+        to: BackendArg,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum BackendArg {
+    Keychain,
+    File,
+}
+
+pub fn run(action: VaultAction, format: &OutputFormat) -> Result<(), String> {
+    match action {
+        VaultAction::Status => {
+            let status = key_vault::status();
+            match format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&status).map_err(|e| e.to_string())?
+                ),
+                OutputFormat::Quiet => println!(
+                    "{}",
+                    match status.backend {
+                        VaultBackend::Keychain => "keychain",
+                        VaultBackend::File => "file",
+                    }
+                ),
+                OutputFormat::Human => match status.backend {
+                    VaultBackend::Keychain => println!("\x1b[32m●\x1b[0m Key vault: OS keychain"),
+                    VaultBackend::File => {
+                        println!(
+                            "\x1b[33m●\x1b[0m Key vault: encrypted file {}",
+                            status.file_path.unwrap_or_default()
+                        );
+                        if status.passphrase_protected {
+                            println!("  Protected by {}", key_vault::file_store::PASSPHRASE_ENV);
+                        } else {
+                            println!(
+                                "  Key bound to this machine — set {} for stronger protection",
+                                key_vault::file_store::PASSPHRASE_ENV
+                            );
+                        }
+                        if let Some(e) = status.keychain_error {
+                            println!("  Keychain: {}", e);
+                        }
+                    }
+                },
+            }
+            Ok(())
+        }
+        VaultAction::Migrate { to } => {
+            let target = match to {
+                BackendArg::Keychain => VaultBackend::Keychain,
+                BackendArg::File => VaultBackend::File,
+            };
+            let count = key_vault::migrate_to(target)?;
+            match format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::json!({ "backend": target, "keys": count })
+                ),
+                OutputFormat::Quiet => {}
+                OutputFormat::Human => println!("Migrated {} keys to {:?}", count, target),
+            }
+            Ok(())
+        }
+    }
+}
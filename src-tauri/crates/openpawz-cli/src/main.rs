@@ -107,6 +107,11 @@ enum Commands {
         #[command(subcommand)]
         action: commands::providers::ProvidersAction,
     },
+    /// Encryption key storage (status, migrate between keychain and file)
+    Vault {
+        #[command(subcommand)]
+        action: commands::vault::VaultAction,
+    },
     /// Engine status and diagnostics
     Status,
     /// Comprehensive health check
@@ -158,6 +163,7 @@ async fn main() {
         Commands::Engram { action } => commands::engram::run(&store, action, &cli.output),
         Commands::Metrics { action } => commands::metrics::run(&store, action, &cli.output),
         Commands::Providers { action } => commands::providers::run(action, &cli.output),
+        Commands::Vault { action } => commands::vault::run(action, &cli.output),
         Commands::Status => commands::status::run(&store, &cli.output),
         Commands::Doctor => commands::doctor::run(&store, &cli.output),
         Commands::Bench { action } => commands::bench::run(&store, action, &cli.output),
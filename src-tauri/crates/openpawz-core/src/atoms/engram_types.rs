@@ -652,6 +652,9 @@ pub struct MemorySearchConfig {
     pub rerank_enabled: bool,
     /// Which reranking strategy to use.
     pub rerank_strategy: RerankStrategy,
+    /// Model used by `RerankStrategy::CrossEncoder`.
+    #[serde(default)]
+    pub reranker: RerankerConfig,
     /// Hybrid search text-boost configuration.
     pub hybrid: HybridSearchConfig,
 }
@@ -666,11 +669,39 @@ impl Default for MemorySearchConfig {
             similarity_threshold: 0.3,
             rerank_enabled: true,
             rerank_strategy: RerankStrategy::default(),
+            reranker: RerankerConfig::default(),
             hybrid: HybridSearchConfig::default(),
         }
     }
 }
 
+/// Per-session context inclusion controls.
+/// Lets users shrink or debug what ContextBuilder assembles without
+/// editing agent files. The default includes everything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ContextControls {
+    /// Inject recalled memories, today's notes and working memory.
+    pub include_memories: bool,
+    /// Inject soul files (IDENTITY.md, USER.md, …).
+    pub include_soul_files: bool,
+    /// Only inject instructions for these skill ids. `None` = all enabled skills.
+    pub skill_ids: Option<Vec<String>>,
+    /// Keep at most this many past messages. `None` = budget-limited only.
+    pub max_history_messages: Option<usize>,
+}
+
+impl Default for ContextControls {
+    fn default() -> Self {
+        Self {
+            include_memories: true,
+            include_soul_files: true,
+            skill_ids: None,
+            max_history_messages: None,
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SECTION 5: Model Capabilities
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1228,8 +1259,9 @@ pub enum RerankStrategy {
     #[default]
     RRFThenMMR,
 
-    /// Cross-encoder reranking using a lightweight local model.
-    /// Most accurate but requires Ollama. Falls back to RRF if unavailable.
+    /// Model-based reranking (see `RerankerConfig`): a local Ollama model
+    /// or a hosted rerank API. Most accurate; falls back to RRFThenMMR when
+    /// the model errors or misses its latency budget.
     CrossEncoder,
 }
 
@@ -1244,6 +1276,50 @@ impl std::fmt::Display for RerankStrategy {
     }
 }
 
+/// Where `RerankStrategy::CrossEncoder` gets its relevance scores.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RerankerBackend {
+    /// Local Ollama model, prompted to score each passage.
+    #[default]
+    Ollama,
+    /// Cohere `/v2/rerank`.
+    Cohere,
+    /// Voyage AI `/v1/rerank`.
+    Voyage,
+}
+
+/// Model reranker settings, part of the per-call search config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RerankerConfig {
+    pub backend: RerankerBackend,
+    /// Model name. Empty = backend default (e.g. `rerank-v3.5`, `rerank-2`).
+    pub model: String,
+    /// Override the backend's endpoint (e.g. a remote Ollama or a proxy).
+    pub base_url: Option<String>,
+    /// API key for hosted backends.
+    pub api_key: String,
+    /// Give up on the model and use the heuristic ranking after this long.
+    pub latency_budget_ms: u64,
+    /// Only the top N candidates are sent to the model; the rest keep
+    /// their heuristic order after them.
+    pub top_n: usize,
+}
+
+impl Default for RerankerConfig {
+    fn default() -> Self {
+        Self {
+            backend: RerankerBackend::default(),
+            model: String::new(),
+            base_url: None,
+            api_key: String::new(),
+            latency_budget_ms: 1_500,
+            top_n: 20,
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SECTION 10: Hybrid Search Configuration (§35.2)
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1554,6 +1630,22 @@ pub struct TemporalCluster {
     pub window_secs: u64,
 }
 
+/// One item on a merged memory + conversation timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineEntry {
+    /// "memory" or "message".
+    pub kind: String,
+    /// Memory or message ID.
+    pub id: String,
+    /// When it happened (ISO 8601, UTC).
+    pub timestamp: String,
+    pub content: String,
+    /// Memory category, or the message role.
+    pub label: String,
+    /// Session the message belongs to (None for memories).
+    pub session_id: Option<String>,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SECTION 16: Intent-Aware Retrieval (§40)
 // ═══════════════════════════════════════════════════════════════════════════
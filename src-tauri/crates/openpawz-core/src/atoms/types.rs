@@ -15,6 +15,22 @@ pub struct ProviderConfig {
     pub api_key: String,
     pub base_url: Option<String>,
     pub default_model: Option<String>,
+    /// Extra JSON fields merged into every request body (OpenAI-compatible
+    /// providers only) — e.g. OpenRouter `provider` preferences / `models`
+    /// fallbacks, or LiteLLM `metadata` tags for cost attribution.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+    /// Extra HTTP headers sent with every request (OpenAI-compatible providers only).
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub extra_headers: std::collections::HashMap<String, String>,
+    /// Talk to the OpenAI Responses API (`/responses`) instead of Chat
+    /// Completions. Endpoints without it fall back to Chat Completions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub responses_api: bool,
+    /// Built-in tools added to every Responses API request as-is, e.g.
+    /// `{"type": "file_search", "vector_store_ids": ["vs_..."]}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub responses_tools: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -107,6 +123,14 @@ pub struct ToolCall {
 pub struct ThoughtPart {
     pub text: String,
     pub thought_signature: String,
+    /// `false` for a signature Gemini put on a plain text part: it is echoed
+    /// back on the assistant's text, not as a separate thought.
+    #[serde(default = "default_true")]
+    pub thought: bool,
+    /// OpenAI Responses API reasoning item id; `thought_signature` then
+    /// holds the item's encrypted content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +158,28 @@ pub struct ToolResult {
     pub tool_call_id: String,
     pub output: String,
     pub success: bool,
+    /// Why a failed call failed, when it could be told (see engine::tool_errors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ToolErrorKind>,
+}
+
+/// Category of a tool failure. Decides whether the agent loop lets the
+/// model retry the tool or blocks it, and how the UI shows the failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// Missing, invalid or expired credentials
+    Auth,
+    /// The service is throttling requests
+    RateLimit,
+    /// The file, record or endpoint does not exist
+    NotFound,
+    /// The credentials work but do not allow this action
+    Permission,
+    /// Timeouts, refused connections, DNS and other transport failures
+    Network,
+    /// The arguments the model sent are wrong
+    InvalidInput,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,6 +211,9 @@ pub enum EngineEvent {
         /// Estimated context token count at this point
         #[serde(skip_serializing_if = "Option::is_none")]
         context_tokens: Option<u32>,
+        /// The agent's autonomy level, if one is set ("manual", "assisted", "autonomous")
+        #[serde(skip_serializing_if = "Option::is_none")]
+        autonomy: Option<String>,
     },
     /// A tool finished executing
     #[serde(rename = "tool_result")]
@@ -174,6 +223,9 @@ pub enum EngineEvent {
         tool_call_id: String,
         output: String,
         success: bool,
+        /// Category of the failure, when `success` is false
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_kind: Option<ToolErrorKind>,
         // ── Inspector metadata (Phase 4) ──
         /// Duration of the tool execution in milliseconds
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -198,6 +250,46 @@ pub enum EngineEvent {
         /// Max rounds configured for this agent
         #[serde(skip_serializing_if = "Option::is_none")]
         max_rounds: Option<u32>,
+        /// Web sources the answer drew on, numbered for footnotes
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        citations: Vec<Citation>,
+    },
+    /// Claims in a completed answer that a judge pass flagged as uncertain.
+    /// Optional (see engine::confidence); follows the `complete` event.
+    #[serde(rename = "confidence")]
+    Confidence {
+        session_id: String,
+        run_id: String,
+        annotations: Vec<ConfidenceAnnotation>,
+    },
+    /// Code the model ran with a provider-side code-execution tool.
+    #[serde(rename = "code_execution")]
+    CodeExecution {
+        session_id: String,
+        run_id: String,
+        language: String,
+        code: String,
+        outcome: Option<String>,
+        output: Option<String>,
+    },
+    /// Searches and sources a provider grounded its answer on (Gemini).
+    #[serde(rename = "grounding")]
+    Grounding {
+        session_id: String,
+        run_id: String,
+        queries: Vec<String>,
+        sources: Vec<Citation>,
+    },
+    /// The agent handed the session to another agent (`handoff_to_agent`).
+    /// `chain` lists every agent that has held the session, oldest first.
+    #[serde(rename = "agent_handoff")]
+    AgentHandoff {
+        session_id: String,
+        run_id: String,
+        from_agent: String,
+        to_agent: String,
+        summary: String,
+        chain: Vec<String>,
     },
     /// A thinking/reasoning delta from extended-thinking models
     #[serde(rename = "thinking_delta")]
@@ -213,6 +305,9 @@ pub enum EngineEvent {
         run_id: String,
         tool_name: String,
         tool_call_id: String,
+        /// The autonomy level that granted the approval, if one is set
+        #[serde(skip_serializing_if = "Option::is_none")]
+        autonomy: Option<String>,
     },
     /// An error occurred during the run
     #[serde(rename = "error")]
@@ -221,6 +316,14 @@ pub enum EngineEvent {
         run_id: String,
         message: String,
     },
+    /// The run was cancelled (`engine_chat_cancel`); ends it instead of
+    /// `complete`. `text` is the answer streamed before the cancellation.
+    #[serde(rename = "cancelled")]
+    Cancelled {
+        session_id: String,
+        run_id: String,
+        text: String,
+    },
     /// Agent pushes a new component to the canvas.
     #[serde(rename = "canvas_push")]
     CanvasPush {
@@ -266,6 +369,57 @@ pub enum EngineEvent {
         total_count: usize,
         duration_ms: u64,
     },
+
+    // ── Plan-then-execute mode ───────────────────────────────────────
+    /// The agent proposed a plan and is waiting for the user to approve,
+    /// edit or reject it (`engine_plan_respond`).
+    #[serde(rename = "plan_proposed")]
+    PlanProposed {
+        session_id: String,
+        run_id: String,
+        plan_id: String,
+        goal: String,
+        steps: Vec<PlanStep>,
+        risks: Vec<String>,
+    },
+    /// A step of an approved plan changed status.
+    #[serde(rename = "plan_step_status")]
+    PlanStepStatus {
+        session_id: String,
+        run_id: String,
+        plan_id: String,
+        step_id: String,
+        index: usize,
+        status: PlanStepState,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+    },
+}
+
+/// One step of a plan-then-execute proposal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanStep {
+    #[serde(default)]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Tools the agent expects to use for this step.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// What could go wrong (destructive actions, cost, external effects).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<String>,
+}
+
+/// Execution state of a plan step.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepState {
+    Running,
+    Done,
+    Failed,
+    Skipped,
 }
 
 // ── Canvas Components (Agent Canvas) ──────────────────────────────────
@@ -423,6 +577,27 @@ pub struct Session {
     pub agent_id: Option<String>,
 }
 
+/// A session in cold storage (see `sessions::archive`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    /// Month of the archive file holding it, e.g. "2026-03".
+    pub archive: String,
+    #[serde(flatten)]
+    pub session: Session,
+    /// Search results only: text around the first matching message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// Result of an archival pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionArchiveReport {
+    pub sessions: usize,
+    pub messages: usize,
+    /// Months whose archive files received sessions.
+    pub archives: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredMessage {
     pub id: String,
@@ -462,6 +637,14 @@ pub struct ChatRequest {
     /// panel. These are merged with the hardcoded `auto_approved_tools` list.
     #[serde(default)]
     pub user_approved_tools: Vec<String>,
+    /// Plan-then-execute: propose a plan first, wait for approval, then run
+    /// the approved steps one at a time.
+    #[serde(default)]
+    pub plan_mode: bool,
+    /// Handles returned by `engine_ingest_file` for files dropped into
+    /// this turn.
+    #[serde(default)]
+    pub ingested: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -494,6 +677,26 @@ pub struct StreamChunk {
     pub thought_parts: Vec<ThoughtPart>,
     /// Thinking/reasoning text delta from extended thinking / reasoning models
     pub thinking_text: Option<String>,
+    /// Provider-side tool activity (Gemini code execution, search grounding)
+    pub meta: Vec<ProviderMeta>,
+}
+
+/// Work a provider did on its own during a response, surfaced as events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderMeta {
+    /// Code the model ran with the provider's code-execution tool.
+    CodeExecution {
+        language: String,
+        code: String,
+        /// e.g. "OUTCOME_OK"; `None` when no result came back.
+        outcome: Option<String>,
+        output: Option<String>,
+    },
+    /// Search queries and sources the provider grounded the answer on.
+    Grounding {
+        queries: Vec<String>,
+        sources: Vec<Citation>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -519,6 +722,40 @@ pub struct TokenUsage {
     pub cache_read_tokens: u64,
 }
 
+/// A web source used in an assistant answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Footnote number, 1-based.
+    pub index: usize,
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    /// The passage of the source the answer draws on, when one matched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+    /// The tool that fetched it (web_read, web_search, ...).
+    pub tool: String,
+}
+
+/// A claim in an assistant answer flagged as uncertain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceAnnotation {
+    /// The claim, quoted exactly from the answer.
+    pub claim: String,
+    /// Estimated probability the claim is correct, 0.0–1.0.
+    pub confidence: f64,
+    pub level: ConfidenceLevel,
+    /// Why it is uncertain (unsourced figure, outdated, speculative, ...).
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfidenceLevel {
+    Low,
+    Medium,
+}
+
 pub struct ModelPrice {
     pub input: f64,
     pub output: f64,
@@ -540,6 +777,18 @@ pub struct AgentFile {
     pub updated_at: String,
 }
 
+/// One stored revision of an agent file. Versions start at 1 per file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentFileRevision {
+    pub agent_id: String,
+    pub file_name: String,
+    pub version: i64,
+    pub content: String,
+    /// "user" (UI / CLI edits and reverts) or "agent" (soul_write, orchestrator)
+    pub author: String,
+    pub created_at: String,
+}
+
 pub const AGENT_STANDARD_FILES: &[(&str, &str, &str)] = &[
     (
         "AGENTS.md",
@@ -682,6 +931,76 @@ pub struct MemoryConfig {
     pub recall_limit: usize,
     /// Minimum similarity score for auto-recall (0.0–1.0)
     pub recall_threshold: f64,
+    /// Retention rules enforced during background maintenance
+    #[serde(default)]
+    pub retention: Vec<RetentionRule>,
+    /// Hybrid search weights, decay and diversity
+    #[serde(default)]
+    pub search: HybridSearchTuning,
+}
+
+/// Tuning for hybrid memory search (BM25 + vector + decay + MMR).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HybridSearchTuning {
+    /// Weight of the normalized BM25 score.
+    pub bm25_weight: f64,
+    /// Weight of the vector (cosine) score.
+    pub vector_weight: f64,
+    /// Days for a memory's score to halve; 0 disables decay.
+    pub decay_half_life_days: f64,
+    /// MMR trade-off: 1.0 = pure relevance, 0.0 = pure diversity.
+    pub mmr_lambda: f64,
+}
+
+/// Every stage's scores for one hybrid memory search (for tuning).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySearchDebug {
+    pub query: String,
+    pub tuning: HybridSearchTuning,
+    pub bm25_count: usize,
+    pub vector_count: usize,
+    /// Whether the query could be embedded (vector stage ran).
+    pub embedded: bool,
+    /// No BM25/vector hits — results came from the keyword fallback.
+    pub keyword_fallback: bool,
+    /// Merged candidates, best final rank first; unselected ones last.
+    pub candidates: Vec<MemorySearchCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySearchCandidate {
+    pub id: String,
+    pub content: String,
+    pub created_at: String,
+    /// Raw FTS5 BM25 score, and normalized to 0–1 across the BM25 hits.
+    pub bm25_raw: Option<f64>,
+    pub bm25: Option<f64>,
+    /// Cosine similarity.
+    pub vector: Option<f64>,
+    /// bm25 × bm25_weight + vector × vector_weight.
+    pub merged: f64,
+    /// Temporal decay multiplier (1.0 = no decay).
+    pub decay: f64,
+    /// merged × decay — the relevance MMR works with.
+    pub decayed: f64,
+    /// MMR objective when picked (None when MMR didn't run).
+    pub mmr: Option<f64>,
+    /// 1-based position in the returned results; None = not returned.
+    pub rank: Option<usize>,
+}
+
+/// Limits on how long / how many episodic memories of a category are kept.
+/// `category` "*" applies to every category. Counts are per agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetentionRule {
+    pub category: String,
+    /// Erase memories older than this many days
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// Keep at most this many (newest first) per agent
+    #[serde(default)]
+    pub max_count: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -725,6 +1044,335 @@ pub(crate) fn default_max_concurrent_runs() -> u32 {
 pub(crate) fn default_context_window_tokens() -> usize {
     32_000
 }
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// A header added to outbound provider requests (LLM gateways, observability).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeaderInjection {
+    pub name: String,
+    /// Header value. `{model}` and `{provider}` are replaced per request.
+    pub value: String,
+    /// Provider ids or kinds (e.g. "anthropic") this applies to. Empty = all.
+    #[serde(default)]
+    pub providers: Vec<String>,
+}
+
+/// Config-driven provider middleware hooks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ProviderMiddlewareConfig {
+    #[serde(default)]
+    pub headers: Vec<HeaderInjection>,
+    /// Log one line per provider request with latency and token usage.
+    #[serde(default)]
+    pub log_requests: bool,
+}
+
+/// Keeping local (Ollama) models loaded between requests.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LocalModelConfig {
+    /// How long Ollama keeps a model in memory after a request, as an Ollama
+    /// duration ("30m", "2h"; "-1" = until Ollama stops). Empty = Ollama's
+    /// own default (5 minutes).
+    pub keep_alive: String,
+    /// Load the session's model when a chat session is opened.
+    pub warm_on_session_open: bool,
+    /// Load a cron task's model shortly before the task is due.
+    pub prewarm_cron: bool,
+    /// How far ahead of a cron task to start loading, in seconds.
+    pub prewarm_lead_secs: u64,
+}
+
+impl Default for LocalModelConfig {
+    fn default() -> Self {
+        LocalModelConfig {
+            keep_alive: "30m".into(),
+            warm_on_session_open: true,
+            prewarm_cron: true,
+            prewarm_lead_secs: 120,
+        }
+    }
+}
+
+/// Resource caps for one orchestrator delegation. Zero / empty = no cap.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WorkerCaps {
+    /// Tool rounds per delegated task (0 = `max_tool_rounds`).
+    pub max_rounds: u32,
+    /// Estimated spend per delegated task, in USD (0 = no cap).
+    pub max_cost_usd: f64,
+    /// Tool groups withheld from the worker: "exec", "filesystem", "web",
+    /// "memory", "comms", "skills", "mcp".
+    pub denied_tool_groups: Vec<String>,
+}
+
+/// Caps and isolation for the worker sub-agents of orchestrator projects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WorkerLimits {
+    /// Caps for workers whose specialty has no entry below.
+    pub defaults: WorkerCaps,
+    /// Per-specialty caps, e.g. {"researcher": {...}}; replace `defaults`.
+    pub specialties: std::collections::HashMap<String, WorkerCaps>,
+    /// Give each delegation its own empty workspace directory instead of
+    /// the agent's shared one.
+    pub isolate_workspaces: bool,
+}
+
+impl WorkerLimits {
+    /// The caps for a worker of this specialty.
+    pub fn caps_for(&self, specialty: &str) -> &WorkerCaps {
+        self.specialties.get(specialty).unwrap_or(&self.defaults)
+    }
+}
+
+/// Thresholds of the agent loop's tool circuit breaker (see
+/// engine::tool_errors).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures of a tool before the model is told to stop
+    /// retrying it (0 = never).
+    pub nudge_after: u32,
+    /// Consecutive failures before the tool is blocked (0 = never).
+    pub block_after: u32,
+    /// Minutes a blocked tool stays blocked (0 = for the rest of the
+    /// session). Once re-enabled, a single failure blocks it again.
+    pub cooldown_minutes: u32,
+    /// Per-tool overrides, e.g. {"web_fetch": {"block_after": 8}}.
+    pub tools: std::collections::HashMap<String, CircuitBreakerOverride>,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig {
+            nudge_after: 3,
+            block_after: 5,
+            cooldown_minutes: 10,
+            tools: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// Duplicate protection for side-effect tools (see engine::idempotency).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct IdempotencyConfig {
+    pub enabled: bool,
+    /// Hours a completed call's key is remembered.
+    pub ttl_hours: u32,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        IdempotencyConfig {
+            enabled: true,
+            ttl_hours: 24,
+        }
+    }
+}
+
+/// New-mail push for the himalaya (IMAP) accounts (see engine::mail_watch).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MailWatchConfig {
+    pub enabled: bool,
+    /// Folders watched on every account.
+    pub folders: Vec<String>,
+    /// Per-account folders, replacing `folders` (empty = don't watch).
+    pub accounts: std::collections::HashMap<String, Vec<String>>,
+}
+
+impl Default for MailWatchConfig {
+    fn default() -> Self {
+        MailWatchConfig {
+            enabled: false,
+            folders: vec!["INBOX".into()],
+            accounts: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl MailWatchConfig {
+    /// The folders to watch on `account`.
+    pub fn folders_of(&self, account: &str) -> &[String] {
+        self.accounts.get(account).unwrap_or(&self.folders)
+    }
+}
+
+/// How one notification category reaches the user (see engine::notifications).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationRoute {
+    /// "os" (system notification), "bridge" (message through `bridge`) or
+    /// "log" (silent: notification drawer and log only).
+    pub delivery: String,
+    /// Bridge for "bridge" delivery: "telegram", "discord" or "webhook".
+    pub bridge: String,
+    /// Bridge chat / channel / username (empty = the bridge's default).
+    pub target: String,
+    /// Batch into one digest per `digest_minutes` instead of one by one.
+    pub digest: bool,
+}
+
+impl Default for NotificationRoute {
+    fn default() -> Self {
+        NotificationRoute {
+            delivery: "os".into(),
+            bridge: String::new(),
+            target: String::new(),
+            digest: false,
+        }
+    }
+}
+
+/// Per-category notification routing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Routes by category ("budget", "pairing", "task", "gateway", "mail",
+    /// "security").
+    /// Categories without one use the built-in default (`route`).
+    pub routes: std::collections::HashMap<String, NotificationRoute>,
+    /// Digest window in minutes.
+    pub digest_minutes: u32,
+    /// Do-not-disturb windows: notices are queued until the window ends.
+    pub dnd: Vec<DndWindow>,
+    /// Categories that break through do-not-disturb (critical notices
+    /// always do).
+    pub breakthrough: Vec<String>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            routes: std::collections::HashMap::new(),
+            digest_minutes: 15,
+            dnd: vec![],
+            breakthrough: vec!["security".into()],
+        }
+    }
+}
+
+/// A do-not-disturb window in the user's timezone. It may cross midnight
+/// ("22:00" to "07:00"); `days` are the days it starts on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DndWindow {
+    /// "mon" … "sun"; empty = every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM"; equal to `start` = the whole day.
+    pub end: String,
+}
+
+impl NotificationConfig {
+    /// The route of `category`. Unconfigured task completions and new mail
+    /// are digested — they are the noisy ones.
+    pub fn route(&self, category: &str) -> NotificationRoute {
+        self.routes
+            .get(category)
+            .cloned()
+            .unwrap_or_else(|| NotificationRoute {
+                digest: matches!(category, "task" | "mail"),
+                ..Default::default()
+            })
+    }
+}
+
+/// A named environment ("home", "work", "demo") that `engine_profile_switch`
+/// applies in one step. Unset fields leave the current setting alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ConfigProfile {
+    pub name: String,
+    /// Provider (and so API key) agents use by default.
+    pub default_provider: Option<String>,
+    pub default_model: Option<String>,
+    /// Agent every channel bridge routes to.
+    pub default_agent: Option<String>,
+    /// Bridges that run under this profile ("telegram", "discord", …);
+    /// every other bridge is disabled and stopped.
+    pub bridges: Option<Vec<String>>,
+    pub daily_budget_usd: Option<f64>,
+}
+
+/// One tool's breaker thresholds; unset fields keep the global value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CircuitBreakerOverride {
+    pub nudge_after: Option<u32>,
+    pub block_after: Option<u32>,
+    pub cooldown_minutes: Option<u32>,
+}
+
+/// A resolved place: what `engine_location_set` / `engine_location_detect`
+/// store as the user's home location.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HomeLocation {
+    /// City / place name, e.g. "Lyon".
+    pub name: String,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA timezone of the place, when the geocoder reports one.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// "manual" (geocoded from what the user typed) or "ip" (auto-detected).
+    #[serde(default)]
+    pub source: String,
+    /// Include the location in agent runtime context.
+    #[serde(default = "default_true")]
+    pub share_with_agents: bool,
+}
+
+impl HomeLocation {
+    /// "Lyon, Auvergne-Rhône-Alpes, France"
+    pub fn display_name(&self) -> String {
+        [&self.name, &self.region, &self.country]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Write batching and WAL checkpointing of the engine DB (see
+/// sessions::write_queue).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DbWriteConfig {
+    /// Seconds between flushes of batched non-critical writes (usage
+    /// records, task activity). 0 = write them immediately.
+    pub flush_secs: u32,
+    /// WAL pages after which a commit runs an automatic checkpoint (SQLite's
+    /// default is 1000). 0 leaves checkpoints to the background writer.
+    pub wal_autocheckpoint_pages: u32,
+    /// Seconds between passive checkpoints run by the background writer
+    /// (0 = none).
+    pub checkpoint_secs: u32,
+    /// The WAL file is truncated back to this size after a checkpoint.
+    pub wal_size_limit_mb: u32,
+}
+
+impl Default for DbWriteConfig {
+    fn default() -> Self {
+        DbWriteConfig {
+            flush_secs: 2,
+            wal_autocheckpoint_pages: 1000,
+            checkpoint_secs: 60,
+            wal_size_limit_mb: 64,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineConfig {
@@ -756,6 +1404,56 @@ pub struct EngineConfig {
     /// If empty, auto-detected via IP geolocation.
     #[serde(default)]
     pub weather_location: Option<String>,
+    /// Home location (geocoded or IP-detected) used by the weather widget,
+    /// `local_time` / `nearby_search`, and agent runtime context.
+    #[serde(default)]
+    pub home_location: Option<HomeLocation>,
+    /// Opt-in: tell chat agents which app/window the user last focused.
+    /// The sample is kept in memory only, never stored.
+    #[serde(default)]
+    pub app_context: bool,
+    /// Request/response hooks applied to every AI provider call.
+    #[serde(default)]
+    pub provider_middleware: ProviderMiddlewareConfig,
+    /// Keep-alive and warm-up for local (Ollama) models.
+    #[serde(default)]
+    pub local_models: LocalModelConfig,
+    /// Distill a reusable playbook from each finished orchestrator project
+    /// and show matching playbooks to the boss agent of new projects.
+    #[serde(default)]
+    pub project_playbooks: bool,
+    /// Caps on what orchestrator workers may spend and use.
+    #[serde(default)]
+    pub worker_limits: WorkerLimits,
+    /// When the agent loop stops a failing tool.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Refuse side-effect tool calls a retried run already made.
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    /// Watch IMAP folders for new mail (IDLE) and push `mail-new` events.
+    #[serde(default)]
+    pub mail_watch: MailWatchConfig,
+    /// How budget, pairing, task, gateway and mail notifications are delivered.
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// Switchable environments; see `ConfigProfile`.
+    #[serde(default)]
+    pub profiles: Vec<ConfigProfile>,
+    /// Name of the profile last switched to.
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Read-only mode: side-effect tools are refused and bridges stay
+    /// disconnected (see engine::safe_mode).
+    #[serde(default)]
+    pub safe_mode: bool,
+    /// Days without activity before a session moves to cold storage
+    /// (0 = never; see sessions::archive).
+    #[serde(default)]
+    pub session_archive_days: u32,
+    /// Batched writes and WAL checkpoint tuning for the engine DB.
+    #[serde(default)]
+    pub db_write: DbWriteConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -794,6 +1492,328 @@ pub struct TaskAgent {
     pub role: String, // lead, collaborator
 }
 
+/// A one-shot follow-up an agent scheduled for itself via `schedule_followup`.
+/// When due, the note is sent back into the same session as a new turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Followup {
+    pub id: String,
+    pub session_id: String,
+    pub agent_id: String,
+    pub note: String,
+    pub due_at: String,
+    pub status: String, // pending, fired, cancelled, skipped
+    pub created_at: String,
+}
+
+/// A user's rating of one agent run (thumbs up = 1, thumbs down = -1).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunFeedback {
+    pub run_id: String,
+    /// Session, agent and model of the run, when the run is known.
+    pub session_id: Option<String>,
+    pub agent_id: Option<String>,
+    pub model: Option<String>,
+    pub rating: i64,
+    pub comment: String,
+    pub created_at: String,
+}
+
+/// Feedback totals for one model — the numbers to compare when A/B testing
+/// model routing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFeedbackStats {
+    pub model: String,
+    pub rated_runs: u32,
+    pub positive: u32,
+    pub negative: u32,
+    /// positive / rated_runs, 0.0–1.0.
+    pub approval_rate: f64,
+    /// Mean cost of the rated runs, from telemetry.
+    pub avg_cost_usd: f64,
+}
+
+/// One run of the schema migrations (one per startup).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRun {
+    pub ran_at: String,
+    pub duration_ms: i64,
+    /// Tables and columns this run added, e.g. "table followups",
+    /// "column tasks.event_trigger". Empty when the schema was already current.
+    pub changes: Vec<String>,
+}
+
+/// Result of an `engine_db_maintenance` action.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DbMaintenanceReport {
+    pub action: String,
+    pub ok: bool,
+    /// Human-readable findings (integrity errors, pages freed, tables rebuilt).
+    pub details: Vec<String>,
+    pub duration_ms: u64,
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<MigrationRun>,
+}
+
+/// How `engine_import_conversations` maps an export onto Paw sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportOptions {
+    /// Agent that owns the imported sessions (None = default agent).
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Model recorded on the sessions; the export's own model slug if unset.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Prepended to each conversation title, e.g. "[ChatGPT] ".
+    #[serde(default)]
+    pub label_prefix: Option<String>,
+    /// Also run imported exchanges through memory auto-capture.
+    #[serde(default)]
+    pub distill_memories: bool,
+    /// Skip conversations last updated before this date (YYYY-MM-DD or RFC 3339).
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Import at most this many conversations (newest first).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Result of importing a ChatGPT or Claude export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub conversations_found: usize,
+    pub imported: usize,
+    /// Already imported earlier, empty, or filtered out by `since`/`limit`.
+    pub skipped: usize,
+    pub messages_imported: usize,
+    pub memories_captured: usize,
+    pub errors: Vec<String>,
+}
+
+/// What `engine_import_bookmarks` reads and how much of it gets indexed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookmarkImportOptions {
+    /// `chrome` (also chromium / brave / edge) or `firefox`.
+    pub browser: String,
+    /// Browser profile directory; the default profile if unset.
+    #[serde(default)]
+    pub profile_path: Option<String>,
+    /// Only bookmarks whose folder path starts with this (case-insensitive).
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Also index frequently visited pages from history.
+    #[serde(default)]
+    pub include_history: bool,
+    /// History pages need at least this many visits (default 5).
+    #[serde(default)]
+    pub history_min_visits: Option<i64>,
+    /// At most this many history pages (default 200).
+    #[serde(default)]
+    pub history_limit: Option<usize>,
+    /// Index at most this many pages in total.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Agent whose memory receives the pages (None = default agent).
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Index title and URL only, without fetching the pages.
+    #[serde(default)]
+    pub titles_only: bool,
+}
+
+/// Result of a bookmark/history import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookmarkImportReport {
+    pub browser: String,
+    pub profile_path: String,
+    pub found: usize,
+    pub indexed: usize,
+    /// Pages already in memory from an earlier import.
+    pub already_indexed: usize,
+    /// Pages that could not be fetched; indexed by title and URL only.
+    pub fetch_failed: usize,
+    pub errors: Vec<String>,
+}
+
+/// How a dropped file was routed by `engine_ingest_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestKind {
+    /// Text, Markdown, HTML, Word, PDF, code — indexed into memory.
+    Document,
+    /// Sent to the model as a vision block.
+    Image,
+    /// CSV / TSV — copied into the workspace for the file and sheet tools.
+    Sheet,
+    /// Transcribed; the transcript is indexed like a document.
+    Audio,
+}
+
+/// What the user wants done with a dropped file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestIntent {
+    /// Route by file type.
+    #[default]
+    Auto,
+    /// Keep it: index documents and transcripts into long-term memory.
+    Index,
+    /// Only for this chat: attach without writing memories.
+    Attach,
+}
+
+/// Result of `engine_ingest_file`. Pass `handle` in `ChatRequest::ingested`
+/// to bring the file into a chat turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestResult {
+    pub handle: String,
+    pub kind: IngestKind,
+    pub name: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// One-line description shown in the composer chip.
+    pub summary: String,
+    /// Copy in the agent workspace (sheets, transcripts).
+    pub workspace_path: Option<String>,
+    /// Memories written when the file was indexed.
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+}
+
+/// A file an agent fetched with `download_file`, registered so the user can
+/// find it again (Downloads list) and agents can refer to it by path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadRecord {
+    pub id: String,
+    pub agent_id: String,
+    pub url: String,
+    /// Absolute path of the finished file.
+    pub path: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the file.
+    pub sha256: String,
+    pub content_type: Option<String>,
+    pub created_at: String,
+}
+
+/// An outbound message (email, chat post, …) an agent drafted that waits in
+/// the outbox for the user to approve, edit or reject.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxItem {
+    pub id: String,
+    pub agent_id: String,
+    pub session_id: Option<String>,
+    pub tool_name: String,
+    /// The tool call's JSON arguments, as they will be sent.
+    pub arguments: String,
+    /// One-line description for the review list ("email_send → bob@x.com: …").
+    pub summary: String,
+    pub status: String, // pending, sending, sent, failed, rejected, expired
+    /// Tool output once sent, or the rejection reason.
+    pub result: Option<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub decided_at: Option<String>,
+}
+
+/// A news topic the user follows. On its schedule the watch searches each
+/// query, keeps results it hasn't seen, has its agent summarize them and
+/// delivers the digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsWatch {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub queries: Vec<String>,
+    #[serde(default = "default_news_agent")]
+    pub agent_id: String,
+    /// Same syntax as task schedules: "every 6h", "daily 08:00".
+    pub schedule: String,
+    /// Bridge the digest goes to: "telegram", "discord", "webhook", or
+    /// empty to keep it in the app only.
+    #[serde(default)]
+    pub deliver_to: String,
+    /// Telegram username / chat ID or Discord channel ID; empty uses the
+    /// bridge default.
+    #[serde(default)]
+    pub deliver_target: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub next_run_at: Option<String>,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+fn default_news_agent() -> String {
+    "default".into()
+}
+
+/// One delivered (or attempted) news digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsDigest {
+    pub id: String,
+    pub watch_id: String,
+    /// New results summarized in this digest.
+    pub item_count: i64,
+    pub summary: String,
+    pub status: String, // delivered, in_app, failed
+    /// Delivery error, when status is failed.
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+/// A long-running research run. Its loop state (sub-questions, sources,
+/// claims) is stored alongside as JSON; see engine::research.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchRun {
+    pub id: String,
+    pub question: String,
+    pub agent_id: String,
+    pub status: String, // running, done, failed, cancelled, interrupted
+    /// Pages the run may read in total.
+    pub page_budget: u32,
+    /// Search/read/reflect cycles before writing the report.
+    pub max_rounds: u32,
+    pub pages_read: u32,
+    /// Workspace path of the finished report.
+    pub report_path: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A message received by a channel bridge, journaled before the agent sees
+/// it so a crash between receipt and reply doesn't lose it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundMessage {
+    /// Dedup key, e.g. "telegram:123456789" (channel + platform message/update ID).
+    pub id: String,
+    pub channel: String,
+    /// Bridge-specific JSON needed to re-run the message and reply.
+    pub payload: String,
+    pub status: String, // pending, done, failed
+    pub attempts: i64,
+    pub received_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// A side-effect tool call that already ran, found by its idempotency key
+/// (see engine::idempotency).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedToolCall {
+    /// "{run_id}#{call_index}"
+    pub key: String,
+    pub tool_name: String,
+    pub session_id: String,
+    pub output: String,
+    pub completed_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskActivity {
     pub id: String,
@@ -813,6 +1833,9 @@ pub struct Project {
     pub boss_agent: String, // agent_id of the orchestrator/boss agent
     #[serde(default)]
     pub agents: Vec<ProjectAgent>,
+    /// Hard spending limit for the whole project in USD (0 = none)
+    #[serde(default)]
+    pub budget_usd: f64,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -847,6 +1870,55 @@ pub struct ProjectMessage {
     pub created_at: String,
 }
 
+/// What happened in one orchestrator project run, built when the run ends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectPostMortem {
+    pub project_id: String,
+    pub title: String,
+    pub goal: String,
+    /// Final project status (completed, failed, …)
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_secs: i64,
+    pub delegations: Vec<DelegationRecord>,
+    /// Errors reported by agents or the run itself
+    pub failures: Vec<String>,
+    pub usage: Vec<ProjectAgentUsage>,
+    pub total_cost_usd: f64,
+    /// The boss agent's closing summary
+    pub summary: String,
+    /// Procedural memory distilled from the run, when playbooks are on
+    #[serde(default)]
+    pub playbook_id: Option<String>,
+}
+
+/// One `delegate_task` call and how it ended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationRecord {
+    pub agent_id: String,
+    pub task: String,
+    pub delegated_at: String,
+    pub finished_at: Option<String>,
+    pub duration_secs: Option<i64>,
+    /// done, failed, unfinished
+    pub outcome: String,
+    /// The agent's result or error message
+    pub note: String,
+}
+
+/// Tokens and cost one project agent spent with one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectAgentUsage {
+    pub agent_id: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub rounds: u32,
+    pub tool_calls: u32,
+}
+
 // ── Inter-Agent Communication ──────────────────────────────────────────────
 
 /// A direct message between agents, independent of any project context.
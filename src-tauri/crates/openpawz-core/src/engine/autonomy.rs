@@ -0,0 +1,204 @@
+// ── Agent Autonomy Levels ───────────────────────────────────────────────────
+//
+// One per-agent setting that expands into a bundle of policy, instead of
+// separate booleans spread across chat requests, tasks and config:
+//
+//   level        auto-approve        tool groups           budget  triggers
+//   ───────────  ──────────────────  ────────────────────  ──────  ────────
+//   manual       safe tools only     no trading domains    0.5×    no
+//   assisted     safe + reversible   all                   1.0×    yes
+//   autonomous   everything          all                   1.5×    yes
+//
+// "Triggers" covers unattended runs: cron schedules and event triggers.
+// Agents without a stored level keep the legacy behaviour (the per-request
+// `auto_approve_all` flag decides).
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use crate::engine::tool_metadata::{self, ToolTier};
+use serde::{Deserialize, Serialize};
+
+/// How much an agent may do without asking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutonomyLevel {
+    Manual,
+    #[default]
+    Assisted,
+    Autonomous,
+}
+
+impl AutonomyLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AutonomyLevel::Manual => "manual",
+            AutonomyLevel::Assisted => "assisted",
+            AutonomyLevel::Autonomous => "autonomous",
+        }
+    }
+
+    /// The policy bundle this level stands for.
+    pub fn policy(self) -> AutonomyPolicy {
+        match self {
+            AutonomyLevel::Manual => AutonomyPolicy {
+                level: self,
+                auto_approve_all: false,
+                auto_approve_reversible: false,
+                blocked_domains: vec!["coinbase", "solana", "dex"],
+                budget_multiplier: 0.5,
+                allow_triggers: false,
+            },
+            AutonomyLevel::Assisted => AutonomyPolicy {
+                level: self,
+                auto_approve_all: false,
+                auto_approve_reversible: true,
+                blocked_domains: Vec::new(),
+                budget_multiplier: 1.0,
+                allow_triggers: true,
+            },
+            AutonomyLevel::Autonomous => AutonomyPolicy {
+                level: self,
+                auto_approve_all: true,
+                auto_approve_reversible: true,
+                blocked_domains: Vec::new(),
+                budget_multiplier: 1.5,
+                allow_triggers: true,
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for AutonomyLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manual" => Ok(AutonomyLevel::Manual),
+            "assisted" => Ok(AutonomyLevel::Assisted),
+            "autonomous" => Ok(AutonomyLevel::Autonomous),
+            other => Err(format!(
+                "Unknown autonomy level '{}' (expected manual, assisted or autonomous)",
+                other
+            )),
+        }
+    }
+}
+
+/// Concrete permissions derived from an `AutonomyLevel`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AutonomyPolicy {
+    pub level: AutonomyLevel,
+    /// Skip approval for every tool call (dangerous ones included).
+    pub auto_approve_all: bool,
+    /// Skip approval for reversible tools (write_file, memory_store, …).
+    pub auto_approve_reversible: bool,
+    /// Tool groups (`tool_metadata::domain_str`) the agent may not use at all.
+    pub blocked_domains: Vec<&'static str>,
+    /// Applied to the configured daily budget.
+    pub budget_multiplier: f64,
+    /// May run unattended from cron schedules and event triggers.
+    pub allow_triggers: bool,
+}
+
+impl AutonomyPolicy {
+    /// Whether the tool is offered to the agent at all.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        !self
+            .blocked_domains
+            .contains(&tool_metadata::domain_str(name))
+    }
+
+    /// Whether a call to the tool runs without user approval.
+    pub fn auto_approves(&self, name: &str) -> bool {
+        if self.auto_approve_all {
+            return true;
+        }
+        match tool_metadata::tier(name) {
+            ToolTier::Safe => true,
+            ToolTier::Reversible => self.auto_approve_reversible,
+            ToolTier::External | ToolTier::Dangerous => false,
+        }
+    }
+
+    /// Daily budget after applying the multiplier. 0 (unlimited) stays 0.
+    pub fn effective_budget(&self, daily_budget_usd: f64) -> f64 {
+        daily_budget_usd * self.budget_multiplier
+    }
+}
+
+// ── Storage ────────────────────────────────────────────────────────────────
+
+fn config_key(agent_id: &str) -> String {
+    format!("agent_autonomy:{}", agent_id)
+}
+
+/// The agent's stored level, if one was ever set.
+pub fn load(store: &SessionStore, agent_id: &str) -> Option<AutonomyLevel> {
+    store
+        .get_config(&config_key(agent_id))
+        .ok()
+        .flatten()
+        .and_then(|s| s.parse().ok())
+}
+
+/// Persist the agent's level.
+pub fn save(store: &SessionStore, agent_id: &str, level: AutonomyLevel) -> EngineResult<()> {
+    store.set_config(&config_key(agent_id), level.as_str())
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn levels_roundtrip_through_store() {
+        let store = test_store();
+        assert_eq!(load(&store, "a1"), None);
+        save(&store, "a1", AutonomyLevel::Manual).unwrap();
+        assert_eq!(load(&store, "a1"), Some(AutonomyLevel::Manual));
+        assert_eq!(load(&store, "a2"), None);
+    }
+
+    #[test]
+    fn parse_rejects_unknown() {
+        assert_eq!("autonomous".parse(), Ok(AutonomyLevel::Autonomous));
+        assert!("yolo".parse::<AutonomyLevel>().is_err());
+    }
+
+    #[test]
+    fn approval_follows_level() {
+        let manual = AutonomyLevel::Manual.policy();
+        let assisted = AutonomyLevel::Assisted.policy();
+        let autonomous = AutonomyLevel::Autonomous.policy();
+
+        let safe = tool_metadata::tools_in_tier(ToolTier::Safe)[0];
+        let reversible = tool_metadata::tools_in_tier(ToolTier::Reversible)[0];
+        let dangerous = tool_metadata::tools_in_tier(ToolTier::Dangerous)[0];
+
+        assert!(manual.auto_approves(safe));
+        assert!(!manual.auto_approves(reversible));
+        assert!(assisted.auto_approves(reversible));
+        assert!(!assisted.auto_approves(dangerous));
+        assert!(autonomous.auto_approves(dangerous));
+    }
+
+    #[test]
+    fn manual_blocks_trading_tools() {
+        let manual = AutonomyLevel::Manual.policy();
+        assert!(!manual.allows_tool("dex_swap"));
+        assert!(!manual.allows_tool("sol_transfer"));
+        assert!(manual.allows_tool("read_file"));
+        assert!(AutonomyLevel::Assisted.policy().allows_tool("dex_swap"));
+        assert!(!manual.allow_triggers);
+    }
+}
@@ -0,0 +1,381 @@
+// ── Bookmarks: read Chrome / Firefox bookmarks and history ─────────────────
+//
+// Reads the user's local browser profile so curated pages can be indexed
+// into memory. Nothing here touches the network.
+//
+//   chrome   `Bookmarks` (JSON) and `History` (SQLite) in the profile dir;
+//            Chromium, Brave and Edge use the same format — pass their
+//            profile directory explicitly
+//   firefox  `places.sqlite` (bookmarks and history in one database)
+//
+// Browser databases are locked while the browser runs, so they are copied
+// to a temp file (with their WAL) first.
+
+use crate::atoms::error::{EngineError, EngineResult};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Browser {
+    Chrome,
+    Firefox,
+}
+
+impl std::str::FromStr for Browser {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chrome" | "chromium" | "brave" | "edge" => Ok(Self::Chrome),
+            "firefox" => Ok(Self::Firefox),
+            other => Err(format!(
+                "Unknown browser '{}' (expected chrome or firefox)",
+                other
+            )),
+        }
+    }
+}
+
+impl Browser {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chrome => "chrome",
+            Self::Firefox => "firefox",
+        }
+    }
+}
+
+/// A bookmarked or frequently visited page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    /// Folder path ("Bookmarks bar/Rust"), or "History" for history entries.
+    pub folder: String,
+    /// SQLite `datetime` format.
+    pub added_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visit_count: Option<i64>,
+}
+
+/// The default profile directory for `browser`, if one exists.
+pub fn default_profile_dir(browser: Browser) -> Option<PathBuf> {
+    match browser {
+        Browser::Chrome => chrome_profile_candidates()
+            .into_iter()
+            .find(|p| p.join("Bookmarks").exists() || p.join("History").exists()),
+        Browser::Firefox => firefox_profile_dir(),
+    }
+}
+
+fn chrome_profile_candidates() -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    if cfg!(target_os = "macos") {
+        if let Some(d) = dirs::data_dir() {
+            out.push(d.join("Google/Chrome/Default"));
+            out.push(d.join("Chromium/Default"));
+        }
+    } else if cfg!(target_os = "windows") {
+        if let Some(d) = dirs::data_local_dir() {
+            out.push(d.join("Google\\Chrome\\User Data\\Default"));
+            out.push(d.join("Chromium\\User Data\\Default"));
+        }
+    } else if let Some(d) = dirs::config_dir() {
+        out.push(d.join("google-chrome/Default"));
+        out.push(d.join("chromium/Default"));
+    }
+    out
+}
+
+/// Firefox keeps several profiles side by side; prefer `*.default-release`,
+/// then whichever `places.sqlite` was written most recently.
+fn firefox_profile_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "macos") {
+        dirs::data_dir()?.join("Firefox/Profiles")
+    } else if cfg!(target_os = "windows") {
+        dirs::data_dir()?.join("Mozilla\\Firefox\\Profiles")
+    } else {
+        dirs::home_dir()?.join(".mozilla/firefox")
+    };
+    let mut profiles: Vec<PathBuf> = std::fs::read_dir(base)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.join("places.sqlite").exists())
+        .collect();
+    let modified = |p: &PathBuf| {
+        std::fs::metadata(p.join("places.sqlite"))
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    profiles.sort_by_key(|p| std::cmp::Reverse(modified(p)));
+    profiles
+        .iter()
+        .find(|p| p.to_string_lossy().ends_with(".default-release"))
+        .or(profiles.first())
+        .cloned()
+}
+
+/// Read bookmarks from `profile`, optionally followed by history entries
+/// visited at least `min_visits` times (at most `history_limit`).
+/// Only http(s) URLs are returned, each once.
+pub fn read_profile(
+    browser: Browser,
+    profile: &Path,
+    history: Option<(i64, usize)>,
+) -> EngineResult<Vec<Bookmark>> {
+    let mut out = match browser {
+        Browser::Chrome => {
+            let path = profile.join("Bookmarks");
+            let text = std::fs::read_to_string(&path).map_err(|e| {
+                EngineError::Other(format!("Cannot read {}: {}", path.display(), e))
+            })?;
+            parse_chrome_bookmarks(&serde_json::from_str(&text)?)
+        }
+        Browser::Firefox => {
+            let conn = open_copy(&profile.join("places.sqlite"))?;
+            read_firefox_bookmarks(&conn)?
+        }
+    };
+    if let Some((min_visits, limit)) = history {
+        let db = open_copy(&profile.join(match browser {
+            Browser::Chrome => "History",
+            Browser::Firefox => "places.sqlite",
+        }))?;
+        out.extend(match browser {
+            Browser::Chrome => read_history(
+                &db,
+                "SELECT url, title, visit_count, last_visit_time FROM urls
+                 WHERE visit_count >= ?1 AND hidden = 0
+                 ORDER BY visit_count DESC LIMIT ?2",
+                min_visits,
+                limit,
+                chrome_time_to_sqlite,
+            )?,
+            Browser::Firefox => read_history(
+                &db,
+                "SELECT url, COALESCE(title, ''), visit_count, last_visit_date FROM moz_places
+                 WHERE visit_count >= ?1 AND hidden = 0
+                 ORDER BY frecency DESC LIMIT ?2",
+                min_visits,
+                limit,
+                unix_micros_to_sqlite,
+            )?,
+        });
+    }
+
+    let mut seen = HashSet::new();
+    out.retain(|b| {
+        (b.url.starts_with("http://") || b.url.starts_with("https://"))
+            && seen.insert(b.url.clone())
+    });
+    Ok(out)
+}
+
+/// Walk Chrome's `Bookmarks` JSON (`roots.bookmark_bar`, `roots.other`, ...).
+pub fn parse_chrome_bookmarks(root: &Value) -> Vec<Bookmark> {
+    fn walk(node: &Value, folder: &str, out: &mut Vec<Bookmark>) {
+        let name = node.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        match node.get("type").and_then(|v| v.as_str()) {
+            Some("url") => {
+                if let Some(url) = node.get("url").and_then(|v| v.as_str()) {
+                    out.push(Bookmark {
+                        url: url.to_string(),
+                        title: name.to_string(),
+                        folder: folder.to_string(),
+                        added_at: node
+                            .get("date_added")
+                            .and_then(|v| v.as_str())
+                            .and_then(|s| s.parse().ok())
+                            .and_then(chrome_time_to_sqlite),
+                        visit_count: None,
+                    });
+                }
+            }
+            Some("folder") => {
+                let path = if folder.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}/{}", folder, name)
+                };
+                for child in node
+                    .get("children")
+                    .and_then(|c| c.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    walk(child, &path, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    if let Some(roots) = root.get("roots").and_then(|r| r.as_object()) {
+        for node in roots.values() {
+            walk(node, "", &mut out);
+        }
+    }
+    out
+}
+
+fn read_firefox_bookmarks(conn: &Connection) -> EngineResult<Vec<Bookmark>> {
+    let mut stmt = conn.prepare(
+        "SELECT p.url, COALESCE(b.title, p.title, ''), b.dateAdded, COALESCE(f.title, '')
+         FROM moz_bookmarks b
+         JOIN moz_places p ON p.id = b.fk
+         LEFT JOIN moz_bookmarks f ON f.id = b.parent
+         WHERE b.type = 1
+         ORDER BY b.dateAdded DESC",
+    )?;
+    let rows = stmt.query_map([], |r| {
+        Ok(Bookmark {
+            url: r.get(0)?,
+            title: r.get(1)?,
+            added_at: r.get::<_, Option<i64>>(2)?.and_then(unix_micros_to_sqlite),
+            folder: r.get(3)?,
+            visit_count: None,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn read_history(
+    conn: &Connection,
+    sql: &str,
+    min_visits: i64,
+    limit: usize,
+    to_time: fn(i64) -> Option<String>,
+) -> EngineResult<Vec<Bookmark>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![min_visits, limit as i64], |r| {
+        Ok(Bookmark {
+            url: r.get(0)?,
+            title: r.get(1)?,
+            folder: "History".into(),
+            visit_count: r.get(2)?,
+            added_at: r.get::<_, Option<i64>>(3)?.and_then(to_time),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// A private copy of a browser database, deleted when dropped.
+struct DbCopy {
+    conn: Option<Connection>,
+    dir: PathBuf,
+}
+
+impl std::ops::Deref for DbCopy {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is open until drop")
+    }
+}
+
+impl Drop for DbCopy {
+    fn drop(&mut self) {
+        drop(self.conn.take());
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Copy a (possibly locked) browser database, with its WAL, and open the copy.
+fn open_copy(path: &Path) -> EngineResult<DbCopy> {
+    let dir = std::env::temp_dir().join(format!("paw-browser-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let copy = dir.join("db.sqlite");
+    let mut guard = DbCopy { conn: None, dir };
+    std::fs::copy(path, &copy)
+        .map_err(|e| EngineError::Other(format!("Cannot read {}: {}", path.display(), e)))?;
+    let wal = PathBuf::from(format!("{}-wal", path.display()));
+    if wal.exists() {
+        let _ = std::fs::copy(&wal, guard.dir.join("db.sqlite-wal"));
+    }
+    guard.conn = Some(Connection::open(&copy)?);
+    Ok(guard)
+}
+
+/// Chrome timestamps are microseconds since 1601-01-01.
+fn chrome_time_to_sqlite(micros: i64) -> Option<String> {
+    const EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+    if micros <= 0 {
+        return None;
+    }
+    unix_micros_to_sqlite(micros - EPOCH_OFFSET_MICROS)
+}
+
+fn unix_micros_to_sqlite(micros: i64) -> Option<String> {
+    let dt = chrono::DateTime::from_timestamp_micros(micros)?;
+    Some(dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn chrome_bookmarks_keep_folder_paths() {
+        let root = json!({
+            "roots": {
+                "bookmark_bar": {
+                    "type": "folder", "name": "Bookmarks bar",
+                    "children": [
+                        { "type": "url", "name": "Rust", "url": "https://www.rust-lang.org/",
+                          "date_added": "13350000000000000" },
+                        { "type": "folder", "name": "Docs", "children": [
+                            { "type": "url", "name": "Tauri", "url": "https://tauri.app/" }
+                        ]}
+                    ]
+                },
+                "other": { "type": "folder", "name": "Other bookmarks", "children": [] }
+            }
+        });
+        let marks = parse_chrome_bookmarks(&root);
+        assert_eq!(marks.len(), 2);
+        assert_eq!(marks[0].folder, "Bookmarks bar");
+        assert_eq!(marks[0].added_at.as_deref(), Some("2024-01-17 21:20:00"));
+        assert_eq!(marks[1].folder, "Bookmarks bar/Docs");
+        assert_eq!(marks[1].title, "Tauri");
+    }
+
+    #[test]
+    fn firefox_profile_reads_bookmarks_and_history() {
+        let dir = std::env::temp_dir().join(format!("paw-ff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = Connection::open(dir.join("places.sqlite")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT,
+                visit_count INTEGER, hidden INTEGER, frecency INTEGER, last_visit_date INTEGER);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                parent INTEGER, title TEXT, dateAdded INTEGER);
+             INSERT INTO moz_places VALUES
+                (1, 'https://docs.rs/', 'Docs.rs', 40, 0, 900, 1700000000000000),
+                (2, 'place:sort=8', NULL, 0, 0, 0, NULL),
+                (3, 'https://news.example/', 'News', 12, 0, 500, 1700000000000000);
+             INSERT INTO moz_bookmarks VALUES
+                (10, 2, NULL, 0, 'toolbar', NULL),
+                (11, 1, 1, 10, 'docs.rs', 1690000000000000),
+                (12, 1, 2, 10, 'Recent', 1690000000000000);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let marks = read_profile(Browser::Firefox, &dir, None).unwrap();
+        assert_eq!(marks.len(), 1);
+        assert_eq!(marks[0].folder, "toolbar");
+        assert_eq!(marks[0].added_at.as_deref(), Some("2023-07-22 04:26:40"));
+
+        // docs.rs appears in both but is returned once
+        let all = read_profile(Browser::Firefox, &dir, Some((10, 50))).unwrap();
+        let urls: Vec<_> = all.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(urls, ["https://docs.rs/", "https://news.example/"]);
+        assert_eq!(all[1].folder, "History");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,108 @@
+// ── Run cancellation ───────────────────────────────────────────────────────
+//
+// A CancelToken stops an agent run cooperatively, so it can still report the
+// output it had so far. The run's task executes inside `scope(token, ..)`;
+// everything it awaits can then see the token without it being threaded
+// through every signature:
+//   - providers read their SSE stream through `or_cancelled`, stop at the
+//     next chunk and return what they have (dropping the response closes the
+//     HTTP stream)
+//   - the agent loop checks `current()` between model calls and tool calls,
+//     and runs tools through `or_cancelled` so a slow tool is dropped too
+// Outside a scope nothing is ever cancelled.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// Shared cancellation flag of one run.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<Inner>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: CancelToken;
+}
+
+/// Run `fut` with `token` as the current run's token.
+pub async fn scope<F: Future>(token: CancelToken, fut: F) -> F::Output {
+    CURRENT.scope(token, fut).await
+}
+
+/// The current run's token (one that's never cancelled outside a scope).
+pub fn current() -> CancelToken {
+    CURRENT.try_with(|t| t.clone()).unwrap_or_default()
+}
+
+/// `fut`'s output, or `None` if the current run is cancelled first.
+pub async fn or_cancelled<F: Future>(fut: F) -> Option<F::Output> {
+    let token = current();
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        out = fut => Some(out),
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn cancelling_drops_the_awaited_future() {
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let out = scope(token.clone(), async {
+            let quick = or_cancelled(async { 1 }).await;
+            let slow = or_cancelled(tokio::time::sleep(Duration::from_secs(30))).await;
+            (quick, slow, current().is_cancelled())
+        })
+        .await;
+        assert_eq!(out, (Some(1), None, true));
+    }
+
+    #[tokio::test]
+    async fn nothing_is_cancelled_outside_a_scope() {
+        assert!(!current().is_cancelled());
+        assert_eq!(or_cancelled(async { 2 }).await, Some(2));
+    }
+}
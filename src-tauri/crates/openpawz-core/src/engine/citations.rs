@@ -0,0 +1,317 @@
+// Paw Agent Engine — Answer citations
+//
+// The agent loop records what each web tool returned during a turn
+// (`Provenance::record`). Once the final answer is in, `attribute` keeps
+// only the sources the answer actually drew on:
+//   - the answer links or names the page (its URL without the scheme), or
+//   - an answer sentence shares most of its words with a sentence of the
+//     page, which becomes the citation's quote.
+// Sources a provider grounded the answer on itself (Gemini search
+// grounding, `record_grounding`) are always cited.
+// Sources are numbered in the order the tools fetched them.
+
+use crate::engine::types::Citation;
+use std::collections::HashSet;
+
+/// Tools whose results are web content worth citing.
+pub const WEB_TOOLS: &[&str] = &["web_read", "web_browse", "web_search", "fetch"];
+
+/// Page text kept per source for matching.
+const MAX_SOURCE_CHARS: usize = 30_000;
+const MAX_SOURCES: usize = 40;
+const MAX_CITATIONS: usize = 10;
+const MAX_QUOTE_CHARS: usize = 300;
+/// An answer sentence needs this many shared words, making up this share
+/// of its words, to count as drawn from a source sentence.
+const MIN_SHARED_WORDS: usize = 5;
+const MIN_OVERLAP: f64 = 0.6;
+
+const STOPWORDS: &[&str] = &[
+    "about", "also", "been", "from", "have", "into", "more", "than", "that", "their", "there",
+    "they", "this", "were", "which", "will", "with",
+];
+
+#[derive(Debug, Clone)]
+struct Source {
+    tool: String,
+    url: String,
+    title: String,
+    text: String,
+    /// Provider-reported grounding: cited even without a textual match.
+    grounded: bool,
+}
+
+/// Web content fetched by tools during one agent turn.
+#[derive(Debug, Default)]
+pub struct Provenance {
+    sources: Vec<Source>,
+}
+
+impl Provenance {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Record a successful tool result. Non-web tools are ignored.
+    pub fn record(&mut self, tool: &str, arguments: &str, output: &str) {
+        if !WEB_TOOLS.contains(&tool) {
+            return;
+        }
+        let args: serde_json::Value = serde_json::from_str(arguments).unwrap_or_default();
+        let arg_url = args["url"].as_str().unwrap_or("").trim();
+        match tool {
+            "web_search" => {
+                for (title, url, snippet) in parse_search_results(output) {
+                    self.add(tool, &url, &title, &snippet);
+                }
+            }
+            "fetch" => {
+                // "HTTP 200 OK\n\n<body>" — error statuses aren't sources
+                let (status, body) = output.split_once("\n\n").unwrap_or((output, ""));
+                if status.ends_with(" OK") {
+                    self.add(tool, arg_url, "", body);
+                }
+            }
+            "web_browse" => self.add(tool, arg_url, "", output),
+            _ => {
+                // web_read: "# Title\nSource: url (HTTP 200)\n\n<text>"
+                let (head, body) = output.split_once("\n\n").unwrap_or(("", output));
+                let title = head
+                    .lines()
+                    .next()
+                    .and_then(|l| l.strip_prefix("# "))
+                    .unwrap_or("");
+                self.add(tool, arg_url, title, body);
+            }
+        }
+    }
+
+    /// Record the sources a provider grounded its answer on; their quotes
+    /// (the supported answer segments) become the source text.
+    pub fn record_grounding(&mut self, sources: &[Citation]) {
+        for source in sources {
+            self.add(
+                &source.tool,
+                &source.url,
+                &source.title,
+                source.quote.as_deref().unwrap_or(""),
+            );
+            let key = url_key(&source.url);
+            if let Some(s) = self.sources.iter_mut().find(|s| url_key(&s.url) == key) {
+                s.grounded = true;
+            }
+        }
+    }
+
+    fn add(&mut self, tool: &str, url: &str, title: &str, text: &str) {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return;
+        }
+        let text = truncate_chars(text, MAX_SOURCE_CHARS);
+        let key = url_key(url);
+        if let Some(existing) = self.sources.iter_mut().find(|s| url_key(&s.url) == key) {
+            // A page read after it turned up in search results replaces the snippet
+            if existing.tool == "web_search" && tool != "web_search" {
+                existing.tool = tool.to_string();
+                existing.text = text;
+                if !title.trim().is_empty() {
+                    existing.title = title.trim().to_string();
+                }
+            }
+            return;
+        }
+        if self.sources.len() < MAX_SOURCES {
+            self.sources.push(Source {
+                tool: tool.to_string(),
+                url: url.to_string(),
+                title: title.trim().to_string(),
+                text,
+                grounded: false,
+            });
+        }
+    }
+
+    /// The sources `answer` used, numbered from 1.
+    pub fn attribute(&self, answer: &str) -> Vec<Citation> {
+        let lower = answer.to_lowercase();
+        let answer_sentences: Vec<HashSet<String>> = sentences(answer)
+            .map(words)
+            .filter(|w| w.len() >= MIN_SHARED_WORDS)
+            .collect();
+
+        let mut citations = Vec::new();
+        for source in &self.sources {
+            let quote = best_quote(&answer_sentences, &source.text);
+            let mentioned = lower.contains(&url_key(&source.url));
+            if quote.is_none() && !mentioned && !source.grounded {
+                continue;
+            }
+            citations.push(Citation {
+                index: citations.len() + 1,
+                url: source.url.clone(),
+                title: source.title.clone(),
+                quote,
+                tool: source.tool.clone(),
+            });
+            if citations.len() == MAX_CITATIONS {
+                break;
+            }
+        }
+        citations
+    }
+}
+
+/// (title, url, snippet) for each entry of a `web_search` result list.
+fn parse_search_results(output: &str) -> Vec<(String, String, String)> {
+    let mut results = Vec::new();
+    for block in output.split("\n\n") {
+        let mut lines = block.lines();
+        let Some(title) = lines
+            .next()
+            .and_then(|l| l.split_once(". **"))
+            .and_then(|(n, rest)| n.trim().parse::<usize>().ok().and(rest.strip_suffix("**")))
+        else {
+            continue;
+        };
+        let Some(url) = lines.next() else { continue };
+        let snippet: Vec<&str> = lines.filter(|l| !l.starts_with("Published: ")).collect();
+        results.push((title.to_string(), url.trim().to_string(), snippet.join(" ")));
+    }
+    results
+}
+
+/// The source sentence that best matches some answer sentence, if any
+/// matches well enough.
+fn best_quote(answer_sentences: &[HashSet<String>], text: &str) -> Option<String> {
+    if answer_sentences.is_empty() {
+        return None;
+    }
+    let mut best: Option<(f64, &str)> = None;
+    for sentence in sentences(text) {
+        let source_words = words(sentence);
+        if source_words.len() < MIN_SHARED_WORDS {
+            continue;
+        }
+        for answer_words in answer_sentences {
+            let shared = answer_words.intersection(&source_words).count();
+            let overlap = shared as f64 / answer_words.len() as f64;
+            if shared >= MIN_SHARED_WORDS
+                && overlap >= MIN_OVERLAP
+                && best.is_none_or(|(b, _)| overlap > b)
+            {
+                best = Some((overlap, sentence));
+            }
+        }
+    }
+    best.map(|(_, s)| truncate_chars(s.trim(), MAX_QUOTE_CHARS))
+}
+
+/// Lines split at sentence-ending punctuation followed by a space, so
+/// "1.80" and "example.com" stay whole.
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .flat_map(|line| {
+            let mut parts = Vec::new();
+            let mut start = 0;
+            let mut chars = line.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let at_break = matches!(c, '.' | '!' | '?')
+                    && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+                if at_break {
+                    parts.push(&line[start..=i]);
+                    start = i + 1;
+                }
+            }
+            parts.push(&line[start..]);
+            parts
+        })
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Distinct, lowercased content words: four letters or more, or anything
+/// with a digit in it.
+fn words(sentence: &str) -> HashSet<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 4 || w.chars().any(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// A URL as it would appear in prose: no scheme, no "www.", no trailing
+/// slash, lowercased.
+fn url_key(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    rest.trim_start_matches("www.")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "# Rust 1.80 released\nSource: https://blog.example/rust-180 (HTTP 200)\n\n\
+        The Rust team shipped version 1.80 on July 25, adding LazyCell and LazyLock to the \
+        standard library. Other changes are minor.";
+
+    #[test]
+    fn cites_sources_the_answer_used() {
+        let mut p = Provenance::default();
+        p.record(
+            "web_read",
+            r#"{"url": "https://blog.example/rust-180"}"#,
+            PAGE,
+        );
+        p.record(
+            "web_search",
+            r#"{"query": "rust"}"#,
+            "Search results for 'rust' (Brave):\n\n\
+             1. **Rust 1.80 released**\nhttps://blog.example/rust-180/\nRelease notes.\n\n\
+             2. **Crates weekly**\nhttps://www.crates.example/weekly\nPublished: 2024-07-26\nNews roundup.\n\n",
+        );
+        p.record(
+            "fetch",
+            r#"{"url": "https://api.example/x"}"#,
+            "HTTP 404 Error\n\nnope",
+        );
+        p.record("exec", r#"{"command": "ls"}"#, "https://not.a.source");
+
+        let answer = "Rust 1.80 shipped on July 25 and adds LazyCell and LazyLock to the standard \
+                      library. See also crates.example/weekly for more.";
+        let cites = p.attribute(answer);
+        assert_eq!(cites.len(), 2);
+        assert_eq!(cites[0].index, 1);
+        assert_eq!(cites[0].title, "Rust 1.80 released");
+        assert_eq!(cites[0].tool, "web_read");
+        assert!(cites[0]
+            .quote
+            .as_deref()
+            .unwrap()
+            .starts_with("The Rust team shipped version 1.80 on July 25"));
+        assert_eq!(cites[1].url, "https://www.crates.example/weekly");
+        assert_eq!(cites[1].quote, None);
+
+        assert!(p.attribute("I couldn't find anything useful.").is_empty());
+
+        p.record_grounding(&[Citation {
+            index: 1,
+            url: "https://grounded.example/page".into(),
+            title: "Grounded".into(),
+            quote: Some("It rained".into()),
+            tool: "google_search".into(),
+        }]);
+        let cites = p.attribute("I couldn't find anything useful.");
+        assert_eq!(cites.len(), 1);
+        assert_eq!(cites[0].title, "Grounded");
+    }
+}
@@ -0,0 +1,156 @@
+// Paw Agent Engine — Confidence annotations
+//
+// An optional pass after a chat turn: a judge model reads the question and
+// the final answer and lists the claims it is unsure about, each with an
+// estimated probability of being right. Only claims quoted verbatim from
+// the answer and below the confidence threshold are kept, so the UI can
+// highlight them in place. The judge is a local Ollama model when
+// configured, otherwise the worker / default model.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use crate::engine::types::{ConfidenceAnnotation, ConfidenceLevel};
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "confidence_settings";
+
+/// Answers shorter than this are not worth judging.
+pub const MIN_ANSWER_CHARS: usize = 200;
+/// Claims the judge rates at or above this are not surfaced.
+const MAX_FLAGGED_CONFIDENCE: f64 = 0.7;
+/// Below this a claim is `low`, otherwise `medium`.
+const LOW_CONFIDENCE: f64 = 0.4;
+const MAX_ANNOTATIONS: usize = 8;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceSettings {
+    /// Run the pass after every chat answer.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Ollama model used as judge. `None` = the Ollama provider's default
+    /// model; without an Ollama provider the worker model judges.
+    #[serde(default)]
+    pub local_model: Option<String>,
+}
+
+pub fn load_settings(store: &SessionStore) -> ConfidenceSettings {
+    store
+        .get_config(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(store: &SessionStore, settings: &ConfidenceSettings) -> EngineResult<()> {
+    store.set_config(SETTINGS_KEY, &serde_json::to_string(settings)?)
+}
+
+pub const JUDGE_SYSTEM: &str = "You are a careful fact-checking judge. You review an \
+assistant's answer and flag the specific claims that may be wrong: unsourced figures, \
+dates and names, outdated facts, speculation stated as fact, and anything you cannot \
+verify from general knowledge. Be calibrated — most well-known facts are not uncertain. \
+Answer with JSON only.";
+
+pub fn judge_prompt(question: &str, answer: &str) -> String {
+    format!(
+        "Question:\n{}\n\nAnswer:\n{}\n\n\
+         List up to {} claims from the answer you are not confident about. Quote each \
+         claim exactly as written in the answer (a sentence or shorter), estimate the \
+         probability it is correct (0.0-1.0), and give a short reason. Use an empty list \
+         when everything is solid. Reply as:\n\
+         {{\"claims\": [{{\"claim\": \"...\", \"confidence\": 0.5, \"reason\": \"...\"}}]}}",
+        question, answer, MAX_ANNOTATIONS
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct JudgedClaim {
+    claim: String,
+    confidence: f64,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Judgement {
+    #[serde(default)]
+    claims: Vec<JudgedClaim>,
+}
+
+/// Annotations from the judge's reply, in answer order. Claims that don't
+/// appear in the answer (paraphrased or invented) are dropped, as are
+/// claims the judge is confident in.
+pub fn parse_annotations(reply: &str, answer: &str) -> Vec<ConfidenceAnnotation> {
+    let (Some(start), Some(end)) = (reply.find('{'), reply.rfind('}')) else {
+        return Vec::new();
+    };
+    if end <= start {
+        return Vec::new();
+    }
+    let Ok(judgement) = serde_json::from_str::<Judgement>(&reply[start..=end]) else {
+        return Vec::new();
+    };
+
+    let haystack = answer.to_lowercase();
+    let mut found: Vec<(usize, ConfidenceAnnotation)> = judgement
+        .claims
+        .into_iter()
+        .filter(|c| c.confidence.is_finite() && c.confidence < MAX_FLAGGED_CONFIDENCE)
+        .filter_map(|c| {
+            let claim = c.claim.trim().trim_end_matches('.').trim();
+            if claim.is_empty() {
+                return None;
+            }
+            let pos = haystack.find(&claim.to_lowercase())?;
+            // Quote the answer's own text, not the judge's casing
+            let quoted = answer.get(pos..pos + claim.len())?.to_string();
+            let confidence = c.confidence.clamp(0.0, 1.0);
+            Some((
+                pos,
+                ConfidenceAnnotation {
+                    claim: quoted,
+                    confidence,
+                    level: if confidence < LOW_CONFIDENCE {
+                        ConfidenceLevel::Low
+                    } else {
+                        ConfidenceLevel::Medium
+                    },
+                    reason: c.reason.trim().to_string(),
+                },
+            ))
+        })
+        .collect();
+    found.sort_by_key(|(pos, _)| *pos);
+    found.dedup_by_key(|(pos, _)| *pos);
+    found
+        .into_iter()
+        .take(MAX_ANNOTATIONS)
+        .map(|(_, a)| a)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_quoted_uncertain_claims_in_order() {
+        let answer = "The bridge opened in 1932. It is 1,149 m long. Roughly 200,000 \
+                      vehicles cross it daily.";
+        let reply = r#"Here you go:
+        {"claims": [
+          {"claim": "roughly 200,000 vehicles cross it daily.", "confidence": 0.3, "reason": "No source given"},
+          {"claim": "It is 1,149 m long", "confidence": 0.55, "reason": "Length varies by source"},
+          {"claim": "The bridge opened in 1932", "confidence": 0.95, "reason": "Well known"},
+          {"claim": "It carries trains", "confidence": 0.2, "reason": "Not in the answer"}
+        ]}"#;
+        let notes = parse_annotations(reply, answer);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].claim, "It is 1,149 m long");
+        assert_eq!(notes[0].level, ConfidenceLevel::Medium);
+        assert_eq!(notes[1].claim, "Roughly 200,000 vehicles cross it daily");
+        assert_eq!(notes[1].level, ConfidenceLevel::Low);
+        assert!(parse_annotations("no json", answer).is_empty());
+    }
+}
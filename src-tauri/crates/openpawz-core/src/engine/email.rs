@@ -0,0 +1,473 @@
+// ── Outgoing email ─────────────────────────────────────────────────────────
+//
+// One description of an email to send — recipients, a plain-text body with
+// an optional HTML alternative, file attachments and reply threading — and
+// the two formats it is sent in:
+//
+//   to_mime() — a raw RFC 5322 message, for the Gmail API
+//   to_smtp() — the same with From / Date / Message-ID and without Bcc,
+//               for SMTP submission by the native mail client
+//   to_mml()  — a himalaya template (MML), for `himalaya template send`
+//
+// Replies carry In-Reply-To (the parent's Message-ID) and References (the
+// parent's References plus its Message-ID), so mail clients thread them.
+
+use crate::atoms::error::EngineResult;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::path::PathBuf;
+
+/// Gmail's limit for a whole message, attachments included.
+pub const MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Line length of base64 bodies (RFC 2045).
+const BASE64_LINE: usize = 76;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutgoingEmail {
+    pub to: String,
+    /// Comma-separated, empty = none
+    pub cc: String,
+    pub bcc: String,
+    pub subject: String,
+    /// Plain-text body
+    pub text: String,
+    /// HTML alternative of the body
+    pub html: Option<String>,
+    /// Files to attach (already resolved and checked by the caller)
+    pub attachments: Vec<PathBuf>,
+    /// Message-ID of the message this replies to
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+}
+
+impl OutgoingEmail {
+    /// Make this a reply to the message with `message_id`, whose own
+    /// References header was `references` (may be empty).
+    pub fn thread_on(&mut self, message_id: &str, references: &str) {
+        let message_id = message_id.trim();
+        if message_id.is_empty() {
+            return;
+        }
+        self.references = references
+            .split_whitespace()
+            .filter(|r| *r != message_id)
+            .map(str::to_string)
+            .collect();
+        self.references.push(message_id.to_string());
+        self.in_reply_to = Some(message_id.to_string());
+    }
+
+    /// Check the attachments exist and fit in one message.
+    pub fn check_attachments(&self) -> EngineResult<()> {
+        let mut total = 0u64;
+        for path in &self.attachments {
+            let meta = std::fs::metadata(path)
+                .map_err(|e| format!("Attachment '{}': {}", path.display(), e))?;
+            if !meta.is_file() {
+                return Err(format!("Attachment '{}' is not a file", path.display()).into());
+            }
+            total += meta.len();
+        }
+        if total > MAX_ATTACHMENT_BYTES {
+            return Err(format!(
+                "Attachments total {:.1} MB — the limit is {} MB",
+                total as f64 / 1_048_576.0,
+                MAX_ATTACHMENT_BYTES / 1_048_576
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Bare addresses of every recipient (To, Cc and Bcc), for the SMTP
+    /// envelope.
+    pub fn recipients(&self) -> Vec<String> {
+        [&self.to, &self.cc, &self.bcc]
+            .into_iter()
+            .flat_map(|list| address_list(list))
+            .map(|mailbox| match (mailbox.rfind('<'), mailbox.rfind('>')) {
+                (Some(open), Some(close)) if open < close => {
+                    mailbox[open + 1..close].trim().to_string()
+                }
+                _ => mailbox,
+            })
+            .filter(|addr| !addr.is_empty())
+            .collect()
+    }
+
+    fn headers(&self, out: &mut String, eol: &str, bcc: bool) {
+        let mut header = |name: &str, value: &str| {
+            if !value.trim().is_empty() {
+                out.push_str(&format!("{}: {}{}", name, value.trim(), eol));
+            }
+        };
+        header("To", &self.to);
+        header("Cc", &self.cc);
+        if bcc {
+            header("Bcc", &self.bcc);
+        }
+        header("Subject", &encode_header(&self.subject));
+        header("In-Reply-To", self.in_reply_to.as_deref().unwrap_or(""));
+        header("References", &self.references.join(" "));
+    }
+
+    /// The raw RFC 5322 message.
+    pub fn to_mime(&self) -> EngineResult<String> {
+        let mut out = String::new();
+        self.headers(&mut out, "\r\n", true);
+        self.mime_body(out)
+    }
+
+    /// The message as submitted over SMTP, from `from_addr` (with
+    /// `from_name` as display name when not empty). Bcc recipients are only
+    /// in the envelope (`recipients`), never in the message.
+    pub fn to_smtp(&self, from_name: &str, from_addr: &str) -> EngineResult<String> {
+        let domain = from_addr.rsplit_once('@').map_or("localhost", |(_, d)| d);
+        let mut out = match from_name.trim() {
+            "" => format!("From: {}\r\n", from_addr),
+            name => format!(
+                "From: {} <{}>\r\n",
+                if name.is_ascii() {
+                    format!("\"{}\"", name.replace('"', "'"))
+                } else {
+                    encode_header(name)
+                },
+                from_addr
+            ),
+        };
+        out.push_str(&format!("Date: {}\r\n", chrono::Utc::now().to_rfc2822()));
+        out.push_str(&format!(
+            "Message-ID: <{}@{}>\r\n",
+            uuid::Uuid::new_v4().simple(),
+            domain
+        ));
+        self.headers(&mut out, "\r\n", false);
+        self.mime_body(out)
+    }
+
+    /// `headers` followed by the MIME body.
+    fn mime_body(&self, mut out: String) -> EngineResult<String> {
+        out.push_str("MIME-Version: 1.0\r\n");
+
+        let body = match &self.html {
+            Some(html) => {
+                let boundary = new_boundary();
+                let mut part = format!(
+                    "Content-Type: multipart/alternative; boundary=\"{}\"\r\n\r\n",
+                    boundary
+                );
+                part.push_str(&format!("--{}\r\n", boundary));
+                part.push_str(&text_part("text/plain", &self.text));
+                part.push_str(&format!("--{}\r\n", boundary));
+                part.push_str(&text_part("text/html", html));
+                part.push_str(&format!("--{}--\r\n", boundary));
+                part
+            }
+            None => text_part("text/plain", &self.text),
+        };
+
+        if self.attachments.is_empty() {
+            out.push_str(&body);
+            return Ok(out);
+        }
+
+        let boundary = new_boundary();
+        out.push_str(&format!(
+            "Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+            boundary
+        ));
+        out.push_str(&format!("--{}\r\n{}", boundary, body));
+        for path in &self.attachments {
+            let data = std::fs::read(path)
+                .map_err(|e| format!("Attachment '{}': {}", path.display(), e))?;
+            let name = file_name(path);
+            out.push_str(&format!("--{}\r\n", boundary));
+            out.push_str(&format!(
+                "Content-Type: {}; name=\"{}\"\r\n",
+                content_type(&name),
+                name
+            ));
+            out.push_str(&format!(
+                "Content-Disposition: attachment; filename=\"{}\"\r\n",
+                name
+            ));
+            out.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+            out.push_str(&base64_lines(&data));
+        }
+        out.push_str(&format!("--{}--\r\n", boundary));
+        Ok(out)
+    }
+
+    /// The himalaya template. himalaya compiles it to MIME, reading the
+    /// attachments itself.
+    pub fn to_mml(&self) -> String {
+        let mut out = String::new();
+        self.headers(&mut out, "\n", true);
+        out.push('\n');
+
+        let mixed = !self.attachments.is_empty();
+        if mixed {
+            out.push_str("<#multipart type=mixed>\n");
+        }
+        match &self.html {
+            Some(html) => {
+                out.push_str("<#multipart type=alternative>\n<#part type=text/plain>\n");
+                out.push_str(&escape_mml(&self.text));
+                out.push_str("\n<#part type=text/html>\n");
+                out.push_str(&escape_mml(html));
+                out.push_str("\n<#/multipart>\n");
+            }
+            None if mixed => {
+                out.push_str("<#part type=text/plain>\n");
+                out.push_str(&escape_mml(&self.text));
+                out.push_str("\n<#/part>\n");
+            }
+            None => out.push_str(&escape_mml(&self.text)),
+        }
+        for path in &self.attachments {
+            out.push_str(&format!(
+                "<#part filename=\"{}\"><#/part>\n",
+                path.display().to_string().replace('"', "\\\"")
+            ));
+        }
+        if mixed {
+            out.push_str("<#/multipart>\n");
+        }
+        out
+    }
+}
+
+/// The value of header `name` in a raw header block (unfolded).
+pub fn header_value(raw: &str, name: &str) -> Option<String> {
+    let mut lines = raw.lines().take_while(|l| !l.is_empty()).peekable();
+    while let Some(line) = lines.next() {
+        // Continuation lines belong to the header above
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case(name) {
+            continue;
+        }
+        let mut value = value.trim().to_string();
+        while let Some(cont) = lines.next_if(|l| l.starts_with([' ', '\t'])) {
+            value.push(' ');
+            value.push_str(cont.trim());
+        }
+        return Some(value);
+    }
+    None
+}
+
+/// The mailboxes of an address list header (`a@x, "Doe, Jo" <jo@x>`),
+/// split on the commas outside quotes and angle brackets.
+pub fn address_list(value: &str) -> Vec<String> {
+    let mut list = Vec::new();
+    let mut current = String::new();
+    let (mut quoted, mut angle) = (false, false);
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => angle = true,
+            '>' if !quoted => angle = false,
+            ',' if !quoted && !angle => {
+                list.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    list.push(current);
+    list.into_iter()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// `Re: subject`, unless it already is one.
+pub fn reply_subject(subject: &str) -> String {
+    let subject = subject.trim();
+    if subject.len() >= 3 && subject[..3].eq_ignore_ascii_case("re:") {
+        subject.to_string()
+    } else {
+        format!("Re: {}", subject)
+    }
+}
+
+fn text_part(content_type: &str, text: &str) -> String {
+    format!(
+        "Content-Type: {}; charset=\"UTF-8\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        content_type,
+        base64_lines(text.as_bytes())
+    )
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE * 2 + 2);
+    for chunk in encoded.as_bytes().chunks(BASE64_LINE) {
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// RFC 2047 encoding for non-ASCII header text.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+fn new_boundary() -> String {
+    format!("=_paw_{}", uuid::Uuid::new_v4().simple())
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().replace('"', "'"))
+        .unwrap_or_else(|| "attachment".into())
+}
+
+/// MIME type of an attachment, from its extension.
+pub fn content_type(filename: &str) -> &'static str {
+    let ext = filename
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("txt") | Some("log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("html") | Some("htm") => "text/html",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("pptx") => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Keep body text that looks like an MML tag from being compiled as one.
+fn escape_mml(text: &str) -> String {
+    text.lines()
+        .map(|l| match l.strip_prefix("<#") {
+            Some(rest) => format!("<#!{}", rest),
+            None => l.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email() -> OutgoingEmail {
+        OutgoingEmail {
+            to: "ana@example.com".into(),
+            cc: "bo@example.com".into(),
+            subject: "Q3 report".into(),
+            text: "See attached.".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replies_thread_on_the_parent() {
+        let mut e = email();
+        e.thread_on("<b@x>", "<a@x>\r\n <b@x>");
+        assert_eq!(e.in_reply_to.as_deref(), Some("<b@x>"));
+        assert_eq!(e.references, vec!["<a@x>", "<b@x>"]);
+        let raw = e.to_mime().unwrap();
+        assert!(raw.contains("In-Reply-To: <b@x>\r\nReferences: <a@x> <b@x>\r\n"));
+        assert_eq!(reply_subject("RE: hi"), "RE: hi");
+        assert_eq!(reply_subject("hi"), "Re: hi");
+
+        let headers = "Message-ID: <c@x>\nReferences: <a@x>\n <b@x>\nSubject: hi\n\n<d@x>";
+        assert_eq!(
+            header_value(headers, "message-id").as_deref(),
+            Some("<c@x>")
+        );
+        assert_eq!(
+            header_value(headers, "References").as_deref(),
+            Some("<a@x> <b@x>")
+        );
+        assert_eq!(header_value(headers, "X-Missing"), None);
+    }
+
+    #[test]
+    fn mime_has_html_alternative_and_attachments() {
+        let path = std::env::temp_dir().join(format!("paw-mail-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "a,b\n1,2\n").unwrap();
+        let mut e = email();
+        e.subject = "Résumé".into();
+        e.html = Some("<p>See attached.</p>".into());
+        e.attachments = vec![path.clone()];
+        e.check_attachments().unwrap();
+
+        let raw = e.to_mime().unwrap();
+        assert!(raw.contains("Subject: =?UTF-8?B?"));
+        assert!(raw.contains("Content-Type: multipart/mixed;"));
+        assert!(raw.contains("Content-Type: multipart/alternative;"));
+        assert!(raw.contains("Content-Type: text/html; charset=\"UTF-8\""));
+        assert!(raw.contains("Content-Type: text/csv; name=\""));
+        assert!(raw.contains(&STANDARD.encode("a,b\n1,2\n")));
+
+        let mml = e.to_mml();
+        assert!(mml.starts_with("To: ana@example.com\nCc: bo@example.com\n"));
+        assert!(mml.contains("<#multipart type=alternative>\n<#part type=text/plain>\n"));
+        assert!(mml.contains(&format!("<#part filename=\"{}\"><#/part>", path.display())));
+        std::fs::remove_file(&path).ok();
+
+        e.attachments = vec![path];
+        assert!(e.check_attachments().is_err());
+    }
+
+    #[test]
+    fn smtp_message_has_sender_and_hides_bcc() {
+        let mut e = email();
+        e.to = "\"Doe, Ana\" <ana@example.com>, cy@example.com".into();
+        e.bcc = "<dee@example.com>".into();
+        assert_eq!(
+            e.recipients(),
+            vec![
+                "ana@example.com",
+                "cy@example.com",
+                "bo@example.com",
+                "dee@example.com"
+            ]
+        );
+
+        let raw = e.to_smtp("Me", "me@paw.dev").unwrap();
+        assert!(raw.starts_with("From: \"Me\" <me@paw.dev>\r\nDate: "));
+        assert!(raw.contains("@paw.dev>\r\nTo: \"Doe, Ana\" <ana@example.com>, cy@example.com\r\n"));
+        assert!(!raw.contains("dee@example.com"));
+        assert!(e.to_mime().unwrap().contains("Bcc: <dee@example.com>\r\n"));
+        assert!(e
+            .to_smtp("", "me@paw.dev")
+            .unwrap()
+            .starts_with("From: me@paw.dev\r\n"));
+    }
+
+    #[test]
+    fn plain_mml_is_just_the_body() {
+        let mut e = email();
+        e.text = "Hi\n<#part>".into();
+        assert_eq!(
+            e.to_mml(),
+            "To: ana@example.com\nCc: bo@example.com\nSubject: Q3 report\n\nHi\n<#!part>"
+        );
+    }
+}
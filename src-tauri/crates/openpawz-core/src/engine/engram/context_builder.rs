@@ -32,7 +32,9 @@
 //   │  └──────────────────────────────────────────┘│
 //   └──────────────────────────────────────────────┘
 
-use crate::atoms::engram_types::{MemoryScope, MemorySearchConfig, RetrievedMemory};
+use crate::atoms::engram_types::{
+    ContextControls, MemoryScope, MemorySearchConfig, RetrievedMemory,
+};
 use crate::atoms::error::EngineResult;
 use crate::engine::engram::encryption;
 use crate::engine::engram::model_caps::{resolve_injection_resistance, resolve_model_capabilities};
@@ -41,6 +43,7 @@ use crate::engine::engram::working_memory::WorkingMemory;
 use crate::engine::memory::EmbeddingClient;
 use crate::engine::sessions::SessionStore;
 use log::{info, warn};
+use serde::Serialize;
 use std::borrow::Cow;
 
 // ═════════════════════════════════════════════════════════════════════════════
@@ -86,10 +89,56 @@ pub struct AssembledContext {
     /// §8.6 Raw query embedding from recall — push into WorkingMemory.push_momentum()
     /// to enable trajectory-aware recall in subsequent turns.
     pub query_embedding: Option<Vec<f32>>,
+    /// Per-section outcome of system prompt assembly, in layer order.
+    pub sections: Vec<SectionReport>,
+}
+
+/// What happened to one system prompt section during budgeting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStatus {
+    Included,
+    /// Replaced by its short fallback text.
+    Fallback,
+    Dropped,
+}
+
+/// Where a system prompt section sits in the layering model. Sections are
+/// emitted in layer order (ties keep insertion order), so the final prompt
+/// always reads platform → global → agent → skills → session → memory,
+/// no matter which sections the budget packer kept or dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLayer {
+    /// Platform awareness, Foreman protocol, runtime context.
+    Platform,
+    /// The global default system prompt.
+    Global,
+    /// Agent profile prompt, soul files, agent roster.
+    Agent,
+    /// Enabled skill instructions.
+    Skills,
+    /// Per-session instructions and consumer sections (task, swarm, …).
+    Session,
+    /// Today's notes, recalled memories, working memory.
+    Memory,
+}
+
+/// One system prompt section as seen by the context inspector.
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionReport {
+    pub name: String,
+    pub layer: PromptLayer,
+    pub priority: u8,
+    pub tokens: usize,
+    pub status: SectionStatus,
+    /// Text that went into the prompt (fallback text when `Fallback`,
+    /// empty when `Dropped`).
+    pub content: String,
 }
 
 /// Token budget breakdown.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct BudgetReport {
     pub context_window: usize,
     pub max_output_tokens: usize,
@@ -105,8 +154,8 @@ pub struct BudgetReport {
 /// Lower priority number = higher importance (never dropped first).
 #[derive(Debug, Clone)]
 struct PromptSection {
-    #[allow(dead_code)] // used in debug logging and future prompt-inspector UI
     name: Cow<'static, str>,
+    layer: PromptLayer,
     content: String,
     priority: u8, // 0 = highest (never drop), 10 = lowest
     tokens: usize,
@@ -128,6 +177,8 @@ pub struct ContextBuilder<'a> {
 
     // System prompt pieces
     base_prompt: Option<String>,
+    agent_prompt: Option<String>,
+    session_instructions: Option<String>,
     runtime_context: Option<String>,
     core_context: Option<String>,
     platform_awareness: Option<String>,
@@ -155,6 +206,9 @@ pub struct ContextBuilder<'a> {
 
     // Override budgets
     context_window_override: Option<usize>,
+
+    // Per-session inclusion controls
+    controls: ContextControls,
 }
 
 impl<'a> ContextBuilder<'a> {
@@ -168,6 +222,8 @@ impl<'a> ContextBuilder<'a> {
             context_window: caps.context_window,
             max_output_tokens: caps.max_output_tokens,
             base_prompt: None,
+            agent_prompt: None,
+            session_instructions: None,
             runtime_context: None,
             core_context: None,
             platform_awareness: None,
@@ -185,6 +241,7 @@ impl<'a> ContextBuilder<'a> {
             working_memory: None,
             messages: Vec::new(),
             context_window_override: None,
+            controls: ContextControls::default(),
         }
     }
 
@@ -194,12 +251,24 @@ impl<'a> ContextBuilder<'a> {
         self
     }
 
-    /// Set the base system prompt (agent personality / instructions).
+    /// Set the base system prompt (global layer — the app-wide default).
     pub fn base_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.base_prompt = Some(prompt.into());
         self
     }
 
+    /// Set the agent's profile prompt (agent layer, ahead of soul files).
+    pub fn agent_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.agent_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Set per-session instructions (session layer, never dropped).
+    pub fn session_instructions(mut self, text: impl Into<String>) -> Self {
+        self.session_instructions = Some(text.into());
+        self
+    }
+
     /// Set runtime context (model, session, time info).
     pub fn runtime_context(mut self, ctx: impl Into<String>) -> Self {
         self.runtime_context = Some(ctx.into());
@@ -296,8 +365,44 @@ impl<'a> ContextBuilder<'a> {
         self
     }
 
+    /// Apply per-session inclusion controls (memories, soul files, skill
+    /// subset, history length). Applied at build time, so call order
+    /// relative to the other setters doesn't matter.
+    pub fn controls(mut self, controls: ContextControls) -> Self {
+        self.controls = controls;
+        self
+    }
+
+    /// Drop the pieces excluded by `self.controls`.
+    fn apply_controls(&mut self) {
+        if !self.controls.include_memories {
+            self.todays_memories = None;
+            self.store = None;
+            self.user_query = None;
+            self.working_memory = None;
+        }
+        if !self.controls.include_soul_files {
+            self.core_context = None;
+        }
+        if let Some(ref ids) = self.controls.skill_ids {
+            self.skill_instructions = self
+                .skill_instructions
+                .take()
+                .and_then(|text| filter_skill_sections(&text, ids));
+        }
+        if let Some(max) = self.controls.max_history_messages {
+            let excess = self.messages.len().saturating_sub(max);
+            self.messages.drain(..excess);
+        }
+    }
+
     /// Build the assembled context.
-    pub async fn build(self) -> EngineResult<AssembledContext> {
+    pub async fn build(mut self) -> EngineResult<AssembledContext> {
+        if self.controls != ContextControls::default() {
+            info!("[engram:context] Session controls: {:?}", self.controls);
+            self.apply_controls();
+        }
+
         let effective_window = self.context_window_override.unwrap_or(self.context_window);
         let reply_reserve = self.max_output_tokens.max(MIN_REPLY_TOKENS);
         let usable_tokens = effective_window.saturating_sub(reply_reserve);
@@ -451,6 +556,7 @@ impl<'a> ContextBuilder<'a> {
             let recall_tokens = self.tokenizer.count_tokens(&recall_text);
             sections.push(PromptSection {
                 name: Cow::Borrowed("recalled_memories"),
+                layer: PromptLayer::Memory,
                 content: recall_text,
                 priority: 7,
                 tokens: recall_tokens,
@@ -465,6 +571,7 @@ impl<'a> ContextBuilder<'a> {
                 let wm_tokens = self.tokenizer.count_tokens(&wm_text);
                 sections.push(PromptSection {
                     name: Cow::Borrowed("working_memory"),
+                    layer: PromptLayer::Memory,
                     content: wm_text,
                     priority: 5, // higher priority than recalled memories
                     tokens: wm_tokens,
@@ -474,8 +581,8 @@ impl<'a> ContextBuilder<'a> {
         }
 
         // ── 4. Assemble system prompt within budget ──────────────────────
-        sections.sort_by_key(|s| s.priority);
-        let (system_prompt, system_tokens) =
+        sections.sort_by_key(|s| s.layer);
+        let (system_prompt, system_tokens, section_reports) =
             assemble_sections(&sections, max_system, &self.tokenizer);
 
         // ── 5. Budget conversation history ───────────────────────────────
@@ -515,97 +622,119 @@ impl<'a> ContextBuilder<'a> {
             budget,
             recalled_memories,
             query_embedding: recall_query_embedding,
+            sections: section_reports,
         })
     }
 
-    /// Collect all configured sections into PromptSection entries.
+    /// Collect all configured sections into PromptSection entries, in
+    /// layer order (the sort in `build` keeps this order within a layer).
     fn collect_sections(&self) -> Vec<PromptSection> {
         let mut sections = Vec::new();
-
-        if let Some(ref text) = self.platform_awareness {
+        let mut push = |name: &'static str,
+                        layer: PromptLayer,
+                        content: String,
+                        priority: u8,
+                        fallback: Option<&str>| {
+            let tokens = self.tokenizer.count_tokens(&content);
             sections.push(PromptSection {
-                name: Cow::Borrowed("platform_awareness"),
-                content: text.clone(),
-                priority: 0,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: None,
+                name: Cow::Borrowed(name),
+                layer,
+                content,
+                priority,
+                tokens,
+                fallback: fallback.map(str::to_string),
             });
-        }
+        };
 
+        // ── Platform ──
+        if let Some(ref text) = self.platform_awareness {
+            push(
+                "platform_awareness",
+                PromptLayer::Platform,
+                text.clone(),
+                0,
+                None,
+            );
+        }
         if let Some(ref text) = self.foreman_protocol {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("foreman_protocol"),
-                content: text.clone(),
-                priority: 0,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: None,
-            });
+            push(
+                "foreman_protocol",
+                PromptLayer::Platform,
+                text.clone(),
+                0,
+                None,
+            );
         }
-
         if let Some(ref text) = self.runtime_context {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("runtime_context"),
-                content: text.clone(),
-                priority: 1,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: None,
-            });
-        }
-
-        if let Some(ref text) = self.core_context {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("soul_files"),
-                content: text.clone(),
-                priority: 2,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: None,
-            });
+            push(
+                "runtime_context",
+                PromptLayer::Platform,
+                text.clone(),
+                1,
+                None,
+            );
         }
 
+        // ── Global ──
         if let Some(ref text) = self.base_prompt {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("base_prompt"),
-                content: text.clone(),
-                priority: 3,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: None,
-            });
+            push("base_prompt", PromptLayer::Global, text.clone(), 3, None);
         }
 
+        // ── Agent ──
+        if let Some(ref text) = self.agent_prompt {
+            push("agent_prompt", PromptLayer::Agent, text.clone(), 3, None);
+        }
+        if let Some(ref text) = self.core_context {
+            push("soul_files", PromptLayer::Agent, text.clone(), 2, None);
+        }
         if let Some(ref text) = self.agent_roster {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("agent_roster"),
-                content: text.clone(),
-                priority: 4,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: Some("Use agent_list to see available agents.".to_string()),
-            });
+            push(
+                "agent_roster",
+                PromptLayer::Agent,
+                text.clone(),
+                4,
+                Some("Use agent_list to see available agents."),
+            );
         }
 
-        if let Some(ref text) = self.todays_memories {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("todays_memories"),
-                content: format!("## Today's Memory Notes\n{}", text),
-                priority: 6,
-                tokens: self.tokenizer.count_tokens(text) + 10,
-                fallback: Some("Use memory_search to find stored information.".to_string()),
-            });
+        // ── Skills ──
+        if let Some(ref text) = self.skill_instructions {
+            push(
+                "skill_instructions",
+                PromptLayer::Skills,
+                text.clone(),
+                8,
+                Some("Use request_tools to get relevant skill instructions."),
+            );
         }
 
-        if let Some(ref text) = self.skill_instructions {
-            sections.push(PromptSection {
-                name: Cow::Borrowed("skill_instructions"),
-                content: text.clone(),
-                priority: 8,
-                tokens: self.tokenizer.count_tokens(text),
-                fallback: Some("Use request_tools to get relevant skill instructions.".to_string()),
-            });
+        // ── Session ──
+        if let Some(ref text) = self.session_instructions {
+            push(
+                "session_instructions",
+                PromptLayer::Session,
+                format!("## Session Instructions\n{}", text),
+                1,
+                None,
+            );
+        }
+
+        // ── Memory ──
+        if let Some(ref text) = self.todays_memories {
+            push(
+                "todays_memories",
+                PromptLayer::Memory,
+                format!("## Today's Memory Notes\n{}", text),
+                6,
+                Some("Use memory_search to find stored information."),
+            );
         }
 
         // Custom sections from consumers (task context, swarm context, etc.)
         for (name, content, priority) in &self.custom_sections {
             sections.push(PromptSection {
                 name: Cow::Owned(name.clone()),
+                layer: PromptLayer::Session,
                 content: content.clone(),
                 priority: *priority,
                 tokens: self.tokenizer.count_tokens(content),
@@ -629,15 +758,16 @@ impl<'a> ContextBuilder<'a> {
 ///    importance-per-token ratio (priority inverted as importance, divided by tokens).
 ///    This maximizes the total importance value within the budget.
 /// 3. Sections that don't fit use their fallback text if available.
+/// 4. Surviving sections are joined in input (layer) order.
 ///
 /// Returns (assembled_prompt, total_tokens).
 fn assemble_sections(
     sections: &[PromptSection],
     budget: usize,
     tokenizer: &Tokenizer,
-) -> (Option<String>, usize) {
+) -> (Option<String>, usize, Vec<SectionReport>) {
     if sections.is_empty() {
-        return (None, 0);
+        return (None, 0, Vec::new());
     }
 
     let separator = "\n\n---\n\n";
@@ -646,9 +776,10 @@ fn assemble_sections(
     let mut included: Vec<&str> = Vec::new();
     let mut fallbacks: Vec<String> = Vec::new();
     let mut used_tokens = 0usize;
+    let mut statuses = vec![SectionStatus::Dropped; sections.len()];
 
     // Phase 1: Always include critical sections (priority ≤ 2)
-    for section in sections {
+    for (idx, section) in sections.iter().enumerate() {
         if section.priority <= 2 {
             let cost = section.tokens
                 + if included.is_empty() {
@@ -659,6 +790,7 @@ fn assemble_sections(
             if used_tokens + cost <= budget {
                 included.push(&section.content);
                 used_tokens += cost;
+                statuses[idx] = SectionStatus::Included;
             }
         }
     }
@@ -689,6 +821,7 @@ fn assemble_sections(
         if used_tokens + cost <= budget {
             included.push(&section.content);
             used_tokens += cost;
+            statuses[idx] = SectionStatus::Included;
         } else if let Some(ref fb) = section.fallback {
             // Section doesn't fit — use fallback if available
             let fb_cost = tokenizer.count_tokens(fb)
@@ -700,23 +833,45 @@ fn assemble_sections(
             if used_tokens + fb_cost <= budget {
                 fallbacks.push(fb.clone());
                 used_tokens += fb_cost;
+                statuses[idx] = SectionStatus::Fallback;
             }
         }
         // else: section dropped entirely
     }
 
+    let reports = sections
+        .iter()
+        .zip(statuses)
+        .map(|(section, status)| SectionReport {
+            name: section.name.to_string(),
+            layer: section.layer,
+            priority: section.priority,
+            tokens: section.tokens,
+            status,
+            content: match status {
+                SectionStatus::Included => section.content.clone(),
+                SectionStatus::Fallback => section.fallback.clone().unwrap_or_default(),
+                SectionStatus::Dropped => String::new(),
+            },
+        })
+        .collect();
+
     if included.is_empty() && fallbacks.is_empty() {
-        return (None, 0);
+        return (None, 0, reports);
     }
 
-    let mut parts: Vec<&str> = included;
-    let fallback_refs: Vec<&str> = fallbacks.iter().map(|s| s.as_str()).collect();
-    parts.extend(fallback_refs);
+    // Emit in section (layer) order, not packing order, so the prompt reads
+    // the same way whichever sections made it in.
+    let parts: Vec<&str> = reports
+        .iter()
+        .filter(|r| r.status != SectionStatus::Dropped)
+        .map(|r| r.content.as_str())
+        .collect();
 
     let assembled = parts.join(separator);
     let total = tokenizer.count_tokens(&assembled);
 
-    (Some(assembled), total)
+    (Some(assembled), total, reports)
 }
 
 // ═════════════════════════════════════════════════════════════════════════════
@@ -769,6 +924,46 @@ enum HistoryBand {
     TagOnly,
 }
 
+/// Keep only the skill sections whose id is in `allowed`.
+/// Sections start at `## ` headings ending in `(<id>)`; the preamble is kept
+/// only when at least one section survives.
+fn filter_skill_sections(text: &str, allowed: &[String]) -> Option<String> {
+    let mut preamble = String::new();
+    let mut kept: Vec<String> = Vec::new();
+    let mut current: Option<(bool, String)> = None;
+
+    for line in text.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            if let Some((keep, body)) = current.take() {
+                if keep {
+                    kept.push(body);
+                }
+            }
+            let id = heading
+                .trim_end()
+                .strip_suffix(')')
+                .and_then(|h| h.rsplit_once('('))
+                .map(|(_, id)| id);
+            let keep = id.is_some_and(|id| allowed.iter().any(|a| a == id));
+            current = Some((keep, format!("{}\n", line)));
+        } else if let Some((_, ref mut body)) = current {
+            body.push_str(line);
+            body.push('\n');
+        } else {
+            preamble.push_str(line);
+            preamble.push('\n');
+        }
+    }
+    if let Some((true, body)) = current {
+        kept.push(body);
+    }
+
+    if kept.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", preamble, kept.concat()))
+}
+
 /// Band allocation ratios (from most recent to oldest).
 const BAND_A_RATIO: f32 = 0.35;
 const BAND_B_RATIO: f32 = 0.25;
@@ -907,7 +1102,7 @@ mod tests {
     #[test]
     fn test_assemble_sections_empty() {
         let tok = make_tokenizer();
-        let (result, tokens) = assemble_sections(&[], 1000, &tok);
+        let (result, tokens, _) = assemble_sections(&[], 1000, &tok);
         assert!(result.is_none());
         assert_eq!(tokens, 0);
     }
@@ -917,14 +1112,17 @@ mod tests {
         let tok = make_tokenizer();
         let sections = vec![PromptSection {
             name: Cow::Borrowed("test"),
+            layer: PromptLayer::Platform,
             content: "Hello world".to_string(),
             priority: 0,
             tokens: tok.count_tokens("Hello world"),
             fallback: None,
         }];
-        let (result, tokens) = assemble_sections(&sections, 1000, &tok);
+        let (result, tokens, reports) = assemble_sections(&sections, 1000, &tok);
         assert_eq!(result.as_deref(), Some("Hello world"));
         assert!(tokens > 0);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].status, SectionStatus::Included);
     }
 
     #[test]
@@ -934,6 +1132,7 @@ mod tests {
         let sections = vec![
             PromptSection {
                 name: Cow::Borrowed("critical"),
+                layer: PromptLayer::Platform,
                 content: "Important".to_string(),
                 priority: 0,
                 tokens: tok.count_tokens("Important"),
@@ -941,6 +1140,7 @@ mod tests {
             },
             PromptSection {
                 name: Cow::Borrowed("optional"),
+                layer: PromptLayer::Memory,
                 content: long_text,
                 priority: 10,
                 tokens: 600, // won't fit
@@ -948,10 +1148,13 @@ mod tests {
             },
         ];
         // Budget of 20 tokens — only "Important" fits
-        let (result, _) = assemble_sections(&sections, 20, &tok);
+        let (result, _, reports) = assemble_sections(&sections, 20, &tok);
         let text = result.unwrap();
         assert!(text.contains("Important"));
         // The fallback might or might not fit at 20 tokens
+        assert_eq!(reports[0].status, SectionStatus::Included);
+        assert_ne!(reports[1].status, SectionStatus::Included);
+        assert!(!reports[1].content.contains("xxx"));
     }
 
     #[test]
@@ -1024,10 +1227,81 @@ mod tests {
         assert_eq!(truncate_str("hello world", 5), "hello");
     }
 
+    #[test]
+    fn test_sections_follow_layer_order() {
+        let tok = make_tokenizer();
+        let builder = ContextBuilder::new("gpt-4o")
+            .todays_memories("notes")
+            .session_instructions("Reply in French.")
+            .skill_instructions("## GitHub Skill (github)\nUse gh.\n")
+            .core_context("SOUL")
+            .agent_prompt("You are Ada.")
+            .base_prompt("You are helpful.")
+            .runtime_context("runtime");
+        let mut sections = builder.collect_sections();
+        sections.sort_by_key(|s| s.layer);
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(
+            names,
+            [
+                "runtime_context",
+                "base_prompt",
+                "agent_prompt",
+                "soul_files",
+                "skill_instructions",
+                "session_instructions",
+                "todays_memories"
+            ]
+        );
+
+        let (text, _, _) = assemble_sections(&sections, 10_000, &tok);
+        let text = text.unwrap();
+        let pos = |needle: &str| text.find(needle).unwrap();
+        assert!(pos("You are helpful.") < pos("You are Ada."));
+        assert!(pos("You are Ada.") < pos("SOUL"));
+        assert!(pos("Use gh.") < pos("Reply in French."));
+        assert!(pos("Reply in French.") < pos("notes"));
+    }
+
     #[test]
     fn test_budget_report_defaults() {
         let report = BudgetReport::default();
         assert_eq!(report.context_window, 0);
         assert_eq!(report.memories_injected, 0);
     }
+
+    #[test]
+    fn test_filter_skill_sections() {
+        let text = "\n\n# Enabled Skills\nIntro.\n\n## GitHub Skill (github)\nUse gh.\n\n## Slack Skill (slack)\nPost.\n\n## Helper (community)\nHelp.\n";
+        let out = filter_skill_sections(text, &["slack".to_string()]).unwrap();
+        assert!(out.contains("# Enabled Skills"));
+        assert!(out.contains("## Slack Skill (slack)\nPost."));
+        assert!(!out.contains("github"));
+        assert!(!out.contains("Helper"));
+        assert!(filter_skill_sections(text, &["missing".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_apply_controls() {
+        let history: Vec<(String, String)> = (0..5)
+            .map(|i| ("user".to_string(), format!("msg {}", i)))
+            .collect();
+        let mut builder = ContextBuilder::new("gpt-4o")
+            .core_context("IDENTITY")
+            .todays_memories("notes")
+            .skill_instructions("## GitHub Skill (github)\nUse gh.\n")
+            .messages(history)
+            .controls(ContextControls {
+                include_memories: false,
+                include_soul_files: false,
+                skill_ids: Some(vec!["github".into()]),
+                max_history_messages: Some(2),
+            });
+        builder.apply_controls();
+        assert!(builder.core_context.is_none());
+        assert!(builder.todays_memories.is_none());
+        assert!(builder.skill_instructions.is_some());
+        assert_eq!(builder.messages.len(), 2);
+        assert_eq!(builder.messages[0].1, "msg 3");
+    }
 }
@@ -14,14 +14,15 @@
 
 use crate::atoms::engram_types::{
     CompressionLevel, EdgeType, EpisodicMemory, MemoryEdge, MemoryScope, MemorySearchConfig,
-    MemoryType, ProceduralMemory, RecallResult, RetrievedMemory, SemanticMemory, TieredContent,
-    TrustScore,
+    MemoryType, ProceduralMemory, RecallResult, RerankStrategy, RetrievedMemory, SemanticMemory,
+    TieredContent, TrustScore,
 };
 use crate::atoms::error::EngineResult;
 use crate::engine::engram::encryption::{
     get_agent_encryption_key, prepare_for_storage, MemorySecurityTier,
 };
 use crate::engine::engram::hybrid_search::resolve_hybrid_weight;
+use crate::engine::engram::reranker;
 use crate::engine::engram::reranking::{cross_type_dedup, rerank_results};
 use crate::engine::engram::retrieval_quality::build_recall_result;
 use crate::engine::engram::tokenizer::Tokenizer;
@@ -592,15 +593,34 @@ pub async fn search(
     });
 
     // ── Reranking (§35.1) ────────────────────────────────────────────
+    // CrossEncoder asks a model first; when it fails or misses its latency
+    // budget the heuristic pipeline runs instead (and is what gets reported).
     let rerank_applied = if config.rerank_enabled {
-        all_results = rerank_results(
-            &all_results,
-            query,
-            raw_query_embedding.as_deref(),
-            config.rerank_strategy,
-            config.mmr_lambda,
-        );
-        Some(config.rerank_strategy)
+        let heuristic = |results: &[RetrievedMemory]| {
+            rerank_results(
+                results,
+                query,
+                raw_query_embedding.as_deref(),
+                config.rerank_strategy,
+                config.mmr_lambda,
+            )
+        };
+        if config.rerank_strategy == RerankStrategy::CrossEncoder {
+            let heuristic_order = heuristic(&all_results);
+            match reranker::rerank(&heuristic_order, query, &config.reranker).await {
+                Some(ranked) => {
+                    all_results = ranked;
+                    Some(RerankStrategy::CrossEncoder)
+                }
+                None => {
+                    all_results = heuristic_order;
+                    Some(RerankStrategy::RRFThenMMR)
+                }
+            }
+        } else {
+            all_results = heuristic(&all_results);
+            Some(config.rerank_strategy)
+        }
     } else {
         None
     };
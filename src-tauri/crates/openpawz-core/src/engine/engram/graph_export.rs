@@ -0,0 +1,352 @@
+// ── Engram: Knowledge Graph Export ─────────────────────────────────────────
+//
+// Dumps the memory graph so users can open it in Gephi, Cytoscape, yEd or
+// a notebook and check what their agents have actually learned.
+//
+// Nodes:
+//   memory  — episodic memories (decrypted, content as the label)
+//   entity  — subjects/objects of semantic triples and tracked entity profiles
+//
+// Edges:
+//   relation — memory_edges (supports, contradicts, related_to, …)
+//   fact     — semantic triple, subject → object, labelled with the predicate
+//   mentions — memory → entity, from entity profiles
+//
+// Formats: JSON (the KnowledgeGraph struct) and GraphML.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use log::info;
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Json,
+    GraphMl,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(GraphFormat::Json),
+            "graphml" | "xml" => Ok(GraphFormat::GraphMl),
+            other => Err(format!(
+                "Unknown graph format '{}' — use json or graphml",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GraphNode {
+    pub id: String,
+    /// "memory" or "entity".
+    pub kind: String,
+    pub label: String,
+    /// Memory category or entity type.
+    pub category: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// "relation", "fact" or "mentions".
+    pub kind: String,
+    /// Relation type or triple predicate.
+    pub label: String,
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeGraph {
+    pub exported_at: String,
+    /// Agent the export is limited to; None = all agents.
+    pub agent_id: Option<String>,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+fn entity_node_id(name: &str) -> String {
+    format!("entity:{}", name.trim().to_lowercase())
+}
+
+/// Collect the graph. `limit` caps the number of memories (most important
+/// first) and of triples; edges are kept only between exported nodes.
+pub fn export_graph(
+    store: &SessionStore,
+    agent_id: Option<&str>,
+    limit: usize,
+) -> EngineResult<KnowledgeGraph> {
+    let mut nodes: Vec<GraphNode> = Vec::new();
+    let mut edges: Vec<GraphEdge> = Vec::new();
+    let mut node_ids: HashSet<String> = HashSet::new();
+
+    let memories: Vec<(String, String, String, String, String)>;
+    let triples: Vec<(String, String, String, f32, String)>;
+    let relations: Vec<(String, String, String, f32)>;
+    let profiles: Vec<(String, String, String, String)>;
+    {
+        let conn = store.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, content_full, category, agent_id, created_at FROM episodic_memories
+             WHERE ?1 IS NULL OR agent_id = ?1
+             ORDER BY importance DESC, created_at DESC LIMIT ?2",
+        )?;
+        memories = stmt
+            .query_map(params![agent_id, limit as i64], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT subject, predicate, object, confidence, created_at FROM semantic_memories
+             WHERE ?1 IS NULL OR scope_agent_id = ?1 OR scope_agent_id = ''
+             ORDER BY confidence DESC LIMIT ?2",
+        )?;
+        triples = stmt
+            .query_map(params![agent_id, limit as i64], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut stmt =
+            conn.prepare("SELECT source_id, target_id, edge_type, weight FROM memory_edges")?;
+        relations = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // entity_profiles is created lazily by entity tracking
+        profiles = match conn.prepare(
+            "SELECT canonical_name, entity_type, first_seen, memory_ids FROM entity_profiles",
+        ) {
+            Ok(mut stmt) => stmt
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?
+                .filter_map(|r| r.ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+    }
+
+    for (id, content, category, owner, created_at) in memories {
+        let mut label = content;
+        if super::encryption::is_encrypted(&label) {
+            if let Ok(key) = super::encryption::get_agent_encryption_key(&owner) {
+                label = super::encryption::decrypt_memory_content(&label, &key).unwrap_or(label);
+            }
+        }
+        node_ids.insert(id.clone());
+        nodes.push(GraphNode {
+            id,
+            kind: "memory".into(),
+            label,
+            category,
+            created_at,
+        });
+    }
+
+    let memory_ids_exported = node_ids.clone();
+    let mut add_entity =
+        |name: &str, category: &str, created_at: &str, nodes: &mut Vec<GraphNode>| {
+            let id = entity_node_id(name);
+            if node_ids.insert(id.clone()) {
+                nodes.push(GraphNode {
+                    id: id.clone(),
+                    kind: "entity".into(),
+                    label: name.trim().to_string(),
+                    category: category.into(),
+                    created_at: created_at.into(),
+                });
+            }
+            id
+        };
+
+    for (subject, predicate, object, confidence, created_at) in triples {
+        let source = add_entity(&subject, "concept", &created_at, &mut nodes);
+        let target = add_entity(&object, "concept", &created_at, &mut nodes);
+        edges.push(GraphEdge {
+            source,
+            target,
+            kind: "fact".into(),
+            label: predicate,
+            weight: confidence,
+        });
+    }
+
+    for (name, entity_type, first_seen, memory_ids) in profiles {
+        let ids: Vec<String> = serde_json::from_str(&memory_ids).unwrap_or_default();
+        let ids: Vec<String> = ids
+            .into_iter()
+            .filter(|id| memory_ids_exported.contains(id))
+            .collect();
+        // In a per-agent export, skip entities only other agents know about
+        if agent_id.is_some() && ids.is_empty() {
+            continue;
+        }
+        let entity = add_entity(&name, &entity_type, &first_seen, &mut nodes);
+        for memory_id in ids {
+            edges.push(GraphEdge {
+                source: memory_id,
+                target: entity.clone(),
+                kind: "mentions".into(),
+                label: "mentions".into(),
+                weight: 1.0,
+            });
+        }
+    }
+
+    for (source, target, edge_type, weight) in relations {
+        edges.push(GraphEdge {
+            source,
+            target,
+            kind: "relation".into(),
+            label: super::encryption::resolve_edge_type(&edge_type),
+            weight,
+        });
+    }
+
+    let present: HashSet<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+    edges.retain(|e| present.contains(e.source.as_str()) && present.contains(e.target.as_str()));
+
+    info!(
+        "[engram:export] Graph export: {} nodes, {} edges (agent={:?})",
+        nodes.len(),
+        edges.len(),
+        agent_id
+    );
+    Ok(KnowledgeGraph {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        agent_id: agent_id.map(str::to_string),
+        nodes,
+        edges,
+    })
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline are not valid XML 1.0
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render as GraphML (directed), with kind/label/category/created_at on
+/// nodes and kind/label/weight on edges.
+pub fn to_graphml(graph: &KnowledgeGraph) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+         <key id=\"n_kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+         <key id=\"n_label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+         <key id=\"n_category\" for=\"node\" attr.name=\"category\" attr.type=\"string\"/>\n  \
+         <key id=\"n_created\" for=\"node\" attr.name=\"created_at\" attr.type=\"string\"/>\n  \
+         <key id=\"e_kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+         <key id=\"e_label\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+         <key id=\"e_weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n  \
+         <graph id=\"engram\" edgedefault=\"directed\">\n",
+    );
+    for n in &graph.nodes {
+        out.push_str(&format!(
+            "    <node id=\"{}\">\n      <data key=\"n_kind\">{}</data>\n      <data key=\"n_label\">{}</data>\n      <data key=\"n_category\">{}</data>\n      <data key=\"n_created\">{}</data>\n    </node>\n",
+            xml_escape(&n.id),
+            xml_escape(&n.kind),
+            xml_escape(&n.label),
+            xml_escape(&n.category),
+            xml_escape(&n.created_at),
+        ));
+    }
+    for (i, e) in graph.edges.iter().enumerate() {
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"e_kind\">{}</data>\n      <data key=\"e_label\">{}</data>\n      <data key=\"e_weight\">{}</data>\n    </edge>\n",
+            i,
+            xml_escape(&e.source),
+            xml_escape(&e.target),
+            xml_escape(&e.kind),
+            xml_escape(&e.label),
+            e.weight,
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+/// Serialize a graph in the requested format.
+pub fn render(graph: &KnowledgeGraph, format: GraphFormat) -> EngineResult<String> {
+    Ok(match format {
+        GraphFormat::Json => serde_json::to_string_pretty(graph)?,
+        GraphFormat::GraphMl => to_graphml(graph),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO episodic_memories (id, content_full, category, agent_id, scope_agent_id)
+                VALUES ('m1', 'User likes <Rust> & Go', 'preference', 'a1', 'a1'),
+                       ('m2', 'Project uses Tauri', 'project', 'a1', 'a1'),
+                       ('m3', 'Other agent note', 'general', 'a2', 'a2');
+             INSERT INTO memory_edges (id, source_id, target_id, edge_type, weight)
+                VALUES ('e1', 'm1', 'm2', 'related_to', 0.8),
+                       ('e2', 'm1', 'm3', 'related_to', 0.4);
+             INSERT INTO semantic_memories (id, subject, predicate, object, confidence, scope_agent_id)
+                VALUES ('s1', 'user', 'prefers', 'Rust', 0.9, 'a1');",
+        )
+        .unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn exports_agent_graph() {
+        let store = test_store();
+        let graph = export_graph(&store, Some("a1"), 100).unwrap();
+        let ids: HashSet<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert!(ids.contains("m1") && ids.contains("m2") && !ids.contains("m3"));
+        assert!(ids.contains("entity:user") && ids.contains("entity:rust"));
+        // m1 → m3 is dropped because m3 belongs to another agent
+        assert_eq!(
+            graph.edges.iter().filter(|e| e.kind == "relation").count(),
+            1
+        );
+        let fact = graph.edges.iter().find(|e| e.kind == "fact").unwrap();
+        assert_eq!(
+            (fact.source.as_str(), fact.label.as_str()),
+            ("entity:user", "prefers")
+        );
+    }
+
+    #[test]
+    fn graphml_is_escaped() {
+        let store = test_store();
+        let graph = export_graph(&store, None, 100).unwrap();
+        let xml = render(&graph, "graphml".parse().unwrap()).unwrap();
+        assert!(xml.contains("User likes &lt;Rust&gt; &amp; Go"));
+        assert!(xml.contains("edgedefault=\"directed\""));
+        assert_eq!(xml.matches("<node ").count(), graph.nodes.len());
+        assert!("dot".parse::<GraphFormat>().is_err());
+    }
+}
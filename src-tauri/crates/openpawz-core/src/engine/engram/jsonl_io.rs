@@ -0,0 +1,473 @@
+// ── Engram: Bulk JSONL Import / Export ──────────────────────────────────────
+//
+// Plain-text counterpart to encrypted_export: one episodic memory per line,
+// so a curated fact list can be written by hand (or by a script) and loaded
+// into a fresh agent, and memories can move between installs.
+//
+//   {"content":"Prefers tea","category":"preference","importance":0.7,
+//    "agent_id":"a1","created_at":"2026-10-01T09:00:00Z",
+//    "metadata":{"id":"…","session_id":"…"}}
+//
+// Only `content` is required. Export decrypts content, so the file holds
+// whatever PII the memories did — it's the user's own data, written where
+// they chose. Import goes back through validation, PII encryption and
+// (optionally) dedup like any other store, and embeds the lines that carry
+// no usable embedding in batches.
+
+use super::hnsw::SharedHnswIndex;
+use crate::atoms::engram_types::{
+    ConsolidationState, EpisodicMemory, MemoryScope, MemorySource, TieredContent,
+};
+use crate::atoms::error::EngineResult;
+use crate::engine::memory::EmbeddingClient;
+use crate::engine::sessions::embedding::bytes_to_f32_vec;
+use crate::engine::sessions::SessionStore;
+use log::{info, warn};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// Errors listed in an import report before the rest are only counted.
+const MAX_REPORTED_ERRORS: usize = 20;
+
+/// One line of a JSONL file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryLine {
+    pub content: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// 0.0–1.0. Values above 1 are read as the 0–10 scale used in the UI.
+    #[serde(default = "default_importance")]
+    pub importance: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    /// RFC 3339; kept on import so migrated memories decay from their real age.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// Free-form; export writes the source id and session. Ignored on import.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+fn default_category() -> String {
+    "general".into()
+}
+
+fn default_importance() -> f32 {
+    0.5
+}
+
+/// What to export. All given fields must match; `query` works as in
+/// `retention::ForgetFilter` (every word, case-insensitive, decrypted).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JsonlExportFilter {
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Only memories created at or after this time (RFC 3339).
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Only memories created before this time (RFC 3339).
+    #[serde(default)]
+    pub before: Option<String>,
+    /// 0.0–1.0.
+    #[serde(default)]
+    pub min_importance: Option<f32>,
+    /// Embeddings make each line several KB; off unless asked for.
+    #[serde(default)]
+    pub include_embeddings: bool,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JsonlImportReport {
+    pub imported: usize,
+    /// Skipped as near-duplicates of a stored memory (dedup only).
+    pub duplicates: usize,
+    pub failed: usize,
+    /// Lines embedded during the import (the rest reused the file's vectors
+    /// or are left for the embedding backfill).
+    pub embedded: usize,
+    /// "line N: reason", first few failures only.
+    pub errors: Vec<String>,
+}
+
+impl JsonlImportReport {
+    fn fail(&mut self, line: usize, reason: String) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(format!("line {}: {}", line, reason));
+        }
+    }
+}
+
+/// Importance as stored (0.0–1.0), accepting the UI's 0–10 scale.
+fn normalize_importance(importance: f32) -> f32 {
+    if importance > 1.0 {
+        (importance / 10.0).min(1.0)
+    } else {
+        importance.max(0.0)
+    }
+}
+
+/// A DB timestamp (`datetime('now')` or RFC 3339) as RFC 3339 UTC.
+fn to_rfc3339(ts: &str) -> String {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
+        return dt
+            .with_timezone(&chrono::Utc)
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+    }
+    match chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S") {
+        Ok(dt) => dt.and_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        Err(_) => ts.to_string(),
+    }
+}
+
+fn parse_time(ts: &str) -> EngineResult<String> {
+    Ok(chrono::DateTime::parse_from_rfc3339(ts)
+        .map_err(|_| format!("'{}' is not an RFC 3339 timestamp", ts))?
+        .with_timezone(&chrono::Utc)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string())
+}
+
+fn decrypt(content: &str, agent_id: &str) -> String {
+    if super::encryption::is_encrypted(content) {
+        if let Ok(key) = super::encryption::get_agent_encryption_key(agent_id) {
+            if let Ok(plain) = super::encryption::decrypt_memory_content(content, &key) {
+                return plain;
+            }
+        }
+    }
+    content.to_string()
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Export
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Memories matching `filter` as JSONL, oldest first (so re-importing keeps
+/// their order). Memories that can't be decrypted are left out.
+pub fn export_jsonl(store: &SessionStore, filter: &JsonlExportFilter) -> EngineResult<String> {
+    let since = filter.since.as_deref().map(parse_time).transpose()?;
+    let before = filter.before.as_deref().map(parse_time).transpose()?;
+    let words: Vec<String> = filter
+        .query
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+
+    type Row = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        f32,
+        Option<Vec<u8>>,
+        Option<String>,
+        String,
+    );
+    let rows: Vec<Row> = {
+        let rc = store.read_conn();
+        let conn = rc.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, content_full, agent_id, session_id, category, importance,
+                    embedding, embedding_model, created_at
+             FROM episodic_memories
+             WHERE (?1 IS NULL OR agent_id = ?1)
+               AND (?2 IS NULL OR category = ?2)
+               AND (?3 IS NULL OR julianday(created_at) >= julianday(?3))
+               AND (?4 IS NULL OR julianday(created_at) < julianday(?4))
+             ORDER BY created_at ASC",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![filter.agent_id, filter.category, since, before],
+                |r| {
+                    Ok((
+                        r.get(0)?,
+                        r.get(1)?,
+                        r.get(2)?,
+                        r.get(3)?,
+                        r.get(4)?,
+                        r.get::<_, f64>(5)? as f32,
+                        if filter.include_embeddings {
+                            r.get(6)?
+                        } else {
+                            None
+                        },
+                        r.get(7)?,
+                        r.get(8)?,
+                    ))
+                },
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        rows
+    };
+
+    let mut out = String::new();
+    let mut exported = 0usize;
+    for row in rows {
+        let (id, content, agent_id, session_id, category, importance, embedding, model, created) =
+            row;
+        if filter.limit.is_some_and(|l| exported >= l) {
+            break;
+        }
+        let importance = normalize_importance(importance);
+        if filter.min_importance.is_some_and(|m| importance < m) {
+            continue;
+        }
+        let plain = decrypt(&content, &agent_id);
+        if super::encryption::is_encrypted(&plain) {
+            warn!("[engram:jsonl] Skipping {} — cannot decrypt", id);
+            continue;
+        }
+        let lower = plain.to_lowercase();
+        if !words.iter().all(|w| lower.contains(w.as_str())) {
+            continue;
+        }
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("id".into(), id.into());
+        if !session_id.is_empty() {
+            metadata.insert("session_id".into(), session_id.into());
+        }
+        let embedding = embedding.map(|b| bytes_to_f32_vec(&b));
+        let line = MemoryLine {
+            content: plain,
+            category,
+            importance,
+            agent_id: Some(agent_id),
+            created_at: Some(to_rfc3339(&created)),
+            embedding_model: embedding.as_ref().and(model),
+            embedding,
+            metadata,
+        };
+        out.push_str(&serde_json::to_string(&line)?);
+        out.push('\n');
+        exported += 1;
+    }
+
+    info!("[engram:jsonl] Exported {} memories", exported);
+    Ok(out)
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Import
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Parse a JSONL document. Blank lines are skipped; each entry carries its
+/// 1-based line number.
+pub fn parse_jsonl(text: &str) -> Vec<(usize, Result<MemoryLine, String>)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            let parsed = serde_json::from_str::<MemoryLine>(l.trim())
+                .map_err(|e| e.to_string())
+                .and_then(|m| {
+                    super::encryption::validate_memory_input(&m.content, &m.category)
+                        .map(|_| m)
+                        .map_err(|e| e.to_string())
+                });
+            (i + 1, parsed)
+        })
+        .collect()
+}
+
+/// Store every line of `text`. `agent_id` overrides the file's agents (to
+/// seed one agent from a shared fact list); without either a memory is
+/// global. With `dedup`, lines too close to an existing memory are skipped
+/// and boost it instead, as for any other store.
+pub async fn import_jsonl(
+    store: &SessionStore,
+    text: &str,
+    agent_id: Option<&str>,
+    dedup: bool,
+    embedding_client: Option<&EmbeddingClient>,
+    hnsw_index: Option<&SharedHnswIndex>,
+) -> EngineResult<JsonlImportReport> {
+    let mut report = JsonlImportReport::default();
+
+    let mut lines = Vec::new();
+    for (n, parsed) in parse_jsonl(text) {
+        match parsed {
+            Ok(line) => lines.push((n, line)),
+            Err(e) => report.fail(n, e),
+        }
+    }
+
+    // A vector from another model can't be compared with this install's,
+    // so only matching ones are kept; the rest are embedded in one batch
+    if let Some(client) = embedding_client {
+        let model = client.model_name();
+        let stale: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, l))| {
+                l.embedding.is_none() || l.embedding_model.as_deref() != Some(model)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let texts: Vec<&str> = stale.iter().map(|&i| lines[i].1.content.as_str()).collect();
+        let vectors = client.embed_batch(&texts).await;
+        for (i, vector) in stale.into_iter().zip(vectors) {
+            let (n, line) = &mut lines[i];
+            match vector {
+                Ok(v) => {
+                    line.embedding = Some(v);
+                    line.embedding_model = Some(model.to_string());
+                    report.embedded += 1;
+                }
+                Err(e) => {
+                    warn!("[engram:jsonl] Embedding line {} failed: {}", n, e);
+                    line.embedding = None;
+                    line.embedding_model = None;
+                }
+            }
+        }
+    }
+
+    // Overlap never exceeds 1.0, so this threshold turns dedup off
+    let threshold = if dedup { None } else { Some(1.0) };
+    for (n, line) in lines {
+        let agent = agent_id.or(line.agent_id.as_deref());
+        let created_at = match line.created_at.as_deref().map(parse_time).transpose() {
+            Ok(t) => t,
+            Err(e) => {
+                report.fail(n, e.to_string());
+                continue;
+            }
+        };
+        let mem = EpisodicMemory {
+            id: uuid::Uuid::new_v4().to_string(),
+            content: TieredContent::from_text(&line.content),
+            outcome: None,
+            category: line.category,
+            importance: normalize_importance(line.importance),
+            agent_id: agent.unwrap_or("default").to_string(),
+            session_id: "import".to_string(),
+            source: MemorySource::Explicit,
+            consolidation_state: ConsolidationState::Fresh,
+            strength: 1.0,
+            scope: MemoryScope {
+                global: agent.is_none(),
+                agent_id: agent.map(str::to_string),
+                ..Default::default()
+            },
+            embedding_model: line.embedding.as_ref().and(line.embedding_model),
+            embedding: line.embedding,
+            negative_contexts: vec![],
+            created_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            last_accessed_at: None,
+            access_count: 0,
+        };
+
+        match super::graph::store_episodic_dedup(store, mem, None, threshold, hnsw_index).await {
+            Ok(Some(id)) => {
+                if let Some(created_at) = created_at {
+                    store.conn.lock().execute(
+                        "UPDATE episodic_memories SET created_at = ?2 WHERE id = ?1",
+                        params![id, created_at],
+                    )?;
+                }
+                report.imported += 1;
+            }
+            Ok(None) => report.duplicates += 1,
+            Err(e) => report.fail(n, e.to_string()),
+        }
+    }
+
+    info!(
+        "[engram:jsonl] Imported {} memories ({} duplicates, {} failed, {} embedded)",
+        report.imported, report.duplicates, report.failed, report.embedded
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO episodic_memories (id, content_full, category, agent_id, session_id, importance, created_at)
+                VALUES ('m1', 'Prefers tea over coffee', 'preference', 'a1', 's1', 1, '2026-09-01 08:00:00'),
+                       ('m2', 'Standup is at 9', 'general', 'a1', 's2', 0, '2026-10-01 09:30:00'),
+                       ('m3', 'Owns a cat called Miso', 'person', 'a2', 's3', 7, '2026-10-02 10:00:00');",
+        )
+        .unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn export_filters_and_writes_one_object_per_line() {
+        let store = test_store();
+        let all = export_jsonl(&store, &JsonlExportFilter::default()).unwrap();
+        let lines: Vec<MemoryLine> = parse_jsonl(&all)
+            .into_iter()
+            .map(|(_, l)| l.unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].content, "Prefers tea over coffee");
+        assert_eq!(lines[0].created_at.as_deref(), Some("2026-09-01T08:00:00Z"));
+        assert_eq!(lines[0].metadata["id"], "m1");
+        // Legacy 0–10 importance comes out on the 0–1 scale
+        assert!((lines[2].importance - 0.7).abs() < 1e-6);
+
+        let filter = JsonlExportFilter {
+            agent_id: Some("a1".into()),
+            since: Some("2026-09-15T00:00:00Z".into()),
+            ..Default::default()
+        };
+        let some = export_jsonl(&store, &filter).unwrap();
+        assert_eq!(some.lines().count(), 1);
+        assert!(some.contains("Standup is at 9"));
+
+        let filter = JsonlExportFilter {
+            query: Some("CAT miso".into()),
+            ..Default::default()
+        };
+        assert!(export_jsonl(&store, &filter)
+            .unwrap()
+            .contains("\"agent_id\":\"a2\""));
+    }
+
+    #[test]
+    fn parse_fills_defaults_and_reports_bad_lines() {
+        let text = "{\"content\":\"Likes hiking\"}\n\n\
+                    not json\n\
+                    {\"content\":\"\",\"category\":\"x\"}\n\
+                    {\"content\":\"Vegetarian\",\"importance\":8,\"category\":\"diet\"}\n";
+        let parsed = parse_jsonl(text);
+        assert_eq!(
+            parsed.iter().map(|(n, _)| *n).collect::<Vec<_>>(),
+            vec![1, 3, 4, 5]
+        );
+        let first = parsed[0].1.as_ref().unwrap();
+        assert_eq!(first.category, "general");
+        assert_eq!(first.importance, 0.5);
+        assert!(parsed[1].1.is_err());
+        assert!(parsed[2].1.is_err());
+        assert_eq!(
+            normalize_importance(parsed[3].1.as_ref().unwrap().importance),
+            0.8
+        );
+    }
+}
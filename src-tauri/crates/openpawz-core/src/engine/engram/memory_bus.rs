@@ -1720,6 +1720,7 @@ mod tests {
                 boss_agent: "other-boss".into(),
                 status: "active".into(),
                 agents: vec![],
+                budget_usd: 0.0,
                 created_at: String::new(),
                 updated_at: String::new(),
             })
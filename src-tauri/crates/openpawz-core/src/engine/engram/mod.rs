@@ -12,11 +12,15 @@
 //   - working_memory: Tier 1 active context with priority eviction
 //   - schema: Tier 2 database tables and migrations
 //   - graph: Memory graph business logic (store, search, relate, decay, GC)
+//   - graph_export: Knowledge graph dump as JSON / GraphML
+//   - jsonl_io: Bulk JSONL import/export of episodic memories (migration, seeding)
+//   - retention: Retention rules and right-to-forget (secure erase by filter)
 //   - consolidation: Async pipeline: episodic→semantic extraction, contradiction resolution
 //   - context_builder: Budget-aware prompt assembly with token-precise allocation
 //   - bridge: Compatibility layer from old engine::memory API to Engram
 //   - retrieval_quality: NDCG + relevancy metrics on every search (§5.3/§35)
 //   - reranking: 4-strategy reranking pipeline (§35.1) + cross-type dedup (§34.3)
+//   - reranker: Model reranking for the CrossEncoder strategy (Ollama / Cohere / Voyage)
 //   - hybrid_search: Auto-detect text-boost weighting (§35.2)
 //   - metadata_inference: Auto-extract structured metadata during consolidation (§35.3)
 //   - emotional_memory: Affective scoring pipeline (§37) — flashbulb encoding
@@ -42,9 +46,11 @@ pub mod encryption;
 pub mod entity_tracking;
 pub mod gated_search;
 pub mod graph;
+pub mod graph_export;
 pub mod hnsw;
 pub mod hybrid_search;
 pub mod intent_classifier;
+pub mod jsonl_io;
 pub mod memory_bus;
 pub mod memory_fusion;
 pub mod meta_cognition;
@@ -54,8 +60,10 @@ pub mod process_hardening;
 pub mod projection;
 pub mod proposition;
 pub mod recall_tuner;
+pub mod reranker;
 pub mod reranking;
 pub mod research_bridge;
+pub mod retention;
 pub mod retrieval_quality;
 pub mod schema;
 pub mod sensory_buffer;
@@ -118,6 +126,8 @@ pub use skill_library::{
     auto_extract_skill, link_sub_skill, record_outcome, suggest_skills, ExtractionResult,
     FailureAnalysis, SkillOutcome, SkillSuggestion,
 };
-pub use temporal_search::{cluster_temporal, recency_score, temporal_search};
+pub use temporal_search::{
+    cluster_temporal, parse_temporal_expression, recency_score, temporal_search, timeline,
+};
 pub use tokenizer::Tokenizer;
 pub use working_memory::WorkingMemory;
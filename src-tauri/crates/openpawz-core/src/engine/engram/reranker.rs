@@ -0,0 +1,324 @@
+// ── Engram: Model Reranker (§35.1) ──────────────────────────────────────────
+//
+// Backs `RerankStrategy::CrossEncoder`. The top candidates from the
+// heuristic pipeline are scored against the query by a model:
+//
+//   - Ollama: a local model prompted to rate each passage (JSON output)
+//   - Cohere: POST /v2/rerank
+//   - Voyage: POST /v1/rerank
+//
+// The whole call runs under the config's latency budget. Any failure —
+// unreachable backend, bad response, timeout — returns None and the
+// caller keeps the heuristic (RRF+MMR) ranking.
+
+use crate::atoms::engram_types::{RerankerBackend, RerankerConfig, RetrievedMemory};
+use log::{info, warn};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+static CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
+
+/// Passages are clipped before scoring; rerankers only need the gist.
+const MAX_PASSAGE_CHARS: usize = 1_000;
+
+/// Rerank `candidates` (already in heuristic order) with the configured model.
+///
+/// Returns the reordered list, or None if the model didn't answer usefully
+/// within the latency budget.
+pub async fn rerank(
+    candidates: &[RetrievedMemory],
+    query: &str,
+    config: &RerankerConfig,
+) -> Option<Vec<RetrievedMemory>> {
+    if candidates.len() < 2 || config.top_n < 2 {
+        return None;
+    }
+    let head = &candidates[..candidates.len().min(config.top_n)];
+    let passages: Vec<&str> = head
+        .iter()
+        .map(|c| &c.content[..c.content.floor_char_boundary(MAX_PASSAGE_CHARS)])
+        .collect();
+
+    let started = Instant::now();
+    let budget = Duration::from_millis(config.latency_budget_ms);
+    let scores = match tokio::time::timeout(budget, score(query, &passages, config)).await {
+        Ok(Ok(scores)) => scores,
+        Ok(Err(e)) => {
+            warn!("[engram] Reranker ({:?}) failed: {}", config.backend, e);
+            return None;
+        }
+        Err(_) => {
+            warn!(
+                "[engram] Reranker ({:?}) exceeded {}ms budget — keeping heuristic order",
+                config.backend, config.latency_budget_ms
+            );
+            return None;
+        }
+    };
+
+    let ranked = apply_scores(candidates, head.len(), &scores)?;
+    info!(
+        "[engram] Reranked {} candidates with {:?} in {}ms",
+        head.len(),
+        config.backend,
+        started.elapsed().as_millis()
+    );
+    Some(ranked)
+}
+
+/// Reorder the first `head_len` candidates by model score (highest first).
+/// Unscored head candidates follow the scored ones, then the untouched tail.
+/// None if the model scored nothing usable.
+fn apply_scores(
+    candidates: &[RetrievedMemory],
+    head_len: usize,
+    scores: &[(usize, f32)],
+) -> Option<Vec<RetrievedMemory>> {
+    let mut scored: Vec<(usize, f32)> = scores
+        .iter()
+        .copied()
+        .filter(|(i, s)| *i < head_len && s.is_finite())
+        .collect();
+    if scored.is_empty() {
+        return None;
+    }
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut order: Vec<usize> = Vec::with_capacity(candidates.len());
+    for (i, _) in scored {
+        if !order.contains(&i) {
+            order.push(i);
+        }
+    }
+    let rest: Vec<usize> = (0..candidates.len())
+        .filter(|i| !order.contains(i))
+        .collect();
+    order.extend(rest);
+    Some(order.into_iter().map(|i| candidates[i].clone()).collect())
+}
+
+async fn score(
+    query: &str,
+    passages: &[&str],
+    config: &RerankerConfig,
+) -> Result<Vec<(usize, f32)>, String> {
+    match config.backend {
+        RerankerBackend::Ollama => score_ollama(query, passages, config).await,
+        RerankerBackend::Cohere => {
+            let url = endpoint(config, "https://api.cohere.com", "/v2/rerank");
+            let model = model_or(config, "rerank-v3.5");
+            let body = json!({
+                "model": model,
+                "query": query,
+                "documents": passages,
+                "top_n": passages.len(),
+            });
+            let v = post_hosted(&url, &body, config).await?;
+            parse_index_scores(&v["results"])
+        }
+        RerankerBackend::Voyage => {
+            let url = endpoint(config, "https://api.voyageai.com", "/v1/rerank");
+            let model = model_or(config, "rerank-2");
+            let body = json!({
+                "model": model,
+                "query": query,
+                "documents": passages,
+                "top_k": passages.len(),
+            });
+            let v = post_hosted(&url, &body, config).await?;
+            parse_index_scores(&v["data"])
+        }
+    }
+}
+
+fn endpoint(config: &RerankerConfig, default_base: &str, path: &str) -> String {
+    let base = config
+        .base_url
+        .as_deref()
+        .filter(|b| !b.is_empty())
+        .unwrap_or(default_base)
+        .trim_end_matches('/');
+    if base.ends_with(path) {
+        base.to_string()
+    } else {
+        format!("{}{}", base, path)
+    }
+}
+
+fn model_or<'a>(config: &'a RerankerConfig, default_model: &'a str) -> &'a str {
+    if config.model.is_empty() {
+        default_model
+    } else {
+        &config.model
+    }
+}
+
+async fn post_hosted(url: &str, body: &Value, config: &RerankerConfig) -> Result<Value, String> {
+    if config.api_key.is_empty() {
+        return Err(format!("no API key configured for {:?}", config.backend));
+    }
+    let resp = CLIENT
+        .post(url)
+        .bearer_auth(&config.api_key)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!(
+            "{} — {}",
+            status,
+            crate::engine::types::truncate_utf8(&text, 200)
+        ));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+/// Cohere `results` / Voyage `data`: `[{index, relevance_score}]`.
+fn parse_index_scores(results: &Value) -> Result<Vec<(usize, f32)>, String> {
+    let items = results
+        .as_array()
+        .ok_or_else(|| "response has no results".to_string())?;
+    Ok(items
+        .iter()
+        .filter_map(|r| {
+            Some((
+                r["index"].as_u64()? as usize,
+                r["relevance_score"].as_f64()? as f32,
+            ))
+        })
+        .collect())
+}
+
+/// Ollama has no rerank endpoint, so a local model rates every passage
+/// 0–10 in one JSON-mode generate call.
+async fn score_ollama(
+    query: &str,
+    passages: &[&str],
+    config: &RerankerConfig,
+) -> Result<Vec<(usize, f32)>, String> {
+    let url = endpoint(config, "http://localhost:11434", "/api/generate");
+    let numbered: Vec<String> = passages
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("[{}] {}", i, p.replace('\n', " ")))
+        .collect();
+    let prompt = format!(
+        "Rate how relevant each passage is to the query, from 0 (unrelated) to 10 \
+         (directly answers it).\n\nQuery: {}\n\nPassages:\n{}\n\n\
+         Respond with JSON only: {{\"scores\": [{{\"index\": 0, \"score\": 7}}, ...]}} \
+         with one entry per passage.",
+        query,
+        numbered.join("\n")
+    );
+    let body = json!({
+        "model": model_or(config, "qwen2.5:1.5b"),
+        "prompt": prompt,
+        "format": "json",
+        "stream": false,
+        "options": { "temperature": 0.0 },
+    });
+    let resp = CLIENT
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama not reachable: {}", e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!("Ollama {} — {}", status, text));
+    }
+    let v: Value = resp.json().await.map_err(|e| e.to_string())?;
+    parse_ollama_scores(v["response"].as_str().unwrap_or(""))
+}
+
+fn parse_ollama_scores(response: &str) -> Result<Vec<(usize, f32)>, String> {
+    let v: Value =
+        serde_json::from_str(response.trim()).map_err(|e| format!("bad JSON from model: {}", e))?;
+    let items = v["scores"]
+        .as_array()
+        .ok_or_else(|| "model response has no scores".to_string())?;
+    Ok(items
+        .iter()
+        .filter_map(|s| Some((s["index"].as_u64()? as usize, s["score"].as_f64()? as f32)))
+        .collect())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::engram_types::{CompressionLevel, MemoryType, TrustScore};
+
+    fn make_memory(id: &str) -> RetrievedMemory {
+        RetrievedMemory {
+            content: format!("memory {}", id),
+            compression_level: CompressionLevel::Full,
+            memory_id: id.into(),
+            memory_type: MemoryType::Episodic,
+            trust_score: TrustScore::from_similarity(0.5),
+            token_cost: 4,
+            category: "general".into(),
+            created_at: String::new(),
+            agent_id: String::new(),
+        }
+    }
+
+    fn ids(ranked: &[RetrievedMemory]) -> Vec<&str> {
+        ranked.iter().map(|m| m.memory_id.as_str()).collect()
+    }
+
+    #[test]
+    fn scores_reorder_only_the_head() {
+        let mems: Vec<_> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|id| make_memory(id))
+            .collect();
+        // Head is a, b, c; the model skipped b and d stays last
+        let ranked = apply_scores(&mems, 3, &[(0, 0.2), (2, 0.9), (3, 1.0)]).unwrap();
+        assert_eq!(ids(&ranked), ["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn no_usable_scores_means_fallback() {
+        let mems: Vec<_> = ["a", "b"].iter().map(|id| make_memory(id)).collect();
+        assert!(apply_scores(&mems, 2, &[(5, 1.0), (0, f32::NAN)]).is_none());
+    }
+
+    #[test]
+    fn parses_hosted_and_ollama_responses() {
+        let cohere =
+            json!([{"index": 1, "relevance_score": 0.8}, {"index": 0, "relevance_score": 0.1}]);
+        assert_eq!(parse_index_scores(&cohere).unwrap(), [(1, 0.8), (0, 0.1)]);
+        assert!(parse_index_scores(&json!(null)).is_err());
+
+        let ollama = r#"{"scores": [{"index": 0, "score": 3}, {"index": 1, "score": 9}]}"#;
+        assert_eq!(parse_ollama_scores(ollama).unwrap(), [(0, 3.0), (1, 9.0)]);
+        assert!(parse_ollama_scores("not json").is_err());
+    }
+
+    #[test]
+    fn endpoint_accepts_base_or_full_url() {
+        let mut config = RerankerConfig {
+            backend: RerankerBackend::Cohere,
+            ..Default::default()
+        };
+        assert_eq!(
+            endpoint(&config, "https://api.cohere.com", "/v2/rerank"),
+            "https://api.cohere.com/v2/rerank"
+        );
+        config.base_url = Some("https://proxy.local/v2/rerank".into());
+        assert_eq!(
+            endpoint(&config, "https://api.cohere.com", "/v2/rerank"),
+            "https://proxy.local/v2/rerank"
+        );
+    }
+}
@@ -5,13 +5,12 @@
 //   1. RRF — Reciprocal Rank Fusion (fast, no model dependency)
 //   2. MMR — Maximal Marginal Relevance (diversity-focused)
 //   3. RRFThenMMR — Combined: RRF first, then MMR for diversity (default)
-//   4. CrossEncoder — LLM-based reranking (most accurate, requires Ollama)
+//   4. CrossEncoder — model-based reranking (see reranker.rs); RRF+MMR here
 //
 // Reranking is step 5 in the recall pipeline (§8.4).
 // Engram has 4 configurable strategies.
 
 use crate::atoms::engram_types::{RerankStrategy, RetrievedMemory};
-use log::warn;
 
 /// Rerank a set of candidate memories after initial retrieval.
 ///
@@ -40,11 +39,8 @@ pub fn rerank_results(
             mmr_rerank(&rrf_ranked, query_embedding, mmr_lambda as f64)
         }
         RerankStrategy::CrossEncoder => {
-            // Cross-encoder reranking requires an Ollama model.
-            // For now, fall back to RRF+MMR since we don't have a cross-encoder
-            // endpoint integrated yet. This will be wired once Ollama exposes
-            // a reranking API or we add a local cross-encoder model.
-            warn!("[engram] CrossEncoder reranking not yet available — falling back to RRFThenMMR");
+            // The model pass lives in `reranker` (async, may fail or time out).
+            // Here CrossEncoder means "model unavailable": use RRF+MMR.
             let rrf_ranked = rrf_rerank(candidates);
             mmr_rerank(&rrf_ranked, query_embedding, mmr_lambda as f64)
         }
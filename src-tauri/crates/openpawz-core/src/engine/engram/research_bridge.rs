@@ -10,6 +10,7 @@
 use crate::atoms::engram_types::{EpisodicMemory, MemoryScope, MemorySource, TieredContent};
 use crate::atoms::error::EngineResult;
 use crate::engine::sessions::SessionStore;
+use crate::engine::types::truncate_utf8;
 use log::info;
 use serde::{Deserialize, Serialize};
 
@@ -69,13 +70,13 @@ pub fn ingest_findings(
                 .content
                 .find(". ")
                 .map(|end| finding.content[..=end].to_string())
-                .unwrap_or_else(|| finding.content[..200].to_string() + "…")
+                .unwrap_or_else(|| truncate_utf8(&finding.content, 200).to_string() + "…")
         } else {
             finding.content.clone()
         };
 
         let key_fact = if finding.content.len() > 100 {
-            finding.content[..100].to_string()
+            truncate_utf8(&finding.content, 100).to_string()
         } else {
             finding.content.clone()
         };
@@ -208,4 +209,27 @@ mod tests {
         let findings = findings_from_web_search(&results, "systems languages", "agent-1");
         assert_eq!(findings.len(), 1);
     }
+
+    #[test]
+    fn test_ingest_non_ascii_and_url_lookup() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::engine::sessions::schema::run_migrations(&conn).unwrap();
+        let store = SessionStore::from_connection(conn);
+
+        // Multi-byte characters straddle the 100/200-byte summary cut points
+        let finding = finding_from_web_read(
+            &"Überblick über Rust — ".repeat(20),
+            "https://example.com/rust_docs",
+            "bookmark",
+            "agent-1",
+        );
+        let report = ingest_findings(&store, &[finding]).unwrap();
+        assert_eq!(report.stored, 1);
+        assert!(store
+            .engram_has_research_url("https://example.com/rust_docs")
+            .unwrap());
+        assert!(!store
+            .engram_has_research_url("https://example.com/rust")
+            .unwrap());
+    }
 }
@@ -0,0 +1,389 @@
+// ── Engram: Retention Policies & Right-to-Forget ───────────────────────────
+//
+// Two ways to remove episodic memories on purpose rather than by decay:
+//
+//   - Retention rules (MemoryConfig.retention): per-category max age and max
+//     count, enforced by the background maintenance loop.
+//   - forget_matching: erase everything matching a query/filter, e.g. "all
+//     memories mentioning my old address", with a dry-run preview first.
+//
+// Both go through `forget`, which secure-erases each memory (content zeroed
+// before the row is deleted; the FTS triggers follow the update and delete),
+// drops its graph edges, removes it from entity profiles and semantic
+// triples derived from it, and evicts it from the HNSW index.
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::RetentionRule;
+use crate::engine::sessions::SessionStore;
+use log::info;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// What to forget. All given fields must match. `query` is matched
+/// case-insensitively against the decrypted content (every word must appear),
+/// since encrypted memories cannot be matched in SQL.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForgetFilter {
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Only memories created before this time (RFC 3339).
+    #[serde(default)]
+    pub before: Option<String>,
+}
+
+impl ForgetFilter {
+    fn is_empty(&self) -> bool {
+        self.query.as_deref().is_none_or(|q| q.trim().is_empty())
+            && self.category.is_none()
+            && self.agent_id.is_none()
+            && self.session_id.is_none()
+            && self.before.is_none()
+    }
+}
+
+/// A memory that would be (or was) erased.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ForgetCandidate {
+    pub id: String,
+    pub agent_id: String,
+    pub category: String,
+    pub created_at: String,
+    /// First 160 characters of the decrypted content.
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ForgetReport {
+    pub episodic_erased: usize,
+    pub semantic_erased: usize,
+    pub entity_refs_removed: usize,
+}
+
+fn decrypt(content: &str, agent_id: &str) -> String {
+    if super::encryption::is_encrypted(content) {
+        if let Ok(key) = super::encryption::get_agent_encryption_key(agent_id) {
+            if let Ok(plain) = super::encryption::decrypt_memory_content(content, &key) {
+                return plain;
+            }
+        }
+    }
+    content.to_string()
+}
+
+fn preview(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(160) {
+        Some((i, _)) => format!("{}…", &flat[..i]),
+        None => flat,
+    }
+}
+
+/// Memories matching `filter`, newest first. Refuses an empty filter so a
+/// stray call can't wipe everything.
+pub fn find_matching(
+    store: &SessionStore,
+    filter: &ForgetFilter,
+) -> EngineResult<Vec<ForgetCandidate>> {
+    if filter.is_empty() {
+        return Err("Forget filter is empty — give a query or at least one filter".into());
+    }
+    let before = match filter.before.as_deref() {
+        Some(b) => Some(
+            chrono::DateTime::parse_from_rfc3339(b)
+                .map_err(|_| format!("'{}' is not an RFC 3339 timestamp", b))?
+                .with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ),
+        None => None,
+    };
+    let words: Vec<String> = filter
+        .query
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+
+    let rows: Vec<(String, String, String, String, String)> = {
+        let conn = store.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, content_full, agent_id, category, created_at FROM episodic_memories
+             WHERE (?1 IS NULL OR category = ?1)
+               AND (?2 IS NULL OR agent_id = ?2)
+               AND (?3 IS NULL OR session_id = ?3)
+               AND (?4 IS NULL OR julianday(created_at) < julianday(?4))
+             ORDER BY created_at DESC",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![filter.category, filter.agent_id, filter.session_id, before],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        rows
+    };
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, content, agent_id, category, created_at)| {
+            let plain = decrypt(&content, &agent_id);
+            let lower = plain.to_lowercase();
+            if !words.iter().all(|w| lower.contains(w.as_str())) {
+                return None;
+            }
+            Some(ForgetCandidate {
+                id,
+                agent_id,
+                category,
+                created_at,
+                preview: preview(&plain),
+            })
+        })
+        .collect())
+}
+
+/// Securely erase episodic memories and everything derived from them.
+/// `reason` is recorded in the audit log.
+pub fn forget(
+    store: &SessionStore,
+    ids: &[String],
+    reason: &str,
+    hnsw_index: Option<&super::hnsw::SharedHnswIndex>,
+) -> EngineResult<ForgetReport> {
+    let mut report = ForgetReport::default();
+    if ids.is_empty() {
+        return Ok(report);
+    }
+
+    for id in ids {
+        // Triples extracted from this memory
+        let derived: Vec<String> = {
+            let conn = store.conn.lock();
+            let mut stmt =
+                conn.prepare("SELECT id FROM semantic_memories WHERE source_memory_id = ?1")?;
+            let rows = stmt
+                .query_map(params![id], |r| r.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+        for sid in &derived {
+            store.engram_secure_erase_semantic(sid)?;
+            report.semantic_erased += 1;
+        }
+
+        store.engram_secure_erase_episodic(id)?;
+        store.engram_audit_log("secure_erase", id, "system", "retention", Some(reason))?;
+        if let Some(idx) = hnsw_index {
+            super::hnsw::remove_shared(idx, id);
+        }
+        report.episodic_erased += 1;
+    }
+    report.entity_refs_removed = remove_entity_refs(store, ids)?;
+
+    store.engram_repad()?;
+    info!(
+        "[engram:retention] Erased {} memories, {} triples, {} entity refs ({})",
+        report.episodic_erased, report.semantic_erased, report.entity_refs_removed, reason
+    );
+    Ok(report)
+}
+
+/// Drop erased memory IDs from entity profiles; profiles left with no
+/// memories are deleted.
+fn remove_entity_refs(store: &SessionStore, ids: &[String]) -> EngineResult<usize> {
+    let conn = store.conn.lock();
+    // entity_profiles is created lazily by entity tracking
+    let Ok(mut stmt) = conn.prepare("SELECT id, memory_ids FROM entity_profiles") else {
+        return Ok(0);
+    };
+    let profiles: Vec<(String, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut removed = 0;
+    for (pid, memory_ids) in profiles {
+        let list: Vec<String> = serde_json::from_str(&memory_ids).unwrap_or_default();
+        let kept: Vec<&String> = list.iter().filter(|m| !ids.contains(m)).collect();
+        if kept.len() == list.len() {
+            continue;
+        }
+        removed += list.len() - kept.len();
+        if kept.is_empty() {
+            conn.execute("DELETE FROM entity_profiles WHERE id = ?1", params![pid])?;
+        } else {
+            conn.execute(
+                "UPDATE entity_profiles SET memory_ids = ?2, mention_count = ?3 WHERE id = ?1",
+                params![pid, serde_json::to_string(&kept)?, kept.len() as i64],
+            )?;
+        }
+    }
+    Ok(removed)
+}
+
+/// Memories that the retention rules say should go.
+pub fn retention_candidates(
+    store: &SessionStore,
+    rules: &[RetentionRule],
+) -> EngineResult<Vec<ForgetCandidate>> {
+    let mut out: Vec<ForgetCandidate> = Vec::new();
+    let conn = store.conn.lock();
+    for rule in rules {
+        let category = (rule.category != "*").then_some(rule.category.as_str());
+        let mut push = |c: ForgetCandidate| {
+            if !out.iter().any(|o| o.id == c.id) {
+                out.push(c);
+            }
+        };
+        let row = |r: &rusqlite::Row| {
+            Ok(ForgetCandidate {
+                id: r.get(0)?,
+                agent_id: r.get(1)?,
+                category: r.get(2)?,
+                created_at: r.get(3)?,
+                preview: String::new(),
+            })
+        };
+
+        if let Some(days) = rule.max_age_days {
+            let mut stmt = conn.prepare(
+                "SELECT id, agent_id, category, created_at FROM episodic_memories
+                 WHERE (?1 IS NULL OR category = ?1)
+                   AND julianday(created_at) < julianday('now', ?2)",
+            )?;
+            let rows = stmt.query_map(params![category, format!("-{} days", days)], row)?;
+            for c in rows.filter_map(|r| r.ok()) {
+                push(c);
+            }
+        }
+
+        if let Some(max) = rule.max_count {
+            let mut stmt = conn.prepare(
+                "SELECT id, agent_id, category, created_at FROM (
+                    SELECT id, agent_id, category, created_at,
+                           ROW_NUMBER() OVER (
+                               PARTITION BY agent_id ORDER BY julianday(created_at) DESC, rowid DESC
+                           ) AS rn
+                    FROM episodic_memories WHERE (?1 IS NULL OR category = ?1)
+                 ) WHERE rn > ?2",
+            )?;
+            let rows = stmt.query_map(params![category, max as i64], row)?;
+            for c in rows.filter_map(|r| r.ok()) {
+                push(c);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Enforce retention rules. Returns what was erased.
+pub fn apply_retention(
+    store: &SessionStore,
+    rules: &[RetentionRule],
+    hnsw_index: Option<&super::hnsw::SharedHnswIndex>,
+) -> EngineResult<ForgetReport> {
+    if rules.is_empty() {
+        return Ok(ForgetReport::default());
+    }
+    let ids: Vec<String> = retention_candidates(store, rules)?
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    forget(store, &ids, "retention_policy", hnsw_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO episodic_memories (id, content_full, category, agent_id, session_id, created_at)
+                VALUES ('m1', 'User lives at 12 Old Street', 'person', 'a1', 's1', datetime('now', '-40 days')),
+                       ('m2', 'Moved away from old street', 'person', 'a1', 's2', datetime('now', '-2 days')),
+                       ('m3', 'Prefers tea', 'preference', 'a1', 's2', datetime('now', '-1 days')),
+                       ('m4', 'Standup at 9', 'general', 'a2', 's3', strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-60 days'));
+             INSERT INTO memory_edges (id, source_id, target_id, edge_type) VALUES ('e1', 'm1', 'm3', 'related_to');",
+        )
+        .unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn count(store: &SessionStore, sql: &str) -> i64 {
+        store.conn.lock().query_row(sql, [], |r| r.get(0)).unwrap()
+    }
+
+    #[test]
+    fn forget_matching_previews_then_erases() {
+        let store = test_store();
+        assert!(find_matching(&store, &ForgetFilter::default()).is_err());
+
+        let filter = ForgetFilter {
+            query: Some("old STREET".into()),
+            ..Default::default()
+        };
+        let matched = find_matching(&store, &filter).unwrap();
+        let ids: Vec<String> = matched.iter().map(|c| c.id.clone()).collect();
+        assert_eq!(ids, vec!["m2".to_string(), "m1".to_string()]);
+        // Dry run leaves everything in place
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM episodic_memories"), 4);
+
+        let report = forget(&store, &ids, "test", None).unwrap();
+        assert_eq!(report.episodic_erased, 2);
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM episodic_memories"), 2);
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM memory_edges"), 0);
+        assert_eq!(
+            count(
+                &store,
+                "SELECT COUNT(*) FROM episodic_memories_fts WHERE episodic_memories_fts MATCH 'street'"
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn retention_rules_by_age_and_count() {
+        let store = test_store();
+        let age = RetentionRule {
+            category: "*".into(),
+            max_age_days: Some(30),
+            max_count: None,
+        };
+        let mut ids: Vec<String> = retention_candidates(&store, std::slice::from_ref(&age))
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["m1", "m4"]);
+
+        let cap = RetentionRule {
+            category: "person".into(),
+            max_age_days: None,
+            max_count: Some(1),
+        };
+        let ids: Vec<String> = retention_candidates(&store, std::slice::from_ref(&cap))
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["m1"]);
+
+        let report = apply_retention(&store, &[age, cap], None).unwrap();
+        assert_eq!(report.episodic_erased, 2);
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM episodic_memories"), 2);
+    }
+}
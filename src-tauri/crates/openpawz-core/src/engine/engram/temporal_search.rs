@@ -13,10 +13,13 @@
 //   - Burst detection (high-activity periods)
 //   - Temporal clustering (group co-located memories)
 //   - Recency-weighted RRF fusion signal
+//   - Timelines merging memories with session messages — timeline
+//   - Natural-language parsing ("yesterday", "last week", "around 3pm
+//     yesterday") in the user's timezone — parse_temporal_expression
 
 use crate::atoms::engram_types::{
     EpisodicMemory, MemoryScope, RetrievedMemory, TemporalCluster, TemporalPattern, TemporalQuery,
-    TemporalSearchResult, TrustScore,
+    TemporalSearchResult, TimelineEntry, TrustScore,
 };
 use crate::atoms::error::EngineResult;
 use crate::engine::sessions::SessionStore;
@@ -117,6 +120,252 @@ pub fn cluster_temporal(memories: &[RetrievedMemory], gap_secs: u64) -> Vec<Temp
     clusters
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// Timeline (memories + conversation history)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Memories and chat messages from the query's window, merged into one
+/// chronological list (oldest first, at most `limit` entries). Messages are
+/// limited to `agent_id`'s sessions when given. Range and proximity queries
+/// bound the messages directly; other kinds use the span of the memories found.
+pub fn timeline(
+    store: &SessionStore,
+    query: &TemporalQuery,
+    scope: &MemoryScope,
+    agent_id: Option<&str>,
+    limit: usize,
+) -> EngineResult<Vec<TimelineEntry>> {
+    let found = temporal_search(store, query, scope, limit)?;
+    let window = match query {
+        TemporalQuery::Range { start, end } => {
+            parse_datetime_opt(start).zip(parse_datetime_opt(end))
+        }
+        TemporalQuery::Proximity {
+            anchor,
+            window_hours,
+        } => parse_datetime_opt(anchor).map(|a| {
+            let w = chrono::Duration::minutes((window_hours * 60.0) as i64);
+            (a - w, a + w)
+        }),
+        _ if found.memories.is_empty() => None,
+        _ => parse_datetime_opt(&found.span_start).zip(parse_datetime_opt(&found.span_end)),
+    };
+
+    let mut entries: Vec<TimelineEntry> = found
+        .memories
+        .into_iter()
+        .map(|m| TimelineEntry {
+            kind: "memory".into(),
+            timestamp: normalize_timestamp(&m.created_at),
+            id: m.memory_id,
+            content: m.content,
+            label: m.category,
+            session_id: None,
+        })
+        .collect();
+
+    if let Some((start, end)) = window {
+        const SQLITE_FMT: &str = "%Y-%m-%d %H:%M:%S";
+        let messages = store.messages_in_range(
+            &start.format(SQLITE_FMT).to_string(),
+            &end.format(SQLITE_FMT).to_string(),
+            agent_id,
+            limit,
+        )?;
+        entries.extend(messages.into_iter().map(|m| TimelineEntry {
+            kind: "message".into(),
+            timestamp: normalize_timestamp(&m.created_at),
+            id: m.id,
+            content: m.content,
+            label: m.role,
+            session_id: Some(m.session_id),
+        }));
+    }
+
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// RFC 3339 or SQLite "YYYY-MM-DD HH:MM:SS" (UTC) → "YYYY-MM-DDTHH:MM:SSZ".
+fn normalize_timestamp(s: &str) -> String {
+    parse_datetime_opt(s)
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|ndt| ndt.and_utc())
+        })
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| s.to_string())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Natural-language time expressions
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Turn a phrase like "yesterday", "this morning", "last week", "3 days ago",
+/// "on tuesday" or "around 3pm yesterday" into a query. Day boundaries are
+/// those of `tz`, so "yesterday" means the user's yesterday, not UTC's.
+/// Returns None when the text has no recognisable time expression.
+pub fn parse_temporal_expression(
+    text: &str,
+    now: chrono::DateTime<chrono::Utc>,
+    tz: chrono_tz::Tz,
+) -> Option<TemporalQuery> {
+    use chrono::{Datelike, Duration, TimeZone};
+
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric() && c != ':')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let has = |w: &str| words.contains(&w);
+    let has_seq = |a: &str, b: &str| words.windows(2).any(|p| p[0] == a && p[1] == b);
+
+    let local_now = now.with_timezone(&tz);
+    let today = local_now.date_naive();
+    // Start of a local day at `hour`, as UTC.
+    let at = |day: chrono::NaiveDate, hour: u32| -> Option<chrono::DateTime<chrono::Utc>> {
+        let naive = day.and_hms_opt(hour, 0, 0)?;
+        Some(
+            tz.from_local_datetime(&naive)
+                .earliest()?
+                .with_timezone(&chrono::Utc),
+        )
+    };
+    let range = |start: chrono::DateTime<chrono::Utc>, end: chrono::DateTime<chrono::Utc>| {
+        TemporalQuery::Range {
+            start: start.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            end: end.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        }
+    };
+    let whole_day = |day: chrono::NaiveDate| Some(range(at(day, 0)?, at(day.succ_opt()?, 0)?));
+
+    // Which day is meant, if any.
+    let day = if has("yesterday") || has_seq("last", "night") {
+        Some(today.pred_opt()?)
+    } else if has("today")
+        || (has("this") && (has("morning") || has("afternoon") || has("evening")))
+    {
+        Some(today)
+    } else {
+        const WEEKDAYS: [&str; 7] = [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ];
+        WEEKDAYS.iter().position(|d| has(d)).map(|target| {
+            let current = today.weekday().num_days_from_monday() as i64;
+            let mut back = (current - target as i64).rem_euclid(7);
+            if back == 0 {
+                back = 7;
+            }
+            today - Duration::days(back)
+        })
+    };
+
+    // "around 3pm" / "at 15:00" anchors a proximity query on that day.
+    let clock = words.iter().enumerate().find_map(|(i, w)| {
+        let follows_marker = i > 0 && matches!(words[i - 1], "around" | "at" | "about");
+        parse_clock(w, words.get(i + 1).copied()).filter(|_| follows_marker)
+    });
+    if let Some((hour, minute)) = clock {
+        let day = day.unwrap_or(today);
+        let naive = day.and_hms_opt(hour, minute, 0)?;
+        let anchor = tz.from_local_datetime(&naive).earliest()?;
+        return Some(TemporalQuery::Proximity {
+            anchor: anchor
+                .with_timezone(&chrono::Utc)
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string(),
+            window_hours: 1.0,
+        });
+    }
+
+    if has_seq("last", "night") {
+        return Some(range(at(day?, 18)?, at(today, 6)?));
+    }
+    if let Some(day) = day {
+        let part = if has("morning") {
+            Some((5, 12))
+        } else if has("afternoon") {
+            Some((12, 18))
+        } else if has("evening") || has("tonight") {
+            Some((18, 24))
+        } else {
+            None
+        };
+        return match part {
+            Some((from, 24)) => Some(range(at(day, from)?, at(day.succ_opt()?, 0)?)),
+            Some((from, to)) => Some(range(at(day, from)?, at(day, to)?)),
+            None => whole_day(day),
+        };
+    }
+
+    // "N days/hours/weeks ago" → that span up to now.
+    if let Some(i) = words.iter().position(|w| *w == "ago") {
+        if i >= 2 {
+            let n: i64 = words[i - 2].parse().ok()?;
+            let span = match words[i - 1].trim_end_matches('s') {
+                "minute" => Duration::minutes(n),
+                "hour" => Duration::hours(n),
+                "day" => Duration::days(n),
+                "week" => Duration::weeks(n),
+                _ => return None,
+            };
+            if words[i - 1].starts_with("day") || words[i - 1].starts_with("week") {
+                // Whole local days, so "2 days ago" includes that entire day.
+                let start_day = (local_now - span).date_naive();
+                return Some(range(at(start_day, 0)?, now));
+            }
+            return Some(range(now - span, now));
+        }
+    }
+
+    if has_seq("last", "week") || has_seq("past", "week") || has_seq("this", "week") {
+        let days = if has("this") {
+            today.weekday().num_days_from_monday() as i64
+        } else {
+            7
+        };
+        return Some(range(at(today - Duration::days(days), 0)?, now));
+    }
+    if has_seq("last", "month") || has_seq("past", "month") {
+        return Some(range(at(today - Duration::days(30), 0)?, now));
+    }
+    None
+}
+
+/// "3pm", "3 pm", "15:00", "9:30am" → (hour, minute).
+fn parse_clock(word: &str, next: Option<&str>) -> Option<(u32, u32)> {
+    let (body, suffix) = if let Some(b) = word.strip_suffix("am") {
+        (b, Some(false))
+    } else if let Some(b) = word.strip_suffix("pm") {
+        (b, Some(true))
+    } else {
+        match next {
+            Some("am") => (word, Some(false)),
+            Some("pm") => (word, Some(true)),
+            _ => (word, None),
+        }
+    };
+    let (h, m) = match body.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None if suffix.is_some() => (body.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match suffix {
+        Some(pm) if (1..=12).contains(&h) => h % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => h,
+    };
+    (hour < 24 && m < 60).then_some((hour, m))
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Internal search implementations
 // ═══════════════════════════════════════════════════════════════════════════
@@ -379,7 +628,14 @@ fn episodic_to_retrieved(mem: &EpisodicMemory, relevance: f32) -> RetrievedMemor
     use crate::atoms::engram_types::{CompressionLevel, MemoryType};
     use crate::engine::engram::tokenizer::Tokenizer;
 
-    let content = mem.content.full.clone();
+    // Decrypt with the per-agent key and sanitize, as gated search does
+    let mut content = mem.content.full.clone();
+    if super::encryption::is_encrypted(&content) {
+        if let Ok(key) = super::encryption::get_agent_encryption_key(&mem.agent_id) {
+            content = super::encryption::decrypt_memory_content(&content, &key).unwrap_or(content);
+        }
+    }
+    let content = super::encryption::sanitize_recalled_memory(&content);
     RetrievedMemory {
         token_cost: Tokenizer::heuristic().count_tokens(&content),
         content,
@@ -476,6 +732,78 @@ mod tests {
         assert!((score - 0.5).abs() < 0.05); // Should be ~0.5 at half-life
     }
 
+    fn range_of(q: Option<TemporalQuery>) -> (String, String) {
+        match q {
+            Some(TemporalQuery::Range { start, end }) => (start, end),
+            other => panic!("expected range, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_temporal_expression_uses_timezone() {
+        use chrono::TimeZone;
+        let tz: chrono_tz::Tz = "America/Chicago".parse().unwrap();
+        // 2026-03-04 03:00 UTC is still the evening of March 3rd in Chicago (CST)
+        let now = chrono::Utc.with_ymd_and_hms(2026, 3, 4, 3, 0, 0).unwrap();
+
+        assert_eq!(
+            range_of(parse_temporal_expression(
+                "what did we do yesterday?",
+                now,
+                tz
+            )),
+            ("2026-03-02T06:00:00Z".into(), "2026-03-03T06:00:00Z".into())
+        );
+        assert_eq!(
+            range_of(parse_temporal_expression("this morning", now, tz)),
+            ("2026-03-03T11:00:00Z".into(), "2026-03-03T18:00:00Z".into())
+        );
+        // March 3rd 2026 is a Tuesday; "monday" is the day before
+        assert_eq!(
+            range_of(parse_temporal_expression("notes from Monday", now, tz)),
+            ("2026-03-02T06:00:00Z".into(), "2026-03-03T06:00:00Z".into())
+        );
+        assert_eq!(
+            range_of(parse_temporal_expression("2 days ago", now, tz)).0,
+            "2026-03-01T06:00:00Z"
+        );
+        match parse_temporal_expression("around 3pm yesterday", now, tz) {
+            Some(TemporalQuery::Proximity { anchor, .. }) => {
+                assert_eq!(anchor, "2026-03-02T21:00:00Z")
+            }
+            other => panic!("expected proximity, got {:?}", other),
+        }
+        assert!(parse_temporal_expression("deploy checklist", now, tz).is_none());
+    }
+
+    #[test]
+    fn test_timeline_includes_messages_in_window() {
+        use crate::engine::sessions::schema::run_migrations;
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, agent_id) VALUES ('s1', 'a1'), ('s2', 'other');
+             INSERT INTO messages (id, session_id, role, content, created_at) VALUES
+                ('m1', 's1', 'user', 'ship it on friday?', '2026-03-02 15:00:00'),
+                ('m2', 's1', 'assistant', 'Agreed: friday', '2026-03-02 15:01:00'),
+                ('m3', 's1', 'tool', 'raw output', '2026-03-02 15:02:00'),
+                ('m4', 's1', 'user', 'next day', '2026-03-03 09:00:00'),
+                ('m5', 's2', 'user', 'other agent', '2026-03-02 16:00:00');",
+        )
+        .unwrap();
+        let store = SessionStore::from_connection(conn);
+
+        let query = TemporalQuery::Range {
+            start: "2026-03-02T00:00:00Z".into(),
+            end: "2026-03-03T00:00:00Z".into(),
+        };
+        let entries = timeline(&store, &query, &MemoryScope::agent("a1"), Some("a1"), 10).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["m1", "m2"]);
+        assert_eq!(entries[0].timestamp, "2026-03-02T15:00:00Z");
+        assert_eq!(entries[1].label, "assistant");
+    }
+
     #[test]
     fn test_cluster_temporal_empty() {
         let clusters = cluster_temporal(&[], 3600);
@@ -0,0 +1,85 @@
+// Paw Agent Engine — Native search grounding
+//
+// Agents can let the model search the web itself with the provider's
+// built-in search tool — often cheaper and better than a web_search round
+// trip. Per agent, off by default. The agent loop adds a built-in marker
+// tool to each round (`native_search_tool`): Gemini gets it as
+// `{"googleSearch": {}}`, the OpenAI Responses API as `{"type":
+// "web_search"}`; Chat Completions and other APIs drop it. The sources the
+// provider reports come back as `ProviderMeta::Grounding`, which the
+// citation system picks up.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use crate::engine::types::{FunctionDefinition, ProviderKind, ToolDefinition};
+
+/// `tool_type` of provider built-in tools; providers that don't know one
+/// must not send it as a function.
+pub const BUILTIN_TOOL_TYPE: &str = "builtin";
+pub const GOOGLE_SEARCH_TOOL: &str = "google_search";
+pub const OPENAI_WEB_SEARCH_TOOL: &str = "openai_web_search";
+
+fn config_key(agent_id: &str) -> String {
+    format!("agent_google_grounding:{}", agent_id)
+}
+
+pub fn is_enabled(store: &SessionStore, agent_id: &str) -> bool {
+    matches!(
+        store
+            .get_config(&config_key(agent_id))
+            .ok()
+            .flatten()
+            .as_deref(),
+        Some("on")
+    )
+}
+
+pub fn set_enabled(store: &SessionStore, agent_id: &str, enabled: bool) -> EngineResult<()> {
+    store.set_config(&config_key(agent_id), if enabled { "on" } else { "off" })
+}
+
+/// Whether `model` accepts Google Search alongside function declarations.
+/// Gemini 2.x rejects the mix, so there grounding only applies to rounds
+/// without function tools.
+pub fn combines_with_functions(model: &str) -> bool {
+    model.starts_with("gemini-3")
+}
+
+/// The built-in search marker for a round on `kind` / `model`, if that
+/// provider has one. `has_functions` is whether the round also sends
+/// function tools.
+pub fn native_search_tool(
+    kind: ProviderKind,
+    model: &str,
+    has_functions: bool,
+) -> Option<ToolDefinition> {
+    match kind {
+        ProviderKind::Google if !has_functions || combines_with_functions(model) => {
+            Some(google_search_tool())
+        }
+        ProviderKind::OpenAI | ProviderKind::AzureFoundry | ProviderKind::Custom => Some(builtin(
+            OPENAI_WEB_SEARCH_TOOL,
+            "OpenAI built-in web search",
+        )),
+        _ => None,
+    }
+}
+
+/// The marker tool definition the Google provider turns into `googleSearch`.
+pub fn google_search_tool() -> ToolDefinition {
+    builtin(
+        GOOGLE_SEARCH_TOOL,
+        "Gemini built-in Google Search grounding",
+    )
+}
+
+fn builtin(name: &str, description: &str) -> ToolDefinition {
+    ToolDefinition {
+        tool_type: BUILTIN_TOOL_TYPE.into(),
+        function: FunctionDefinition {
+            name: name.into(),
+            description: description.into(),
+            parameters: serde_json::json!({}),
+        },
+    }
+}
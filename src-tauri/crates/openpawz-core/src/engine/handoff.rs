@@ -0,0 +1,194 @@
+// Paw Agent Engine — Agent handoff
+//
+// A session can change its acting agent mid-conversation (e.g. a chat that
+// drifts from coding into finance). The new agent gets the history as
+// usual, plus a handoff note stored as a system message: who handed over
+// and a model-written summary of the conversation so far, so it doesn't
+// have to reconstruct the goals and decisions from raw turns.
+//
+// Agents can also hand over themselves with the `handoff_to_agent` tool:
+// the current agent writes the summary, the target agent then continues the
+// same session in a new turn. Each session keeps its chain of handoffs,
+// which also caps how often agents can bounce a conversation around.
+
+use crate::engine::types::StoredMessage;
+use crate::engine::util::safe_truncate;
+use serde::{Deserialize, Serialize};
+
+/// `name` of the stored handoff system message.
+pub const HANDOFF_MESSAGE_NAME: &str = "agent_handoff";
+
+/// Transcript budget for the summary prompt (newest turns are kept).
+pub const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+const MAX_TURN_CHARS: usize = 1_500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionHandoff {
+    pub session_id: String,
+    pub from_agent: String,
+    pub to_agent: String,
+    /// The summary given to the new agent; `None` when the session had no
+    /// conversation yet or summarizing failed.
+    pub summary: Option<String>,
+}
+
+/// Tool-initiated handoffs allowed per session within `HANDOFF_WINDOW_SECS`.
+pub const MAX_HANDOFFS_PER_WINDOW: usize = 3;
+pub const HANDOFF_WINDOW_SECS: i64 = 600;
+
+/// One entry in a session's handoff chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffLink {
+    pub from_agent: String,
+    pub to_agent: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// RFC 3339 timestamp.
+    pub at: String,
+}
+
+/// Whether another handoff fits in the loop guard, given the session's
+/// chain so far.
+pub fn handoff_allowed(chain: &[HandoffLink], now: chrono::DateTime<chrono::Utc>) -> bool {
+    let recent = chain
+        .iter()
+        .filter_map(|l| chrono::DateTime::parse_from_rfc3339(&l.at).ok())
+        .filter(|at| (now - at.with_timezone(&chrono::Utc)).num_seconds() < HANDOFF_WINDOW_SECS)
+        .count();
+    recent < MAX_HANDOFFS_PER_WINDOW
+}
+
+/// Agents in the order they held the session, e.g. `default → coder → finance`.
+pub fn chain_agents(chain: &[HandoffLink]) -> Vec<String> {
+    let mut agents: Vec<String> = chain
+        .first()
+        .map(|l| l.from_agent.clone())
+        .into_iter()
+        .collect();
+    agents.extend(chain.iter().map(|l| l.to_agent.clone()));
+    agents
+}
+
+/// The user-role message that starts the target agent's turn.
+pub fn continuation_message(from_agent: &str, summary: &str) -> String {
+    format!(
+        "[Handoff from agent `{}`] {}\n\nPick up from here and reply to the user directly.",
+        from_agent,
+        summary.trim()
+    )
+}
+
+pub const HANDOFF_SYSTEM: &str = "You write handoff notes between AI agents. Be concise \
+and factual; write for the agent taking over, not for the user.";
+
+/// User and assistant turns, oldest first, trimmed to fit `max_chars` by
+/// dropping the oldest turns.
+pub fn transcript(messages: &[StoredMessage], max_chars: usize) -> String {
+    let mut turns: Vec<String> = Vec::new();
+    let mut used = 0;
+    for m in messages.iter().rev() {
+        let speaker = match m.role.as_str() {
+            "user" => "User",
+            "assistant" => "Assistant",
+            _ => continue,
+        };
+        let text = m.content.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let turn = format!("{}: {}", speaker, safe_truncate(text, MAX_TURN_CHARS));
+        used += turn.len() + 2;
+        if used > max_chars && !turns.is_empty() {
+            break;
+        }
+        turns.push(turn);
+    }
+    turns.reverse();
+    turns.join("\n\n")
+}
+
+pub fn handoff_prompt(from_agent: &str, to_agent: &str, transcript: &str) -> String {
+    format!(
+        "Agent `{}` is handing this conversation over to agent `{}`.\n\n\
+         Conversation:\n\n{}\n\n\
+         Write the handoff note in at most 200 words: what the user wants, what has \
+         been done or decided so far, open questions and next steps, and any \
+         preferences the user expressed. Use short bullet points.",
+        from_agent, to_agent, transcript
+    )
+}
+
+/// Content of the handoff system message.
+pub fn handoff_note(from_agent: &str, to_agent: &str, summary: Option<&str>) -> String {
+    let mut note = format!(
+        "[Agent handoff] This conversation was handed over from agent `{}` to you \
+         (`{}`). Continue it in your own role; don't repeat work already done.",
+        from_agent, to_agent
+    );
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        note.push_str("\n\nHandoff summary:\n");
+        note.push_str(summary);
+    }
+    note
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> StoredMessage {
+        StoredMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: "s1".into(),
+            role: role.into(),
+            content: content.into(),
+            tool_calls_json: None,
+            tool_call_id: None,
+            name: None,
+            created_at: "2026-10-15".into(),
+        }
+    }
+
+    #[test]
+    fn transcript_keeps_newest_turns() {
+        let messages = vec![
+            msg("user", "Fix my build"),
+            msg("tool", "cargo output"),
+            msg("assistant", "Done, it builds."),
+            msg("user", "Now, should I buy index funds?"),
+        ];
+        let full = transcript(&messages, MAX_TRANSCRIPT_CHARS);
+        assert!(full.starts_with("User: Fix my build\n\nAssistant: Done"));
+        assert!(!full.contains("cargo output"));
+
+        let short = transcript(&messages, 40);
+        assert_eq!(short, "User: Now, should I buy index funds?");
+
+        let note = handoff_note("coder", "finance", Some("- User asks about funds"));
+        assert!(note.contains("from agent `coder` to you (`finance`)"));
+        assert!(note.ends_with("Handoff summary:\n- User asks about funds"));
+        assert!(!handoff_note("a", "b", Some(" ")).contains("summary"));
+    }
+
+    #[test]
+    fn guard_limits_recent_handoffs() {
+        let now = chrono::Utc::now();
+        let link = |from: &str, to: &str, mins_ago: i64| HandoffLink {
+            from_agent: from.into(),
+            to_agent: to.into(),
+            summary: None,
+            at: (now - chrono::Duration::minutes(mins_ago)).to_rfc3339(),
+        };
+        let mut chain = vec![link("default", "coder", 30), link("coder", "finance", 5)];
+        assert!(handoff_allowed(&chain, now));
+        chain.push(link("finance", "coder", 2));
+        assert!(handoff_allowed(&chain, now));
+        chain.push(link("coder", "finance", 1));
+        assert!(!handoff_allowed(&chain, now));
+        assert_eq!(
+            chain_agents(&chain),
+            vec!["default", "coder", "finance", "coder", "finance"]
+        );
+        assert!(chain_agents(&[]).is_empty());
+    }
+}
@@ -64,14 +64,18 @@ pub async fn retry_delay(attempt: u32, retry_after_secs: Option<u64>) -> Duratio
 /// Uses a longer cap (5 minutes) than request retries.
 /// `attempt` is 0-based.
 pub async fn reconnect_delay(attempt: u32) -> Duration {
-    let base_ms = INITIAL_RETRY_DELAY_MS * 2u64.pow(attempt.min(12));
-    let capped_ms = base_ms.min(MAX_RECONNECT_DELAY_MS);
-    let jittered = apply_jitter(capped_ms);
-    let delay = Duration::from_millis(jittered);
+    let delay = reconnect_backoff(attempt);
     tokio::time::sleep(delay).await;
     delay
 }
 
+/// The jittered delay `reconnect_delay` would sleep for, without sleeping.
+pub fn reconnect_backoff(attempt: u32) -> Duration {
+    let base_ms = INITIAL_RETRY_DELAY_MS * 2u64.pow(attempt.min(12));
+    let capped_ms = base_ms.min(MAX_RECONNECT_DELAY_MS);
+    Duration::from_millis(apply_jitter(capped_ms))
+}
+
 /// Apply ±25% jitter to prevent thundering-herd effects.
 fn apply_jitter(base_ms: u64) -> u64 {
     let jitter_range = (base_ms / 4) as i64;
@@ -426,6 +430,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reconnect_backoff_grows_and_caps() {
+        let first = reconnect_backoff(0).as_millis() as u64;
+        assert!(first <= INITIAL_RETRY_DELAY_MS * 5 / 4);
+        let capped = reconnect_backoff(30).as_millis() as u64;
+        assert!(capped > first);
+        assert!(capped <= MAX_RECONNECT_DELAY_MS * 5 / 4);
+    }
+
     #[test]
     fn circuit_breaker_trips_and_recovers() {
         let cb = CircuitBreaker::new(3, 1); // trip after 3 failures, 1s cooldown
@@ -0,0 +1,75 @@
+// ── Idempotency keys for side-effect tools ─────────────────────────────────
+//
+// A run that is retried after a crash (e.g. a journaled Telegram message
+// replayed on restart) must not send the same email or place the same trade
+// twice. Every tool call of a run gets a deterministic key — the run id plus
+// the call's position in the run — and side-effect tools record their key
+// in SQLite once they succeed. A later call with a recorded key is refused
+// and the model is shown the original result instead.
+//
+// Keys expire after IdempotencyConfig.ttl_hours. The model can still repeat
+// an action deliberately by passing `"allow_duplicate": true`.
+
+use crate::engine::tool_metadata::{self, ToolMutability};
+use crate::engine::types::CompletedToolCall;
+use crate::engine::util::safe_truncate;
+
+/// Argument that lets a call through even though its key was recorded.
+pub const OVERRIDE_ARG: &str = "allow_duplicate";
+
+/// The key of the `call_index`-th tool call (0-based) of a run.
+pub fn key(run_id: &str, call_index: u32) -> String {
+    format!("{}#{}", run_id, call_index)
+}
+
+/// Whether the tool has external side effects worth protecting.
+pub fn applies_to(tool: &str) -> bool {
+    tool_metadata::mutability(tool) == ToolMutability::WriteSideEffect
+}
+
+/// Whether the call's arguments ask to run it again anyway.
+pub fn overridden(arguments: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()
+        .and_then(|args| args[OVERRIDE_ARG].as_bool())
+        .unwrap_or(false)
+}
+
+/// The tool result for a call that already ran.
+pub fn duplicate_message(done: &CompletedToolCall) -> String {
+    format!(
+        "Not executed: this '{}' call already ran at {} UTC as part of this run (idempotency key \
+         {}), so it was skipped to avoid doing it twice. Its result was:\n{}\n\nIf the user really \
+         wants it done again, call the tool again with \"{}\": true.",
+        done.tool_name,
+        done.completed_at,
+        done.key,
+        safe_truncate(&done.output, 2000),
+        OVERRIDE_ARG
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_and_overrides() {
+        assert_eq!(key("inbound:telegram:42", 3), "inbound:telegram:42#3");
+        assert!(applies_to("email_send"));
+        assert!(!applies_to("read_file"));
+        assert!(overridden(r#"{"to":"a@b.c","allow_duplicate":true}"#));
+        assert!(!overridden(r#"{"to":"a@b.c"}"#));
+        assert!(!overridden("not json"));
+
+        let done = CompletedToolCall {
+            key: key("r1", 0),
+            tool_name: "email_send".into(),
+            session_id: "s1".into(),
+            output: "Email sent".into(),
+            completed_at: "2026-01-01 10:00:00".into(),
+        };
+        let msg = duplicate_message(&done);
+        assert!(msg.contains("Email sent") && msg.contains("\"allow_duplicate\": true"));
+    }
+}
@@ -0,0 +1,319 @@
+// ── Encrypted File Store (keychain fallback) ───────────────────────────────
+//
+// Used when the OS keychain is unavailable — typically headless Linux
+// servers with no Secret Service (GNOME Keyring / KWallet) running.
+//
+// The same JSON vault blob that would go into the keychain is encrypted
+// with AES-256-GCM and written to `{data_root}/key-vault.enc` (mode 0600).
+//
+// Key derivation:
+//   - `OPENPAWZ_VAULT_PASSPHRASE` set → PBKDF2-HMAC-SHA256 (600k rounds)
+//     over the passphrase. Recommended: the file is useless without it.
+//   - Otherwise → HKDF-SHA256 over the machine id + OS user. This only
+//     binds the file to the host; anyone who can read both the file and
+//     /etc/machine-id as this user can decrypt it. Logged as a warning.
+//
+// The KDF, salt and round count are stored in the envelope so a file
+// written with one mode is always read back with the same mode.
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the vault passphrase.
+pub const PASSPHRASE_ENV: &str = "OPENPAWZ_VAULT_PASSPHRASE";
+
+const FILE_NAME: &str = "key-vault.enc";
+const ENVELOPE_VERSION: u8 = 1;
+const PBKDF2_ROUNDS: u32 = 600_000;
+const HKDF_INFO: &[u8] = b"openpawz-key-vault-v1";
+
+const KDF_PASSPHRASE: &str = "pbkdf2-sha256";
+const KDF_MACHINE: &str = "hkdf-machine";
+
+/// On-disk format. Everything except `kdf` and `rounds` is base64.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    version: u8,
+    kdf: String,
+    #[serde(default)]
+    rounds: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Path of the encrypted vault file.
+pub fn path() -> PathBuf {
+    crate::engine::paths::paw_data_dir().join(FILE_NAME)
+}
+
+/// Whether an encrypted vault file exists.
+pub fn exists() -> bool {
+    path().exists()
+}
+
+/// Whether a passphrase is configured (vs. machine-bound key).
+pub fn has_passphrase() -> bool {
+    std::env::var(PASSPHRASE_ENV).is_ok_and(|p| !p.is_empty())
+}
+
+/// Read and decrypt the vault JSON. `Ok(None)` when no file exists.
+pub fn read() -> Result<Option<Zeroizing<String>>, String> {
+    read_from(&path())
+}
+
+/// Encrypt and write the vault JSON atomically.
+pub fn write(json: &str) -> Result<(), String> {
+    write_to(&path(), json)
+}
+
+/// Delete the vault file (after migrating to the keychain).
+pub fn remove() -> Result<(), String> {
+    match std::fs::remove_file(path()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove vault file: {}", e)),
+    }
+}
+
+fn read_from(path: &Path) -> Result<Option<Zeroizing<String>>, String> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read vault file: {}", e)),
+    };
+    let envelope: Envelope =
+        serde_json::from_str(&raw).map_err(|e| format!("Corrupt vault file: {}", e))?;
+    if envelope.version != ENVELOPE_VERSION {
+        return Err(format!(
+            "Unsupported vault file version {}",
+            envelope.version
+        ));
+    }
+    let secret = secret_for(&envelope.kdf)?;
+    decrypt(&envelope, &secret).map(Some)
+}
+
+fn write_to(path: &Path, json: &str) -> Result<(), String> {
+    let (kdf, rounds) = if has_passphrase() {
+        (KDF_PASSPHRASE, PBKDF2_ROUNDS)
+    } else {
+        warn!(
+            "[key-vault] {} not set — file vault key is bound to this machine only",
+            PASSPHRASE_ENV
+        );
+        (KDF_MACHINE, 0)
+    };
+    let secret = secret_for(kdf)?;
+    let envelope = encrypt(json, kdf, rounds, &secret)?;
+    let body = serde_json::to_string(&envelope).map_err(|e| e.to_string())?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let tmp = path.with_extension("enc.tmp");
+    write_private(&tmp, body.as_bytes())?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace vault file: {}", e))
+}
+
+/// Write a file readable only by the current user.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut f = opts
+        .open(path)
+        .map_err(|e| format!("Failed to write vault file: {}", e))?;
+    f.write_all(bytes)
+        .and_then(|_| f.sync_all())
+        .map_err(|e| format!("Failed to write vault file: {}", e))
+}
+
+// ── Key derivation ─────────────────────────────────────────────────────────
+
+/// Input secret for a KDF mode: the passphrase or the machine identity.
+fn secret_for(kdf: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    match kdf {
+        KDF_PASSPHRASE => std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(|p| Zeroizing::new(p.into_bytes()))
+            .ok_or_else(|| {
+                format!(
+                    "Vault file is passphrase-protected — set {} to unlock it",
+                    PASSPHRASE_ENV
+                )
+            }),
+        KDF_MACHINE => machine_secret(),
+        other => Err(format!("Unknown vault KDF '{}'", other)),
+    }
+}
+
+/// Stable per-host, per-user identity used when no passphrase is set.
+fn machine_secret() -> Result<Zeroizing<Vec<u8>>, String> {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|p| std::fs::read_to_string(p).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "No machine id found — set {} to use the file vault",
+                PASSPHRASE_ENV
+            )
+        })?;
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    Ok(Zeroizing::new(
+        format!("{}:{}", machine_id, user).into_bytes(),
+    ))
+}
+
+fn derive_key(
+    kdf: &str,
+    rounds: u32,
+    secret: &[u8],
+    salt: &[u8],
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    match kdf {
+        KDF_PASSPHRASE => {
+            if rounds == 0 {
+                return Err("Vault file has zero PBKDF2 rounds".into());
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(secret, salt, rounds, key.as_mut());
+        }
+        _ => {
+            hkdf::Hkdf::<Sha256>::new(Some(salt), secret)
+                .expand(HKDF_INFO, key.as_mut())
+                .map_err(|_| "HKDF expand failed".to_string())?;
+        }
+    }
+    Ok(key)
+}
+
+// ── Encrypt / decrypt ──────────────────────────────────────────────────────
+
+fn encrypt(json: &str, kdf: &str, rounds: u32, secret: &[u8]) -> Result<Envelope, String> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    getrandom::getrandom(&mut salt)
+        .and_then(|_| getrandom::getrandom(&mut nonce))
+        .map_err(|e| format!("OS CSPRNG failed: {}", e))?;
+
+    let key = derive_key(kdf, rounds, secret, &salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| "Invalid vault key".to_string())?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), json.as_bytes())
+        .map_err(|e| format!("Vault encryption failed: {}", e))?;
+
+    Ok(Envelope {
+        version: ENVELOPE_VERSION,
+        kdf: kdf.to_string(),
+        rounds,
+        salt: B64.encode(salt),
+        nonce: B64.encode(nonce),
+        ciphertext: B64.encode(ciphertext),
+    })
+}
+
+fn decrypt(envelope: &Envelope, secret: &[u8]) -> Result<Zeroizing<String>, String> {
+    let decode = |s: &str| {
+        B64.decode(s)
+            .map_err(|e| format!("Corrupt vault file: {}", e))
+    };
+    let salt = decode(&envelope.salt)?;
+    let nonce = decode(&envelope.nonce)?;
+    let ciphertext = decode(&envelope.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("Corrupt vault file: bad nonce".into());
+    }
+
+    let key = derive_key(&envelope.kdf, envelope.rounds, secret, &salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| "Invalid vault key".to_string())?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| {
+                "Vault file decryption failed — wrong passphrase or machine".to_string()
+            })?,
+    );
+    String::from_utf8(plaintext.to_vec())
+        .map(Zeroizing::new)
+        .map_err(|_| "Corrupt vault file: not UTF-8".to_string())
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_passphrase_mode() {
+        let env = encrypt(
+            r#"{"db-encryption":"abc"}"#,
+            KDF_PASSPHRASE,
+            1_000,
+            b"hunter2",
+        )
+        .unwrap();
+        assert_eq!(env.kdf, KDF_PASSPHRASE);
+        assert!(!env.ciphertext.contains("abc"));
+        let out = decrypt(&env, b"hunter2").unwrap();
+        assert_eq!(out.as_str(), r#"{"db-encryption":"abc"}"#);
+    }
+
+    #[test]
+    fn wrong_secret_fails() {
+        let env = encrypt("{}", KDF_PASSPHRASE, 1_000, b"right").unwrap();
+        assert!(decrypt(&env, b"wrong").is_err());
+    }
+
+    #[test]
+    fn roundtrip_machine_mode() {
+        let env = encrypt("{}", KDF_MACHINE, 0, b"machine-id:user").unwrap();
+        assert_eq!(decrypt(&env, b"machine-id:user").unwrap().as_str(), "{}");
+        assert!(decrypt(&env, b"other-host:user").is_err());
+    }
+
+    #[test]
+    fn salt_and_nonce_are_fresh() {
+        let a = encrypt("{}", KDF_MACHINE, 0, b"s").unwrap();
+        let b = encrypt("{}", KDF_MACHINE, 0, b"s").unwrap();
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.nonce, b.nonce);
+    }
+
+    #[test]
+    fn missing_file_reads_as_none() {
+        let path = std::env::temp_dir().join(format!("paw-vault-{}.enc", uuid::Uuid::new_v4()));
+        assert!(read_from(&path).unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn written_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("paw-vault-{}.enc", uuid::Uuid::new_v4()));
+        write_private(&path, b"x").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_file(&path).ok();
+    }
+}
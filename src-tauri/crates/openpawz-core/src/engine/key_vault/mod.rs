@@ -0,0 +1,536 @@
+// ── Unified Key Vault ──────────────────────────────────────────────────────
+//
+// Consolidates all OS keychain entries into a SINGLE keychain item stored as
+// a JSON blob.  This reduces macOS Keychain Access prompts from 6+ to 1:
+//
+//   ONE "openpawz" / "key-vault" entry → 1 prompt
+//
+// Architecture:
+//   - Single keychain entry: service="openpawz", user="key-vault"
+//   - In-memory HashMap<String, Zeroizing<String>> protected by RwLock
+//   - Each subsystem calls get()/set() with a purpose constant
+//   - Keys are generated on first access if missing
+//
+// Security:
+//   - All in-memory key material is wrapped in `Zeroizing<String>` so it
+//     is securely overwritten with zeroes when dropped or replaced —
+//     prevents secrets from lingering in freed heap memory.
+//   - The vault blob is stored in the OS keychain (encrypted at rest by
+//     macOS Keychain / GNOME Keyring / Windows Credential Manager).
+//   - In-memory cache is process-scoped — cleared (and zeroed) on exit.
+//   - Write operations hold the lock across read-check + insert + persist
+//     to prevent TOCTOU races between concurrent threads.
+//   - Lock poison is recovered with a logged warning — a panicked thread
+//     should not permanently brick the vault for the rest of the app.
+//
+// Backends:
+//   - Keychain (default) — the single OS keychain entry described above.
+//   - File — AES-256-GCM encrypted `key-vault.enc` in the data dir (see
+//     file_store.rs). Selected automatically when the keychain is
+//     definitively unavailable (headless Linux without a Secret Service),
+//     or forced with `OPENPAWZ_VAULT_BACKEND=file|keychain`. A locked
+//     keychain or a dismissed prompt keeps the keychain backend.
+//   - The keychain is probed once per process; the probe's read is handed
+//     to the loader so startup costs a single OS prompt.
+//   - When the keychain is usable and a vault file exists, the file's keys
+//     are merged into the keychain and the file is deleted. `migrate_to()`
+//     moves the vault explicitly in either direction.
+
+pub mod file_store;
+
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use zeroize::Zeroizing;
+
+const VAULT_SERVICE: &str = "openpawz";
+const VAULT_USER: &str = "key-vault";
+
+/// Type alias: all in-memory key material is wrapped in `Zeroizing` so it
+/// is securely overwritten with zeroes when dropped or replaced.
+type VaultMap = HashMap<String, Zeroizing<String>>;
+
+/// In-memory cache of the vault contents.
+/// None = not yet loaded, Some = loaded (possibly empty on fresh install).
+/// Values are `Zeroizing<String>` — zeroed on drop.
+static VAULT_CACHE: RwLock<Option<VaultMap>> = RwLock::new(None);
+
+/// Environment variable that forces a backend (`file` or `keychain`).
+pub const BACKEND_ENV: &str = "OPENPAWZ_VAULT_BACKEND";
+
+/// Where the vault blob is persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VaultBackend {
+    Keychain,
+    File,
+}
+
+/// Selected backend. None = not yet probed.
+static BACKEND: RwLock<Option<VaultBackend>> = RwLock::new(None);
+
+/// Set when an existing vault (file or keychain entry) could not be read.
+/// Persisting would overwrite it with an empty vault, so writes are refused
+/// until restart.
+static LOAD_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Result of the last keychain probe. `Ready` and `Unavailable` are final
+/// for the process; `Failed` is retried by the next `keychain_status()`.
+#[derive(Debug, Clone)]
+enum KeychainProbe {
+    Ready,
+    Unavailable(String),
+    Failed(String),
+}
+
+static KEYCHAIN_PROBE: Mutex<Option<KeychainProbe>> = Mutex::new(None);
+
+/// Vault JSON read by the probe, taken by the first `read_keychain()` so
+/// the entry is not read (and the user not prompted) twice.
+static PROBED_VAULT: Mutex<Option<Zeroizing<String>>> = Mutex::new(None);
+
+// ── Lock helpers ───────────────────────────────────────────────────────────
+// Recover from a poisoned RwLock (another thread panicked while holding it)
+// but always log a warning so we know something went wrong.  The alternative
+// — `.unwrap()` — would crash the entire app, which is worse than operating
+// on potentially stale data.
+
+fn read_lock(lock: &RwLock<Option<VaultMap>>) -> std::sync::RwLockReadGuard<'_, Option<VaultMap>> {
+    lock.read().unwrap_or_else(|poisoned| {
+        warn!("[key-vault] RwLock was poisoned (read) — recovering");
+        poisoned.into_inner()
+    })
+}
+
+fn write_lock(
+    lock: &RwLock<Option<VaultMap>>,
+) -> std::sync::RwLockWriteGuard<'_, Option<VaultMap>> {
+    lock.write().unwrap_or_else(|poisoned| {
+        warn!("[key-vault] RwLock was poisoned (write) — recovering");
+        poisoned.into_inner()
+    })
+}
+
+// ── Public API ─────────────────────────────────────────────────────────────
+
+/// Purpose constants — each subsystem uses its own key.
+pub const PURPOSE_DB_ENCRYPTION: &str = "db-encryption";
+pub const PURPOSE_LOCK_SCREEN: &str = "lock-screen";
+pub const PURPOSE_SKILL_VAULT: &str = "skill-vault";
+pub const PURPOSE_MEMORY_VAULT: &str = "memory-vault";
+pub const PURPOSE_N8N_ENCRYPTION: &str = "n8n-encryption";
+pub const PURPOSE_N8N_OWNER: &str = "n8n-owner";
+pub const PURPOSE_AUDIT_CHAIN: &str = "audit-chain";
+pub const PURPOSE_NOSTR_KEY: &str = "nostr-key";
+pub const PURPOSE_SCC_SIGNING: &str = "scc-signing";
+
+/// Prefetch the vault — triggers the single keychain access so that all
+/// subsequent `get()` calls are pure in-memory lookups.
+/// Call this early in app startup (before subsystems initialise).
+pub fn prefetch() {
+    ensure_loaded();
+    let guard = read_lock(&VAULT_CACHE);
+    let count = guard.as_ref().map_or(0, |m| m.len());
+    info!("[key-vault] Prefetch complete — {} keys available", count);
+}
+
+/// Check whether the vault was successfully loaded.
+/// Returns `true` if `prefetch()` (or any `get()`/`set()`) has populated
+/// the in-memory cache from the selected backend (keychain or file).
+pub fn is_loaded() -> bool {
+    read_lock(&VAULT_CACHE).is_some()
+}
+
+/// Probe the OS keychain. `Ok(())` when the vault entry is readable or
+/// simply doesn't exist yet. A successful or definitive result is cached,
+/// so only a failed probe touches the keychain again.
+pub fn keychain_status() -> Result<(), String> {
+    match probe_keychain() {
+        KeychainProbe::Ready => Ok(()),
+        KeychainProbe::Unavailable(e) | KeychainProbe::Failed(e) => Err(e),
+    }
+}
+
+/// The backend the vault persists to, probing the keychain on first call.
+pub fn backend() -> VaultBackend {
+    if let Some(b) = *BACKEND.read().unwrap_or_else(|e| e.into_inner()) {
+        return b;
+    }
+    let mut guard = BACKEND.write().unwrap_or_else(|e| e.into_inner());
+    *guard.get_or_insert_with(select_backend)
+}
+
+/// Summary of the vault backend for health checks.
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    pub backend: VaultBackend,
+    /// File backend only: whether a passphrase (vs. machine key) protects it.
+    pub passphrase_protected: bool,
+    pub file_path: Option<String>,
+    /// Last keychain probe error, when the file backend was auto-selected.
+    pub keychain_error: Option<String>,
+}
+
+pub fn status() -> VaultStatus {
+    let backend = backend();
+    VaultStatus {
+        backend,
+        passphrase_protected: backend == VaultBackend::File && file_store::has_passphrase(),
+        file_path: (backend == VaultBackend::File)
+            .then(|| file_store::path().to_string_lossy().to_string()),
+        keychain_error: last_keychain_error(),
+    }
+}
+
+/// Move the whole vault to `target` and delete it from the other backend.
+/// Returns the number of keys migrated.
+pub fn migrate_to(target: VaultBackend) -> Result<usize, String> {
+    let mut guard = write_lock(&VAULT_CACHE);
+    if guard.is_none() {
+        *guard = Some(read_vault());
+    }
+    if LOAD_FAILED.load(Ordering::SeqCst) {
+        return Err("Vault could not be read at load — refusing to migrate".into());
+    }
+    let map = guard.get_or_insert_with(VaultMap::new);
+    let source = backend();
+    if source == target {
+        return Ok(map.len());
+    }
+
+    let json = serialize_vault(map)?;
+    match target {
+        VaultBackend::Keychain => {
+            keychain_status()?;
+            write_keychain(&json)?;
+            file_store::remove()?;
+        }
+        VaultBackend::File => {
+            file_store::write(&json)?;
+            if let Err(e) = delete_keychain() {
+                warn!(
+                    "[key-vault] Migrated to file but keychain cleanup failed: {}",
+                    e
+                );
+            }
+        }
+    }
+    *BACKEND.write().unwrap_or_else(|e| e.into_inner()) = Some(target);
+    info!(
+        "[key-vault] Migrated {} keys from {:?} to {:?}",
+        map.len(),
+        source,
+        target
+    );
+    Ok(map.len())
+}
+
+/// Get a value from the vault by purpose key.
+/// Returns `None` if the key has never been stored.
+///
+/// The returned `Zeroizing<String>` is securely zeroed when dropped,
+/// preventing key material from lingering in freed heap memory.
+pub fn get(purpose: &str) -> Option<Zeroizing<String>> {
+    ensure_loaded();
+    let guard = read_lock(&VAULT_CACHE);
+    guard.as_ref().and_then(|map| map.get(purpose)).cloned()
+}
+
+/// Store a value in the vault and persist the whole blob to the backend.
+/// Creates the vault entry if it doesn't exist yet.
+///
+/// Thread-safe: holds the write lock across read-check + insert + persist
+/// to prevent TOCTOU races between concurrent callers.
+pub fn set(purpose: &str, value: &str) {
+    let mut guard = write_lock(&VAULT_CACHE);
+    if guard.is_none() {
+        *guard = Some(read_vault());
+    }
+    let map = guard.get_or_insert_with(VaultMap::new);
+    map.insert(purpose.to_string(), Zeroizing::new(value.to_string()));
+    persist_vault(map);
+}
+
+/// Remove a value from the vault and persist.
+/// Used by lock_screen_remove_passphrase(), oauth revoke, etc.
+pub fn remove(purpose: &str) {
+    let mut guard = write_lock(&VAULT_CACHE);
+    if guard.is_none() {
+        *guard = Some(read_vault());
+    }
+    if let Some(map) = guard.as_mut() {
+        if map.remove(purpose).is_some() {
+            persist_vault(map);
+            info!("[key-vault] Removed '{}' from vault", purpose);
+        }
+    }
+}
+
+// ── Internal ───────────────────────────────────────────────────────────────
+
+/// Ensure the vault is loaded into memory (double-checked lock pattern).
+/// On first call, reads the unified keychain entry (1 OS prompt max).
+/// If no vault exists yet, creates an empty in-memory map (no prompt).
+fn ensure_loaded() {
+    // Fast path: already cached
+    {
+        if read_lock(&VAULT_CACHE).is_some() {
+            return;
+        }
+    }
+    // Slow path: acquire write lock and double-check
+    let mut guard = write_lock(&VAULT_CACHE);
+    if guard.is_some() {
+        return;
+    }
+    *guard = Some(read_vault());
+}
+
+/// Pick the backend: explicit override, else keychain if it answers.
+fn select_backend() -> VaultBackend {
+    match std::env::var(BACKEND_ENV).as_deref() {
+        Ok("file") => {
+            info!(
+                "[key-vault] Using encrypted file store ({}=file)",
+                BACKEND_ENV
+            );
+            return VaultBackend::File;
+        }
+        Ok("keychain") => return VaultBackend::Keychain,
+        _ => {}
+    }
+    match probe_keychain() {
+        KeychainProbe::Ready => VaultBackend::Keychain,
+        KeychainProbe::Unavailable(e) => {
+            warn!(
+                "[key-vault] {} — falling back to encrypted file store at {}",
+                e,
+                file_store::path().display()
+            );
+            VaultBackend::File
+        }
+        KeychainProbe::Failed(e) => {
+            // The keychain exists but refused this read (locked, prompt
+            // dismissed). Moving to the file store would strand the keys
+            // already in it, so stay and let the loader retry.
+            warn!("[key-vault] {} — staying on the OS keychain", e);
+            VaultBackend::Keychain
+        }
+    }
+}
+
+/// Read the vault entry once and classify the outcome. Cached unless the
+/// read failed in a way that may succeed later.
+fn probe_keychain() -> KeychainProbe {
+    let mut guard = KEYCHAIN_PROBE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(probe @ (KeychainProbe::Ready | KeychainProbe::Unavailable(_))) = guard.as_ref() {
+        return probe.clone();
+    }
+    let probe = match keyring::Entry::new(VAULT_SERVICE, VAULT_USER) {
+        Err(e) => KeychainProbe::Unavailable(format!("Keyring init failed: {}", e)),
+        Ok(entry) => match entry.get_password() {
+            Ok(json) => {
+                *PROBED_VAULT.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some(Zeroizing::new(json));
+                KeychainProbe::Ready
+            }
+            Err(keyring::Error::NoEntry) => KeychainProbe::Ready,
+            Err(e) if keychain_missing(&e) => {
+                KeychainProbe::Unavailable(format!("Keychain unavailable: {}", e))
+            }
+            Err(e) => KeychainProbe::Failed(format!("Keychain read failed: {}", e)),
+        },
+    };
+    *guard = Some(probe.clone());
+    probe
+}
+
+/// Whether a keychain error means there is no keychain to use at all, as
+/// opposed to one that is locked or whose prompt was dismissed.
+fn keychain_missing(e: &keyring::Error) -> bool {
+    match e {
+        // macOS: errSecNotAvailable / errSecNoSuchKeychain. Everything else,
+        // including a cancelled prompt, surfaces as PlatformFailure.
+        keyring::Error::NoStorageAccess(_) => cfg!(target_os = "macos"),
+        // Secret Service: no D-Bus session or daemon. Locked collections
+        // and prompts come back as NoStorageAccess.
+        keyring::Error::PlatformFailure(_) => !cfg!(target_os = "macos"),
+        _ => false,
+    }
+}
+
+/// The last probe error, without probing again.
+fn last_keychain_error() -> Option<String> {
+    match KEYCHAIN_PROBE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()?
+    {
+        KeychainProbe::Ready => None,
+        KeychainProbe::Unavailable(e) | KeychainProbe::Failed(e) => Some(e.clone()),
+    }
+}
+
+/// Read the unified vault from the selected backend.
+/// If no vault exists yet, returns an empty map.
+fn read_vault() -> VaultMap {
+    match backend() {
+        VaultBackend::Keychain => {
+            let mut map = read_keychain();
+            if file_store::exists() {
+                import_file_into(&mut map);
+            }
+            map
+        }
+        VaultBackend::File => match file_store::read() {
+            Ok(Some(json)) => parse_vault(&json, "file vault"),
+            Ok(None) => {
+                info!("[key-vault] No vault file found — will create on first write");
+                VaultMap::new()
+            }
+            Err(e) => {
+                error!("[key-vault] {} — vault writes disabled", e);
+                LOAD_FAILED.store(true, Ordering::SeqCst);
+                VaultMap::new()
+            }
+        },
+    }
+}
+
+/// Keychain became available: merge keys from a leftover vault file
+/// (keychain wins on conflict), persist, and delete the file.
+fn import_file_into(map: &mut VaultMap) {
+    let json = match file_store::read() {
+        Ok(Some(json)) => json,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("[key-vault] Found vault file but cannot read it: {}", e);
+            return;
+        }
+    };
+    let file_map = parse_vault(&json, "file vault");
+    let before = map.len();
+    for (k, v) in file_map {
+        map.entry(k).or_insert(v);
+    }
+    let imported = map.len() - before;
+    let persisted = serialize_vault(map).and_then(|json| write_keychain(&json));
+    match persisted.and_then(|_| file_store::remove()) {
+        Ok(()) => info!(
+            "[key-vault] Migrated {} keys from vault file into OS keychain",
+            imported
+        ),
+        Err(e) => warn!("[key-vault] Vault file migration incomplete: {}", e),
+    }
+}
+
+fn parse_vault(json: &str, source: &str) -> VaultMap {
+    // Deserialise into plain HashMap first, then wrap values
+    match serde_json::from_str::<HashMap<String, String>>(json) {
+        Ok(plain) => {
+            let count = plain.len();
+            let map: VaultMap = plain
+                .into_iter()
+                .map(|(k, v)| (k, Zeroizing::new(v)))
+                .collect();
+            info!("[key-vault] Loaded unified {} ({} keys)", source, count);
+            map
+        }
+        Err(e) => {
+            error!(
+                "[key-vault] Corrupt {} JSON: {} — starting fresh",
+                source, e
+            );
+            VaultMap::new()
+        }
+    }
+}
+
+/// Read the unified vault JSON from the keychain, reusing the probe's read
+/// when there is one.
+fn read_keychain() -> VaultMap {
+    let probed = PROBED_VAULT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(json_str) = probed {
+        return parse_vault(&json_str, "vault");
+    }
+    match keyring::Entry::new(VAULT_SERVICE, VAULT_USER) {
+        Ok(entry) => match entry.get_password() {
+            Ok(json_str) => parse_vault(&json_str, "vault"),
+            Err(keyring::Error::NoEntry) => {
+                info!("[key-vault] No unified vault found — will create on first write");
+                VaultMap::new()
+            }
+            Err(e) => {
+                error!(
+                    "[key-vault] Keychain read error: {} — vault writes disabled",
+                    e
+                );
+                LOAD_FAILED.store(true, Ordering::SeqCst);
+                VaultMap::new()
+            }
+        },
+        Err(e) => {
+            error!(
+                "[key-vault] Keyring init failed: {} — vault writes disabled",
+                e
+            );
+            LOAD_FAILED.store(true, Ordering::SeqCst);
+            VaultMap::new()
+        }
+    }
+}
+
+/// Serialise the vault map to JSON.
+/// Accepts `VaultMap` (Zeroizing values) — unwraps to plain strings for
+/// JSON serialisation only; the serialised JSON is zeroed on drop.
+fn serialize_vault(map: &VaultMap) -> Result<Zeroizing<String>, String> {
+    // Build a plain HashMap for serde (Zeroizing<String> doesn't impl Serialize)
+    let plain: HashMap<&str, &str> = map.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    serde_json::to_string(&plain)
+        .map(Zeroizing::new)
+        .map_err(|e| format!("Failed to serialise vault: {}", e))
+}
+
+fn write_keychain(json: &str) -> Result<(), String> {
+    keyring::Entry::new(VAULT_SERVICE, VAULT_USER)
+        .map_err(|e| format!("Keyring init failed on persist: {}", e))?
+        .set_password(json)
+        .map_err(|e| format!("Failed to persist vault: {}", e))
+}
+
+fn delete_keychain() -> Result<(), String> {
+    let entry = keyring::Entry::new(VAULT_SERVICE, VAULT_USER)
+        .map_err(|e| format!("Keyring init failed: {}", e))?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Persist the whole vault blob to the selected backend.
+fn persist_vault(map: &VaultMap) {
+    let json = match serialize_vault(map) {
+        Ok(j) => j,
+        Err(e) => {
+            error!("[key-vault] {}", e);
+            return;
+        }
+    };
+
+    let result = match backend() {
+        _ if LOAD_FAILED.load(Ordering::SeqCst) => {
+            Err("vault could not be read at load — not overwriting it".into())
+        }
+        VaultBackend::Keychain => write_keychain(&json),
+        VaultBackend::File => file_store::write(&json),
+    };
+    match result {
+        Ok(()) => debug!("[key-vault] Persisted unified vault ({} keys)", map.len()),
+        Err(e) => error!("[key-vault] Failed to persist vault: {}", e),
+    }
+}
@@ -7,13 +7,22 @@
 // When the provider is `Auto` (default) the legacy cascade is kept:
 //   Ollama → OpenAI-at-same-URL → user's chat provider fallback
 // so existing setups keep working without configuration changes.
+//
+// Vectors are cached in-process by content hash, batch requests go out
+// a chunk at a time, and a shared semaphore bounds concurrent requests
+// so indexing runs don't flood a local Ollama.
 
 use crate::atoms::error::EngineResult;
 use crate::engine::types::*;
 use log::{info, warn};
+use parking_lot::Mutex;
 use reqwest::Client;
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use tokio::sync::Semaphore;
 
 /// Track whether we've already tried to pull the model this session.
 static MODEL_PULL_ATTEMPTED: AtomicBool = AtomicBool::new(false);
@@ -23,6 +32,86 @@ static MODEL_PULL_ATTEMPTED: AtomicBool = AtomicBool::new(false);
 /// rest of this process to avoid spamming 400s.
 static PROVIDER_EMBED_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
 
+/// Texts sent per batch embedding request.
+pub const EMBED_BATCH_SIZE: usize = 64;
+
+/// Embedding requests allowed in flight at once, across all clients.
+const EMBED_CONCURRENCY: usize = 4;
+
+/// Vectors kept in the in-process embedding cache.
+const EMBED_CACHE_CAPACITY: usize = 4096;
+
+static EMBED_PERMITS: Semaphore = Semaphore::const_new(EMBED_CONCURRENCY);
+
+static EMBED_CACHE: LazyLock<Mutex<EmbeddingCache>> =
+    LazyLock::new(|| Mutex::new(EmbeddingCache::new(EMBED_CACHE_CAPACITY)));
+
+/// Content-hash → vector cache, evicting the oldest entry when full.
+struct EmbeddingCache {
+    vectors: HashMap<[u8; 32], Vec<f32>>,
+    order: VecDeque<[u8; 32]>,
+    capacity: usize,
+}
+
+impl EmbeddingCache {
+    fn new(capacity: usize) -> Self {
+        EmbeddingCache {
+            vectors: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&self, key: &[u8; 32]) -> Option<Vec<f32>> {
+        self.vectors.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: [u8; 32], vector: Vec<f32>) {
+        if self.vectors.insert(key, vector).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.vectors.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Safety truncation: nomic-embed-text context is 8192 tokens (~6K chars).
+/// Truncate rather than fail for oversized inputs.
+/// Use floor_char_boundary to avoid panicking on multi-byte chars (e.g. em dash —)
+fn truncate_for_embedding(text: &str) -> &str {
+    &text[..text.floor_char_boundary(6000)]
+}
+
+/// Parse an OpenAI-format `data: [{index, embedding}]` response, in input order.
+fn parse_openai_embeddings(v: &Value) -> Vec<Vec<f32>> {
+    let mut items: Vec<(u64, Vec<f32>)> = v["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, d)| {
+            (
+                d["index"].as_u64().unwrap_or(i as u64),
+                parse_vector(&d["embedding"]),
+            )
+        })
+        .collect();
+    items.sort_by_key(|(index, _)| *index);
+    items.into_iter().map(|(_, vec)| vec).collect()
+}
+
+fn parse_vector(v: &Value) -> Vec<f32> {
+    v.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect()
+}
+
 /// Optional fallback to an OpenAI-compatible provider when Ollama is not running.
 #[derive(Clone, Debug)]
 pub struct OpenAiFallback {
@@ -79,20 +168,148 @@ impl EmbeddingClient {
     /// Every path still ends with the keyword-search safety net in the
     /// memory layer, so we never lose memories.
     pub async fn embed(&self, text: &str) -> EngineResult<Vec<f32>> {
-        // Safety truncation: nomic-embed-text context is 8192 tokens (~6K chars).
-        // Truncate rather than fail for oversized inputs.
-        // Use floor_char_boundary to avoid panicking on multi-byte chars (e.g. em dash —)
-        let safe_text: &str = &text[..text.floor_char_boundary(6000)];
+        let safe_text = truncate_for_embedding(text);
+        let key = self.cache_key(safe_text);
+        if let Some(vec) = EMBED_CACHE.lock().get(&key) {
+            return Ok(vec);
+        }
+
+        let vec = {
+            let _permit = EMBED_PERMITS.acquire().await;
+            match self.provider {
+                EmbeddingProvider::Ollama => self.embed_route_ollama(safe_text).await,
+                EmbeddingProvider::OpenAI => self.embed_route_openai(safe_text).await,
+                EmbeddingProvider::Google => self.embed_route_google(safe_text).await,
+                EmbeddingProvider::Provider => self.embed_route_provider(safe_text).await,
+                EmbeddingProvider::Auto => self.embed_route_auto(safe_text).await,
+            }
+        }?;
+        EMBED_CACHE.lock().insert(key, vec.clone());
+        Ok(vec)
+    }
+
+    /// Embed many texts, returning one result per input in order.
+    ///
+    /// Cached and duplicate texts are only embedded once. The rest go out in
+    /// batches of [`EMBED_BATCH_SIZE`] (a few in parallel) using the route's
+    /// batch endpoint. A batch the backend rejects is retried one text at a
+    /// time through [`embed`](Self::embed) and its full fallback cascade —
+    /// unless the first text fails too, in which case the whole batch fails
+    /// with that error instead of hammering an unavailable backend.
+    pub async fn embed_batch<S: AsRef<str>>(&self, texts: &[S]) -> Vec<EngineResult<Vec<f32>>> {
+        let safe: Vec<&str> = texts
+            .iter()
+            .map(|t| truncate_for_embedding(t.as_ref()))
+            .collect();
+        let mut resolved: HashMap<&str, Result<Vec<f32>, String>> = HashMap::new();
+        let mut missing: Vec<&str> = Vec::new();
+        {
+            let cache = EMBED_CACHE.lock();
+            for &text in &safe {
+                if resolved.contains_key(text) || missing.contains(&text) {
+                    continue;
+                }
+                match cache.get(&self.cache_key(text)) {
+                    Some(vec) => {
+                        resolved.insert(text, Ok(vec));
+                    }
+                    None => missing.push(text),
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            info!(
+                "[memory] Batch embedding {} texts ({} cached)",
+                missing.len(),
+                safe.len() - missing.len()
+            );
+        }
+        // Chunks run concurrently, bounded by the shared request permits
+        let batches = futures::future::join_all(
+            missing
+                .chunks(EMBED_BATCH_SIZE)
+                .map(|c| self.embed_chunk(c)),
+        )
+        .await;
+        resolved.extend(batches.into_iter().flatten());
+
+        safe.iter()
+            .map(|text| match &resolved[text] {
+                Ok(vec) => Ok(vec.clone()),
+                Err(e) => Err(e.clone().into()),
+            })
+            .collect()
+    }
 
-        match self.provider {
-            EmbeddingProvider::Ollama => self.embed_route_ollama(safe_text).await,
-            EmbeddingProvider::OpenAI => self.embed_route_openai(safe_text).await,
-            EmbeddingProvider::Google => self.embed_route_google(safe_text).await,
-            EmbeddingProvider::Provider => self.embed_route_provider(safe_text).await,
-            EmbeddingProvider::Auto => self.embed_route_auto(safe_text).await,
+    async fn embed_chunk<'a>(&self, chunk: &[&'a str]) -> Vec<(&'a str, Result<Vec<f32>, String>)> {
+        let batch = {
+            let _permit = EMBED_PERMITS.acquire().await;
+            self.embed_many(chunk).await
+        };
+        match batch {
+            Ok(vecs) if vecs.len() == chunk.len() && vecs.iter().all(|v| !v.is_empty()) => {
+                let mut cache = EMBED_CACHE.lock();
+                chunk
+                    .iter()
+                    .zip(vecs)
+                    .map(|(text, vec)| {
+                        cache.insert(self.cache_key(text), vec.clone());
+                        (*text, Ok(vec))
+                    })
+                    .collect()
+            }
+            other => {
+                match other {
+                    Err(e) => warn!(
+                        "[memory] Batch embed failed, embedding one at a time: {}",
+                        e
+                    ),
+                    Ok(_) => warn!(
+                        "[memory] Batch embed returned mismatched vectors, embedding one at a time"
+                    ),
+                }
+                let mut results = Vec::with_capacity(chunk.len());
+                for (i, text) in chunk.iter().enumerate() {
+                    let result = self.embed(text).await.map_err(|e| e.to_string());
+                    if i == 0 {
+                        if let Err(e) = &result {
+                            return chunk.iter().map(|t| (*t, Err(e.clone()))).collect();
+                        }
+                    }
+                    results.push((*text, result));
+                }
+                results
+            }
         }
     }
 
+    /// One batch request on the configured route's primary endpoint.
+    async fn embed_many(&self, texts: &[&str]) -> EngineResult<Vec<Vec<f32>>> {
+        match (&self.provider, &self.openai_fallback) {
+            (EmbeddingProvider::Ollama | EmbeddingProvider::Auto, _) => {
+                self.embed_ollama_batch(texts).await
+            }
+            (EmbeddingProvider::Google, Some(fb)) => self.embed_google_batch(texts, fb).await,
+            (EmbeddingProvider::OpenAI | EmbeddingProvider::Provider, Some(fb)) => {
+                self.embed_openai_provider_batch(texts, fb).await
+            }
+            (EmbeddingProvider::OpenAI, None) => self.embed_openai_batch(texts).await,
+            _ => Err("No batch embedding endpoint for this configuration".into()),
+        }
+    }
+
+    /// Cache key: the vector depends on the backend and model as well as the text.
+    fn cache_key(&self, text: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{:?}\0{}\0", self.provider, self.model));
+        if let Some(fb) = &self.openai_fallback {
+            hasher.update(format!("{}\0{}\0", fb.base_url, fb.embedding_model));
+        }
+        hasher.update(text.as_bytes());
+        hasher.finalize().into()
+    }
+
     // ── Route: Auto (legacy cascade) ─────────────────────────────────────
     async fn embed_route_auto(&self, text: &str) -> EngineResult<Vec<f32>> {
         // Try Ollama format first (new /api/embed endpoint, then legacy /api/embeddings)
@@ -516,6 +733,168 @@ impl EmbeddingClient {
         Ok(vec)
     }
 
+    /// Ollama batch: POST /api/embed { model, input: [...] } → { embeddings: [[f32...], ...] }
+    async fn embed_ollama_batch(&self, texts: &[&str]) -> EngineResult<Vec<Vec<f32>>> {
+        let url = format!("{}/api/embed", self.base_url.trim_end_matches('/'));
+        let body = json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Ollama not reachable at {} — {}", self.base_url, e))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("Ollama batch embed {} — {}", status, text).into());
+        }
+
+        let v: Value = resp.json().await?;
+        Ok(v["embeddings"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(parse_vector)
+            .collect())
+    }
+
+    /// OpenAI-compatible batch at the configured base_url: POST /v1/embeddings { model, input: [...] }
+    async fn embed_openai_batch(&self, texts: &[&str]) -> EngineResult<Vec<Vec<f32>>> {
+        let url = format!("{}/v1/embeddings", self.base_url.trim_end_matches('/'));
+        let body = json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(60))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("OpenAI batch embed {} — {}", status, text).into());
+        }
+
+        let v: Value = resp.json().await?;
+        Ok(parse_openai_embeddings(&v))
+    }
+
+    /// Batch embeddings through the user's configured OpenAI provider.
+    async fn embed_openai_provider_batch(
+        &self,
+        texts: &[&str],
+        fb: &OpenAiFallback,
+    ) -> EngineResult<Vec<Vec<f32>>> {
+        if PROVIDER_EMBED_UNSUPPORTED.load(Ordering::Relaxed) {
+            return Err("Provider does not support embeddings".into());
+        }
+        let base = fb.base_url.trim_end_matches('/');
+        let url = if base.contains(".azure.com") {
+            if base.contains('?') {
+                format!("{}/embeddings", base)
+            } else {
+                format!("{}/embeddings?api-version=2024-05-01-preview", base)
+            }
+        } else {
+            format!("{}/embeddings", base)
+        };
+
+        let body = json!({
+            "model": fb.embedding_model,
+            "input": texts,
+        });
+
+        let mut req = self
+            .client
+            .post(&url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(60));
+
+        // Azure uses api-key header; standard OpenAI uses Bearer token
+        if fb.base_url.contains(".azure.com") {
+            req = req.header("api-key", &fb.api_key);
+        } else {
+            req = req.bearer_auth(&fb.api_key);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| format!("OpenAI provider batch embed request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("OpenAI provider batch embed {} — {}", status, text).into());
+        }
+
+        let v: Value = resp.json().await?;
+        Ok(parse_openai_embeddings(&v))
+    }
+
+    /// Google Gemini batch: POST models/{model}:batchEmbedContents
+    async fn embed_google_batch(
+        &self,
+        texts: &[&str],
+        fb: &OpenAiFallback,
+    ) -> EngineResult<Vec<Vec<f32>>> {
+        let model = if fb.embedding_model.is_empty() {
+            "text-embedding-004"
+        } else {
+            &fb.embedding_model
+        };
+        let base = fb.base_url.trim_end_matches('/');
+        let url = format!(
+            "{}/models/{}:batchEmbedContents?key={}",
+            base, model, fb.api_key
+        );
+
+        let requests: Vec<Value> = texts
+            .iter()
+            .map(|text| {
+                json!({
+                    "model": format!("models/{}", model),
+                    "content": { "parts": [{ "text": text }] },
+                })
+            })
+            .collect();
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&json!({ "requests": requests }))
+            .timeout(std::time::Duration::from_secs(60))
+            .send()
+            .await
+            .map_err(|e| format!("Google batch embed request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body_text = resp.text().await.unwrap_or_default();
+            return Err(format!("Google batch embed {} — {}", status, body_text).into());
+        }
+
+        let v: Value = resp.json().await?;
+        Ok(v["embeddings"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|e| parse_vector(&e["values"]))
+            .collect())
+    }
+
     /// Check if the embedding service is reachable and the model works.
     pub async fn test_connection(&self) -> EngineResult<usize> {
         let vec = self.embed("test connection").await?;
@@ -781,3 +1160,42 @@ impl EmbeddingClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_evicts_oldest_first() {
+        let mut cache = EmbeddingCache::new(2);
+        cache.insert([1; 32], vec![1.0]);
+        cache.insert([2; 32], vec![2.0]);
+        cache.insert([1; 32], vec![1.5]); // update, not a new entry
+        cache.insert([3; 32], vec![3.0]);
+        assert_eq!(cache.get(&[1; 32]), None);
+        assert_eq!(cache.get(&[2; 32]), Some(vec![2.0]));
+        assert_eq!(cache.get(&[3; 32]), Some(vec![3.0]));
+    }
+
+    #[test]
+    fn openai_batch_response_is_put_in_input_order() {
+        let v = json!({"data": [
+            {"index": 1, "embedding": [0.5, 0.25]},
+            {"index": 0, "embedding": [1.0]},
+        ]});
+        assert_eq!(
+            parse_openai_embeddings(&v),
+            vec![vec![1.0], vec![0.5, 0.25]]
+        );
+    }
+
+    #[test]
+    fn cache_key_depends_on_model() {
+        let mut config = MemoryConfig::default();
+        let a = EmbeddingClient::new(&config);
+        config.embedding_model = "other-model".into();
+        let b = EmbeddingClient::new(&config);
+        assert_ne!(a.cache_key("hello"), b.cache_key("hello"));
+        assert_eq!(a.cache_key("hello"), a.cache_key("hello"));
+    }
+}
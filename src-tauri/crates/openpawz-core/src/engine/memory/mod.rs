@@ -6,7 +6,7 @@
 //
 // Module layout:
 //   ollama.rs    — Ollama lifecycle (auto-start, model discovery/pull)
-//   embedding.rs — EmbeddingClient (Ollama + OpenAI-compatible API calls)
+//   embedding.rs — EmbeddingClient (Ollama + OpenAI-compatible API calls, batching, cache)
 //   mod.rs       — store, search (hybrid BM25+vector), MMR, fact extraction
 
 pub mod embedding;
@@ -157,7 +157,7 @@ pub fn content_overlap(a: &str, b: &str) -> f64 {
 /// Strategy:
 /// 1. BM25 full-text search via FTS5 (fast, exact-match aware)
 /// 2. Vector semantic search via embeddings (meaning-aware)
-/// 3. Merge results with weighted scoring (0.4 BM25 + 0.6 vector)
+/// 3. Merge results with weighted scoring (`tuning` BM25/vector weights)
 /// 4. Apply temporal decay (newer memories score higher)
 /// 5. Apply MMR re-ranking (maximize diversity in top results)
 /// 6. Optionally filter by agent_id
@@ -168,7 +168,53 @@ pub async fn search_memories(
     threshold: f64,
     embedding_client: Option<&EmbeddingClient>,
     agent_id: Option<&str>,
+    tuning: &HybridSearchTuning,
 ) -> EngineResult<Vec<Memory>> {
+    let (results, _) = hybrid_search(
+        store,
+        query,
+        limit,
+        threshold,
+        embedding_client,
+        agent_id,
+        tuning,
+    )
+    .await?;
+    Ok(results)
+}
+
+/// Run [`search_memories`] and report every candidate's per-stage scores.
+pub async fn search_memories_debug(
+    store: &SessionStore,
+    query: &str,
+    limit: usize,
+    threshold: f64,
+    embedding_client: Option<&EmbeddingClient>,
+    agent_id: Option<&str>,
+    tuning: &HybridSearchTuning,
+) -> EngineResult<MemorySearchDebug> {
+    let (_, debug) = hybrid_search(
+        store,
+        query,
+        limit,
+        threshold,
+        embedding_client,
+        agent_id,
+        tuning,
+    )
+    .await?;
+    Ok(debug)
+}
+
+async fn hybrid_search(
+    store: &SessionStore,
+    query: &str,
+    limit: usize,
+    threshold: f64,
+    embedding_client: Option<&EmbeddingClient>,
+    agent_id: Option<&str>,
+    tuning: &HybridSearchTuning,
+) -> EngineResult<(Vec<Memory>, MemorySearchDebug)> {
     // Truncate long queries — embedding models have limited context windows
     // (nomic-embed-text: 8192 tokens ≈ 6K chars). For search, first 2K chars
     // is more than enough to capture intent.
@@ -230,8 +276,23 @@ pub async fn search_memories(
         }
     }
 
+    let mut debug = MemorySearchDebug {
+        query: truncated_query.to_string(),
+        tuning: tuning.clone(),
+        bm25_count: bm25_results.len(),
+        vector_count: vector_results.len(),
+        embedded: query_embedding.is_some(),
+        keyword_fallback: false,
+        candidates: Vec::new(),
+    };
+
     // ── Step 3: Merge with weighted scoring ────────────────────────
-    let mut merged = merge_search_results(&bm25_results, &vector_results, 0.4, 0.6);
+    let mut merged = merge_search_results(
+        &bm25_results,
+        &vector_results,
+        tuning.bm25_weight,
+        tuning.vector_weight,
+    );
 
     if merged.is_empty() {
         // Final fallback: keyword LIKE search
@@ -242,26 +303,34 @@ pub async fn search_memories(
             results.len(),
             query_preview
         );
-        return Ok(results);
+        debug.keyword_fallback = true;
+        return Ok((results, debug));
     }
 
     // ── Step 4: Apply temporal decay ───────────────────────────────
-    apply_temporal_decay(&mut merged);
+    apply_temporal_decay(&mut merged, tuning.decay_half_life_days);
 
     // ── Step 5: MMR re-ranking for diversity ───────────────────────
     let merged_count = merged.len();
-    let final_results = if query_embedding.is_some() && merged.len() > limit {
-        mmr_rerank(&merged, limit, 0.7) // lambda=0.7 (70% relevance, 30% diversity)
+    let memories: Vec<Memory> = merged.iter().map(|c| c.memory.clone()).collect();
+    let picks: Vec<(usize, Option<f64>)> = if query_embedding.is_some() && merged.len() > limit {
+        mmr_rerank(&memories, limit, tuning.mmr_lambda)
+            .into_iter()
+            .map(|(i, mmr)| (i, Some(mmr)))
+            .collect()
     } else {
-        merged.sort_by(|a, b| {
-            b.score
+        let mut order: Vec<usize> = (0..memories.len()).collect();
+        order.sort_by(|&a, &b| {
+            memories[b]
+                .score
                 .unwrap_or(0.0)
-                .partial_cmp(&a.score.unwrap_or(0.0))
+                .partial_cmp(&memories[a].score.unwrap_or(0.0))
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
-        merged.truncate(limit);
-        merged
+        order.truncate(limit);
+        order.into_iter().map(|i| (i, None)).collect()
     };
+    let final_results: Vec<Memory> = picks.iter().map(|&(i, _)| memories[i].clone()).collect();
 
     info!(
         "[memory] Hybrid search: returning {} results for '{}' (BM25={}, vector={}, merged={})",
@@ -272,11 +341,57 @@ pub async fn search_memories(
         merged_count
     );
 
-    Ok(final_results)
+    // Trace: returned candidates in rank order, then the rest by score
+    let mut candidates: Vec<Option<MemorySearchCandidate>> =
+        merged.into_iter().map(|c| Some(c.into_debug())).collect();
+    for (rank, &(i, mmr)) in picks.iter().enumerate() {
+        if let Some(c) = candidates[i].as_mut() {
+            c.rank = Some(rank + 1);
+            c.mmr = mmr;
+        }
+        debug.candidates.extend(candidates[i].take());
+    }
+    let mut rest: Vec<MemorySearchCandidate> = candidates.into_iter().flatten().collect();
+    rest.sort_by(|a, b| {
+        b.decayed
+            .partial_cmp(&a.decayed)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    debug.candidates.extend(rest);
+
+    Ok((final_results, debug))
 }
 
 // ── Search internals ───────────────────────────────────────────────────
 
+/// A merged search hit with the scores from each stage.
+struct ScoredMemory {
+    memory: Memory,
+    bm25_raw: Option<f64>,
+    bm25: Option<f64>,
+    vector: Option<f64>,
+    merged: f64,
+    decay: f64,
+}
+
+impl ScoredMemory {
+    fn into_debug(self) -> MemorySearchCandidate {
+        MemorySearchCandidate {
+            id: self.memory.id,
+            content: self.memory.content,
+            created_at: self.memory.created_at,
+            bm25_raw: self.bm25_raw,
+            bm25: self.bm25,
+            vector: self.vector,
+            merged: self.merged,
+            decay: self.decay,
+            decayed: self.memory.score.unwrap_or(0.0),
+            mmr: None,
+            rank: None,
+        }
+    }
+}
+
 /// Merge BM25 and vector search results with weighted scoring.
 /// Normalizes scores from each source to [0,1] range before combining.
 ///
@@ -288,10 +403,10 @@ fn merge_search_results(
     vector: &[Memory],
     bm25_weight: f64,
     vector_weight: f64,
-) -> Vec<Memory> {
+) -> Vec<ScoredMemory> {
     use std::collections::HashMap;
 
-    let mut score_map: HashMap<String, (Option<f64>, Option<f64>, Memory)> = HashMap::new();
+    let mut score_map: HashMap<String, ScoredMemory> = HashMap::new();
 
     // Normalize BM25 scores to [0,1]
     let bm25_max = bm25.iter().filter_map(|m| m.score).fold(0.0f64, f64::max);
@@ -307,46 +422,69 @@ fn merge_search_results(
                 (s - bm25_min) / bm25_range
             }
         });
-        score_map.insert(mem.id.clone(), (normalized, None, mem.clone()));
+        score_map.insert(
+            mem.id.clone(),
+            ScoredMemory {
+                memory: mem.clone(),
+                bm25_raw: mem.score,
+                bm25: normalized,
+                vector: None,
+                merged: 0.0,
+                decay: 1.0,
+            },
+        );
     }
 
     // Vector scores are already cosine similarity [0,1]
     for mem in vector {
         if let Some(entry) = score_map.get_mut(&mem.id) {
-            entry.1 = mem.score;
+            entry.vector = mem.score;
         } else {
-            score_map.insert(mem.id.clone(), (None, mem.score, mem.clone()));
+            score_map.insert(
+                mem.id.clone(),
+                ScoredMemory {
+                    memory: mem.clone(),
+                    bm25_raw: None,
+                    bm25: None,
+                    vector: mem.score,
+                    merged: 0.0,
+                    decay: 1.0,
+                },
+            );
         }
     }
 
     // Combine scores
     score_map
         .into_values()
-        .map(|(bm25_score, vec_score, mut mem)| {
-            let b = bm25_score.unwrap_or(0.0) * bm25_weight;
-            let v = vec_score.unwrap_or(0.0) * vector_weight;
-            mem.score = Some(b + v);
-            mem
+        .map(|mut hit| {
+            let b = hit.bm25.unwrap_or(0.0) * bm25_weight;
+            let v = hit.vector.unwrap_or(0.0) * vector_weight;
+            hit.merged = b + v;
+            hit.memory.score = Some(hit.merged);
+            hit
         })
         .collect()
 }
 
 /// Apply temporal decay: boost newer memories, penalize old ones.
-/// Uses exponential decay with a half-life of 30 days.
-fn apply_temporal_decay(memories: &mut [Memory]) {
+/// Uses exponential decay with the given half-life (0 = no decay).
+fn apply_temporal_decay(hits: &mut [ScoredMemory], half_life_days: f64) {
+    if half_life_days <= 0.0 {
+        return;
+    }
     let now = chrono::Utc::now();
-    let half_life_days: f64 = 30.0;
     let decay_constant = (2.0f64).ln() / half_life_days;
 
-    for mem in memories.iter_mut() {
+    for hit in hits.iter_mut() {
         if let Ok(created) =
-            chrono::NaiveDateTime::parse_from_str(&mem.created_at, "%Y-%m-%d %H:%M:%S")
+            chrono::NaiveDateTime::parse_from_str(&hit.memory.created_at, "%Y-%m-%d %H:%M:%S")
         {
             let created_utc = created.and_utc();
             let age_days = (now - created_utc).num_hours() as f64 / 24.0;
-            let decay_factor = (-decay_constant * age_days).exp();
-            if let Some(ref mut score) = mem.score {
-                *score *= decay_factor;
+            hit.decay = (-decay_constant * age_days).exp();
+            if let Some(ref mut score) = hit.memory.score {
+                *score *= hit.decay;
             }
         }
     }
@@ -355,24 +493,27 @@ fn apply_temporal_decay(memories: &mut [Memory]) {
 /// Maximal Marginal Relevance re-ranking.
 /// Selects diverse results by penalizing redundancy.
 /// lambda: 1.0 = pure relevance, 0.0 = pure diversity. 0.7 is a good default.
-fn mmr_rerank(candidates: &[Memory], k: usize, lambda: f64) -> Vec<Memory> {
+///
+/// Returns (index into `candidates`, MMR objective when picked) in pick order.
+fn mmr_rerank(candidates: &[Memory], k: usize, lambda: f64) -> Vec<(usize, f64)> {
     if candidates.is_empty() || k == 0 {
         return Vec::new();
     }
 
-    let mut selected: Vec<Memory> = Vec::with_capacity(k);
-    let mut remaining: Vec<&Memory> = candidates.iter().collect();
+    let mut selected: Vec<(usize, f64)> = Vec::with_capacity(k);
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
 
     // Pick the highest-scored item first
-    remaining.sort_by(|a, b| {
-        b.score
+    remaining.sort_by(|&a, &b| {
+        candidates[b]
+            .score
             .unwrap_or(0.0)
-            .partial_cmp(&a.score.unwrap_or(0.0))
+            .partial_cmp(&candidates[a].score.unwrap_or(0.0))
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    if let Some(first) = remaining.first() {
-        selected.push((*first).clone());
+    if let Some(&first) = remaining.first() {
+        selected.push((first, lambda * candidates[first].score.unwrap_or(0.0)));
         remaining.remove(0);
     }
 
@@ -381,11 +522,13 @@ fn mmr_rerank(candidates: &[Memory], k: usize, lambda: f64) -> Vec<Memory> {
         let mut best_idx = 0;
         let mut best_mmr = f64::NEG_INFINITY;
 
-        for (i, candidate) in remaining.iter().enumerate() {
-            let relevance = candidate.score.unwrap_or(0.0);
+        for (i, &candidate) in remaining.iter().enumerate() {
+            let relevance = candidates[candidate].score.unwrap_or(0.0);
             let max_similarity = selected
                 .iter()
-                .map(|s| content_similarity(&candidate.content, &s.content))
+                .map(|&(s, _)| {
+                    content_similarity(&candidates[candidate].content, &candidates[s].content)
+                })
                 .fold(0.0f64, f64::max);
 
             let mmr_score = lambda * relevance - (1.0 - lambda) * max_similarity;
@@ -396,7 +539,7 @@ fn mmr_rerank(candidates: &[Memory], k: usize, lambda: f64) -> Vec<Memory> {
             }
         }
 
-        selected.push(remaining[best_idx].clone());
+        selected.push((remaining[best_idx], best_mmr));
         remaining.remove(best_idx);
     }
 
@@ -429,8 +572,10 @@ pub async fn backfill_embeddings(
     let mut success = 0usize;
     let mut fail = 0usize;
 
-    for mem in &memories {
-        match client.embed(&mem.content).await {
+    let texts: Vec<&str> = memories.iter().map(|m| m.content.as_str()).collect();
+    let vectors = client.embed_batch(&texts).await;
+    for (mem, result) in memories.iter().zip(vectors) {
+        match result {
             Ok(vec) => {
                 let bytes = f32_vec_to_bytes(&vec);
                 if let Err(e) = store.update_memory_embedding(&mem.id, &bytes) {
@@ -453,7 +598,6 @@ pub async fn backfill_embeddings(
                 fail += 1;
             }
         }
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
     }
 
     info!(
@@ -909,3 +1053,64 @@ pub async fn compress_history(
         }
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Tests
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_memory(id: &str, score: f64, created_at: &str) -> Memory {
+        Memory {
+            id: id.into(),
+            content: format!("memory {}", id),
+            category: "general".into(),
+            importance: 5,
+            created_at: created_at.into(),
+            score: Some(score),
+            agent_id: None,
+        }
+    }
+
+    #[test]
+    fn merge_uses_configured_weights() {
+        let bm25 = [make_memory("a", 5.0, ""), make_memory("b", 1.0, "")];
+        let vector = [make_memory("b", 0.5, "")];
+        let mut hits = merge_search_results(&bm25, &vector, 0.8, 0.2);
+        hits.sort_by(|x, y| x.memory.id.cmp(&y.memory.id));
+
+        assert_eq!(hits[0].bm25, Some(1.0));
+        assert!((hits[0].merged - 0.8).abs() < 1e-9);
+        assert_eq!(hits[1].bm25, Some(0.0));
+        assert_eq!(hits[1].vector, Some(0.5));
+        assert!((hits[1].merged - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_half_life_disables_decay() {
+        let old = make_memory("a", 1.0, "2000-01-01 00:00:00");
+        let mut hits = merge_search_results(&[], &[old], 0.4, 0.6);
+
+        apply_temporal_decay(&mut hits, 0.0);
+        assert_eq!(hits[0].decay, 1.0);
+        assert!((hits[0].memory.score.unwrap() - 0.6).abs() < 1e-9);
+
+        apply_temporal_decay(&mut hits, 30.0);
+        assert!(hits[0].decay < 1e-6);
+    }
+
+    #[test]
+    fn mmr_reports_pick_order() {
+        let mems = [
+            make_memory("a", 0.9, ""),
+            make_memory("b", 0.5, ""),
+            make_memory("c", 0.7, ""),
+        ];
+        let picks = mmr_rerank(&mems, 2, 1.0);
+        let order: Vec<usize> = picks.iter().map(|&(i, _)| i).collect();
+        assert_eq!(order, [0, 2]);
+        assert!((picks[1].1 - 0.7).abs() < 1e-9);
+    }
+}
@@ -8,17 +8,74 @@
 use std::os::windows::process::CommandExt;
 
 use crate::atoms::error::EngineResult;
+use crate::engine::processes;
 use crate::engine::types::*;
 use log::{error, info, warn};
+use parking_lot::RwLock;
 use reqwest::Client;
 use serde_json::{json, Value};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::LazyLock;
 
 use super::embedding::EmbeddingClient;
 
 /// Track whether we've already run ensure_ollama_ready this session.
 static OLLAMA_INIT_DONE: AtomicBool = AtomicBool::new(false);
 
+/// PID of an `ollama serve` we started ourselves (0 = none). Only this
+/// process is stopped on quit — a user-run Ollama is left alone.
+static SPAWNED_OLLAMA_PID: AtomicU32 = AtomicU32::new(0);
+
+/// `OLLAMA_KEEP_ALIVE` for an `ollama serve` Paw starts (empty = Ollama's default).
+static KEEP_ALIVE: LazyLock<RwLock<String>> = LazyLock::new(Default::default);
+
+/// Set the keep-alive duration used by `apply_keep_alive` (called on
+/// startup and config save, from `EngineConfig.local_models`).
+pub fn set_keep_alive(duration: &str) {
+    *KEEP_ALIVE.write() = duration.trim().to_string();
+}
+
+/// Pass the configured keep-alive to an `ollama serve` command, so models
+/// stay loaded between requests by default.
+pub fn apply_keep_alive(cmd: &mut std::process::Command) -> &mut std::process::Command {
+    let duration = KEEP_ALIVE.read().clone();
+    if !duration.is_empty() {
+        cmd.env("OLLAMA_KEEP_ALIVE", duration);
+    }
+    cmd
+}
+
+/// Remember an `ollama serve` child started by Paw so shutdown can stop it.
+pub fn register_spawned_ollama(pid: u32) {
+    SPAWNED_OLLAMA_PID.store(pid, Ordering::SeqCst);
+    processes::track_service(pid, "ollama");
+}
+
+/// Stop the `ollama serve` Paw started, if any. Returns true if one was signalled.
+pub fn stop_spawned_ollama() -> bool {
+    let pid = SPAWNED_OLLAMA_PID.swap(0, Ordering::SeqCst);
+    if pid == 0 {
+        return false;
+    }
+    info!("[memory] Stopping ollama serve (pid {})", pid);
+    if processes::kill(pid).is_ok() {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        let _ = std::process::Command::new("kill")
+            .arg(pid.to_string())
+            .status();
+    }
+    #[cfg(windows)]
+    {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/F"])
+            .status();
+    }
+    true
+}
+
 /// Status returned by ensure_ollama_ready.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OllamaReadyStatus {
@@ -207,24 +264,28 @@ async fn start_ollama_process() -> EngineResult<()> {
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
-        Command::new(&path)
-            .arg("serve")
+        let mut cmd = Command::new(&path);
+        cmd.arg("serve")
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
-            .creation_flags(0x00000008) // DETACHED_PROCESS
-            .spawn()?;
+            .creation_flags(0x00000008); // DETACHED_PROCESS
+        apply_keep_alive(&mut cmd);
+        let child = processes::bind_to_parent(&mut cmd).spawn()?;
+        register_spawned_ollama(child.id());
     }
 
     #[cfg(not(target_os = "windows"))]
     {
         use std::process::Command;
-        Command::new(&path)
-            .arg("serve")
+        let mut cmd = Command::new(&path);
+        cmd.arg("serve")
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn()?;
+            .stderr(std::process::Stdio::null());
+        apply_keep_alive(&mut cmd);
+        let child = processes::bind_to_parent(&mut cmd).spawn()?;
+        register_spawned_ollama(child.id());
     }
 
     Ok(())
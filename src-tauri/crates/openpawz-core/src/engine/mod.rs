@@ -2,18 +2,38 @@
 // No Tauri dependency — these modules work in CLI, server, and desktop contexts.
 
 pub mod audit;
+pub mod autonomy;
+pub mod bookmarks;
+pub mod cancel;
+pub mod citations;
+pub mod confidence;
 pub mod constrained;
+pub mod email;
 pub mod engram;
+pub mod grounding;
+pub mod handoff;
 pub mod http;
+pub mod idempotency;
 pub mod injection;
 pub mod key_vault;
 pub mod memory;
+pub mod outbox;
 pub mod paths;
+pub mod postmortem;
 pub mod pricing;
+pub mod processes;
+pub mod project_budget;
+pub mod prompt_library;
 pub mod provider_registry;
 pub mod providers;
+pub mod reflection;
+pub mod research;
 pub mod scc;
 pub mod sessions;
+pub mod tool_args;
+pub mod tool_errors;
 pub mod tool_metadata;
+pub mod translate;
 pub mod types;
 pub mod util;
+pub mod youtube;
@@ -0,0 +1,181 @@
+// ── Outbox: draft-and-approve for outbound messages ─────────────────────────
+//
+// Instead of a modal per message, outbound communication tools (email, chat
+// posts, forum replies — see `tool_metadata::is_outbound_message`) can be
+// parked in a reviewable queue. The agent is told the message was queued and
+// carries on; the user approves, edits or rejects drafts in a batch later.
+//
+//   mode     queued
+//   ───────  ─────────────────────────────────────────────────────────────
+//   off      nothing — outbound tools go through the usual approval modal
+//   review   outbound calls that would otherwise need approval
+//   all      every outbound call, including auto-approved ones (autonomous
+//            agents, cron runs)
+//
+// Public posts (`tool_metadata::always_reviewed`, e.g. `social_post`) are
+// queued in every mode, including `off`.
+//
+// Pending drafts expire after `expiry_hours` so stale messages are never
+// sent days later by accident.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use crate::engine::tool_metadata;
+use crate::engine::types::truncate_utf8;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "outbox_settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxMode {
+    #[default]
+    Off,
+    Review,
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxSettings {
+    #[serde(default)]
+    pub mode: OutboxMode,
+    /// Pending drafts expire after this many hours (0 = never).
+    #[serde(default = "default_expiry_hours")]
+    pub expiry_hours: u32,
+}
+
+fn default_expiry_hours() -> u32 {
+    72
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        OutboxSettings {
+            mode: OutboxMode::Off,
+            expiry_hours: default_expiry_hours(),
+        }
+    }
+}
+
+impl OutboxSettings {
+    /// Whether this tool call goes to the outbox. `needs_approval` is true
+    /// when the call would otherwise wait on the approval modal.
+    pub fn should_queue(&self, tool_name: &str, needs_approval: bool) -> bool {
+        if !tool_metadata::is_outbound_message(tool_name) {
+            return false;
+        }
+        if tool_metadata::always_reviewed(tool_name) {
+            return true;
+        }
+        match self.mode {
+            OutboxMode::Off => false,
+            OutboxMode::Review => needs_approval,
+            OutboxMode::All => true,
+        }
+    }
+}
+
+pub fn load_settings(store: &SessionStore) -> OutboxSettings {
+    store
+        .get_config(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(store: &SessionStore, settings: &OutboxSettings) -> EngineResult<()> {
+    store.set_config(SETTINGS_KEY, &serde_json::to_string(settings)?)
+}
+
+/// One-line description of a drafted message for the review list:
+/// `tool → recipient: preview`.
+pub fn summarize(tool_name: &str, arguments: &serde_json::Value) -> String {
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| arguments.get(*k))
+            .map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .filter_map(|i| i.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                other => other.to_string(),
+            })
+            .filter(|s| !s.trim().is_empty())
+    };
+    let target = field(&[
+        "to",
+        "recipient",
+        "recipients",
+        "channel",
+        "channel_id",
+        "chat_id",
+        "topic_id",
+        "username",
+        "networks",
+        "network",
+        "reply_to",
+        "event_id",
+        "url",
+    ]);
+    let preview = field(&[
+        "subject", "title", "text", "message", "content", "body", "raw", "reaction",
+    ])
+    .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+    .unwrap_or_default();
+    let preview = if preview.len() > 120 {
+        format!("{}…", truncate_utf8(&preview, 120))
+    } else {
+        preview
+    };
+    match target {
+        Some(t) => format!("{} → {}: {}", tool_name, t, preview),
+        None => format!("{}: {}", tool_name, preview),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    #[test]
+    fn queue_decision_follows_mode() {
+        let mut settings = OutboxSettings::default();
+        assert!(!settings.should_queue("email_send", true));
+        assert!(settings.should_queue("social_post", false));
+        assert!(settings.should_queue("nostr_react", false));
+
+        settings.mode = OutboxMode::Review;
+        assert!(settings.should_queue("email_send", true));
+        assert!(!settings.should_queue("email_send", false));
+        assert!(!settings.should_queue("write_file", true));
+
+        settings.mode = OutboxMode::All;
+        assert!(settings.should_queue("slack_send", false));
+    }
+
+    #[test]
+    fn settings_roundtrip_and_summary() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        let store = SessionStore::from_connection(conn);
+        assert_eq!(load_settings(&store), OutboxSettings::default());
+        let settings = OutboxSettings {
+            mode: OutboxMode::Review,
+            expiry_hours: 24,
+        };
+        save_settings(&store, &settings).unwrap();
+        assert_eq!(load_settings(&store), settings);
+
+        let s = summarize(
+            "email_send",
+            &json!({ "to": ["a@x.com", "b@x.com"], "subject": "Q3\n report", "body": "…" }),
+        );
+        assert_eq!(s, "email_send → a@x.com, b@x.com: Q3 report");
+    }
+}
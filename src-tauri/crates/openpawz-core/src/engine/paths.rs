@@ -118,7 +118,21 @@ pub fn browser_profile_dir(profile_id: &str) -> PathBuf {
     paw_data_dir().join("browser-profiles").join(profile_id)
 }
 
+/// Isolated workspace of one project delegation:
+/// `{data_root}/project-workspaces/{project_id}/{delegation_id}/`
+pub fn delegation_workspace_dir(project_id: &str, delegation_id: &str) -> PathBuf {
+    paw_data_dir()
+        .join("project-workspaces")
+        .join(project_id)
+        .join(delegation_id)
+}
+
 /// Agent workspaces base: `{data_root}/workspaces/`
 pub fn workspaces_base_dir() -> PathBuf {
     paw_data_dir().join("workspaces")
 }
+
+/// Session cold storage, one SQLite file per month: `{data_root}/archive/`
+pub fn session_archive_dir() -> PathBuf {
+    paw_data_dir().join("archive")
+}
@@ -0,0 +1,503 @@
+// ── Project post-mortems and playbooks ─────────────────────────────────────
+//
+// When an orchestrator project run ends, its message bus and telemetry are
+// folded into a ProjectPostMortem: every delegation with how long it took and
+// how it ended, the failures, and tokens and cost per agent. The report is
+// stored with the project (one per project, replaced on re-run).
+//
+// With `project_playbooks` on, the boss model also distills the run into a
+// playbook — a procedural memory of the steps that worked, scoped to the
+// boss agent. When a new project starts, playbooks whose trigger shares
+// enough words with the goal are added to the boss's instructions.
+
+use crate::atoms::engram_types::{MemoryScope, ProceduralMemory, ProceduralStep};
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::sessions::SessionStore;
+use crate::engine::types::{
+    DelegationRecord, Message, MessageContent, Project, ProjectAgentUsage, ProjectMessage,
+    ProjectPostMortem, Role,
+};
+use crate::engine::util::safe_truncate;
+use log::{info, warn};
+use serde::Deserialize;
+
+/// Prefix of a playbook's trigger; the rest is the project goal.
+const PLAYBOOK_TRIGGER: &str = "Project playbook:";
+
+/// Share of a playbook's goal words a new goal must contain.
+const PLAYBOOK_MIN_MATCH: f32 = 0.4;
+
+/// Steps kept from one distillation.
+const MAX_PLAYBOOK_STEPS: usize = 8;
+
+const PLAYBOOK_PROMPT: &str = "You are reviewing a finished multi-agent project. \
+Below is its post-mortem: the goal, each task the boss delegated and how it ended, failures \
+and cost. Distill a reusable playbook for a similar future project: the ordered steps worth \
+repeating, with the specialty best suited to each and what to watch out for. Generalize — \
+no project-specific names, paths or values. If the run taught nothing reusable, respond \
+with []. Otherwise respond with ONLY a JSON array of at most 8 objects: \
+{\"step\": \"<what to do>\", \"specialty\": \"coder|researcher|designer|communicator|security|general\", \
+\"watch_out\": \"<pitfall from this run, or empty>\"}.";
+
+/// SQLite `datetime('now')` format, used by project messages and telemetry.
+const DB_TIME: &str = "%Y-%m-%d %H:%M:%S";
+
+fn parse_db_time(s: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(s, DB_TIME)
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|t| t.naive_utc())
+        })
+}
+
+fn secs_between(from: &str, to: &str) -> Option<i64> {
+    Some((parse_db_time(to)? - parse_db_time(from)?).num_seconds())
+}
+
+/// The current time in the format the project tables use.
+pub fn db_now() -> String {
+    chrono::Utc::now().format(DB_TIME).to_string()
+}
+
+// ── Report ─────────────────────────────────────────────────────────────────
+
+/// Build the report for a run from the project messages and telemetry
+/// recorded since it started. A delegation is closed by the agent's next
+/// result or error, or a progress report with status done/error/blocked.
+pub fn build(
+    project: &Project,
+    messages: &[ProjectMessage],
+    usage: Vec<ProjectAgentUsage>,
+    started_at: &str,
+    finished_at: &str,
+    summary: &str,
+) -> ProjectPostMortem {
+    let mut delegations: Vec<DelegationRecord> = Vec::new();
+    let mut failures = Vec::new();
+
+    for msg in messages {
+        if msg.kind == "delegation" {
+            if let Some(agent_id) = &msg.to_agent {
+                delegations.push(DelegationRecord {
+                    agent_id: agent_id.clone(),
+                    task: msg.content.clone(),
+                    delegated_at: msg.created_at.clone(),
+                    finished_at: None,
+                    duration_secs: None,
+                    outcome: "unfinished".into(),
+                    note: String::new(),
+                });
+            }
+            continue;
+        }
+
+        let progress_status = msg
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+            .and_then(|m| m["status"].as_str().map(str::to_string));
+        let outcome = match (msg.kind.as_str(), progress_status.as_deref()) {
+            ("result", _) | ("progress", Some("done")) => "done",
+            ("error", _) | ("progress", Some("error" | "blocked")) => "failed",
+            _ => continue,
+        };
+        if outcome == "failed" {
+            failures.push(format!(
+                "{}: {}",
+                msg.from_agent,
+                safe_truncate(&msg.content, 300)
+            ));
+        }
+        if let Some(open) = delegations
+            .iter_mut()
+            .find(|d| d.agent_id == msg.from_agent && d.finished_at.is_none())
+        {
+            open.finished_at = Some(msg.created_at.clone());
+            open.duration_secs = secs_between(&open.delegated_at, &msg.created_at);
+            open.outcome = outcome.into();
+            open.note = safe_truncate(&msg.content, 500).to_string();
+        }
+    }
+
+    let total_cost_usd = usage.iter().map(|u| u.cost_usd).sum();
+    ProjectPostMortem {
+        project_id: project.id.clone(),
+        title: project.title.clone(),
+        goal: project.goal.clone(),
+        status: project.status.clone(),
+        started_at: started_at.to_string(),
+        finished_at: finished_at.to_string(),
+        duration_secs: secs_between(started_at, finished_at).unwrap_or(0),
+        delegations,
+        failures,
+        usage,
+        total_cost_usd,
+        summary: summary.to_string(),
+        playbook_id: None,
+    }
+}
+
+/// The report as Markdown, for the model and for display.
+pub fn to_markdown(report: &ProjectPostMortem) -> String {
+    let mut md = format!(
+        "# Post-mortem: {}\n\n**Goal:** {}\n**Status:** {} · {} min · ${:.4}\n",
+        report.title,
+        report.goal,
+        report.status,
+        report.duration_secs / 60,
+        report.total_cost_usd
+    );
+    if !report.delegations.is_empty() {
+        md.push_str("\n## Delegations\n");
+        for d in &report.delegations {
+            let took = d
+                .duration_secs
+                .map(|s| format!(" ({} s)", s))
+                .unwrap_or_default();
+            md.push_str(&format!(
+                "- [{}] {} → {}{}\n",
+                d.outcome,
+                d.agent_id,
+                safe_truncate(&d.task, 300),
+                took
+            ));
+        }
+    }
+    if !report.failures.is_empty() {
+        md.push_str("\n## Failures\n");
+        for f in &report.failures {
+            md.push_str(&format!("- {}\n", f));
+        }
+    }
+    if !report.usage.is_empty() {
+        md.push_str("\n## Cost\n");
+        for u in &report.usage {
+            md.push_str(&format!(
+                "- {} ({}): {} in / {} out tokens, {} rounds, ${:.4}\n",
+                u.agent_id, u.model, u.input_tokens, u.output_tokens, u.rounds, u.cost_usd
+            ));
+        }
+    }
+    if !report.summary.is_empty() {
+        md.push_str(&format!(
+            "\n## Summary\n{}\n",
+            safe_truncate(&report.summary, 3000)
+        ));
+    }
+    md
+}
+
+// ── Playbooks ──────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct PlaybookStep {
+    step: String,
+    #[serde(default)]
+    specialty: String,
+    #[serde(default)]
+    watch_out: String,
+}
+
+/// Ask the model for the reusable steps of a run. Errors and unparseable
+/// replies yield no steps.
+pub async fn distill_playbook(
+    provider: &AnyProvider,
+    model: &str,
+    report: &ProjectPostMortem,
+) -> Vec<ProceduralStep> {
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(PLAYBOOK_PROMPT.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(to_markdown(report)),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+    match provider
+        .chat_stream(&messages, &[], model, Some(0.0), None)
+        .await
+    {
+        Ok(chunks) => {
+            let text: String = chunks
+                .iter()
+                .filter_map(|c| c.delta_text.as_deref())
+                .collect();
+            parse_playbook(&text)
+        }
+        Err(e) => {
+            warn!("[postmortem] Playbook distillation failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Parse the model's JSON array of steps.
+pub fn parse_playbook(text: &str) -> Vec<ProceduralStep> {
+    let trimmed = text.trim();
+    let json = match (trimmed.find('['), trimmed.rfind(']')) {
+        (Some(start), Some(end)) if end > start => &trimmed[start..=end],
+        _ => return Vec::new(),
+    };
+    let steps: Vec<PlaybookStep> = serde_json::from_str(json).unwrap_or_default();
+    steps
+        .into_iter()
+        .filter(|s| !s.step.trim().is_empty())
+        .take(MAX_PLAYBOOK_STEPS)
+        .map(|s| ProceduralStep {
+            description: s.step.trim().to_string(),
+            tool_name: Some("delegate_task".into()),
+            args_pattern: Some(s.specialty.trim().to_string()).filter(|s| !s.is_empty()),
+            expected_outcome: Some(s.watch_out.trim().to_string()).filter(|s| !s.is_empty()),
+        })
+        .collect()
+}
+
+/// Store a playbook for the boss agent. Returns its memory id.
+pub fn store_playbook(
+    store: &SessionStore,
+    boss_agent: &str,
+    report: &ProjectPostMortem,
+    steps: Vec<ProceduralStep>,
+) -> EngineResult<String> {
+    let succeeded = report.status == "completed";
+    let playbook = ProceduralMemory {
+        id: uuid::Uuid::new_v4().to_string(),
+        trigger: format!(
+            "{} {}",
+            PLAYBOOK_TRIGGER,
+            safe_truncate(report.goal.trim(), 300)
+        ),
+        steps,
+        success_rate: if succeeded { 1.0 } else { 0.0 },
+        execution_count: 1,
+        scope: MemoryScope {
+            agent_id: Some(boss_agent.to_string()),
+            ..Default::default()
+        },
+        created_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        updated_at: None,
+    };
+    store.engram_store_procedural(&playbook)?;
+    info!(
+        "[postmortem] Stored playbook {} ({} steps) for '{}'",
+        playbook.id,
+        playbook.steps.len(),
+        report.title
+    );
+    Ok(playbook.id)
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of the playbook goal's words that appear in `goal` (0.0–1.0).
+fn goal_match(playbook_trigger: &str, goal: &str) -> f32 {
+    let playbook_goal = playbook_trigger
+        .strip_prefix(PLAYBOOK_TRIGGER)
+        .unwrap_or(playbook_trigger);
+    let wanted = words(playbook_goal);
+    if wanted.is_empty() {
+        return 0.0;
+    }
+    let have = words(goal);
+    wanted.iter().filter(|w| have.contains(w)).count() as f32 / wanted.len() as f32
+}
+
+/// Playbooks of `boss_agent` for goals like `goal`, best match first.
+pub fn find_playbooks(
+    store: &SessionStore,
+    boss_agent: &str,
+    goal: &str,
+    limit: usize,
+) -> EngineResult<Vec<ProceduralMemory>> {
+    let scope = MemoryScope {
+        agent_id: Some(boss_agent.to_string()),
+        ..Default::default()
+    };
+    let mut matches: Vec<(f32, ProceduralMemory)> = store
+        .engram_search_procedural(PLAYBOOK_TRIGGER, &scope, 100)?
+        .into_iter()
+        .filter(|p| p.success_rate > 0.0)
+        .map(|p| (goal_match(&p.trigger, goal), p))
+        .filter(|(score, _)| *score >= PLAYBOOK_MIN_MATCH)
+        .collect();
+    matches.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(matches.into_iter().take(limit).map(|(_, p)| p).collect())
+}
+
+/// Playbooks as a section of the boss's instructions.
+pub fn format_playbooks(playbooks: &[ProceduralMemory]) -> String {
+    let mut out = String::from(
+        "## Playbooks from similar projects\n\
+         Plans that worked before. Adapt them to this goal; don't follow them blindly.\n",
+    );
+    for p in playbooks {
+        out.push_str(&format!(
+            "\n### {}\n",
+            p.trigger
+                .strip_prefix(PLAYBOOK_TRIGGER)
+                .unwrap_or(&p.trigger)
+                .trim()
+        ));
+        for (i, step) in p.steps.iter().enumerate() {
+            out.push_str(&format!("{}. {}", i + 1, step.description));
+            if let Some(specialty) = &step.args_pattern {
+                out.push_str(&format!(" ({})", specialty));
+            }
+            if let Some(watch_out) = &step.expected_outcome {
+                out.push_str(&format!(" — watch out: {}", watch_out));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(from: &str, to: Option<&str>, kind: &str, content: &str, at: &str) -> ProjectMessage {
+        ProjectMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: "p1".into(),
+            from_agent: from.into(),
+            to_agent: to.map(str::to_string),
+            kind: kind.into(),
+            content: content.into(),
+            metadata: None,
+            created_at: at.into(),
+        }
+    }
+
+    fn project() -> Project {
+        Project {
+            id: "p1".into(),
+            title: "Launch".into(),
+            goal: "Write and publish the launch blog post".into(),
+            status: "completed".into(),
+            boss_agent: "boss".into(),
+            agents: vec![],
+            budget_usd: 0.0,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn delegations_are_closed_by_results_and_errors() {
+        let mut blocked = msg(
+            "coder",
+            Some("boss"),
+            "progress",
+            "no creds",
+            "2026-01-01 10:03:00",
+        );
+        blocked.metadata = Some(r#"{"status":"blocked"}"#.into());
+        let messages = vec![
+            msg(
+                "boss",
+                Some("writer"),
+                "delegation",
+                "Draft the post",
+                "2026-01-01 10:00:00",
+            ),
+            msg(
+                "boss",
+                Some("coder"),
+                "delegation",
+                "Deploy it",
+                "2026-01-01 10:01:00",
+            ),
+            msg(
+                "writer",
+                Some("boss"),
+                "progress",
+                "halfway",
+                "2026-01-01 10:02:00",
+            ),
+            blocked,
+            msg(
+                "writer",
+                Some("boss"),
+                "result",
+                "Draft ready",
+                "2026-01-01 10:04:30",
+            ),
+            msg(
+                "boss",
+                Some("writer"),
+                "delegation",
+                "Proofread",
+                "2026-01-01 10:05:00",
+            ),
+        ];
+        let usage = vec![ProjectAgentUsage {
+            agent_id: "writer".into(),
+            model: "m".into(),
+            input_tokens: 10,
+            output_tokens: 5,
+            cost_usd: 0.25,
+            rounds: 2,
+            tool_calls: 1,
+        }];
+        let report = build(
+            &project(),
+            &messages,
+            usage,
+            "2026-01-01 10:00:00",
+            "2026-01-01 10:10:00",
+            "Shipped",
+        );
+
+        assert_eq!(report.duration_secs, 600);
+        assert_eq!(report.total_cost_usd, 0.25);
+        assert_eq!(report.delegations.len(), 3);
+        let d = &report.delegations;
+        assert_eq!(
+            (d[0].outcome.as_str(), d[0].duration_secs),
+            ("done", Some(270))
+        );
+        assert_eq!(d[0].note, "Draft ready");
+        assert_eq!(
+            (d[1].outcome.as_str(), d[1].duration_secs),
+            ("failed", Some(120))
+        );
+        assert_eq!(d[2].outcome, "unfinished");
+        assert_eq!(report.failures, vec!["coder: no creds".to_string()]);
+        assert!(to_markdown(&report).contains("[failed] coder → Deploy it (120 s)"));
+    }
+
+    #[test]
+    fn playbook_steps_parse_and_match_goals() {
+        let steps = parse_playbook(
+            r#"Here you go: [{"step":"Research the audience","specialty":"researcher","watch_out":""},
+               {"step":"  ","specialty":"coder"},
+               {"step":"Write the draft","specialty":"communicator","watch_out":"Check links"}]"#,
+        );
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].args_pattern.as_deref(), Some("researcher"));
+        assert_eq!(steps[0].expected_outcome, None);
+        assert_eq!(steps[1].expected_outcome.as_deref(), Some("Check links"));
+        assert!(parse_playbook("nothing reusable").is_empty());
+
+        let trigger = "Project playbook: Write and publish the launch blog post";
+        assert!(goal_match(trigger, "Publish a blog post for the v2 launch") >= PLAYBOOK_MIN_MATCH);
+        assert!(goal_match(trigger, "Audit the firewall rules") < PLAYBOOK_MIN_MATCH);
+    }
+}
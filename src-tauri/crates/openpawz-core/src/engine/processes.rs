@@ -0,0 +1,611 @@
+// Paw Agent Engine — Child Process Registry
+//
+// Every long-running process Paw starts (n8n, `ollama serve`, MCP servers,
+// tunnels, the headless browser, exec tool commands) is recorded in
+// `{data_root}/processes.json` with its purpose, so that:
+//
+//   - Settings can list them and stop one by hand (`engine_processes_*`)
+//   - After a crash, the next launch reconciles the file (`reconcile`):
+//     processes that are gone are dropped, services Paw reconnects to by
+//     port (n8n, ollama) are adopted, and everything else is killed
+//     instead of running on as an orphan
+//   - Where the OS allows it, children never outlive Paw in the first
+//     place (`bind_to_parent`): PR_SET_PDEATHSIG on Linux, a kill-on-close
+//     job object on Windows. macOS has neither and relies on reconciliation.
+//
+// Children started through `bind_to_parent` lead their own process group
+// on Unix, so stopping one also stops what it spawned (npx → node, sh -c).
+//
+// processes.json outlives a reboot, and PIDs and group ids are reused
+// across boots, so every entry records the boot it was started in and the
+// process's start time. Entries from another boot are dropped without
+// being signalled. Within one boot an entry is only treated as running
+// when the PID's start time still matches; a group whose leader already
+// exited is trusted as long as it exists, since a group id is never handed
+// out again while any member is alive. Windows has no boot id here, but
+// its start times are wall-clock and never repeat across boots.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::paths;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+const REGISTRY_FILE: &str = "processes.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagedProcess {
+    pub pid: u32,
+    /// What it is for, e.g. "n8n", "ollama", "mcp:github", "exec"
+    pub purpose: String,
+    /// Executable name when it was registered
+    pub program: String,
+    /// RFC 3339
+    pub started_at: String,
+    /// The Paw process that started (or adopted) it
+    pub owner_pid: u32,
+    /// Paw reconnects to it after a restart instead of killing it
+    pub adoptable: bool,
+    /// Leads its own process group (Unix) — stopped as a whole
+    pub group: bool,
+    /// Boot the process was started in (None where the OS has no boot id)
+    #[serde(default)]
+    pub boot_id: Option<String>,
+    /// OS start time of the process, compared before it is signalled
+    #[serde(default)]
+    pub start_time: Option<String>,
+    /// Still running (filled in by `list`)
+    #[serde(default)]
+    pub alive: bool,
+}
+
+static REGISTRY: LazyLock<Mutex<Vec<ManagedProcess>>> =
+    LazyLock::new(|| Mutex::new(read_registry()));
+
+static BOOT_ID: LazyLock<Option<String>> = LazyLock::new(boot_id);
+
+fn registry_path() -> PathBuf {
+    paths::paw_data_dir().join(REGISTRY_FILE)
+}
+
+fn read_registry() -> Vec<ManagedProcess> {
+    std::fs::read(registry_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_registry(entries: &[ManagedProcess]) {
+    let path = registry_path();
+    let result = serde_json::to_vec_pretty(entries)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("[processes] Could not write {}: {}", path.display(), e);
+    }
+}
+
+// ── Lifetime binding ───────────────────────────────────────────────────
+
+/// Put the child in its own process group and, on Linux, have the kernel
+/// send it SIGTERM when Paw exits. Call before `spawn`; for tokio commands
+/// pass `cmd.as_std_mut()`.
+///
+/// PR_SET_PDEATHSIG fires when the *thread* that spawned the child exits,
+/// so only spawn bound children from threads that live as long as the app
+/// (the async runtime's workers, not `spawn_blocking`).
+pub fn bind_to_parent(cmd: &mut std::process::Command) -> &mut std::process::Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+        #[cfg(target_os = "linux")]
+        {
+            let parent = std::process::id() as libc::pid_t;
+            // SAFETY: only async-signal-safe calls between fork and exec
+            unsafe {
+                cmd.pre_exec(move || {
+                    if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    // Paw died before prctl took effect
+                    if libc::getppid() != parent {
+                        libc::_exit(0);
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+    cmd
+}
+
+/// Record a child that should die with Paw (killed on the next launch if
+/// it survived a crash).
+pub fn track(pid: u32, purpose: &str) {
+    register(pid, purpose, false);
+}
+
+/// Record a service Paw reconnects to by address after a restart.
+pub fn track_service(pid: u32, purpose: &str) {
+    register(pid, purpose, true);
+}
+
+fn register(pid: u32, purpose: &str, adoptable: bool) {
+    if pid == 0 {
+        return;
+    }
+    #[cfg(windows)]
+    job::assign(pid);
+
+    let entry = ManagedProcess {
+        pid,
+        purpose: purpose.to_string(),
+        program: process_name(pid).unwrap_or_default(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        owner_pid: std::process::id(),
+        adoptable,
+        group: leads_group(pid),
+        boot_id: BOOT_ID.clone(),
+        start_time: start_time(pid),
+        alive: true,
+    };
+    let mut entries = REGISTRY.lock();
+    entries.retain(|e| e.pid != pid);
+    entries.push(entry);
+    write_registry(&entries);
+}
+
+/// Forget a child that exited or was stopped by its owner.
+pub fn untrack(pid: u32) {
+    let mut entries = REGISTRY.lock();
+    let before = entries.len();
+    entries.retain(|e| e.pid != pid);
+    if entries.len() != before {
+        write_registry(&entries);
+    }
+}
+
+/// Registered processes with their current state. Entries that are no
+/// longer running are dropped from the registry.
+pub fn list() -> Vec<ManagedProcess> {
+    let mut entries = REGISTRY.lock();
+    let mut out: Vec<ManagedProcess> = entries
+        .iter()
+        .cloned()
+        .map(|mut e| {
+            e.alive = is_running(&e);
+            e
+        })
+        .collect();
+    let before = entries.len();
+    entries.retain(|e| out.iter().any(|o| o.pid == e.pid && o.alive));
+    if entries.len() != before {
+        write_registry(&entries);
+    }
+    out.retain(|e| e.alive);
+    out
+}
+
+/// Stop a registered process (and its group or tree). Unknown PIDs are
+/// refused so this can't be used to signal arbitrary processes.
+pub fn kill(pid: u32) -> EngineResult<()> {
+    let entry = REGISTRY
+        .lock()
+        .iter()
+        .find(|e| e.pid == pid)
+        .cloned()
+        .ok_or_else(|| format!("Process {} was not started by Paw", pid))?;
+    if is_running(&entry) {
+        info!(
+            "[processes] Stopping {} (pid {}{})",
+            entry.purpose,
+            pid,
+            if entry.group { ", process group" } else { "" }
+        );
+        terminate(&entry);
+    }
+    untrack(pid);
+    Ok(())
+}
+
+/// Stop everything this Paw still has registered (last step of shutdown).
+pub fn stop_all() {
+    let me = std::process::id();
+    let owned: Vec<u32> = REGISTRY
+        .lock()
+        .iter()
+        .filter(|e| e.owner_pid == me)
+        .map(|e| e.pid)
+        .collect();
+    for pid in owned {
+        let _ = kill(pid);
+    }
+}
+
+/// Settle entries left by a previous run: drop the dead and those from an
+/// earlier boot, adopt adoptable services, kill the rest. Returns the
+/// adopted services so their owners can pick them up again. Entries of a
+/// Paw that is still running are left alone.
+pub fn reconcile() -> Vec<ManagedProcess> {
+    let me = std::process::id();
+    let mut entries = REGISTRY.lock();
+    if entries.iter().any(|e| e.boot_id != *BOOT_ID) {
+        info!("[processes] Discarding the process registry from an earlier boot");
+        entries.retain(|e| e.boot_id == *BOOT_ID);
+    }
+    let mut adopted = vec![];
+    let mut kept = vec![];
+    for mut entry in entries.drain(..) {
+        if entry.owner_pid == me || owner_running(entry.owner_pid) {
+            kept.push(entry);
+            continue;
+        }
+        if !is_running(&entry) {
+            continue;
+        }
+        if entry.adoptable {
+            info!(
+                "[processes] Adopting {} (pid {}) from the previous run",
+                entry.purpose, entry.pid
+            );
+            entry.owner_pid = me;
+            #[cfg(windows)]
+            job::assign(entry.pid);
+            adopted.push(entry.clone());
+            kept.push(entry);
+        } else {
+            warn!(
+                "[processes] Killing orphaned {} (pid {}) left by the previous run",
+                entry.purpose, entry.pid
+            );
+            terminate(&entry);
+        }
+    }
+    *entries = kept;
+    write_registry(&entries);
+    adopted
+}
+
+/// Whether the previous owner is another Paw that is still running.
+fn owner_running(owner_pid: u32) -> bool {
+    owner_pid != 0
+        && pid_alive(owner_pid)
+        && process_name(owner_pid).is_some_and(|name| name == current_name())
+}
+
+fn current_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .map(|n| truncate_name(&n))
+        .unwrap_or_default()
+}
+
+/// The registered process (or its group) is still the one Paw started.
+fn is_running(entry: &ManagedProcess) -> bool {
+    if entry.boot_id != *BOOT_ID {
+        return false;
+    }
+    if pid_alive(entry.pid) {
+        // A live PID must be the very process that was registered, whether
+        // or not it leads a group
+        return entry.start_time.is_some()
+            && start_time(entry.pid) == entry.start_time
+            && (entry.program.is_empty()
+                || process_name(entry.pid).is_some_and(|name| name == entry.program));
+    }
+    // Leader gone: its group id can't have been reused while members remain
+    entry.group && group_alive(entry.pid)
+}
+
+// ── Platform helpers ───────────────────────────────────────────────────
+
+/// Linux limits `comm` to 15 bytes; compare names the same way everywhere.
+fn truncate_name(name: &str) -> String {
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    name.char_indices()
+        .take_while(|(i, c)| i + c.len_utf8() <= 15)
+        .map(|(_, c)| c)
+        .collect()
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks for existence and permission
+    let rc = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(unix)]
+fn group_alive(pgid: u32) -> bool {
+    // SAFETY: as above, for the whole group
+    let rc = unsafe { libc::kill(-(pgid as libc::pid_t), 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(unix)]
+fn leads_group(pid: u32) -> bool {
+    // SAFETY: getpgid has no memory-safety preconditions
+    unsafe { libc::getpgid(pid as libc::pid_t) == pid as libc::pid_t }
+}
+
+#[cfg(unix)]
+fn terminate(entry: &ManagedProcess) {
+    let target = if entry.group {
+        -(entry.pid as libc::pid_t)
+    } else {
+        entry.pid as libc::pid_t
+    };
+    // SAFETY: plain signal delivery to a process or group we registered
+    unsafe {
+        libc::kill(target, libc::SIGTERM);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| truncate_name(name.trim()))
+}
+
+#[cfg(target_os = "linux")]
+fn boot_id() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Field 22 of /proc/<pid>/stat: clock ticks since boot.
+#[cfg(target_os = "linux")]
+fn start_time(pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // `comm` (field 2) may hold spaces and parens; fields resume after the last ')'
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(19).map(str::to_string)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn boot_id() -> Option<String> {
+    // "{ sec = 1700000000, usec = 123456 } Tue Nov 14 ..."
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "kern.boottime"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let id = text.split('}').next()?.trim_start_matches('{').trim();
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn start_time(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "lstart="])
+        .output()
+        .ok()?;
+    let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!started.is_empty()).then_some(started)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_name(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let name = path.rsplit('/').next()?;
+    (!name.is_empty()).then(|| truncate_name(name))
+}
+
+#[cfg(windows)]
+fn pid_alive(pid: u32) -> bool {
+    process_name(pid).is_some()
+}
+
+#[cfg(windows)]
+fn group_alive(pid: u32) -> bool {
+    pid_alive(pid)
+}
+
+#[cfg(windows)]
+fn boot_id() -> Option<String> {
+    None
+}
+
+/// Creation time as a FILETIME (100 ns since 1601) — wall-clock, so unique
+/// across boots.
+#[cfg(windows)]
+fn start_time(pid: u32) -> Option<String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    // SAFETY: the process handle is closed before returning
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let zero = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut created, mut exited, mut kernel, mut user) = (zero, zero, zero, zero);
+        let ok = GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user);
+        CloseHandle(process);
+        (ok != 0).then(|| {
+            (((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64).to_string()
+        })
+    }
+}
+
+#[cfg(windows)]
+fn leads_group(_pid: u32) -> bool {
+    // Trees are stopped with `taskkill /T` instead
+    false
+}
+
+#[cfg(windows)]
+fn terminate(entry: &ManagedProcess) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/PID", &entry.pid.to_string(), "/T", "/F"])
+        .status();
+}
+
+#[cfg(windows)]
+fn process_name(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output()
+        .ok()?;
+    // "node.exe","1234","Console","1","45,000 K" — or an INFO line
+    let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let mut fields = line.split("\",\"");
+    let name = fields.next()?.trim_start_matches('"');
+    let listed_pid = fields.next()?;
+    (listed_pid == pid.to_string()).then(|| truncate_name(name))
+}
+
+/// One job object for Paw's lifetime; closing it (when Paw exits, however
+/// it exits) kills every process assigned to it and their descendants.
+#[cfg(windows)]
+mod job {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+    };
+
+    /// The job handle as an integer (raw handles aren't Send); 0 if creation failed.
+    static JOB: OnceLock<usize> = OnceLock::new();
+
+    fn handle() -> usize {
+        *JOB.get_or_init(|| {
+            // SAFETY: plain Win32 calls; the handle is deliberately never
+            // closed — the OS closes it when Paw exits, which fires the limit
+            unsafe {
+                let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if job.is_null() {
+                    return 0;
+                }
+                let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let ok = SetInformationJobObject(
+                    job,
+                    JobObjectExtendedLimitInformation,
+                    &limits as *const _ as *const _,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                );
+                if ok == 0 {
+                    CloseHandle(job);
+                    return 0;
+                }
+                job as usize
+            }
+        })
+    }
+
+    pub(super) fn assign(pid: u32) {
+        let job = handle();
+        if job == 0 {
+            return;
+        }
+        // SAFETY: the process handle is closed before returning
+        unsafe {
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return;
+            }
+            if AssignProcessToJobObject(job as _, process) == 0 {
+                log::debug!("[processes] Could not add pid {} to the job object", pid);
+            }
+            CloseHandle(process);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_compare_like_proc_comm() {
+        assert_eq!(truncate_name("node.exe"), "node");
+        assert_eq!(truncate_name("cloudflared"), "cloudflared");
+        assert_eq!(truncate_name("chrome_crashpad_handler"), "chrome_crashpad");
+    }
+
+    #[cfg(unix)]
+    fn entry_for(pid: u32) -> ManagedProcess {
+        ManagedProcess {
+            pid,
+            purpose: "test".into(),
+            program: process_name(pid).unwrap_or_default(),
+            started_at: String::new(),
+            owner_pid: std::process::id(),
+            adoptable: false,
+            group: leads_group(pid),
+            boot_id: BOOT_ID.clone(),
+            start_time: start_time(pid),
+            alive: true,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bound_child_leads_its_group() {
+        let mut cmd = std::process::Command::new("sleep");
+        cmd.arg("5");
+        let mut child = bind_to_parent(&mut cmd).spawn().unwrap();
+        let entry = entry_for(child.id());
+        assert!(entry.group);
+        assert!(entry.start_time.is_some());
+        assert!(is_running(&entry));
+        terminate(&entry);
+        child.wait().unwrap();
+        assert!(!is_running(&entry));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reused_ids_are_not_treated_as_ours() {
+        let mut cmd = std::process::Command::new("sleep");
+        cmd.arg("5");
+        let mut child = bind_to_parent(&mut cmd).spawn().unwrap();
+        let entry = entry_for(child.id());
+
+        // Same PID, different process (started at another time)
+        let restarted = ManagedProcess {
+            start_time: Some("0".into()),
+            ..entry.clone()
+        };
+        assert!(!is_running(&restarted));
+
+        // Recorded before a reboot
+        let earlier_boot = ManagedProcess {
+            boot_id: Some("another-boot".into()),
+            ..entry.clone()
+        };
+        assert!(!is_running(&earlier_boot));
+
+        // No start time recorded: can't be verified, never signalled
+        let unverified = ManagedProcess {
+            start_time: None,
+            ..entry.clone()
+        };
+        assert!(!is_running(&unverified));
+
+        terminate(&entry);
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+// ── Project budgets and cost estimates ─────────────────────────────────────
+//
+// A project can carry a hard budget (Project.budget_usd). Before delegating,
+// the boss can price a plan with `estimate_cost`: each task is priced with
+// the pricing registry for the model its agent will run on, using the tokens
+// an average delegated task took in past projects (from their post-mortems),
+// plus the boss's own overhead per task. Without history, conservative
+// defaults stand in.
+
+use crate::engine::pricing::estimate_cost_usd;
+use crate::engine::types::ProjectPostMortem;
+use serde::Serialize;
+
+/// Session suffix of the boss agent in a project's telemetry.
+const BOSS_SESSION: &str = "boss";
+
+/// Tokens one delegated task takes, on average.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskCostProfile {
+    pub worker_input_tokens: u64,
+    pub worker_output_tokens: u64,
+    /// The boss's rounds spent planning, delegating and reviewing the task
+    pub boss_input_tokens: u64,
+    pub boss_output_tokens: u64,
+    /// Past delegations the averages come from (0 = defaults)
+    pub samples: u32,
+}
+
+impl Default for TaskCostProfile {
+    /// A tool-using worker resends its growing context every round, so
+    /// input dominates.
+    fn default() -> Self {
+        TaskCostProfile {
+            worker_input_tokens: 60_000,
+            worker_output_tokens: 3_000,
+            boss_input_tokens: 15_000,
+            boss_output_tokens: 1_000,
+            samples: 0,
+        }
+    }
+}
+
+impl TaskCostProfile {
+    /// Average tokens per delegation over past runs.
+    pub fn from_postmortems(reports: &[ProjectPostMortem]) -> Self {
+        let mut tokens = [0u64; 4];
+        let mut samples = 0u64;
+        for report in reports.iter().filter(|r| !r.delegations.is_empty()) {
+            samples += report.delegations.len() as u64;
+            for u in &report.usage {
+                let offset = if u.agent_id == BOSS_SESSION { 2 } else { 0 };
+                tokens[offset] += u.input_tokens;
+                tokens[offset + 1] += u.output_tokens;
+            }
+        }
+        if samples == 0 {
+            return Self::default();
+        }
+        TaskCostProfile {
+            worker_input_tokens: tokens[0] / samples,
+            worker_output_tokens: tokens[1] / samples,
+            boss_input_tokens: tokens[2] / samples,
+            boss_output_tokens: tokens[3] / samples,
+            samples: samples as u32,
+        }
+    }
+}
+
+/// One task of a plan, with the model its agent runs on.
+#[derive(Debug, Clone)]
+pub struct PlannedTask {
+    pub agent_id: String,
+    pub task: String,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskEstimate {
+    pub agent_id: String,
+    pub task: String,
+    pub model: String,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    pub tasks: Vec<TaskEstimate>,
+    /// The boss's overhead for the whole plan
+    pub boss_cost_usd: f64,
+    pub total_usd: f64,
+    pub samples: u32,
+}
+
+/// Price a plan.
+pub fn estimate(plan: &[PlannedTask], boss_model: &str, profile: &TaskCostProfile) -> CostEstimate {
+    let tasks: Vec<TaskEstimate> = plan
+        .iter()
+        .map(|t| TaskEstimate {
+            agent_id: t.agent_id.clone(),
+            task: t.task.clone(),
+            model: t.model.clone(),
+            cost_usd: estimate_cost_usd(
+                &t.model,
+                profile.worker_input_tokens,
+                profile.worker_output_tokens,
+                0,
+                0,
+            ),
+        })
+        .collect();
+    let boss_cost_usd = plan.len() as f64
+        * estimate_cost_usd(
+            boss_model,
+            profile.boss_input_tokens,
+            profile.boss_output_tokens,
+            0,
+            0,
+        );
+    let total_usd = tasks.iter().map(|t| t.cost_usd).sum::<f64>() + boss_cost_usd;
+    CostEstimate {
+        tasks,
+        boss_cost_usd,
+        total_usd,
+        samples: profile.samples,
+    }
+}
+
+/// The estimate as the boss reads it, checked against the project budget
+/// (`budget_usd` 0 = none).
+pub fn format_estimate(est: &CostEstimate, budget_usd: f64, spent_usd: f64) -> String {
+    let mut out = format!(
+        "Estimated cost: ${:.4} for {} task(s), including ${:.4} of your own coordination.\n",
+        est.total_usd,
+        est.tasks.len(),
+        est.boss_cost_usd
+    );
+    for t in &est.tasks {
+        out.push_str(&format!(
+            "- {} ({}): ${:.4} — {}\n",
+            t.agent_id,
+            t.model,
+            t.cost_usd,
+            crate::engine::util::safe_truncate(&t.task, 80)
+        ));
+    }
+    if est.samples > 0 {
+        out.push_str(&format!(
+            "Based on the average of {} past delegated tasks.\n",
+            est.samples
+        ));
+    } else {
+        out.push_str("No past projects to learn from yet — based on typical task sizes.\n");
+    }
+    if budget_usd > 0.0 {
+        let remaining = (budget_usd - spent_usd).max(0.0);
+        out.push_str(&format!(
+            "Budget: ${:.2}, spent ${:.4}, remaining ${:.4}. ",
+            budget_usd, spent_usd, remaining
+        ));
+        if est.total_usd <= remaining {
+            out.push_str("The plan fits.");
+        } else {
+            out.push_str(&format!(
+                "The plan is over budget by ${:.4} — cut or merge tasks, or assign agents on \
+                 cheaper models.",
+                est.total_usd - remaining
+            ));
+        }
+    } else {
+        out.push_str("The project has no budget limit.");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::{DelegationRecord, ProjectAgentUsage};
+
+    fn usage(agent_id: &str, input: u64, output: u64) -> ProjectAgentUsage {
+        ProjectAgentUsage {
+            agent_id: agent_id.into(),
+            model: "gpt-4o-mini".into(),
+            input_tokens: input,
+            output_tokens: output,
+            cost_usd: 0.0,
+            rounds: 1,
+            tool_calls: 0,
+        }
+    }
+
+    fn delegation() -> DelegationRecord {
+        DelegationRecord {
+            agent_id: "coder".into(),
+            task: "Build it".into(),
+            delegated_at: String::new(),
+            finished_at: None,
+            duration_secs: None,
+            outcome: "done".into(),
+            note: String::new(),
+        }
+    }
+
+    fn report(delegations: usize, usage: Vec<ProjectAgentUsage>) -> ProjectPostMortem {
+        ProjectPostMortem {
+            project_id: "p".into(),
+            title: String::new(),
+            goal: String::new(),
+            status: "completed".into(),
+            started_at: String::new(),
+            finished_at: String::new(),
+            duration_secs: 0,
+            delegations: vec![delegation(); delegations],
+            failures: vec![],
+            usage,
+            total_cost_usd: 0.0,
+            summary: String::new(),
+            playbook_id: None,
+        }
+    }
+
+    #[test]
+    fn profile_averages_tokens_per_delegation() {
+        let reports = vec![
+            report(
+                2,
+                vec![usage("coder", 30_000, 2_000), usage("boss", 8_000, 400)],
+            ),
+            report(
+                2,
+                vec![usage("writer", 10_000, 2_000), usage("boss", 4_000, 400)],
+            ),
+            report(0, vec![usage("boss", 99_000, 9_000)]),
+        ];
+        let profile = TaskCostProfile::from_postmortems(&reports);
+        assert_eq!(profile.samples, 4);
+        assert_eq!(profile.worker_input_tokens, 10_000);
+        assert_eq!(profile.worker_output_tokens, 1_000);
+        assert_eq!(profile.boss_input_tokens, 3_000);
+        assert_eq!(profile.boss_output_tokens, 200);
+        assert_eq!(
+            TaskCostProfile::from_postmortems(&[]),
+            TaskCostProfile::default()
+        );
+    }
+
+    #[test]
+    fn estimate_is_checked_against_the_remaining_budget() {
+        let plan = vec![PlannedTask {
+            agent_id: "coder".into(),
+            task: "Build it".into(),
+            model: "gpt-4o".into(),
+        }];
+        let profile = TaskCostProfile {
+            worker_input_tokens: 1_000_000,
+            worker_output_tokens: 0,
+            boss_input_tokens: 0,
+            boss_output_tokens: 0,
+            samples: 3,
+        };
+        let est = estimate(&plan, "gpt-4o", &profile);
+        assert!((est.total_usd - 2.5).abs() < 1e-9);
+
+        assert!(format_estimate(&est, 5.0, 1.0).ends_with("The plan fits."));
+        let over = format_estimate(&est, 3.0, 1.0);
+        assert!(over.contains("over budget by $0.5000"), "{}", over);
+        assert!(format_estimate(&est, 0.0, 1.0).ends_with("no budget limit."));
+    }
+}
@@ -0,0 +1,381 @@
+// ── Prompt Library: reusable templates with typed variables ────────────────
+//
+// Named prompts for recurring workflows ("weekly review", "PR description").
+// A template body uses `{{variable}}` placeholders; every placeholder must be
+// declared with a type so the UI can render a form and `render` can validate
+// the values before anything is sent:
+//
+//   text     any string
+//   number   must parse as a number
+//   boolean  true / false
+//   choice   one of the declared `options`
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableKind {
+    #[default]
+    Text,
+    Number,
+    Boolean,
+    Choice,
+}
+
+impl VariableKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VariableKind::Text => "text",
+            VariableKind::Number => "number",
+            VariableKind::Boolean => "boolean",
+            VariableKind::Choice => "choice",
+        }
+    }
+}
+
+/// One declared `{{name}}` placeholder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    #[serde(default)]
+    pub kind: VariableKind,
+    #[serde(default)]
+    pub description: String,
+    /// Used when no value is supplied.
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// Allowed values for `choice` variables.
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub body: String,
+    pub variables: Vec<PromptVariable>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// ── Placeholders & validation ──────────────────────────────────────────────
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Placeholder names in a body, in order of first appearance.
+pub fn placeholders(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim();
+        if valid_name(name) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Check a template before it is stored.
+pub fn validate(name: &str, body: &str, variables: &[PromptVariable]) -> EngineResult<()> {
+    if name.trim().is_empty() {
+        return Err("Template name is required".into());
+    }
+    if body.trim().is_empty() {
+        return Err("Template body is required".into());
+    }
+    for (i, v) in variables.iter().enumerate() {
+        if !valid_name(&v.name) {
+            return Err(format!(
+                "Invalid variable name '{}' — use letters, digits and _",
+                v.name
+            )
+            .into());
+        }
+        if variables[..i].iter().any(|o| o.name == v.name) {
+            return Err(format!("Variable '{}' is declared twice", v.name).into());
+        }
+        if v.kind == VariableKind::Choice && v.options.is_empty() {
+            return Err(format!("Choice variable '{}' needs options", v.name).into());
+        }
+        if let Some(ref d) = v.default {
+            check_value(v, d)?;
+        }
+    }
+    for p in placeholders(body) {
+        if !variables.iter().any(|v| v.name == p) {
+            return Err(format!("Placeholder {{{{{}}}}} is not declared as a variable", p).into());
+        }
+    }
+    Ok(())
+}
+
+fn check_value(var: &PromptVariable, value: &str) -> EngineResult<()> {
+    let ok = match var.kind {
+        VariableKind::Text => true,
+        VariableKind::Number => value.trim().parse::<f64>().is_ok(),
+        VariableKind::Boolean => matches!(value.trim(), "true" | "false"),
+        VariableKind::Choice => var.options.iter().any(|o| o == value),
+    };
+    if ok {
+        Ok(())
+    } else if var.kind == VariableKind::Choice {
+        Err(format!("'{}' must be one of: {}", var.name, var.options.join(", ")).into())
+    } else {
+        Err(format!("'{}' must be a {} value", var.name, var.kind.as_str()).into())
+    }
+}
+
+/// Fill in a template. Values may be JSON strings, numbers or booleans;
+/// missing values fall back to the variable's default.
+pub fn render(
+    template: &PromptTemplate,
+    values: &HashMap<String, serde_json::Value>,
+) -> EngineResult<String> {
+    let mut resolved: HashMap<&str, String> = HashMap::new();
+    for var in &template.variables {
+        let value = match values.get(&var.name) {
+            Some(serde_json::Value::String(s)) if !s.trim().is_empty() => Some(s.clone()),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            Some(serde_json::Value::Bool(b)) => Some(b.to_string()),
+            _ => var.default.clone(),
+        };
+        match value {
+            Some(v) => {
+                check_value(var, &v)?;
+                resolved.insert(var.name.as_str(), v);
+            }
+            None if var.required => {
+                return Err(format!("Missing value for '{}'", var.name).into());
+            }
+            None => {
+                resolved.insert(var.name.as_str(), String::new());
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(template.body.len());
+    let mut rest = template.body.as_str();
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        match resolved.get(after[..end].trim()) {
+            Some(v) => out.push_str(v),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out.trim().to_string())
+}
+
+// ── Storage ────────────────────────────────────────────────────────────────
+
+fn template_from_row(row: &rusqlite::Row) -> rusqlite::Result<PromptTemplate> {
+    let variables_json: String = row.get(4)?;
+    Ok(PromptTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        body: row.get(3)?,
+        variables: serde_json::from_str(&variables_json).unwrap_or_default(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+const TEMPLATE_COLUMNS: &str = "id, name, description, body, variables, created_at, updated_at";
+
+/// All templates, by name.
+pub fn list(store: &SessionStore) -> EngineResult<Vec<PromptTemplate>> {
+    let conn = store.conn.lock();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM prompt_templates ORDER BY name COLLATE NOCASE",
+        TEMPLATE_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], template_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn get(store: &SessionStore, id: &str) -> EngineResult<Option<PromptTemplate>> {
+    let conn = store.conn.lock();
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM prompt_templates WHERE id = ?1",
+                TEMPLATE_COLUMNS
+            ),
+            params![id],
+            template_from_row,
+        )
+        .optional()?)
+}
+
+/// Create (no `id`) or update a template. Names are unique.
+pub fn save(
+    store: &SessionStore,
+    id: Option<&str>,
+    name: &str,
+    description: &str,
+    body: &str,
+    variables: &[PromptVariable],
+) -> EngineResult<PromptTemplate> {
+    validate(name, body, variables)?;
+    let name = name.trim();
+    let id = id
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    {
+        let conn = store.conn.lock();
+        let taken: Option<String> = conn
+            .query_row(
+                "SELECT id FROM prompt_templates WHERE name = ?1 COLLATE NOCASE AND id != ?2",
+                params![name, id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if taken.is_some() {
+            return Err(format!("A template named '{}' already exists", name).into());
+        }
+        conn.execute(
+            "INSERT INTO prompt_templates (id, name, description, body, variables)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name, description = excluded.description,
+                body = excluded.body, variables = excluded.variables,
+                updated_at = datetime('now')",
+            params![
+                id,
+                name,
+                description.trim(),
+                body,
+                serde_json::to_string(variables)?
+            ],
+        )?;
+    }
+    get(store, &id)?.ok_or_else(|| "Template vanished after save".into())
+}
+
+/// Delete a template. Returns false if it did not exist.
+pub fn delete(store: &SessionStore, id: &str) -> EngineResult<bool> {
+    let conn = store.conn.lock();
+    Ok(conn.execute("DELETE FROM prompt_templates WHERE id = ?1", params![id])? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn var(name: &str, kind: VariableKind) -> PromptVariable {
+        PromptVariable {
+            name: name.into(),
+            kind,
+            description: String::new(),
+            default: None,
+            required: true,
+            options: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn validates_declarations() {
+        let text = var("topic", VariableKind::Text);
+        assert!(validate("Review", "About {{ topic }}", std::slice::from_ref(&text)).is_ok());
+        assert!(validate(
+            "Review",
+            "About {{topic}} in {{repo}}",
+            std::slice::from_ref(&text)
+        )
+        .is_err());
+        assert!(validate("Review", "x", &[text.clone(), text]).is_err());
+        assert!(validate("Review", "x", &[var("mode", VariableKind::Choice)]).is_err());
+        assert!(validate("", "x", &[]).is_err());
+    }
+
+    #[test]
+    fn renders_typed_values() {
+        let mut days = var("days", VariableKind::Number);
+        days.default = Some("7".into());
+        let mut tone = var("tone", VariableKind::Choice);
+        tone.options = vec!["brief".into(), "detailed".into()];
+        let mut notes = var("notes", VariableKind::Text);
+        notes.required = false;
+        let t = PromptTemplate {
+            id: "t1".into(),
+            name: "Weekly review".into(),
+            description: String::new(),
+            body: "Review the last {{days}} days, {{tone}}. {{notes}} {{unknown}}".into(),
+            variables: vec![days, tone, notes],
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        let mut values = HashMap::new();
+        values.insert("tone".to_string(), serde_json::json!("brief"));
+        assert_eq!(
+            render(&t, &values).unwrap(),
+            "Review the last 7 days, brief.  {{unknown}}"
+        );
+
+        values.insert("days".to_string(), serde_json::json!("a week"));
+        assert!(render(&t, &values).is_err());
+        values.insert("days".to_string(), serde_json::json!(3));
+        values.insert("tone".to_string(), serde_json::json!("chatty"));
+        assert!(render(&t, &values).is_err());
+        values.remove("tone");
+        assert!(render(&t, &values).is_err());
+    }
+
+    #[test]
+    fn save_update_and_unique_names() {
+        let store = test_store();
+        let vars = vec![var("pr", VariableKind::Text)];
+        let t = save(&store, None, "PR description", "", "Describe {{pr}}", &vars).unwrap();
+        assert_eq!(t.variables, vars);
+
+        let updated = save(
+            &store,
+            Some(&t.id),
+            "PR description",
+            "For GitHub",
+            "Summarise {{pr}}",
+            &vars,
+        )
+        .unwrap();
+        assert_eq!(updated.body, "Summarise {{pr}}");
+        assert_eq!(list(&store).unwrap().len(), 1);
+
+        assert!(save(&store, None, "pr DESCRIPTION", "", "x", &[]).is_err());
+        assert!(delete(&store, &t.id).unwrap());
+        assert!(get(&store, &t.id).unwrap().is_none());
+    }
+}
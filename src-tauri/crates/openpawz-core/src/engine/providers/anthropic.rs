@@ -3,6 +3,7 @@
 // All Claude-specific SSE event parsing and prompt-caching logic lives here.
 
 use crate::atoms::traits::{AiProvider, ProviderError};
+use crate::engine::cancel;
 use crate::engine::http::{
     pinned_client, sign_and_log_request, update_last_audit_status, CircuitBreaker,
 };
@@ -15,6 +16,7 @@ use futures::StreamExt;
 use log::{error, info, warn};
 use reqwest::Client;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::LazyLock;
 use zeroize::Zeroizing;
 
@@ -63,6 +65,14 @@ impl AnthropicProvider {
         let mut system = None;
         let mut formatted = Vec::new();
 
+        // Thinking only needs replaying within the current tool loop (after
+        // the last user message); older turns' thinking is stripped anyway
+        // and may carry another provider's signatures.
+        let replay_from = messages
+            .iter()
+            .rposition(|m| m.role == Role::User)
+            .unwrap_or(0);
+
         let mut idx = 0;
         while idx < messages.len() {
             let msg = &messages[idx];
@@ -101,6 +111,22 @@ impl AnthropicProvider {
                 if let Some(tool_calls) = &msg.tool_calls {
                     // Assistant message with tool use
                     let mut content_blocks: Vec<Value> = vec![];
+                    if idx > replay_from {
+                        let thoughts = tool_calls.iter().flat_map(|tc| &tc.thought_parts);
+                        for tp in thoughts
+                            .filter(|tp| tp.item_id.is_none() && !tp.thought_signature.is_empty())
+                        {
+                            content_blocks.push(if tp.text.is_empty() {
+                                json!({"type": "redacted_thinking", "data": tp.thought_signature})
+                            } else {
+                                json!({
+                                    "type": "thinking",
+                                    "thinking": tp.text,
+                                    "signature": tp.thought_signature,
+                                })
+                            });
+                        }
+                    }
                     let text = msg.content.as_text();
                     if !text.is_empty() {
                         content_blocks.push(json!({"type": "text", "text": text}));
@@ -256,156 +282,6 @@ impl AnthropicProvider {
             .collect()
     }
 
-    fn parse_sse_event(data: &str) -> Option<StreamChunk> {
-        let v: Value = serde_json::from_str(data).ok()?;
-        let event_type = v["type"].as_str()?;
-
-        match event_type {
-            "content_block_delta" => {
-                let delta = &v["delta"];
-                let delta_type = delta["type"].as_str().unwrap_or("");
-                match delta_type {
-                    "text_delta" => Some(StreamChunk {
-                        delta_text: delta["text"].as_str().map(|s| s.to_string()),
-                        tool_calls: vec![],
-                        finish_reason: None,
-                        usage: None,
-                        model: None,
-                        thought_parts: vec![],
-                        thinking_text: None,
-                    }),
-                    "thinking_delta" => {
-                        // Anthropic extended thinking: stream the reasoning text
-                        Some(StreamChunk {
-                            delta_text: None,
-                            tool_calls: vec![],
-                            finish_reason: None,
-                            usage: None,
-                            model: None,
-                            thought_parts: vec![],
-                            thinking_text: delta["thinking"].as_str().map(|s| s.to_string()),
-                        })
-                    }
-                    "input_json_delta" => {
-                        let index = v["index"].as_u64().unwrap_or(0) as usize;
-                        Some(StreamChunk {
-                            delta_text: None,
-                            tool_calls: vec![ToolCallDelta {
-                                index,
-                                id: None,
-                                function_name: None,
-                                arguments_delta: delta["partial_json"]
-                                    .as_str()
-                                    .map(|s| s.to_string()),
-                                thought_signature: None,
-                            }],
-                            finish_reason: None,
-                            usage: None,
-                            model: None,
-                            thought_parts: vec![],
-                            thinking_text: None,
-                        })
-                    }
-                    _ => None,
-                }
-            }
-            "content_block_start" => {
-                let block = &v["content_block"];
-                let block_type = block["type"].as_str().unwrap_or("");
-                if block_type == "tool_use" {
-                    let index = v["index"].as_u64().unwrap_or(0) as usize;
-                    Some(StreamChunk {
-                        delta_text: None,
-                        tool_calls: vec![ToolCallDelta {
-                            index,
-                            id: block["id"].as_str().map(|s| s.to_string()),
-                            function_name: block["name"].as_str().map(|s| s.to_string()),
-                            arguments_delta: None,
-                            thought_signature: None,
-                        }],
-                        finish_reason: None,
-                        usage: None,
-                        model: None,
-                        thought_parts: vec![],
-                        thinking_text: None,
-                    })
-                } else {
-                    None
-                }
-            }
-            "message_delta" => {
-                let stop_reason = v["delta"]["stop_reason"].as_str().map(|s| s.to_string());
-                // Anthropic reports usage in message_delta
-                let usage = v.get("usage").and_then(|u| {
-                    let output = u["output_tokens"].as_u64().unwrap_or(0);
-                    if output > 0 {
-                        Some(TokenUsage {
-                            input_tokens: 0, // Anthropic reports input in message_start
-                            output_tokens: output,
-                            total_tokens: output,
-                            ..Default::default()
-                        })
-                    } else {
-                        None
-                    }
-                });
-                Some(StreamChunk {
-                    delta_text: None,
-                    tool_calls: vec![],
-                    finish_reason: stop_reason,
-                    usage,
-                    model: None,
-                    thought_parts: vec![],
-                    thinking_text: None,
-                })
-            }
-            "message_start" => {
-                // Anthropic message_start contains input token count AND the actual model name
-                let msg = v.get("message");
-                let model = msg.and_then(|m| m["model"].as_str()).map(|s| s.to_string());
-                let usage = msg.and_then(|m| m.get("usage")).and_then(|u| {
-                    let input = u["input_tokens"].as_u64().unwrap_or(0);
-                    let cache_create = u["cache_creation_input_tokens"].as_u64().unwrap_or(0);
-                    let cache_read = u["cache_read_input_tokens"].as_u64().unwrap_or(0);
-                    if cache_create > 0 || cache_read > 0 {
-                        info!("[engine] Anthropic cache: {} tokens created, {} tokens read (input: {})",
-                            cache_create, cache_read, input);
-                    }
-                    if input > 0 {
-                        Some(TokenUsage {
-                            input_tokens: input,
-                            output_tokens: 0,
-                            total_tokens: input,
-                            cache_creation_tokens: cache_create,
-                            cache_read_tokens: cache_read,
-                        })
-                    } else {
-                        None
-                    }
-                });
-                Some(StreamChunk {
-                    delta_text: None,
-                    tool_calls: vec![],
-                    finish_reason: None,
-                    usage,
-                    model,
-                    thought_parts: vec![],
-                    thinking_text: None,
-                })
-            }
-            "message_stop" => Some(StreamChunk {
-                delta_text: None,
-                tool_calls: vec![],
-                finish_reason: Some("stop".into()),
-                usage: None,
-                model: None,
-                thought_parts: vec![],
-                thinking_text: None,
-            }),
-            _ => None,
-        }
-    }
-
     /// Inner implementation with full SSE + retry logic + error classification.
     async fn chat_stream_inner(
         &self,
@@ -562,7 +438,8 @@ impl AnthropicProvider {
             if !self.is_azure_openai {
                 req = req.header(
                     "anthropic-beta",
-                    "prompt-caching-2024-07-31,interleaved-thinking-2025-05-14",
+                    "prompt-caching-2024-07-31,interleaved-thinking-2025-05-14,\
+                     fine-grained-tool-streaming-2025-05-14",
                 );
             }
             // Azure AI Foundry /anthropic proxy uses native Anthropic auth
@@ -577,6 +454,7 @@ impl AnthropicProvider {
             let body_bytes = serde_json::to_vec(&body).unwrap_or_default();
             sign_and_log_request("anthropic", model, &body_bytes);
 
+            let req = super::middleware::apply_request_headers(req);
             let response = match req.json(&body).send().await {
                 Ok(r) => {
                     update_last_audit_status(r.status().as_u16());
@@ -637,10 +515,12 @@ impl AnthropicProvider {
             }
 
             let mut chunks = Vec::new();
+            let mut stream = AnthropicStream::default();
+            let mut stream_error = None;
             let mut byte_stream = response.bytes_stream();
             let mut buffer = String::new();
 
-            while let Some(result) = byte_stream.next().await {
+            'read: while let Some(Some(result)) = cancel::or_cancelled(byte_stream.next()).await {
                 let bytes = result
                     .map_err(|e| ProviderError::Transport(format!("Stream read error: {}", e)))?;
                 buffer.push_str(&String::from_utf8_lossy(&bytes));
@@ -650,13 +530,33 @@ impl AnthropicProvider {
                     buffer = buffer[line_end + 1..].to_string();
 
                     if let Some(data) = line.strip_prefix("data: ") {
-                        if let Some(chunk) = Self::parse_sse_event(data) {
-                            chunks.push(chunk);
+                        match stream.push(data) {
+                            Ok(parsed) => chunks.extend(parsed),
+                            Err(e) => {
+                                stream_error = Some(e);
+                                break 'read;
+                            }
                         }
                     }
                 }
             }
 
+            // Mid-stream errors (e.g. overloaded) retry like HTTP errors
+            if let Some(ProviderError::Api { status, message }) = stream_error {
+                error!("[engine] Anthropic stream error {}: {}", status, message);
+                ANTHROPIC_CIRCUIT.record_failure();
+                last_status = status;
+                last_error = message;
+                if is_retryable_status(status) && attempt < MAX_RETRIES {
+                    continue;
+                }
+                return Err(ProviderError::Api {
+                    status,
+                    message: last_error,
+                });
+            }
+
+            stream.finish(&mut chunks);
             ANTHROPIC_CIRCUIT.record_success();
             return Ok(chunks);
         }
@@ -676,6 +576,251 @@ impl AnthropicProvider {
     }
 }
 
+// ── SSE stream parsing ────────────────────────────────────────────────────────
+
+/// Turns Messages API stream events into stream chunks.
+///
+/// Parallel `tool_use` blocks keep their content-block index, so each call
+/// accumulates separately in the loop. Tool input arrives as raw JSON
+/// fragments (fine-grained streaming), buffered here only to check it is
+/// complete: a call cut off by `max_tokens` is dropped rather than run with
+/// half its arguments. Thinking blocks (with their signatures) ride along on
+/// the first tool call so they can be replayed ahead of it next round.
+#[derive(Default)]
+struct AnthropicStream {
+    /// tool_use blocks: content-block index → accumulated input JSON.
+    tool_inputs: HashMap<usize, String>,
+    /// Thinking block being streamed: (thinking text, signature).
+    thinking: Option<(String, String)>,
+    /// Finished thinking blocks waiting for the next tool call.
+    pending_thoughts: Vec<ThoughtPart>,
+    stop_reason: Option<String>,
+}
+
+impl AnthropicStream {
+    fn chunk() -> StreamChunk {
+        StreamChunk {
+            delta_text: None,
+            tool_calls: vec![],
+            finish_reason: None,
+            usage: None,
+            model: None,
+            thought_parts: vec![],
+            thinking_text: None,
+            meta: vec![],
+        }
+    }
+
+    fn push(&mut self, data: &str) -> Result<Vec<StreamChunk>, ProviderError> {
+        let Ok(v) = serde_json::from_str::<Value>(data) else {
+            return Ok(vec![]);
+        };
+        let index = v["index"].as_u64().unwrap_or(0) as usize;
+        let mut chunks = Vec::new();
+
+        match v["type"].as_str().unwrap_or("") {
+            "content_block_start" => {
+                let block = &v["content_block"];
+                match block["type"].as_str().unwrap_or("") {
+                    "tool_use" => {
+                        self.tool_inputs.insert(index, String::new());
+                        chunks.push(StreamChunk {
+                            tool_calls: vec![ToolCallDelta {
+                                index,
+                                id: block["id"].as_str().map(|s| s.to_string()),
+                                function_name: block["name"].as_str().map(|s| s.to_string()),
+                                arguments_delta: None,
+                                thought_signature: None,
+                            }],
+                            thought_parts: std::mem::take(&mut self.pending_thoughts),
+                            ..Self::chunk()
+                        });
+                    }
+                    "thinking" => self.thinking = Some((String::new(), String::new())),
+                    // Redacted thinking: only the opaque data goes back
+                    "redacted_thinking" => self.pending_thoughts.push(ThoughtPart {
+                        text: String::new(),
+                        thought_signature: block["data"].as_str().unwrap_or("").to_string(),
+                        thought: true,
+                        item_id: None,
+                    }),
+                    _ => {}
+                }
+            }
+            "content_block_delta" => {
+                let delta = &v["delta"];
+                match delta["type"].as_str().unwrap_or("") {
+                    "text_delta" => chunks.push(StreamChunk {
+                        delta_text: delta["text"].as_str().map(|s| s.to_string()),
+                        ..Self::chunk()
+                    }),
+                    "thinking_delta" => {
+                        let text = delta["thinking"].as_str().unwrap_or("");
+                        if let Some((thinking, _)) = &mut self.thinking {
+                            thinking.push_str(text);
+                        }
+                        // Anthropic extended thinking: stream the reasoning text
+                        chunks.push(StreamChunk {
+                            thinking_text: Some(text.to_string()),
+                            ..Self::chunk()
+                        });
+                    }
+                    "signature_delta" => {
+                        if let Some((_, signature)) = &mut self.thinking {
+                            signature.push_str(delta["signature"].as_str().unwrap_or(""));
+                        }
+                    }
+                    "input_json_delta" => {
+                        let partial = delta["partial_json"].as_str().unwrap_or("");
+                        if let Some(input) = self.tool_inputs.get_mut(&index) {
+                            input.push_str(partial);
+                        }
+                        if !partial.is_empty() {
+                            chunks.push(StreamChunk {
+                                tool_calls: vec![Self::arguments(index, partial)],
+                                ..Self::chunk()
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                if let Some(input) = self.tool_inputs.get_mut(&index) {
+                    // Tools without parameters stream no input at all
+                    if input.trim().is_empty() {
+                        input.push_str("{}");
+                        chunks.push(StreamChunk {
+                            tool_calls: vec![Self::arguments(index, "{}")],
+                            ..Self::chunk()
+                        });
+                    }
+                } else if let Some((text, signature)) = self.thinking.take() {
+                    self.pending_thoughts.push(ThoughtPart {
+                        text,
+                        thought_signature: signature,
+                        thought: true,
+                        item_id: None,
+                    });
+                }
+            }
+            "message_delta" => {
+                self.stop_reason = v["delta"]["stop_reason"].as_str().map(|s| s.to_string());
+                // Anthropic reports usage in message_delta
+                let output = v["usage"]["output_tokens"].as_u64().unwrap_or(0);
+                let usage = (output > 0).then(|| TokenUsage {
+                    input_tokens: 0, // Anthropic reports input in message_start
+                    output_tokens: output,
+                    total_tokens: output,
+                    ..Default::default()
+                });
+                chunks.push(StreamChunk {
+                    finish_reason: self.stop_reason.clone(),
+                    usage,
+                    ..Self::chunk()
+                });
+            }
+            "message_start" => {
+                // Anthropic message_start contains input token count AND the actual model name
+                let msg = &v["message"];
+                let u = &msg["usage"];
+                let input = u["input_tokens"].as_u64().unwrap_or(0);
+                let cache_create = u["cache_creation_input_tokens"].as_u64().unwrap_or(0);
+                let cache_read = u["cache_read_input_tokens"].as_u64().unwrap_or(0);
+                if cache_create > 0 || cache_read > 0 {
+                    info!(
+                        "[engine] Anthropic cache: {} tokens created, {} tokens read (input: {})",
+                        cache_create, cache_read, input
+                    );
+                }
+                chunks.push(StreamChunk {
+                    usage: (input > 0).then_some(TokenUsage {
+                        input_tokens: input,
+                        output_tokens: 0,
+                        total_tokens: input,
+                        cache_creation_tokens: cache_create,
+                        cache_read_tokens: cache_read,
+                    }),
+                    model: msg["model"].as_str().map(|s| s.to_string()),
+                    ..Self::chunk()
+                });
+            }
+            // message_delta already carried the real stop reason (tool_use,
+            // max_tokens, ...) — only fall back to "stop" without one
+            "message_stop" if self.stop_reason.is_none() => {
+                chunks.push(StreamChunk {
+                    finish_reason: Some("stop".into()),
+                    ..Self::chunk()
+                });
+            }
+            "error" => {
+                let error = &v["error"];
+                let status = match error["type"].as_str() {
+                    Some("overloaded_error") => 529,
+                    Some("rate_limit_error") => 429,
+                    _ => 500,
+                };
+                return Err(ProviderError::Api {
+                    status,
+                    message: format!(
+                        "Stream error: {}",
+                        error["message"].as_str().unwrap_or("unknown error")
+                    ),
+                });
+            }
+            _ => {}
+        }
+        Ok(chunks)
+    }
+
+    /// Tool calls whose input isn't complete JSON were cut off (fine-grained
+    /// streaming sends input unvalidated, e.g. up to `max_tokens`). They are
+    /// removed from the stream so the loop never runs them; complete calls
+    /// are kept.
+    fn finish(&self, chunks: &mut [StreamChunk]) {
+        let truncated: Vec<usize> = self
+            .tool_inputs
+            .iter()
+            .filter(|(_, input)| serde_json::from_str::<Value>(input).is_err())
+            .map(|(index, _)| *index)
+            .collect();
+        if truncated.is_empty() {
+            return;
+        }
+        warn!(
+            "[engine] Anthropic: dropping {} tool call(s) cut off by stop_reason={:?}",
+            truncated.len(),
+            self.stop_reason
+        );
+        for chunk in chunks.iter_mut() {
+            chunk.tool_calls.retain(|tc| !truncated.contains(&tc.index));
+        }
+        // Keep thinking that came with a dropped call on the first kept one
+        let orphaned: Vec<ThoughtPart> = chunks
+            .iter_mut()
+            .filter(|c| c.tool_calls.is_empty())
+            .flat_map(|c| std::mem::take(&mut c.thought_parts))
+            .collect();
+        if let Some(first) = chunks.iter_mut().find(|c| {
+            c.tool_calls
+                .first()
+                .is_some_and(|tc| tc.function_name.is_some())
+        }) {
+            first.thought_parts.splice(0..0, orphaned);
+        }
+    }
+
+    fn arguments(index: usize, arguments: &str) -> ToolCallDelta {
+        ToolCallDelta {
+            index,
+            id: None,
+            function_name: None,
+            arguments_delta: Some(arguments.to_string()),
+            thought_signature: None,
+        }
+    }
+}
+
 // ── AiProvider trait implementation ───────────────────────────────────────────
 
 #[async_trait]
@@ -700,3 +845,145 @@ impl AiProvider for AnthropicProvider {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(stream: &mut AnthropicStream, events: &[Value]) -> Vec<StreamChunk> {
+        let mut chunks = Vec::new();
+        for e in events {
+            chunks.extend(stream.push(&e.to_string()).unwrap());
+        }
+        stream.finish(&mut chunks);
+        chunks
+    }
+
+    fn tool_start(index: usize, id: &str, name: &str) -> Value {
+        json!({"type": "content_block_start", "index": index,
+            "content_block": {"type": "tool_use", "id": id, "name": name, "input": {}}})
+    }
+
+    fn tool_input(index: usize, partial: &str) -> Value {
+        json!({"type": "content_block_delta", "index": index,
+            "delta": {"type": "input_json_delta", "partial_json": partial}})
+    }
+
+    fn stop(index: usize) -> Value {
+        json!({"type": "content_block_stop", "index": index})
+    }
+
+    fn finish_reasons(chunks: &[StreamChunk]) -> Vec<&str> {
+        chunks
+            .iter()
+            .filter_map(|c| c.finish_reason.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn parallel_tool_calls_keep_separate_indices() {
+        let mut stream = AnthropicStream::default();
+        let chunks = feed(
+            &mut stream,
+            &[
+                tool_start(1, "toolu_a", "read_file"),
+                tool_input(1, "{\"path\":"),
+                tool_start(2, "toolu_b", "list_dir"),
+                tool_input(1, "\"a.rs\"}"),
+                stop(1),
+                stop(2),
+                json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+                    "usage": {"output_tokens": 12}}),
+                json!({"type": "message_stop"}),
+            ],
+        );
+        let calls: Vec<&ToolCallDelta> = chunks.iter().flat_map(|c| &c.tool_calls).collect();
+        let args = |index| {
+            calls
+                .iter()
+                .filter(|tc| tc.index == index)
+                .filter_map(|tc| tc.arguments_delta.as_deref())
+                .collect::<String>()
+        };
+        assert_eq!(args(1), r#"{"path":"a.rs"}"#);
+        // No input streamed for a parameterless tool
+        assert_eq!(args(2), "{}");
+        // message_stop doesn't overwrite the real stop reason
+        assert_eq!(finish_reasons(&chunks), ["tool_use"]);
+    }
+
+    #[test]
+    fn calls_cut_off_at_max_tokens_are_dropped() {
+        let mut stream = AnthropicStream::default();
+        let chunks = feed(
+            &mut stream,
+            &[
+                tool_start(0, "toolu_a", "read_file"),
+                tool_input(0, "{\"path\":\"a.rs\"}"),
+                stop(0),
+                tool_start(1, "toolu_b", "write_file"),
+                tool_input(1, "{\"path\":\"b.rs\",\"content\":\"fn m"),
+                stop(1),
+                json!({"type": "message_delta", "delta": {"stop_reason": "max_tokens"}}),
+                json!({"type": "message_stop"}),
+            ],
+        );
+        assert!(chunks
+            .iter()
+            .flat_map(|c| &c.tool_calls)
+            .all(|tc| tc.index == 0));
+        assert_eq!(finish_reasons(&chunks), ["max_tokens"]);
+    }
+
+    #[test]
+    fn thinking_is_replayed_within_the_tool_loop() {
+        let mut stream = AnthropicStream::default();
+        let chunks = feed(
+            &mut stream,
+            &[
+                json!({"type": "content_block_start", "index": 0,
+                    "content_block": {"type": "thinking", "thinking": ""}}),
+                json!({"type": "content_block_delta", "index": 0,
+                    "delta": {"type": "thinking_delta", "thinking": "Check the file."}}),
+                json!({"type": "content_block_delta", "index": 0,
+                    "delta": {"type": "signature_delta", "signature": "sig"}}),
+                stop(0),
+                tool_start(1, "toolu_a", "read_file"),
+                stop(1),
+            ],
+        );
+        let thoughts = &chunks.iter().find(|c| !c.tool_calls.is_empty()).unwrap();
+        assert_eq!(thoughts.tool_calls[0].index, 1);
+        assert_eq!(thoughts.thought_parts[0].thought_signature, "sig");
+
+        let call = ToolCall {
+            id: "toolu_a".into(),
+            call_type: "function".into(),
+            function: FunctionCall {
+                name: "read_file".into(),
+                arguments: "{}".into(),
+            },
+            thought_signature: None,
+            thought_parts: thoughts.thought_parts.clone(),
+        };
+        let message = |role, tool_calls| Message {
+            role,
+            content: MessageContent::Text(String::new()),
+            tool_calls,
+            tool_call_id: None,
+            name: None,
+        };
+        let messages = vec![
+            message(Role::User, None),
+            message(Role::Assistant, Some(vec![call.clone()])),
+            message(Role::User, None),
+            message(Role::Assistant, Some(vec![call])),
+        ];
+        let (_, formatted) = AnthropicProvider::format_messages(&messages);
+        // Earlier turns are sent without their thinking
+        assert_eq!(formatted[1]["content"][0]["type"], "tool_use");
+        assert_eq!(formatted[3]["content"][0]["type"], "thinking");
+        assert_eq!(formatted[3]["content"][0]["signature"], "sig");
+        assert_eq!(formatted[3]["content"][1]["type"], "tool_use");
+    }
+}
@@ -1,8 +1,10 @@
 // Paw Agent Engine — Google Gemini Provider
 // Implements the AiProvider golden trait.
-// Preserves the two-pass thought-part parsing for Gemini thinking models.
+// Streamed responses go through `GeminiStream`, which keeps function calls,
+// thought signatures and code-execution parts together across SSE events.
 
 use crate::atoms::traits::{AiProvider, ProviderError};
+use crate::engine::cancel;
 use crate::engine::http::{
     pinned_client, sign_and_log_request, update_last_audit_status, CircuitBreaker,
 };
@@ -21,6 +23,7 @@ use zeroize::Zeroizing;
 
 // Import constrained decoding for function_calling_config
 use crate::engine::constrained;
+use crate::engine::grounding;
 
 /// Circuit breaker shared across all Google/Gemini requests.
 static GOOGLE_CIRCUIT: LazyLock<CircuitBreaker> = LazyLock::new(|| CircuitBreaker::new(5, 60));
@@ -96,12 +99,22 @@ impl GoogleProvider {
                 if let Some(tool_calls) = &msg.tool_calls {
                     let mut parts: Vec<Value> = vec![];
                     let text = msg.content.as_text();
-                    if !text.is_empty() {
-                        parts.push(json!({"text": text}));
+                    // A signature that arrived on a text part goes back on the text
+                    let text_signature = tool_calls
+                        .iter()
+                        .flat_map(|tc| &tc.thought_parts)
+                        .find(|tp| !tp.thought)
+                        .map(|tp| tp.thought_signature.as_str());
+                    if !text.is_empty() || text_signature.is_some() {
+                        let mut text_part = json!({"text": text});
+                        if let Some(sig) = text_signature {
+                            text_part["thoughtSignature"] = json!(sig);
+                        }
+                        parts.push(text_part);
                     }
                     // Echo back thought parts (from thinking models) before functionCall parts
                     for tc in tool_calls {
-                        for tp in &tc.thought_parts {
+                        for tp in tc.thought_parts.iter().filter(|tp| tp.thought) {
                             let mut thought_part = json!({
                                 "thought": true,
                                 "text": tp.text,
@@ -300,6 +313,7 @@ impl GoogleProvider {
     fn format_tools(tools: &[ToolDefinition]) -> Value {
         let function_declarations: Vec<Value> = tools
             .iter()
+            .filter(|t| t.tool_type != grounding::BUILTIN_TOOL_TYPE)
             .map(|t| {
                 let sanitized = Self::sanitize_schema(&t.function.parameters);
                 // If sanitization reduced parameters to an empty object (e.g. no-param
@@ -321,9 +335,17 @@ impl GoogleProvider {
             })
             .collect();
 
-        json!([{
-            "functionDeclarations": function_declarations
-        }])
+        let mut entries = Vec::new();
+        if !function_declarations.is_empty() {
+            entries.push(json!({ "functionDeclarations": function_declarations }));
+        }
+        if tools.iter().any(|t| {
+            t.tool_type == grounding::BUILTIN_TOOL_TYPE
+                && t.function.name == grounding::GOOGLE_SEARCH_TOOL
+        }) {
+            entries.push(json!({ "googleSearch": {} }));
+        }
+        json!(entries)
     }
 
     /// Inner implementation with full SSE + retry logic + error classification.
@@ -366,8 +388,14 @@ impl GoogleProvider {
             body["tools"] = Self::format_tools(tools);
 
             // Apply function_calling_config for structured tool calling
-            let constraint_config = constrained::detect_constraints(ProviderKind::Google, model);
-            constrained::apply_google_tool_config(&mut body, &constraint_config);
+            if tools
+                .iter()
+                .any(|t| t.tool_type != grounding::BUILTIN_TOOL_TYPE)
+            {
+                let constraint_config =
+                    constrained::detect_constraints(ProviderKind::Google, model);
+                constrained::apply_google_tool_config(&mut body, &constraint_config);
+            }
         }
         if let Some(temp) = temperature {
             body["generationConfig"] = json!({"temperature": temp, "maxOutputTokens": 8192});
@@ -400,6 +428,7 @@ impl GoogleProvider {
                     body["generationConfig"]["responseModalities"] = json!(["TEXT"]);
                     body["generationConfig"]["thinkingConfig"] = json!({
                         "thinkingBudget": budget,
+                        "includeThoughts": true,
                     });
                     info!(
                         "[engine] Google: thinking enabled (budget={}) for model={}",
@@ -451,6 +480,7 @@ impl GoogleProvider {
                 body["generationConfig"]["responseModalities"] = json!(["TEXT"]);
                 body["generationConfig"]["thinkingConfig"] = json!({
                     "thinkingBudget": budget,
+                    "includeThoughts": true,
                 });
                 info!(
                     "[engine] Google: thinking config set (budget={}) for model={}",
@@ -483,10 +513,11 @@ impl GoogleProvider {
             let body_bytes = serde_json::to_vec(&body).unwrap_or_default();
             sign_and_log_request("google", model, &body_bytes);
 
-            let response = match self
+            let req = self
                 .client
                 .post(&url)
-                .header("Content-Type", "application/json")
+                .header("Content-Type", "application/json");
+            let response = match super::middleware::apply_request_headers(req)
                 .json(&body)
                 .send()
                 .await
@@ -546,10 +577,11 @@ impl GoogleProvider {
             }
 
             let mut chunks = Vec::new();
+            let mut stream = GeminiStream::default();
             let mut byte_stream = response.bytes_stream();
             let mut buffer = String::new();
 
-            while let Some(result) = byte_stream.next().await {
+            while let Some(Some(result)) = cancel::or_cancelled(byte_stream.next()).await {
                 let bytes = result
                     .map_err(|e| ProviderError::Transport(format!("Stream read error: {}", e)))?;
                 buffer.push_str(&String::from_utf8_lossy(&bytes));
@@ -565,218 +597,17 @@ impl GoogleProvider {
                         } else {
                             log::debug!(
                                 "[engine] Google SSE: {}... ({}b)",
-                                &data[..500],
+                                safe_truncate(data, 500),
                                 data.len()
                             );
                         }
                         if let Ok(v) = serde_json::from_str::<Value>(data) {
-                            // Extract actual model version from Google's response
-                            let api_model = v["modelVersion"].as_str().map(|s| s.to_string());
-
-                            // Parse Google's streaming format
-                            let mut fc_index_counter: usize = 0; // unique index per function call
-                            if let Some(candidates) = v["candidates"].as_array() {
-                                for candidate in candidates {
-                                    let content = &candidate["content"];
-                                    let finish_reason =
-                                        candidate["finishReason"].as_str().map(|s| s.to_string());
-
-                                    // Detect blocked/empty responses (e.g. SAFETY, RECITATION, OTHER)
-                                    // Also check for empty parts array [] — not just null
-                                    let parts_empty = content.is_null()
-                                        || content["parts"].is_null()
-                                        || content["parts"]
-                                            .as_array()
-                                            .map(|a| a.is_empty())
-                                            .unwrap_or(false);
-                                    if parts_empty {
-                                        if let Some(ref reason) = finish_reason {
-                                            // Log ALL empty-content responses, including STOP
-                                            let safety_info = candidate
-                                                .get("safetyRatings")
-                                                .map(|r| r.to_string())
-                                                .unwrap_or_else(|| "none".to_string());
-                                            warn!(
-                                                "[engine] Google: empty content chunk — finishReason={} safety={}",
-                                                reason,
-                                                safe_truncate(&safety_info, 500)
-                                            );
-                                            if reason != "STOP" {
-                                                // Emit a visible error chunk so the agent loop can surface it
-                                                let msg = match reason.as_str() {
-                                                    "SAFETY" => "My response was blocked by Google's safety filter. Try rephrasing your request.".to_string(),
-                                                    "RECITATION" => "My response was blocked by a recitation filter. Try rephrasing.".to_string(),
-                                                    "MAX_TOKENS" => "I ran out of output tokens. Try shortening the conversation or compacting the session.".to_string(),
-                                                    "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" =>
-                                                        format!("Response blocked ({reason}). Try rephrasing your request."),
-                                                    "MALFORMED_FUNCTION_CALL" => {
-                                                        warn!("[engine] Google: MALFORMED_FUNCTION_CALL — model produced invalid tool call JSON");
-                                                        "[MALFORMED_TOOL_CALL] The model tried to call a tool but produced invalid JSON. \
-                                                        Simplify the call — pass body as a JSON object, not an escaped string.".to_string()
-                                                    }
-                                                    other => format!(
-                                                        "The model returned an empty response (reason: {other}). Please retry or rephrase."
-                                                    ),
-                                                };
-                                                chunks.push(StreamChunk {
-                                                    delta_text: Some(msg),
-                                                    tool_calls: vec![],
-                                                    finish_reason: finish_reason.clone(),
-                                                    usage: None,
-                                                    model: api_model.clone(),
-                                                    thought_parts: vec![],
-                                                    thinking_text: None,
-                                                });
-                                            }
-                                        }
-                                        continue;
-                                    }
-
-                                    if let Some(parts) = content["parts"].as_array() {
-                                        // First pass: collect thought parts (only those with "thought": true).
-                                        // Note: thoughtSignature alone does NOT indicate thinking — it's a
-                                        // round-trip signature. We still collect it for API replay.
-                                        let mut collected_thoughts: Vec<ThoughtPart> = Vec::new();
-                                        for part in parts {
-                                            if part
-                                                .get("thought")
-                                                .and_then(|v| v.as_bool())
-                                                .unwrap_or(false)
-                                            {
-                                                if let Some(text) = part["text"].as_str() {
-                                                    let sig = part
-                                                        .get("thoughtSignature")
-                                                        .or_else(|| part.get("thought_signature"))
-                                                        .and_then(|v| v.as_str());
-                                                    // Emit thinking text to the frontend
-                                                    chunks.push(StreamChunk {
-                                                        delta_text: None,
-                                                        tool_calls: vec![],
-                                                        finish_reason: None,
-                                                        usage: None,
-                                                        model: api_model.clone(),
-                                                        thought_parts: vec![],
-                                                        thinking_text: Some(text.to_string()),
-                                                    });
-                                                    info!("[engine] Google: thought part detected (len={})", text.len());
-                                                    if let Some(s) = sig {
-                                                        collected_thoughts.push(ThoughtPart {
-                                                            text: text.to_string(),
-                                                            thought_signature: s.to_string(),
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
-
-                                        // Second pass: process text and functionCall parts
-                                        for part in parts {
-                                            // Skip thought parts (already collected above)
-                                            if part
-                                                .get("thought")
-                                                .and_then(|v| v.as_bool())
-                                                .unwrap_or(false)
-                                            {
-                                                continue;
-                                            }
-                                            // Collect thoughtSignature from text parts for round-tripping
-                                            if let Some(text) = part["text"].as_str() {
-                                                let sig = part
-                                                    .get("thoughtSignature")
-                                                    .or_else(|| part.get("thought_signature"))
-                                                    .and_then(|v| v.as_str());
-                                                if let Some(s) = sig {
-                                                    collected_thoughts.push(ThoughtPart {
-                                                        text: text.to_string(),
-                                                        thought_signature: s.to_string(),
-                                                    });
-                                                }
-                                                chunks.push(StreamChunk {
-                                                    delta_text: Some(text.to_string()),
-                                                    tool_calls: vec![],
-                                                    finish_reason: finish_reason.clone(),
-                                                    usage: None,
-                                                    model: api_model.clone(),
-                                                    thought_parts: vec![],
-                                                    thinking_text: None,
-                                                });
-                                            }
-                                            if let Some(fc) = part.get("functionCall") {
-                                                let name =
-                                                    fc["name"].as_str().unwrap_or("").to_string();
-                                                let args = fc["args"].clone();
-                                                // thought_signature can be at the part level OR inside functionCall
-                                                let thought_sig = part
-                                                    .get("thoughtSignature")
-                                                    .or_else(|| part.get("thought_signature"))
-                                                    .or_else(|| fc.get("thoughtSignature"))
-                                                    .or_else(|| fc.get("thought_signature"))
-                                                    .and_then(|v| v.as_str())
-                                                    .map(|s| s.to_string());
-                                                if thought_sig.is_some() {
-                                                    info!("[engine] Google: captured thoughtSignature for fn={}", name);
-                                                } else {
-                                                    warn!("[engine] Google: NO thoughtSignature found for fn={} (part keys: {:?})", name, part.as_object().map(|o| o.keys().collect::<Vec<_>>()));
-                                                }
-                                                let fc_idx = fc_index_counter;
-                                                fc_index_counter += 1;
-                                                chunks.push(StreamChunk {
-                                                    delta_text: None,
-                                                    tool_calls: vec![ToolCallDelta {
-                                                        index: fc_idx,
-                                                        id: Some(format!(
-                                                            "call_{}",
-                                                            uuid::Uuid::new_v4()
-                                                        )),
-                                                        function_name: Some(name),
-                                                        arguments_delta: Some(
-                                                            serde_json::to_string(&args)
-                                                                .unwrap_or_default(),
-                                                        ),
-                                                        thought_signature: thought_sig,
-                                                    }],
-                                                    finish_reason: finish_reason.clone(),
-                                                    usage: None,
-                                                    model: api_model.clone(),
-                                                    // Attach thought parts to the first functionCall chunk
-                                                    thought_parts: collected_thoughts.clone(),
-                                                    thinking_text: None,
-                                                });
-                                                // Only attach thoughts to first function call chunk
-                                                collected_thoughts.clear();
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Gemini reports usage in usageMetadata
-                            if let Some(um) = v.get("usageMetadata") {
-                                let input = um["promptTokenCount"].as_u64().unwrap_or(0);
-                                let output = um["candidatesTokenCount"].as_u64().unwrap_or(0);
-                                if input > 0 || output > 0 {
-                                    chunks.push(StreamChunk {
-                                        delta_text: None,
-                                        tool_calls: vec![],
-                                        finish_reason: None,
-                                        usage: Some(TokenUsage {
-                                            input_tokens: input,
-                                            output_tokens: output,
-                                            total_tokens: um["totalTokenCount"]
-                                                .as_u64()
-                                                .unwrap_or(input + output),
-                                            ..Default::default()
-                                        }),
-                                        model: api_model.clone(),
-                                        thought_parts: vec![],
-                                        thinking_text: None,
-                                    });
-                                }
-                            }
+                            chunks.extend(stream.push_event(&v));
                         }
                     }
                 }
             }
+            chunks.extend(stream.finish());
 
             GOOGLE_CIRCUIT.record_success();
             return Ok(chunks);
@@ -797,6 +628,415 @@ impl GoogleProvider {
     }
 }
 
+// ── Stream parsing ────────────────────────────────────────────────────────────
+
+/// A function call whose arguments are still arriving as `partialArgs`.
+struct OpenCall {
+    index: usize,
+    args: Value,
+}
+
+/// Turns the SSE events of one `streamGenerateContent` response into stream
+/// chunks. State carries across events: function-call indices, arguments
+/// streamed over several events, signed thought parts waiting for the
+/// function call they must be echoed back with, code waiting for its
+/// execution result, and search grounding (reported once, at the end).
+#[derive(Default)]
+struct GeminiStream {
+    model: Option<String>,
+    next_call_index: usize,
+    open_call: Option<OpenCall>,
+    pending_thoughts: Vec<ThoughtPart>,
+    pending_code: Option<(String, String)>,
+    queries: Vec<String>,
+    sources: Vec<Citation>,
+}
+
+impl GeminiStream {
+    fn chunk(&self) -> StreamChunk {
+        StreamChunk {
+            delta_text: None,
+            tool_calls: vec![],
+            finish_reason: None,
+            usage: None,
+            model: self.model.clone(),
+            thought_parts: vec![],
+            thinking_text: None,
+            meta: vec![],
+        }
+    }
+
+    fn push_event(&mut self, v: &Value) -> Vec<StreamChunk> {
+        let mut chunks = Vec::new();
+        // Extract actual model version from Google's response
+        if let Some(model) = v["modelVersion"].as_str() {
+            self.model = Some(model.to_string());
+        }
+
+        for candidate in v["candidates"].as_array().into_iter().flatten() {
+            let content = &candidate["content"];
+            let finish_reason = candidate["finishReason"].as_str().map(|s| s.to_string());
+            self.collect_grounding(&candidate["groundingMetadata"]);
+
+            // Detect blocked/empty responses (e.g. SAFETY, RECITATION, OTHER)
+            // Also check for empty parts array [] — not just null
+            let parts = content["parts"].as_array().filter(|p| !p.is_empty());
+            let Some(parts) = parts else {
+                if let Some(reason) = &finish_reason {
+                    // Log ALL empty-content responses, including STOP
+                    let safety_info = candidate
+                        .get("safetyRatings")
+                        .map(|r| r.to_string())
+                        .unwrap_or_else(|| "none".to_string());
+                    warn!(
+                        "[engine] Google: empty content chunk — finishReason={} safety={}",
+                        reason,
+                        safe_truncate(&safety_info, 500)
+                    );
+                    if reason != "STOP" {
+                        // Emit a visible error chunk so the agent loop can surface it
+                        chunks.push(StreamChunk {
+                            delta_text: Some(blocked_message(reason)),
+                            finish_reason: finish_reason.clone(),
+                            ..self.chunk()
+                        });
+                    }
+                }
+                continue;
+            };
+
+            for part in parts {
+                let signature = part
+                    .get("thoughtSignature")
+                    .or_else(|| part.get("thought_signature"))
+                    .and_then(|v| v.as_str());
+
+                if part["thought"].as_bool().unwrap_or(false) {
+                    let text = part["text"].as_str().unwrap_or("");
+                    if !text.is_empty() {
+                        // Emit thinking text to the frontend
+                        chunks.push(StreamChunk {
+                            thinking_text: Some(text.to_string()),
+                            ..self.chunk()
+                        });
+                    }
+                    // Signed thoughts are echoed back with the next function call
+                    if let Some(sig) = signature {
+                        self.pending_thoughts.push(ThoughtPart {
+                            text: text.to_string(),
+                            thought_signature: sig.to_string(),
+                            thought: true,
+                            item_id: None,
+                        });
+                    }
+                } else if let Some(fc) = part.get("functionCall") {
+                    chunks.extend(self.function_call(fc, signature, &finish_reason));
+                } else if let Some(text) = part["text"].as_str() {
+                    // A signature on a plain text part goes back on the text
+                    if let Some(sig) = signature {
+                        self.pending_thoughts.push(ThoughtPart {
+                            text: text.to_string(),
+                            thought_signature: sig.to_string(),
+                            thought: false,
+                            item_id: None,
+                        });
+                    }
+                    chunks.push(StreamChunk {
+                        delta_text: Some(text.to_string()),
+                        finish_reason: finish_reason.clone(),
+                        ..self.chunk()
+                    });
+                } else if let Some(code) = part.get("executableCode") {
+                    chunks.extend(self.flush_code());
+                    self.pending_code = Some((
+                        code["language"].as_str().unwrap_or("").to_lowercase(),
+                        code["code"].as_str().unwrap_or("").to_string(),
+                    ));
+                } else if let Some(result) = part.get("codeExecutionResult") {
+                    let (language, code) = self.pending_code.take().unwrap_or_default();
+                    chunks.push(StreamChunk {
+                        meta: vec![ProviderMeta::CodeExecution {
+                            language,
+                            code,
+                            outcome: result["outcome"].as_str().map(str::to_string),
+                            output: result["output"].as_str().map(str::to_string),
+                        }],
+                        ..self.chunk()
+                    });
+                }
+            }
+        }
+
+        // Gemini reports usage in usageMetadata
+        if let Some(um) = v.get("usageMetadata") {
+            let input = um["promptTokenCount"].as_u64().unwrap_or(0);
+            let output = um["candidatesTokenCount"].as_u64().unwrap_or(0)
+                + um["thoughtsTokenCount"].as_u64().unwrap_or(0);
+            if input > 0 || output > 0 {
+                chunks.push(StreamChunk {
+                    usage: Some(TokenUsage {
+                        input_tokens: input,
+                        output_tokens: output,
+                        total_tokens: um["totalTokenCount"].as_u64().unwrap_or(input + output),
+                        ..Default::default()
+                    }),
+                    ..self.chunk()
+                });
+            }
+        }
+        chunks
+    }
+
+    /// A `functionCall` part: either a complete call, the start of one whose
+    /// arguments stream in (`willContinue`), or a continuation of that one.
+    fn function_call(
+        &mut self,
+        fc: &Value,
+        signature: Option<&str>,
+        finish_reason: &Option<String>,
+    ) -> Vec<StreamChunk> {
+        let mut chunks = Vec::new();
+        let name = fc["name"].as_str().unwrap_or("");
+        let will_continue = fc["willContinue"].as_bool().unwrap_or(false);
+        let partial_args = fc["partialArgs"].as_array();
+
+        if name.is_empty() {
+            if let Some(open) = self.open_call.as_mut() {
+                for arg in partial_args.into_iter().flatten() {
+                    apply_partial_arg(&mut open.args, arg);
+                }
+                if !will_continue {
+                    chunks.extend(self.close_call(finish_reason));
+                }
+                return chunks;
+            }
+        }
+        chunks.extend(self.close_call(&None));
+
+        let index = self.next_call_index;
+        self.next_call_index += 1;
+        // thought_signature can be at the part level OR inside functionCall
+        let thought_sig = signature
+            .or_else(|| fc.get("thoughtSignature").and_then(|v| v.as_str()))
+            .or_else(|| fc.get("thought_signature").and_then(|v| v.as_str()))
+            .map(|s| s.to_string());
+        if thought_sig.is_some() {
+            info!("[engine] Google: captured thoughtSignature for fn={}", name);
+        } else if index == 0 {
+            // Only the first call of a turn carries one; parallel calls don't
+            warn!("[engine] Google: NO thoughtSignature found for fn={}", name);
+        }
+
+        let mut args = match &fc["args"] {
+            Value::Object(_) => fc["args"].clone(),
+            _ => json!({}),
+        };
+        for arg in partial_args.into_iter().flatten() {
+            apply_partial_arg(&mut args, arg);
+        }
+        let streaming = will_continue;
+        chunks.push(StreamChunk {
+            tool_calls: vec![ToolCallDelta {
+                index,
+                id: Some(format!("call_{}", uuid::Uuid::new_v4())),
+                function_name: Some(name.to_string()),
+                arguments_delta: if streaming {
+                    None
+                } else {
+                    Some(serde_json::to_string(&args).unwrap_or_default())
+                },
+                thought_signature: thought_sig,
+            }],
+            finish_reason: if streaming {
+                None
+            } else {
+                finish_reason.clone()
+            },
+            // Signed thoughts so far belong to the first call that follows them
+            thought_parts: std::mem::take(&mut self.pending_thoughts),
+            ..self.chunk()
+        });
+        if streaming {
+            self.open_call = Some(OpenCall { index, args });
+        }
+        chunks
+    }
+
+    /// Emit the arguments of a streamed function call once complete.
+    fn close_call(&mut self, finish_reason: &Option<String>) -> Option<StreamChunk> {
+        let open = self.open_call.take()?;
+        Some(StreamChunk {
+            tool_calls: vec![ToolCallDelta {
+                index: open.index,
+                id: None,
+                function_name: None,
+                arguments_delta: Some(serde_json::to_string(&open.args).unwrap_or_default()),
+                thought_signature: None,
+            }],
+            finish_reason: finish_reason.clone(),
+            ..self.chunk()
+        })
+    }
+
+    /// Code whose execution result never arrived.
+    fn flush_code(&mut self) -> Option<StreamChunk> {
+        let (language, code) = self.pending_code.take()?;
+        Some(StreamChunk {
+            meta: vec![ProviderMeta::CodeExecution {
+                language,
+                code,
+                outcome: None,
+                output: None,
+            }],
+            ..self.chunk()
+        })
+    }
+
+    fn collect_grounding(&mut self, grounding: &Value) {
+        for query in grounding["webSearchQueries"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            if let Some(q) = query
+                .as_str()
+                .filter(|q| !self.queries.iter().any(|e| e == q))
+            {
+                self.queries.push(q.to_string());
+            }
+        }
+        let Some(web_chunks) = grounding["groundingChunks"].as_array() else {
+            return;
+        };
+        let supports = grounding["groundingSupports"].as_array();
+        for (i, web) in web_chunks.iter().filter_map(|c| c.get("web")).enumerate() {
+            let Some(url) = web["uri"].as_str() else {
+                continue;
+            };
+            if self.sources.iter().any(|s| s.url == url) {
+                continue;
+            }
+            // The first answer segment this source supports
+            let quote = supports
+                .into_iter()
+                .flatten()
+                .find(|s| {
+                    s["groundingChunkIndices"]
+                        .as_array()
+                        .is_some_and(|idx| idx.iter().any(|n| n.as_u64() == Some(i as u64)))
+                })
+                .and_then(|s| s["segment"]["text"].as_str())
+                .map(str::to_string);
+            self.sources.push(Citation {
+                index: self.sources.len() + 1,
+                url: url.to_string(),
+                title: web["title"].as_str().unwrap_or("").to_string(),
+                quote,
+                tool: "google_search".into(),
+            });
+        }
+    }
+
+    /// Close anything still open when the stream ends.
+    fn finish(&mut self) -> Vec<StreamChunk> {
+        let mut chunks: Vec<StreamChunk> = Vec::new();
+        chunks.extend(self.close_call(&None));
+        chunks.extend(self.flush_code());
+        if !self.queries.is_empty() || !self.sources.is_empty() {
+            chunks.push(StreamChunk {
+                meta: vec![ProviderMeta::Grounding {
+                    queries: std::mem::take(&mut self.queries),
+                    sources: std::mem::take(&mut self.sources),
+                }],
+                ..self.chunk()
+            });
+        }
+        chunks
+    }
+}
+
+/// User-facing text for a response Gemini ended without content.
+fn blocked_message(reason: &str) -> String {
+    match reason {
+        "SAFETY" => {
+            "My response was blocked by Google's safety filter. Try rephrasing your request."
+                .to_string()
+        }
+        "RECITATION" => {
+            "My response was blocked by a recitation filter. Try rephrasing.".to_string()
+        }
+        "MAX_TOKENS" => {
+            "I ran out of output tokens. Try shortening the conversation or compacting the session."
+                .to_string()
+        }
+        "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => {
+            format!("Response blocked ({reason}). Try rephrasing your request.")
+        }
+        "MALFORMED_FUNCTION_CALL" => {
+            warn!(
+                "[engine] Google: MALFORMED_FUNCTION_CALL — model produced invalid tool call JSON"
+            );
+            "[MALFORMED_TOOL_CALL] The model tried to call a tool but produced invalid JSON. \
+            Simplify the call — pass body as a JSON object, not an escaped string."
+                .to_string()
+        }
+        other => format!(
+            "The model returned an empty response (reason: {other}). Please retry or rephrase."
+        ),
+    }
+}
+
+/// Apply one streamed argument (`{"jsonPath": "$.a.b[0]", "stringValue":
+/// "..."}`) to the call's arguments. String values arrive in pieces and
+/// are appended.
+fn apply_partial_arg(args: &mut Value, arg: &Value) {
+    let Some(slot) = arg["jsonPath"]
+        .as_str()
+        .and_then(|path| partial_arg_slot(args, path))
+    else {
+        return;
+    };
+    if let Some(piece) = arg["stringValue"].as_str() {
+        match slot {
+            Value::String(existing) => existing.push_str(piece),
+            _ => *slot = json!(piece),
+        }
+    } else if let Some(v) = arg.get("numberValue").or_else(|| arg.get("boolValue")) {
+        *slot = v.clone();
+    } else if arg.get("nullValue").is_some() {
+        *slot = Value::Null;
+    }
+}
+
+/// The value at a `$.key[0].key` path, created along the way.
+fn partial_arg_slot<'a>(root: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    let mut slot = root;
+    for segment in path.strip_prefix('$')?.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !key.is_empty() {
+            if !slot.is_object() {
+                *slot = json!({});
+            }
+            slot = slot
+                .as_object_mut()?
+                .entry(key.to_string())
+                .or_insert(Value::Null);
+        }
+        for index in indices.split('[').filter_map(|s| s.strip_suffix(']')) {
+            let i: usize = index.parse().ok()?;
+            if !slot.is_array() {
+                *slot = json!([]);
+            }
+            let items = slot.as_array_mut()?;
+            if items.len() <= i {
+                items.resize(i + 1, Value::Null);
+            }
+            slot = &mut items[i];
+        }
+    }
+    Some(slot)
+}
+
 // ── AiProvider trait implementation ───────────────────────────────────────────
 
 #[async_trait]
@@ -821,3 +1061,175 @@ impl AiProvider for GoogleProvider {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(events: &[Value]) -> Vec<StreamChunk> {
+        let mut stream = GeminiStream::default();
+        let mut chunks: Vec<StreamChunk> =
+            events.iter().flat_map(|e| stream.push_event(e)).collect();
+        chunks.extend(stream.finish());
+        chunks
+    }
+
+    fn calls(chunks: &[StreamChunk]) -> Vec<(usize, String, String)> {
+        let mut calls: Vec<(usize, String, String)> = Vec::new();
+        for d in chunks.iter().flat_map(|c| &c.tool_calls) {
+            match calls.iter_mut().find(|(i, _, _)| *i == d.index) {
+                Some(call) => call.2.push_str(d.arguments_delta.as_deref().unwrap_or("")),
+                None => calls.push((
+                    d.index,
+                    d.function_name.clone().unwrap_or_default(),
+                    d.arguments_delta.clone().unwrap_or_default(),
+                )),
+            }
+        }
+        calls
+    }
+
+    #[test]
+    fn function_calls_across_events_and_partial_args() {
+        let chunks = parse(&[
+            json!({"candidates": [{"content": {"parts": [
+                {"thought": true, "text": "Need the weather", "thoughtSignature": "sig-t"},
+            ]}}]}),
+            json!({"candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "weather", "args": {"city": "Oslo"}}, "thoughtSignature": "sig-1"},
+            ]}}]}),
+            json!({"candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "write_file", "partialArgs": [
+                    {"jsonPath": "$.path", "stringValue": "notes.md"},
+                    {"jsonPath": "$.content", "stringValue": "Hel", "willContinue": true},
+                ], "willContinue": true}},
+            ]}}]}),
+            json!({"candidates": [{"content": {"parts": [
+                {"functionCall": {"partialArgs": [
+                    {"jsonPath": "$.content", "stringValue": "lo"},
+                    {"jsonPath": "$.tags[1]", "stringValue": "b"},
+                ]}},
+            ]}, "finishReason": "STOP"}]}),
+        ]);
+        assert_eq!(
+            calls(&chunks),
+            vec![
+                (0, "weather".into(), r#"{"city":"Oslo"}"#.into()),
+                (
+                    1,
+                    "write_file".into(),
+                    r#"{"content":"Hello","path":"notes.md","tags":[null,"b"]}"#.into()
+                ),
+            ]
+        );
+        let first = chunks.iter().find(|c| !c.tool_calls.is_empty()).unwrap();
+        assert_eq!(
+            first.tool_calls[0].thought_signature.as_deref(),
+            Some("sig-1")
+        );
+        assert_eq!(first.thought_parts.len(), 1);
+        assert!(first.thought_parts[0].thought);
+        assert_eq!(
+            chunks
+                .iter()
+                .filter_map(|c| c.thinking_text.as_deref())
+                .collect::<Vec<_>>(),
+            vec!["Need the weather"]
+        );
+    }
+
+    #[test]
+    fn text_signature_goes_back_on_the_text() {
+        let chunks = parse(&[json!({"candidates": [{"content": {"parts": [
+            {"text": "Checking.", "thoughtSignature": "sig-x"},
+            {"functionCall": {"name": "weather", "args": {}}},
+        ]}}]})]);
+        let call = chunks.iter().find(|c| !c.tool_calls.is_empty()).unwrap();
+        assert!(!call.thought_parts[0].thought);
+
+        let (_, contents) = GoogleProvider::format_messages(&[
+            Message {
+                role: Role::User,
+                content: MessageContent::Text("Weather?".into()),
+                tool_calls: None,
+                tool_call_id: None,
+                name: None,
+            },
+            Message {
+                role: Role::Assistant,
+                content: MessageContent::Text("Checking.".into()),
+                tool_calls: Some(vec![ToolCall {
+                    id: "call_1".into(),
+                    call_type: "function".into(),
+                    function: FunctionCall {
+                        name: "weather".into(),
+                        arguments: "{}".into(),
+                    },
+                    thought_signature: None,
+                    thought_parts: call.thought_parts.clone(),
+                }]),
+                tool_call_id: None,
+                name: None,
+            },
+        ]);
+        let parts = contents[1]["parts"].as_array().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0],
+            json!({"text": "Checking.", "thoughtSignature": "sig-x"})
+        );
+        assert!(parts[1].get("functionCall").is_some());
+    }
+
+    #[test]
+    fn google_search_is_sent_as_a_builtin() {
+        let weather = ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "weather".into(),
+                description: "Weather".into(),
+                parameters: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+            },
+        };
+        let both = GoogleProvider::format_tools(&[weather, grounding::google_search_tool()]);
+        assert_eq!(both.as_array().unwrap().len(), 2);
+        assert_eq!(both[0]["functionDeclarations"][0]["name"], "weather");
+        assert_eq!(both[1], json!({"googleSearch": {}}));
+        assert_eq!(
+            GoogleProvider::format_tools(&[grounding::google_search_tool()]),
+            json!([{"googleSearch": {}}])
+        );
+    }
+
+    #[test]
+    fn surfaces_code_execution_and_grounding() {
+        let chunks = parse(&[json!({"candidates": [{
+            "content": {"parts": [
+                {"executableCode": {"language": "PYTHON", "code": "print(6*7)"}},
+                {"codeExecutionResult": {"outcome": "OUTCOME_OK", "output": "42\n"}},
+                {"text": "It's 42, per Example."},
+            ]},
+            "groundingMetadata": {
+                "webSearchQueries": ["six times seven"],
+                "groundingChunks": [{"web": {"uri": "https://example.com/a", "title": "Example"}}],
+                "groundingSupports": [{"segment": {"text": "It's 42"}, "groundingChunkIndices": [0]}],
+            },
+        }]})]);
+        let meta: Vec<&ProviderMeta> = chunks.iter().flat_map(|c| &c.meta).collect();
+        assert_eq!(
+            meta[0],
+            &ProviderMeta::CodeExecution {
+                language: "python".into(),
+                code: "print(6*7)".into(),
+                outcome: Some("OUTCOME_OK".into()),
+                output: Some("42\n".into()),
+            }
+        );
+        let ProviderMeta::Grounding { queries, sources } = meta[1] else {
+            panic!("expected grounding");
+        };
+        assert_eq!(queries, &vec!["six times seven".to_string()]);
+        assert_eq!(sources[0].url, "https://example.com/a");
+        assert_eq!(sources[0].quote.as_deref(), Some("It's 42"));
+    }
+}
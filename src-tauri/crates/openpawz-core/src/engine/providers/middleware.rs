@@ -0,0 +1,305 @@
+// Paw Agent Engine — Provider Middleware
+//
+// Hooks that observe or modify every AnyProvider request and observe every
+// response, without touching provider code. Two sources of hooks:
+//   • Config-driven: header injection (LiteLLM / Helicone / Portkey gateways)
+//     and request logging, from EngineConfig.provider_middleware.
+//   • Registered: any `ProviderMiddleware` impl passed to `register()`
+//     (custom telemetry, auditing).
+//
+// Extra request headers are carried to the concrete provider through a
+// task-local, so concurrent requests never see each other's headers.
+
+use crate::atoms::types::{ProviderKind, ProviderMiddlewareConfig, StreamChunk};
+use log::info;
+use parking_lot::RwLock;
+use std::sync::{Arc, LazyLock};
+
+// ── Hook types ─────────────────────────────────────────────────────────────
+
+/// What a hook sees (and may modify) before a request is sent.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub provider_id: String,
+    pub provider_kind: ProviderKind,
+    pub model: String,
+    pub message_count: usize,
+    pub tool_count: usize,
+    /// Extra HTTP headers added to the outbound request.
+    pub headers: Vec<(String, String)>,
+}
+
+/// What a hook sees after the provider returns.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseContext {
+    pub duration_ms: u64,
+    pub chunk_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// The model name reported by the API, if any.
+    pub response_model: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ResponseContext {
+    pub fn from_chunks(chunks: &[StreamChunk], duration_ms: u64) -> Self {
+        let mut ctx = ResponseContext {
+            duration_ms,
+            chunk_count: chunks.len(),
+            ..Default::default()
+        };
+        for c in chunks {
+            if let Some(u) = &c.usage {
+                ctx.input_tokens = ctx.input_tokens.max(u.input_tokens);
+                ctx.output_tokens = ctx.output_tokens.max(u.output_tokens);
+            }
+            if ctx.response_model.is_none() {
+                ctx.response_model = c.model.clone();
+            }
+        }
+        ctx
+    }
+}
+
+/// A request/response hook. Both methods default to no-ops.
+pub trait ProviderMiddleware: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn on_request(&self, _req: &mut RequestContext) {}
+
+    fn on_response(&self, _req: &RequestContext, _resp: &ResponseContext) {}
+}
+
+// ── Registry ───────────────────────────────────────────────────────────────
+
+static REGISTERED: LazyLock<RwLock<Vec<Arc<dyn ProviderMiddleware>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+static CONFIG: LazyLock<RwLock<ProviderMiddlewareConfig>> =
+    LazyLock::new(|| RwLock::new(ProviderMiddlewareConfig::default()));
+
+/// Register a hook for every subsequent provider request.
+/// A hook with the same name replaces the previous one.
+pub fn register(hook: Arc<dyn ProviderMiddleware>) {
+    let mut hooks = REGISTERED.write();
+    hooks.retain(|h| h.name() != hook.name());
+    hooks.push(hook);
+}
+
+/// Remove a registered hook by name. Returns true if one was removed.
+pub fn unregister(name: &str) -> bool {
+    let mut hooks = REGISTERED.write();
+    let before = hooks.len();
+    hooks.retain(|h| h.name() != name);
+    hooks.len() != before
+}
+
+/// Install the config-driven hooks (called on startup and config save).
+pub fn set_config(config: ProviderMiddlewareConfig) {
+    *CONFIG.write() = config;
+}
+
+/// Run every `on_request` hook: config-driven first, then registered.
+pub fn run_request_hooks(req: &mut RequestContext) {
+    let config = CONFIG.read().clone();
+    ConfigMiddleware(&config).on_request(req);
+    for hook in REGISTERED.read().iter() {
+        hook.on_request(req);
+    }
+}
+
+/// Run every `on_response` hook: config-driven first, then registered.
+pub fn run_response_hooks(req: &RequestContext, resp: &ResponseContext) {
+    let config = CONFIG.read().clone();
+    ConfigMiddleware(&config).on_response(req, resp);
+    for hook in REGISTERED.read().iter() {
+        hook.on_response(req, resp);
+    }
+}
+
+// ── Header propagation ─────────────────────────────────────────────────────
+
+tokio::task_local! {
+    static REQUEST_HEADERS: Vec<(String, String)>;
+}
+
+/// Run `fut` with `headers` visible to `apply_request_headers`.
+pub async fn with_request_headers<F: std::future::Future>(
+    headers: Vec<(String, String)>,
+    fut: F,
+) -> F::Output {
+    REQUEST_HEADERS.scope(headers, fut).await
+}
+
+/// Add the current request's middleware headers to an outbound request.
+/// Called by each concrete provider right before `.send()`.
+pub fn apply_request_headers(mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let headers = REQUEST_HEADERS.try_with(|h| h.clone()).unwrap_or_default();
+    for (name, value) in headers {
+        req = req.header(name, value);
+    }
+    req
+}
+
+// ── Config-driven hooks ────────────────────────────────────────────────────
+
+/// Applies `ProviderMiddlewareConfig`: header injection and request logging.
+struct ConfigMiddleware<'a>(&'a ProviderMiddlewareConfig);
+
+impl ProviderMiddleware for ConfigMiddleware<'_> {
+    fn name(&self) -> &str {
+        "config"
+    }
+
+    fn on_request(&self, req: &mut RequestContext) {
+        let kind = format!("{:?}", req.provider_kind).to_lowercase();
+        for rule in &self.0.headers {
+            let applies = rule.providers.is_empty()
+                || rule
+                    .providers
+                    .iter()
+                    .any(|p| p == &req.provider_id || p.to_lowercase() == kind);
+            if applies && !rule.name.trim().is_empty() {
+                let value = rule
+                    .value
+                    .replace("{model}", &req.model)
+                    .replace("{provider}", &req.provider_id);
+                req.headers.push((rule.name.clone(), value));
+            }
+        }
+    }
+
+    fn on_response(&self, req: &RequestContext, resp: &ResponseContext) {
+        if !self.0.log_requests {
+            return;
+        }
+        info!(
+            "[provider-middleware] {} model={} msgs={} tools={} → {}ms chunks={} in={} out={}{}",
+            req.provider_id,
+            req.model,
+            req.message_count,
+            req.tool_count,
+            resp.duration_ms,
+            resp.chunk_count,
+            resp.input_tokens,
+            resp.output_tokens,
+            resp.error
+                .as_deref()
+                .map(|e| format!(" error={}", e))
+                .unwrap_or_default()
+        );
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::types::HeaderInjection;
+
+    fn request(provider_id: &str, kind: ProviderKind) -> RequestContext {
+        RequestContext {
+            provider_id: provider_id.into(),
+            provider_kind: kind,
+            model: "gpt-4o".into(),
+            message_count: 2,
+            tool_count: 0,
+            headers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn config_injects_headers_with_placeholders() {
+        let cfg = ProviderMiddlewareConfig {
+            headers: vec![HeaderInjection {
+                name: "Helicone-Property-Model".into(),
+                value: "{provider}/{model}".into(),
+                providers: vec![],
+            }],
+            log_requests: false,
+        };
+        let mut req = request("openai-main", ProviderKind::OpenAI);
+        ConfigMiddleware(&cfg).on_request(&mut req);
+        assert_eq!(
+            req.headers,
+            vec![(
+                "Helicone-Property-Model".to_string(),
+                "openai-main/gpt-4o".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn config_headers_respect_provider_filter() {
+        let cfg = ProviderMiddlewareConfig {
+            headers: vec![HeaderInjection {
+                name: "x-litellm-key".into(),
+                value: "k".into(),
+                providers: vec!["anthropic".into()],
+            }],
+            log_requests: false,
+        };
+        let mut openai = request("openai-main", ProviderKind::OpenAI);
+        ConfigMiddleware(&cfg).on_request(&mut openai);
+        assert!(openai.headers.is_empty());
+
+        let mut claude = request("my-claude", ProviderKind::Anthropic);
+        ConfigMiddleware(&cfg).on_request(&mut claude);
+        assert_eq!(claude.headers.len(), 1);
+    }
+
+    struct Tagger;
+    impl ProviderMiddleware for Tagger {
+        fn name(&self) -> &str {
+            "test-tagger"
+        }
+        fn on_request(&self, req: &mut RequestContext) {
+            req.headers.push(("x-tag".into(), "1".into()));
+        }
+    }
+
+    #[test]
+    fn registered_hooks_run_and_replace_by_name() {
+        register(Arc::new(Tagger));
+        register(Arc::new(Tagger));
+        let mut req = request("p", ProviderKind::OpenAI);
+        run_request_hooks(&mut req);
+        assert_eq!(req.headers.iter().filter(|(n, _)| n == "x-tag").count(), 1);
+        assert!(unregister("test-tagger"));
+        assert!(!unregister("test-tagger"));
+    }
+
+    #[test]
+    fn response_context_from_chunks() {
+        let chunks = vec![StreamChunk {
+            delta_text: Some("hi".into()),
+            tool_calls: vec![],
+            finish_reason: None,
+            usage: Some(crate::atoms::types::TokenUsage {
+                input_tokens: 10,
+                output_tokens: 3,
+                total_tokens: 13,
+                ..Default::default()
+            }),
+            model: Some("gpt-4o-2024".into()),
+            thought_parts: vec![],
+            thinking_text: None,
+            meta: vec![],
+        }];
+        let resp = ResponseContext::from_chunks(&chunks, 42);
+        assert_eq!(resp.input_tokens, 10);
+        assert_eq!(resp.output_tokens, 3);
+        assert_eq!(resp.response_model.as_deref(), Some("gpt-4o-2024"));
+    }
+
+    #[tokio::test]
+    async fn headers_scoped_to_task() {
+        assert!(REQUEST_HEADERS.try_with(|h| h.len()).is_err());
+        let n = with_request_headers(vec![("a".into(), "b".into())], async {
+            REQUEST_HEADERS.try_with(|h| h.len()).unwrap()
+        })
+        .await;
+        assert_eq!(n, 1);
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod anthropic;
 pub mod google;
+pub mod middleware;
 pub mod openai;
 
 pub use anthropic::AnthropicProvider;
@@ -17,8 +18,12 @@ use crate::engine::types::{Message, ProviderConfig, ProviderKind, StreamChunk, T
 // ── Provider factory ───────────────────────────────────────────────────────────
 
 /// Type-erased AI provider.  Callers hold `AnyProvider` and call `.chat_stream()`
-/// without knowing which concrete backend is in use.
-pub struct AnyProvider(Box<dyn AiProvider>);
+/// without knowing which concrete backend is in use.  Every request passes
+/// through the middleware hooks in `middleware.rs`.
+pub struct AnyProvider {
+    inner: Box<dyn AiProvider>,
+    provider_id: String,
+}
 
 impl AnyProvider {
     /// Construct the right concrete provider from a `ProviderConfig`.
@@ -52,7 +57,10 @@ impl AnyProvider {
             // OpenAI, Ollama, OpenRouter, Custom, DeepSeek, Grok, Mistral, Moonshot
             _ => Box::new(OpenAiProvider::new(config)),
         };
-        AnyProvider(provider)
+        AnyProvider {
+            inner: provider,
+            provider_id: config.id.clone(),
+        }
     }
 
     /// Chat completion with SSE streaming.
@@ -66,20 +74,46 @@ impl AnyProvider {
         temperature: Option<f64>,
         thinking_level: Option<&str>,
     ) -> EngineResult<Vec<StreamChunk>> {
-        self.0
-            .chat_stream(messages, tools, model, temperature, thinking_level)
-            .await
-            .map_err(|e| crate::atoms::error::EngineError::Other(e.to_string()))
+        let mut req = middleware::RequestContext {
+            provider_id: self.provider_id.clone(),
+            provider_kind: self.inner.kind(),
+            model: model.to_string(),
+            message_count: messages.len(),
+            tool_count: tools.len(),
+            headers: Vec::new(),
+        };
+        middleware::run_request_hooks(&mut req);
+
+        let started = std::time::Instant::now();
+        let result = middleware::with_request_headers(
+            req.headers.clone(),
+            self.inner
+                .chat_stream(messages, tools, model, temperature, thinking_level),
+        )
+        .await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let resp = match &result {
+            Ok(chunks) => middleware::ResponseContext::from_chunks(chunks, duration_ms),
+            Err(e) => middleware::ResponseContext {
+                duration_ms,
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+        middleware::run_response_hooks(&req, &resp);
+
+        result.map_err(|e| crate::atoms::error::EngineError::Other(e.to_string()))
     }
 
     /// The ProviderKind discriminant of the underlying provider.
     pub fn kind(&self) -> ProviderKind {
-        self.0.kind()
+        self.inner.kind()
     }
 
     /// List available models from the provider.
     pub async fn list_models(&self) -> EngineResult<Vec<ModelInfo>> {
-        self.0
+        self.inner
             .list_models()
             .await
             .map_err(|e| crate::atoms::error::EngineError::Other(e.to_string()))
@@ -3,9 +3,11 @@
 // Implements the AiProvider Golden Trait.
 
 use crate::atoms::traits::{AiProvider, ModelInfo, ProviderError};
+use crate::engine::cancel;
+use crate::engine::grounding;
 use crate::engine::types::{
-    ContentBlock, Message, MessageContent, ProviderConfig, ProviderKind, Role, StreamChunk,
-    TokenUsage, ToolCallDelta, ToolDefinition,
+    Citation, ContentBlock, Message, MessageContent, ProviderConfig, ProviderKind, ProviderMeta,
+    Role, StreamChunk, ThoughtPart, TokenUsage, ToolCallDelta, ToolDefinition,
 };
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -29,7 +31,7 @@ pub(crate) use crate::engine::http::{
 use crate::engine::http::{
     pinned_client, sign_and_log_request, update_last_audit_status, CircuitBreaker,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock, Mutex};
 
 /// Per-endpoint circuit breakers so failures from one provider/model
@@ -38,6 +40,14 @@ use std::sync::{Arc, LazyLock, Mutex};
 static OPENAI_CIRCUITS: LazyLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// Base URLs that answered a Responses API request with 404/405.
+static RESPONSES_UNSUPPORTED: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn responses_unsupported(base_url: &str) -> bool {
+    RESPONSES_UNSUPPORTED.lock().unwrap().contains(base_url)
+}
+
 /// Get (or create) the circuit breaker for a given base URL.
 fn get_circuit(base_url: &str) -> Arc<CircuitBreaker> {
     let mut map = OPENAI_CIRCUITS.lock().unwrap();
@@ -54,6 +64,34 @@ fn is_reasoning_model(model: &str) -> bool {
     m.starts_with("o1") || m.starts_with("o3") || m.starts_with("o4") || m.starts_with("gpt-5")
 }
 
+/// Request-body keys the engine owns; user extra params never overwrite them.
+const PROTECTED_BODY_KEYS: &[&str] = &["messages", "input", "stream", "tools"];
+
+/// Merge user-supplied routing metadata (OpenRouter `provider`/`models`,
+/// LiteLLM `metadata`/`tags`, …) into a request body.  Objects are merged
+/// recursively; any other value replaces the existing one.
+fn merge_extra_body(body: &mut Value, extra: &serde_json::Map<String, Value>) {
+    fn merge(dst: &mut Value, src: &Value) {
+        match (dst, src) {
+            (Value::Object(d), Value::Object(s)) => {
+                for (k, v) in s {
+                    merge(d.entry(k.clone()).or_insert(Value::Null), v);
+                }
+            }
+            (d, s) => *d = s.clone(),
+        }
+    }
+    let Some(obj) = body.as_object_mut() else {
+        return;
+    };
+    for (key, value) in extra {
+        if PROTECTED_BODY_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        merge(obj.entry(key.clone()).or_insert(Value::Null), value);
+    }
+}
+
 /// Extract the tool name from an OpenAI "Invalid schema for function 'X'" error.
 ///
 /// Returns `Some("X")` if the error body matches, `None` otherwise.
@@ -87,6 +125,14 @@ pub struct OpenAiProvider {
     /// True when the endpoint uses the OpenAI Responses API format
     /// (e.g. Azure AI Foundry o3-pro at /openai/responses).
     is_responses_api: bool,
+    /// User-supplied routing metadata merged into each request body.
+    extra_body: serde_json::Map<String, Value>,
+    /// User-supplied headers added to each request.
+    extra_headers: Vec<(String, String)>,
+    /// Use the Responses API at `{base_url}/responses` (see `ProviderConfig`).
+    responses_api: bool,
+    /// Built-in tools sent with every Responses API request.
+    responses_tools: Vec<Value>,
 }
 
 impl OpenAiProvider {
@@ -177,6 +223,23 @@ impl OpenAiProvider {
             provider_kind: config.kind,
             circuit,
             is_responses_api,
+            extra_body: config.extra_body.clone(),
+            extra_headers: config
+                .extra_headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            responses_api: config.responses_api,
+            responses_tools: config.responses_tools.clone(),
+        }
+    }
+
+    /// The Azure Responses deployment URL as given, else `{base}/responses`.
+    fn responses_url(&self) -> String {
+        if self.is_responses_api {
+            self.base_url.clone()
+        } else {
+            format!("{}/responses", self.base_url.trim_end_matches('/'))
         }
     }
 
@@ -240,8 +303,10 @@ impl OpenAiProvider {
     }
 
     fn format_tools(tools: &[ToolDefinition]) -> Vec<Value> {
+        // Built-in tools only exist in the Responses API
         tools
             .iter()
+            .filter(|t| t.tool_type != grounding::BUILTIN_TOOL_TYPE)
             .map(|t| {
                 json!({
                     "type": t.tool_type,
@@ -289,6 +354,25 @@ impl OpenAiProvider {
                 _ => {
                     // For assistant messages with tool_calls, emit function_call items
                     if let Some(tc) = &msg.tool_calls {
+                        // Reasoning items go back first, in front of the calls
+                        // they led to (encrypted, since requests aren't stored)
+                        for tp in tc.iter().flat_map(|call| &call.thought_parts) {
+                            let Some(id) = &tp.item_id else { continue };
+                            let summary: Vec<Value> = if tp.text.is_empty() {
+                                vec![]
+                            } else {
+                                vec![json!({"type": "summary_text", "text": tp.text})]
+                            };
+                            let mut item = json!({
+                                "type": "reasoning",
+                                "id": id,
+                                "summary": summary,
+                            });
+                            if !tp.thought_signature.is_empty() {
+                                item["encrypted_content"] = json!(tp.thought_signature);
+                            }
+                            input.push(item);
+                        }
                         // Emit text content if present
                         if let MessageContent::Text(s) = &msg.content {
                             if !s.is_empty() {
@@ -346,10 +430,11 @@ impl OpenAiProvider {
         input
     }
 
-    /// Send a request via the OpenAI Responses API (`/openai/responses`).
+    /// Send a request via the OpenAI Responses API (`/responses`).
     ///
     /// Used for models like o3-pro on Azure AI Foundry that only support
-    /// the Responses API, not Chat Completions.
+    /// the Responses API, not Chat Completions, and for providers with
+    /// `responses_api` turned on (built-in tools, encrypted reasoning).
     async fn chat_stream_responses(
         &self,
         messages: &[Message],
@@ -358,7 +443,7 @@ impl OpenAiProvider {
         temperature: Option<f64>,
         thinking_level: Option<&str>,
     ) -> Result<Vec<StreamChunk>, ProviderError> {
-        let url = &self.base_url;
+        let url = &self.responses_url();
 
         let input = Self::format_responses_input(messages);
         let mut body = json!({
@@ -367,22 +452,33 @@ impl OpenAiProvider {
             "stream": true,
         });
 
-        if !tools.is_empty() {
-            // Responses API uses a flat tool format with top-level
-            // name/description/parameters (NOT nested under "function").
-            let resp_tools: Vec<Value> = tools
-                .iter()
-                .map(|t| {
-                    json!({
-                        "type": "function",
-                        "name": t.function.name,
-                        "description": t.function.description,
-                        "parameters": t.function.parameters,
-                    })
-                })
-                .collect();
+        // Responses API uses a flat tool format with top-level
+        // name/description/parameters (NOT nested under "function").
+        let mut resp_tools: Vec<Value> = tools
+            .iter()
+            .filter_map(|t| {
+                if t.tool_type == grounding::BUILTIN_TOOL_TYPE {
+                    return (t.function.name == grounding::OPENAI_WEB_SEARCH_TOOL)
+                        .then(|| json!({"type": "web_search"}));
+                }
+                Some(json!({
+                    "type": "function",
+                    "name": t.function.name,
+                    "description": t.function.description,
+                    "parameters": t.function.parameters,
+                }))
+            })
+            .collect();
+        resp_tools.extend(self.responses_tools.iter().cloned());
+        if !resp_tools.is_empty() {
             body["tools"] = json!(resp_tools);
         }
+        // Nothing is stored server-side, so reasoning items travel with the
+        // conversation as encrypted content.
+        body["store"] = json!(false);
+        if is_reasoning_model(model) {
+            body["include"] = json!(["reasoning.encrypted_content"]);
+        }
         if let Some(temp) = temperature {
             if !is_reasoning_model(model) {
                 body["temperature"] = json!(temp);
@@ -394,9 +490,11 @@ impl OpenAiProvider {
                 "high" => "high",
                 _ => "medium",
             };
-            body["reasoning"] = json!({ "effort": effort });
+            body["reasoning"] = json!({ "effort": effort, "summary": "auto" });
         }
 
+        merge_extra_body(&mut body, &self.extra_body);
+
         info!(
             "[engine] OpenAI Responses API request to {} model={}",
             url, model
@@ -434,6 +532,10 @@ impl OpenAiProvider {
             let body_bytes = serde_json::to_vec(&body).unwrap_or_default();
             sign_and_log_request("openai-responses", model, &body_bytes);
 
+            for (name, value) in &self.extra_headers {
+                req = req.header(name.as_str(), value.as_str());
+            }
+            let req = super::middleware::apply_request_headers(req);
             let response = match req.json(&body).send().await {
                 Ok(r) => {
                     update_last_audit_status(r.status().as_u16());
@@ -521,13 +623,15 @@ impl OpenAiProvider {
             }
 
             // ── Parse Responses API SSE stream ──────────────────────
-            // Events use `event: <type>\ndata: <json>\n\n` format.
+            // Events use `event: <type>\ndata: <json>\n\n` format; the
+            // JSON repeats the type, which some compatibles send alone.
             let mut chunks = Vec::new();
+            let mut stream = ResponsesStream::default();
             let mut byte_stream = response.bytes_stream();
             let mut raw_buf: Vec<u8> = Vec::new();
             let mut current_event = String::new();
 
-            while let Some(result) = byte_stream.next().await {
+            while let Some(Some(result)) = cancel::or_cancelled(byte_stream.next()).await {
                 let bytes = result
                     .map_err(|e| ProviderError::Transport(format!("Stream read error: {}", e)))?;
                 raw_buf.extend_from_slice(&bytes);
@@ -556,107 +660,11 @@ impl OpenAiProvider {
                             Ok(v) => v,
                             Err(_) => continue,
                         };
-
-                        match current_event.as_str() {
-                            "response.output_text.delta" => {
-                                if let Some(delta) = v["delta"].as_str() {
-                                    chunks.push(StreamChunk {
-                                        delta_text: Some(delta.to_string()),
-                                        tool_calls: vec![],
-                                        finish_reason: None,
-                                        usage: None,
-                                        model: None,
-                                        thought_parts: vec![],
-                                        thinking_text: None,
-                                    });
-                                }
-                            }
-                            "response.reasoning_summary_text.delta" => {
-                                if let Some(delta) = v["delta"].as_str() {
-                                    chunks.push(StreamChunk {
-                                        delta_text: None,
-                                        tool_calls: vec![],
-                                        finish_reason: None,
-                                        usage: None,
-                                        model: None,
-                                        thought_parts: vec![],
-                                        thinking_text: Some(delta.to_string()),
-                                    });
-                                }
-                            }
-                            "response.output_item.added" => {
-                                if v["type"].as_str() == Some("function_call") {
-                                    let output_index = v["output_index"].as_u64().unwrap_or(0);
-                                    let call_id = v["call_id"].as_str().unwrap_or("").to_string();
-                                    let name = v["name"].as_str().unwrap_or("").to_string();
-                                    chunks.push(StreamChunk {
-                                        delta_text: None,
-                                        tool_calls: vec![ToolCallDelta {
-                                            index: output_index as usize,
-                                            id: Some(call_id),
-                                            function_name: Some(name),
-                                            arguments_delta: None,
-                                            thought_signature: None,
-                                        }],
-                                        finish_reason: None,
-                                        usage: None,
-                                        model: None,
-                                        thought_parts: vec![],
-                                        thinking_text: None,
-                                    });
-                                }
-                            }
-                            "response.function_call_arguments.delta" => {
-                                let output_index = v["output_index"].as_u64().unwrap_or(0);
-                                if let Some(delta) = v["delta"].as_str() {
-                                    chunks.push(StreamChunk {
-                                        delta_text: None,
-                                        tool_calls: vec![ToolCallDelta {
-                                            index: output_index as usize,
-                                            id: None,
-                                            function_name: None,
-                                            arguments_delta: Some(delta.to_string()),
-                                            thought_signature: None,
-                                        }],
-                                        finish_reason: None,
-                                        usage: None,
-                                        model: None,
-                                        thought_parts: vec![],
-                                        thinking_text: None,
-                                    });
-                                }
-                            }
-                            "response.completed" => {
-                                let usage = v.get("usage").and_then(|u| {
-                                    let input_tok = u["input_tokens"].as_u64().unwrap_or(0);
-                                    let output_tok = u["output_tokens"].as_u64().unwrap_or(0);
-                                    if input_tok > 0 || output_tok > 0 {
-                                        Some(TokenUsage {
-                                            input_tokens: input_tok,
-                                            output_tokens: output_tok,
-                                            total_tokens: u["total_tokens"]
-                                                .as_u64()
-                                                .unwrap_or(input_tok + output_tok),
-                                            ..Default::default()
-                                        })
-                                    } else {
-                                        None
-                                    }
-                                });
-                                let model_name = v["model"].as_str().map(|s| s.to_string());
-                                chunks.push(StreamChunk {
-                                    delta_text: None,
-                                    tool_calls: vec![],
-                                    finish_reason: Some("stop".to_string()),
-                                    usage,
-                                    model: model_name,
-                                    thought_parts: vec![],
-                                    thinking_text: None,
-                                });
-                                self.circuit.record_success();
-                                return Ok(chunks);
-                            }
-                            _ => {} // Ignore other event types
+                        let event = v["type"].as_str().unwrap_or(&current_event).to_string();
+                        chunks.extend(stream.push(&event, &v)?);
+                        if stream.done {
+                            self.circuit.record_success();
+                            return Ok(chunks);
                         }
                     }
                 }
@@ -755,10 +763,217 @@ impl OpenAiProvider {
             model,
             thought_parts: vec![],
             thinking_text,
+            meta: vec![],
         })
     }
 }
 
+// ── Responses API stream parsing ───────────────────────────────────────────
+
+/// Turns Responses API stream events into stream chunks. Function calls
+/// are indexed by their output position. Finished reasoning items wait for
+/// the next function call, which carries them into the next round's input;
+/// web searches and URL citations are reported once the response completes.
+#[derive(Default)]
+struct ResponsesStream {
+    pending_reasoning: Vec<ThoughtPart>,
+    /// Output indices whose arguments arrived as deltas.
+    streamed_args: HashSet<usize>,
+    queries: Vec<String>,
+    sources: Vec<Citation>,
+    /// Set once the response completed (or ended incomplete).
+    done: bool,
+}
+
+impl ResponsesStream {
+    fn chunk() -> StreamChunk {
+        StreamChunk {
+            delta_text: None,
+            tool_calls: vec![],
+            finish_reason: None,
+            usage: None,
+            model: None,
+            thought_parts: vec![],
+            thinking_text: None,
+            meta: vec![],
+        }
+    }
+
+    fn push(&mut self, event: &str, v: &Value) -> Result<Vec<StreamChunk>, ProviderError> {
+        let index = v["output_index"].as_u64().unwrap_or(0) as usize;
+        let item = &v["item"];
+        let mut chunks = Vec::new();
+        match event {
+            "response.output_text.delta" => {
+                if let Some(delta) = v["delta"].as_str() {
+                    chunks.push(StreamChunk {
+                        delta_text: Some(delta.to_string()),
+                        ..Self::chunk()
+                    });
+                }
+            }
+            "response.reasoning_summary_text.delta" => {
+                if let Some(delta) = v["delta"].as_str() {
+                    chunks.push(StreamChunk {
+                        thinking_text: Some(delta.to_string()),
+                        ..Self::chunk()
+                    });
+                }
+            }
+            "response.output_item.added" if item["type"] == "function_call" => {
+                chunks.push(StreamChunk {
+                    tool_calls: vec![ToolCallDelta {
+                        index,
+                        id: item["call_id"].as_str().map(str::to_string),
+                        function_name: item["name"].as_str().map(str::to_string),
+                        arguments_delta: None,
+                        thought_signature: None,
+                    }],
+                    thought_parts: std::mem::take(&mut self.pending_reasoning),
+                    ..Self::chunk()
+                });
+            }
+            "response.function_call_arguments.delta" => {
+                if let Some(delta) = v["delta"].as_str() {
+                    self.streamed_args.insert(index);
+                    chunks.push(StreamChunk {
+                        tool_calls: vec![Self::arguments(index, delta)],
+                        ..Self::chunk()
+                    });
+                }
+            }
+            "response.output_item.done" => match item["type"].as_str() {
+                // Compatibles that only send the arguments in full
+                Some("function_call") if !self.streamed_args.contains(&index) => {
+                    let arguments = item["arguments"].as_str().unwrap_or("{}");
+                    chunks.push(StreamChunk {
+                        tool_calls: vec![Self::arguments(index, arguments)],
+                        ..Self::chunk()
+                    });
+                }
+                Some("reasoning") => {
+                    let summary: Vec<&str> = item["summary"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|s| s["text"].as_str())
+                        .collect();
+                    if let Some(id) = item["id"].as_str() {
+                        self.pending_reasoning.push(ThoughtPart {
+                            text: summary.join("\n\n"),
+                            thought_signature: item["encrypted_content"]
+                                .as_str()
+                                .unwrap_or("")
+                                .to_string(),
+                            thought: true,
+                            item_id: Some(id.to_string()),
+                        });
+                    }
+                }
+                Some("web_search_call") => {
+                    if let Some(q) = item["action"]["query"].as_str() {
+                        if !self.queries.iter().any(|e| e == q) {
+                            self.queries.push(q.to_string());
+                        }
+                    }
+                }
+                Some("message") => {
+                    let annotations = item["content"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|c| c["annotations"].as_array().into_iter().flatten());
+                    for a in annotations.filter(|a| a["type"] == "url_citation") {
+                        let Some(url) = a["url"].as_str() else {
+                            continue;
+                        };
+                        if !self.sources.iter().any(|s| s.url == url) {
+                            self.sources.push(Citation {
+                                index: self.sources.len() + 1,
+                                url: url.to_string(),
+                                title: a["title"].as_str().unwrap_or("").to_string(),
+                                quote: None,
+                                tool: "openai_web_search".into(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            },
+            "response.completed" | "response.incomplete" => {
+                let response = &v["response"];
+                let u = &response["usage"];
+                let input_tok = u["input_tokens"].as_u64().unwrap_or(0);
+                let output_tok = u["output_tokens"].as_u64().unwrap_or(0);
+                let usage = (input_tok > 0 || output_tok > 0).then(|| TokenUsage {
+                    input_tokens: input_tok,
+                    output_tokens: output_tok,
+                    total_tokens: u["total_tokens"].as_u64().unwrap_or(input_tok + output_tok),
+                    cache_read_tokens: u["input_tokens_details"]["cached_tokens"]
+                        .as_u64()
+                        .unwrap_or(0),
+                    ..Default::default()
+                });
+                if !self.queries.is_empty() || !self.sources.is_empty() {
+                    chunks.push(StreamChunk {
+                        meta: vec![ProviderMeta::Grounding {
+                            queries: std::mem::take(&mut self.queries),
+                            sources: std::mem::take(&mut self.sources),
+                        }],
+                        ..Self::chunk()
+                    });
+                }
+                let finish_reason = if event == "response.completed" {
+                    "stop".to_string()
+                } else {
+                    let reason = response["incomplete_details"]["reason"]
+                        .as_str()
+                        .unwrap_or("incomplete");
+                    warn!("[engine] Responses API: response incomplete ({})", reason);
+                    if reason == "max_output_tokens" {
+                        "length".to_string()
+                    } else {
+                        reason.to_string()
+                    }
+                };
+                chunks.push(StreamChunk {
+                    finish_reason: Some(finish_reason),
+                    usage,
+                    model: response["model"].as_str().map(str::to_string),
+                    ..Self::chunk()
+                });
+                self.done = true;
+            }
+            "response.failed" | "error" => {
+                let error = if event == "error" {
+                    v
+                } else {
+                    &v["response"]["error"]
+                };
+                return Err(ProviderError::Api {
+                    status: 500,
+                    message: format!(
+                        "Responses API error: {}",
+                        error["message"].as_str().unwrap_or("response failed")
+                    ),
+                });
+            }
+            _ => {} // Ignore other event types
+        }
+        Ok(chunks)
+    }
+
+    fn arguments(index: usize, arguments: &str) -> ToolCallDelta {
+        ToolCallDelta {
+            index,
+            id: None,
+            function_name: None,
+            arguments_delta: Some(arguments.to_string()),
+            thought_signature: None,
+        }
+    }
+}
+
 // ── AiProvider implementation ──────────────────────────────────────────────
 
 #[async_trait]
@@ -789,6 +1004,26 @@ impl AiProvider for OpenAiProvider {
                 .chat_stream_responses(messages, tools, model, temperature, thinking_level)
                 .await;
         }
+        // Opted in: compatibles without a /responses endpoint fall back to
+        // Chat Completions, and are remembered so later calls go straight there.
+        if self.responses_api && !self.is_azure && !responses_unsupported(&self.base_url) {
+            match self
+                .chat_stream_responses(messages, tools, model, temperature, thinking_level)
+                .await
+            {
+                Err(ProviderError::Api { status, .. }) if status == 404 || status == 405 => {
+                    warn!(
+                        "[engine] {} has no Responses API (HTTP {}), using Chat Completions",
+                        self.base_url, status
+                    );
+                    RESPONSES_UNSUPPORTED
+                        .lock()
+                        .unwrap()
+                        .insert(self.base_url.clone());
+                }
+                result => return result,
+            }
+        }
 
         let url = if self.is_azure {
             if self.base_url.contains("/chat/completions") {
@@ -858,6 +1093,8 @@ impl AiProvider for OpenAiProvider {
             body["reasoning_effort"] = json!(effort);
         }
 
+        merge_extra_body(&mut body, &self.extra_body);
+
         info!("[engine] OpenAI request to {} model={}", url, model);
 
         // Circuit breaker: reject immediately if too many recent failures
@@ -896,6 +1133,10 @@ impl AiProvider for OpenAiProvider {
             let body_bytes = serde_json::to_vec(&body).unwrap_or_default();
             sign_and_log_request("openai", model, &body_bytes);
 
+            for (name, value) in &self.extra_headers {
+                req = req.header(name.as_str(), value.as_str());
+            }
+            let req = super::middleware::apply_request_headers(req);
             let response = match req.json(&body).send().await {
                 Ok(r) => {
                     update_last_audit_status(r.status().as_u16());
@@ -1002,7 +1243,7 @@ impl AiProvider for OpenAiProvider {
             let mut byte_stream = response.bytes_stream();
             let mut raw_buf: Vec<u8> = Vec::new();
 
-            while let Some(result) = byte_stream.next().await {
+            while let Some(Some(result)) = cancel::or_cancelled(byte_stream.next()).await {
                 let bytes = result
                     .map_err(|e| ProviderError::Transport(format!("Stream read error: {}", e)))?;
                 raw_buf.extend_from_slice(&bytes);
@@ -1159,3 +1400,139 @@ impl AiProvider for OpenAiProvider {
         Ok(models)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::types::{FunctionCall, ToolCall};
+
+    #[test]
+    fn extra_body_adds_routing_hints() {
+        let mut body = json!({"model": "openai/gpt-4o", "messages": []});
+        let extra = json!({
+            "provider": {"order": ["Azure", "OpenAI"], "allow_fallbacks": true},
+            "models": ["anthropic/claude-sonnet-4"]
+        });
+        merge_extra_body(&mut body, extra.as_object().unwrap());
+        assert_eq!(body["provider"]["order"][0], "Azure");
+        assert_eq!(body["models"][0], "anthropic/claude-sonnet-4");
+    }
+
+    #[test]
+    fn extra_body_merges_objects_recursively() {
+        let mut body = json!({"metadata": {"a": 1}});
+        let extra = json!({"metadata": {"tags": ["team-x"]}});
+        merge_extra_body(&mut body, extra.as_object().unwrap());
+        assert_eq!(body["metadata"]["a"], 1);
+        assert_eq!(body["metadata"]["tags"][0], "team-x");
+    }
+
+    #[test]
+    fn extra_body_never_overwrites_protected_keys() {
+        let mut body = json!({"messages": [{"role": "user"}], "stream": true});
+        let extra = json!({"messages": [], "stream": false, "temperature": 0.2});
+        merge_extra_body(&mut body, extra.as_object().unwrap());
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["temperature"], 0.2);
+    }
+
+    #[test]
+    fn responses_stream_reads_calls_from_the_item() {
+        let mut stream = ResponsesStream::default();
+        let reasoning = json!({"item": {
+            "type": "reasoning", "id": "rs_1", "encrypted_content": "enc",
+            "summary": [{"type": "summary_text", "text": "Look it up."}],
+        }});
+        assert!(stream
+            .push("response.output_item.done", &reasoning)
+            .unwrap()
+            .is_empty());
+
+        let added = json!({"output_index": 1, "item": {
+            "type": "function_call", "call_id": "call_1", "name": "fetch",
+        }});
+        let chunks = stream.push("response.output_item.added", &added).unwrap();
+        let call = &chunks[0].tool_calls[0];
+        assert_eq!((call.index, call.id.as_deref()), (1, Some("call_1")));
+        assert_eq!(call.function_name.as_deref(), Some("fetch"));
+        assert_eq!(chunks[0].thought_parts[0].item_id.as_deref(), Some("rs_1"));
+
+        let delta = json!({"output_index": 1, "delta": "{\"url\":1}"});
+        stream
+            .push("response.function_call_arguments.delta", &delta)
+            .unwrap();
+        // Arguments already streamed aren't repeated when the item finishes
+        let done = json!({"output_index": 1, "item": {
+            "type": "function_call", "arguments": "{\"url\":1}",
+        }});
+        assert!(stream
+            .push("response.output_item.done", &done)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn responses_input_replays_reasoning_before_calls() {
+        let messages = vec![Message {
+            role: Role::Assistant,
+            content: MessageContent::Text(String::new()),
+            tool_calls: Some(vec![ToolCall {
+                id: "call_1".into(),
+                call_type: "function".into(),
+                function: FunctionCall {
+                    name: "fetch".into(),
+                    arguments: "{}".into(),
+                },
+                thought_signature: None,
+                thought_parts: vec![ThoughtPart {
+                    text: "Look it up.".into(),
+                    thought_signature: "enc".into(),
+                    thought: true,
+                    item_id: Some("rs_1".into()),
+                }],
+            }]),
+            tool_call_id: None,
+            name: None,
+        }];
+        let input = OpenAiProvider::format_responses_input(&messages);
+        assert_eq!(input[0]["type"], "reasoning");
+        assert_eq!(input[0]["encrypted_content"], "enc");
+        assert_eq!(input[0]["summary"][0]["text"], "Look it up.");
+        assert_eq!(input[1]["type"], "function_call");
+    }
+
+    #[test]
+    fn responses_stream_reports_search_and_usage_on_completion() {
+        let mut stream = ResponsesStream::default();
+        let search = json!({"item": {
+            "type": "web_search_call", "action": {"query": "rust release"},
+        }});
+        stream.push("response.output_item.done", &search).unwrap();
+        let message = json!({"item": {"type": "message", "content": [{
+            "type": "output_text",
+            "annotations": [{"type": "url_citation", "url": "https://a.dev", "title": "A"}],
+        }]}});
+        stream.push("response.output_item.done", &message).unwrap();
+
+        let completed = json!({"response": {"model": "gpt-5", "usage": {
+            "input_tokens": 10, "output_tokens": 5,
+            "input_tokens_details": {"cached_tokens": 4},
+        }}});
+        let chunks = stream.push("response.completed", &completed).unwrap();
+        match &chunks[0].meta[0] {
+            ProviderMeta::Grounding { queries, sources } => {
+                assert_eq!(queries, &["rust release"]);
+                assert_eq!(sources[0].url, "https://a.dev");
+            }
+            other => panic!("unexpected meta {other:?}"),
+        }
+        let usage = chunks[1].usage.as_ref().unwrap();
+        assert_eq!((usage.total_tokens, usage.cache_read_tokens), (15, 4));
+        assert_eq!(chunks[1].finish_reason.as_deref(), Some("stop"));
+        assert!(stream.done);
+
+        let failed = json!({"response": {"error": {"message": "boom"}}});
+        assert!(stream.push("response.failed", &failed).is_err());
+    }
+}
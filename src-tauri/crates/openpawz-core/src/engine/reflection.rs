@@ -0,0 +1,353 @@
+// ── Self-Reflection: guarded soul updates ──────────────────────────────────
+//
+// Optional post-run pass (per agent, off by default). After a turn the agent
+// reviews the exchange — the user's message, its reply and any tool errors —
+// against its current soul files and may propose rewrites. Proposals are
+// queued for the user; they are applied straight away only when the agent's
+// autonomy policy auto-approves everything. Applied proposals go through
+// `set_agent_file_as(.., "agent")`, so they show up in the file history.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::sessions::SessionStore;
+use crate::engine::types::{AgentFile, Message, MessageContent, Role};
+use crate::engine::util::safe_truncate;
+use log::{info, warn};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Soul files the reflection pass may propose changes to.
+pub const REFLECTABLE_FILES: &[&str] = &["IDENTITY.md", "SOUL.md", "AGENTS.md", "TOOLS.md"];
+
+/// At most this many proposals are kept from one reflection.
+const MAX_PROPOSALS_PER_RUN: usize = 2;
+
+const REFLECTION_PROMPT: &str = "You are reviewing your own behaviour after a conversation turn. \
+Below are your current soul files, the user's message, your reply, and any tool errors. \
+Only if the exchange shows a lasting lesson — explicit user feedback, a repeated mistake, a \
+preference you should keep — propose an updated version of ONE or TWO files. \
+Most turns need no change: then respond with []. \
+Otherwise respond with ONLY a JSON array of objects: \
+{\"file_name\": \"SOUL.md\", \"content\": \"<full new file content>\", \"rationale\": \"<one sentence>\"}. \
+Allowed files: IDENTITY.md, SOUL.md, AGENTS.md, TOOLS.md. Keep edits minimal and preserve \
+everything that is still true.";
+
+/// A change the agent suggested for one of its soul files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReflectionProposal {
+    pub file_name: String,
+    pub content: String,
+    #[serde(default)]
+    pub rationale: String,
+}
+
+/// A stored proposal and its review state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoulProposal {
+    pub id: String,
+    pub agent_id: String,
+    pub session_id: Option<String>,
+    pub file_name: String,
+    pub proposed_content: String,
+    pub rationale: String,
+    pub status: String, // pending, applied, rejected
+    pub created_at: String,
+}
+
+// ── Settings ───────────────────────────────────────────────────────────────
+
+fn config_key(agent_id: &str) -> String {
+    format!("agent_reflection:{}", agent_id)
+}
+
+pub fn is_enabled(store: &SessionStore, agent_id: &str) -> bool {
+    matches!(
+        store
+            .get_config(&config_key(agent_id))
+            .ok()
+            .flatten()
+            .as_deref(),
+        Some("on")
+    )
+}
+
+pub fn set_enabled(store: &SessionStore, agent_id: &str, enabled: bool) -> EngineResult<()> {
+    store.set_config(&config_key(agent_id), if enabled { "on" } else { "off" })
+}
+
+// ── Reflection pass ────────────────────────────────────────────────────────
+
+/// Ask the model whether this turn warrants soul changes. Errors and
+/// unparseable replies yield no proposals.
+pub async fn reflect(
+    provider: &AnyProvider,
+    model: &str,
+    soul_files: &[AgentFile],
+    user_message: &str,
+    assistant_response: &str,
+    tool_errors: &[String],
+) -> Vec<ReflectionProposal> {
+    let mut context = String::new();
+    for f in soul_files
+        .iter()
+        .filter(|f| REFLECTABLE_FILES.contains(&f.file_name.as_str()))
+    {
+        context.push_str(&format!(
+            "### {}\n{}\n\n",
+            f.file_name,
+            safe_truncate(&f.content, 3000)
+        ));
+    }
+    if context.is_empty() {
+        context.push_str("(no soul files yet)\n\n");
+    }
+    context.push_str(&format!(
+        "### User\n{}\n\n### Your reply\n{}\n",
+        safe_truncate(user_message, 1500),
+        safe_truncate(assistant_response, 3000)
+    ));
+    if !tool_errors.is_empty() {
+        context.push_str("\n### Tool errors\n");
+        for e in tool_errors.iter().take(5) {
+            context.push_str(&format!("- {}\n", safe_truncate(e, 300)));
+        }
+    }
+
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(REFLECTION_PROMPT.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(context),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+
+    match provider
+        .chat_stream(&messages, &[], model, Some(0.0), None)
+        .await
+    {
+        Ok(chunks) => {
+            let text: String = chunks
+                .iter()
+                .filter_map(|c| c.delta_text.as_deref())
+                .collect();
+            parse_proposals(&text, soul_files)
+        }
+        Err(e) => {
+            warn!("[reflection] Reflection call failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Parse the model's JSON reply, dropping proposals for files outside
+/// `REFLECTABLE_FILES`, empty ones and ones identical to the current file.
+pub fn parse_proposals(text: &str, soul_files: &[AgentFile]) -> Vec<ReflectionProposal> {
+    let trimmed = text.trim();
+    let json = match (trimmed.find('['), trimmed.rfind(']')) {
+        (Some(start), Some(end)) if end > start => &trimmed[start..=end],
+        _ => return Vec::new(),
+    };
+    let proposals: Vec<ReflectionProposal> = match serde_json::from_str(json) {
+        Ok(p) => p,
+        Err(_) => return Vec::new(),
+    };
+    proposals
+        .into_iter()
+        .filter(|p| REFLECTABLE_FILES.contains(&p.file_name.as_str()))
+        .filter(|p| !p.content.trim().is_empty())
+        .filter(|p| {
+            !soul_files
+                .iter()
+                .any(|f| f.file_name == p.file_name && f.content.trim() == p.content.trim())
+        })
+        .take(MAX_PROPOSALS_PER_RUN)
+        .collect()
+}
+
+// ── Queue ──────────────────────────────────────────────────────────────────
+
+fn proposal_from_row(row: &rusqlite::Row) -> rusqlite::Result<SoulProposal> {
+    Ok(SoulProposal {
+        id: row.get(0)?,
+        agent_id: row.get(1)?,
+        session_id: row.get(2)?,
+        file_name: row.get(3)?,
+        proposed_content: row.get(4)?,
+        rationale: row.get(5)?,
+        status: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Store proposals, applying them right away when `auto_apply` is set
+/// (autonomy policy allows it). Returns the stored records.
+pub fn queue_proposals(
+    store: &SessionStore,
+    agent_id: &str,
+    session_id: Option<&str>,
+    proposals: Vec<ReflectionProposal>,
+    auto_apply: bool,
+) -> EngineResult<Vec<SoulProposal>> {
+    let mut stored = Vec::new();
+    for p in proposals {
+        let id = uuid::Uuid::new_v4().to_string();
+        if auto_apply {
+            store.set_agent_file_as(agent_id, &p.file_name, &p.content, "agent")?;
+        }
+        let status = if auto_apply { "applied" } else { "pending" };
+        {
+            let conn = store.conn.lock();
+            conn.execute(
+                "INSERT INTO soul_proposals (id, agent_id, session_id, file_name, proposed_content, rationale, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![id, agent_id, session_id, p.file_name, p.content, p.rationale, status],
+            )?;
+        }
+        info!(
+            "[reflection] {} proposal for {}/{}: {}",
+            status, agent_id, p.file_name, p.rationale
+        );
+        if let Some(row) = get_proposal(store, &id)? {
+            stored.push(row);
+        }
+    }
+    Ok(stored)
+}
+
+pub fn get_proposal(store: &SessionStore, id: &str) -> EngineResult<Option<SoulProposal>> {
+    let conn = store.conn.lock();
+    Ok(conn
+        .query_row(
+            "SELECT id, agent_id, session_id, file_name, proposed_content, rationale, status, created_at
+             FROM soul_proposals WHERE id = ?1",
+            params![id],
+            proposal_from_row,
+        )
+        .optional()?)
+}
+
+/// Pending proposals, optionally for one agent, newest first.
+pub fn list_pending(
+    store: &SessionStore,
+    agent_id: Option<&str>,
+) -> EngineResult<Vec<SoulProposal>> {
+    let conn = store.conn.lock();
+    let mut stmt = conn.prepare(
+        "SELECT id, agent_id, session_id, file_name, proposed_content, rationale, status, created_at
+         FROM soul_proposals WHERE status = 'pending' AND (?1 IS NULL OR agent_id = ?1)
+         ORDER BY created_at DESC",
+    )?;
+    let rows = stmt.query_map(params![agent_id], proposal_from_row)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Approve (apply) or reject a pending proposal. Returns the new file
+/// version when a proposal was applied.
+pub fn resolve(store: &SessionStore, id: &str, approve: bool) -> EngineResult<Option<i64>> {
+    let proposal = get_proposal(store, id)?.ok_or_else(|| format!("Proposal not found: {}", id))?;
+    if proposal.status != "pending" {
+        return Err(format!("Proposal {} is already {}", id, proposal.status).into());
+    }
+    let version = if approve {
+        store.set_agent_file_as(
+            &proposal.agent_id,
+            &proposal.file_name,
+            &proposal.proposed_content,
+            "agent",
+        )?
+    } else {
+        None
+    };
+    let conn = store.conn.lock();
+    conn.execute(
+        "UPDATE soul_proposals SET status = ?2 WHERE id = ?1",
+        params![id, if approve { "applied" } else { "rejected" }],
+    )?;
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn soul(content: &str) -> AgentFile {
+        AgentFile {
+            agent_id: "a1".into(),
+            file_name: "SOUL.md".into(),
+            content: content.into(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn parse_filters_disallowed_and_unchanged() {
+        let text = r#"Sure:
+```json
+[{"file_name":"SOUL.md","content":"calm","rationale":"same"},
+ {"file_name":"USER.md","content":"x","rationale":"not allowed"},
+ {"file_name":"TOOLS.md","content":"prefer rg","rationale":"user asked"}]
+```"#;
+        let parsed = parse_proposals(text, &[soul("calm")]);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].file_name, "TOOLS.md");
+        assert!(parse_proposals("[]", &[]).is_empty());
+        assert!(parse_proposals("no changes", &[]).is_empty());
+    }
+
+    #[test]
+    fn proposals_wait_for_approval() {
+        let store = test_store();
+        store.set_agent_file("a1", "SOUL.md", "calm").unwrap();
+        let queued = queue_proposals(
+            &store,
+            "a1",
+            Some("s1"),
+            vec![ReflectionProposal {
+                file_name: "SOUL.md".into(),
+                content: "calm and brief".into(),
+                rationale: "user asked for shorter replies".into(),
+            }],
+            false,
+        )
+        .unwrap();
+        assert_eq!(queued[0].status, "pending");
+        assert_eq!(
+            store
+                .get_agent_file("a1", "SOUL.md")
+                .unwrap()
+                .unwrap()
+                .content,
+            "calm"
+        );
+        assert_eq!(list_pending(&store, Some("a1")).unwrap().len(), 1);
+
+        assert_eq!(resolve(&store, &queued[0].id, true).unwrap(), Some(2));
+        assert_eq!(
+            store
+                .get_agent_file("a1", "SOUL.md")
+                .unwrap()
+                .unwrap()
+                .content,
+            "calm and brief"
+        );
+        assert!(list_pending(&store, None).unwrap().is_empty());
+        assert!(resolve(&store, &queued[0].id, false).is_err());
+    }
+}
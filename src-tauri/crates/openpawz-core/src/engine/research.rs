@@ -0,0 +1,312 @@
+// Paw Agent Engine — Research run state
+//
+// The research loop (root crate, engine::research) checkpoints a
+// `ResearchState` after every step so an interrupted run resumes where it
+// stopped. This module holds that state and the citation bookkeeping:
+//   - sources are numbered once, in discovery order, deduped by URL;
+//   - every claim carries the source numbers it came from;
+//   - the final report may only cite numbers that exist, and its Sources
+//     section lists exactly the sources it cites.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Where a run is in its loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResearchPhase {
+    #[default]
+    Planning,
+    Gathering,
+    Writing,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubQuestion {
+    pub question: String,
+    /// Set by the reflection step once the claims answer it.
+    #[serde(default)]
+    pub answered: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchSource {
+    /// Citation number, 1-based.
+    pub id: usize,
+    pub url: String,
+    pub title: String,
+    /// Set once the page has been read (or failed to read).
+    #[serde(default)]
+    pub read: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchClaim {
+    pub text: String,
+    /// Index into `sub_questions`.
+    pub sub_question: usize,
+    pub sources: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ResearchState {
+    pub phase: ResearchPhase,
+    /// Completed search/read/reflect cycles.
+    pub round: u32,
+    pub sub_questions: Vec<SubQuestion>,
+    /// Queries waiting to be searched this round.
+    pub pending_queries: Vec<String>,
+    /// Every query already searched, to avoid repeats.
+    pub searched: Vec<String>,
+    pub sources: Vec<ResearchSource>,
+    pub claims: Vec<ResearchClaim>,
+}
+
+impl ResearchState {
+    pub fn pages_read(&self) -> u32 {
+        self.sources
+            .iter()
+            .filter(|s| s.read && s.error.is_none())
+            .count() as u32
+    }
+
+    /// Queue queries that haven't been searched or queued yet.
+    pub fn queue_queries<I: IntoIterator<Item = String>>(&mut self, queries: I) {
+        for q in queries {
+            let q = q.trim().to_string();
+            let key = q.to_lowercase();
+            let known = self
+                .searched
+                .iter()
+                .chain(&self.pending_queries)
+                .any(|s| s.to_lowercase() == key);
+            if !q.is_empty() && !known {
+                self.pending_queries.push(q);
+            }
+        }
+    }
+
+    /// Register a source and return its citation number; a URL seen before
+    /// keeps its number.
+    pub fn add_source(&mut self, url: &str, title: &str) -> usize {
+        let key = url.trim_end_matches('/');
+        if let Some(s) = self
+            .sources
+            .iter()
+            .find(|s| s.url.trim_end_matches('/') == key)
+        {
+            return s.id;
+        }
+        let id = self.sources.len() + 1;
+        self.sources.push(ResearchSource {
+            id,
+            url: url.to_string(),
+            title: title.to_string(),
+            read: false,
+            error: None,
+        });
+        id
+    }
+
+    pub fn source_mut(&mut self, id: usize) -> Option<&mut ResearchSource> {
+        self.sources.iter_mut().find(|s| s.id == id)
+    }
+
+    /// Sources found but not read yet, in discovery order.
+    pub fn unread_sources(&self) -> Vec<usize> {
+        self.sources
+            .iter()
+            .filter(|s| !s.read)
+            .map(|s| s.id)
+            .collect()
+    }
+
+    /// Record a claim; sub-question indexes out of range are clamped to the
+    /// last one so nothing extracted is lost.
+    pub fn add_claim(&mut self, text: &str, sub_question: usize, source: usize) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let sub_question = sub_question.min(self.sub_questions.len().saturating_sub(1));
+        if let Some(existing) = self
+            .claims
+            .iter_mut()
+            .find(|c| c.sub_question == sub_question && c.text.eq_ignore_ascii_case(text))
+        {
+            if !existing.sources.contains(&source) {
+                existing.sources.push(source);
+            }
+            return;
+        }
+        self.claims.push(ResearchClaim {
+            text: text.to_string(),
+            sub_question,
+            sources: vec![source],
+        });
+    }
+
+    /// Claims grouped under their sub-question, each tagged with its
+    /// citation numbers — the material for reflection and the report.
+    pub fn notes(&self) -> String {
+        let mut out = String::new();
+        for (i, sq) in self.sub_questions.iter().enumerate() {
+            out.push_str(&format!("## Q{}: {}\n", i + 1, sq.question));
+            let mut any = false;
+            for c in self.claims.iter().filter(|c| c.sub_question == i) {
+                let cites: Vec<String> = c.sources.iter().map(|n| format!("[{}]", n)).collect();
+                out.push_str(&format!("- {} {}\n", c.text, cites.join("")));
+                any = true;
+            }
+            if !any {
+                out.push_str("- (no findings yet)\n");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Citation numbers used in `text`: "[3]", "[1, 4]", "[2][5]".
+pub fn cited_ids(text: &str) -> BTreeSet<usize> {
+    let mut ids = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find(']') else { break };
+        let inner = &rest[..end];
+        let nums: Option<Vec<usize>> = inner
+            .split(',')
+            .map(|n| n.trim().parse::<usize>().ok())
+            .collect();
+        if let Some(nums) = nums {
+            ids.extend(nums);
+        }
+        rest = &rest[end + 1..];
+    }
+    ids
+}
+
+/// Drop citations to sources that don't exist (or were never read) and append a Sources section
+/// listing each cited source once, in citation-number order.
+pub fn finalize_report(report: &str, sources: &[ResearchSource]) -> String {
+    let valid: BTreeSet<usize> = sources
+        .iter()
+        .filter(|s| s.read && s.error.is_none())
+        .map(|s| s.id)
+        .collect();
+
+    let mut body = String::with_capacity(report.len());
+    let mut rest = report;
+    while let Some(start) = rest.find('[') {
+        body.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let parsed = after.find(']').and_then(|end| {
+            after[..end]
+                .split(',')
+                .map(|n| n.trim().parse::<usize>().ok())
+                .collect::<Option<Vec<usize>>>()
+                .map(|nums| (end, nums))
+        });
+        match parsed {
+            Some((end, nums)) => {
+                let kept: Vec<String> = nums
+                    .iter()
+                    .filter(|n| valid.contains(n))
+                    .map(|n| n.to_string())
+                    .collect();
+                if !kept.is_empty() {
+                    body.push_str(&format!("[{}]", kept.join(", ")));
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                body.push('[');
+                rest = after;
+            }
+        }
+    }
+    body.push_str(rest);
+
+    let cited = cited_ids(&body);
+    let mut out = body.trim_end().to_string();
+    if !cited.is_empty() {
+        out.push_str("\n\n## Sources\n\n");
+        for s in sources.iter().filter(|s| cited.contains(&s.id)) {
+            let title = if s.title.trim().is_empty() {
+                s.url.as_str()
+            } else {
+                s.title.trim()
+            };
+            out.push_str(&format!("[{}] {} — {}\n", s.id, title, s.url));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ResearchState {
+        ResearchState {
+            sub_questions: vec![
+                SubQuestion {
+                    question: "What is it?".into(),
+                    answered: false,
+                },
+                SubQuestion {
+                    question: "Who uses it?".into(),
+                    answered: false,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sources_and_claims_dedupe() {
+        let mut s = state();
+        assert_eq!(s.add_source("https://a.example/x/", "A"), 1);
+        assert_eq!(s.add_source("https://b.example", "B"), 2);
+        assert_eq!(s.add_source("https://a.example/x", "A again"), 1);
+
+        s.add_claim("Rust is a language", 0, 1);
+        s.add_claim("rust is a language", 0, 2);
+        s.add_claim("Used by many", 9, 2);
+        assert_eq!(s.claims.len(), 2);
+        assert_eq!(s.claims[0].sources, vec![1, 2]);
+        assert_eq!(s.claims[1].sub_question, 1);
+        assert!(s.notes().contains("- Rust is a language [1][2]"));
+
+        s.queue_queries(vec!["rust".into(), "Rust".into(), " ".into()]);
+        assert_eq!(s.pending_queries, vec!["rust"]);
+    }
+
+    #[test]
+    fn report_citations_are_checked() {
+        let mut s = state();
+        s.add_source("https://a.example", "A");
+        s.add_source("https://b.example", "");
+        s.add_source("https://c.example", "C");
+        s.add_source("https://d.example", "D");
+        for src in s.sources.iter_mut() {
+            src.read = src.id != 4;
+        }
+        s.source_mut(3).unwrap().error = Some("404".into());
+
+        let report = finalize_report(
+            "Fact one [1]. Fact two [2, 7]. Dead [3][4]. Array a[i] ok.",
+            &s.sources,
+        );
+        assert!(report.starts_with("Fact one [1]. Fact two [2]. Dead . Array a[i] ok."));
+        assert!(report.contains(
+            "## Sources\n\n[1] A — https://a.example\n[2] https://b.example — https://b.example\n"
+        ));
+        assert!(!report.contains("c.example"));
+        assert_eq!(cited_ids("x [1][4, 2] [y]"), BTreeSet::from([1, 2, 4]));
+    }
+}
@@ -1,7 +1,110 @@
 use super::SessionStore;
 use crate::atoms::error::EngineResult;
-use crate::engine::types::AgentFile;
-use rusqlite::params;
+use crate::engine::types::{AgentFile, AgentFileRevision};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// Revisions kept per file; older ones are pruned on write.
+const MAX_AGENT_FILE_REVISIONS: i64 = 50;
+
+/// Latest stored version of a file (0 if it has no history yet).
+fn latest_revision(conn: &Connection, agent_id: &str, file_name: &str) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM agent_file_revisions
+         WHERE agent_id = ?1 AND file_name = ?2",
+        params![agent_id, file_name],
+        |row| row.get(0),
+    )
+}
+
+fn insert_revision(
+    conn: &Connection,
+    agent_id: &str,
+    file_name: &str,
+    version: i64,
+    content: &str,
+    author: &str,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO agent_file_revisions (agent_id, file_name, version, content, author)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![agent_id, file_name, version, content, author],
+    )?;
+    Ok(())
+}
+
+/// How deep `@include` directives may nest.
+const MAX_INCLUDE_DEPTH: usize = 3;
+/// Largest file an `@include` directive will inline.
+const MAX_INCLUDE_BYTES: u64 = 16 * 1024;
+
+/// Expand `@include(path)` lines in a soul file. Each directive must sit on
+/// its own line; `path` is relative to the agent's workspace and may not
+/// leave it (absolute paths, `..` and symlinks pointing outside are refused).
+/// Missing or refused files leave a short note in place of the directive.
+pub fn resolve_includes(content: &str, workspace: &Path) -> String {
+    resolve_includes_at(content, workspace, 0)
+}
+
+fn resolve_includes_at(content: &str, workspace: &Path, depth: usize) -> String {
+    if !content.contains("@include(") {
+        return content.to_string();
+    }
+    content
+        .lines()
+        .map(|line| {
+            let target = line
+                .trim()
+                .strip_prefix("@include(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .map(str::trim);
+            match target {
+                Some(rel) if depth >= MAX_INCLUDE_DEPTH => {
+                    format!("*[include skipped: {} — nested too deep]*", rel)
+                }
+                Some(rel) => match read_include(rel, workspace) {
+                    Ok(text) => resolve_includes_at(&text, workspace, depth + 1),
+                    Err(why) => {
+                        log::warn!("[engine] @include({}) skipped: {}", rel, why);
+                        format!("*[include skipped: {} — {}]*", rel, why)
+                    }
+                },
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn read_include(rel: &str, workspace: &Path) -> Result<String, &'static str> {
+    let rel_path = Path::new(rel);
+    if rel.is_empty()
+        || rel_path.is_absolute()
+        || rel_path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err("path must stay inside the agent workspace");
+    }
+    let root = workspace
+        .canonicalize()
+        .map_err(|_| "agent workspace not found")?;
+    let full = root
+        .join(rel_path)
+        .canonicalize()
+        .map_err(|_| "file not found")?;
+    if !full.starts_with(&root) {
+        return Err("path must stay inside the agent workspace");
+    }
+    let meta = std::fs::metadata(&full).map_err(|_| "file not found")?;
+    if !meta.is_file() {
+        return Err("not a file");
+    }
+    if meta.len() > MAX_INCLUDE_BYTES {
+        return Err("file larger than 16 KB");
+    }
+    std::fs::read_to_string(&full).map_err(|_| "file is not valid UTF-8 text")
+}
 
 impl SessionStore {
     // ── Agent Files (Soul / Persona) ───────────────────────────────────
@@ -50,19 +153,116 @@ impl SessionStore {
         }
     }
 
+    /// Write an agent file as the user. See `set_agent_file_as`.
     pub fn set_agent_file(
         &self,
         agent_id: &str,
         file_name: &str,
         content: &str,
     ) -> EngineResult<()> {
+        self.set_agent_file_as(agent_id, file_name, content, "user")
+            .map(|_| ())
+    }
+
+    /// Write an agent file and record the change as a new revision by
+    /// `author` ("user" or "agent"). Returns the new version, or `None`
+    /// when the content was unchanged and no revision was recorded.
+    pub fn set_agent_file_as(
+        &self,
+        agent_id: &str,
+        file_name: &str,
+        content: &str,
+        author: &str,
+    ) -> EngineResult<Option<i64>> {
         let conn = self.conn.lock();
+        let previous: Option<String> = conn
+            .query_row(
+                "SELECT content FROM agent_files WHERE agent_id = ?1 AND file_name = ?2",
+                params![agent_id, file_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if previous.as_deref() == Some(content) {
+            return Ok(None);
+        }
+
         conn.execute(
             "INSERT OR REPLACE INTO agent_files (agent_id, file_name, content, updated_at)
              VALUES (?1, ?2, ?3, datetime('now'))",
             params![agent_id, file_name, content],
         )?;
-        Ok(())
+
+        let mut version = latest_revision(&conn, agent_id, file_name)?;
+        // Files written before history existed: keep their content as v1
+        if version == 0 {
+            if let Some(prev) = previous.as_deref().filter(|p| !p.is_empty()) {
+                version = 1;
+                insert_revision(&conn, agent_id, file_name, version, prev, "user")?;
+            }
+        }
+        version += 1;
+        insert_revision(&conn, agent_id, file_name, version, content, author)?;
+        conn.execute(
+            "DELETE FROM agent_file_revisions
+             WHERE agent_id = ?1 AND file_name = ?2 AND version <= ?3",
+            params![agent_id, file_name, version - MAX_AGENT_FILE_REVISIONS],
+        )?;
+        Ok(Some(version))
+    }
+
+    /// Revisions of an agent file, newest first.
+    pub fn list_agent_file_revisions(
+        &self,
+        agent_id: &str,
+        file_name: &str,
+    ) -> EngineResult<Vec<AgentFileRevision>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT agent_id, file_name, version, content, author, created_at
+             FROM agent_file_revisions WHERE agent_id = ?1 AND file_name = ?2
+             ORDER BY version DESC",
+        )?;
+        let revisions = stmt
+            .query_map(params![agent_id, file_name], |row| {
+                Ok(AgentFileRevision {
+                    agent_id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    version: row.get(2)?,
+                    content: row.get(3)?,
+                    author: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(revisions)
+    }
+
+    /// Restore an earlier revision. The restored content is written as a
+    /// new revision by the user, so the revert itself stays in history.
+    pub fn revert_agent_file(
+        &self,
+        agent_id: &str,
+        file_name: &str,
+        version: i64,
+    ) -> EngineResult<Option<i64>> {
+        let content: Option<String> = {
+            let conn = self.conn.lock();
+            conn.query_row(
+                "SELECT content FROM agent_file_revisions
+                 WHERE agent_id = ?1 AND file_name = ?2 AND version = ?3",
+                params![agent_id, file_name, version],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+        let content = content.ok_or_else(|| {
+            format!(
+                "No revision {} of {} for agent {}",
+                version, file_name, agent_id
+            )
+        })?;
+        self.set_agent_file_as(agent_id, file_name, &content, "user")
     }
 
     pub fn delete_agent_file(&self, agent_id: &str, file_name: &str) -> EngineResult<()> {
@@ -81,20 +281,21 @@ impl SessionStore {
         if files.is_empty() {
             return Ok(None);
         }
+        let workspace = crate::engine::paths::agent_workspace_dir(agent_id);
         // Compose in a specific order: IDENTITY → SOUL → USER → AGENTS → TOOLS
         let order = ["IDENTITY.md", "SOUL.md", "USER.md", "AGENTS.md", "TOOLS.md"];
         let mut sections = Vec::new();
         for name in &order {
             if let Some(f) = files.iter().find(|f| f.file_name == *name) {
                 if !f.content.trim().is_empty() {
-                    sections.push(f.content.clone());
+                    sections.push(resolve_includes(&f.content, &workspace));
                 }
             }
         }
         // Also include any non-standard files
         for f in &files {
             if !order.contains(&f.file_name.as_str()) && !f.content.trim().is_empty() {
-                sections.push(f.content.clone());
+                sections.push(resolve_includes(&f.content, &workspace));
             }
         }
         if sections.is_empty() {
@@ -112,9 +313,11 @@ impl SessionStore {
     pub fn compose_core_context(&self, agent_id: &str) -> EngineResult<Option<String>> {
         const MAX_SOUL_FILE_CHARS: usize = 3000;
         let core_files = ["IDENTITY.md", "SOUL.md", "USER.md"];
+        let workspace = crate::engine::paths::agent_workspace_dir(agent_id);
         let mut sections = Vec::new();
         for name in &core_files {
-            if let Ok(Some(f)) = self.get_agent_file(agent_id, name) {
+            if let Ok(Some(mut f)) = self.get_agent_file(agent_id, name) {
+                f.content = resolve_includes(&f.content, &workspace);
                 if !f.content.trim().is_empty() {
                     if f.content.len() > MAX_SOUL_FILE_CHARS {
                         let truncated =
@@ -141,3 +344,71 @@ impl SessionStore {
         Ok(Some(sections.join("\n\n---\n\n")))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn includes_resolve_inside_workspace_only() {
+        let dir = std::env::temp_dir().join(format!("paw-include-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+        std::fs::write(dir.join("notes/style.md"), "Be brief.\n@include(loop.md)").unwrap();
+        std::fs::write(dir.join("loop.md"), "@include(loop.md)").unwrap();
+
+        let out = resolve_includes("# Soul\n@include(notes/style.md)\nEnd", &dir);
+        assert!(out.starts_with("# Soul\nBe brief.\n"));
+        assert!(out.contains("nested too deep"));
+        assert!(out.ends_with("\nEnd"));
+
+        for bad in [
+            "@include(../secret)",
+            "@include(/etc/passwd)",
+            "@include(missing.md)",
+        ] {
+            let out = resolve_includes(bad, &dir);
+            assert!(out.starts_with("*[include skipped:"), "{}", out);
+        }
+        assert_eq!(resolve_includes("no directives", &dir), "no directives");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn writes_are_versioned_and_revertible() {
+        let store = test_store();
+        store.set_agent_file("a1", "SOUL.md", "calm").unwrap();
+        assert_eq!(
+            store
+                .set_agent_file_as("a1", "SOUL.md", "snarky", "agent")
+                .unwrap(),
+            Some(2)
+        );
+        // Unchanged content doesn't add a revision
+        assert_eq!(
+            store
+                .set_agent_file_as("a1", "SOUL.md", "snarky", "agent")
+                .unwrap(),
+            None
+        );
+
+        let history = store.list_agent_file_revisions("a1", "SOUL.md").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].author, "agent");
+        assert_eq!(history[1].content, "calm");
+
+        assert_eq!(
+            store.revert_agent_file("a1", "SOUL.md", 1).unwrap(),
+            Some(3)
+        );
+        let file = store.get_agent_file("a1", "SOUL.md").unwrap().unwrap();
+        assert_eq!(file.content, "calm");
+        assert!(store.revert_agent_file("a1", "SOUL.md", 9).is_err());
+    }
+}
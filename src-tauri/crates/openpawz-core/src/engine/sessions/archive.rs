@@ -0,0 +1,457 @@
+// Session cold storage.
+//
+// Sessions idle past a threshold move, with their messages and citations,
+// out of engine.db into one SQLite file per month of last activity
+// (`{dir}/sessions-YYYY-MM.db`). That keeps the hot DB small; archived
+// sessions drop out of the normal listings but can be listed, searched and
+// restored from their archive file.
+//
+// A transaction over the ATTACHed archive isn't atomic across the two
+// files while engine.db is in WAL mode, so a move takes two: the rows are
+// copied into the target file and committed, then deleted from the source.
+// A crash in between leaves a session in both places — never in neither —
+// and the copy is an upsert, so the next archive or restore finishes it.
+
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::{ArchivedSession, Session, SessionArchiveReport};
+use log::info;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+/// Tables that move with a session: (table, session column, unique key),
+/// parent first.
+const TABLES: [(&str, &str, &str); 3] = [
+    ("sessions", "id", "id"),
+    ("messages", "session_id", "id"),
+    ("message_citations", "session_id", "run_id"),
+];
+
+const SESSION_COLUMNS: &str =
+    "id, label, model, system_prompt, created_at, updated_at, message_count, agent_id";
+
+fn archive_path(dir: &Path, month: &str) -> PathBuf {
+    dir.join(format!("sessions-{}.db", month))
+}
+
+/// Archive files in `dir` as (month, path), newest month first.
+fn archives(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let month = name.strip_prefix("sessions-")?.strip_suffix(".db")?;
+            Some((month.to_string(), e.path()))
+        })
+        .collect();
+    found.sort_by(|a, b| b.0.cmp(&a.0));
+    found
+}
+
+fn contains_session(path: &Path, session_id: &str) -> bool {
+    open_read_only(path)
+        .and_then(|conn| {
+            Ok(conn
+                .query_row("SELECT 1 FROM sessions WHERE id = ?1", [session_id], |_| {
+                    Ok(())
+                })
+                .optional()?)
+        })
+        .is_ok_and(|found| found.is_some())
+}
+
+fn open_read_only(path: &Path) -> EngineResult<Connection> {
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+fn columns(conn: &Connection, schema: &str, table: &str) -> EngineResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Copy the rows of `table` belonging to the sessions in
+/// `temp.archive_batch` from schema `from` to `to`, creating or widening the
+/// target table first.
+fn copy_rows(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    (table, session_col, key): (&str, &str, &str),
+) -> EngineResult<usize> {
+    let source = columns(conn, from, table)?;
+    if source.is_empty() {
+        return Ok(0);
+    }
+    let mut target = columns(conn, to, table)?;
+    if target.is_empty() {
+        conn.execute_batch(&format!(
+            "CREATE TABLE {to}.{table} AS SELECT * FROM {from}.{table} WHERE 0;
+             CREATE UNIQUE INDEX {to}.{table}_{key} ON {table}({key});"
+        ))?;
+        target = source.clone();
+    }
+    if to == "archive" {
+        // Columns added to engine.db since this archive was created
+        let missing: Vec<String> = source
+            .iter()
+            .filter(|c| !target.contains(c))
+            .cloned()
+            .collect();
+        for col in missing {
+            conn.execute_batch(&format!("ALTER TABLE {to}.{table} ADD COLUMN \"{col}\""))?;
+            target.push(col);
+        }
+    }
+    let shared = source
+        .iter()
+        .filter(|c| target.contains(c))
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {to}.{table} ({shared})
+             SELECT {shared} FROM {from}.{table}
+             WHERE {session_col} IN (SELECT id FROM temp.archive_batch)"
+        ),
+        [],
+    )?)
+}
+
+/// Move the sessions `select` puts into `temp.archive_batch`, with their
+/// rows in every table, from schema `from` to `to` — one of them the
+/// archive for `month`, attached as `archive`. Returns the rows moved per
+/// table, in TABLES order.
+fn move_sessions(
+    conn: &Connection,
+    dir: &Path,
+    month: &str,
+    (from, to): (&str, &str),
+    select: impl FnOnce(&Connection) -> EngineResult<()>,
+) -> EngineResult<[usize; 3]> {
+    std::fs::create_dir_all(dir)?;
+    let path = archive_path(dir, month);
+    conn.execute(
+        "ATTACH DATABASE ?1 AS archive",
+        params![path.to_string_lossy()],
+    )?;
+    let result = (|| {
+        // Copy and commit in the target first (parents first) …
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "CREATE TEMP TABLE IF NOT EXISTS archive_batch (id TEXT PRIMARY KEY);
+             DELETE FROM temp.archive_batch;",
+        )?;
+        select(&tx)?;
+        let mut moved = [0; 3];
+        for (count, table) in moved.iter_mut().zip(TABLES) {
+            *count = copy_rows(&tx, from, to, table)?;
+        }
+        tx.commit()?;
+
+        // … then delete from the source, children first
+        let tx = conn.unchecked_transaction()?;
+        for (table, session_col, _) in TABLES.iter().rev() {
+            if !columns(&tx, from, table)?.is_empty() {
+                tx.execute(
+                    &format!(
+                        "DELETE FROM {from}.{table}
+                         WHERE {session_col} IN (SELECT id FROM temp.archive_batch)"
+                    ),
+                    [],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(moved)
+    })();
+    conn.execute("DETACH DATABASE archive", [])?;
+    result
+}
+
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<Session> {
+    Ok(Session {
+        id: row.get(0)?,
+        label: row.get(1)?,
+        model: row.get(2)?,
+        system_prompt: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        message_count: row.get(6)?,
+        agent_id: row.get(7)?,
+    })
+}
+
+/// Up to ~120 characters of `text` around the first match of `query`.
+fn snippet(text: &str, query: &str) -> String {
+    let lower = text.to_lowercase();
+    let at = lower
+        .find(&query.to_lowercase())
+        .map(|i| lower[..i].chars().count())
+        .unwrap_or(0);
+    let start = at.saturating_sub(40);
+    let chars: String = text.chars().skip(start).take(120).collect();
+    let mut out = chars.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        out.insert(0, '…');
+    }
+    if text.chars().count() > start + 120 {
+        out.push('…');
+    }
+    out
+}
+
+impl SessionStore {
+    // ── Cold storage ───────────────────────────────────────────────────
+
+    /// Move sessions with no activity for `older_than_days` into the
+    /// per-month archives in `dir`.
+    pub fn archive_sessions(
+        &self,
+        dir: &Path,
+        older_than_days: u32,
+    ) -> EngineResult<SessionArchiveReport> {
+        let conn = self.conn.lock();
+        let cutoff = format!("-{} days", older_than_days);
+        let months = {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT strftime('%Y-%m', updated_at) FROM sessions
+                 WHERE updated_at < datetime('now', ?1)
+                 ORDER BY 1",
+            )?;
+            let months = stmt
+                .query_map(params![cutoff], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            months
+        };
+
+        let mut report = SessionArchiveReport::default();
+        for month in months {
+            let [sessions, messages, _] =
+                move_sessions(&conn, dir, &month, ("main", "archive"), |tx| {
+                    tx.execute(
+                        "INSERT INTO temp.archive_batch SELECT id FROM main.sessions
+                         WHERE updated_at < datetime('now', ?1)
+                           AND strftime('%Y-%m', updated_at) = ?2",
+                        params![cutoff, month],
+                    )?;
+                    Ok(())
+                })?;
+            report.sessions += sessions;
+            report.messages += messages;
+            report.archives.push(month);
+        }
+        if report.sessions > 0 {
+            info!(
+                "[sessions] Archived {} session(s), {} message(s) into {}",
+                report.sessions,
+                report.messages,
+                report.archives.join(", ")
+            );
+        }
+        Ok(report)
+    }
+
+    /// Move an archived session (and its messages) back into engine.db.
+    pub fn restore_archived_session(&self, dir: &Path, session_id: &str) -> EngineResult<Session> {
+        let month = archives(dir)
+            .into_iter()
+            .find(|(_, path)| contains_session(path, session_id))
+            .map(|(month, _)| month)
+            .ok_or_else(|| format!("No archived session '{}'", session_id))?;
+
+        {
+            let conn = self.conn.lock();
+            move_sessions(&conn, dir, &month, ("archive", "main"), |tx| {
+                tx.execute("INSERT INTO temp.archive_batch VALUES (?1)", [session_id])?;
+                Ok(())
+            })?;
+        }
+        info!("[sessions] Restored {} from archive {}", session_id, month);
+        self.get_session(session_id)?
+            .ok_or_else(|| format!("Session '{}' was not restored", session_id).into())
+    }
+}
+
+/// Archived sessions, newest first — from one month's archive ("2026-03")
+/// or all of them.
+pub fn list_archived_sessions(
+    dir: &Path,
+    month: Option<&str>,
+) -> EngineResult<Vec<ArchivedSession>> {
+    let mut out = Vec::new();
+    for (archive, path) in archives(dir) {
+        if month.is_some_and(|m| m != archive) {
+            continue;
+        }
+        let conn = open_read_only(&path)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM sessions ORDER BY updated_at DESC",
+            SESSION_COLUMNS
+        ))?;
+        let sessions = stmt
+            .query_map([], session_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        out.extend(sessions.into_iter().map(|session| ArchivedSession {
+            archive: archive.clone(),
+            session,
+            snippet: None,
+        }));
+    }
+    Ok(out)
+}
+
+/// Search archived session labels and message text (case-insensitive),
+/// newest first, with a snippet around the first matching message.
+pub fn search_archived_sessions(
+    dir: &Path,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<ArchivedSession>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    for (archive, path) in archives(dir) {
+        if out.len() >= limit {
+            break;
+        }
+        let conn = open_read_only(&path)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, (SELECT m.content FROM messages m
+                         WHERE m.session_id = sessions.id
+                           AND instr(lower(m.content), lower(?1)) > 0
+                         ORDER BY m.created_at LIMIT 1) AS hit
+             FROM sessions
+             WHERE instr(lower(COALESCE(label, '')), lower(?1)) > 0 OR hit IS NOT NULL
+             ORDER BY updated_at DESC
+             LIMIT ?2",
+            SESSION_COLUMNS
+        ))?;
+        let hits = stmt
+            .query_map(params![query, (limit - out.len()) as i64], |row| {
+                Ok((session_from_row(row)?, row.get::<_, Option<String>>(8)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        out.extend(hits.into_iter().map(|(session, hit)| ArchivedSession {
+            archive: archive.clone(),
+            snippet: hit.map(|text| snippet(&text, query)),
+            session,
+        }));
+    }
+    Ok(out)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(store: &SessionStore) {
+        let conn = store.conn.lock();
+        conn.execute_batch(
+            "INSERT INTO sessions (id, label, model, updated_at, message_count) VALUES
+                ('old-jan', 'Tax questions', 'gpt-4o', '2025-01-10 09:00:00', 2),
+                ('old-feb', NULL, 'gpt-4o', '2025-02-03 09:00:00', 1),
+                ('fresh', 'Today', 'gpt-4o', datetime('now'), 1);
+             INSERT INTO messages (id, session_id, role, content) VALUES
+                ('m1', 'old-jan', 'user', 'How do I file my 2024 taxes?'),
+                ('m2', 'old-jan', 'assistant', 'Start with your W-2.'),
+                ('m3', 'old-feb', 'user', 'Plan a trip to Lisbon'),
+                ('m4', 'fresh', 'user', 'Hello');
+             INSERT INTO message_citations (run_id, session_id, citations) VALUES
+                ('r1', 'old-jan', '[]');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn archive_search_and_restore() {
+        let dir = std::env::temp_dir().join(format!("paw-archive-{}", uuid::Uuid::new_v4()));
+        let store = SessionStore::open_in_memory().unwrap();
+        seed(&store);
+
+        let report = store.archive_sessions(&dir, 90).unwrap();
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.messages, 3);
+        assert_eq!(report.archives, ["2025-01", "2025-02"]);
+        let hot: Vec<_> = store.list_sessions(10).unwrap();
+        assert_eq!(hot.len(), 1);
+        assert_eq!(hot[0].id, "fresh");
+        assert!(store.get_messages("old-jan", 10).unwrap().is_empty());
+
+        let all = list_archived_sessions(&dir, None).unwrap();
+        let ids: Vec<_> = all
+            .iter()
+            .map(|a| (a.archive.as_str(), a.session.id.as_str()))
+            .collect();
+        assert_eq!(ids, [("2025-02", "old-feb"), ("2025-01", "old-jan")]);
+
+        let hits = search_archived_sessions(&dir, "w-2", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session.id, "old-jan");
+        assert_eq!(hits[0].snippet.as_deref(), Some("Start with your W-2."));
+        let by_label = search_archived_sessions(&dir, "tax", 10).unwrap();
+        assert_eq!(by_label[0].session.id, "old-jan");
+
+        // Nothing left to archive; a second pass is a no-op
+        assert_eq!(store.archive_sessions(&dir, 90).unwrap().sessions, 0);
+
+        let restored = store.restore_archived_session(&dir, "old-jan").unwrap();
+        assert_eq!(restored.label.as_deref(), Some("Tax questions"));
+        assert_eq!(store.get_messages("old-jan", 10).unwrap().len(), 2);
+        assert_eq!(
+            list_archived_sessions(&dir, Some("2025-01")).unwrap().len(),
+            0
+        );
+        assert!(store.restore_archived_session(&dir, "old-jan").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn interrupted_move_is_finished_by_the_next_run() {
+        let dir = std::env::temp_dir().join(format!("paw-archive-{}", uuid::Uuid::new_v4()));
+        let store = SessionStore::open_in_memory().unwrap();
+        seed(&store);
+        store.archive_sessions(&dir, 90).unwrap();
+        // A crash after the copy committed: the session is in both places
+        store
+            .conn
+            .lock()
+            .execute_batch(
+                "INSERT INTO sessions (id, model, updated_at)
+                    VALUES ('old-jan', 'gpt-4o', '2025-01-10 09:00:00');
+                 INSERT INTO messages (id, session_id, role, content) VALUES
+                    ('m1', 'old-jan', 'user', 'How do I file my 2024 taxes?'),
+                    ('m2', 'old-jan', 'assistant', 'Start with your W-2.');",
+            )
+            .unwrap();
+
+        let report = store.archive_sessions(&dir, 90).unwrap();
+        assert_eq!((report.sessions, report.messages), (1, 2));
+        assert!(store.get_messages("old-jan", 10).unwrap().is_empty());
+        let archived = list_archived_sessions(&dir, Some("2025-01")).unwrap();
+        assert_eq!(archived.len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn snippet_centres_on_the_match() {
+        let text = format!("{} needle {}", "a ".repeat(60), "b ".repeat(60));
+        let s = snippet(&text, "NEEDLE");
+        assert!(s.starts_with('…') && s.ends_with('…'));
+        assert!(s.contains("needle"));
+        assert_eq!(snippet("short", "x"), "short");
+    }
+}
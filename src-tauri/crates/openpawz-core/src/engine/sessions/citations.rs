@@ -0,0 +1,96 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::Citation;
+use rusqlite::params;
+use std::collections::HashMap;
+
+impl SessionStore {
+    // ── Message citations ──────────────────────────────────────────────
+
+    /// Record the citations of a completed run.
+    pub fn citations_save(
+        &self,
+        run_id: &str,
+        session_id: &str,
+        citations: &[Citation],
+    ) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO message_citations (run_id, session_id, citations)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(run_id) DO UPDATE SET citations = excluded.citations",
+            params![run_id, session_id, serde_json::to_string(citations)?],
+        )?;
+        Ok(())
+    }
+
+    /// Attach a run's citations to the assistant message that was stored for
+    /// it. A no-op when the run cited nothing.
+    pub fn citations_link_message(&self, run_id: &str, message_id: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE message_citations SET message_id = ?2 WHERE run_id = ?1",
+            params![run_id, message_id],
+        )?;
+        Ok(())
+    }
+
+    /// Citations for a session's stored messages, keyed by message id.
+    pub fn citations_for_session(
+        &self,
+        session_id: &str,
+    ) -> EngineResult<HashMap<String, Vec<Citation>>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT message_id, citations FROM message_citations
+             WHERE session_id = ?1 AND message_id IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(id, json)| Some((id, serde_json::from_str(&json).ok()?)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn citations_follow_their_message() {
+        let store = test_store();
+        let cite = Citation {
+            index: 1,
+            url: "https://a.example/post".into(),
+            title: "A post".into(),
+            quote: Some("The answer is 42.".into()),
+            tool: "web_read".into(),
+        };
+        store
+            .citations_save("run-1", "s1", std::slice::from_ref(&cite))
+            .unwrap();
+        store
+            .citations_save("run-2", "s1", std::slice::from_ref(&cite))
+            .unwrap();
+        assert!(store.citations_for_session("s1").unwrap().is_empty());
+
+        store.citations_link_message("run-1", "msg-1").unwrap();
+        let map = store.citations_for_session("s1").unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["msg-1"], vec![cite]);
+
+        store.clear_messages("s1").unwrap();
+        assert!(store.citations_for_session("s1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::DownloadRecord;
+use rusqlite::params;
+
+const DOWNLOAD_COLUMNS: &str =
+    "id, agent_id, url, path, size_bytes, sha256, content_type, created_at";
+
+fn download_from_row(row: &rusqlite::Row) -> rusqlite::Result<DownloadRecord> {
+    Ok(DownloadRecord {
+        id: row.get(0)?,
+        agent_id: row.get(1)?,
+        url: row.get(2)?,
+        path: row.get(3)?,
+        size_bytes: row.get::<_, i64>(4)? as u64,
+        sha256: row.get(5)?,
+        content_type: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+impl SessionStore {
+    // ── Downloads ──────────────────────────────────────────────────────
+
+    /// Register a finished download. Downloading to the same path again
+    /// replaces the earlier record.
+    pub fn download_record(
+        &self,
+        agent_id: &str,
+        url: &str,
+        path: &str,
+        size_bytes: u64,
+        sha256: &str,
+        content_type: Option<&str>,
+    ) -> EngineResult<DownloadRecord> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM downloads WHERE path = ?1", params![path])?;
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO downloads (id, agent_id, url, path, size_bytes, sha256, content_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                agent_id,
+                url,
+                path,
+                size_bytes as i64,
+                sha256,
+                content_type
+            ],
+        )?;
+        let sql = format!("SELECT {} FROM downloads WHERE id = ?1", DOWNLOAD_COLUMNS);
+        Ok(conn.query_row(&sql, params![id], download_from_row)?)
+    }
+
+    /// Newest first, optionally for one agent.
+    pub fn downloads_list(
+        &self,
+        agent_id: Option<&str>,
+        limit: u32,
+    ) -> EngineResult<Vec<DownloadRecord>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM downloads WHERE (?1 IS NULL OR agent_id = ?1)
+             ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+            DOWNLOAD_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params![agent_id, limit], download_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn downloads_are_registered_per_path() {
+        let store = test_store();
+        store
+            .download_record("a1", "https://x/v1.zip", "/ws/v.zip", 10, "aa", None)
+            .unwrap();
+        store
+            .download_record("a2", "https://x/data.csv", "/ws/data.csv", 5, "bb", None)
+            .unwrap();
+        let again = store
+            .download_record(
+                "a1",
+                "https://x/v2.zip",
+                "/ws/v.zip",
+                12,
+                "cc",
+                Some("application/zip"),
+            )
+            .unwrap();
+
+        let all = store.downloads_list(None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, again.id);
+        assert_eq!(all[0].size_bytes, 12);
+        let a2 = store.downloads_list(Some("a2"), 10).unwrap();
+        assert_eq!(a2.len(), 1);
+        assert_eq!(a2[0].url, "https://x/data.csv");
+    }
+}
@@ -603,6 +603,21 @@ impl SessionStore {
         Ok(count as usize)
     }
 
+    /// Whether a research memory already cites `url` (used to skip pages
+    /// that were indexed before).
+    pub fn engram_has_research_url(&self, url: &str) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        // `source` holds the Debug form of MemorySource, where URLs are quoted
+        let needle = format!("{:?}", url);
+        let found: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM episodic_memories
+                           WHERE source LIKE 'ResearchDiscovery%' AND instr(source, ?1) > 0)",
+            params![needle],
+            |r| r.get(0),
+        )?;
+        Ok(found)
+    }
+
     /// Update episodic memory content and optionally re-embed.
     pub fn engram_update_episodic_content(
         &self,
@@ -0,0 +1,142 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::{ModelFeedbackStats, RunFeedback};
+use rusqlite::{params, OptionalExtension};
+
+const FEEDBACK_COLUMNS: &str = "run_id, session_id, agent_id, model, rating, comment, created_at";
+
+fn feedback_from_row(row: &rusqlite::Row) -> rusqlite::Result<RunFeedback> {
+    Ok(RunFeedback {
+        run_id: row.get(0)?,
+        session_id: row.get(1)?,
+        agent_id: row.get(2)?,
+        model: row.get(3)?,
+        rating: row.get(4)?,
+        comment: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl SessionStore {
+    // ── Run feedback ───────────────────────────────────────────────────
+
+    /// Record (or replace) the user's rating of a run. Session, agent and
+    /// model are looked up from the run's telemetry.
+    pub fn feedback_submit(
+        &self,
+        run_id: &str,
+        rating: i64,
+        comment: &str,
+    ) -> EngineResult<RunFeedback> {
+        let conn = self.conn.lock();
+        let run: Option<(String, String, Option<String>)> = conn
+            .query_row(
+                "SELECT m.session_id, m.model, s.agent_id
+                 FROM telemetry_metrics m LEFT JOIN sessions s ON s.id = m.session_id
+                 WHERE m.run_id = ?1 ORDER BY m.id DESC LIMIT 1",
+                params![run_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let (session_id, model, agent_id) = match run {
+            Some((session, model, agent)) => (Some(session), Some(model), agent),
+            None => (None, None, None),
+        };
+        conn.execute(
+            "INSERT INTO run_feedback (run_id, session_id, agent_id, model, rating, comment)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(run_id) DO UPDATE SET
+                rating = excluded.rating, comment = excluded.comment,
+                created_at = datetime('now')",
+            params![run_id, session_id, agent_id, model, rating, comment],
+        )?;
+        let sql = format!(
+            "SELECT {} FROM run_feedback WHERE run_id = ?1",
+            FEEDBACK_COLUMNS
+        );
+        Ok(conn.query_row(&sql, params![run_id], feedback_from_row)?)
+    }
+
+    pub fn feedback_get(&self, run_id: &str) -> EngineResult<Option<RunFeedback>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM run_feedback WHERE run_id = ?1",
+            FEEDBACK_COLUMNS
+        );
+        Ok(conn
+            .query_row(&sql, params![run_id], feedback_from_row)
+            .optional()?)
+    }
+
+    /// Approval per model over the last `days` days, most-rated first.
+    pub fn feedback_model_stats(&self, days: u32) -> EngineResult<Vec<ModelFeedbackStats>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT f.model, COUNT(*), SUM(f.rating > 0), SUM(f.rating < 0),
+                    COALESCE(AVG(m.cost_usd), 0.0)
+             FROM run_feedback f LEFT JOIN telemetry_metrics m ON m.run_id = f.run_id
+             WHERE f.model IS NOT NULL AND f.created_at >= datetime('now', ?1)
+             GROUP BY f.model
+             ORDER BY COUNT(*) DESC, f.model",
+        )?;
+        let rows = stmt
+            .query_map(params![format!("-{} days", days)], |row| {
+                let rated: i64 = row.get(1)?;
+                let positive: i64 = row.get(2)?;
+                Ok(ModelFeedbackStats {
+                    model: row.get(0)?,
+                    rated_runs: rated as u32,
+                    positive: positive as u32,
+                    negative: row.get::<_, i64>(3)? as u32,
+                    approval_rate: positive as f64 / rated.max(1) as f64,
+                    avg_cost_usd: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn feedback_is_attributed_to_the_run_model() {
+        let store = test_store();
+        store
+            .create_session("s1", "model-a", None, Some("writer"))
+            .unwrap();
+        for (run, model, cost) in [("r1", "model-a", 0.02), ("r2", "model-b", 0.01)] {
+            store
+                .record_metric("2026-10-15", "s1", model, 100, 50, cost, 0, 0, 10, 10, 1)
+                .unwrap();
+            store.tag_latest_metric_run("s1", run).unwrap();
+        }
+
+        let fb = store.feedback_submit("r1", -1, "Too verbose").unwrap();
+        assert_eq!(fb.model.as_deref(), Some("model-a"));
+        assert_eq!(fb.agent_id.as_deref(), Some("writer"));
+        store.feedback_submit("r1", 1, "").unwrap();
+        store.feedback_submit("r2", -1, "").unwrap();
+        let unknown = store.feedback_submit("r-old", 1, "").unwrap();
+        assert!(unknown.model.is_none());
+        assert_eq!(store.feedback_get("r1").unwrap().unwrap().rating, 1);
+
+        let stats = store.feedback_model_stats(30).unwrap();
+        assert_eq!(stats.len(), 2);
+        let a = stats.iter().find(|s| s.model == "model-a").unwrap();
+        assert_eq!((a.rated_runs, a.positive, a.negative), (1, 1, 0));
+        assert!((a.avg_cost_usd - 0.02).abs() < 1e-9);
+        let b = stats.iter().find(|s| s.model == "model-b").unwrap();
+        assert_eq!(b.approval_rate, 0.0);
+    }
+}
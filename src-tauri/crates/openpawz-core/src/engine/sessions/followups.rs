@@ -0,0 +1,115 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::Followup;
+use rusqlite::params;
+
+const FOLLOWUP_COLUMNS: &str = "id, session_id, agent_id, note, due_at, status, created_at";
+
+fn followup_from_row(row: &rusqlite::Row) -> rusqlite::Result<Followup> {
+    Ok(Followup {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        agent_id: row.get(2)?,
+        note: row.get(3)?,
+        due_at: row.get(4)?,
+        status: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl SessionStore {
+    // ── Follow-ups ─────────────────────────────────────────────────────
+
+    /// Schedule a one-shot follow-up. `due_at` is RFC 3339.
+    pub fn create_followup(
+        &self,
+        session_id: &str,
+        agent_id: &str,
+        note: &str,
+        due_at: &str,
+    ) -> EngineResult<String> {
+        let conn = self.conn.lock();
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO followups (id, session_id, agent_id, note, due_at, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'pending')",
+            params![id, session_id, agent_id, note, due_at],
+        )?;
+        Ok(id)
+    }
+
+    /// Pending follow-ups, optionally limited to one session, soonest first.
+    pub fn list_followups(&self, session_id: Option<&str>) -> EngineResult<Vec<Followup>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM followups
+             WHERE status = 'pending' AND (?1 IS NULL OR session_id = ?1)
+             ORDER BY due_at ASC",
+            FOLLOWUP_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![session_id], followup_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Pending follow-ups whose due time has passed.
+    pub fn get_due_followups(&self) -> EngineResult<Vec<Followup>> {
+        let conn = self.conn.lock();
+        let now = chrono::Utc::now().to_rfc3339();
+        let sql = format!(
+            "SELECT {} FROM followups
+             WHERE status = 'pending' AND due_at <= ?1
+             ORDER BY due_at ASC",
+            FOLLOWUP_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![now], followup_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Move a pending follow-up to a final status. Returns false if it was
+    /// not pending (already fired or cancelled).
+    pub fn finish_followup(&self, id: &str, status: &str) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        let changed = conn.execute(
+            "UPDATE followups SET status = ?2 WHERE id = ?1 AND status = 'pending'",
+            params![id, status],
+        )?;
+        Ok(changed > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn due_followups_fire_once() {
+        let store = test_store();
+        let past = (chrono::Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        let due = store
+            .create_followup("s1", "a1", "check CI", &past)
+            .unwrap();
+        store.create_followup("s1", "a1", "later", &future).unwrap();
+
+        let found = store.get_due_followups().unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, due);
+        assert_eq!(found[0].note, "check CI");
+
+        assert!(store.finish_followup(&due, "fired").unwrap());
+        assert!(!store.finish_followup(&due, "fired").unwrap());
+        assert!(store.get_due_followups().unwrap().is_empty());
+        assert_eq!(store.list_followups(Some("s1")).unwrap().len(), 1);
+        assert!(store.list_followups(Some("s2")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::CompletedToolCall;
+use rusqlite::{params, OptionalExtension};
+
+impl SessionStore {
+    // ── Tool idempotency keys ──────────────────────────────────────────
+
+    /// The completed call recorded under `key`, unless it is older than
+    /// `ttl_hours`.
+    pub fn idempotency_get(
+        &self,
+        key: &str,
+        ttl_hours: u32,
+    ) -> EngineResult<Option<CompletedToolCall>> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT key, tool_name, session_id, output, completed_at FROM tool_idempotency
+                 WHERE key = ?1 AND completed_at >= datetime('now', ?2)",
+                params![key, format!("-{} hours", ttl_hours)],
+                |row| {
+                    Ok(CompletedToolCall {
+                        key: row.get(0)?,
+                        tool_name: row.get(1)?,
+                        session_id: row.get(2)?,
+                        output: row.get(3)?,
+                        completed_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Record a completed side-effect call, dropping keys older than
+    /// `ttl_hours` on the way.
+    pub fn idempotency_record(
+        &self,
+        key: &str,
+        tool_name: &str,
+        session_id: &str,
+        output: &str,
+        ttl_hours: u32,
+    ) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM tool_idempotency WHERE completed_at < datetime('now', ?1)",
+            params![format!("-{} hours", ttl_hours)],
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO tool_idempotency (key, tool_name, session_id, output)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![key, tool_name, session_id, output],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn completed_calls_expire_after_the_ttl() {
+        let store = test_store();
+        assert!(store.idempotency_get("run-1#0", 24).unwrap().is_none());
+        store
+            .idempotency_record("run-1#0", "email_send", "s1", "Sent", 24)
+            .unwrap();
+        let done = store.idempotency_get("run-1#0", 24).unwrap().unwrap();
+        assert_eq!(
+            (done.tool_name.as_str(), done.output.as_str()),
+            ("email_send", "Sent")
+        );
+
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE tool_idempotency SET completed_at = datetime('now', '-2 days')",
+                [],
+            )
+            .unwrap();
+        assert!(store.idempotency_get("run-1#0", 24).unwrap().is_none());
+        store
+            .idempotency_record("run-2#0", "email_send", "s1", "Sent", 24)
+            .unwrap();
+        let rows: i64 = store
+            .conn
+            .lock()
+            .query_row("SELECT COUNT(*) FROM tool_idempotency", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(rows, 1, "expired keys are pruned");
+    }
+}
@@ -0,0 +1,493 @@
+use super::SessionStore;
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::types::ImportOptions;
+use rusqlite::params;
+use serde_json::Value;
+
+/// Assistant whose data export is being imported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportSource {
+    /// ChatGPT "Export data" — `conversations.json` with a message tree per chat.
+    ChatGpt,
+    /// Claude "Export data" — `conversations.json` with a flat `chat_messages` list.
+    Claude,
+}
+
+impl std::str::FromStr for ImportSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chatgpt" => Ok(Self::ChatGpt),
+            "claude" => Ok(Self::Claude),
+            other => Err(format!(
+                "Unknown import source '{}' (expected chatgpt or claude)",
+                other
+            )),
+        }
+    }
+}
+
+impl ImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChatGpt => "chatgpt",
+            Self::Claude => "claude",
+        }
+    }
+
+    /// Guess the export format from the shape of its first conversation.
+    pub fn detect(export: &Value) -> Option<Self> {
+        let first = export.as_array()?.first()?;
+        if first.get("mapping").is_some() {
+            Some(Self::ChatGpt)
+        } else if first.get("chat_messages").is_some() {
+            Some(Self::Claude)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedMessage {
+    pub role: String, // user, assistant
+    pub content: String,
+    /// SQLite `datetime` format, like the rest of the messages table.
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedConversation {
+    /// The conversation's ID in the source export.
+    pub source_id: String,
+    pub title: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// Model slug recorded by the source, if any.
+    pub model: Option<String>,
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// Parse a `conversations.json` export. Conversations without any
+/// user/assistant text are dropped.
+pub fn parse_export(
+    source: ImportSource,
+    export: &Value,
+) -> EngineResult<Vec<ImportedConversation>> {
+    let list = export.as_array().ok_or_else(|| {
+        EngineError::Other(format!(
+            "Not a {} export: expected a JSON array of conversations",
+            source.as_str()
+        ))
+    })?;
+    let parse = match source {
+        ImportSource::ChatGpt => parse_chatgpt_conversation,
+        ImportSource::Claude => parse_claude_conversation,
+    };
+    Ok(list
+        .iter()
+        .filter_map(parse)
+        .filter(|c| !c.messages.is_empty())
+        .collect())
+}
+
+/// Apply `since` and `limit`, newest conversations first. Returns the
+/// selection and how many were left out.
+pub fn select_conversations(
+    mut conversations: Vec<ImportedConversation>,
+    options: &ImportOptions,
+) -> (Vec<ImportedConversation>, usize) {
+    let total = conversations.len();
+    conversations.sort_by(|a, b| {
+        let ka = a.updated_at.as_ref().or(a.created_at.as_ref());
+        let kb = b.updated_at.as_ref().or(b.created_at.as_ref());
+        kb.cmp(&ka)
+    });
+    if let Some(since) = options.since.as_deref().and_then(normalize_since) {
+        conversations.retain(|c| {
+            c.updated_at
+                .as_ref()
+                .or(c.created_at.as_ref())
+                .is_none_or(|t| *t >= since)
+        });
+    }
+    if let Some(limit) = options.limit {
+        conversations.truncate(limit);
+    }
+    let skipped = total - conversations.len();
+    (conversations, skipped)
+}
+
+/// Session ID for an imported conversation — stable, so re-running an
+/// import skips what is already there.
+pub fn import_session_id(source: ImportSource, source_id: &str) -> String {
+    format!("import-{}-{}", source.as_str(), source_id)
+}
+
+impl SessionStore {
+    // ── Conversation import ────────────────────────────────────────────
+
+    /// Store one imported conversation as a session, keeping the original
+    /// timestamps. Returns the session ID, or None if it was imported before.
+    pub fn import_conversation(
+        &self,
+        source: ImportSource,
+        conversation: &ImportedConversation,
+        options: &ImportOptions,
+    ) -> EngineResult<Option<String>> {
+        let session_id = import_session_id(source, &conversation.source_id);
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let created_at = conversation
+            .created_at
+            .clone()
+            .or_else(|| conversation.messages[0].created_at.clone())
+            .unwrap_or(now);
+        let updated_at = conversation
+            .updated_at
+            .clone()
+            .unwrap_or_else(|| created_at.clone());
+        let label = format!(
+            "{}{}",
+            options.label_prefix.as_deref().unwrap_or(""),
+            conversation.title
+        );
+        let model = options
+            .model
+            .as_deref()
+            .or(conversation.model.as_deref())
+            .unwrap_or("");
+
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO sessions
+                (id, label, model, agent_id, created_at, updated_at, message_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                session_id,
+                label,
+                model,
+                options.agent_id,
+                created_at,
+                updated_at,
+                conversation.messages.len() as i64
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        // Messages without their own timestamp inherit the previous one so
+        // (created_at, rowid) ordering keeps them in place.
+        let mut last_at = created_at;
+        for msg in &conversation.messages {
+            if let Some(at) = &msg.created_at {
+                last_at = at.clone();
+            }
+            tx.execute(
+                "INSERT INTO messages (id, session_id, role, content, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    session_id,
+                    msg.role,
+                    msg.content,
+                    last_at
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(Some(session_id))
+    }
+}
+
+// ── ChatGPT ────────────────────────────────────────────────────────────
+
+/// ChatGPT stores each conversation as a tree (edits and regenerations
+/// branch it). Follow `current_node` back to the root to get the branch the
+/// user last saw.
+fn parse_chatgpt_conversation(conv: &Value) -> Option<ImportedConversation> {
+    let mapping = conv.get("mapping")?.as_object()?;
+    let source_id = conv
+        .get("conversation_id")
+        .or_else(|| conv.get("id"))
+        .and_then(|v| v.as_str())?
+        .to_string();
+
+    let mut messages = Vec::new();
+    let mut model = None;
+    let mut cursor = conv.get("current_node").and_then(|v| v.as_str());
+    // Bounded by the node count in case of a malformed parent cycle
+    for _ in 0..=mapping.len() {
+        let Some(node) = cursor.and_then(|id| mapping.get(id)) else {
+            break;
+        };
+        if let Some(msg) = node.get("message") {
+            if model.is_none() {
+                model = msg
+                    .pointer("/metadata/model_slug")
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
+            }
+            if let Some(m) = chatgpt_message(msg) {
+                messages.push(m);
+            }
+        }
+        cursor = node.get("parent").and_then(|v| v.as_str());
+    }
+    messages.reverse();
+
+    Some(ImportedConversation {
+        source_id,
+        title: conversation_title(conv.get("title")),
+        created_at: conv.get("create_time").and_then(epoch_to_sqlite),
+        updated_at: conv.get("update_time").and_then(epoch_to_sqlite),
+        model,
+        messages,
+    })
+}
+
+fn chatgpt_message(msg: &Value) -> Option<ImportedMessage> {
+    let role = msg.pointer("/author/role")?.as_str()?;
+    if role != "user" && role != "assistant" {
+        return None;
+    }
+    if msg
+        .pointer("/metadata/is_visually_hidden_from_conversation")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    let content = msg.get("content")?;
+    let content_type = content.get("content_type").and_then(|v| v.as_str());
+    if !matches!(content_type, Some("text" | "multimodal_text")) {
+        // Code interpreter runs, browsing results, etc.
+        return None;
+    }
+    // Image/file parts are objects; only the text parts are kept
+    let text = content
+        .get("parts")?
+        .as_array()?
+        .iter()
+        .filter_map(|p| p.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(ImportedMessage {
+        role: role.to_string(),
+        content: text.to_string(),
+        created_at: msg.get("create_time").and_then(epoch_to_sqlite),
+    })
+}
+
+// ── Claude ─────────────────────────────────────────────────────────────
+
+fn parse_claude_conversation(conv: &Value) -> Option<ImportedConversation> {
+    let source_id = conv.get("uuid")?.as_str()?.to_string();
+    let messages = conv
+        .get("chat_messages")?
+        .as_array()?
+        .iter()
+        .filter_map(claude_message)
+        .collect();
+    Some(ImportedConversation {
+        source_id,
+        title: conversation_title(conv.get("name")),
+        created_at: conv.get("created_at").and_then(rfc3339_to_sqlite),
+        updated_at: conv.get("updated_at").and_then(rfc3339_to_sqlite),
+        model: None,
+        messages,
+    })
+}
+
+fn claude_message(msg: &Value) -> Option<ImportedMessage> {
+    let role = match msg.get("sender")?.as_str()? {
+        "human" => "user",
+        "assistant" => "assistant",
+        _ => return None,
+    };
+    // Newer exports split messages into typed content blocks; `text` holds
+    // the flattened version (and is all older exports have).
+    let from_blocks = msg
+        .get("content")
+        .and_then(|c| c.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    let text = if from_blocks.trim().is_empty() {
+        msg.get("text").and_then(|t| t.as_str()).unwrap_or("")
+    } else {
+        from_blocks.as_str()
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(ImportedMessage {
+        role: role.to_string(),
+        content: text.to_string(),
+        created_at: msg.get("created_at").and_then(rfc3339_to_sqlite),
+    })
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────
+
+fn conversation_title(title: Option<&Value>) -> String {
+    title
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Imported conversation")
+        .to_string()
+}
+
+fn epoch_to_sqlite(v: &Value) -> Option<String> {
+    let secs = v.as_f64()?;
+    let dt = chrono::DateTime::from_timestamp(secs.trunc() as i64, 0)?;
+    Some(dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn rfc3339_to_sqlite(v: &Value) -> Option<String> {
+    let dt = chrono::DateTime::parse_from_rfc3339(v.as_str()?).ok()?;
+    Some(
+        dt.with_timezone(&chrono::Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+    )
+}
+
+/// Accept a bare date or a full RFC 3339 timestamp for `since`.
+fn normalize_since(since: &str) -> Option<String> {
+    if let Ok(d) = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Some(format!("{} 00:00:00", d));
+    }
+    rfc3339_to_sqlite(&Value::String(since.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+    use serde_json::json;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn chatgpt_export() -> Value {
+        let node = |id: &str, parent: Option<&str>, role: &str, text: &str, t: f64| {
+            json!({
+                "id": id,
+                "parent": parent,
+                "message": {
+                    "author": { "role": role },
+                    "content": { "content_type": "text", "parts": [text] },
+                    "create_time": t,
+                    "metadata": { "model_slug": "gpt-4o" }
+                }
+            })
+        };
+        json!([{
+            "conversation_id": "c1",
+            "title": "Trip planning",
+            "create_time": 1700000000.5,
+            "update_time": 1700000300.0,
+            "current_node": "a2",
+            "mapping": {
+                "root": { "id": "root", "parent": null, "message": null },
+                "sys": node("sys", Some("root"), "system", "", 1700000000.0),
+                "u1": node("u1", Some("sys"), "user", "I live in Lisbon", 1700000100.0),
+                "a1": node("a1", Some("u1"), "assistant", "Old answer", 1700000200.0),
+                "a2": node("a2", Some("u1"), "assistant", "Noted!", 1700000250.0)
+            }
+        }])
+    }
+
+    #[test]
+    fn parses_both_formats() {
+        let export = chatgpt_export();
+        assert_eq!(ImportSource::detect(&export), Some(ImportSource::ChatGpt));
+        let convs = parse_export(ImportSource::ChatGpt, &export).unwrap();
+        assert_eq!(convs.len(), 1);
+        let c = &convs[0];
+        assert_eq!(c.title, "Trip planning");
+        assert_eq!(c.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(c.created_at.as_deref(), Some("2023-11-14 22:13:20"));
+        // Only the current branch, system message dropped
+        let texts: Vec<_> = c.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(texts, ["I live in Lisbon", "Noted!"]);
+
+        let export = json!([{
+            "uuid": "k1",
+            "name": "",
+            "created_at": "2024-05-01T10:00:00.000000+00:00",
+            "updated_at": "2024-05-01T10:05:00.000000+00:00",
+            "chat_messages": [
+                { "sender": "human", "text": "Hi", "created_at": "2024-05-01T10:00:00Z" },
+                { "sender": "assistant", "text": "flattened",
+                  "content": [{ "type": "text", "text": "Hello!" }, { "type": "tool_use" }],
+                  "created_at": "2024-05-01T10:00:05Z" }
+            ]
+        }]);
+        assert_eq!(ImportSource::detect(&export), Some(ImportSource::Claude));
+        let convs = parse_export(ImportSource::Claude, &export).unwrap();
+        let c = &convs[0];
+        assert_eq!(c.title, "Imported conversation");
+        assert_eq!(c.messages[0].role, "user");
+        assert_eq!(c.messages[1].content, "Hello!");
+        assert_eq!(
+            c.messages[1].created_at.as_deref(),
+            Some("2024-05-01 10:00:05")
+        );
+    }
+
+    #[test]
+    fn import_keeps_timestamps_and_skips_reimport() {
+        let store = test_store();
+        let convs = parse_export(ImportSource::ChatGpt, &chatgpt_export()).unwrap();
+        let options = ImportOptions {
+            label_prefix: Some("[ChatGPT] ".into()),
+            ..Default::default()
+        };
+
+        let id = store
+            .import_conversation(ImportSource::ChatGpt, &convs[0], &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, "import-chatgpt-c1");
+        let session = store.get_session(&id).unwrap().unwrap();
+        assert_eq!(session.label.as_deref(), Some("[ChatGPT] Trip planning"));
+        assert_eq!(session.message_count, 2);
+        assert_eq!(session.created_at, "2023-11-14 22:13:20");
+
+        assert!(store
+            .import_conversation(ImportSource::ChatGpt, &convs[0], &options)
+            .unwrap()
+            .is_none());
+
+        let (selected, skipped) = select_conversations(
+            convs,
+            &ImportOptions {
+                since: Some("2024-01-01".into()),
+                ..Default::default()
+            },
+        );
+        assert!(selected.is_empty());
+        assert_eq!(skipped, 1);
+    }
+}
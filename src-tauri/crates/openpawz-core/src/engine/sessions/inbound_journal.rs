@@ -0,0 +1,125 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::InboundMessage;
+use rusqlite::params;
+
+const INBOUND_COLUMNS: &str = "id, channel, payload, status, attempts, received_at, completed_at";
+
+fn inbound_from_row(row: &rusqlite::Row) -> rusqlite::Result<InboundMessage> {
+    Ok(InboundMessage {
+        id: row.get(0)?,
+        channel: row.get(1)?,
+        payload: row.get(2)?,
+        status: row.get(3)?,
+        attempts: row.get(4)?,
+        received_at: row.get(5)?,
+        completed_at: row.get(6)?,
+    })
+}
+
+impl SessionStore {
+    // ── Inbound journal ────────────────────────────────────────────────
+
+    /// Record a received bridge message before processing it. Returns false
+    /// if `id` was already journaled (a redelivery), in which case the caller
+    /// should skip it — a pending entry is picked up by the startup replay.
+    pub fn journal_inbound(&self, id: &str, channel: &str, payload: &str) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO inbound_journal (id, channel, payload) VALUES (?1, ?2, ?3)",
+            params![id, channel, payload],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Count a processing attempt. Returns the attempt number (1-based).
+    pub fn journal_begin_attempt(&self, id: &str) -> EngineResult<i64> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE inbound_journal SET attempts = attempts + 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(conn.query_row(
+            "SELECT attempts FROM inbound_journal WHERE id = ?1",
+            params![id],
+            |r| r.get(0),
+        )?)
+    }
+
+    /// Mark a journaled message finished (`done` once replied, or `failed`
+    /// when it should not be retried).
+    pub fn journal_complete(&self, id: &str, status: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE inbound_journal SET status = ?2, completed_at = datetime('now') WHERE id = ?1",
+            params![id, status],
+        )?;
+        Ok(())
+    }
+
+    /// Messages on `channel` that were received but never completed, oldest first.
+    pub fn journal_pending(&self, channel: &str) -> EngineResult<Vec<InboundMessage>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM inbound_journal
+             WHERE channel = ?1 AND status = 'pending'
+             ORDER BY received_at ASC, rowid ASC",
+            INBOUND_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![channel], inbound_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Drop completed entries older than `days`. Their dedup keys are only
+    /// needed while the platform might still redeliver them.
+    pub fn journal_prune(&self, days: u32) -> EngineResult<usize> {
+        let conn = self.conn.lock();
+        let n = conn.execute(
+            "DELETE FROM inbound_journal
+             WHERE status != 'pending' AND completed_at < datetime('now', ?1)",
+            params![format!("-{} days", days)],
+        )?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn journal_dedups_and_tracks_pending() {
+        let store = test_store();
+        assert!(store
+            .journal_inbound("telegram:1", "telegram", "{}")
+            .unwrap());
+        assert!(store
+            .journal_inbound("telegram:2", "telegram", "{}")
+            .unwrap());
+        assert!(!store
+            .journal_inbound("telegram:1", "telegram", "{}")
+            .unwrap());
+        assert!(store.journal_inbound("discord:1", "discord", "{}").unwrap());
+
+        assert_eq!(store.journal_begin_attempt("telegram:1").unwrap(), 1);
+        assert_eq!(store.journal_begin_attempt("telegram:1").unwrap(), 2);
+        store.journal_complete("telegram:2", "done").unwrap();
+
+        let pending = store.journal_pending("telegram").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, "telegram:1");
+        assert_eq!(pending[0].attempts, 2);
+
+        // Completed today — not old enough to prune
+        assert_eq!(store.journal_prune(1).unwrap(), 0);
+    }
+}
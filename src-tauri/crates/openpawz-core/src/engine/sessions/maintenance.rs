@@ -0,0 +1,263 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::{DbMaintenanceReport, MigrationRun};
+use rusqlite::params;
+
+/// Maintenance operations exposed through `engine_db_maintenance`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DbMaintenanceAction {
+    /// `PRAGMA integrity_check` — reports corruption, changes nothing.
+    IntegrityCheck,
+    /// Return free pages to the filesystem (auto_vacuum = INCREMENTAL).
+    IncrementalVacuum,
+    /// Rebuild every FTS5 index and REINDEX the b-tree indexes.
+    ReindexFts,
+    /// List recent schema migration runs with timing.
+    MigrationStatus,
+    /// Periodic background pass: quick check, incremental vacuum and FTS
+    /// merge — cheap enough to run unattended.
+    Scheduled,
+}
+
+impl std::str::FromStr for DbMaintenanceAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "integrity_check" => Ok(Self::IntegrityCheck),
+            "incremental_vacuum" => Ok(Self::IncrementalVacuum),
+            "reindex_fts" => Ok(Self::ReindexFts),
+            "migration_status" => Ok(Self::MigrationStatus),
+            "scheduled" => Ok(Self::Scheduled),
+            other => Err(format!(
+                "Unknown maintenance action '{}' (expected integrity_check, incremental_vacuum, reindex_fts, migration_status or scheduled)",
+                other
+            )),
+        }
+    }
+}
+
+impl DbMaintenanceAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::IntegrityCheck => "integrity_check",
+            Self::IncrementalVacuum => "incremental_vacuum",
+            Self::ReindexFts => "reindex_fts",
+            Self::MigrationStatus => "migration_status",
+            Self::Scheduled => "scheduled",
+        }
+    }
+}
+
+impl SessionStore {
+    // ── Database maintenance ───────────────────────────────────────────
+
+    /// Run one maintenance action and report what it found or changed.
+    pub fn run_db_maintenance(
+        &self,
+        action: DbMaintenanceAction,
+    ) -> EngineResult<DbMaintenanceReport> {
+        let started = std::time::Instant::now();
+        let size_before_bytes = self.db_size_bytes()?;
+        let mut report = DbMaintenanceReport {
+            action: action.as_str().into(),
+            ok: true,
+            size_before_bytes,
+            ..Default::default()
+        };
+
+        match action {
+            DbMaintenanceAction::IntegrityCheck => {
+                self.check_integrity("integrity_check", &mut report)?
+            }
+            DbMaintenanceAction::IncrementalVacuum => self.incremental_vacuum(&mut report)?,
+            DbMaintenanceAction::ReindexFts => self.reindex_fts(&mut report)?,
+            DbMaintenanceAction::MigrationStatus => {
+                report.migrations = self.migration_history(20)?;
+                let applied = report
+                    .migrations
+                    .iter()
+                    .filter(|m| !m.changes.is_empty())
+                    .count();
+                report.details.push(format!(
+                    "{} recent migration run(s), {} with schema changes",
+                    report.migrations.len(),
+                    applied
+                ));
+            }
+            DbMaintenanceAction::Scheduled => {
+                self.check_integrity("quick_check", &mut report)?;
+                if report.ok {
+                    self.incremental_vacuum(&mut report)?;
+                    self.optimize_fts(&mut report)?;
+                }
+            }
+        }
+
+        report.size_after_bytes = self.db_size_bytes()?;
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+
+    /// Recent migration runs, newest first.
+    pub fn migration_history(&self, limit: usize) -> EngineResult<Vec<MigrationRun>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT ran_at, duration_ms, changes FROM schema_migrations
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |r| {
+            let changes: String = r.get(2)?;
+            Ok(MigrationRun {
+                ran_at: r.get(0)?,
+                duration_ms: r.get(1)?,
+                changes: serde_json::from_str(&changes).unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    fn db_size_bytes(&self) -> EngineResult<u64> {
+        let conn = self.conn.lock();
+        let pages: i64 = conn.query_row("PRAGMA page_count", [], |r| r.get(0))?;
+        let size: i64 = conn.query_row("PRAGMA page_size", [], |r| r.get(0))?;
+        Ok((pages * size).max(0) as u64)
+    }
+
+    fn check_integrity(&self, pragma: &str, report: &mut DbMaintenanceReport) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!("PRAGMA {}", pragma))?;
+        let lines: Vec<String> = stmt
+            .query_map([], |r| r.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        if lines.len() == 1 && lines[0] == "ok" {
+            report.details.push(format!("{}: ok", pragma));
+        } else {
+            report.ok = false;
+            report
+                .details
+                .extend(lines.into_iter().map(|l| format!("{}: {}", pragma, l)));
+        }
+        Ok(())
+    }
+
+    fn incremental_vacuum(&self, report: &mut DbMaintenanceReport) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        let mode: i64 = conn.query_row("PRAGMA auto_vacuum", [], |r| r.get(0))?;
+        let free_before: i64 = conn.query_row("PRAGMA freelist_count", [], |r| r.get(0))?;
+        if mode != 2 {
+            // auto_vacuum can only be switched on by a full VACUUM
+            report.details.push(format!(
+                "auto_vacuum is not INCREMENTAL on this database; {} free page(s) left in place",
+                free_before
+            ));
+            return Ok(());
+        }
+        conn.execute_batch("PRAGMA incremental_vacuum;")?;
+        let free_after: i64 = conn.query_row("PRAGMA freelist_count", [], |r| r.get(0))?;
+        report.details.push(format!(
+            "incremental_vacuum: released {} free page(s)",
+            free_before - free_after
+        ));
+        Ok(())
+    }
+
+    fn fts_tables(&self) -> EngineResult<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND sql LIKE 'CREATE VIRTUAL TABLE%USING fts5%'
+             ORDER BY name",
+        )?;
+        let rows = stmt.query_map([], |r| r.get(0))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    fn reindex_fts(&self, report: &mut DbMaintenanceReport) -> EngineResult<()> {
+        for table in self.fts_tables()? {
+            let conn = self.conn.lock();
+            match conn.execute_batch(&format!(
+                "INSERT INTO {t}({t}) VALUES('rebuild');",
+                t = table
+            )) {
+                Ok(()) => report.details.push(format!("rebuilt {}", table)),
+                Err(e) => {
+                    report.ok = false;
+                    report
+                        .details
+                        .push(format!("rebuild {} failed: {}", table, e));
+                }
+            }
+        }
+        self.conn.lock().execute_batch("REINDEX;")?;
+        report.details.push("reindexed b-tree indexes".into());
+        Ok(())
+    }
+
+    /// Merge FTS5 index segments — much cheaper than a rebuild.
+    fn optimize_fts(&self, report: &mut DbMaintenanceReport) -> EngineResult<()> {
+        for table in self.fts_tables()? {
+            let conn = self.conn.lock();
+            if let Err(e) = conn.execute_batch(&format!(
+                "INSERT INTO {t}({t}) VALUES('optimize');",
+                t = table
+            )) {
+                report
+                    .details
+                    .push(format!("optimize {} failed: {}", table, e));
+            }
+        }
+        report.details.push("optimized FTS indexes".into());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn maintenance_actions_run() {
+        let store = test_store();
+        for action in [
+            "integrity_check",
+            "incremental_vacuum",
+            "reindex_fts",
+            "scheduled",
+        ] {
+            let report = store.run_db_maintenance(action.parse().unwrap()).unwrap();
+            assert!(report.ok, "{} failed: {:?}", action, report.details);
+        }
+        let report = store
+            .run_db_maintenance(DbMaintenanceAction::ReindexFts)
+            .unwrap();
+        assert!(report
+            .details
+            .iter()
+            .any(|d| d == "rebuilt episodic_memories_fts"));
+        assert!("vacuum".parse::<DbMaintenanceAction>().is_err());
+    }
+
+    #[test]
+    fn migration_history_records_first_run_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        let store = SessionStore::from_connection(conn);
+
+        let runs = store.migration_history(10).unwrap();
+        assert_eq!(runs.len(), 2);
+        // Newest first: the second run found nothing to do
+        assert!(runs[0].changes.is_empty());
+        assert!(runs[1].changes.contains(&"table followups".to_string()));
+    }
+}
@@ -65,7 +65,8 @@ impl SessionStore {
 
     /// Get a single memory by ID.
     pub fn get_memory_by_id(&self, id: &str) -> EngineResult<Option<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let result = conn.query_row(
             "SELECT id, content, category, importance, created_at, agent_id FROM memories WHERE id = ?1",
             params![id],
@@ -108,7 +109,8 @@ impl SessionStore {
     }
 
     pub fn memory_stats(&self) -> EngineResult<MemoryStats> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         // Count from both old `memories` table and new `episodic_memories` table
         let old_total: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |r| r.get(0))?;
@@ -176,7 +178,8 @@ impl SessionStore {
         threshold: f64,
         agent_id: Option<&str>,
     ) -> EngineResult<Vec<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         let mut stmt = conn.prepare(
             "SELECT id, content, category, importance, embedding, created_at, agent_id FROM memories WHERE embedding IS NOT NULL"
@@ -249,7 +252,8 @@ impl SessionStore {
         limit: usize,
         agent_id: Option<&str>,
     ) -> EngineResult<Vec<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         // FTS5 match query — escape special characters
         let fts_query = query
@@ -339,7 +343,8 @@ impl SessionStore {
 
     /// Keyword-based fallback search (no embeddings needed).
     pub fn search_memories_keyword(&self, query: &str, limit: usize) -> EngineResult<Vec<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         let pattern = format!("%{}%", query.to_lowercase());
         let mut stmt = conn.prepare(
@@ -359,7 +364,8 @@ impl SessionStore {
 
     /// Get all memories (for export / listing), newest first.
     pub fn list_memories(&self, limit: usize) -> EngineResult<Vec<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let mut stmt = conn.prepare(
             "SELECT id, content, category, importance, created_at, agent_id FROM memories
              ORDER BY created_at DESC LIMIT ?1",
@@ -375,7 +381,8 @@ impl SessionStore {
 
     /// List memories that have no embedding vector (for backfill).
     pub fn list_memories_without_embeddings(&self, limit: usize) -> EngineResult<Vec<Memory>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let mut stmt = conn.prepare(
             "SELECT id, content, category, importance, created_at, agent_id FROM memories
              WHERE embedding IS NULL
@@ -403,7 +410,8 @@ impl SessionStore {
     /// Get memories created today — lightweight daily context injection.
     /// Returns a compact summary string (max 10 entries, highest importance first).
     pub fn get_todays_memories(&self, agent_id: &str) -> EngineResult<Option<String>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
         let today_start = format!("{} 00:00:00", today);
 
@@ -462,7 +470,8 @@ impl SessionStore {
 
     /// Get raw content strings of today's memories (for dedup against auto-recall).
     pub fn get_todays_memory_contents(&self, agent_id: &str) -> EngineResult<Vec<String>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
         let today_start = format!("{} 00:00:00", today);
         let mut stmt = conn.prepare(
@@ -491,7 +500,8 @@ impl SessionStore {
         category: &str,
         agent_id: Option<&str>,
     ) -> EngineResult<Vec<String>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
         let cutoff = chrono::Utc::now() - chrono::Duration::seconds(max_age_secs);
         let cutoff_str = cutoff.format("%Y-%m-%d %H:%M:%S").to_string();
         let aid = agent_id.unwrap_or("");
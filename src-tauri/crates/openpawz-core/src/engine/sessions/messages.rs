@@ -46,7 +46,8 @@ impl SessionStore {
     }
 
     pub fn get_messages(&self, session_id: &str, limit: i64) -> EngineResult<Vec<StoredMessage>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         // Subquery gets the NEWEST `limit` messages (DESC), then the outer
         // query re-sorts ASC so the caller receives chronological order.
@@ -81,6 +82,48 @@ impl SessionStore {
         Ok(messages)
     }
 
+    /// User and assistant messages created between `start` and `end`
+    /// (inclusive, SQLite "YYYY-MM-DD HH:MM:SS" UTC), oldest first. With an
+    /// `agent_id`, only that agent's sessions are searched.
+    pub fn messages_in_range(
+        &self,
+        start: &str,
+        end: &str,
+        agent_id: Option<&str>,
+        limit: usize,
+    ) -> EngineResult<Vec<StoredMessage>> {
+        let rc = self.read_conn();
+        let conn = rc.lock();
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.session_id, m.role, m.content, m.tool_calls_json, m.tool_call_id,
+                    m.name, m.created_at
+             FROM messages m JOIN sessions s ON s.id = m.session_id
+             WHERE m.created_at >= ?1 AND m.created_at <= ?2
+               AND m.role IN ('user', 'assistant') AND m.content != ''
+               AND (?3 IS NULL OR s.agent_id = ?3)
+             ORDER BY m.created_at ASC, m.rowid ASC
+             LIMIT ?4",
+        )?;
+
+        let messages = stmt
+            .query_map(params![start, end, agent_id, limit as i64], |row| {
+                Ok(StoredMessage {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    role: row.get(2)?,
+                    content: row.get(3)?,
+                    tool_calls_json: row.get(4)?,
+                    tool_call_id: row.get(5)?,
+                    name: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(messages)
+    }
+
     /// Convert stored messages to engine Message types for sending to AI provider.
     ///
     /// `max_context_tokens` caps the total conversation size.  Pass `None` to
@@ -12,32 +12,52 @@
 //   agent_files    — soul/persona file CRUD + context composition
 //   memories       — vector+FTS memory store + search
 //   tasks          — task CRUD, cron scheduling, task agents
+//   followups      — one-shot deferred turns scheduled by agents
+//   inbound_journal — write-ahead journal of bridge messages awaiting a reply
+//   idempotency    — completed side-effect tool calls, so retries don't repeat them
+//   import         — ChatGPT / Claude export parsing into sessions
+//   archive        — cold storage of idle sessions in per-month archive files
+//   maintenance    — integrity check, incremental vacuum, FTS rebuild, migration history
+//   outbox         — outbound messages held for review before sending
 //   projects       — project CRUD, project agents, message bus
 //   embedding      — bytes_to_f32_vec, f32_vec_to_bytes, cosine_similarity
+//   write_queue    — batched non-critical writes + WAL checkpoint tuning
 
 use crate::atoms::error::EngineResult;
 use log::info;
 use parking_lot::Mutex;
 use rusqlite::Connection;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 mod agent_files;
 mod agent_messages;
+pub mod archive;
 mod canvas;
+mod citations;
 pub mod community_skills;
 mod config;
 mod dashboard_tabs;
 mod dashboard_windows;
 mod dashboards;
+mod downloads;
 pub mod embedding;
 pub mod engram;
+mod feedback;
 mod flows;
+mod followups;
+mod idempotency;
+pub mod import;
+mod inbound_journal;
+pub mod maintenance;
 mod memories;
 mod messages;
+mod news;
+mod outbox;
 mod positions;
 mod projects;
+mod research;
 pub mod schema;
 #[allow(clippy::module_inception)]
 mod sessions;
@@ -49,14 +69,17 @@ mod tasks;
 pub mod telemetry;
 mod templates;
 mod trades;
+pub mod write_queue;
 
 // ── Re-exports (preserve crate::engine::sessions::* API) ─────────────────────
 
+pub use agent_files::resolve_includes;
 pub use community_skills::get_community_skill_instructions;
 pub use community_skills::CommunitySkill;
 pub use embedding::f32_vec_to_bytes;
 pub use skill_outputs::SkillOutput;
 pub use skill_storage::SkillStorageItem;
+pub use write_queue::DeferredWrite;
 
 /// Get the path to the engine's SQLite database.
 pub fn engine_db_path() -> PathBuf {
@@ -82,15 +105,23 @@ pub struct SessionStore {
     read_pool: Vec<Arc<Mutex<Connection>>>,
     /// Atomic counter for round-robin read pool selection.
     read_idx: AtomicUsize,
+    /// Whether `defer_write` queues instead of writing (see write_queue).
+    batch_writes: AtomicBool,
+    /// Writes waiting for the next `flush_deferred`.
+    pending_writes: Mutex<Vec<DeferredWrite>>,
 }
 
 impl SessionStore {
     /// Open (or create) the engine database and initialize tables.
     pub fn open() -> EngineResult<Self> {
-        let path = engine_db_path();
+        Self::open_at(&engine_db_path())
+    }
+
+    /// Open (or create) an engine database at `path` with its read pool.
+    pub fn open_at(path: &Path) -> EngineResult<Self> {
         info!("[engine] Opening session store at {:?}", path);
 
-        let conn = Connection::open(&path)?;
+        let conn = Connection::open(path)?;
 
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
 
@@ -110,7 +141,7 @@ impl SessionStore {
         let mut read_pool = Vec::with_capacity(READ_POOL_SIZE);
         for i in 0..READ_POOL_SIZE {
             let rc = Connection::open_with_flags(
-                &path,
+                path,
                 rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY
                     | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
             )?;
@@ -126,6 +157,8 @@ impl SessionStore {
             conn: Arc::new(Mutex::new(conn)),
             read_pool,
             read_idx: AtomicUsize::new(0),
+            batch_writes: AtomicBool::new(false),
+            pending_writes: Mutex::new(Vec::new()),
         })
     }
 
@@ -135,18 +168,25 @@ impl SessionStore {
         Arc::clone(&self.conn)
     }
 
-    /// Get a read-only connection from the pool (round-robin).
+    /// Get a read-only connection from the pool.
     ///
     /// Use this for search and query operations that don't mutate state.
     /// Multiple threads can hold different read connections simultaneously,
     /// eliminating serialization on the write mutex for read-heavy workloads.
+    /// Round-robin, skipping connections held by a long query (a memory
+    /// search, a big session list) while another one is free.
     ///
     /// Falls back to the write connection if the read pool is empty (tests).
     pub fn read_conn(&self) -> Arc<Mutex<Connection>> {
         if self.read_pool.is_empty() {
             return Arc::clone(&self.conn);
         }
-        let idx = self.read_idx.fetch_add(1, Ordering::Relaxed) % self.read_pool.len();
+        let n = self.read_pool.len();
+        let start = self.read_idx.fetch_add(1, Ordering::Relaxed);
+        let idx = (0..n)
+            .map(|i| (start + i) % n)
+            .find(|&i| !self.read_pool[i].is_locked())
+            .unwrap_or(start % n);
         Arc::clone(&self.read_pool[idx])
     }
 
@@ -159,6 +199,8 @@ impl SessionStore {
             conn: Arc::new(Mutex::new(conn)),
             read_pool: Vec::new(),
             read_idx: AtomicUsize::new(0),
+            batch_writes: AtomicBool::new(false),
+            pending_writes: Mutex::new(Vec::new()),
         })
     }
 
@@ -169,6 +211,8 @@ impl SessionStore {
             conn: Arc::new(Mutex::new(conn)),
             read_pool: Vec::new(),
             read_idx: AtomicUsize::new(0),
+            batch_writes: AtomicBool::new(false),
+            pending_writes: Mutex::new(Vec::new()),
         }
     }
 }
@@ -178,3 +222,34 @@ impl SessionStore {
 pub fn schema_for_testing(conn: &Connection) {
     schema::run_migrations(conn).expect("schema_for_testing: migrations failed");
 }
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readers_do_not_wait_for_a_busy_reader_or_the_writer() {
+        let dir = std::env::temp_dir().join(format!("paw-pool-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = SessionStore::open_at(&dir.join("engine.db")).unwrap();
+        store.create_session("s1", "m", None, None).unwrap();
+
+        // A long search holds one reader; the next one handed out is free
+        let busy = store.read_conn();
+        let search = busy.lock();
+        let other = store.read_conn();
+        assert!(!Arc::ptr_eq(&busy, &other));
+        assert!(!other.is_locked());
+
+        // ...and so is the writer
+        let writer = store.conn.lock();
+        assert!(store.get_session("s1").unwrap().is_some());
+
+        drop(writer);
+        drop(search);
+        drop(store);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
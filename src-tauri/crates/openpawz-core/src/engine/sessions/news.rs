@@ -0,0 +1,255 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::{NewsDigest, NewsWatch};
+use rusqlite::{params, OptionalExtension};
+
+const WATCH_COLUMNS: &str = "id, name, queries, agent_id, schedule, deliver_to, deliver_target, \
+                             enabled, last_run_at, next_run_at, created_at";
+
+fn watch_from_row(row: &rusqlite::Row) -> rusqlite::Result<NewsWatch> {
+    let queries: String = row.get(2)?;
+    Ok(NewsWatch {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        queries: serde_json::from_str(&queries).unwrap_or_default(),
+        agent_id: row.get(3)?,
+        schedule: row.get(4)?,
+        deliver_to: row.get(5)?,
+        deliver_target: row.get(6)?,
+        enabled: row.get::<_, i64>(7)? != 0,
+        last_run_at: row.get(8)?,
+        next_run_at: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
+
+fn digest_from_row(row: &rusqlite::Row) -> rusqlite::Result<NewsDigest> {
+    Ok(NewsDigest {
+        id: row.get(0)?,
+        watch_id: row.get(1)?,
+        item_count: row.get(2)?,
+        summary: row.get(3)?,
+        status: row.get(4)?,
+        error: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+impl SessionStore {
+    // ── News watches ───────────────────────────────────────────────────
+
+    /// Insert or update a watch. A watch with an empty ID gets a new one;
+    /// the stored ID is returned.
+    pub fn news_watch_save(&self, watch: &NewsWatch) -> EngineResult<String> {
+        let conn = self.conn.lock();
+        let id = if watch.id.is_empty() {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            watch.id.clone()
+        };
+        conn.execute(
+            "INSERT INTO news_watches
+                (id, name, queries, agent_id, schedule, deliver_to, deliver_target, enabled, next_run_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name, queries = excluded.queries, agent_id = excluded.agent_id,
+                schedule = excluded.schedule, deliver_to = excluded.deliver_to,
+                deliver_target = excluded.deliver_target, enabled = excluded.enabled,
+                next_run_at = excluded.next_run_at",
+            params![
+                id,
+                watch.name,
+                serde_json::to_string(&watch.queries)?,
+                watch.agent_id,
+                watch.schedule,
+                watch.deliver_to,
+                watch.deliver_target,
+                watch.enabled as i64,
+                watch.next_run_at,
+            ],
+        )?;
+        Ok(id)
+    }
+
+    pub fn news_watch_list(&self) -> EngineResult<Vec<NewsWatch>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM news_watches ORDER BY created_at ASC",
+            WATCH_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], watch_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    pub fn news_watch_get(&self, id: &str) -> EngineResult<Option<NewsWatch>> {
+        let conn = self.conn.lock();
+        let sql = format!("SELECT {} FROM news_watches WHERE id = ?1", WATCH_COLUMNS);
+        Ok(conn
+            .query_row(&sql, params![id], watch_from_row)
+            .optional()?)
+    }
+
+    /// Delete a watch with its seen URLs and digest history.
+    pub fn news_watch_delete(&self, id: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM news_seen WHERE watch_id = ?1", params![id])?;
+        conn.execute("DELETE FROM news_digests WHERE watch_id = ?1", params![id])?;
+        conn.execute("DELETE FROM news_watches WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Enabled watches whose next run has passed.
+    pub fn news_watch_due(&self) -> EngineResult<Vec<NewsWatch>> {
+        let conn = self.conn.lock();
+        let now = chrono::Utc::now().to_rfc3339();
+        let sql = format!(
+            "SELECT {} FROM news_watches
+             WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?1",
+            WATCH_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![now], watch_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    pub fn news_watch_mark_run(
+        &self,
+        id: &str,
+        last_run_at: &str,
+        next_run_at: Option<&str>,
+    ) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE news_watches SET last_run_at = ?2, next_run_at = ?3 WHERE id = ?1",
+            params![id, last_run_at, next_run_at],
+        )?;
+        Ok(())
+    }
+
+    /// The subset of `urls` this watch has not seen yet, in input order.
+    pub fn news_unseen(&self, watch_id: &str, urls: &[String]) -> EngineResult<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT 1 FROM news_seen WHERE watch_id = ?1 AND url = ?2")?;
+        let mut unseen = Vec::new();
+        for url in urls {
+            if !stmt.exists(params![watch_id, url])? {
+                unseen.push(url.clone());
+            }
+        }
+        Ok(unseen)
+    }
+
+    /// Remember `(url, title)` pairs so later runs skip them.
+    pub fn news_mark_seen(&self, watch_id: &str, items: &[(String, String)]) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "INSERT OR IGNORE INTO news_seen (watch_id, url, title) VALUES (?1, ?2, ?3)",
+        )?;
+        for (url, title) in items {
+            stmt.execute(params![watch_id, url, title])?;
+        }
+        Ok(())
+    }
+
+    pub fn news_digest_add(
+        &self,
+        watch_id: &str,
+        item_count: usize,
+        summary: &str,
+        status: &str,
+        error: Option<&str>,
+    ) -> EngineResult<NewsDigest> {
+        let conn = self.conn.lock();
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO news_digests (id, watch_id, item_count, summary, status, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, watch_id, item_count as i64, summary, status, error],
+        )?;
+        Ok(conn.query_row(
+            "SELECT id, watch_id, item_count, summary, status, error, created_at
+             FROM news_digests WHERE id = ?1",
+            params![id],
+            digest_from_row,
+        )?)
+    }
+
+    /// Most recent digests first, optionally for one watch.
+    pub fn news_digest_list(
+        &self,
+        watch_id: Option<&str>,
+        limit: u32,
+    ) -> EngineResult<Vec<NewsDigest>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, watch_id, item_count, summary, status, error, created_at
+             FROM news_digests WHERE (?1 IS NULL OR watch_id = ?1)
+             ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![watch_id, limit], digest_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn watch(next_run_at: Option<String>) -> NewsWatch {
+        NewsWatch {
+            id: String::new(),
+            name: "Rust".into(),
+            queries: vec!["rust release".into(), "cargo".into()],
+            agent_id: "default".into(),
+            schedule: "every 6h".into(),
+            deliver_to: "telegram".into(),
+            deliver_target: None,
+            enabled: true,
+            last_run_at: None,
+            next_run_at,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn watches_are_due_and_dedupe_seen_urls() {
+        let store = test_store();
+        let past = (chrono::Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        let due = store.news_watch_save(&watch(Some(past))).unwrap();
+        store.news_watch_save(&watch(Some(future))).unwrap();
+
+        let found = store.news_watch_due().unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, due);
+        assert_eq!(found[0].queries, vec!["rust release", "cargo"]);
+
+        let urls = vec!["https://a.example".to_string(), "https://b.example".into()];
+        store
+            .news_mark_seen(&due, &[("https://a.example".into(), "A".into())])
+            .unwrap();
+        assert_eq!(
+            store.news_unseen(&due, &urls).unwrap(),
+            vec!["https://b.example"]
+        );
+
+        store
+            .news_digest_add(&due, 1, "B happened", "in_app", None)
+            .unwrap();
+        assert_eq!(store.news_digest_list(Some(&due), 10).unwrap().len(), 1);
+
+        store.news_watch_delete(&due).unwrap();
+        assert!(store.news_watch_get(&due).unwrap().is_none());
+        assert_eq!(store.news_unseen(&due, &urls).unwrap().len(), 2);
+        assert!(store.news_digest_list(None, 10).unwrap().is_empty());
+    }
+}
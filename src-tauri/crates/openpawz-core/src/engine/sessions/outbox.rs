@@ -0,0 +1,184 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::types::OutboxItem;
+use rusqlite::{params, OptionalExtension};
+
+const OUTBOX_COLUMNS: &str = "id, agent_id, session_id, tool_name, arguments, summary, status, \
+                              result, created_at, expires_at, decided_at";
+
+fn outbox_from_row(row: &rusqlite::Row) -> rusqlite::Result<OutboxItem> {
+    Ok(OutboxItem {
+        id: row.get(0)?,
+        agent_id: row.get(1)?,
+        session_id: row.get(2)?,
+        tool_name: row.get(3)?,
+        arguments: row.get(4)?,
+        summary: row.get(5)?,
+        status: row.get(6)?,
+        result: row.get(7)?,
+        created_at: row.get(8)?,
+        expires_at: row.get(9)?,
+        decided_at: row.get(10)?,
+    })
+}
+
+impl SessionStore {
+    // ── Outbox ─────────────────────────────────────────────────────────
+
+    /// Hold an outbound tool call for review. `expiry_hours` of 0 means the
+    /// item never expires.
+    pub fn outbox_enqueue(
+        &self,
+        agent_id: &str,
+        session_id: Option<&str>,
+        tool_name: &str,
+        arguments: &str,
+        summary: &str,
+        expiry_hours: u32,
+    ) -> EngineResult<String> {
+        let conn = self.conn.lock();
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO outbox (id, agent_id, session_id, tool_name, arguments, summary, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6,
+                     CASE WHEN ?7 > 0 THEN datetime('now', '+' || ?7 || ' hours') END)",
+            params![id, agent_id, session_id, tool_name, arguments, summary, expiry_hours],
+        )?;
+        Ok(id)
+    }
+
+    /// Outbox items, optionally filtered by status, oldest first.
+    pub fn outbox_list(&self, status: Option<&str>) -> EngineResult<Vec<OutboxItem>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM outbox
+             WHERE (?1 IS NULL OR status = ?1)
+             ORDER BY created_at ASC, rowid ASC",
+            OUTBOX_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![status], outbox_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    pub fn outbox_get(&self, id: &str) -> EngineResult<Option<OutboxItem>> {
+        let conn = self.conn.lock();
+        let sql = format!("SELECT {} FROM outbox WHERE id = ?1", OUTBOX_COLUMNS);
+        Ok(conn
+            .query_row(&sql, params![id], outbox_from_row)
+            .optional()?)
+    }
+
+    /// Replace a pending item's arguments (and summary). Returns false if the
+    /// item is no longer pending.
+    pub fn outbox_edit(&self, id: &str, arguments: &str, summary: &str) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        let changed = conn.execute(
+            "UPDATE outbox SET arguments = ?2, summary = ?3 WHERE id = ?1 AND status = 'pending'",
+            params![id, arguments, summary],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Move a pending item to `sending` so it is sent exactly once, even if
+    /// approve is clicked twice. Returns false if it was not pending.
+    pub fn outbox_claim(&self, id: &str) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        let changed = conn.execute(
+            "UPDATE outbox SET status = 'sending', decided_at = datetime('now')
+             WHERE id = ?1 AND status = 'pending'",
+            params![id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Record the final status (`sent`, `failed`, `rejected`) and the tool
+    /// output or rejection reason. Only pending or sending items change.
+    pub fn outbox_finish(
+        &self,
+        id: &str,
+        status: &str,
+        result: Option<&str>,
+    ) -> EngineResult<bool> {
+        let conn = self.conn.lock();
+        let changed = conn.execute(
+            "UPDATE outbox SET status = ?2, result = ?3, decided_at = datetime('now')
+             WHERE id = ?1 AND status IN ('pending', 'sending')",
+            params![id, status, result],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Expire pending items whose deadline has passed.
+    pub fn outbox_expire(&self) -> EngineResult<usize> {
+        let conn = self.conn.lock();
+        let n = conn.execute(
+            "UPDATE outbox SET status = 'expired', decided_at = datetime('now')
+             WHERE status = 'pending' AND expires_at IS NOT NULL AND expires_at <= datetime('now')",
+            [],
+        )?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn outbox_lifecycle() {
+        let store = test_store();
+        let a = store
+            .outbox_enqueue("agent", Some("s1"), "email_send", "{}", "draft", 72)
+            .unwrap();
+        let b = store
+            .outbox_enqueue("agent", None, "slack_send", "{}", "post", 0)
+            .unwrap();
+        assert_eq!(store.outbox_list(Some("pending")).unwrap().len(), 2);
+        assert!(store.outbox_get(&b).unwrap().unwrap().expires_at.is_none());
+
+        assert!(store
+            .outbox_edit(&a, r#"{"to":"bob@example.com"}"#, "edited")
+            .unwrap());
+        assert!(store.outbox_claim(&a).unwrap());
+        // Second approve of the same item is refused
+        assert!(!store.outbox_claim(&a).unwrap());
+        assert!(!store.outbox_edit(&a, "{}", "late edit").unwrap());
+        store.outbox_finish(&a, "sent", Some("ok")).unwrap();
+
+        let sent = store.outbox_get(&a).unwrap().unwrap();
+        assert_eq!(sent.status, "sent");
+        assert_eq!(sent.summary, "edited");
+
+        store.outbox_finish(&b, "rejected", Some("tone")).unwrap();
+        assert!(store.outbox_list(Some("pending")).unwrap().is_empty());
+        assert_eq!(store.outbox_list(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn outbox_expires_overdue_items() {
+        let store = test_store();
+        let id = store
+            .outbox_enqueue("agent", None, "email_send", "{}", "draft", 1)
+            .unwrap();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE outbox SET expires_at = datetime('now', '-1 minute') WHERE id = ?1",
+                params![id],
+            )
+            .unwrap();
+        assert_eq!(store.outbox_expire().unwrap(), 1);
+        assert_eq!(store.outbox_get(&id).unwrap().unwrap().status, "expired");
+        assert!(!store.outbox_claim(&id).unwrap());
+    }
+}
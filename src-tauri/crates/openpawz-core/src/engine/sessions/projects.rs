@@ -1,7 +1,9 @@
 use super::SessionStore;
 use crate::atoms::error::EngineResult;
-use crate::engine::types::{Project, ProjectAgent, ProjectMessage};
-use rusqlite::params;
+use crate::engine::types::{
+    Project, ProjectAgent, ProjectAgentUsage, ProjectMessage, ProjectPostMortem,
+};
+use rusqlite::{params, OptionalExtension};
 
 impl ProjectAgent {
     /// Map columns starting at `offset` → ProjectAgent.
@@ -30,7 +32,7 @@ impl SessionStore {
     pub fn list_projects(&self) -> EngineResult<Vec<Project>> {
         let conn = self.conn.lock();
         let mut stmt = conn.prepare(
-            "SELECT id, title, goal, status, boss_agent, created_at, updated_at FROM projects ORDER BY updated_at DESC"
+            "SELECT id, title, goal, status, boss_agent, created_at, updated_at, budget_usd FROM projects ORDER BY updated_at DESC"
         )?;
 
         let projects = stmt
@@ -42,6 +44,7 @@ impl SessionStore {
                     status: row.get(3)?,
                     boss_agent: row.get(4)?,
                     agents: vec![],
+                    budget_usd: row.get(7)?,
                     created_at: row.get(5)?,
                     updated_at: row.get(6)?,
                 })
@@ -64,11 +67,45 @@ impl SessionStore {
         Ok(result)
     }
 
+    /// A project's status without loading its agents (None = no such project).
+    pub fn get_project_status(&self, id: &str) -> EngineResult<Option<String>> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT status FROM projects WHERE id=?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// A project's budget in USD (0 = none, or no such project).
+    pub fn get_project_budget(&self, id: &str) -> EngineResult<f64> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT budget_usd FROM projects WHERE id=?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0.0))
+    }
+
+    pub fn set_project_status(&self, id: &str, status: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE projects SET status=?2, updated_at=datetime('now') WHERE id=?1",
+            params![id, status],
+        )?;
+        Ok(())
+    }
+
     pub fn create_project(&self, project: &Project) -> EngineResult<()> {
         let conn = self.conn.lock();
         conn.execute(
-            "INSERT INTO projects (id, title, goal, status, boss_agent) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![project.id, project.title, project.goal, project.status, project.boss_agent],
+            "INSERT INTO projects (id, title, goal, status, boss_agent, budget_usd) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![project.id, project.title, project.goal, project.status, project.boss_agent, project.budget_usd],
         )?;
         Ok(())
     }
@@ -76,8 +113,8 @@ impl SessionStore {
     pub fn update_project(&self, project: &Project) -> EngineResult<()> {
         let conn = self.conn.lock();
         conn.execute(
-            "UPDATE projects SET title=?2, goal=?3, status=?4, boss_agent=?5, updated_at=datetime('now') WHERE id=?1",
-            params![project.id, project.title, project.goal, project.status, project.boss_agent],
+            "UPDATE projects SET title=?2, goal=?3, status=?4, boss_agent=?5, budget_usd=?6, updated_at=datetime('now') WHERE id=?1",
+            params![project.id, project.title, project.goal, project.status, project.boss_agent, project.budget_usd],
         )?;
         Ok(())
     }
@@ -228,6 +265,28 @@ impl SessionStore {
         Ok(())
     }
 
+    pub fn get_project_message(&self, id: &str) -> EngineResult<Option<ProjectMessage>> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                "SELECT id, project_id, from_agent, to_agent, kind, content, metadata, created_at FROM project_messages WHERE id=?1",
+                params![id],
+                |row| {
+                    Ok(ProjectMessage {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        from_agent: row.get(2)?,
+                        to_agent: row.get(3)?,
+                        kind: row.get(4)?,
+                        content: row.get(5)?,
+                        metadata: row.get(6)?,
+                        created_at: row.get(7)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
     pub fn get_project_messages(
         &self,
         project_id: &str,
@@ -258,4 +317,97 @@ impl SessionStore {
         result.reverse();
         Ok(result)
     }
+
+    // ── Orchestrator: Post-mortems ─────────────────────────────────────
+
+    /// Store the report of a project's latest run, replacing the previous one.
+    pub fn save_project_postmortem(&self, report: &ProjectPostMortem) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT OR REPLACE INTO project_postmortems (project_id, report) VALUES (?1, ?2)",
+            params![report.project_id, serde_json::to_string(report)?],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_project_postmortem(
+        &self,
+        project_id: &str,
+    ) -> EngineResult<Option<ProjectPostMortem>> {
+        let conn = self.conn.lock();
+        let report: Option<String> = conn
+            .query_row(
+                "SELECT report FROM project_postmortems WHERE project_id=?1",
+                params![project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(report.and_then(|r| serde_json::from_str(&r).ok()))
+    }
+
+    /// The most recent post-mortems across all projects, newest first.
+    pub fn list_project_postmortems(&self, limit: i64) -> EngineResult<Vec<ProjectPostMortem>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare("SELECT report FROM project_postmortems ORDER BY created_at DESC LIMIT ?1")?;
+        let reports = stmt
+            .query_map(params![limit], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .filter_map(|r| serde_json::from_str(&r).ok())
+            .collect();
+        Ok(reports)
+    }
+
+    /// Tokens and cost per agent and model recorded in a project's sessions
+    /// (`eng-project-{id}-{agent}`) since `since` (SQLite datetime).
+    pub fn get_project_usage(
+        &self,
+        project_id: &str,
+        since: &str,
+    ) -> EngineResult<Vec<ProjectAgentUsage>> {
+        let conn = self.conn.lock();
+        let prefix = format!("eng-project-{}-", project_id);
+        let mut stmt = conn.prepare(
+            "SELECT substr(session_id, ?2), model, SUM(input_tokens), SUM(output_tokens),
+                    SUM(cost_usd), SUM(rounds), SUM(tool_calls)
+             FROM telemetry_metrics
+             WHERE substr(session_id, 1, ?3) = ?1 AND created_at >= ?4
+             GROUP BY session_id, model
+             ORDER BY SUM(cost_usd) DESC",
+        )?;
+        let usage = stmt
+            .query_map(
+                params![prefix, prefix.len() as i64 + 1, prefix.len() as i64, since],
+                |row| {
+                    Ok(ProjectAgentUsage {
+                        agent_id: row.get(0)?,
+                        model: row.get(1)?,
+                        input_tokens: row.get::<_, i64>(2)? as u64,
+                        output_tokens: row.get::<_, i64>(3)? as u64,
+                        cost_usd: row.get(4)?,
+                        rounds: row.get::<_, i64>(5)? as u32,
+                        tool_calls: row.get::<_, i64>(6)? as u32,
+                    })
+                },
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(usage)
+    }
+
+    /// Estimated USD spent by all of a project's agents, across all runs.
+    /// Flushes the write queue first: budget checks need the usage of the
+    /// round that just finished.
+    pub fn get_project_spend(&self, project_id: &str) -> EngineResult<f64> {
+        self.flush_deferred()?;
+        let conn = self.conn.lock();
+        let prefix = format!("eng-project-{}-", project_id);
+        let spend: f64 = conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0.0) FROM telemetry_metrics
+             WHERE substr(session_id, 1, ?2) = ?1",
+            params![prefix, prefix.len() as i64],
+            |row| row.get(0),
+        )?;
+        Ok(spend)
+    }
 }
@@ -0,0 +1,173 @@
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::engine::research::ResearchState;
+use crate::engine::types::ResearchRun;
+use rusqlite::{params, OptionalExtension};
+
+const RUN_COLUMNS: &str = "id, question, agent_id, status, page_budget, max_rounds, pages_read, \
+                           report_path, error, created_at, updated_at";
+
+fn run_from_row(row: &rusqlite::Row) -> rusqlite::Result<ResearchRun> {
+    Ok(ResearchRun {
+        id: row.get(0)?,
+        question: row.get(1)?,
+        agent_id: row.get(2)?,
+        status: row.get(3)?,
+        page_budget: row.get(4)?,
+        max_rounds: row.get(5)?,
+        pages_read: row.get(6)?,
+        report_path: row.get(7)?,
+        error: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+impl SessionStore {
+    // ── Research runs ──────────────────────────────────────────────────
+
+    pub fn research_create(
+        &self,
+        question: &str,
+        agent_id: &str,
+        page_budget: u32,
+        max_rounds: u32,
+    ) -> EngineResult<String> {
+        let conn = self.conn.lock();
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO research_runs (id, question, agent_id, page_budget, max_rounds)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, question, agent_id, page_budget, max_rounds],
+        )?;
+        Ok(id)
+    }
+
+    /// Runs, newest first.
+    pub fn research_list(&self, limit: u32) -> EngineResult<Vec<ResearchRun>> {
+        let conn = self.conn.lock();
+        let sql = format!(
+            "SELECT {} FROM research_runs ORDER BY created_at DESC, rowid DESC LIMIT ?1",
+            RUN_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![limit], run_from_row)?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    pub fn research_get(&self, id: &str) -> EngineResult<Option<ResearchRun>> {
+        let conn = self.conn.lock();
+        let sql = format!("SELECT {} FROM research_runs WHERE id = ?1", RUN_COLUMNS);
+        Ok(conn.query_row(&sql, params![id], run_from_row).optional()?)
+    }
+
+    /// The run's checkpointed loop state (default for a fresh run).
+    pub fn research_load_state(&self, id: &str) -> EngineResult<ResearchState> {
+        let conn = self.conn.lock();
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT state FROM research_runs WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(json
+            .and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default())
+    }
+
+    /// Checkpoint the loop state.
+    pub fn research_save_state(&self, id: &str, state: &ResearchState) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE research_runs SET state = ?2, pages_read = ?3, updated_at = datetime('now')
+             WHERE id = ?1",
+            params![id, serde_json::to_string(state)?, state.pages_read()],
+        )?;
+        Ok(())
+    }
+
+    pub fn research_set_status(
+        &self,
+        id: &str,
+        status: &str,
+        report_path: Option<&str>,
+        error: Option<&str>,
+    ) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE research_runs
+             SET status = ?2, report_path = COALESCE(?3, report_path), error = ?4,
+                 updated_at = datetime('now')
+             WHERE id = ?1",
+            params![id, status, report_path, error],
+        )?;
+        Ok(())
+    }
+
+    /// Mark runs left `running` by a previous process as `interrupted`.
+    /// Returns how many were marked.
+    pub fn research_mark_interrupted(&self) -> EngineResult<usize> {
+        let conn = self.conn.lock();
+        Ok(conn.execute(
+            "UPDATE research_runs SET status = 'interrupted', updated_at = datetime('now')
+             WHERE status = 'running'",
+            [],
+        )?)
+    }
+
+    pub fn research_delete(&self, id: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM research_runs WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::research::ResearchPhase;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn state_checkpoints_survive_interruption() {
+        let store = test_store();
+        let id = store
+            .research_create("Why is the sky blue?", "default", 12, 3)
+            .unwrap();
+        assert_eq!(
+            store.research_load_state(&id).unwrap().phase,
+            ResearchPhase::Planning
+        );
+
+        let mut state = ResearchState {
+            phase: ResearchPhase::Gathering,
+            ..Default::default()
+        };
+        let src = state.add_source("https://a.example", "A");
+        state.source_mut(src).unwrap().read = true;
+        store.research_save_state(&id, &state).unwrap();
+
+        assert_eq!(store.research_mark_interrupted().unwrap(), 1);
+        let run = store.research_get(&id).unwrap().unwrap();
+        assert_eq!(run.status, "interrupted");
+        assert_eq!(run.pages_read, 1);
+        let loaded = store.research_load_state(&id).unwrap();
+        assert_eq!(loaded.phase, ResearchPhase::Gathering);
+        assert_eq!(loaded.sources.len(), 1);
+
+        store
+            .research_set_status(&id, "done", Some("research/sky.md"), None)
+            .unwrap();
+        let run = store.research_get(&id).unwrap().unwrap();
+        assert_eq!(run.report_path.as_deref(), Some("research/sky.md"));
+        assert_eq!(store.research_list(10).unwrap().len(), 1);
+    }
+}
@@ -7,8 +7,71 @@
 use crate::atoms::error::EngineResult;
 use log::{info, warn};
 use rusqlite::Connection;
+use std::collections::{BTreeSet, HashSet};
 
+/// Run all migrations, timing the run and recording which tables and columns
+/// it added in `schema_migrations` (see `SessionStore::migration_history`).
 pub fn run_migrations(conn: &Connection) -> EngineResult<()> {
+    let before = schema_columns(conn);
+    let started = std::time::Instant::now();
+    apply_migrations(conn)?;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    let after = schema_columns(conn);
+    let before_tables: HashSet<&str> = before
+        .iter()
+        .filter_map(|c| c.split_once('.').map(|(t, _)| t))
+        .collect();
+    let mut changes: Vec<String> = Vec::new();
+    for col in after.difference(&before) {
+        let Some((table, column)) = col.split_once('.') else {
+            continue;
+        };
+        if !before_tables.contains(table) {
+            let entry = format!("table {}", table);
+            if !changes.contains(&entry) {
+                changes.push(entry);
+            }
+        } else {
+            changes.push(format!("column {}.{}", table, column));
+        }
+    }
+    changes.sort();
+
+    conn.execute(
+        "INSERT INTO schema_migrations (duration_ms, changes) VALUES (?1, ?2)",
+        rusqlite::params![duration_ms, serde_json::to_string(&changes)?],
+    )?;
+    // Keep the history bounded — one row per startup
+    conn.execute(
+        "DELETE FROM schema_migrations WHERE id NOT IN
+            (SELECT id FROM schema_migrations ORDER BY id DESC LIMIT 100)",
+        [],
+    )?;
+    if !changes.is_empty() {
+        info!(
+            "[engine] Schema migrations applied in {}ms: {} change(s)",
+            duration_ms,
+            changes.len()
+        );
+    }
+    Ok(())
+}
+
+/// Every `table.column` in the schema (SQLite internals excluded).
+fn schema_columns(conn: &Connection) -> BTreeSet<String> {
+    conn.prepare(
+        "SELECT m.name || '.' || p.name FROM sqlite_master m, pragma_table_info(m.name) p
+         WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map([], |r| r.get::<_, String>(0))
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+    })
+    .unwrap_or_default()
+}
+
+fn apply_migrations(conn: &Connection) -> EngineResult<()> {
     // ── Pre-migration: detect stale project_agents schema ───────────
     // Older versions created project_agents with (id INTEGER PK, project_id INTEGER,
     // name TEXT, …) which is incompatible with the current (project_id TEXT,
@@ -549,6 +612,14 @@ pub fn run_migrations(conn: &Connection) -> EngineResult<()> {
         CREATE INDEX IF NOT EXISTS idx_telemetry_session ON telemetry_metrics(session_id);",
     )
     .ok();
+    // Run id links a turn's metrics to user feedback on it
+    conn.execute("ALTER TABLE telemetry_metrics ADD COLUMN run_id TEXT", [])
+        .ok();
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_telemetry_run ON telemetry_metrics(run_id)",
+        [],
+    )
+    .ok();
 
     // ── Tool Registry: persistent embedding index (Phase 2) ─────────
     conn.execute_batch(
@@ -578,6 +649,241 @@ pub fn run_migrations(conn: &Connection) -> EngineResult<()> {
     )
     .ok();
 
+    // ── Follow-ups: one-shot deferred turns scheduled by agents ──────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS followups (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            agent_id TEXT NOT NULL,
+            note TEXT NOT NULL,
+            due_at TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_followups_due ON followups(status, due_at);",
+    )
+    .ok();
+
+    // ── Agent file revisions: history for soul/persona edits ─────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS agent_file_revisions (
+            agent_id TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            author TEXT NOT NULL DEFAULT 'user',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (agent_id, file_name, version)
+        );",
+    )
+    .ok();
+
+    // ── Soul proposals: reflection suggestions awaiting review ───────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS soul_proposals (
+            id TEXT PRIMARY KEY,
+            agent_id TEXT NOT NULL,
+            session_id TEXT,
+            file_name TEXT NOT NULL,
+            proposed_content TEXT NOT NULL,
+            rationale TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT 'pending',
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_soul_proposals_status ON soul_proposals(status, agent_id);",
+    )
+    .ok();
+
+    // ── Prompt library: reusable templates with typed variables ──────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS prompt_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            body TEXT NOT NULL,
+            variables TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );",
+    )
+    .ok();
+
+    // ── Inbound journal: bridge messages persisted before processing ──
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS inbound_journal (
+            id TEXT PRIMARY KEY,
+            channel TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            received_at TEXT NOT NULL DEFAULT (datetime('now')),
+            completed_at TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_inbound_journal_status ON inbound_journal(channel, status);",
+    )
+    .ok();
+
+    // ── Outbox: outbound messages held for review ────────────────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS outbox (
+            id TEXT PRIMARY KEY,
+            agent_id TEXT NOT NULL,
+            session_id TEXT,
+            tool_name TEXT NOT NULL,
+            arguments TEXT NOT NULL,
+            summary TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT 'pending',
+            result TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            expires_at TEXT,
+            decided_at TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_outbox_status ON outbox(status, created_at);",
+    )
+    .ok();
+
+    // ── News watches: scheduled topic digests ────────────────────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS news_watches (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            queries TEXT NOT NULL DEFAULT '[]',
+            agent_id TEXT NOT NULL DEFAULT 'default',
+            schedule TEXT NOT NULL,
+            deliver_to TEXT NOT NULL DEFAULT '',
+            deliver_target TEXT,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            last_run_at TEXT,
+            next_run_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE IF NOT EXISTS news_seen (
+            watch_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            title TEXT NOT NULL DEFAULT '',
+            seen_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (watch_id, url)
+        );
+        CREATE TABLE IF NOT EXISTS news_digests (
+            id TEXT PRIMARY KEY,
+            watch_id TEXT NOT NULL,
+            item_count INTEGER NOT NULL DEFAULT 0,
+            summary TEXT NOT NULL,
+            status TEXT NOT NULL,
+            error TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_news_digests_watch ON news_digests(watch_id, created_at);",
+    )
+    .ok();
+
+    // ── Research runs: resumable multi-round research with citations ─
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS research_runs (
+            id TEXT PRIMARY KEY,
+            question TEXT NOT NULL,
+            agent_id TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'running',
+            page_budget INTEGER NOT NULL,
+            max_rounds INTEGER NOT NULL,
+            pages_read INTEGER NOT NULL DEFAULT 0,
+            state TEXT NOT NULL DEFAULT '{}',
+            report_path TEXT,
+            error TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );",
+    )
+    .ok();
+
+    // ── Message citations: web sources behind an assistant answer ────
+    // Saved per run when the turn completes; `message_id` is filled in once
+    // the caller stores the final assistant message.
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS message_citations (
+            run_id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            message_id TEXT,
+            citations TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_message_citations_session
+            ON message_citations(session_id);",
+    )
+    .ok();
+
+    // ── Run feedback: user ratings of agent answers ──────────────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS run_feedback (
+            run_id TEXT PRIMARY KEY,
+            session_id TEXT,
+            agent_id TEXT,
+            model TEXT,
+            rating INTEGER NOT NULL,
+            comment TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_run_feedback_model ON run_feedback(model);",
+    )
+    .ok();
+
+    // ── Downloads: files fetched by the download_file tool ──────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS downloads (
+            id TEXT PRIMARY KEY,
+            agent_id TEXT NOT NULL DEFAULT 'default',
+            url TEXT NOT NULL,
+            path TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL DEFAULT 0,
+            sha256 TEXT NOT NULL DEFAULT '',
+            content_type TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_downloads_agent ON downloads(agent_id, created_at);",
+    )
+    .ok();
+
+    // ── Project post-mortems: latest run report per project ──────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS project_postmortems (
+            project_id TEXT PRIMARY KEY,
+            report TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );",
+    )
+    .ok();
+
+    // ── Project budgets: hard spending limit per project (0 = none) ──
+    conn.execute(
+        "ALTER TABLE projects ADD COLUMN budget_usd REAL NOT NULL DEFAULT 0",
+        [],
+    )
+    .ok();
+
+    // ── Idempotency keys: side-effect tool calls already completed ────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_idempotency (
+            key TEXT PRIMARY KEY,
+            tool_name TEXT NOT NULL,
+            session_id TEXT NOT NULL DEFAULT '',
+            output TEXT NOT NULL DEFAULT '',
+            completed_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_tool_idempotency_completed ON tool_idempotency(completed_at);",
+    )
+    .ok();
+
+    // ── Schema migration history (written by run_migrations) ─────────
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ran_at TEXT NOT NULL DEFAULT (datetime('now')),
+            duration_ms INTEGER NOT NULL,
+            changes TEXT NOT NULL DEFAULT '[]'
+        );",
+    )?;
+
     Ok(())
 }
 
@@ -627,5 +933,13 @@ mod tests {
         assert!(tables.contains(&"engine_config".to_string()));
         assert!(tables.contains(&"tool_embeddings".to_string()));
         assert!(tables.contains(&"tool_sequences".to_string()));
+        assert!(tables.contains(&"followups".to_string()));
+        assert!(tables.contains(&"agent_file_revisions".to_string()));
+        assert!(tables.contains(&"prompt_templates".to_string()));
+        assert!(tables.contains(&"news_watches".to_string()));
+        assert!(tables.contains(&"research_runs".to_string()));
+        assert!(tables.contains(&"message_citations".to_string()));
+        assert!(tables.contains(&"run_feedback".to_string()));
+        assert!(tables.contains(&"project_postmortems".to_string()));
     }
 }
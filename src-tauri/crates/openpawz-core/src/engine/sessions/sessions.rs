@@ -1,9 +1,30 @@
 use super::SessionStore;
+use crate::atoms::engram_types::ContextControls;
 use crate::atoms::error::EngineResult;
+use crate::engine::handoff::HandoffLink;
 use crate::engine::types::Session;
 use log::info;
 use rusqlite::params;
 
+/// engine_config key holding a session's context controls.
+fn context_controls_key(session_id: &str) -> String {
+    format!("context_controls:{}", session_id)
+}
+
+/// engine_config key holding a session's extra instructions.
+fn session_instructions_key(session_id: &str) -> String {
+    format!("session_instructions:{}", session_id)
+}
+
+/// engine_config key holding a session's model override (`/model`).
+fn session_model_key(session_id: &str) -> String {
+    format!("session_model:{}", session_id)
+}
+
+fn handoff_chain_key(session_id: &str) -> String {
+    format!("handoff_chain:{}", session_id)
+}
+
 impl SessionStore {
     // ── Session CRUD ───────────────────────────────────────────────────
 
@@ -43,7 +64,8 @@ impl SessionStore {
         limit: i64,
         agent_id: Option<&str>,
     ) -> EngineResult<Vec<Session>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         let (sql, params_vec): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = if let Some(aid) =
             agent_id
@@ -85,7 +107,8 @@ impl SessionStore {
     }
 
     pub fn get_session(&self, id: &str) -> EngineResult<Option<Session>> {
-        let conn = self.conn.lock();
+        let rc = self.read_conn();
+        let conn = rc.lock();
 
         let result = conn.query_row(
             "SELECT id, label, model, system_prompt, created_at, updated_at, message_count, agent_id
@@ -121,13 +144,123 @@ impl SessionStore {
         Ok(())
     }
 
+    /// Hand the session to another agent (see engine::handoff).
+    pub fn set_session_agent(&self, id: &str, agent_id: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE sessions SET agent_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+            params![agent_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Every agent handoff in the session, oldest first.
+    pub fn get_handoff_chain(&self, session_id: &str) -> EngineResult<Vec<HandoffLink>> {
+        Ok(self
+            .get_config(&handoff_chain_key(session_id))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// Append a handoff to the session's chain and return the whole chain.
+    pub fn push_handoff(
+        &self,
+        session_id: &str,
+        link: HandoffLink,
+    ) -> EngineResult<Vec<HandoffLink>> {
+        let mut chain = self.get_handoff_chain(session_id)?;
+        chain.push(link);
+        self.set_config(
+            &handoff_chain_key(session_id),
+            &serde_json::to_string(&chain)?,
+        )?;
+        Ok(chain)
+    }
+
     pub fn delete_session(&self, id: &str) -> EngineResult<()> {
         let conn = self.conn.lock();
         conn.execute("DELETE FROM messages WHERE session_id = ?1", params![id])?;
+        conn.execute(
+            "DELETE FROM message_citations WHERE session_id = ?1",
+            params![id],
+        )?;
         conn.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+        conn.execute(
+            "DELETE FROM engine_config WHERE key IN (?1, ?2, ?3, ?4)",
+            params![
+                context_controls_key(id),
+                session_instructions_key(id),
+                session_model_key(id),
+                handoff_chain_key(id)
+            ],
+        )?;
         Ok(())
     }
 
+    /// Extra instructions layered on top of the agent prompt for one session.
+    pub fn get_session_instructions(&self, session_id: &str) -> EngineResult<Option<String>> {
+        Ok(self
+            .get_config(&session_instructions_key(session_id))?
+            .filter(|s| !s.trim().is_empty()))
+    }
+
+    /// Set or clear (empty text) a session's instructions.
+    pub fn set_session_instructions(&self, session_id: &str, text: &str) -> EngineResult<()> {
+        let key = session_instructions_key(session_id);
+        if text.trim().is_empty() {
+            let conn = self.conn.lock();
+            conn.execute("DELETE FROM engine_config WHERE key = ?1", params![key])?;
+            return Ok(());
+        }
+        self.set_config(&key, text.trim())
+    }
+
+    /// Model pinned to a session with `/model`, if any. Takes precedence
+    /// over the model a surface would otherwise pick.
+    pub fn get_session_model(&self, session_id: &str) -> EngineResult<Option<String>> {
+        Ok(self
+            .get_config(&session_model_key(session_id))?
+            .filter(|m| !m.trim().is_empty()))
+    }
+
+    /// Pin a model to a session, or clear the pin with `None`.
+    pub fn set_session_model(&self, session_id: &str, model: Option<&str>) -> EngineResult<()> {
+        let key = session_model_key(session_id);
+        match model.map(str::trim).filter(|m| !m.is_empty()) {
+            Some(m) => self.set_config(&key, m),
+            None => {
+                let conn = self.conn.lock();
+                conn.execute("DELETE FROM engine_config WHERE key = ?1", params![key])?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Context inclusion controls for a session (defaults when unset).
+    pub fn get_context_controls(&self, session_id: &str) -> EngineResult<ContextControls> {
+        Ok(self
+            .get_config(&context_controls_key(session_id))?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// Persist context inclusion controls for a session.
+    /// Storing the defaults removes the entry.
+    pub fn set_context_controls(
+        &self,
+        session_id: &str,
+        controls: &ContextControls,
+    ) -> EngineResult<()> {
+        let key = context_controls_key(session_id);
+        if *controls == ContextControls::default() {
+            let conn = self.conn.lock();
+            conn.execute("DELETE FROM engine_config WHERE key = ?1", params![key])?;
+            return Ok(());
+        }
+        let json = serde_json::to_string(controls)?;
+        self.set_config(&key, &json)
+    }
+
     /// Clear all messages for a session but keep the session itself.
     pub fn clear_messages(&self, session_id: &str) -> EngineResult<()> {
         let conn = self.conn.lock();
@@ -135,6 +268,10 @@ impl SessionStore {
             "DELETE FROM messages WHERE session_id = ?1",
             params![session_id],
         )?;
+        conn.execute(
+            "DELETE FROM message_citations WHERE session_id = ?1",
+            params![session_id],
+        )?;
         conn.execute(
             "UPDATE sessions SET message_count = 0, updated_at = datetime('now') WHERE id = ?1",
             params![session_id],
@@ -223,3 +360,80 @@ impl SessionStore {
         Ok(deleted)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sessions::schema::run_migrations;
+    use rusqlite::Connection;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    #[test]
+    fn test_context_controls_roundtrip() {
+        let store = test_store();
+        assert_eq!(
+            store.get_context_controls("s1").unwrap(),
+            ContextControls::default()
+        );
+        let controls = ContextControls {
+            include_memories: false,
+            skill_ids: Some(vec!["github".into()]),
+            max_history_messages: Some(10),
+            ..Default::default()
+        };
+        store.set_context_controls("s1", &controls).unwrap();
+        assert_eq!(store.get_context_controls("s1").unwrap(), controls);
+        assert_eq!(
+            store.get_context_controls("s2").unwrap(),
+            ContextControls::default()
+        );
+    }
+
+    #[test]
+    fn test_context_controls_reset_and_delete() {
+        let store = test_store();
+        let controls = ContextControls {
+            include_soul_files: false,
+            ..Default::default()
+        };
+        store.set_context_controls("s1", &controls).unwrap();
+        store
+            .set_context_controls("s1", &ContextControls::default())
+            .unwrap();
+        assert!(store.get_config("context_controls:s1").unwrap().is_none());
+
+        store.set_context_controls("s1", &controls).unwrap();
+        store.delete_session("s1").unwrap();
+        assert!(store.get_config("context_controls:s1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_session_instructions() {
+        let store = test_store();
+        assert!(store.get_session_instructions("s1").unwrap().is_none());
+        store
+            .set_session_instructions("s1", "  Reply in French. ")
+            .unwrap();
+        assert_eq!(
+            store.get_session_instructions("s1").unwrap().as_deref(),
+            Some("Reply in French.")
+        );
+        store.set_session_instructions("s1", " ").unwrap();
+        assert!(store.get_session_instructions("s1").unwrap().is_none());
+
+        store.set_session_instructions("s1", "Be terse.").unwrap();
+        store.set_session_model("s1", Some("gpt-4o")).unwrap();
+        assert_eq!(
+            store.get_session_model("s1").unwrap().as_deref(),
+            Some("gpt-4o")
+        );
+        store.delete_session("s1").unwrap();
+        assert!(store.get_session_instructions("s1").unwrap().is_none());
+        assert!(store.get_session_model("s1").unwrap().is_none());
+    }
+}
@@ -102,6 +102,21 @@ impl SessionStore {
         Ok(keys)
     }
 
+    /// List every stored `(skill_id, cred_key)` pair (not the values).
+    pub fn list_all_skill_credential_keys(&self) -> EngineResult<Vec<(String, String)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT skill_id, cred_key FROM skill_credentials ORDER BY skill_id, cred_key",
+        )?;
+        let pairs: Vec<(String, String)> = stmt
+            .query_map([], |row: &rusqlite::Row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(pairs)
+    }
+
     /// Get/set skill enabled state.
     pub fn set_skill_enabled(&self, skill_id: &str, enabled: bool) -> EngineResult<()> {
         let conn = self.conn.lock();
@@ -223,6 +223,27 @@ impl SessionStore {
         Ok(tasks)
     }
 
+    /// Enabled cron tasks that aren't due yet but will be by `until` (RFC 3339).
+    pub fn get_upcoming_cron_tasks(&self, until: &str) -> EngineResult<Vec<Task>> {
+        let conn = self.conn.lock();
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut stmt = conn.prepare(
+            "SELECT id, title, description, status, priority, assigned_agent, session_id,
+                    cron_schedule, cron_enabled, last_run_at, next_run_at, created_at, updated_at, model,
+                    event_trigger, persistent
+             FROM tasks WHERE cron_enabled = 1 AND next_run_at > ?1 AND next_run_at <= ?2",
+        )?;
+
+        let mut tasks: Vec<Task> = stmt
+            .query_map(params![now, until], Task::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        load_task_agents(&conn, &mut tasks)?;
+
+        Ok(tasks)
+    }
+
     /// Update a task's cron run timestamps.
     pub fn update_task_cron_run(
         &self,
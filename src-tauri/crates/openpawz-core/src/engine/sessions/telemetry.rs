@@ -5,6 +5,8 @@
 use crate::atoms::error::EngineResult;
 use crate::atoms::types::TelemetryMetricRow;
 use crate::engine::sessions::SessionStore;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike};
+use std::collections::HashMap;
 
 impl SessionStore {
     /// Record a telemetry metric for a given date and session.
@@ -46,6 +48,19 @@ impl SessionStore {
         Ok(())
     }
 
+    /// Link the session's newest metric row to the run it measured, so
+    /// feedback on the run can be attributed to its model.
+    pub fn tag_latest_metric_run(&self, session_id: &str, run_id: &str) -> EngineResult<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE telemetry_metrics SET run_id = ?2
+             WHERE id = (SELECT MAX(id) FROM telemetry_metrics
+                         WHERE session_id = ?1 AND run_id IS NULL)",
+            rusqlite::params![session_id, run_id],
+        )?;
+        Ok(())
+    }
+
     /// Get aggregated metrics for a single date.
     pub fn get_daily_metrics(&self, date: &str) -> EngineResult<TelemetryDailySummary> {
         let conn = self.conn.lock();
@@ -226,6 +241,220 @@ pub struct TelemetryModelBreakdown {
     pub turn_count: u32,
 }
 
+// ── Timeseries ────────────────────────────────────────────────────────
+// Trend lines for the dashboard, bucketed in SQLite so the frontend never
+// aggregates raw tables. Buckets are UTC, like telemetry_metrics.date.
+
+/// Most buckets one series may have (a year of hours is too many to draw).
+const MAX_BUCKETS: i64 = 2000;
+
+/// Metrics `stats_timeseries` can chart.
+pub const STATS_METRICS: [&str; 5] = [
+    "messages",
+    "tokens",
+    "cost",
+    "tool_calls",
+    "tool_success_rate",
+];
+
+/// One bucket of a timeseries.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StatsPoint {
+    /// Bucket start: "YYYY-MM-DD HH:00" for hours, "YYYY-MM-DD" for days
+    /// and weeks (which start on Monday).
+    pub bucket: String,
+    pub value: f64,
+    /// Rows behind `value`. A rate with no samples is a gap, not a zero.
+    pub samples: u64,
+}
+
+/// Bucket width of a timeseries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsResolution {
+    Hour,
+    Day,
+    Week,
+}
+
+impl std::str::FromStr for StatsResolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            other => Err(format!(
+                "Unknown resolution '{}' (expected hour, day or week)",
+                other
+            )),
+        }
+    }
+}
+
+impl StatsResolution {
+    fn step(self) -> chrono::Duration {
+        match self {
+            Self::Hour => chrono::Duration::hours(1),
+            Self::Day => chrono::Duration::days(1),
+            Self::Week => chrono::Duration::weeks(1),
+        }
+    }
+
+    /// Start of the bucket containing `t`.
+    fn floor(self, t: NaiveDateTime) -> NaiveDateTime {
+        let day = t.date().and_time(NaiveTime::MIN);
+        match self {
+            Self::Hour => day + chrono::Duration::hours(t.hour() as i64),
+            Self::Day => day,
+            Self::Week => day - chrono::Duration::days(t.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    fn label(self, t: NaiveDateTime) -> String {
+        match self {
+            Self::Hour => t.format("%Y-%m-%d %H:00").to_string(),
+            Self::Day | Self::Week => t.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    /// SQL for the label of the bucket containing timestamp `ts`.
+    fn sql_label(self, ts: &str) -> String {
+        match self {
+            Self::Hour => format!("strftime('%Y-%m-%d %H:00', {})", ts),
+            Self::Day => format!("date({})", ts),
+            Self::Week => format!("date({}, 'weekday 0', '-6 days')", ts),
+        }
+    }
+}
+
+/// Parse a period like "24h", "7d" or "12w".
+fn parse_period(period: &str) -> Result<chrono::Duration, String> {
+    let invalid = || format!("Invalid period '{}' (expected e.g. 24h, 7d or 12w)", period);
+    let period = period.trim();
+    let unit = period.chars().last().ok_or_else(invalid)?;
+    let n: i64 = period[..period.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    if n <= 0 {
+        return Err(invalid());
+    }
+    match unit {
+        'h' => Ok(chrono::Duration::hours(n)),
+        'd' => Ok(chrono::Duration::days(n)),
+        'w' => Ok(chrono::Duration::weeks(n)),
+        _ => Err(invalid()),
+    }
+}
+
+impl SessionStore {
+    /// A metric over the last `period`, one point per `resolution` bucket
+    /// (oldest first, empty buckets included). Metrics:
+    ///   - messages: user and assistant messages
+    ///   - tokens / cost: input + output tokens and USD from telemetry
+    ///   - tool_calls / tool_success_rate: from the audit log (rate is 0–1)
+    pub fn stats_timeseries(
+        &self,
+        metric: &str,
+        period: &str,
+        resolution: &str,
+    ) -> EngineResult<Vec<StatsPoint>> {
+        self.stats_timeseries_at(metric, period, resolution, chrono::Utc::now().naive_utc())
+    }
+
+    fn stats_timeseries_at(
+        &self,
+        metric: &str,
+        period: &str,
+        resolution: &str,
+        now: NaiveDateTime,
+    ) -> EngineResult<Vec<StatsPoint>> {
+        let resolution: StatsResolution = resolution.parse()?;
+        let span = parse_period(period)?;
+        let step = resolution.step();
+        let buckets = (span.num_seconds() + step.num_seconds() - 1) / step.num_seconds();
+        if buckets > MAX_BUCKETS {
+            return Err(format!(
+                "{} buckets is too many to chart — use a coarser resolution or a shorter period",
+                buckets
+            )
+            .into());
+        }
+        let first = resolution.floor(now) - step * (buckets as i32 - 1);
+
+        // (table, timestamp, value, filter)
+        let (table, ts, value, filter) = match metric {
+            "messages" => (
+                "messages",
+                "created_at",
+                "COUNT(*)",
+                "role IN ('user', 'assistant')",
+            ),
+            "tokens" => (
+                "telemetry_metrics",
+                "created_at",
+                "SUM(input_tokens + output_tokens)",
+                "1",
+            ),
+            "cost" => ("telemetry_metrics", "created_at", "SUM(cost_usd)", "1"),
+            "tool_calls" => (
+                "unified_audit_log",
+                "datetime(timestamp)",
+                "COUNT(*)",
+                "category = 'tool_call'",
+            ),
+            "tool_success_rate" => (
+                "unified_audit_log",
+                "datetime(timestamp)",
+                "AVG(success)",
+                "category = 'tool_call'",
+            ),
+            other => {
+                return Err(format!(
+                    "Unknown metric '{}' (expected one of: {})",
+                    other,
+                    STATS_METRICS.join(", ")
+                )
+                .into())
+            }
+        };
+        let sql = format!(
+            "SELECT {label} AS bucket, {value}, COUNT(*)
+             FROM {table}
+             WHERE {filter} AND {ts} >= ?1
+             GROUP BY bucket",
+            label = resolution.sql_label(ts),
+        );
+
+        let rc = self.read_conn();
+        let conn = rc.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let found = stmt
+            .query_map(
+                rusqlite::params![first.format("%Y-%m-%d %H:%M:%S").to_string()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        (row.get::<_, f64>(1)?, row.get::<_, i64>(2)? as u64),
+                    ))
+                },
+            )?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok((0..buckets as i32)
+            .map(|i| {
+                let bucket = resolution.label(first + step * i);
+                let (value, samples) = found.get(&bucket).copied().unwrap_or((0.0, 0));
+                StatsPoint {
+                    bucket,
+                    value,
+                    samples,
+                }
+            })
+            .collect())
+    }
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -415,4 +644,72 @@ mod tests {
         assert_eq!(range.len(), 1);
         assert_eq!(range[0].date, "2026-03-04");
     }
+
+    #[test]
+    fn timeseries_fills_empty_buckets() {
+        let store = test_store();
+        {
+            let conn = store.conn.lock();
+            conn.execute_batch(
+                "INSERT INTO sessions (id, model) VALUES ('s1', 'gpt-4o');
+                 INSERT INTO messages (id, session_id, role, created_at) VALUES
+                    ('m1', 's1', 'user', '2026-10-13 09:00:00'),
+                    ('m2', 's1', 'assistant', '2026-10-13 09:00:05'),
+                    ('m3', 's1', 'tool', '2026-10-13 09:00:06'),
+                    ('m4', 's1', 'user', '2026-10-15 11:30:00'),
+                    ('m5', 's1', 'user', '2026-09-01 11:30:00');
+                 INSERT INTO unified_audit_log
+                    (timestamp, category, action, subject, success, prev_hash, signature)
+                 VALUES
+                    ('2026-10-14T10:00:00+00:00', 'tool_call', 'execute', 'exec', 1, '', ''),
+                    ('2026-10-14T23:30:00-02:00', 'tool_call', 'execute', 'exec', 0, '', ''),
+                    ('2026-10-14T12:00:00.123456+00:00', 'memory', 'store', '', 1, '', '');",
+            )
+            .unwrap();
+        }
+        let now = NaiveDateTime::parse_from_str("2026-10-15 12:00", "%Y-%m-%d %H:%M").unwrap();
+
+        let messages = store
+            .stats_timeseries_at("messages", "3d", "day", now)
+            .unwrap();
+        let days: Vec<_> = messages
+            .iter()
+            .map(|p| (p.bucket.as_str(), p.value))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("2026-10-13", 2.0),
+                ("2026-10-14", 0.0),
+                ("2026-10-15", 1.0)
+            ]
+        );
+
+        // 23:30 at UTC-2 is 01:30 UTC the next day
+        let rate = store
+            .stats_timeseries_at("tool_success_rate", "3d", "day", now)
+            .unwrap();
+        assert_eq!((rate[1].value, rate[1].samples), (1.0, 1));
+        assert_eq!((rate[2].value, rate[2].samples), (0.0, 1));
+
+        let weeks = store
+            .stats_timeseries_at("messages", "2w", "week", now)
+            .unwrap();
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[1].bucket, "2026-10-12");
+        assert_eq!(weeks[1].value, 3.0);
+
+        assert!(store
+            .stats_timeseries_at("messages", "7x", "day", now)
+            .is_err());
+        assert!(store
+            .stats_timeseries_at("sessions", "7d", "day", now)
+            .is_err());
+        assert!(store
+            .stats_timeseries_at("tokens", "1y", "hour", now)
+            .is_err());
+        assert!(store
+            .stats_timeseries_at("tokens", "365d", "hour", now)
+            .is_err());
+    }
 }
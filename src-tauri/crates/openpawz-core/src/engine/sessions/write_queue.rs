@@ -0,0 +1,357 @@
+// Paw Agent Engine — Batched writes and WAL tuning
+//
+// A streaming run writes usage records and task activity next to its
+// messages. Rather than one commit (and one WAL append) each, they're
+// queued here and written in a single transaction by the background writer
+// in lib.rs every `db_write.flush_secs`. Messages and everything else the
+// engine reads back stay synchronous. The one reader of queued rows mid-run
+// is the project budget check (get_project_spend), which flushes first.
+//
+// The same writer runs a PASSIVE checkpoint every `checkpoint_secs`, so the
+// WAL is folded back between runs instead of SQLite's automatic checkpoint
+// (`wal_autocheckpoint_pages`) landing on a message insert mid-run.
+
+use super::SessionStore;
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::DbWriteConfig;
+use log::{info, warn};
+use rusqlite::{params, Connection};
+use std::sync::atomic::Ordering;
+
+/// Flush early once this many writes are queued.
+const MAX_PENDING: usize = 256;
+
+/// A non-critical write that may be delayed until the next flush.
+#[derive(Debug, Clone)]
+pub enum DeferredWrite {
+    /// A `telemetry_metrics` row (as `record_metric`), tagged with its run.
+    Metric {
+        date: String,
+        session_id: String,
+        run_id: Option<String>,
+        model: String,
+        input_tokens: u64,
+        output_tokens: u64,
+        cost_usd: f64,
+        tool_calls: u32,
+        tool_duration_ms: u64,
+        llm_duration_ms: u64,
+        total_duration_ms: u64,
+        rounds: u32,
+    },
+    /// A `task_activity` row (as `add_task_activity`).
+    TaskActivity {
+        id: String,
+        task_id: String,
+        kind: String,
+        agent: Option<String>,
+        content: String,
+    },
+}
+
+impl DeferredWrite {
+    fn write(&self, conn: &Connection) -> rusqlite::Result<()> {
+        match self {
+            DeferredWrite::Metric {
+                date,
+                session_id,
+                run_id,
+                model,
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                tool_calls,
+                tool_duration_ms,
+                llm_duration_ms,
+                total_duration_ms,
+                rounds,
+            } => conn.execute(
+                "INSERT INTO telemetry_metrics
+                    (date, session_id, run_id, model, input_tokens, output_tokens, cost_usd,
+                     tool_calls, tool_duration_ms, llm_duration_ms, total_duration_ms, rounds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    date,
+                    session_id,
+                    run_id,
+                    model,
+                    input_tokens,
+                    output_tokens,
+                    cost_usd,
+                    tool_calls,
+                    tool_duration_ms,
+                    llm_duration_ms,
+                    total_duration_ms,
+                    rounds,
+                ],
+            ),
+            DeferredWrite::TaskActivity {
+                id,
+                task_id,
+                kind,
+                agent,
+                content,
+            } => conn.execute(
+                "INSERT INTO task_activity (id, task_id, kind, agent, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, task_id, kind, agent, content],
+            ),
+        }
+        .map(|_| ())
+    }
+}
+
+/// Whether a write failed because of the database's state rather than the
+/// row itself, so it may succeed on the next flush.
+fn is_transient(e: &rusqlite::Error) -> bool {
+    use rusqlite::ErrorCode::*;
+    matches!(
+        e.sqlite_error_code(),
+        Some(DatabaseBusy | DatabaseLocked | DiskFull | SystemIoFailure | ReadOnly | OutOfMemory)
+    )
+}
+
+impl SessionStore {
+    // ── Batched writes ─────────────────────────────────────────────────
+
+    /// Apply the write config to the write connection. Until this is called
+    /// (tests, side connections) deferred writes go straight to the DB.
+    pub fn tune_writes(&self, config: &DbWriteConfig) -> EngineResult<()> {
+        {
+            let conn = self.conn.lock();
+            conn.pragma_update(None, "wal_autocheckpoint", config.wal_autocheckpoint_pages)?;
+            conn.pragma_update(
+                None,
+                "journal_size_limit",
+                i64::from(config.wal_size_limit_mb) * 1024 * 1024,
+            )?;
+        }
+        let batching = config.flush_secs > 0;
+        if self.batch_writes.swap(batching, Ordering::SeqCst) && !batching {
+            self.flush_deferred()?;
+        }
+        info!(
+            "[db] Write batching {} (flush every {}s), WAL autocheckpoint {} pages",
+            if batching { "on" } else { "off" },
+            config.flush_secs,
+            config.wal_autocheckpoint_pages
+        );
+        Ok(())
+    }
+
+    /// Queue a non-critical write for the next flush (or write it now when
+    /// batching is off). Failures are logged, not returned.
+    pub fn defer_write(&self, write: DeferredWrite) {
+        if !self.batch_writes.load(Ordering::SeqCst) {
+            if let Err(e) = write.write(&self.conn.lock()) {
+                warn!("[db] Deferred write failed: {}", e);
+            }
+            return;
+        }
+        let full = {
+            let mut pending = self.pending_writes.lock();
+            pending.push(write);
+            pending.len() >= MAX_PENDING
+        };
+        if full {
+            if let Err(e) = self.flush_deferred() {
+                warn!("[db] Flushing queued writes failed: {}", e);
+            }
+        }
+    }
+
+    /// Write every queued write in one transaction; returns how many.
+    /// If the transaction fails the writes are retried one at a time: rows
+    /// that fail on their own (a constraint, a bad value) are logged and
+    /// dropped so they can't wedge the queue, while rows that failed because
+    /// the database was busy or unwritable go back for the next flush.
+    pub fn flush_deferred(&self) -> EngineResult<usize> {
+        let batch = std::mem::take(&mut *self.pending_writes.lock());
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        let result = (|| -> rusqlite::Result<()> {
+            let mut conn = self.conn.lock();
+            let tx = conn.transaction()?;
+            for write in &batch {
+                write.write(&tx)?;
+            }
+            tx.commit()
+        })();
+        let Err(batch_err) = result else {
+            return Ok(batch.len());
+        };
+        warn!(
+            "[db] Flushing {} queued writes failed: {} — retrying one by one",
+            batch.len(),
+            batch_err
+        );
+        let mut written = 0;
+        let mut retry = Vec::new();
+        {
+            let conn = self.conn.lock();
+            for write in batch {
+                match write.write(&conn) {
+                    Ok(()) => written += 1,
+                    Err(e) if is_transient(&e) => retry.push(write),
+                    Err(e) => warn!("[db] Dropping queued write {:?}: {}", write, e),
+                }
+            }
+        }
+        if !retry.is_empty() {
+            let mut pending = self.pending_writes.lock();
+            let newer = std::mem::replace(&mut *pending, retry);
+            pending.extend(newer);
+            if written == 0 {
+                return Err(batch_err.into());
+            }
+        }
+        Ok(written)
+    }
+
+    /// Number of writes waiting for the next flush.
+    pub fn pending_write_count(&self) -> usize {
+        self.pending_writes.lock().len()
+    }
+
+    // ── WAL checkpoints ────────────────────────────────────────────────
+
+    /// Checkpoint as much of the WAL as readers allow without blocking
+    /// them. Returns (frames in the WAL, frames checkpointed).
+    pub fn checkpoint_wal(&self) -> EngineResult<(i64, i64)> {
+        let conn = self.conn.lock();
+        let (log, checkpointed) = conn.query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |row| {
+            Ok((row.get(1)?, row.get(2)?))
+        })?;
+        Ok((log, checkpointed))
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_store() -> SessionStore {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::engine::sessions::schema_for_testing(&conn);
+        // task_activity rows reference their task
+        conn.execute("INSERT INTO tasks (id, title) VALUES ('t1', 'Task')", [])
+            .unwrap();
+        SessionStore::from_connection(conn)
+    }
+
+    fn activity(id: &str) -> DeferredWrite {
+        activity_for(id, "t1")
+    }
+
+    fn activity_for(id: &str, task_id: &str) -> DeferredWrite {
+        DeferredWrite::TaskActivity {
+            id: id.into(),
+            task_id: task_id.into(),
+            kind: "agent_started".into(),
+            agent: Some("default".into()),
+            content: "started".into(),
+        }
+    }
+
+    fn activity_rows(store: &SessionStore) -> i64 {
+        store
+            .conn
+            .lock()
+            .query_row("SELECT COUNT(*) FROM task_activity", [], |r| r.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn project_spend_includes_queued_usage() {
+        let store = test_store();
+        store.tune_writes(&DbWriteConfig::default()).unwrap();
+        store.defer_write(DeferredWrite::Metric {
+            date: "2026-10-15".into(),
+            session_id: "eng-project-p1-worker".into(),
+            run_id: None,
+            model: "m".into(),
+            input_tokens: 10,
+            output_tokens: 5,
+            cost_usd: 0.25,
+            tool_calls: 0,
+            tool_duration_ms: 0,
+            llm_duration_ms: 3,
+            total_duration_ms: 3,
+            rounds: 1,
+        });
+        assert_eq!(store.get_project_spend("p1").unwrap(), 0.25);
+        assert_eq!(store.pending_write_count(), 0);
+    }
+
+    #[test]
+    fn writes_are_immediate_until_batching_is_on() {
+        let store = test_store();
+        store.defer_write(activity("a1"));
+        assert_eq!(activity_rows(&store), 1);
+
+        store.tune_writes(&DbWriteConfig::default()).unwrap();
+        store.defer_write(activity("a2"));
+        store.defer_write(DeferredWrite::Metric {
+            date: "2026-10-15".into(),
+            session_id: "s1".into(),
+            run_id: Some("r1".into()),
+            model: "m".into(),
+            input_tokens: 10,
+            output_tokens: 5,
+            cost_usd: 0.01,
+            tool_calls: 1,
+            tool_duration_ms: 2,
+            llm_duration_ms: 3,
+            total_duration_ms: 5,
+            rounds: 1,
+        });
+        assert_eq!(activity_rows(&store), 1);
+        assert_eq!(store.pending_write_count(), 2);
+
+        assert_eq!(store.flush_deferred().unwrap(), 2);
+        assert_eq!(activity_rows(&store), 2);
+        let run: String = store
+            .conn
+            .lock()
+            .query_row("SELECT run_id FROM telemetry_metrics", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(run, "r1");
+    }
+
+    #[test]
+    fn turning_batching_off_flushes_the_queue() {
+        let store = test_store();
+        store.tune_writes(&DbWriteConfig::default()).unwrap();
+        store.defer_write(activity("a1"));
+        assert_eq!(activity_rows(&store), 0);
+
+        let off = DbWriteConfig {
+            flush_secs: 0,
+            ..DbWriteConfig::default()
+        };
+        store.tune_writes(&off).unwrap();
+        assert_eq!(activity_rows(&store), 1);
+        assert_eq!(store.pending_write_count(), 0);
+    }
+
+    #[test]
+    fn a_bad_row_is_dropped_not_requeued() {
+        let store = test_store();
+        store.tune_writes(&DbWriteConfig::default()).unwrap();
+        store.defer_write(activity("a1"));
+        store.defer_write(activity_for("a2", "no-such-task"));
+        store.defer_write(activity("a3"));
+
+        assert_eq!(store.flush_deferred().unwrap(), 2);
+        assert_eq!(activity_rows(&store), 2);
+        assert_eq!(store.pending_write_count(), 0);
+
+        // Alone in its batch, too
+        store.defer_write(activity_for("a4", "no-such-task"));
+        assert_eq!(store.flush_deferred().unwrap(), 0);
+        assert_eq!(store.pending_write_count(), 0);
+    }
+}
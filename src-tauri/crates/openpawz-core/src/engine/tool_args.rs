@@ -0,0 +1,298 @@
+// ── Tool-call argument validation ──────────────────────────────────────────
+//
+// Before a tool runs, its parsed arguments are checked against the
+// ToolDefinition's JSON schema, so a model that sends `"count": "five"` or
+// leaves out a required path is told exactly which field is wrong instead of
+// seeing the tool fail in some unrelated way.
+//
+// Only the subset of JSON Schema that tool definitions use is checked:
+// `type` (a name or a list), `required`, `properties`, `items` and `enum`.
+// Unknown keywords are ignored and extra properties are allowed, so a
+// schema can never reject a call for something it does not describe.
+//
+// Failures are counted per tool (in memory, since startup) to spot tools
+// whose schema or description keeps confusing models.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Errors listed to the model per failed call.
+const MAX_REPORTED_ERRORS: usize = 10;
+
+/// One argument that does not match the schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArgError {
+    /// Path of the field, e.g. `path` or `nodes[2].tool`
+    pub field: String,
+    /// What the schema expects, e.g. "integer" or "one of: done, error"
+    pub expected: String,
+    /// What was sent ("missing" when a required field is absent)
+    pub found: String,
+}
+
+/// Validation counters of one tool.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolValidationStats {
+    pub tool: String,
+    pub checked: u64,
+    pub failed: u64,
+    /// Fields that failed, with how often
+    pub fields: HashMap<String, u64>,
+    pub last_error: Option<String>,
+    pub last_failed_at: Option<String>,
+}
+
+static STATS: LazyLock<Mutex<HashMap<String, ToolValidationStats>>> =
+    LazyLock::new(Default::default);
+
+/// Check `args` against a tool's parameter schema.
+pub fn validate(schema: &Value, args: &Value) -> Vec<ArgError> {
+    let mut errors = Vec::new();
+    check(schema, args, "", &mut errors);
+    errors.truncate(MAX_REPORTED_ERRORS);
+    errors
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<ArgError>) {
+    let field = || {
+        if path.is_empty() {
+            "(arguments)".to_string()
+        } else {
+            path.to_string()
+        }
+    };
+
+    if let Some(expected) = type_names(schema) {
+        if !expected.iter().any(|t| has_type(value, t)) {
+            errors.push(ArgError {
+                field: field(),
+                expected: expected.join(" or "),
+                found: json_type(value).into(),
+            });
+            return;
+        }
+    }
+
+    if let Some(options) = schema["enum"].as_array() {
+        if !options.contains(value) {
+            let listed: Vec<String> = options
+                .iter()
+                .map(|o| {
+                    o.as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| o.to_string())
+                })
+                .collect();
+            errors.push(ArgError {
+                field: field(),
+                expected: format!("one of: {}", listed.join(", ")),
+                found: value.to_string(),
+            });
+            return;
+        }
+    }
+
+    if let Some(obj) = value.as_object() {
+        let properties = schema["properties"].as_object();
+        let required = schema["required"].as_array().into_iter().flatten();
+        for name in required.filter_map(Value::as_str) {
+            if obj.get(name).is_none_or(Value::is_null) {
+                let expected = properties
+                    .and_then(|p| p.get(name))
+                    .and_then(type_names)
+                    .map(|t| t.join(" or "))
+                    .unwrap_or_else(|| "a value".into());
+                errors.push(ArgError {
+                    field: join_path(path, name),
+                    expected,
+                    found: "missing".into(),
+                });
+            }
+        }
+        for (name, sub_schema) in properties.into_iter().flatten() {
+            match obj.get(name) {
+                // An explicit null for an optional field means "not given"
+                Some(Value::Null) | None => {}
+                Some(v) => check(sub_schema, v, &join_path(path, name), errors),
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            check(items, item, &format!("{}[{}]", field(), i), errors);
+        }
+    }
+}
+
+fn type_names(schema: &Value) -> Option<Vec<&str>> {
+    match &schema["type"] {
+        Value::String(t) => Some(vec![t.as_str()]),
+        Value::Array(ts) => Some(ts.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+fn has_type(value: &Value, type_name: &str) -> bool {
+    match type_name {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        // Unknown type names are not enforced
+        _ => true,
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// The tool result the model gets for invalid arguments: a readable list
+/// plus the same errors as JSON.
+pub fn error_message(tool: &str, errors: &[ArgError]) -> String {
+    let mut out = format!(
+        "ERROR: Invalid arguments for '{}'. Fix these fields and call the tool again:\n",
+        tool
+    );
+    for e in errors {
+        out.push_str(&format!(
+            "- `{}`: expected {}, got {}\n",
+            e.field, e.expected, e.found
+        ));
+    }
+    out.push_str(&serde_json::json!({ "validation_errors": errors }).to_string());
+    out
+}
+
+/// Count one validated call of `tool`.
+pub fn record(tool: &str, errors: &[ArgError]) {
+    let mut stats = STATS.lock();
+    let entry = stats
+        .entry(tool.to_string())
+        .or_insert_with(|| ToolValidationStats {
+            tool: tool.to_string(),
+            ..Default::default()
+        });
+    entry.checked += 1;
+    if errors.is_empty() {
+        return;
+    }
+    entry.failed += 1;
+    for e in errors {
+        *entry.fields.entry(e.field.clone()).or_default() += 1;
+    }
+    entry.last_error = Some(format!(
+        "{}: expected {}, got {}",
+        errors[0].field, errors[0].expected, errors[0].found
+    ));
+    entry.last_failed_at = Some(chrono::Utc::now().to_rfc3339());
+}
+
+/// Validation counters of every tool with at least one failure, most
+/// failures first.
+pub fn stats() -> Vec<ToolValidationStats> {
+    let mut all: Vec<ToolValidationStats> = STATS
+        .lock()
+        .values()
+        .filter(|s| s.failed > 0)
+        .cloned()
+        .collect();
+    all.sort_by(|a, b| b.failed.cmp(&a.failed).then_with(|| a.tool.cmp(&b.tool)));
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "timeout": { "type": "integer" },
+                "mode": { "type": "string", "enum": ["read", "write"] },
+                "nodes": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "tool": { "type": "string" } },
+                        "required": ["tool"]
+                    }
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[test]
+    fn valid_arguments_pass() {
+        let args = json!({ "path": "a.txt", "timeout": 30, "mode": "read", "extra": true });
+        assert!(validate(&schema(), &args).is_empty());
+        assert!(validate(&schema(), &json!({ "path": "a", "timeout": null })).is_empty());
+        assert!(validate(&json!({}), &json!("anything")).is_empty());
+    }
+
+    #[test]
+    fn errors_name_the_field_and_expected_type() {
+        let args = json!({
+            "timeout": "30",
+            "mode": "append",
+            "nodes": [{ "tool": "exec" }, { "args": {} }]
+        });
+        let errors = validate(&schema(), &args);
+        let by_field: HashMap<&str, &ArgError> =
+            errors.iter().map(|e| (e.field.as_str(), e)).collect();
+        assert_eq!(by_field["path"].found, "missing");
+        assert_eq!(by_field["path"].expected, "string");
+        assert_eq!(by_field["timeout"].expected, "integer");
+        assert_eq!(by_field["timeout"].found, "string");
+        assert_eq!(by_field["mode"].expected, "one of: read, write");
+        assert_eq!(by_field["nodes[1].tool"].found, "missing");
+        assert_eq!(errors.len(), 4);
+
+        let top = validate(&schema(), &json!([1, 2]));
+        assert_eq!(top[0].field, "(arguments)");
+        assert_eq!(top[0].expected, "object");
+    }
+
+    #[test]
+    fn failures_are_counted_per_tool() {
+        let errors = validate(&schema(), &json!({}));
+        record("stats_test_tool", &errors);
+        record("stats_test_tool", &[]);
+        let s = stats()
+            .into_iter()
+            .find(|s| s.tool == "stats_test_tool")
+            .unwrap();
+        assert_eq!((s.checked, s.failed), (2, 1));
+        assert_eq!(s.fields["path"], 1);
+        assert!(error_message("stats_test_tool", &errors).contains("\"validation_errors\""));
+    }
+}
@@ -0,0 +1,544 @@
+// ── Tool error taxonomy ────────────────────────────────────────────────────
+//
+// A failed tool call gets a ToolErrorKind: auth, rate-limit, not-found,
+// permission, network or invalid-input. Typed engine errors map directly
+// (an HTTP status, an io::ErrorKind, EngineError::Auth…); the free-form
+// messages most tools still return are classified from their wording.
+//
+// The kind drives the agent loop's circuit breaker (ToolFailures):
+//   • auth / permission — retrying cannot help, the tool is blocked at once
+//   • rate-limit / network — transient, the model may retry after a pause
+//   • not-found / invalid-input — the model should change its arguments
+// and the breaker's system nudges summarize the kinds seen so far.
+//
+// Thresholds come from EngineConfig.circuit_breaker, with per-tool
+// overrides. A blocked tool stays blocked for the session until its
+// cooldown ends, then gets one more chance.
+
+use crate::atoms::error::EngineError;
+use crate::atoms::types::{CircuitBreakerConfig, ToolErrorKind};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+impl ToolErrorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ToolErrorKind::Auth => "authentication failed",
+            ToolErrorKind::RateLimit => "rate limited",
+            ToolErrorKind::NotFound => "not found",
+            ToolErrorKind::Permission => "permission denied",
+            ToolErrorKind::Network => "network error",
+            ToolErrorKind::InvalidInput => "invalid input",
+        }
+    }
+
+    /// Whether calling the tool again, unchanged, can succeed.
+    pub fn is_transient(self) -> bool {
+        matches!(self, ToolErrorKind::RateLimit | ToolErrorKind::Network)
+    }
+
+    /// Whether the tool should be blocked after a single failure.
+    pub fn blocks_tool(self) -> bool {
+        matches!(self, ToolErrorKind::Auth | ToolErrorKind::Permission)
+    }
+
+    /// What the model should do about it.
+    pub fn advice(self) -> &'static str {
+        match self {
+            ToolErrorKind::Auth => {
+                "The credentials for this tool are missing or invalid — the user has to fix them \
+                 in the skill settings."
+            }
+            ToolErrorKind::RateLimit => {
+                "The service is throttling requests — wait before retrying, make fewer calls, or \
+                 use another source."
+            }
+            ToolErrorKind::NotFound => {
+                "What you asked for does not exist — check names, paths and ids (list or search \
+                 first) instead of guessing."
+            }
+            ToolErrorKind::Permission => {
+                "This action is not allowed with the current permissions — ask the user or do \
+                 without it."
+            }
+            ToolErrorKind::Network => {
+                "The service could not be reached — retry once, then tell the user if it is \
+                 still down."
+            }
+            ToolErrorKind::InvalidInput => {
+                "The arguments are wrong — read the error and the tool's parameters and change \
+                 them before calling again."
+            }
+        }
+    }
+
+    fn from_status(status: u16) -> Option<Self> {
+        match status {
+            401 => Some(ToolErrorKind::Auth),
+            403 => Some(ToolErrorKind::Permission),
+            404 | 410 => Some(ToolErrorKind::NotFound),
+            429 => Some(ToolErrorKind::RateLimit),
+            400 | 422 => Some(ToolErrorKind::InvalidInput),
+            502..=504 => Some(ToolErrorKind::Network),
+            _ => None,
+        }
+    }
+}
+
+/// Classify a typed engine error, falling back to its message.
+pub fn classify_error(err: &EngineError) -> Option<ToolErrorKind> {
+    match err {
+        EngineError::Auth(_) | EngineError::Keyring(_) => Some(ToolErrorKind::Auth),
+        EngineError::Security(_) => Some(ToolErrorKind::Permission),
+        EngineError::Serialization(_) => Some(ToolErrorKind::InvalidInput),
+        EngineError::Network(e) => e
+            .status()
+            .and_then(|s| ToolErrorKind::from_status(s.as_u16()))
+            .or(Some(ToolErrorKind::Network)),
+        EngineError::Io(e) => match e.kind() {
+            std::io::ErrorKind::NotFound => Some(ToolErrorKind::NotFound),
+            std::io::ErrorKind::PermissionDenied => Some(ToolErrorKind::Permission),
+            std::io::ErrorKind::TimedOut
+            | std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionReset => Some(ToolErrorKind::Network),
+            std::io::ErrorKind::InvalidInput => Some(ToolErrorKind::InvalidInput),
+            _ => classify(&e.to_string()),
+        },
+        _ => classify(&err.to_string()),
+    }
+}
+
+/// Phrases that give a failure's kind away, checked in order.
+const PHRASES: &[(ToolErrorKind, &[&str])] = &[
+    (
+        ToolErrorKind::RateLimit,
+        &[
+            "rate limit",
+            "rate-limit",
+            "too many requests",
+            "quota exceeded",
+        ],
+    ),
+    (
+        ToolErrorKind::InvalidInput,
+        &["invalid argument", "missing required", "malformed"],
+    ),
+    (
+        ToolErrorKind::Auth,
+        &[
+            "unauthorized",
+            "unauthenticated",
+            "not authenticated",
+            "authentication",
+            "invalid api key",
+            "invalid_api_key",
+            "invalid token",
+            "token expired",
+            "expired token",
+            "credentials",
+        ],
+    ),
+    (
+        ToolErrorKind::Permission,
+        &[
+            "permission denied",
+            "forbidden",
+            "access denied",
+            "not permitted",
+            "not allowed",
+            "requires human approval",
+        ],
+    ),
+    (
+        ToolErrorKind::NotFound,
+        &[
+            "not found",
+            "no such file",
+            "does not exist",
+            "doesn't exist",
+            "unknown tool",
+        ],
+    ),
+    (
+        ToolErrorKind::Network,
+        &[
+            "timed out",
+            "connection refused",
+            "connection reset",
+            "dns error",
+            "could not connect",
+            "failed to connect",
+            "error sending request",
+            "network error",
+            "unreachable",
+        ],
+    ),
+    (
+        ToolErrorKind::InvalidInput,
+        &["invalid value", "is required", "must be"],
+    ),
+];
+
+/// Classify a free-form error message.
+pub fn classify(message: &str) -> Option<ToolErrorKind> {
+    let text = message.to_lowercase();
+    // An HTTP status in the message is the most reliable signal
+    for status in [429, 401, 403, 404, 410, 422, 400, 502, 503, 504] {
+        if has_status(&text, status) {
+            return ToolErrorKind::from_status(status);
+        }
+    }
+    PHRASES
+        .iter()
+        .find(|(_, needles)| needles.iter().any(|n| text.contains(n)))
+        .map(|(kind, _)| *kind)
+}
+
+/// Whether `text` mentions the status code as a number of its own
+/// ("HTTP 404", "(429)"), not as part of a longer number.
+fn has_status(text: &str, status: u16) -> bool {
+    let code = status.to_string();
+    text.match_indices(&code).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + code.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric() || c == '.')
+            && !after.is_some_and(|c| c.is_ascii_alphanumeric() || c == '.')
+    })
+}
+
+/// What the circuit breaker tells the model after a failure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakerNudge {
+    /// The tool is blocked until its cooldown ends
+    pub blocked: bool,
+    pub failures: u32,
+    /// System message for the model
+    pub text: String,
+}
+
+/// The thresholds that apply to one tool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Thresholds {
+    nudge_after: u32,
+    block_after: u32,
+    /// None = blocked for the rest of the session
+    cooldown: Option<Duration>,
+}
+
+impl Thresholds {
+    fn of(config: &CircuitBreakerConfig, tool: &str) -> Self {
+        let over = config.tools.get(tool).cloned().unwrap_or_default();
+        let cooldown_minutes = over.cooldown_minutes.unwrap_or(config.cooldown_minutes);
+        Thresholds {
+            nudge_after: over.nudge_after.unwrap_or(config.nudge_after),
+            block_after: over.block_after.unwrap_or(config.block_after),
+            cooldown: (cooldown_minutes > 0)
+                .then(|| Duration::from_secs(u64::from(cooldown_minutes) * 60)),
+        }
+    }
+
+    fn block_span(&self) -> String {
+        match self.cooldown {
+            Some(d) => format!("for {} minutes", d.as_secs() / 60),
+            None => "for the rest of this session".into(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ToolBreaker {
+    /// Kinds of the consecutive failures so far
+    fails: Vec<Option<ToolErrorKind>>,
+    blocked_at: Option<Instant>,
+    /// Re-enabled after a cooldown: the next failure blocks it again
+    probation: bool,
+}
+
+/// Consecutive failures per tool within one chat session. Kept across
+/// turns, so a flapping tool stays quiet until its cooldown ends.
+#[derive(Debug, Default)]
+pub struct ToolFailures {
+    config: CircuitBreakerConfig,
+    per_tool: HashMap<String, ToolBreaker>,
+}
+
+impl ToolFailures {
+    /// Apply the current config (it may have changed since the last turn).
+    pub fn configure(&mut self, config: &CircuitBreakerConfig) {
+        self.config = config.clone();
+    }
+
+    /// The refusal for a call to a blocked tool, or None if it may run.
+    /// A tool whose cooldown has ended is re-enabled on probation.
+    pub fn blocked(&mut self, tool: &str, now: Instant) -> Option<String> {
+        let limits = Thresholds::of(&self.config, tool);
+        let breaker = self.per_tool.get_mut(tool)?;
+        let blocked_at = breaker.blocked_at?;
+        if let Some(cooldown) = limits.cooldown {
+            let elapsed = now.saturating_duration_since(blocked_at);
+            if elapsed >= cooldown {
+                breaker.fails.clear();
+                breaker.blocked_at = None;
+                breaker.probation = true;
+                return None;
+            }
+        }
+        let until = match limits.cooldown {
+            Some(cooldown) => {
+                let left = cooldown.saturating_sub(now.saturating_duration_since(blocked_at));
+                format!(
+                    "for another {} minute(s)",
+                    left.as_secs().div_ceil(60).max(1)
+                )
+            }
+            None => "for the rest of this session".into(),
+        };
+        Some(match breaker.fails.last().copied().flatten() {
+            Some(kind) if kind.blocks_tool() => format!(
+                "Error: Tool '{}' is blocked {} ({}). {}",
+                tool,
+                until,
+                kind.label(),
+                kind.advice()
+            ),
+            _ => format!(
+                "Error: Tool '{}' is blocked {} after {} consecutive failures ({}). Use a \
+                 different tool or tell the user.",
+                tool,
+                until,
+                breaker.fails.len(),
+                summarize(&breaker.fails)
+            ),
+        })
+    }
+
+    /// Record the outcome of a call. Returns the system nudge to inject,
+    /// if the failures call for one.
+    pub fn record(
+        &mut self,
+        tool: &str,
+        success: bool,
+        kind: Option<ToolErrorKind>,
+        now: Instant,
+    ) -> Option<BreakerNudge> {
+        if success {
+            self.per_tool.remove(tool);
+            return None;
+        }
+        let limits = Thresholds::of(&self.config, tool);
+        let breaker = self.per_tool.entry(tool.to_string()).or_default();
+        breaker.fails.push(kind);
+        let count = breaker.fails.len() as u32;
+
+        if let Some(kind) = kind.filter(|k| k.blocks_tool()) {
+            breaker.blocked_at = Some(now);
+            return Some(BreakerNudge {
+                blocked: true,
+                failures: count,
+                text: format!(
+                    "[SYSTEM] HARD STOP: The tool '{}' failed with {} and is now BLOCKED {} — \
+                     retrying cannot fix this. {} Do NOT call '{}' again; tell the user what \
+                     happened and continue without it.",
+                    tool,
+                    kind.label(),
+                    limits.block_span(),
+                    kind.advice(),
+                    tool
+                ),
+            });
+        }
+        if breaker.probation || (limits.block_after > 0 && count >= limits.block_after) {
+            let why = if breaker.probation {
+                "failed again right after its cooldown".to_string()
+            } else {
+                format!("has failed {} times in a row", count)
+            };
+            breaker.blocked_at = Some(now);
+            return Some(BreakerNudge {
+                blocked: true,
+                failures: count,
+                text: format!(
+                    "[SYSTEM] HARD STOP: The tool '{}' {} ({}) and is now BLOCKED {}. Do NOT call \
+                     '{}' again — it will not work. Instead, tell the user what happened and \
+                     suggest they check their skill configuration or try a different approach. \
+                     Provide a text summary now.",
+                    tool,
+                    why,
+                    summarize(&breaker.fails),
+                    limits.block_span(),
+                    tool
+                ),
+            });
+        }
+        if limits.nudge_after == 0 || count < limits.nudge_after {
+            return None;
+        }
+        let advice = match kind {
+            Some(kind) => kind.advice(),
+            None => {
+                "Use `request_tools` to discover alternative tools that might work better. For \
+                 example, if google_api failed, try dedicated tools like google_docs_create, \
+                 google_drive_upload, or google_drive_share instead."
+            }
+        };
+        let retry = if kind.is_some_and(ToolErrorKind::is_transient) {
+            "The failures look temporary, so one more attempt later is fine, but do not loop."
+        } else {
+            "Stop calling it with the same arguments — try a DIFFERENT tool or approach instead."
+        };
+        Some(BreakerNudge {
+            blocked: false,
+            failures: count,
+            text: format!(
+                "[SYSTEM] The tool '{}' has failed {} times in a row ({}). {} {}",
+                tool,
+                count,
+                summarize(&breaker.fails),
+                retry,
+                advice
+            ),
+        })
+    }
+}
+
+/// "2× not found, 1× network error" — most frequent first.
+fn summarize(fails: &[Option<ToolErrorKind>]) -> String {
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    for kind in fails {
+        let label = kind.map_or("unclassified", ToolErrorKind::label);
+        match counts.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((label, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    counts
+        .iter()
+        .map(|(label, n)| format!("{}× {}", n, label))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_classified() {
+        let cases = [
+            (
+                "Error: HTTP 429 from api.github.com",
+                Some(ToolErrorKind::RateLimit),
+            ),
+            (
+                "Slack API error: invalid_api_key",
+                Some(ToolErrorKind::Auth),
+            ),
+            (
+                "Request failed (403): Forbidden",
+                Some(ToolErrorKind::Permission),
+            ),
+            (
+                "Error: No such file or directory: notes.md",
+                Some(ToolErrorKind::NotFound),
+            ),
+            ("Error: operation timed out", Some(ToolErrorKind::Network)),
+            (
+                "Missing required field 'path'",
+                Some(ToolErrorKind::InvalidInput),
+            ),
+            ("Found 14040 results", None),
+            ("Exit code 1: build failed", None),
+        ];
+        for (message, kind) in cases {
+            assert_eq!(classify(message), kind, "{}", message);
+        }
+        let io = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            classify_error(&EngineError::Io(io)),
+            Some(ToolErrorKind::Permission)
+        );
+        assert_eq!(
+            classify_error(&EngineError::Auth("token revoked".into())),
+            Some(ToolErrorKind::Auth)
+        );
+    }
+
+    #[test]
+    fn auth_failures_block_at_once() {
+        let now = Instant::now();
+        let mut fails = ToolFailures::default();
+        let nudge = fails
+            .record("github_api", false, Some(ToolErrorKind::Auth), now)
+            .unwrap();
+        assert!(nudge.blocked);
+        let refusal = fails.blocked("github_api", now).unwrap();
+        assert!(refusal.contains("authentication failed"), "{}", refusal);
+        assert!(refusal.contains("another 10 minute(s)"), "{}", refusal);
+        assert!(fails.blocked("web_fetch", now).is_none());
+    }
+
+    #[test]
+    fn other_failures_nudge_then_block_and_reset_on_success() {
+        let now = Instant::now();
+        let mut fails = ToolFailures::default();
+        assert!(fails
+            .record("web_fetch", false, Some(ToolErrorKind::Network), now)
+            .is_none());
+        assert!(fails
+            .record("web_fetch", false, Some(ToolErrorKind::NotFound), now)
+            .is_none());
+        let nudge = fails
+            .record("web_fetch", false, Some(ToolErrorKind::Network), now)
+            .unwrap();
+        assert!(!nudge.blocked);
+        assert!(
+            nudge.text.contains("2× network error, 1× not found"),
+            "{}",
+            nudge.text
+        );
+        assert!(nudge.text.contains("temporary"));
+
+        fails.record("web_fetch", false, None, now);
+        let nudge = fails.record("web_fetch", false, None, now).unwrap();
+        assert!(nudge.blocked);
+        assert!(fails.blocked("web_fetch", now).is_some());
+
+        fails.record("web_fetch", true, None, now);
+        assert!(fails.blocked("web_fetch", now).is_none());
+    }
+
+    #[test]
+    fn per_tool_thresholds_and_cooldowns() {
+        let config: CircuitBreakerConfig = serde_json::from_str(
+            r#"{"cooldown_minutes": 0,
+                "tools": {"flaky_api": {"nudge_after": 0, "block_after": 2, "cooldown_minutes": 5}}}"#,
+        )
+        .unwrap();
+        assert_eq!((config.nudge_after, config.block_after), (3, 5));
+        let mut fails = ToolFailures::default();
+        fails.configure(&config);
+
+        let start = Instant::now();
+        assert!(fails.record("flaky_api", false, None, start).is_none());
+        let nudge = fails.record("flaky_api", false, None, start).unwrap();
+        assert!(nudge.blocked && nudge.text.contains("for 5 minutes"));
+
+        // Still blocked in a later turn, until the cooldown ends
+        let later = start + Duration::from_secs(4 * 60);
+        assert!(fails.blocked("flaky_api", later).is_some());
+        let after = start + Duration::from_secs(5 * 60);
+        assert!(fails.blocked("flaky_api", after).is_none());
+        // On probation: one more failure blocks it again
+        let nudge = fails.record("flaky_api", false, None, after).unwrap();
+        assert!(nudge.blocked && nudge.text.contains("right after its cooldown"));
+
+        // Cooldown 0: blocked for the rest of the session
+        for _ in 0..5 {
+            fails.record("web_fetch", false, None, start);
+        }
+        let much_later = start + Duration::from_secs(24 * 3600);
+        let refusal = fails.blocked("web_fetch", much_later).unwrap();
+        assert!(refusal.contains("rest of this session"), "{}", refusal);
+    }
+}
@@ -69,6 +69,7 @@ pub enum ToolDomain {
     Google,
     Discord,
     Discourse,
+    Social,
     Trello,
     Microsoft,
     Mcp,
@@ -150,12 +151,34 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
         false
     ),
     tool!("list_directory", Safe, ReadOnly, Filesystem, true, true),
+    tool!(
+        "archive_extract",
+        Reversible,
+        WriteLocal,
+        Filesystem,
+        false,
+        false
+    ),
+    tool!(
+        "archive_create",
+        Reversible,
+        WriteLocal,
+        Filesystem,
+        false,
+        false
+    ),
     // ── Web ─────────────────────────────────────────────────────────────
     tool!("fetch", Safe, ReadOnly, Web, true, true),
+    tool!("download_file", Reversible, WriteLocal, Web, true, false),
     tool!("web_search", Safe, ReadOnly, Web, true, true),
     tool!("web_read", Safe, ReadOnly, Web, true, true),
     tool!("web_screenshot", Safe, ReadOnly, Web, true, true),
     tool!("web_browse", Safe, ReadOnly, Web, true, true),
+    tool!("youtube_transcript", Safe, ReadOnly, Web, true, true),
+    tool!("audio_summarize", Reversible, WriteLocal, Web, true, false),
+    tool!("local_time", Safe, ReadOnly, Web, true, true),
+    tool!("nearby_search", Safe, ReadOnly, Web, true, true),
+    tool!("screen_capture", External, ReadOnly, System, false, false),
     // ── Identity ────────────────────────────────────────────────────────
     tool!("soul_read", Safe, ReadOnly, Identity, true, true),
     tool!("soul_write", Reversible, WriteLocal, Identity, true, true),
@@ -172,6 +195,7 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
     // ── Memory ──────────────────────────────────────────────────────────
     tool!("memory_search", Safe, ReadOnly, Memory, true, true),
     tool!("memory_store", Reversible, WriteLocal, Memory, true, true),
+    tool!("memory_timeline", Safe, ReadOnly, Memory, true, true),
     tool!(
         "memory_knowledge",
         Reversible,
@@ -192,6 +216,14 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
         true,
         false
     ),
+    tool!(
+        "handoff_to_agent",
+        Reversible,
+        WriteLocal,
+        Agents,
+        false,
+        false
+    ),
     // ── Communication ───────────────────────────────────────────────────
     tool!(
         "agent_send_message",
@@ -209,6 +241,7 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
         true,
         true
     ),
+    tool!("translate", Safe, ReadOnly, Communication, true, true),
     // ── Squads ──────────────────────────────────────────────────────────
     tool!("create_squad", Reversible, WriteLocal, Squads, true, true),
     tool!("list_squads", Safe, ReadOnly, Squads, true, true),
@@ -225,6 +258,14 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
     tool!("create_task", Reversible, WriteLocal, Tasks, true, true),
     tool!("list_tasks", Safe, ReadOnly, Tasks, true, true),
     tool!("manage_task", Reversible, WriteLocal, Tasks, true, false),
+    tool!(
+        "schedule_followup",
+        Reversible,
+        WriteLocal,
+        Tasks,
+        false,
+        false
+    ),
     // ── Skills ──────────────────────────────────────────────────────────
     tool!("skill_search", Safe, ReadOnly, Skills, true, false),
     tool!("skill_list", Safe, ReadOnly, Skills, true, false),
@@ -305,6 +346,41 @@ pub static TOOL_REGISTRY: &[ToolMeta] = &[
         false
     ),
     tool!("telegram_read", Safe, ReadOnly, Messaging, true, false),
+    // ── Social ──────────────────────────────────────────────────────────
+    tool!(
+        "social_post",
+        External,
+        WriteSideEffect,
+        Social,
+        false,
+        false
+    ),
+    tool!(
+        "social_reply",
+        External,
+        WriteSideEffect,
+        Social,
+        false,
+        false
+    ),
+    tool!("social_search", Safe, ReadOnly, Social, true, false),
+    tool!(
+        "nostr_publish_note",
+        External,
+        WriteSideEffect,
+        Social,
+        false,
+        false
+    ),
+    tool!(
+        "nostr_react",
+        External,
+        WriteSideEffect,
+        Social,
+        false,
+        false
+    ),
+    tool!("nostr_fetch_profile", Safe, ReadOnly, Social, true, false),
     // ── Github ──────────────────────────────────────────────────────────
     tool!(
         "github_api",
@@ -701,6 +777,9 @@ pub fn domain(name: &str) -> ToolDomain {
     if name.starts_with("discourse_") {
         return ToolDomain::Discourse;
     }
+    if name.starts_with("social_") || name.starts_with("nostr_") {
+        return ToolDomain::Social;
+    }
     if name.starts_with("google_") || name.starts_with("gmail_") {
         return ToolDomain::Google;
     }
@@ -767,6 +846,7 @@ pub fn domain_str(name: &str) -> &'static str {
         ToolDomain::Google => "google",
         ToolDomain::Discord => "discord",
         ToolDomain::Discourse => "discourse",
+        ToolDomain::Social => "social",
         ToolDomain::Trello => "trello",
         ToolDomain::Microsoft => "microsoft",
         ToolDomain::Mcp => "mcp",
@@ -826,6 +906,60 @@ fn dynamic_mutability(name: &str) -> ToolMutability {
     }
 }
 
+// ═════════════════════════════════════════════════════════════════════════════
+// Outbound communication
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Tools that send a message to people outside Paw (email, chat posts,
+/// forum replies). These can be held in the outbox for review.
+const OUTBOUND_MESSAGE_TOOLS: &[&str] = &[
+    "email_send",
+    "google_gmail_send",
+    "outlook_mail_send",
+    "slack_send",
+    "telegram_send",
+    "teams_send_message",
+    "discord_send_message",
+    "discord_edit_message",
+    "discourse_create_topic",
+    "discourse_reply",
+    "discourse_send_pm",
+    "webhook_send",
+    "social_post",
+    "social_reply",
+    "nostr_publish_note",
+    "nostr_react",
+];
+
+/// Outbound tools that publish to the open web. These always go through the
+/// outbox, whatever its mode, and are never auto-approved.
+const ALWAYS_REVIEWED_TOOLS: &[&str] = &[
+    "social_post",
+    "social_reply",
+    "nostr_publish_note",
+    "nostr_react",
+];
+
+/// Tools that look at the user's own machine (their screen). Autonomy
+/// policies never approve these; each call is asked for unless the user
+/// consented for the session.
+const CONSENT_TOOLS: &[&str] = &["screen_capture"];
+
+/// Whether the tool sends a message on the user's behalf.
+pub fn is_outbound_message(name: &str) -> bool {
+    OUTBOUND_MESSAGE_TOOLS.contains(&name)
+}
+
+/// Whether every call to the tool must be reviewed by the user.
+pub fn always_reviewed(name: &str) -> bool {
+    ALWAYS_REVIEWED_TOOLS.contains(&name)
+}
+
+/// Whether the tool needs the user's approval per call or per-session consent.
+pub fn needs_consent(name: &str) -> bool {
+    CONSENT_TOOLS.contains(&name)
+}
+
 // ═════════════════════════════════════════════════════════════════════════════
 // Convenience: collect tools by tier
 // ═════════════════════════════════════════════════════════════════════════════
@@ -919,6 +1053,28 @@ mod tests {
         assert_eq!(domain_str("discord_send"), "discord");
     }
 
+    #[test]
+    fn outbound_messages_need_approval() {
+        for name in OUTBOUND_MESSAGE_TOOLS {
+            assert_ne!(tier(name), ToolTier::Safe, "{} sends messages", name);
+        }
+        assert!(is_outbound_message("email_send"));
+        assert!(!is_outbound_message("email_read"));
+        for name in ALWAYS_REVIEWED_TOOLS {
+            assert!(is_outbound_message(name), "{} must be outbound", name);
+        }
+    }
+
+    #[test]
+    fn consent_tools_are_never_auto_approved() {
+        assert!(needs_consent("screen_capture"));
+        assert!(!needs_consent("web_screenshot"));
+        for name in CONSENT_TOOLS {
+            assert!(!auto_approved_tools().contains(name));
+            assert!(!worker_allowed(name), "{} must not run in workers", name);
+        }
+    }
+
     #[test]
     fn mcp_mutability_heuristics() {
         assert_eq!(
@@ -0,0 +1,393 @@
+// ── Translation: settings, language detection, prompts ──────────────────────
+//
+// The `translate` tool and per-bridge auto-translate share these settings.
+// Translations run on a local Ollama model when one is configured (private,
+// free) and fall back to the worker / default model otherwise.
+//
+// Auto-translate is set per channel bridge (keyed by channel prefix —
+// "telegram", "discord", …) for households and communities where people
+// write in different languages:
+//
+//   mode          behaviour
+//   ────────────  ─────────────────────────────────────────────────────────
+//   off           nothing
+//   reply_in_kind the user's language is detected and the agent is told to
+//                 answer in it
+//   pivot         messages are translated into `pivot_language` before the
+//                 agent sees them and replies are translated back — for
+//                 agents (or small models) that only work well in one
+//                 language
+//
+// Detection is a cheap local heuristic (script ranges + common words). Short
+// or ambiguous messages detect as `None` and are passed through unchanged.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::sessions::SessionStore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SETTINGS_KEY: &str = "translate_settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoTranslate {
+    #[default]
+    Off,
+    ReplyInKind,
+    Pivot,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslateSettings {
+    /// Ollama model used for translation. `None` = the Ollama provider's
+    /// default model; without an Ollama provider the worker model is used.
+    #[serde(default)]
+    pub local_model: Option<String>,
+    /// Language the agent works in for `pivot` bridges (ISO 639-1 code).
+    #[serde(default = "default_pivot_language")]
+    pub pivot_language: String,
+    /// Auto-translate mode per bridge, keyed by channel prefix.
+    #[serde(default)]
+    pub bridges: HashMap<String, AutoTranslate>,
+}
+
+fn default_pivot_language() -> String {
+    "en".into()
+}
+
+impl Default for TranslateSettings {
+    fn default() -> Self {
+        TranslateSettings {
+            local_model: None,
+            pivot_language: default_pivot_language(),
+            bridges: HashMap::new(),
+        }
+    }
+}
+
+impl TranslateSettings {
+    pub fn mode_for(&self, channel_prefix: &str) -> AutoTranslate {
+        self.bridges
+            .get(channel_prefix)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+pub fn load_settings(store: &SessionStore) -> TranslateSettings {
+    store
+        .get_config(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(store: &SessionStore, settings: &TranslateSettings) -> EngineResult<()> {
+    store.set_config(SETTINGS_KEY, &serde_json::to_string(settings)?)
+}
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// English name for an ISO 639-1 code; anything else (a name such as
+/// "Brazilian Portuguese", an unknown code) is returned as given.
+pub fn language_name(code: &str) -> &str {
+    let lower = code.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == lower)
+        .map(|(_, name)| *name)
+        .unwrap_or(code.trim())
+}
+
+/// Same language, whether given as a code or an English name.
+pub fn same_language(a: &str, b: &str) -> bool {
+    language_name(a).eq_ignore_ascii_case(language_name(b))
+}
+
+/// Frequent short words that are distinctive enough to vote for a language.
+const COMMON_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "what", "this", "that", "with", "have", "for", "not",
+            "can", "how", "please", "thanks", "it's", "i'm", "my", "your",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "du", "wie", "was", "ein", "eine",
+            "mit", "für", "bitte", "sind", "auch", "danke", "mir", "kannst",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "les", "et", "est", "je", "vous", "tu", "pas", "une", "des", "pour", "avec",
+            "c'est", "merci", "ce", "qui", "mais", "bonjour", "j'ai", "sur",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "está", "por", "para", "con", "una", "gracias", "cómo", "qué",
+            "hola", "pero", "muy", "yo", "tengo", "puedes", "del",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "è", "che", "di", "per", "non", "sono", "come", "grazie", "questo",
+            "ciao", "della", "anche", "ho", "mi", "puoi", "perché",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "é", "não", "uma", "você", "obrigado", "obrigada", "com", "está", "isso",
+            "olá", "mas", "muito", "eu", "tenho", "pode", "do", "da",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "het", "een", "en", "niet", "ik", "je", "wat", "van", "met", "voor", "zijn", "dat",
+            "bedankt", "hoe", "ook", "maar", "kun", "hallo", "graag",
+        ],
+    ),
+    (
+        "sv",
+        &[
+            "och", "är", "det", "att", "jag", "inte", "ett", "som", "på", "med", "vad", "tack",
+            "hur", "kan", "du", "hej", "också", "men",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "w",
+            "nie",
+            "się",
+            "jest",
+            "że",
+            "na",
+            "jak",
+            "co",
+            "dla",
+            "dziękuję",
+            "czy",
+            "proszę",
+            "mam",
+            "ale",
+            "cześć",
+            "to",
+            "jestem",
+        ],
+    ),
+    (
+        "tr",
+        &[
+            "ve",
+            "bir",
+            "bu",
+            "için",
+            "ne",
+            "değil",
+            "ben",
+            "sen",
+            "nasıl",
+            "mi",
+            "çok",
+            "teşekkürler",
+            "var",
+            "merhaba",
+            "ama",
+            "mı",
+            "lütfen",
+        ],
+    ),
+];
+
+/// Letters (mostly) unique to one Latin-script language.
+const MARKER_LETTERS: &[(&str, &str)] = &[
+    ("es", "ñ¿¡"),
+    ("de", "ß"),
+    ("pt", "ãõ"),
+    ("pl", "łąęśżźćń"),
+    ("tr", "şğı"),
+    ("sv", "å"),
+    ("fr", "œ"),
+];
+
+/// Best-effort ISO 639-1 code of `text`, or `None` when it is too short or
+/// ambiguous to tell.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    // Non-Latin scripts decide on their own
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let mut letters = 0usize;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let script = match c as u32 {
+            0x3040..=0x30FF => Some("ja"),
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => Some("ko"),
+            0x4E00..=0x9FFF => Some("han"),
+            0x0400..=0x04FF => Some(if "іїєґ".contains(c.to_lowercase().next()?) {
+                "uk"
+            } else {
+                "cyrillic"
+            }),
+            0x0600..=0x06FF => Some("ar"),
+            0x0590..=0x05FF => Some("he"),
+            0x0370..=0x03FF => Some("el"),
+            0x0900..=0x097F => Some("hi"),
+            0x0E00..=0x0E7F => Some("th"),
+            _ => None,
+        };
+        if let Some(s) = script {
+            *counts.entry(s).or_default() += 1;
+        }
+    }
+    if letters == 0 {
+        return None;
+    }
+    let non_latin: usize = counts.values().sum();
+    if non_latin * 2 > letters {
+        if counts.contains_key("ja") {
+            return Some("ja");
+        }
+        if counts.contains_key("uk") {
+            return Some("uk");
+        }
+        let (script, _) = counts.into_iter().max_by_key(|(_, n)| *n)?;
+        return Some(match script {
+            "han" => "zh",
+            "cyrillic" => "ru",
+            other => other,
+        });
+    }
+
+    // Latin script: vote with common words and marker letters
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut scores: Vec<(&'static str, usize)> = COMMON_WORDS
+        .iter()
+        .map(|(lang, common)| {
+            let mut score = words.iter().filter(|w| common.contains(w)).count();
+            if let Some((_, marks)) = MARKER_LETTERS.iter().find(|(l, _)| l == lang) {
+                if lower.chars().any(|c| marks.contains(c)) {
+                    score += 2;
+                }
+            }
+            (*lang, score)
+        })
+        .collect();
+    scores.sort_by_key(|s| std::cmp::Reverse(s.1));
+    match scores.as_slice() {
+        [(lang, best), (_, second), ..] if *best >= 2 && best > second => Some(lang),
+        _ => None,
+    }
+}
+
+/// System prompt for a translation call.
+pub fn translation_prompt(target: &str, source: Option<&str>) -> String {
+    let from = source
+        .map(|s| format!(" from {}", language_name(s)))
+        .unwrap_or_default();
+    format!(
+        "You are a translator. Translate the user's message{} into {}.\n\
+         - Keep meaning, tone and register; translate idioms naturally, not word for word.\n\
+         - Keep formatting (Markdown, lists, line breaks), names, URLs, code, numbers and emoji unchanged.\n\
+         - If the message is already in {}, return it unchanged.\n\
+         - Output only the translation — no notes, quotes or explanations.",
+        from,
+        language_name(target),
+        language_name(target)
+    )
+}
+
+/// Channel context line for `reply_in_kind` bridges.
+pub fn reply_in_kind_instruction(language: &str) -> String {
+    format!(
+        "The user writes in {}. Reply in {} unless they ask for another language.",
+        language_name(language),
+        language_name(language)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_languages() {
+        assert_eq!(
+            detect_language("Can you tell me what the weather is like this weekend?"),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("Kannst du mir bitte sagen, wie das Wetter am Wochenende ist?"),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language("¿Puedes decirme qué tiempo hará el fin de semana?"),
+            Some("es")
+        );
+        assert_eq!(
+            detect_language("Est-ce que tu peux me dire le temps pour le week-end ? Merci"),
+            Some("fr")
+        );
+        assert_eq!(
+            detect_language("Ты можешь сказать, какая будет погода?"),
+            Some("ru")
+        );
+        assert_eq!(detect_language("週末の天気を教えてください"), Some("ja"));
+        assert_eq!(detect_language("周末天气怎么样"), Some("zh"));
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(detect_language("👍 123"), None);
+    }
+
+    #[test]
+    fn settings_and_names() {
+        let mut settings = TranslateSettings::default();
+        assert_eq!(settings.mode_for("telegram"), AutoTranslate::Off);
+        settings
+            .bridges
+            .insert("telegram".into(), AutoTranslate::Pivot);
+        assert_eq!(settings.mode_for("telegram"), AutoTranslate::Pivot);
+
+        let parsed: TranslateSettings =
+            serde_json::from_str(r#"{"bridges":{"discord":"reply_in_kind"}}"#).unwrap();
+        assert_eq!(parsed.pivot_language, "en");
+        assert_eq!(parsed.mode_for("discord"), AutoTranslate::ReplyInKind);
+
+        assert_eq!(language_name("DE"), "German");
+        assert_eq!(language_name("Klingon"), "Klingon");
+        assert!(same_language("de", "german"));
+        assert!(!same_language("de", "en"));
+    }
+}
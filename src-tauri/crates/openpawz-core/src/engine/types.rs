@@ -156,6 +156,19 @@ impl Default for MemoryConfig {
             auto_capture: true,
             recall_limit: 5,
             recall_threshold: 0.3,
+            retention: Vec::new(),
+            search: HybridSearchTuning::default(),
+        }
+    }
+}
+
+impl Default for HybridSearchTuning {
+    fn default() -> Self {
+        HybridSearchTuning {
+            bm25_weight: 0.4,
+            vector_weight: 0.6,
+            decay_half_life_days: 30.0,
+            mmr_lambda: 0.7,
         }
     }
 }
@@ -269,10 +282,34 @@ Be thorough, resourceful, and action-oriented. When the user asks you to do some
             daily_budget_usd: default_daily_budget_usd(),
             context_window_tokens: default_context_window_tokens(),
             weather_location: None,
+            home_location: None,
+            app_context: false,
+            provider_middleware: ProviderMiddlewareConfig::default(),
+            local_models: LocalModelConfig::default(),
+            project_playbooks: false,
+            worker_limits: WorkerLimits::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            mail_watch: MailWatchConfig::default(),
+            notifications: NotificationConfig::default(),
+            profiles: vec![],
+            active_profile: None,
+            safe_mode: false,
+            session_archive_days: 0,
+            db_write: DbWriteConfig::default(),
         }
     }
 }
 
+impl EngineConfig {
+    /// The user's timezone for schedules, reminders, date phrases and
+    /// displayed times. Falls back to UTC if `user_timezone` isn't a valid
+    /// IANA name.
+    pub fn timezone(&self) -> chrono_tz::Tz {
+        self.user_timezone.parse().unwrap_or(chrono_tz::UTC)
+    }
+}
+
 // ── Tasks ──────────────────────────────────────────────────────────────
 
 // ── Orchestrator: Projects ────────────────────────────────────────────
@@ -0,0 +1,498 @@
+// ── YouTube: video metadata and transcripts ────────────────────────────────
+//
+// Backs the `youtube_transcript` tool so agents can read and summarize a
+// video the user pastes. No API key is needed:
+//
+//   1. the watch page gives the InnerTube API key (and the publish date)
+//   2. the InnerTube `player` endpoint gives video details and the list of
+//      caption tracks
+//   3. the chosen track's timedtext XML is fetched and flattened into
+//      timestamped paragraphs
+//
+// Manually written captions are preferred over auto-generated ones in the
+// requested language; any other track is the last resort. Long transcripts
+// are split into parts that fit comfortably in context, fetched one at a time
+// with the `part` argument.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::types::truncate_utf8;
+use log::info;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Size of one transcript part (~3k tokens).
+const PART_BYTES: usize = 12_000;
+/// Start a new timestamped paragraph after this many seconds.
+const PARAGRAPH_SECS: f64 = 30.0;
+const MAX_DESCRIPTION_BYTES: usize = 1_500;
+
+#[derive(Debug, Clone, Default)]
+pub struct VideoInfo {
+    pub id: String,
+    pub title: String,
+    pub channel: String,
+    pub duration_secs: u64,
+    pub views: Option<u64>,
+    pub published: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptionTrack {
+    pub base_url: String,
+    pub language_code: String,
+    pub name: String,
+    pub auto_generated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: f64,
+    pub text: String,
+}
+
+/// Video ID from a watch / youtu.be / shorts / embed / live URL, or a bare ID.
+pub fn video_id(input: &str) -> Option<String> {
+    static ID: OnceLock<Regex> = OnceLock::new();
+    let re = ID.get_or_init(|| {
+        Regex::new(
+            r"(?:[?&]v=|youtu\.be/|/shorts/|/embed/|/live/|/v/)([A-Za-z0-9_-]{11})(?:[^A-Za-z0-9_-]|$)",
+        )
+        .unwrap()
+    });
+    let input = input.trim();
+    if let Some(c) = re.captures(input) {
+        return Some(c[1].to_string());
+    }
+    let bare = input.len() == 11
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    bare.then(|| input.to_string())
+}
+
+/// Best caption track for `lang`: manual in that language, then
+/// auto-generated in that language, then any manual track, then anything.
+pub fn pick_track<'a>(tracks: &'a [CaptionTrack], lang: &str) -> Option<&'a CaptionTrack> {
+    let matches_lang = |t: &CaptionTrack| {
+        t.language_code == lang || t.language_code.split('-').next() == Some(lang)
+    };
+    tracks
+        .iter()
+        .find(|t| matches_lang(t) && !t.auto_generated)
+        .or_else(|| tracks.iter().find(|t| matches_lang(t)))
+        .or_else(|| tracks.iter().find(|t| !t.auto_generated))
+        .or_else(|| tracks.first())
+}
+
+/// Parse timedtext XML — the classic `<text start="1.2">` format or srv3
+/// `<p t="1200">` — into segments.
+pub fn parse_timedtext(xml: &str) -> Vec<Segment> {
+    static TEXT: OnceLock<Regex> = OnceLock::new();
+    static SRV3: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let text_re =
+        TEXT.get_or_init(|| Regex::new(r#"(?s)<text start="([\d.]+)"[^>]*>(.*?)</text>"#).unwrap());
+    let srv3_re = SRV3.get_or_init(|| Regex::new(r#"(?s)<p t="(\d+)"[^>]*>(.*?)</p>"#).unwrap());
+    let tag_re = TAG.get_or_init(|| Regex::new(r"<[^>]+>").unwrap());
+
+    let mut segments: Vec<Segment> = text_re
+        .captures_iter(xml)
+        .map(|c| (c[1].parse::<f64>().unwrap_or(0.0), c[2].to_string()))
+        .chain(srv3_re.captures_iter(xml).map(|c| {
+            (
+                c[1].parse::<f64>().unwrap_or(0.0) / 1000.0,
+                c[2].to_string(),
+            )
+        }))
+        .map(|(start, raw)| {
+            // Caption text is escaped twice (XML, then HTML inside it)
+            let text = decode_entities(&decode_entities(&tag_re.replace_all(&raw, "")));
+            Segment {
+                start,
+                text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+            }
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect();
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    segments
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((ch, end + 1))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                out.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `m:ss`, or `h:mm:ss` for long videos.
+pub fn format_timestamp(secs: f64) -> String {
+    let s = secs.max(0.0) as u64;
+    if s >= 3600 {
+        format!("{}:{:02}:{:02}", s / 3600, (s % 3600) / 60, s % 60)
+    } else {
+        format!("{}:{:02}", s / 60, s % 60)
+    }
+}
+
+/// One `[m:ss] …` paragraph per ~30 seconds of speech.
+pub fn format_transcript(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    let mut para_start: Option<f64> = None;
+    for seg in segments {
+        match para_start {
+            Some(start) if seg.start - start < PARAGRAPH_SECS => {
+                out.push(' ');
+            }
+            _ => {
+                if para_start.is_some() {
+                    out.push_str("\n\n");
+                }
+                out.push_str(&format!("[{}] ", format_timestamp(seg.start)));
+                para_start = Some(seg.start);
+            }
+        }
+        out.push_str(&seg.text);
+    }
+    out
+}
+
+/// Split text into parts of at most `max_bytes`, breaking between
+/// paragraphs (or, for a single huge paragraph, between words).
+pub fn split_parts(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for para in text.split("\n\n") {
+        for piece in split_long(para, max_bytes) {
+            if !current.is_empty() && current.len() + 2 + piece.len() > max_bytes {
+                parts.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(piece);
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+fn split_long(para: &str, max_bytes: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = para;
+    while rest.len() > max_bytes {
+        let head = truncate_utf8(rest, max_bytes);
+        let cut = head.rfind(' ').filter(|&i| i > 0).unwrap_or(head.len());
+        pieces.push(&rest[..cut]);
+        rest = rest[cut..].trim_start();
+    }
+    pieces.push(rest);
+    pieces
+}
+
+// ── Fetching ───────────────────────────────────────────────────────────────
+
+fn client() -> EngineResult<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .build()?)
+}
+
+fn text_of(v: &Value) -> String {
+    v["simpleText"]
+        .as_str()
+        .map(String::from)
+        .or_else(|| {
+            v["runs"].as_array().map(|runs| {
+                runs.iter()
+                    .filter_map(|r| r["text"].as_str())
+                    .collect::<String>()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Video details and caption tracks for `id`.
+pub async fn fetch_video(
+    client: &reqwest::Client,
+    id: &str,
+) -> EngineResult<(VideoInfo, Vec<CaptionTrack>)> {
+    static KEY: OnceLock<Regex> = OnceLock::new();
+    static PUBLISHED: OnceLock<Regex> = OnceLock::new();
+    let key_re = KEY.get_or_init(|| Regex::new(r#""INNERTUBE_API_KEY":\s*"([^"]+)""#).unwrap());
+    let published_re =
+        PUBLISHED.get_or_init(|| Regex::new(r#""publishDate":\s*"([^"]+)""#).unwrap());
+
+    // The consent cookie skips the EU consent interstitial
+    let html = client
+        .get(format!("https://www.youtube.com/watch?v={}", id))
+        .header("Accept-Language", "en-US,en;q=0.9")
+        .header("Cookie", "CONSENT=YES+cb; SOCS=CAI")
+        .send()
+        .await?
+        .text()
+        .await?;
+    let api_key = key_re
+        .captures(&html)
+        .map(|c| c[1].to_string())
+        .ok_or("Could not read the YouTube page (it may be blocked or rate-limited)")?;
+
+    let player: Value = client
+        .post(format!(
+            "https://www.youtube.com/youtubei/v1/player?key={}",
+            api_key
+        ))
+        .json(&json!({
+            "context": { "client": { "clientName": "ANDROID", "clientVersion": "20.10.38" } },
+            "videoId": id,
+        }))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    let status = player["playabilityStatus"]["status"]
+        .as_str()
+        .unwrap_or("OK");
+    if status != "OK" {
+        let reason = player["playabilityStatus"]["reason"]
+            .as_str()
+            .unwrap_or(status);
+        return Err(format!("YouTube video {} is unavailable: {}", id, reason).into());
+    }
+
+    let details = &player["videoDetails"];
+    let info = VideoInfo {
+        id: id.to_string(),
+        title: details["title"].as_str().unwrap_or_default().to_string(),
+        channel: details["author"].as_str().unwrap_or_default().to_string(),
+        duration_secs: details["lengthSeconds"]
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0),
+        views: details["viewCount"].as_str().and_then(|s| s.parse().ok()),
+        published: published_re.captures(&html).map(|c| c[1].to_string()),
+        description: details["shortDescription"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+    };
+
+    let tracks = player["captions"]["playerCaptionsTracklistRenderer"]["captionTracks"]
+        .as_array()
+        .map(|tracks| {
+            tracks
+                .iter()
+                .filter_map(|t| {
+                    Some(CaptionTrack {
+                        base_url: t["baseUrl"].as_str()?.replace("&fmt=srv3", ""),
+                        language_code: t["languageCode"].as_str().unwrap_or_default().into(),
+                        name: text_of(&t["name"]),
+                        auto_generated: t["kind"].as_str() == Some("asr"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((info, tracks))
+}
+
+pub async fn fetch_captions(
+    client: &reqwest::Client,
+    track: &CaptionTrack,
+) -> EngineResult<Vec<Segment>> {
+    let xml = client.get(&track.base_url).send().await?.text().await?;
+    Ok(parse_timedtext(&xml))
+}
+
+/// `youtube_transcript` tool: metadata header, description (first part
+/// only) and one part of the timestamped transcript.
+pub async fn execute_youtube_transcript(args: &Value) -> EngineResult<String> {
+    let url = args["url"]
+        .as_str()
+        .ok_or("youtube_transcript: missing 'url'")?;
+    let id = video_id(url).ok_or_else(|| format!("Not a YouTube video URL: {}", url))?;
+    let lang = args["language"].as_str().unwrap_or("en");
+    let part = args["part"].as_u64().unwrap_or(1).max(1) as usize;
+
+    let client = client()?;
+    let (info, tracks) = fetch_video(&client, &id).await?;
+    let track = pick_track(&tracks, lang);
+    let segments = match track {
+        Some(t) => fetch_captions(&client, t).await?,
+        None => vec![],
+    };
+    info!(
+        "[youtube] {} — {} caption track(s), {} segment(s)",
+        id,
+        tracks.len(),
+        segments.len()
+    );
+
+    let mut out = format!("# {}\n", info.title);
+    let mut meta = vec![format!("Channel: {}", info.channel)];
+    if info.duration_secs > 0 {
+        meta.push(format!(
+            "Duration: {}",
+            format_timestamp(info.duration_secs as f64)
+        ));
+    }
+    if let Some(p) = &info.published {
+        meta.push(format!("Published: {}", truncate_utf8(p, 10)));
+    }
+    if let Some(v) = info.views {
+        meta.push(format!("Views: {}", v));
+    }
+    out.push_str(&meta.join(" · "));
+    out.push_str(&format!("\nURL: https://www.youtube.com/watch?v={}\n", id));
+
+    let parts = split_parts(&format_transcript(&segments), PART_BYTES);
+    if part == 1 && !info.description.trim().is_empty() {
+        let desc = info.description.trim();
+        out.push_str("\n## Description\n");
+        out.push_str(truncate_utf8(desc, MAX_DESCRIPTION_BYTES));
+        if desc.len() > MAX_DESCRIPTION_BYTES {
+            out.push('…');
+        }
+        out.push('\n');
+    }
+
+    let Some(track) = track.filter(|_| !parts.is_empty()) else {
+        out.push_str("\nNo captions are available for this video.");
+        return Ok(out);
+    };
+    if part > parts.len() {
+        return Err(format!(
+            "Transcript has only {} part(s); part {} does not exist",
+            parts.len(),
+            part
+        )
+        .into());
+    }
+    out.push_str(&format!(
+        "\nCaptions: {}{}\n",
+        if track.name.is_empty() {
+            &track.language_code
+        } else {
+            &track.name
+        },
+        if track.auto_generated {
+            " (auto-generated, may contain errors)"
+        } else {
+            ""
+        }
+    ));
+    if parts.len() > 1 {
+        out.push_str(&format!(
+            "\n## Transcript (part {} of {}",
+            part,
+            parts.len()
+        ));
+        if part < parts.len() {
+            out.push_str(&format!(" — call again with part={} for more", part + 1));
+        }
+        out.push_str(")\n");
+    } else {
+        out.push_str("\n## Transcript\n");
+    }
+    out.push_str(&parts[part - 1]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn video_ids() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s",
+            "https://youtu.be/dQw4w9WgXcQ?si=abc",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://m.youtube.com/embed/dQw4w9WgXcQ",
+            "dQw4w9WgXcQ",
+        ] {
+            assert_eq!(video_id(url).as_deref(), Some("dQw4w9WgXcQ"), "{}", url);
+        }
+        assert!(video_id("https://www.youtube.com/@channel").is_none());
+    }
+
+    #[test]
+    fn transcript_parsing_and_parts() {
+        let xml = r#"<?xml version="1.0"?><transcript>
+            <text start="0.5" dur="2">It&amp;#39;s  a
+            test</text><text start="31.2" dur="2">Tom &amp;amp; Jerry</text>
+            <text start="3" dur="1">second</text><text start="40" dur="1"></text></transcript>"#;
+        let segs = parse_timedtext(xml);
+        assert_eq!(segs.len(), 3);
+        assert_eq!(segs[0].text, "It's a test");
+        assert_eq!(segs[2].text, "Tom & Jerry");
+        assert_eq!(
+            format_transcript(&segs),
+            "[0:00] It's a test second\n\n[0:31] Tom & Jerry"
+        );
+
+        let srv3 = r#"<timedtext><body><p t="3723000" d="500"><s>one</s><s> two</s></p></body></timedtext>"#;
+        let segs = parse_timedtext(srv3);
+        assert_eq!(segs[0].text, "one two");
+        assert_eq!(format_timestamp(segs[0].start), "1:02:03");
+
+        let text = format!("{}\n\n{}", "a ".repeat(30).trim(), "b".repeat(10));
+        let parts = split_parts(&text, 25);
+        assert!(parts.iter().all(|p| p.len() <= 25));
+        assert!(parts.last().unwrap().ends_with(&"b".repeat(10)));
+        assert_eq!(parts.concat().matches('a').count(), 30);
+    }
+
+    #[test]
+    fn track_preference() {
+        let track = |lang: &str, asr: bool| CaptionTrack {
+            base_url: String::new(),
+            language_code: lang.into(),
+            name: String::new(),
+            auto_generated: asr,
+        };
+        let tracks = vec![track("de", false), track("en", true), track("en-GB", false)];
+        let picked = pick_track(&tracks, "en").unwrap();
+        assert_eq!(picked.language_code, "en-GB");
+        assert_eq!(pick_track(&tracks, "fr").unwrap().language_code, "de");
+        assert!(pick_track(&[], "en").is_none());
+    }
+}
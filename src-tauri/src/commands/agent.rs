@@ -2,7 +2,10 @@
 //
 // Thin Tauri command wrappers for:
 //   - Agent CRUD    (engine_list_all_agents, _create_agent, _delete_agent)
-//   - Agent Files   (engine_agent_file_list, _get, _set, _delete)
+//   - Agent Files   (engine_agent_file_list, _get, _set, _delete, _history, _revert)
+//   - Autonomy      (engine_agent_autonomy_get, _set)
+//   - Reflection    (engine_agent_reflection_get, _set, engine_soul_proposals_list, _resolve)
+//   - Grounding     (engine_agent_grounding_get, _set)
 //
 // All commands are 1-3 lines: extract, delegate to SessionStore, return.
 
@@ -10,6 +13,9 @@ use log::info;
 use tauri::State;
 
 use crate::commands::state::EngineState;
+use crate::engine::autonomy::{self, AutonomyLevel, AutonomyPolicy};
+use crate::engine::grounding;
+use crate::engine::reflection::{self, SoulProposal};
 use crate::engine::types::*;
 
 // ── Agent CRUD ────────────────────────────────────────────────────────────────
@@ -132,3 +138,150 @@ pub fn engine_agent_file_delete(
         .delete_agent_file(&aid, &file_name)
         .map_err(|e| e.to_string())
 }
+
+/// Stored revisions of an agent file, newest first.
+#[tauri::command]
+pub fn engine_agent_file_history(
+    state: State<'_, EngineState>,
+    agent_id: Option<String>,
+    file_name: String,
+) -> Result<Vec<AgentFileRevision>, String> {
+    let aid = agent_id.unwrap_or_else(|| "default".into());
+    state
+        .store
+        .list_agent_file_revisions(&aid, &file_name)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore a previous revision (recorded as a new user revision).
+#[tauri::command]
+pub fn engine_agent_file_revert(
+    state: State<'_, EngineState>,
+    agent_id: Option<String>,
+    file_name: String,
+    version: i64,
+) -> Result<Option<i64>, String> {
+    let aid = agent_id.unwrap_or_else(|| "default".into());
+    info!(
+        "[engine] Reverting agent file {}/{} to v{}",
+        aid, file_name, version
+    );
+    state
+        .store
+        .revert_agent_file(&aid, &file_name, version)
+        .map_err(|e| e.to_string())
+}
+
+// ── Autonomy Levels ───────────────────────────────────────────────────────────
+
+/// The agent's autonomy policy. Agents without a stored level report the
+/// default (assisted) bundle.
+#[tauri::command]
+pub fn engine_agent_autonomy_get(
+    state: State<'_, EngineState>,
+    agent_id: String,
+) -> Result<AutonomyPolicy, String> {
+    Ok(autonomy::load(&state.store, &agent_id)
+        .unwrap_or_default()
+        .policy())
+}
+
+#[tauri::command]
+pub fn engine_agent_autonomy_set(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    agent_id: String,
+    level: String,
+) -> Result<AutonomyPolicy, String> {
+    use tauri::Emitter;
+    let level: AutonomyLevel = level.parse()?;
+    info!(
+        "[engine] Setting autonomy for agent '{}' to {}",
+        agent_id,
+        level.as_str()
+    );
+    autonomy::save(&state.store, &agent_id, level)?;
+    let policy = level.policy();
+    let _ = app_handle.emit(
+        "agent-autonomy-changed",
+        serde_json::json!({
+            "agent_id": agent_id,
+            "policy": policy,
+        }),
+    );
+    Ok(policy)
+}
+
+// ── Self-Reflection ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn engine_agent_reflection_get(
+    state: State<'_, EngineState>,
+    agent_id: String,
+) -> Result<bool, String> {
+    Ok(reflection::is_enabled(&state.store, &agent_id))
+}
+
+#[tauri::command]
+pub fn engine_agent_reflection_set(
+    state: State<'_, EngineState>,
+    agent_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    info!(
+        "[engine] Reflection for agent '{}': {}",
+        agent_id,
+        if enabled { "on" } else { "off" }
+    );
+    reflection::set_enabled(&state.store, &agent_id, enabled).map_err(|e| e.to_string())
+}
+
+/// Soul-file proposals from reflection that await review.
+#[tauri::command]
+pub fn engine_soul_proposals_list(
+    state: State<'_, EngineState>,
+    agent_id: Option<String>,
+) -> Result<Vec<SoulProposal>, String> {
+    reflection::list_pending(&state.store, agent_id.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Apply or reject a proposal. Returns the new file version when applied.
+#[tauri::command]
+pub fn engine_soul_proposal_resolve(
+    state: State<'_, EngineState>,
+    proposal_id: String,
+    approve: bool,
+) -> Result<Option<i64>, String> {
+    info!(
+        "[engine] Soul proposal {} {}",
+        proposal_id,
+        if approve { "approved" } else { "rejected" }
+    );
+    reflection::resolve(&state.store, &proposal_id, approve).map_err(|e| e.to_string())
+}
+
+// ── Search Grounding ──────────────────────────────────────────────────────────
+
+/// Whether the agent lets models use their provider's built-in web search
+/// (Gemini Google Search grounding, OpenAI `web_search`).
+#[tauri::command]
+pub fn engine_agent_grounding_get(
+    state: State<'_, EngineState>,
+    agent_id: String,
+) -> Result<bool, String> {
+    Ok(grounding::is_enabled(&state.store, &agent_id))
+}
+
+#[tauri::command]
+pub fn engine_agent_grounding_set(
+    state: State<'_, EngineState>,
+    agent_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    info!(
+        "[engine] Search grounding for agent '{}': {}",
+        agent_id,
+        if enabled { "on" } else { "off" }
+    );
+    grounding::set_enabled(&state.store, &agent_id, enabled).map_err(|e| e.to_string())
+}
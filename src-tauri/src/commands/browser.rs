@@ -1,5 +1,6 @@
 // commands/browser.rs — Browser profile management, screenshot serving,
-// per-agent workspace management, and outbound domain allowlist.
+// screen capture consent, per-agent workspace management (incl. the
+// download_file registry), and outbound domain allowlist.
 
 use crate::commands::state::EngineState;
 use log::info;
@@ -277,6 +278,36 @@ pub fn engine_screenshot_delete(filename: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Allow (or revoke) `screen_capture` for a chat session without asking
+/// before each capture. Lasts until revoked or the app restarts.
+#[tauri::command]
+pub fn engine_screen_capture_consent(
+    state: State<'_, EngineState>,
+    session_id: String,
+    allow: bool,
+) -> Result<(), String> {
+    let mut consent = state.screen_consent.lock();
+    if allow {
+        consent.insert(session_id.clone());
+    } else {
+        consent.remove(&session_id);
+    }
+    info!(
+        "[browser] Screen capture consent for session {}: {}",
+        session_id, allow
+    );
+    Ok(())
+}
+
+/// Whether the session has standing screen capture consent.
+#[tauri::command]
+pub fn engine_screen_capture_consent_get(
+    state: State<'_, EngineState>,
+    session_id: String,
+) -> Result<bool, String> {
+    Ok(state.screen_consent.lock().contains(&session_id))
+}
+
 // ── Per-Agent Workspaces ───────────────────────────────────────────────
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -400,6 +431,19 @@ pub fn engine_workspace_delete(agent_id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Files fetched with the download_file tool, newest first.
+#[tauri::command]
+pub fn engine_downloads_list(
+    state: State<'_, EngineState>,
+    agent_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<crate::engine::types::DownloadRecord>, String> {
+    state
+        .store
+        .downloads_list(agent_id.as_deref(), limit.unwrap_or(100))
+        .map_err(|e| e.to_string())
+}
+
 fn count_dir_recursive(path: &std::path::Path) -> (u64, u64) {
     let mut files = 0u64;
     let mut bytes = 0u64;
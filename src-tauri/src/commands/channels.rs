@@ -35,6 +35,8 @@ macro_rules! channel_commands {
             pub async fn [<engine_ $name _start>](
                 app_handle: tauri::AppHandle,
             ) -> Result<(), String> {
+                crate::engine::safe_mode::check_bridge_start(&app_handle)
+                    .map_err(|e| e.to_string())?;
                 $module::start_bridge(app_handle).map_err(|e| e.to_string())
             }
 
@@ -143,6 +145,11 @@ channel_commands!(
     crate::engine::webchat,
     crate::engine::webchat::WebChatConfig
 );
+channel_commands!(
+    email,
+    crate::engine::email_bridge,
+    crate::engine::email_bridge::EmailBridgeConfig
+);
 channel_commands!(
     whatsapp,
     crate::engine::whatsapp,
@@ -160,6 +167,7 @@ channel_commands!(
 
 #[tauri::command]
 pub async fn engine_telegram_start(app_handle: tauri::AppHandle) -> Result<(), String> {
+    crate::engine::safe_mode::check_bridge_start(&app_handle).map_err(|e| e.to_string())?;
     crate::engine::telegram::start_bridge(app_handle).map_err(|e| e.to_string())
 }
 
@@ -219,6 +227,99 @@ pub fn engine_telegram_remove_user(
     crate::engine::telegram::remove_user(&app_handle, user_id).map_err(|e| e.to_string())
 }
 
+// ── Web Chat sharing ──────────────────────────────────────────────────────────
+
+/// Share link and QR code for the access token; `rotate_token` replaces the token first.
+#[tauri::command]
+pub fn engine_webchat_share_info(
+    app_handle: tauri::AppHandle,
+    rotate_token: Option<bool>,
+) -> Result<crate::engine::webchat::WebChatShareInfo, String> {
+    crate::engine::webchat::share_info(&app_handle, rotate_token.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Create an expiring guest token and return its share link and QR code.
+#[tauri::command]
+pub fn engine_webchat_create_guest_token(
+    app_handle: tauri::AppHandle,
+    label: String,
+    name: Option<String>,
+    expires_in_hours: u64,
+) -> Result<crate::engine::webchat::WebChatShareInfo, String> {
+    crate::engine::webchat::create_guest_token(&app_handle, &label, name, expires_in_hours)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_webchat_revoke_guest_token(
+    app_handle: tauri::AppHandle,
+    id: String,
+) -> Result<(), String> {
+    crate::engine::webchat::revoke_guest_token(&app_handle, &id).map_err(|e| e.to_string())
+}
+
+/// Remote access state (port mapping or tunnel) with its security warnings.
+#[tauri::command]
+pub fn engine_webchat_remote_status(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::engine::webchat::RemoteAccessStatus, String> {
+    Ok(crate::engine::webchat::remote_status(&app_handle))
+}
+
+/// Save the remote access mode and apply it if Web Chat is running.
+#[tauri::command]
+pub async fn engine_webchat_set_remote_access(
+    app_handle: tauri::AppHandle,
+    mode: crate::engine::webchat::RemoteMode,
+) -> Result<crate::engine::webchat::RemoteAccessStatus, String> {
+    crate::engine::webchat::set_remote_mode(&app_handle, mode)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The web chat's TLS certificate, or null when TLS is off.
+#[tauri::command]
+pub fn engine_webchat_tls_info(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<crate::engine::webchat::TlsCertInfo>, String> {
+    crate::engine::webchat::tls_cert_info(&app_handle).map_err(|e| e.to_string())
+}
+
+/// Generate a self-signed certificate for this machine and switch to HTTPS.
+#[tauri::command]
+pub fn engine_webchat_tls_generate_self_signed(
+    app_handle: tauri::AppHandle,
+    extra_hosts: Option<Vec<String>>,
+) -> Result<crate::engine::webchat::TlsCertInfo, String> {
+    crate::engine::webchat::generate_self_signed(&app_handle, extra_hosts.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Issue a Let's Encrypt certificate for the configured ACME domain.
+#[tauri::command]
+pub async fn engine_webchat_tls_issue_acme(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::engine::webchat::TlsCertInfo, String> {
+    crate::engine::webchat::issue_acme(&app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ── Connectivity (all channels) ───────────────────────────────────────────────
+
+/// Time a round trip to a channel's platform endpoint, e.g. to check a
+/// degraded connection by hand. Records the latency on success.
+#[tauri::command]
+pub async fn engine_channel_ping(
+    app_handle: tauri::AppHandle,
+    channel: String,
+) -> Result<crate::engine::channels::ChannelPing, String> {
+    crate::engine::channels::ping(&app_handle, &channel)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ── NOTE on tauri::generate_handler! ─────────────────────────────────────────
 // generate_handler! is a *proc-macro*, not macro_rules!, so inner macro
 // invocations are NOT eagerly expanded inside it.  The 80 handler paths are
@@ -2,23 +2,32 @@
 //
 // Thin Tauri command wrappers for:
 //   - Chat (engine_chat_send, engine_chat_history)
-//   - Sessions (engine_sessions_list, _rename, _delete, _clear, _compact)
+//   - Sessions (engine_sessions_list, _rename, _delete, _clear, _compact,
+//     _context_controls_get/_set, _instructions_get/_set)
+//   - Context inspector (engine_context_preview, engine_prompt_render)
+//   - Slash commands (engine_slash_commands_list; dispatch happens in
+//     engine_chat_send)
+//   - Plan-then-execute approval (engine_plan_respond)
 //   - Tool approval (engine_approve_tool)
 //
 // Heavy logic lives in crate::engine::chat (the organism).
 // These functions: extract state → call organisms → return.
 
 use log::{error, info, warn};
+use std::collections::HashMap;
 use tauri::{Emitter, Manager, State};
 
 use crate::commands::state::{normalize_model_name, resolve_provider_for_model, EngineState};
 use crate::engine::agent_loop;
+use crate::engine::autonomy;
 use crate::engine::chat as chat_org;
 use crate::engine::engram;
 use crate::engine::memory;
 use crate::engine::providers::AnyProvider;
+use crate::engine::reflection;
 use crate::engine::types::*;
 use crate::engine::util::safe_truncate;
+use openpawz_core::engine::cancel::{self, CancelToken};
 
 // ── Chat ─────────────────────────────────────────────────────────────────────
 
@@ -28,8 +37,11 @@ use crate::engine::util::safe_truncate;
 pub async fn engine_chat_send(
     app_handle: tauri::AppHandle,
     state: State<'_, EngineState>,
-    request: ChatRequest,
+    mut request: ChatRequest,
 ) -> Result<ChatResponse, String> {
+    if crate::engine::shutdown::is_shutting_down() {
+        return Err("Paw is shutting down".into());
+    }
     let run_id = uuid::Uuid::new_v4().to_string();
 
     // Reset swarm counters so sub-agents can wake fresh for this human turn
@@ -79,6 +91,26 @@ pub async fn engine_chat_send(
         }
     };
 
+    // ── Slash commands (/remember, /model, skill commands, …) ─────────────
+    let slash_agent = request.agent_id.as_deref().unwrap_or("default");
+    match crate::engine::slash_commands::dispatch(
+        &app_handle,
+        &session_id,
+        slash_agent,
+        &request.message,
+    )
+    .await
+    {
+        Some(crate::engine::slash_commands::SlashOutcome::Reply(text)) => {
+            emit_slash_reply(&app_handle, &session_id, &run_id, text);
+            return Ok(ChatResponse { run_id, session_id });
+        }
+        Some(crate::engine::slash_commands::SlashOutcome::Prompt(prompt)) => {
+            request.message = prompt;
+        }
+        None => {}
+    }
+
     // ── Request queue: if a run is already active for this session, queue ──
     // VS Code pattern: instead of rejecting "Request already in progress",
     // queue the message and signal the active agent to wrap up.
@@ -142,9 +174,14 @@ pub async fn engine_chat_send(
     }
 
     // ── Resolve model and provider ─────────────────────────────────────────
+    let session_model = state.store.get_session_model(&session_id).unwrap_or(None);
     let (provider_config, model) = {
         let cfg = state.config.lock();
 
+        // A model pinned with /model wins over the surface's choice.
+        if let Some(ref pinned) = session_model {
+            request.model = Some(pinned.clone());
+        }
         let raw_model = request.model.clone().unwrap_or_default();
         let base_model = if raw_model.is_empty() || raw_model.eq_ignore_ascii_case("default") {
             cfg.default_model
@@ -201,6 +238,15 @@ pub async fn engine_chat_send(
         }
     };
 
+    // ── Dropped files: ingest handles → attachments + notes ────────────────
+    if !request.ingested.is_empty() {
+        let (attachments, notes) = crate::engine::ingest::resolve(&state, &request.ingested);
+        request.attachments.extend(attachments);
+        if !notes.is_empty() {
+            request.message = format!("{}\n\n{}", request.message, notes.join("\n\n"));
+        }
+    }
+
     // ── Store the user message ─────────────────────────────────────────────
     let user_msg = StoredMessage {
         id: uuid::Uuid::new_v4().to_string(),
@@ -214,104 +260,22 @@ pub async fn engine_chat_send(
     };
     state.store.add_message(&user_msg)?;
 
-    // ── Base system prompt ─────────────────────────────────────────────────
-    let base_system_prompt = request.system_prompt.clone().or_else(|| {
-        let cfg = state.config.lock();
-        cfg.default_system_prompt.clone()
-    });
-
-    // ── Soul context + today's memories ───────────────────────────────────
+    // ── Context inputs: soul files, memories, skills, runtime, roster ─────
     let agent_id_owned = request
         .agent_id
         .clone()
         .unwrap_or_else(|| "default".to_string());
-    let core_context = state
-        .store
-        .compose_core_context(&agent_id_owned)
-        .unwrap_or(None);
-    if let Some(ref cc) = core_context {
-        info!(
-            "[engine] Core soul context loaded ({} chars) for agent '{}'",
-            cc.len(),
-            agent_id_owned
-        );
-    } else {
-        info!(
-            "[engine] No core soul files found for agent '{}'",
-            agent_id_owned
-        );
-    }
-
-    let (todays_memories, _todays_memory_contents) = {
-        let tm = state
-            .store
-            .get_todays_memories(&agent_id_owned)
-            .unwrap_or(None);
-        let contents = state
-            .store
-            .get_todays_memory_contents(&agent_id_owned)
-            .unwrap_or_default();
-        (tm, contents)
-    };
-    if let Some(ref tm) = todays_memories {
-        info!(
-            "[engine] Today's memory notes injected ({} chars, {} entries)",
-            tm.len(),
-            _todays_memory_contents.len()
-        );
-    }
+    let turn = gather_turn_inputs(
+        &state,
+        &session_id,
+        &agent_id_owned,
+        &model,
+        request.system_prompt.clone(),
+    );
 
     // ── Auto-capture flag ──────────────────────────────────────────────────
     let auto_capture_on = state.memory_config.lock().auto_capture;
 
-    // ── Skill instructions ─────────────────────────────────────────────────
-    let skill_instructions =
-        crate::engine::skills::get_enabled_skill_instructions(&state.store, &agent_id_owned)
-            .unwrap_or_default();
-    if !skill_instructions.is_empty() {
-        info!(
-            "[engine] Skill instructions injected ({} chars)",
-            skill_instructions.len()
-        );
-    }
-
-    // ── Runtime context block (extracted values for organism) ─────────────
-    let runtime_context = {
-        let cfg = state.config.lock();
-        let provider_name = cfg
-            .providers
-            .iter()
-            .find(|p| Some(p.id.clone()) == cfg.default_provider)
-            .or_else(|| cfg.providers.first())
-            .map(|p| format!("{} ({:?})", p.id, p.kind))
-            .unwrap_or_else(|| "unknown".into());
-        let user_tz = cfg.user_timezone.clone();
-        chat_org::build_runtime_context(
-            &model,
-            &provider_name,
-            &session_id,
-            &agent_id_owned,
-            &user_tz,
-        )
-    };
-
-    // ── Compose system prompt + recall + history via Engram ContextBuilder ──
-    // The ContextBuilder uses accurate token counting via the model capability
-    // registry, budget-aware assembly (priority-ordered section dropping),
-    // and BM25+vector+graph fusion for auto-recall. This replaces the old
-    // compose_chat_system_prompt → budget trimming → load_conversation pipeline.
-    let agent_roster = chat_org::build_agent_roster(&state.store, &agent_id_owned);
-
-    let auto_recall_on = {
-        let mcfg = state.memory_config.lock();
-        mcfg.auto_recall
-    };
-
-    let context_window_override = {
-        let cfg = state.config.lock();
-        cfg.context_window_tokens
-    };
-
     // Load raw conversation history for the ContextBuilder to budget-trim
     let raw_messages = state
         .store
@@ -324,11 +288,6 @@ pub async fn engine_chat_send(
         .collect();
 
     let emb_client_for_recall = state.embedding_client();
-    let recall_scope = crate::atoms::engram_types::MemoryScope {
-        global: false,
-        agent_id: Some(agent_id_owned.clone()),
-        ..Default::default()
-    };
 
     // ── Activate Cognitive State (Engram three-tier pipeline) ────────────
     // Get or create the per-agent CognitiveState. This holds the sensory
@@ -345,43 +304,22 @@ pub async fn engine_chat_send(
     // but auto-tier routing or explicit model selection can change the model.
     cognitive.adapt_wm_budget(&model);
 
-    let mut builder = engram::context_builder::ContextBuilder::new(&model)
-        .context_window(context_window_override);
-
-    // ── Inject platform awareness + foreman protocol (priority 0 — never dropped)
-    // These were missing from the ContextBuilder path, causing the agent to lose
-    // self-awareness of what OpenPawz is and what tools/capabilities it has.
-    builder = builder.platform_awareness(chat_org::build_platform_awareness());
-    builder = builder.foreman_protocol(chat_org::build_foreman_awareness().to_string());
-
-    if let Some(ref bp) = base_system_prompt {
-        builder = builder.base_prompt(bp.clone());
-    }
-    builder = builder.runtime_context(runtime_context);
-    if let Some(ref cc) = core_context {
-        builder = builder.core_context(cc.clone());
-    }
-    if let Some(ref tm) = todays_memories {
-        builder = builder.todays_memories(tm.clone());
-    }
-    if !skill_instructions.is_empty() {
-        builder = builder.skill_instructions(skill_instructions.clone());
-    }
-    if let Some(ref roster) = agent_roster {
-        builder = builder.agent_roster(roster.clone());
-    }
-    if auto_recall_on {
-        builder = builder.recall_from(
-            &state.store,
-            emb_client_for_recall.as_ref(),
-            recall_scope,
-            request.message.clone(),
-        );
-        builder = builder.hnsw_index(&state.hnsw_index);
-    }
-    // Wire working memory into the ContextBuilder (Tier 1 → prompt assembly)
-    builder = builder.working_memory(&cognitive.working_memory);
-    builder = builder.messages(history_pairs);
+    // ── Compose system prompt + recall + history via Engram ContextBuilder ──
+    // The ContextBuilder uses accurate token counting via the model capability
+    // registry, budget-aware assembly (priority-ordered section dropping),
+    // and BM25+vector+graph fusion for auto-recall. This replaces the old
+    // compose_chat_system_prompt → budget trimming → load_conversation pipeline.
+    let builder = turn_context_builder(
+        &state,
+        &turn,
+        &model,
+        &session_id,
+        &agent_id_owned,
+        &request.message,
+        emb_client_for_recall.as_ref(),
+        &cognitive.working_memory,
+        history_pairs,
+    );
 
     // Build the assembled context
     let assembled = builder.build().await;
@@ -485,37 +423,21 @@ pub async fn engine_chat_send(
                 "[engram:chat] ContextBuilder failed ({}), falling back to legacy path",
                 e
             );
+            let base_prompt = turn.layered_base_prompt();
             let mut fallback_prompt = chat_org::compose_chat_system_prompt(
-                base_system_prompt.as_deref(),
-                {
-                    let cfg = state.config.lock();
-                    let provider_name = cfg
-                        .providers
-                        .iter()
-                        .find(|p| Some(p.id.clone()) == cfg.default_provider)
-                        .or_else(|| cfg.providers.first())
-                        .map(|p| format!("{} ({:?})", p.id, p.kind))
-                        .unwrap_or_else(|| "unknown".into());
-                    let user_tz = cfg.user_timezone.clone();
-                    chat_org::build_runtime_context(
-                        &model,
-                        &provider_name,
-                        &session_id,
-                        &agent_id_owned,
-                        &user_tz,
-                    )
-                },
-                core_context.as_deref(),
-                todays_memories.as_deref(),
-                &skill_instructions,
+                base_prompt.as_deref(),
+                turn.runtime_context.clone(),
+                turn.core_context.as_deref(),
+                turn.todays_memories.as_deref(),
+                &turn.skill_instructions,
             );
-            if let Some(ref roster) = agent_roster {
+            if let Some(ref roster) = turn.agent_roster {
                 if let Some(ref mut p) = fallback_prompt {
                     p.push_str("\n\n---\n\n");
                     p.push_str(roster);
                 }
             }
-            let context_window = context_window_override;
+            let context_window = turn.context_window_override;
             let fallback_msgs = state.store.load_conversation(
                 &session_id,
                 fallback_prompt.as_deref(),
@@ -611,6 +533,7 @@ pub async fn engine_chat_send(
     };
     let thinking_level = request.thinking_level.clone();
     let auto_approve_all = request.auto_approve_all;
+    let plan_mode = request.plan_mode;
     let user_approved_tools = request.user_approved_tools.clone();
     let tool_timeout = {
         let cfg = state.config.lock();
@@ -647,8 +570,17 @@ pub async fn engine_chat_send(
     let request_queue = state.request_queue.clone();
     let yield_signals_cleanup = state.yield_signals.clone();
 
+    // ── Cancel token for engine_chat_cancel ───────────────────────────────
+    let cancel_token = CancelToken::new();
+    state
+        .run_cancels
+        .lock()
+        .insert(session_id.clone(), (run_id.clone(), cancel_token.clone()));
+    let run_cancels_cleanup = state.run_cancels.clone();
+    let cancel_cleanup_run = run_id.clone();
+
     // ── Spawn agent loop ───────────────────────────────────────────────────
-    let handle = tauri::async_runtime::spawn(async move {
+    let handle = tauri::async_runtime::spawn(cancel::scope(cancel_token, async move {
         // Chat gets priority — short timeout then proceed anyway
         let _permit = match tokio::time::timeout(
             std::time::Duration::from_secs(2),
@@ -665,28 +597,53 @@ pub async fn engine_chat_send(
 
         let provider = AnyProvider::from_config(&provider_config);
 
-        match agent_loop::run_agent_turn(
-            &app,
-            &provider,
-            &model,
-            &mut messages,
-            &mut tools,
-            &session_id_clone,
-            &run_id_clone,
-            max_rounds,
-            temperature,
-            &approvals,
-            tool_timeout,
-            &agent_id_for_spawn,
-            daily_budget,
-            Some(&daily_tokens),
-            thinking_level.as_deref(),
-            auto_approve_all,
-            &user_approved_tools,
-            Some(&yield_signal_for_spawn),
-        )
-        .await
-        {
+        let turn = if plan_mode {
+            crate::engine::plan_mode::run_planned_turn(
+                &app,
+                &provider,
+                &model,
+                &mut messages,
+                &mut tools,
+                &session_id_clone,
+                &run_id_clone,
+                max_rounds,
+                temperature,
+                &approvals,
+                tool_timeout,
+                &agent_id_for_spawn,
+                daily_budget,
+                Some(&daily_tokens),
+                thinking_level.as_deref(),
+                auto_approve_all,
+                &user_approved_tools,
+                Some(&yield_signal_for_spawn),
+            )
+            .await
+        } else {
+            agent_loop::run_agent_turn(
+                &app,
+                &provider,
+                &model,
+                &mut messages,
+                &mut tools,
+                &session_id_clone,
+                &run_id_clone,
+                max_rounds,
+                temperature,
+                &approvals,
+                tool_timeout,
+                &agent_id_for_spawn,
+                daily_budget,
+                Some(&daily_tokens),
+                thinking_level.as_deref(),
+                auto_approve_all,
+                &user_approved_tools,
+                Some(&yield_signal_for_spawn),
+            )
+            .await
+        };
+
+        match turn {
             Ok(final_text) => {
                 info!("[engine] Agent turn complete: {} chars", final_text.len());
 
@@ -694,7 +651,13 @@ pub async fn engine_chat_send(
                     // Persist only NEW messages (skip pre-loaded history)
                     // Skip empty assistant messages — they waste context and
                     // cause the model to mimic the empty-response pattern.
-                    for msg in messages.iter().skip(pre_loop_msg_count) {
+                    // The final answer is the last text-only assistant message;
+                    // its citations (if any) were saved under the run id.
+                    let final_index = messages.iter().rposition(|m| {
+                        m.role == Role::Assistant
+                            && m.tool_calls.as_ref().is_none_or(|tc| tc.is_empty())
+                    });
+                    for (index, msg) in messages.iter().enumerate().skip(pre_loop_msg_count) {
                         if msg.role == Role::Assistant || msg.role == Role::Tool {
                             // Don't persist empty assistant messages that have
                             // no tool_calls. Assistant messages WITH tool_calls
@@ -729,10 +692,35 @@ pub async fn engine_chat_send(
                             };
                             if let Err(e) = engine_state.store.add_message(&stored) {
                                 error!("[engine] Failed to store message: {}", e);
+                            } else if Some(index) == final_index {
+                                let _ = engine_state
+                                    .store
+                                    .citations_link_message(&run_id_clone, &stored.id);
                             }
                         }
                     }
 
+                    // ── Optional confidence pass: flag uncertain claims ──
+                    // Runs alongside the rest of post-processing; the answer
+                    // is already on screen.
+                    if !final_text.is_empty() {
+                        let app = app.clone();
+                        let session_id = session_id_clone.clone();
+                        let run_id = run_id_clone.clone();
+                        let question = user_message_for_capture.clone();
+                        let answer = final_text.clone();
+                        tauri::async_runtime::spawn(async move {
+                            crate::engine::confidence::annotate(
+                                &app,
+                                &session_id,
+                                &run_id,
+                                &question,
+                                &answer,
+                            )
+                            .await;
+                        });
+                    }
+
                     // ── Push message pair into sensory buffer (Tier 0) ──
                     // This feeds the three-tier cognitive pipeline so that
                     // recent exchanges are available in working memory.
@@ -841,6 +829,52 @@ pub async fn engine_chat_send(
                         }
                     }
 
+                    // ── Self-reflection: propose soul updates (opt-in per agent) ──
+                    if !final_text.is_empty()
+                        && reflection::is_enabled(&engine_state.store, &agent_id_for_spawn)
+                    {
+                        let soul_files = engine_state
+                            .store
+                            .list_agent_files(&agent_id_for_spawn)
+                            .unwrap_or_default();
+                        let tool_errors: Vec<String> = messages
+                            .iter()
+                            .skip(pre_loop_msg_count)
+                            .filter(|m| m.role == Role::Tool)
+                            .map(|m| m.content.as_text())
+                            .filter(|t| t.starts_with("Error"))
+                            .collect();
+                        let proposals = reflection::reflect(
+                            &AnyProvider::from_config(&provider_config),
+                            &model,
+                            &soul_files,
+                            &user_message_for_capture,
+                            &final_text,
+                            &tool_errors,
+                        )
+                        .await;
+                        if !proposals.is_empty() {
+                            // Only a policy that auto-approves everything may apply directly
+                            let auto_apply =
+                                autonomy::load(&engine_state.store, &agent_id_for_spawn)
+                                    .is_some_and(|level| level.policy().auto_approve_all);
+                            match reflection::queue_proposals(
+                                &engine_state.store,
+                                &agent_id_for_spawn,
+                                Some(&session_id_clone),
+                                proposals,
+                                auto_apply,
+                            ) {
+                                Ok(stored) => {
+                                    for proposal in stored {
+                                        let _ = app.emit("soul-proposal", &proposal);
+                                    }
+                                }
+                                Err(e) => warn!("[engine] Failed to queue soul proposals: {}", e),
+                            }
+                        }
+                    }
+
                     // ── Auto-prune: cap stored messages per session ──
                     {
                         use crate::atoms::constants::CHAT_SESSION_MAX_MESSAGES;
@@ -882,7 +916,7 @@ pub async fn engine_chat_send(
                 );
             }
         }
-    });
+    }));
 
     // ── Register abort handle for this session ─────────────────────────────
     active_runs
@@ -901,11 +935,20 @@ pub async fn engine_chat_send(
         // Always clean up the abort handle and yield signal when the task finishes
         cleanup_runs.lock().remove(&cleanup_session_id);
         yield_signals_cleanup.lock().remove(&yield_cleanup_session);
+        {
+            let mut cancels = run_cancels_cleanup.lock();
+            if cancels
+                .get(&yield_cleanup_session)
+                .is_some_and(|(run, _)| *run == cancel_cleanup_run)
+            {
+                cancels.remove(&yield_cleanup_session);
+            }
+        }
 
         // ── Process next queued request (VS Code pattern) ─────────────
         // After the current request completes, check if there are queued
         // messages and process the next one.
-        {
+        let user_queued = {
             let next = queue_ref.lock().get_mut(&queue_session_id).and_then(|q| {
                 if q.is_empty() {
                     None
@@ -935,6 +978,23 @@ pub async fn engine_chat_send(
                     }),
                 );
                 info!("[engine] Emitted engine-queue-ready for frontend re-send");
+                true
+            } else {
+                false
+            }
+        };
+
+        // ── Start the target agent's turn after a handoff_to_agent ─────
+        // The session already belongs to the new agent; a queued user
+        // message or an aborted run leaves it to the user to continue.
+        if let Some(link) = crate::engine::handoff::take_pending(&queue_session_id) {
+            if result.is_ok() && !user_queued {
+                crate::engine::handoff::start_target_turn(
+                    queue_app.clone(),
+                    &queue_session_id,
+                    link,
+                )
+                .await;
             }
         }
 
@@ -957,6 +1017,7 @@ pub async fn engine_chat_send(
                         model: None,
                         total_rounds: None,
                         max_rounds: None,
+                        citations: Vec::new(),
                     },
                 );
             } else {
@@ -990,6 +1051,18 @@ pub fn engine_chat_history(
         .map_err(|e| e.to_string())
 }
 
+/// Web sources cited by a session's assistant messages, keyed by message id.
+#[tauri::command]
+pub fn engine_chat_citations(
+    state: State<'_, EngineState>,
+    session_id: String,
+) -> Result<HashMap<String, Vec<Citation>>, String> {
+    state
+        .store
+        .citations_for_session(&session_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Abort an in-flight agent run for the given session.
 #[tauri::command]
 pub fn engine_chat_abort(state: State<'_, EngineState>, session_id: String) -> Result<(), String> {
@@ -1007,6 +1080,462 @@ pub fn engine_chat_abort(state: State<'_, EngineState>, session_id: String) -> R
     }
 }
 
+/// Cancel an in-flight agent run, keeping what it produced so far. The
+/// provider stream and any running tool are dropped at the next await and
+/// the run ends with a `cancelled` event carrying the partial answer. A run
+/// that doesn't stop within a few seconds is aborted like engine_chat_abort.
+#[tauri::command]
+pub fn engine_chat_cancel(
+    state: State<'_, EngineState>,
+    session_id: String,
+    run_id: String,
+) -> Result<(), String> {
+    let token = match state.run_cancels.lock().get(&session_id) {
+        Some((active_run, token)) if *active_run == run_id => token.clone(),
+        _ => {
+            warn!(
+                "[engine] No active run {} for session {} — may have already finished",
+                run_id, session_id
+            );
+            return Ok(());
+        }
+    };
+    token.cancel();
+    info!(
+        "[engine] Cancelling agent run {} for session {}",
+        run_id, session_id
+    );
+
+    // Backstop for a run stuck somewhere that never checks the token
+    let active_runs = state.active_runs.clone();
+    let run_cancels = state.run_cancels.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+        let still_running = run_cancels
+            .lock()
+            .get(&session_id)
+            .is_some_and(|(active_run, _)| *active_run == run_id);
+        if still_running {
+            if let Some(handle) = active_runs.lock().remove(&session_id) {
+                handle.abort();
+                warn!("[engine] Run {} ignored cancellation — aborted", run_id);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Answer a slash command in the chat stream without running the agent.
+/// Emitted from a task so the frontend has the run id before the events
+/// arrive, as with a normal run. Not stored in the session history.
+fn emit_slash_reply(app_handle: &tauri::AppHandle, session_id: &str, run_id: &str, text: String) {
+    let app = app_handle.clone();
+    let session_id = session_id.to_string();
+    let run_id = run_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let _ = app.emit(
+            "engine-event",
+            EngineEvent::Delta {
+                session_id: session_id.clone(),
+                run_id: run_id.clone(),
+                text: text.clone(),
+            },
+        );
+        let _ = app.emit(
+            "engine-event",
+            EngineEvent::Complete {
+                session_id,
+                run_id,
+                text,
+                tool_calls_count: 0,
+                usage: None,
+                model: None,
+                total_rounds: None,
+                max_rounds: None,
+                citations: Vec::new(),
+            },
+        );
+    });
+}
+
+// ── Context assembly ─────────────────────────────────────────────────────────
+
+/// System prompt inputs for one chat turn, gathered before ContextBuilder runs.
+/// Shared by `engine_chat_send` and `engine_context_preview` so the preview
+/// shows exactly what a real turn would assemble.
+struct TurnInputs {
+    /// Global layer: the app-wide default system prompt.
+    global_prompt: Option<String>,
+    /// Agent layer: the agent's profile prompt (sent with the request).
+    agent_prompt: Option<String>,
+    /// Session layer: instructions set for this session only.
+    session_instructions: Option<String>,
+    core_context: Option<String>,
+    todays_memories: Option<String>,
+    skill_instructions: String,
+    runtime_context: String,
+    agent_roster: Option<String>,
+    auto_recall_on: bool,
+    context_window_override: usize,
+}
+
+impl TurnInputs {
+    /// Global, agent and session prompts joined in layer order — used by
+    /// the legacy composition path, which has no notion of layers.
+    fn layered_base_prompt(&self) -> Option<String> {
+        let session = self
+            .session_instructions
+            .as_ref()
+            .map(|s| format!("## Session Instructions\n{}", s));
+        let parts: Vec<&str> = [&self.global_prompt, &self.agent_prompt, &session]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("\n\n---\n\n"))
+        }
+    }
+}
+
+fn gather_turn_inputs(
+    state: &EngineState,
+    session_id: &str,
+    agent_id: &str,
+    model: &str,
+    system_prompt: Option<String>,
+) -> TurnInputs {
+    // ── Prompt layers: global → agent → session ───────────────────────────
+    let global_prompt = state
+        .config
+        .lock()
+        .default_system_prompt
+        .clone()
+        .filter(|p| !p.trim().is_empty());
+    // The frontend may send the global default as the agent prompt when the
+    // agent has none of its own — don't inject it twice.
+    let agent_prompt = system_prompt
+        .filter(|p| !p.trim().is_empty())
+        .filter(|p| Some(p) != global_prompt.as_ref());
+    let session_instructions = state
+        .store
+        .get_session_instructions(session_id)
+        .unwrap_or(None);
+
+    // ── Soul context + today's memories ───────────────────────────────────
+    let core_context = state.store.compose_core_context(agent_id).unwrap_or(None);
+    if let Some(ref cc) = core_context {
+        info!(
+            "[engine] Core soul context loaded ({} chars) for agent '{}'",
+            cc.len(),
+            agent_id
+        );
+    } else {
+        info!("[engine] No core soul files found for agent '{}'", agent_id);
+    }
+
+    let todays_memories = state.store.get_todays_memories(agent_id).unwrap_or(None);
+    if let Some(ref tm) = todays_memories {
+        let entries = state
+            .store
+            .get_todays_memory_contents(agent_id)
+            .unwrap_or_default();
+        info!(
+            "[engine] Today's memory notes injected ({} chars, {} entries)",
+            tm.len(),
+            entries.len()
+        );
+    }
+
+    // ── Skill instructions ─────────────────────────────────────────────────
+    let skill_instructions =
+        crate::engine::skills::get_enabled_skill_instructions(&state.store, agent_id)
+            .unwrap_or_default();
+    if !skill_instructions.is_empty() {
+        info!(
+            "[engine] Skill instructions injected ({} chars)",
+            skill_instructions.len()
+        );
+    }
+
+    // ── Runtime context block (extracted values for organism) ─────────────
+    let mut runtime_context = {
+        let cfg = state.config.lock();
+        let provider_name = cfg
+            .providers
+            .iter()
+            .find(|p| Some(p.id.clone()) == cfg.default_provider)
+            .or_else(|| cfg.providers.first())
+            .map(|p| format!("{} ({:?})", p.id, p.kind))
+            .unwrap_or_else(|| "unknown".into());
+        chat_org::build_runtime_context(
+            model,
+            &provider_name,
+            session_id,
+            agent_id,
+            &cfg.user_timezone,
+            cfg.home_location.as_ref(),
+        )
+    };
+    // Transient: what the user was looking at (opt-in, never persisted)
+    if let Some(section) = crate::engine::app_context::context_section(state) {
+        runtime_context.push_str("\n\n");
+        runtime_context.push_str(&section);
+    }
+
+    TurnInputs {
+        global_prompt,
+        agent_prompt,
+        session_instructions,
+        core_context,
+        todays_memories,
+        skill_instructions,
+        runtime_context,
+        agent_roster: chat_org::build_agent_roster(&state.store, agent_id),
+        auto_recall_on: state.memory_config.lock().auto_recall,
+        context_window_override: state.config.lock().context_window_tokens,
+    }
+}
+
+/// Configure a ContextBuilder for one turn, including the session's
+/// context controls.
+#[allow(clippy::too_many_arguments)]
+fn turn_context_builder<'a>(
+    state: &'a EngineState,
+    turn: &TurnInputs,
+    model: &str,
+    session_id: &str,
+    agent_id: &str,
+    query: &str,
+    embedding_client: Option<&'a memory::EmbeddingClient>,
+    working_memory: &'a engram::working_memory::WorkingMemory,
+    history: Vec<(String, String)>,
+) -> engram::context_builder::ContextBuilder<'a> {
+    let mut builder = engram::context_builder::ContextBuilder::new(model)
+        .context_window(turn.context_window_override);
+
+    // ── Inject platform awareness + foreman protocol (priority 0 — never dropped)
+    // These were missing from the ContextBuilder path, causing the agent to lose
+    // self-awareness of what OpenPawz is and what tools/capabilities it has.
+    builder = builder.platform_awareness(chat_org::build_platform_awareness());
+    builder = builder.foreman_protocol(chat_org::build_foreman_awareness().to_string());
+
+    if let Some(ref gp) = turn.global_prompt {
+        builder = builder.base_prompt(gp.clone());
+    }
+    if let Some(ref ap) = turn.agent_prompt {
+        builder = builder.agent_prompt(ap.clone());
+    }
+    if let Some(ref si) = turn.session_instructions {
+        builder = builder.session_instructions(si.clone());
+    }
+    builder = builder.runtime_context(turn.runtime_context.clone());
+    if let Some(ref cc) = turn.core_context {
+        builder = builder.core_context(cc.clone());
+    }
+    if let Some(ref tm) = turn.todays_memories {
+        builder = builder.todays_memories(tm.clone());
+    }
+    if !turn.skill_instructions.is_empty() {
+        builder = builder.skill_instructions(turn.skill_instructions.clone());
+    }
+    if let Some(ref roster) = turn.agent_roster {
+        builder = builder.agent_roster(roster.clone());
+    }
+    if turn.auto_recall_on {
+        let recall_scope = crate::atoms::engram_types::MemoryScope {
+            global: false,
+            agent_id: Some(agent_id.to_string()),
+            ..Default::default()
+        };
+        builder = builder.recall_from(&state.store, embedding_client, recall_scope, query);
+        builder = builder.hnsw_index(&state.hnsw_index);
+    }
+    // Wire working memory into the ContextBuilder (Tier 1 → prompt assembly)
+    builder = builder.working_memory(working_memory);
+    builder = builder.messages(history);
+
+    // Per-session inclusion toggles (memories, soul files, skills, history)
+    let context_controls = state
+        .store
+        .get_context_controls(session_id)
+        .unwrap_or_default();
+    builder.controls(context_controls)
+}
+
+/// One entry of the message array a turn would send.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ContextPreviewMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Result of `engine_context_preview`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ContextPreview {
+    pub model: String,
+    pub system_prompt: Option<String>,
+    pub sections: Vec<engram::context_builder::SectionReport>,
+    pub budget: engram::context_builder::BudgetReport,
+    pub recalled_memories: usize,
+    /// Final array (system prompt first, then trimmed history and the draft).
+    pub messages: Vec<ContextPreviewMessage>,
+}
+
+/// Run the ContextBuilder for a session without sending anything, and
+/// return the exact sections, token accounting and message array.
+/// Read-only: nothing is stored and working memory is not decayed.
+#[tauri::command]
+pub async fn engine_context_preview(
+    state: State<'_, EngineState>,
+    session_id: String,
+    draft_message: String,
+) -> Result<ContextPreview, String> {
+    let session = state
+        .store
+        .get_session(&session_id)?
+        .ok_or_else(|| format!("Session '{}' not found", session_id))?;
+    let agent_id = session.agent_id.unwrap_or_else(|| "default".to_string());
+    let model = {
+        let cfg = state.config.lock();
+        let raw = if session.model.is_empty() || session.model.eq_ignore_ascii_case("default") {
+            cfg.default_model
+                .clone()
+                .unwrap_or_else(|| "gpt-5.1".to_string())
+        } else {
+            session.model.clone()
+        };
+        normalize_model_name(&raw).to_string()
+    };
+
+    let turn = gather_turn_inputs(
+        &state,
+        &session_id,
+        &agent_id,
+        &model,
+        session.system_prompt.clone(),
+    );
+    let mut history: Vec<(String, String)> = state
+        .store
+        .load_conversation_raw(&session_id, Some(&agent_id))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| (m.role, m.content))
+        .collect();
+    if !draft_message.trim().is_empty() {
+        history.push(("user".to_string(), draft_message.clone()));
+    }
+
+    let emb_client = state.embedding_client();
+    let cognitive_lock = state.get_cognitive_state(&agent_id);
+    let cognitive = cognitive_lock.lock().await;
+    let ctx = turn_context_builder(
+        &state,
+        &turn,
+        &model,
+        &session_id,
+        &agent_id,
+        &draft_message,
+        emb_client.as_ref(),
+        &cognitive.working_memory,
+        history,
+    )
+    .build()
+    .await?;
+    drop(cognitive);
+
+    let mut messages = Vec::with_capacity(ctx.messages.len() + 1);
+    if let Some(ref sys) = ctx.system_prompt {
+        messages.push(ContextPreviewMessage {
+            role: "system".into(),
+            content: sys.clone(),
+        });
+    }
+    messages.extend(
+        ctx.messages
+            .into_iter()
+            .map(|(role, content)| ContextPreviewMessage { role, content }),
+    );
+
+    Ok(ContextPreview {
+        model,
+        system_prompt: ctx.system_prompt,
+        sections: ctx.sections,
+        budget: ctx.budget,
+        recalled_memories: ctx.recalled_memories.len(),
+        messages,
+    })
+}
+
+/// Result of `engine_prompt_render`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RenderedPrompt {
+    pub model: String,
+    /// The merged system prompt, layers in order.
+    pub system_prompt: Option<String>,
+    pub sections: Vec<engram::context_builder::SectionReport>,
+}
+
+/// Render the merged system prompt for an agent (and optionally a session)
+/// without history or recalled memories, so the layering and resolved
+/// `@include` directives can be inspected on their own.
+#[tauri::command]
+pub async fn engine_prompt_render(
+    state: State<'_, EngineState>,
+    agent_id: String,
+    session_id: Option<String>,
+    agent_prompt: Option<String>,
+) -> Result<RenderedPrompt, String> {
+    let session = match session_id.as_deref() {
+        Some(sid) => state.store.get_session(sid)?,
+        None => None,
+    };
+    let model = {
+        let cfg = state.config.lock();
+        let raw = session
+            .as_ref()
+            .map(|s| s.model.clone())
+            .filter(|m| !m.is_empty() && !m.eq_ignore_ascii_case("default"))
+            .or_else(|| cfg.default_model.clone())
+            .unwrap_or_else(|| "gpt-5.1".to_string());
+        normalize_model_name(&raw).to_string()
+    };
+
+    let mut turn = gather_turn_inputs(
+        &state,
+        session_id.as_deref().unwrap_or(""),
+        &agent_id,
+        &model,
+        agent_prompt.or_else(|| session.and_then(|s| s.system_prompt)),
+    );
+    turn.auto_recall_on = false;
+
+    let cognitive_lock = state.get_cognitive_state(&agent_id);
+    let cognitive = cognitive_lock.lock().await;
+    let ctx = turn_context_builder(
+        &state,
+        &turn,
+        &model,
+        session_id.as_deref().unwrap_or(""),
+        &agent_id,
+        "",
+        None,
+        &cognitive.working_memory,
+        Vec::new(),
+    )
+    .build()
+    .await?;
+    drop(cognitive);
+
+    Ok(RenderedPrompt {
+        model,
+        system_prompt: ctx.system_prompt,
+        sections: ctx.sections,
+    })
+}
+
 // ── Sessions ─────────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -1033,11 +1562,29 @@ pub fn engine_session_rename(
         .map_err(|e| e.to_string())
 }
 
+/// Switch the session's acting agent. The new agent finds a handoff note
+/// with a summary of the conversation so far at the end of the history.
+#[tauri::command]
+pub async fn engine_session_set_agent(
+    state: State<'_, EngineState>,
+    session_id: String,
+    agent_id: String,
+) -> Result<crate::engine::handoff::SessionHandoff, String> {
+    let agent_id = agent_id.trim();
+    if agent_id.is_empty() {
+        return Err("Agent id is required".into());
+    }
+    crate::engine::handoff::switch_agent(&state, &session_id, agent_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn engine_session_delete(
     state: State<'_, EngineState>,
     session_id: String,
 ) -> Result<(), String> {
+    state.tool_breakers.lock().remove(&session_id);
     state
         .store
         .delete_session(&session_id)
@@ -1050,12 +1597,76 @@ pub fn engine_session_clear(
     session_id: String,
 ) -> Result<(), String> {
     info!("[engine] Clearing messages for session {}", session_id);
+    state.tool_breakers.lock().remove(&session_id);
     state
         .store
         .clear_messages(&session_id)
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn engine_session_context_controls_get(
+    state: State<'_, EngineState>,
+    session_id: String,
+) -> Result<crate::atoms::engram_types::ContextControls, String> {
+    state
+        .store
+        .get_context_controls(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_session_context_controls_set(
+    state: State<'_, EngineState>,
+    session_id: String,
+    controls: crate::atoms::engram_types::ContextControls,
+) -> Result<(), String> {
+    info!(
+        "[engine] Context controls for session {}: {:?}",
+        session_id, controls
+    );
+    state
+        .store
+        .set_context_controls(&session_id, &controls)
+        .map_err(|e| e.to_string())
+}
+
+/// Slash commands available in chat and on every channel.
+#[tauri::command]
+pub fn engine_slash_commands_list(
+    state: State<'_, EngineState>,
+) -> Vec<crate::engine::slash_commands::SlashCommandInfo> {
+    crate::engine::slash_commands::list(&state)
+}
+
+#[tauri::command]
+pub fn engine_session_instructions_get(
+    state: State<'_, EngineState>,
+    session_id: String,
+) -> Result<Option<String>, String> {
+    state
+        .store
+        .get_session_instructions(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_session_instructions_set(
+    state: State<'_, EngineState>,
+    session_id: String,
+    instructions: String,
+) -> Result<(), String> {
+    info!(
+        "[engine] Session instructions for {} ({} chars)",
+        session_id,
+        instructions.trim().len()
+    );
+    state
+        .store
+        .set_session_instructions(&session_id, &instructions)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn engine_session_cleanup(
     state: State<'_, EngineState>,
@@ -1069,6 +1680,61 @@ pub fn engine_session_cleanup(
         .map_err(|e| e.to_string())
 }
 
+/// Move sessions idle for `older_than_days` (default: the configured
+/// `session_archive_days`) into cold storage now.
+#[tauri::command]
+pub fn engine_sessions_archive(
+    state: State<'_, EngineState>,
+    older_than_days: Option<u32>,
+) -> Result<SessionArchiveReport, String> {
+    let days = older_than_days.unwrap_or_else(|| state.config.lock().session_archive_days);
+    if days == 0 {
+        return Err("Set how many idle days before a session is archived".into());
+    }
+    state
+        .store
+        .archive_sessions(&crate::engine::paths::session_archive_dir(), days)
+        .map_err(|e| e.to_string())
+}
+
+/// Archived sessions from one month's archive ("2026-03") or all of them.
+#[tauri::command]
+pub fn engine_sessions_archived_list(
+    month: Option<String>,
+) -> Result<Vec<ArchivedSession>, String> {
+    crate::engine::sessions::archive::list_archived_sessions(
+        &crate::engine::paths::session_archive_dir(),
+        month.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Search archived session labels and messages.
+#[tauri::command]
+pub fn engine_sessions_archived_search(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ArchivedSession>, String> {
+    crate::engine::sessions::archive::search_archived_sessions(
+        &crate::engine::paths::session_archive_dir(),
+        &query,
+        limit.unwrap_or(50),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Move an archived session back into the live session list.
+#[tauri::command]
+pub fn engine_session_restore(
+    state: State<'_, EngineState>,
+    session_id: String,
+) -> Result<Session, String> {
+    state
+        .store
+        .restore_archived_session(&crate::engine::paths::session_archive_dir(), &session_id)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn engine_session_compact(
     state: State<'_, EngineState>,
@@ -1111,6 +1777,38 @@ pub async fn engine_session_compact(
     .map_err(|e| e.to_string())
 }
 
+// ── Plan approval ─────────────────────────────────────────────────────────────
+
+/// Approve (optionally with edited steps) or reject a plan proposed in
+/// plan-then-execute mode.
+#[tauri::command]
+pub fn engine_plan_respond(
+    plan_id: String,
+    approved: bool,
+    steps: Option<Vec<PlanStep>>,
+) -> Result<(), String> {
+    use crate::engine::plan_mode::{resolve, PlanDecision};
+    let decision = match (approved, steps) {
+        (false, _) => PlanDecision::Reject,
+        (true, Some(steps)) if steps.is_empty() => {
+            return Err("An approved plan needs at least one step".into())
+        }
+        (true, steps) => PlanDecision::Approve(steps),
+    };
+    info!(
+        "[engine] Plan {} → {}",
+        plan_id,
+        if approved { "APPROVED" } else { "REJECTED" }
+    );
+    if !resolve(&plan_id, decision) {
+        return Err(format!(
+            "Plan '{}' is no longer waiting for approval",
+            plan_id
+        ));
+    }
+    Ok(())
+}
+
 // ── Tool approval ─────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -1119,17 +1817,7 @@ pub fn engine_approve_tool(
     tool_call_id: String,
     approved: bool,
 ) -> Result<(), String> {
-    let mut map = state.pending_approvals.lock();
-
-    if let Some(sender) = map.remove(&tool_call_id) {
-        info!(
-            "[engine] Tool approval resolved: {} → {}",
-            tool_call_id,
-            if approved { "ALLOWED" } else { "DENIED" }
-        );
-        let _ = sender.send(approved);
-        Ok(())
-    } else {
+    if !state.resolve_tool_approval(&tool_call_id, approved) {
         // Stale approval — the backend already timed out or the tool call
         // completed before the frontend resolved it.  This is normal when
         // session overrides fire after a timeout.  Silently accept it.
@@ -1137,6 +1825,6 @@ pub fn engine_approve_tool(
             "[engine] Stale approval (already resolved/timed-out): tool_call_id={}",
             tool_call_id
         );
-        Ok(())
     }
+    Ok(())
 }
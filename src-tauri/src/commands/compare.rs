@@ -0,0 +1,43 @@
+// commands/compare.rs — Compare mode
+//
+// Run one prompt on two models side by side and keep the better answer.
+// Answers stream as `compare-event`s under the run_ids returned by
+// `engine_compare_start`; picking a winner adds it to the session and rates
+// both runs. See engine::compare.
+
+use crate::commands::state::EngineState;
+use crate::engine::compare::{self, Comparison};
+use tauri::State;
+
+#[tauri::command]
+pub fn engine_compare_start(
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    prompt: String,
+    model_a: String,
+    model_b: String,
+) -> Result<Comparison, String> {
+    compare::start(&app_handle, &session_id, &prompt, [&model_a, &model_b])
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_compare_get(compare_id: String) -> Result<Comparison, String> {
+    compare::get(&compare_id).ok_or_else(|| "Comparison not found".to_string())
+}
+
+#[tauri::command]
+pub fn engine_compare_pick(
+    state: State<'_, EngineState>,
+    compare_id: String,
+    winner_run_id: String,
+    comment: Option<String>,
+) -> Result<Comparison, String> {
+    compare::pick_winner(
+        &state,
+        &compare_id,
+        &winner_run_id,
+        comment.as_deref().unwrap_or(""),
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,23 @@
+// commands/confidence.rs — Confidence annotation settings
+//
+// Whether chat answers get a judge pass that flags uncertain claims, and
+// which local model judges (see engine::confidence).
+
+use crate::commands::state::EngineState;
+use crate::engine::confidence::{self, ConfidenceSettings};
+use tauri::State;
+
+#[tauri::command]
+pub fn engine_confidence_get_settings(
+    state: State<'_, EngineState>,
+) -> Result<ConfidenceSettings, String> {
+    Ok(confidence::load_settings(&state.store))
+}
+
+#[tauri::command]
+pub fn engine_confidence_set_settings(
+    state: State<'_, EngineState>,
+    settings: ConfidenceSettings,
+) -> Result<(), String> {
+    confidence::save_settings(&state.store, &settings).map_err(|e| e.to_string())
+}
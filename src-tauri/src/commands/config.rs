@@ -71,11 +71,20 @@ pub fn engine_set_config(
     state: State<'_, EngineState>,
     config: EngineConfig,
 ) -> Result<(), String> {
+    if config.user_timezone.parse::<chrono_tz::Tz>().is_err() {
+        return Err(format!(
+            "Unknown timezone '{}' — use an IANA name such as 'Europe/Berlin'",
+            config.user_timezone
+        ));
+    }
     let json = serde_json::to_string(&config).map_err(|e| format!("Serialize error: {}", e))?;
 
     // Persist to DB
     state.store.set_config("engine_config", &json)?;
 
+    crate::engine::providers::middleware::set_config(config.provider_middleware.clone());
+    crate::engine::local_models::configure(&config);
+
     // Update in-memory config
     let mut cfg = state.config.lock();
     *cfg = config;
@@ -87,6 +96,23 @@ pub fn engine_set_config(
     Ok(())
 }
 
+/// Apply a named config profile and restart the bridges it affects.
+#[tauri::command]
+pub async fn engine_profile_switch(
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> Result<ConfigProfile, String> {
+    crate::engine::profiles::switch(&app_handle, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Turn safe mode on (disconnecting every bridge) or off.
+#[tauri::command]
+pub fn engine_safe_mode_set(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::engine::safe_mode::set(&app_handle, enabled).map_err(|e| e.to_string())
+}
+
 /// Add or update a single provider without replacing the entire config.
 #[tauri::command]
 pub fn engine_upsert_provider(
@@ -110,6 +136,7 @@ pub fn engine_upsert_provider(
     // Persist
     let json = serde_json::to_string(&*cfg).map_err(|e| format!("Serialize error: {}", e))?;
     state.store.set_config("engine_config", &json)?;
+    crate::engine::local_models::configure(&cfg);
 
     info!(
         "[engine] Provider upserted, {} total providers",
@@ -135,6 +162,7 @@ pub fn engine_remove_provider(
 
     let json = serde_json::to_string(&*cfg).map_err(|e| format!("Serialize error: {}", e))?;
     state.store.set_config("engine_config", &json)?;
+    crate::engine::local_models::configure(&cfg);
 
     info!(
         "[engine] Provider removed, {} remaining",
@@ -220,7 +248,11 @@ pub async fn engine_auto_setup(state: State<'_, EngineState>) -> Result<serde_js
         Ok(resp) if resp.status().is_success() => true,
         _ => {
             // Try to start Ollama if the binary exists
-            if let Ok(_child) = std::process::Command::new("ollama").arg("serve").spawn() {
+            let mut cmd = std::process::Command::new("ollama");
+            cmd.arg("serve");
+            crate::engine::memory::ollama::apply_keep_alive(&mut cmd);
+            if let Ok(child) = crate::engine::processes::bind_to_parent(&mut cmd).spawn() {
+                crate::engine::memory::ollama::register_spawned_ollama(child.id());
                 info!("[engine] Attempting to auto-start Ollama...");
                 // Wait for it to come up
                 let mut up = false;
@@ -328,6 +360,10 @@ pub async fn engine_auto_setup(state: State<'_, EngineState>) -> Result<serde_js
         api_key: String::new(),
         base_url: Some(base_url.to_string()),
         default_model: Some(model_name.clone()),
+        extra_body: Default::default(),
+        extra_headers: Default::default(),
+        responses_api: false,
+        responses_tools: Vec::new(),
     };
 
     {
@@ -338,6 +374,7 @@ pub async fn engine_auto_setup(state: State<'_, EngineState>) -> Result<serde_js
 
         let json = serde_json::to_string(&*cfg).map_err(|e| format!("Serialize: {}", e))?;
         state.store.set_config("engine_config", &json)?;
+        crate::engine::local_models::configure(&cfg);
     }
 
     info!(
@@ -449,3 +486,92 @@ fn walkdir_calc(path: &std::path::Path) -> u64 {
     }
     total
 }
+
+// ── Active-window awareness ────────────────────────────────────────────
+
+/// Turn active-window awareness on or off. Off clears the sample at once.
+#[tauri::command]
+pub fn engine_app_context_set(state: State<'_, EngineState>, enabled: bool) -> Result<(), String> {
+    let mut cfg = state.config.lock();
+    cfg.app_context = enabled;
+    let json = serde_json::to_string(&*cfg).map_err(|e| format!("Serialize error: {}", e))?;
+    state.store.set_config("engine_config", &json)?;
+    if !enabled {
+        state.active_window.lock().take();
+    }
+    info!(
+        "[app-context] Active-window awareness {}",
+        if enabled { "on" } else { "off" }
+    );
+    Ok(())
+}
+
+/// What agents currently see as the active window (None when off or stale).
+#[tauri::command]
+pub fn engine_app_context_get(
+    state: State<'_, EngineState>,
+) -> Result<Option<crate::engine::app_context::ActiveWindow>, String> {
+    Ok(crate::engine::app_context::current(&state))
+}
+
+// ── Home location ──────────────────────────────────────────────────────
+
+fn save_home_location(
+    state: &EngineState,
+    home: Option<HomeLocation>,
+) -> Result<Option<HomeLocation>, String> {
+    let mut cfg = state.config.lock();
+    cfg.home_location = home;
+    let json = serde_json::to_string(&*cfg).map_err(|e| format!("Serialize error: {}", e))?;
+    state.store.set_config("engine_config", &json)?;
+    Ok(cfg.home_location.clone())
+}
+
+/// Geocode a place name and store it as the home location.
+#[tauri::command]
+pub async fn engine_location_set(
+    state: State<'_, EngineState>,
+    query: String,
+) -> Result<Option<HomeLocation>, String> {
+    let client = crate::engine::location::client().map_err(|e| e.to_string())?;
+    let mut home = crate::engine::location::geocode(&client, query.trim())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(previous) = crate::engine::location::home(&state) {
+        home.share_with_agents = previous.share_with_agents;
+    }
+    info!("[location] Home location set to {}", home.display_name());
+    save_home_location(&state, Some(home))
+}
+
+/// Detect the home location from the public IP and store it.
+#[tauri::command]
+pub async fn engine_location_detect(
+    state: State<'_, EngineState>,
+) -> Result<Option<HomeLocation>, String> {
+    let client = crate::engine::location::client().map_err(|e| e.to_string())?;
+    let home = crate::engine::location::detect_by_ip(&client)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!("[location] Home location detected: {}", home.display_name());
+    save_home_location(&state, Some(home))
+}
+
+/// Forget the home location.
+#[tauri::command]
+pub fn engine_location_clear(state: State<'_, EngineState>) -> Result<(), String> {
+    save_home_location(&state, None).map(|_| ())
+}
+
+/// Whether agents see the home location in their runtime context.
+#[tauri::command]
+pub fn engine_location_share(
+    state: State<'_, EngineState>,
+    share: bool,
+) -> Result<Option<HomeLocation>, String> {
+    let home = crate::engine::location::home(&state).map(|h| HomeLocation {
+        share_with_agents: share,
+        ..h
+    });
+    save_home_location(&state, home)
+}
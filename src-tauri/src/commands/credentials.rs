@@ -0,0 +1,53 @@
+// commands/credentials.rs — Encrypted credential import/export.
+// Moves skill vault credentials, the Nostr key and channel bridge secrets
+// to another machine as a passphrase-protected bundle (Argon2id + AES-GCM).
+
+use crate::engine::credential_bundle::{self, CredentialItem, ImportReport};
+use crate::engine::state::EngineState;
+use tauri::State;
+
+/// List credentials available for export (ids and labels, no values).
+#[tauri::command]
+pub fn engine_credentials_list(
+    state: State<'_, EngineState>,
+) -> Result<Vec<CredentialItem>, String> {
+    credential_bundle::list_items(&state.store).map_err(|e| e.to_string())
+}
+
+/// Export the selected credentials (all when `items` is empty) as an
+/// encrypted bundle. Returns the bundle JSON for the caller to save.
+#[tauri::command]
+pub fn engine_credentials_export(
+    state: State<'_, EngineState>,
+    passphrase: String,
+    items: Option<Vec<String>>,
+) -> Result<String, String> {
+    credential_bundle::export(&state.store, &passphrase, &items.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Decrypt a bundle and list its contents so the user can pick what to import.
+#[tauri::command]
+pub fn engine_credentials_inspect(
+    bundle: String,
+    passphrase: String,
+) -> Result<Vec<CredentialItem>, String> {
+    credential_bundle::inspect(&bundle, &passphrase).map_err(|e| e.to_string())
+}
+
+/// Import the selected items (all when `items` is empty) from a bundle.
+#[tauri::command]
+pub fn engine_credentials_import(
+    state: State<'_, EngineState>,
+    bundle: String,
+    passphrase: String,
+    items: Option<Vec<String>>,
+) -> Result<ImportReport, String> {
+    credential_bundle::import(
+        &state.store,
+        &bundle,
+        &passphrase,
+        &items.unwrap_or_default(),
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,44 @@
+// commands/diagnostics.rs — Self-test and diagnostics bundle export.
+// Runs connectivity checks (providers, bridges, keychain, DB) and packages
+// the report with redacted config and logs into a zip for bug reports.
+// Also exposes engine database maintenance (integrity check, vacuum, FTS
+// rebuild, migration history).
+
+use crate::commands::state::EngineState;
+use crate::engine::diagnostics::{self, DiagnosticsExport, SelfTestReport};
+use crate::engine::sessions::maintenance::DbMaintenanceAction;
+use crate::engine::types::DbMaintenanceReport;
+use tauri::State;
+
+/// Run the self-test. `connectivity` adds live provider calls.
+#[tauri::command]
+pub async fn engine_diagnostics_self_test(
+    app_handle: tauri::AppHandle,
+    connectivity: Option<bool>,
+) -> Result<SelfTestReport, String> {
+    Ok(diagnostics::run_self_test(&app_handle, connectivity.unwrap_or(true)).await)
+}
+
+/// Run the full self-test and write a diagnostics zip. Returns its path.
+#[tauri::command]
+pub async fn engine_diagnostics_export(
+    app_handle: tauri::AppHandle,
+) -> Result<DiagnosticsExport, String> {
+    diagnostics::export_bundle(&app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run a database maintenance action: `integrity_check`, `incremental_vacuum`,
+/// `reindex_fts`, `migration_status` or `scheduled` (the periodic pass).
+#[tauri::command]
+pub async fn engine_db_maintenance(
+    state: State<'_, EngineState>,
+    action: String,
+) -> Result<DbMaintenanceReport, String> {
+    let action: DbMaintenanceAction = action.parse()?;
+    state
+        .store
+        .run_db_maintenance(action)
+        .map_err(|e| e.to_string())
+}
@@ -356,3 +356,18 @@ pub fn engine_compliance_export_to_file(
     std::fs::write(&path, &json).map_err(|e| format!("Failed to write file: {}", e))?;
     Ok(path)
 }
+
+/// Write the whole engine database (credentials excluded) to a portable
+/// archive. `path` defaults to a timestamped file under the data dir.
+#[tauri::command]
+pub async fn engine_export_all(
+    app_handle: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<crate::engine::db_archive::ArchiveExport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::engine::db_archive::export_all(&app_handle, path.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
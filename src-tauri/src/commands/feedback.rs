@@ -0,0 +1,78 @@
+// commands/feedback.rs — Run feedback
+//
+// Thumbs up / down on an agent run, with an optional comment. Ratings are
+// attributed to the run's model for the A/B routing stats and adjust the
+// agent's memory trust scores like `engine_message_feedback`; a thumbs-down
+// with a comment is also remembered (when memory auto-capture is on) so the
+// agent avoids the approach next time.
+
+use crate::commands::state::EngineState;
+use crate::engine::engram;
+use crate::engine::types::{ModelFeedbackStats, RunFeedback};
+use log::{info, warn};
+use tauri::State;
+
+#[tauri::command]
+pub async fn engine_feedback_submit(
+    state: State<'_, EngineState>,
+    run_id: String,
+    rating: i64,
+    comment: Option<String>,
+) -> Result<RunFeedback, String> {
+    if rating != 1 && rating != -1 {
+        return Err("Rating must be 1 (good) or -1 (bad)".into());
+    }
+    let comment = comment.unwrap_or_default().trim().to_string();
+    let feedback = state
+        .store
+        .feedback_submit(&run_id, rating, &comment)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(agent_id) = &feedback.agent_id {
+        let _ = state.store.update_trust_from_feedback(agent_id, rating > 0);
+    }
+
+    let remember = state.memory_config.lock().auto_capture;
+    if rating < 0 && !comment.is_empty() && remember {
+        let content = format!("User disliked this approach: {}", comment);
+        let emb_client = state.embedding_client();
+        match engram::bridge::store_auto_capture(
+            &state.store,
+            &content,
+            "preference",
+            emb_client.as_ref(),
+            feedback.agent_id.as_deref(),
+            feedback.session_id.as_deref(),
+            None,
+            None,
+            Some(&state.hnsw_index),
+        )
+        .await
+        {
+            Ok(Some(_)) => info!("[feedback] Remembered negative feedback on run {}", run_id),
+            Ok(None) => info!("[feedback] Feedback memory skipped (near-duplicate)"),
+            Err(e) => warn!("[feedback] Failed to store feedback memory: {}", e),
+        }
+    }
+    Ok(feedback)
+}
+
+#[tauri::command]
+pub fn engine_feedback_get(
+    state: State<'_, EngineState>,
+    run_id: String,
+) -> Result<Option<RunFeedback>, String> {
+    state.store.feedback_get(&run_id).map_err(|e| e.to_string())
+}
+
+/// Approval rate and cost per model over the last `days` days (default 30).
+#[tauri::command]
+pub fn engine_feedback_stats(
+    state: State<'_, EngineState>,
+    days: Option<u32>,
+) -> Result<Vec<ModelFeedbackStats>, String> {
+    state
+        .store
+        .feedback_model_stats(days.unwrap_or(30).clamp(1, 365))
+        .map_err(|e| e.to_string())
+}
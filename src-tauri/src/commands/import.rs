@@ -0,0 +1,79 @@
+// commands/import.rs — Import from Other Tools
+//
+// Thin wrappers over the engine importers:
+//   - engine::import          ChatGPT / Claude exports → sessions (+ memories)
+//   - engine::bookmark_import browser bookmarks / history → memory index
+//   - engine::ingest          files dropped into chat → memory / attachments
+//   - engine::db_archive      a full engine archive from another machine
+
+use crate::commands::state::EngineState;
+use crate::engine::types::{
+    BookmarkImportOptions, BookmarkImportReport, ImportOptions, ImportReport, IngestIntent,
+    IngestResult,
+};
+use tauri::State;
+
+/// Import a ChatGPT or Claude export (the downloaded zip or its
+/// `conversations.json`). `source` is `chatgpt`, `claude`, or omitted to
+/// detect it. Emits `import-progress` events while running.
+#[tauri::command]
+pub async fn engine_import_conversations(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    path: String,
+    source: Option<String>,
+    options: Option<ImportOptions>,
+) -> Result<ImportReport, String> {
+    crate::engine::import::import_conversations(
+        &app_handle,
+        &state,
+        std::path::Path::new(&path),
+        source.as_deref(),
+        &options.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Fetch and index Chrome / Firefox bookmarks (and optionally history) into
+/// memory. Emits `bookmark-import-progress` events while running.
+#[tauri::command]
+pub async fn engine_import_bookmarks(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    options: BookmarkImportOptions,
+) -> Result<BookmarkImportReport, String> {
+    crate::engine::bookmark_import::import_bookmarks(&app_handle, &state, &options).await
+}
+
+/// Ingest a file dropped into Paw, routed by type (document → memory,
+/// image → vision, CSV → workspace sheet, audio → transcript). Pass the
+/// returned handle in `ChatRequest::ingested` to use it in a turn.
+#[tauri::command]
+pub async fn engine_ingest_file(
+    state: State<'_, EngineState>,
+    path: String,
+    intent: Option<IngestIntent>,
+    agent_id: Option<String>,
+) -> Result<IngestResult, String> {
+    crate::engine::ingest::ingest_file(
+        &state,
+        std::path::Path::new(&path),
+        intent.unwrap_or_default(),
+        agent_id.as_deref().unwrap_or("default"),
+    )
+    .await
+}
+
+/// Restore an archive written by `engine_export_all` into this engine.
+#[tauri::command]
+pub async fn engine_import_all(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<crate::engine::db_archive::ArchiveImport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::engine::db_archive::import_all(&app_handle, &path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -223,8 +223,7 @@ pub struct CalendarEvent {
 pub async fn engine_calendar_events_today(
     app_handle: tauri::AppHandle,
 ) -> Result<Vec<CalendarEvent>, String> {
-    use crate::engine::key_vault;
-    use crate::engine::skills::crypto::{decrypt_credential, get_vault_key};
+    use crate::engine::oauth_tokens::{self, TokenError};
 
     // ── 1. Check if google-calendar is in the connected list ─────────
     let connected = load_connected_ids(&app_handle);
@@ -236,24 +235,14 @@ pub async fn engine_calendar_events_today(
     }
 
     // ── 2. Load the OAuth access token ───────────────────────────────
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {e}"))?;
-    let encrypted =
-        match key_vault::get("oauth:google-workspace").or_else(|| key_vault::get("oauth:google")) {
-            Some(v) => v,
-            None => {
-                info!("[calendar] No Google OAuth tokens found — skipping");
-                return Ok(vec![]);
-            }
-        };
-    let json =
-        decrypt_credential(&encrypted, &vault_key).map_err(|e| format!("Decrypt error: {e}"))?;
-
-    #[derive(Deserialize)]
-    struct Tokens {
-        access_token: String,
-    }
-    let tokens: Tokens =
-        serde_json::from_str(&json).map_err(|e| format!("Deserialize error: {e}"))?;
+    let access_token = match oauth_tokens::access_token("google-workspace").await {
+        Ok(t) => t,
+        Err(TokenError::NotConnected) => {
+            info!("[calendar] No Google OAuth tokens found — skipping");
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(format!("Google token error: {e}")),
+    };
 
     // ── 3. Build time range: today (local midnight → midnight) ───────
     let now = chrono::Utc::now();
@@ -281,7 +270,7 @@ pub async fn engine_calendar_events_today(
     let client = reqwest::Client::new();
     let resp = client
         .get(&url)
-        .bearer_auth(&tokens.access_token)
+        .bearer_auth(&access_token)
         .timeout(std::time::Duration::from_secs(10))
         .send()
         .await
@@ -1,9 +1,13 @@
-// commands/mail.rs — Himalaya email bridge commands + Gmail API bridge.
+// commands/mail.rs — Mail commands (native IMAP/SMTP, himalaya as fallback)
+// + Gmail API bridge.
 
+use crate::engine::email::OutgoingEmail;
+use crate::engine::mail_check::{self, MailAccountTest};
+use crate::engine::mail_client;
+use crate::engine::mail_oauth::{self, MailProvider};
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::process::Command;
 
 /// Set restrictive file permissions (owner-only read/write) on Unix.
 #[cfg(unix)]
@@ -22,6 +26,11 @@ fn set_owner_only_permissions(_path: &std::path::Path) -> Result<(), String> {
 /// Write (or merge) a Himalaya TOML config for an IMAP/SMTP email account.
 /// Password is stored in the OS keychain — the TOML contains only a keyring
 /// command reference so himalaya can look it up at runtime.
+///
+/// With `oauth_provider` ("google" / "microsoft") the account uses XOAUTH2
+/// instead: `mail_oauth_connect` must have stored its refresh token, and
+/// himalaya gets access tokens from the app (`--mail-access-token`).
+/// `password` is ignored then.
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub fn write_himalaya_config(
@@ -33,6 +42,7 @@ pub fn write_himalaya_config(
     smtp_host: String,
     smtp_port: u16,
     password: String,
+    oauth_provider: Option<String>,
 ) -> Result<bool, String> {
     let home = dirs::home_dir().ok_or("Cannot find home directory")?;
     let config_dir = home.join(".config/himalaya");
@@ -41,15 +51,36 @@ pub fn write_himalaya_config(
     fs::create_dir_all(&config_dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
 
     let keyring_service = format!("paw-mail-{}", account_name);
-    let entry = keyring::Entry::new(&keyring_service, &email)
-        .map_err(|e| format!("Keyring init failed: {}", e))?;
-    entry
-        .set_password(&password)
-        .map_err(|e| format!("Failed to store password in keychain: {}", e))?;
-    info!(
-        "Stored password for '{}' in OS keychain (service={})",
-        email, keyring_service
-    );
+    let auth = match oauth_provider.as_deref() {
+        Some(name) => {
+            let provider = MailProvider::parse(name)
+                .ok_or_else(|| format!("Unsupported OAuth mail provider '{}'", name))?;
+            mail_oauth::himalaya_auth(provider, &account_name).map_err(|e| e.to_string())?
+        }
+        None => {
+            let entry = keyring::Entry::new(&keyring_service, &email)
+                .map_err(|e| format!("Keyring init failed: {}", e))?;
+            entry
+                .set_password(&password)
+                .map_err(|e| format!("Failed to store password in keychain: {}", e))?;
+            info!(
+                "Stored password for '{}' in OS keychain (service={})",
+                email, keyring_service
+            );
+            format!(
+                r#"auth.type = "password"
+auth.cmd = "security find-generic-password -s '{service}' -a '{email}' -w 2>/dev/null || secret-tool lookup service '{service}' username '{email}' 2>/dev/null""#,
+                service = keyring_service,
+                email = email,
+            )
+        }
+    };
+    let prefixed = |prefix: &str| {
+        auth.lines()
+            .map(|line| format!("{}.{}", prefix, line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
 
     let display = display_name.unwrap_or_else(|| email.clone());
     let account_toml = format!(
@@ -62,16 +93,14 @@ backend.host = "{imap_host}"
 backend.port = {imap_port}
 backend.encryption = "tls"
 backend.login = "{email}"
-backend.auth.type = "password"
-backend.auth.cmd = "security find-generic-password -s '{service}' -a '{email}' -w 2>/dev/null || secret-tool lookup service '{service}' username '{email}' 2>/dev/null"
+{imap_auth}
 
 message.send.backend.type = "smtp"
 message.send.backend.host = "{smtp_host}"
 message.send.backend.port = {smtp_port}
 message.send.backend.encryption = "tls"
 message.send.backend.login = "{email}"
-message.send.backend.auth.type = "password"
-message.send.backend.auth.cmd = "security find-generic-password -s '{service}' -a '{email}' -w 2>/dev/null || secret-tool lookup service '{service}' username '{email}' 2>/dev/null"
+{smtp_auth}
 "#,
         name = account_name,
         email = email,
@@ -80,7 +109,8 @@ message.send.backend.auth.cmd = "security find-generic-password -s '{service}' -
         imap_port = imap_port,
         smtp_host = smtp_host,
         smtp_port = smtp_port,
-        service = keyring_service,
+        imap_auth = prefixed("backend"),
+        smtp_auth = prefixed("message.send.backend"),
     );
 
     if config_path.exists() {
@@ -121,12 +151,44 @@ message.send.backend.auth.cmd = "security find-generic-password -s '{service}' -
 
     set_owner_only_permissions(&config_path)?;
     info!(
-        "Wrote himalaya config for account '{}' at {:?} (mode 600, password in keychain)",
+        "Wrote himalaya config for account '{}' at {:?} (mode 600, credentials in keychain)",
         account_name, config_path
     );
     Ok(true)
 }
 
+/// Sign in to Gmail / Outlook for IMAP + SMTP (XOAUTH2) and store the
+/// refresh token in the OS keychain. Follow with `write_himalaya_config`
+/// passing the same `oauth_provider`.
+#[tauri::command]
+pub async fn mail_oauth_connect(
+    app_handle: tauri::AppHandle,
+    account_name: String,
+    email: String,
+    oauth_provider: String,
+) -> Result<(), String> {
+    let provider = MailProvider::parse(&oauth_provider)
+        .ok_or_else(|| format!("Unsupported OAuth mail provider '{}'", oauth_provider))?;
+    let tokens = crate::engine::oauth::start_oauth_flow_with_scopes(
+        provider.service_id(),
+        provider.scopes(),
+        &app_handle,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let refresh_token = tokens
+        .refresh_token
+        .ok_or("The provider returned no refresh token — please try again")?;
+    mail_oauth::store_refresh_token(&account_name, &email, &refresh_token)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "Stored {} OAuth refresh token for '{}' in OS keychain",
+        provider.service_id(),
+        email
+    );
+    Ok(())
+}
+
 /// Read the current Himalaya config. Auth command lines are redacted.
 #[tauri::command]
 pub fn read_himalaya_config() -> Result<String, String> {
@@ -236,175 +298,122 @@ pub fn remove_himalaya_account(account_name: String) -> Result<bool, String> {
     }
 }
 
-/// Fetch emails from an IMAP account via himalaya CLI.
+/// Check an account's IMAP and SMTP settings stage by stage (DNS, TCP,
+/// TLS, login, folder list) without sending anything.
 #[tauri::command]
-pub fn fetch_emails(
+pub async fn test_mail_account(account_name: String) -> Result<MailAccountTest, String> {
+    mail_check::test_account(&account_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fetch the newest envelopes of a folder as JSON.
+#[tauri::command]
+pub async fn fetch_emails(
     account: Option<String>,
     folder: Option<String>,
     page_size: Option<u32>,
 ) -> Result<String, String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("envelope").arg("list");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    if let Some(f) = folder {
-        cmd.arg("--folder").arg(f);
-    }
-    cmd.arg("--page-size")
-        .arg(page_size.unwrap_or(50).to_string());
-    cmd.arg("--output").arg("json");
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    mail_client::list_envelopes(
+        account.as_deref(),
+        folder.as_deref(),
+        page_size.unwrap_or(50),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
-/// Fetch a single email's full content via himalaya CLI.
+/// Fetch a single email's full content.
 #[tauri::command]
-pub fn fetch_email_content(
+pub async fn fetch_email_content(
     account: Option<String>,
     folder: Option<String>,
     id: String,
 ) -> Result<String, String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("message").arg("read");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    if let Some(f) = folder {
-        cmd.arg("--folder").arg(f);
-    }
-    cmd.arg(&id);
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    mail_client::read_message(account.as_deref(), folder.as_deref(), &id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Send an email via himalaya CLI.
+/// Send an email.
+///
+/// `html` adds an HTML alternative to the plain-text body, `attachments`
+/// are absolute file paths, and `reply_to_id` (an envelope id in `folder`)
+/// threads the message as a reply to it.
 #[tauri::command]
-pub fn send_email(
+#[allow(clippy::too_many_arguments)]
+pub async fn send_email(
     account: Option<String>,
     to: String,
     subject: String,
     body: String,
+    html: Option<String>,
+    cc: Option<String>,
+    bcc: Option<String>,
+    attachments: Option<Vec<String>>,
+    reply_to_id: Option<String>,
+    folder: Option<String>,
 ) -> Result<(), String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("template").arg("send");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    let email_template = format!("To: {}\nSubject: {}\n\n{}", to, subject, body);
-    cmd.stdin(std::process::Stdio::piped());
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn himalaya: {}", e))?;
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        stdin
-            .write_all(email_template.as_bytes())
-            .map_err(|e| format!("Failed to write: {}", e))?;
-    }
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("Failed to wait: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if !stderr.contains("Folder doesn't exist") {
-            return Err(format!("himalaya failed: {}", stderr));
-        }
-    }
-    Ok(())
+    let email = OutgoingEmail {
+        to,
+        cc: cc.unwrap_or_default(),
+        bcc: bcc.unwrap_or_default(),
+        subject,
+        text: body,
+        html: html.filter(|h| !h.trim().is_empty()),
+        attachments: attachments
+            .unwrap_or_default()
+            .into_iter()
+            .map(std::path::PathBuf::from)
+            .collect(),
+        ..Default::default()
+    };
+    email.check_attachments().map_err(|e| e.to_string())?;
+    mail_client::send(
+        account.as_deref(),
+        folder.as_deref(),
+        email,
+        reply_to_id.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
-/// List mail folders via himalaya CLI.
+/// List mail folders as JSON.
 #[tauri::command]
-pub fn list_mail_folders(account: Option<String>) -> Result<String, String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("folder").arg("list");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    cmd.arg("--output").arg("json");
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+pub async fn list_mail_folders(account: Option<String>) -> Result<String, String> {
+    mail_client::list_folders(account.as_deref())
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Move an email to a folder via himalaya CLI.
+/// Move an INBOX email to a folder.
 #[tauri::command]
-pub fn move_email(account: Option<String>, id: String, folder: String) -> Result<(), String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("message").arg("move");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    cmd.arg(&id).arg(&folder);
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(())
+pub async fn move_email(account: Option<String>, id: String, folder: String) -> Result<(), String> {
+    mail_client::move_message(account.as_deref(), None, &id, &folder)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Delete an email via himalaya CLI.
+/// Delete an INBOX email (to the trash folder when there is one).
 #[tauri::command]
-pub fn delete_email(account: Option<String>, id: String) -> Result<(), String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("message").arg("delete");
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    cmd.arg(&id);
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(())
+pub async fn delete_email(account: Option<String>, id: String) -> Result<(), String> {
+    mail_client::delete_message(account.as_deref(), None, &id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Set/remove a flag on an email via himalaya CLI.
+/// Set/remove a flag on an INBOX email.
 #[tauri::command]
-pub fn set_email_flag(
+pub async fn set_email_flag(
     account: Option<String>,
     id: String,
     flag: String,
     add: bool,
 ) -> Result<(), String> {
-    let mut cmd = Command::new("himalaya");
-    cmd.arg("flag").arg(if add { "add" } else { "remove" });
-    if let Some(acct) = account {
-        cmd.arg("--account").arg(acct);
-    }
-    cmd.arg(&id).arg("--flag").arg(&flag);
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("himalaya failed: {}", stderr));
-    }
-    Ok(())
+    mail_client::set_flag(account.as_deref(), None, &id, &flag, add)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // ── Gmail API Inbox ────────────────────────────────────────────────────
@@ -425,28 +434,17 @@ pub struct GmailMessage {
 /// Returns an empty list if Gmail/Google is not connected.
 #[tauri::command]
 pub async fn engine_gmail_inbox(page_size: Option<u32>) -> Result<Vec<GmailMessage>, String> {
-    use crate::engine::key_vault;
-    use crate::engine::skills::crypto::{decrypt_credential, get_vault_key};
+    use crate::engine::oauth_tokens::{self, TokenError};
 
     // ── 1. Load the OAuth access token ───────────────────────────────
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {e}"))?;
-    let encrypted =
-        match key_vault::get("oauth:google-workspace").or_else(|| key_vault::get("oauth:google")) {
-            Some(v) => v,
-            None => {
-                info!("[gmail] No Google OAuth tokens found");
-                return Ok(vec![]);
-            }
-        };
-    let json =
-        decrypt_credential(&encrypted, &vault_key).map_err(|e| format!("Decrypt error: {e}"))?;
-
-    #[derive(Deserialize)]
-    struct Tokens {
-        access_token: String,
-    }
-    let tokens: Tokens =
-        serde_json::from_str(&json).map_err(|e| format!("Deserialize error: {e}"))?;
+    let access_token = match oauth_tokens::access_token("google-workspace").await {
+        Ok(t) => t,
+        Err(TokenError::NotConnected) => {
+            info!("[gmail] No Google OAuth tokens found");
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(format!("Google token error: {e}")),
+    };
 
     // ── 2. List messages from inbox ──────────────────────────────────
     let max = page_size.unwrap_or(20).min(50);
@@ -458,7 +456,7 @@ pub async fn engine_gmail_inbox(page_size: Option<u32>) -> Result<Vec<GmailMessa
     let client = reqwest::Client::new();
     let list_resp = client
         .get(&list_url)
-        .bearer_auth(&tokens.access_token)
+        .bearer_auth(&access_token)
         .timeout(std::time::Duration::from_secs(10))
         .send()
         .await
@@ -506,7 +504,7 @@ pub async fn engine_gmail_inbox(page_size: Option<u32>) -> Result<Vec<GmailMessa
             );
             client
                 .get(&url)
-                .bearer_auth(&tokens.access_token)
+                .bearer_auth(&access_token)
                 .timeout(std::time::Duration::from_secs(10))
                 .send()
         })
@@ -96,6 +96,68 @@ pub async fn engine_memory_search(
         .collect())
 }
 
+/// Run the hybrid (BM25 + vector) search and report each candidate's
+/// per-stage scores, using the current `memory_config.search` tuning.
+#[tauri::command]
+pub async fn engine_memory_search_debug(
+    state: State<'_, EngineState>,
+    query: String,
+    limit: Option<usize>,
+    agent_id: Option<String>,
+) -> Result<MemorySearchDebug, String> {
+    let (thresh, tuning) = {
+        let cfg = state.memory_config.lock();
+        (cfg.recall_threshold, cfg.search.clone())
+    };
+    let emb_client = state.embedding_client();
+    memory::search_memories_debug(
+        &state.store,
+        &query,
+        limit.unwrap_or(10),
+        thresh,
+        emb_client.as_ref(),
+        agent_id.as_deref(),
+        &tuning,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Memories and conversation messages from a time window, oldest first.
+/// `when` is a phrase like "last tuesday" read in the user's timezone;
+/// `start`/`end` (RFC 3339) take precedence. Without `agent_id` all
+/// agents' memories and sessions are included.
+#[tauri::command]
+pub fn engine_memory_timeline(
+    state: State<'_, EngineState>,
+    when: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    agent_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<crate::atoms::engram_types::TimelineEntry>, String> {
+    let tz = state.config.lock().timezone();
+    let query = crate::engine::tools::memory::resolve_temporal_query(
+        when.as_deref(),
+        start.as_deref(),
+        end.as_deref(),
+        tz,
+    )
+    .map_err(|e| e.to_string())?;
+    let scope = match agent_id.as_deref() {
+        Some(aid) => crate::atoms::engram_types::MemoryScope::agent(aid),
+        None => crate::atoms::engram_types::MemoryScope::global(),
+    };
+    engram::timeline(
+        &state.store,
+        &query,
+        &scope,
+        agent_id.as_deref(),
+        limit.unwrap_or(50),
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn engine_memory_stats(state: State<'_, EngineState>) -> Result<MemoryStats, String> {
     state.store.memory_stats().map_err(|e| e.to_string())
@@ -240,6 +302,61 @@ pub fn engine_memory_edges(
         .map_err(|e| e.to_string())
 }
 
+/// Export the knowledge graph (memories, entities, relations) as `json`
+/// or `graphml`, optionally limited to one agent. Returns the document text.
+#[tauri::command]
+pub fn engine_memory_graph_export(
+    state: State<'_, EngineState>,
+    format: String,
+    agent_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    let format: engram::graph_export::GraphFormat = format.parse()?;
+    let graph = engram::graph_export::export_graph(
+        &state.store,
+        agent_id.as_deref(),
+        limit.unwrap_or(5000),
+    )?;
+    engram::graph_export::render(&graph, format).map_err(|e| e.to_string())
+}
+
+/// Memories matching `filter` as JSONL (one memory per line, content
+/// decrypted), for migration or curating a fact list.
+#[tauri::command]
+pub fn engine_memory_export(
+    state: State<'_, EngineState>,
+    filter: Option<engram::jsonl_io::JsonlExportFilter>,
+) -> Result<String, String> {
+    engram::jsonl_io::export_jsonl(&state.store, &filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Store every memory in the JSONL file at `path`, embedding lines without
+/// a current-model embedding in batches. `agent_id` overrides the file's
+/// agents; with `dedup`, near-duplicates of stored memories are skipped.
+#[tauri::command]
+pub async fn engine_memory_import(
+    state: State<'_, EngineState>,
+    path: String,
+    dedup: Option<bool>,
+    agent_id: Option<String>,
+) -> Result<engram::jsonl_io::JsonlImportReport, String> {
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let emb_client = state.embedding_client();
+    engram::jsonl_io::import_jsonl(
+        &state.store,
+        &text,
+        agent_id.as_deref(),
+        dedup.unwrap_or(true),
+        emb_client.as_ref(),
+        Some(&state.hnsw_index),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 // ── Memory config ──────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -454,6 +571,53 @@ pub fn engine_memory_purge_user(
     }))
 }
 
+/// Right-to-forget by filter: find memories matching `filter` and, unless
+/// `dry_run`, securely erase them along with their FTS rows, graph edges,
+/// entity references and derived triples.
+#[tauri::command]
+pub fn engine_memory_forget_matching(
+    state: State<'_, EngineState>,
+    filter: engram::retention::ForgetFilter,
+    dry_run: bool,
+) -> Result<serde_json::Value, String> {
+    let matched = engram::retention::find_matching(&state.store, &filter)?;
+    let report = if dry_run {
+        None
+    } else {
+        let ids: Vec<String> = matched.iter().map(|c| c.id.clone()).collect();
+        Some(engram::retention::forget(
+            &state.store,
+            &ids,
+            "forget_matching",
+            Some(&state.hnsw_index),
+        )?)
+    };
+    Ok(serde_json::json!({ "matched": matched, "report": report }))
+}
+
+/// Apply the configured retention rules now (the maintenance loop also runs
+/// them every few minutes). With `dry_run`, only lists what would go.
+#[tauri::command]
+pub fn engine_memory_retention_run(
+    state: State<'_, EngineState>,
+    dry_run: bool,
+) -> Result<serde_json::Value, String> {
+    let rules = state.memory_config.lock().retention.clone();
+    let matched = engram::retention::retention_candidates(&state.store, &rules)?;
+    let report = if dry_run {
+        None
+    } else {
+        let ids: Vec<String> = matched.iter().map(|c| c.id.clone()).collect();
+        Some(engram::retention::forget(
+            &state.store,
+            &ids,
+            "retention_policy",
+            Some(&state.hnsw_index),
+        )?)
+    };
+    Ok(serde_json::json!({ "matched": matched, "report": report }))
+}
+
 // ── Message Feedback (RLHF) ───────────────────────────────────────────
 
 /// Record user feedback (thumbs up/down) on an assistant message.
@@ -12,23 +12,35 @@ pub mod browser;
 pub mod canvas;
 pub mod channels;
 pub mod chat;
+pub mod compare;
+pub mod confidence;
 pub mod config;
+pub mod credentials;
 pub mod dashboard_tabs;
 pub mod dashboards;
+pub mod diagnostics;
 pub mod export;
+pub mod feedback;
 pub mod flows;
 pub mod forge;
 pub mod guardrails;
 pub mod health_monitor;
+pub mod import;
 pub mod integrations;
 pub mod mail;
 pub mod mcp;
 pub mod memory;
 pub mod n8n;
+pub mod news;
 pub mod oauth;
 pub mod ollama;
+pub mod outbox;
+pub mod processes;
 pub mod project;
+pub mod prompts;
 pub mod queries;
+pub mod research;
+pub mod search;
 pub mod skill_wizard;
 pub mod skills;
 pub mod squad;
@@ -37,7 +49,10 @@ pub mod tailscale;
 pub mod task;
 pub mod telemetry;
 pub mod tool_bridge;
+pub mod tool_rag;
 pub mod trade;
+pub mod translate;
 pub mod tts;
 pub mod utility;
 pub mod webhook;
+pub mod worker_delegation;
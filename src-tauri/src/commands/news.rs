@@ -0,0 +1,116 @@
+// commands/news.rs — News watches
+//
+// Create, edit, delete and run news watches and read their digest history
+// (see engine::news). Scheduled runs happen on the cron heartbeat.
+
+use crate::commands::state::EngineState;
+use crate::engine::news;
+use crate::engine::types::{NewsDigest, NewsWatch};
+use log::info;
+use tauri::State;
+
+fn load_watch(state: &EngineState, id: &str) -> Result<NewsWatch, String> {
+    state
+        .store
+        .news_watch_get(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("News watch {} not found", id))
+}
+
+#[tauri::command]
+pub fn engine_news_list(state: State<'_, EngineState>) -> Result<Vec<NewsWatch>, String> {
+    state.store.news_watch_list().map_err(|e| e.to_string())
+}
+
+/// Create (empty `id`) or update a watch. The next run is rescheduled from
+/// now whenever the schedule changes or the watch is (re)enabled.
+#[tauri::command]
+pub fn engine_news_save(
+    state: State<'_, EngineState>,
+    mut watch: NewsWatch,
+) -> Result<NewsWatch, String> {
+    watch.name = watch.name.trim().to_string();
+    watch.queries = watch
+        .queries
+        .iter()
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty())
+        .collect();
+    if watch.name.is_empty() {
+        return Err("A news watch needs a name".into());
+    }
+    if watch.queries.is_empty() {
+        return Err("A news watch needs at least one search query".into());
+    }
+    if !news::DELIVERY_TARGETS.contains(&watch.deliver_to.as_str()) {
+        return Err(format!(
+            "Unknown delivery target '{}' (use telegram, discord, webhook or leave empty)",
+            watch.deliver_to
+        ));
+    }
+    let next = news::next_run(&state, &watch.schedule).ok_or_else(|| {
+        format!(
+            "Invalid schedule '{}' — use e.g. 'every 6h' or 'daily 08:00'",
+            watch.schedule
+        )
+    })?;
+
+    let previous = if watch.id.is_empty() {
+        None
+    } else {
+        Some(load_watch(&state, &watch.id)?)
+    };
+    watch.next_run_at = match previous {
+        Some(p) if p.enabled && p.schedule == watch.schedule && p.next_run_at.is_some() => {
+            p.next_run_at
+        }
+        _ => Some(next),
+    };
+
+    let id = state
+        .store
+        .news_watch_save(&watch)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "[news] Saved watch '{}' ({} queries, {})",
+        watch.name,
+        watch.queries.len(),
+        watch.schedule
+    );
+    load_watch(&state, &id)
+}
+
+#[tauri::command]
+pub fn engine_news_delete(state: State<'_, EngineState>, id: String) -> Result<(), String> {
+    state
+        .store
+        .news_watch_delete(&id)
+        .map_err(|e| e.to_string())
+}
+
+/// Run a watch now, outside its schedule. Returns `None` when nothing new
+/// was found.
+#[tauri::command]
+pub async fn engine_news_run(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    id: String,
+) -> Result<Option<NewsDigest>, String> {
+    let watch = load_watch(&state, &id)?;
+    news::run_watch(&app_handle, &watch)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recent digests, newest first, optionally for one watch.
+#[tauri::command]
+pub fn engine_news_digests(
+    state: State<'_, EngineState>,
+    watch_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<NewsDigest>, String> {
+    state
+        .store
+        .news_digest_list(watch_id.as_deref(), limit.unwrap_or(20).min(200))
+        .map_err(|e| e.to_string())
+}
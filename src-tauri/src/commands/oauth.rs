@@ -10,17 +10,13 @@
 //   Tier 5 — Manual API keys (existing flow)
 
 use crate::commands::n8n::{get_n8n_endpoint, map_integration_to_skill};
-use crate::engine::key_vault;
 use crate::engine::oauth::{
     get_n8n_oauth_type, get_oauth_config, get_rfc7591_config, n8n_credential_url,
-    n8n_oauth_service_ids, oauth_service_ids, refresh_access_token, resolve_tier,
-    rfc7591_service_ids, start_oauth_flow, start_rfc7591_flow, tier_label, OAuthResult, OAuthTier,
-    OAuthTokens,
-};
-use crate::engine::skills::{
-    self,
-    crypto::{decrypt_credential, encrypt_credential, get_vault_key},
+    n8n_oauth_service_ids, oauth_service_ids, resolve_tier, rfc7591_service_ids, start_oauth_flow,
+    start_rfc7591_flow, tier_label, OAuthResult, OAuthTier, OAuthTokens,
 };
+use crate::engine::oauth_tokens::{self, TokenError};
+use crate::engine::skills;
 use crate::engine::state::EngineState;
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
@@ -119,7 +115,7 @@ pub async fn engine_oauth_start(
     };
 
     // Store tokens encrypted in the skill vault
-    if let Err(e) = store_oauth_tokens(&service_id, &tokens) {
+    if let Err(e) = oauth_tokens::store(&service_id, &tokens) {
         error!(
             "[oauth-cmd] Failed to store tokens for '{}': {}",
             service_id, e
@@ -172,71 +168,33 @@ pub async fn engine_oauth_start(
     })
 }
 
-/// Refresh an expired OAuth token for a service.
+/// Refresh an OAuth token for a service now, regardless of expiry.
 #[tauri::command]
 pub async fn engine_oauth_refresh(service_id: String) -> Result<OAuthResult, String> {
     info!("[oauth-cmd] Refreshing token for '{}'", service_id);
 
-    // Load existing tokens
-    let existing = match load_oauth_tokens(&service_id) {
-        Ok(Some(t)) => t,
-        Ok(None) => {
-            return Ok(OAuthResult {
-                service_id,
-                success: false,
-                scopes_granted: vec![],
-                error: Some("No OAuth tokens found — connect the service first".to_string()),
-            });
-        }
-        Err(e) => {
-            return Ok(OAuthResult {
-                service_id,
-                success: false,
-                scopes_granted: vec![],
-                error: Some(format!("Failed to load tokens: {}", e)),
-            });
-        }
-    };
-
-    let refresh_token = match &existing.refresh_token {
-        Some(rt) => rt.clone(),
-        None => {
-            return Ok(OAuthResult {
-                service_id,
-                success: false,
-                scopes_granted: vec![],
-                error: Some("No refresh token available — reconnect the service".to_string()),
-            });
-        }
-    };
-
-    // Refresh
-    let new_tokens = match refresh_access_token(&service_id, &refresh_token).await {
+    let new_tokens = match oauth_tokens::force_refresh(&service_id).await {
         Ok(t) => t,
         Err(e) => {
             warn!(
                 "[oauth-cmd] Token refresh failed for '{}': {}",
                 service_id, e
             );
+            let error = match e {
+                TokenError::NotConnected => {
+                    "No OAuth tokens found — connect the service first".to_string()
+                }
+                other => other.to_string(),
+            };
             return Ok(OAuthResult {
                 service_id,
                 success: false,
                 scopes_granted: vec![],
-                error: Some(e.to_string()),
+                error: Some(error),
             });
         }
     };
 
-    // Store updated tokens
-    if let Err(e) = store_oauth_tokens(&service_id, &new_tokens) {
-        return Ok(OAuthResult {
-            service_id,
-            success: false,
-            scopes_granted: vec![],
-            error: Some(format!("Failed to store refreshed tokens: {}", e)),
-        });
-    }
-
     let scopes_granted = new_tokens
         .scope
         .as_deref()
@@ -261,7 +219,7 @@ pub async fn engine_oauth_refresh(service_id: String) -> Result<OAuthResult, Str
 /// showing a misleading "connected" state for broken tokens.
 #[tauri::command]
 pub async fn engine_oauth_status(service_id: String) -> Result<OAuthTokenStatus, String> {
-    let tokens = match load_oauth_tokens(&service_id) {
+    let tokens = match oauth_tokens::load(&service_id) {
         Ok(Some(t)) => t,
         Ok(None) => {
             return Ok(OAuthTokenStatus {
@@ -282,8 +240,7 @@ pub async fn engine_oauth_status(service_id: String) -> Result<OAuthTokenStatus,
                 service_id, e
             );
             // Clean up the broken vault entry so a fresh connect works
-            let vault_purpose = format!("oauth:{}", service_id);
-            key_vault::remove(&vault_purpose);
+            oauth_tokens::remove(&service_id);
             return Ok(OAuthTokenStatus {
                 service_id,
                 connected: false,
@@ -360,8 +317,7 @@ pub async fn engine_oauth_revoke(service_id: String) -> Result<(), String> {
     info!("[oauth-cmd] Revoking OAuth tokens for '{}'", service_id);
 
     // Delete from unified vault
-    let vault_purpose = format!("oauth:{}", service_id);
-    key_vault::remove(&vault_purpose);
+    oauth_tokens::remove(&service_id);
 
     // Also remove legacy key variants (e.g. google ↔ google-workspace)
     let legacy_keys: &[(&str, &str)] = &[
@@ -370,8 +326,7 @@ pub async fn engine_oauth_revoke(service_id: String) -> Result<(), String> {
     ];
     for (from, to) in legacy_keys {
         if service_id == *from {
-            let alt = format!("oauth:{}", to);
-            key_vault::remove(&alt);
+            oauth_tokens::remove(to);
         }
     }
 
@@ -478,7 +433,7 @@ pub async fn engine_oauth_rfc7591_start(
     };
 
     // Store tokens
-    if let Err(e) = store_oauth_tokens(&service_id, &tokens) {
+    if let Err(e) = oauth_tokens::store(&service_id, &tokens) {
         error!(
             "[oauth-cmd] Failed to store RFC 7591 tokens for '{}': {}",
             service_id, e
@@ -513,78 +468,11 @@ pub async fn engine_oauth_rfc7591_start(
 }
 
 /// Get the access token for a service (used internally by tool execution).
-/// Automatically refreshes if expired.
+/// Refreshes ahead of expiry via the shared token manager.
 pub async fn get_oauth_access_token(service_id: &str) -> Result<String, String> {
-    let tokens = load_oauth_tokens(service_id)
-        .map_err(|e| format!("Failed to load OAuth tokens: {}", e))?
-        .ok_or_else(|| format!("No OAuth tokens for service '{}'", service_id))?;
-
-    // Check if token needs refresh
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    if let Some(expires_at) = tokens.expires_at {
-        // Refresh 5 minutes before actual expiry
-        if now >= expires_at.saturating_sub(300) {
-            if let Some(ref refresh_token) = tokens.refresh_token {
-                info!("[oauth] Auto-refreshing expired token for '{}'", service_id);
-                match refresh_access_token(service_id, refresh_token).await {
-                    Ok(new_tokens) => {
-                        let _ = store_oauth_tokens(service_id, &new_tokens);
-                        return Ok(new_tokens.access_token);
-                    }
-                    Err(e) => {
-                        warn!(
-                            "[oauth] Auto-refresh failed for '{}': {} — using existing token",
-                            service_id, e
-                        );
-                        // Fall through to return existing token
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(tokens.access_token)
-}
-
-// ── Vault Storage ────────────────────────────────────────────────
-
-/// Store OAuth tokens encrypted in the unified key vault.
-fn store_oauth_tokens(service_id: &str, tokens: &OAuthTokens) -> Result<(), String> {
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {}", e))?;
-    let json = serde_json::to_string(tokens).map_err(|e| format!("Serialize error: {}", e))?;
-    let encrypted =
-        encrypt_credential(&json, &vault_key).map_err(|e| format!("Encryption error: {}", e))?;
-
-    let vault_purpose = format!("oauth:{}", service_id);
-    key_vault::set(&vault_purpose, &encrypted);
-
-    info!(
-        "[oauth] Stored encrypted OAuth tokens for '{}' in unified vault",
-        service_id
-    );
-    Ok(())
-}
-
-/// Load OAuth tokens from the unified key vault.
-fn load_oauth_tokens(service_id: &str) -> Result<Option<OAuthTokens>, String> {
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {}", e))?;
-
-    let vault_purpose = format!("oauth:{}", service_id);
-
-    match key_vault::get(&vault_purpose) {
-        Some(encrypted) => {
-            let json = decrypt_credential(&encrypted, &vault_key)
-                .map_err(|e| format!("Decrypt error: {}", e))?;
-            let tokens: OAuthTokens =
-                serde_json::from_str(&json).map_err(|e| format!("Deserialize error: {}", e))?;
-            Ok(Some(tokens))
-        }
-        None => Ok(None),
-    }
+    oauth_tokens::access_token(service_id)
+        .await
+        .map_err(|e| format!("OAuth token for '{}': {}", service_id, e))
 }
 
 // ── Frontend Types ───────────────────────────────────────────────
@@ -942,10 +830,13 @@ async fn find_n8n_credential(
 ///
 /// Designed to be spawned once at app startup via `tauri::async_runtime::spawn`.
 /// Runs every 15 minutes, staggered 20 seconds after launch to avoid
-/// competing with other startup tasks.
+/// competing with other startup tasks. Refreshes go through the shared
+/// token manager, so they never race a tool's on-demand refresh.
 pub async fn oauth_token_refresh_loop(app_handle: tauri::AppHandle) {
     use crate::engine::oauth::oauth_service_ids;
 
+    oauth_tokens::init(&app_handle);
+
     // Initial delay — let the app stabilize
     tokio::time::sleep(std::time::Duration::from_secs(20)).await;
     log::info!("[oauth-refresh] Background token refresh started (900s interval)");
@@ -955,80 +846,29 @@ pub async fn oauth_token_refresh_loop(app_handle: tauri::AppHandle) {
         let mut failed = 0u32;
         let mut skipped = 0u32;
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
         for service_id in oauth_service_ids() {
-            // Try to load tokens — skip if not stored
-            let tokens = match load_oauth_tokens(service_id) {
+            // Skip services with no stored tokens
+            let before = match oauth_tokens::load(service_id) {
                 Ok(Some(t)) => t,
-                Ok(None) => continue, // No tokens for this service
-                Err(e) => {
-                    // Tokens exist but can't be decrypted — clean them up
-                    log::warn!(
-                        "[oauth-refresh] Tokens for '{}' are corrupt/undecryptable: {} — removing",
-                        service_id,
-                        e
-                    );
-                    let vault_purpose = format!("oauth:{}", service_id);
-                    key_vault::remove(&vault_purpose);
+                Ok(None) => continue,
+                Err(_) => {
+                    // Corrupt — refresh_if_needed removes it below
                     failed += 1;
+                    let _ = oauth_tokens::refresh_if_needed(service_id, 600).await;
                     continue;
                 }
             };
 
-            // Check if refresh is needed (< 10 minutes to expiry)
-            let needs_refresh = match tokens.expires_at {
-                Some(exp) => now >= exp.saturating_sub(600), // 10 min buffer
-                None => false,                               // No expiry → never-expiring token
-            };
-
-            if !needs_refresh {
-                skipped += 1;
-                continue;
-            }
-
-            // Need a refresh token to refresh
-            let refresh_token = match &tokens.refresh_token {
-                Some(rt) => rt.clone(),
-                None => {
-                    log::debug!(
-                        "[oauth-refresh] Token for '{}' expiring but no refresh_token",
-                        service_id
-                    );
-                    skipped += 1;
-                    continue;
-                }
-            };
-
-            // Attempt refresh
-            match refresh_access_token(service_id, &refresh_token).await {
-                Ok(new_tokens) => {
-                    // Store refreshed tokens
-                    if let Err(e) = store_oauth_tokens(service_id, &new_tokens) {
-                        log::warn!(
-                            "[oauth-refresh] Refreshed '{}' but storage failed: {}",
-                            service_id,
-                            e
-                        );
-                        failed += 1;
-                        continue;
-                    }
-
+            match oauth_tokens::refresh_if_needed(service_id, 600).await {
+                Ok(tokens) if tokens.access_token != before.access_token => {
                     // Re-provision to skill vault with new access token
-                    provision_oauth_to_skill_vault(
-                        service_id,
-                        &new_tokens.access_token,
-                        &app_handle,
-                    );
+                    provision_oauth_to_skill_vault(service_id, &tokens.access_token, &app_handle);
 
                     // Update n8n credential with refreshed tokens
                     // (no workflow deploy needed — already exists)
                     {
                         let sid = service_id.to_string();
-                        let tok = new_tokens.clone();
+                        let tok = tokens.clone();
                         let app = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
                             provision_oauth_to_n8n(&sid, &tok, &app).await;
@@ -1036,12 +876,9 @@ pub async fn oauth_token_refresh_loop(app_handle: tauri::AppHandle) {
                     }
 
                     refreshed += 1;
-                    log::info!(
-                        "[oauth-refresh] Refreshed token for '{}' (expires in {}s)",
-                        service_id,
-                        new_tokens.expires_at.unwrap_or(0).saturating_sub(now)
-                    );
+                    log::info!("[oauth-refresh] Refreshed token for '{}'", service_id);
                 }
+                Ok(_) => skipped += 1,
                 Err(e) => {
                     log::warn!("[oauth-refresh] Failed to refresh '{}': {}", service_id, e);
                     failed += 1;
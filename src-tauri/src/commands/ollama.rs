@@ -1,8 +1,9 @@
 // commands/ollama.rs — Tauri IPC commands for Ollama model management
 //
-// Provides commands for listing, pulling, and creating Ollama models.
-// Used by the Zero-Gap auto-setup flow to create the worker-qwen model.
+// Provides commands for listing, pulling, creating and warming up Ollama
+// models. Used by the Zero-Gap auto-setup flow to create the worker-qwen model.
 
+use crate::engine::local_models::{self, ModelWarmUp};
 use log::info;
 use serde::{Deserialize, Serialize};
 
@@ -194,6 +195,19 @@ pub async fn engine_ollama_create_model(
     })
 }
 
+/// Load the model a chat session will use into memory ahead of the first
+/// message. Returns `None` when it isn't a local model (or warm-up is off).
+#[tauri::command]
+pub async fn engine_local_model_warm(
+    state: tauri::State<'_, crate::engine::state::EngineState>,
+    session_id: Option<String>,
+    model: Option<String>,
+) -> Result<Option<ModelWarmUp>, String> {
+    local_models::warm_for_session(&state, session_id.as_deref(), model.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Set up the worker-qwen model for the Architect/Worker pattern.
 ///
 /// This is the "one-click" setup that:
@@ -0,0 +1,152 @@
+// commands/outbox.rs — Outbox review
+//
+// List, edit, approve and reject outbound messages that agents drafted
+// while the outbox policy was on (see engine::outbox). Approving an item
+// runs the original tool call with its (possibly edited) arguments.
+
+use crate::commands::state::EngineState;
+use crate::engine::outbox::{self, OutboxSettings};
+use crate::engine::types::{FunctionCall, OutboxItem, ToolCall};
+use log::info;
+use tauri::{Emitter, State};
+
+fn emit_updated(app_handle: &tauri::AppHandle, item: &OutboxItem) {
+    let _ = app_handle.emit(
+        "outbox-updated",
+        serde_json::json!({ "id": item.id, "status": item.status, "summary": item.summary }),
+    );
+}
+
+fn load_item(state: &EngineState, id: &str) -> Result<OutboxItem, String> {
+    state
+        .store
+        .outbox_get(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Outbox item {} not found", id))
+}
+
+/// Outbox items, optionally filtered by status (`pending`, `sent`, …).
+/// Overdue pending items are expired first.
+#[tauri::command]
+pub fn engine_outbox_list(
+    state: State<'_, EngineState>,
+    status: Option<String>,
+) -> Result<Vec<OutboxItem>, String> {
+    state.store.outbox_expire().map_err(|e| e.to_string())?;
+    state
+        .store
+        .outbox_list(status.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Send a pending item now by running its tool call.
+#[tauri::command]
+pub async fn engine_outbox_approve(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    id: String,
+) -> Result<OutboxItem, String> {
+    state.store.outbox_expire().map_err(|e| e.to_string())?;
+    let item = load_item(&state, &id)?;
+    if !state.store.outbox_claim(&id).map_err(|e| e.to_string())? {
+        return Err(format!("Outbox item is {}, not pending", item.status));
+    }
+
+    let tc = ToolCall {
+        id: format!("outbox-{}", item.id),
+        call_type: "function".into(),
+        function: FunctionCall {
+            name: item.tool_name.clone(),
+            arguments: item.arguments.clone(),
+        },
+        thought_signature: None,
+        thought_parts: vec![],
+    };
+    let result = crate::engine::tools::execute_tool(&tc, &app_handle, &item.agent_id).await;
+    info!(
+        "[outbox] Approved {} ({}) success={}",
+        item.id, item.tool_name, result.success
+    );
+    crate::engine::audit::log_tool_call(
+        &state.store,
+        &item.agent_id,
+        item.session_id.as_deref().unwrap_or(""),
+        &item.tool_name,
+        &tc.id,
+        &item.arguments,
+        result.success,
+        &result.output,
+    );
+    state
+        .store
+        .outbox_finish(
+            &id,
+            if result.success { "sent" } else { "failed" },
+            Some(&result.output),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let item = load_item(&state, &id)?;
+    emit_updated(&app_handle, &item);
+    Ok(item)
+}
+
+/// Discard a pending item without sending it.
+#[tauri::command]
+pub fn engine_outbox_reject(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    id: String,
+    reason: Option<String>,
+) -> Result<OutboxItem, String> {
+    let item = load_item(&state, &id)?;
+    if item.status != "pending"
+        || !state
+            .store
+            .outbox_finish(&id, "rejected", reason.as_deref())
+            .map_err(|e| e.to_string())?
+    {
+        return Err(format!("Outbox item is {}, not pending", item.status));
+    }
+    let item = load_item(&state, &id)?;
+    emit_updated(&app_handle, &item);
+    Ok(item)
+}
+
+/// Replace a pending item's tool arguments (e.g. to reword the message).
+#[tauri::command]
+pub fn engine_outbox_edit(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    id: String,
+    arguments: serde_json::Value,
+) -> Result<OutboxItem, String> {
+    if !arguments.is_object() {
+        return Err("Outbox arguments must be a JSON object".into());
+    }
+    let item = load_item(&state, &id)?;
+    let summary = outbox::summarize(&item.tool_name, &arguments);
+    if !state
+        .store
+        .outbox_edit(&id, &arguments.to_string(), &summary)
+        .map_err(|e| e.to_string())?
+    {
+        return Err(format!("Outbox item is {}, not pending", item.status));
+    }
+    let item = load_item(&state, &id)?;
+    emit_updated(&app_handle, &item);
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn engine_outbox_get_settings(state: State<'_, EngineState>) -> Result<OutboxSettings, String> {
+    Ok(outbox::load_settings(&state.store))
+}
+
+#[tauri::command]
+pub fn engine_outbox_set_settings(
+    state: State<'_, EngineState>,
+    settings: OutboxSettings,
+) -> Result<(), String> {
+    outbox::save_settings(&state.store, &settings).map_err(|e| e.to_string())
+}
@@ -0,0 +1,29 @@
+// commands/processes.rs — Child process registry
+//
+// List the processes Paw started (n8n, ollama, MCP servers, tunnels, the
+// headless browser, running exec commands) and stop one by hand, and what
+// they cost in CPU and memory. See engine::processes and engine::resources.
+
+use crate::engine::processes::{self, ManagedProcess};
+use crate::engine::resources::{self, ResourceUsage};
+
+/// Running processes Paw started, oldest first.
+#[tauri::command]
+pub fn engine_processes_list() -> Vec<ManagedProcess> {
+    processes::list()
+}
+
+/// Stop a process Paw started, with its process group or tree. PIDs that
+/// aren't in the registry are refused.
+#[tauri::command]
+pub fn engine_processes_kill(pid: u32) -> Result<(), String> {
+    processes::kill(pid).map_err(|e| e.to_string())
+}
+
+/// CPU and memory of Paw and its child processes, plus system memory.
+#[tauri::command]
+pub async fn engine_resource_usage() -> Result<ResourceUsage, String> {
+    tokio::task::spawn_blocking(resources::sample)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -91,3 +91,59 @@ pub async fn engine_project_run(
 
     Ok(run_id)
 }
+
+/// Report of the project's latest run (None until a run has finished).
+#[tauri::command]
+pub fn engine_project_postmortem(
+    state: State<'_, EngineState>,
+    project_id: String,
+) -> Result<Option<ProjectPostMortem>, String> {
+    state
+        .store
+        .get_project_postmortem(&project_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Stop delegating: the boss stops at its next round, agents already
+/// working finish their tasks.
+#[tauri::command]
+pub fn engine_project_pause(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+) -> Result<(), String> {
+    crate::engine::orchestrator::pause_project(&app_handle, &project_id).map_err(|e| e.to_string())
+}
+
+/// Continue a paused (or interrupted) project in the background.
+#[tauri::command]
+pub fn engine_project_resume(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    project_id: String,
+) -> Result<(), String> {
+    crate::engine::orchestrator::check_resumable(&state, &project_id).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn(async move {
+        match crate::engine::orchestrator::resume_project(&app_handle, &project_id).await {
+            Ok(text) => info!(
+                "[orchestrator] Project {} finished after resume: {}...",
+                project_id,
+                truncate_utf8(&text, 200)
+            ),
+            Err(e) => error!(
+                "[orchestrator] Resumed project {} failed: {}",
+                project_id, e
+            ),
+        }
+    });
+    Ok(())
+}
+
+/// Hand a past delegation to its agent again. Returns the new step's id.
+#[tauri::command]
+pub fn engine_project_rerun_step(
+    app_handle: tauri::AppHandle,
+    step_id: String,
+) -> Result<String, String> {
+    crate::engine::orchestrator::rerun_step(&app_handle, &step_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,102 @@
+// Prompt Library Commands — Tauri IPC wrappers.
+// Template CRUD, rendering, and sending a rendered template into a session.
+
+use std::collections::HashMap;
+
+use log::info;
+use tauri::State;
+
+use crate::engine::prompt_library::{self, PromptTemplate, PromptVariable};
+use crate::engine::state::EngineState;
+use crate::engine::types::{ChatRequest, ChatResponse};
+
+fn load_template(state: &EngineState, id: &str) -> Result<PromptTemplate, String> {
+    prompt_library::get(&state.store, id)?.ok_or_else(|| format!("Template not found: {}", id))
+}
+
+#[tauri::command]
+pub fn engine_prompts_list(state: State<'_, EngineState>) -> Result<Vec<PromptTemplate>, String> {
+    prompt_library::list(&state.store).map_err(|e| e.to_string())
+}
+
+/// Create a template (no `id`) or update an existing one.
+#[tauri::command]
+pub fn engine_prompts_save(
+    state: State<'_, EngineState>,
+    id: Option<String>,
+    name: String,
+    description: Option<String>,
+    body: String,
+    variables: Vec<PromptVariable>,
+) -> Result<PromptTemplate, String> {
+    let template = prompt_library::save(
+        &state.store,
+        id.as_deref(),
+        &name,
+        description.as_deref().unwrap_or(""),
+        &body,
+        &variables,
+    )?;
+    info!(
+        "[prompts] Saved template '{}' ({})",
+        template.name, template.id
+    );
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn engine_prompts_delete(state: State<'_, EngineState>, id: String) -> Result<bool, String> {
+    prompt_library::delete(&state.store, &id).map_err(|e| e.to_string())
+}
+
+/// Render a template with the given variable values.
+#[tauri::command]
+pub fn engine_prompts_render(
+    state: State<'_, EngineState>,
+    id: String,
+    values: HashMap<String, serde_json::Value>,
+) -> Result<String, String> {
+    let template = load_template(&state, &id)?;
+    prompt_library::render(&template, &values).map_err(|e| e.to_string())
+}
+
+/// Render a template and send it as a user message. Without `session_id`
+/// a new session is started, as with a normal chat send. `system_prompt`
+/// is the agent's profile prompt, passed through like the chat view does.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn engine_prompts_run_template(
+    app_handle: tauri::AppHandle,
+    state: State<'_, EngineState>,
+    id: String,
+    values: HashMap<String, serde_json::Value>,
+    session_id: Option<String>,
+    agent_id: Option<String>,
+    model: Option<String>,
+    system_prompt: Option<String>,
+) -> Result<ChatResponse, String> {
+    let template = load_template(&state, &id)?;
+    let message = prompt_library::render(&template, &values)?;
+    info!(
+        "[prompts] Running template '{}' in session {:?}",
+        template.name, session_id
+    );
+    let request = ChatRequest {
+        session_id,
+        message,
+        model,
+        system_prompt,
+        temperature: None,
+        provider_id: None,
+        tools_enabled: Some(true),
+        agent_id,
+        tool_filter: None,
+        attachments: Vec::new(),
+        thinking_level: None,
+        auto_approve_all: false,
+        user_approved_tools: Vec::new(),
+        plan_mode: false,
+        ingested: vec![],
+    };
+    crate::commands::chat::engine_chat_send(app_handle, state, request).await
+}
@@ -0,0 +1,100 @@
+// commands/research.rs — Research runs
+//
+// Start, follow, cancel and resume long-running research runs (see
+// engine::research). Progress arrives as `research-event`.
+
+use crate::commands::state::EngineState;
+use crate::engine::research::{self, ResearchState};
+use crate::engine::types::ResearchRun;
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct ResearchRunDetail {
+    pub run: ResearchRun,
+    /// Whether the run is executing in this process right now.
+    pub active: bool,
+    pub state: ResearchState,
+    /// Report markdown, once written.
+    pub report: Option<String>,
+}
+
+#[tauri::command]
+pub fn engine_research_start(
+    app_handle: tauri::AppHandle,
+    question: String,
+    agent_id: Option<String>,
+    page_budget: Option<u32>,
+    max_rounds: Option<u32>,
+) -> Result<ResearchRun, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("Enter a research question".into());
+    }
+    research::start(
+        &app_handle,
+        question,
+        agent_id.as_deref().unwrap_or("default"),
+        page_budget.unwrap_or(research::DEFAULT_PAGE_BUDGET),
+        max_rounds.unwrap_or(research::DEFAULT_ROUNDS),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_research_list(
+    state: State<'_, EngineState>,
+    limit: Option<u32>,
+) -> Result<Vec<ResearchRun>, String> {
+    state
+        .store
+        .research_list(limit.unwrap_or(50).min(500))
+        .map_err(|e| e.to_string())
+}
+
+/// A run with its sources, claims and (when finished) report.
+#[tauri::command]
+pub fn engine_research_get(
+    state: State<'_, EngineState>,
+    id: String,
+) -> Result<ResearchRunDetail, String> {
+    let run = state
+        .store
+        .research_get(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Research run {} not found", id))?;
+    let report = run.report_path.as_ref().and_then(|p| {
+        std::fs::read_to_string(crate::engine::tools::agent_workspace(&run.agent_id).join(p)).ok()
+    });
+    Ok(ResearchRunDetail {
+        active: research::is_active(&id),
+        state: state
+            .store
+            .research_load_state(&id)
+            .map_err(|e| e.to_string())?,
+        report,
+        run,
+    })
+}
+
+#[tauri::command]
+pub fn engine_research_cancel(id: String) -> Result<bool, String> {
+    Ok(research::cancel(&id))
+}
+
+#[tauri::command]
+pub fn engine_research_resume(
+    app_handle: tauri::AppHandle,
+    id: String,
+) -> Result<ResearchRun, String> {
+    research::resume(&app_handle, &id).map_err(|e| e.to_string())
+}
+
+/// Delete a finished run's record. The report file stays in the workspace.
+#[tauri::command]
+pub fn engine_research_delete(state: State<'_, EngineState>, id: String) -> Result<(), String> {
+    if research::is_active(&id) {
+        return Err("Cancel the run before deleting it".into());
+    }
+    state.store.research_delete(&id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,93 @@
+// commands/search.rs — Web search provider settings
+//
+// Pick the backend behind `web_search` and store its API key, instance URL
+// and rate limit (see engine::search).
+
+use crate::commands::state::EngineState;
+use crate::engine::search::{self, SearchBackend, SearchProviderConfig, SearchProviderStatus};
+use log::info;
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct SearchProvidersResponse {
+    pub active: SearchBackend,
+    pub fallback: bool,
+    pub providers: Vec<SearchProviderStatus>,
+}
+
+fn response(settings: &search::SearchSettings) -> SearchProvidersResponse {
+    SearchProvidersResponse {
+        active: settings.active,
+        fallback: settings.fallback,
+        providers: search::provider_status(settings),
+    }
+}
+
+#[tauri::command]
+pub fn engine_search_provider_get(
+    state: State<'_, EngineState>,
+) -> Result<SearchProvidersResponse, String> {
+    Ok(response(&search::load_settings(&state.store)))
+}
+
+/// Configure one backend. `api_key`: `None` keeps the stored key, `""`
+/// removes it. `make_active` switches `web_search` to this backend once it
+/// has what it needs (key or instance URL).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn engine_search_provider_set(
+    state: State<'_, EngineState>,
+    backend: SearchBackend,
+    api_key: Option<String>,
+    base_url: Option<String>,
+    requests_per_minute: Option<u32>,
+    make_active: Option<bool>,
+    fallback: Option<bool>,
+) -> Result<SearchProvidersResponse, String> {
+    let mut settings = search::load_settings(&state.store);
+
+    if let Some(key) = api_key.as_deref() {
+        if !backend.needs_api_key() && !key.is_empty() {
+            return Err(format!("{} does not use an API key", backend.label()));
+        }
+        search::set_api_key(backend, key);
+    }
+    let index = match settings.providers.iter().position(|p| p.backend == backend) {
+        Some(i) => i,
+        None => {
+            settings.providers.push(SearchProviderConfig {
+                backend,
+                base_url: None,
+                requests_per_minute: None,
+            });
+            settings.providers.len() - 1
+        }
+    };
+    let provider = &mut settings.providers[index];
+    if let Some(url) = base_url {
+        let url = url.trim().trim_end_matches('/').to_string();
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("Instance URL must start with http:// or https://".into());
+        }
+        provider.base_url = (!url.is_empty()).then_some(url);
+    }
+    if requests_per_minute.is_some() {
+        provider.requests_per_minute = requests_per_minute;
+    }
+    if let Some(f) = fallback {
+        settings.fallback = f;
+    }
+    if make_active.unwrap_or(false) {
+        search::check_configured(&settings, backend)?;
+        settings.active = backend;
+    }
+
+    search::save_settings(&state.store, &settings).map_err(|e| e.to_string())?;
+    info!(
+        "[search] Provider {} updated (active: {})",
+        backend.label(),
+        settings.active.label()
+    );
+    Ok(response(&settings))
+}
@@ -133,7 +133,8 @@ pub fn engine_tasks_cron_tick(state: State<'_, EngineState>) -> Result<Vec<Strin
     for task in due {
         info!("[engine] Cron task due: {} ({})", task.title, task.id);
         let now = chrono::Utc::now();
-        let next = tasks::compute_next_run(&task.cron_schedule, &now);
+        let tz = state.config.lock().timezone();
+        let next = tasks::compute_next_run_in(&task.cron_schedule, &now, &tz);
         state
             .store
             .update_task_cron_run(&task.id, &now.to_rfc3339(), next.as_deref())?;
@@ -153,3 +154,29 @@ pub fn engine_tasks_cron_tick(state: State<'_, EngineState>) -> Result<Vec<Strin
 
     Ok(triggered_ids)
 }
+
+// ── Follow-up Commands ─────────────────────────────────────────────────
+
+/// Pending follow-ups scheduled by agents, optionally for one session.
+#[tauri::command]
+pub fn engine_followups_list(
+    state: State<'_, EngineState>,
+    session_id: Option<String>,
+) -> Result<Vec<Followup>, String> {
+    state
+        .store
+        .list_followups(session_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn engine_followup_cancel(
+    state: State<'_, EngineState>,
+    followup_id: String,
+) -> Result<bool, String> {
+    info!("[engine] Cancelling follow-up {}", followup_id);
+    state
+        .store
+        .finish_followup(&followup_id, "cancelled")
+        .map_err(|e| e.to_string())
+}
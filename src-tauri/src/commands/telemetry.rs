@@ -1,9 +1,13 @@
 // Telemetry Commands — Tauri IPC wrappers (Canvas Phase 5).
-// Exposes daily/weekly metrics and session metric history to the frontend.
+// Exposes daily/weekly metrics and session metric history to the frontend,
+// plus per-tool argument validation failures.
 
 use crate::atoms::types::TelemetryMetricRow;
-use crate::engine::sessions::telemetry::{TelemetryDailySummary, TelemetryModelBreakdown};
+use crate::engine::sessions::telemetry::{
+    StatsPoint, TelemetryDailySummary, TelemetryModelBreakdown,
+};
 use crate::engine::state::EngineState;
+use crate::engine::tool_args::ToolValidationStats;
 use tauri::State;
 
 /// Get aggregated metrics for a single date (YYYY-MM-DD).
@@ -66,3 +70,24 @@ pub fn engine_purge_old_metrics(
         .purge_metrics_before(&cutoff_date)
         .map_err(|e| e.to_string())
 }
+
+/// A metric over the last `period` ("24h", "7d", "12w"), one point per
+/// `resolution` bucket ("hour", "day", "week"), for dashboard trend lines.
+#[tauri::command]
+pub fn engine_stats_timeseries(
+    state: State<'_, EngineState>,
+    metric: String,
+    period: String,
+    resolution: String,
+) -> Result<Vec<StatsPoint>, String> {
+    state
+        .store
+        .stats_timeseries(&metric, &period, &resolution)
+        .map_err(|e| e.to_string())
+}
+
+/// Tool-call argument validation failures per tool, since startup.
+#[tauri::command]
+pub fn engine_tool_validation_stats() -> Vec<ToolValidationStats> {
+    crate::engine::tool_args::stats()
+}
@@ -0,0 +1,92 @@
+// commands/tool_rag.rs — Tool RAG index maintenance and relevance tuning.
+// Rebuild the librarian's index, inspect how a query is scored, and control
+// how many dynamic tools a request_tools call injects.
+
+use crate::engine::state::EngineState;
+use crate::engine::tool_index::ToolIndex;
+use crate::engine::tool_registry::{ToolRagConfig, ToolRagDebugReport, ToolRagRebuildReport};
+use crate::engine::tools::request_tools::build_all_tools_for_index;
+use log::info;
+use tauri::State;
+
+const CONFIG_KEY: &str = "tool_rag_config";
+
+/// Maximum candidates returned by the debug query.
+const DEBUG_CANDIDATE_LIMIT: usize = 50;
+
+/// Drop every cached tool embedding and re-index builtins, skills and MCP tools.
+#[tauri::command]
+pub async fn engine_toolrag_rebuild(
+    state: State<'_, EngineState>,
+) -> Result<ToolRagRebuildReport, String> {
+    let emb_client = state.embedding_client();
+    let mcp_tools = {
+        let reg = state.mcp_registry.lock().await;
+        reg.all_tool_definitions()
+    };
+    let all_tools = build_all_tools_for_index(&state, &mcp_tools);
+
+    let conn = state.store.conn();
+    let report = {
+        let mut registry = state.persistent_tool_registry.lock().await;
+        registry
+            .rebuild(&all_tools, emb_client.as_ref(), &conn)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+
+    // Reset the in-memory fallback index so it re-embeds lazily.
+    *state.tool_index.lock().await = ToolIndex::new();
+
+    info!(
+        "[tool-rag] Index rebuilt: {} tools ({} embedded, {} failed, tier={:?})",
+        report.total, report.embedded, report.failed, report.tier
+    );
+    Ok(report)
+}
+
+/// Score a query against the tool index and return every candidate with
+/// its vector and BM25 scores, plus the set request_tools would load.
+#[tauri::command]
+pub async fn engine_toolrag_query_debug(
+    state: State<'_, EngineState>,
+    query: String,
+) -> Result<ToolRagDebugReport, String> {
+    let emb_client = state.embedding_client();
+    let conn = state.store.conn();
+    let registry = state.persistent_tool_registry.lock().await;
+    registry
+        .debug_search(&query, DEBUG_CANDIDATE_LIMIT, emb_client.as_ref(), &conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the current Tool RAG relevance tuning.
+#[tauri::command]
+pub async fn engine_toolrag_get_config(
+    state: State<'_, EngineState>,
+) -> Result<ToolRagConfig, String> {
+    let registry = state.persistent_tool_registry.lock().await;
+    Ok(registry.config().clone())
+}
+
+/// Save Tool RAG relevance tuning. Values are clamped to a usable range.
+#[tauri::command]
+pub async fn engine_toolrag_set_config(
+    state: State<'_, EngineState>,
+    config: ToolRagConfig,
+) -> Result<ToolRagConfig, String> {
+    let config = config.sanitized();
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state.store.set_config(CONFIG_KEY, &json)?;
+    state
+        .persistent_tool_registry
+        .lock()
+        .await
+        .set_config(config.clone());
+    info!(
+        "[tool-rag] Config saved: top_k={}, max_results={}, min_relevance={:.2}",
+        config.top_k, config.max_results, config.min_relevance
+    );
+    Ok(config)
+}
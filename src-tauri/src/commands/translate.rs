@@ -0,0 +1,23 @@
+// commands/translate.rs — Translation settings
+//
+// Local translation model, pivot language and the per-bridge auto-translate
+// modes (see engine::translate).
+
+use crate::commands::state::EngineState;
+use crate::engine::translate::{self, TranslateSettings};
+use tauri::State;
+
+#[tauri::command]
+pub fn engine_translate_get_settings(
+    state: State<'_, EngineState>,
+) -> Result<TranslateSettings, String> {
+    Ok(translate::load_settings(&state.store))
+}
+
+#[tauri::command]
+pub fn engine_translate_set_settings(
+    state: State<'_, EngineState>,
+    settings: TranslateSettings,
+) -> Result<(), String> {
+    translate::save_settings(&state.store, &settings).map_err(|e| e.to_string())
+}
@@ -390,38 +390,17 @@ async fn stt_openai_whisper(
         _ => "webm",
     };
 
-    let file_part = reqwest::multipart::Part::bytes(audio_bytes)
-        .file_name(format!("audio.{}", ext))
-        .mime_str(mime_type)
-        .map_err(|e| format!("MIME error: {}", e))?;
-
-    let form = reqwest::multipart::Form::new()
-        .text("model", "whisper-1")
-        .text("language", "en")
-        .part("file", file_part);
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .post(format!(
-            "{}/audio/transcriptions",
-            base_url.trim_end_matches('/')
-        ))
-        .header("Authorization", format!("Bearer {}", api_key))
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Whisper API request failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Whisper API error ({}): {}", status, body));
-    }
-
-    let result: serde_json::Value = resp
-        .json()
-        .await
-        .map_err(|e| format!("Whisper API JSON parse error: {}", e))?;
+    let result = crate::engine::transcribe::whisper_api(
+        api_key,
+        base_url,
+        audio_bytes,
+        &format!("audio.{}", ext),
+        mime_type,
+        Some("en"),
+        false,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     result["text"]
         .as_str()
@@ -352,12 +352,30 @@ pub struct KeychainHealth {
 }
 
 /// Check health of the unified key vault.
-/// All encryption keys now live in a single OS keychain entry.
+/// All encryption keys live in a single OS keychain entry, or in the
+/// encrypted file store when the keychain is unavailable.
 #[tauri::command]
 pub fn check_keychain_health() -> KeychainHealth {
     let keychain_ok = key_vault::is_loaded();
-
-    if keychain_ok {
+    let vault = key_vault::status();
+
+    if keychain_ok && vault.backend == key_vault::VaultBackend::File {
+        let protection = if vault.passphrase_protected {
+            "passphrase-protected"
+        } else {
+            "bound to this machine — set OPENPAWZ_VAULT_PASSPHRASE for stronger protection"
+        };
+        KeychainHealth {
+            status: "degraded".to_string(),
+            db_key_ok: true,
+            vault_key_ok: true,
+            message: format!(
+                "OS keychain unavailable — using encrypted file store ({})",
+                protection
+            ),
+            error: vault.keychain_error,
+        }
+    } else if keychain_ok {
         KeychainHealth {
             status: "healthy".to_string(),
             db_key_ok: true,
@@ -377,6 +395,18 @@ pub fn check_keychain_health() -> KeychainHealth {
     }
 }
 
+/// Move the key vault between the OS keychain and the encrypted file store.
+/// `target` is "keychain" or "file". Returns the number of keys moved.
+#[tauri::command]
+pub fn key_vault_migrate(target: String) -> Result<usize, String> {
+    let target = match target.as_str() {
+        "keychain" => key_vault::VaultBackend::Keychain,
+        "file" => key_vault::VaultBackend::File,
+        other => return Err(format!("Unknown vault backend '{}'", other)),
+    };
+    key_vault::migrate_to(target)
+}
+
 /// Geocode a location string via Open-Meteo. Tries the full input first,
 /// then falls back to just the city name (before the first comma) since
 /// Open-Meteo doesn't understand "City, State" format well.
@@ -433,9 +463,10 @@ pub async fn _geocode_query(
 /// Fetch weather data via Open-Meteo (free, no API key, reliable).
 ///
 /// Location priority:
-///   1. `config.weather_location` (user-configured)
-///   2. Legacy integration credentials (`weather-api`)
-///   3. IP geolocation auto-detect
+///   1. `config.home_location` (already geocoded — see engine::location)
+///   2. `config.weather_location` (user-configured)
+///   3. Legacy integration credentials (`weather-api`)
+///   4. IP geolocation auto-detect
 ///
 /// Two-step: geocode location → fetch forecast with lat/lon.
 #[tauri::command]
@@ -445,7 +476,22 @@ pub async fn fetch_weather(app_handle: tauri::AppHandle) -> Result<String, Strin
         .build()
         .map_err(|e| format!("HTTP client error: {}", e))?;
 
-    // 1. Try config.weather_location
+    // 1. Home location — coordinates are already known
+    if let Some(home) = app_handle
+        .try_state::<crate::engine::state::EngineState>()
+        .and_then(|state| crate::engine::location::home(&state))
+    {
+        return fetch_weather_by_coords(
+            &client,
+            home.latitude,
+            home.longitude,
+            &home.name,
+            &home.country,
+        )
+        .await;
+    }
+
+    // 2. Try config.weather_location
     let mut loc = String::new();
     if let Some(state) = app_handle.try_state::<crate::engine::state::EngineState>() {
         let cfg = state.config.lock();
@@ -456,7 +502,7 @@ pub async fn fetch_weather(app_handle: tauri::AppHandle) -> Result<String, Strin
         }
     }
 
-    // 2. Legacy: try integration credentials
+    // 3. Legacy: try integration credentials
     if loc.is_empty() {
         if let Ok(creds) = crate::engine::channels::load_channel_config::<
             std::collections::HashMap<String, String>,
@@ -481,20 +527,27 @@ pub async fn fetch_weather(app_handle: tauri::AppHandle) -> Result<String, Strin
         }
     }
 
-    // 3. Auto-detect via IP geolocation
+    // 4. Auto-detect via IP geolocation
     if loc.is_empty() {
         log::info!("[weather] No location configured — auto-detecting via IP");
-        match auto_detect_location(&client).await {
-            Ok((lat, lon, city, country)) => {
+        let ip_client = crate::engine::location::client().map_err(|e| e.to_string())?;
+        match crate::engine::location::detect_by_ip(&ip_client).await {
+            Ok(place) => {
                 log::info!(
-                    "[weather] Auto-detected location: {}, {} ({}, {})",
-                    city,
-                    country,
-                    lat,
-                    lon
+                    "[weather] Auto-detected location: {} ({}, {})",
+                    place.display_name(),
+                    place.latitude,
+                    place.longitude
                 );
                 // Go straight to weather fetch with known coords
-                return fetch_weather_by_coords(&client, lat, lon, &city, &country).await;
+                return fetch_weather_by_coords(
+                    &client,
+                    place.latitude,
+                    place.longitude,
+                    &place.name,
+                    &place.country,
+                )
+                .await;
             }
             Err(e) => {
                 log::warn!("[weather] IP geolocation failed: {}", e);
@@ -561,35 +614,3 @@ async fn fetch_weather_by_coords(
     );
     serde_json::to_string(&wx).map_err(|e| format!("JSON serialization error: {}", e))
 }
-
-/// Auto-detect user location via IP geolocation (ipapi.co — free, no key).
-/// Returns (lat, lon, city, country).
-async fn auto_detect_location(
-    client: &reqwest::Client,
-) -> Result<(f64, f64, String, String), String> {
-    let resp = client
-        .get("https://ipapi.co/json/")
-        .header("User-Agent", "OpenPawz/1.0")
-        .send()
-        .await
-        .map_err(|e| format!("IP geolocation failed: {}", e))?;
-    if !resp.status().is_success() {
-        return Err(format!("IP geolocation returned {}", resp.status()));
-    }
-    let text = resp
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read IP geo response: {}", e))?;
-    let data: serde_json::Value =
-        serde_json::from_str(&text).map_err(|e| format!("Invalid IP geo JSON: {}", e))?;
-
-    let lat = data["latitude"]
-        .as_f64()
-        .ok_or("IP geolocation missing latitude")?;
-    let lon = data["longitude"]
-        .as_f64()
-        .ok_or("IP geolocation missing longitude")?;
-    let city = data["city"].as_str().unwrap_or("Unknown").to_string();
-    let country = data["country_name"].as_str().unwrap_or("").to_string();
-    Ok((lat, lon, city, country))
-}
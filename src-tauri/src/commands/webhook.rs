@@ -6,6 +6,7 @@ use crate::engine::webhook::{self, WebhookConfig};
 /// Start the webhook HTTP server.
 #[tauri::command]
 pub async fn engine_webhook_start(app_handle: tauri::AppHandle) -> Result<(), String> {
+    crate::engine::safe_mode::check_bridge_start(&app_handle).map_err(|e| e.to_string())?;
     webhook::start_bridge(app_handle).map_err(|e| e.to_string())
 }
 
@@ -0,0 +1,57 @@
+// commands/worker_delegation.rs — Foreman delegation rules and savings stats.
+// Configure which tool calls go to which named worker model, and report how
+// many boss-model tokens delegation has avoided.
+
+use crate::engine::state::EngineState;
+use crate::engine::tools::worker_delegate::{
+    self,
+    rules::{WorkerDelegationConfig, WorkerStats, CONFIG_KEY},
+};
+use log::info;
+use std::collections::HashMap;
+use tauri::State;
+
+/// Get the current delegation rules and named workers.
+#[tauri::command]
+pub fn engine_worker_delegation_get_config(
+    state: State<'_, EngineState>,
+) -> Result<WorkerDelegationConfig, String> {
+    Ok(worker_delegate::load_config(&state))
+}
+
+/// Save delegation rules. Rules referencing unknown workers are kept but
+/// never match until the worker is defined.
+#[tauri::command]
+pub fn engine_worker_delegation_set_config(
+    state: State<'_, EngineState>,
+    config: WorkerDelegationConfig,
+) -> Result<(), String> {
+    if let Some(w) = config.workers.iter().find(|w| w.name.trim().is_empty()) {
+        return Err(format!("Worker with model '{}' needs a name", w.model));
+    }
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state.store.set_config(CONFIG_KEY, &json)?;
+    *state.worker_delegation.lock() = config.clone();
+    info!(
+        "[worker-delegate] Config saved: enabled={}, {} workers, {} rules",
+        config.enabled,
+        config.workers.len(),
+        config.rules.len()
+    );
+    Ok(())
+}
+
+/// Per-worker delegation counts and estimated tokens saved since startup.
+#[tauri::command]
+pub fn engine_worker_delegation_stats(
+    state: State<'_, EngineState>,
+) -> Result<HashMap<String, WorkerStats>, String> {
+    Ok(state.worker_stats.lock().clone())
+}
+
+/// Reset the delegation counters.
+#[tauri::command]
+pub fn engine_worker_delegation_reset_stats(state: State<'_, EngineState>) -> Result<(), String> {
+    state.worker_stats.lock().clear();
+    Ok(())
+}
@@ -2,13 +2,256 @@
 //
 // Keeps the main `run_agent_turn` loop focused on orchestration by
 // pulling out self-contained sub-operations: malformed call recovery,
-// empty response nudging, tool-RAG hot-loading, and mid-loop context
-// truncation.
+// empty response nudging, tool-RAG hot-loading, per-round tool pruning,
+// session-scoped tool calls, screen capture consent, idempotency keys,
+// outbox queueing, and mid-loop context truncation.
 
+use crate::engine::state::with_store;
+use crate::engine::tool_registry::PersistentToolRegistry;
 use crate::engine::types::*;
 use log::{info, warn};
 use std::collections::HashSet;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+
+// ── Session-scoped tools ───────────────────────────────────────────────
+
+/// The error kind of a session-scoped tool's result.
+fn error_kind(success: bool, output: &str) -> Option<ToolErrorKind> {
+    if success {
+        None
+    } else {
+        crate::engine::tool_errors::classify(output)
+    }
+}
+
+/// Run `schedule_followup` for the current session.
+pub fn followup_tool_result(
+    tc: &ToolCall,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    agent_id: &str,
+) -> ToolResult {
+    let args: serde_json::Value =
+        serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+    let (output, success) =
+        match crate::engine::tools::followups::schedule(app_handle, session_id, agent_id, &args) {
+            Ok(out) => (out, true),
+            Err(err) => (format!("Error: {}", err), false),
+        };
+    ToolResult {
+        tool_call_id: tc.id.clone(),
+        error_kind: error_kind(success, &output),
+        output,
+        success,
+    }
+}
+
+/// Run `handoff_to_agent` for the current session.
+pub fn handoff_tool_result(
+    tc: &ToolCall,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    agent_id: &str,
+) -> ToolResult {
+    let args: serde_json::Value =
+        serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+    let (output, success) =
+        match crate::engine::handoff::request(app_handle, session_id, run_id, agent_id, &args) {
+            Ok(out) => (out, true),
+            Err(err) => (format!("Error: {}", err), false),
+        };
+    ToolResult {
+        tool_call_id: tc.id.clone(),
+        error_kind: error_kind(success, &output),
+        output,
+        success,
+    }
+}
+
+/// Run `screen_capture`, returning the image message to show the model.
+pub async fn screen_capture_result(tc: &ToolCall) -> (ToolResult, Option<Message>) {
+    let args: serde_json::Value =
+        serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+    let (output, success, image) = match crate::engine::tools::screen::capture(&args).await {
+        Ok(capture) => (capture.summary(), true, Some(capture.image_message())),
+        Err(err) => (format!("Error: {}", err), false, None),
+    };
+    let result = ToolResult {
+        tool_call_id: tc.id.clone(),
+        error_kind: error_kind(success, &output),
+        output,
+        success,
+    };
+    (result, image)
+}
+
+// ── Cancellation ───────────────────────────────────────────────────────
+
+pub const CANCELLED_TOOL_OUTPUT: &str = "Cancelled by the user.";
+
+/// Result of a tool call the user cancelled before it finished.
+pub fn cancelled_tool_result(tc: &ToolCall) -> ToolResult {
+    ToolResult {
+        tool_call_id: tc.id.clone(),
+        output: CANCELLED_TOOL_OUTPUT.into(),
+        success: false,
+        error_kind: None,
+    }
+}
+
+/// Answer a tool call that was never run because the run was cancelled,
+/// so the history keeps a result for every call.
+pub fn skip_cancelled_call(messages: &mut Vec<Message>, tc: &ToolCall) {
+    messages.push(Message {
+        role: Role::Tool,
+        content: MessageContent::Text(CANCELLED_TOOL_OUTPUT.into()),
+        tool_calls: None,
+        tool_call_id: Some(tc.id.clone()),
+        name: Some(tc.function.name.clone()),
+    });
+}
+
+/// End a cancelled run: tell the frontend, which keeps the partial output.
+pub fn finish_cancelled(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    text: String,
+) -> String {
+    info!("[engine] Run {} cancelled (session={})", run_id, session_id);
+    let _ = app_handle.emit(
+        "engine-event",
+        EngineEvent::Cancelled {
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            text: text.clone(),
+        },
+    );
+    text
+}
+
+/// Whether the user allowed consent-gated tools for the whole session.
+pub fn has_session_consent(app_handle: &tauri::AppHandle, session_id: &str) -> bool {
+    app_handle
+        .try_state::<crate::engine::state::EngineState>()
+        .is_some_and(|state| state.screen_consent.lock().contains(session_id))
+}
+
+// ── Idempotency ────────────────────────────────────────────────────────
+
+/// The earlier run of a side-effect call with this idempotency key, if it
+/// already completed (and the model did not ask to run it again).
+pub async fn completed_call(
+    app_handle: &tauri::AppHandle,
+    config: &IdempotencyConfig,
+    key: &str,
+    tc: &ToolCall,
+) -> Option<CompletedToolCall> {
+    use crate::engine::idempotency;
+    if !config.enabled
+        || !idempotency::applies_to(&tc.function.name)
+        || idempotency::overridden(&tc.function.arguments)
+    {
+        return None;
+    }
+    let (key, ttl_hours) = (key.to_string(), config.ttl_hours);
+    let done = with_store(app_handle, move |store| {
+        store.idempotency_get(&key, ttl_hours)
+    })
+    .await
+    .ok()??;
+    // The same position in the run holding another tool is a different call
+    (done.tool_name == tc.function.name).then_some(done)
+}
+
+/// Remember a successful side-effect call under its idempotency key.
+pub async fn record_completed_call(
+    app_handle: &tauri::AppHandle,
+    config: &IdempotencyConfig,
+    key: &str,
+    tc: &ToolCall,
+    session_id: &str,
+    output: &str,
+) {
+    if !config.enabled || !crate::engine::idempotency::applies_to(&tc.function.name) {
+        return;
+    }
+    let (owned_key, tool, session, output, ttl_hours) = (
+        key.to_string(),
+        tc.function.name.clone(),
+        session_id.to_string(),
+        output.to_string(),
+        config.ttl_hours,
+    );
+    if let Err(e) = with_store(app_handle, move |store| {
+        store.idempotency_record(&owned_key, &tool, &session, &output, ttl_hours)
+    })
+    .await
+    {
+        warn!("[engine] Failed to record idempotency key {}: {}", key, e);
+    }
+}
+
+// ── Outbox ─────────────────────────────────────────────────────────────
+
+/// Park an outbound message in the outbox when the outbox policy says so.
+/// Returns the result to hand back to the model, or None to carry on with
+/// the normal approval and execution path.
+pub async fn queue_in_outbox(
+    tc: &ToolCall,
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    agent_id: &str,
+    needs_approval: bool,
+) -> Option<ToolResult> {
+    let (call, session, agent) = (tc.clone(), session_id.to_string(), agent_id.to_string());
+    let queued = with_store(app_handle, move |store| {
+        let settings = crate::engine::outbox::load_settings(store);
+        if !settings.should_queue(&call.function.name, needs_approval) {
+            return Ok(None);
+        }
+        let args: serde_json::Value =
+            serde_json::from_str(&call.function.arguments).unwrap_or(serde_json::Value::Null);
+        let summary = crate::engine::outbox::summarize(&call.function.name, &args);
+        let id = store.outbox_enqueue(
+            &agent,
+            Some(&session),
+            &call.function.name,
+            &call.function.arguments,
+            &summary,
+            settings.expiry_hours,
+        )?;
+        Ok(Some((id, summary)))
+    })
+    .await;
+    match queued.transpose()? {
+        Ok((id, summary)) => {
+            info!("[engine] Queued {} in outbox: {}", tc.function.name, id);
+            let _ = app_handle.emit(
+                "outbox-updated",
+                serde_json::json!({ "id": id, "status": "pending", "summary": summary }),
+            );
+            Some(ToolResult {
+                tool_call_id: tc.id.clone(),
+                output: format!(
+                    "Queued in the outbox for the user to review (id {}). It has NOT been sent yet; \
+                     it will be sent if the user approves it. Do not send it again.",
+                    id
+                ),
+                success: true,
+                error_kind: None,
+            })
+        }
+        Err(e) => {
+            warn!(
+                "[engine] Outbox enqueue failed, falling back to approval: {}",
+                e
+            );
+            None
+        }
+    }
+}
 
 // ── Malformed tool-call recovery ───────────────────────────────────────
 
@@ -129,7 +372,7 @@ pub fn empty_response_fallback() -> String {
 /// After tool execution, check if `request_tools` added new tool names to
 /// `loaded_tools`. If so, find their definitions and inject them into the
 /// active tool list so the model can use them in the next round.
-pub fn refresh_tool_rag(app_handle: &tauri::AppHandle, tools: &mut Vec<ToolDefinition>) {
+pub async fn refresh_tool_rag(app_handle: &tauri::AppHandle, tools: &mut Vec<ToolDefinition>) {
     let Some(state) = app_handle.try_state::<crate::engine::state::EngineState>() else {
         warn!("[tool-rag] No engine state available");
         return;
@@ -154,17 +397,20 @@ pub fn refresh_tool_rag(app_handle: &tauri::AppHandle, tools: &mut Vec<ToolDefin
 
     // Build the full tool registry to find the definitions
     let mut all_defs = crate::engine::tools::builtin_tools();
-    let enabled_ids: Vec<String> = crate::engine::skills::builtin_skills()
-        .iter()
-        .filter(|s| {
-            state
-                .store
-                .get_skill_enabled_state(&s.id)
-                .unwrap_or(None)
-                .unwrap_or(s.default_enabled)
-        })
-        .map(|s| s.id.clone())
-        .collect();
+    let enabled_ids: Vec<String> = with_store(app_handle, |store| {
+        Ok(crate::engine::skills::builtin_skills()
+            .iter()
+            .filter(|s| {
+                store
+                    .get_skill_enabled_state(&s.id)
+                    .unwrap_or(None)
+                    .unwrap_or(s.default_enabled)
+            })
+            .map(|s| s.id.clone())
+            .collect())
+    })
+    .await
+    .unwrap_or_default();
     all_defs.extend(crate::engine::tools::skill_tools(&enabled_ids));
 
     let mut added = 0;
@@ -187,6 +433,132 @@ pub fn refresh_tool_rag(app_handle: &tauri::AppHandle, tools: &mut Vec<ToolDefin
     }
 }
 
+// ── Per-round tool pruning ─────────────────────────────────────────────
+
+/// Max chars of the latest user / assistant text used as pruning context.
+const PRUNE_CONTEXT_CHARS: usize = 500;
+
+/// Build the text the tool list is ranked against: the latest user message,
+/// the latest assistant text, and the names of recently called tools.
+fn pruning_context(messages: &[Message]) -> String {
+    let last_text = |role: Role| {
+        messages
+            .iter()
+            .rev()
+            .find(|m| m.role == role && !m.content.as_text_ref().trim().is_empty())
+            .map(|m| {
+                crate::engine::util::safe_truncate(m.content.as_text_ref(), PRUNE_CONTEXT_CHARS)
+                    .to_string()
+            })
+    };
+    let mut parts: Vec<String> = Vec::new();
+    parts.extend(last_text(Role::User));
+    parts.extend(last_text(Role::Assistant));
+    let recent_tools: Vec<&str> = messages
+        .iter()
+        .rev()
+        .filter_map(|m| m.tool_calls.as_ref())
+        .flatten()
+        .take(5)
+        .map(|tc| tc.function.name.as_str())
+        .collect();
+    if !recent_tools.is_empty() {
+        parts.push(recent_tools.join(" "));
+    }
+    parts.join("\n")
+}
+
+/// Select the tools sent to the model this round.
+///
+/// Core tools, tools loaded via `request_tools` and tools already called in
+/// this conversation always stay, as do tools not indexed yet. The remaining
+/// tools are ranked against the conversation context and only the top
+/// `prune_top_k` are kept. Fails open
+/// (returns the full list) when pruning is disabled or nothing can be scored.
+pub async fn prune_tools_for_round(
+    app_handle: &tauri::AppHandle,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+) -> Vec<ToolDefinition> {
+    let Some(state) = app_handle.try_state::<crate::engine::state::EngineState>() else {
+        return tools.to_vec();
+    };
+
+    let loaded = state.loaded_tools.lock().clone();
+    let called: HashSet<&str> = messages
+        .iter()
+        .filter_map(|m| m.tool_calls.as_ref())
+        .flatten()
+        .map(|tc| tc.function.name.as_str())
+        .collect();
+    let is_pinned = |name: &str| {
+        crate::engine::tool_index::CORE_TOOLS.contains(&name)
+            || name == "request_tools"
+            || loaded.contains(name)
+            || called.contains(name)
+    };
+    let candidates: Vec<String> = tools
+        .iter()
+        .map(|t| t.function.name.clone())
+        .filter(|n| !is_pinned(n))
+        .collect();
+
+    let config = state.persistent_tool_registry.lock().await.config().clone();
+    if !config.auto_prune || candidates.len() <= config.prune_top_k {
+        return tools.to_vec();
+    }
+
+    let context = pruning_context(messages);
+    let emb_client = state.embedding_client();
+    let index = match with_store(app_handle, |store| {
+        let conn = store.read_conn();
+        let db = conn.lock();
+        PersistentToolRegistry::load_all(&db)
+    })
+    .await
+    {
+        Ok(index) => index,
+        Err(e) => {
+            warn!(
+                "[tool-rag] Tool pruning failed, sending full tool list: {}",
+                e
+            );
+            return tools.to_vec();
+        }
+    };
+    let kept: HashSet<String> = match PersistentToolRegistry::rank_for_context(
+        &context,
+        &candidates,
+        emb_client.as_ref(),
+        index,
+        config.prune_top_k,
+    )
+    .await
+    {
+        Ok(ranked) if !ranked.is_empty() => ranked.into_iter().collect(),
+        Ok(_) => return tools.to_vec(),
+        Err(e) => {
+            warn!(
+                "[tool-rag] Tool pruning failed, sending full tool list: {}",
+                e
+            );
+            return tools.to_vec();
+        }
+    };
+    let pruned: Vec<ToolDefinition> = tools
+        .iter()
+        .filter(|t| is_pinned(&t.function.name) || kept.contains(&t.function.name))
+        .cloned()
+        .collect();
+    info!(
+        "[tool-rag] Pruned tool list for round: {} → {} (kept {} contextual)",
+        tools.len(),
+        pruned.len(),
+        kept.len()
+    );
+    pruned
+}
+
 // ── Mid-loop context truncation ────────────────────────────────────────
 
 /// Estimate the token count of a single message (chars/4 heuristic).
@@ -6,12 +6,14 @@ pub(crate) mod helpers;
 mod trading;
 
 use crate::atoms::error::EngineResult;
+use crate::engine::notifications::{self, Notice};
 use crate::engine::providers::AnyProvider;
-use crate::engine::state::{DailyTokenTracker, PendingApprovals};
+use crate::engine::state::{with_store, DailyTokenTracker, PendingApprovals};
 use crate::engine::telemetry::{integration as telem, RunCollector};
 use crate::engine::tools;
 use crate::engine::types::*;
 use log::{info, warn};
+use openpawz_core::engine::cancel;
 use openpawz_core::engine::tool_metadata::{self, ToolTier};
 use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
@@ -55,14 +57,42 @@ pub async fn run_agent_turn(
     let turn_start = Instant::now();
     let mut tool_duration_total_ms: u64 = 0;
     let mut tool_call_count: u32 = 0;
+    // Position of every tool call in the run; with the run id it makes the
+    // call's idempotency key, so a retried run can't repeat side effects.
+    let mut call_index: u32 = 0;
+    let idempotency_config = app_handle
+        .try_state::<crate::engine::state::EngineState>()
+        .map(|es| es.config.lock().idempotency.clone())
+        .unwrap_or_default();
 
-    // Circuit breaker: track consecutive failures per tool name.
-    // After MAX_CONSECUTIVE_TOOL_FAILS of the same tool, inject a system nudge.
-    // After HARD_STOP_TOOL_FAILS, block further execution of that tool entirely.
-    let mut tool_fail_counter: std::collections::HashMap<String, u32> =
-        std::collections::HashMap::new();
-    const MAX_CONSECUTIVE_TOOL_FAILS: u32 = 3;
-    const HARD_STOP_TOOL_FAILS: u32 = 5;
+    // Web content fetched this turn, for the citations on the final answer.
+    let mut provenance = crate::engine::citations::Provenance::default();
+    // The provider's own web search, when the agent has it turned on.
+    let grounded = {
+        let agent = agent_id.to_string();
+        with_store(app_handle, move |store| {
+            Ok(crate::engine::grounding::is_enabled(store, &agent))
+        })
+        .await
+        .unwrap_or(false)
+    };
+
+    // Circuit breaker: track consecutive failures per tool name and their
+    // error kinds. Repeated failures inject a system nudge, then block the
+    // tool; auth and permission failures block it at once (see tool_errors).
+    // The breaker belongs to the session, so blocks outlive this turn.
+    let tool_breakers: crate::engine::state::ToolBreakers = app_handle
+        .try_state::<crate::engine::state::EngineState>()
+        .map(|es| {
+            let config = es.config.lock().circuit_breaker.clone();
+            es.tool_breakers
+                .lock()
+                .entry(session_id.to_string())
+                .or_default()
+                .configure(&config);
+            es.tool_breakers.clone()
+        })
+        .unwrap_or_default();
 
     // Repetition detector: track the tool-call "signature" (hashed tool names
     // + args) for each round.  If the same signature appears consecutively
@@ -91,9 +121,47 @@ pub async fn run_agent_turn(
         .unwrap_or_default();
     let mut speculation_stats = crate::engine::speculative::SpeculationStats::default();
 
+    // ── Autonomy level: per-agent policy bundle ───────────────────────
+    // When the agent has a stored level it decides auto-approval, which
+    // tool groups are offered and the effective daily budget. Agents
+    // without one keep the per-request `auto_approve_all` behaviour.
+    let autonomy = {
+        let agent = agent_id.to_string();
+        with_store(app_handle, move |store| {
+            Ok(crate::engine::autonomy::load(store, &agent))
+        })
+        .await
+        .ok()
+        .flatten()
+        .map(|level| level.policy())
+    };
+    let autonomy_label = autonomy.as_ref().map(|p| p.level.as_str().to_string());
+    let daily_budget_usd = match &autonomy {
+        Some(policy) => {
+            tools.retain(|t| policy.allows_tool(&t.function.name));
+            info!(
+                "[engine] Agent '{}' autonomy: {} (budget ×{})",
+                agent_id,
+                policy.level.as_str(),
+                policy.budget_multiplier
+            );
+            policy.effective_budget(daily_budget_usd)
+        }
+        None => daily_budget_usd,
+    };
+
+    // Set by engine_chat_cancel; checked between model calls and tool calls
+    let cancel_token = cancel::current();
+
     loop {
         round += 1;
 
+        if cancel_token.is_cancelled() {
+            return Ok(helpers::finish_cancelled(
+                app_handle, session_id, run_id, final_text,
+            ));
+        }
+
         // ── Yield check: if a new user message was queued, wrap up gracefully ─
         // VS Code pattern: when yield is requested, the agent stops its loop
         // and returns whatever it has so far.  The queued message will be
@@ -120,6 +188,7 @@ pub async fn run_agent_turn(
                         model: None,
                         total_rounds: Some(round),
                         max_rounds: Some(max_rounds),
+                        citations: Vec::new(),
                     },
                 );
                 return Ok(final_text);
@@ -150,6 +219,7 @@ pub async fn run_agent_turn(
                         model: None,
                         total_rounds: Some(round),
                         max_rounds: Some(max_rounds),
+                        citations: Vec::new(),
                     },
                 );
             }
@@ -185,9 +255,33 @@ pub async fn run_agent_turn(
         }
 
         // ── 1. Call the AI model ──────────────────────────────────────
-        let chunks = provider
-            .chat_stream(messages, tools, model, temperature, thinking_level)
-            .await?;
+        // Only contextually relevant tools are sent; `tools` keeps the full
+        // set so validation and hot-loading still see everything.
+        let mut round_tools = helpers::prune_tools_for_round(app_handle, messages, tools).await;
+        if grounded {
+            round_tools.extend(crate::engine::grounding::native_search_tool(
+                provider.kind(),
+                model,
+                !round_tools.is_empty(),
+            ));
+        }
+        // The stream borrows `messages`, so it lives in its own block
+        let chunks = {
+            let stream =
+                provider.chat_stream(messages, &round_tools, model, temperature, thinking_level);
+            tokio::pin!(stream);
+            tokio::select! {
+                res = &mut stream => res?,
+                _ = cancel_token.cancelled() => {
+                    // Providers stop reading at the next chunk and return what
+                    // arrived; one still connecting is dropped after a moment.
+                    match tokio::time::timeout(Duration::from_millis(500), &mut stream).await {
+                        Ok(Ok(chunks)) => chunks,
+                        _ => Vec::new(),
+                    }
+                }
+            }
+        };
 
         // ── 2. Assemble the response from chunks ──────────────────────
         let mut text_accum = String::new();
@@ -233,6 +327,35 @@ pub async fn run_agent_turn(
                 );
             }
 
+            // Provider-side tool activity (Gemini code execution, grounding)
+            for meta in &chunk.meta {
+                let event = match meta.clone() {
+                    ProviderMeta::CodeExecution {
+                        language,
+                        code,
+                        outcome,
+                        output,
+                    } => EngineEvent::CodeExecution {
+                        session_id: session_id.to_string(),
+                        run_id: run_id.to_string(),
+                        language,
+                        code,
+                        outcome,
+                        output,
+                    },
+                    ProviderMeta::Grounding { queries, sources } => {
+                        provenance.record_grounding(&sources);
+                        EngineEvent::Grounding {
+                            session_id: session_id.to_string(),
+                            run_id: run_id.to_string(),
+                            queries,
+                            sources,
+                        }
+                    }
+                };
+                let _ = app_handle.emit("engine-event", event);
+            }
+
             // Accumulate tool call deltas
             for tc_delta in &chunk.tool_calls {
                 has_tool_calls = true;
@@ -339,6 +462,14 @@ pub async fn run_agent_turn(
                         pct, est_usd, daily_budget_usd
                     );
                     warn!("[engine] {}", msg);
+                    notifications::notify(
+                        app_handle,
+                        Notice::new(
+                            notifications::BUDGET,
+                            format!("{}% of the daily budget used", pct),
+                            msg.clone(),
+                        ),
+                    );
                     let _ = app_handle.emit(
                         "engine-event",
                         EngineEvent::Error {
@@ -351,6 +482,33 @@ pub async fn run_agent_turn(
             }
         }
 
+        // ── Cancelled mid-stream: keep the partial answer, drop any
+        // half-streamed tool calls ──────────────────────────────────────
+        if cancel_token.is_cancelled() {
+            if let Some(batch) = delta_batcher.flush() {
+                let _ = app_handle.emit(
+                    "engine-event",
+                    EngineEvent::Delta {
+                        session_id: session_id.to_string(),
+                        run_id: run_id.to_string(),
+                        text: batch.combined_text,
+                    },
+                );
+            }
+            if !text_accum.is_empty() {
+                messages.push(Message {
+                    role: Role::Assistant,
+                    content: MessageContent::Text(text_accum.clone()),
+                    tool_calls: None,
+                    tool_call_id: None,
+                    name: None,
+                });
+            }
+            return Ok(helpers::finish_cancelled(
+                app_handle, session_id, run_id, text_accum,
+            ));
+        }
+
         // ── 3. If no tool calls, we're done ──────────────────────────
         if !has_tool_calls || tool_call_map.is_empty() {
             final_text = text_accum.clone();
@@ -434,6 +592,21 @@ pub async fn run_agent_turn(
             } else {
                 None
             };
+            let citations = provenance.attribute(&final_text);
+            if !citations.is_empty() {
+                let (run, session, saved) = (
+                    run_id.to_string(),
+                    session_id.to_string(),
+                    citations.clone(),
+                );
+                if let Err(e) = with_store(app_handle, move |store| {
+                    store.citations_save(&run, &session, &saved)
+                })
+                .await
+                {
+                    warn!("[engine] Failed to store citations: {}", e);
+                }
+            }
             let _ = app_handle.emit(
                 "engine-event",
                 EngineEvent::Complete {
@@ -445,6 +618,7 @@ pub async fn run_agent_turn(
                     model: confirmed_model.clone(),
                     total_rounds: Some(round),
                     max_rounds: Some(max_rounds),
+                    citations,
                 },
             );
 
@@ -469,8 +643,13 @@ pub async fn run_agent_turn(
                     total_cache_create,
                 );
 
-                if let Some(es) = app_handle.try_state::<crate::engine::state::EngineState>() {
-                    telem::persist_summary(&es.store, &summary);
+                {
+                    let summary = summary.clone();
+                    let _ = with_store(app_handle, move |store| {
+                        telem::persist_summary(store, &summary);
+                        Ok(())
+                    })
+                    .await;
                 }
                 telem::emit_summary(app_handle, &summary);
             }
@@ -739,8 +918,17 @@ pub async fn run_agent_turn(
             continue;
         }
 
+        // Screen captures are shown to the model after this round's results
+        let mut capture_images: Vec<Message> = Vec::new();
+
         for tc in &tool_calls {
+            if cancel_token.is_cancelled() {
+                helpers::skip_cancelled_call(messages, tc);
+                continue;
+            }
             info!("[engine] Tool call: {} id={}", tc.function.name, tc.id);
+            let idempotency_key = crate::engine::idempotency::key(run_id, call_index);
+            call_index += 1;
 
             // ─── Tool classification via centralized registry ───
             let tool_name = tc.function.name.as_str();
@@ -761,18 +949,71 @@ pub async fn run_agent_turn(
                     || tool_name.starts_with("dex_")
                     || tool_name.starts_with("coinbase_"));
 
-            // ── Circuit breaker: block tools that already hit HARD_STOP ──
-            if let Some(count) = tool_fail_counter.get(&tc.function.name) {
-                if *count >= HARD_STOP_TOOL_FAILS {
+            // ── Circuit breaker: refuse tools it has blocked ──
+            let refusal = tool_breakers
+                .lock()
+                .entry(session_id.to_string())
+                .or_default()
+                .blocked(&tc.function.name, Instant::now());
+            if let Some(refusal) = refusal {
+                warn!("[engine] Circuit breaker: blocking '{}'", tc.function.name);
+                messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Text(refusal),
+                    tool_calls: None,
+                    tool_call_id: Some(tc.id.clone()),
+                    name: Some(tc.function.name.clone()),
+                });
+                continue;
+            }
+
+            // ── Idempotency: side effects this run already produced ──
+            if let Some(done) =
+                helpers::completed_call(app_handle, &idempotency_config, &idempotency_key, tc).await
+            {
+                warn!(
+                    "[engine] Skipping duplicate {} call ({})",
+                    tc.function.name, idempotency_key
+                );
+                messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Text(crate::engine::idempotency::duplicate_message(
+                        &done,
+                    )),
+                    tool_calls: None,
+                    tool_call_id: Some(tc.id.clone()),
+                    name: Some(tc.function.name.clone()),
+                });
+                continue;
+            }
+
+            // ── Safe mode: only read-only tools run ──
+            if let Some(refusal) = crate::engine::safe_mode::refusal(app_handle, tool_name) {
+                warn!("[engine] Safe mode: blocking '{}'", tool_name);
+                messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Text(refusal),
+                    tool_calls: None,
+                    tool_call_id: Some(tc.id.clone()),
+                    name: Some(tc.function.name.clone()),
+                });
+                continue;
+            }
+
+            // ── Autonomy: tool groups outside the agent's level are refused ──
+            if let Some(policy) = &autonomy {
+                if !policy.allows_tool(tool_name) {
                     warn!(
-                        "[engine] Circuit breaker: blocking '{}' (already failed {} times)",
-                        tc.function.name, count
+                        "[engine] Tool '{}' blocked by autonomy level '{}'",
+                        tool_name,
+                        policy.level.as_str()
                     );
                     messages.push(Message {
                         role: Role::Tool,
                         content: MessageContent::Text(format!(
-                            "Error: Tool '{}' is blocked after {} consecutive failures. Use a different tool or tell the user.",
-                            tc.function.name, count
+                            "Error: Tool '{}' is not available at this agent's autonomy level ({}).",
+                            tc.function.name,
+                            policy.level.as_str()
                         )),
                         tool_calls: None,
                         tool_call_id: Some(tc.id.clone()),
@@ -782,20 +1023,56 @@ pub async fn run_agent_turn(
                 }
             }
 
-            let skip_hil = if auto_approve_all
-                || auto_approved.contains(&tool_name)
-                || user_approved_tools.iter().any(|t| t == &tc.function.name)
+            let policy_approves = match &autonomy {
+                Some(policy) => policy.auto_approves(tool_name),
+                None => auto_approve_all || auto_approved.contains(&tool_name),
+            };
+
+            let skip_hil = if tool_metadata::always_reviewed(&tc.function.name) {
+                // Public posts are never auto-approved
+                false
+            } else if tool_metadata::needs_consent(&tc.function.name) {
+                // Screen capture: asked every time unless consented for this session
+                helpers::has_session_consent(app_handle, session_id)
+            } else if policy_approves || user_approved_tools.iter().any(|t| t == &tc.function.name)
             {
                 true
             } else if is_trading_dangerous {
                 check_trading_auto_approve(&tc.function.name, &tc.function.arguments, app_handle)
+                    .await
             } else {
                 false
             };
 
+            // ── Outbox: park outbound messages for batch review ──
+            if let Some(queued) =
+                helpers::queue_in_outbox(tc, app_handle, session_id, agent_id, !skip_hil).await
+            {
+                let _ = app_handle.emit(
+                    "engine-event",
+                    EngineEvent::ToolResultEvent {
+                        session_id: session_id.to_string(),
+                        run_id: run_id.to_string(),
+                        tool_call_id: tc.id.clone(),
+                        output: queued.output.clone(),
+                        success: true,
+                        error_kind: None,
+                        duration_ms: None,
+                    },
+                );
+                messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Text(queued.output),
+                    tool_calls: None,
+                    tool_call_id: Some(tc.id.clone()),
+                    name: Some(tc.function.name.clone()),
+                });
+                continue;
+            }
+
             let approved = if skip_hil {
                 // Distinguish agent-level auto-approve from safe-tool auto-approve in logs
-                if auto_approve_all && !auto_approved.contains(&tool_name) {
+                if policy_approves && !auto_approved.contains(&tool_name) {
                     info!(
                         "[engine] Tool auto-approved (agent policy): {}",
                         tc.function.name
@@ -808,6 +1085,7 @@ pub async fn run_agent_turn(
                             run_id: run_id.to_string(),
                             tool_name: tc.function.name.clone(),
                             tool_call_id: tc.id.clone(),
+                            autonomy: autonomy_label.clone(),
                         },
                     );
                 } else {
@@ -834,18 +1112,25 @@ pub async fn run_agent_turn(
                         round_number: Some(round + 1),
                         loaded_tools: None,
                         context_tokens: None,
+                        autonomy: autonomy_label.clone(),
                     },
                 );
 
                 // Wait for user approval (with timeout)
                 let timeout_duration = Duration::from_secs(tool_timeout_secs);
-                match tokio::time::timeout(timeout_duration, approval_rx).await {
-                    Ok(Ok(allowed)) => allowed,
-                    Ok(Err(_)) => {
+                match cancel::or_cancelled(tokio::time::timeout(timeout_duration, approval_rx))
+                    .await
+                {
+                    None => {
+                        pending_approvals.lock().remove(&tc.id);
+                        false
+                    }
+                    Some(Ok(Ok(allowed))) => allowed,
+                    Some(Ok(Err(_))) => {
                         warn!("[engine] Approval channel closed for {}", tc.id);
                         false
                     }
-                    Err(_) => {
+                    Some(Err(_)) => {
                         warn!(
                             "[engine] Approval timeout ({}s) for tool {}",
                             tool_timeout_secs, tc.function.name
@@ -858,6 +1143,11 @@ pub async fn run_agent_turn(
                 }
             };
 
+            if cancel_token.is_cancelled() {
+                helpers::skip_cancelled_call(messages, tc);
+                continue;
+            }
+
             if !approved {
                 info!(
                     "[engine] Tool DENIED by user: {} id={}",
@@ -865,14 +1155,20 @@ pub async fn run_agent_turn(
                 );
 
                 // Audit: log tool denial
-                if let Some(es) = app_handle.try_state::<crate::engine::state::EngineState>() {
-                    crate::engine::audit::log_tool_denied(
-                        &es.store,
-                        agent_id,
-                        session_id,
-                        &tc.function.name,
-                        &tc.id,
-                    );
+                {
+                    let (agent, session, call) =
+                        (agent_id.to_string(), session_id.to_string(), tc.clone());
+                    let _ = with_store(app_handle, move |store| {
+                        crate::engine::audit::log_tool_denied(
+                            store,
+                            &agent,
+                            &session,
+                            &call.function.name,
+                            &call.id,
+                        );
+                        Ok(())
+                    })
+                    .await;
                 }
 
                 // Emit denial as tool result
@@ -884,6 +1180,7 @@ pub async fn run_agent_turn(
                         tool_call_id: tc.id.clone(),
                         output: "Tool execution denied by user.".into(),
                         success: false,
+                        error_kind: Some(ToolErrorKind::Permission),
                         duration_ms: None,
                     },
                 );
@@ -901,10 +1198,34 @@ pub async fn run_agent_turn(
 
             // Execute the tool (pass agent_id so tools know which agent is calling)
             let tool_timer = telem::ToolTimer::start(&tc.function.name);
-            let result = tools::execute_tool(tc, app_handle, agent_id).await;
+            let result = if tc.function.name == "schedule_followup" {
+                // Needs the session id, which the generic dispatcher doesn't carry
+                helpers::followup_tool_result(tc, app_handle, session_id, agent_id)
+            } else if tc.function.name == "handoff_to_agent" {
+                helpers::handoff_tool_result(tc, app_handle, session_id, run_id, agent_id)
+            } else if tc.function.name == "screen_capture" {
+                let (result, image) = helpers::screen_capture_result(tc).await;
+                capture_images.extend(image);
+                result
+            } else {
+                cancel::or_cancelled(tools::execute_tool(tc, app_handle, agent_id))
+                    .await
+                    .unwrap_or_else(|| helpers::cancelled_tool_result(tc))
+            };
             let tool_ms = tool_timer.finish(&telem_collector, &telem_root_id, result.success);
             tool_duration_total_ms += tool_ms;
             tool_call_count += 1;
+            if result.success {
+                helpers::record_completed_call(
+                    app_handle,
+                    &idempotency_config,
+                    &idempotency_key,
+                    tc,
+                    session_id,
+                    &result.output,
+                )
+                .await;
+            }
 
             info!(
                 "[engine] Tool result: {} success={} output_len={}",
@@ -913,18 +1234,30 @@ pub async fn run_agent_turn(
                 result.output.len()
             );
 
-            // Audit: log tool execution result
-            if let Some(es) = app_handle.try_state::<crate::engine::state::EngineState>() {
-                crate::engine::audit::log_tool_call(
-                    &es.store,
-                    agent_id,
-                    session_id,
-                    &tc.function.name,
-                    &tc.id,
-                    &tc.function.arguments,
-                    result.success,
-                    &result.output,
-                );
+            // Audit: log tool execution result (signed and chained, so it
+            // reads the chain head — kept off the runtime worker)
+            {
+                let (agent, session, call) =
+                    (agent_id.to_string(), session_id.to_string(), tc.clone());
+                let (success, output) = (result.success, result.output.clone());
+                let _ = with_store(app_handle, move |store| {
+                    crate::engine::audit::log_tool_call(
+                        store,
+                        &agent,
+                        &session,
+                        &call.function.name,
+                        &call.id,
+                        &call.function.arguments,
+                        success,
+                        &output,
+                    );
+                    Ok(())
+                })
+                .await;
+            }
+
+            if result.success {
+                provenance.record(&tc.function.name, &tc.function.arguments, &result.output);
             }
 
             // Emit tool result event
@@ -936,80 +1269,83 @@ pub async fn run_agent_turn(
                     tool_call_id: tc.id.clone(),
                     output: result.output.clone(),
                     success: result.success,
+                    error_kind: result.error_kind,
                     duration_ms: Some(tool_ms),
                 },
             );
 
+            // Oversized results are condensed by the worker model (raw output
+            // kept as a workspace artifact) before entering the context.
+            let context_output = tools::worker_delegate::summarize::summarize_oversized_result(
+                app_handle,
+                agent_id,
+                tc,
+                &result.output,
+            )
+            .await
+            .unwrap_or_else(|| result.output.clone());
+
             // Add tool result to message history
             messages.push(Message {
                 role: Role::Tool,
-                content: MessageContent::Text(result.output.clone()),
+                content: MessageContent::Text(context_output),
                 tool_calls: None,
                 tool_call_id: Some(tc.id.clone()),
                 name: Some(tc.function.name.clone()),
             });
 
             // ── Circuit breaker: track consecutive failures per tool ──
-            if !result.success {
-                let count = tool_fail_counter
-                    .entry(tc.function.name.clone())
-                    .or_insert(0);
-                *count += 1;
-                if *count >= HARD_STOP_TOOL_FAILS {
-                    warn!(
-                        "[engine] Circuit breaker HARD STOP: tool '{}' failed {} consecutive times. Blocking further calls.",
-                        tc.function.name, count
-                    );
-                    messages.push(Message {
-                        role: Role::System,
-                        content: MessageContent::Text(format!(
-                            "[SYSTEM] HARD STOP: The tool '{}' has failed {} times in a row and is now BLOCKED. \
-                            Do NOT call '{}' again — it will not work. \
-                            Instead, tell the user what happened and suggest they check their \
-                            skill configuration or try a different approach. Provide a text summary now.",
-                            tc.function.name, count, tc.function.name
-                        )),
-                        tool_calls: None,
-                        tool_call_id: None,
-                        name: None,
-                    });
-                } else if *count >= MAX_CONSECUTIVE_TOOL_FAILS {
-                    warn!(
-                        "[engine] Circuit breaker: tool '{}' failed {} consecutive times. Injecting stop-retry nudge.",
-                        tc.function.name, count
-                    );
-                    messages.push(Message {
-                        role: Role::System,
-                        content: MessageContent::Text(format!(
-                            "[SYSTEM] The tool '{}' has failed {} times in a row. \
-                            Stop calling '{}' with the same arguments — try a DIFFERENT tool or approach instead. \
-                            Use `request_tools` to discover alternative tools that might work better. \
-                            For example, if google_api failed, try dedicated tools like google_docs_create, \
-                            google_drive_upload, or google_drive_share instead.",
-                            tc.function.name, count, tc.function.name
-                        )),
-                        tool_calls: None,
-                        tool_call_id: None,
-                        name: None,
-                    });
-                }
-            } else {
-                // Reset counter on success
-                tool_fail_counter.remove(&tc.function.name);
+            let nudge = tool_breakers
+                .lock()
+                .entry(session_id.to_string())
+                .or_default()
+                .record(
+                    &tc.function.name,
+                    result.success,
+                    result.error_kind,
+                    Instant::now(),
+                );
+            if let Some(nudge) = nudge {
+                warn!(
+                    "[engine] Circuit breaker{}: tool '{}' failed {} consecutive times ({:?}). \
+                     Injecting nudge.",
+                    if nudge.blocked { " HARD STOP" } else { "" },
+                    tc.function.name,
+                    nudge.failures,
+                    result.error_kind
+                );
+                messages.push(Message {
+                    role: Role::System,
+                    content: MessageContent::Text(nudge.text),
+                    tool_calls: None,
+                    tool_call_id: None,
+                    name: None,
+                });
             }
 
             // ── Phase 4: Record tool transition & predict next tool ───
             // After each tool execution, record the A→B transition in SQLite
             // and predict the next likely tool call for speculative pre-warming.
-            if let Some(es) = app_handle.try_state::<crate::engine::state::EngineState>() {
-                let conn = es.store.conn();
-                let db = conn.lock();
-                if let Some(candidate) = crate::engine::speculative::predict_and_record(
-                    &db,
-                    previous_tool.as_deref(),
-                    &tc.function.name,
-                    &speculation_config,
-                ) {
+            {
+                let (previous, completed, config) = (
+                    previous_tool.clone(),
+                    tc.function.name.clone(),
+                    speculation_config.clone(),
+                );
+                let prediction = with_store(app_handle, move |store| {
+                    let conn = store.conn();
+                    let db = conn.lock();
+                    Ok(crate::engine::speculative::predict_and_record(
+                        &db,
+                        previous.as_deref(),
+                        &completed,
+                        &config,
+                    ))
+                })
+                .await
+                .ok()
+                .flatten();
+                if let Some(candidate) = prediction {
                     speculation_stats.predictions += 1;
                     info!(
                         "[speculative] Predicted next tool: {} (p={:.2})",
@@ -1040,13 +1376,14 @@ pub async fn run_agent_turn(
             // Update previous_tool for the next iteration's transition recording
             previous_tool = Some(tc.function.name.clone());
         }
+        messages.extend(capture_images);
 
         // ── 6. Tool RAG: refresh tools if request_tools was called ─────
-        helpers::refresh_tool_rag(app_handle, tools);
+        helpers::refresh_tool_rag(app_handle, tools).await;
 
         // ── 7. Mid-loop context truncation ─────────────────────────────
         // §24 Checkpoint: snapshot conversation state before truncation destroys messages
-        if let Some(es) = app_handle.try_state::<crate::engine::state::EngineState>() {
+        {
             let checkpoint_msgs: Vec<crate::atoms::engram_types::CheckpointMessage> = messages
                 .iter()
                 .map(|m| crate::atoms::engram_types::CheckpointMessage {
@@ -1065,24 +1402,28 @@ pub async fn run_agent_turn(
                     timestamp: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
                 })
                 .collect();
-            let empty_wm = crate::atoms::engram_types::WorkingMemorySnapshot {
-                agent_id: agent_id.to_string(),
-                slots: vec![],
-                momentum_embeddings: vec![],
-                saved_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-            };
-            let empty_hashes = std::collections::HashMap::new();
-            let req = crate::engine::engram::context_continuity::CaptureCheckpointRequest {
-                agent_id,
-                session_id,
-                messages: &checkpoint_msgs,
-                working_memory: &empty_wm,
-                file_hashes: &empty_hashes,
-                tasks: &[],
-                key_decisions: &[],
-            };
-            if let Err(e) =
-                crate::engine::engram::context_continuity::capture_checkpoint(&es.store, &req)
+            let (agent, session) = (agent_id.to_string(), session_id.to_string());
+            if let Err(e) = with_store(app_handle, move |store| {
+                let empty_wm = crate::atoms::engram_types::WorkingMemorySnapshot {
+                    agent_id: agent.clone(),
+                    slots: vec![],
+                    momentum_embeddings: vec![],
+                    saved_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                };
+                let empty_hashes = std::collections::HashMap::new();
+                let req = crate::engine::engram::context_continuity::CaptureCheckpointRequest {
+                    agent_id: &agent,
+                    session_id: &session,
+                    messages: &checkpoint_msgs,
+                    working_memory: &empty_wm,
+                    file_hashes: &empty_hashes,
+                    tasks: &[],
+                    key_decisions: &[],
+                };
+                crate::engine::engram::context_continuity::capture_checkpoint(store, &req)
+                    .map(|_| ())
+            })
+            .await
             {
                 warn!(
                     "[engine] Failed to capture pre-truncation checkpoint: {}",
@@ -3,15 +3,14 @@
 // Policy-based auto-approval for trading write tools (Coinbase, Solana, EVM DEX).
 // Checks configurable limits (max trade size, daily loss, allowed pairs, transfer caps).
 
-use crate::engine::state::EngineState;
+use crate::engine::state::with_store;
 use crate::engine::types::*;
 use log::info;
-use tauri::Manager;
 
 /// Policy-based auto-approval for trading write tools (all chains: Coinbase, Solana, EVM DEX).
 /// Checks configurable limits (max trade size, daily loss, allowed pairs, transfer caps).
 /// Returns false (requiring HIL) when no policy is configured or limits exceeded.
-pub(crate) fn check_trading_auto_approve(
+pub(crate) async fn check_trading_auto_approve(
     tool_name: &str,
     args_str: &str,
     app_handle: &tauri::AppHandle,
@@ -30,15 +29,21 @@ pub(crate) fn check_trading_auto_approve(
         _ => return false,
     }
 
-    // Load trading policy from engine config
-    let state = match app_handle.try_state::<EngineState>() {
-        Some(s) => s,
-        None => return false,
+    // Load trading policy from engine config, with today's spend
+    let Ok((policy_json, daily_summary)) = with_store(app_handle, |store| {
+        Ok((
+            store.get_config("trading_policy").ok().flatten(),
+            store.daily_trade_summary().ok(),
+        ))
+    })
+    .await
+    else {
+        return false;
     };
 
-    let policy: TradingPolicy = match state.store.get_config("trading_policy") {
-        Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_default(),
-        _ => TradingPolicy::default(),
+    let policy: TradingPolicy = match policy_json {
+        Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+        None => TradingPolicy::default(),
     };
 
     if !policy.auto_approve {
@@ -92,7 +97,7 @@ pub(crate) fn check_trading_auto_approve(
         }
 
         // Check daily spending limit
-        if let Ok(summary) = state.store.daily_trade_summary() {
+        if let Some(summary) = &daily_summary {
             let daily_spent = summary["daily_spent_usd"].as_f64().unwrap_or(0.0);
             if daily_spent + amount > policy.max_daily_loss_usd {
                 info!(
@@ -135,7 +140,7 @@ pub(crate) fn check_trading_auto_approve(
         }
 
         // Check daily spending limit (transfers count toward it too)
-        if let Ok(summary) = state.store.daily_trade_summary() {
+        if let Some(summary) = &daily_summary {
             let daily_spent = summary["daily_spent_usd"].as_f64().unwrap_or(0.0);
             if daily_spent + amount > policy.max_daily_loss_usd {
                 info!(
@@ -0,0 +1,204 @@
+// Paw Agent Engine — Active-window awareness (opt-in)
+//
+// When `EngineConfig::app_context` is on, a background watcher samples the
+// focused application and window title every few seconds and keeps the last
+// one that isn't Paw itself. Chat turns get it as a short "Active Window"
+// section so "summarize this document" can resolve to what the user was
+// looking at before switching to Paw.
+//
+// Privacy: the sample only lives in memory (`EngineState::active_window`) —
+// it is never written to the database or disk — and turning the setting off
+// clears it immediately.
+
+use crate::engine::state::EngineState;
+use log::info;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::Manager;
+
+/// How often the focused window is sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Samples older than this are too stale to describe "what I'm looking at".
+const MAX_AGE_MINUTES: i64 = 15;
+
+/// The last focused application outside Paw.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveWindow {
+    /// Application / process name, e.g. "Preview" or "code".
+    pub app: String,
+    /// Window title, e.g. "quarterly-report.pdf".
+    pub title: String,
+    /// When this window was last seen focused (RFC 3339).
+    pub seen_at: String,
+}
+
+/// Start the background watcher. Idle (no OS calls) while the setting is off.
+pub fn spawn_watcher(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let Some(state) = app_handle.try_state::<EngineState>() else {
+                continue;
+            };
+            if !state.config.lock().app_context {
+                state.active_window.lock().take();
+                continue;
+            }
+            let Some((app, title)) = focused_window().await else {
+                continue;
+            };
+            if is_paw(&app) {
+                continue;
+            }
+            let sample = ActiveWindow {
+                app,
+                title,
+                seen_at: chrono::Utc::now().to_rfc3339(),
+            };
+            // The setting may have been turned off while sampling
+            if state.config.lock().app_context {
+                *state.active_window.lock() = Some(sample);
+            }
+        }
+    });
+    info!("[app-context] Active-window watcher started");
+}
+
+/// The current sample, if the setting is on and the sample is recent.
+pub fn current(state: &EngineState) -> Option<ActiveWindow> {
+    if !state.config.lock().app_context {
+        return None;
+    }
+    let sample = state.active_window.lock().clone()?;
+    let seen = chrono::DateTime::parse_from_rfc3339(&sample.seen_at).ok()?;
+    let age = chrono::Utc::now() - seen.with_timezone(&chrono::Utc);
+    (age <= chrono::Duration::minutes(MAX_AGE_MINUTES)).then_some(sample)
+}
+
+/// Context section for the system prompt, or None when off / nothing seen.
+pub fn context_section(state: &EngineState) -> Option<String> {
+    current(state).map(|w| format_section(&w))
+}
+
+fn format_section(w: &ActiveWindow) -> String {
+    let title = if w.title.trim().is_empty() {
+        "(no window title)".to_string()
+    } else {
+        crate::engine::types::truncate_utf8(w.title.trim(), 200).to_string()
+    };
+    format!(
+        "## Active Window\n\
+        Before switching to Paw the user was looking at: {} — \"{}\".\n\
+        When they say \"this document\", \"this page\" or \"what I'm looking at\", they likely mean this. \
+        You only know the title; read the file or page with your tools, or ask.",
+        w.app, title
+    )
+}
+
+/// Whether the focused app is Paw itself (chatting focuses Paw).
+fn is_paw(app: &str) -> bool {
+    // "Open Pawz Desktop" on macOS/Windows, the `openpawz` binary on Linux
+    app.to_lowercase().contains("pawz")
+}
+
+#[cfg(target_os = "macos")]
+async fn focused_window() -> Option<(String, String)> {
+    let script = r#"tell application "System Events"
+  set p to first application process whose frontmost is true
+  set a to name of p
+  set t to ""
+  try
+    set t to name of front window of p
+  end try
+end tell
+return a & linefeed & t"#;
+    let out = tokio::process::Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .await
+        .ok()?;
+    parse_app_title(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(target_os = "linux")]
+async fn focused_window() -> Option<(String, String)> {
+    // X11 only — Wayland compositors don't expose the focused window
+    let out = tokio::process::Command::new("xdotool")
+        .args(["getactivewindow", "getwindowpid", "getwindowname"])
+        .output()
+        .await
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut lines = text.lines();
+    let pid = lines.next()?.trim();
+    let title = lines.next().unwrap_or_default();
+    let app = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    parse_app_title(&format!("{}\n{}", app.trim(), title))
+}
+
+#[cfg(target_os = "windows")]
+async fn focused_window() -> Option<(String, String)> {
+    let script = r#"Add-Type @"
+using System; using System.Text; using System.Runtime.InteropServices;
+public static class PawFg {
+  [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+  [DllImport("user32.dll")] public static extern int GetWindowText(IntPtr h, StringBuilder s, int n);
+  [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr h, out uint pid);
+}
+"@
+$h = [PawFg]::GetForegroundWindow()
+$sb = New-Object System.Text.StringBuilder 512
+[PawFg]::GetWindowText($h, $sb, 512) | Out-Null
+$procId = 0
+[PawFg]::GetWindowThreadProcessId($h, [ref]$procId) | Out-Null
+(Get-Process -Id $procId).ProcessName
+$sb.ToString()"#;
+    let out = tokio::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+        .await
+        .ok()?;
+    parse_app_title(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+async fn focused_window() -> Option<(String, String)> {
+    None
+}
+
+/// "app\ntitle" → (app, title). None when there's no app name.
+fn parse_app_title(output: &str) -> Option<(String, String)> {
+    let mut lines = output.lines();
+    let app = lines.next()?.trim();
+    if app.is_empty() {
+        return None;
+    }
+    let title = lines.next().unwrap_or_default().trim();
+    Some((app.to_string(), title.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_app_and_title() {
+        assert_eq!(
+            parse_app_title("Preview\nreport.pdf\n"),
+            Some(("Preview".into(), "report.pdf".into()))
+        );
+        assert_eq!(
+            parse_app_title("Finder\n"),
+            Some(("Finder".into(), String::new()))
+        );
+        assert_eq!(parse_app_title("\n"), None);
+    }
+
+    #[test]
+    fn skips_paw_itself() {
+        assert!(is_paw("Open Pawz Desktop"));
+        assert!(is_paw("openpawz"));
+        assert!(!is_paw("Preview"));
+    }
+}
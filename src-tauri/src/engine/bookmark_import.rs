@@ -0,0 +1,156 @@
+// Paw Agent Engine — Bookmark & History Import
+//
+// Indexes pages the user has already curated in their browser so a new
+// agent starts out knowing them. Bookmarks (and optionally frequently
+// visited history) are read from the local Chrome or Firefox profile,
+// each page is fetched and reduced to readable text, and the result is
+// stored through the research bridge as a `ResearchDiscovery` memory that
+// cites the page URL.
+//
+// Re-running an import skips URLs that are already in memory. A page that
+// can't be fetched is still indexed by title, URL and folder.
+//
+// Progress is reported as `bookmark-import-progress` events.
+
+use crate::engine::bookmarks::{self, Bookmark, Browser};
+use crate::engine::engram::research_bridge::{self, ResearchFinding};
+use crate::engine::state::EngineState;
+use crate::engine::types::{truncate_utf8, BookmarkImportOptions, BookmarkImportReport};
+use log::{info, warn};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// Page text kept per bookmark — enough for the gist and key terms without
+/// flooding memory with whole articles.
+const MAX_PAGE_BYTES: usize = 8_000;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub async fn import_bookmarks(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    options: &BookmarkImportOptions,
+) -> Result<BookmarkImportReport, String> {
+    let browser: Browser = options.browser.parse()?;
+    let profile = match &options.profile_path {
+        Some(p) => PathBuf::from(p),
+        None => bookmarks::default_profile_dir(browser).ok_or_else(|| {
+            format!(
+                "No {} profile found — pass the profile directory explicitly",
+                browser.as_str()
+            )
+        })?,
+    };
+    let history = options.include_history.then(|| {
+        (
+            options.history_min_visits.unwrap_or(5),
+            options.history_limit.unwrap_or(200),
+        )
+    });
+
+    let mut marks =
+        bookmarks::read_profile(browser, &profile, history).map_err(|e| e.to_string())?;
+    if let Some(prefix) = options.folder.as_deref().map(str::to_lowercase) {
+        marks.retain(|b| b.folder.to_lowercase().starts_with(&prefix));
+    }
+    if let Some(limit) = options.limit {
+        marks.truncate(limit);
+    }
+
+    let mut report = BookmarkImportReport {
+        browser: browser.as_str().into(),
+        profile_path: profile.display().to_string(),
+        found: marks.len(),
+        ..Default::default()
+    };
+    info!(
+        "[bookmarks] Importing {} page(s) from {} profile {}",
+        marks.len(),
+        browser.as_str(),
+        report.profile_path
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let agent_id = options.agent_id.as_deref().unwrap_or("default");
+    let total = marks.len();
+
+    for (i, mark) in marks.iter().enumerate() {
+        let _ = app_handle.emit(
+            "bookmark-import-progress",
+            serde_json::json!({ "done": i, "total": total, "url": mark.url }),
+        );
+        match state.store.engram_has_research_url(&mark.url) {
+            Ok(true) => {
+                report.already_indexed += 1;
+                continue;
+            }
+            Ok(false) => {}
+            Err(e) => warn!("[bookmarks] Lookup failed for {}: {}", mark.url, e),
+        }
+
+        let page = if options.titles_only {
+            None
+        } else {
+            match crate::engine::web::fetch_readable(&client, &mark.url).await {
+                Ok(page) => Some(page),
+                Err(e) => {
+                    report.fetch_failed += 1;
+                    report.errors.push(format!("{}: {}", mark.url, e));
+                    None
+                }
+            }
+        };
+
+        let finding = ResearchFinding {
+            content: page_content(mark, page),
+            source_url: Some(mark.url.clone()),
+            query: if mark.folder == "History" {
+                "browser history".into()
+            } else {
+                format!("bookmark: {}", mark.folder)
+            },
+            category: "reference".into(),
+            agent_id: agent_id.to_string(),
+        };
+        match research_bridge::ingest_findings(&state.store, &[finding]) {
+            Ok(r) => report.indexed += r.stored,
+            Err(e) => report.errors.push(format!("{}: {}", mark.url, e)),
+        }
+    }
+
+    let _ = app_handle.emit(
+        "bookmark-import-progress",
+        serde_json::json!({ "done": total, "total": total }),
+    );
+    info!(
+        "[bookmarks] Done: {} indexed, {} already indexed, {} fetch failure(s)",
+        report.indexed, report.already_indexed, report.fetch_failed
+    );
+    Ok(report)
+}
+
+/// Title, URL and folder first so the memory is useful even when the page
+/// text is missing or cut short.
+fn page_content(mark: &Bookmark, page: Option<(String, String)>) -> String {
+    let (page_title, text) = page.unwrap_or_default();
+    let title = if mark.title.trim().is_empty() {
+        page_title.as_str()
+    } else {
+        mark.title.as_str()
+    };
+    let mut content = format!("{}\n{}", title.trim(), mark.url);
+    if mark.folder != "History" && !mark.folder.is_empty() {
+        content.push_str(&format!("\nBookmarked in: {}", mark.folder));
+    }
+    let text = text.trim();
+    if !text.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(truncate_utf8(text, MAX_PAGE_BYTES));
+    }
+    content
+}
@@ -4,6 +4,7 @@
 
 use super::PendingUser;
 use crate::atoms::error::EngineResult;
+use crate::engine::notifications::{self, Notice};
 use crate::engine::state::EngineState;
 use log::info;
 use tauri::Manager;
@@ -127,6 +128,22 @@ pub fn remove_user_generic(
     Ok(())
 }
 
+/// Tell the user someone on `channel` asked to pair (category "pairing").
+pub fn notify_pairing(app_handle: &tauri::AppHandle, channel: &str, who: &str) {
+    notifications::notify(
+        app_handle,
+        Notice::new(
+            notifications::PAIRING,
+            format!("Pairing request on {}", channel),
+            format!(
+                "{} wants to talk to your agent — approve or deny in Channels",
+                who
+            ),
+        )
+        .navigate_to("channels"),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
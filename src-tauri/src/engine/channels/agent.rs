@@ -9,14 +9,28 @@ use crate::engine::chat as chat_org;
 use crate::engine::engram;
 use crate::engine::injection;
 use crate::engine::memory;
+use crate::engine::notifications::{self, Notice};
 use crate::engine::providers::AnyProvider;
 use crate::engine::state::{
-    normalize_model_name, resolve_provider_for_model, EngineState, PendingApprovals,
+    normalize_model_name, resolve_provider_for_model, with_store, with_store_async, EngineState,
+    PendingApprovals,
 };
+use crate::engine::translate;
 use crate::engine::types::*;
 use log::{error, info, warn};
 use tauri::Manager;
 
+tokio::task_local! {
+    static RUN_ID: String;
+}
+
+/// Run `fut` with `run_id` as the id of the channel agent runs inside it.
+/// A journaled message that is replayed after a crash runs with the same
+/// id, so its side-effect tool calls keep their idempotency keys.
+pub async fn with_run_id<F: std::future::Future>(run_id: String, fut: F) -> F::Output {
+    RUN_ID.scope(run_id, fut).await
+}
+
 /// Run a user message through the agent loop and return the text response.
 /// This is the shared core that every channel bridge calls after receiving a message.
 ///
@@ -37,6 +51,9 @@ pub async fn run_channel_agent(
     let engine_state = app_handle
         .try_state::<EngineState>()
         .ok_or("Engine not initialized")?;
+    if crate::engine::shutdown::is_shutting_down() {
+        return Err("Paw is shutting down — please resend in a moment".into());
+    }
 
     // ── Prompt injection scan ──────────────────────────────────────
     let scan = injection::scan_for_injection(message);
@@ -48,6 +65,18 @@ pub async fn run_channel_agent(
                 "[{}] Blocked critical injection from user {}",
                 channel_prefix, user_id
             );
+            notifications::notify(
+                app_handle,
+                Notice::new(
+                    notifications::SECURITY,
+                    format!("Blocked a prompt injection on {}", channel_prefix),
+                    format!(
+                        "A message from {} was blocked by the security scanner.",
+                        user_id
+                    ),
+                )
+                .critical(),
+            );
             return Ok("Your message was blocked by the security scanner. If this is a mistake, please rephrase.".into());
         }
     }
@@ -55,6 +84,78 @@ pub async fn run_channel_agent(
     // Per-user per-agent session: eng-{channel}-{agent}-{user_id}
     let session_id = format!("eng-{}-{}-{}", channel_prefix, agent_id, user_id);
 
+    // ── Slash commands (same registry as the desktop chat) ─────────
+    let expanded_prompt;
+    let message =
+        match crate::engine::slash_commands::dispatch(app_handle, &session_id, agent_id, message)
+            .await
+        {
+            Some(crate::engine::slash_commands::SlashOutcome::Reply(text)) => return Ok(text),
+            Some(crate::engine::slash_commands::SlashOutcome::Prompt(prompt)) => {
+                expanded_prompt = prompt;
+                expanded_prompt.as_str()
+            }
+            None => message,
+        };
+
+    // ── Auto-translate (per bridge, see engine::translate) ─────────
+    // reply_in_kind: tell the agent which language to answer in.
+    // pivot: the agent sees (and answers) the pivot language; the reply is
+    // translated back into the user's language before it is returned.
+    let translate_settings =
+        with_store(app_handle, |store| Ok(translate::load_settings(store))).await?;
+    let translate_mode = translate_settings.mode_for(channel_prefix);
+    let user_language = match translate_mode {
+        translate::AutoTranslate::Off => None,
+        _ => translate::detect_language(message),
+    };
+    let mut translate_back: Option<&str> = None;
+    let (translated_message, translated_context);
+    let (message, channel_context) = match (translate_mode, user_language) {
+        (translate::AutoTranslate::ReplyInKind, Some(lang)) => {
+            translated_context = format!(
+                "{}\n\n{}",
+                channel_context,
+                translate::reply_in_kind_instruction(lang)
+            );
+            (message, translated_context.as_str())
+        }
+        (translate::AutoTranslate::Pivot, Some(lang))
+            if !translate::same_language(lang, &translate_settings.pivot_language) =>
+        {
+            match crate::engine::tools::translate::translate_text(
+                &engine_state,
+                message,
+                &translate_settings.pivot_language,
+                Some(lang),
+            )
+            .await
+            {
+                Ok(text) => {
+                    translate_back = Some(lang);
+                    translated_message = text;
+                    (translated_message.as_str(), channel_context)
+                }
+                Err(e) => {
+                    warn!(
+                        "[{}] Auto-translate into {} failed, passing the message through: {}",
+                        channel_prefix, translate_settings.pivot_language, e
+                    );
+                    (message, channel_context)
+                }
+            }
+        }
+        _ => (message, channel_context),
+    };
+    let session_model = {
+        let session_id = session_id.clone();
+        with_store(app_handle, move |store| {
+            store.get_session_model(&session_id)
+        })
+        .await
+        .unwrap_or(None)
+    };
+
     // Get provider config — channel bridges use the DEFAULT model (not worker_model).
     // Channel bridges handle complex multi-step tasks (creating 15+ Discord channels,
     // managing permissions, etc.) that require a capable model. The worker_model is
@@ -69,13 +170,17 @@ pub async fn run_channel_agent(
         // Use "channel" role — falls through to the default model since there's
         // no channel-specific override in model routing. Users can add one in
         // agent_models if they want a specific model for a specific agent.
-        let model = normalize_model_name(&cfg.model_routing.resolve(
-            agent_id,
-            "channel",
-            "",
-            &default_model,
-        ))
-        .to_string();
+        // A model pinned with /model wins over routing.
+        let model = match session_model {
+            Some(ref pinned) => normalize_model_name(pinned).to_string(),
+            None => normalize_model_name(&cfg.model_routing.resolve(
+                agent_id,
+                "channel",
+                "",
+                &default_model,
+            ))
+            .to_string(),
+        };
         let provider = resolve_provider_for_model(&model, &cfg.providers)
             .or_else(|| {
                 cfg.default_provider
@@ -99,68 +204,6 @@ pub async fn run_channel_agent(
         )
     };
 
-    // Ensure session exists
-    let session_exists = engine_state
-        .store
-        .get_session(&session_id)
-        .map(|opt| opt.is_some())
-        .unwrap_or(false);
-    if !session_exists {
-        engine_state.store.create_session(
-            &session_id,
-            &model,
-            system_prompt.as_deref(),
-            Some(agent_id),
-        )?;
-    } else {
-        // Check if the previous conversation is poisoned by:
-        // 1. Failed tool-call loops (all tool calls, no useful text)
-        // 2. Accumulated error patterns from empty responses / fallbacks
-        // 3. Malformed tool calls or model confusion
-        // In any of these cases, clear the session to start fresh.
-        let recent = engine_state
-            .store
-            .load_conversation(&session_id, None, Some(2_000), Some(agent_id))
-            .unwrap_or_default();
-
-        let should_clear = if recent.len() >= 4 {
-            let last_msgs: Vec<&Message> = recent.iter().rev().take(6).collect();
-            // Detect tool-call spam: all recent messages are tool/system with no user-visible text
-            let all_tool_spam = last_msgs.iter().all(|m| {
-                m.role == Role::Tool
-                    || m.role == Role::System
-                    || (m.role == Role::Assistant && m.tool_calls.is_some())
-            });
-            // Detect error / empty-response accumulation across recent history
-            let error_count = last_msgs
-                .iter()
-                .filter(|m| {
-                    if m.role != Role::Assistant {
-                        return false;
-                    }
-                    let text = m.content.as_text_ref();
-                    text.contains("wasn't able to generate")
-                        || text.contains("empty response")
-                        || text.contains("[MALFORMED_TOOL_CALL]")
-                        || text.contains("start a new session")
-                        || text.contains("content filter was triggered")
-                        || (text.len() < 30 && text.trim().is_empty())
-                })
-                .count();
-            all_tool_spam || error_count >= 2
-        } else {
-            false
-        };
-
-        if should_clear {
-            info!(
-                "[{}] Session {} appears poisoned. Clearing history for fresh start.",
-                channel_prefix, session_id
-            );
-            let _ = engine_state.store.clear_messages(&session_id);
-        }
-    }
-
     // Store user message
     let user_msg = StoredMessage {
         id: uuid::Uuid::new_v4().to_string(),
@@ -172,16 +215,91 @@ pub async fn run_channel_agent(
         name: None,
         created_at: chrono::Utc::now().to_rfc3339(),
     };
-    engine_state.store.add_message(&user_msg)?;
+
+    // Ensure the session exists (clearing a poisoned history) and store the
+    // user message, on the blocking pool rather than the bridge's worker
+    {
+        let session_id = session_id.clone();
+        let model = model.clone();
+        let system_prompt = system_prompt.clone();
+        let agent_id = agent_id.to_string();
+        let channel_prefix = channel_prefix.to_string();
+        with_store(app_handle, move |store| {
+            let session_exists = store
+                .get_session(&session_id)
+                .map(|opt| opt.is_some())
+                .unwrap_or(false);
+            if !session_exists {
+                store.create_session(
+                    &session_id,
+                    &model,
+                    system_prompt.as_deref(),
+                    Some(&agent_id),
+                )?;
+            } else {
+                // Check if the previous conversation is poisoned by:
+                // 1. Failed tool-call loops (all tool calls, no useful text)
+                // 2. Accumulated error patterns from empty responses / fallbacks
+                // 3. Malformed tool calls or model confusion
+                // In any of these cases, clear the session to start fresh.
+                let recent = store
+                    .load_conversation(&session_id, None, Some(2_000), Some(&agent_id))
+                    .unwrap_or_default();
+
+                let should_clear = if recent.len() >= 4 {
+                    let last_msgs: Vec<&Message> = recent.iter().rev().take(6).collect();
+                    // Detect tool-call spam: all recent messages are tool/system with no user-visible text
+                    let all_tool_spam = last_msgs.iter().all(|m| {
+                        m.role == Role::Tool
+                            || m.role == Role::System
+                            || (m.role == Role::Assistant && m.tool_calls.is_some())
+                    });
+                    // Detect error / empty-response accumulation across recent history
+                    let error_count = last_msgs
+                        .iter()
+                        .filter(|m| {
+                            if m.role != Role::Assistant {
+                                return false;
+                            }
+                            let text = m.content.as_text_ref();
+                            text.contains("wasn't able to generate")
+                                || text.contains("empty response")
+                                || text.contains("[MALFORMED_TOOL_CALL]")
+                                || text.contains("start a new session")
+                                || text.contains("content filter was triggered")
+                                || (text.len() < 30 && text.trim().is_empty())
+                        })
+                        .count();
+                    all_tool_spam || error_count >= 2
+                } else {
+                    false
+                };
+
+                if should_clear {
+                    info!(
+                        "[{}] Session {} appears poisoned. Clearing history for fresh start.",
+                        channel_prefix, session_id
+                    );
+                    let _ = store.clear_messages(&session_id);
+                }
+            }
+            store.add_message(&user_msg)
+        })
+        .await?;
+    }
 
     // Load core soul files (IDENTITY.md, SOUL.md, USER.md) — lean identity only.
     // We do NOT load compose_agent_context() here — it includes ALL agent files
     // (IDENTITY, SOUL, USER, AGENTS, TOOLS, custom files) which can add ~10K chars
     // of irrelevant context about other agents, coding tools, etc.
-    let core_context = engine_state
-        .store
-        .compose_core_context(agent_id)
-        .unwrap_or(None);
+    let core_context = {
+        let agent_id = agent_id.to_string();
+        with_store(app_handle, move |store| {
+            store.compose_core_context(&agent_id)
+        })
+        .await
+        .unwrap_or(None)
+    };
     if let Some(ref cc) = core_context {
         info!(
             "[{}] Core soul context loaded ({} chars) for agent '{}'",
@@ -227,29 +345,37 @@ pub async fn run_channel_agent(
         } else {
             cognitive.working_memory.momentum().to_vec()
         };
-        let mom_ref: Option<&[Vec<f32>]> = if mom_vecs.is_empty() {
-            None
-        } else {
-            Some(&mom_vecs)
-        };
         drop(cognitive);
 
-        match engram::gated_search::gated_search(
-            &engine_state.store,
-            &engram::gated_search::GatedSearchRequest {
-                query: message,
-                scope: &scope,
-                config: &config,
-                embedding_client: emb_client.as_ref(),
-                budget_tokens: 8_000, // lightweight budget for channels
-                momentum: mom_ref,
-                model: Some(&model), // per-model injection limits (§58.5)
-                capability: read_cap.as_ref(),
-                hnsw_index: Some(&engine_state.hnsw_index),
-            },
-        )
-        .await
-        {
+        // Recall queries the store between embedding calls — run it off
+        // the runtime worker
+        let (query, search_model) = (message.to_string(), model.clone());
+        let search = with_store_async(app_handle, move |state| {
+            Box::pin(async move {
+                let mom_ref: Option<&[Vec<f32>]> = if mom_vecs.is_empty() {
+                    None
+                } else {
+                    Some(&mom_vecs)
+                };
+                engram::gated_search::gated_search(
+                    &state.store,
+                    &engram::gated_search::GatedSearchRequest {
+                        query: &query,
+                        scope: &scope,
+                        config: &config,
+                        embedding_client: emb_client.as_ref(),
+                        budget_tokens: 8_000, // lightweight budget for channels
+                        momentum: mom_ref,
+                        model: Some(&search_model), // per-model injection limits (§58.5)
+                        capability: read_cap.as_ref(),
+                        hnsw_index: Some(&state.hnsw_index),
+                    },
+                )
+                .await
+            })
+        })
+        .await;
+        match search {
             Ok(result) if !result.memories.is_empty() => {
                 info!(
                     "[{}] Engram gated recall: {} memories for agent '{}'",
@@ -283,12 +409,18 @@ pub async fn run_channel_agent(
     };
 
     let provider_name = format!("{:?}", provider_config.kind);
-    let user_tz = {
+    let (user_tz, home_location) = {
         let cfg = engine_state.config.lock();
-        cfg.user_timezone.clone()
+        (cfg.user_timezone.clone(), cfg.home_location.clone())
     };
-    let runtime_ctx =
-        chat_org::build_runtime_context(&model, &provider_name, &session_id, agent_id, &user_tz);
+    let runtime_ctx = chat_org::build_runtime_context(
+        &model,
+        &provider_name,
+        &session_id,
+        agent_id,
+        &user_tz,
+        home_location.as_ref(),
+    );
 
     let discipline_text = "## Conversation Discipline\n\
         - **Act immediately.** When the user asks you to do something, start doing it with your tools right now. Don't ask for confirmation.\n\
@@ -379,12 +511,20 @@ pub async fn run_channel_agent(
         let channel_cap = model_window / 2;
         std::cmp::min(cfg.context_window_tokens, channel_cap.max(16_000))
     };
-    let mut messages = engine_state.store.load_conversation(
-        &session_id,
-        full_system_prompt.as_deref(),
-        Some(context_window),
-        Some(agent_id),
-    )?;
+    let mut messages = {
+        let session_id = session_id.clone();
+        let system_prompt = full_system_prompt.clone();
+        let agent_id = agent_id.to_string();
+        with_store(app_handle, move |store| {
+            store.load_conversation(
+                &session_id,
+                system_prompt.as_deref(),
+                Some(context_window),
+                Some(&agent_id),
+            )
+        })
+        .await?
+    };
 
     // Build tools — CHANNEL WHITELIST.
     //
@@ -449,7 +589,9 @@ pub async fn run_channel_agent(
     };
 
     let provider = AnyProvider::from_config(&provider_config);
-    let run_id = uuid::Uuid::new_v4().to_string();
+    let run_id = RUN_ID
+        .try_with(String::clone)
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
 
     // Channel bridge tool policy: deny side-effect tools that the agent loop
     // flags for HIL approval. Read-only tools are already auto-approved by the
@@ -571,7 +713,6 @@ pub async fn run_channel_agent(
                     // Reset messages to pre-loop state for retry
                     messages.truncate(pre_loop_msg_count);
 
-                    let fb_run_id = uuid::Uuid::new_v4().to_string();
                     match agent_loop::run_agent_turn(
                         app_handle,
                         &fb_provider,
@@ -579,7 +720,9 @@ pub async fn run_channel_agent(
                         &mut messages,
                         &mut tools,
                         &session_id,
-                        &fb_run_id,
+                        // Same run id: side effects the failed attempt already
+                        // produced are not repeated (idempotency keys)
+                        &run_id,
                         max_rounds,
                         None,
                         &approvals,
@@ -620,9 +763,10 @@ pub async fn run_channel_agent(
     auto_approver.abort();
 
     // Store new messages from the agent turn
+    let mut new_messages = Vec::new();
     for msg in messages.iter().skip(pre_loop_msg_count) {
         if msg.role == Role::Assistant || msg.role == Role::Tool {
-            let stored = StoredMessage {
+            new_messages.push(StoredMessage {
                 id: uuid::Uuid::new_v4().to_string(),
                 session_id: session_id.clone(),
                 role: match msg.role {
@@ -638,12 +782,19 @@ pub async fn run_channel_agent(
                 tool_call_id: msg.tool_call_id.clone(),
                 name: msg.name.clone(),
                 created_at: chrono::Utc::now().to_rfc3339(),
-            };
-            if let Err(e) = engine_state.store.add_message(&stored) {
-                error!("[{}] Failed to store message: {}", channel_prefix, e);
-            }
+            });
         }
     }
+    let prefix = channel_prefix.to_string();
+    let _ = with_store(app_handle, move |store| {
+        for stored in &new_messages {
+            if let Err(e) = store.add_message(stored) {
+                error!("[{}] Failed to store message: {}", prefix, e);
+            }
+        }
+        Ok(())
+    })
+    .await;
 
     // Auto-capture memories (with dedup)
     if let Ok(final_text) = &result {
@@ -660,42 +811,75 @@ pub async fn run_channel_agent(
             let facts = memory::extract_memorable_facts_heuristic(message, final_text);
             if !facts.is_empty() {
                 let emb_client = engine_state.embedding_client();
-                for (content, category) in &facts {
-                    // Legacy memory store
-                    match memory::store_memory_dedup(
-                        &engine_state.store,
-                        content,
-                        category,
-                        5,
-                        emb_client.as_ref(),
-                        None,
-                    )
-                    .await
-                    {
-                        Ok(Some(_)) => {}
-                        Ok(None) => info!("[channel-agent] Skipped duplicate memory"),
-                        Err(e) => warn!("[channel-agent] Memory store failed: {}", e),
-                    }
+                let (agent_id, session_id, channel_prefix, user_id) = (
+                    agent_id.to_string(),
+                    session_id.clone(),
+                    channel_prefix.to_string(),
+                    user_id.to_string(),
+                );
+                let _ = with_store_async(app_handle, move |state| {
+                    Box::pin(async move {
+                        for (content, category) in &facts {
+                            // Legacy memory store
+                            match memory::store_memory_dedup(
+                                &state.store,
+                                content,
+                                category,
+                                5,
+                                emb_client.as_ref(),
+                                None,
+                            )
+                            .await
+                            {
+                                Ok(Some(_)) => {}
+                                Ok(None) => info!("[channel-agent] Skipped duplicate memory"),
+                                Err(e) => warn!("[channel-agent] Memory store failed: {}", e),
+                            }
+
+                            // Engram three-tier store (with channel/user scope)
+                            let _ = engram::bridge::store_auto_capture(
+                                &state.store,
+                                content,
+                                category,
+                                emb_client.as_ref(),
+                                Some(&agent_id),
+                                Some(&session_id),
+                                Some(&channel_prefix),
+                                Some(&user_id),
+                                Some(&state.hnsw_index),
+                            )
+                            .await;
+                        }
+                        Ok(())
+                    })
+                })
+                .await;
+            }
+        }
+    }
 
-                    // Engram three-tier store (with channel/user scope)
-                    let _ = engram::bridge::store_auto_capture(
-                        &engine_state.store,
-                        content,
-                        category,
-                        emb_client.as_ref(),
-                        Some(agent_id),
-                        Some(&session_id),
-                        Some(channel_prefix),
-                        Some(user_id),
-                        Some(&engine_state.hnsw_index),
-                    )
-                    .await;
+    match (result, translate_back) {
+        (Ok(reply), Some(lang)) if !reply.trim().is_empty() => {
+            match crate::engine::tools::translate::translate_text(
+                &engine_state,
+                &reply,
+                lang,
+                Some(&translate_settings.pivot_language),
+            )
+            .await
+            {
+                Ok(translated) => Ok(translated),
+                Err(e) => {
+                    warn!(
+                        "[{}] Auto-translate of the reply into {} failed: {}",
+                        channel_prefix, lang, e
+                    );
+                    Ok(reply)
                 }
             }
         }
+        (result, _) => result,
     }
-
-    result
 }
 
 // ── Utility ────────────────────────────────────────────────────────────
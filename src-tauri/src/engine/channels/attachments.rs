@@ -0,0 +1,223 @@
+// Paw Agent Engine — Channel file attachments
+//
+// Inbound: bridges download a user's file and hand the bytes to
+// `ingest_bytes`, which runs them through the desktop ingestion pipeline
+// (engine::ingest). Channel turns are text-only, so the agent gets each
+// file's ingest note: short documents inline, long ones indexed into
+// memory, sheets and transcripts saved to the workspace.
+//
+// Outbound: the agent sends a workspace file by writing `[attach: path]` on
+// its own line (see `ATTACH_HINT`). `take_attachments` strips those lines
+// from the reply and loads the files; the bridge uploads them its own way.
+// Paths may not leave the agent workspace.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::ingest;
+use crate::engine::state::EngineState;
+use crate::engine::types::IngestIntent;
+use log::info;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Largest inbound file a bridge should download.
+pub const MAX_INBOUND_BYTES: u64 = 100 * 1024 * 1024;
+/// Largest workspace file sent back to a chat.
+const MAX_OUTBOUND_BYTES: u64 = 50 * 1024 * 1024;
+/// Files attached to one reply.
+pub const MAX_FILES: usize = 5;
+
+/// Appended to the channel context of bridges that can post files.
+pub const ATTACH_HINT: &str = "To send the user a file from your workspace, put \
+                               `[attach: relative/path]` on its own line in your reply.";
+
+/// Ingest a downloaded file and return the note for the agent's message.
+pub async fn ingest_bytes(
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+    name: &str,
+    bytes: &[u8],
+) -> EngineResult<String> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+
+    let dir = std::env::temp_dir().join(format!("paw-channel-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(safe_file_name(name));
+    std::fs::write(&path, bytes)?;
+    let result = ingest::ingest_file(&state, &path, IngestIntent::Auto, agent_id).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    let result = result?;
+    info!("[channels] Ingested {} → {:?}", name, result.kind);
+
+    let (attachments, notes) = ingest::resolve(&state, std::slice::from_ref(&result.handle));
+    if !attachments.is_empty() {
+        // Images and PDFs go to the model as attachments, which channel
+        // turns can't carry
+        return Ok(format!(
+            "[Shared file {} ({}) — images and PDFs can't be viewed from this chat; ask the user to paste the relevant text]",
+            name, result.mime_type
+        ));
+    }
+    Ok(notes
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| format!("[Shared file {} — {}]", name, result.summary)))
+}
+
+/// Append ingest notes (one per shared file) to the user's message.
+pub fn with_file_notes(message: &str, notes: &[String]) -> String {
+    if notes.is_empty() {
+        return message.to_string();
+    }
+    let message = if message.trim().is_empty() {
+        "I shared these files with you."
+    } else {
+        message
+    };
+    format!("{}\n\n{}", message, notes.join("\n\n"))
+}
+
+/// A workspace file the agent asked to send.
+#[derive(Debug)]
+pub struct OutboundFile {
+    pub name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Strip `[attach: path]` lines from a reply and load those files from the
+/// agent workspace. Files that can't be sent are noted in the returned text.
+pub fn take_attachments(reply: &str, agent_id: &str) -> (String, Vec<OutboundFile>) {
+    let (text, paths) = split_attach_lines(reply);
+    if paths.is_empty() {
+        return (text, vec![]);
+    }
+    let mut text = text;
+    let mut files = Vec::new();
+    let workspace = crate::engine::tools::ensure_workspace(agent_id)
+        .and_then(|w| w.canonicalize().map_err(Into::into));
+    for raw in paths {
+        let loaded = match &workspace {
+            Ok(ws) if files.len() < MAX_FILES => load_workspace_file(ws, &raw),
+            Ok(_) => Err(format!("at most {} files per message", MAX_FILES).into()),
+            Err(e) => Err(format!("workspace unavailable: {}", e).into()),
+        };
+        match loaded {
+            Ok(file) => files.push(file),
+            Err(e) => text.push_str(&format!("\n\n(Could not attach {}: {})", raw, e)),
+        }
+    }
+    (text.trim().to_string(), files)
+}
+
+fn split_attach_lines(reply: &str) -> (String, Vec<String>) {
+    let mut paths = Vec::new();
+    let kept: Vec<&str> = reply
+        .lines()
+        .filter(|line| {
+            let path = line
+                .trim()
+                .strip_prefix("[attach:")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .map(|p| p.trim().trim_matches('`'));
+            match path {
+                Some(p) if !p.is_empty() => {
+                    paths.push(p.to_string());
+                    false
+                }
+                _ => true,
+            }
+        })
+        .collect();
+    (kept.join("\n").trim().to_string(), paths)
+}
+
+fn load_workspace_file(workspace: &Path, raw: &str) -> EngineResult<OutboundFile> {
+    let path: PathBuf = workspace
+        .join(raw)
+        .canonicalize()
+        .map_err(|_| "file not found in the workspace")?;
+    if !path.starts_with(workspace) {
+        return Err("outside the workspace".into());
+    }
+    let meta = std::fs::metadata(&path)?;
+    if !meta.is_file() {
+        return Err("not a file".into());
+    }
+    if meta.len() > MAX_OUTBOUND_BYTES {
+        return Err(format!("larger than {} MB", MAX_OUTBOUND_BYTES / 1024 / 1024).into());
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".into());
+    Ok(OutboundFile {
+        mime_type: ingest::mime_type(&path).to_string(),
+        bytes: std::fs::read(&path)?,
+        name,
+    })
+}
+
+/// File names from chat platforms are untrusted: keep them to one path segment.
+pub fn safe_file_name(name: &str) -> String {
+    let name = name.replace(['/', '\\'], "_");
+    let name = name.trim_matches('.');
+    if name.is_empty() {
+        "file".into()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_attach_lines() {
+        let (text, paths) = split_attach_lines(
+            "Here's the report.\n[attach: reports/q3.pdf]\n  [attach: `a.csv`]\nThanks",
+        );
+        assert_eq!(text, "Here's the report.\nThanks");
+        assert_eq!(paths, vec!["reports/q3.pdf", "a.csv"]);
+
+        let (text, paths) = split_attach_lines("Use [attach: x] inline? no");
+        assert_eq!(text, "Use [attach: x] inline? no");
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn workspace_files_stay_inside() {
+        let dir = std::env::temp_dir().join(format!("paw-attach-{}", uuid::Uuid::new_v4()));
+        let ws = dir.join("ws");
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::write(ws.join("notes.md"), "hi").unwrap();
+        std::fs::write(dir.join("secret.txt"), "no").unwrap();
+        let ws = ws.canonicalize().unwrap();
+
+        let file = load_workspace_file(&ws, "notes.md").unwrap();
+        assert_eq!(file.name, "notes.md");
+        assert_eq!(file.bytes, b"hi");
+        assert!(load_workspace_file(&ws, "../secret.txt").is_err());
+        assert!(load_workspace_file(&ws, "missing.txt").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sanitizes_names() {
+        assert_eq!(safe_file_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(safe_file_name(".."), "file");
+        assert_eq!(safe_file_name("report.pdf"), "report.pdf");
+    }
+
+    #[test]
+    fn appends_file_notes() {
+        assert_eq!(with_file_notes("hi", &[]), "hi");
+        assert_eq!(
+            with_file_notes("", &["[note]".to_string()]),
+            "I shared these files with you.\n\n[note]"
+        );
+    }
+}
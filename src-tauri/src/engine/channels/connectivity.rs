@@ -0,0 +1,398 @@
+// Paw Agent Engine — Bridge connection quality
+//
+// Bridges reconnect on their own, which used to be invisible outside the
+// logs. Each bridge reports connect / disconnect / latency here; the
+// per-bridge metrics are folded into `ChannelStatus` and every state change
+// is emitted as a `channel-connectivity` event so the UI can show a
+// degraded-connection banner. A bridge going down is also routed through
+// engine::notifications (category "gateway").
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::notifications::{self, Notice};
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// Latency above this marks a connected bridge as degraded.
+const DEGRADED_LATENCY_MS: u64 = 3_000;
+
+/// Give up on a manual ping after this long.
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+static METRICS: LazyLock<Mutex<HashMap<String, ConnectionMetrics>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    /// Never connected since the app started.
+    #[default]
+    Idle,
+    Connected,
+    /// Dropped; a reconnect is scheduled.
+    Reconnecting,
+    /// Stopped cleanly or gave up.
+    Disconnected,
+}
+
+/// Per-bridge connection quality, reset when the app restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionMetrics {
+    pub state: ConnectionState,
+    /// Connection drops since the app started.
+    pub disconnect_count: u64,
+    /// 1-based attempt of the pending reconnect (0 when connected).
+    pub reconnect_attempt: u32,
+    /// Backoff before the pending reconnect.
+    pub reconnect_delay_ms: Option<u64>,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_connected_at: Option<String>,
+    pub last_disconnected_at: Option<String>,
+}
+
+impl ConnectionMetrics {
+    /// Reconnecting, or connected but slow.
+    pub fn degraded(&self) -> bool {
+        match self.state {
+            ConnectionState::Reconnecting => true,
+            ConnectionState::Connected => self
+                .last_latency_ms
+                .is_some_and(|ms| ms > DEGRADED_LATENCY_MS),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ConnectivityEvent<'a> {
+    channel: &'a str,
+    degraded: bool,
+    #[serde(flatten)]
+    metrics: &'a ConnectionMetrics,
+}
+
+/// Result of `engine_channel_ping`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPing {
+    pub channel: String,
+    /// URL or `host:port` that was probed.
+    pub target: String,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+// ── Recording ──────────────────────────────────────────────────────────
+
+/// Current metrics for `channel` (default if it never reported).
+pub fn connectivity(channel: &str) -> ConnectionMetrics {
+    METRICS.lock().get(channel).cloned().unwrap_or_default()
+}
+
+/// The bridge (re)connected.
+pub fn mark_connected(app_handle: &tauri::AppHandle, channel: &str) {
+    update(app_handle, channel, |m| {
+        m.state = ConnectionState::Connected;
+        m.reconnect_attempt = 0;
+        m.reconnect_delay_ms = None;
+        m.last_connected_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+}
+
+/// The connection dropped with `error`; reconnect `attempt` (1-based) is
+/// scheduled after `delay`.
+pub fn mark_reconnecting(
+    app_handle: &tauri::AppHandle,
+    channel: &str,
+    error: &str,
+    attempt: u32,
+    delay: Duration,
+) {
+    update(app_handle, channel, |m| {
+        m.state = ConnectionState::Reconnecting;
+        m.disconnect_count += 1;
+        m.reconnect_attempt = attempt;
+        m.reconnect_delay_ms = Some(delay.as_millis() as u64);
+        m.last_error = Some(error.to_string());
+        m.last_disconnected_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+    // Only the first drop — retries of the same outage stay quiet
+    if attempt == 1 {
+        notifications::notify(
+            app_handle,
+            Notice::new(
+                notifications::GATEWAY,
+                format!("{} disconnected — reconnecting", channel),
+                error,
+            )
+            .navigate_to("channels"),
+        );
+    }
+}
+
+/// The bridge stopped and will not reconnect on its own — cleanly, or
+/// after `error`.
+pub fn mark_stopped(app_handle: &tauri::AppHandle, channel: &str, error: Option<&str>) {
+    update(app_handle, channel, |m| {
+        m.state = ConnectionState::Disconnected;
+        if let Some(error) = error {
+            m.last_error = Some(error.to_string());
+            m.last_disconnected_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        m.reconnect_attempt = 0;
+        m.reconnect_delay_ms = None;
+    });
+    if let Some(error) = error {
+        notifications::notify(
+            app_handle,
+            Notice::new(
+                notifications::GATEWAY,
+                format!("{} is down", channel),
+                error,
+            )
+            .navigate_to("channels"),
+        );
+    }
+}
+
+/// Record a measured round trip. Only emits when this flips the degraded
+/// flag, so heartbeat-rate updates don't flood the frontend.
+pub fn record_latency(app_handle: &tauri::AppHandle, channel: &str, latency: Duration) {
+    let (changed, snapshot) = {
+        let mut metrics = METRICS.lock();
+        let m = metrics.entry(channel.to_string()).or_default();
+        let was = m.degraded();
+        m.last_latency_ms = Some(latency.as_millis() as u64);
+        (was != m.degraded(), m.clone())
+    };
+    if changed {
+        emit(app_handle, channel, &snapshot);
+    }
+}
+
+/// Record the error, then sleep for the exponential backoff before
+/// reconnect attempt `attempt` (0-based, as for `http::reconnect_delay`).
+pub async fn reconnect_backoff(
+    app_handle: &tauri::AppHandle,
+    channel: &str,
+    attempt: u32,
+    error: &str,
+) -> Duration {
+    let delay = crate::engine::http::reconnect_backoff(attempt);
+    mark_reconnecting(app_handle, channel, error, attempt + 1, delay);
+    tokio::time::sleep(delay).await;
+    delay
+}
+
+fn update(app_handle: &tauri::AppHandle, channel: &str, f: impl FnOnce(&mut ConnectionMetrics)) {
+    let snapshot = {
+        let mut metrics = METRICS.lock();
+        let m = metrics.entry(channel.to_string()).or_default();
+        f(m);
+        m.clone()
+    };
+    emit(app_handle, channel, &snapshot);
+}
+
+fn emit(app_handle: &tauri::AppHandle, channel: &str, metrics: &ConnectionMetrics) {
+    let _ = app_handle.emit(
+        "channel-connectivity",
+        ConnectivityEvent {
+            channel,
+            degraded: metrics.degraded(),
+            metrics,
+        },
+    );
+}
+
+// ── Manual ping ────────────────────────────────────────────────────────
+
+enum PingTarget {
+    Http(String),
+    Tcp(String),
+}
+
+/// Time a round trip to the platform endpoint `channel` is configured for.
+/// Any HTTP response counts as reachable — the point is the network path,
+/// not the credentials.
+pub async fn ping(app_handle: &tauri::AppHandle, channel: &str) -> EngineResult<ChannelPing> {
+    let target = ping_target(app_handle, channel)?;
+    let started = Instant::now();
+    let (target, outcome) = match target {
+        PingTarget::Http(url) => {
+            let client = reqwest::Client::builder()
+                .timeout(PING_TIMEOUT)
+                .build()
+                .map_err(|e| EngineError::Other(format!("HTTP client error: {}", e)))?;
+            let outcome = client
+                .get(&url)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            (url, outcome)
+        }
+        PingTarget::Tcp(addr) => {
+            let outcome =
+                match tokio::time::timeout(PING_TIMEOUT, tokio::net::TcpStream::connect(&addr))
+                    .await
+                {
+                    Ok(Ok(_)) => Ok(()),
+                    Ok(Err(e)) => Err(e.to_string()),
+                    Err(_) => Err(format!("timed out after {}s", PING_TIMEOUT.as_secs())),
+                };
+            (addr, outcome)
+        }
+    };
+    let elapsed = started.elapsed();
+
+    Ok(match outcome {
+        Ok(()) => {
+            record_latency(app_handle, channel, elapsed);
+            ChannelPing {
+                channel: channel.to_string(),
+                target,
+                reachable: true,
+                latency_ms: Some(elapsed.as_millis() as u64),
+                error: None,
+            }
+        }
+        Err(e) => {
+            warn!("[{}] Ping to {} failed: {}", channel, target, e);
+            ChannelPing {
+                channel: channel.to_string(),
+                target,
+                reachable: false,
+                latency_ms: None,
+                error: Some(e),
+            }
+        }
+    })
+}
+
+fn ping_target(app_handle: &tauri::AppHandle, channel: &str) -> EngineResult<PingTarget> {
+    use crate::engine::{email_bridge, irc, matrix, mattermost, nextcloud, nostr};
+    let target = match channel {
+        "discord" => PingTarget::Http("https://discord.com/api/v10/gateway".into()),
+        "slack" => PingTarget::Http("https://slack.com/api/api.test".into()),
+        "telegram" => PingTarget::Http("https://api.telegram.org".into()),
+        "twitch" => PingTarget::Http("https://id.twitch.tv/oauth2/keys".into()),
+        "matrix" => {
+            let config = matrix::load_config(app_handle)?;
+            PingTarget::Http(format!(
+                "{}/_matrix/client/versions",
+                base_url(&config.homeserver, channel)?
+            ))
+        }
+        "mattermost" => {
+            let config = mattermost::load_config(app_handle)?;
+            PingTarget::Http(format!(
+                "{}/api/v4/system/ping",
+                base_url(&config.server_url, channel)?
+            ))
+        }
+        "nextcloud" => {
+            let config = nextcloud::load_config(app_handle)?;
+            PingTarget::Http(format!(
+                "{}/status.php",
+                base_url(&config.server_url, channel)?
+            ))
+        }
+        "irc" => {
+            let config = irc::load_config(app_handle)?;
+            if config.server.is_empty() {
+                return Err(not_configured(channel));
+            }
+            PingTarget::Tcp(format!("{}:{}", config.server, config.port))
+        }
+        "nostr" => {
+            let config = nostr::load_config(app_handle)?;
+            let relay = config
+                .relays
+                .first()
+                .ok_or_else(|| not_configured(channel))?;
+            PingTarget::Tcp(relay_addr(relay)?)
+        }
+        "email" => {
+            let account = email_bridge::load_config(app_handle)?.imap_account()?;
+            PingTarget::Tcp(format!("{}:{}", account.host, account.port))
+        }
+        "webchat" | "whatsapp" | "webhook" => {
+            return Err(EngineError::Channel {
+                channel: channel.into(),
+                message: "runs locally — there is no remote endpoint to ping".into(),
+            })
+        }
+        _ => return Err(format!("Unknown channel: {}", channel).into()),
+    };
+    Ok(target)
+}
+
+fn base_url<'a>(url: &'a str, channel: &str) -> EngineResult<&'a str> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        Err(not_configured(channel))
+    } else {
+        Ok(url)
+    }
+}
+
+/// `wss://relay.example/path` → `relay.example:443`.
+fn relay_addr(relay: &str) -> EngineResult<String> {
+    let url = reqwest::Url::parse(relay)
+        .map_err(|e| EngineError::Other(format!("Invalid relay URL {}: {}", relay, e)))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| EngineError::Other(format!("Relay URL {} has no host", relay)))?;
+    let port = url
+        .port_or_known_default()
+        .unwrap_or(if url.scheme() == "ws" { 80 } else { 443 });
+    Ok(format!("{}:{}", host, port))
+}
+
+fn not_configured(channel: &str) -> EngineError {
+    EngineError::Channel {
+        channel: channel.into(),
+        message: "no server configured".into(),
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degraded_when_reconnecting_or_slow() {
+        let mut m = ConnectionMetrics::default();
+        assert!(!m.degraded());
+        m.state = ConnectionState::Reconnecting;
+        assert!(m.degraded());
+        m.state = ConnectionState::Connected;
+        m.last_latency_ms = Some(120);
+        assert!(!m.degraded());
+        m.last_latency_ms = Some(DEGRADED_LATENCY_MS + 1);
+        assert!(m.degraded());
+        m.state = ConnectionState::Disconnected;
+        assert!(!m.degraded());
+    }
+
+    #[test]
+    fn relay_addr_uses_scheme_default_port() {
+        assert_eq!(
+            relay_addr("wss://relay.damus.io").unwrap(),
+            "relay.damus.io:443"
+        );
+        assert_eq!(
+            relay_addr("ws://localhost:7000/").unwrap(),
+            "localhost:7000"
+        );
+        assert!(relay_addr("not a url").is_err());
+    }
+}
@@ -6,9 +6,13 @@
 //   - ChannelConfig trait  — common config shape for load/save/user management
 //   - split_message()      — splits long responses for platform message limits
 //   - Access control       — allowlist / pairing logic
+//   - Connectivity         — reconnect / latency metrics + channel-connectivity events
+//   - Attachments          — inbound file ingestion, outbound `[attach: path]` files
 
 mod access;
 mod agent;
+pub mod attachments;
+mod connectivity;
 
 use crate::atoms::error::{EngineError, EngineResult};
 use crate::engine::state::EngineState;
@@ -16,8 +20,14 @@ use serde::{Deserialize, Serialize};
 use tauri::Manager;
 
 // Re-export public API
-pub use access::{approve_user_generic, check_access, deny_user_generic, remove_user_generic};
-pub use agent::{run_channel_agent, run_routed_channel_agent};
+pub use access::{
+    approve_user_generic, check_access, deny_user_generic, notify_pairing, remove_user_generic,
+};
+pub use agent::{run_channel_agent, run_routed_channel_agent, with_run_id};
+pub use connectivity::{
+    connectivity, mark_connected, mark_reconnecting, mark_stopped, ping, reconnect_backoff,
+    record_latency, ChannelPing, ConnectionMetrics, ConnectionState,
+};
 
 // ── Common Channel Config ──────────────────────────────────────────────
 
@@ -76,6 +86,9 @@ pub struct ChannelStatus {
     pub allowed_users: Vec<String>,
     pub pending_users: Vec<PendingUser>,
     pub dm_policy: String,
+    /// Connection quality since the app started.
+    #[serde(default)]
+    pub connectivity: ConnectionMetrics,
 }
 
 // ── Utility ────────────────────────────────────────────────────────────
@@ -135,6 +135,7 @@ pub fn build_runtime_context(
     session_id: &str,
     agent_id: &str,
     user_timezone: &str,
+    home_location: Option<&HomeLocation>,
 ) -> String {
     // Resolve a human-friendly agent name so the model knows its own identity.
     // The default agent's display name is "Pawz" (set in frontend), but the
@@ -199,11 +200,15 @@ pub fn build_runtime_context(
     });
     let app_version = env!("CARGO_PKG_VERSION");
 
+    let location = crate::engine::location::context_line(home_location)
+        .map(|line| format!("{}\n", line))
+        .unwrap_or_default();
+
     format!(
         "## Runtime\n\
         Name: {} | Agent ID: {} | Model: {} | Provider: {}\n\
         Session: {} | Time: {}\n\
-        Workspace: {}\n\
+        {}Workspace: {}\n\
         \n\
         ## Environment\n\
         OS: {} ({}) | Shell: {}\n\
@@ -215,6 +220,7 @@ pub fn build_runtime_context(
         provider_name,
         session_id,
         time_str,
+        location,
         ws.display(),
         os_name,
         os_arch,
@@ -0,0 +1,429 @@
+// Paw Agent Engine — Compare mode
+//
+// Send one prompt to two models at once — typically a local model against a
+// paid one — and let the user pick the better answer.
+//
+// Both answers are generated concurrently from the session's history, each
+// under its own run_id, and streamed as ordinary EngineEvents on the
+// `compare-event` channel (not `engine-event`, so the chat view's own stream
+// never picks them up). Each run is recorded in telemetry like a chat turn.
+// Nothing is written to the session until the user picks a winner, which
+//   - appends the prompt and the winning answer to the session, and
+//   - rates the winning run 👍 and the other 👎 in run_feedback, so the
+//     comparison shows up in `engine_feedback_stats` per model.
+//
+// Answers are single model calls without tools: the comparison measures the
+// models, not the luck of a tool loop. Comparisons live in memory until
+// picked (the most recent few are kept).
+
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{normalize_model_name, provider_for_model_or_default, EngineState};
+use crate::engine::types::*;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::Instant;
+use tauri::{Emitter, Manager};
+
+/// Unpicked comparisons kept in memory.
+const MAX_KEPT: usize = 20;
+
+static COMPARISONS: LazyLock<Mutex<Vec<Comparison>>> = LazyLock::new(Default::default);
+
+/// Held while a run records its metric and tags it with its run_id, so the
+/// two concurrent runs of a comparison can't tag each other's row.
+static METRICS: Mutex<()> = parking_lot::const_mutex(());
+
+/// One side of a comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareRun {
+    pub run_id: String,
+    pub model: String,
+    pub provider_id: String,
+    /// "running" | "done" | "failed"
+    pub status: String,
+    pub text: String,
+    pub error: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub cost_usd: f64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub id: String,
+    pub session_id: String,
+    pub prompt: String,
+    pub runs: Vec<CompareRun>,
+    /// run_id of the answer the user picked
+    pub winner: Option<String>,
+    pub created_at: String,
+}
+
+/// Start generating `prompt` with both models. Returns at once with the two
+/// run_ids; answers arrive as `compare-event`s.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    prompt: &str,
+    models: [&str; 2],
+) -> EngineResult<Comparison> {
+    let state = app_handle.state::<EngineState>();
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return Err("Nothing to compare — the prompt is empty".into());
+    }
+
+    let (picked, default_prompt, context_window, budget) = {
+        let cfg = state.config.lock();
+        let mut picked = Vec::new();
+        for model in models {
+            let model = normalize_model_name(model.trim()).to_string();
+            if model.is_empty() {
+                return Err("Choose two models to compare".into());
+            }
+            let provider = provider_for_model_or_default(&model, &cfg)
+                .ok_or_else(|| format!("No provider configured for {}", model))?;
+            picked.push((provider, model));
+        }
+        (
+            picked,
+            cfg.default_system_prompt.clone(),
+            cfg.context_window_tokens,
+            cfg.daily_budget_usd,
+        )
+    };
+    if budget > 0.0 && state.daily_tokens.check_budget(budget).is_some() {
+        return Err("Daily budget exhausted — compare mode would add to it".into());
+    }
+
+    let session = state.store.get_session(session_id)?;
+    let agent_id = session.as_ref().and_then(|s| s.agent_id.clone());
+    let system_prompt = session.and_then(|s| s.system_prompt).or(default_prompt);
+    let mut messages = state.store.load_conversation(
+        session_id,
+        system_prompt.as_deref(),
+        Some(context_window),
+        agent_id.as_deref(),
+    )?;
+    messages.push(Message {
+        role: Role::User,
+        content: MessageContent::Text(prompt.to_string()),
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+    });
+
+    let comparison = Comparison {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        prompt: prompt.to_string(),
+        runs: picked
+            .iter()
+            .map(|(provider, model)| CompareRun {
+                run_id: uuid::Uuid::new_v4().to_string(),
+                model: model.clone(),
+                provider_id: provider.id.clone(),
+                status: "running".into(),
+                text: String::new(),
+                error: None,
+                usage: None,
+                cost_usd: 0.0,
+                duration_ms: 0,
+            })
+            .collect(),
+        winner: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    {
+        let mut all = COMPARISONS.lock();
+        all.push(comparison.clone());
+        let excess = all.len().saturating_sub(MAX_KEPT);
+        all.drain(..excess);
+    }
+    info!(
+        "[compare] {} vs {} in session {}",
+        picked[0].1, picked[1].1, session_id
+    );
+
+    for ((provider, model), run) in picked.into_iter().zip(&comparison.runs) {
+        let app = app_handle.clone();
+        let compare_id = comparison.id.clone();
+        let session_id = session_id.to_string();
+        let run_id = run.run_id.clone();
+        let messages = messages.clone();
+        tauri::async_runtime::spawn(async move {
+            generate(
+                &app,
+                &compare_id,
+                &session_id,
+                &run_id,
+                &provider,
+                &model,
+                &messages,
+            )
+            .await;
+        });
+    }
+    Ok(comparison)
+}
+
+/// One side: call the model, stream the answer, record telemetry.
+async fn generate(
+    app_handle: &tauri::AppHandle,
+    compare_id: &str,
+    session_id: &str,
+    run_id: &str,
+    provider: &ProviderConfig,
+    model: &str,
+    messages: &[Message],
+) {
+    let started = Instant::now();
+    let result = AnyProvider::from_config(provider)
+        .chat_stream(messages, &[], model, None, None)
+        .await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let chunks = match result {
+        Ok(chunks) => chunks,
+        Err(e) => {
+            warn!("[compare] {} failed: {}", model, e);
+            update(compare_id, run_id, |run| {
+                run.status = "failed".into();
+                run.error = Some(e.to_string());
+                run.duration_ms = duration_ms;
+            });
+            let _ = app_handle.emit(
+                "compare-event",
+                EngineEvent::Error {
+                    session_id: session_id.to_string(),
+                    run_id: run_id.to_string(),
+                    message: e.to_string(),
+                },
+            );
+            return;
+        }
+    };
+
+    let mut text = String::new();
+    for delta in chunks.iter().filter_map(|c| c.delta_text.as_deref()) {
+        text.push_str(delta);
+        let _ = app_handle.emit(
+            "compare-event",
+            EngineEvent::Delta {
+                session_id: session_id.to_string(),
+                run_id: run_id.to_string(),
+                text: delta.to_string(),
+            },
+        );
+    }
+    let usage = chunks.iter().rev().find_map(|c| c.usage.clone());
+    let reported_model = chunks.iter().find_map(|c| c.model.clone());
+    let (input, output, cache_read, cache_create) = usage.as_ref().map_or((0, 0, 0, 0), |u| {
+        (
+            u.input_tokens,
+            u.output_tokens,
+            u.cache_read_tokens,
+            u.cache_creation_tokens,
+        )
+    });
+    let cost_usd = estimate_cost_usd(model, input, output, cache_read, cache_create);
+
+    let state = app_handle.state::<EngineState>();
+    state
+        .daily_tokens
+        .record(model, input, output, cache_read, cache_create);
+    {
+        let _guard = METRICS.lock();
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        match state.store.record_metric(
+            &date,
+            session_id,
+            model,
+            input,
+            output,
+            cost_usd,
+            0,
+            0,
+            duration_ms,
+            duration_ms,
+            1,
+        ) {
+            Ok(()) => {
+                let _ = state.store.tag_latest_metric_run(session_id, run_id);
+            }
+            Err(e) => warn!("[compare] Failed to record metrics for {}: {}", model, e),
+        }
+    }
+
+    update(compare_id, run_id, |run| {
+        run.status = "done".into();
+        run.text = text.clone();
+        run.usage = usage.clone();
+        run.cost_usd = cost_usd;
+        run.duration_ms = duration_ms;
+    });
+    let _ = app_handle.emit(
+        "compare-event",
+        EngineEvent::Complete {
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            text,
+            tool_calls_count: 0,
+            usage,
+            model: reported_model.or_else(|| Some(model.to_string())),
+            total_rounds: Some(1),
+            max_rounds: None,
+            citations: Vec::new(),
+        },
+    );
+}
+
+fn update(compare_id: &str, run_id: &str, apply: impl FnOnce(&mut CompareRun)) {
+    let mut all = COMPARISONS.lock();
+    if let Some(run) = all
+        .iter_mut()
+        .filter(|c| c.id == compare_id)
+        .flat_map(|c| c.runs.iter_mut())
+        .find(|r| r.run_id == run_id)
+    {
+        apply(run);
+    }
+}
+
+/// A comparison still in memory.
+pub fn get(compare_id: &str) -> Option<Comparison> {
+    COMPARISONS
+        .lock()
+        .iter()
+        .find(|c| c.id == compare_id)
+        .cloned()
+}
+
+/// Keep `winner_run_id`'s answer: add the exchange to the session and rate
+/// both runs (a failed run isn't rated).
+pub fn pick_winner(
+    state: &EngineState,
+    compare_id: &str,
+    winner_run_id: &str,
+    comment: &str,
+) -> EngineResult<Comparison> {
+    let comparison = {
+        let mut all = COMPARISONS.lock();
+        let comparison = all
+            .iter_mut()
+            .find(|c| c.id == compare_id)
+            .ok_or("Comparison not found — it may have expired")?;
+        choose(comparison, winner_run_id)?;
+        comparison.clone()
+    };
+    let winner = comparison
+        .runs
+        .iter()
+        .find(|r| r.run_id == winner_run_id)
+        .ok_or("Unknown run")?;
+
+    let session_id = &comparison.session_id;
+    if state.store.get_session(session_id)?.is_none() {
+        state
+            .store
+            .create_session(session_id, &winner.model, None, None)?;
+    }
+    for (role, content) in [("user", &comparison.prompt), ("assistant", &winner.text)] {
+        state.store.add_message(&StoredMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.clone(),
+            role: role.into(),
+            content: content.clone(),
+            tool_calls_json: None,
+            tool_call_id: None,
+            name: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })?;
+    }
+
+    for run in comparison.runs.iter().filter(|r| r.status == "done") {
+        if run.run_id == winner_run_id {
+            state
+                .store
+                .feedback_submit(&run.run_id, 1, comment.trim())?;
+        } else {
+            state.store.feedback_submit(&run.run_id, -1, "")?;
+        }
+    }
+    info!(
+        "[compare] {} preferred over {} in session {}",
+        winner.model,
+        comparison
+            .runs
+            .iter()
+            .filter(|r| r.run_id != winner_run_id)
+            .map(|r| r.model.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        session_id
+    );
+    Ok(comparison)
+}
+
+/// Mark `winner_run_id` as the winner, if the comparison allows it.
+fn choose(comparison: &mut Comparison, winner_run_id: &str) -> EngineResult<()> {
+    if comparison.winner.is_some() {
+        return Err("A winner was already picked".into());
+    }
+    if comparison.runs.iter().any(|r| r.status == "running") {
+        return Err("Wait until both answers are finished".into());
+    }
+    match comparison.runs.iter().find(|r| r.run_id == winner_run_id) {
+        Some(run) if run.status == "done" => {
+            comparison.winner = Some(winner_run_id.to_string());
+            Ok(())
+        }
+        Some(_) => Err("That model failed — it can't win".into()),
+        None => Err("That run isn't part of this comparison".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str, status: &str) -> CompareRun {
+        CompareRun {
+            run_id: id.into(),
+            model: format!("model-{}", id),
+            provider_id: "p".into(),
+            status: status.into(),
+            text: String::new(),
+            error: None,
+            usage: None,
+            cost_usd: 0.0,
+            duration_ms: 0,
+        }
+    }
+
+    fn comparison(a: &str, b: &str) -> Comparison {
+        Comparison {
+            id: "c".into(),
+            session_id: "s".into(),
+            prompt: "hi".into(),
+            runs: vec![run("a", a), run("b", b)],
+            winner: None,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn winner_needs_finished_answers() {
+        assert!(choose(&mut comparison("done", "running"), "a").is_err());
+        assert!(choose(&mut comparison("failed", "done"), "a").is_err());
+        assert!(choose(&mut comparison("done", "done"), "x").is_err());
+
+        let mut c = comparison("done", "failed");
+        choose(&mut c, "a").unwrap();
+        assert_eq!(c.winner.as_deref(), Some("a"));
+        assert!(choose(&mut c, "a").is_err(), "only one pick per comparison");
+    }
+}
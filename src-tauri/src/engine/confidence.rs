@@ -0,0 +1,119 @@
+// Paw Agent Engine — Confidence pass
+//
+// Runs the judge over a finished chat answer (when enabled in the
+// confidence settings) and emits the flagged claims as a `confidence`
+// engine event for the run. Failures are logged and otherwise ignored —
+// the answer has already been delivered.
+
+pub use openpawz_core::engine::confidence::*;
+
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{resolve_provider_for_model, EngineState};
+use crate::engine::types::*;
+use log::{info, warn};
+use tauri::{Emitter, Manager};
+
+/// Judge `answer` and emit its annotations. A no-op when the pass is
+/// disabled or the answer is too short to be worth it.
+pub async fn annotate(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    question: &str,
+    answer: &str,
+) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let settings = load_settings(&state.store);
+    if !settings.enabled || answer.chars().count() < MIN_ANSWER_CHARS {
+        return;
+    }
+
+    let (local, remote) = {
+        let cfg = state.config.lock();
+        let local = cfg
+            .providers
+            .iter()
+            .find(|p| p.kind == ProviderKind::Ollama)
+            .and_then(|p| {
+                let model = settings
+                    .local_model
+                    .clone()
+                    .filter(|m| !m.is_empty())
+                    .or_else(|| p.default_model.clone())?;
+                Some((p.clone(), model))
+            });
+        let remote = cfg
+            .model_routing
+            .worker_model
+            .clone()
+            .or_else(|| cfg.default_model.clone())
+            .and_then(|model| {
+                let provider = resolve_provider_for_model(&model, &cfg.providers)
+                    .or_else(|| cfg.providers.first().cloned())?;
+                Some((provider, model))
+            });
+        (local, remote)
+    };
+
+    let prompt = judge_prompt(question, answer);
+    for (provider_config, model) in local.into_iter().chain(remote) {
+        match judge(&provider_config, &model, &prompt).await {
+            Ok(reply) => {
+                let annotations = parse_annotations(&reply, answer);
+                info!(
+                    "[confidence] {} flagged {} claim(s) in run {}",
+                    model,
+                    annotations.len(),
+                    run_id
+                );
+                let _ = app_handle.emit(
+                    "engine-event",
+                    EngineEvent::Confidence {
+                        session_id: session_id.to_string(),
+                        run_id: run_id.to_string(),
+                        annotations,
+                    },
+                );
+                return;
+            }
+            Err(e) => warn!("[confidence] Judge {} failed: {}", model, e),
+        }
+    }
+}
+
+async fn judge(
+    provider_config: &ProviderConfig,
+    model: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(JUDGE_SYSTEM.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(prompt.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+    let chunks = AnyProvider::from_config(provider_config)
+        .chat_stream(&messages, &[], model, Some(0.0), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let reply: String = chunks
+        .iter()
+        .filter_map(|c| c.delta_text.as_deref())
+        .collect();
+    if reply.trim().is_empty() {
+        return Err(format!("{} returned an empty reply", model));
+    }
+    Ok(reply)
+}
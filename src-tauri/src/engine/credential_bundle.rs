@@ -0,0 +1,485 @@
+// Paw Agent Engine — Credential Bundle (import / export)
+//
+// Moves secrets between machines: skill vault credentials, the Nostr
+// identity key, and channel bridge configs (bot tokens, passwords).
+//
+// Bundle format (JSON text, safe to store anywhere):
+//   { format, version, created_at, item_count,
+//     kdf: { alg: "argon2id", m_cost, t_cost, p_cost, salt },
+//     nonce, ciphertext }
+// The ciphertext is AES-256-GCM over the JSON item list, keyed by Argon2id
+// of the user's passphrase. Item ids and values are only visible after
+// decryption; the caller picks which items to export and which to import.
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::key_vault;
+use crate::engine::sessions::SessionStore;
+use crate::engine::skills::{decrypt_credential, encrypt_credential, get_vault_key};
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+const BUNDLE_FORMAT: &str = "openpawz-credentials";
+const BUNDLE_VERSION: u8 = 1;
+
+/// Minimum passphrase length accepted for export.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Argon2id cost: 64 MiB, 3 passes, 1 lane (OWASP baseline).
+const ARGON2_M_COST: u32 = 64 * 1024;
+const ARGON2_T_COST: u32 = 3;
+const ARGON2_P_COST: u32 = 1;
+
+/// Upper bounds on the cost a bundle header may ask for (4× the export
+/// defaults), so a crafted bundle can't pin the CPU or exhaust memory.
+const MAX_M_COST: u32 = ARGON2_M_COST * 4;
+const MAX_T_COST: u32 = ARGON2_T_COST * 4;
+const MAX_P_COST: u32 = ARGON2_P_COST * 4;
+
+/// Channel bridges whose stored config carries secrets.
+const CHANNEL_CONFIGS: &[(&str, &str)] = &[
+    ("discord", "discord_config"),
+    ("slack", "slack_config"),
+    ("telegram", "telegram_config"),
+    ("matrix", "matrix_config"),
+    ("mattermost", "mattermost_config"),
+    ("irc", "irc_config"),
+    ("twitch", "twitch_config"),
+    ("nextcloud", "nextcloud_config"),
+    ("nostr", "nostr_config"),
+    ("webchat", "webchat_config"),
+    ("whatsapp", "whatsapp_config"),
+    ("webhook", "webhook_config"),
+];
+
+// ── Types ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    /// One skill vault credential (`skill:<skill_id>:<key>`).
+    Skill,
+    /// The Nostr identity private key (`nostr:key`).
+    NostrKey,
+    /// A channel bridge's stored config (`channel:<name>`).
+    Channel,
+}
+
+/// A selectable credential — never includes the secret value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CredentialItem {
+    pub id: String,
+    pub kind: CredentialKind,
+    pub label: String,
+}
+
+/// A credential with its plaintext value, only ever inside the ciphertext.
+#[derive(Serialize, Deserialize)]
+struct BundleEntry {
+    #[serde(flatten)]
+    item: CredentialItem,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    alg: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    version: u8,
+    created_at: String,
+    item_count: usize,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    /// Items in the bundle that were not selected.
+    pub skipped: Vec<String>,
+    /// `(item id, error)` for items that failed to apply.
+    pub failed: Vec<(String, String)>,
+    /// Channels whose config changed — they must be restarted to pick it up.
+    pub restart_channels: Vec<String>,
+}
+
+// ── Listing ────────────────────────────────────────────────────────────────
+
+/// Every credential that can be exported from this machine.
+pub fn list_items(store: &SessionStore) -> EngineResult<Vec<CredentialItem>> {
+    let mut items = Vec::new();
+    for (skill_id, key) in store.list_all_skill_credential_keys()? {
+        items.push(CredentialItem {
+            id: format!("skill:{}:{}", skill_id, key),
+            kind: CredentialKind::Skill,
+            label: format!("{} — {}", skill_id, key),
+        });
+    }
+    if key_vault::get(key_vault::PURPOSE_NOSTR_KEY).is_some() {
+        items.push(CredentialItem {
+            id: "nostr:key".into(),
+            kind: CredentialKind::NostrKey,
+            label: "Nostr identity key".into(),
+        });
+    }
+    for (name, config_key) in CHANNEL_CONFIGS {
+        if store.get_config(config_key)?.is_some() {
+            items.push(CredentialItem {
+                id: format!("channel:{}", name),
+                kind: CredentialKind::Channel,
+                label: format!("{} bridge settings", name),
+            });
+        }
+    }
+    Ok(items)
+}
+
+// ── Export ─────────────────────────────────────────────────────────────────
+
+/// Encrypt the selected items (all when `selected` is empty) into a bundle.
+pub fn export(store: &SessionStore, passphrase: &str, selected: &[String]) -> EngineResult<String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(EngineError::Other(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    let items: Vec<CredentialItem> = list_items(store)?
+        .into_iter()
+        .filter(|i| selected.is_empty() || selected.contains(&i.id))
+        .collect();
+    if items.is_empty() {
+        return Err(EngineError::Other(
+            "No credentials selected for export".into(),
+        ));
+    }
+
+    let mut entries = Vec::with_capacity(items.len());
+    for item in items {
+        match read_value(store, &item)? {
+            Some(value) => entries.push(BundleEntry {
+                item,
+                value: value.to_string(),
+            }),
+            None => warn!("[credentials] '{}' vanished during export", item.id),
+        }
+    }
+
+    let bundle = seal(&entries, passphrase, ARGON2_M_COST, ARGON2_T_COST)?;
+    for e in &mut entries {
+        zeroize::Zeroize::zeroize(&mut e.value);
+    }
+    info!("[credentials] Exported {} credentials", bundle.item_count);
+    Ok(serde_json::to_string_pretty(&bundle)?)
+}
+
+fn read_value(
+    store: &SessionStore,
+    item: &CredentialItem,
+) -> EngineResult<Option<Zeroizing<String>>> {
+    match item.kind {
+        CredentialKind::Skill => {
+            let (skill_id, key) = split_skill_id(&item.id)?;
+            let Some(encrypted) = store.get_skill_credential(skill_id, key)? else {
+                return Ok(None);
+            };
+            let vault_key = get_vault_key()?;
+            decrypt_credential(&encrypted, &vault_key).map(|v| Some(Zeroizing::new(v)))
+        }
+        CredentialKind::NostrKey => Ok(key_vault::get(key_vault::PURPOSE_NOSTR_KEY)),
+        CredentialKind::Channel => {
+            let config_key = channel_config_key(&item.id)?;
+            Ok(store.get_config(config_key)?.map(Zeroizing::new))
+        }
+    }
+}
+
+// ── Import ─────────────────────────────────────────────────────────────────
+
+/// Decrypt a bundle and list its items without applying anything.
+pub fn inspect(bundle_json: &str, passphrase: &str) -> EngineResult<Vec<CredentialItem>> {
+    Ok(open(bundle_json, passphrase)?
+        .into_iter()
+        .map(|e| e.item)
+        .collect())
+}
+
+/// Decrypt a bundle and apply the selected items (all when `selected` is
+/// empty). Existing values with the same id are overwritten.
+pub fn import(
+    store: &SessionStore,
+    bundle_json: &str,
+    passphrase: &str,
+    selected: &[String],
+) -> EngineResult<ImportReport> {
+    let entries = open(bundle_json, passphrase)?;
+    let mut report = ImportReport::default();
+    for entry in entries {
+        let id = entry.item.id.clone();
+        if !selected.is_empty() && !selected.contains(&id) {
+            report.skipped.push(id);
+            continue;
+        }
+        match apply(store, &entry) {
+            Ok(()) => {
+                if entry.item.kind == CredentialKind::Channel {
+                    report
+                        .restart_channels
+                        .push(id.trim_start_matches("channel:").to_string());
+                }
+                report.imported.push(id);
+            }
+            Err(e) => report.failed.push((id, e.to_string())),
+        }
+    }
+    info!(
+        "[credentials] Imported {} credentials ({} skipped, {} failed)",
+        report.imported.len(),
+        report.skipped.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+fn apply(store: &SessionStore, entry: &BundleEntry) -> EngineResult<()> {
+    match entry.item.kind {
+        CredentialKind::Skill => {
+            let (skill_id, key) = split_skill_id(&entry.item.id)?;
+            let vault_key = get_vault_key()?;
+            let encrypted = encrypt_credential(&entry.value, &vault_key)?;
+            store.set_skill_credential(skill_id, key, &encrypted)
+        }
+        CredentialKind::NostrKey => {
+            let hex = entry.value.trim();
+            if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(EngineError::Other("Invalid Nostr key in bundle".into()));
+            }
+            key_vault::set(key_vault::PURPOSE_NOSTR_KEY, hex);
+            Ok(())
+        }
+        CredentialKind::Channel => {
+            let config_key = channel_config_key(&entry.item.id)?;
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&entry.value)
+                .map_err(|e| EngineError::Config(format!("Invalid channel config: {}", e)))?;
+            store.set_config(config_key, &entry.value)
+        }
+    }
+}
+
+fn split_skill_id(id: &str) -> EngineResult<(&str, &str)> {
+    id.strip_prefix("skill:")
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| EngineError::Other(format!("Malformed skill credential id '{}'", id)))
+}
+
+fn channel_config_key(id: &str) -> EngineResult<&'static str> {
+    let name = id.strip_prefix("channel:").unwrap_or(id);
+    CHANNEL_CONFIGS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, key)| *key)
+        .ok_or_else(|| EngineError::Other(format!("Unknown channel '{}'", name)))
+}
+
+// ── Crypto ─────────────────────────────────────────────────────────────────
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> EngineResult<Zeroizing<[u8; 32]>> {
+    let params = argon2::Params::new(m_cost, t_cost, p_cost, Some(32))
+        .map_err(|e| EngineError::Other(format!("Invalid Argon2 parameters: {}", e)))?;
+    let argon = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| EngineError::Other(format!("Argon2 key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+fn seal(
+    entries: &[BundleEntry],
+    passphrase: &str,
+    m_cost: u32,
+    t_cost: u32,
+) -> EngineResult<Bundle> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    getrandom::getrandom(&mut salt)
+        .and_then(|_| getrandom::getrandom(&mut nonce))
+        .map_err(|e| EngineError::Other(format!("OS CSPRNG failed: {}", e)))?;
+
+    let key = derive_key(passphrase, &salt, m_cost, t_cost, ARGON2_P_COST)?;
+    let plaintext = Zeroizing::new(serde_json::to_vec(entries)?);
+    let ciphertext = Aes256Gcm::new_from_slice(key.as_ref())
+        .map_err(|_| EngineError::Other("Invalid bundle key".into()))?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|e| EngineError::Other(format!("Bundle encryption failed: {}", e)))?;
+
+    Ok(Bundle {
+        format: BUNDLE_FORMAT.into(),
+        version: BUNDLE_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        item_count: entries.len(),
+        kdf: KdfParams {
+            alg: "argon2id".into(),
+            m_cost,
+            t_cost,
+            p_cost: ARGON2_P_COST,
+            salt: B64.encode(salt),
+        },
+        nonce: B64.encode(nonce),
+        ciphertext: B64.encode(ciphertext),
+    })
+}
+
+fn open(bundle_json: &str, passphrase: &str) -> EngineResult<Vec<BundleEntry>> {
+    let bundle: Bundle = serde_json::from_str(bundle_json)
+        .map_err(|e| EngineError::Other(format!("Not a credential bundle: {}", e)))?;
+    if bundle.format != BUNDLE_FORMAT || bundle.version != BUNDLE_VERSION {
+        return Err(EngineError::Other(format!(
+            "Unsupported bundle {} v{}",
+            bundle.format, bundle.version
+        )));
+    }
+    if bundle.kdf.alg != "argon2id" {
+        return Err(EngineError::Other(format!(
+            "Unsupported bundle KDF '{}'",
+            bundle.kdf.alg
+        )));
+    }
+    let kdf = &bundle.kdf;
+    if kdf.m_cost > MAX_M_COST || kdf.t_cost > MAX_T_COST || kdf.p_cost > MAX_P_COST {
+        return Err(EngineError::Other(format!(
+            "Bundle KDF cost too high (m={} t={} p={})",
+            kdf.m_cost, kdf.t_cost, kdf.p_cost
+        )));
+    }
+    let decode = |s: &str| {
+        B64.decode(s)
+            .map_err(|e| EngineError::Other(format!("Corrupt bundle: {}", e)))
+    };
+    let salt = decode(&bundle.kdf.salt)?;
+    let nonce = decode(&bundle.nonce)?;
+    let ciphertext = decode(&bundle.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err(EngineError::Other("Corrupt bundle: bad nonce".into()));
+    }
+
+    let key = derive_key(
+        passphrase,
+        &salt,
+        bundle.kdf.m_cost,
+        bundle.kdf.t_cost,
+        bundle.kdf.p_cost,
+    )?;
+    let plaintext = Zeroizing::new(
+        Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| EngineError::Other("Invalid bundle key".into()))?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| EngineError::Other("Wrong passphrase or corrupted bundle".into()))?,
+    );
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, kind: CredentialKind, value: &str) -> BundleEntry {
+        BundleEntry {
+            item: CredentialItem {
+                id: id.into(),
+                kind,
+                label: id.into(),
+            },
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn seal_and_open_roundtrip() {
+        let entries = vec![
+            entry("skill:github:GITHUB_TOKEN", CredentialKind::Skill, "ghp_x"),
+            entry(
+                "channel:discord",
+                CredentialKind::Channel,
+                r#"{"bot_token":"t"}"#,
+            ),
+        ];
+        let bundle = seal(&entries, "correct horse", 256, 1).unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("ghp_x"));
+        assert!(!json.contains("GITHUB_TOKEN"));
+
+        let opened = open(&json, "correct horse").unwrap();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[0].value, "ghp_x");
+        assert_eq!(opened[1].item.kind, CredentialKind::Channel);
+    }
+
+    #[test]
+    fn wrong_passphrase_rejected() {
+        let bundle = seal(
+            &[entry("nostr:key", CredentialKind::NostrKey, "ab")],
+            "right pass",
+            256,
+            1,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(open(&json, "wrong pass").is_err());
+    }
+
+    #[test]
+    fn excessive_kdf_cost_rejected() {
+        let mut bundle = seal(
+            &[entry("nostr:key", CredentialKind::NostrKey, "ab")],
+            "right pass",
+            256,
+            1,
+        )
+        .unwrap();
+        bundle.kdf.m_cost = MAX_M_COST + 1;
+        let json = serde_json::to_string(&bundle).unwrap();
+        let err = open(&json, "right pass")
+            .err()
+            .expect("bundle with excessive KDF cost opened")
+            .to_string();
+        assert!(err.contains("too high"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_foreign_json() {
+        assert!(open(r#"{"hello": "world"}"#, "x").is_err());
+    }
+
+    #[test]
+    fn id_parsing() {
+        assert_eq!(
+            split_skill_id("skill:google:API_KEY").unwrap(),
+            ("google", "API_KEY")
+        );
+        assert!(split_skill_id("channel:discord").is_err());
+        assert_eq!(channel_config_key("channel:slack").unwrap(), "slack_config");
+        assert!(channel_config_key("channel:fax").is_err());
+    }
+}
@@ -0,0 +1,527 @@
+// Paw Agent Engine — Portable engine archive (export / import)
+//
+// Moves the whole engine database to another machine. The archive is a zip:
+//   manifest.json        — format, version, app version, row count per table
+//   tables/<name>.jsonl  — one JSON object per row (column → value; blobs
+//                          as { "$blob": base64 })
+//
+// Credentials stay on the machine: skill_credentials is skipped, channel
+// bridge configs are dropped and secret fields (API keys, tokens, passwords)
+// are blanked in the stored settings. Import keeps the target machine's own
+// secrets for those fields; move the rest with the credential bundle.
+// Machine-local tables (migration history, in-flight run journals, tool
+// embeddings, engram padding) and full-text index shadows are skipped too.
+//
+// Import matches tables and columns by name, so an archive from an older or
+// newer schema restores every column both sides have. Rows replace rows
+// with the same primary key, all in one transaction.
+//
+// Memories stored encrypted (sensitive / confidential tiers) stay encrypted
+// with this machine's memory key; the manifest counts them.
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::engram::encryption;
+use crate::engine::state::EngineState;
+use crate::engine::types::EngineConfig;
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use log::{info, warn};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Seek, Write};
+use tauri::Manager;
+
+const ARCHIVE_FORMAT: &str = "openpawz-engine-archive";
+const ARCHIVE_VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+
+/// Tables that never go into an archive.
+const SKIPPED_TABLES: &[&str] = &[
+    // Credentials — moved with the encrypted credential bundle instead
+    "skill_credentials",
+    // Machine-local state
+    "schema_migrations",
+    "inbound_journal",
+    "tool_idempotency",
+    "tool_embeddings",
+    "_engram_padding",
+];
+
+/// Name fragments of settings fields blanked on export.
+const SECRET_FIELDS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "token",
+    "password",
+    "secret",
+    "private_key",
+    "auth_key",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    /// Rows per archived table.
+    pub tables: BTreeMap<String, u64>,
+    /// Values still encrypted with the exporting machine's memory key.
+    pub encrypted_values: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveExport {
+    pub path: String,
+    pub manifest: ArchiveManifest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveImport {
+    pub manifest: ArchiveManifest,
+    /// Rows restored per table.
+    pub restored: BTreeMap<String, u64>,
+    /// Archived tables this database doesn't have.
+    pub skipped_tables: Vec<String>,
+}
+
+// ── Export ─────────────────────────────────────────────────────────────
+
+/// Write the engine database to an archive at `path` (default: a
+/// timestamped file under `<data dir>/exports`).
+pub fn export_all(
+    app_handle: &tauri::AppHandle,
+    path: Option<&str>,
+) -> EngineResult<ArchiveExport> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+    let path = match path {
+        Some(p) if !p.trim().is_empty() => std::path::PathBuf::from(p.trim()),
+        _ => {
+            let dir = crate::engine::paths::paw_data_dir().join("exports");
+            std::fs::create_dir_all(&dir)?;
+            dir.join(format!(
+                "openpawz-engine-{}.zip",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            ))
+        }
+    };
+    let file = std::fs::File::create(&path)?;
+    let manifest = {
+        let conn = state.store.conn.lock();
+        write_archive(&conn, file)?
+    };
+    info!(
+        "[archive] Exported {} tables to {}",
+        manifest.tables.len(),
+        path.display()
+    );
+    Ok(ArchiveExport {
+        path: path.to_string_lossy().to_string(),
+        manifest,
+    })
+}
+
+/// Serialize every archivable table of `conn` into a zip on `out`.
+pub fn write_archive<W: Write + Seek>(conn: &Connection, out: W) -> EngineResult<ArchiveManifest> {
+    let mut zip = zip::ZipWriter::new(out);
+    let opts = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| format!("Zip write failed: {}", e);
+
+    let mut manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT.into(),
+        version: ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").into(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        tables: BTreeMap::new(),
+        encrypted_values: 0,
+    };
+
+    for table in archivable_tables(conn)? {
+        zip.start_file(format!("tables/{}.jsonl", table), opts)
+            .map_err(zip_err)?;
+        let mut stmt = conn.prepare(&format!("SELECT * FROM \"{}\"", table))?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let mut rows = stmt.query([])?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let mut obj = Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                let value = to_json(row.get_ref(i)?);
+                if value.as_str().is_some_and(encryption::is_encrypted) {
+                    manifest.encrypted_values += 1;
+                }
+                obj.insert(column.clone(), value);
+            }
+            if table == "engine_config" && !export_setting(&mut obj) {
+                continue;
+            }
+            serde_json::to_writer(&mut zip, &obj)?;
+            zip.write_all(b"\n")?;
+            count += 1;
+        }
+        manifest.tables.insert(table, count);
+    }
+
+    zip.start_file(MANIFEST, opts).map_err(zip_err)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish().map_err(zip_err)?;
+    Ok(manifest)
+}
+
+/// Regular tables minus skipped ones and full-text index shadows.
+fn archivable_tables(conn: &Connection) -> EngineResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, COALESCE(sql, '') FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let tables: Vec<(String, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    let virtual_tables: Vec<&str> = tables
+        .iter()
+        .filter(|(_, sql)| sql.to_uppercase().starts_with("CREATE VIRTUAL TABLE"))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    Ok(tables
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| !SKIPPED_TABLES.contains(&name.as_str()))
+        .filter(|name| {
+            !virtual_tables
+                .iter()
+                .any(|v| *name == v || name.starts_with(&format!("{}_", v)))
+        })
+        .cloned()
+        .collect())
+}
+
+/// Drop bridge configs and blank secrets in a stored setting. Returns false
+/// to leave the row out.
+fn export_setting(row: &mut Map<String, serde_json::Value>) -> bool {
+    let key = row["key"].as_str().unwrap_or_default();
+    if crate::engine::profiles::BRIDGES
+        .iter()
+        .any(|b| key == format!("{}_config", b))
+    {
+        return false;
+    }
+    if let Some(mut value) = row["value"]
+        .as_str()
+        .and_then(|v| serde_json::from_str::<serde_json::Value>(v).ok())
+    {
+        blank_secrets(&mut value);
+        row.insert("value".into(), value.to_string().into());
+    }
+    true
+}
+
+fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_FIELDS.iter().any(|s| name.contains(s))
+}
+
+fn blank_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if v.is_string() && is_secret_field(k) {
+                    *v = "".into();
+                } else {
+                    blank_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(blank_secrets),
+        _ => {}
+    }
+}
+
+fn to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(b) => serde_json::json!({ "$blob": B64.encode(b) }),
+    }
+}
+
+// ── Import ─────────────────────────────────────────────────────────────
+
+/// Restore an archive into the engine database, then reload the engine
+/// config. Other subsystems pick up restored settings on the next start.
+pub fn import_all(app_handle: &tauri::AppHandle, path: &str) -> EngineResult<ArchiveImport> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+    let file = std::fs::File::open(path.trim())
+        .map_err(|e| format!("Cannot open archive {}: {}", path, e))?;
+    let result = {
+        let mut conn = state.store.conn.lock();
+        read_archive(&mut conn, file)?
+    };
+
+    if let Some(json) = state.store.get_config("engine_config")? {
+        match serde_json::from_str::<EngineConfig>(&json) {
+            Ok(config) => *state.config.lock() = config,
+            Err(e) => warn!("[archive] Restored engine config doesn't parse: {}", e),
+        }
+    }
+    info!(
+        "[archive] Imported {} tables from {}",
+        result.restored.len(),
+        path
+    );
+    Ok(result)
+}
+
+/// Restore every table of the archive on `input` that `conn` also has.
+pub fn read_archive<R: Read + Seek>(
+    conn: &mut Connection,
+    input: R,
+) -> EngineResult<ArchiveImport> {
+    let zip_err = |e: zip::result::ZipError| format!("Not an engine archive: {}", e);
+    let mut zip = zip::ZipArchive::new(input).map_err(zip_err)?;
+    let manifest: ArchiveManifest = {
+        let file = zip.by_name(MANIFEST).map_err(zip_err)?;
+        serde_json::from_reader(file)?
+    };
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(EngineError::Other(format!(
+            "Not an engine archive (format '{}')",
+            manifest.format
+        )));
+    }
+    if manifest.version > ARCHIVE_VERSION {
+        return Err(EngineError::Other(format!(
+            "This archive (v{}, from Paw {}) is newer than this app understands — update first",
+            manifest.version, manifest.app_version
+        )));
+    }
+
+    let local_tables = archivable_tables(conn)?;
+    let tx = conn.transaction()?;
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+    let mut restored = BTreeMap::new();
+    let mut skipped_tables = Vec::new();
+    for table in manifest.tables.keys() {
+        if !local_tables.contains(table) {
+            skipped_tables.push(table.clone());
+            continue;
+        }
+        let columns = table_columns(&tx, table)?;
+        let file = zip
+            .by_name(&format!("tables/{}.jsonl", table))
+            .map_err(zip_err)?;
+        let mut count = 0;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut row: Map<String, serde_json::Value> = serde_json::from_str(&line)?;
+            row.retain(|k, _| columns.contains(k));
+            if row.is_empty() {
+                continue;
+            }
+            if table == "engine_config" {
+                keep_local_secrets(&tx, &mut row)?;
+            }
+            let names: Vec<String> = row.keys().map(|k| format!("\"{}\"", k)).collect();
+            let sql = format!(
+                "INSERT OR REPLACE INTO \"{}\" ({}) VALUES ({})",
+                table,
+                names.join(", "),
+                vec!["?"; names.len()].join(", ")
+            );
+            let values: Vec<Value> = row.into_values().map(from_json).collect();
+            tx.prepare_cached(&sql)?
+                .execute(rusqlite::params_from_iter(values))?;
+            count += 1;
+        }
+        restored.insert(table.clone(), count);
+    }
+    tx.commit()?;
+
+    Ok(ArchiveImport {
+        manifest,
+        restored,
+        skipped_tables,
+    })
+}
+
+fn table_columns(conn: &Connection, table: &str) -> EngineResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(\"{}\")", table))?;
+    let columns = stmt
+        .query_map([], |r| r.get::<_, String>(1))?
+        .collect::<Result<_, _>>()?;
+    Ok(columns)
+}
+
+/// Fill the secrets blanked on export from this machine's own setting.
+fn keep_local_secrets(
+    conn: &Connection,
+    row: &mut Map<String, serde_json::Value>,
+) -> EngineResult<()> {
+    let Some(key) = row.get("key").and_then(|k| k.as_str()) else {
+        return Ok(());
+    };
+    let local: Option<String> = conn
+        .query_row(
+            "SELECT value FROM engine_config WHERE key = ?1",
+            [key],
+            |r| r.get(0),
+        )
+        .ok();
+    let (Some(local), Some(incoming)) = (local, row.get("value").and_then(|v| v.as_str())) else {
+        return Ok(());
+    };
+    if let (Ok(local), Ok(mut incoming)) = (
+        serde_json::from_str::<serde_json::Value>(&local),
+        serde_json::from_str::<serde_json::Value>(incoming),
+    ) {
+        restore_secrets(&mut incoming, &local);
+        row.insert("value".into(), incoming.to_string().into());
+    }
+    Ok(())
+}
+
+/// Copy secret fields that are blank in `incoming` from `local`. Array
+/// items are matched by their `id` when they have one.
+fn restore_secrets(incoming: &mut serde_json::Value, local: &serde_json::Value) {
+    match incoming {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                let Some(local_v) = local.get(k) else {
+                    continue;
+                };
+                if v.as_str() == Some("") && is_secret_field(k) {
+                    *v = local_v.clone();
+                } else {
+                    restore_secrets(v, local_v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let Some(local_items) = local.as_array() else {
+                return;
+            };
+            for (i, item) in items.iter_mut().enumerate() {
+                let matching = match item.get("id") {
+                    Some(id) => local_items.iter().find(|l| l.get("id") == Some(id)),
+                    None => local_items.get(i),
+                };
+                if let Some(local_item) = matching {
+                    restore_secrets(item, local_item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn from_json(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s),
+        serde_json::Value::Object(ref map) => match map.get("$blob").and_then(|b| b.as_str()) {
+            Some(b64) => B64
+                .decode(b64)
+                .map(Value::Blob)
+                .unwrap_or(Value::Text(value.to_string())),
+            None => Value::Text(value.to_string()),
+        },
+        serde_json::Value::Array(_) => Value::Text(value.to_string()),
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE sessions (id TEXT PRIMARY KEY, label TEXT, pinned INTEGER, icon BLOB);
+             CREATE TABLE engine_config (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             CREATE TABLE skill_credentials (skill_id TEXT, value TEXT);
+             CREATE VIRTUAL TABLE notes_fts USING fts5(body);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn round_trip_skips_credentials_and_keeps_local_keys() {
+        let src = db();
+        src.execute_batch(
+            r#"INSERT INTO sessions VALUES ('s1', 'Trip planning', 1, x'00ff');
+               INSERT INTO skill_credentials VALUES ('github', 'ghp_secret');
+               INSERT INTO notes_fts VALUES ('indexed');
+               INSERT INTO engine_config VALUES ('telegram_config', '{"bot_token":"123:abc"}');
+               INSERT INTO engine_config VALUES ('engine_config',
+                 '{"providers":[{"id":"anthropic","api_key":"sk-old"}],"max_tool_rounds":30}');"#,
+        )
+        .unwrap();
+        let mut buf = Cursor::new(Vec::new());
+        let manifest = write_archive(&src, &mut buf).unwrap();
+        assert_eq!(
+            manifest.tables.keys().collect::<Vec<_>>(),
+            ["engine_config", "sessions"]
+        );
+        assert_eq!(manifest.tables["engine_config"], 1);
+
+        // The new machine has its own key and an older schema (no `icon`)
+        let mut dst = Connection::open_in_memory().unwrap();
+        dst.execute_batch(
+            r#"CREATE TABLE sessions (id TEXT PRIMARY KEY, label TEXT, pinned INTEGER);
+               CREATE TABLE engine_config (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+               INSERT INTO engine_config VALUES ('engine_config',
+                 '{"providers":[{"id":"anthropic","api_key":"sk-new"}]}');"#,
+        )
+        .unwrap();
+        buf.set_position(0);
+        let result = read_archive(&mut dst, buf).unwrap();
+        assert_eq!(result.restored["sessions"], 1);
+        assert!(result.skipped_tables.is_empty());
+
+        let label: String = dst
+            .query_row("SELECT label FROM sessions WHERE id = 's1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(label, "Trip planning");
+        let config: String = dst
+            .query_row(
+                "SELECT value FROM engine_config WHERE key = 'engine_config'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        let config: serde_json::Value = serde_json::from_str(&config).unwrap();
+        assert_eq!(config["providers"][0]["api_key"], "sk-new");
+        assert_eq!(config["max_tool_rounds"], 30);
+    }
+
+    #[test]
+    fn blobs_survive_the_json_round_trip() {
+        let value = to_json(ValueRef::Blob(&[0, 255, 7]));
+        assert_eq!(from_json(value), Value::Blob(vec![0, 255, 7]));
+        assert_eq!(from_json(to_json(ValueRef::Real(1.5))), Value::Real(1.5));
+        assert_eq!(from_json(serde_json::json!(true)), Value::Integer(1));
+    }
+}
@@ -0,0 +1,588 @@
+// Paw Agent Engine — Self-test & Diagnostics Bundle
+//
+// Connectivity self-tests (providers, channel bridges, OS keychain, DB
+// integrity), a static config lint that flags common misconfigurations, and
+// a zip exporter that packages the report with redacted config and recent
+// logs for attaching to bug reports.
+//
+// Nothing secret leaves the machine: API keys, header values and anything
+// that looks like a token are replaced with [REDACTED] before writing.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{with_store, EngineState};
+use crate::engine::types::EngineConfig;
+use log::{info, warn};
+use regex::Regex;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// Config key marking that the first-run self-test has completed.
+const FIRST_RUN_KEY: &str = "diagnostics_first_run_done";
+
+/// Per-provider connectivity timeout.
+const PROVIDER_TIMEOUT_SECS: u64 = 10;
+
+/// Maximum bytes of each log file included in the bundle (tail).
+const MAX_LOG_BYTES: usize = 2_000_000;
+
+// ── Report types ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestCheck {
+    /// providers | bridges | keychain | database | config
+    pub category: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl SelfTestCheck {
+    fn new(category: &str, name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        SelfTestCheck {
+            category: category.into(),
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    pub fn problems(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|c| c.status != CheckStatus::Ok)
+    }
+}
+
+/// Result of `engine_diagnostics_export`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsExport {
+    pub path: String,
+    pub report: SelfTestReport,
+}
+
+// ── Config lint (pure) ─────────────────────────────────────────────────────
+
+/// Flag common misconfigurations without any network access.
+pub fn lint_config(cfg: &EngineConfig) -> Vec<SelfTestCheck> {
+    let mut checks = Vec::new();
+    let c = |status, name: &str, detail: String| SelfTestCheck::new("config", name, status, detail);
+
+    if cfg.providers.is_empty() {
+        checks.push(c(
+            CheckStatus::Fail,
+            "providers",
+            "No AI providers configured — add one in Settings → Models.".into(),
+        ));
+        return checks;
+    }
+
+    match cfg.default_provider.as_deref() {
+        None => checks.push(c(
+            CheckStatus::Warn,
+            "default_provider",
+            "No default provider set — the first provider will be used.".into(),
+        )),
+        Some(id) if !cfg.providers.iter().any(|p| p.id == id) => checks.push(c(
+            CheckStatus::Fail,
+            "default_provider",
+            format!("Default provider '{}' does not exist.", id),
+        )),
+        _ => {}
+    }
+
+    if cfg
+        .default_model
+        .as_deref()
+        .is_none_or(|m| m.trim().is_empty())
+    {
+        checks.push(c(
+            CheckStatus::Warn,
+            "default_model",
+            "No default model set.".into(),
+        ));
+    }
+
+    for p in &cfg.providers {
+        let needs_key = !matches!(
+            p.kind,
+            crate::engine::types::ProviderKind::Ollama | crate::engine::types::ProviderKind::Custom
+        );
+        if needs_key && p.api_key.trim().is_empty() {
+            checks.push(c(
+                CheckStatus::Fail,
+                &format!("provider:{}", p.id),
+                "API key is empty.".into(),
+            ));
+        }
+        if let Some(url) = p.base_url.as_deref().filter(|u| !u.is_empty()) {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                checks.push(c(
+                    CheckStatus::Fail,
+                    &format!("provider:{}", p.id),
+                    format!("Base URL '{}' is missing http(s)://", url),
+                ));
+            }
+        }
+    }
+
+    if cfg.daily_budget_usd < 0.0 {
+        checks.push(c(
+            CheckStatus::Warn,
+            "daily_budget_usd",
+            "Daily budget is negative — budget enforcement is effectively off.".into(),
+        ));
+    }
+    if cfg.max_concurrent_runs == 0 {
+        checks.push(c(
+            CheckStatus::Fail,
+            "max_concurrent_runs",
+            "max_concurrent_runs is 0 — no agent run can start.".into(),
+        ));
+    }
+    if cfg.context_window_tokens < 4_000 {
+        checks.push(c(
+            CheckStatus::Warn,
+            "context_window_tokens",
+            format!(
+                "Context window of {} tokens is very small — agents will lose track of the conversation.",
+                cfg.context_window_tokens
+            ),
+        ));
+    }
+    if let Some(worker) = cfg.model_routing.worker_model.as_deref() {
+        if !worker.is_empty()
+            && crate::engine::state::resolve_provider_for_model(worker, &cfg.providers).is_none()
+        {
+            checks.push(c(
+                CheckStatus::Warn,
+                "worker_model",
+                format!("No provider matches worker model '{}'.", worker),
+            ));
+        }
+    }
+
+    if checks.is_empty() {
+        checks.push(c(
+            CheckStatus::Ok,
+            "engine_config",
+            "No problems found.".into(),
+        ));
+    }
+    checks
+}
+
+// ── Redaction (pure) ───────────────────────────────────────────────────────
+
+static SECRET_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"sk-[A-Za-z0-9_\-]{16,}",
+        r"AIza[0-9A-Za-z_\-]{30,}",
+        r"gh[pousr]_[A-Za-z0-9]{20,}",
+        r"xox[abposr]-[A-Za-z0-9\-]{10,}",
+        r"(?i)bearer\s+[A-Za-z0-9._\-]{16,}",
+        r"(?i)(api[_-]?key|x-api-key|authorization|password|secret|token)(\x22?\s*[:=]\s*\x22?)[^\s\x22,}]+",
+    ]
+    .iter()
+    .filter_map(|p| Regex::new(p).ok())
+    .collect()
+});
+
+/// Replace anything that looks like a credential with `[REDACTED]`.
+pub fn redact_secrets(text: &str) -> String {
+    let mut out = crate::engine::engram::encryption::redact_for_log(text);
+    for (i, re) in SECRET_PATTERNS.iter().enumerate() {
+        out = if i == SECRET_PATTERNS.len() - 1 {
+            re.replace_all(&out, "$1$2[REDACTED]").to_string()
+        } else {
+            re.replace_all(&out, "[REDACTED]").to_string()
+        };
+    }
+    out
+}
+
+/// Serialize the engine config with every secret field blanked.
+pub fn redact_config(cfg: &EngineConfig) -> serde_json::Value {
+    let mut cfg = cfg.clone();
+    for p in &mut cfg.providers {
+        if !p.api_key.is_empty() {
+            p.api_key = "[REDACTED]".into();
+        }
+        for v in p.extra_headers.values_mut() {
+            *v = "[REDACTED]".into();
+        }
+        // Some gateways take their key in the body (e.g. "api_key")
+        for v in p.extra_body.values_mut() {
+            *v = "[REDACTED]".into();
+        }
+    }
+    for h in &mut cfg.provider_middleware.headers {
+        h.value = "[REDACTED]".into();
+    }
+    // Down to the place name's precision (~10 km), not the street
+    if let Some(home) = &mut cfg.home_location {
+        home.latitude = (home.latitude * 10.0).round() / 10.0;
+        home.longitude = (home.longitude * 10.0).round() / 10.0;
+    }
+    serde_json::to_value(&cfg).unwrap_or(serde_json::Value::Null)
+}
+
+// ── Self-test (side effects) ───────────────────────────────────────────────
+
+/// Run every self-test. `connectivity` adds live provider calls.
+pub async fn run_self_test(app_handle: &tauri::AppHandle, connectivity: bool) -> SelfTestReport {
+    let mut checks = Vec::new();
+
+    let cfg = app_handle
+        .try_state::<EngineState>()
+        .map(|s| s.config.lock().clone());
+    if let Some(ref cfg) = cfg {
+        checks.extend(lint_config(cfg));
+    }
+
+    // Keychain
+    checks.push(match crate::engine::key_vault::keychain_status() {
+        Ok(()) => SelfTestCheck::new("keychain", "os_keychain", CheckStatus::Ok, "Reachable"),
+        Err(e) => SelfTestCheck::new("keychain", "os_keychain", CheckStatus::Fail, e),
+    });
+
+    // Database integrity
+    if app_handle.try_state::<EngineState>().is_some() {
+        // A full scan of the database: on a reader, off the async runtime
+        let result = with_store(app_handle, |store| {
+            let conn = store.read_conn();
+            let db = conn.lock();
+            Ok(db.query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0))?)
+        })
+        .await
+        .map_err(|e| e.to_string());
+        checks.push(match result {
+            Ok(r) if r == "ok" => {
+                SelfTestCheck::new("database", "integrity_check", CheckStatus::Ok, "ok")
+            }
+            Ok(r) => SelfTestCheck::new("database", "integrity_check", CheckStatus::Fail, r),
+            Err(e) => SelfTestCheck::new("database", "integrity_check", CheckStatus::Fail, e),
+        });
+    }
+
+    // Channel bridges — only report the ones that are running.
+    for (name, running, connected) in bridge_statuses(app_handle) {
+        if running {
+            checks.push(if connected {
+                SelfTestCheck::new("bridges", &name, CheckStatus::Ok, "Connected")
+            } else {
+                SelfTestCheck::new(
+                    "bridges",
+                    &name,
+                    CheckStatus::Warn,
+                    "Running but not connected",
+                )
+            });
+        }
+    }
+
+    // Providers
+    if connectivity {
+        if let Some(ref cfg) = cfg {
+            for p in &cfg.providers {
+                checks.push(check_provider(p).await);
+            }
+        }
+    }
+
+    SelfTestReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        checks,
+    }
+}
+
+async fn check_provider(p: &crate::engine::types::ProviderConfig) -> SelfTestCheck {
+    let name = format!("provider:{}", p.id);
+    let provider = AnyProvider::from_config(p);
+    match tokio::time::timeout(
+        Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+        provider.list_models(),
+    )
+    .await
+    {
+        Ok(Ok(models)) => SelfTestCheck::new(
+            "providers",
+            &name,
+            CheckStatus::Ok,
+            format!("Reachable ({} models)", models.len()),
+        ),
+        Ok(Err(e)) if e.to_string().starts_with("unsupported") => SelfTestCheck::new(
+            "providers",
+            &name,
+            CheckStatus::Ok,
+            "Model listing unsupported — connectivity not verified",
+        ),
+        Ok(Err(e)) => SelfTestCheck::new(
+            "providers",
+            &name,
+            CheckStatus::Fail,
+            redact_secrets(&e.to_string()),
+        ),
+        Err(_) => SelfTestCheck::new(
+            "providers",
+            &name,
+            CheckStatus::Fail,
+            format!("Timed out after {}s", PROVIDER_TIMEOUT_SECS),
+        ),
+    }
+}
+
+fn bridge_statuses(app: &tauri::AppHandle) -> Vec<(String, bool, bool)> {
+    use crate::engine as e;
+    let tg = e::telegram::get_status(app);
+    let mut out = vec![("telegram".to_string(), tg.running, tg.connected)];
+    for (name, s) in [
+        ("discord", e::discord::get_status(app)),
+        ("slack", e::slack::get_status(app)),
+        ("matrix", e::matrix::get_status(app)),
+        ("mattermost", e::mattermost::get_status(app)),
+        ("irc", e::irc::get_status(app)),
+        ("twitch", e::twitch::get_status(app)),
+        ("nextcloud", e::nextcloud::get_status(app)),
+        ("nostr", e::nostr::get_status(app)),
+        ("webchat", e::webchat::get_status(app)),
+        ("whatsapp", e::whatsapp::bridge::get_status(app)),
+        ("email", e::email_bridge::get_status(app)),
+        ("webhook", e::webhook::get_status(app)),
+    ] {
+        out.push((name.to_string(), s.running, s.connected));
+    }
+    out
+}
+
+// ── Bundle export ──────────────────────────────────────────────────────────
+
+/// Run the full self-test and write a zip with the report, redacted
+/// config and redacted log tails. Returns the zip path and the report.
+pub async fn export_bundle(app_handle: &tauri::AppHandle) -> EngineResult<DiagnosticsExport> {
+    let report = run_self_test(app_handle, true).await;
+
+    let config_json = app_handle
+        .try_state::<EngineState>()
+        .map(|s| redact_config(&s.config.lock()))
+        .unwrap_or(serde_json::Value::Null);
+
+    let mut logs: Vec<(String, String)> = Vec::new();
+    if let Ok(dir) = app_handle.path().app_log_dir() {
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "log") {
+                    if let Ok(bytes) = std::fs::read(&path) {
+                        let tail = &bytes[bytes.len().saturating_sub(MAX_LOG_BYTES)..];
+                        let text = String::from_utf8_lossy(tail);
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        logs.push((name, redact_secrets(&text)));
+                    }
+                }
+            }
+        }
+    }
+
+    let out_dir = crate::engine::paths::paw_data_dir().join("diagnostics");
+    std::fs::create_dir_all(&out_dir)?;
+    let path = out_dir.join(format!(
+        "openpawz-diagnostics-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    write_zip(&path, &report, &config_json, &logs)?;
+
+    info!(
+        "[diagnostics] Bundle written to {} ({} checks, {} logs)",
+        path.display(),
+        report.checks.len(),
+        logs.len()
+    );
+    Ok(DiagnosticsExport {
+        path: path.to_string_lossy().to_string(),
+        report,
+    })
+}
+
+fn write_zip(
+    path: &std::path::Path,
+    report: &SelfTestReport,
+    config_json: &serde_json::Value,
+    logs: &[(String, String)],
+) -> EngineResult<()> {
+    let file = std::fs::File::create(path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let opts = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| format!("Zip write failed: {}", e);
+
+    zip.start_file("self-test.json", opts).map_err(zip_err)?;
+    zip.write_all(serde_json::to_string_pretty(report)?.as_bytes())?;
+    zip.start_file("engine-config.json", opts)
+        .map_err(zip_err)?;
+    zip.write_all(serde_json::to_string_pretty(config_json)?.as_bytes())?;
+    for (name, text) in logs {
+        zip.start_file(format!("logs/{}", name), opts)
+            .map_err(zip_err)?;
+        zip.write_all(text.as_bytes())?;
+    }
+    zip.finish().map_err(zip_err)?;
+    Ok(())
+}
+
+// ── First-run self-test ────────────────────────────────────────────────────
+
+/// Run the self-test once per install and warn about misconfigurations.
+/// Problems are logged and emitted to the frontend as `engine-diagnostics`.
+pub async fn first_run_self_test(app_handle: &tauri::AppHandle) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    if state
+        .store
+        .get_config(FIRST_RUN_KEY)
+        .ok()
+        .flatten()
+        .is_some()
+    {
+        return;
+    }
+
+    let report = run_self_test(app_handle, false).await;
+    let problems: Vec<&SelfTestCheck> = report.problems().collect();
+    for p in &problems {
+        warn!(
+            "[diagnostics] {} / {}: {:?} — {}",
+            p.category, p.name, p.status, p.detail
+        );
+    }
+    if !problems.is_empty() {
+        let _ = app_handle.emit("engine-diagnostics", &report);
+    }
+    info!(
+        "[diagnostics] First-run self-test complete: {} checks, {} problems",
+        report.checks.len(),
+        problems.len()
+    );
+    state.store.set_config(FIRST_RUN_KEY, "1").ok();
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::{ProviderConfig, ProviderKind};
+
+    fn provider(id: &str, kind: ProviderKind, key: &str) -> ProviderConfig {
+        ProviderConfig {
+            id: id.into(),
+            kind,
+            api_key: key.into(),
+            base_url: None,
+            default_model: None,
+            extra_body: Default::default(),
+            extra_headers: Default::default(),
+            responses_api: false,
+            responses_tools: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lint_flags_missing_providers() {
+        let cfg = EngineConfig::default();
+        let checks = lint_config(&cfg);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn lint_flags_unknown_default_and_empty_key() {
+        let cfg = EngineConfig {
+            providers: vec![provider("openai", ProviderKind::OpenAI, "")],
+            default_provider: Some("anthropic".into()),
+            default_model: Some("gpt-4o".into()),
+            ..Default::default()
+        };
+        let checks = lint_config(&cfg);
+        assert!(checks
+            .iter()
+            .any(|c| c.name == "default_provider" && c.status == CheckStatus::Fail));
+        assert!(checks
+            .iter()
+            .any(|c| c.name == "provider:openai" && c.detail.contains("API key")));
+    }
+
+    #[test]
+    fn lint_ok_for_local_ollama() {
+        let cfg = EngineConfig {
+            providers: vec![provider("ollama", ProviderKind::Ollama, "")],
+            default_provider: Some("ollama".into()),
+            default_model: Some("llama3.2:3b".into()),
+            ..Default::default()
+        };
+        let checks = lint_config(&cfg);
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Ok));
+    }
+
+    #[test]
+    fn redact_config_blanks_keys() {
+        let mut p = provider("openai", ProviderKind::OpenAI, "sk-live-123");
+        p.extra_headers
+            .insert("x-litellm-key".into(), "secret".into());
+        p.extra_body.insert("api_key".into(), "body-secret".into());
+        let cfg = EngineConfig {
+            providers: vec![p],
+            home_location: Some(crate::atoms::types::HomeLocation {
+                name: "Lyon".into(),
+                region: String::new(),
+                country: "France".into(),
+                latitude: 45.764043,
+                longitude: 4.835659,
+                timezone: None,
+                source: "manual".into(),
+                share_with_agents: true,
+            }),
+            ..Default::default()
+        };
+        let redacted = redact_config(&cfg);
+        let json = redacted.to_string();
+        assert!(!json.contains("sk-live-123"));
+        assert!(!json.contains("\"secret\""));
+        assert!(!json.contains("body-secret"));
+        assert!(!json.contains("45.764043") && !json.contains("4.835659"));
+        assert_eq!(redacted["home_location"]["latitude"], 45.8);
+        assert_eq!(redacted["home_location"]["longitude"], 4.8);
+    }
+
+    #[test]
+    fn redact_secrets_in_logs() {
+        let line = "auth header Bearer abcdefghijklmnopqrstuvwxyz key sk-abcdefghijklmnopqrstu";
+        let out = redact_secrets(line);
+        assert!(!out.contains("abcdefghijklmnopqrstuvwxyz"));
+        assert!(!out.contains("sk-abcdefghijklmnopqrstu"));
+        let kv = redact_secrets(r#"{"api_key": "plain-value"}"#);
+        assert!(!kv.contains("plain-value"));
+    }
+}
@@ -192,6 +192,7 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
 
                     if is_fatal {
                         error!("[discord] Fatal: {} — stopping (user must fix config)", msg);
+                        channels::mark_stopped(&app_handle, "discord", Some(&msg));
                         let _ = app_handle.emit(
                             "discord-status",
                             json!({
@@ -212,7 +213,13 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
                     }
 
                     error!("[discord] Bridge error: {} — reconnecting", e);
-                    let delay = crate::engine::http::reconnect_delay(reconnect_attempt - 1).await;
+                    let delay = channels::reconnect_backoff(
+                        &app_handle,
+                        "discord",
+                        reconnect_attempt - 1,
+                        &msg,
+                    )
+                    .await;
                     warn!(
                         "[discord] Reconnecting in {}ms (attempt {})",
                         delay.as_millis(),
@@ -260,6 +267,9 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
             }
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        if channels::connectivity("discord").state != channels::ConnectionState::Disconnected {
+            channels::mark_stopped(&app_handle, "discord", None);
+        }
         info!("[discord] Bridge stopped");
     });
 
@@ -285,6 +295,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("discord"),
     }
 }
 
@@ -381,6 +392,9 @@ async fn run_gateway_loop(app_handle: tauri::AppHandle, config: DiscordConfig) -
 
     let hb_write = Arc::new(tokio::sync::Mutex::new(write));
     let hb_write_clone = hb_write.clone();
+    // When the last heartbeat went out — its ACK (op 11) gives the latency
+    let hb_sent = Arc::new(parking_lot::Mutex::new(None::<std::time::Instant>));
+    let hb_sent_clone = hb_sent.clone();
 
     let heartbeat_task = tauri::async_runtime::spawn(async move {
         loop {
@@ -397,6 +411,7 @@ async fn run_gateway_loop(app_handle: tauri::AppHandle, config: DiscordConfig) -
                 warn!("[discord] Heartbeat send failed: {}", e);
                 break;
             }
+            *hb_sent_clone.lock() = Some(std::time::Instant::now());
         }
     });
 
@@ -482,6 +497,7 @@ async fn run_gateway_loop(app_handle: tauri::AppHandle, config: DiscordConfig) -
                                 _session_id_discord = Some(ready.session_id);
                                 _resume_url = Some(ready.resume_gateway_url);
 
+                                channels::mark_connected(&app_handle, "discord");
                                 let _ = app_handle.emit(
                                     "discord-status",
                                     json!({
@@ -595,6 +611,7 @@ async fn run_gateway_loop(app_handle: tauri::AppHandle, config: DiscordConfig) -
                                                 "username": &username,
                                             }),
                                         );
+                                        channels::notify_pairing(&app_handle, "Discord", &username);
                                         let _ = send_message(
                                             &http_client,
                                             &token,
@@ -689,7 +706,11 @@ async fn run_gateway_loop(app_handle: tauri::AppHandle, config: DiscordConfig) -
                 }
             }
             // Heartbeat ACK
-            11 => {}
+            11 => {
+                if let Some(sent) = hb_sent.lock().take() {
+                    channels::record_latency(&app_handle, "discord", sent.elapsed());
+                }
+            }
             // Reconnect
             7 => {
                 info!("[discord] Gateway requested reconnect");
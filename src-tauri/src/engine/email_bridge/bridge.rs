@@ -0,0 +1,279 @@
+// Email Bridge — Core Bridge Lifecycle
+// statics, start_bridge, stop_bridge, get_status, run_session (IMAP IDLE)
+
+use super::config::{EmailBridgeConfig, CONFIG_KEY};
+use super::messages::handle_inbound_email;
+use super::parse::InboundEmail;
+use crate::atoms::error::EngineResult;
+use crate::engine::channels::{self, ChannelStatus};
+use crate::engine::mail_watch::{self, Imap, ImapAccount, Response};
+use log::{error, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// ── Global State ───────────────────────────────────────────────────────
+
+/// Channel name for sessions, connectivity and profiles.
+pub(crate) const CHANNEL: &str = "email";
+
+static BRIDGE_RUNNING: AtomicBool = AtomicBool::new(false);
+pub(crate) static MESSAGE_COUNT: AtomicI64 = AtomicI64::new(0);
+static STOP_SIGNAL: std::sync::OnceLock<Arc<AtomicBool>> = std::sync::OnceLock::new();
+
+fn get_stop_signal() -> Arc<AtomicBool> {
+    STOP_SIGNAL
+        .get_or_init(|| Arc::new(AtomicBool::new(false)))
+        .clone()
+}
+
+/// A connection that lived this long resets the reconnect backoff.
+const STABLE_SESSION: Duration = Duration::from_secs(300);
+
+/// Where the bridge is in the folder: every message below `next_uid` has
+/// been seen. Kept across reconnects, so mail that arrives while the
+/// connection is down is still answered.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Cursor {
+    uid_validity: Option<u32>,
+    /// 0 until the first connection
+    next_uid: u32,
+}
+
+// ── Bridge Control ─────────────────────────────────────────────────────
+
+pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
+    if BRIDGE_RUNNING.load(Ordering::Relaxed) {
+        return Err("Email bridge is already running".into());
+    }
+
+    let config: EmailBridgeConfig = channels::load_channel_config(&app_handle, CONFIG_KEY)?;
+    if !config.enabled {
+        return Err("Email bridge is disabled.".into());
+    }
+    if config.dm_policy != "open" && config.authserv_id.trim().is_empty() {
+        return Err("Set the mail server's authserv-id to authenticate senders.".into());
+    }
+    let account = config.imap_account()?;
+    let folder = match config.folder.trim() {
+        "" => "INBOX".to_string(),
+        f => f.to_string(),
+    };
+
+    let stop = get_stop_signal();
+    stop.store(false, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(true, Ordering::Relaxed);
+
+    info!(
+        "[email] Starting bridge for {} ({}/{})",
+        account.email, account.name, folder
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let mut cursor = Cursor::default();
+        let mut reconnect_attempt: u32 = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let started = Instant::now();
+            let result = tokio::select! {
+                r = run_session(&app_handle, &account, &folder, &mut cursor) => r,
+                _ = stopped(&stop) => break,
+            };
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            if started.elapsed() >= STABLE_SESSION {
+                reconnect_attempt = 0;
+            }
+            let reason = match result {
+                Ok(()) => "Server closed the connection".to_string(),
+                Err(e) => e.to_string(),
+            };
+            error!("[email] {} — reconnecting", reason);
+            channels::reconnect_backoff(&app_handle, CHANNEL, reconnect_attempt, &reason).await;
+            reconnect_attempt += 1;
+        }
+        BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, CHANNEL, None);
+        info!("[email] Bridge stopped");
+    });
+
+    Ok(())
+}
+
+pub fn stop_bridge() {
+    let stop = get_stop_signal();
+    stop.store(true, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+    info!("[email] Stop signal sent");
+}
+
+pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
+    let config: EmailBridgeConfig =
+        channels::load_channel_config(app_handle, CONFIG_KEY).unwrap_or_default();
+    let account = config.imap_account().ok();
+    let running = BRIDGE_RUNNING.load(Ordering::Relaxed);
+    ChannelStatus {
+        running,
+        connected: running
+            && channels::connectivity(CHANNEL).state == channels::ConnectionState::Connected,
+        bot_name: account.as_ref().map(|a| a.email.clone()),
+        bot_id: account.map(|a| a.name),
+        message_count: MESSAGE_COUNT.load(Ordering::Relaxed) as u64,
+        allowed_users: config.allowed_users,
+        pending_users: config.pending_users,
+        dm_policy: config.dm_policy,
+        connectivity: channels::connectivity(CHANNEL),
+    }
+}
+
+/// Resolves once the stop signal is set (IDLE can otherwise hold the
+/// session for up to IDLE_RENEW).
+async fn stopped(stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+// ── IMAP Session ───────────────────────────────────────────────────────
+
+/// One connection: log in, select the folder, then answer new mail and
+/// wait in IDLE (or NOOP polling) until the connection drops.
+async fn run_session(
+    app_handle: &tauri::AppHandle,
+    account: &ImapAccount,
+    folder: &str,
+    cursor: &mut Cursor,
+) -> EngineResult<()> {
+    let mut imap = Imap::login(account).await?;
+    let idle = imap.supports_idle().await?;
+    let selected = imap
+        .command(&format!("SELECT {}", mail_watch::quote(folder)))
+        .await?;
+    let uid_validity = selected
+        .iter()
+        .find_map(|r| response_code(&r.line, "UIDVALIDITY"));
+
+    // First connection, or the server renumbered the folder: start at the
+    // next message instead of answering the whole folder
+    if cursor.next_uid == 0 || cursor.uid_validity != uid_validity {
+        let next_uid = match selected
+            .iter()
+            .find_map(|r| response_code(&r.line, "UIDNEXT"))
+        {
+            Some(uid) => uid,
+            None => {
+                let all = imap.command("UID SEARCH ALL").await?;
+                search_uids(&all, 0).last().map_or(1, |uid| uid + 1)
+            }
+        };
+        *cursor = Cursor {
+            uid_validity,
+            next_uid,
+        };
+    }
+
+    channels::mark_connected(app_handle, CHANNEL);
+    info!(
+        "[email] {}/{}: connected, {}",
+        account.name,
+        folder,
+        if idle { "idling" } else { "polling" }
+    );
+
+    loop {
+        answer_new(app_handle, &mut imap, account, cursor).await?;
+        let responses = if idle {
+            imap.idle(mail_watch::IDLE_RENEW).await?
+        } else {
+            tokio::time::sleep(mail_watch::POLL_INTERVAL).await;
+            imap.command("NOOP").await?
+        };
+        if responses.iter().any(|r| r.line.starts_with("* BYE")) {
+            return Ok(());
+        }
+    }
+}
+
+/// Answer every message at or above the cursor, oldest first.
+async fn answer_new(
+    app_handle: &tauri::AppHandle,
+    imap: &mut Imap,
+    account: &ImapAccount,
+    cursor: &mut Cursor,
+) -> EngineResult<()> {
+    let found = imap
+        .command(&format!("UID SEARCH UID {}:*", cursor.next_uid))
+        .await?;
+    for uid in search_uids(&found, cursor.next_uid) {
+        let fetched = imap
+            .command(&format!("UID FETCH {} (BODY.PEEK[])", uid))
+            .await?;
+        // Move on before answering: a message the agent fails on isn't
+        // retried on every reconnect
+        cursor.next_uid = uid + 1;
+        let Some(raw) = fetched.iter().find_map(|r| r.literal.as_deref()) else {
+            warn!("[email] UID {} vanished before it was fetched", uid);
+            continue;
+        };
+        if handle_inbound_email(app_handle, account, InboundEmail::parse(raw)).await {
+            imap.command(&format!(
+                "UID STORE {} +FLAGS.SILENT (\\Seen \\Answered)",
+                uid
+            ))
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// The number of a response code, e.g. `* OK [UIDNEXT 4392] Predicted`.
+fn response_code(line: &str, code: &str) -> Option<u32> {
+    let start = line.find(&format!("[{} ", code))? + code.len() + 2;
+    let end = start + line[start..].find(']')?;
+    line[start..end].trim().parse().ok()
+}
+
+/// UIDs from `* SEARCH` responses that are at least `from`, ascending.
+/// (`n:*` always matches the last message, even below `n`.)
+fn search_uids(responses: &[Response], from: u32) -> Vec<u32> {
+    let mut uids: Vec<u32> = responses
+        .iter()
+        .filter_map(|r| r.line.strip_prefix("* SEARCH"))
+        .flat_map(|rest| rest.split_whitespace().filter_map(|n| n.parse().ok()))
+        .filter(|&uid| uid >= from)
+        .collect();
+    uids.sort_unstable();
+    uids.dedup();
+    uids
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(l: &str) -> Response {
+        Response {
+            line: l.into(),
+            literal: None,
+        }
+    }
+
+    #[test]
+    fn select_and_search_responses_are_parsed() {
+        assert_eq!(
+            response_code("* OK [UIDNEXT 4392] Predicted next UID", "UIDNEXT"),
+            Some(4392)
+        );
+        assert_eq!(
+            response_code("* OK [UIDVALIDITY 3857529045] UIDs valid", "UIDVALIDITY"),
+            Some(3857529045)
+        );
+        assert_eq!(response_code("* 12 EXISTS", "UIDNEXT"), None);
+
+        let found = [line("* SEARCH 4391"), line("* SEARCH 4395 4393")];
+        assert_eq!(search_uids(&found, 4392), vec![4393, 4395]);
+        assert!(search_uids(&[line("* SEARCH")], 1).is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+// Email Bridge — Configuration
+// EmailBridgeConfig, CONFIG_KEY, load_config, save_config, approve/deny/remove_user
+
+use crate::atoms::error::EngineResult;
+use crate::engine::channels::{self, PendingUser};
+use crate::engine::mail_watch::{self, ImapAccount};
+use serde::{Deserialize, Serialize};
+
+// ── Constants ──────────────────────────────────────────────────────────
+
+pub(crate) const CONFIG_KEY: &str = "email_config";
+
+// ── Config Struct ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailBridgeConfig {
+    pub enabled: bool,
+    /// himalaya account to watch and reply from (empty = the first IMAP account)
+    pub account: String,
+    /// Folder to watch (default: "INBOX")
+    pub folder: String,
+    /// "open" | "allowlist" | "pairing"
+    pub dm_policy: String,
+    /// Allowed sender addresses (lower-case, e.g. "me@example.com")
+    pub allowed_users: Vec<String>,
+    /// authserv-id of the receiving mail server — the first token of the
+    /// Authentication-Results header it adds, e.g. "mx.google.com". Unless
+    /// dm_policy is "open", only mail that server authenticated is answered.
+    pub authserv_id: String,
+    pub pending_users: Vec<PendingUser>,
+    /// Which agent to route messages to
+    pub agent_id: Option<String>,
+    /// Allow dangerous/side-effect tools for messages from this channel
+    pub allow_dangerous_tools: bool,
+}
+
+impl Default for EmailBridgeConfig {
+    fn default() -> Self {
+        EmailBridgeConfig {
+            enabled: false,
+            account: String::new(),
+            folder: "INBOX".into(),
+            dm_policy: "pairing".into(),
+            allowed_users: vec![],
+            authserv_id: String::new(),
+            pending_users: vec![],
+            agent_id: None,
+            allow_dangerous_tools: false,
+        }
+    }
+}
+
+impl EmailBridgeConfig {
+    /// The IMAP account from the himalaya config this bridge uses.
+    pub(crate) fn imap_account(&self) -> EngineResult<ImapAccount> {
+        let name = self.account.trim();
+        let mut accounts = mail_watch::load_accounts().into_iter();
+        let found = if name.is_empty() {
+            accounts.next()
+        } else {
+            accounts.find(|a| a.name == name)
+        };
+        match found {
+            Some(account) => Ok(account),
+            None if name.is_empty() => {
+                Err("No IMAP mail account — set one up in Mail first.".into())
+            }
+            None => Err(format!("No IMAP mail account named '{}'.", name).into()),
+        }
+    }
+}
+
+// ── Config Persistence ─────────────────────────────────────────────────
+
+pub fn load_config(app_handle: &tauri::AppHandle) -> EngineResult<EmailBridgeConfig> {
+    channels::load_channel_config(app_handle, CONFIG_KEY)
+}
+
+pub fn save_config(app_handle: &tauri::AppHandle, config: &EmailBridgeConfig) -> EngineResult<()> {
+    channels::save_channel_config(app_handle, CONFIG_KEY, config)
+}
+
+pub fn approve_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::approve_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn deny_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::deny_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn remove_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::remove_user_generic(app_handle, CONFIG_KEY, user_id)
+}
@@ -0,0 +1,156 @@
+// Email Bridge — Inbound Message Handling
+// handle_inbound_email, send_reply
+
+use super::bridge::{CHANNEL, MESSAGE_COUNT};
+use super::config::{EmailBridgeConfig, CONFIG_KEY};
+use super::parse::InboundEmail;
+use crate::atoms::error::EngineResult;
+use crate::engine::channels;
+use crate::engine::email::{reply_subject, OutgoingEmail};
+use crate::engine::mail_client;
+use crate::engine::mail_watch::ImapAccount;
+use log::{debug, error, info};
+use serde_json::json;
+use std::sync::atomic::Ordering;
+use tauri::Emitter;
+
+/// Answer one new message. Returns whether a reply was sent, so the caller
+/// can flag the message as answered.
+pub(crate) async fn handle_inbound_email(
+    app_handle: &tauri::AppHandle,
+    account: &ImapAccount,
+    email: InboundEmail,
+) -> bool {
+    if email.from_address.is_empty() || email.from_address.eq_ignore_ascii_case(&account.email) {
+        return false;
+    }
+    // Load config for access control
+    let mut config: EmailBridgeConfig = match channels::load_channel_config(app_handle, CONFIG_KEY)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("[email] Load config: {}", e);
+            return false;
+        }
+    };
+
+    // The From header is whatever the sender wrote: allowlist and pairing
+    // decisions rest on it only when the receiving server authenticated it
+    let skip = if email.automated {
+        Some("automated")
+    } else if email.dmarc_failed {
+        Some("DMARC-failing")
+    } else if config.dm_policy != "open" && !email.sender_authenticated(&config.authserv_id) {
+        Some("unauthenticated")
+    } else {
+        None
+    };
+    if let Some(kind) = skip {
+        info!(
+            "[email] Skipping {} message from {}",
+            kind, email.from_address
+        );
+        return false;
+    }
+
+    debug!(
+        "[email] Message from {} ({}): {}",
+        email.from_name, email.from_address, email.subject
+    );
+    // Addresses are compared case-insensitively
+    let allowed: Vec<String> = config
+        .allowed_users
+        .iter()
+        .map(|u| u.trim().to_ascii_lowercase())
+        .collect();
+
+    // Access control
+    if let Err(denial_msg) = channels::check_access(
+        &config.dm_policy,
+        &email.from_address,
+        &email.from_address,
+        &email.from_name,
+        &allowed,
+        &mut config.pending_users,
+    ) {
+        let _ = channels::save_channel_config(app_handle, CONFIG_KEY, &config);
+        let _ = app_handle.emit(
+            "email-status",
+            json!({
+                "kind": "pairing_request",
+                "user_id": &email.from_address,
+                "user_name": &email.from_name,
+            }),
+        );
+        // Only pairing answers strangers: with an allowlist, a reply to
+        // every unknown sender would just confirm the address to spammers
+        if config.dm_policy != "pairing" {
+            return false;
+        }
+        channels::notify_pairing(app_handle, "Email", &email.from_name);
+        return match send_reply(account, &email, &denial_msg.to_string()).await {
+            Ok(()) => true,
+            Err(e) => {
+                error!("[email] Send failed: {}", e);
+                false
+            }
+        };
+    }
+
+    MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+
+    // Route to agent
+    let agent_id = config.agent_id.as_deref().unwrap_or("default");
+    let ctx = "You are answering an email. Your reply is sent as a plain-text email in \
+               the same thread: write only the body — no subject line, no Markdown, and \
+               no placeholder greeting or signature.";
+    let message = if email.text.is_empty() {
+        format!("Subject: {}", email.subject)
+    } else {
+        format!("Subject: {}\n\n{}", email.subject, email.text)
+    };
+
+    let response = channels::run_channel_agent(
+        app_handle,
+        CHANNEL,
+        ctx,
+        &message,
+        &email.from_address,
+        agent_id,
+        config.allow_dangerous_tools,
+    )
+    .await;
+
+    let reply = match response {
+        Ok(reply) if !reply.is_empty() => reply,
+        Ok(_) => return false,
+        Err(e) => {
+            error!("[email] Agent error for {}: {}", email.from_address, e);
+            format!("Error: {}", e)
+        }
+    };
+    match send_reply(account, &email, &reply).await {
+        Ok(()) => true,
+        Err(e) => {
+            error!("[email] Send failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Reply to `to` in its thread from `account`, over the account's SMTP
+/// server (see engine::mail_client).
+async fn send_reply(account: &ImapAccount, to: &InboundEmail, text: &str) -> EngineResult<()> {
+    // Always the From address: a Reply-To could route the agent's answer
+    // to someone who isn't on the allowlist
+    let mut email = OutgoingEmail {
+        to: to.from_address.clone(),
+        subject: reply_subject(&to.subject),
+        text: text.to_string(),
+        ..Default::default()
+    };
+    email.thread_on(&to.message_id, &to.references);
+    mail_client::send(Some(&account.name), None, email, None).await?;
+    info!("[email] Replied to {}", to.from_address);
+    Ok(())
+}
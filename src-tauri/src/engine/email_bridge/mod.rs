@@ -0,0 +1,32 @@
+// Paw Agent Engine — Email Bridge (IMAP IDLE + SMTP)
+//
+// Turns a mail account into a channel: the bridge keeps one folder of the
+// account open in IMAP IDLE, runs each new message from an allowed sender
+// through the agent (one session per sender address) and answers it as a
+// threaded reply, sent like the Mail view's replies (engine::mail_client).
+//
+// The account is one of the himalaya accounts set up in Mail; login
+// (password or XOAUTH2) and the IMAP client are mail_watch's. Only mail
+// that arrives after the bridge first connects is answered. Automated mail
+// (Auto-Submitted, Precedence bulk/list, List-Id, no-reply senders), the
+// account's own messages and mail the receiving server marked as failing
+// DMARC are skipped, so the bridge can't loop with an auto-responder or
+// answer a forged From address.
+//
+// Module layout:
+//   config   — EmailBridgeConfig, CONFIG_KEY, load/save/approve/deny/remove
+//   parse    — InboundEmail::parse (sender, thread headers, MIME text body)
+//   messages — handle_inbound_email (access control, agent, SMTP reply)
+//   bridge   — statics, start_bridge, stop_bridge, get_status, IDLE session
+
+pub mod bridge;
+pub mod config;
+pub(crate) mod messages;
+pub(crate) mod parse;
+
+// ── Re-exports (crate::engine::email_bridge::* API) ──────────────────
+
+pub use bridge::{get_status, start_bridge, stop_bridge};
+pub use config::{
+    approve_user, deny_user, load_config, remove_user, save_config, EmailBridgeConfig,
+};
@@ -0,0 +1,563 @@
+// Email Bridge — Inbound Message Parsing
+// InboundEmail::parse: sender, thread headers and the plain-text body of a
+// raw RFC 5322 message (multipart, base64 / quoted-printable, RFC 2047
+// headers), with the quoted thread cut off. sender_authenticated: whether
+// the receiving server vouched for the From address.
+
+use crate::engine::email::header_value;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct InboundEmail {
+    /// Sender address, lower-case — the channel user ID
+    pub from_address: String,
+    /// Sender display name (the address when there is none)
+    pub from_name: String,
+    pub subject: String,
+    pub message_id: String,
+    pub references: String,
+    /// The new text of the message, without the quoted thread or signature
+    pub text: String,
+    /// Auto-replies, bounces, list and no-reply mail
+    pub automated: bool,
+    /// The receiving server recorded a DMARC failure for the From domain
+    pub dmarc_failed: bool,
+    /// The topmost Authentication-Results header, the one the receiving
+    /// server added (those below it may come from the sender)
+    pub auth_results: String,
+}
+
+impl InboundEmail {
+    pub(crate) fn parse(raw: &str) -> Self {
+        let header = |name: &str| header_value(raw, name).unwrap_or_default();
+        let auth_results = header("Authentication-Results");
+        let (name, address) = mailbox(&decode_words(&header("From")));
+        let auto_submitted = header("Auto-Submitted").to_ascii_lowercase();
+        let precedence = header("Precedence").to_ascii_lowercase();
+        let local_part = address.split('@').next().unwrap_or("");
+        let automated = (!auto_submitted.is_empty() && auto_submitted != "no")
+            || matches!(precedence.as_str(), "bulk" | "list" | "junk")
+            || header_value(raw, "List-Id").is_some()
+            || matches!(local_part, "mailer-daemon" | "postmaster")
+            || local_part.contains("noreply")
+            || local_part.contains("no-reply");
+        InboundEmail {
+            from_name: if name.is_empty() {
+                address.clone()
+            } else {
+                name
+            },
+            from_address: address,
+            subject: decode_words(&header("Subject")),
+            message_id: header("Message-ID"),
+            references: header("References"),
+            text: strip_quoted(&body_text(raw)),
+            automated,
+            dmarc_failed: auth_results.to_ascii_lowercase().contains("dmarc=fail"),
+            auth_results,
+        }
+    }
+
+    /// Whether the server identified by `authserv_id` authenticated the From
+    /// address: its topmost Authentication-Results header records
+    /// `dmarc=pass`, or a `dkim=pass` / `spf=pass` for a domain aligned with
+    /// the From domain. A topmost header from any other authserv-id (or a
+    /// missing one) is not trusted, since the sender can write those.
+    pub(crate) fn sender_authenticated(&self, authserv_id: &str) -> bool {
+        let authserv_id = authserv_id.trim();
+        let Some(from_domain) = self.from_address.rsplit_once('@').map(|(_, d)| d) else {
+            return false;
+        };
+        let header = strip_comments(&self.auth_results).to_ascii_lowercase();
+        let mut parts = header.split(';');
+        let server = parts
+            .next()
+            .and_then(|p| p.split_whitespace().next())
+            .unwrap_or("");
+        if authserv_id.is_empty() || !server.eq_ignore_ascii_case(authserv_id) {
+            return false;
+        }
+
+        let mut passed = false;
+        for resinfo in parts {
+            let Some((method, result)) = resinfo
+                .split_whitespace()
+                .next()
+                .and_then(|t| t.split_once('='))
+            else {
+                continue;
+            };
+            let property = |name: &str| {
+                resinfo
+                    .split_whitespace()
+                    .find_map(|t| t.strip_prefix(name)?.strip_prefix('='))
+                    .map(|v| v.rsplit('@').next().unwrap_or(v).trim_matches('"'))
+            };
+            match (method, result) {
+                ("dmarc", "pass") => passed = true,
+                ("dmarc", _) => return false,
+                ("dkim", "pass") => {
+                    passed |= property("header.d")
+                        .or_else(|| property("header.i"))
+                        .is_some_and(|d| aligned(d, from_domain));
+                }
+                ("spf", "pass") => {
+                    passed |= property("smtp.mailfrom").is_some_and(|d| aligned(d, from_domain));
+                }
+                _ => {}
+            }
+        }
+        passed
+    }
+}
+
+/// Relaxed DMARC alignment without a public-suffix list: the same domain,
+/// or one a subdomain of the other (never a bare TLD).
+fn aligned(domain: &str, from_domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.');
+    domain.contains('.')
+        && (domain == from_domain
+            || from_domain.ends_with(&format!(".{}", domain))
+            || domain.ends_with(&format!(".{}", from_domain)))
+}
+
+/// A header value without its RFC 5322 `(comments)`.
+fn strip_comments(value: &str) -> String {
+    let mut depth = 0usize;
+    value
+        .chars()
+        .filter(|&c| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect()
+}
+
+/// Display name and lower-case address of a `Name <addr>` mailbox.
+pub(crate) fn mailbox(value: &str) -> (String, String) {
+    match (value.rfind('<'), value.rfind('>')) {
+        (Some(open), Some(close)) if open < close => (
+            value[..open].trim().trim_matches('"').trim().to_string(),
+            value[open + 1..close].trim().to_ascii_lowercase(),
+        ),
+        _ => (String::new(), value.trim().to_ascii_lowercase()),
+    }
+}
+
+// ── MIME body ──────────────────────────────────────────────────────────
+
+/// The plain-text body; HTML-only mail is reduced to its text.
+pub(crate) fn body_text(raw: &str) -> String {
+    find_part(raw, "text/plain")
+        .or_else(|| find_part(raw, "text/html").map(|html| html_to_text(&html)))
+        .unwrap_or_default()
+}
+
+/// The decoded content of the first inline `mime` part of `entity`.
+fn find_part(entity: &str, mime: &str) -> Option<String> {
+    let (headers, body) = split_entity(entity);
+    let content_type = header_value(headers, "Content-Type").unwrap_or_else(|| "text/plain".into());
+    let kind = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    if kind.starts_with("multipart/") {
+        let boundary = param(&content_type, "boundary")?;
+        return multipart(body, &boundary)
+            .into_iter()
+            .find_map(|part| find_part(part, mime));
+    }
+    let attachment = header_value(headers, "Content-Disposition").is_some_and(|d| {
+        d.trim_start()
+            .to_ascii_lowercase()
+            .starts_with("attachment")
+    });
+    if kind != mime || attachment {
+        return None;
+    }
+    let encoding = header_value(headers, "Content-Transfer-Encoding")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let bytes = match encoding.trim() {
+        "base64" => STANDARD
+            .decode(body.split_whitespace().collect::<String>())
+            .ok()?,
+        "quoted-printable" => quoted_printable(body, false),
+        _ => body.as_bytes().to_vec(),
+    };
+    Some(decode_charset(
+        &bytes,
+        param(&content_type, "charset").as_deref(),
+    ))
+}
+
+/// Header block and body of a message or MIME part.
+fn split_entity(entity: &str) -> (&str, &str) {
+    // A part without headers starts with the blank line
+    for blank in ["\r\n", "\n"] {
+        if let Some(body) = entity.strip_prefix(blank) {
+            return ("", body);
+        }
+    }
+    [("\r\n\r\n", 4), ("\n\n", 2)]
+        .into_iter()
+        .filter_map(|(sep, len)| entity.find(sep).map(|i| (i, i + len)))
+        .min()
+        .map(|(end, start)| (&entity[..end], &entity[start..]))
+        .unwrap_or((entity, ""))
+}
+
+/// The parts of a multipart body.
+fn multipart<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{}", boundary);
+    body.split(delimiter.as_str())
+        .skip(1) // preamble
+        .take_while(|part| !part.starts_with("--"))
+        .map(|part| {
+            part.strip_prefix("\r\n")
+                .or_else(|| part.strip_prefix('\n'))
+                .unwrap_or(part)
+        })
+        .collect()
+}
+
+/// A parameter of a structured header, e.g. `charset` of Content-Type.
+fn param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (key, val) = p.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| val.trim().trim_matches('"').to_string())
+    })
+}
+
+fn quoted_printable(text: &str, q_encoding: bool) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' => {
+                let rest = &bytes[i + 1..];
+                // Soft line break
+                if rest.starts_with(b"\r\n") {
+                    i += 3;
+                } else if rest.starts_with(b"\n") {
+                    i += 2;
+                } else if let Some(b) = rest
+                    .get(..2)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                {
+                    out.push(b);
+                    i += 3;
+                } else {
+                    out.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if q_encoding => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Text of `bytes` in `charset`; anything but Latin-1 is read as UTF-8.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let charset = charset.unwrap_or("utf-8").to_ascii_lowercase();
+    if charset.starts_with("iso-8859-1")
+        || charset.starts_with("latin1")
+        || charset.starts_with("windows-1252")
+    {
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Decode the RFC 2047 encoded words (`=?UTF-8?B?...?=`) of a header.
+pub(crate) fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        match encoded_word(word) {
+            Some((decoded, len)) => {
+                // Whitespace between two encoded words isn't part of the text
+                if !(after_word && before.trim().is_empty()) {
+                    out.push_str(before);
+                }
+                out.push_str(&decoded);
+                rest = &word[len..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &word[2..];
+                after_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The encoded word at the start of `s`, decoded, and its length.
+fn encoded_word(s: &str) -> Option<(String, usize)> {
+    let mut fields = s.strip_prefix("=?")?.splitn(3, '?');
+    let charset = fields.next()?;
+    let encoding = fields.next()?;
+    let rest = fields.next()?;
+    let end = rest.find("?=")?;
+    let text = &rest[..end];
+    let bytes = match encoding {
+        "B" | "b" => STANDARD.decode(text).ok()?,
+        "Q" | "q" => quoted_printable(text, true),
+        _ => return None,
+    };
+    let len = 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_charset(&bytes, Some(charset)), len))
+}
+
+// ── Text cleanup ───────────────────────────────────────────────────────
+
+/// Cut the quoted thread and signature under the new text. Mail that is
+/// all quote (inline replies) is kept whole.
+fn strip_quoted(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut end = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        let attribution = trimmed.ends_with("wrote:")
+            && lines[i + 1..]
+                .iter()
+                .find(|l| !l.trim().is_empty())
+                .is_some_and(|l| l.trim_start().starts_with('>'));
+        if *line == "-- "
+            || trimmed == "-----Original Message-----"
+            || trimmed.starts_with('>')
+            || attribution
+        {
+            end = i;
+            // "On <date>, <name>" wrapped before "wrote:"
+            if attribution && i > 0 && lines[i - 1].trim_start().starts_with("On ") {
+                end = i - 1;
+            }
+            break;
+        }
+    }
+    let kept = lines[..end].join("\n").trim().to_string();
+    if kept.is_empty() {
+        text.trim().to_string()
+    } else {
+        kept
+    }
+}
+
+/// Tags out, line breaks in — enough to read HTML-only mail.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[open + 1..open + close].trim().to_ascii_lowercase();
+        rest = &rest[open + close + 1..];
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        match name {
+            "head" | "style" | "script" if !closing => {
+                // ASCII lower-casing keeps byte offsets
+                let end = format!("</{}", name);
+                rest = match rest.to_ascii_lowercase().find(&end) {
+                    Some(i) => &rest[i..],
+                    None => "",
+                };
+            }
+            "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                text.push('\n')
+            }
+            _ => {}
+        }
+    }
+    text.push_str(rest);
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push('\n');
+            blank = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_reply_is_parsed() {
+        let raw = "From: =?UTF-8?B?SsO8cmdlbg==?= <Juergen@Example.COM>\r\n\
+Subject: =?UTF-8?Q?Caf=C3=A9?= =?UTF-8?Q?_plans?=\r\n\
+Message-ID: <m2@example.com>\r\n\
+References: <m0@paw> <m1@paw>\r\n\
+Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+\r\n\
+preamble\r\n\
+--b1\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Can we move it to Caf=C3=A9 Nero? The long line is wrapped=\r\n\
+\x20softly.\r\n\
+\r\n\
+On Tue, 13 Oct 2026 at 09:00, Paw <paw@example.com>\r\n\
+wrote:\r\n\
+> Lunch at noon?\r\n\
+--b1\r\n\
+Content-Type: text/html; charset=utf-8\r\n\
+\r\n\
+<p>ignored</p>\r\n\
+--b1--\r\n";
+        let email = InboundEmail::parse(raw);
+        assert_eq!(email.from_address, "juergen@example.com");
+        assert_eq!(email.from_name, "Jürgen");
+        assert_eq!(email.subject, "Café plans");
+        assert_eq!(email.message_id, "<m2@example.com>");
+        assert_eq!(email.references, "<m0@paw> <m1@paw>");
+        assert_eq!(
+            email.text,
+            "Can we move it to Café Nero? The long line is wrapped softly."
+        );
+        assert!(!email.automated);
+        assert!(!email.dmarc_failed);
+    }
+
+    #[test]
+    fn html_only_and_automated_mail() {
+        let raw = "From: no-reply@shop.example\n\
+Auto-Submitted: auto-generated\n\
+Authentication-Results: mx.example.com; dmarc=fail (p=reject) header.from=shop.example\n\
+Subject: Your order\n\
+Content-Type: text/html; charset=\"iso-8859-1\"\n\
+Content-Transfer-Encoding: base64\n\
+\n\
+PGh0bWw+PGhlYWQ+PHN0eWxlPnB7fTwvc3R5bGU+PC9oZWFkPjxib2R5PjxwPk9yZGVyICZhbXA7IHBhaWQ8L3A+PHA+VGhhbmtzPGJyPuk8L3A+PC9ib2R5PjwvaHRtbD4=\n";
+        let email = InboundEmail::parse(raw);
+        assert_eq!(email.from_address, "no-reply@shop.example");
+        assert_eq!(email.from_name, "no-reply@shop.example");
+        assert_eq!(email.text, "Order & paid\n\nThanks\né");
+        assert!(email.automated);
+        assert!(email.dmarc_failed);
+    }
+
+    fn from_with_auth(auth_results: &[&str]) -> InboundEmail {
+        let mut raw = String::new();
+        for header in auth_results {
+            raw.push_str(&format!("Authentication-Results: {}\n", header));
+        }
+        raw.push_str("From: Ann <ann@mail.example.org>\nSubject: hi\n\nHello\n");
+        InboundEmail::parse(&raw)
+    }
+
+    #[test]
+    fn sender_authenticated_by_the_receiving_server() {
+        let dmarc = from_with_auth(&[
+            "mx.example.com; dmarc=pass (p=none dis=none) header.from=mail.example.org",
+        ]);
+        assert!(dmarc.sender_authenticated("mx.example.com"));
+        assert!(dmarc.sender_authenticated("MX.example.com"));
+        // Not the configured server, or none configured
+        assert!(!dmarc.sender_authenticated("mx.other.net"));
+        assert!(!dmarc.sender_authenticated(""));
+
+        // Aligned DKIM (organizational domain) or SPF pass
+        let dkim = from_with_auth(&[
+            "mx.example.com 1; dkim=pass header.i=@example.org header.s=s1; spf=softfail",
+        ]);
+        assert!(dkim.sender_authenticated("mx.example.com"));
+        let spf =
+            from_with_auth(&["mx.example.com; spf=pass smtp.mailfrom=bounce@mail.example.org"]);
+        assert!(spf.sender_authenticated("mx.example.com"));
+    }
+
+    #[test]
+    fn forged_senders_are_not_authenticated() {
+        // No Authentication-Results at all
+        assert!(!from_with_auth(&[]).sender_authenticated("mx.example.com"));
+        // A sender-supplied header below the server's own
+        let forged = from_with_auth(&[
+            "mx.example.com; spf=none smtp.mailfrom=evil.example; dkim=none",
+            "mx.example.com; dmarc=pass header.from=mail.example.org",
+        ]);
+        assert!(!forged.sender_authenticated("mx.example.com"));
+        // Passes for a domain that isn't the From domain
+        let unaligned = from_with_auth(&[
+            "mx.example.com; dkim=pass header.d=evil.example; spf=pass smtp.mailfrom=x@org",
+        ]);
+        assert!(!unaligned.sender_authenticated("mx.example.com"));
+        // DMARC failure outweighs a DKIM pass
+        let failed = from_with_auth(&[
+            "mx.example.com; dkim=pass header.d=example.org; dmarc=fail header.from=mail.example.org",
+        ]);
+        assert!(!failed.sender_authenticated("mx.example.com"));
+        assert!(failed.dmarc_failed);
+    }
+
+    #[test]
+    fn quotes_and_signatures_are_cut() {
+        assert_eq!(strip_quoted("Yes.\n\n-- \nAlex\nCEO"), "Yes.");
+        assert_eq!(
+            strip_quoted("Sure\n-----Original Message-----\nFrom: x"),
+            "Sure"
+        );
+        // An inline reply starting with a quote stays whole
+        assert_eq!(strip_quoted("> question?\nanswer"), "> question?\nanswer");
+        assert_eq!(decode_words("plain =?bogus"), "plain =?bogus");
+    }
+}
@@ -4,6 +4,8 @@
 // fires matching tasks. Integrated into:
 //   - Webhook server (on inbound request)
 //   - Agent messaging (on message delivery)
+//   - Twitch bridge (on channel point redemption)
+//   - Mail watcher (on new mail in a watched IMAP folder)
 //   - Cron heartbeat (periodic event check)
 //
 // Event trigger JSON format:
@@ -11,7 +13,12 @@
 //   {"type": "webhook", "path": "/deploy"}        — specific webhook path
 //   {"type": "agent_message", "channel": "alerts"} — message on alerts channel
 //   {"type": "agent_message", "from": "monitor"}   — message from specific agent
+//   {"type": "twitch_redemption", "reward": "Hydrate"} — channel point reward
+//     (optional "channel": broadcaster login)
+//   {"type": "new_mail", "account": "work", "folder": "INBOX"} — new mail
+//     (both filters optional)
 
+use crate::engine::sessions::DeferredWrite;
 use crate::engine::state::EngineState;
 use log::{info, warn};
 use std::collections::HashMap;
@@ -42,6 +49,21 @@ pub enum EngineEvent {
         channel: String,
         content: String,
     },
+    /// A viewer redeemed a channel point reward on Twitch.
+    TwitchRedemption {
+        channel: String,
+        reward: String,
+        user: String,
+        input: String,
+    },
+    /// New messages arrived in a watched mail folder.
+    NewMail {
+        account: String,
+        folder: String,
+        count: u32,
+        /// "From: Subject" of the newest message
+        latest: String,
+    },
 }
 
 /// Check all event-triggered tasks and execute those that match the given event.
@@ -77,6 +99,14 @@ pub async fn dispatch_event(app_handle: &tauri::AppHandle, event: &EngineEvent)
         };
 
         if matches_event(&trigger, event) {
+            if let Some(agent_id) = crate::engine::tasks::trigger_blocked_by(&state, task) {
+                info!(
+                    "[events] Skipping task '{}' — agent '{}' autonomy level disallows triggers",
+                    task.id, agent_id
+                );
+                continue;
+            }
+
             // §Security: enforce cooldown to prevent event amplification attacks.
             // An agent spamming agent_send_message cannot re-trigger the same
             // task faster than EVENT_TRIGGER_COOLDOWN_SECS.
@@ -116,17 +146,29 @@ pub async fn dispatch_event(app_handle: &tauri::AppHandle, event: &EngineEvent)
                     channel,
                     ..
                 } => format!("agent_message:{}#{}", from_agent, channel),
+                EngineEvent::TwitchRedemption {
+                    channel,
+                    reward,
+                    user,
+                    ..
+                } => format!("twitch_redemption:{}#{} by {}", reward, channel, user),
+                EngineEvent::NewMail {
+                    account,
+                    folder,
+                    count,
+                    latest,
+                } => format!(
+                    "new_mail:{}/{} ({} new, latest {})",
+                    account, folder, count, latest
+                ),
             };
-            state
-                .store
-                .add_task_activity(
-                    &aid,
-                    &task.id,
-                    "event_triggered",
-                    None,
-                    &format!("Event-triggered: {}", event_desc),
-                )
-                .ok();
+            state.store.defer_write(DeferredWrite::TaskActivity {
+                id: aid,
+                task_id: task.id.clone(),
+                kind: "event_triggered".into(),
+                agent: None,
+                content: format!("Event-triggered: {}", event_desc),
+            });
 
             let task_id = task.id.clone();
             let app = app_handle.clone();
@@ -191,6 +233,38 @@ fn matches_event(trigger: &serde_json::Value, event: &EngineEvent) -> bool {
             };
             channel_match && from_match
         }
+        (
+            "twitch_redemption",
+            EngineEvent::TwitchRedemption {
+                channel, reward, ..
+            },
+        ) => {
+            let reward_match = match trigger["reward"].as_str() {
+                Some(r) => r.trim().eq_ignore_ascii_case(reward.trim()),
+                None => true,
+            };
+            let channel_match = match trigger["channel"].as_str() {
+                Some(ch) => ch.trim_start_matches('#').eq_ignore_ascii_case(channel),
+                None => true,
+            };
+            reward_match && channel_match
+        }
+        (
+            "new_mail",
+            EngineEvent::NewMail {
+                account, folder, ..
+            },
+        ) => {
+            let account_match = match trigger["account"].as_str() {
+                Some(a) => a == account,
+                None => true,
+            };
+            let folder_match = match trigger["folder"].as_str() {
+                Some(f) => f.eq_ignore_ascii_case(folder),
+                None => true,
+            };
+            account_match && folder_match
+        }
         _ => false,
     }
 }
@@ -285,6 +359,36 @@ mod tests {
         assert!(!matches_event(&trigger, &other));
     }
 
+    #[test]
+    fn twitch_redemption_matches_reward_and_channel() {
+        let event = EngineEvent::TwitchRedemption {
+            channel: "paw_streams".into(),
+            reward: "Hydrate".into(),
+            user: "viewer1".into(),
+            input: String::new(),
+        };
+        assert!(matches_event(
+            &serde_json::json!({"type": "twitch_redemption"}),
+            &event
+        ));
+        assert!(matches_event(
+            &serde_json::json!({"type": "twitch_redemption", "reward": "hydrate", "channel": "#paw_streams"}),
+            &event
+        ));
+        assert!(!matches_event(
+            &serde_json::json!({"type": "twitch_redemption", "reward": "Song Request"}),
+            &event
+        ));
+        assert!(!matches_event(
+            &serde_json::json!({"type": "twitch_redemption", "channel": "other"}),
+            &event
+        ));
+        assert!(!matches_event(
+            &serde_json::json!({"type": "webhook"}),
+            &event
+        ));
+    }
+
     #[test]
     fn mismatched_types_dont_match() {
         let trigger = serde_json::json!({"type": "webhook"});
@@ -413,4 +517,22 @@ mod tests {
         };
         assert!(!matches_event(&trigger, &event));
     }
+
+    #[test]
+    fn new_mail_matches_account_and_folder() {
+        let event = EngineEvent::NewMail {
+            account: "work".into(),
+            folder: "INBOX".into(),
+            count: 2,
+            latest: "ana@example.com: Invoice".into(),
+        };
+        assert!(matches_event(
+            &serde_json::json!({"type": "new_mail"}),
+            &event
+        ));
+        let trigger = serde_json::json!({"type": "new_mail", "account": "work", "folder": "inbox"});
+        assert!(matches_event(&trigger, &event));
+        let other = serde_json::json!({"type": "new_mail", "account": "personal"});
+        assert!(!matches_event(&other, &event));
+    }
 }
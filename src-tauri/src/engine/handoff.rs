@@ -0,0 +1,290 @@
+// Paw Agent Engine — Agent handoff
+//
+// Switches a session's acting agent and leaves a handoff note in the
+// history. The summary is written by the worker / default model; if that
+// fails the note still records the switch, without a summary.
+//
+// `handoff_to_agent` (the agent-initiated variant) brings its own summary.
+// The switch is recorded while the current turn is still running; the
+// target agent's turn starts once that run has finished (`take_pending`,
+// polled by the chat command's completion task).
+
+pub use openpawz_core::engine::handoff::*;
+
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{resolve_provider_for_model, EngineState};
+use crate::engine::types::*;
+use log::{error, info, warn};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+use tauri::{Emitter, Manager};
+
+/// Tool handoffs whose target turn hasn't started yet, by session.
+static PENDING: LazyLock<Mutex<HashMap<String, HandoffLink>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Hand `session_id` over to `to_agent`. A no-op (no note) when that agent
+/// already owns the session.
+pub async fn switch_agent(
+    state: &EngineState,
+    session_id: &str,
+    to_agent: &str,
+) -> EngineResult<SessionHandoff> {
+    let session = state
+        .store
+        .get_session(session_id)?
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let from_agent = session.agent_id.unwrap_or_else(|| "default".to_string());
+    if from_agent == to_agent {
+        return Ok(SessionHandoff {
+            session_id: session_id.to_string(),
+            from_agent,
+            to_agent: to_agent.to_string(),
+            summary: None,
+        });
+    }
+
+    let messages = state.store.get_messages(session_id, 200)?;
+    let transcript = transcript(&messages, MAX_TRANSCRIPT_CHARS);
+    let summary = if transcript.is_empty() {
+        None
+    } else {
+        match summarize(state, &from_agent, to_agent, &transcript).await {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                warn!("[handoff] Summary for session {} failed: {}", session_id, e);
+                None
+            }
+        }
+    };
+
+    record(state, session_id, &from_agent, to_agent, summary.as_deref())?;
+
+    Ok(SessionHandoff {
+        session_id: session_id.to_string(),
+        from_agent,
+        to_agent: to_agent.to_string(),
+        summary,
+    })
+}
+
+/// Store the handoff note, give the session to `to_agent` and append the
+/// switch to the session's handoff chain, which is returned.
+fn record(
+    state: &EngineState,
+    session_id: &str,
+    from_agent: &str,
+    to_agent: &str,
+    summary: Option<&str>,
+) -> EngineResult<Vec<HandoffLink>> {
+    state.store.add_message(&StoredMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        role: "system".into(),
+        content: handoff_note(from_agent, to_agent, summary),
+        tool_calls_json: None,
+        tool_call_id: None,
+        name: Some(HANDOFF_MESSAGE_NAME.to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })?;
+    state.store.set_session_agent(session_id, to_agent)?;
+    let chain = state.store.push_handoff(
+        session_id,
+        HandoffLink {
+            from_agent: from_agent.to_string(),
+            to_agent: to_agent.to_string(),
+            summary: summary.map(str::to_string),
+            at: chrono::Utc::now().to_rfc3339(),
+        },
+    )?;
+    info!(
+        "[handoff] Session {} handed from '{}' to '{}' (summary: {})",
+        session_id,
+        from_agent,
+        to_agent,
+        summary.is_some()
+    );
+    Ok(chain)
+}
+
+/// Run `handoff_to_agent` for the calling agent: validate the target,
+/// record the switch and queue the target's turn. The returned text tells
+/// the calling agent to wrap up.
+pub fn request(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    run_id: &str,
+    agent_id: &str,
+    args: &serde_json::Value,
+) -> Result<String, String> {
+    let to_agent = args["agent_id"]
+        .as_str()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .ok_or("handoff_to_agent: missing 'agent_id'")?;
+    let summary = args["summary"]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or("handoff_to_agent: missing 'summary'")?;
+    if to_agent == agent_id {
+        return Err("handoff_to_agent: you are already that agent".into());
+    }
+    // Channel, project and swarm sessions are bound to their agent
+    if session_id.starts_with("eng-") {
+        return Err("handoff_to_agent: this session can't change agents".into());
+    }
+
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine state not available")?;
+    let known = to_agent == "default"
+        || state
+            .store
+            .list_all_agents()
+            .unwrap_or_default()
+            .iter()
+            .any(|(_, a)| a.agent_id == to_agent)
+        || state
+            .store
+            .list_agent_files(to_agent)
+            .is_ok_and(|files| !files.is_empty());
+    if !known {
+        return Err(format!(
+            "handoff_to_agent: unknown agent '{}' — use agent_list to see the available agents",
+            to_agent
+        ));
+    }
+    if PENDING.lock().contains_key(session_id) {
+        return Err("handoff_to_agent: a handoff is already pending for this turn".into());
+    }
+    let chain = state.store.get_handoff_chain(session_id)?;
+    if !handoff_allowed(&chain, chrono::Utc::now()) {
+        return Err(format!(
+            "handoff_to_agent: this conversation was handed over {} times in the last {} minutes; \
+             answer the user yourself",
+            MAX_HANDOFFS_PER_WINDOW,
+            HANDOFF_WINDOW_SECS / 60
+        ));
+    }
+
+    let chain = record(&state, session_id, agent_id, to_agent, Some(summary))?;
+    if let Some(link) = chain.last() {
+        PENDING.lock().insert(session_id.to_string(), link.clone());
+    }
+    let _ = app_handle.emit(
+        "engine-event",
+        EngineEvent::AgentHandoff {
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            from_agent: agent_id.to_string(),
+            to_agent: to_agent.to_string(),
+            summary: summary.to_string(),
+            chain: chain_agents(&chain),
+        },
+    );
+
+    Ok(format!(
+        "Handed off to agent '{}'. It takes over this conversation as soon as your turn \
+         ends. Don't call more tools — finish with one short line telling the user who \
+         is taking over.",
+        to_agent
+    ))
+}
+
+/// The handoff recorded during the session's last run, if its target turn
+/// still has to start.
+pub fn take_pending(session_id: &str) -> Option<HandoffLink> {
+    PENDING.lock().remove(session_id)
+}
+
+/// Start `link.to_agent`'s turn in `session_id`, seeded with the handoff
+/// summary. Boxed because it runs from inside `engine_chat_send`'s own
+/// completion task, which would otherwise make that future recursive.
+pub fn start_target_turn(
+    app_handle: tauri::AppHandle,
+    session_id: &str,
+    link: HandoffLink,
+) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    let request = ChatRequest {
+        session_id: Some(session_id.to_string()),
+        message: continuation_message(&link.from_agent, link.summary.as_deref().unwrap_or("")),
+        model: None,
+        system_prompt: None,
+        temperature: None,
+        provider_id: None,
+        tools_enabled: Some(true),
+        agent_id: Some(link.to_agent.clone()),
+        tool_filter: None,
+        attachments: Vec::new(),
+        thinking_level: None,
+        auto_approve_all: false,
+        user_approved_tools: Vec::new(),
+        plan_mode: false,
+        ingested: vec![],
+    };
+    Box::pin(async move {
+        let state = app_handle.state::<EngineState>();
+        if let Err(e) =
+            crate::commands::chat::engine_chat_send(app_handle.clone(), state, request).await
+        {
+            error!(
+                "[handoff] Turn for '{}' failed to start: {}",
+                link.to_agent, e
+            );
+        }
+    })
+}
+
+async fn summarize(
+    state: &EngineState,
+    from_agent: &str,
+    to_agent: &str,
+    transcript: &str,
+) -> EngineResult<String> {
+    let (provider_config, model) = {
+        let cfg = state.config.lock();
+        let model = cfg
+            .model_routing
+            .worker_model
+            .clone()
+            .or_else(|| cfg.default_model.clone())
+            .ok_or("No model configured")?;
+        let provider = resolve_provider_for_model(&model, &cfg.providers)
+            .or_else(|| cfg.providers.first().cloned())
+            .ok_or("No AI provider configured")?;
+        (provider, model)
+    };
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(HANDOFF_SYSTEM.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(handoff_prompt(from_agent, to_agent, transcript)),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+    let chunks = AnyProvider::from_config(&provider_config)
+        .chat_stream(&messages, &[], &model, Some(0.3), None)
+        .await?;
+    let summary: String = chunks
+        .iter()
+        .filter_map(|c| c.delta_text.as_deref())
+        .collect();
+    let summary = summary.trim();
+    if summary.is_empty() {
+        return Err(format!("{} returned an empty summary", model).into());
+    }
+    Ok(summary.to_string())
+}
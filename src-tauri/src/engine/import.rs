@@ -0,0 +1,190 @@
+// Paw Agent Engine — Conversation Import
+//
+// Brings history over from other assistants so switchers don't start from
+// zero. Both ChatGPT and Claude "Export data" downloads are zip archives
+// with a `conversations.json` inside; either the archive or the extracted
+// JSON file can be imported.
+//
+//   1. read      — open the zip / JSON and detect the format
+//   2. sessions  — one Paw session per conversation, original timestamps kept
+//                  (re-importing the same export skips what's already there)
+//   3. memories  — optional: each user/assistant exchange goes through the
+//                  same fact extraction as live chat; the maintenance loop's
+//                  consolidation pass later merges them into semantic memory
+//
+// Progress is reported as `import-progress` events.
+
+use crate::engine::engram;
+use crate::engine::memory;
+use crate::engine::sessions::import::{self, ImportSource, ImportedConversation};
+use crate::engine::state::EngineState;
+use crate::engine::types::{ImportOptions, ImportReport};
+use log::{info, warn};
+use std::io::Read;
+use std::path::Path;
+use tauri::Emitter;
+
+/// Largest `conversations.json` we will load into memory.
+const MAX_EXPORT_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Import every conversation in the export at `path`. `source` is
+/// `chatgpt`, `claude`, or None to detect it from the file.
+pub async fn import_conversations(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    path: &Path,
+    source: Option<&str>,
+    options: &ImportOptions,
+) -> Result<ImportReport, String> {
+    let raw = read_export(path)?;
+    let export: serde_json::Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid conversations.json: {}", e))?;
+    drop(raw);
+
+    let source = match source {
+        Some(s) => s.parse::<ImportSource>()?,
+        None => ImportSource::detect(&export).ok_or_else(|| {
+            "Could not recognise the export format — pass source 'chatgpt' or 'claude'".to_string()
+        })?,
+    };
+    let conversations = import::parse_export(source, &export).map_err(|e| e.to_string())?;
+    drop(export);
+
+    let mut report = ImportReport {
+        source: source.as_str().into(),
+        conversations_found: conversations.len(),
+        ..Default::default()
+    };
+    let (selected, filtered) = import::select_conversations(conversations, options);
+    report.skipped = filtered;
+    let total = selected.len();
+    info!(
+        "[import] {} export: {} conversation(s), importing {}",
+        source.as_str(),
+        report.conversations_found,
+        total
+    );
+
+    for (i, conversation) in selected.iter().enumerate() {
+        let _ = app_handle.emit(
+            "import-progress",
+            serde_json::json!({
+                "source": source.as_str(),
+                "done": i,
+                "total": total,
+                "title": conversation.title,
+            }),
+        );
+        match state
+            .store
+            .import_conversation(source, conversation, options)
+        {
+            Ok(Some(session_id)) => {
+                report.imported += 1;
+                report.messages_imported += conversation.messages.len();
+                if options.distill_memories {
+                    report.memories_captured +=
+                        distill(state, conversation, &session_id, options).await;
+                }
+            }
+            Ok(None) => report.skipped += 1,
+            Err(e) => {
+                warn!("[import] '{}' failed: {}", conversation.title, e);
+                report.errors.push(format!("{}: {}", conversation.title, e));
+            }
+        }
+    }
+
+    let _ = app_handle.emit(
+        "import-progress",
+        serde_json::json!({ "source": source.as_str(), "done": total, "total": total }),
+    );
+    info!(
+        "[import] Done: {} imported, {} skipped, {} memories, {} error(s)",
+        report.imported,
+        report.skipped,
+        report.memories_captured,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+/// Read `conversations.json` from an export archive or a bare JSON file.
+fn read_export(path: &Path) -> Result<String, String> {
+    let is_zip = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        let len = std::fs::metadata(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+            .len();
+        if len > MAX_EXPORT_BYTES {
+            return Err(format!("{} is too large to import", path.display()));
+        }
+        return std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e));
+    }
+
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid zip archive: {}", e))?;
+    let name = archive
+        .file_names()
+        .filter(|n| n.rsplit('/').next() == Some("conversations.json"))
+        .min_by_key(|n| n.len())
+        .map(String::from)
+        .ok_or("No conversations.json in the archive")?;
+    let entry = archive
+        .by_name(&name)
+        .map_err(|e| format!("Cannot read {}: {}", name, e))?;
+    if entry.size() > MAX_EXPORT_BYTES {
+        return Err(format!("{} is too large to import", name));
+    }
+    let mut raw = String::new();
+    entry
+        .take(MAX_EXPORT_BYTES)
+        .read_to_string(&mut raw)
+        .map_err(|e| format!("Cannot read {}: {}", name, e))?;
+    Ok(raw)
+}
+
+/// Run each user → assistant exchange through heuristic fact extraction
+/// (the LLM extractor would cost a model call per exchange). Returns how
+/// many new memories were stored.
+async fn distill(
+    state: &EngineState,
+    conversation: &ImportedConversation,
+    session_id: &str,
+    options: &ImportOptions,
+) -> usize {
+    let emb_client = state.embedding_client();
+    let mut captured = 0;
+    for pair in conversation.messages.windows(2) {
+        if pair[0].role != "user" || pair[1].role != "assistant" {
+            continue;
+        }
+        for (content, category) in
+            memory::extract_memorable_facts_heuristic(&pair[0].content, &pair[1].content)
+        {
+            match engram::bridge::store_auto_capture(
+                &state.store,
+                &content,
+                &category,
+                emb_client.as_ref(),
+                options.agent_id.as_deref(),
+                Some(session_id),
+                None,
+                None,
+                Some(&state.hnsw_index),
+            )
+            .await
+            {
+                Ok(Some(_)) => captured += 1,
+                Ok(None) => {}
+                Err(e) => warn!("[import] Memory capture failed: {}", e),
+            }
+        }
+    }
+    captured
+}
@@ -0,0 +1,530 @@
+// Paw Agent Engine — File-drop ingestion
+//
+// One backend path for files the user drags into Paw. `ingest_file` looks
+// at the file type and routes it:
+//
+//   - document (text, Markdown, HTML, Word, code) → chunked into memory
+//   - PDF   → sent to the model as a document block (no text extraction)
+//   - image → sent to the model as a vision block
+//   - CSV / TSV → copied into the agent workspace for read_file, exec and
+//     the Google Sheets tools, with a header + row preview
+//   - audio → transcribed; the transcript is saved and indexed like a document
+//
+// The result carries a handle. Passing it in `ChatRequest::ingested` brings
+// the file into that turn: `resolve` turns each handle into an attachment
+// and/or a short note appended to the user message. Handles live in memory
+// only (`EngineState::ingested`) and the oldest are dropped past a limit.
+
+use crate::engine::state::EngineState;
+use crate::engine::types::{ChatAttachment, IngestIntent, IngestKind, IngestResult};
+use crate::engine::{engram, transcribe, youtube};
+use base64::Engine as _;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Largest file accepted at all (long recordings are the big ones).
+const MAX_FILE_BYTES: u64 = 500 * 1024 * 1024;
+/// Largest image / PDF sent to the model inline.
+const MAX_ATTACH_BYTES: u64 = 20 * 1024 * 1024;
+/// Documents and transcripts up to this size are also inlined in the turn.
+const MAX_INLINE_BYTES: usize = 24_000;
+/// Memory chunk size for indexed documents.
+const CHUNK_BYTES: usize = 4_000;
+/// Indexing stops after this many chunks (~800 KB of text).
+const MAX_CHUNKS: usize = 200;
+/// Rows shown in a sheet preview.
+const PREVIEW_ROWS: usize = 5;
+/// Handles kept in memory before the oldest are dropped.
+const MAX_HANDLES: usize = 32;
+
+/// An ingested file, ready to be brought into a chat turn.
+#[derive(Debug, Clone)]
+pub struct IngestedFile {
+    pub result: IngestResult,
+    /// Sent with the turn (image, PDF, short document text).
+    attachment: Option<ChatAttachment>,
+    /// Appended to the user message: where the file is and how to use it.
+    note: String,
+    added: Instant,
+}
+
+/// Ingest `path` and register a handle for it.
+pub async fn ingest_file(
+    state: &EngineState,
+    path: &Path,
+    intent: IngestIntent,
+    agent_id: &str,
+) -> Result<IngestResult, String> {
+    let meta =
+        std::fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if !meta.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    if meta.len() > MAX_FILE_BYTES {
+        return Err(format!("{} is too large to ingest", path.display()));
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".into());
+    let (kind, mime_type) = classify(path).ok_or_else(|| {
+        format!(
+            "Unsupported file type: {} — drop a document, image, CSV or audio file",
+            name
+        )
+    })?;
+    info!(
+        "[ingest] {} → {:?} ({}, {} bytes, intent {:?})",
+        name,
+        kind,
+        mime_type,
+        meta.len(),
+        intent
+    );
+
+    let mut result = IngestResult {
+        handle: uuid::Uuid::new_v4().to_string(),
+        kind,
+        name: name.clone(),
+        mime_type: mime_type.to_string(),
+        size_bytes: meta.len(),
+        summary: String::new(),
+        workspace_path: None,
+        memory_ids: vec![],
+    };
+    let (attachment, note) = match kind {
+        IngestKind::Image => attach_file(path, &mut result)?,
+        IngestKind::Document if !is_text(mime_type) => attach_file(path, &mut result)?,
+        IngestKind::Document => {
+            let text = extract_text(path, mime_type)?;
+            if text.trim().is_empty() {
+                return Err(format!("{} has no readable text", name));
+            }
+            ingest_text(state, &mut result, &text, intent, agent_id).await?
+        }
+        IngestKind::Sheet => {
+            let workspace = crate::engine::tools::ensure_workspace(agent_id)?;
+            let dest = copy_to_uploads(path, &workspace)?;
+            let text = std::fs::read_to_string(&dest)
+                .map_err(|e| format!("{} is not a UTF-8 text file: {}", name, e))?;
+            let delimiter = if mime_type == "text/tab-separated-values" {
+                '\t'
+            } else {
+                ','
+            };
+            let preview = SheetPreview::parse(&text, delimiter);
+            result.summary = format!(
+                "Sheet — {} rows × {} columns, copied to the workspace",
+                preview.rows,
+                preview.columns.len()
+            );
+            let note = format!(
+                "[Dropped sheet: {} saved at {}]\nColumns: {}\nRows: {}\nFirst rows:\n```\n{}\n```\n\
+                 Read or analyze it with read_file / exec, or push rows to a spreadsheet with google_sheets_append.",
+                name,
+                dest.display(),
+                preview.columns.join(", "),
+                preview.rows,
+                preview.head
+            );
+            result.workspace_path = Some(dest.to_string_lossy().into_owned());
+            (None, note)
+        }
+        IngestKind::Audio => {
+            let backend = transcribe::pick_backend(state)?;
+            let segments = transcribe::transcribe_file(&backend, path, None, |done, total| {
+                if total > 1 {
+                    info!("[ingest] Transcribing segment {}/{}", done + 1, total);
+                }
+            })
+            .await?;
+            if segments.is_empty() {
+                return Err("No speech was detected in the recording.".into());
+            }
+            let transcript = youtube::format_transcript(&segments);
+            let workspace = crate::engine::tools::ensure_workspace(agent_id)?;
+            let dir = workspace.join("transcripts");
+            std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "recording".into());
+            let dest = dir.join(format!("{}.md", stem));
+            std::fs::write(
+                &dest,
+                format!(
+                    "# {}\n\nTranscribed with {}\n\n{}\n",
+                    name,
+                    backend.label(),
+                    transcript
+                ),
+            )
+            .map_err(|e| e.to_string())?;
+            result.workspace_path = Some(dest.to_string_lossy().into_owned());
+            ingest_text(state, &mut result, &transcript, intent, agent_id).await?
+        }
+    };
+
+    register(
+        state,
+        IngestedFile {
+            result: result.clone(),
+            attachment,
+            note,
+            added: Instant::now(),
+        },
+    );
+    Ok(result)
+}
+
+/// Images and PDFs go to the model as-is.
+fn attach_file(
+    path: &Path,
+    result: &mut IngestResult,
+) -> Result<(Option<ChatAttachment>, String), String> {
+    if result.size_bytes > MAX_ATTACH_BYTES {
+        return Err(format!(
+            "{} is too large to send to the model (max {} MB)",
+            result.name,
+            MAX_ATTACH_BYTES / 1024 / 1024
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    result.summary = if result.kind == IngestKind::Image {
+        "Image — sent to the model".into()
+    } else {
+        "PDF — sent to the model as a document (not indexed)".into()
+    };
+    let attachment = ChatAttachment {
+        mime_type: result.mime_type.clone(),
+        content: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        name: Some(result.name.clone()),
+    };
+    Ok((Some(attachment), String::new()))
+}
+
+/// Index text into memory (unless the intent is attach-only) and decide how
+/// it reaches the turn: inline when short, otherwise through memory or a
+/// workspace copy.
+async fn ingest_text(
+    state: &EngineState,
+    result: &mut IngestResult,
+    text: &str,
+    intent: IngestIntent,
+    agent_id: &str,
+) -> Result<(Option<ChatAttachment>, String), String> {
+    let label = if result.kind == IngestKind::Audio {
+        "Transcript"
+    } else {
+        "Document"
+    };
+    let mut parts = youtube::split_parts(text, CHUNK_BYTES);
+    if intent != IngestIntent::Attach {
+        if parts.len() > MAX_CHUNKS {
+            warn!(
+                "[ingest] {} has {} parts — indexing the first {}",
+                result.name,
+                parts.len(),
+                MAX_CHUNKS
+            );
+            parts.truncate(MAX_CHUNKS);
+        }
+        let emb_client = state.embedding_client();
+        let total = parts.len();
+        for (i, part) in parts.iter().enumerate() {
+            let content = format!(
+                "[{}: {} — part {}/{}]\n{}",
+                label,
+                result.name,
+                i + 1,
+                total,
+                part
+            );
+            match engram::bridge::store(
+                &state.store,
+                &content,
+                "document",
+                0.6,
+                emb_client.as_ref(),
+                Some(agent_id),
+                None,
+                Some(&state.hnsw_index),
+            )
+            .await
+            {
+                Ok(Some(id)) => result.memory_ids.push(id),
+                Ok(None) => {} // duplicate of an existing memory
+                Err(e) => warn!("[ingest] Failed to index part {}: {}", i + 1, e),
+            }
+        }
+    }
+
+    let indexed = if result.memory_ids.is_empty() {
+        String::new()
+    } else {
+        format!(", indexed into memory as {} parts", result.memory_ids.len())
+    };
+    result.summary = format!(
+        "{} — {} words{}",
+        label,
+        text.split_whitespace().count(),
+        indexed
+    );
+
+    if text.len() <= MAX_INLINE_BYTES {
+        let attachment = ChatAttachment {
+            mime_type: "text/plain".into(),
+            content: base64::engine::general_purpose::STANDARD.encode(text),
+            name: Some(result.name.clone()),
+        };
+        return Ok((Some(attachment), String::new()));
+    }
+
+    // Too long to inline: point the agent at the saved copy and/or memory
+    let saved = match &result.workspace_path {
+        Some(p) => p.clone(),
+        None => {
+            let workspace = crate::engine::tools::ensure_workspace(agent_id)?;
+            let dir = workspace.join("uploads");
+            std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let dest = dir.join(format!("{}.txt", result.name));
+            std::fs::write(&dest, text).map_err(|e| e.to_string())?;
+            let p = dest.to_string_lossy().into_owned();
+            result.workspace_path = Some(p.clone());
+            p
+        }
+    };
+    let recall = if result.memory_ids.is_empty() {
+        ""
+    } else {
+        " or search memory_search for the relevant parts"
+    };
+    let note = format!(
+        "[Dropped file: {} — {}]\nFull text: {} (read it with read_file{})",
+        result.name, result.summary, saved, recall
+    );
+    Ok((None, note))
+}
+
+/// Attachments and message notes for the given handles. Unknown handles are
+/// skipped with a note so the model doesn't pretend to have seen the file.
+pub fn resolve(state: &EngineState, handles: &[String]) -> (Vec<ChatAttachment>, Vec<String>) {
+    let files = state.ingested.lock();
+    let mut attachments = Vec::new();
+    let mut notes = Vec::new();
+    for handle in handles {
+        match files.get(handle) {
+            Some(file) => {
+                attachments.extend(file.attachment.clone());
+                if !file.note.is_empty() {
+                    notes.push(file.note.clone());
+                }
+            }
+            None => notes.push(
+                "[A dropped file is no longer available — ask the user to drop it again]".into(),
+            ),
+        }
+    }
+    (attachments, notes)
+}
+
+fn register(state: &EngineState, file: IngestedFile) {
+    let mut files = state.ingested.lock();
+    while files.len() >= MAX_HANDLES {
+        let Some(oldest) = files
+            .iter()
+            .min_by_key(|(_, f)| f.added)
+            .map(|(h, _)| h.clone())
+        else {
+            break;
+        };
+        files.remove(&oldest);
+    }
+    files.insert(file.result.handle.clone(), file);
+}
+
+/// Route by extension: (kind, MIME type). None for unsupported types.
+/// MIME type by extension, for files sent out of the workspace.
+pub fn mime_type(path: &Path) -> &'static str {
+    classify(path).map_or("application/octet-stream", |(_, mime)| mime)
+}
+
+fn classify(path: &Path) -> Option<(IngestKind, &'static str)> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let routed = match ext.as_str() {
+        "png" => (IngestKind::Image, "image/png"),
+        "jpg" | "jpeg" => (IngestKind::Image, "image/jpeg"),
+        "gif" => (IngestKind::Image, "image/gif"),
+        "webp" => (IngestKind::Image, "image/webp"),
+        "pdf" => (IngestKind::Document, "application/pdf"),
+        "docx" => (
+            IngestKind::Document,
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        ),
+        "html" | "htm" => (IngestKind::Document, "text/html"),
+        "md" | "markdown" => (IngestKind::Document, "text/markdown"),
+        "csv" => (IngestKind::Sheet, "text/csv"),
+        "tsv" => (IngestKind::Sheet, "text/tab-separated-values"),
+        "mp3" => (IngestKind::Audio, "audio/mpeg"),
+        "wav" => (IngestKind::Audio, "audio/wav"),
+        "m4a" | "aac" => (IngestKind::Audio, "audio/mp4"),
+        "ogg" | "opus" => (IngestKind::Audio, "audio/ogg"),
+        "flac" => (IngestKind::Audio, "audio/flac"),
+        "webm" => (IngestKind::Audio, "audio/webm"),
+        _ if looks_like_text(path) => (IngestKind::Document, "text/plain"),
+        _ => return None,
+    };
+    Some(routed)
+}
+
+fn is_text(mime_type: &str) -> bool {
+    mime_type.starts_with("text/") || mime_type.ends_with("wordprocessingml.document")
+}
+
+/// Plain text, code, logs, JSON… — UTF-8 without NUL bytes in the first 8 KB.
+fn looks_like_text(path: &Path) -> bool {
+    use std::io::Read;
+    let mut buf = [0u8; 8192];
+    let Ok(n) = std::fs::File::open(path).and_then(|mut f| f.read(&mut buf)) else {
+        return false;
+    };
+    let head = &buf[..n];
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // A multi-byte character may straddle the 8 KB boundary
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+fn extract_text(path: &Path, mime_type: &str) -> Result<String, String> {
+    match mime_type {
+        "text/html" => {
+            let html = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let document = scraper::Html::parse_document(&html);
+            Ok(crate::engine::web::extract_readable_text(&document))
+        }
+        m if m.ends_with("wordprocessingml.document") => {
+            use std::io::Read;
+            let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+            let mut archive =
+                zip::ZipArchive::new(file).map_err(|e| format!("Not a valid .docx file: {}", e))?;
+            let mut xml = String::new();
+            archive
+                .by_name("word/document.xml")
+                .map_err(|e| format!("Not a valid .docx file: {}", e))?
+                .read_to_string(&mut xml)
+                .map_err(|e| e.to_string())?;
+            Ok(docx_text(&xml))
+        }
+        _ => {
+            let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+    }
+}
+
+/// Paragraph text from a .docx `word/document.xml`.
+fn docx_text(xml: &str) -> String {
+    let xml = xml
+        .replace("</w:p>", "\n")
+        .replace("<w:tab/>", "\t")
+        .replace("<w:br/>", "\n");
+    let mut text = String::with_capacity(xml.len() / 4);
+    let mut in_tag = false;
+    for c in xml.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Copy a dropped file into the workspace `uploads/` folder.
+fn copy_to_uploads(path: &Path, workspace: &Path) -> Result<PathBuf, String> {
+    let dir = workspace.join("uploads");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dest = dir.join(path.file_name().ok_or("File has no name")?);
+    if dest != path {
+        std::fs::copy(path, &dest).map_err(|e| format!("Cannot copy into workspace: {}", e))?;
+    }
+    Ok(dest)
+}
+
+/// Header, data row count and the first rows of a CSV / TSV.
+struct SheetPreview {
+    columns: Vec<String>,
+    rows: usize,
+    head: String,
+}
+
+impl SheetPreview {
+    fn parse(text: &str, delimiter: char) -> Self {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let columns = lines
+            .next()
+            .map(|h| {
+                h.split(delimiter)
+                    .map(|c| c.trim().trim_matches('"').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let data: Vec<&str> = lines.collect();
+        let head = text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .take(PREVIEW_ROWS + 1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        SheetPreview {
+            columns,
+            rows: data.len(),
+            head,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_by_extension() {
+        let kind = |p: &str| classify(Path::new(p)).map(|(k, _)| k);
+        assert_eq!(kind("photo.JPG"), Some(IngestKind::Image));
+        assert_eq!(kind("report.pdf"), Some(IngestKind::Document));
+        assert_eq!(kind("notes.docx"), Some(IngestKind::Document));
+        assert_eq!(kind("sales.csv"), Some(IngestKind::Sheet));
+        assert_eq!(kind("memo.m4a"), Some(IngestKind::Audio));
+        // Unknown extensions are sniffed; a missing file isn't text
+        assert_eq!(kind("/nonexistent/blob.bin"), None);
+    }
+
+    #[test]
+    fn previews_sheets() {
+        let csv = "name,\"amount\"\nAda,3\n\nGrace,5\n";
+        let preview = SheetPreview::parse(csv, ',');
+        assert_eq!(preview.columns, ["name", "amount"]);
+        assert_eq!(preview.rows, 2);
+        assert_eq!(preview.head, "name,\"amount\"\nAda,3\nGrace,5");
+    }
+
+    #[test]
+    fn extracts_docx_paragraphs() {
+        let xml = r#"<w:document><w:body><w:p><w:r><w:t>Q3 &amp; Q4</w:t></w:r></w:p><w:p><w:r><w:t>Totals</w:t></w:r></w:p></w:body></w:document>"#;
+        assert_eq!(docx_text(xml), "Q3 & Q4\nTotals\n");
+    }
+}
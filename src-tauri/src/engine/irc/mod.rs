@@ -0,0 +1,860 @@
+// Paw Agent Engine — IRC Bridge
+//
+// Connects Paw to any IRC server via outbound TCP/TLS.
+// The simplest chat protocol — text-based, no special API.
+//
+// Setup: Pick a server (e.g. irc.libera.chat), set a nick, join channels.
+// Extra networks (e.g. OFTC alongside Libera) go in `networks`; each runs
+// its own connection and shares the access policy and agent.
+//
+// Auth, per network:
+//   - SASL PLAIN (account + password) or EXTERNAL (TLS client cert / CertFP),
+//     negotiated before registration (see `sasl`)
+//   - NickServ IDENTIFY after registration when SASL isn't used or failed;
+//     a taken nick is reclaimed with REGAIN once identified
+//   - Channel keys: write `#channel key` in `channels_to_join`
+//
+// Security:
+//   - Allowlist by IRC nick (`nick@network` on extra networks)
+//   - Optional pairing mode
+//   - TLS encryption to server (enabled by default, port 6697)
+
+mod sasl;
+
+pub use sasl::SaslMechanism;
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::channels::{self, ChannelStatus, PendingUser};
+use log::{debug, error, info, warn};
+use sasl::SaslNegotiation;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, WriteHalf};
+use tokio::net::TcpStream;
+
+// ── IRC Config ─────────────────────────────────────────────────────────
+
+/// The top-level connection fields are the primary network; `networks`
+/// adds more. Access policy, agent and channel behaviour are shared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrcConfig {
+    pub server: String,
+    pub port: u16,
+    pub tls: bool,
+    pub nick: String,
+    /// Server password (PASS)
+    pub password: Option<String>,
+    /// Channels to join (e.g. ["#paw", "#private key"])
+    pub channels_to_join: Vec<String>,
+    #[serde(default)]
+    pub sasl_mechanism: SaslMechanism,
+    /// SASL account name (defaults to the nick)
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+    #[serde(default)]
+    pub sasl_password: Option<String>,
+    /// PEM file with the client certificate and its key, for SASL EXTERNAL
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    /// Sent as NickServ IDENTIFY when SASL isn't used or failed
+    #[serde(default)]
+    pub nickserv_password: Option<String>,
+    /// Additional networks connected at the same time
+    #[serde(default)]
+    pub networks: Vec<IrcNetwork>,
+    pub enabled: bool,
+    /// "open" | "allowlist" | "pairing"
+    pub dm_policy: String,
+    /// IRC nicks allowed to talk privately (`nick@network` for extra networks)
+    pub allowed_users: Vec<String>,
+    #[serde(default)]
+    pub pending_users: Vec<PendingUser>,
+    pub agent_id: Option<String>,
+    /// Whether to respond to messages in channels (not just DMs)
+    #[serde(default)]
+    pub respond_in_channels: bool,
+    /// Phase C: allow dangerous/side-effect tools for messages from this channel
+    #[serde(default)]
+    pub allow_dangerous_tools: bool,
+}
+
+/// One IRC network connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrcNetwork {
+    /// Short label for logs and user ids (e.g. "oftc")
+    pub name: String,
+    pub server: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_true")]
+    pub tls: bool,
+    pub nick: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub channels_to_join: Vec<String>,
+    #[serde(default)]
+    pub sasl_mechanism: SaslMechanism,
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+    #[serde(default)]
+    pub sasl_password: Option<String>,
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    #[serde(default)]
+    pub nickserv_password: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_port() -> u16 {
+    6697
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for IrcConfig {
+    fn default() -> Self {
+        IrcConfig {
+            server: "irc.libera.chat".into(),
+            port: 6697,
+            tls: true,
+            nick: "paw-bot".into(),
+            password: None,
+            channels_to_join: vec![],
+            sasl_mechanism: SaslMechanism::None,
+            sasl_username: None,
+            sasl_password: None,
+            client_cert_path: None,
+            nickserv_password: None,
+            networks: vec![],
+            enabled: false,
+            dm_policy: "pairing".into(),
+            allowed_users: vec![],
+            pending_users: vec![],
+            agent_id: None,
+            respond_in_channels: false,
+            allow_dangerous_tools: false,
+        }
+    }
+}
+
+impl IrcConfig {
+    /// The primary network (from the top-level fields).
+    fn primary_network(&self) -> IrcNetwork {
+        IrcNetwork {
+            name: self.server.clone(),
+            server: self.server.clone(),
+            port: self.port,
+            tls: self.tls,
+            nick: self.nick.clone(),
+            password: self.password.clone(),
+            channels_to_join: self.channels_to_join.clone(),
+            sasl_mechanism: self.sasl_mechanism,
+            sasl_username: self.sasl_username.clone(),
+            sasl_password: self.sasl_password.clone(),
+            client_cert_path: self.client_cert_path.clone(),
+            nickserv_password: self.nickserv_password.clone(),
+            enabled: true,
+        }
+    }
+
+    /// Every network to connect: the primary one first, then the enabled extras.
+    fn all_networks(&self) -> Vec<IrcNetwork> {
+        std::iter::once(self.primary_network())
+            .chain(
+                self.networks
+                    .iter()
+                    .filter(|n| n.enabled && !n.server.is_empty() && !n.nick.is_empty())
+                    .cloned(),
+            )
+            .collect()
+    }
+}
+
+// ── Global State ───────────────────────────────────────────────────────
+
+static BRIDGE_RUNNING: AtomicBool = AtomicBool::new(false);
+static MESSAGE_COUNT: AtomicI64 = AtomicI64::new(0);
+/// Network connections still running; the bridge stops when it hits zero.
+static ACTIVE_NETWORKS: AtomicUsize = AtomicUsize::new(0);
+static STOP_SIGNAL: std::sync::OnceLock<Arc<AtomicBool>> = std::sync::OnceLock::new();
+
+fn get_stop_signal() -> Arc<AtomicBool> {
+    STOP_SIGNAL
+        .get_or_init(|| Arc::new(AtomicBool::new(false)))
+        .clone()
+}
+
+const CONFIG_KEY: &str = "irc_config";
+
+/// How long to hold channel joins waiting for NickServ to confirm the login
+/// (registered-only channels refuse unidentified users).
+const IDENTIFY_WAIT: Duration = Duration::from_secs(15);
+
+// ── Bridge Core ────────────────────────────────────────────────────────
+
+pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
+    if BRIDGE_RUNNING.load(Ordering::Relaxed) {
+        return Err("IRC bridge is already running".into());
+    }
+
+    let config: IrcConfig = channels::load_channel_config(&app_handle, CONFIG_KEY)?;
+    if config.server.is_empty() || config.nick.is_empty() {
+        return Err("IRC server and nick are required.".into());
+    }
+    if !config.enabled {
+        return Err("IRC bridge is disabled.".into());
+    }
+
+    let stop = get_stop_signal();
+    stop.store(false, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(true, Ordering::Relaxed);
+
+    let networks = config.all_networks();
+    ACTIVE_NETWORKS.store(networks.len(), Ordering::Relaxed);
+
+    for (i, network) in networks.into_iter().enumerate() {
+        info!(
+            "[irc] Starting bridge to {} ({}:{})",
+            network.name, network.server, network.port
+        );
+        let app_handle = app_handle.clone();
+        let config = config.clone();
+        tauri::async_runtime::spawn(async move {
+            let name = network.name.clone();
+            let error = run_irc_loop(app_handle.clone(), config, network, i == 0)
+                .await
+                .err()
+                .map(|e| {
+                    error!("[irc] {} crashed: {}", name, e);
+                    format!("{}: {}", name, e)
+                });
+            // The last network to stop takes the bridge down with it
+            if ACTIVE_NETWORKS.fetch_sub(1, Ordering::Relaxed) == 1 {
+                BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+                channels::mark_stopped(&app_handle, "irc", error.as_deref());
+                info!("[irc] Bridge stopped");
+            } else if let Some(e) = error {
+                let _ = app_handle.emit(
+                    "irc-status",
+                    json!({
+                        "kind": "network_error",
+                        "network": &name,
+                        "error": e,
+                    }),
+                );
+            }
+        });
+    }
+
+    Ok(())
+}
+
+pub fn stop_bridge() {
+    let stop = get_stop_signal();
+    stop.store(true, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+    info!("[irc] Stop signal sent");
+}
+
+pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
+    let config: IrcConfig =
+        channels::load_channel_config(app_handle, CONFIG_KEY).unwrap_or_default();
+    let servers: Vec<String> = config
+        .all_networks()
+        .iter()
+        .map(|n| format!("{}:{}", n.server, n.port))
+        .collect();
+    ChannelStatus {
+        running: BRIDGE_RUNNING.load(Ordering::Relaxed),
+        connected: BRIDGE_RUNNING.load(Ordering::Relaxed),
+        bot_name: Some(config.nick.clone()),
+        bot_id: Some(servers.join(", ")),
+        message_count: MESSAGE_COUNT.load(Ordering::Relaxed) as u64,
+        allowed_users: config.allowed_users,
+        pending_users: config.pending_users,
+        dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("irc"),
+    }
+}
+
+// ── IRC Connection Loop ────────────────────────────────────────────────
+
+/// Trait alias for TLS or plain TCP streams — both implement AsyncRead + AsyncWrite.
+trait IrcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IrcStream for T {}
+
+type IrcWriter = Arc<tokio::sync::Mutex<WriteHalf<Box<dyn IrcStream>>>>;
+
+async fn send_line(writer: &IrcWriter, line: &str) {
+    let mut w = writer.lock().await;
+    let _ = w.write_all(format!("{}\r\n", line).as_bytes()).await;
+}
+
+/// TLS client config, presenting the client certificate when one is set
+/// (SASL EXTERNAL / CertFP).
+fn tls_client_config(network: &IrcNetwork) -> EngineResult<rustls::ClientConfig> {
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let builder = rustls::ClientConfig::builder().with_root_certificates(root_store);
+
+    let Some(path) = network
+        .client_cert_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+    else {
+        return Ok(builder.with_no_client_auth());
+    };
+    let pem = std::fs::read(path).map_err(|e| format!("Open client cert {path}: {e}"))?;
+    let certs: Vec<_> = rustls_pemfile::certs(&mut pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Parse client cert: {e}"))?;
+    let key = rustls_pemfile::private_key(&mut pem.as_slice())
+        .map_err(|e| format!("Parse client key: {e}"))?
+        .ok_or_else(|| format!("No private key found in {path}"))?;
+    builder
+        .with_client_auth_cert(certs, key)
+        .map_err(|e| format!("Client cert: {e}").into())
+}
+
+/// `#channel` or `#channel key` → JOIN line.
+fn join_command(entry: &str) -> Option<String> {
+    let mut parts = entry.split_whitespace();
+    let channel = parts.next()?;
+    Some(match parts.next() {
+        Some(key) => format!("JOIN {} {}", channel, key),
+        None => format!("JOIN {}", channel),
+    })
+}
+
+/// Per-connection registration state: nick, SASL, NickServ and pending joins.
+struct Session {
+    /// The nick the server gave us (may differ from the configured one)
+    nick: String,
+    registered: bool,
+    logged_in: bool,
+    identify_sent_at: Option<Instant>,
+    joined: bool,
+}
+
+async fn run_irc_loop(
+    app_handle: tauri::AppHandle,
+    config: IrcConfig,
+    network: IrcNetwork,
+    primary: bool,
+) -> EngineResult<()> {
+    let stop = get_stop_signal();
+    let addr = format!("{}:{}", network.server, network.port);
+    let label = network.name.clone();
+
+    let tcp = TcpStream::connect(&addr)
+        .await
+        .map_err(|e| format!("TCP connect to {} failed: {}", addr, e))?;
+
+    // Wrap with TLS if enabled
+    let stream: Box<dyn IrcStream> = if network.tls {
+        info!("[irc] Upgrading to TLS for {}", addr);
+
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_client_config(&network)?));
+
+        let server_name =
+            rustls::pki_types::ServerName::try_from(network.server.clone()).map_err(|e| {
+                EngineError::Channel {
+                    channel: "irc".into(),
+                    message: format!("Invalid server name: {}", e),
+                }
+            })?;
+
+        let tls_stream = connector
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| format!("TLS handshake with {} failed: {}", addr, e))?;
+
+        info!("[irc] TLS handshake complete for {}", addr);
+        Box::new(tls_stream)
+    } else {
+        warn!(
+            "[irc] Connecting WITHOUT TLS to {} — credentials will be sent in plaintext!",
+            addr
+        );
+        if network.sasl_mechanism == SaslMechanism::External {
+            return Err(
+                "SASL EXTERNAL needs TLS (it authenticates with the client certificate)".into(),
+            );
+        }
+        Box::new(tcp)
+    };
+
+    let (reader, writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    let write_handle: IrcWriter = Arc::new(tokio::sync::Mutex::new(writer));
+
+    // Register with the server (CAP LS first so SASL can run before NICK/USER completes)
+    let mut sasl = SaslNegotiation::new(
+        network.sasl_mechanism,
+        network
+            .sasl_username
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .unwrap_or(&network.nick),
+        network.sasl_password.as_deref().unwrap_or_default(),
+    );
+    if let Some(ref sasl) = sasl {
+        send_line(&write_handle, sasl.start()).await;
+    }
+    if let Some(ref pass) = network.password {
+        send_line(&write_handle, &format!("PASS {}", pass)).await;
+    }
+    send_line(&write_handle, &format!("NICK {}", network.nick)).await;
+    send_line(
+        &write_handle,
+        &format!("USER {} 0 * :Paw Agent", network.nick),
+    )
+    .await;
+
+    info!("[irc] Sent NICK/USER to {}", addr);
+
+    let nickserv_password = network.nickserv_password.clone().filter(|p| !p.is_empty());
+    let mut session = Session {
+        nick: network.nick.clone(),
+        registered: false,
+        logged_in: false,
+        identify_sent_at: None,
+        joined: false,
+    };
+    let mut current_config = config.clone();
+    let mut last_config_reload = std::time::Instant::now();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let line = line.trim_end().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Handle PING
+        if line.starts_with("PING") {
+            let pong = line.replace("PING", "PONG");
+            send_line(&write_handle, &pong).await;
+            continue;
+        }
+
+        // Parse IRC message
+        let parsed = parse_irc_line(&line);
+
+        // CAP / SASL negotiation (before registration completes)
+        if let Some(negotiation) = sasl.as_mut() {
+            let was_pending = negotiation.outcome().is_none();
+            for reply in
+                negotiation.handle(&parsed.command, &parsed.params, parsed.trailing.as_deref())
+            {
+                send_line(&write_handle, &reply).await;
+            }
+            match negotiation.outcome() {
+                Some(Ok(())) if was_pending => {
+                    info!("[irc] {}: SASL authentication succeeded", label);
+                    session.logged_in = true;
+                }
+                Some(Err(reason)) if was_pending => {
+                    warn!("[irc] {}: SASL failed — {}", label, reason);
+                    let _ = app_handle.emit(
+                        "irc-status",
+                        json!({
+                            "kind": "sasl_failed",
+                            "network": &label,
+                            "error": reason,
+                        }),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        match parsed.command.as_str() {
+            // RPL_LOGGEDIN — from SASL or a NickServ login
+            "900" => session.logged_in = true,
+            // ERR_NICKNAMEINUSE during registration — try an alternate nick
+            "433" if !session.registered => {
+                session.nick.push('_');
+                warn!("[irc] {}: nick taken, trying {}", label, session.nick);
+                send_line(&write_handle, &format!("NICK {}", session.nick)).await;
+            }
+            // Our own nick changed (e.g. after REGAIN)
+            "NICK" if parsed.prefix_nick().as_deref() == Some(session.nick.as_str()) => {
+                if let Some(new_nick) = parsed
+                    .trailing
+                    .clone()
+                    .or_else(|| parsed.params.first().cloned())
+                {
+                    info!("[irc] {}: now known as {}", label, new_nick);
+                    session.nick = new_nick;
+                }
+            }
+            "NOTICE"
+                if parsed
+                    .prefix_nick()
+                    .is_some_and(|n| n.eq_ignore_ascii_case("NickServ")) =>
+            {
+                let text = parsed.trailing.as_deref().unwrap_or("").to_lowercase();
+                if text.contains("you are now identified")
+                    || text.contains("you are now recognized")
+                    || text.contains("password accepted")
+                {
+                    session.logged_in = true;
+                }
+            }
+            _ => {}
+        }
+
+        // Check for registration complete (RPL_WELCOME = 001)
+        if parsed.command == "001" && !session.registered {
+            session.registered = true;
+            if let Some(nick) = parsed.params.first() {
+                session.nick = nick.clone();
+            }
+            info!("[irc] {}: registered as {}", label, session.nick);
+
+            channels::mark_connected(&app_handle, "irc");
+            let _ = app_handle.emit(
+                "irc-status",
+                json!({
+                    "kind": "connected",
+                    "nick": &session.nick,
+                    "server": &network.server,
+                    "network": &label,
+                }),
+            );
+
+            if let (false, Some(pass)) = (session.logged_in, nickserv_password.as_deref()) {
+                info!("[irc] {}: identifying with NickServ", label);
+                send_line(
+                    &write_handle,
+                    &format!("PRIVMSG NickServ :IDENTIFY {} {}", network.nick, pass),
+                )
+                .await;
+                session.identify_sent_at = Some(Instant::now());
+            }
+        }
+
+        // Reclaim the configured nick once logged in to the account
+        if session.registered
+            && session.logged_in
+            && nickserv_password.is_some()
+            && !session.nick.eq_ignore_ascii_case(&network.nick)
+            && session.identify_sent_at.is_some()
+        {
+            send_line(
+                &write_handle,
+                &format!("PRIVMSG NickServ :REGAIN {}", network.nick),
+            )
+            .await;
+            session.identify_sent_at = None;
+        }
+
+        // Join configured channels — after NickServ confirms, or give up waiting
+        if session.registered && !session.joined {
+            let waiting_for_nickserv = !session.logged_in
+                && session
+                    .identify_sent_at
+                    .is_some_and(|t| t.elapsed() < IDENTIFY_WAIT);
+            if !waiting_for_nickserv {
+                session.joined = true;
+                for entry in &network.channels_to_join {
+                    if let Some(join) = join_command(entry) {
+                        send_line(&write_handle, &join).await;
+                        info!(
+                            "[irc] {}: joining {}",
+                            label,
+                            entry.split_whitespace().next().unwrap_or(entry)
+                        );
+                    }
+                }
+            }
+        }
+
+        // Handle PRIVMSG
+        if parsed.command == "PRIVMSG" {
+            let sender_nick = parsed.prefix_nick().unwrap_or_default();
+            if sender_nick == session.nick {
+                continue;
+            } // Skip own messages
+
+            let target = parsed.params.first().map(|s| s.as_str()).unwrap_or("");
+            let text = parsed.trailing.clone().unwrap_or_default();
+            if text.is_empty() {
+                continue;
+            }
+            // Services talk to us by PRIVMSG on some networks — never route them
+            if ["nickserv", "chanserv"].contains(&sender_nick.to_lowercase().as_str()) {
+                continue;
+            }
+
+            let nick = session.nick.as_str();
+            let is_dm = target.eq_ignore_ascii_case(nick); // DM = target is our nick
+            let is_channel = target.starts_with('#') || target.starts_with('&');
+
+            // In channels, only respond if enabled or if directly addressed
+            if is_channel {
+                let addressed = text.starts_with(&format!("{}:", nick))
+                    || text.starts_with(&format!("{},", nick));
+                if !current_config.respond_in_channels && !addressed {
+                    continue;
+                }
+            }
+
+            // Strip nick prefix if addressed
+            let content = if is_channel {
+                let prefixes = [
+                    format!("{}: ", nick),
+                    format!("{}, ", nick),
+                    format!("{}:", nick),
+                    format!("{},", nick),
+                ];
+                let mut c = text.clone();
+                for p in &prefixes {
+                    if c.starts_with(p) {
+                        c = c[p.len()..].trim().to_string();
+                        break;
+                    }
+                }
+                c
+            } else {
+                text.clone()
+            };
+
+            if content.is_empty() {
+                continue;
+            }
+
+            debug!(
+                "[irc] {} {} from {}: {}",
+                label,
+                if is_dm { "DM" } else { "Channel msg" },
+                sender_nick,
+                if content.len() > 50 {
+                    format!("{}...", &content[..content.floor_char_boundary(50)])
+                } else {
+                    content.clone()
+                }
+            );
+
+            // Nicks are only unique per network
+            let user_key = if primary {
+                sender_nick.clone()
+            } else {
+                format!("{}@{}", sender_nick, label)
+            };
+
+            // Access control (DMs only)
+            if is_dm {
+                if let Err(denial_msg) = channels::check_access(
+                    &current_config.dm_policy,
+                    &user_key,
+                    &user_key,
+                    &sender_nick,
+                    &current_config.allowed_users,
+                    &mut current_config.pending_users,
+                ) {
+                    let _ = channels::save_channel_config(&app_handle, CONFIG_KEY, &current_config);
+                    let _ = app_handle.emit(
+                        "irc-status",
+                        json!({
+                            "kind": "pairing_request",
+                            "user_id": &user_key,
+                            "username": &sender_nick,
+                        }),
+                    );
+                    channels::notify_pairing(&app_handle, "IRC", &sender_nick);
+                    send_line(
+                        &write_handle,
+                        &format!("PRIVMSG {} :{}", sender_nick, denial_msg),
+                    )
+                    .await;
+                    continue;
+                }
+            }
+
+            MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+
+            // Route to agent
+            let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
+            let ctx = "You are chatting via IRC. Keep responses concise and plain-text. \
+                       No markdown rendering — use simple text formatting. \
+                       IRC messages should ideally be under 400 characters.";
+
+            let response = channels::run_channel_agent(
+                &app_handle,
+                "irc",
+                ctx,
+                &content,
+                &user_key,
+                agent_id,
+                current_config.allow_dangerous_tools,
+            )
+            .await;
+
+            let reply_target = if is_dm { sender_nick.as_str() } else { target };
+
+            match response {
+                Ok(reply) if !reply.is_empty() => {
+                    // IRC has ~512 byte line limit, split at 400 chars
+                    for chunk in channels::split_message(&reply, 400) {
+                        // Replace newlines with separate PRIVMSG lines
+                        for line in chunk.lines() {
+                            if !line.trim().is_empty() {
+                                send_line(
+                                    &write_handle,
+                                    &format!("PRIVMSG {} :{}", reply_target, line),
+                                )
+                                .await;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("[irc] Agent error for {}: {}", user_key, e);
+                    send_line(
+                        &write_handle,
+                        &format!("PRIVMSG {} :Error: {}", reply_target, e),
+                    )
+                    .await;
+                }
+                _ => {}
+            }
+        }
+
+        // Reload config periodically
+        if last_config_reload.elapsed() > std::time::Duration::from_secs(30) {
+            if let Ok(fresh) = channels::load_channel_config::<IrcConfig>(&app_handle, CONFIG_KEY) {
+                current_config = fresh;
+            }
+            last_config_reload = std::time::Instant::now();
+        }
+    }
+
+    let _ = app_handle.emit(
+        "irc-status",
+        json!({
+            "kind": "disconnected",
+            "network": &label,
+        }),
+    );
+
+    Ok(())
+}
+
+// ── IRC Message Parser ─────────────────────────────────────────────────
+
+struct IrcParsed {
+    prefix: Option<String>,
+    command: String,
+    params: Vec<String>,
+    trailing: Option<String>,
+}
+
+impl IrcParsed {
+    fn prefix_nick(&self) -> Option<String> {
+        self.prefix
+            .as_ref()
+            .map(|p| p.split('!').next().unwrap_or(p).to_string())
+    }
+}
+
+fn parse_irc_line(line: &str) -> IrcParsed {
+    let mut remaining = line;
+    let prefix = if remaining.starts_with(':') {
+        let end = remaining.find(' ').unwrap_or(remaining.len());
+        let p = remaining[1..end].to_string();
+        remaining = remaining[end..].trim_start();
+        Some(p)
+    } else {
+        None
+    };
+
+    // Split trailing (after ' :')
+    let (main, trailing) = if let Some(idx) = remaining.find(" :") {
+        (&remaining[..idx], Some(remaining[idx + 2..].to_string()))
+    } else {
+        (remaining, None)
+    };
+
+    let parts: Vec<&str> = main.split_whitespace().collect();
+    let command = parts.first().unwrap_or(&"").to_string();
+    let params: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
+
+    IrcParsed {
+        prefix,
+        command,
+        params,
+        trailing,
+    }
+}
+
+// ── Config Persistence ─────────────────────────────────────────────────
+
+pub fn load_config(app_handle: &tauri::AppHandle) -> EngineResult<IrcConfig> {
+    channels::load_channel_config(app_handle, CONFIG_KEY)
+}
+
+pub fn save_config(app_handle: &tauri::AppHandle, config: &IrcConfig) -> EngineResult<()> {
+    channels::save_channel_config(app_handle, CONFIG_KEY, config)
+}
+
+pub fn approve_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::approve_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn deny_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::deny_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn remove_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::remove_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_lines_carry_keys() {
+        assert_eq!(join_command("#paw").as_deref(), Some("JOIN #paw"));
+        assert_eq!(
+            join_command("  #private  s3cret ").as_deref(),
+            Some("JOIN #private s3cret")
+        );
+        assert_eq!(join_command("   "), None);
+    }
+
+    #[test]
+    fn extra_networks_follow_the_primary() {
+        let config = IrcConfig {
+            networks: vec![
+                serde_json::from_value(json!({
+                    "name": "oftc", "server": "irc.oftc.net", "nick": "paw"
+                }))
+                .unwrap(),
+                serde_json::from_value(json!({
+                    "name": "off", "server": "irc.example.org", "nick": "paw", "enabled": false
+                }))
+                .unwrap(),
+            ],
+            ..Default::default()
+        };
+        let networks = config.all_networks();
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks[0].server, "irc.libera.chat");
+        assert_eq!(networks[1].port, 6697);
+        assert!(networks[1].tls);
+    }
+}
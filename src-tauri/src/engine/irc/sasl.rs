@@ -0,0 +1,257 @@
+// Paw Agent Engine — IRC SASL (IRCv3 CAP negotiation)
+//
+// Registration with SASL:
+//   CAP LS 302 → (server lists caps, maybe over several lines)
+//   CAP REQ :sasl → CAP ACK :sasl
+//   AUTHENTICATE PLAIN|EXTERNAL → AUTHENTICATE +
+//   AUTHENTICATE <base64 payload, 400-byte chunks> (or `+` for EXTERNAL)
+//   903 (success) / 902, 904, 905, 906 (failure) → CAP END
+//
+// EXTERNAL authenticates with the TLS client certificate (CertFP), so the
+// connection has to present one — see `IrcNetwork::client_cert_path`.
+// A failed negotiation still ends with CAP END so registration completes;
+// NickServ IDENTIFY is the fallback.
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SaslMechanism {
+    #[default]
+    None,
+    Plain,
+    External,
+}
+
+impl SaslMechanism {
+    fn name(self) -> &'static str {
+        match self {
+            SaslMechanism::None => "",
+            SaslMechanism::Plain => "PLAIN",
+            SaslMechanism::External => "EXTERNAL",
+        }
+    }
+}
+
+/// AUTHENTICATE payloads are sent in chunks of this many bytes.
+const CHUNK: usize = 400;
+
+/// CAP/SASL state for one connection attempt.
+pub(crate) struct SaslNegotiation {
+    mechanism: SaslMechanism,
+    username: String,
+    password: String,
+    caps: Vec<String>,
+    outcome: Option<Result<(), String>>,
+}
+
+impl SaslNegotiation {
+    /// `None` when SASL isn't configured.
+    pub(crate) fn new(mechanism: SaslMechanism, username: &str, password: &str) -> Option<Self> {
+        if mechanism == SaslMechanism::None {
+            return None;
+        }
+        Some(SaslNegotiation {
+            mechanism,
+            username: username.to_string(),
+            password: password.to_string(),
+            caps: vec![],
+            outcome: None,
+        })
+    }
+
+    /// Sent before NICK/USER so the server holds registration for CAP END.
+    pub(crate) fn start(&self) -> &'static str {
+        "CAP LS 302"
+    }
+
+    /// `Some(Ok)` after 903, `Some(Err(reason))` after a failure.
+    pub(crate) fn outcome(&self) -> Option<&Result<(), String>> {
+        self.outcome.as_ref()
+    }
+
+    /// Feed one server line (command, middle params, trailing); returns the
+    /// lines to send back.
+    pub(crate) fn handle(
+        &mut self,
+        command: &str,
+        params: &[String],
+        trailing: Option<&str>,
+    ) -> Vec<String> {
+        if self.outcome.is_some() {
+            return vec![];
+        }
+        match command {
+            "CAP" => {
+                let sub = params.get(1).map(|s| s.to_uppercase()).unwrap_or_default();
+                // Caps are normally the trailing param; a lone cap may come bare
+                let bare = params.get(2..).map(|p| p.join(" ")).unwrap_or_default();
+                let caps = trailing.unwrap_or(&bare);
+                match sub.as_str() {
+                    "LS" => {
+                        self.caps
+                            .extend(caps.split_whitespace().map(str::to_string));
+                        // `CAP * LS * :…` — more lines follow
+                        if params.get(2).map(String::as_str) == Some("*") {
+                            return vec![];
+                        }
+                        match self.sasl_offered() {
+                            Ok(()) => vec!["CAP REQ :sasl".into()],
+                            Err(reason) => self.fail(reason),
+                        }
+                    }
+                    "ACK" if caps.split_whitespace().any(|c| c == "sasl") => {
+                        vec![format!("AUTHENTICATE {}", self.mechanism.name())]
+                    }
+                    "NAK" => self.fail("server refused the sasl capability".into()),
+                    _ => vec![],
+                }
+            }
+            "AUTHENTICATE" if params.first().map(String::as_str) == Some("+") => {
+                match self.mechanism {
+                    SaslMechanism::Plain => plain_payload(&self.username, &self.password)
+                        .into_iter()
+                        .map(|chunk| format!("AUTHENTICATE {}", chunk))
+                        .collect(),
+                    _ => vec!["AUTHENTICATE +".into()],
+                }
+            }
+            "903" => {
+                self.outcome = Some(Ok(()));
+                vec!["CAP END".into()]
+            }
+            "902" | "904" | "905" | "906" => {
+                let reason = trailing.unwrap_or("SASL authentication failed").to_string();
+                self.fail(reason)
+            }
+            _ => vec![],
+        }
+    }
+
+    fn sasl_offered(&self) -> Result<(), String> {
+        let sasl = self
+            .caps
+            .iter()
+            .find(|c| *c == "sasl" || c.starts_with("sasl="))
+            .ok_or("server does not offer SASL")?;
+        // `sasl=PLAIN,EXTERNAL` (CAP 302) lists the mechanisms
+        match sasl.strip_prefix("sasl=") {
+            Some(mechs)
+                if !mechs
+                    .split(',')
+                    .any(|m| m.eq_ignore_ascii_case(self.mechanism.name())) =>
+            {
+                Err(format!(
+                    "server does not support SASL {} (offers {})",
+                    self.mechanism.name(),
+                    mechs
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn fail(&mut self, reason: String) -> Vec<String> {
+        self.outcome = Some(Err(reason));
+        vec!["CAP END".into()]
+    }
+}
+
+/// base64(`authzid \0 authcid \0 password`) split into AUTHENTICATE chunks.
+/// A payload that is an exact multiple of the chunk size ends with `+`.
+fn plain_payload(username: &str, password: &str) -> Vec<String> {
+    let encoded = base64::engine::general_purpose::STANDARD
+        .encode(format!("{}\0{}\0{}", username, username, password));
+    let mut chunks: Vec<String> = encoded
+        .as_bytes()
+        .chunks(CHUNK)
+        .map(|c| String::from_utf8_lossy(c).into_owned())
+        .collect();
+    if encoded.len() % CHUNK == 0 {
+        chunks.push("+".into());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(p: &[&str]) -> Vec<String> {
+        p.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_flow() {
+        let mut sasl = SaslNegotiation::new(SaslMechanism::Plain, "paw", "hunter2").unwrap();
+        assert!(sasl
+            .handle("CAP", &params(&["*", "LS", "*"]), Some("multi-prefix"))
+            .is_empty());
+        assert_eq!(
+            sasl.handle(
+                "CAP",
+                &params(&["*", "LS"]),
+                Some("sasl=PLAIN,EXTERNAL account-tag")
+            ),
+            vec!["CAP REQ :sasl"]
+        );
+        assert_eq!(
+            sasl.handle("CAP", &params(&["*", "ACK"]), Some("sasl")),
+            vec!["AUTHENTICATE PLAIN"]
+        );
+        let expected = base64::engine::general_purpose::STANDARD.encode("paw\0paw\0hunter2");
+        assert_eq!(
+            sasl.handle("AUTHENTICATE", &params(&["+"]), None),
+            vec![format!("AUTHENTICATE {}", expected)]
+        );
+        assert_eq!(
+            sasl.handle(
+                "903",
+                &params(&["paw"]),
+                Some("SASL authentication successful")
+            ),
+            vec!["CAP END"]
+        );
+        assert_eq!(sasl.outcome(), Some(&Ok(())));
+    }
+
+    #[test]
+    fn external_and_failures() {
+        let mut sasl = SaslNegotiation::new(SaslMechanism::External, "", "").unwrap();
+        sasl.handle("CAP", &params(&["*", "LS"]), Some("sasl"));
+        sasl.handle("CAP", &params(&["*", "ACK"]), Some("sasl"));
+        assert_eq!(
+            sasl.handle("AUTHENTICATE", &params(&["+"]), None),
+            vec!["AUTHENTICATE +"]
+        );
+        assert_eq!(
+            sasl.handle("904", &params(&["paw"]), Some("SASL authentication failed")),
+            vec!["CAP END"]
+        );
+        assert!(matches!(sasl.outcome(), Some(Err(_))));
+
+        let mut unsupported = SaslNegotiation::new(SaslMechanism::External, "", "").unwrap();
+        assert_eq!(
+            unsupported.handle("CAP", &params(&["*", "LS"]), Some("sasl=PLAIN")),
+            vec!["CAP END"]
+        );
+        let mut absent = SaslNegotiation::new(SaslMechanism::Plain, "a", "b").unwrap();
+        assert_eq!(
+            absent.handle("CAP", &params(&["*", "LS"]), Some("multi-prefix")),
+            vec!["CAP END"]
+        );
+        assert!(SaslNegotiation::new(SaslMechanism::None, "", "").is_none());
+    }
+
+    #[test]
+    fn long_payloads_are_chunked() {
+        let chunks = plain_payload("user", &"x".repeat(600));
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].len(), CHUNK);
+        assert!(chunks.iter().all(|c| c.len() <= CHUNK));
+        // "u\0u\0" + 296 bytes = 300 raw bytes → exactly 400 base64 bytes
+        let exact = plain_payload("u", &"x".repeat(296));
+        assert_eq!(exact, vec![exact[0].clone(), "+".to_string()]);
+    }
+}
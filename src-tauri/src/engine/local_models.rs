@@ -0,0 +1,270 @@
+// Paw Agent Engine — Local model warm-up and keep-alive
+//
+// Ollama unloads a model five minutes after its last request, and loading it
+// back can take 20 s before the first token. EngineConfig.local_models keeps
+// local models resident:
+//
+//   - keep_alive: passed as OLLAMA_KEEP_ALIVE to an `ollama serve` Paw
+//     starts, and re-applied after every Ollama response (the OpenAI-compatible
+//     endpoint Paw chats through has no keep_alive field), so it also holds
+//     for an Ollama the user runs themselves.
+//   - warm_on_session_open: `engine_local_model_warm` loads the session's
+//     model when the chat view opens it.
+//   - prewarm_cron: the cron heartbeat loads a task's model shortly before
+//     the task is due.
+//
+// Loading is Ollama's empty /api/generate request: no prompt, no tokens
+// generated, just the model brought into memory with the given keep_alive.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::memory::ollama;
+use crate::engine::providers::middleware::{
+    self, ProviderMiddleware, RequestContext, ResponseContext,
+};
+use crate::engine::state::{normalize_model_name, provider_for_model_or_default, EngineState};
+use crate::engine::tasks;
+use crate::engine::types::*;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+const MIDDLEWARE_NAME: &str = "ollama-keep-alive";
+/// Loading a large model from disk can take a while on a slow machine.
+const WARM_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Cron runs already pre-warmed, as (task id, next_run_at).
+static PREWARMED: LazyLock<Mutex<HashSet<(String, String)>>> = LazyLock::new(Default::default);
+
+/// A model that was loaded ahead of use.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelWarmUp {
+    pub model: String,
+    pub provider_id: String,
+    /// How long the load request took (close to 0 when already loaded)
+    pub duration_ms: u64,
+}
+
+/// Apply `config.local_models` (called on startup and config save).
+pub fn configure(config: &EngineConfig) {
+    let keep_alive = config.local_models.keep_alive.trim().to_string();
+    ollama::set_keep_alive(&keep_alive);
+    if keep_alive.is_empty() {
+        middleware::unregister(MIDDLEWARE_NAME);
+        return;
+    }
+    let base_urls = config
+        .providers
+        .iter()
+        .filter(|p| p.kind == ProviderKind::Ollama)
+        .map(|p| (p.id.clone(), native_base_url(p.base_url.as_deref())))
+        .collect();
+    middleware::register(Arc::new(KeepAlive {
+        keep_alive,
+        base_urls,
+    }));
+}
+
+/// Ollama's native API root for a provider base URL, which may carry the
+/// OpenAI-compatible `/v1` suffix.
+fn native_base_url(base_url: Option<&str>) -> String {
+    let url = base_url
+        .map(|u| u.trim().trim_end_matches('/'))
+        .filter(|u| !u.is_empty())
+        .unwrap_or("http://localhost:11434");
+    url.strip_suffix("/v1").unwrap_or(url).to_string()
+}
+
+/// Load `model` into memory and hold it for `keep_alive` (empty = Ollama's
+/// default). Returns how long the request took.
+pub async fn warm(base_url: &str, model: &str, keep_alive: &str) -> EngineResult<Duration> {
+    let mut body = serde_json::json!({ "model": model, "stream": false });
+    if !keep_alive.is_empty() {
+        body["keep_alive"] = keep_alive.into();
+    }
+    let started = Instant::now();
+    let resp = reqwest::Client::new()
+        .post(format!("{}/api/generate", base_url))
+        .json(&body)
+        .timeout(WARM_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama unreachable: {}", e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!("Ollama could not load {}: {} {}", model, status, text).into());
+    }
+    Ok(started.elapsed())
+}
+
+/// Load the model a chat session will use, if it runs on Ollama and
+/// `warm_on_session_open` is on: the model pinned to the session, else
+/// `model` (the agent's choice), else the default model.
+pub async fn warm_for_session(
+    state: &EngineState,
+    session_id: Option<&str>,
+    model: Option<&str>,
+) -> EngineResult<Option<ModelWarmUp>> {
+    let pinned = session_id
+        .and_then(|id| state.store.get_session_model(id).ok().flatten())
+        .filter(|m| !m.is_empty());
+    let (provider, model, keep_alive) = {
+        let cfg = state.config.lock();
+        if !cfg.local_models.warm_on_session_open {
+            return Ok(None);
+        }
+        let model = match pinned.as_deref().or(model) {
+            Some(m) if !m.is_empty() && !m.eq_ignore_ascii_case("default") => m.to_string(),
+            _ => match cfg.default_model.clone() {
+                Some(m) => m,
+                None => return Ok(None),
+            },
+        };
+        let model = normalize_model_name(&model).to_string();
+        let provider = provider_for_model_or_default(&model, &cfg);
+        (
+            provider,
+            model,
+            cfg.local_models.keep_alive.trim().to_string(),
+        )
+    };
+    let Some(provider) = provider.filter(|p| p.kind == ProviderKind::Ollama) else {
+        return Ok(None);
+    };
+    let took = warm(
+        &native_base_url(provider.base_url.as_deref()),
+        &model,
+        &keep_alive,
+    )
+    .await?;
+    info!("[local-models] {} ready in {} ms", model, took.as_millis());
+    Ok(Some(ModelWarmUp {
+        model,
+        provider_id: provider.id,
+        duration_ms: took.as_millis() as u64,
+    }))
+}
+
+/// Start loading the local models of cron tasks due within
+/// `prewarm_lead_secs`, once per scheduled run. Called by the heartbeat.
+pub fn prewarm_upcoming_tasks(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<EngineState>();
+    let cfg = state.config.lock().clone();
+    let settings = &cfg.local_models;
+    if !settings.prewarm_cron {
+        return;
+    }
+    let default_model = cfg
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "gpt-5.1".to_string());
+    let now = chrono::Utc::now();
+    let horizon = now + chrono::Duration::seconds(settings.prewarm_lead_secs as i64);
+    let Ok(upcoming) = state.store.get_upcoming_cron_tasks(&horizon.to_rfc3339()) else {
+        return;
+    };
+
+    let mut to_warm: HashSet<(String, String)> = HashSet::new();
+    let mut prewarmed = PREWARMED.lock();
+    for task in &upcoming {
+        let next_run = task.next_run_at.clone().unwrap_or_default();
+        if !prewarmed.insert((task.id.clone(), next_run)) {
+            continue;
+        }
+        for agent_id in tasks::task_agent_ids(task) {
+            let model = tasks::task_model(task, &agent_id, &cfg.model_routing, &default_model);
+            if let Some(provider) = provider_for_model_or_default(&model, &cfg)
+                .filter(|p| p.kind == ProviderKind::Ollama)
+            {
+                to_warm.insert((native_base_url(provider.base_url.as_deref()), model));
+            }
+        }
+    }
+    // Forget runs that have passed
+    prewarmed.retain(|(_, next_run)| {
+        chrono::DateTime::parse_from_rfc3339(next_run).is_ok_and(|due| due > now)
+    });
+    drop(prewarmed);
+
+    for (base_url, model) in to_warm {
+        let keep_alive = settings.keep_alive.trim().to_string();
+        tauri::async_runtime::spawn(async move {
+            match warm(&base_url, &model, &keep_alive).await {
+                Ok(took) => info!(
+                    "[local-models] Pre-warmed {} for a cron task ({} ms)",
+                    model,
+                    took.as_millis()
+                ),
+                Err(e) => warn!("[local-models] Pre-warm of {} failed: {}", model, e),
+            }
+        });
+    }
+}
+
+// ── Keep-alive middleware ──────────────────────────────────────────────
+
+/// Re-applies keep_alive after each successful Ollama response.
+struct KeepAlive {
+    keep_alive: String,
+    /// Ollama provider id → native API root
+    base_urls: HashMap<String, String>,
+}
+
+impl ProviderMiddleware for KeepAlive {
+    fn name(&self) -> &str {
+        MIDDLEWARE_NAME
+    }
+
+    fn on_response(&self, req: &RequestContext, resp: &ResponseContext) {
+        if req.provider_kind != ProviderKind::Ollama || resp.error.is_some() {
+            return;
+        }
+        let base_url = self
+            .base_urls
+            .get(&req.provider_id)
+            .cloned()
+            .unwrap_or_else(|| native_base_url(None));
+        let model = req.model.clone();
+        let keep_alive = self.keep_alive.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = warm(&base_url, &model, &keep_alive).await {
+                warn!("[local-models] keep_alive for {} not applied: {}", model, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_base_url_drops_openai_suffix() {
+        assert_eq!(native_base_url(None), "http://localhost:11434");
+        assert_eq!(native_base_url(Some("")), "http://localhost:11434");
+        assert_eq!(
+            native_base_url(Some("http://gpu-box:11434/v1/")),
+            "http://gpu-box:11434"
+        );
+        assert_eq!(
+            native_base_url(Some("http://127.0.0.1:11434")),
+            "http://127.0.0.1:11434"
+        );
+    }
+
+    #[test]
+    fn local_model_settings_default_when_missing() {
+        let cfg: EngineConfig = serde_json::from_str(
+            r#"{"providers":[],"max_tool_rounds":20,"tool_timeout_secs":300,
+                "local_models":{"keep_alive":"2h"}}"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.local_models.keep_alive, "2h");
+        assert!(cfg.local_models.warm_on_session_open);
+        assert_eq!(cfg.local_models.prewarm_lead_secs, 120);
+    }
+}
@@ -0,0 +1,268 @@
+// Paw Agent Engine — Home location
+//
+// One place the user calls home, stored in `EngineConfig::home_location`:
+//   - typed in Settings / on the Today dashboard and geocoded with
+//     Open-Meteo (free, no key), or
+//   - auto-detected from the public IP via ipapi.co, only when asked.
+//
+// Used by the weather widget, the `local_time` and `nearby_search` tools,
+// and — unless the user turns sharing off — the agent runtime context, so
+// "what's the weather" or "find a pharmacy" needs no follow-up question.
+//
+// Nearby search uses OpenStreetMap Nominatim, bounded to a box around the
+// point and sorted by distance.
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::HomeLocation;
+use crate::engine::state::EngineState;
+use serde::Serialize;
+use std::time::Duration;
+
+const USER_AGENT: &str = "OpenPawz/1.0 (+https://github.com/elisplash/paw)";
+
+pub fn client() -> EngineResult<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(USER_AGENT)
+        .build()?)
+}
+
+/// The configured home location, if any.
+pub fn home(state: &EngineState) -> Option<HomeLocation> {
+    state.config.lock().home_location.clone()
+}
+
+/// Geocode a place name ("Lyon", "Portland, OR") with Open-Meteo.
+pub async fn geocode(client: &reqwest::Client, query: &str) -> EngineResult<HomeLocation> {
+    // Open-Meteo matches on the place name only; "Portland, OR" would miss
+    let name = query.split(',').next().unwrap_or(query).trim();
+    let resp = client
+        .get("https://geocoding-api.open-meteo.com/v1/search")
+        .query(&[
+            ("name", name),
+            ("count", "10"),
+            ("language", "en"),
+            ("format", "json"),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Geocoding failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Geocoding API returned {}", resp.status()).into());
+    }
+    let json: serde_json::Value = resp.json().await?;
+    let results = json["results"].as_array().cloned().unwrap_or_default();
+    let place = pick_geocode_result(&results, query)
+        .ok_or_else(|| format!("Could not find a place called '{}'", query))?;
+    Ok(HomeLocation {
+        name: place["name"].as_str().unwrap_or(name).to_string(),
+        region: place["admin1"].as_str().unwrap_or("").to_string(),
+        country: place["country"].as_str().unwrap_or("").to_string(),
+        latitude: place["latitude"].as_f64().ok_or("Missing latitude")?,
+        longitude: place["longitude"].as_f64().ok_or("Missing longitude")?,
+        timezone: place["timezone"].as_str().map(String::from),
+        source: "manual".into(),
+        share_with_agents: true,
+    })
+}
+
+/// Open-Meteo results come ranked by population; a qualifier after the
+/// comma ("Paris, Texas", "Portland, OR") picks the match whose region or
+/// country starts with it.
+fn pick_geocode_result<'a>(
+    results: &'a [serde_json::Value],
+    query: &str,
+) -> Option<&'a serde_json::Value> {
+    let qualifier = query
+        .split_once(',')
+        .map(|(_, q)| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+    if let Some(q) = qualifier {
+        let matches = |v: &serde_json::Value, key: &str| {
+            v[key]
+                .as_str()
+                .is_some_and(|s| s.to_lowercase().starts_with(&q))
+        };
+        if let Some(hit) = results
+            .iter()
+            .find(|r| matches(r, "admin1") || matches(r, "country") || matches(r, "country_code"))
+        {
+            return Some(hit);
+        }
+    }
+    results.first()
+}
+
+/// Approximate location from the public IP (ipapi.co — free, no key).
+pub async fn detect_by_ip(client: &reqwest::Client) -> EngineResult<HomeLocation> {
+    let resp = client
+        .get("https://ipapi.co/json/")
+        .send()
+        .await
+        .map_err(|e| format!("IP geolocation failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("IP geolocation returned {}", resp.status()).into());
+    }
+    let data: serde_json::Value = resp.json().await?;
+    Ok(HomeLocation {
+        name: data["city"].as_str().unwrap_or("Unknown").to_string(),
+        region: data["region"].as_str().unwrap_or("").to_string(),
+        country: data["country_name"].as_str().unwrap_or("").to_string(),
+        latitude: data["latitude"]
+            .as_f64()
+            .ok_or("IP geolocation missing latitude")?,
+        longitude: data["longitude"]
+            .as_f64()
+            .ok_or("IP geolocation missing longitude")?,
+        timezone: data["timezone"].as_str().map(String::from),
+        source: "ip".into(),
+        share_with_agents: true,
+    })
+}
+
+/// Runtime-context line for agents, or `None` when unset or not shared.
+pub fn context_line(home: Option<&HomeLocation>) -> Option<String> {
+    let home = home.filter(|h| h.share_with_agents)?;
+    Some(format!(
+        "User location: {} ({:.2}, {:.2}){}",
+        home.display_name(),
+        home.latitude,
+        home.longitude,
+        if home.source == "ip" {
+            " — approximate, from IP"
+        } else {
+            ""
+        }
+    ))
+}
+
+// ── Nearby search ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Place {
+    pub name: String,
+    pub kind: String,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_km: f64,
+}
+
+/// Great-circle distance in km.
+pub fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (p1, p2) = (lat1.to_radians(), lat2.to_radians());
+    let dp = (lat2 - lat1).to_radians();
+    let dl = (lon2 - lon1).to_radians();
+    let a = (dp / 2.0).sin().powi(2) + p1.cos() * p2.cos() * (dl / 2.0).sin().powi(2);
+    6371.0 * 2.0 * a.sqrt().asin()
+}
+
+/// Places matching `query` ("pharmacy", "thai restaurant") within
+/// `radius_km` of a point, nearest first.
+pub async fn nearby(
+    client: &reqwest::Client,
+    query: &str,
+    lat: f64,
+    lon: f64,
+    radius_km: f64,
+    limit: usize,
+) -> EngineResult<Vec<Place>> {
+    // Bounding box: 1° latitude ≈ 111 km; longitude shrinks with cos(lat)
+    let dlat = radius_km / 111.0;
+    let dlon = radius_km / (111.0 * lat.to_radians().cos().abs().max(0.01));
+    let viewbox = format!(
+        "{},{},{},{}",
+        lon - dlon,
+        lat + dlat,
+        lon + dlon,
+        lat - dlat
+    );
+    let resp = client
+        .get("https://nominatim.openstreetmap.org/search")
+        .query(&[
+            ("q", query),
+            ("format", "jsonv2"),
+            ("limit", "40"),
+            ("viewbox", &viewbox),
+            ("bounded", "1"),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Nearby search failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("OpenStreetMap search returned {}", resp.status()).into());
+    }
+    let json: serde_json::Value = resp.json().await?;
+    let mut places: Vec<Place> = json
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|p| parse_place(p, lat, lon))
+                .collect()
+        })
+        .unwrap_or_default();
+    places.retain(|p| p.distance_km <= radius_km);
+    places.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    places.truncate(limit);
+    Ok(places)
+}
+
+fn parse_place(p: &serde_json::Value, lat: f64, lon: f64) -> Option<Place> {
+    // Nominatim returns coordinates as strings
+    let plat: f64 = p["lat"].as_str()?.parse().ok()?;
+    let plon: f64 = p["lon"].as_str()?.parse().ok()?;
+    let address = p["display_name"].as_str().unwrap_or("").to_string();
+    let name = p["name"]
+        .as_str()
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| address.split(',').next().unwrap_or("").trim().to_string());
+    Some(Place {
+        name,
+        kind: p["type"].as_str().unwrap_or("").replace('_', " "),
+        address,
+        latitude: plat,
+        longitude: plon,
+        distance_km: distance_km(lat, lon, plat, plon),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn distances_and_places() {
+        // Paris → London ≈ 344 km
+        let d = distance_km(48.8566, 2.3522, 51.5074, -0.1278);
+        assert!((d - 344.0).abs() < 5.0, "{}", d);
+
+        let place = parse_place(
+            &json!({ "lat": "48.86", "lon": "2.35", "name": "", "type": "pharmacy",
+                     "display_name": "Pharmacie Centrale, Rue de Rivoli, Paris" }),
+            48.8566,
+            2.3522,
+        )
+        .unwrap();
+        assert_eq!(place.name, "Pharmacie Centrale");
+        assert!(place.distance_km < 1.0);
+    }
+
+    #[test]
+    fn geocode_qualifier_picks_region() {
+        let results = vec![
+            json!({ "name": "Paris", "admin1": "Île-de-France", "country": "France" }),
+            json!({ "name": "Paris", "admin1": "Texas", "country": "United States" }),
+        ];
+        assert_eq!(
+            pick_geocode_result(&results, "Paris, Texas").unwrap()["admin1"],
+            "Texas"
+        );
+        assert_eq!(
+            pick_geocode_result(&results, "Paris").unwrap()["country"],
+            "France"
+        );
+    }
+}
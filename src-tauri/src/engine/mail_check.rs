@@ -0,0 +1,257 @@
+// Paw Agent Engine — Mail account test
+//
+// test_account checks an account from the himalaya config without sending
+// anything, stage by stage: DNS, TCP, TLS and login on the IMAP server plus
+// its folder list, the same up to login on the SMTP server. Each server's
+// test stops at the first failing stage, so a wrong port, a certificate
+// problem or a bad password shows up during setup instead of on the first
+// real send. The IMAP client is mail_watch's, SMTP is engine::smtp.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::mail_watch::{self, Imap, ImapAccount, SmtpServer};
+use crate::engine::smtp::Smtp;
+use log::info;
+use serde::Serialize;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+/// Time allowed for the TCP probe of a connection test.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One step of a connection test.
+#[derive(Debug, Clone, Serialize)]
+pub struct MailTestStage {
+    /// "dns", "tcp", "tls", "login" or "folders"
+    pub stage: &'static str,
+    pub ok: bool,
+    /// What was found, or the error
+    pub detail: String,
+    pub elapsed_ms: u64,
+}
+
+/// The test of one server. Stops at the first failing stage.
+#[derive(Debug, Clone, Serialize)]
+pub struct MailServerTest {
+    pub host: String,
+    pub port: u16,
+    pub encryption: String,
+    /// Every stage passed
+    pub ok: bool,
+    pub stages: Vec<MailTestStage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MailAccountTest {
+    pub account: String,
+    pub imap: MailServerTest,
+    /// None when the account has no SMTP backend
+    pub smtp: Option<MailServerTest>,
+}
+
+impl MailServerTest {
+    fn new(host: &str, port: u16, encryption: &str) -> Self {
+        MailServerTest {
+            host: host.to_string(),
+            port,
+            encryption: encryption.to_string(),
+            ok: false,
+            stages: vec![],
+        }
+    }
+
+    /// Record how `stage` went; its value if it succeeded.
+    fn record<T>(
+        &mut self,
+        stage: &'static str,
+        (result, elapsed): (EngineResult<T>, Duration),
+        detail: impl FnOnce(&T) -> String,
+    ) -> Option<T> {
+        let (ok, detail, value) = match result {
+            Ok(value) => (true, detail(&value), Some(value)),
+            Err(e) => (false, e.to_string(), None),
+        };
+        self.stages.push(MailTestStage {
+            stage,
+            ok,
+            detail,
+            elapsed_ms: elapsed.as_millis() as u64,
+        });
+        value
+    }
+
+    /// The dns and tcp stages.
+    async fn reach(&mut self) -> Option<()> {
+        let target = (self.host.clone(), self.port);
+        let addrs = self.record(
+            "dns",
+            timed(async {
+                let addrs: Vec<SocketAddr> = tokio::net::lookup_host(&target)
+                    .await
+                    .map_err(|e| format!("Can't resolve {}: {}", target.0, e))?
+                    .collect();
+                if addrs.is_empty() {
+                    return Err(format!("{} has no addresses", target.0).into());
+                }
+                Ok(addrs)
+            })
+            .await,
+            |addrs| {
+                let ips: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+                ips.join(", ")
+            },
+        )?;
+        self.record(
+            "tcp",
+            timed(async {
+                let tcp = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(&addrs[..]))
+                    .await
+                    .map_err(|_| {
+                        format!(
+                            "No answer on port {} within {}s",
+                            target.1,
+                            PROBE_TIMEOUT.as_secs()
+                        )
+                    })?
+                    .map_err(|e| format!("Can't connect to port {}: {}", target.1, e))?;
+                Ok(tcp.peer_addr().map(|a| a.to_string()).unwrap_or_default())
+            })
+            .await,
+            |peer| format!("Connected to {}", peer),
+        )
+        .map(|_| ())
+    }
+}
+
+/// Check that `account_name` works without sending anything: IMAP login
+/// and folder list, SMTP EHLO and login.
+pub async fn test_account(account_name: &str) -> EngineResult<MailAccountTest> {
+    let account = mail_watch::load_accounts()
+        .into_iter()
+        .find(|a| a.name == account_name)
+        .ok_or_else(|| format!("No IMAP account '{}' in the mail config", account_name))?;
+
+    let mut imap_test = MailServerTest::new(&account.host, account.port, &account.encryption);
+    imap_test.ok = test_imap(&account, &mut imap_test).await.is_some();
+    let smtp_test = match &account.smtp {
+        Some(server) => {
+            let mut test = MailServerTest::new(&server.host, server.port, &server.encryption);
+            test.ok = test_smtp(&account, server, &mut test).await.is_some();
+            Some(test)
+        }
+        None => None,
+    };
+    info!(
+        "[mail] Tested {}: IMAP {}, SMTP {}",
+        account.name,
+        if imap_test.ok { "ok" } else { "failed" },
+        match &smtp_test {
+            Some(t) if t.ok => "ok",
+            Some(_) => "failed",
+            None => "not configured",
+        }
+    );
+    Ok(MailAccountTest {
+        account: account.name,
+        imap: imap_test,
+        smtp: smtp_test,
+    })
+}
+
+async fn test_imap(account: &ImapAccount, test: &mut MailServerTest) -> Option<()> {
+    test.reach().await?;
+    let mut imap = test.record("tls", timed(Imap::connect(account)).await, |_| {
+        security(&account.encryption)
+    })?;
+    test.record("login", timed(imap.authenticate(account)).await, |_| {
+        login_method(account, &account.login)
+    })?;
+    let folders = timed(async {
+        Ok(imap
+            .command("LIST \"\" \"*\"")
+            .await?
+            .iter()
+            .filter(|r| r.line.starts_with("* LIST"))
+            .count())
+    })
+    .await;
+    let listed = test.record("folders", folders, |n| format!("{} folders", n));
+    imap.logout().await;
+    listed.map(|_| ())
+}
+
+async fn test_smtp(
+    account: &ImapAccount,
+    server: &SmtpServer,
+    test: &mut MailServerTest,
+) -> Option<()> {
+    test.reach().await?;
+    let (mut smtp, extensions) =
+        test.record("tls", timed(Smtp::connect(server)).await, |(_, ext)| {
+            let auth = ext.iter().find_map(|e| e.strip_prefix("AUTH "));
+            format!(
+                "{}, AUTH {}",
+                security(&server.encryption),
+                auth.unwrap_or("not offered")
+            )
+        })?;
+    let logged_in = test.record(
+        "login",
+        timed(smtp.authenticate(account, server, &extensions)).await,
+        |_| login_method(account, &server.login),
+    );
+    smtp.quit().await;
+    logged_in
+}
+
+async fn timed<T>(f: impl Future<Output = EngineResult<T>>) -> (EngineResult<T>, Duration) {
+    let started = Instant::now();
+    let result = f.await;
+    (result, started.elapsed())
+}
+
+fn security(encryption: &str) -> String {
+    match encryption {
+        "start-tls" => "STARTTLS",
+        "none" => "No encryption — the password is sent in plaintext",
+        _ => "TLS",
+    }
+    .to_string()
+}
+
+fn login_method(account: &ImapAccount, login: &str) -> String {
+    match account.oauth {
+        Some(_) => format!("XOAUTH2 as {}", login),
+        None => format!("Password as {}", login),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn connection_test_stops_at_the_failing_stage() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut open = MailServerTest::new("127.0.0.1", port, "tls");
+        assert_eq!(open.reach().await, Some(()));
+        let stages: Vec<_> = open.stages.iter().map(|s| (s.stage, s.ok)).collect();
+        assert_eq!(stages, vec![("dns", true), ("tcp", true)]);
+
+        drop(listener);
+        let mut closed = MailServerTest::new("127.0.0.1", port, "tls");
+        assert_eq!(closed.reach().await, None);
+        let tcp = &closed.stages[1];
+        assert!(!tcp.ok && tcp.detail.starts_with("Can't connect to port"));
+
+        let (result, _) = timed(async { EngineResult::<()>::Err("535 Bad".into()) }).await;
+        let mut refused = MailServerTest::new("smtp.example.com", 465, "tls");
+        assert_eq!(
+            refused.record("login", (result, Duration::ZERO), |_| unreachable!()),
+            None
+        );
+        assert_eq!(refused.stages[0].detail, "535 Bad");
+    }
+}
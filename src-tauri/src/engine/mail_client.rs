@@ -0,0 +1,947 @@
+// Paw Agent Engine — Native mail client
+//
+// Backs the Mail view's commands (commands::mail) and the email bridge's
+// replies with async-imap (reading, folders, flags, moves) and lettre
+// (SMTP submission), so reading and sending mail doesn't need the himalaya
+// binary. The accounts still come from the himalaya config the Mail setup
+// writes, and the results keep himalaya's JSON shapes (envelopes, folders).
+// Connections are opened by mail_watch::open_stream (the same TLS setup as
+// the watcher), logging in with the keychain password or XOAUTH2.
+//
+// himalaya remains the fallback, used when
+//   - the account isn't an IMAP account we can parse (maildir, notmuch, …)
+//     or has no SMTP backend (sending only)
+//   - the native login fails (e.g. an `auth.cmd` password that isn't in
+//     the keychain) and himalaya is installed
+// Errors after a successful login are returned as they are: himalaya would
+// hit the same server, and retrying a half-done move or send could repeat it.
+//
+// Message ids are IMAP UIDs.
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::email::{address_list, header_value, OutgoingEmail};
+use crate::engine::email_bridge::parse::{body_text, decode_words, mailbox};
+use crate::engine::mail_oauth;
+use crate::engine::mail_watch::{self, ImapAccount, MailStream, SmtpServer};
+use async_imap::types::{Fetch, Flag, Name, NameAttribute};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use futures::TryStreamExt;
+use lettre::address::{Address as SmtpAddress, Envelope as SmtpEnvelope};
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
+use lettre::transport::smtp::extension::ClientId;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Trash folder names tried when the server doesn't mark one (RFC 6154).
+const TRASH_NAMES: &[&str] = &["Trash", "Deleted Items", "Deleted Messages", "Deleted"];
+const SENT_NAMES: &[&str] = &["Sent", "Sent Items", "Sent Messages", "Sent Mail"];
+const INBOX: &str = "INBOX";
+/// Timeout of every SMTP command.
+const SMTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+type Session = async_imap::Session<Box<dyn MailStream>>;
+
+/// A message summary, as `himalaya envelope list --output json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Envelope {
+    pub id: String,
+    /// Without the backslash: "Seen", "Answered", "Flagged", …
+    pub flags: Vec<String>,
+    pub subject: String,
+    pub from: Address,
+    pub to: Address,
+    /// RFC 3339 when the Date header parses, as sent otherwise
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Address {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub addr: String,
+}
+
+/// A folder, as `himalaya folder list --output json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Folder {
+    pub name: String,
+    /// The LIST attributes, e.g. "\HasNoChildren, \Sent"
+    pub desc: String,
+}
+
+// ── Operations ─────────────────────────────────────────────────────────
+//
+// `folder` is the folder the message ids refer to; None means INBOX, as
+// with himalaya.
+
+/// The newest `page_size` envelopes of `folder`, newest first, as JSON.
+pub async fn list_envelopes(
+    account: Option<&str>,
+    folder: Option<&str>,
+    page_size: u32,
+) -> EngineResult<String> {
+    if let Some((_, mut imap)) = native(account).await? {
+        let envelopes = native_envelopes(&mut imap, folder.unwrap_or(INBOX), page_size).await?;
+        logout(imap).await;
+        return Ok(serde_json::to_string(&envelopes)?);
+    }
+    let page_size = page_size.to_string();
+    himalaya(
+        &["envelope", "list"],
+        account,
+        folder,
+        &["--page-size", &page_size, "--output", "json"],
+        None,
+    )
+    .await
+}
+
+/// One message as text: the main headers, a blank line and the body.
+/// Marks it seen.
+pub async fn read_message(
+    account: Option<&str>,
+    folder: Option<&str>,
+    id: &str,
+) -> EngineResult<String> {
+    if let Some((_, mut imap)) = native(account).await? {
+        let folder = folder.unwrap_or(INBOX);
+        select(&mut imap, folder).await?;
+        let fetched = uid_fetch(&mut imap, uid(id)?, "BODY[]").await?;
+        logout(imap).await;
+        let raw = fetched
+            .iter()
+            .find_map(|f| f.body())
+            .ok_or_else(|| format!("No message {} in {}", id, folder))?;
+        return Ok(render_message(&String::from_utf8_lossy(raw)));
+    }
+    himalaya(&["message", "read"], account, folder, &[id], None).await
+}
+
+/// The account's folders as JSON.
+pub async fn list_folders(account: Option<&str>) -> EngineResult<String> {
+    if let Some((_, mut imap)) = native(account).await? {
+        let folders: Vec<Folder> = list(&mut imap)
+            .await?
+            .into_iter()
+            .filter(|(attrs, _)| {
+                !attrs.iter().any(|a| {
+                    a.eq_ignore_ascii_case("\\Noselect") || a.eq_ignore_ascii_case("\\NonExistent")
+                })
+            })
+            .map(|(attrs, name)| Folder {
+                name,
+                desc: attrs.join(", "),
+            })
+            .collect();
+        logout(imap).await;
+        return Ok(serde_json::to_string(&folders)?);
+    }
+    himalaya(
+        &["folder", "list"],
+        account,
+        None,
+        &["--output", "json"],
+        None,
+    )
+    .await
+}
+
+/// Move message `id` of `folder` to `target`.
+pub async fn move_message(
+    account: Option<&str>,
+    folder: Option<&str>,
+    id: &str,
+    target: &str,
+) -> EngineResult<()> {
+    if let Some((_, mut imap)) = native(account).await? {
+        native_move(&mut imap, folder.unwrap_or(INBOX), uid(id)?, target).await?;
+        logout(imap).await;
+        return Ok(());
+    }
+    himalaya(&["message", "move"], account, folder, &[id, target], None)
+        .await
+        .map(|_| ())
+}
+
+/// Move message `id` to the trash; expunge it when it's already there (or
+/// there is no trash).
+pub async fn delete_message(
+    account: Option<&str>,
+    folder: Option<&str>,
+    id: &str,
+) -> EngineResult<()> {
+    if let Some((_, mut imap)) = native(account).await? {
+        let (folder, uid) = (folder.unwrap_or(INBOX), uid(id)?);
+        match special_folder(&mut imap, "\\Trash", TRASH_NAMES).await? {
+            Some(trash) if !trash.eq_ignore_ascii_case(folder) => {
+                native_move(&mut imap, folder, uid, &trash).await?
+            }
+            _ => {
+                let uidplus = has_capability(&mut imap, "UIDPLUS").await?;
+                select(&mut imap, folder).await?;
+                expunge(&mut imap, uidplus, uid).await?;
+            }
+        }
+        logout(imap).await;
+        return Ok(());
+    }
+    himalaya(&["message", "delete"], account, folder, &[id], None)
+        .await
+        .map(|_| ())
+}
+
+/// Add or remove `flag` ("seen", "flagged", … or a keyword) on message `id`.
+pub async fn set_flag(
+    account: Option<&str>,
+    folder: Option<&str>,
+    id: &str,
+    flag: &str,
+    add: bool,
+) -> EngineResult<()> {
+    if let Some((_, mut imap)) = native(account).await? {
+        let flag = imap_flag(flag)?;
+        select(&mut imap, folder.unwrap_or(INBOX)).await?;
+        let query = format!("{}FLAGS.SILENT ({})", if add { "+" } else { "-" }, flag);
+        uid_store(&mut imap, uid(id)?, &query).await?;
+        logout(imap).await;
+        return Ok(());
+    }
+    let action = if add { "add" } else { "remove" };
+    himalaya(
+        &["flag", action],
+        account,
+        folder,
+        &[id, "--flag", flag],
+        None,
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Send `email`; with `reply_to_id` (a message in `folder`) it is threaded
+/// as a reply to that message. A copy goes to the Sent folder unless the
+/// provider files submitted mail itself.
+pub async fn send(
+    account: Option<&str>,
+    folder: Option<&str>,
+    mut email: OutgoingEmail,
+    reply_to_id: Option<&str>,
+) -> EngineResult<()> {
+    let Some((acct, mut imap)) = native(account).await? else {
+        return send_himalaya(account, folder, email, reply_to_id).await;
+    };
+    let Some(server) = acct.smtp.clone() else {
+        logout(imap).await;
+        return send_himalaya(account, folder, email, reply_to_id).await;
+    };
+    let smtp = smtp_transport(&acct, &server).await?;
+    match smtp.test_connection().await {
+        Ok(true) => {}
+        failed if himalaya_installed() => {
+            let reason = match failed {
+                Ok(_) => "no connection".to_string(),
+                Err(e) => e.to_string(),
+            };
+            warn!(
+                "[mail] SMTP login to {} failed ({}), using himalaya",
+                acct.name, reason
+            );
+            logout(imap).await;
+            return send_himalaya(account, folder, email, reply_to_id).await;
+        }
+        Ok(_) => return Err(format!("Could not connect to {}", server.host).into()),
+        Err(e) => return Err(smtp_err(e)),
+    }
+
+    if let Some(id) = reply_to_id {
+        select(&mut imap, folder.unwrap_or(INBOX)).await?;
+        let fetched = uid_fetch(&mut imap, uid(id)?, "BODY.PEEK[HEADER]").await?;
+        let headers = fetched
+            .iter()
+            .find_map(|f| f.header())
+            .map(|h| String::from_utf8_lossy(h).into_owned());
+        match headers
+            .as_deref()
+            .and_then(|h| header_value(h, "Message-ID"))
+        {
+            Some(parent) => email.thread_on(
+                &parent,
+                &headers
+                    .as_deref()
+                    .and_then(|h| header_value(h, "References"))
+                    .unwrap_or_default(),
+            ),
+            None => warn!("[mail] No Message-ID on {} to reply to", id),
+        }
+    }
+
+    let name = acct.display_name.as_deref().unwrap_or("");
+    let message = crlf(&email.to_smtp(name, &acct.email)?);
+    let envelope = smtp_envelope(&acct.email, &email.recipients())?;
+    smtp.send_raw(&envelope, message.as_bytes())
+        .await
+        .map_err(smtp_err)?;
+    info!("[mail] Sent \"{}\" from {}", email.subject, acct.name);
+    save_sent(&acct, &mut imap, &message).await;
+    logout(imap).await;
+    Ok(())
+}
+
+// ── Native helpers ─────────────────────────────────────────────────────
+
+fn imap_err(e: async_imap::error::Error) -> EngineError {
+    format!("IMAP error: {}", e).into()
+}
+
+fn smtp_err(e: lettre::transport::smtp::Error) -> EngineError {
+    format!("SMTP error: {}", e).into()
+}
+
+/// Log in to `account` (the first IMAP account when None); None means
+/// himalaya handles it (see the module comment).
+async fn native(account: Option<&str>) -> EngineResult<Option<(ImapAccount, Session)>> {
+    let mut accounts = mail_watch::load_accounts().into_iter();
+    let found = match account {
+        Some(name) => accounts.find(|a| a.name == name),
+        None => accounts.next(),
+    };
+    let Some(acct) = found else {
+        return Ok(None);
+    };
+    match login(&acct).await {
+        Ok(imap) => Ok(Some((acct, imap))),
+        Err(e) if himalaya_installed() => {
+            warn!(
+                "[mail] IMAP login to {} failed ({}), using himalaya",
+                acct.name, e
+            );
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// XOAUTH2 for async-imap: the token once, then an empty response to the
+/// error challenge a refusal comes with.
+struct XOAuth2(Option<String>);
+
+impl async_imap::Authenticator for XOAuth2 {
+    type Response = String;
+
+    fn process(&mut self, _challenge: &[u8]) -> String {
+        self.0.take().unwrap_or_default()
+    }
+}
+
+async fn login(account: &ImapAccount) -> EngineResult<Session> {
+    let (stream, greeted) = mail_watch::open_stream(account).await?;
+    let mut client = async_imap::Client::new(stream);
+    if !greeted {
+        client
+            .read_response()
+            .await
+            .map_err(|e| format!("IMAP greeting failed: {}", e))?
+            .ok_or("The server closed the connection")?;
+    }
+    let session = match account.oauth {
+        Some(provider) => {
+            let token = mail_oauth::access_token(&account.name, &account.email, provider).await?;
+            let auth = XOAuth2(Some(mail_oauth::xoauth2_payload(&account.login, &token)));
+            client.authenticate("XOAUTH2", auth).await
+        }
+        None => {
+            client
+                .login(&account.login, mail_watch::password(account)?)
+                .await
+        }
+    };
+    session.map_err(|(e, _)| imap_err(e))
+}
+
+/// Say goodbye; errors don't matter any more.
+async fn logout(mut imap: Session) {
+    let _ = imap.logout().await;
+}
+
+async fn native_envelopes(
+    imap: &mut Session,
+    folder: &str,
+    page_size: u32,
+) -> EngineResult<Vec<Envelope>> {
+    let exists = select(imap, folder).await?;
+    if exists == 0 || page_size == 0 {
+        return Ok(vec![]);
+    }
+    let first = exists.saturating_sub(page_size - 1).max(1);
+    let fetched: Vec<Fetch> = imap
+        .fetch(
+            format!("{}:{}", first, exists),
+            "(UID FLAGS BODY.PEEK[HEADER])",
+        )
+        .await
+        .map_err(imap_err)?
+        .try_collect()
+        .await
+        .map_err(imap_err)?;
+    let mut envelopes: Vec<(u32, Envelope)> = fetched
+        .iter()
+        .filter_map(|f| Some((f.message, parse_envelope(f)?)))
+        .collect();
+    envelopes.sort_by_key(|(seq, _)| std::cmp::Reverse(*seq));
+    Ok(envelopes.into_iter().map(|(_, e)| e).collect())
+}
+
+/// SELECT `folder`; returns its message count.
+async fn select(imap: &mut Session, folder: &str) -> EngineResult<u32> {
+    let mailbox = imap.select(folder_arg(folder)?).await.map_err(imap_err)?;
+    Ok(mailbox.exists)
+}
+
+async fn uid_fetch(imap: &mut Session, uid: u32, query: &str) -> EngineResult<Vec<Fetch>> {
+    imap.uid_fetch(uid.to_string(), query)
+        .await
+        .map_err(imap_err)?
+        .try_collect()
+        .await
+        .map_err(imap_err)
+}
+
+async fn uid_store(imap: &mut Session, uid: u32, query: &str) -> EngineResult<()> {
+    imap.uid_store(uid.to_string(), query)
+        .await
+        .map_err(imap_err)?
+        .try_collect::<Vec<Fetch>>()
+        .await
+        .map_err(imap_err)
+        .map(|_| ())
+}
+
+async fn has_capability(imap: &mut Session, capability: &str) -> EngineResult<bool> {
+    Ok(imap
+        .capabilities()
+        .await
+        .map_err(imap_err)?
+        .has_str(capability))
+}
+
+async fn native_move(imap: &mut Session, from: &str, uid: u32, to: &str) -> EngineResult<()> {
+    let can_move = has_capability(imap, "MOVE").await?;
+    let uidplus = has_capability(imap, "UIDPLUS").await?;
+    let target = folder_arg(to)?;
+    select(imap, from).await?;
+    if can_move {
+        return imap
+            .uid_mv(uid.to_string(), &target)
+            .await
+            .map_err(imap_err);
+    }
+    imap.uid_copy(uid.to_string(), &target)
+        .await
+        .map_err(imap_err)?;
+    expunge(imap, uidplus, uid).await
+}
+
+/// Delete message `uid` of the selected folder for good.
+async fn expunge(imap: &mut Session, uidplus: bool, uid: u32) -> EngineResult<()> {
+    uid_store(imap, uid, "+FLAGS.SILENT (\\Deleted)").await?;
+    // Without UIDPLUS, EXPUNGE also removes whatever else is marked deleted
+    let expunged = if uidplus {
+        imap.uid_expunge(uid.to_string())
+            .await
+            .map_err(imap_err)?
+            .try_collect::<Vec<u32>>()
+            .await
+    } else {
+        imap.expunge()
+            .await
+            .map_err(imap_err)?
+            .try_collect::<Vec<u32>>()
+            .await
+    };
+    expunged.map_err(imap_err).map(|_| ())
+}
+
+/// Attributes and (decoded) names of every folder.
+async fn list(imap: &mut Session) -> EngineResult<Vec<(Vec<String>, String)>> {
+    let names: Vec<Name> = imap
+        .list(Some(""), Some("*"))
+        .await
+        .map_err(imap_err)?
+        .try_collect()
+        .await
+        .map_err(imap_err)?;
+    Ok(names
+        .iter()
+        .map(|n| {
+            let attrs = n.attributes().iter().map(attribute_name).collect();
+            (attrs, decode_utf7(n.name()))
+        })
+        .collect())
+}
+
+/// The folder with special-use attribute `attr` (RFC 6154), else the first
+/// of `names` that exists.
+async fn special_folder(
+    imap: &mut Session,
+    attr: &str,
+    names: &[&str],
+) -> EngineResult<Option<String>> {
+    Ok(pick_special(&list(imap).await?, attr, names))
+}
+
+fn pick_special(listed: &[(Vec<String>, String)], attr: &str, names: &[&str]) -> Option<String> {
+    if let Some((_, name)) = listed
+        .iter()
+        .find(|(attrs, _)| attrs.iter().any(|a| a.eq_ignore_ascii_case(attr)))
+    {
+        return Some(name.clone());
+    }
+    names.iter().find_map(|wanted| {
+        listed
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(wanted))
+            .map(|(_, name)| name.clone())
+    })
+}
+
+/// Keep a copy of a sent message. Best effort: the mail is already out.
+async fn save_sent(account: &ImapAccount, imap: &mut Session, message: &str) {
+    // Gmail and Microsoft 365 file what goes through their SMTP already
+    let host = account.host.to_ascii_lowercase();
+    if [
+        "gmail.com",
+        "googlemail.com",
+        "office365.com",
+        "outlook.com",
+    ]
+    .iter()
+    .any(|h| host.ends_with(h))
+    {
+        return;
+    }
+    let saved = async {
+        let Some(sent) = special_folder(imap, "\\Sent", SENT_NAMES).await? else {
+            return Ok(false);
+        };
+        imap.append(folder_arg(&sent)?, Some("(\\Seen)"), None, message)
+            .await
+            .map_err(imap_err)?;
+        EngineResult::Ok(true)
+    };
+    match saved.await {
+        Ok(true) => {}
+        Ok(false) => info!(
+            "[mail] No Sent folder on {}, not keeping a copy",
+            account.name
+        ),
+        Err(e) => warn!("[mail] Saving the sent copy failed: {}", e),
+    }
+}
+
+/// lettre transport for `server`, logging in with the account's
+/// credentials (keychain password or OAuth) unless it has its own
+/// `auth.raw`.
+async fn smtp_transport(
+    account: &ImapAccount,
+    server: &SmtpServer,
+) -> EngineResult<AsyncSmtpTransport<Tokio1Executor>> {
+    let builder = match server.encryption.as_str() {
+        "none" => {
+            warn!(
+                "[mail] Connecting WITHOUT TLS to {}:{} — the password is sent in plaintext!",
+                server.host, server.port
+            );
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&server.host)
+        }
+        "start-tls" => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&server.host).map_err(smtp_err)?
+        }
+        _ => AsyncSmtpTransport::<Tokio1Executor>::relay(&server.host).map_err(smtp_err)?,
+    };
+    let (secret, mechanisms) = match (account.oauth, &server.raw_password) {
+        (_, Some(raw)) => (raw.clone(), vec![Mechanism::Plain, Mechanism::Login]),
+        (Some(provider), None) => (
+            mail_oauth::access_token(&account.name, &account.email, provider).await?,
+            vec![Mechanism::Xoauth2],
+        ),
+        (None, None) => (
+            mail_watch::password(account)?,
+            vec![Mechanism::Plain, Mechanism::Login],
+        ),
+    };
+    Ok(builder
+        .port(server.port)
+        // There's no hostname worth announcing
+        .hello_name(ClientId::Domain("localhost".into()))
+        .credentials(Credentials::new(server.login.clone(), secret))
+        .authentication(mechanisms)
+        .timeout(Some(SMTP_TIMEOUT))
+        .build())
+}
+
+fn smtp_envelope(from: &str, recipients: &[String]) -> EngineResult<SmtpEnvelope> {
+    if recipients.is_empty() {
+        return Err("The email has no recipients".into());
+    }
+    let parse = |addr: &str| {
+        addr.parse::<SmtpAddress>()
+            .map_err(|e| EngineError::from(format!("Invalid address '{}': {}", addr, e)))
+    };
+    let to = recipients
+        .iter()
+        .map(|r| parse(r))
+        .collect::<EngineResult<Vec<_>>>()?;
+    SmtpEnvelope::new(Some(parse(from)?), to).map_err(|e| format!("Invalid envelope: {}", e).into())
+}
+
+/// The message with CRLF line ends, as SMTP and IMAP APPEND expect.
+fn crlf(message: &str) -> String {
+    let mut out = String::with_capacity(message.len() + 64);
+    for line in message.lines() {
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+// ── himalaya fallback ──────────────────────────────────────────────────
+
+fn himalaya_installed() -> bool {
+    static INSTALLED: OnceLock<bool> = OnceLock::new();
+    *INSTALLED.get_or_init(|| {
+        std::process::Command::new("himalaya")
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    })
+}
+
+/// Run `himalaya <subcommand> [--account …] [--folder …] <args>`; returns
+/// its stdout.
+async fn himalaya(
+    subcommand: &[&str],
+    account: Option<&str>,
+    folder: Option<&str>,
+    args: &[&str],
+    stdin: Option<&str>,
+) -> EngineResult<String> {
+    let mut cmd = tokio::process::Command::new("himalaya");
+    cmd.args(subcommand);
+    if let Some(acct) = account {
+        cmd.arg("--account").arg(acct);
+    }
+    if let Some(f) = folder {
+        cmd.arg("--folder").arg(f);
+    }
+    cmd.args(args)
+        .stdin(if stdin.is_some() {
+            std::process::Stdio::piped()
+        } else {
+            std::process::Stdio::null()
+        })
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run himalaya: {}", e))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to wait: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("himalaya failed: {}", stderr).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn send_himalaya(
+    account: Option<&str>,
+    folder: Option<&str>,
+    mut email: OutgoingEmail,
+    reply_to_id: Option<&str>,
+) -> EngineResult<()> {
+    if let Some(id) = reply_to_id {
+        // himalaya's own reply template carries the thread headers
+        let template = himalaya(&["template", "reply"], account, folder, &[id], None).await?;
+        match header_value(&template, "In-Reply-To") {
+            Some(parent) => email.thread_on(
+                &parent,
+                &header_value(&template, "References").unwrap_or_default(),
+            ),
+            None => warn!("[mail] No In-Reply-To in reply template for {}", id),
+        }
+    }
+    let mml = email.to_mml();
+    match himalaya(&["template", "send"], account, None, &[], Some(&mml)).await {
+        // Sent, but there's no Sent folder to save a copy to
+        Err(e) if e.to_string().contains("Folder doesn't exist") => Ok(()),
+        result => result.map(|_| ()),
+    }
+}
+
+// ── Parsing ────────────────────────────────────────────────────────────
+
+fn uid(id: &str) -> EngineResult<u32> {
+    id.trim()
+        .parse()
+        .map_err(|_| format!("Invalid message id '{}'", id).into())
+}
+
+/// A folder name as an IMAP mailbox argument (modified UTF-7); async-imap
+/// quotes it.
+fn folder_arg(folder: &str) -> EngineResult<String> {
+    if folder.is_empty() || folder.chars().any(char::is_control) {
+        return Err(format!("Invalid folder name '{}'", folder.escape_debug()).into());
+    }
+    Ok(encode_utf7(folder))
+}
+
+/// The IMAP form of a himalaya flag name; other names are keywords.
+fn imap_flag(flag: &str) -> EngineResult<String> {
+    let flag = flag.trim();
+    let system = match flag.to_ascii_lowercase().as_str() {
+        "seen" => "\\Seen",
+        "answered" => "\\Answered",
+        "flagged" => "\\Flagged",
+        "deleted" => "\\Deleted",
+        "draft" => "\\Draft",
+        _ => "",
+    };
+    if !system.is_empty() {
+        return Ok(system.to_string());
+    }
+    if flag.is_empty()
+        || !flag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '$' | '-' | '_' | '.'))
+    {
+        return Err(format!("Invalid flag '{}'", flag).into());
+    }
+    Ok(flag.to_string())
+}
+
+/// A fetched flag without the backslash, as himalaya names it.
+fn flag_name(flag: &Flag) -> String {
+    match flag {
+        Flag::Seen => "Seen".into(),
+        Flag::Answered => "Answered".into(),
+        Flag::Flagged => "Flagged".into(),
+        Flag::Deleted => "Deleted".into(),
+        Flag::Draft => "Draft".into(),
+        Flag::Recent => "Recent".into(),
+        Flag::MayCreate => "*".into(),
+        Flag::Custom(name) => name.trim_start_matches('\\').to_string(),
+    }
+}
+
+/// A LIST attribute as the server sent it, e.g. "\HasNoChildren".
+fn attribute_name(attr: &NameAttribute) -> String {
+    match attr {
+        NameAttribute::Extension(name) => format!("\\{}", name.trim_start_matches('\\')),
+        other => format!("\\{:?}", other),
+    }
+}
+
+/// The envelope of a `(UID FLAGS BODY.PEEK[HEADER])` fetch; None for
+/// flags-only updates.
+fn parse_envelope(fetch: &Fetch) -> Option<Envelope> {
+    let headers = String::from_utf8_lossy(fetch.header()?);
+    let flags = fetch.flags().map(|f| flag_name(&f)).collect();
+    Some(envelope(fetch.uid?, flags, &headers))
+}
+
+fn envelope(uid: u32, flags: Vec<String>, headers: &str) -> Envelope {
+    let address = |name: &str| {
+        let value = header_value(headers, name).unwrap_or_default();
+        let first = address_list(&value).into_iter().next().unwrap_or_default();
+        let (name, addr) = mailbox(&decode_words(&first));
+        Address {
+            name: (!name.is_empty()).then_some(name),
+            addr,
+        }
+    };
+    let date = header_value(headers, "Date").unwrap_or_default();
+    Envelope {
+        id: uid.to_string(),
+        flags,
+        subject: decode_words(&header_value(headers, "Subject").unwrap_or_default()),
+        from: address("From"),
+        to: address("To"),
+        date: parse_date(&date).unwrap_or(date),
+    }
+}
+
+/// An RFC 5322 date as RFC 3339, ignoring a trailing comment like "(UTC)".
+fn parse_date(date: &str) -> Option<String> {
+    let date = date.split(" (").next()?.trim();
+    chrono::DateTime::parse_from_rfc2822(date)
+        .ok()
+        .map(|d| d.to_rfc3339())
+}
+
+/// Main headers, a blank line, then the plain-text body.
+fn render_message(raw: &str) -> String {
+    let mut out = String::new();
+    for name in ["From", "To", "Cc", "Subject", "Date"] {
+        if let Some(value) = header_value(raw, name) {
+            out.push_str(&format!("{}: {}\n", name, decode_words(&value)));
+        }
+    }
+    out.push('\n');
+    out.push_str(body_text(raw).trim_end());
+    out
+}
+
+/// RFC 3501 §5.1.3 modified UTF-7 → text.
+fn decode_utf7(name: &str) -> String {
+    let mut out = String::new();
+    let mut rest = name;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let Some(dash) = after.find('-') else {
+            out.push_str(&rest[amp..]);
+            return out;
+        };
+        let chunk = &after[..dash];
+        if chunk.is_empty() {
+            out.push('&');
+        } else {
+            match STANDARD_NO_PAD.decode(chunk.replace(',', "/")) {
+                Ok(bytes) => {
+                    let units: Vec<u16> = bytes
+                        .chunks_exact(2)
+                        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                        .collect();
+                    out.push_str(&String::from_utf16_lossy(&units));
+                }
+                Err(_) => out.push_str(&rest[amp..amp + dash + 2]),
+            }
+        }
+        rest = &after[dash + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Text → RFC 3501 §5.1.3 modified UTF-7.
+fn encode_utf7(name: &str) -> String {
+    fn flush(out: &mut String, pending: &mut Vec<u16>) {
+        if pending.is_empty() {
+            return;
+        }
+        let bytes: Vec<u8> = pending.iter().flat_map(|u| u.to_be_bytes()).collect();
+        out.push('&');
+        out.push_str(&STANDARD_NO_PAD.encode(bytes).replace('/', ","));
+        out.push('-');
+        pending.clear();
+    }
+
+    let mut out = String::new();
+    let mut pending = Vec::new();
+    for c in name.chars() {
+        if (' '..='~').contains(&c) {
+            flush(&mut out, &mut pending);
+            match c {
+                '&' => out.push_str("&-"),
+                c => out.push(c),
+            }
+        } else {
+            pending.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+        }
+    }
+    flush(&mut out, &mut pending);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelopes_are_parsed_from_headers() {
+        let envelope = envelope(
+            4391,
+            vec![flag_name(&Flag::Seen), flag_name(&Flag::Answered)],
+            "From: =?UTF-8?B?Sm9zw6k=?= <Jose@Example.com>\r\n\
+             To: \"Doe, Ana\" <ana@x.com>, bo@x.com\r\n\
+             Subject: Q3\r\n\
+             Date: Tue, 13 Oct 2026 09:05:00 +0200 (CEST)\r\n\r\n",
+        );
+        assert_eq!(envelope.id, "4391");
+        assert_eq!(envelope.flags, vec!["Seen", "Answered"]);
+        assert_eq!(envelope.from.name.as_deref(), Some("José"));
+        assert_eq!(envelope.from.addr, "jose@example.com");
+        assert_eq!(envelope.to.name.as_deref(), Some("Doe, Ana"));
+        assert_eq!(envelope.date, "2026-10-13T09:05:00+02:00");
+        assert_eq!(flag_name(&Flag::Custom("$Label1".into())), "$Label1");
+    }
+
+    #[test]
+    fn special_folders_are_found_by_attribute_then_name() {
+        let listed = vec![
+            (vec!["\\HasNoChildren".to_string()], "INBOX".to_string()),
+            (vec!["\\Sent".to_string()], "Éléments envoyés".to_string()),
+            (vec![], "Deleted Items".to_string()),
+        ];
+        assert_eq!(
+            pick_special(&listed, "\\sent", SENT_NAMES).as_deref(),
+            Some("Éléments envoyés")
+        );
+        assert_eq!(
+            pick_special(&listed, "\\Trash", TRASH_NAMES).as_deref(),
+            Some("Deleted Items")
+        );
+        assert_eq!(pick_special(&listed[..1], "\\Trash", TRASH_NAMES), None);
+        assert_eq!(
+            attribute_name(&NameAttribute::Extension("Sent".into())),
+            "\\Sent"
+        );
+    }
+
+    #[test]
+    fn folder_names_and_flags_are_encoded() {
+        for name in ["INBOX", "Éléments envoyés", "Tom & Jerry", "日本語/メール"] {
+            assert_eq!(decode_utf7(&encode_utf7(name)), name);
+        }
+        assert_eq!(encode_utf7("Tom & Jerry"), "Tom &- Jerry");
+        assert_eq!(
+            decode_utf7("&AMk-l&AOk-ments envoy&AOk-s"),
+            "Éléments envoyés"
+        );
+        assert_eq!(folder_arg("Drafts").unwrap(), "Drafts");
+        assert!(folder_arg("x\r\nA1 LOGOUT").is_err());
+
+        assert_eq!(imap_flag("Seen").unwrap(), "\\Seen");
+        assert_eq!(imap_flag("$Label1").unwrap(), "$Label1");
+        assert!(imap_flag("x) UID EXPUNGE (").is_err());
+        assert!(uid("12 3").is_err());
+    }
+
+    #[test]
+    fn messages_render_headers_then_text() {
+        let raw = "From: Ana <ana@x.com>\r\nSubject: =?UTF-8?Q?Caf=C3=A9?=\r\nX-Spam: no\r\n\r\nSee you there.\r\n";
+        assert_eq!(
+            render_message(raw),
+            "From: Ana <ana@x.com>\nSubject: Café\n\nSee you there."
+        );
+    }
+
+    #[test]
+    fn submitted_mail_has_crlf_line_ends() {
+        assert_eq!(crlf("A: b\r\n\r\none\ntwo\n"), "A: b\r\n\r\none\r\ntwo\r\n");
+        assert!(smtp_envelope("me@x.com", &[]).is_err());
+        assert!(smtp_envelope("me@x.com", &["you@y.org".into()]).is_ok());
+    }
+}
@@ -0,0 +1,231 @@
+// Paw Agent Engine — OAuth2 (XOAUTH2) for IMAP/SMTP mail accounts
+//
+// Gmail and Outlook are retiring password logins for IMAP/SMTP. For those
+// accounts the Mail setup runs the OAuth PKCE flow with the provider's mail
+// scopes and keeps only the refresh token — in the OS keychain, under the
+// same `paw-mail-{account}` entry a password would use.
+//
+// Access tokens are minted on demand:
+//   - himalaya runs `<paw> --mail-access-token <account>` (its
+//     `auth.access-token.cmd`), which prints a fresh token and exits
+//   - the IDLE watcher (mail_watch) mints one in-process per connection
+// Microsoft rotates refresh tokens; the new one replaces the stored one.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::mail_watch;
+use crate::engine::oauth::{self, get_oauth_config, OAuthConfig};
+use base64::Engine as _;
+use log::info;
+
+/// Command-line flag himalaya calls the app with to get an access token.
+pub const TOKEN_FLAG: &str = "--mail-access-token";
+
+/// A mail provider that supports XOAUTH2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailProvider {
+    Google,
+    Microsoft,
+}
+
+impl MailProvider {
+    pub const ALL: [MailProvider; 2] = [MailProvider::Google, MailProvider::Microsoft];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "google" | "gmail" => Some(MailProvider::Google),
+            "microsoft" | "outlook" => Some(MailProvider::Microsoft),
+            _ => None,
+        }
+    }
+
+    /// The OAuth service id in `oauth::get_oauth_config`.
+    pub fn service_id(self) -> &'static str {
+        match self {
+            MailProvider::Google => "google",
+            MailProvider::Microsoft => "microsoft",
+        }
+    }
+
+    fn config(self) -> &'static OAuthConfig {
+        get_oauth_config(self.service_id()).expect("mail providers have a shipped OAuth config")
+    }
+
+    /// Scopes granting IMAP + SMTP access.
+    pub fn scopes(self) -> &'static [&'static str] {
+        match self {
+            MailProvider::Google => &["https://mail.google.com/"],
+            MailProvider::Microsoft => &[
+                "offline_access",
+                "https://outlook.office.com/IMAP.AccessAsUser.All",
+                "https://outlook.office.com/SMTP.Send",
+            ],
+        }
+    }
+
+    /// The provider whose token endpoint is `token_url` (as written to the
+    /// himalaya config by `himalaya_auth`).
+    pub fn from_token_url(token_url: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.config().token_url == token_url)
+    }
+}
+
+// ── Keychain ───────────────────────────────────────────────────────────
+
+fn keychain_entry(account: &str, email: &str) -> EngineResult<keyring::Entry> {
+    keyring::Entry::new(&format!("paw-mail-{}", account), email)
+        .map_err(|e| format!("Keyring init failed: {}", e).into())
+}
+
+/// Keep an account's refresh token in the OS keychain.
+pub fn store_refresh_token(account: &str, email: &str, refresh_token: &str) -> EngineResult<()> {
+    keychain_entry(account, email)?
+        .set_password(refresh_token)
+        .map_err(|e| format!("Failed to store token in keychain: {}", e))?;
+    Ok(())
+}
+
+fn refresh_token(account: &str, email: &str) -> EngineResult<String> {
+    keychain_entry(account, email)?.get_password().map_err(|e| {
+        format!(
+            "No OAuth token in the keychain for {} — sign in again: {}",
+            email, e
+        )
+        .into()
+    })
+}
+
+// ── Tokens ─────────────────────────────────────────────────────────────
+
+/// Mint an access token for a mail account from its stored refresh token.
+pub async fn access_token(
+    account: &str,
+    email: &str,
+    provider: MailProvider,
+) -> EngineResult<String> {
+    let stored = refresh_token(account, email)?;
+    let tokens =
+        oauth::refresh_access_token_with_scopes(provider.service_id(), &stored, provider.scopes())
+            .await?;
+    if let Some(rotated) = tokens.refresh_token.as_deref().filter(|t| *t != stored) {
+        store_refresh_token(account, email, rotated)?;
+        info!(
+            "[mail-oauth] Stored rotated refresh token for '{}'",
+            account
+        );
+    }
+    Ok(tokens.access_token)
+}
+
+/// SASL XOAUTH2 initial client response.
+pub fn xoauth2(user: &str, access_token: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(xoauth2_payload(user, access_token))
+}
+
+/// The XOAUTH2 response before base64, for clients that encode it
+/// themselves (async-imap).
+pub fn xoauth2_payload(user: &str, access_token: &str) -> String {
+    format!("user={}\x01auth=Bearer {}\x01\x01", user, access_token)
+}
+
+// ── himalaya ───────────────────────────────────────────────────────────
+
+/// The `auth.*` lines of a himalaya backend using XOAUTH2, without the
+/// backend prefix. The access token comes from `TOKEN_FLAG`.
+pub fn himalaya_auth(provider: MailProvider, account: &str) -> EngineResult<String> {
+    let exe =
+        std::env::current_exe().map_err(|e| format!("Cannot locate the app executable: {}", e))?;
+    let command = format!("\"{}\" {} \"{}\"", exe.display(), TOKEN_FLAG, account);
+    let config = provider.config();
+    let scopes: Vec<toml::Value> = provider.scopes().iter().map(|&s| s.into()).collect();
+    Ok(format!(
+        "auth.type = \"oauth2\"
+auth.method = \"xoauth2\"
+auth.client-id = {client_id}
+auth.auth-url = {auth_url}
+auth.token-url = {token_url}
+auth.pkce = true
+auth.scopes = {scopes}
+auth.access-token.cmd = {command}",
+        client_id = toml::Value::from(config.effective_client_id()),
+        auth_url = toml::Value::from(config.auth_url),
+        token_url = toml::Value::from(config.token_url),
+        scopes = toml::Value::from(scopes),
+        command = toml::Value::from(command),
+    ))
+}
+
+/// Answer `<paw> --mail-access-token <account>`: print an access token for
+/// himalaya and exit. Returns when the flag isn't on the command line.
+pub fn run_cli() {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(pos) = args.iter().position(|a| a == TOKEN_FLAG) else {
+        return;
+    };
+    match mint_for_cli(args.get(pos + 1).map(String::as_str)) {
+        Ok(token) => {
+            println!("{}", token);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn mint_for_cli(name: Option<&str>) -> EngineResult<String> {
+    let name = name.ok_or_else(|| format!("Usage: {} <account>", TOKEN_FLAG))?;
+    let account = mail_watch::load_accounts()
+        .into_iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("No mail account '{}' in the himalaya config", name))?;
+    let provider = account
+        .oauth
+        .ok_or_else(|| format!("Mail account '{}' doesn't use OAuth", name))?;
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start runtime: {}", e))?
+        .block_on(access_token(&account.name, &account.email, provider))
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xoauth2_initial_response() {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(xoauth2("me@example.com", "ya29.token"))
+            .unwrap();
+        assert_eq!(
+            decoded,
+            b"user=me@example.com\x01auth=Bearer ya29.token\x01\x01"
+        );
+    }
+
+    #[test]
+    fn himalaya_auth_is_detected_by_the_watcher() {
+        for provider in MailProvider::ALL {
+            let auth = himalaya_auth(provider, "work").unwrap();
+            let backend: String = auth.lines().map(|l| format!("backend.{}\n", l)).collect();
+            let config = format!(
+                "[accounts.work]\nemail = \"me@example.com\"\nbackend.type = \"imap\"\n\
+                 backend.host = \"imap.example.com\"\n{}",
+                backend
+            );
+            let accounts = mail_watch::himalaya_accounts(&config);
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(accounts[0].oauth, Some(provider));
+        }
+        assert_eq!(
+            MailProvider::parse("outlook"),
+            Some(MailProvider::Microsoft)
+        );
+        assert_eq!(MailProvider::parse("fastmail"), None);
+    }
+}
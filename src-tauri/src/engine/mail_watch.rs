@@ -0,0 +1,833 @@
+// Paw Agent Engine — New-mail push (IMAP IDLE)
+//
+// `fetch_emails` only polls when the Mail view asks. With
+// MailWatchConfig.enabled, one watcher per (account, folder) keeps an IMAP
+// connection open in IDLE and, when the server reports new messages:
+//   - emits `mail-new` (account, folder, count and the new messages'
+//     From / Subject / Date) so the UI can refresh
+//   - notifies the user through engine::notifications (category "mail")
+//   - dispatches a `new_mail` event, so tasks with the event trigger
+//     {"type": "new_mail"} (e.g. a triage agent) wake up
+//
+// Accounts come from the himalaya config written by the Mail setup, with
+// the password from the OS keychain (or `backend.auth.raw`); Gmail/Outlook
+// accounts set up with OAuth log in with XOAUTH2 (see mail_oauth). Servers
+// without IDLE are polled with NOOP instead.
+//
+// A supervisor reconciles the running watchers with the config every
+// RECONCILE_INTERVAL, so toggling the setting or adding an account needs no
+// restart. A dropped connection is retried with exponential backoff.
+//
+// The IMAP client below is shared with the email bridge (engine::email_bridge)
+// and the account test (engine::mail_check); the Mail view's native client
+// (engine::mail_client) uses async-imap over the same connection setup
+// (open_stream).
+
+use crate::atoms::error::EngineResult;
+use crate::engine::email::header_value;
+use crate::engine::events::{self, EngineEvent};
+use crate::engine::mail_oauth::{self, MailProvider};
+use crate::engine::notifications::{self, Notice};
+use crate::engine::state::EngineState;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+    ReadHalf, WriteHalf,
+};
+use tokio::net::TcpStream;
+
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+/// RFC 2177: re-issue IDLE before the server's 30-minute timeout.
+pub(crate) const IDLE_RENEW: Duration = Duration::from_secs(25 * 60);
+/// NOOP polling interval for servers without IDLE.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(120);
+/// Timeout of every command outside IDLE.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// A connection that lived this long resets the backoff.
+const STABLE_SESSION: Duration = Duration::from_secs(300);
+/// New messages whose headers are fetched per notification.
+const MAX_NOTIFIED: u32 = 10;
+/// Largest literal (message body, header block) read into memory.
+const MAX_LITERAL: usize = 64 * 1024 * 1024;
+
+/// A running watcher and the account settings it was started with.
+type Watcher = (ImapAccount, tauri::async_runtime::JoinHandle<()>);
+
+/// Running watchers by "account/folder".
+static WATCHERS: LazyLock<Mutex<HashMap<String, Watcher>>> = LazyLock::new(Default::default);
+
+// ── Accounts ───────────────────────────────────────────────────────────
+
+/// An IMAP account from the himalaya config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImapAccount {
+    pub name: String,
+    pub email: String,
+    /// `display-name`, for the From header of sent mail
+    pub display_name: Option<String>,
+    pub host: String,
+    pub port: u16,
+    /// "tls", "start-tls" or "none"
+    pub encryption: String,
+    pub login: String,
+    /// `backend.auth.raw`; otherwise the password is in the keychain
+    pub raw_password: Option<String>,
+    /// Set for `backend.auth.type = "oauth2"` accounts written by the Mail
+    /// setup; the keychain then holds the refresh token.
+    pub oauth: Option<MailProvider>,
+    /// `message.send.backend` when it is SMTP
+    pub smtp: Option<SmtpServer>,
+}
+
+/// The SMTP server of an account. It logs in with the account's
+/// credentials (keychain password or OAuth) unless it has its own
+/// `auth.raw`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmtpServer {
+    pub host: String,
+    pub port: u16,
+    /// "tls", "start-tls" or "none"
+    pub encryption: String,
+    pub login: String,
+    pub raw_password: Option<String>,
+}
+
+/// The IMAP accounts of a himalaya config.toml.
+pub fn himalaya_accounts(config: &str) -> Vec<ImapAccount> {
+    let Ok(table) = config.parse::<toml::Table>() else {
+        return vec![];
+    };
+    let Some(accounts) = table.get("accounts").and_then(|a| a.as_table()) else {
+        return vec![];
+    };
+    accounts
+        .iter()
+        .filter_map(|(name, acct)| {
+            let backend = acct.get("backend")?;
+            if backend.get("type")?.as_str()? != "imap" {
+                return None;
+            }
+            let email = acct.get("email")?.as_str()?.to_string();
+            let str_of = |key: &str| backend.get(key).and_then(|v| v.as_str());
+            let auth = backend.get("auth");
+            let auth_of = |key: &str| auth.and_then(|a| a.get(key)).and_then(|v| v.as_str());
+            let oauth = match auth_of("type") {
+                // Only providers whose tokens we can mint
+                Some("oauth2") => Some(MailProvider::from_token_url(auth_of("token-url")?)?),
+                _ => None,
+            };
+            let smtp = acct
+                .get("message")
+                .and_then(|m| m.get("send"))
+                .and_then(|s| s.get("backend"))
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("smtp"))
+                .and_then(|b| {
+                    let str_of = |key: &str| b.get(key).and_then(|v| v.as_str());
+                    Some(SmtpServer {
+                        host: str_of("host")?.to_string(),
+                        port: b
+                            .get("port")
+                            .and_then(|p| p.as_integer())
+                            .and_then(|p| u16::try_from(p).ok())
+                            .unwrap_or(465),
+                        encryption: str_of("encryption").unwrap_or("tls").to_string(),
+                        login: str_of("login").unwrap_or(&email).to_string(),
+                        raw_password: b
+                            .get("auth")
+                            .and_then(|a| a.get("raw"))
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string),
+                    })
+                });
+            Some(ImapAccount {
+                name: name.clone(),
+                display_name: acct
+                    .get("display-name")
+                    .and_then(|d| d.as_str())
+                    .map(str::to_string),
+                host: str_of("host")?.to_string(),
+                port: backend
+                    .get("port")
+                    .and_then(|p| p.as_integer())
+                    .and_then(|p| u16::try_from(p).ok())
+                    .unwrap_or(993),
+                encryption: str_of("encryption").unwrap_or("tls").to_string(),
+                login: str_of("login").unwrap_or(&email).to_string(),
+                raw_password: auth_of("raw").map(str::to_string),
+                oauth,
+                smtp,
+                email,
+            })
+        })
+        .collect()
+}
+
+pub fn load_accounts() -> Vec<ImapAccount> {
+    let Some(home) = dirs::home_dir() else {
+        return vec![];
+    };
+    std::fs::read_to_string(home.join(".config/himalaya/config.toml"))
+        .map(|c| himalaya_accounts(&c))
+        .unwrap_or_default()
+}
+
+pub(crate) fn password(account: &ImapAccount) -> EngineResult<String> {
+    if let Some(raw) = &account.raw_password {
+        return Ok(raw.clone());
+    }
+    // Stored by `write_himalaya_config`
+    let entry = keyring::Entry::new(&format!("paw-mail-{}", account.name), &account.email)
+        .map_err(|e| format!("Keyring init failed: {}", e))?;
+    entry
+        .get_password()
+        .map_err(|e| format!("No password in the keychain for {}: {}", account.email, e).into())
+}
+
+// ── Supervisor ─────────────────────────────────────────────────────────
+
+/// Start the supervisor. Idle (no connections) while the setting is off.
+pub fn spawn_supervisor(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            reconcile(&app_handle);
+            tokio::time::sleep(RECONCILE_INTERVAL).await;
+        }
+    });
+    info!("[mail-watch] Supervisor started");
+}
+
+fn reconcile(app_handle: &tauri::AppHandle) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let config = state.config.lock().mail_watch.clone();
+    let mut wanted: HashMap<String, (ImapAccount, String)> = HashMap::new();
+    if config.enabled {
+        for account in load_accounts() {
+            for folder in config.folders_of(&account.name) {
+                let key = format!("{}/{}", account.name, folder);
+                wanted.insert(key, (account.clone(), folder.clone()));
+            }
+        }
+    }
+
+    let mut watchers = WATCHERS.lock();
+    // Watchers of removed folders, or of accounts whose settings changed
+    watchers.retain(|key, (account, handle)| {
+        let keep = wanted.get(key).is_some_and(|(a, _)| a == account);
+        if !keep {
+            info!("[mail-watch] Stopping {}", key);
+            handle.abort();
+        }
+        keep
+    });
+    for (key, (account, folder)) in wanted {
+        if watchers.contains_key(&key) {
+            continue;
+        }
+        info!("[mail-watch] Watching {}", key);
+        let app = app_handle.clone();
+        let started = account.clone();
+        let handle =
+            tauri::async_runtime::spawn(async move { watch_folder(app, account, folder).await });
+        watchers.insert(key, (started, handle));
+    }
+}
+
+// ── Watcher ────────────────────────────────────────────────────────────
+
+/// Reconnect delays: 5s doubling up to 5 minutes.
+#[derive(Debug, Default)]
+struct Backoff {
+    failures: u32,
+}
+
+impl Backoff {
+    fn next_delay(&mut self) -> Duration {
+        let secs = 5u64.saturating_mul(1 << self.failures.min(6)).min(300);
+        self.failures += 1;
+        Duration::from_secs(secs)
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+async fn watch_folder(app_handle: tauri::AppHandle, account: ImapAccount, folder: String) {
+    let mut backoff = Backoff::default();
+    loop {
+        let started = Instant::now();
+        let result = watch_session(&app_handle, &account, &folder).await;
+        if started.elapsed() >= STABLE_SESSION {
+            backoff.reset();
+        }
+        let delay = backoff.next_delay();
+        match result {
+            Ok(()) => info!(
+                "[mail-watch] {}/{}: server closed the connection, reconnecting in {}s",
+                account.name,
+                folder,
+                delay.as_secs()
+            ),
+            Err(e) => warn!(
+                "[mail-watch] {}/{}: {} — retrying in {}s",
+                account.name,
+                folder,
+                e,
+                delay.as_secs()
+            ),
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// One connection: log in, select the folder and wait for new mail until
+/// the connection drops.
+async fn watch_session(
+    app_handle: &tauri::AppHandle,
+    account: &ImapAccount,
+    folder: &str,
+) -> EngineResult<()> {
+    let mut imap = Imap::login(account).await?;
+    let idle = imap.supports_idle().await?;
+
+    let mut exists = 0;
+    for r in imap.command(&format!("SELECT {}", quote(folder))).await? {
+        track_exists(&mut exists, &r.line);
+    }
+    info!(
+        "[mail-watch] {}/{}: {} messages, {}",
+        account.name,
+        folder,
+        exists,
+        if idle { "idling" } else { "polling" }
+    );
+
+    loop {
+        let before = exists;
+        let responses = if idle {
+            imap.idle(IDLE_RENEW).await?
+        } else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            imap.command("NOOP").await?
+        };
+        for r in &responses {
+            if r.line.starts_with("* BYE") {
+                return Ok(());
+            }
+            track_exists(&mut exists, &r.line);
+        }
+        if exists > before {
+            notify(app_handle, &mut imap, account, folder, before, exists).await?;
+        }
+    }
+}
+
+/// Emit `mail-new`, notify and dispatch the `new_mail` event for messages
+/// `before + 1 ..= after`.
+async fn notify(
+    app_handle: &tauri::AppHandle,
+    imap: &mut Imap,
+    account: &ImapAccount,
+    folder: &str,
+    before: u32,
+    after: u32,
+) -> EngineResult<()> {
+    let first = (before + 1).max(after.saturating_sub(MAX_NOTIFIED - 1));
+    let fetched = imap
+        .command(&format!(
+            "FETCH {}:{} (BODY.PEEK[HEADER.FIELDS (FROM SUBJECT DATE)])",
+            first, after
+        ))
+        .await?;
+    let messages: Vec<serde_json::Value> = fetched
+        .iter()
+        .filter_map(|r| {
+            let headers = r.literal.as_deref()?;
+            Some(json!({
+                "seq": fetch_seq(&r.line)?,
+                "from": header_value(headers, "From").unwrap_or_default(),
+                "subject": header_value(headers, "Subject").unwrap_or_default(),
+                "date": header_value(headers, "Date").unwrap_or_default(),
+            }))
+        })
+        .collect();
+    let count = after - before;
+    info!(
+        "[mail-watch] {}/{}: {} new message(s)",
+        account.name, folder, count
+    );
+
+    let latest = messages
+        .last()
+        .map(|m| {
+            format!(
+                "{}: {}",
+                m["from"].as_str().unwrap_or(""),
+                m["subject"].as_str().unwrap_or("")
+            )
+        })
+        .unwrap_or_default();
+    let _ = app_handle.emit(
+        "mail-new",
+        json!({
+            "account": &account.name,
+            "folder": folder,
+            "count": count,
+            "messages": messages,
+        }),
+    );
+    let title = format!(
+        "{} new email{} in {}/{}",
+        count,
+        if count == 1 { "" } else { "s" },
+        account.name,
+        folder
+    );
+    notifications::notify(
+        app_handle,
+        Notice::new(notifications::MAIL, title, latest.clone()).navigate_to("mail"),
+    );
+    events::dispatch_event(
+        app_handle,
+        &EngineEvent::NewMail {
+            account: account.name.clone(),
+            folder: folder.to_string(),
+            count,
+            latest,
+        },
+    )
+    .await;
+    Ok(())
+}
+
+/// Follow the message count through untagged EXISTS / EXPUNGE responses.
+pub(crate) fn track_exists(exists: &mut u32, line: &str) {
+    let mut parts = line.split_whitespace();
+    if parts.next() != Some("*") {
+        return;
+    }
+    let (Some(n), Some(kind)) = (
+        parts.next().and_then(|n| n.parse::<u32>().ok()),
+        parts.next(),
+    ) else {
+        return;
+    };
+    if kind.eq_ignore_ascii_case("EXISTS") {
+        *exists = n;
+    } else if kind.eq_ignore_ascii_case("EXPUNGE") {
+        *exists = exists.saturating_sub(1);
+    }
+}
+
+/// Sequence number of a `* N FETCH ...` response.
+pub(crate) fn fetch_seq(line: &str) -> Option<u32> {
+    let mut parts = line.split_whitespace();
+    (parts.next()? == "*").then_some(())?;
+    let n = parts.next()?.parse().ok()?;
+    parts.next()?.eq_ignore_ascii_case("FETCH").then_some(n)
+}
+
+/// An IMAP quoted string.
+pub(crate) fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Size of the literal announced at the end of a line (`... {123}`).
+fn literal_len(line: &str) -> Option<usize> {
+    let open = line.strip_suffix('}')?.rfind('{')?;
+    line[open + 1..line.len() - 1].parse().ok()
+}
+
+/// A response line with its literals read in; the line keeps the `{n}`
+/// markers and everything around them. Literals above MAX_LITERAL are
+/// refused rather than buffered.
+async fn read_response<R: AsyncBufRead + Unpin>(reader: &mut R) -> EngineResult<Response> {
+    async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> EngineResult<String> {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| format!("Read failed: {}", e))?
+            == 0
+        {
+            return Err("Connection closed".into());
+        }
+        Ok(line.trim_end().to_string())
+    }
+
+    let mut line = read_line(reader).await?;
+    let mut literal = None;
+    // Each part after a literal may end in another one, e.g. a FETCH of
+    // several body sections
+    let mut next = literal_len(&line);
+    while let Some(len) = next {
+        if len > MAX_LITERAL {
+            return Err(format!(
+                "The server sent a {} byte literal (the limit is {} bytes)",
+                len, MAX_LITERAL
+            )
+            .into());
+        }
+        let mut buf = vec![0u8; len];
+        reader
+            .read_exact(&mut buf)
+            .await
+            .map_err(|e| format!("Read failed: {}", e))?;
+        literal.get_or_insert_with(|| String::from_utf8_lossy(&buf).into_owned());
+        // The rest of the response after the literal, e.g. ")"
+        let rest = read_line(reader).await?;
+        next = literal_len(&rest);
+        line.push_str(&rest);
+    }
+    Ok(Response { line, literal })
+}
+
+// ── IMAP connection ────────────────────────────────────────────────────
+
+/// A plain or TLS connection to a mail server (Debug for async-imap).
+pub(crate) trait MailStream:
+    AsyncRead + AsyncWrite + Unpin + Send + std::fmt::Debug
+{
+}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + std::fmt::Debug> MailStream for T {}
+
+/// One server response line, with the first literal it carried (FETCH
+/// bodies).
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub(crate) line: String,
+    pub(crate) literal: Option<String>,
+}
+
+pub(crate) struct Imap {
+    reader: BufReader<ReadHalf<Box<dyn MailStream>>>,
+    writer: WriteHalf<Box<dyn MailStream>>,
+    tag: u32,
+}
+
+pub(crate) async fn tls_upgrade(host: &str, tcp: TcpStream) -> EngineResult<Box<dyn MailStream>> {
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+    let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| format!("Invalid server name: {}", e))?;
+    let tls = connector
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| format!("TLS handshake with {} failed: {}", host, e))?;
+    Ok(Box::new(tls))
+}
+
+impl Imap {
+    /// Connect to `account` and log in (XOAUTH2 for OAuth accounts).
+    pub(crate) async fn login(account: &ImapAccount) -> EngineResult<Self> {
+        let mut imap = Imap::connect(account).await?;
+        imap.authenticate(account).await?;
+        Ok(imap)
+    }
+
+    /// Log in on a connection from `connect`.
+    pub(crate) async fn authenticate(&mut self, account: &ImapAccount) -> EngineResult<()> {
+        match account.oauth {
+            Some(provider) => {
+                let token =
+                    mail_oauth::access_token(&account.name, &account.email, provider).await?;
+                self.xoauth2(&account.login, &token).await
+            }
+            None => {
+                let pass = password(account)?;
+                self.command(&format!("LOGIN {} {}", quote(&account.login), quote(&pass)))
+                    .await
+                    .map(|_| ())
+            }
+        }
+    }
+
+    /// Whether the server advertises IDLE.
+    pub(crate) async fn supports_idle(&mut self) -> EngineResult<bool> {
+        Ok(self.capabilities().await?.iter().any(|c| c == "IDLE"))
+    }
+
+    /// The server's capabilities, upper-case.
+    pub(crate) async fn capabilities(&mut self) -> EngineResult<Vec<String>> {
+        Ok(self
+            .command("CAPABILITY")
+            .await?
+            .iter()
+            .filter_map(|r| r.line.strip_prefix("* CAPABILITY"))
+            .flat_map(|caps| caps.split_whitespace().map(str::to_ascii_uppercase))
+            .collect())
+    }
+
+    /// Say goodbye; errors don't matter any more.
+    pub(crate) async fn logout(mut self) {
+        let _ = self.command("LOGOUT").await;
+    }
+
+    /// Open the connection (TLS / STARTTLS) and read the greeting.
+    pub(crate) async fn connect(account: &ImapAccount) -> EngineResult<Self> {
+        let (stream, greeted) = open_stream(account).await?;
+        let mut imap = Imap::from_stream(stream);
+        if !greeted {
+            imap.read_timed().await?;
+        }
+        Ok(imap)
+    }
+
+    fn from_stream(stream: Box<dyn MailStream>) -> Self {
+        let (reader, writer) = tokio::io::split(stream);
+        Imap {
+            reader: BufReader::new(reader),
+            writer,
+            tag: 0,
+        }
+    }
+
+    async fn read(&mut self) -> EngineResult<Response> {
+        read_response(&mut self.reader).await
+    }
+
+    async fn read_timed(&mut self) -> EngineResult<Response> {
+        tokio::time::timeout(COMMAND_TIMEOUT, self.read())
+            .await
+            .map_err(|_| "The server stopped responding")?
+    }
+
+    async fn send(&mut self, line: &str) -> EngineResult<()> {
+        self.writer
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .await
+            .map_err(|e| format!("Write failed: {}", e).into())
+    }
+
+    fn next_tag(&mut self) -> String {
+        self.tag += 1;
+        format!("p{}", self.tag)
+    }
+
+    /// Run a command; returns its untagged responses.
+    pub(crate) async fn command(&mut self, command: &str) -> EngineResult<Vec<Response>> {
+        let tag = self.next_tag();
+        self.send(&format!("{} {}", tag, command)).await?;
+        self.collect(&tag).await
+    }
+
+    async fn collect(&mut self, tag: &str) -> EngineResult<Vec<Response>> {
+        let mut untagged = Vec::new();
+        loop {
+            let r = self.read_timed().await?;
+            if let Some(status) = r.line.strip_prefix(tag) {
+                let status = status.trim_start();
+                if status.starts_with("OK") {
+                    return Ok(untagged);
+                }
+                // Keep passwords out of the error: only the status is shown
+                return Err(format!("Server refused the command: {}", status).into());
+            }
+            untagged.push(r);
+        }
+    }
+
+    /// AUTHENTICATE XOAUTH2 with an initial response. A refusal comes as a
+    /// `+` challenge carrying the error; an empty reply ends the exchange.
+    async fn xoauth2(&mut self, user: &str, access_token: &str) -> EngineResult<()> {
+        let tag = self.next_tag();
+        let response = mail_oauth::xoauth2(user, access_token);
+        self.send(&format!("{} AUTHENTICATE XOAUTH2 {}", tag, response))
+            .await?;
+        let first = self.read_timed().await?;
+        if first.line.starts_with('+') {
+            self.send("").await?;
+        } else if let Some(status) = first.line.strip_prefix(tag.as_str()) {
+            let status = status.trim_start();
+            if !status.starts_with("OK") {
+                return Err(format!("Server refused the command: {}", status).into());
+            }
+            return Ok(());
+        }
+        self.collect(&tag).await.map(|_| ())
+    }
+
+    /// IDLE until the server reports something or `renew` passes; returns
+    /// the untagged responses seen.
+    pub(crate) async fn idle(&mut self, renew: Duration) -> EngineResult<Vec<Response>> {
+        let tag = self.next_tag();
+        self.send(&format!("{} IDLE", tag)).await?;
+        let mut seen = Vec::new();
+        loop {
+            let r = self.read_timed().await?;
+            if r.line.starts_with('+') {
+                break;
+            }
+            seen.push(r);
+        }
+
+        if let Ok(r) = tokio::time::timeout(renew, self.read()).await {
+            seen.push(r?);
+        }
+        self.send("DONE").await?;
+        seen.extend(self.collect(&tag).await?);
+        Ok(seen)
+    }
+}
+
+/// Connect to `account`'s IMAP server (TLS / STARTTLS). Also returns
+/// whether the greeting was already read, which STARTTLS does before the
+/// upgrade; otherwise it is the first thing on the stream.
+pub(crate) async fn open_stream(
+    account: &ImapAccount,
+) -> EngineResult<(Box<dyn MailStream>, bool)> {
+    let addr = format!("{}:{}", account.host, account.port);
+    let tcp = tokio::time::timeout(COMMAND_TIMEOUT, TcpStream::connect(&addr))
+        .await
+        .map_err(|_| format!("Connecting to {} timed out", addr))?
+        .map_err(|e| format!("TCP connect to {} failed: {}", addr, e))?;
+    match account.encryption.as_str() {
+        "none" => {
+            warn!(
+                "[mail-watch] Connecting WITHOUT TLS to {} — the password is sent in plaintext!",
+                addr
+            );
+            Ok((Box::new(tcp), false))
+        }
+        "start-tls" => Ok((starttls(&account.host, tcp).await?, true)),
+        _ => Ok((tls_upgrade(&account.host, tcp).await?, false)),
+    }
+}
+
+/// Read the greeting, switch to TLS with STARTTLS. Read byte by byte so
+/// nothing of the TLS handshake ends up in a buffer.
+async fn starttls(host: &str, mut tcp: TcpStream) -> EngineResult<Box<dyn MailStream>> {
+    async fn read_line(tcp: &mut TcpStream) -> EngineResult<String> {
+        let mut line = Vec::new();
+        loop {
+            let byte = tokio::time::timeout(COMMAND_TIMEOUT, tcp.read_u8())
+                .await
+                .map_err(|_| "The server stopped responding")?
+                .map_err(|e| format!("Read failed: {}", e))?;
+            if byte == b'\n' {
+                return Ok(String::from_utf8_lossy(&line).trim_end().to_string());
+            }
+            line.push(byte);
+        }
+    }
+
+    read_line(&mut tcp).await?; // greeting
+    tcp.write_all(b"p0 STARTTLS\r\n")
+        .await
+        .map_err(|e| format!("Write failed: {}", e))?;
+    loop {
+        let line = read_line(&mut tcp).await?;
+        if let Some(status) = line.strip_prefix("p0 ") {
+            if !status.starts_with("OK") {
+                return Err(format!("STARTTLS refused: {}", status).into());
+            }
+            return tls_upgrade(host, tcp).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn responses_with_several_literals_are_read_whole() {
+        let mut input: &[u8] =
+            b"* 1 FETCH (UID 7 BODY[1] {3}\r\none BODY[2] {5}\r\ntwo\r\n)\r\n* 2 EXISTS\r\n";
+        let r = read_response(&mut input).await.unwrap();
+        assert_eq!(r.line, "* 1 FETCH (UID 7 BODY[1] {3} BODY[2] {5})");
+        assert_eq!(r.literal.as_deref(), Some("one"));
+        assert_eq!(read_response(&mut input).await.unwrap().line, "* 2 EXISTS");
+
+        let mut huge: &[u8] = b"* 1 FETCH (BODY[] {999999999999}\r\n";
+        assert!(read_response(&mut huge).await.is_err());
+    }
+
+    #[test]
+    fn accounts_are_read_from_the_himalaya_config() {
+        let config = r#"
+[accounts.work]
+email = "me@work.com"
+display-name = "Me at work"
+backend.type = "imap"
+backend.host = "imap.work.com"
+backend.port = 993
+backend.encryption = "tls"
+backend.login = "me"
+backend.auth.type = "password"
+backend.auth.cmd = "security find-generic-password"
+message.send.backend.type = "smtp"
+message.send.backend.host = "smtp.work.com"
+message.send.backend.port = 587
+message.send.backend.encryption = "start-tls"
+
+[accounts.local]
+email = "me@local"
+backend.type = "maildir"
+backend.root-dir = "~/Mail"
+"#;
+        let accounts = himalaya_accounts(config);
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].name, "work");
+        assert_eq!(accounts[0].host, "imap.work.com");
+        assert_eq!(accounts[0].login, "me");
+        assert_eq!(accounts[0].raw_password, None);
+        assert_eq!(accounts[0].oauth, None);
+        assert_eq!(accounts[0].display_name.as_deref(), Some("Me at work"));
+        assert_eq!(
+            accounts[0].smtp,
+            Some(SmtpServer {
+                host: "smtp.work.com".into(),
+                port: 587,
+                encryption: "start-tls".into(),
+                login: "me@work.com".into(),
+                raw_password: None,
+            })
+        );
+        assert!(himalaya_accounts("not toml [").is_empty());
+    }
+
+    #[test]
+    fn responses_are_parsed() {
+        let mut exists = 0;
+        track_exists(&mut exists, "* 12 EXISTS");
+        assert_eq!(exists, 12);
+        track_exists(&mut exists, "* 3 EXPUNGE");
+        track_exists(&mut exists, "* 1 RECENT");
+        assert_eq!(exists, 11);
+        track_exists(&mut exists, "p3 OK IDLE terminated");
+        assert_eq!(exists, 11);
+
+        assert_eq!(
+            fetch_seq("* 12 FETCH (BODY[HEADER.FIELDS (FROM)] {22}"),
+            Some(12)
+        );
+        assert_eq!(
+            literal_len("* 12 FETCH (BODY[HEADER.FIELDS (FROM)] {22}"),
+            Some(22)
+        );
+        assert_eq!(literal_len("* OK [UIDVALIDITY 3]"), None);
+        assert_eq!(quote(r#"pa"ss\"#), r#""pa\"ss\\""#);
+    }
+
+    #[test]
+    fn backoff_grows_to_five_minutes() {
+        let mut b = Backoff::default();
+        let delays: Vec<u64> = (0..8).map(|_| b.next_delay().as_secs()).collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 80, 160, 300, 300]);
+        b.reset();
+        assert_eq!(b.next_delay().as_secs(), 5);
+    }
+}
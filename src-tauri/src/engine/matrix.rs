@@ -139,7 +139,13 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
                         break;
                     }
                     error!("[matrix] Bridge error: {} — reconnecting", e);
-                    let delay = crate::engine::http::reconnect_delay(reconnect_attempt).await;
+                    let delay = channels::reconnect_backoff(
+                        &app_handle,
+                        "matrix",
+                        reconnect_attempt,
+                        &e.to_string(),
+                    )
+                    .await;
                     warn!(
                         "[matrix] Reconnecting in {}ms (attempt {})",
                         delay.as_millis(),
@@ -153,6 +159,7 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
             }
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "matrix", None);
         info!("[matrix] Bridge stopped");
     });
 
@@ -178,6 +185,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("matrix"),
     }
 }
 
@@ -246,6 +254,7 @@ async fn run_sdk_bridge(
         bot_user_id_str
     );
 
+    channels::mark_connected(&app_handle, "matrix");
     let _ = app_handle.emit(
         "matrix-status",
         json!({
@@ -415,6 +424,7 @@ async fn handle_room_message(
                     "user_id": &sender,
                 }),
             );
+            channels::notify_pairing(&app_handle, "Matrix", &sender);
             send_room_message(&room, &denial_str).await;
             return;
         }
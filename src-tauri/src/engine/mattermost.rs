@@ -3,6 +3,10 @@
 // Connects Paw to Mattermost via outbound WebSocket + REST API.
 // Similar to Slack/Discord — WebSocket for real-time events, REST for replies.
 //
+// Files: attachments on a post are downloaded and ingested
+// (channels::attachments); files the agent attaches with `[attach: path]`
+// are uploaded through /api/v4/files and posted with the reply.
+//
 // Setup: Mattermost Admin → Integrations → Bot Accounts → Create Bot → Copy token.
 //        Or: User Settings → Security → Personal Access Tokens.
 //
@@ -13,6 +17,7 @@
 //   - All communication goes through the Mattermost server's TLS API
 
 use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::channels::attachments::{self, OutboundFile, MAX_FILES, MAX_INBOUND_BYTES};
 use crate::engine::channels::{self, ChannelStatus, PendingUser};
 use futures::{SinkExt, StreamExt};
 use log::{debug, error, info, warn};
@@ -153,16 +158,24 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
             if get_stop_signal().load(Ordering::Relaxed) {
                 break;
             }
-            if let Err(e) = run_ws_loop(&app_handle, &config).await {
-                error!("[mattermost] WebSocket error: {}", e);
-            }
+            let reason = match run_ws_loop(&app_handle, &config).await {
+                Ok(()) => "connection closed".to_string(),
+                Err(e) => {
+                    error!("[mattermost] WebSocket error: {}", e);
+                    e.to_string()
+                }
+            };
             if get_stop_signal().load(Ordering::Relaxed) {
                 break;
             }
             warn!("[mattermost] Reconnecting in 5s...");
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            let attempt = channels::connectivity("mattermost").reconnect_attempt + 1;
+            let delay = std::time::Duration::from_secs(5);
+            channels::mark_reconnecting(&app_handle, "mattermost", &reason, attempt, delay);
+            tokio::time::sleep(delay).await;
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "mattermost", None);
         info!("[mattermost] Bridge stopped");
     });
 
@@ -188,6 +201,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("mattermost"),
     }
 }
 
@@ -255,6 +269,7 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
             message: e.to_string(),
         })?;
 
+    channels::mark_connected(app_handle, "mattermost");
     let _ = app_handle.emit(
         "mattermost-status",
         json!({
@@ -317,7 +332,10 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
 
             let channel_id = post["channel_id"].as_str().unwrap_or("").to_string();
             let message = post["message"].as_str().unwrap_or("").to_string();
-            if message.is_empty() {
+            let has_files = post["file_ids"]
+                .as_array()
+                .is_some_and(|ids| !ids.is_empty());
+            if message.is_empty() && !has_files {
                 continue;
             }
 
@@ -339,7 +357,7 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
                 .replace(&format!("@{}", bot_username), "")
                 .trim()
                 .to_string();
-            if content.is_empty() {
+            if content.is_empty() && !has_files {
                 continue;
             }
 
@@ -380,8 +398,16 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
                             "username": &sender_username,
                         }),
                     );
-                    let _ = mm_send_message(&client, base, &config.token, &channel_id, &denial_str)
-                        .await;
+                    channels::notify_pairing(app_handle, "Mattermost", &sender_username);
+                    let _ = mm_send_message(
+                        &client,
+                        base,
+                        &config.token,
+                        &channel_id,
+                        &denial_str,
+                        &[],
+                    )
+                    .await;
                     continue;
                 }
             }
@@ -389,13 +415,25 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
             MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
 
             let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
-            let ctx = "You are chatting via Mattermost. Use Mattermost markdown for formatting. \
-                       Keep responses concise. Mattermost supports most standard markdown.";
+            let ctx = format!(
+                "You are chatting via Mattermost. Use Mattermost markdown for formatting. \
+                 Keep responses concise. Mattermost supports most standard markdown. {}",
+                attachments::ATTACH_HINT
+            );
+
+            let content = if has_files {
+                let notes =
+                    mm_ingest_files(app_handle, &client, base, &config.token, &post, agent_id)
+                        .await;
+                attachments::with_file_notes(&content, &notes)
+            } else {
+                content
+            };
 
             let response = channels::run_channel_agent(
                 app_handle,
                 "mattermost",
-                ctx,
+                &ctx,
                 &content,
                 sender_id,
                 agent_id,
@@ -405,9 +443,20 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
 
             match response {
                 Ok(reply) if !reply.is_empty() => {
-                    for chunk in channels::split_message(&reply, 16383) {
-                        let _ = mm_send_message(&client, base, &config.token, &channel_id, &chunk)
-                            .await;
+                    let (text, files) = attachments::take_attachments(&reply, agent_id);
+                    let file_ids =
+                        mm_upload_files(&client, base, &config.token, &channel_id, files).await;
+                    // Files go with the last chunk (or alone when the reply was only files)
+                    let chunks = channels::split_message(&text, 16383);
+                    let last = chunks.len() - 1;
+                    for (i, chunk) in chunks.iter().enumerate() {
+                        let ids: &[String] = if i == last { &file_ids } else { &[] };
+                        if chunk.is_empty() && ids.is_empty() {
+                            continue;
+                        }
+                        let _ =
+                            mm_send_message(&client, base, &config.token, &channel_id, chunk, ids)
+                                .await;
                     }
                 }
                 Err(e) => {
@@ -418,6 +467,7 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &MattermostConfig) -
                         &config.token,
                         &channel_id,
                         &format!("Error: {}", e),
+                        &[],
                     )
                     .await;
                 }
@@ -454,12 +504,16 @@ async fn mm_send_message(
     token: &str,
     channel_id: &str,
     message: &str,
+    file_ids: &[String],
 ) -> EngineResult<()> {
     let url = format!("{}/api/v4/posts", base);
-    let body = json!({
+    let mut body = json!({
         "channel_id": channel_id,
         "message": message,
     });
+    if !file_ids.is_empty() {
+        body["file_ids"] = json!(file_ids);
+    }
 
     match client
         .post(&url)
@@ -477,6 +531,143 @@ async fn mm_send_message(
     Ok(())
 }
 
+/// Download and ingest the files on a post. One note per file, failures
+/// included, for the agent's message.
+async fn mm_ingest_files(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    base: &str,
+    token: &str,
+    post: &serde_json::Value,
+    agent_id: &str,
+) -> Vec<String> {
+    let ids: Vec<&str> = post["file_ids"]
+        .as_array()
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+    let mut notes = Vec::new();
+    for id in ids.iter().take(MAX_FILES) {
+        // `posted` events usually carry file infos in the post metadata
+        let info = match post["metadata"]["files"]
+            .as_array()
+            .and_then(|infos| infos.iter().find(|f| f["id"].as_str() == Some(id)))
+        {
+            Some(info) => Ok(info.clone()),
+            None => mm_get_json(client, &format!("{}/api/v4/files/{}/info", base, id), token).await,
+        };
+        let result = match info {
+            Ok(info) => {
+                let name = info["name"].as_str().unwrap_or("file").to_string();
+                match mm_download_file(client, base, token, id, &info).await {
+                    Ok(bytes) => attachments::ingest_bytes(app_handle, agent_id, &name, &bytes)
+                        .await
+                        .map_err(|e| (name, e)),
+                    Err(e) => Err((name, e)),
+                }
+            }
+            Err(e) => Err((id.to_string(), e)),
+        };
+        match result {
+            Ok(note) => notes.push(note),
+            Err((name, e)) => {
+                warn!("[mattermost] Could not ingest {}: {}", name, e);
+                notes.push(format!("[Shared file {} could not be read: {}]", name, e));
+            }
+        }
+    }
+    if ids.len() > MAX_FILES {
+        notes.push(format!(
+            "[{} more files were shared but not read — at most {} per message]",
+            ids.len() - MAX_FILES,
+            MAX_FILES
+        ));
+    }
+    notes
+}
+
+async fn mm_get_json(
+    client: &reqwest::Client,
+    url: &str,
+    token: &str,
+) -> EngineResult<serde_json::Value> {
+    let resp = client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()).into());
+    }
+    Ok(resp.json().await?)
+}
+
+async fn mm_download_file(
+    client: &reqwest::Client,
+    base: &str,
+    token: &str,
+    file_id: &str,
+    info: &serde_json::Value,
+) -> EngineResult<Vec<u8>> {
+    if info["size"].as_u64().unwrap_or(0) > MAX_INBOUND_BYTES {
+        return Err(format!("larger than {} MB", MAX_INBOUND_BYTES / 1024 / 1024).into());
+    }
+    let resp = client
+        .get(format!("{}/api/v4/files/{}", base, file_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("download failed with HTTP {}", resp.status()).into());
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+/// Upload files to a channel and return their ids for a post's `file_ids`.
+/// Failed uploads are logged and left out.
+async fn mm_upload_files(
+    client: &reqwest::Client,
+    base: &str,
+    token: &str,
+    channel_id: &str,
+    files: Vec<OutboundFile>,
+) -> Vec<String> {
+    let mut ids = Vec::new();
+    for file in files {
+        let name = file.name.clone();
+        let part = match reqwest::multipart::Part::bytes(file.bytes)
+            .file_name(file.name)
+            .mime_str(&file.mime_type)
+        {
+            Ok(part) => part,
+            Err(e) => {
+                warn!("[mattermost] Upload {}: {}", name, e);
+                continue;
+            }
+        };
+        let form = reqwest::multipart::Form::new()
+            .text("channel_id", channel_id.to_string())
+            .part("files", part);
+        let resp = client
+            .post(format!("{}/api/v4/files", base))
+            .header("Authorization", format!("Bearer {}", token))
+            .multipart(form)
+            .send()
+            .await;
+        match resp {
+            Ok(r) if r.status().is_success() => {
+                let body: serde_json::Value = r.json().await.unwrap_or_default();
+                match body["file_infos"][0]["id"].as_str() {
+                    Some(id) => ids.push(id.to_string()),
+                    None => warn!("[mattermost] Upload {}: no file id returned", name),
+                }
+            }
+            Ok(r) => warn!("[mattermost] Upload {} failed: {}", name, r.status()),
+            Err(e) => warn!("[mattermost] Upload {} error: {}", name, e),
+        }
+    }
+    ids
+}
+
 // ── Config Persistence ─────────────────────────────────────────────────
 
 pub fn load_config(app_handle: &tauri::AppHandle) -> EngineResult<MattermostConfig> {
@@ -7,6 +7,7 @@
 // Both are wrapped by McpTransportHandle for unified API.
 
 use super::types::{JsonRpcRequest, JsonRpcResponse};
+use crate::engine::processes;
 use crate::engine::util::safe_truncate;
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
@@ -72,9 +73,17 @@ impl StdioTransport {
             cmd.env(k, v);
         }
 
+        processes::bind_to_parent(cmd.as_std_mut());
         let mut child = cmd
             .spawn()
             .map_err(|e| format!("Failed to spawn MCP server `{}`: {}", command, e))?;
+        if let Some(pid) = child.id() {
+            let name = cmd_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| command.to_string());
+            processes::track(pid, &format!("mcp:{}", name));
+        }
 
         let stdin = child.stdin.take().ok_or("Failed to open stdin")?;
         let stdout = child.stdout.take().ok_or("Failed to open stdout")?;
@@ -240,7 +249,7 @@ impl StdioTransport {
         let mut guard = self.child.lock().await;
         if let Some(ref mut child) = *guard {
             info!("[mcp] Killing child process");
-            let _ = child.kill().await;
+            stop_child(child).await;
         }
         *guard = None;
     }
@@ -267,12 +276,20 @@ impl Drop for StdioTransport {
         tokio::spawn(async move {
             let mut guard = child.lock().await;
             if let Some(ref mut child) = *guard {
-                let _ = child.kill().await;
+                stop_child(child).await;
             }
         });
     }
 }
 
+/// Stop the server and anything it started (`npx` → `node`), then reap it.
+async fn stop_child(child: &mut Child) {
+    if let Some(pid) = child.id() {
+        let _ = processes::kill(pid);
+    }
+    let _ = child.kill().await;
+}
+
 // ── Content-Length framed message reader ────────────────────────────────
 
 /// Read a single Content-Length framed message from the stream.
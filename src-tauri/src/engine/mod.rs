@@ -3,44 +3,90 @@
 // for zero-network-hop communication.
 
 pub mod agent_loop;
+pub mod app_context;
 pub mod audit;
+pub mod autonomy;
 pub mod binary_ipc;
+pub mod bookmarks;
 pub mod http;
+pub mod idempotency;
+pub mod outbox;
 pub mod paths;
+pub mod postmortem;
 pub mod pricing;
+pub mod processes;
+pub mod profiles;
+pub mod project_budget;
 pub mod providers;
 pub mod sessions;
 pub mod state;
+pub mod tool_args;
+pub mod tool_errors;
 pub mod tools;
+pub mod translate;
 pub mod types;
+pub mod youtube;
 // commands module moved to crate::commands::channels — see src/commands/channels.rs
+pub mod bookmark_import;
 pub mod channels;
 pub mod chat;
+pub mod citations;
 pub mod compaction;
+pub mod compare;
+pub mod confidence;
 pub mod constrained;
+pub mod credential_bundle;
+pub mod db_archive;
 pub mod dex;
+pub mod diagnostics;
 pub mod discord;
+pub mod email;
+pub mod email_bridge;
 pub mod engram;
 pub mod events;
 pub mod forge;
+pub mod grounding;
+pub mod handoff;
+pub mod import;
+pub mod ingest;
 pub mod injection;
 pub mod irc;
 pub mod key_vault;
+pub mod local_models;
+pub mod location;
+pub mod mail_check;
+pub mod mail_client;
+pub mod mail_oauth;
+pub mod mail_watch;
 pub mod matrix;
 pub mod mattermost;
 pub mod mcp;
 pub mod memory;
 pub mod n8n_engine;
+pub mod news;
 pub mod nextcloud;
 pub mod nostr;
+pub mod notifications;
 pub mod oauth;
+pub mod oauth_tokens;
 pub mod orchestrator;
 pub mod plan;
+pub mod plan_mode;
+pub mod prompt_library;
 pub mod provider_registry;
+pub mod reflection;
+pub mod research;
+pub mod resources;
 pub mod routing;
+pub mod safe_mode;
 pub mod sandbox;
+pub mod search;
+pub mod shutdown;
+pub mod single_instance;
 pub mod skills;
 pub mod slack;
+pub mod slash_commands;
+pub mod smtp;
 pub mod sol_dex;
 pub mod speculative;
 pub mod swarm;
@@ -49,6 +95,7 @@ pub mod telegram;
 pub mod telemetry;
 pub mod tool_index;
 pub mod tool_registry;
+pub mod transcribe;
 pub mod twitch;
 pub mod util;
 pub mod web;
@@ -42,6 +42,19 @@ fn emit_status(app_handle: &tauri::AppHandle, kind: &str, message: &str) {
     );
 }
 
+/// Emit a provisioning status carrying a 0–1 progress fraction.
+fn emit_progress(app_handle: &tauri::AppHandle, message: &str, progress: f64) {
+    use tauri::Emitter;
+    let _ = app_handle.emit(
+        "n8n-status",
+        serde_json::json!({
+            "kind": "provisioning",
+            "message": message,
+            "progress": progress.clamp(0.0, 1.0),
+        }),
+    );
+}
+
 // ── Utility ────────────────────────────────────────────────────────────
 
 /// Get the application data directory.
@@ -7,8 +7,17 @@
 //
 // The binary is **not** added to PATH; we use the full path internally
 // when spawning `npx n8n`.
+//
+// The archive is streamed to disk while being hashed and checked against
+// the SHA-256 manifest bundled with the app (`node-shasums256.txt`, the
+// nodejs.org SHASUMS256.txt lines for the pinned version) before anything
+// is unpacked. Extraction is done in-process (tar + flate2, zip on
+// Windows) into a staging directory that replaces the installed runtime
+// only once it is complete, so a failed upgrade leaves the old one working.
 
 use crate::atoms::error::{EngineError, EngineResult};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 /// The pinned Node.js version we download.  Using the Active LTS release
@@ -24,6 +33,14 @@ const NODE_VERSION: &str = "24.14.0";
 /// Subdirectory under `~/.openpawz/` where the Node.js tarball is extracted.
 const NODE_DIR_NAME: &str = "node";
 
+/// SHA-256 of the nodejs.org archives for `NODE_VERSION`, in SHASUMS256.txt
+/// format. Regenerate with `scripts/update-node-shasums.sh` when bumping
+/// the version.
+const BUNDLED_SHASUMS: &str = include_str!("node-shasums256.txt");
+
+/// Extraction progress is reported every this many entries.
+const EXTRACT_PROGRESS_EVERY: usize = 500;
+
 // ── Public API ─────────────────────────────────────────────────────────
 
 /// Return the path to a usable `node` binary.
@@ -57,7 +74,8 @@ pub fn local_node_bin_dir() -> Option<PathBuf> {
 }
 
 /// Download and extract Node.js if not already present.
-/// If an older auto-provisioned version exists, upgrades it in-place.
+/// If an older auto-provisioned version exists, it is replaced once the
+/// new one has been verified and unpacked.
 ///
 /// Emits status events so the frontend can show progress.
 pub async fn ensure_node_available(app_handle: &tauri::AppHandle) -> EngineResult<PathBuf> {
@@ -71,12 +89,12 @@ pub async fn ensure_node_available(app_handle: &tauri::AppHandle) -> EngineResul
         if is_current_version(&bin) {
             return Ok(bin);
         }
-        // Stale version — fall through to re-download
+        // Stale version — fall through to re-download. The old runtime is
+        // only removed once the new one is installed.
         log::info!(
             "[n8n] Local Node.js is outdated — upgrading to v{}",
             NODE_VERSION
         );
-        cleanup_old_versions();
     }
 
     log::info!(
@@ -91,46 +109,16 @@ pub async fn ensure_node_available(app_handle: &tauri::AppHandle) -> EngineResul
         &format!("Downloading Node.js v{}…", NODE_VERSION),
     );
 
-    let archive_url = download_url();
-    log::info!("[n8n] Downloading Node.js from {}", archive_url);
-
-    // Download the archive
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(300))
-        .build()
-        .map_err(|e| EngineError::Other(format!("HTTP client error: {}", e)))?;
-
-    let resp = client
-        .get(&archive_url)
-        .send()
-        .await
-        .map_err(|e| EngineError::Other(format!("Failed to download Node.js: {}", e)))?;
-
-    if !resp.status().is_success() {
-        return Err(EngineError::Other(format!(
-            "Node.js download failed: HTTP {}",
-            resp.status()
-        )));
-    }
-
-    let bytes = resp
-        .bytes()
-        .await
-        .map_err(|e| EngineError::Other(format!("Failed to read Node.js archive: {}", e)))?;
-
-    log::info!(
-        "[n8n] Downloaded {:.1} MB — extracting…",
-        bytes.len() as f64 / 1_048_576.0
-    );
-
-    super::emit_status(app_handle, "provisioning", "Extracting Node.js…");
-
-    // Extract
     let dest = node_dir();
     std::fs::create_dir_all(&dest)
         .map_err(|e| EngineError::Other(format!("Failed to create node dir: {}", e)))?;
 
-    extract_archive(&bytes, &dest)?;
+    let archive_url = download_url();
+    let archive_path = dest.join(format!("{}.download", archive_name()));
+    log::info!("[n8n] Downloading Node.js from {}", archive_url);
+    let result = install_from(app_handle, &archive_url, &archive_path, &dest).await;
+    let _ = std::fs::remove_file(&archive_path);
+    result?;
 
     // Verify the binary works
     let node_bin = node_bin_path();
@@ -140,25 +128,12 @@ pub async fn ensure_node_available(app_handle: &tauri::AppHandle) -> EngineResul
             node_bin.display()
         )));
     }
-
-    // Make binary executable on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let _ = std::fs::set_permissions(&node_bin, std::fs::Permissions::from_mode(0o755));
-        // Also make npx executable
-        let npx_bin = node_bin
-            .parent()
-            .ok_or_else(|| EngineError::Other("node binary has no parent directory".into()))?
-            .join("npx");
-        let _ = std::fs::set_permissions(&npx_bin, std::fs::Permissions::from_mode(0o755));
-    }
-
     if !validate_local_node(&node_bin) {
         return Err(EngineError::Other(
             "Downloaded Node.js binary failed validation".into(),
         ));
     }
+    cleanup_old_versions();
 
     log::info!(
         "[n8n] Node.js v{} installed to {}",
@@ -175,6 +150,150 @@ pub async fn ensure_node_available(app_handle: &tauri::AppHandle) -> EngineResul
     Ok(node_bin)
 }
 
+/// Download → verify → extract into a staging dir → swap into place.
+async fn install_from(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    archive_path: &Path,
+    dest: &Path,
+) -> EngineResult<()> {
+    let sha256 = download_to(app_handle, url, archive_path).await?;
+
+    super::emit_status(app_handle, "provisioning", "Verifying Node.js download…");
+    let expected = expected_sha256()?;
+    if !sha256.eq_ignore_ascii_case(&expected) {
+        return Err(EngineError::Other(format!(
+            "Node.js download failed verification (SHA-256 {} does not match {})",
+            sha256, expected
+        )));
+    }
+    log::info!("[n8n] Node.js archive verified (SHA-256 {})", sha256);
+
+    let staging = dest.join(format!("{}.partial", extracted_dir_name()));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| EngineError::Other(format!("Failed to create staging dir: {}", e)))?;
+
+    let handle = app_handle.clone();
+    let archive = archive_path.to_path_buf();
+    let staging_dir = staging.clone();
+    let extracted = tokio::task::spawn_blocking(move || {
+        extract_archive(&archive, &staging_dir, &mut |done| {
+            super::emit_status(
+                &handle,
+                "provisioning",
+                &format!("Extracting Node.js… {} files", done),
+            );
+        })
+    })
+    .await
+    .map_err(|e| EngineError::Other(format!("Extraction task failed: {}", e)))?;
+    if let Err(e) = extracted {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // The archive's single top-level folder becomes the installed runtime
+    let unpacked = staging.join(extracted_dir_name());
+    let target = dest.join(extracted_dir_name());
+    let _ = std::fs::remove_dir_all(&target);
+    let moved = std::fs::rename(&unpacked, &target);
+    let _ = std::fs::remove_dir_all(&staging);
+    moved.map_err(|e| EngineError::Other(format!("Failed to install Node.js: {}", e)))
+}
+
+/// Stream `url` into `path`, reporting percentage progress. Returns the
+/// hex SHA-256 of what was written.
+async fn download_to(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    path: &Path,
+) -> EngineResult<String> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(30))
+        .read_timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| EngineError::Other(format!("HTTP client error: {}", e)))?;
+
+    let mut resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| EngineError::Other(format!("Failed to download Node.js: {}", e)))?;
+
+    if !resp.status().is_success() {
+        return Err(EngineError::Other(format!(
+            "Node.js download failed: HTTP {}",
+            resp.status()
+        )));
+    }
+
+    let total = resp.content_length();
+    let mut file = std::fs::File::create(path)
+        .map_err(|e| EngineError::Other(format!("Failed to write archive: {}", e)))?;
+    let mut hasher = Sha256::new();
+    let mut done: u64 = 0;
+    let mut last_percent = None;
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| EngineError::Other(format!("Failed to read Node.js archive: {}", e)))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| EngineError::Other(format!("Failed to write archive: {}", e)))?;
+        done += chunk.len() as u64;
+        let percent = total.map(|t| done * 100 / t.max(1));
+        if percent != last_percent && percent.is_some_and(|p| p % 5 == 0) {
+            last_percent = percent;
+            super::emit_progress(
+                app_handle,
+                &format!(
+                    "Downloading Node.js v{}… {:.1} MB",
+                    NODE_VERSION,
+                    done as f64 / 1_048_576.0
+                ),
+                done as f64 / total.unwrap_or(done).max(1) as f64,
+            );
+        }
+    }
+    file.flush()
+        .map_err(|e| EngineError::Other(format!("Failed to write archive: {}", e)))?;
+
+    log::info!("[n8n] Downloaded {:.1} MB", done as f64 / 1_048_576.0);
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// The expected SHA-256 of this platform's archive, from the bundled
+/// manifest only. A missing entry is a packaging error, not a reason to
+/// trust whatever nodejs.org (or a MITM) serves at install time.
+fn expected_sha256() -> EngineResult<String> {
+    let name = archive_name();
+    lookup_sha256(BUNDLED_SHASUMS, &name).ok_or_else(|| {
+        EngineError::Other(format!(
+            "No bundled SHA-256 for {} — refusing to install (run scripts/update-node-shasums.sh)",
+            name
+        ))
+    })
+}
+
+/// Find `file`'s hash in SHASUMS256.txt-format text (`<sha256>  <file>`).
+fn lookup_sha256(manifest: &str, file: &str) -> Option<String> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with('#'))
+        .find_map(|line| {
+            let (sha, name) = line.split_once(char::is_whitespace)?;
+            let valid = sha.len() == 64 && sha.chars().all(|c| c.is_ascii_hexdigit());
+            (valid && name.trim_start_matches([' ', '*']) == file).then(|| sha.to_lowercase())
+        })
+}
+
 // ── Upgrade helpers ────────────────────────────────────────────────────
 
 /// Check whether the local auto-provisioned node matches the current
@@ -201,9 +320,9 @@ fn is_current_version(node_bin: &Path) -> bool {
     }
 }
 
-/// Remove previously auto-provisioned Node.js directories so the new
-/// version can be extracted cleanly.  Only deletes directories whose
-/// name matches the known old extracted-dir pattern.
+/// Remove previously auto-provisioned Node.js directories once the new
+/// version is in place.  Only deletes directories whose name matches the
+/// known old extracted-dir pattern.
 fn cleanup_old_versions() {
     let base = node_dir();
     if !base.exists() {
@@ -290,121 +409,106 @@ fn platform_arch() -> String {
     format!("{}-{}", os, arch)
 }
 
-/// Build the nodejs.org download URL.
+/// The nodejs.org archive file name.
 ///
 /// macOS/Linux: `.tar.gz` (e.g. `node-v22.14.0-darwin-arm64.tar.gz`)
 /// Windows:     `.zip`     (e.g. `node-v22.14.0-win-x64.zip`)
-fn download_url() -> String {
+fn archive_name() -> String {
     let (os, arch) = platform_pair();
-    if cfg!(target_os = "windows") {
-        format!(
-            "https://nodejs.org/dist/v{}/node-v{}-{}-{}.zip",
-            NODE_VERSION, NODE_VERSION, os, arch
-        )
+    let ext = if cfg!(target_os = "windows") {
+        "zip"
     } else {
-        format!(
-            "https://nodejs.org/dist/v{}/node-v{}-{}-{}.tar.gz",
-            NODE_VERSION, NODE_VERSION, os, arch
-        )
-    }
+        "tar.gz"
+    };
+    format!("node-v{}-{}-{}.{}", NODE_VERSION, os, arch, ext)
+}
+
+/// Build the nodejs.org download URL.
+fn download_url() -> String {
+    format!(
+        "https://nodejs.org/dist/v{}/{}",
+        NODE_VERSION,
+        archive_name()
+    )
 }
 
 // ── Extraction ─────────────────────────────────────────────────────────
 
-/// Extract a `.tar.gz` or `.zip` archive into `dest`.
-fn extract_archive(bytes: &[u8], dest: &Path) -> EngineResult<()> {
-    if cfg!(target_os = "windows") {
-        extract_zip(bytes, dest)
+/// Extract the `.tar.gz` (macOS, Linux) or `.zip` (Windows) archive into
+/// `dest`, calling `progress` with the number of entries unpacked so far.
+fn extract_archive(
+    archive: &Path,
+    dest: &Path,
+    progress: &mut dyn FnMut(usize),
+) -> EngineResult<()> {
+    let file = std::fs::File::open(archive)
+        .map_err(|e| EngineError::Other(format!("Failed to open archive: {}", e)))?;
+    if archive_name().ends_with(".zip") {
+        extract_zip(file, dest, progress)
     } else {
-        extract_tar_gz(bytes, dest)
+        extract_tar_gz(file, dest, progress)
     }
 }
 
-/// Extract a `.tar.gz` archive (macOS, Linux).
-fn extract_tar_gz(bytes: &[u8], dest: &Path) -> EngineResult<()> {
-    // Shell out to `tar` which is always available on macOS/Linux.
-    let archive_path = dest.join("node-download.tar.gz");
-    std::fs::write(&archive_path, bytes)
-        .map_err(|e| EngineError::Other(format!("Failed to write archive: {}", e)))?;
-
-    let output = std::process::Command::new("tar")
-        .args([
-            "xzf",
-            &archive_path.to_string_lossy(),
-            "-C",
-            &dest.to_string_lossy(),
-        ])
-        .output()
-        .map_err(|e| EngineError::Other(format!("tar extraction failed: {}", e)))?;
-
-    // Clean up the archive file
-    let _ = std::fs::remove_file(&archive_path);
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(EngineError::Other(format!(
-            "tar extraction failed: {}",
-            stderr
-        )));
+/// Unpack a gzipped tarball. `unpack_in` refuses entries that would land
+/// outside `dest` and keeps file modes, so `bin/node` stays executable and
+/// the `npm` / `npx` symlinks survive.
+fn extract_tar_gz(
+    reader: impl Read,
+    dest: &Path,
+    progress: &mut dyn FnMut(usize),
+) -> EngineResult<()> {
+    let tar_err = |e: std::io::Error| EngineError::Other(format!("tar extraction failed: {}", e));
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    archive.set_preserve_permissions(true);
+    for (i, entry) in archive.entries().map_err(tar_err)?.enumerate() {
+        let mut entry = entry.map_err(tar_err)?;
+        if !entry.unpack_in(dest).map_err(tar_err)? {
+            log::warn!("[n8n] Skipped unsafe archive entry {:?}", entry.path().ok());
+        }
+        if (i + 1) % EXTRACT_PROGRESS_EVERY == 0 {
+            progress(i + 1);
+        }
     }
-
     Ok(())
 }
 
-/// Extract a `.zip` archive (Windows).
-fn extract_zip(bytes: &[u8], dest: &Path) -> EngineResult<()> {
-    // Shell out to PowerShell's Expand-Archive on Windows
-    let archive_path = dest.join("node-download.zip");
-    std::fs::write(&archive_path, bytes)
-        .map_err(|e| EngineError::Other(format!("Failed to write archive: {}", e)))?;
-
-    #[cfg(target_os = "windows")]
-    {
-        let output = std::process::Command::new("powershell")
-            .args([
-                "-NoProfile",
-                "-Command",
-                &format!(
-                    "Expand-Archive -Force -Path '{}' -DestinationPath '{}'",
-                    archive_path.to_string_lossy(),
-                    dest.to_string_lossy()
-                ),
-            ])
-            .output()
-            .map_err(|e| EngineError::Other(format!("zip extraction failed: {}", e)))?;
-
-        let _ = std::fs::remove_file(&archive_path);
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(EngineError::Other(format!(
-                "zip extraction failed: {}",
-                stderr
-            )));
+/// Unpack a zip archive (Windows builds). Entries with unsafe names are
+/// skipped.
+fn extract_zip(
+    reader: impl Read + std::io::Seek,
+    dest: &Path,
+    progress: &mut dyn FnMut(usize),
+) -> EngineResult<()> {
+    let zip_err =
+        |e: zip::result::ZipError| EngineError::Other(format!("zip extraction failed: {}", e));
+    let io_err = |e: std::io::Error| EngineError::Other(format!("zip extraction failed: {}", e));
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_err)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        let Some(rel) = entry.enclosed_name() else {
+            log::warn!("[n8n] Skipped unsafe archive entry {:?}", entry.name());
+            continue;
+        };
+        let target = dest.join(rel);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(io_err)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(io_err)?;
+            }
+            let mut out = std::fs::File::create(&target).map_err(io_err)?;
+            std::io::copy(&mut entry, &mut out).map_err(io_err)?;
+            #[cfg(unix)]
+            if let Some(mode) = entry.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(&target, std::fs::Permissions::from_mode(mode));
+            }
         }
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Fallback: use `unzip` command (shouldn't happen, but safety net)
-        let output = std::process::Command::new("unzip")
-            .args([
-                "-o",
-                &archive_path.to_string_lossy(),
-                "-d",
-                &dest.to_string_lossy(),
-            ])
-            .output()
-            .map_err(|e| EngineError::Other(format!("unzip failed: {}", e)))?;
-
-        let _ = std::fs::remove_file(&archive_path);
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(EngineError::Other(format!("unzip failed: {}", stderr)));
+        if (i + 1) % EXTRACT_PROGRESS_EVERY == 0 {
+            progress(i + 1);
         }
     }
-
     Ok(())
 }
 
@@ -464,6 +568,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn download_url_ends_with_archive_name() {
+        let name = archive_name();
+        assert!(download_url().ends_with(&format!("/{}", name)));
+        assert!(name.starts_with(&extracted_dir_name()));
+    }
+
+    #[test]
+    fn bundled_manifest_covers_every_archive() {
+        for (os, arch, ext) in [
+            ("darwin", "arm64", "tar.gz"),
+            ("darwin", "x64", "tar.gz"),
+            ("linux", "arm64", "tar.gz"),
+            ("linux", "x64", "tar.gz"),
+            ("win", "x64", "zip"),
+        ] {
+            let name = format!("node-v{}-{}-{}.{}", NODE_VERSION, os, arch, ext);
+            assert!(
+                lookup_sha256(BUNDLED_SHASUMS, &name).is_some(),
+                "node-shasums256.txt has no hash for {} — run scripts/update-node-shasums.sh",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_sha256_parses_shasums_format() {
+        let sha = "a".repeat(64);
+        let manifest = format!(
+            "# comment {sha}  node.tar.gz\n{sha}  node-v1-linux-x64.tar.gz\n{upper} *node-v1-win-x64.zip\nnot-a-hash  node-v1-darwin-x64.tar.gz\n",
+            sha = sha,
+            upper = "B".repeat(64),
+        );
+        assert_eq!(
+            lookup_sha256(&manifest, "node-v1-linux-x64.tar.gz"),
+            Some(sha)
+        );
+        assert_eq!(
+            lookup_sha256(&manifest, "node-v1-win-x64.zip"),
+            Some("b".repeat(64))
+        );
+        assert_eq!(lookup_sha256(&manifest, "node-v1-darwin-x64.tar.gz"), None);
+        assert_eq!(lookup_sha256(&manifest, "node.tar.gz"), None);
+    }
+
     #[test]
     fn extracted_dir_name_format() {
         let dir = extracted_dir_name();
@@ -5,7 +5,7 @@
 use super::health::poll_n8n_ready;
 use super::types::*;
 use crate::atoms::error::{EngineError, EngineResult};
-use crate::engine::key_vault;
+use crate::engine::{key_vault, processes};
 
 // ── Runtime check ──────────────────────────────────────────────────────
 
@@ -118,8 +118,8 @@ pub async fn start_n8n_process(app_handle: &tauri::AppHandle) -> EngineResult<N8
     let npx_cmd = resolve_npx();
     let enriched_path = path_with_local_node();
 
-    let child = std::process::Command::new(&npx_cmd)
-        .arg("--yes")
+    let mut cmd = std::process::Command::new(&npx_cmd);
+    cmd.arg("--yes")
         .arg("n8n@latest")
         .env("PATH", &enriched_path)
         .env("N8N_PORT", port.to_string())
@@ -139,23 +139,20 @@ pub async fn start_n8n_process(app_handle: &tauri::AppHandle) -> EngineResult<N8
         .env("N8N_REINSTALL_MISSING_PACKAGES", "true")
         .env("N8N_COMMUNITY_PACKAGES_ALLOW_TOOL_USAGE", "true")
         .stdout(stdout_sink)
-        .stderr(stderr_sink)
+        .stderr(stderr_sink);
+    let child = processes::bind_to_parent(&mut cmd)
         .spawn()
         .map_err(|e| EngineError::Other(format!("Failed to start npx n8n: {}", e)))?;
 
     let mut pid = child.id();
+    processes::track_service(pid, "n8n");
     let url = format!("http://127.0.0.1:{}", port);
 
     // Poll for readiness
     let ready = poll_n8n_ready(&url, &api_key).await;
     if !ready {
         // Try to kill the process since it didn't become ready
-        #[cfg(unix)]
-        {
-            let _ = std::process::Command::new("kill")
-                .arg(pid.to_string())
-                .status();
-        }
+        stop_process(pid);
 
         // Read tail of n8n log for diagnostics
         let tail = std::fs::read_to_string(&log_path)
@@ -234,8 +231,8 @@ pub async fn start_n8n_process(app_handle: &tauri::AppHandle) -> EngineResult<N8
                 .map(std::process::Stdio::from)
                 .unwrap_or_else(std::process::Stdio::null);
 
-            let child2 = std::process::Command::new(&npx_cmd)
-                .arg("--yes")
+            let mut cmd2 = std::process::Command::new(&npx_cmd);
+            cmd2.arg("--yes")
                 .arg("n8n@latest")
                 .env("PATH", &enriched_path)
                 .env("N8N_PORT", port.to_string())
@@ -252,12 +249,13 @@ pub async fn start_n8n_process(app_handle: &tauri::AppHandle) -> EngineResult<N8
                 .env("N8N_REINSTALL_MISSING_PACKAGES", "true")
                 .env("N8N_COMMUNITY_PACKAGES_ALLOW_TOOL_USAGE", "true")
                 .stdout(stdout2)
-                .stderr(stderr2)
-                .spawn();
+                .stderr(stderr2);
+            let child2 = processes::bind_to_parent(&mut cmd2).spawn();
 
             match child2 {
                 Ok(c2) => {
                     let pid2 = c2.id();
+                    processes::track_service(pid2, "n8n");
                     let ready2 = poll_n8n_ready(&url, &api_key).await;
 
                     // Restore package.json so the packages are visible again
@@ -397,6 +395,10 @@ pub async fn start_n8n_process(app_handle: &tauri::AppHandle) -> EngineResult<N8
 
 /// Kill a managed child process by PID.
 pub fn stop_process(pid: u32) {
+    // Registered children are stopped with their process group (npx → node)
+    if processes::kill(pid).is_ok() {
+        return;
+    }
     #[cfg(unix)]
     {
         let _ = std::process::Command::new("kill")
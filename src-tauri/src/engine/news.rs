@@ -0,0 +1,363 @@
+// Paw Agent Engine — News watches
+//
+// A watch is a named set of search queries on a task-style schedule
+// ("every 6h", "daily 08:00"). Each run, from the cron heartbeat or on
+// demand:
+//   1. searches every query through engine::search (the user's backend),
+//   2. drops results the watch has already seen (normalized URLs),
+//   3. hands the new ones to the watch's agent in its own channel session
+//      ("eng-news-<agent>-<watch>"), so it remembers earlier digests,
+//   4. delivers the digest through a bridge send tool and records it.
+//
+// Runs with nothing new produce no digest and cost no LLM call.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::search::{self, SearchResult};
+use crate::engine::state::EngineState;
+use crate::engine::types::{FunctionCall, NewsDigest, NewsWatch, ToolCall};
+use log::{error, info, warn};
+use std::collections::HashSet;
+use tauri::{Emitter, Manager};
+
+/// Results fetched per query.
+const RESULTS_PER_QUERY: usize = 10;
+/// New items summarized per digest; the rest wait for the next run.
+const MAX_DIGEST_ITEMS: usize = 30;
+
+/// Bridges a digest can be delivered to ("" keeps it in the app).
+pub const DELIVERY_TARGETS: &[&str] = &["", "telegram", "discord", "webhook"];
+
+/// Next run time for a watch schedule, in the user's timezone.
+pub fn next_run(state: &EngineState, schedule: &str) -> Option<String> {
+    let tz = state.config.lock().timezone();
+    crate::engine::tasks::compute_next_run_in(&Some(schedule.to_string()), &chrono::Utc::now(), &tz)
+}
+
+/// Dedupe key for a result URL: no fragment, tracking parameters or
+/// trailing slash, so the same story shared with different tracking tags
+/// is only reported once.
+pub fn normalize_url(raw: &str) -> String {
+    let Ok(mut url) = url::Url::parse(raw.trim()) else {
+        return raw.trim().trim_end_matches('/').to_lowercase();
+    };
+    url.set_fragment(None);
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| {
+            !k.starts_with("utm_") && !matches!(k.as_ref(), "fbclid" | "gclid" | "ref" | "mc_cid")
+        })
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    let host = url.host_str().unwrap_or("").trim_start_matches("www.");
+    format!(
+        "{}{}{}",
+        host,
+        url.path().trim_end_matches('/'),
+        url.query().map(|q| format!("?{}", q)).unwrap_or_default()
+    )
+}
+
+/// Search every query and keep the results the watch hasn't seen, deduped
+/// across queries. Fails only if every query failed.
+async fn collect_new_items(
+    state: &EngineState,
+    watch: &NewsWatch,
+) -> EngineResult<Vec<(String, SearchResult)>> {
+    let mut batch: Vec<(String, SearchResult)> = Vec::new();
+    let mut keys = HashSet::new();
+    let mut last_error = None;
+    for query in &watch.queries {
+        match search::search(&state.store, query, RESULTS_PER_QUERY).await {
+            Ok((results, _)) => {
+                for r in results {
+                    let key = normalize_url(&r.url);
+                    if !key.is_empty() && keys.insert(key.clone()) {
+                        batch.push((key, r));
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "[news] '{}': search for '{}' failed: {}",
+                    watch.name, query, e
+                );
+                last_error = Some(e);
+            }
+        }
+    }
+    if batch.is_empty() {
+        if let Some(e) = last_error {
+            return Err(e);
+        }
+    }
+    let keys: Vec<String> = batch.iter().map(|(k, _)| k.clone()).collect();
+    let unseen: HashSet<String> = state
+        .store
+        .news_unseen(&watch.id, &keys)?
+        .into_iter()
+        .collect();
+    batch.retain(|(k, _)| unseen.contains(k));
+    Ok(batch)
+}
+
+fn digest_prompt(watch: &NewsWatch, items: &[(String, SearchResult)]) -> String {
+    let mut prompt = format!(
+        "[News watch: {}]\nTopics: {}\n\n{} new result(s) since the last digest:\n\n",
+        watch.name,
+        watch.queries.join("; "),
+        items.len()
+    );
+    for (i, (_, r)) in items.iter().enumerate() {
+        prompt.push_str(&format!("{}. {}\n   {}\n", i + 1, r.title, r.url));
+        if let Some(date) = &r.published {
+            prompt.push_str(&format!("   Published: {}\n", date));
+        }
+        if !r.snippet.is_empty() {
+            prompt.push_str(&format!("   {}\n", r.snippet));
+        }
+    }
+    prompt.push_str(
+        "\nWrite a short digest of what is actually new: group related items, lead with \
+         the most important, skip anything that repeats earlier digests or is off-topic, \
+         and link each story once. Reply with the digest only — it is delivered as is.",
+    );
+    prompt
+}
+
+/// Send the digest to the watch's bridge. Returns the digest status.
+async fn deliver(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    watch: &NewsWatch,
+    text: &str,
+) -> EngineResult<&'static str> {
+    if watch.deliver_to.is_empty() {
+        return Ok("in_app");
+    }
+    send_to_bridge(
+        app_handle,
+        state,
+        &watch.agent_id,
+        &watch.deliver_to,
+        watch.deliver_target.as_deref(),
+        text,
+        serde_json::json!({ "watch": watch.name }),
+    )
+    .await?;
+    Ok("delivered")
+}
+
+/// Send `text` through a bridge's send tool, audited under `agent_id`.
+/// `bridge` is one of DELIVERY_TARGETS; `target` the chat / channel (empty
+/// or `None` = the bridge's default). The webhook gets `webhook_payload`
+/// with `text` added.
+pub async fn send_to_bridge(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    agent_id: &str,
+    bridge: &str,
+    target: Option<&str>,
+    text: &str,
+    mut webhook_payload: serde_json::Value,
+) -> EngineResult<()> {
+    let target = target.filter(|t| !t.is_empty());
+    let (tool, args) = match bridge {
+        "telegram" => {
+            let mut args = serde_json::json!({ "text": text });
+            match target.map(|t| (t, t.parse::<i64>())) {
+                Some((_, Ok(chat_id))) => args["chat_id"] = chat_id.into(),
+                Some((username, Err(_))) => args["username"] = username.into(),
+                None => {}
+            }
+            ("telegram_send", args)
+        }
+        "discord" => {
+            // Discord caps messages at 2000 characters
+            let mut args = serde_json::json!({
+                "content": crate::engine::types::truncate_utf8(text, 1990)
+            });
+            if let Some(channel_id) = target {
+                args["channel_id"] = channel_id.into();
+            }
+            ("discord_send_message", args)
+        }
+        "webhook" => {
+            webhook_payload["text"] = text.into();
+            (
+                "webhook_send",
+                serde_json::json!({ "payload": webhook_payload }),
+            )
+        }
+        other => return Err(format!("Unknown delivery target '{}'", other).into()),
+    };
+
+    let tc = ToolCall {
+        id: format!("bridge-{}", uuid::Uuid::new_v4()),
+        call_type: "function".into(),
+        function: FunctionCall {
+            name: tool.into(),
+            arguments: args.to_string(),
+        },
+        thought_signature: None,
+        thought_parts: vec![],
+    };
+    let result = crate::engine::tools::execute_tool(&tc, app_handle, agent_id).await;
+    crate::engine::audit::log_tool_call(
+        &state.store,
+        agent_id,
+        "",
+        tool,
+        &tc.id,
+        &tc.function.arguments,
+        result.success,
+        &result.output,
+    );
+    if result.success {
+        Ok(())
+    } else {
+        Err(result.output.into())
+    }
+}
+
+/// Run one watch now. Returns `None` when nothing new turned up.
+pub async fn run_watch(
+    app_handle: &tauri::AppHandle,
+    watch: &NewsWatch,
+) -> EngineResult<Option<NewsDigest>> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+    if watch.queries.is_empty() {
+        return Err(format!("News watch '{}' has no queries", watch.name).into());
+    }
+
+    let mut items = collect_new_items(&state, watch).await?;
+    if items.is_empty() {
+        info!("[news] '{}': nothing new", watch.name);
+        return Ok(None);
+    }
+    items.truncate(MAX_DIGEST_ITEMS);
+
+    let ctx = "You are writing a scheduled news digest. No one is chatting with you — \
+               the reply is delivered as a message, so keep it skimmable plain markdown.";
+    let summary = crate::engine::channels::run_channel_agent(
+        app_handle,
+        "news",
+        ctx,
+        &digest_prompt(watch, &items),
+        &watch.id,
+        &watch.agent_id,
+        false,
+    )
+    .await?;
+    if summary.trim().is_empty() {
+        return Err(format!("Agent returned an empty digest for '{}'", watch.name).into());
+    }
+
+    // Only now are the items "seen": a failed summary retries them next run
+    let seen: Vec<(String, String)> = items
+        .iter()
+        .map(|(k, r)| (k.clone(), r.title.clone()))
+        .collect();
+    state.store.news_mark_seen(&watch.id, &seen)?;
+
+    let (status, delivery_error) = match deliver(app_handle, &state, watch, &summary).await {
+        Ok(status) => (status, None),
+        Err(e) => {
+            warn!("[news] '{}': delivery failed: {}", watch.name, e);
+            ("failed", Some(e.to_string()))
+        }
+    };
+    let digest = state.store.news_digest_add(
+        &watch.id,
+        items.len(),
+        &summary,
+        status,
+        delivery_error.as_deref(),
+    )?;
+    info!(
+        "[news] '{}': digest of {} item(s), {}",
+        watch.name,
+        items.len(),
+        status
+    );
+    app_handle
+        .emit(
+            "news-digest",
+            serde_json::json!({
+                "watch_id": watch.id,
+                "name": watch.name,
+                "item_count": digest.item_count,
+                "status": digest.status,
+            }),
+        )
+        .ok();
+    Ok(Some(digest))
+}
+
+/// Cron heartbeat hook: start every due watch. The next run is booked
+/// before the watch starts so a slow run is never picked up twice.
+pub async fn run_due_watches(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<EngineState>();
+    let due = match state.store.news_watch_due() {
+        Ok(w) => w,
+        Err(e) => {
+            warn!("[news] Failed to query due watches: {}", e);
+            return;
+        }
+    };
+
+    for watch in due {
+        let now = chrono::Utc::now().to_rfc3339();
+        let next = next_run(&state, &watch.schedule);
+        if let Err(e) = state
+            .store
+            .news_watch_mark_run(&watch.id, &now, next.as_deref())
+        {
+            error!("[news] Failed to book next run for '{}': {}", watch.name, e);
+            continue;
+        }
+
+        let allowed = crate::engine::autonomy::load(&state.store, &watch.agent_id)
+            .map(|level| level.policy().allow_triggers)
+            .unwrap_or(true);
+        if !allowed {
+            info!(
+                "[news] Skipping '{}' — agent '{}' autonomy level disallows unattended runs",
+                watch.name, watch.agent_id
+            );
+            continue;
+        }
+
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = run_watch(&app, &watch).await {
+                error!("[news] '{}' failed: {}", watch.name, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_url_drops_tracking() {
+        assert_eq!(
+            normalize_url("https://www.example.com/story/?utm_source=x&id=7#comments"),
+            "example.com/story?id=7"
+        );
+        assert_eq!(
+            normalize_url("http://example.com/story"),
+            normalize_url("https://example.com/story/?fbclid=abc")
+        );
+        // DuckDuckGo's HTML results carry a bare display URL
+        assert_eq!(normalize_url("Example.com/Story/"), "example.com/story");
+    }
+}
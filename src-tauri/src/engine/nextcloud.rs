@@ -3,6 +3,11 @@
 // Connects Paw to Nextcloud Talk via HTTP long-polling.
 // Pure outbound HTTP — no webhooks, no public URL.
 //
+// Files: files shared into a conversation are downloaded over WebDAV and
+// ingested (channels::attachments); files the agent attaches with
+// `[attach: path]` are uploaded to the bot's `Talk/` folder and shared into
+// the conversation.
+//
 // Setup: Nextcloud → Settings → Security → Create App Password.
 //        Give a server URL (e.g. "https://cloud.example.com"),
 //        username, and the app password.
@@ -14,6 +19,7 @@
 //   - Basic auth over TLS
 
 use crate::atoms::error::EngineResult;
+use crate::engine::channels::attachments::{self, OutboundFile, MAX_INBOUND_BYTES};
 use crate::engine::channels::{self, ChannelStatus, PendingUser};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
@@ -146,10 +152,15 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
     info!("[nextcloud] Starting bridge to {}", config.server_url);
 
     tauri::async_runtime::spawn(async move {
-        if let Err(e) = run_poll_loop(app_handle, config).await {
-            error!("[nextcloud] Bridge crashed: {}", e);
-        }
+        let error = run_poll_loop(app_handle.clone(), config)
+            .await
+            .err()
+            .map(|e| {
+                error!("[nextcloud] Bridge crashed: {}", e);
+                e.to_string()
+            });
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "nextcloud", error.as_deref());
         info!("[nextcloud] Bridge stopped");
     });
 
@@ -175,6 +186,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("nextcloud"),
     }
 }
 
@@ -214,6 +226,7 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
 
     info!("[nextcloud] Authenticated as {}", bot_user);
 
+    channels::mark_connected(&app_handle, "nextcloud");
     let _ = app_handle.emit(
         "nextcloud-status",
         json!({
@@ -314,7 +327,16 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
                 let actor_id = msg["actorId"].as_str().unwrap_or("");
                 let actor_name = msg["actorDisplayName"].as_str().unwrap_or(actor_id);
                 let message_type = msg["messageType"].as_str().unwrap_or("");
-                let text = msg["message"].as_str().unwrap_or("").to_string();
+                // A shared file arrives as `{file}` (or a caption) plus a
+                // `file` message parameter
+                let shared_file = msg["messageParameters"]["file"]
+                    .as_object()
+                    .filter(|f| f.get("type").and_then(|t| t.as_str()) == Some("file"))
+                    .map(|_| &msg["messageParameters"]["file"]);
+                let text = match msg["message"].as_str().unwrap_or("") {
+                    "{file}" if shared_file.is_some() => String::new(),
+                    other => other.to_string(),
+                };
 
                 // Skip system messages, own messages, bot messages
                 if message_type == "system" {
@@ -326,7 +348,7 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
                 if actor_id == bot_user {
                     continue;
                 }
-                if text.is_empty() {
+                if text.is_empty() && shared_file.is_none() {
                     continue;
                 }
 
@@ -362,6 +384,7 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
                                 "username": actor_name,
                             }),
                         );
+                        channels::notify_pairing(&app_handle, "Nextcloud Talk", actor_name);
                         let _ = nc_send_message(
                             &client,
                             base,
@@ -378,15 +401,43 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
                 MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
 
                 let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
-                let ctx =
+                let ctx = format!(
                     "You are chatting via Nextcloud Talk. Use plain text or simple markdown. \
-                           Keep responses concise.";
+                     Keep responses concise. {}",
+                    attachments::ATTACH_HINT
+                );
+
+                let content = match shared_file {
+                    Some(file) => {
+                        let name = file["name"].as_str().unwrap_or("file");
+                        let note = match nc_download_file(
+                            &client,
+                            base,
+                            &config.username,
+                            &config.password,
+                            file,
+                        )
+                        .await
+                        {
+                            Ok(bytes) => {
+                                attachments::ingest_bytes(&app_handle, agent_id, name, &bytes).await
+                            }
+                            Err(e) => Err(e),
+                        }
+                        .unwrap_or_else(|e| {
+                            warn!("[nextcloud] Could not ingest {}: {}", name, e);
+                            format!("[Shared file {} could not be read: {}]", name, e)
+                        });
+                        attachments::with_file_notes(&text, &[note])
+                    }
+                    None => text,
+                };
 
                 let response = channels::run_channel_agent(
                     &app_handle,
                     "nextcloud",
-                    ctx,
-                    &text,
+                    &ctx,
+                    &content,
                     actor_id,
                     agent_id,
                     current_config.allow_dangerous_tools,
@@ -395,8 +446,12 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
 
                 match response {
                     Ok(reply) if !reply.is_empty() => {
+                        let (reply, files) = attachments::take_attachments(&reply, agent_id);
                         // Nextcloud Talk max message length is 32000 chars
                         for chunk in channels::split_message(&reply, 32000) {
+                            if chunk.is_empty() {
+                                continue;
+                            }
                             let _ = nc_send_message(
                                 &client,
                                 base,
@@ -407,6 +462,20 @@ async fn run_poll_loop(app_handle: tauri::AppHandle, config: NextcloudConfig) ->
                             )
                             .await;
                         }
+                        for file in files {
+                            if let Err(e) = nc_share_file(
+                                &client,
+                                base,
+                                &config.username,
+                                &config.password,
+                                &token,
+                                file,
+                            )
+                            .await
+                            {
+                                warn!("[nextcloud] Sharing file failed: {}", e);
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("[nextcloud] Agent error for {}: {}", actor_id, e);
@@ -511,6 +580,110 @@ async fn nc_send_message(
     Ok(())
 }
 
+/// WebDAV URL for a path in the bot user's files.
+fn dav_url(base: &str, username: &str, path: &str) -> String {
+    let encoded: Vec<String> = path
+        .trim_start_matches('/')
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect();
+    format!(
+        "{}/remote.php/dav/files/{}/{}",
+        base,
+        urlencoding::encode(username),
+        encoded.join("/")
+    )
+}
+
+/// Download a file shared into a conversation. The `path` message
+/// parameter is relative to the bot's own files (shares land in `Talk/`).
+async fn nc_download_file(
+    client: &reqwest::Client,
+    base: &str,
+    username: &str,
+    password: &str,
+    file: &serde_json::Value,
+) -> EngineResult<Vec<u8>> {
+    let size = file["size"]
+        .as_u64()
+        .or_else(|| file["size"].as_str().and_then(|s| s.parse().ok()))
+        .unwrap_or(0);
+    if size > MAX_INBOUND_BYTES {
+        return Err(format!("larger than {} MB", MAX_INBOUND_BYTES / 1024 / 1024).into());
+    }
+    let path = file["path"]
+        .as_str()
+        .ok_or("the share has no path for this account")?;
+    let resp = client
+        .get(dav_url(base, username, path))
+        .basic_auth(username, Some(password))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("download failed with HTTP {}", resp.status()).into());
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+/// Upload a file to the bot's `Talk/` folder and share it into the
+/// conversation, which posts it as a file message.
+async fn nc_share_file(
+    client: &reqwest::Client,
+    base: &str,
+    username: &str,
+    password: &str,
+    room_token: &str,
+    file: OutboundFile,
+) -> EngineResult<()> {
+    // MKCOL answers 405 when the folder already exists
+    if let Ok(mkcol) = reqwest::Method::from_bytes(b"MKCOL") {
+        let _ = client
+            .request(mkcol, dav_url(base, username, "Talk"))
+            .basic_auth(username, Some(password))
+            .send()
+            .await;
+    }
+
+    // Timestamped so a new version never replaces a file shared earlier
+    let name = format!(
+        "{}-{}",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        file.name
+    );
+    let path = format!("Talk/{}", name);
+    let resp = client
+        .put(dav_url(base, username, &path))
+        .basic_auth(username, Some(password))
+        .header("Content-Type", file.mime_type)
+        .body(file.bytes)
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("upload of {} failed with HTTP {}", name, resp.status()).into());
+    }
+
+    let resp = client
+        .post(format!(
+            "{}/ocs/v2.php/apps/files_sharing/api/v1/shares",
+            base
+        ))
+        .basic_auth(username, Some(password))
+        .header("OCS-APIREQUEST", "true")
+        .header("Accept", "application/json")
+        .form(&[
+            ("shareType", "10"),
+            ("shareWith", room_token),
+            ("path", &format!("/{}", path)),
+        ])
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("sharing {} failed with HTTP {}", name, resp.status()).into());
+    }
+    info!("[nextcloud] Shared {} into {}", name, room_token);
+    Ok(())
+}
+
 // ── Config Persistence ─────────────────────────────────────────────────
 
 pub fn load_config(app_handle: &tauri::AppHandle) -> EngineResult<NextcloudConfig> {
@@ -1,7 +1,9 @@
 // Paw Agent Engine — Nostr Cryptography
 //
-// Event signing (secp256k1 Schnorr / BIP-340), NIP-04 encrypted DMs
-// (ECDH + AES-256-CBC), pubkey derivation, and hex utilities.
+// Event signing and verification (secp256k1 Schnorr / BIP-340), NIP-04
+// encrypted DMs (ECDH + AES-256-CBC), NIP-44 v2 encrypted payloads
+// (ECDH + HKDF + ChaCha20 + HMAC-SHA256), pubkey derivation, and hex
+// utilities.
 
 use crate::atoms::error::{EngineError, EngineResult};
 use serde_json::json;
@@ -20,18 +22,23 @@ pub(crate) fn sign_event(
     tags: &serde_json::Value,
     content: &str,
 ) -> EngineResult<serde_json::Value> {
-    use k256::schnorr::SigningKey;
-    use sha2::{Digest, Sha256};
-
     let created_at = chrono::Utc::now().timestamp();
+    sign_event_at(secret_key, pubkey_hex, created_at, kind, tags, content)
+}
 
-    // Serialize for id computation: [0, pubkey, created_at, kind, tags, content]
-    let serialized = json!([0, pubkey_hex, created_at, kind, tags, content]);
-    let serialized_str = serde_json::to_string(&serialized)?;
+/// `sign_event` with an explicit `created_at` (NIP-59 seals and gift wraps
+/// backdate theirs).
+pub(crate) fn sign_event_at(
+    secret_key: &[u8],
+    pubkey_hex: &str,
+    created_at: i64,
+    kind: u64,
+    tags: &serde_json::Value,
+    content: &str,
+) -> EngineResult<serde_json::Value> {
+    use k256::schnorr::SigningKey;
 
-    let mut hasher = Sha256::new();
-    hasher.update(serialized_str.as_bytes());
-    let id_bytes = hasher.finalize();
+    let id_bytes = event_id(pubkey_hex, created_at, kind, tags, content)?;
     let id_hex = hex_encode(&id_bytes);
 
     // BIP-340 Schnorr signature over the event id
@@ -55,6 +62,50 @@ pub(crate) fn sign_event(
     }))
 }
 
+/// NIP-01 event id: sha256 of `[0, pubkey, created_at, kind, tags, content]`.
+pub(crate) fn event_id(
+    pubkey_hex: &str,
+    created_at: i64,
+    kind: u64,
+    tags: &serde_json::Value,
+    content: &str,
+) -> EngineResult<[u8; 32]> {
+    use sha2::{Digest, Sha256};
+
+    let serialized = json!([0, pubkey_hex, created_at, kind, tags, content]);
+    let serialized_str = serde_json::to_string(&serialized)?;
+    Ok(Sha256::digest(serialized_str.as_bytes()).into())
+}
+
+/// Check that a signed event's id matches its fields and that `sig` is a
+/// valid BIP-340 signature of it by `pubkey`.
+pub(crate) fn verify_event(event: &serde_json::Value) -> EngineResult<()> {
+    use k256::schnorr::{Signature, VerifyingKey};
+
+    let field = |name: &str| {
+        event[name]
+            .as_str()
+            .ok_or_else(|| EngineError::Other(format!("Event is missing '{}'", name)))
+    };
+    let pubkey_hex = field("pubkey")?;
+    let id = event_id(
+        pubkey_hex,
+        event["created_at"].as_i64().unwrap_or_default(),
+        event["kind"].as_u64().unwrap_or_default(),
+        &event["tags"],
+        field("content")?,
+    )?;
+    if hex_encode(&id) != field("id")? {
+        return Err("Event id does not match its contents".into());
+    }
+    let key = VerifyingKey::from_bytes(&hex_decode(pubkey_hex)?)
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    let sig = Signature::try_from(hex_decode(field("sig")?)?.as_slice())
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    key.verify_raw(&id, &sig)
+        .map_err(|_| EngineError::Other("Invalid event signature".into()))
+}
+
 /// Build a kind-1 public reply event (NIP-01).
 pub(crate) fn build_reply_event(
     secret_key: &[u8],
@@ -74,9 +125,9 @@ pub(crate) fn build_reply_event(
 //   2. AES-256-CBC encrypt with random 16-byte IV and PKCS#7 padding
 //   3. Content format: base64(ciphertext) + "?iv=" + base64(iv)
 //
-// Note: NIP-04 is deprecated in favor of NIP-44 (ChaCha20 + HMAC-SHA256)
-// with NIP-17 gift wrapping. Kind-4 DMs remain widely supported by
-// clients (Damus, Amethyst, Primal, etc.).
+// Note: NIP-04 is deprecated in favor of NIP-44 (below) with NIP-17 gift
+// wrapping. Kind-4 DMs are still what many clients send, so the bridge
+// keeps answering them for counterparts not known to support NIP-17.
 
 /// Compute ECDH shared secret (x-coordinate) between our secret key and a pubkey.
 /// Returns a Zeroizing wrapper to ensure the secret is wiped from memory.
@@ -109,9 +160,8 @@ fn compute_shared_secret(
 ///
 /// # Security Warning
 /// NIP-04 is **deprecated** — it uses AES-256-CBC without authentication (no HMAC),
-/// making it vulnerable to padding oracle attacks. Migrate to NIP-44
-/// (XChaCha20 + HMAC-SHA256) with NIP-17 gift wrapping when relay support is
-/// sufficient. See: <https://github.com/nostr-protocol/nips/blob/master/44.md>
+/// making it vulnerable to padding oracle attacks. Only used as the fallback
+/// for counterparts that haven't shown NIP-17 support; see `nip44_encrypt`.
 pub(crate) fn nip04_encrypt(
     secret_key: &[u8],
     receiver_pk_hex: &str,
@@ -177,6 +227,169 @@ pub(crate) fn nip04_decrypt(
     String::from_utf8(plaintext.to_vec()).map_err(|e| EngineError::Other(e.to_string()))
 }
 
+// ── NIP-44 v2 Encrypted Payloads ──────────────────────────────────────
+//
+// NIP-44 v2 (used by NIP-17 DMs and NIP-59 seals / gift wraps):
+//   1. conversation_key = HKDF-extract(salt = "nip44-v2", ikm = ECDH x-coordinate)
+//   2. per message: random 32-byte nonce; HKDF-expand(conversation_key, nonce, 76)
+//      → chacha_key (32) ‖ chacha_nonce (12) ‖ hmac_key (32)
+//   3. pad: u16 BE length ‖ plaintext ‖ zeros up to calc_padded_len
+//   4. ciphertext = ChaCha20(padded); mac = HMAC-SHA256(hmac_key, nonce ‖ ciphertext)
+//   5. payload = base64(0x02 ‖ nonce ‖ ciphertext ‖ mac)
+// See: <https://github.com/nostr-protocol/nips/blob/master/44.md>
+
+const NIP44_VERSION: u8 = 2;
+const NIP44_MAX_PLAINTEXT: usize = 65_535;
+
+/// NIP-44 conversation key between our secret key and a pubkey. Symmetric:
+/// both sides of a conversation derive the same key.
+fn nip44_conversation_key(
+    secret_key: &[u8],
+    pubkey_hex: &str,
+) -> EngineResult<zeroize::Zeroizing<[u8; 32]>> {
+    let shared = compute_shared_secret(secret_key, pubkey_hex)?;
+    let (prk, _) = hkdf::Hkdf::<sha2::Sha256>::extract(Some(b"nip44-v2"), shared.as_ref());
+    let mut key = zeroize::Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&prk);
+    Ok(key)
+}
+
+/// ChaCha20 key, ChaCha20 nonce and HMAC key for one message.
+fn nip44_message_keys(
+    conversation_key: &[u8; 32],
+    nonce: &[u8; 32],
+) -> EngineResult<zeroize::Zeroizing<[u8; 76]>> {
+    let hk = hkdf::Hkdf::<sha2::Sha256>::from_prk(conversation_key)
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    let mut okm = zeroize::Zeroizing::new([0u8; 76]);
+    hk.expand(nonce, okm.as_mut())
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    Ok(okm)
+}
+
+/// Padded plaintext length: 32 bytes minimum, then power-of-two-ish
+/// buckets so ciphertext length leaks little about message length.
+fn nip44_padded_len(len: usize) -> usize {
+    if len <= 32 {
+        return 32;
+    }
+    let next_power = 1usize << (usize::BITS - (len - 1).leading_zeros());
+    let chunk = if next_power <= 256 {
+        32
+    } else {
+        next_power / 8
+    };
+    chunk * ((len - 1) / chunk + 1)
+}
+
+fn nip44_hmac(hmac_key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> EngineResult<Vec<u8>> {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(hmac_key)
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    mac.update(nonce);
+    mac.update(ciphertext);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn nip44_chacha(keys: &[u8; 76], buf: &mut [u8]) {
+    use chacha20::cipher::{KeyIvInit, StreamCipher};
+
+    let mut cipher = chacha20::ChaCha20::new(keys[..32].into(), keys[32..44].into());
+    cipher.apply_keystream(buf);
+}
+
+/// NIP-44 v2 encrypt `plaintext` for `receiver_pk_hex`.
+pub(crate) fn nip44_encrypt(
+    secret_key: &[u8],
+    receiver_pk_hex: &str,
+    plaintext: &str,
+) -> EngineResult<String> {
+    let mut nonce = [0u8; 32];
+    getrandom::getrandom(&mut nonce).expect("OS CSPRNG failed");
+    let conversation_key = nip44_conversation_key(secret_key, receiver_pk_hex)?;
+    nip44_encrypt_with(&conversation_key, &nonce, plaintext)
+}
+
+fn nip44_encrypt_with(
+    conversation_key: &[u8; 32],
+    nonce: &[u8; 32],
+    plaintext: &str,
+) -> EngineResult<String> {
+    use base64::Engine;
+
+    let pt = plaintext.as_bytes();
+    if pt.is_empty() || pt.len() > NIP44_MAX_PLAINTEXT {
+        return Err(format!(
+            "NIP-44 plaintext must be 1–{} bytes (got {})",
+            NIP44_MAX_PLAINTEXT,
+            pt.len()
+        )
+        .into());
+    }
+    let keys = nip44_message_keys(conversation_key, nonce)?;
+
+    let mut buf = vec![0u8; 2 + nip44_padded_len(pt.len())];
+    buf[..2].copy_from_slice(&(pt.len() as u16).to_be_bytes());
+    buf[2..2 + pt.len()].copy_from_slice(pt);
+    nip44_chacha(&keys, &mut buf);
+    let mac = nip44_hmac(&keys[44..], nonce, &buf)?;
+
+    let mut payload = Vec::with_capacity(1 + 32 + buf.len() + 32);
+    payload.push(NIP44_VERSION);
+    payload.extend_from_slice(nonce);
+    payload.extend_from_slice(&buf);
+    payload.extend_from_slice(&mac);
+    Ok(base64::engine::general_purpose::STANDARD.encode(payload))
+}
+
+/// NIP-44 v2 decrypt a payload from `sender_pk_hex`.
+pub(crate) fn nip44_decrypt(
+    secret_key: &[u8],
+    sender_pk_hex: &str,
+    payload: &str,
+) -> EngineResult<String> {
+    let conversation_key = nip44_conversation_key(secret_key, sender_pk_hex)?;
+    nip44_decrypt_with(&conversation_key, payload)
+}
+
+fn nip44_decrypt_with(conversation_key: &[u8; 32], payload: &str) -> EngineResult<String> {
+    use base64::Engine;
+    use subtle::ConstantTimeEq;
+
+    if payload.starts_with('#') {
+        return Err("Unsupported NIP-44 encryption version".into());
+    }
+    if !(132..=87_472).contains(&payload.len()) {
+        return Err(format!("Invalid NIP-44 payload length: {}", payload.len()).into());
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| EngineError::Other(e.to_string()))?;
+    if !(99..=65_603).contains(&data.len()) {
+        return Err(format!("Invalid NIP-44 data length: {}", data.len()).into());
+    }
+    if data[0] != NIP44_VERSION {
+        return Err(format!("Unsupported NIP-44 version: {}", data[0]).into());
+    }
+    let nonce: [u8; 32] = data[1..33].try_into().expect("length checked above");
+    let (ciphertext, mac) = data[33..].split_at(data.len() - 33 - 32);
+
+    let keys = nip44_message_keys(conversation_key, &nonce)?;
+    let expected = nip44_hmac(&keys[44..], &nonce, ciphertext)?;
+    if !bool::from(expected.ct_eq(mac)) {
+        return Err("NIP-44 MAC mismatch".into());
+    }
+
+    let mut padded = ciphertext.to_vec();
+    nip44_chacha(&keys, &mut padded);
+    let len = u16::from_be_bytes([padded[0], padded[1]]) as usize;
+    if len == 0 || padded.len() != 2 + nip44_padded_len(len) {
+        return Err("Invalid NIP-44 padding".into());
+    }
+    String::from_utf8(padded[2..2 + len].to_vec()).map_err(|e| EngineError::Other(e.to_string()))
+}
+
 // ── secp256k1 Pubkey Derivation (BIP-340 x-only) ──────────────────────
 //
 // Nostr uses the x-coordinate of the secp256k1 public key (BIP-340).
@@ -198,6 +411,126 @@ pub(crate) fn derive_pubkey(secret_key: &[u8]) -> EngineResult<Vec<u8>> {
     Ok(compressed[1..].to_vec())
 }
 
+// ── NIP-19 bech32 (npub / note) ───────────────────────────────────────
+//
+// Plain bech32 (BIP-173) of the raw 32 bytes with an `npub` / `note`
+// prefix. TLV forms (nprofile, nevent) aren't needed by the tools.
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ff_ffff) << 5) ^ u32::from(*v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+/// Regroup bits (8 → 5 with padding when encoding, 5 → 8 without).
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::new();
+    for &value in data {
+        acc = (acc << from) | u32::from(value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+/// Encode bytes as NIP-19 bech32 (`npub1…`, `note1…`).
+pub(crate) fn bech32_encode(hrp: &str, bytes: &[u8]) -> String {
+    let data = convert_bits(bytes, 8, 5, true).unwrap_or_default();
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0; 6]);
+    let checksum = bech32_polymod(&values) ^ 1;
+    let mut out = format!("{}1", hrp);
+    for v in data
+        .iter()
+        .copied()
+        .chain((0..6).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8))
+    {
+        out.push(BECH32_CHARSET[v as usize] as char);
+    }
+    out
+}
+
+/// Decode NIP-19 bech32 into `(hrp, bytes)`.
+pub(crate) fn bech32_decode(s: &str) -> EngineResult<(String, Vec<u8>)> {
+    let s = s.trim().to_lowercase();
+    let (hrp, data) = s
+        .rsplit_once('1')
+        .ok_or_else(|| EngineError::Other(format!("Not a bech32 string: {}", s)))?;
+    if hrp.is_empty() || data.len() < 6 {
+        return Err(format!("Not a bech32 string: {}", s).into());
+    }
+    let values = data
+        .bytes()
+        .map(|c| {
+            BECH32_CHARSET
+                .iter()
+                .position(|&x| x == c)
+                .map(|p| p as u8)
+                .ok_or_else(|| {
+                    EngineError::Other(format!("Invalid bech32 character '{}'", c as char))
+                })
+        })
+        .collect::<EngineResult<Vec<u8>>>()?;
+    let mut check = bech32_hrp_expand(hrp);
+    check.extend_from_slice(&values);
+    if bech32_polymod(&check) != 1 {
+        return Err(format!("Invalid bech32 checksum: {}", s).into());
+    }
+    let bytes = convert_bits(&values[..values.len() - 6], 5, 8, false)
+        .ok_or_else(|| EngineError::Other(format!("Invalid bech32 padding: {}", s)))?;
+    Ok((hrp.to_string(), bytes))
+}
+
+/// Accept a 64-char hex id/pubkey, or its NIP-19 form with the expected
+/// prefix (`npub` / `note`), optionally behind a `nostr:` URI scheme.
+pub(crate) fn parse_hex_or_bech32(input: &str, expected_hrp: &str) -> EngineResult<String> {
+    let input = input.trim().trim_start_matches("nostr:");
+    if input.len() == 64 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(input.to_lowercase());
+    }
+    let (hrp, bytes) = bech32_decode(input)?;
+    if hrp != expected_hrp || bytes.len() != 32 {
+        return Err(format!(
+            "Expected a hex key or {}1… identifier, got '{}'",
+            expected_hrp, input
+        )
+        .into());
+    }
+    Ok(hex_encode(&bytes))
+}
+
 // ── Hex Utils ──────────────────────────────────────────────────────────
 
 pub(crate) fn hex_decode(hex: &str) -> EngineResult<Vec<u8>> {
@@ -284,6 +617,98 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn verify_event_accepts_signed_and_rejects_tampered() {
+        let sk = test_secret_key();
+        let pk_hex = hex_encode(&derive_pubkey(&sk).unwrap());
+        let mut event =
+            sign_event_at(&sk, &pk_hex, 1_700_000_000, 13, &serde_json::json!([]), "x").unwrap();
+        assert!(verify_event(&event).is_ok());
+        event["content"] = serde_json::json!("y");
+        assert!(verify_event(&event).is_err());
+    }
+
+    #[test]
+    fn nip44_padded_len_buckets() {
+        for (len, padded) in [
+            (1, 32),
+            (32, 32),
+            (33, 64),
+            (37, 64),
+            (64, 64),
+            (65, 96),
+            (100, 128),
+            (256, 256),
+            (257, 320),
+            (1000, 1024),
+            (65_535, 65_536),
+        ] {
+            assert_eq!(nip44_padded_len(len), padded, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn nip44_matches_spec_vector() {
+        // First encrypt_decrypt vector from nip44.vectors.json
+        let mut sec1 = [0u8; 32];
+        sec1[31] = 1;
+        let mut sec2 = [0u8; 32];
+        sec2[31] = 2;
+        let pub2 = hex_encode(&derive_pubkey(&sec2).unwrap());
+        let conversation_key = nip44_conversation_key(&sec1, &pub2).unwrap();
+        assert_eq!(
+            hex_encode(conversation_key.as_ref()),
+            "c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"
+        );
+        let mut nonce = [0u8; 32];
+        nonce[31] = 1;
+        let payload = nip44_encrypt_with(&conversation_key, &nonce, "a").unwrap();
+        assert_eq!(
+            payload,
+            "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb"
+        );
+        assert_eq!(
+            nip44_decrypt_with(&conversation_key, &payload).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn nip44_roundtrip_and_tamper_detection() {
+        let sk1 = test_secret_key();
+        let sk2 =
+            hex_decode("0b1c4c1a5e0c3d5e7f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e1f0a2b4c6d8e").unwrap();
+        let pk1_hex = hex_encode(&derive_pubkey(&sk1).unwrap());
+        let pk2_hex = hex_encode(&derive_pubkey(&sk2).unwrap());
+
+        let plaintext = "Gift-wrapped hello 🎁";
+        let payload = nip44_encrypt(&sk1, &pk2_hex, plaintext).unwrap();
+        assert_eq!(nip44_decrypt(&sk2, &pk1_hex, &payload).unwrap(), plaintext);
+
+        use base64::Engine;
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let mut data = b64.decode(&payload).unwrap();
+        data[40] ^= 1;
+        assert!(nip44_decrypt(&sk2, &pk1_hex, &b64.encode(data)).is_err());
+        assert!(nip44_encrypt(&sk1, &pk2_hex, "").is_err());
+    }
+
+    #[test]
+    fn bech32_matches_nip19_example() {
+        // Example from NIP-19
+        let hex = "3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d";
+        let npub = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+        assert_eq!(bech32_encode("npub", &hex_decode(hex).unwrap()), npub);
+        assert_eq!(parse_hex_or_bech32(npub, "npub").unwrap(), hex);
+        assert_eq!(
+            parse_hex_or_bech32(&format!("nostr:{}", npub), "npub").unwrap(),
+            hex
+        );
+        assert_eq!(parse_hex_or_bech32(hex, "note").unwrap(), hex);
+        assert!(parse_hex_or_bech32(npub, "note").is_err());
+        assert!(parse_hex_or_bech32(&npub.replace('w', "q"), "npub").is_err());
+    }
+
     #[test]
     fn hex_encode_decode_roundtrip() {
         let original = vec![0xde, 0xad, 0xbe, 0xef];
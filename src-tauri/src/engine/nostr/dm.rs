@@ -0,0 +1,266 @@
+// Paw Agent Engine — Nostr Direct Messages (NIP-17 / NIP-59 / NIP-04)
+//
+// NIP-17 private DMs are three nested events:
+//   rumor     kind 14, unsigned, the actual message (sender pubkey + text)
+//   seal      kind 13, signed by the sender, NIP-44(rumor) for the receiver
+//   gift wrap kind 1059, signed by a throwaway key, NIP-44(seal), `p`-tagged
+//             with the receiver — relays only ever see the wrap
+// Seals and wraps carry randomized past timestamps so relays can't
+// correlate timing either.
+//
+// Not every client reads NIP-17 yet, so replies go out the way the
+// counterpart last wrote to us — and as NIP-17 once they are known to
+// support it (they sent one, or published a kind-10050 DM relay list).
+// Anything that can't be gift-wrapped falls back to a NIP-04 kind-4 DM.
+
+use super::crypto::{
+    derive_pubkey, event_id, hex_encode, nip04_encrypt, nip44_decrypt, nip44_encrypt, sign_event,
+    sign_event_at, verify_event,
+};
+use crate::atoms::error::EngineResult;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+pub(crate) const KIND_NIP04_DM: u64 = 4;
+pub(crate) const KIND_SEAL: u64 = 13;
+pub(crate) const KIND_PRIVATE_DM: u64 = 14;
+pub(crate) const KIND_GIFT_WRAP: u64 = 1059;
+/// NIP-17 "DM relay list" — publishing one advertises NIP-17 support.
+pub(crate) const KIND_DM_RELAYS: u64 = 10050;
+
+/// Seal / wrap timestamps are pushed up to this far into the past.
+pub(crate) const TIMESTAMP_TWEAK_SECS: i64 = 2 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DmProtocol {
+    /// Kind-4, NIP-04 encrypted.
+    Nip04,
+    /// Kind-14 rumor, sealed and gift-wrapped (NIP-17 / NIP-59 / NIP-44).
+    Nip17,
+}
+
+/// Counterparts known to read NIP-17, learned while the app runs.
+static NIP17_CAPABLE: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Remember that `pubkey_hex` reads NIP-17 DMs.
+pub(crate) fn mark_nip17_capable(pubkey_hex: &str) {
+    NIP17_CAPABLE.lock().insert(pubkey_hex.to_string(), true);
+}
+
+/// Whether we have already learned anything about `pubkey_hex`.
+pub(crate) fn capability_known(pubkey_hex: &str) -> bool {
+    NIP17_CAPABLE.lock().contains_key(pubkey_hex)
+}
+
+/// Record that we asked about `pubkey_hex` so the kind-10050 lookup is only
+/// sent once; a later `mark_nip17_capable` still upgrades them.
+pub(crate) fn mark_capability_queried(pubkey_hex: &str) {
+    NIP17_CAPABLE
+        .lock()
+        .entry(pubkey_hex.to_string())
+        .or_insert(false);
+}
+
+/// How to answer a DM that arrived via `received`.
+pub(crate) fn reply_protocol(pubkey_hex: &str, received: DmProtocol) -> DmProtocol {
+    let capable = NIP17_CAPABLE
+        .lock()
+        .get(pubkey_hex)
+        .copied()
+        .unwrap_or(false);
+    if received == DmProtocol::Nip17 || capable {
+        DmProtocol::Nip17
+    } else {
+        DmProtocol::Nip04
+    }
+}
+
+/// A decrypted, authenticated NIP-17 message.
+pub(crate) struct UnwrappedDm {
+    pub sender_pk: String,
+    pub content: String,
+    /// The rumor's real send time (the wrap's is randomized).
+    pub created_at: i64,
+}
+
+/// Open a kind-1059 gift wrap addressed to us. Returns `None` for wraps
+/// that carry something other than a kind-14 DM.
+pub(crate) fn unwrap_gift(
+    secret_key: &[u8],
+    wrap: &serde_json::Value,
+) -> EngineResult<Option<UnwrappedDm>> {
+    let wrap_pk = wrap["pubkey"].as_str().unwrap_or_default();
+    let seal_json = nip44_decrypt(secret_key, wrap_pk, wrap["content"].as_str().unwrap_or(""))?;
+    let seal: serde_json::Value = serde_json::from_str(&seal_json)?;
+    if seal["kind"].as_u64() != Some(KIND_SEAL) {
+        return Err("Gift wrap does not contain a seal".into());
+    }
+    verify_event(&seal)?;
+
+    let sender_pk = seal["pubkey"].as_str().unwrap_or_default().to_string();
+    let rumor_json = nip44_decrypt(
+        secret_key,
+        &sender_pk,
+        seal["content"].as_str().unwrap_or(""),
+    )?;
+    let rumor: serde_json::Value = serde_json::from_str(&rumor_json)?;
+    // The seal signature is what authenticates the sender — a rumor
+    // claiming another author is an impersonation attempt.
+    if rumor["pubkey"].as_str() != Some(sender_pk.as_str()) {
+        return Err("Rumor author does not match seal signer".into());
+    }
+    if rumor["kind"].as_u64() != Some(KIND_PRIVATE_DM) {
+        return Ok(None);
+    }
+    Ok(Some(UnwrappedDm {
+        sender_pk,
+        content: rumor["content"].as_str().unwrap_or_default().to_string(),
+        created_at: rumor["created_at"].as_i64().unwrap_or_default(),
+    }))
+}
+
+/// Build the kind-1059 gift wrap carrying `content` to `receiver_pk`.
+pub(crate) fn gift_wrap(
+    secret_key: &[u8],
+    pubkey_hex: &str,
+    receiver_pk: &str,
+    content: &str,
+) -> EngineResult<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+
+    let rumor_tags = json!([["p", receiver_pk]]);
+    let rumor_id = event_id(pubkey_hex, now, KIND_PRIVATE_DM, &rumor_tags, content)?;
+    let rumor = json!({
+        "id": hex_encode(&rumor_id),
+        "pubkey": pubkey_hex,
+        "created_at": now,
+        "kind": KIND_PRIVATE_DM,
+        "tags": rumor_tags,
+        "content": content,
+    });
+
+    let sealed = nip44_encrypt(secret_key, receiver_pk, &rumor.to_string())?;
+    let seal = sign_event_at(
+        secret_key,
+        pubkey_hex,
+        randomized_timestamp(now),
+        KIND_SEAL,
+        &json!([]),
+        &sealed,
+    )?;
+
+    let wrap_sk = zeroize::Zeroizing::new(ephemeral_secret_key());
+    let wrap_pk = hex_encode(&derive_pubkey(wrap_sk.as_ref())?);
+    let wrapped = nip44_encrypt(wrap_sk.as_ref(), receiver_pk, &seal.to_string())?;
+    sign_event_at(
+        wrap_sk.as_ref(),
+        &wrap_pk,
+        randomized_timestamp(now),
+        KIND_GIFT_WRAP,
+        &json!([["p", receiver_pk]]),
+        &wrapped,
+    )
+}
+
+/// Build the event answering `receiver_pk` with `content` over `protocol`.
+/// NIP-17 falls back to NIP-04 if wrapping fails (e.g. the reply exceeds
+/// NIP-44's 64 KB limit).
+pub(crate) fn build_dm(
+    secret_key: &[u8],
+    pubkey_hex: &str,
+    receiver_pk: &str,
+    content: &str,
+    protocol: DmProtocol,
+) -> EngineResult<(serde_json::Value, DmProtocol)> {
+    if protocol == DmProtocol::Nip17 {
+        match gift_wrap(secret_key, pubkey_hex, receiver_pk, content) {
+            Ok(wrap) => return Ok((wrap, DmProtocol::Nip17)),
+            Err(e) => log::warn!("[nostr] Gift wrap failed, falling back to NIP-04: {}", e),
+        }
+    }
+    let encrypted = nip04_encrypt(secret_key, receiver_pk, content)?;
+    let tags = json!([["p", receiver_pk]]);
+    let event = sign_event(secret_key, pubkey_hex, KIND_NIP04_DM, &tags, &encrypted)?;
+    Ok((event, DmProtocol::Nip04))
+}
+
+/// `now` minus a random offset of up to two days.
+fn randomized_timestamp(now: i64) -> i64 {
+    let mut buf = [0u8; 8];
+    getrandom::getrandom(&mut buf).expect("OS CSPRNG failed");
+    now - (u64::from_le_bytes(buf) % TIMESTAMP_TWEAK_SECS as u64) as i64
+}
+
+/// A fresh secp256k1 secret key for a single gift wrap.
+fn ephemeral_secret_key() -> [u8; 32] {
+    k256::SecretKey::random(&mut rand_core::OsRng)
+        .to_bytes()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::crypto::hex_decode;
+    use super::*;
+
+    fn keypair(hex: &str) -> (Vec<u8>, String) {
+        let sk = hex_decode(hex).unwrap();
+        let pk = hex_encode(&derive_pubkey(&sk).unwrap());
+        (sk, pk)
+    }
+
+    #[test]
+    fn gift_wrap_roundtrip() {
+        let (alice_sk, alice_pk) =
+            keypair("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35");
+        let (bob_sk, bob_pk) =
+            keypair("0b1c4c1a5e0c3d5e7f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e1f0a2b4c6d8e");
+
+        let wrap = gift_wrap(&alice_sk, &alice_pk, &bob_pk, "hi bob").unwrap();
+        assert_eq!(wrap["kind"].as_u64(), Some(KIND_GIFT_WRAP));
+        assert_ne!(wrap["pubkey"].as_str(), Some(alice_pk.as_str()));
+        assert_eq!(wrap["tags"][0][1].as_str(), Some(bob_pk.as_str()));
+        assert!(verify_event(&wrap).is_ok());
+        let now = chrono::Utc::now().timestamp();
+        let ts = wrap["created_at"].as_i64().unwrap();
+        assert!(ts <= now && ts > now - TIMESTAMP_TWEAK_SECS - 1);
+
+        let dm = unwrap_gift(&bob_sk, &wrap).unwrap().unwrap();
+        assert_eq!(dm.sender_pk, alice_pk);
+        assert_eq!(dm.content, "hi bob");
+        assert!(dm.created_at >= now - 5);
+
+        // Only the addressee can open it
+        assert!(unwrap_gift(&alice_sk, &wrap).is_err());
+    }
+
+    #[test]
+    fn reply_protocol_upgrades_once_capable() {
+        let pk = "f".repeat(64);
+        assert_eq!(reply_protocol(&pk, DmProtocol::Nip04), DmProtocol::Nip04);
+        assert_eq!(reply_protocol(&pk, DmProtocol::Nip17), DmProtocol::Nip17);
+        mark_capability_queried(&pk);
+        assert!(capability_known(&pk));
+        assert_eq!(reply_protocol(&pk, DmProtocol::Nip04), DmProtocol::Nip04);
+        mark_nip17_capable(&pk);
+        assert_eq!(reply_protocol(&pk, DmProtocol::Nip04), DmProtocol::Nip17);
+    }
+
+    #[test]
+    fn build_dm_falls_back_to_nip04_for_oversized_replies() {
+        let (sk, pk) = keypair("e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35");
+        let (_, bob_pk) =
+            keypair("0b1c4c1a5e0c3d5e7f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e1f0a2b4c6d8e");
+        let (event, used) = build_dm(&sk, &pk, &bob_pk, "short", DmProtocol::Nip17).unwrap();
+        assert_eq!(used, DmProtocol::Nip17);
+        assert_eq!(event["kind"].as_u64(), Some(KIND_GIFT_WRAP));
+
+        let long = "x".repeat(70_000);
+        let (event, used) = build_dm(&sk, &pk, &bob_pk, &long, DmProtocol::Nip17).unwrap();
+        assert_eq!(used, DmProtocol::Nip04);
+        assert_eq!(event["kind"].as_u64(), Some(KIND_NIP04_DM));
+    }
+}
@@ -10,26 +10,30 @@
 // Protocol:
 //   - NIP-01: Basic event subscription + publishing
 //   - NIP-04: Encrypted direct messages (ECDH + AES-256-CBC)
+//   - NIP-44 / NIP-59 / NIP-17: Gift-wrapped private DMs (see dm.rs)
 //   - kind 1 (text notes): Respond to @mentions in public
-//   - kind 4 (encrypted DMs): Decrypt incoming, encrypt outgoing replies
+//   - kind 4 (encrypted DMs): Decrypt incoming; reply in kind unless the
+//     sender is known to read NIP-17
+//   - kind 1059 (gift wraps): Unwrap incoming, gift-wrap outgoing replies
 //   - Events are signed with secp256k1 Schnorr (BIP-340) via the k256 crate
+//   - NIP-19 (npub / note) and NIP-25 reactions for the agent's nostr_*
+//     tools, which publish through one-shot relay connections (relay.rs)
 //
 // Security:
 //   - Private key stored in OS keychain, never in the config DB
-//   - DM content encrypted end-to-end via ECDH shared secret
+//   - DM content encrypted end-to-end via ECDH shared secret; NIP-17 DMs
+//     also hide the sender and send time from relays
 //   - Allowlist by npub / hex pubkey
 //   - Optional pairing mode
 //   - All communication through relay TLS WebSockets
-//
-// Future: NIP-44 (ChaCha20 + HMAC-SHA256) with NIP-17 gift wrapping for
-//         improved metadata privacy. Kind-4 NIP-04 DMs remain widely supported.
 
 mod crypto;
+mod dm;
 mod relay;
 
 use crate::atoms::error::EngineResult;
 use crate::engine::channels::{self, ChannelStatus, PendingUser};
-use crypto::{derive_pubkey, hex_decode, hex_encode};
+use crypto::{derive_pubkey, hex_encode};
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
@@ -165,18 +169,21 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
                     if get_stop_signal().load(Ordering::Relaxed) {
                         break;
                     }
-                    match relay::run_relay_loop(&app, &cfg, &relay_url, &pk_hex, &sk).await {
-                        Ok(()) => {
-                            attempt = 0;
-                        }
-                        Err(e) => {
-                            warn!("[nostr] Relay {} error: {}", relay_url, e);
-                        }
-                    }
+                    let reason =
+                        match relay::run_relay_loop(&app, &cfg, &relay_url, &pk_hex, &sk).await {
+                            Ok(()) => {
+                                attempt = 0;
+                                format!("relay {} closed the connection", relay_url)
+                            }
+                            Err(e) => {
+                                warn!("[nostr] Relay {} error: {}", relay_url, e);
+                                format!("relay {}: {}", relay_url, e)
+                            }
+                        };
                     if get_stop_signal().load(Ordering::Relaxed) {
                         break;
                     }
-                    let delay = crate::engine::http::reconnect_delay(attempt).await;
+                    let delay = channels::reconnect_backoff(&app, "nostr", attempt, &reason).await;
                     debug!(
                         "[nostr] Relay {} reconnect in {}ms (attempt {})",
                         relay_url,
@@ -195,6 +202,7 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
         }
 
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "nostr", None);
         info!("[nostr] Bridge stopped");
     });
 
@@ -220,6 +228,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("nostr"),
     }
 }
 
@@ -260,3 +269,86 @@ pub fn deny_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<(
 pub fn remove_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
     channels::remove_user_generic(app_handle, CONFIG_KEY, user_id)
 }
+
+// ── Agent Tool API ─────────────────────────────────────────────────────
+//
+// Used by the `nostr_*` agent tools (tools/nostr.rs). These sign with the
+// bridge's key but work whether or not the bridge is running.
+
+pub(crate) use crypto::{bech32_encode, hex_decode, parse_hex_or_bech32};
+
+/// Relays from the bridge config (defaults if the bridge was never set up).
+pub(crate) fn configured_relays(app_handle: &tauri::AppHandle) -> EngineResult<Vec<String>> {
+    let config = load_config(app_handle)?;
+    if config.relays.is_empty() {
+        return Err("No Nostr relays are configured. Add one in Channels → Nostr.".into());
+    }
+    Ok(config.relays)
+}
+
+/// The bot's hex pubkey, derived from the vault key.
+pub(crate) fn own_pubkey(app_handle: &tauri::AppHandle) -> EngineResult<String> {
+    let config = load_config(app_handle)?;
+    let sk = signing_key(&config)?;
+    Ok(hex_encode(&derive_pubkey(&sk)?))
+}
+
+fn signing_key(config: &NostrConfig) -> EngineResult<zeroize::Zeroizing<Vec<u8>>> {
+    if config.private_key_hex.is_empty() {
+        return Err(
+            "No Nostr key is configured. Ask the user to set one up in Channels → Nostr.".into(),
+        );
+    }
+    let sk = zeroize::Zeroizing::new(
+        hex_decode(&config.private_key_hex).map_err(|_| "Invalid private key hex")?,
+    );
+    if sk.len() != 32 {
+        return Err("Private key must be 32 bytes (64 hex chars)".into());
+    }
+    Ok(sk)
+}
+
+/// Sign an event with the bot's key and publish it to every configured
+/// relay. Returns the signed event and each relay's verdict; errors only
+/// if no relay accepted it.
+pub(crate) async fn sign_and_publish(
+    app_handle: &tauri::AppHandle,
+    kind: u64,
+    tags: &serde_json::Value,
+    content: &str,
+) -> EngineResult<(serde_json::Value, Vec<(String, Result<(), String>)>)> {
+    let config = load_config(app_handle)?;
+    if config.relays.is_empty() {
+        return Err("No Nostr relays are configured. Add one in Channels → Nostr.".into());
+    }
+    let sk = signing_key(&config)?;
+    let pubkey_hex = hex_encode(&derive_pubkey(&sk)?);
+    let event = crypto::sign_event(&sk, &pubkey_hex, kind, tags, content)?;
+
+    let outcomes = relay::publish_event(&config.relays, &event).await;
+    if outcomes.iter().all(|(_, r)| r.is_err()) {
+        let reasons: Vec<String> = outcomes
+            .iter()
+            .filter_map(|(relay, r)| r.as_ref().err().map(|e| format!("{}: {}", relay, e)))
+            .collect();
+        return Err(format!("No relay accepted the event — {}", reasons.join("; ")).into());
+    }
+    info!(
+        "[nostr] Published kind {} event {} ({}/{} relays)",
+        kind,
+        event["id"].as_str().unwrap_or_default(),
+        outcomes.iter().filter(|(_, r)| r.is_ok()).count(),
+        outcomes.len()
+    );
+    Ok((event, outcomes))
+}
+
+/// Run a `REQ` filter against the configured relays (verified events,
+/// deduplicated, newest first).
+pub(crate) async fn query(
+    app_handle: &tauri::AppHandle,
+    filter: &serde_json::Value,
+) -> EngineResult<Vec<serde_json::Value>> {
+    let relays = configured_relays(app_handle)?;
+    Ok(relay::query_events(&relays, filter).await)
+}
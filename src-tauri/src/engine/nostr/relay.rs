@@ -3,7 +3,8 @@
 // Connects to a single Nostr relay, subscribes to mentions and DMs,
 // handles incoming events, and publishes signed replies.
 
-use super::crypto::{build_reply_event, nip04_decrypt, nip04_encrypt, sign_event};
+use super::crypto::{build_reply_event, nip04_decrypt};
+use super::dm::{self, DmProtocol, KIND_DM_RELAYS, KIND_GIFT_WRAP, KIND_NIP04_DM};
 use super::{get_stop_signal, NostrConfig, MESSAGE_COUNT};
 
 use crate::atoms::error::{EngineError, EngineResult};
@@ -33,6 +34,7 @@ pub(crate) async fn run_relay_loop(
     let (mut ws_tx, mut ws_rx) = ws_stream.split();
 
     info!("[nostr] Connected to relay {}", relay_url);
+    channels::mark_connected(app_handle, "nostr");
 
     let _ = app_handle.emit(
         "nostr-status",
@@ -43,12 +45,20 @@ pub(crate) async fn run_relay_loop(
     );
 
     // Subscribe to events mentioning our pubkey (NIP-01)
-    // kind 1 = text notes, kind 4 = encrypted DMs (NIP-04)
+    // kind 1 = text notes, kind 4 = encrypted DMs (NIP-04),
+    // kind 1059 = gift-wrapped DMs (NIP-17). Wraps are backdated by up to
+    // two days, so their filter reaches further back and freshness is
+    // checked against the unwrapped rumor instead.
+    let started_at = chrono::Utc::now().timestamp();
     let sub_id = format!("paw-{}", &pubkey_hex[..8]);
     let req = json!(["REQ", &sub_id, {
         "#p": [pubkey_hex],
-        "kinds": [1, 4],
-        "since": chrono::Utc::now().timestamp() - 10, // Only new events
+        "kinds": [1, KIND_NIP04_DM],
+        "since": started_at - 10, // Only new events
+    }, {
+        "#p": [pubkey_hex],
+        "kinds": [KIND_GIFT_WRAP],
+        "since": started_at - dm::TIMESTAMP_TWEAK_SECS - 10,
     }]);
     ws_tx
         .send(WsMessage::Text(req.to_string().into()))
@@ -121,11 +131,18 @@ pub(crate) async fn run_relay_loop(
                 }
 
                 let kind = event["kind"].as_u64().unwrap_or(0);
-                if kind != 1 && kind != 4 {
+                if kind == KIND_DM_RELAYS {
+                    // Answer to a capability lookup: they read NIP-17
+                    if let Some(author) = event["pubkey"].as_str() {
+                        dm::mark_nip17_capable(author);
+                    }
+                    continue;
+                }
+                if kind != 1 && kind != KIND_NIP04_DM && kind != KIND_GIFT_WRAP {
                     continue;
                 }
 
-                let sender_pk = event["pubkey"].as_str().unwrap_or("").to_string();
+                let mut sender_pk = event["pubkey"].as_str().unwrap_or("").to_string();
                 if sender_pk == pubkey_hex {
                     continue;
                 } // Skip own events
@@ -135,10 +152,40 @@ pub(crate) async fn run_relay_loop(
                     continue;
                 }
 
-                // Decrypt kind-4 DMs (NIP-04), pass through kind-1 text notes
-                let (content, is_dm) = if kind == 4 {
-                    match nip04_decrypt(secret_key, &sender_pk, &raw_content) {
-                        Ok(pt) => (pt, true),
+                // Unwrap NIP-17 gift wraps, decrypt kind-4 DMs (NIP-04),
+                // pass through kind-1 text notes
+                let (content, dm_protocol) = match kind {
+                    KIND_GIFT_WRAP => match dm::unwrap_gift(secret_key, event) {
+                        Ok(Some(unwrapped)) if unwrapped.created_at >= started_at - 10 => {
+                            sender_pk = unwrapped.sender_pk;
+                            if sender_pk == pubkey_hex {
+                                continue;
+                            }
+                            dm::mark_nip17_capable(&sender_pk);
+                            (unwrapped.content, Some(DmProtocol::Nip17))
+                        }
+                        Ok(_) => continue, // Stale, or not a kind-14 DM
+                        Err(e) => {
+                            warn!("[nostr] Failed to unwrap gift wrap {}: {}", event_id, e);
+                            continue;
+                        }
+                    },
+                    KIND_NIP04_DM => match nip04_decrypt(secret_key, &sender_pk, &raw_content) {
+                        Ok(pt) => {
+                            if !dm::capability_known(&sender_pk) {
+                                // Ask for their NIP-17 DM relay list; if they
+                                // have one, later replies upgrade to NIP-17
+                                dm::mark_capability_queried(&sender_pk);
+                                let lookup = json!(["REQ", format!("paw-caps-{}", &sender_pk[..8]), {
+                                    "kinds": [KIND_DM_RELAYS],
+                                    "authors": [&sender_pk],
+                                    "limit": 1,
+                                }]);
+                                let _ =
+                                    ws_tx.send(WsMessage::Text(lookup.to_string().into())).await;
+                            }
+                            (pt, Some(DmProtocol::Nip04))
+                        }
                         Err(e) => {
                             warn!(
                                 "[nostr] Failed to decrypt DM from {}...{}: {}",
@@ -148,10 +195,10 @@ pub(crate) async fn run_relay_loop(
                             );
                             continue;
                         }
-                    }
-                } else {
-                    (raw_content, false)
+                    },
+                    _ => (raw_content, None),
                 };
+                let is_dm = dm_protocol.is_some();
                 if content.is_empty() {
                     continue;
                 }
@@ -189,6 +236,7 @@ pub(crate) async fn run_relay_loop(
                             "pubkey": &sender_pk,
                         }),
                     );
+                    channels::notify_pairing(app_handle, "Nostr", &sender_pk[..12]);
                     // Don't reply to denied users on public Nostr
                     continue;
                 }
@@ -197,9 +245,9 @@ pub(crate) async fn run_relay_loop(
 
                 let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
                 let ctx = if is_dm {
-                    "You are replying to a private Nostr DM (NIP-04 encrypted). \
+                    "You are replying to a private, end-to-end encrypted Nostr DM. \
                      Use plain text. Keep responses concise. \
-                     Your reply will be encrypted and sent as a kind-4 DM."
+                     Your reply will be encrypted and sent privately."
                 } else {
                     "You are chatting via Nostr (a decentralized social network). \
                      Use plain text. Keep responses concise. \
@@ -219,25 +267,23 @@ pub(crate) async fn run_relay_loop(
 
                 match response {
                     Ok(reply) if !reply.is_empty() => {
-                        if is_dm {
-                            // Encrypt and send as kind-4 DM (NIP-04)
-                            match nip04_encrypt(secret_key, &sender_pk, &reply) {
-                                Ok(encrypted) => {
-                                    let tags = json!([["p", &sender_pk]]);
-                                    match sign_event(secret_key, pubkey_hex, 4, &tags, &encrypted) {
-                                        Ok(dm_event) => {
-                                            let publish = json!(["EVENT", dm_event]);
-                                            if let Err(e) = ws_tx
-                                                .send(WsMessage::Text(publish.to_string().into()))
-                                                .await
-                                            {
-                                                warn!("[nostr] Failed to send DM: {}", e);
-                                            }
-                                        }
-                                        Err(e) => error!("[nostr] Failed to sign DM: {}", e),
+                        if let Some(received) = dm_protocol {
+                            // Gift-wrap (NIP-17) when they can read it,
+                            // otherwise a kind-4 DM (NIP-04)
+                            let protocol = dm::reply_protocol(&sender_pk, received);
+                            match dm::build_dm(secret_key, pubkey_hex, &sender_pk, &reply, protocol)
+                            {
+                                Ok((dm_event, used)) => {
+                                    debug!("[nostr] Sending DM reply via {:?}", used);
+                                    let publish = json!(["EVENT", dm_event]);
+                                    if let Err(e) = ws_tx
+                                        .send(WsMessage::Text(publish.to_string().into()))
+                                        .await
+                                    {
+                                        warn!("[nostr] Failed to send DM: {}", e);
                                     }
                                 }
-                                Err(e) => error!("[nostr] Failed to encrypt DM reply: {}", e),
+                                Err(e) => error!("[nostr] Failed to build DM reply: {}", e),
                             }
                         } else {
                             // Public reply (kind-1)
@@ -269,7 +315,14 @@ pub(crate) async fn run_relay_loop(
                 }
             }
             "EOSE" => {
-                info!("[nostr] End of stored events from {}", relay_url);
+                let sub = arr.get(1).and_then(|v| v.as_str()).unwrap_or("");
+                if sub.starts_with("paw-caps-") {
+                    // One-shot capability lookup is done
+                    let close = json!(["CLOSE", sub]);
+                    let _ = ws_tx.send(WsMessage::Text(close.to_string().into())).await;
+                } else {
+                    info!("[nostr] End of stored events from {}", relay_url);
+                }
             }
             "NOTICE" => {
                 let notice = arr.get(1).and_then(|v| v.as_str()).unwrap_or("");
@@ -307,3 +360,118 @@ pub(crate) async fn run_relay_loop(
 
     Ok(())
 }
+
+// ── One-shot relay requests (agent tools) ──────────────────────────────
+//
+// The agent's Nostr tools don't go through the bridge loop (which may not
+// be running): each call opens its own short-lived connection to every
+// configured relay.
+
+/// How long to wait on any one relay.
+const ONE_SHOT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Publish a signed event to every relay. Returns `(relay, outcome)` per
+/// relay, where the outcome is the relay's `OK` verdict.
+pub(crate) async fn publish_event(
+    relays: &[String],
+    event: &serde_json::Value,
+) -> Vec<(String, Result<(), String>)> {
+    let event_id = event["id"].as_str().unwrap_or_default();
+    let futs = relays.iter().map(|relay| async move {
+        let outcome = tokio::time::timeout(ONE_SHOT_TIMEOUT, async {
+            let (ws, _) = connect_async(relay.as_str())
+                .await
+                .map_err(|e| e.to_string())?;
+            let (mut tx, mut rx) = ws.split();
+            let publish = json!(["EVENT", event]);
+            tx.send(WsMessage::Text(publish.to_string().into()))
+                .await
+                .map_err(|e| e.to_string())?;
+            while let Some(msg) = rx.next().await {
+                let Ok(WsMessage::Text(text)) = msg else {
+                    continue;
+                };
+                let Ok(arr) = serde_json::from_str::<Vec<serde_json::Value>>(&text) else {
+                    continue;
+                };
+                if arr.first().and_then(|v| v.as_str()) == Some("OK")
+                    && arr.get(1).and_then(|v| v.as_str()) == Some(event_id)
+                {
+                    let _ = tx.close().await;
+                    return if arr.get(2).and_then(|v| v.as_bool()).unwrap_or(false) {
+                        Ok(())
+                    } else {
+                        Err(arr
+                            .get(3)
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("rejected")
+                            .to_string())
+                    };
+                }
+            }
+            Err("connection closed before the relay answered".to_string())
+        })
+        .await
+        .unwrap_or_else(|_| Err("timed out".into()));
+        (relay.clone(), outcome)
+    });
+    futures::future::join_all(futs).await
+}
+
+/// Run one `REQ` filter against every relay and collect the stored events
+/// each returns before `EOSE`, deduplicated by id and newest first.
+pub(crate) async fn query_events(
+    relays: &[String],
+    filter: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let futs = relays.iter().map(|relay| async move {
+        let mut events = Vec::new();
+        let _ = tokio::time::timeout(ONE_SHOT_TIMEOUT, async {
+            let Ok((ws, _)) = connect_async(relay.as_str()).await else {
+                return;
+            };
+            let (mut tx, mut rx) = ws.split();
+            let sub_id = "paw-query";
+            let req = json!(["REQ", sub_id, filter]);
+            if tx
+                .send(WsMessage::Text(req.to_string().into()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+            while let Some(msg) = rx.next().await {
+                let Ok(WsMessage::Text(text)) = msg else {
+                    continue;
+                };
+                let Ok(arr) = serde_json::from_str::<Vec<serde_json::Value>>(&text) else {
+                    continue;
+                };
+                match arr.first().and_then(|v| v.as_str()) {
+                    Some("EVENT") if arr.len() >= 3 => events.push(arr[2].clone()),
+                    Some("EOSE") | Some("CLOSED") => break,
+                    _ => {}
+                }
+            }
+            let close = json!(["CLOSE", sub_id]);
+            let _ = tx.send(WsMessage::Text(close.to_string().into())).await;
+            let _ = tx.close().await;
+        })
+        .await;
+        if events.is_empty() {
+            debug!("[nostr] No results from {}", relay);
+        }
+        events
+    });
+
+    let mut seen = std::collections::HashSet::new();
+    let mut events: Vec<serde_json::Value> = futures::future::join_all(futs)
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|e| super::crypto::verify_event(e).is_ok())
+        .filter(|e| seen.insert(e["id"].as_str().unwrap_or_default().to_string()))
+        .collect();
+    events.sort_by_key(|e| std::cmp::Reverse(e["created_at"].as_i64().unwrap_or_default()));
+    events
+}
@@ -0,0 +1,374 @@
+// Paw Agent Engine — Notification router
+//
+// Subsystems that want the user's attention (budget warnings, pairing
+// requests, finished tasks, a bridge going down, new mail) call `notify`
+// with a category instead of each picking its own way to reach the user.
+// NotificationConfig routes every category:
+//   - "os"     — a system notification, plus the in-app notification drawer
+//   - "bridge" — a message through the Telegram / Discord / webhook bridge
+//   - "log"    — silent: the notification drawer and the log only
+// Noisy categories can be digested: their notices are held for
+// `digest_minutes` and delivered as one summary.
+//
+// Do-not-disturb windows (per day of week, in the user's timezone) queue
+// everything except critical notices and `breakthrough` categories
+// (security by default); the queue is delivered as one digest per
+// category when the window ends.
+//
+// The frontend renders everything from the `notification` event.
+
+use crate::engine::news;
+use crate::engine::state::EngineState;
+use crate::engine::types::{DndWindow, NotificationRoute};
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDateTime, NaiveTime};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+pub const BUDGET: &str = "budget";
+pub const PAIRING: &str = "pairing";
+pub const TASK: &str = "task";
+pub const GATEWAY: &str = "gateway";
+pub const MAIL: &str = "mail";
+pub const SECURITY: &str = "security";
+
+/// Event the frontend renders notifications from.
+pub const EVENT: &str = "notification";
+
+/// Notices held for a digest, by category.
+static PENDING: LazyLock<Mutex<HashMap<String, Vec<Notice>>>> = LazyLock::new(Default::default);
+
+/// Notices queued by do-not-disturb, in arrival order.
+static QUIET: LazyLock<Mutex<Vec<Notice>>> = LazyLock::new(Default::default);
+
+/// A flush of the do-not-disturb queue is scheduled.
+static QUIET_FLUSH: AtomicBool = AtomicBool::new(false);
+
+/// One thing to tell the user.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Notice {
+    pub category: String,
+    pub title: String,
+    pub body: String,
+    /// View to open when the notification is clicked (e.g. "tasks").
+    pub navigate_to: Option<String>,
+    /// Breaks through do-not-disturb.
+    pub critical: bool,
+}
+
+impl Notice {
+    pub fn new(category: &str, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Notice {
+            category: category.to_string(),
+            title: title.into(),
+            body: body.into(),
+            navigate_to: None,
+            critical: false,
+        }
+    }
+
+    pub fn critical(mut self) -> Self {
+        self.critical = true;
+        self
+    }
+
+    pub fn navigate_to(mut self, view: &str) -> Self {
+        self.navigate_to = Some(view.to_string());
+        self
+    }
+}
+
+/// Payload of `EVENT`.
+#[derive(Clone, Serialize)]
+struct Payload<'a> {
+    #[serde(flatten)]
+    notice: &'a Notice,
+    /// Show a system notification too.
+    os: bool,
+}
+
+/// Route a notice by its category.
+pub fn notify(app_handle: &tauri::AppHandle, notice: Notice) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let (route, window, quiet_until) = {
+        let cfg = state.config.lock();
+        let quiet = !notice.critical && !cfg.notifications.breakthrough.contains(&notice.category);
+        let now = chrono::Utc::now()
+            .with_timezone(&cfg.timezone())
+            .naive_local();
+        (
+            cfg.notifications.route(&notice.category),
+            cfg.notifications.digest_minutes,
+            quiet
+                .then(|| dnd_until(&cfg.notifications.dnd, now).map(|end| end - now))
+                .flatten(),
+        )
+    };
+    info!("[notify] {}: {}", notice.category, notice.title);
+
+    if let Some(remaining) = quiet_until {
+        QUIET.lock().push(notice);
+        schedule_quiet_flush(app_handle, remaining);
+        return;
+    }
+
+    if route.digest && window > 0 {
+        let category = notice.category.clone();
+        let first = {
+            let mut pending = PENDING.lock();
+            let held = pending.entry(category.clone()).or_default();
+            held.push(notice);
+            held.len() == 1
+        };
+        // The first held notice opens the window; its flush takes the rest
+        if first {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(window as u64 * 60)).await;
+                flush(&app_handle, &category);
+            });
+        }
+        return;
+    }
+    deliver(app_handle, &route, notice);
+}
+
+/// Flush the do-not-disturb queue after `remaining`, unless a flush is
+/// already scheduled.
+fn schedule_quiet_flush(app_handle: &tauri::AppHandle, remaining: ChronoDuration) {
+    if QUIET_FLUSH.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    let delay = remaining.to_std().unwrap_or_default() + Duration::from_secs(1);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        QUIET_FLUSH.store(false, Ordering::SeqCst);
+        flush_quiet(&app_handle);
+    });
+}
+
+/// Deliver the notices queued by do-not-disturb, one digest per category —
+/// or wait again if another window has started in the meantime.
+fn flush_quiet(app_handle: &tauri::AppHandle) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let (still_quiet, routes) = {
+        let cfg = state.config.lock();
+        let now = chrono::Utc::now()
+            .with_timezone(&cfg.timezone())
+            .naive_local();
+        (
+            dnd_until(&cfg.notifications.dnd, now).map(|end| end - now),
+            cfg.notifications.clone(),
+        )
+    };
+    if let Some(remaining) = still_quiet {
+        schedule_quiet_flush(app_handle, remaining);
+        return;
+    }
+    let queued = std::mem::take(&mut *QUIET.lock());
+    let mut by_category: Vec<(String, Vec<Notice>)> = Vec::new();
+    for notice in queued {
+        match by_category.iter_mut().find(|(c, _)| *c == notice.category) {
+            Some((_, held)) => held.push(notice),
+            None => by_category.push((notice.category.clone(), vec![notice])),
+        }
+    }
+    for (category, held) in by_category {
+        if let Some(notice) = digest(&held) {
+            deliver(app_handle, &routes.route(&category), notice);
+        }
+    }
+}
+
+/// When the do-not-disturb window active at `now` ends (the latest end if
+/// several overlap); `None` outside every window.
+pub fn dnd_until(windows: &[DndWindow], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+    let mut until = None;
+    for w in windows {
+        let (Some(start), Some(end)) = (parse(&w.start), parse(&w.end)) else {
+            continue;
+        };
+        // A window that started yesterday may still run past midnight
+        for day in [now.date() - ChronoDuration::days(1), now.date()] {
+            let weekday = day.weekday().to_string().to_lowercase();
+            if !w.days.is_empty() && !w.days.iter().any(|d| d.to_lowercase() == weekday) {
+                continue;
+            }
+            let from = day.and_time(start);
+            let to = if end > start {
+                day.and_time(end)
+            } else {
+                (day + ChronoDuration::days(1)).and_time(end)
+            };
+            if from <= now && now < to && until.is_none_or(|u| to > u) {
+                until = Some(to);
+            }
+        }
+    }
+    until
+}
+
+/// Deliver the digest of a category's held notices.
+fn flush(app_handle: &tauri::AppHandle, category: &str) {
+    let held = PENDING.lock().remove(category).unwrap_or_default();
+    let Some(notice) = digest(&held) else {
+        return;
+    };
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let route = state.config.lock().notifications.route(category);
+    deliver(app_handle, &route, notice);
+}
+
+/// One notice summarizing `held` (the notice itself when there's one).
+pub fn digest(held: &[Notice]) -> Option<Notice> {
+    match held {
+        [] => None,
+        [only] => Some(only.clone()),
+        [first, ..] => {
+            let body = held
+                .iter()
+                .map(|n| format!("• {}", n.title))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(Notice {
+                category: first.category.clone(),
+                title: format!("{} {} notifications", held.len(), first.category),
+                body,
+                navigate_to: first.navigate_to.clone(),
+                critical: held.iter().any(|n| n.critical),
+            })
+        }
+    }
+}
+
+fn deliver(app_handle: &tauri::AppHandle, route: &NotificationRoute, notice: Notice) {
+    let _ = app_handle.emit(
+        EVENT,
+        Payload {
+            notice: &notice,
+            os: route.delivery == "os",
+        },
+    );
+    if route.delivery != "bridge" {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    let route = route.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<EngineState>() else {
+            return;
+        };
+        let text = if notice.body.is_empty() {
+            notice.title.clone()
+        } else {
+            format!("{}\n{}", notice.title, notice.body)
+        };
+        if let Err(e) = news::send_to_bridge(
+            &app_handle,
+            &state,
+            "default",
+            &route.bridge,
+            Some(&route.target),
+            &text,
+            serde_json::json!({ "category": notice.category, "title": notice.title }),
+        )
+        .await
+        {
+            warn!(
+                "[notify] {} notification via '{}' failed: {}",
+                notice.category, route.bridge, e
+            );
+        }
+    });
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::NotificationConfig;
+
+    #[test]
+    fn digest_summarizes_held_notices() {
+        assert_eq!(digest(&[]), None);
+        let one = Notice::new(TASK, "Task finished: Weekly report", "").navigate_to("tasks");
+        assert_eq!(digest(std::slice::from_ref(&one)), Some(one.clone()));
+
+        let two = Notice::new(TASK, "Task failed: Sync", "");
+        let d = digest(&[one, two]).unwrap();
+        assert_eq!(d.title, "2 task notifications");
+        assert_eq!(
+            d.body,
+            "• Task finished: Weekly report\n• Task failed: Sync"
+        );
+        assert_eq!(d.navigate_to.as_deref(), Some("tasks"));
+    }
+
+    #[test]
+    fn dnd_windows_cross_midnight_per_weekday() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let nights = DndWindow {
+            days: vec!["mon".into(), "tue".into()],
+            start: "22:00".into(),
+            end: "07:00".into(),
+        };
+        let windows = [nights];
+        // 2026-10-12 is a Monday
+        assert_eq!(dnd_until(&windows, at("2026-10-12 21:59")), None);
+        assert_eq!(
+            dnd_until(&windows, at("2026-10-12 23:30")),
+            Some(at("2026-10-13 07:00"))
+        );
+        // Tuesday 03:00 belongs to Monday night
+        assert_eq!(
+            dnd_until(&windows, at("2026-10-13 03:00")),
+            Some(at("2026-10-13 07:00"))
+        );
+        assert_eq!(dnd_until(&windows, at("2026-10-13 07:00")), None);
+        // Wednesday night isn't configured; Thursday 03:00 is free
+        assert_eq!(dnd_until(&windows, at("2026-10-14 23:00")), None);
+        assert_eq!(dnd_until(&windows, at("2026-10-15 03:00")), None);
+
+        let all_day = DndWindow {
+            days: vec!["sun".into()],
+            start: "00:00".into(),
+            end: "00:00".into(),
+        };
+        assert_eq!(
+            dnd_until(&[all_day], at("2026-10-18 15:00")),
+            Some(at("2026-10-19 00:00"))
+        );
+    }
+
+    #[test]
+    fn noisy_categories_are_digested_by_default() {
+        let mut cfg = NotificationConfig::default();
+        assert!(cfg.route(MAIL).digest);
+        assert!(!cfg.route(PAIRING).digest);
+        assert_eq!(cfg.route(BUDGET).delivery, "os");
+
+        cfg.routes.insert(
+            MAIL.into(),
+            NotificationRoute {
+                delivery: "log".into(),
+                ..Default::default()
+            },
+        );
+        assert!(!cfg.route(MAIL).digest);
+        assert_eq!(cfg.route(MAIL).delivery, "log");
+    }
+}
@@ -899,6 +899,22 @@ pub async fn start_oauth_flow(
             service_id
         ))
     })?;
+    start_oauth_flow_with_scopes(service_id, config.default_scopes, app_handle).await
+}
+
+/// `start_oauth_flow` requesting `scopes` instead of the service's
+/// default scopes (e.g. IMAP/SMTP access for the mail setup).
+pub async fn start_oauth_flow_with_scopes(
+    service_id: &str,
+    scopes: &[&str],
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<OAuthTokens> {
+    let config = get_oauth_config(service_id).ok_or_else(|| {
+        EngineError::Other(format!(
+            "No OAuth configuration found for service '{}'",
+            service_id
+        ))
+    })?;
 
     // Resolve effective client ID (compile-time or runtime env var)
     let client_id = config.effective_client_id();
@@ -940,7 +956,7 @@ pub async fn start_oauth_flow(
     );
 
     // 3. Build authorization URL
-    let scopes = config.default_scopes.join(" ");
+    let scopes = scopes.join(" ");
     let mut auth_url = format!(
         "{}?response_type=code&client_id={}&redirect_uri={}&code_challenge={}&code_challenge_method=S256",
         config.auth_url,
@@ -1169,6 +1185,16 @@ async fn exchange_code(
 pub async fn refresh_access_token(
     service_id: &str,
     refresh_token: &str,
+) -> EngineResult<OAuthTokens> {
+    refresh_access_token_with_scopes(service_id, refresh_token, &[]).await
+}
+
+/// `refresh_access_token` asking for `scopes` (empty: the granted ones).
+/// Microsoft needs the scopes again for tokens of a non-Graph resource.
+pub async fn refresh_access_token_with_scopes(
+    service_id: &str,
+    refresh_token: &str,
+    scopes: &[&str],
 ) -> EngineResult<OAuthTokens> {
     let config = get_oauth_config(service_id).ok_or_else(|| {
         EngineError::Other(format!(
@@ -1187,6 +1213,10 @@ pub async fn refresh_access_token(
     params.insert("grant_type", "refresh_token");
     params.insert("refresh_token", refresh_token);
     params.insert("client_id", client_id);
+    let scope = scopes.join(" ");
+    if !scope.is_empty() {
+        params.insert("scope", &scope);
+    }
 
     // Some providers require client_secret for refresh too
     if let Some(secret) = client_secret {
@@ -1214,10 +1244,17 @@ pub async fn refresh_access_token(
             "[oauth] Token refresh returned {} for '{}': {}",
             status, service_id, body
         );
-        return Err(EngineError::Other(format!(
+        let message = format!(
             "Token refresh failed (HTTP {}). You may need to reconnect {}.",
             status, config.name
-        )));
+        );
+        // 400/401/403 mean the refresh token was revoked or expired
+        // (invalid_grant) — retrying won't help, the user must reconnect.
+        return Err(if matches!(status.as_u16(), 400 | 401 | 403) {
+            EngineError::Auth(message)
+        } else {
+            EngineError::Other(message)
+        });
     }
 
     let token_response: TokenResponse = response
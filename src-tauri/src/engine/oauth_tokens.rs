@@ -0,0 +1,301 @@
+// Paw Agent Engine — OAuth Token Manager
+//
+// One place for every OAuth-backed skill (Google, Microsoft, Spotify,
+// service_api, …) to get a usable access token:
+//   - Tokens live encrypted in the unified key vault under `oauth:<service>`.
+//   - `access_token()` refreshes ahead of expiry, so callers never see a
+//     token that is about to lapse mid-request.
+//   - Concurrent refreshes for the same service are serialized: the first
+//     caller refreshes, the rest wait and reuse the new token.
+//   - Permanent failures (refresh token revoked/expired) emit an
+//     `oauth-token-failed` event so the UI can prompt a reconnect.
+
+use crate::atoms::error::EngineError;
+use crate::engine::key_vault;
+use crate::engine::oauth::{refresh_access_token, OAuthTokens};
+use crate::engine::skills::crypto::{decrypt_credential, encrypt_credential, get_vault_key};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, OnceLock};
+use tauri::Emitter;
+
+/// Refresh when fewer than this many seconds of validity remain.
+pub const REFRESH_AHEAD_SECS: u64 = 300;
+
+/// Event emitted when a token can't be refreshed and the user must reconnect.
+pub const EVENT_TOKEN_FAILED: &str = "oauth-token-failed";
+
+/// Service ids that share one connection, tried in order.
+const ALIASES: &[&[&str]] = &[
+    &["google-workspace", "google"],
+    &["microsoft", "microsoft-365"],
+];
+
+/// Per-service refresh locks — one in-flight refresh per service.
+static REFRESH_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// App handle used to emit failure events (set once at startup).
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Why a token couldn't be provided.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenError {
+    /// No tokens stored for the service.
+    NotConnected,
+    /// Stored tokens couldn't be decrypted (vault key changed) — removed.
+    Corrupt,
+    /// Refresh was rejected; the user has to reconnect the service.
+    ReconnectRequired(String),
+    /// Vault, network or other transient failure.
+    Other(String),
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::NotConnected => write!(f, "not connected"),
+            TokenError::Corrupt => write!(f, "stored token is corrupted"),
+            TokenError::ReconnectRequired(e) => write!(f, "reconnect required: {}", e),
+            TokenError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Payload of `EVENT_TOKEN_FAILED`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenFailure {
+    pub service_id: String,
+    pub error: String,
+}
+
+/// Register the app handle for failure events. Called once at startup.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle.clone());
+}
+
+// ── Storage ────────────────────────────────────────────────────────────
+
+fn vault_purpose(service_id: &str) -> String {
+    format!("oauth:{}", service_id)
+}
+
+/// Store tokens encrypted in the unified key vault.
+pub fn store(service_id: &str, tokens: &OAuthTokens) -> Result<(), String> {
+    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {}", e))?;
+    let json = serde_json::to_string(tokens).map_err(|e| format!("Serialize error: {}", e))?;
+    let encrypted =
+        encrypt_credential(&json, &vault_key).map_err(|e| format!("Encryption error: {}", e))?;
+    key_vault::set(&vault_purpose(service_id), &encrypted);
+    info!(
+        "[oauth] Stored encrypted OAuth tokens for '{}' in unified vault",
+        service_id
+    );
+    Ok(())
+}
+
+/// Load tokens from the unified key vault. `Ok(None)` when not stored.
+pub fn load(service_id: &str) -> Result<Option<OAuthTokens>, String> {
+    load_tokens(service_id).map_err(|e| e.to_string())
+}
+
+/// `load`, telling tokens that can't be read back (`Corrupt`) from vault
+/// failures (`Other`).
+fn load_tokens(service_id: &str) -> Result<Option<OAuthTokens>, TokenError> {
+    let vault_key =
+        get_vault_key().map_err(|e| TokenError::Other(format!("Vault key error: {}", e)))?;
+    let Some(encrypted) = key_vault::get(&vault_purpose(service_id)) else {
+        return Ok(None);
+    };
+    let tokens = decrypt_credential(&encrypted, &vault_key)
+        .map_err(|e| format!("Decrypt error: {}", e))
+        .and_then(|json| {
+            serde_json::from_str(&json).map_err(|e| format!("Deserialize error: {}", e))
+        });
+    match tokens {
+        Ok(tokens) => Ok(Some(tokens)),
+        Err(e) => {
+            warn!("[oauth] Tokens for '{}' are unreadable: {}", service_id, e);
+            Err(TokenError::Corrupt)
+        }
+    }
+}
+
+/// Delete stored tokens for a service.
+pub fn remove(service_id: &str) {
+    key_vault::remove(&vault_purpose(service_id));
+}
+
+/// The service id (or alias) that actually has tokens stored.
+fn resolve_stored(service_id: &str) -> Option<String> {
+    candidates(service_id)
+        .into_iter()
+        .find(|id| key_vault::get(&vault_purpose(id)).is_some())
+}
+
+/// `service_id` followed by its aliases.
+fn candidates(service_id: &str) -> Vec<String> {
+    let mut ids = vec![service_id.to_string()];
+    if let Some(group) = ALIASES.iter().find(|g| g.contains(&service_id)) {
+        ids.extend(
+            group
+                .iter()
+                .filter(|id| **id != service_id)
+                .map(|id| id.to_string()),
+        );
+    }
+    ids
+}
+
+// ── Access ─────────────────────────────────────────────────────────────
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Whether a token expiring at `expires_at` should be refreshed now.
+pub fn needs_refresh(expires_at: Option<u64>, now: u64, ahead_secs: u64) -> bool {
+    expires_at.is_some_and(|exp| now >= exp.saturating_sub(ahead_secs))
+}
+
+/// Get a valid access token, refreshing ahead of expiry when needed.
+/// Aliases (e.g. `google` ↔ `google-workspace`) are resolved automatically.
+pub async fn access_token(service_id: &str) -> Result<String, TokenError> {
+    let stored_id = resolve_stored(service_id).ok_or(TokenError::NotConnected)?;
+    let tokens = load_or_discard(&stored_id)?;
+    if !needs_refresh(tokens.expires_at, now_secs(), REFRESH_AHEAD_SECS) {
+        return Ok(tokens.access_token);
+    }
+    match refresh_if_needed(&stored_id, REFRESH_AHEAD_SECS).await {
+        Ok(fresh) => Ok(fresh.access_token),
+        // Refresh failed transiently but the old token hasn't lapsed yet
+        Err(TokenError::Other(e)) if !needs_refresh(tokens.expires_at, now_secs(), 0) => {
+            warn!(
+                "[oauth] Refresh for '{}' failed: {} — using existing token",
+                stored_id, e
+            );
+            Ok(tokens.access_token)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Refresh the service's tokens now, regardless of expiry.
+/// Aliases are resolved like `access_token()`.
+pub async fn force_refresh(service_id: &str) -> Result<OAuthTokens, TokenError> {
+    let stored_id = resolve_stored(service_id).ok_or(TokenError::NotConnected)?;
+    refresh(&stored_id, None).await
+}
+
+/// Refresh under the service's lock. Re-checks expiry after acquiring it so
+/// callers that queued behind another refresh reuse its result.
+/// Returns the current (possibly just-refreshed) tokens.
+pub async fn refresh_if_needed(
+    service_id: &str,
+    ahead_secs: u64,
+) -> Result<OAuthTokens, TokenError> {
+    refresh(service_id, Some(ahead_secs)).await
+}
+
+/// `refresh_if_needed`; with `ahead_secs` None it refreshes unconditionally,
+/// tokens without an expiry included.
+async fn refresh(service_id: &str, ahead_secs: Option<u64>) -> Result<OAuthTokens, TokenError> {
+    let lock = REFRESH_LOCKS
+        .lock()
+        .entry(service_id.to_string())
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    let tokens = load_or_discard(service_id)?;
+    if ahead_secs.is_some_and(|ahead| !needs_refresh(tokens.expires_at, now_secs(), ahead)) {
+        return Ok(tokens);
+    }
+    let refresh_token = tokens.refresh_token.clone().ok_or_else(|| {
+        let e = TokenError::ReconnectRequired("no refresh token available".into());
+        report_failure(service_id, &e);
+        e
+    })?;
+
+    info!("[oauth] Refreshing token for '{}'", service_id);
+    match refresh_access_token(service_id, &refresh_token).await {
+        Ok(mut fresh) => {
+            // Most providers (Google included) only send a refresh token on
+            // the first grant — never drop the one we have.
+            if fresh.refresh_token.is_none() {
+                fresh.refresh_token = tokens.refresh_token;
+            }
+            store(service_id, &fresh).map_err(TokenError::Other)?;
+            Ok(fresh)
+        }
+        Err(EngineError::Auth(msg)) => {
+            let e = TokenError::ReconnectRequired(msg);
+            report_failure(service_id, &e);
+            Err(e)
+        }
+        Err(e) => Err(TokenError::Other(e.to_string())),
+    }
+}
+
+/// Load tokens, removing them if they can no longer be decrypted. Vault
+/// errors are passed on as they are; the tokens may be fine.
+fn load_or_discard(service_id: &str) -> Result<OAuthTokens, TokenError> {
+    match load_tokens(service_id) {
+        Ok(Some(t)) => Ok(t),
+        Ok(None) => Err(TokenError::NotConnected),
+        Err(TokenError::Corrupt) => {
+            warn!(
+                "[oauth] Removing the unreadable tokens for '{}'",
+                service_id
+            );
+            remove(service_id);
+            report_failure(service_id, &TokenError::Corrupt);
+            Err(TokenError::Corrupt)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn report_failure(service_id: &str, error: &TokenError) {
+    warn!("[oauth] Token for '{}' unusable: {}", service_id, error);
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(
+            EVENT_TOKEN_FAILED,
+            TokenFailure {
+                service_id: service_id.to_string(),
+                error: error.to_string(),
+            },
+        );
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_window() {
+        assert!(!needs_refresh(None, 1_000, REFRESH_AHEAD_SECS));
+        assert!(!needs_refresh(Some(2_000), 1_000, 300));
+        assert!(needs_refresh(Some(1_200), 1_000, 300));
+        assert!(needs_refresh(Some(900), 1_000, 0));
+        assert!(needs_refresh(Some(5_000), 1_000, u64::MAX));
+    }
+
+    #[test]
+    fn aliases_expand_both_ways() {
+        assert_eq!(candidates("google"), vec!["google", "google-workspace"]);
+        assert_eq!(
+            candidates("microsoft-365"),
+            vec!["microsoft-365", "microsoft"]
+        );
+        assert_eq!(candidates("spotify"), vec!["spotify"]);
+    }
+}
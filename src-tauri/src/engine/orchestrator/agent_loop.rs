@@ -2,17 +2,28 @@
 //
 // Unified streaming agent loop used by both boss and worker agents.
 // Parameterized by `AgentRole` to handle the few behavioural differences:
-//   - Boss intercepts orchestrator tools and stops on `project_complete`
+//   - Boss intercepts orchestrator tools and stops on `project_complete`,
+//     or at the start of a round once the project is paused
 //   - Worker intercepts `report_progress` and stops on status=done
 //   - Boss emits EngineEvent::Complete on final text; worker does not
+//   - Worker enforces its caps (rounds, spend, withheld tools) and reports
+//     violations to the boss — see caps.rs
+//   - Both stop once the project budget is used up: the boss pauses the
+//     project, a worker reports to the boss — see budget.rs
+//
+// Each round's token usage is recorded in telemetry under the agent's
+// project session, which is where the project post-mortem reads costs from.
 
 use crate::atoms::error::EngineError;
 use crate::engine::providers::AnyProvider;
-use crate::engine::state::PendingApprovals;
+use crate::engine::sessions::DeferredWrite;
+use crate::engine::state::{EngineState, PendingApprovals};
 use crate::engine::types::*;
 use log::{info, warn};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 
+use super::budget;
+use super::caps::{self, Caps, Violation};
 use super::handlers::{execute_boss_tool, execute_worker_tool};
 use crate::atoms::error::EngineResult;
 
@@ -21,7 +32,7 @@ use crate::atoms::error::EngineResult;
 /// Distinguishes boss from worker behaviour inside the shared loop.
 pub(crate) enum AgentRole<'a> {
     Boss,
-    Worker { agent_id: &'a str },
+    Worker { agent_id: &'a str, caps: &'a Caps },
 }
 
 // ── Safe tools (centralized registry) ───────────────────────────────────
@@ -32,6 +43,60 @@ fn is_orchestrator_safe(name: &str) -> bool {
     openpawz_core::engine::tool_metadata::orchestrator_safe(name)
 }
 
+fn is_paused(app_handle: &tauri::AppHandle, project_id: &str) -> bool {
+    let state = app_handle.state::<EngineState>();
+    let status = state.store.get_project_status(project_id).ok().flatten();
+    status.as_deref() == Some("paused")
+}
+
+// ── Usage ──────────────────────────────────────────────────────────────
+
+/// Count one round's tokens toward the daily budget and record them in
+/// telemetry under the agent's project session (read by the post-mortem).
+/// Returns the round's estimated cost.
+fn record_round_usage(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    model: &str,
+    chunks: &[StreamChunk],
+    tool_calls: u32,
+    llm_ms: u64,
+) -> f64 {
+    let Some(usage) = chunks.iter().rev().find_map(|c| c.usage.clone()) else {
+        return 0.0;
+    };
+    let state = app_handle.state::<EngineState>();
+    state.daily_tokens.record(
+        model,
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_read_tokens,
+        usage.cache_creation_tokens,
+    );
+    let cost = estimate_cost_usd(
+        model,
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_read_tokens,
+        usage.cache_creation_tokens,
+    );
+    state.store.defer_write(DeferredWrite::Metric {
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        session_id: session_id.to_string(),
+        run_id: None,
+        model: model.to_string(),
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cost_usd: cost,
+        tool_calls,
+        tool_duration_ms: 0,
+        llm_duration_ms: llm_ms,
+        total_duration_ms: llm_ms,
+        rounds: 1,
+    });
+    cost
+}
+
 // ── Unified loop ───────────────────────────────────────────────────────
 
 /// Run a streaming agent loop that intercepts role-specific tools.
@@ -56,11 +121,16 @@ pub(crate) async fn run_orchestrator_loop(
 ) -> EngineResult<String> {
     let label = match &role {
         AgentRole::Boss => "Boss".to_string(),
-        AgentRole::Worker { agent_id } => format!("Worker {}", agent_id),
+        AgentRole::Worker { agent_id, .. } => format!("Worker {}", agent_id),
+    };
+    let worker_caps = match &role {
+        AgentRole::Boss => None,
+        AgentRole::Worker { caps, .. } => Some(*caps),
     };
 
     let mut round = 0u32;
     let mut final_text = String::new();
+    let mut spent_usd = 0.0;
 
     loop {
         round += 1;
@@ -69,8 +139,33 @@ pub(crate) async fn run_orchestrator_loop(
                 "[orchestrator] {} max rounds ({}) reached",
                 label, max_rounds
             );
+            if worker_caps.is_some() {
+                let violation = Violation::MaxRounds { limit: max_rounds };
+                caps::report(app_handle, project_id, agent_id, &violation);
+                return Err(violation.describe().into());
+            }
+            return Ok(final_text);
+        }
+        if matches!(role, AgentRole::Boss) && is_paused(app_handle, project_id) {
+            info!(
+                "[orchestrator] Boss stopping — project {} paused",
+                project_id
+            );
             return Ok(final_text);
         }
+        let over_budget = budget::exhausted(&app_handle.state::<EngineState>().store, project_id);
+        if let Some(over) = over_budget {
+            if worker_caps.is_none() {
+                budget::pause_for_budget(app_handle, project_id, &over);
+                return Ok(final_text);
+            }
+            let violation = Violation::ProjectBudget {
+                budget_usd: over.budget_usd,
+                spent_usd: over.spent_usd,
+            };
+            caps::report(app_handle, project_id, agent_id, &violation);
+            return Err(violation.describe().into());
+        }
 
         info!(
             "[orchestrator] {} round {}/{} project={}",
@@ -78,9 +173,11 @@ pub(crate) async fn run_orchestrator_loop(
         );
 
         // ── Stream from the AI model ───────────────────────────────
+        let llm_started = std::time::Instant::now();
         let chunks = provider
             .chat_stream(messages, tools, model, None, None)
             .await?;
+        let llm_ms = llm_started.elapsed().as_millis() as u64;
 
         let mut text_accum = String::new();
         let mut tool_call_map: std::collections::HashMap<
@@ -152,6 +249,23 @@ pub(crate) async fn run_orchestrator_loop(
             }
         }
 
+        spent_usd += record_round_usage(
+            app_handle,
+            session_id,
+            model,
+            &chunks,
+            tool_call_map.len() as u32,
+            llm_ms,
+        );
+        if let Some(caps) = worker_caps.filter(|c| c.over_budget(spent_usd)) {
+            let violation = Violation::MaxCost {
+                limit_usd: caps.max_cost_usd,
+                spent_usd,
+            };
+            caps::report(app_handle, project_id, agent_id, &violation);
+            return Err(violation.describe().into());
+        }
+
         // ── No tool calls → final response ─────────────────────────
         if !has_tool_calls || tool_call_map.is_empty() {
             final_text = text_accum.clone();
@@ -175,6 +289,7 @@ pub(crate) async fn run_orchestrator_loop(
                         model: confirmed_model.clone(),
                         total_rounds: Some(round),
                         max_rounds: Some(max_rounds),
+                        citations: Vec::new(),
                     },
                 );
             }
@@ -230,6 +345,23 @@ pub(crate) async fn run_orchestrator_loop(
                 label, tc.function.name, tc.id
             );
 
+            // Tools withheld from a worker are refused, and the boss told
+            if let Some(group) = worker_caps.and_then(|c| c.denied_tools.get(&tc.function.name)) {
+                let violation = Violation::DeniedTool {
+                    tool: tc.function.name.clone(),
+                    group: group.clone(),
+                };
+                caps::report(app_handle, project_id, agent_id, &violation);
+                messages.push(Message {
+                    role: Role::Tool,
+                    content: MessageContent::Text(format!("Error: {}", violation.describe())),
+                    tool_calls: None,
+                    tool_call_id: Some(tc.id.clone()),
+                    name: Some(tc.function.name.clone()),
+                });
+                continue;
+            }
+
             // Try role-specific interception first
             let intercepted: Option<Result<String, String>> = match &role {
                 AgentRole::Boss => {
@@ -266,6 +398,7 @@ pub(crate) async fn run_orchestrator_loop(
                         tool_call_id: tc.id.clone(),
                         output: output.clone(),
                         success: true,
+                        error_kind: None,
                         duration_ms: None,
                     },
                 );
@@ -300,6 +433,7 @@ pub(crate) async fn run_orchestrator_loop(
                         round_number: Some(round + 1),
                         loaded_tools: None,
                         context_tokens: None,
+                        autonomy: None,
                     },
                 );
                 match tokio::time::timeout(
@@ -337,6 +471,7 @@ pub(crate) async fn run_orchestrator_loop(
                     tool_call_id: tc.id.clone(),
                     output: result.output.clone(),
                     success: result.success,
+                    error_kind: result.error_kind,
                     duration_ms: None,
                 },
             );
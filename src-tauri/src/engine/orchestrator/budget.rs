@@ -0,0 +1,125 @@
+// Paw Agent Engine — Orchestrator Project Budgets
+//
+// The boss's `estimate_cost` tool prices a plan (see engine::project_budget)
+// against what is left of the project budget. The budget itself is enforced
+// in the agent loop: every round of the boss and of each worker first checks
+// the project's spend so far. Once the budget is used up, workers stop and
+// tell the boss, and the boss pauses the project — raising the budget and
+// resuming carries on from there.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::project_budget::{self, PlannedTask, TaskCostProfile};
+use crate::engine::sessions::SessionStore;
+use crate::engine::state::EngineState;
+use log::{info, warn};
+use tauri::{Emitter, Manager};
+
+use super::control::event_message;
+use super::resolve_boss_model;
+use super::sub_agent::resolve_worker_model;
+
+/// `metadata.event` of the system message a budget stop leaves on the bus.
+const EVENT_BUDGET_EXHAUSTED: &str = "budget_exhausted";
+
+/// Past runs whose post-mortems the per-task averages come from.
+const HISTORY_RUNS: i64 = 20;
+
+/// A project that has spent its budget.
+pub(crate) struct Exhausted {
+    pub budget_usd: f64,
+    pub spent_usd: f64,
+}
+
+/// Whether the project has a budget and has spent it.
+pub(crate) fn exhausted(store: &SessionStore, project_id: &str) -> Option<Exhausted> {
+    let budget_usd = store.get_project_budget(project_id).ok()?;
+    if budget_usd <= 0.0 {
+        return None;
+    }
+    let spent_usd = store.get_project_spend(project_id).ok()?;
+    (spent_usd >= budget_usd).then_some(Exhausted {
+        budget_usd,
+        spent_usd,
+    })
+}
+
+/// Pause a project whose budget is used up (called by the boss loop).
+pub(crate) fn pause_for_budget(app_handle: &tauri::AppHandle, project_id: &str, over: &Exhausted) {
+    let state = app_handle.state::<EngineState>();
+    info!(
+        "[orchestrator] Project {} used its budget (${:.4} of ${:.2}) — pausing",
+        project_id, over.spent_usd, over.budget_usd
+    );
+    if let Err(e) = state.store.set_project_status(project_id, "paused") {
+        warn!("[orchestrator] Failed to pause project: {}", e);
+        return;
+    }
+    let msg = event_message(
+        project_id,
+        EVENT_BUDGET_EXHAUSTED,
+        format!(
+            "Project budget used up: ${:.4} spent of ${:.2}. Paused — raise the budget and \
+             resume to continue.",
+            over.spent_usd, over.budget_usd
+        ),
+    );
+    state.store.add_project_message(&msg).ok();
+    app_handle
+        .emit(
+            "project-event",
+            serde_json::json!({
+                "kind": "project_paused",
+                "project_id": project_id,
+                "reason": "budget",
+            }),
+        )
+        .ok();
+}
+
+/// The boss's `estimate_cost` tool.
+pub(crate) fn handle_estimate_cost(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> EngineResult<String> {
+    let steps = args["plan"]
+        .as_array()
+        .filter(|p| !p.is_empty())
+        .ok_or("estimate_cost requires a plan with at least one task")?;
+    let state = app_handle.state::<EngineState>();
+    let project = state
+        .store
+        .list_projects()?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let (plan, boss_model) = {
+        let cfg = state.config.lock();
+        let mut plan = Vec::new();
+        for step in steps {
+            let agent_id = step["agent_id"].as_str().unwrap_or("");
+            let agent = project
+                .agents
+                .iter()
+                .find(|a| a.agent_id == agent_id)
+                .ok_or_else(|| format!("'{}' is not on this project's team", agent_id))?;
+            plan.push(PlannedTask {
+                agent_id: agent_id.to_string(),
+                task: step["task"].as_str().unwrap_or("").to_string(),
+                model: resolve_worker_model(&cfg, agent_id, Some(agent)),
+            });
+        }
+        (plan, resolve_boss_model(&cfg, &project))
+    };
+
+    let history = state.store.list_project_postmortems(HISTORY_RUNS)?;
+    let profile = TaskCostProfile::from_postmortems(&history);
+    let estimate = project_budget::estimate(&plan, &boss_model, &profile);
+    let spent = state.store.get_project_spend(project_id)?;
+    Ok(project_budget::format_estimate(
+        &estimate,
+        project.budget_usd,
+        spent,
+    ))
+}
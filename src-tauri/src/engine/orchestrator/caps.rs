@@ -0,0 +1,225 @@
+// Paw Agent Engine — Orchestrator Worker Caps
+//
+// EngineConfig.worker_limits caps what one delegated task may use, per
+// worker specialty: tool rounds, estimated spend and tool groups.
+// run_sub_agent withholds the denied groups' tools and the agent loop
+// enforces the rest. Hitting a cap is not a silent failure: the violation is
+// posted to the message bus as an "error" for the boss, so it can re-plan —
+// split the task, hand it to another agent or do without.
+//
+// With `isolate_workspaces` on, each delegation also works in its own empty
+// directory (tools::with_workspace), so parallel tasks of the same agent
+// cannot trample each other's files.
+
+use crate::engine::state::EngineState;
+use crate::engine::types::*;
+use log::warn;
+use std::collections::HashMap;
+use tauri::{Emitter, Manager};
+
+/// The caps of one delegation, resolved from the config.
+pub(crate) struct Caps {
+    pub max_rounds: u32,
+    /// 0 = no cap.
+    pub max_cost_usd: f64,
+    /// Withheld tool name → its group.
+    pub denied_tools: HashMap<String, String>,
+}
+
+impl Caps {
+    /// Resolve the caps of a worker with this specialty.
+    pub fn resolve(app_handle: &tauri::AppHandle, cfg: &EngineConfig, specialty: &str) -> Caps {
+        let caps = cfg.worker_limits.caps_for(specialty);
+        let mut denied_tools = HashMap::new();
+        for group in &caps.denied_tool_groups {
+            if !crate::engine::tools::TOOL_GROUPS.contains(&group.as_str()) {
+                warn!(
+                    "[orchestrator] Unknown tool group in worker_limits: {}",
+                    group
+                );
+                continue;
+            }
+            for tool in crate::engine::tools::group_tools(group, app_handle) {
+                denied_tools.insert(tool.function.name, group.clone());
+            }
+        }
+        Caps {
+            max_rounds: match caps.max_rounds {
+                0 => cfg.max_tool_rounds,
+                n => n,
+            },
+            max_cost_usd: caps.max_cost_usd.max(0.0),
+            denied_tools,
+        }
+    }
+
+    /// Drop the withheld tools from a worker's tool list.
+    pub fn filter_tools(&self, tools: &mut Vec<ToolDefinition>) {
+        tools.retain(|t| !self.denied_tools.contains_key(&t.function.name));
+    }
+
+    /// Whether spend so far breaks the cost cap.
+    pub fn over_budget(&self, spent_usd: f64) -> bool {
+        self.max_cost_usd > 0.0 && spent_usd > self.max_cost_usd
+    }
+}
+
+/// A worker hitting one of its caps.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Violation {
+    MaxRounds {
+        limit: u32,
+    },
+    MaxCost {
+        limit_usd: f64,
+        spent_usd: f64,
+    },
+    DeniedTool {
+        tool: String,
+        group: String,
+    },
+    /// The whole project's budget is used up (see budget.rs)
+    ProjectBudget {
+        budget_usd: f64,
+        spent_usd: f64,
+    },
+}
+
+impl Violation {
+    fn kind(&self) -> &'static str {
+        match self {
+            Violation::MaxRounds { .. } => "max_rounds",
+            Violation::MaxCost { .. } => "max_cost",
+            Violation::DeniedTool { .. } => "denied_tool",
+            Violation::ProjectBudget { .. } => "project_budget",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Violation::MaxRounds { limit } => format!(
+                "Stopped after the round limit ({} rounds) without finishing the task",
+                limit
+            ),
+            Violation::MaxCost {
+                limit_usd,
+                spent_usd,
+            } => format!(
+                "Stopped at the cost limit: spent ${:.4} of ${:.4} without finishing the task",
+                spent_usd, limit_usd
+            ),
+            Violation::DeniedTool { tool, group } => format!(
+                "Tried to use `{}`, but the '{}' tool group is not allowed for this agent",
+                tool, group
+            ),
+            Violation::ProjectBudget {
+                budget_usd,
+                spent_usd,
+            } => format!(
+                "Stopped because the project budget is used up (${:.4} spent of ${:.2})",
+                spent_usd, budget_usd
+            ),
+        }
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        let mut meta = match self {
+            Violation::MaxRounds { limit } => serde_json::json!({ "limit": limit }),
+            Violation::MaxCost {
+                limit_usd,
+                spent_usd,
+            } => serde_json::json!({ "limit_usd": limit_usd, "spent_usd": spent_usd }),
+            Violation::DeniedTool { tool, group } => {
+                serde_json::json!({ "tool": tool, "group": group })
+            }
+            Violation::ProjectBudget {
+                budget_usd,
+                spent_usd,
+            } => serde_json::json!({ "budget_usd": budget_usd, "spent_usd": spent_usd }),
+        };
+        meta["violation"] = self.kind().into();
+        meta
+    }
+}
+
+/// Post a violation to the message bus for the boss.
+pub(crate) fn report(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    agent_id: &str,
+    violation: &Violation,
+) {
+    warn!(
+        "[orchestrator] Worker {} in project {}: {}",
+        agent_id,
+        project_id,
+        violation.describe()
+    );
+    let state = app_handle.state::<EngineState>();
+    let msg = ProjectMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        from_agent: agent_id.to_string(),
+        to_agent: Some("boss".into()),
+        kind: "error".into(),
+        content: format!("Resource limit: {}", violation.describe()),
+        metadata: Some(violation.metadata().to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = state.store.add_project_message(&msg) {
+        warn!("[orchestrator] Failed to report violation: {}", e);
+    }
+    app_handle
+        .emit(
+            "project-event",
+            serde_json::json!({
+                "kind": "worker_violation",
+                "project_id": project_id,
+                "agent_id": agent_id,
+                "violation": violation.kind(),
+            }),
+        )
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specialty_caps_replace_the_defaults() {
+        let limits: WorkerLimits = serde_json::from_str(
+            r#"{"defaults":{"max_cost_usd":0.5},
+                "specialties":{"researcher":{"max_rounds":5,"denied_tool_groups":["exec"]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(limits.caps_for("coder").max_cost_usd, 0.5);
+        let researcher = limits.caps_for("researcher");
+        assert_eq!(researcher.max_rounds, 5);
+        assert_eq!(researcher.max_cost_usd, 0.0);
+        assert_eq!(researcher.denied_tool_groups, vec!["exec".to_string()]);
+        assert!(!limits.isolate_workspaces);
+    }
+
+    #[test]
+    fn violations_carry_their_kind_for_the_boss() {
+        let caps = Caps {
+            max_rounds: 10,
+            max_cost_usd: 0.25,
+            denied_tools: HashMap::new(),
+        };
+        assert!(!caps.over_budget(0.25));
+        assert!(caps.over_budget(0.26));
+
+        let v = Violation::MaxCost {
+            limit_usd: 0.25,
+            spent_usd: 0.26,
+        };
+        assert_eq!(v.metadata()["violation"], "max_cost");
+        let denied = Violation::DeniedTool {
+            tool: "exec".into(),
+            group: "exec".into(),
+        };
+        assert_eq!(denied.metadata()["group"], "exec");
+    }
+}
@@ -0,0 +1,321 @@
+// Paw Agent Engine — Orchestrator Pause / Resume / Step Re-run
+//
+// Pausing sets the project status to "paused" (persisted, so a pause
+// survives a restart). The boss stops at the start of its next round and
+// `delegate_task` refuses new work; sub-agents already working finish and
+// report to the message bus as usual.
+//
+// Resuming starts a new boss run. The boss session only keeps the goal and
+// the boss's final answers, so the run opens with a brief rebuilt from the
+// message bus: the team's status and the project log so far. A project left
+// "running" with no boss (the app quit mid-run) can be resumed the same way.
+//
+// Re-running a step hands a past delegation to the same agent again, with
+// the same task and context.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::state::EngineState;
+use crate::engine::types::*;
+use crate::engine::util::safe_truncate;
+use log::info;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use tauri::{Emitter, Manager};
+
+use super::handlers::spawn_sub_agent;
+
+/// `metadata.event` of the system messages marking a run's lifecycle.
+pub(crate) const EVENT_STARTED: &str = "started";
+pub(crate) const EVENT_RESUMED: &str = "resumed";
+const EVENT_PAUSED: &str = "paused";
+
+/// Log entries included in a resume brief.
+const BRIEF_LOG_ENTRIES: usize = 60;
+
+/// Projects whose boss loop is running in this process.
+static ACTIVE: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// Marks a project's boss loop as running until dropped.
+pub(crate) struct ActiveRun(String);
+
+impl ActiveRun {
+    pub(crate) fn claim(project_id: &str) -> EngineResult<Self> {
+        if !ACTIVE.lock().insert(project_id.to_string()) {
+            return Err("The project is already running".into());
+        }
+        Ok(ActiveRun(project_id.to_string()))
+    }
+}
+
+impl Drop for ActiveRun {
+    fn drop(&mut self) {
+        ACTIVE.lock().remove(&self.0);
+    }
+}
+
+fn is_active(project_id: &str) -> bool {
+    ACTIVE.lock().contains(project_id)
+}
+
+/// A system message on the project bus marking a lifecycle event.
+pub(crate) fn event_message(project_id: &str, event: &str, content: String) -> ProjectMessage {
+    ProjectMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        from_agent: "system".into(),
+        to_agent: None,
+        kind: "message".into(),
+        content,
+        metadata: Some(serde_json::json!({ "event": event }).to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+fn event_of(msg: &ProjectMessage) -> Option<String> {
+    let meta: serde_json::Value = serde_json::from_str(msg.metadata.as_deref()?).ok()?;
+    meta["event"].as_str().map(str::to_string)
+}
+
+/// When the run being resumed started: the latest "started" event.
+pub(crate) fn run_started_at(messages: &[ProjectMessage]) -> Option<String> {
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.from_agent == "system" && event_of(m).as_deref() == Some(EVENT_STARTED))
+        .map(|m| m.created_at.clone())
+}
+
+// ── Pause / resume ─────────────────────────────────────────────────────
+
+/// Pause a running project.
+pub fn pause_project(app_handle: &tauri::AppHandle, project_id: &str) -> EngineResult<()> {
+    let state = app_handle.state::<EngineState>();
+    match state.store.get_project_status(project_id)?.as_deref() {
+        Some("running") => {}
+        Some(status) => {
+            return Err(format!("Only a running project can be paused ({})", status).into())
+        }
+        None => return Err(format!("Project not found: {}", project_id).into()),
+    }
+    state.store.set_project_status(project_id, "paused")?;
+    state.store.add_project_message(&event_message(
+        project_id,
+        EVENT_PAUSED,
+        "Project paused by the user. Agents already working will finish their tasks.".into(),
+    ))?;
+    info!("[orchestrator] Project {} paused", project_id);
+    app_handle
+        .emit(
+            "project-event",
+            serde_json::json!({
+                "kind": "project_paused",
+                "project_id": project_id,
+            }),
+        )
+        .ok();
+    Ok(())
+}
+
+/// Check that `engine_project_resume` may start a boss run for the project.
+pub fn check_resumable(state: &EngineState, project_id: &str) -> EngineResult<()> {
+    if let Some(over) = super::budget::exhausted(&state.store, project_id) {
+        return Err(format!(
+            "The project budget is used up (${:.4} spent of ${:.2}) — raise it to resume",
+            over.spent_usd, over.budget_usd
+        )
+        .into());
+    }
+    match state.store.get_project_status(project_id)?.as_deref() {
+        Some("paused") => Ok(()),
+        Some("running") if !is_active(project_id) => Ok(()),
+        Some("running") => Err("The project is already running".into()),
+        Some(status) => Err(format!("Only a paused project can be resumed ({})", status).into()),
+        None => Err(format!("Project not found: {}", project_id).into()),
+    }
+}
+
+/// The boss's opening message on resume, rebuilt from the message bus.
+pub(crate) fn resume_brief(project: &Project, messages: &[ProjectMessage]) -> String {
+    let mut brief = format!(
+        "Project '{}' is being resumed after an interruption. Your earlier conversation may be \
+         incomplete — this is the project's state from its log.\n\nGoal: {}\n\n## Team\n",
+        project.title, project.goal
+    );
+    for a in project.agents.iter().filter(|a| a.role != "boss") {
+        brief.push_str(&format!(
+            "- {} ({}): status={}, task={}\n",
+            a.agent_id,
+            a.specialty,
+            a.status,
+            a.current_task.as_deref().unwrap_or("none")
+        ));
+    }
+
+    let log: Vec<&ProjectMessage> = messages.iter().filter(|m| event_of(m).is_none()).collect();
+    brief.push_str("\n## Log\n");
+    if log.is_empty() {
+        brief.push_str("(nothing yet)\n");
+    }
+    let skipped = log.len().saturating_sub(BRIEF_LOG_ENTRIES);
+    if skipped > 0 {
+        brief.push_str(&format!("({} earlier entries omitted)\n", skipped));
+    }
+    for m in &log[skipped..] {
+        let to = m.to_agent.as_deref().unwrap_or("all");
+        brief.push_str(&format!(
+            "- [{}] {} → {}: {}\n",
+            m.kind,
+            m.from_agent,
+            to,
+            safe_truncate(&m.content, 300)
+        ));
+    }
+    brief.push_str(
+        "\nContinue from here: work out what is still missing, check on agents that are \
+         still working, delegate the remaining work and call `project_complete` when done.",
+    );
+    brief
+}
+
+// ── Step re-run ────────────────────────────────────────────────────────
+
+/// Delegate a past step (a delegation message) to its agent again.
+/// Returns the id of the new delegation.
+pub fn rerun_step(app_handle: &tauri::AppHandle, step_id: &str) -> EngineResult<String> {
+    let state = app_handle.state::<EngineState>();
+    let step = state
+        .store
+        .get_project_message(step_id)?
+        .ok_or_else(|| format!("Step not found: {}", step_id))?;
+    let agent_id = match (&step.to_agent, step.kind.as_str()) {
+        (Some(agent_id), "delegation") => agent_id.clone(),
+        _ => return Err("Only delegated tasks can be re-run".into()),
+    };
+    let project_id = step.project_id.clone();
+    if state.store.get_project_status(&project_id)?.as_deref() == Some("paused") {
+        return Err("The project is paused — resume it to re-run steps".into());
+    }
+    let agents = state.store.get_project_agents(&project_id)?;
+    match agents.iter().find(|a| a.agent_id == agent_id) {
+        Some(a) if a.status == "working" => {
+            return Err(format!("{} is still working on a task", agent_id).into())
+        }
+        Some(_) => {}
+        None => return Err(format!("{} is no longer part of the project", agent_id).into()),
+    }
+
+    let context = step
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+        .and_then(|m| m["context"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    let rerun = ProjectMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: project_id.clone(),
+        from_agent: "user".into(),
+        to_agent: Some(agent_id.clone()),
+        kind: "delegation".into(),
+        content: step.content.clone(),
+        metadata: Some(serde_json::json!({ "context": context, "rerun_of": step_id }).to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.store.add_project_message(&rerun)?;
+    state.store.update_project_agent_status(
+        &project_id,
+        &agent_id,
+        "working",
+        Some(&step.content),
+    )?;
+    info!(
+        "[orchestrator] Re-running step {} with {} in project {}",
+        step_id, agent_id, project_id
+    );
+    app_handle
+        .emit(
+            "project-event",
+            serde_json::json!({
+                "kind": "delegation",
+                "project_id": project_id,
+                "agent_id": agent_id,
+                "task": step.content,
+                "rerun_of": step_id,
+            }),
+        )
+        .ok();
+
+    spawn_sub_agent(app_handle, &project_id, &agent_id, &step.content, &context);
+    Ok(rerun.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(from: &str, to: Option<&str>, kind: &str, content: &str) -> ProjectMessage {
+        ProjectMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: "p1".into(),
+            from_agent: from.into(),
+            to_agent: to.map(str::to_string),
+            kind: kind.into(),
+            content: content.into(),
+            metadata: None,
+            created_at: "2026-01-01 10:00:00".into(),
+        }
+    }
+
+    #[test]
+    fn resume_brief_rebuilds_state_from_the_log() {
+        let project = Project {
+            id: "p1".into(),
+            title: "Launch".into(),
+            goal: "Publish the launch post".into(),
+            status: "paused".into(),
+            boss_agent: "boss".into(),
+            agents: vec![ProjectAgent {
+                agent_id: "writer".into(),
+                role: "worker".into(),
+                specialty: "communicator".into(),
+                status: "done".into(),
+                current_task: None,
+                model: None,
+                system_prompt: None,
+                capabilities: vec![],
+            }],
+            budget_usd: 0.0,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let mut started = event_message("p1", EVENT_STARTED, "Project started".into());
+        started.created_at = "2026-01-01 09:59:00".into();
+        let messages = vec![
+            started,
+            msg("boss", Some("writer"), "delegation", "Draft the post"),
+            msg("writer", Some("boss"), "result", "Draft ready"),
+            event_message("p1", EVENT_PAUSED, "Project paused".into()),
+        ];
+
+        let brief = resume_brief(&project, &messages);
+        assert!(brief.contains("- writer (communicator): status=done, task=none"));
+        assert!(brief.contains("- [delegation] boss → writer: Draft the post"));
+        assert!(brief.contains("- [result] writer → boss: Draft ready"));
+        assert!(
+            !brief.contains("Project paused"),
+            "lifecycle events are left out"
+        );
+        assert_eq!(
+            run_started_at(&messages).as_deref(),
+            Some("2026-01-01 09:59:00")
+        );
+    }
+
+    #[test]
+    fn a_project_runs_once_at_a_time() {
+        let run = ActiveRun::claim("p-active").unwrap();
+        assert!(ActiveRun::claim("p-active").is_err());
+        drop(run);
+        assert!(ActiveRun::claim("p-active").is_ok());
+    }
+}
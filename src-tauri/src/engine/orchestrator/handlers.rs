@@ -1,7 +1,7 @@
 // Paw Agent Engine — Orchestrator Tool Handlers
 //
 // Boss tool handlers: delegate_task, check_agent_status, send_agent_message,
-// project_complete, create_sub_agent.
+// project_complete, create_sub_agent, estimate_cost (budget.rs).
 // Worker tool handler: report_progress (execute_worker_tool).
 
 use crate::engine::sessions::SessionStore;
@@ -49,6 +49,10 @@ pub async fn execute_boss_tool(
         "create_sub_agent" => {
             Some(handle_create_sub_agent(&args, app_handle, project_id).map_err(|e| e.to_string()))
         }
+        "estimate_cost" => Some(
+            super::budget::handle_estimate_cost(&args, app_handle, project_id)
+                .map_err(|e| e.to_string()),
+        ),
         _ => None,
     }
 }
@@ -138,6 +142,18 @@ fn handle_delegate_task(
 
     let store = get_store(app_handle);
     if let Some(ref store) = store {
+        if store.get_project_status(project_id)?.as_deref() == Some("paused") {
+            return Err(
+                "The project is paused — no new delegations until the user resumes it".into(),
+            );
+        }
+        if let Some(over) = super::budget::exhausted(store, project_id) {
+            return Err(format!(
+                "The project budget is used up (${:.4} spent of ${:.2}) — no new delegations",
+                over.spent_usd, over.budget_usd
+            )
+            .into());
+        }
         let msg = ProjectMessage {
             id: uuid::Uuid::new_v4().to_string(),
             project_id: project_id.to_string(),
@@ -170,11 +186,28 @@ fn handle_delegate_task(
         )
         .ok();
 
+    spawn_sub_agent(app_handle, project_id, &agent_id, &task_desc, &context);
+
+    Ok(format!(
+        "Task delegated to agent '{}'. They are now working on: {}",
+        agent_id, task_desc
+    ))
+}
+
+/// Run a sub-agent on `task` in the background, reporting a failure to the
+/// boss on the project message bus.
+pub(crate) fn spawn_sub_agent(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    agent_id: &str,
+    task: &str,
+    context: &str,
+) {
     let app = app_handle.clone();
     let pid = project_id.to_string();
-    let aid = agent_id.clone();
-    let task = task_desc.clone();
-    let ctx = context.clone();
+    let aid = agent_id.to_string();
+    let task = task.to_string();
+    let ctx = context.to_string();
 
     tauri::async_runtime::spawn(async move {
         if let Err(e) = run_sub_agent(&app, &pid, &aid, &task, &ctx).await {
@@ -197,11 +230,6 @@ fn handle_delegate_task(
             }
         }
     });
-
-    Ok(format!(
-        "Task delegated to agent '{}'. They are now working on: {}",
-        agent_id, task_desc
-    ))
 }
 
 fn handle_check_agent_status(
@@ -406,7 +434,7 @@ fn handle_create_sub_agent(
                         name, agent_id, role, specialty, system_prompt
                     );
                     store
-                        .set_agent_file(&agent_id, "IDENTITY.md", &identity_content)
+                        .set_agent_file_as(&agent_id, "IDENTITY.md", &identity_content, "agent")
                         .ok();
 
                     let msg = ProjectMessage {
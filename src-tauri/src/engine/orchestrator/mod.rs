@@ -8,9 +8,17 @@
 //   handlers.rs   — execute_boss_tool / execute_worker_tool + handler fns
 //   agent_loop.rs — unified streaming loop (boss & worker, parameterized)
 //   sub_agent.rs  — run_sub_agent() setup + resolve_provider_for_model()
+//   caps.rs       — worker caps (rounds, spend, tool groups) + violations
+//   postmortem.rs — post-mortem + playbook when a run ends, playbook recall
+//   control.rs    — pause / resume / step re-run
+//   budget.rs     — project budget enforcement + estimate_cost
 
 mod agent_loop;
+mod budget;
+mod caps;
+mod control;
 mod handlers;
+mod postmortem;
 pub(crate) mod sub_agent;
 pub mod tools;
 
@@ -24,6 +32,7 @@ use tauri::{Emitter, Manager};
 use crate::atoms::error::EngineResult;
 use crate::engine::util::safe_truncate;
 use agent_loop::{run_orchestrator_loop, AgentRole};
+pub use control::{check_resumable, pause_project, rerun_step};
 use sub_agent::resolve_provider_for_model;
 use tools::boss_tools;
 
@@ -33,8 +42,46 @@ use tools::boss_tools;
 /// The boss agent gets a special system prompt + delegation tools,
 /// and orchestrates sub-agents to achieve the project goal.
 pub async fn run_project(app_handle: &tauri::AppHandle, project_id: &str) -> EngineResult<String> {
+    run_boss(app_handle, project_id, false).await
+}
+
+/// Continue a paused or interrupted project with a new boss run that
+/// starts from the project's message bus (see control.rs).
+pub async fn resume_project(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> EngineResult<String> {
+    run_boss(app_handle, project_id, true).await
+}
+
+/// The model a project's boss runs on: per-agent field > model_routing > default.
+pub(crate) fn resolve_boss_model(cfg: &EngineConfig, project: &Project) -> String {
+    let boss_entry = project.agents.iter().find(|a| a.role == "boss");
+    if let Some(agent_model) = boss_entry
+        .and_then(|a| a.model.as_deref())
+        .filter(|m| !m.is_empty())
+    {
+        return agent_model.to_string();
+    }
+    let default_model = cfg
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "gpt-5.1".to_string());
+    let boss_specialty = boss_entry
+        .map(|a| a.specialty.as_str())
+        .unwrap_or("general");
+    cfg.model_routing
+        .resolve(&project.boss_agent, "boss", boss_specialty, &default_model)
+}
+
+async fn run_boss(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    resume: bool,
+) -> EngineResult<String> {
     let state = app_handle.state::<EngineState>();
     let run_id = uuid::Uuid::new_v4().to_string();
+    let _active = control::ActiveRun::claim(project_id)?;
 
     // Load project
     let projects = state.store.list_projects()?;
@@ -48,12 +95,25 @@ pub async fn run_project(app_handle: &tauri::AppHandle, project_id: &str) -> Eng
     }
 
     info!(
-        "[orchestrator] Starting project '{}' with {} agents, boss='{}'",
+        "[orchestrator] {} project '{}' with {} agents, boss='{}'",
+        if resume { "Resuming" } else { "Starting" },
         project.title,
         project.agents.len(),
         project.boss_agent
     );
 
+    // On resume, the run keeps its original start and the boss is briefed
+    // from the message bus
+    let (started_at, resume_brief) = if resume {
+        let log = state.store.get_project_messages(project_id, 500)?;
+        (
+            control::run_started_at(&log).unwrap_or_else(crate::engine::postmortem::db_now),
+            Some(control::resume_brief(&project, &log)),
+        )
+    } else {
+        (crate::engine::postmortem::db_now(), None)
+    };
+
     // Update project status to running
     {
         let mut p = project.clone();
@@ -66,50 +126,35 @@ pub async fn run_project(app_handle: &tauri::AppHandle, project_id: &str) -> Eng
         .emit(
             "project-event",
             serde_json::json!({
-                "kind": "project_started",
+                "kind": if resume { "project_resumed" } else { "project_started" },
                 "project_id": project_id,
             }),
         )
         .ok();
 
     // Record initial message
-    let init_msg = ProjectMessage {
-        id: uuid::Uuid::new_v4().to_string(),
-        project_id: project_id.to_string(),
-        from_agent: "system".into(),
-        to_agent: None,
-        kind: "message".into(),
-        content: format!(
-            "Project '{}' started. Goal: {}",
-            project.title, project.goal
-        ),
-        metadata: None,
-        created_at: chrono::Utc::now().to_rfc3339(),
+    let init_msg = if resume {
+        control::event_message(
+            project_id,
+            control::EVENT_RESUMED,
+            format!("Project '{}' resumed.", project.title),
+        )
+    } else {
+        control::event_message(
+            project_id,
+            control::EVENT_STARTED,
+            format!(
+                "Project '{}' started. Goal: {}",
+                project.title, project.goal
+            ),
+        )
     };
     state.store.add_project_message(&init_msg)?;
 
     // Get provider config — use model routing for boss agent
     let (provider_config, model) = {
         let cfg = state.config.lock();
-        let default_model = cfg
-            .default_model
-            .clone()
-            .unwrap_or_else(|| "gpt-5.1".to_string());
-
-        let boss_entry = project.agents.iter().find(|a| a.role == "boss");
-        let boss_specialty = boss_entry
-            .map(|a| a.specialty.as_str())
-            .unwrap_or("general");
-
-        let model = if let Some(agent_model) = boss_entry
-            .and_then(|a| a.model.as_deref())
-            .filter(|m| !m.is_empty())
-        {
-            agent_model.to_string()
-        } else {
-            cfg.model_routing
-                .resolve(&project.boss_agent, "boss", boss_specialty, &default_model)
-        };
+        let model = resolve_boss_model(&cfg, &project);
 
         info!(
             "[orchestrator] Boss agent '{}' using model '{}'",
@@ -167,7 +212,7 @@ pub async fn run_project(app_handle: &tauri::AppHandle, project_id: &str) -> Eng
         cfg.context_window_tokens
     };
 
-    let orchestrator_context = format!(
+    let mut orchestrator_context = format!(
         r#"## Orchestrator Mode
 
 You are the **Boss Agent** orchestrating project "{}".
@@ -199,6 +244,17 @@ You are the **Boss Agent** orchestrating project "{}".
             agent_roster.join("\n")
         }
     );
+    if project.budget_usd > 0.0 {
+        let spent = state.store.get_project_spend(project_id).unwrap_or(0.0);
+        orchestrator_context.push_str(&format!(
+            "\n\n### Budget\nThis project has a hard budget of ${:.2}; ${:.4} is spent so far. \
+             Before delegating, price your plan with `estimate_cost` and keep it within what is \
+             left. When the budget is used up, all work stops and the project is paused.",
+            project.budget_usd, spent
+        ));
+    }
+
+    let playbooks = postmortem::playbook_section(&state, &project);
 
     let boss_system_prompt = {
         let emb_client = state.embedding_client();
@@ -218,6 +274,9 @@ You are the **Boss Agent** orchestrating project "{}".
         }
         // Orchestrator context at priority 1 (critical — it's the boss's instructions)
         builder = builder.custom_section("orchestrator_mode", &orchestrator_context, 1);
+        if let Some(ref playbooks) = playbooks {
+            builder = builder.custom_section("project_playbooks", playbooks, 3);
+        }
         // Team roster at priority 4
         if !agent_roster.is_empty() {
             builder = builder.agent_roster(agent_roster.join("\n"));
@@ -259,6 +318,9 @@ You are the **Boss Agent** orchestrating project "{}".
                     parts.push(soul.clone());
                 }
                 parts.push(orchestrator_context.clone());
+                if let Some(ref playbooks) = playbooks {
+                    parts.push(playbooks.clone());
+                }
                 parts.join("\n\n---\n\n")
             }
         }
@@ -295,15 +357,17 @@ You are the **Boss Agent** orchestrating project "{}".
         )?;
     }
 
-    // User message = project goal
+    // User message = project goal, or the resume brief
     let user_msg = StoredMessage {
         id: uuid::Uuid::new_v4().to_string(),
         session_id: session_id.clone(),
         role: "user".into(),
-        content: format!(
-            "Execute this project:\n\nTitle: {}\nGoal: {}",
-            project.title, project.goal
-        ),
+        content: resume_brief.unwrap_or_else(|| {
+            format!(
+                "Execute this project:\n\nTitle: {}\nGoal: {}",
+                project.title, project.goal
+            )
+        }),
         tool_calls_json: None,
         tool_call_id: None,
         name: None,
@@ -337,6 +401,10 @@ You are the **Boss Agent** orchestrating project "{}".
     )
     .await;
 
+    // A paused run isn't over: no outcome capture, post-mortem or finish
+    let paused =
+        result.is_ok() && state.store.get_project_status(project_id)?.as_deref() == Some("paused");
+
     // Save final response
     match &result {
         Ok(text) => {
@@ -354,7 +422,7 @@ You are the **Boss Agent** orchestrating project "{}".
             state.store.add_message(&stored).ok();
 
             // §17 Post-capture: store project outcome in Engram memory
-            if !text.is_empty() {
+            if !text.is_empty() && !paused {
                 let summary = if text.len() > 4000 {
                     &text[..4000]
                 } else {
@@ -406,6 +474,25 @@ You are the **Boss Agent** orchestrating project "{}".
         }
     }
 
+    if paused {
+        info!("[orchestrator] Project '{}' paused", project.title);
+        return result;
+    }
+
+    let summary = match &result {
+        Ok(text) => text.clone(),
+        Err(err) => format!("Project failed: {}", err),
+    };
+    postmortem::record(
+        app_handle,
+        project_id,
+        &started_at,
+        &summary,
+        &provider,
+        &model,
+    )
+    .await;
+
     app_handle
         .emit(
             "project-event",
@@ -0,0 +1,110 @@
+// Paw Agent Engine — Orchestrator Post-mortems
+//
+// Called when a project run ends: builds the run's post-mortem from the
+// project messages and telemetry since `started_at`, stores it with the
+// project and, with `project_playbooks` on, distills a playbook with the
+// boss's model. See engine::postmortem.
+
+use crate::engine::postmortem;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::EngineState;
+use crate::engine::types::*;
+use log::{info, warn};
+use tauri::{Emitter, Manager};
+
+/// Project messages read for one run — far above what a run produces.
+const MAX_MESSAGES: i64 = 2000;
+
+/// Write the post-mortem of the run that started at `started_at`.
+pub(crate) async fn record(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    started_at: &str,
+    summary: &str,
+    provider: &AnyProvider,
+    model: &str,
+) {
+    let state = app_handle.state::<EngineState>();
+    let project = match state.store.list_projects() {
+        Ok(projects) => match projects.into_iter().find(|p| p.id == project_id) {
+            Some(p) => p,
+            None => return,
+        },
+        Err(e) => {
+            warn!("[orchestrator] Post-mortem skipped: {}", e);
+            return;
+        }
+    };
+    let messages: Vec<ProjectMessage> = state
+        .store
+        .get_project_messages(project_id, MAX_MESSAGES)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| m.created_at.as_str() >= started_at)
+        .collect();
+    let usage = state
+        .store
+        .get_project_usage(project_id, started_at)
+        .unwrap_or_default();
+
+    let mut report = postmortem::build(
+        &project,
+        &messages,
+        usage,
+        started_at,
+        &postmortem::db_now(),
+        summary,
+    );
+
+    let learn = state.config.lock().project_playbooks;
+    if learn && !report.delegations.is_empty() {
+        let steps = postmortem::distill_playbook(provider, model, &report).await;
+        if !steps.is_empty() {
+            match postmortem::store_playbook(&state.store, &project.boss_agent, &report, steps) {
+                Ok(id) => report.playbook_id = Some(id),
+                Err(e) => warn!("[orchestrator] Failed to store playbook: {}", e),
+            }
+        }
+    }
+
+    if let Err(e) = state.store.save_project_postmortem(&report) {
+        warn!("[orchestrator] Failed to store post-mortem: {}", e);
+        return;
+    }
+    info!(
+        "[orchestrator] Post-mortem for '{}': {} delegations, {} failures, ${:.4}",
+        project.title,
+        report.delegations.len(),
+        report.failures.len(),
+        report.total_cost_usd
+    );
+    app_handle
+        .emit(
+            "project-event",
+            serde_json::json!({
+                "kind": "postmortem_ready",
+                "project_id": project_id,
+                "playbook_id": report.playbook_id,
+            }),
+        )
+        .ok();
+}
+
+/// Playbooks for the project's goal, as a section for the boss's
+/// instructions. None when playbooks are off or none match.
+pub(crate) fn playbook_section(state: &EngineState, project: &Project) -> Option<String> {
+    if !state.config.lock().project_playbooks {
+        return None;
+    }
+    let playbooks =
+        postmortem::find_playbooks(&state.store, &project.boss_agent, &project.goal, 2).ok()?;
+    if playbooks.is_empty() {
+        return None;
+    }
+    info!(
+        "[orchestrator] {} playbook(s) from similar projects for '{}'",
+        playbooks.len(),
+        project.title
+    );
+    Some(postmortem::format_playbooks(&playbooks))
+}
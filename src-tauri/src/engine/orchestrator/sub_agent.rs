@@ -1,7 +1,7 @@
 // Paw Agent Engine — Orchestrator Sub-Agent Runner
 //
 // Sets up and runs a worker sub-agent within a project.
-// Builds its system prompt, tool set, capability filter, and session,
+// Builds its system prompt, tool set, capability filter, caps and session,
 // then delegates to the unified `run_orchestrator_loop`.
 
 use crate::engine::providers::AnyProvider;
@@ -12,6 +12,7 @@ use log::info;
 use tauri::{Emitter, Manager};
 
 use super::agent_loop::{run_orchestrator_loop, AgentRole};
+use super::caps::Caps;
 use super::handlers::get_store;
 use super::tools::worker_tools;
 use crate::atoms::error::EngineResult;
@@ -69,6 +70,27 @@ pub(crate) fn resolve_provider_for_model(
         .or_else(|| cfg.providers.first().cloned())
 }
 
+/// The model a project worker runs on: per-agent field > model_routing > default.
+pub(crate) fn resolve_worker_model(
+    cfg: &EngineConfig,
+    agent_id: &str,
+    agent: Option<&ProjectAgent>,
+) -> String {
+    if let Some(agent_model) = agent
+        .and_then(|a| a.model.as_deref())
+        .filter(|m| !m.is_empty())
+    {
+        return agent_model.to_string();
+    }
+    let default_model = cfg
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "gpt-5.1".to_string());
+    let specialty = agent.map(|a| a.specialty.as_str()).unwrap_or("general");
+    cfg.model_routing
+        .resolve(agent_id, "worker", specialty, &default_model)
+}
+
 /// Run a sub-agent on a delegated task within a project.
 pub(crate) async fn run_sub_agent(
     app_handle: &tauri::AppHandle,
@@ -82,10 +104,6 @@ pub(crate) async fn run_sub_agent(
     // Get provider — use model routing for worker agents
     let (provider_config, model, agent_capabilities, agent_specialty) = {
         let cfg = state.config.lock();
-        let default_model = cfg
-            .default_model
-            .clone()
-            .unwrap_or_else(|| "gpt-5.1".to_string());
 
         // Look up this agent in the project to get specialty and per-agent model override
         let agent_entry = state
@@ -102,17 +120,7 @@ pub(crate) async fn run_sub_agent(
             .map(|a| a.capabilities.clone())
             .unwrap_or_default();
 
-        // Resolve model: per-agent field > model_routing > default
-        let model = if let Some(agent_model) = agent_entry
-            .as_ref()
-            .and_then(|a| a.model.as_deref())
-            .filter(|m| !m.is_empty())
-        {
-            agent_model.to_string()
-        } else {
-            cfg.model_routing
-                .resolve(agent_id, "worker", &specialty, &default_model)
-        };
+        let model = resolve_worker_model(&cfg, agent_id, agent_entry.as_ref());
 
         info!(
             "[orchestrator] Worker agent '{}' (specialty={}) using model '{}'",
@@ -126,14 +134,26 @@ pub(crate) async fn run_sub_agent(
         }
     };
 
-    let (base_system_prompt, max_rounds, tool_timeout) = {
+    let (base_system_prompt, tool_timeout, caps, isolate) = {
         let cfg = state.config.lock();
         (
             cfg.default_system_prompt.clone(),
-            cfg.max_tool_rounds,
             cfg.tool_timeout_secs,
+            Caps::resolve(app_handle, &cfg, &agent_specialty),
+            cfg.worker_limits.isolate_workspaces,
         )
     };
+    let run_id = uuid::Uuid::new_v4().to_string();
+
+    // Isolated workspace for this delegation
+    let workspace = if isolate {
+        let dir = crate::engine::paths::delegation_workspace_dir(project_id, &run_id);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create the task workspace: {}", e))?;
+        Some(dir)
+    } else {
+        None
+    };
 
     // Build system prompt for sub-agent
     let agent_soul = state.store.compose_agent_context(agent_id).unwrap_or(None);
@@ -177,6 +197,35 @@ Your boss agent has delegated this task to you.
         }
     ));
 
+    let mut limits = vec![format!("- At most {} tool rounds.", caps.max_rounds)];
+    if caps.max_cost_usd > 0.0 {
+        limits.push(format!(
+            "- At most ${:.2} of model usage.",
+            caps.max_cost_usd
+        ));
+    }
+    if !caps.denied_tools.is_empty() {
+        let mut groups: Vec<&str> = caps.denied_tools.values().map(String::as_str).collect();
+        groups.sort();
+        groups.dedup();
+        limits.push(format!(
+            "- These tool groups are not available to you: {}.",
+            groups.join(", ")
+        ));
+    }
+    if let Some(dir) = &workspace {
+        limits.push(format!(
+            "- Your working directory for this task is {} (it starts empty). Mention the \
+             files you leave there in your final report.",
+            dir.display()
+        ));
+    }
+    sys_parts.push(format!(
+        "## Task Limits\nThis task runs under limits. If you hit one, the task stops and the boss \
+         is told.\n{}",
+        limits.join("\n")
+    ));
+
     // Automation-executor specialty: add foreman instructions
     if agent_specialty == "automation-executor" {
         sys_parts.push(r#"## Automation Executor Mode
@@ -220,6 +269,8 @@ When told to install a package:
     all_tools.extend(worker_tools());
     // Add tools from connected MCP servers
     all_tools.extend(crate::engine::tools::mcp_tools(app_handle));
+    // Withhold the tool groups the worker's caps deny
+    caps.filter_tools(&mut all_tools);
 
     // Apply per-agent tool capabilities filter
     if !agent_capabilities.is_empty() {
@@ -245,7 +296,6 @@ When told to install a package:
 
     // Create per-agent session
     let session_id = format!("eng-project-{}-{}", project_id, agent_id);
-    let run_id = uuid::Uuid::new_v4().to_string();
 
     if state
         .store
@@ -288,7 +338,7 @@ When told to install a package:
     let aid = agent_id.to_string();
 
     // Run the unified agent loop as a Worker
-    let result = run_orchestrator_loop(
+    let agent_loop = run_orchestrator_loop(
         app_handle,
         &provider,
         &model,
@@ -296,14 +346,22 @@ When told to install a package:
         &all_tools,
         &session_id,
         &run_id,
-        max_rounds,
+        caps.max_rounds,
         &pending,
         tool_timeout,
         &pid,
         &aid,
-        AgentRole::Worker { agent_id: &aid },
-    )
-    .await;
+        AgentRole::Worker {
+            agent_id: &aid,
+            caps: &caps,
+        },
+    );
+    let result = match &workspace {
+        Some(dir) => crate::engine::tools::with_workspace(dir.clone(), agent_loop).await,
+        None => agent_loop.await,
+    };
+    // Keep the workspace only if the task left files in it
+    let workspace = workspace.filter(|dir| std::fs::remove_dir(dir).is_err());
 
     // Record result
     let store = get_store(app_handle);
@@ -320,7 +378,9 @@ When told to install a package:
                     to_agent: Some("boss".into()),
                     kind: "result".into(),
                     content: format!("Task completed: {}", safe_truncate(text, 500)),
-                    metadata: None,
+                    metadata: workspace.as_ref().map(|dir| {
+                        serde_json::json!({ "workspace": dir.to_string_lossy() }).to_string()
+                    }),
                     created_at: chrono::Utc::now().to_rfc3339(),
                 };
                 store.add_project_message(&msg).ok();
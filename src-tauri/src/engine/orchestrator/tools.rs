@@ -1,7 +1,8 @@
 // Paw Agent Engine — Orchestrator Tool Definitions
 //
 // Boss and worker agents each get a distinct set of orchestrator-specific tools.
-// Boss: delegate_task, check_agent_status, send_agent_message, project_complete, create_sub_agent
+// Boss: delegate_task, check_agent_status, send_agent_message, project_complete, create_sub_agent,
+//       estimate_cost
 // Worker: report_progress
 
 use crate::engine::types::*;
@@ -129,6 +130,37 @@ pub fn boss_tools() -> Vec<ToolDefinition> {
                 }),
             },
         },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "estimate_cost".into(),
+                description: "Estimate what a plan of delegated tasks will cost, priced for the model each agent runs on and based on what past tasks used, and check it against the project budget. Call this before delegating.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "plan": {
+                            "type": "array",
+                            "description": "The tasks you intend to delegate",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "agent_id": {
+                                        "type": "string",
+                                        "description": "The team member who would do the task"
+                                    },
+                                    "task": {
+                                        "type": "string",
+                                        "description": "Short description of the task"
+                                    }
+                                },
+                                "required": ["agent_id", "task"]
+                            }
+                        }
+                    },
+                    "required": ["plan"]
+                }),
+            },
+        },
     ]
 }
 
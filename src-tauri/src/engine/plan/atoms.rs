@@ -131,6 +131,8 @@ pub enum PlanValidationError {
     UnknownTool { node_id: String, tool: String },
     /// A node's arguments contain a raw secret.
     SecretInArguments { node_id: String, field: String },
+    /// A node calls a tool that must be approved by the user on its own.
+    NeedsApproval { node_id: String, tool: String },
 }
 
 impl std::fmt::Display for PlanValidationError {
@@ -161,6 +163,13 @@ impl std::fmt::Display for PlanValidationError {
                     field, node_id
                 )
             }
+            Self::NeedsApproval { node_id, tool } => {
+                write!(
+                    f,
+                    "Node '{}' uses '{}', which needs the user's approval — call it on its own",
+                    node_id, tool
+                )
+            }
         }
     }
 }
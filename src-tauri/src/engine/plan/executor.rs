@@ -263,6 +263,7 @@ async fn execute_node_with_retry(
                         tool_call_id: tool_call.id.clone(),
                         output: result.output.clone(),
                         success: result.success,
+                        error_kind: result.error_kind,
                         duration_ms: Some(duration_ms),
                     },
                 );
@@ -66,6 +66,12 @@ pub fn validate_plan(
                 tool: node.tool.clone(),
             });
         }
+        if openpawz_core::engine::tool_metadata::needs_consent(&node.tool) {
+            errors.push(PlanValidationError::NeedsApproval {
+                node_id: node.id.clone(),
+                tool: node.tool.clone(),
+            });
+        }
     }
 
     errors
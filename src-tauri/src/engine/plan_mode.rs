@@ -0,0 +1,381 @@
+// Paw Agent Engine — Plan-then-Execute Mode
+//
+// Optional wrapper around `run_agent_turn` for chat requests with
+// `plan_mode: true`:
+//   1. Ask the model (no tools) for a structured plan: goal, steps, risks.
+//   2. Emit `plan_proposed` and wait for `engine_plan_respond` — the user
+//      approves (possibly with edited steps) or rejects.
+//   3. Run each approved step as its own agent turn, in order, emitting
+//      `plan_step_status` (running → done/failed, remaining → skipped).
+//
+// If the model doesn't return a parseable plan, the request falls back to
+// a normal agent turn so the user still gets an answer.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::agent_loop::run_agent_turn;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{DailyTokenTracker, PendingApprovals, YieldSignal};
+use crate::engine::types::*;
+use crate::engine::util::safe_truncate;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// How long to wait for the user to approve a plan.
+const PLAN_DECISION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Upper bound on steps in one plan.
+const MAX_PLAN_STEPS: usize = 12;
+
+/// Max chars of step output carried in `plan_step_status` summaries.
+const STEP_SUMMARY_CHARS: usize = 300;
+
+/// The user's answer to a proposed plan.
+#[derive(Debug)]
+pub enum PlanDecision {
+    /// Run the proposed steps (`None`) or the user's edited version.
+    Approve(Option<Vec<PlanStep>>),
+    Reject,
+}
+
+/// Plans awaiting a decision, keyed by plan id.
+static PENDING_PLANS: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<PlanDecision>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Deliver the user's decision. Returns false if the plan is unknown
+/// (already decided, timed out, or the run was aborted).
+pub fn resolve(plan_id: &str, decision: PlanDecision) -> bool {
+    match PENDING_PLANS.lock().remove(plan_id) {
+        Some(sender) => sender.send(decision).is_ok(),
+        None => false,
+    }
+}
+
+// ── Plan parsing ───────────────────────────────────────────────────────
+
+/// Plan JSON as requested from the model.
+#[derive(Debug, Deserialize)]
+struct PlanDraft {
+    #[serde(default)]
+    goal: String,
+    steps: Vec<PlanStep>,
+    #[serde(default)]
+    risks: Vec<String>,
+}
+
+const PLANNER_PROMPT: &str = "You are in PLANNING mode. Do not call tools and do not do the work yet. \
+Write a short plan for the user's latest request and respond with ONLY a JSON object:\n\
+{\"goal\": \"...\", \"steps\": [{\"title\": \"...\", \"description\": \"...\", \"tools\": [\"tool_name\"], \"risk\": \"... or null\"}], \"risks\": [\"...\"]}\n\
+Keep it to the fewest steps that get the job done (max 12). Each step must be independently executable. \
+Mention destructive, costly or externally visible actions in `risk`.";
+
+/// Extract a plan from the model's reply. Tolerates code fences and prose
+/// around the JSON object. Assigns step ids and drops empty steps.
+fn parse_plan(text: &str) -> Option<PlanDraft> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    let mut draft: PlanDraft = serde_json::from_str(&text[start..=end]).ok()?;
+    draft.steps.retain(|s| !s.title.trim().is_empty());
+    draft.steps.truncate(MAX_PLAN_STEPS);
+    if draft.steps.is_empty() {
+        return None;
+    }
+    normalize_steps(&mut draft.steps);
+    Some(draft)
+}
+
+/// Give every step a unique id (`step-N`) when missing or duplicated.
+fn normalize_steps(steps: &mut [PlanStep]) {
+    let mut seen = std::collections::HashSet::new();
+    for (i, step) in steps.iter_mut().enumerate() {
+        if step.id.trim().is_empty() || !seen.insert(step.id.clone()) {
+            step.id = format!("step-{}", i + 1);
+            seen.insert(step.id.clone());
+        }
+    }
+}
+
+/// Ask the model for a plan, without tools.
+async fn propose_plan(
+    provider: &AnyProvider,
+    model: &str,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+) -> EngineResult<Option<PlanDraft>> {
+    let tool_names: Vec<&str> = tools.iter().map(|t| t.function.name.as_str()).collect();
+    let mut planning = messages.to_vec();
+    planning.push(Message {
+        role: Role::System,
+        content: MessageContent::Text(format!(
+            "{}\n\nAvailable tools: {}",
+            PLANNER_PROMPT,
+            tool_names.join(", ")
+        )),
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+    });
+    let chunks = provider
+        .chat_stream(&planning, &[], model, Some(0.2), None)
+        .await?;
+    let text: String = chunks
+        .iter()
+        .filter_map(|c| c.delta_text.as_deref())
+        .collect();
+    Ok(parse_plan(&text))
+}
+
+/// Render the approved plan as the assistant message kept in history.
+fn format_plan(goal: &str, steps: &[PlanStep]) -> String {
+    let mut out = format!("**Plan:** {}\n", goal);
+    for (i, step) in steps.iter().enumerate() {
+        out.push_str(&format!("\n{}. {}", i + 1, step.title));
+        if !step.description.is_empty() {
+            out.push_str(&format!(" — {}", step.description));
+        }
+    }
+    out
+}
+
+fn step_prompt(index: usize, total: usize, step: &PlanStep) -> String {
+    let mut prompt = format!(
+        "Execute step {}/{} of the approved plan: {}",
+        index + 1,
+        total,
+        step.title
+    );
+    if !step.description.is_empty() {
+        prompt.push_str(&format!("\n{}", step.description));
+    }
+    if !step.tools.is_empty() {
+        prompt.push_str(&format!("\nSuggested tools: {}", step.tools.join(", ")));
+    }
+    prompt.push_str("\nDo only this step, then briefly report what you did.");
+    prompt
+}
+
+fn text_message(role: Role, text: String) -> Message {
+    Message {
+        role,
+        content: MessageContent::Text(text),
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+    }
+}
+
+// ── Execution ──────────────────────────────────────────────────────────
+
+/// Plan-then-execute wrapper around `run_agent_turn`. Takes the same
+/// arguments; returns the final step's reply (or a cancellation notice).
+#[allow(clippy::too_many_arguments)]
+pub async fn run_planned_turn(
+    app_handle: &tauri::AppHandle,
+    provider: &AnyProvider,
+    model: &str,
+    messages: &mut Vec<Message>,
+    tools: &mut Vec<ToolDefinition>,
+    session_id: &str,
+    run_id: &str,
+    max_rounds: u32,
+    temperature: Option<f64>,
+    pending_approvals: &PendingApprovals,
+    tool_timeout_secs: u64,
+    agent_id: &str,
+    daily_budget_usd: f64,
+    daily_tokens: Option<&DailyTokenTracker>,
+    thinking_level: Option<&str>,
+    auto_approve_all: bool,
+    user_approved_tools: &[String],
+    yield_signal: Option<&YieldSignal>,
+) -> EngineResult<String> {
+    macro_rules! agent_turn {
+        () => {
+            run_agent_turn(
+                app_handle,
+                provider,
+                model,
+                messages,
+                tools,
+                session_id,
+                run_id,
+                max_rounds,
+                temperature,
+                pending_approvals,
+                tool_timeout_secs,
+                agent_id,
+                daily_budget_usd,
+                daily_tokens,
+                thinking_level,
+                auto_approve_all,
+                user_approved_tools,
+                yield_signal,
+            )
+        };
+    }
+
+    // ── 1. Propose ────────────────────────────────────────────────────
+    let Some(draft) = propose_plan(provider, model, messages, tools).await? else {
+        warn!("[plan-mode] Model returned no usable plan — running a normal turn");
+        return agent_turn!().await;
+    };
+
+    let plan_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    PENDING_PLANS.lock().insert(plan_id.clone(), tx);
+    info!(
+        "[plan-mode] Proposed plan {} with {} steps for session {}",
+        plan_id,
+        draft.steps.len(),
+        session_id
+    );
+    let _ = app_handle.emit(
+        "engine-event",
+        EngineEvent::PlanProposed {
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            plan_id: plan_id.clone(),
+            goal: draft.goal.clone(),
+            steps: draft.steps.clone(),
+            risks: draft.risks.clone(),
+        },
+    );
+
+    // ── 2. Wait for approval ──────────────────────────────────────────
+    let decision = tokio::time::timeout(PLAN_DECISION_TIMEOUT, rx).await;
+    PENDING_PLANS.lock().remove(&plan_id);
+    let mut steps = match decision {
+        Ok(Ok(PlanDecision::Approve(edited))) => edited.unwrap_or_else(|| draft.steps.clone()),
+        outcome => {
+            let text = match outcome {
+                Err(_) => "Plan approval timed out — nothing was executed.",
+                _ => "Plan cancelled — nothing was executed.",
+            }
+            .to_string();
+            info!("[plan-mode] Plan {}: {}", plan_id, text);
+            messages.push(text_message(Role::Assistant, text.clone()));
+            let _ = app_handle.emit(
+                "engine-event",
+                EngineEvent::Complete {
+                    session_id: session_id.to_string(),
+                    run_id: run_id.to_string(),
+                    text: text.clone(),
+                    tool_calls_count: 0,
+                    usage: None,
+                    model: None,
+                    total_rounds: None,
+                    max_rounds: None,
+                    citations: Vec::new(),
+                },
+            );
+            return Ok(text);
+        }
+    };
+    steps.truncate(MAX_PLAN_STEPS);
+    normalize_steps(&mut steps);
+    messages.push(text_message(
+        Role::Assistant,
+        format_plan(&draft.goal, &steps),
+    ));
+
+    // ── 3. Execute step by step ───────────────────────────────────────
+    let emit_status = |index: usize, status: PlanStepState, summary: Option<String>| {
+        let _ = app_handle.emit(
+            "engine-event",
+            EngineEvent::PlanStepStatus {
+                session_id: session_id.to_string(),
+                run_id: run_id.to_string(),
+                plan_id: plan_id.clone(),
+                step_id: steps[index].id.clone(),
+                index,
+                status,
+                summary,
+            },
+        );
+    };
+    let skip_from = |from: usize| {
+        for i in from..steps.len() {
+            emit_status(i, PlanStepState::Skipped, None);
+        }
+    };
+
+    let total = steps.len();
+    let mut final_text = String::new();
+    for (index, step) in steps.iter().enumerate() {
+        if yield_signal.is_some_and(|ys| ys.is_yield_requested()) {
+            info!("[plan-mode] Yield requested — skipping remaining steps");
+            skip_from(index);
+            break;
+        }
+        emit_status(index, PlanStepState::Running, None);
+        messages.push(text_message(Role::User, step_prompt(index, total, step)));
+        match agent_turn!().await {
+            Ok(text) => {
+                emit_status(
+                    index,
+                    PlanStepState::Done,
+                    Some(safe_truncate(&text, STEP_SUMMARY_CHARS).to_string()),
+                );
+                final_text = text;
+            }
+            Err(e) => {
+                emit_status(index, PlanStepState::Failed, Some(e.to_string()));
+                skip_from(index + 1);
+                return Err(e);
+            }
+        }
+    }
+    Ok(final_text)
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_plan() {
+        let text = "Here you go:\n```json\n{\"goal\": \"Ship it\", \"steps\": [\
+            {\"title\": \"Build\", \"tools\": [\"exec\"]},\
+            {\"title\": \"Deploy\", \"risk\": \"touches prod\"}],\
+            \"risks\": [\"downtime\"]}\n```";
+        let plan = parse_plan(text).unwrap();
+        assert_eq!(plan.goal, "Ship it");
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].id, "step-1");
+        assert_eq!(plan.steps[0].tools, vec!["exec"]);
+        assert_eq!(plan.steps[1].risk.as_deref(), Some("touches prod"));
+        assert_eq!(plan.risks, vec!["downtime"]);
+    }
+
+    #[test]
+    fn rejects_empty_or_invalid_plans() {
+        assert!(parse_plan("no json here").is_none());
+        assert!(parse_plan("{\"steps\": []}").is_none());
+        assert!(parse_plan("{\"steps\": [{\"title\": \"  \"}]}").is_none());
+    }
+
+    #[test]
+    fn caps_steps_and_dedupes_ids() {
+        let steps: Vec<String> = (0..20)
+            .map(|i| format!("{{\"id\": \"s\", \"title\": \"Step {}\"}}", i))
+            .collect();
+        let plan = parse_plan(&format!("{{\"steps\": [{}]}}", steps.join(","))).unwrap();
+        assert_eq!(plan.steps.len(), MAX_PLAN_STEPS);
+        assert_eq!(plan.steps[0].id, "s");
+        assert_eq!(plan.steps[1].id, "step-2");
+    }
+
+    #[test]
+    fn unknown_plan_does_not_resolve() {
+        assert!(!resolve("missing", PlanDecision::Reject));
+    }
+}
@@ -0,0 +1,262 @@
+// Paw Agent Engine — Config profiles
+//
+// A ConfigProfile ("home", "work", "demo") bundles what changes between
+// environments: the default provider and model, the agent channel bridges
+// route to, which bridges run, and the daily budget.
+//
+// `switch` applies one atomically: the new EngineConfig is built and
+// persisted before it replaces the live one under a single config lock, so
+// no agent run ever sees half a profile. Only then are the affected bridges
+// reconfigured and restarted.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::channels;
+use crate::engine::state::EngineState;
+use crate::engine::types::{ConfigProfile, EngineConfig};
+use log::{info, warn};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+/// Bridges a profile can enable, by the name used in their DB config key.
+pub const BRIDGES: [&str; 13] = [
+    "telegram",
+    "discord",
+    "slack",
+    "matrix",
+    "mattermost",
+    "nextcloud",
+    "irc",
+    "twitch",
+    "nostr",
+    "whatsapp",
+    "email",
+    "webchat",
+    "webhook",
+];
+
+/// How long a restarted bridge gets to shut its old connection down.
+const STOP_GRACE: Duration = Duration::from_secs(5);
+
+/// `config` with `profile` applied. Fails on a provider or bridge that
+/// doesn't exist, leaving the caller's config untouched.
+pub fn apply(config: &EngineConfig, profile: &ConfigProfile) -> EngineResult<EngineConfig> {
+    let mut next = config.clone();
+    if let Some(provider) = &profile.default_provider {
+        if !config.providers.iter().any(|p| &p.id == provider) {
+            return Err(format!(
+                "Profile '{}' uses provider '{}', which isn't configured",
+                profile.name, provider
+            )
+            .into());
+        }
+        next.default_provider = Some(provider.clone());
+    }
+    if let Some(model) = &profile.default_model {
+        next.default_model = Some(model.clone());
+    }
+    if let Some(budget) = profile.daily_budget_usd {
+        next.daily_budget_usd = budget.max(0.0);
+    }
+    if let Some(unknown) = profile
+        .bridges
+        .iter()
+        .flatten()
+        .find(|b| !BRIDGES.contains(&b.as_str()))
+    {
+        return Err(format!(
+            "Profile '{}' enables unknown bridge '{}'",
+            profile.name, unknown
+        )
+        .into());
+    }
+    next.active_profile = Some(profile.name.clone());
+    Ok(next)
+}
+
+/// Switch to the profile named `name` and restart the bridges it affects.
+pub async fn switch(app_handle: &tauri::AppHandle, name: &str) -> EngineResult<ConfigProfile> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+
+    let profile = {
+        let mut cfg = state.config.lock();
+        let profile = cfg
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .ok_or_else(|| format!("No config profile named '{}'", name))?;
+        let next = apply(&cfg, &profile)?;
+        let json = serde_json::to_string(&next)?;
+        state.store.set_config("engine_config", &json)?;
+        crate::engine::providers::middleware::set_config(next.provider_middleware.clone());
+        crate::engine::local_models::configure(&next);
+        *cfg = next;
+        profile
+    };
+    info!("[profiles] Switched to '{}'", profile.name);
+
+    if profile.bridges.is_some() || profile.default_agent.is_some() {
+        for bridge in BRIDGES {
+            restart_bridge(app_handle, &state, &profile, bridge).await;
+        }
+    }
+
+    let _ = app_handle.emit(
+        "profile-switched",
+        serde_json::json!({ "name": profile.name }),
+    );
+    Ok(profile)
+}
+
+/// Rewrite a bridge's stored `enabled` / agent for `profile`, then stop it
+/// and start it again if the profile runs it.
+async fn restart_bridge(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    profile: &ConfigProfile,
+    bridge: &str,
+) {
+    let key = format!("{}_config", bridge);
+    let Ok(Some(json)) = state.store.get_config(&key) else {
+        // Never configured: nothing to enable or stop
+        return;
+    };
+    let Ok(mut config) = serde_json::from_str::<serde_json::Value>(&json) else {
+        warn!("[profiles] Unreadable {} config, leaving it alone", bridge);
+        return;
+    };
+    let enabled = match &profile.bridges {
+        Some(bridges) => bridges.iter().any(|b| b == bridge),
+        None => config["enabled"].as_bool().unwrap_or(false),
+    };
+    config["enabled"] = enabled.into();
+    if let Some(agent) = &profile.default_agent {
+        // The webhook bridge takes its agent per request, with a fallback
+        let field = if bridge == "webhook" {
+            "default_agent_id"
+        } else {
+            "agent_id"
+        };
+        config[field] = agent.clone().into();
+    }
+    if let Err(e) = state.store.set_config(&key, &config.to_string()) {
+        warn!("[profiles] Failed to save {} config: {}", bridge, e);
+        return;
+    }
+
+    stop_bridge(bridge);
+    let deadline = tokio::time::Instant::now() + STOP_GRACE;
+    while channels::connectivity(bridge).state != channels::ConnectionState::Disconnected
+        && tokio::time::Instant::now() < deadline
+    {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    if enabled {
+        match start_bridge(app_handle, bridge) {
+            Ok(()) => info!("[profiles] Restarted {} bridge", bridge),
+            Err(e) => warn!("[profiles] {} bridge didn't start: {}", bridge, e),
+        }
+    }
+}
+
+fn stop_bridge(bridge: &str) {
+    match bridge {
+        "telegram" => crate::engine::telegram::stop_bridge(),
+        "discord" => crate::engine::discord::stop_bridge(),
+        "slack" => crate::engine::slack::stop_bridge(),
+        "matrix" => crate::engine::matrix::stop_bridge(),
+        "mattermost" => crate::engine::mattermost::stop_bridge(),
+        "nextcloud" => crate::engine::nextcloud::stop_bridge(),
+        "irc" => crate::engine::irc::stop_bridge(),
+        "twitch" => crate::engine::twitch::stop_bridge(),
+        "nostr" => crate::engine::nostr::stop_bridge(),
+        "whatsapp" => crate::engine::whatsapp::stop_bridge(),
+        "email" => crate::engine::email_bridge::stop_bridge(),
+        "webchat" => crate::engine::webchat::stop_bridge(),
+        "webhook" => crate::engine::webhook::stop_bridge(),
+        _ => {}
+    }
+}
+
+fn start_bridge(app_handle: &tauri::AppHandle, bridge: &str) -> EngineResult<()> {
+    crate::engine::safe_mode::check_bridge_start(app_handle)?;
+    let app_handle = app_handle.clone();
+    match bridge {
+        "telegram" => crate::engine::telegram::start_bridge(app_handle),
+        "discord" => crate::engine::discord::start_bridge(app_handle),
+        "slack" => crate::engine::slack::start_bridge(app_handle),
+        "matrix" => crate::engine::matrix::start_bridge(app_handle),
+        "mattermost" => crate::engine::mattermost::start_bridge(app_handle),
+        "nextcloud" => crate::engine::nextcloud::start_bridge(app_handle),
+        "irc" => crate::engine::irc::start_bridge(app_handle),
+        "twitch" => crate::engine::twitch::start_bridge(app_handle),
+        "nostr" => crate::engine::nostr::start_bridge(app_handle),
+        "whatsapp" => crate::engine::whatsapp::start_bridge(app_handle),
+        "email" => crate::engine::email_bridge::start_bridge(app_handle),
+        "webchat" => crate::engine::webchat::start_bridge(app_handle),
+        "webhook" => crate::engine::webhook::start_bridge(app_handle),
+        _ => Err(format!("Unknown bridge '{}'", bridge).into()),
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::types::{ProviderConfig, ProviderKind};
+
+    fn config() -> EngineConfig {
+        EngineConfig {
+            providers: vec![ProviderConfig {
+                id: "anthropic".into(),
+                kind: ProviderKind::Anthropic,
+                api_key: "sk-work".into(),
+                base_url: None,
+                default_model: None,
+                extra_body: Default::default(),
+                extra_headers: Default::default(),
+                responses_api: false,
+                responses_tools: Vec::new(),
+            }],
+            daily_budget_usd: 5.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn apply_sets_only_what_the_profile_names() {
+        let cfg = config();
+        let demo = ConfigProfile {
+            name: "demo".into(),
+            default_provider: Some("anthropic".into()),
+            daily_budget_usd: Some(1.0),
+            bridges: Some(vec!["webchat".into()]),
+            ..Default::default()
+        };
+        let next = apply(&cfg, &demo).unwrap();
+        assert_eq!(next.default_provider.as_deref(), Some("anthropic"));
+        assert_eq!(next.daily_budget_usd, 1.0);
+        assert_eq!(next.default_model, cfg.default_model);
+        assert_eq!(next.active_profile.as_deref(), Some("demo"));
+    }
+
+    #[test]
+    fn apply_rejects_unknown_providers_and_bridges() {
+        let cfg = config();
+        let missing = ConfigProfile {
+            name: "work".into(),
+            default_provider: Some("openai".into()),
+            ..Default::default()
+        };
+        assert!(apply(&cfg, &missing).is_err());
+        let typo = ConfigProfile {
+            name: "home".into(),
+            bridges: Some(vec!["telegarm".into()]),
+            ..Default::default()
+        };
+        assert!(apply(&cfg, &typo).is_err());
+    }
+}
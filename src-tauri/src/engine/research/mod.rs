@@ -0,0 +1,545 @@
+// Paw Agent Engine — Research Mode
+//
+// A long-running research run answers one question with a cited report.
+// Unlike a chat turn it is driven by the engine, step by step, like the
+// orchestrator drives a project:
+//   1. Plan     — the lead model splits the question into sub-questions,
+//                 each with a few search queries.
+//   2. Gather   — search (engine::search), read the top unseen pages
+//                 (within the page budget) and have the worker model pull
+//                 claims from each page, tagged with the page's source number.
+//   3. Reflect  — the lead model marks answered sub-questions and proposes
+//                 follow-up queries for the gaps; repeat 2–3 until no gaps
+//                 remain, the round limit is hit or the budget is spent.
+//   4. Write    — the lead model writes the report from the claims; citations
+//                 are checked against real sources and a Sources section is
+//                 appended. The report is saved to the agent workspace.
+//
+// State (sub-questions, queries, sources, claims) is checkpointed after
+// every page, so a run interrupted by a crash or quit resumes where it
+// stopped. Progress goes out as `research-event`.
+
+mod prompts;
+
+pub use openpawz_core::engine::research::*;
+
+use crate::atoms::error::EngineResult;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{resolve_provider_for_model, EngineState};
+use crate::engine::types::*;
+use crate::engine::util::safe_truncate;
+use log::{info, warn};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+pub const DEFAULT_PAGE_BUDGET: u32 = 20;
+pub const MAX_PAGE_BUDGET: u32 = 100;
+pub const DEFAULT_ROUNDS: u32 = 3;
+pub const MAX_ROUNDS: u32 = 8;
+
+/// Search results considered per query.
+const RESULTS_PER_QUERY: usize = 6;
+/// New pages queued per query; the rest of the results are ignored.
+const PAGES_PER_QUERY: usize = 3;
+/// Page text handed to the extraction call.
+const PAGE_TEXT_BYTES: usize = 12_000;
+/// Notes handed to the reflect / write calls.
+const NOTES_BYTES: usize = 60_000;
+
+/// Runs executing in this process, with their cancel flags.
+static ACTIVE: LazyLock<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn is_active(run_id: &str) -> bool {
+    ACTIVE.lock().contains_key(run_id)
+}
+
+/// Ask a running run to stop after its current step. Returns false if the
+/// run is not executing.
+pub fn cancel(run_id: &str) -> bool {
+    match ACTIVE.lock().get(run_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Create a run and start it in the background.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    question: &str,
+    agent_id: &str,
+    page_budget: u32,
+    max_rounds: u32,
+) -> EngineResult<ResearchRun> {
+    let state = app_handle.state::<EngineState>();
+    let id = state.store.research_create(
+        question,
+        agent_id,
+        page_budget.clamp(1, MAX_PAGE_BUDGET),
+        max_rounds.clamp(1, MAX_ROUNDS),
+    )?;
+    info!("[research] Starting run {} — {}", id, question);
+    spawn(app_handle, &id)?;
+    state
+        .store
+        .research_get(&id)?
+        .ok_or_else(|| "Research run vanished".into())
+}
+
+/// Continue an interrupted, failed or cancelled run from its checkpoint.
+pub fn resume(app_handle: &tauri::AppHandle, run_id: &str) -> EngineResult<ResearchRun> {
+    let state = app_handle.state::<EngineState>();
+    let run = state
+        .store
+        .research_get(run_id)?
+        .ok_or_else(|| format!("Research run {} not found", run_id))?;
+    if run.status == "done" {
+        return Err("This research run already finished".into());
+    }
+    state
+        .store
+        .research_set_status(run_id, "running", None, None)?;
+    info!("[research] Resuming run {}", run_id);
+    spawn(app_handle, run_id)?;
+    state
+        .store
+        .research_get(run_id)?
+        .ok_or_else(|| "Research run vanished".into())
+}
+
+fn spawn(app_handle: &tauri::AppHandle, run_id: &str) -> EngineResult<()> {
+    let flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = ACTIVE.lock();
+        if active.contains_key(run_id) {
+            return Err("This research run is already running".into());
+        }
+        active.insert(run_id.to_string(), flag.clone());
+    }
+    emit(app_handle, run_id, "started", serde_json::json!({}));
+
+    let app = app_handle.clone();
+    let run_id = run_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = drive(&app, &run_id, &flag).await;
+        ACTIVE.lock().remove(&run_id);
+        let state = app.state::<EngineState>();
+        let (status, path, error) = match result {
+            Ok(Some(path)) => ("done", Some(path), None),
+            Ok(None) => ("cancelled", None, None),
+            Err(e) => {
+                warn!("[research] Run {} failed: {}", run_id, e);
+                ("failed", None, Some(e.to_string()))
+            }
+        };
+        if let Err(e) =
+            state
+                .store
+                .research_set_status(&run_id, status, path.as_deref(), error.as_deref())
+        {
+            warn!("[research] Failed to record status for {}: {}", run_id, e);
+        }
+        emit(
+            &app,
+            &run_id,
+            "finished",
+            serde_json::json!({ "status": status, "report_path": path, "error": error }),
+        );
+    });
+    Ok(())
+}
+
+fn emit(app_handle: &tauri::AppHandle, run_id: &str, kind: &str, detail: serde_json::Value) {
+    let mut payload = serde_json::json!({ "kind": kind, "run_id": run_id });
+    if let (Some(p), Some(d)) = (payload.as_object_mut(), detail.as_object()) {
+        p.extend(d.clone());
+    }
+    app_handle.emit("research-event", payload).ok();
+}
+
+// ── Models ─────────────────────────────────────────────────────────────
+
+/// A provider + model pair for one kind of step.
+struct Llm {
+    provider: AnyProvider,
+    model: String,
+}
+
+impl Llm {
+    async fn complete(&self, system: &str, user: &str) -> EngineResult<String> {
+        let messages = vec![
+            Message {
+                role: Role::System,
+                content: MessageContent::Text(system.to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+                name: None,
+            },
+            Message {
+                role: Role::User,
+                content: MessageContent::Text(user.to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+                name: None,
+            },
+        ];
+        let chunks = self
+            .provider
+            .chat_stream(&messages, &[], &self.model, Some(0.2), None)
+            .await?;
+        let text: String = chunks
+            .iter()
+            .filter_map(|c| c.delta_text.as_deref())
+            .collect();
+        if text.trim().is_empty() {
+            return Err(format!("{} returned an empty reply", self.model).into());
+        }
+        Ok(text)
+    }
+}
+
+/// The lead model plans, reflects and writes (boss routing); the worker
+/// model reads pages.
+fn models(state: &EngineState, agent_id: &str) -> EngineResult<(Llm, Llm)> {
+    let cfg = state.config.lock();
+    let default_model = cfg
+        .default_model
+        .clone()
+        .ok_or("No default model configured")?;
+    let pick = |role: &str| -> EngineResult<Llm> {
+        let model = cfg
+            .model_routing
+            .resolve(agent_id, role, "research", &default_model);
+        let provider = resolve_provider_for_model(&model, &cfg.providers)
+            .or_else(|| cfg.providers.first().cloned())
+            .ok_or("No AI provider configured")?;
+        Ok(Llm {
+            provider: AnyProvider::from_config(&provider),
+            model,
+        })
+    };
+    Ok((pick("boss")?, pick("worker")?))
+}
+
+// ── The loop ───────────────────────────────────────────────────────────
+
+/// Drive a run from its checkpoint to the end. `Ok(None)` means cancelled;
+/// `Ok(Some(path))` is the saved report.
+async fn drive(
+    app_handle: &tauri::AppHandle,
+    run_id: &str,
+    cancelled: &AtomicBool,
+) -> EngineResult<Option<String>> {
+    let state = app_handle.state::<EngineState>();
+    let run = state
+        .store
+        .research_get(run_id)?
+        .ok_or_else(|| format!("Research run {} not found", run_id))?;
+    let mut rs = state.store.research_load_state(run_id)?;
+    let (lead, worker) = models(&state, &run.agent_id)?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()?;
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            info!("[research] Run {} cancelled", run_id);
+            return Ok(None);
+        }
+        match rs.phase {
+            ResearchPhase::Planning => {
+                let reply = lead
+                    .complete(prompts::PLAN_SYSTEM, &prompts::plan_prompt(&run.question))
+                    .await?;
+                let plan = prompts::parse_plan(&reply).unwrap_or_else(|| {
+                    warn!("[research] Unparseable plan, researching the question directly");
+                    vec![prompts::PlannedQuestion {
+                        question: run.question.clone(),
+                        queries: vec![run.question.clone()],
+                    }]
+                });
+                for q in plan {
+                    rs.queue_queries(q.queries);
+                    rs.sub_questions.push(SubQuestion {
+                        question: q.question,
+                        answered: false,
+                    });
+                }
+                rs.phase = ResearchPhase::Gathering;
+                state.store.research_save_state(run_id, &rs)?;
+                let questions: Vec<&str> = rs
+                    .sub_questions
+                    .iter()
+                    .map(|q| q.question.as_str())
+                    .collect();
+                emit(
+                    app_handle,
+                    run_id,
+                    "planned",
+                    serde_json::json!({ "sub_questions": questions }),
+                );
+            }
+            ResearchPhase::Gathering => {
+                gather_round(
+                    app_handle, &state, &run, &mut rs, &worker, &client, cancelled,
+                )
+                .await?;
+                if cancelled.load(Ordering::Relaxed) {
+                    continue;
+                }
+                rs.round += 1;
+                let budget_spent = rs.pages_read() >= run.page_budget;
+                if rs.round >= run.max_rounds || budget_spent {
+                    rs.phase = ResearchPhase::Writing;
+                } else {
+                    let reply = lead
+                        .complete(
+                            prompts::REFLECT_SYSTEM,
+                            &prompts::reflect_prompt(
+                                &run.question,
+                                safe_truncate(&rs.notes(), NOTES_BYTES),
+                                &rs.searched,
+                            ),
+                        )
+                        .await?;
+                    let reflection = prompts::parse_reflection(&reply);
+                    for n in reflection.answered {
+                        if let Some(sq) = rs.sub_questions.get_mut(n.wrapping_sub(1)) {
+                            sq.answered = true;
+                        }
+                    }
+                    rs.queue_queries(reflection.queries.into_iter().take(4));
+                    if rs.pending_queries.is_empty() && rs.unread_sources().is_empty() {
+                        rs.phase = ResearchPhase::Writing;
+                    }
+                }
+                state.store.research_save_state(run_id, &rs)?;
+                emit(
+                    app_handle,
+                    run_id,
+                    "round_done",
+                    serde_json::json!({
+                        "round": rs.round,
+                        "pages_read": rs.pages_read(),
+                        "claims": rs.claims.len(),
+                        "next_queries": rs.pending_queries,
+                    }),
+                );
+            }
+            ResearchPhase::Writing => {
+                emit(app_handle, run_id, "writing", serde_json::json!({}));
+                if rs.claims.is_empty() {
+                    return Err("No usable findings — every page failed or was off-topic".into());
+                }
+                let draft = lead
+                    .complete(
+                        prompts::WRITE_SYSTEM,
+                        &prompts::write_prompt(
+                            &run.question,
+                            safe_truncate(&rs.notes(), NOTES_BYTES),
+                        ),
+                    )
+                    .await?;
+                let report = finalize_report(&draft, &rs.sources);
+                let path = save_report(&run, &rs, &report)?;
+                rs.phase = ResearchPhase::Done;
+                state.store.research_save_state(run_id, &rs)?;
+                state
+                    .store
+                    .research_set_status(run_id, "running", Some(&path), None)?;
+                info!("[research] Run {} wrote {}", run_id, path);
+                return Ok(Some(path));
+            }
+            ResearchPhase::Done => {
+                return run
+                    .report_path
+                    .clone()
+                    .map(Some)
+                    .ok_or_else(|| "Run finished without a report".into());
+            }
+        }
+    }
+}
+
+/// Search the pending queries, then read unread sources until the budget
+/// is spent. Checkpoints after every query and page.
+async fn gather_round(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    run: &ResearchRun,
+    rs: &mut ResearchState,
+    worker: &Llm,
+    client: &reqwest::Client,
+    cancelled: &AtomicBool,
+) -> EngineResult<()> {
+    while let Some(query) = rs.pending_queries.first().cloned() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match crate::engine::search::search(&state.store, &query, RESULTS_PER_QUERY).await {
+            Ok((results, _)) => {
+                let mut queued = 0;
+                for r in results.iter().filter(|r| r.url.starts_with("http")) {
+                    if queued >= PAGES_PER_QUERY {
+                        break;
+                    }
+                    let known = rs.sources.len();
+                    rs.add_source(&r.url, &r.title);
+                    if rs.sources.len() > known {
+                        queued += 1;
+                    }
+                }
+                emit(
+                    app_handle,
+                    &run.id,
+                    "searched",
+                    serde_json::json!({ "query": query, "new_sources": queued }),
+                );
+            }
+            Err(e) => warn!("[research] Search '{}' failed: {}", query, e),
+        }
+        rs.pending_queries.remove(0);
+        rs.searched.push(query);
+        state.store.research_save_state(&run.id, rs)?;
+    }
+
+    let sub_questions: Vec<String> = rs
+        .sub_questions
+        .iter()
+        .map(|q| q.question.clone())
+        .collect();
+    for source_id in rs.unread_sources() {
+        if cancelled.load(Ordering::Relaxed) || rs.pages_read() >= run.page_budget {
+            return Ok(());
+        }
+        let Some(source) = rs.source_mut(source_id) else {
+            continue;
+        };
+        let url = source.url.clone();
+        let page = crate::engine::web::fetch_readable(client, &url)
+            .await
+            .and_then(|(title, text)| {
+                if text.trim().is_empty() {
+                    Err("no readable text".into())
+                } else {
+                    Ok((title, text))
+                }
+            });
+        let (title, text) = match page {
+            Ok(page) => page,
+            Err(e) => {
+                let source = rs.source_mut(source_id).expect("source exists");
+                source.read = true;
+                source.error = Some(e.to_string());
+                state.store.research_save_state(&run.id, rs)?;
+                continue;
+            }
+        };
+        let title = if title.is_empty() {
+            rs.source_mut(source_id)
+                .map(|s| s.title.clone())
+                .unwrap_or_default()
+        } else {
+            title
+        };
+
+        // Extraction failures (provider down, quota) fail the run so it can
+        // be resumed, rather than burning through the sources
+        let reply = worker
+            .complete(
+                prompts::EXTRACT_SYSTEM,
+                &prompts::extract_prompt(
+                    &run.question,
+                    &sub_questions,
+                    &title,
+                    &url,
+                    safe_truncate(&text, PAGE_TEXT_BYTES),
+                ),
+            )
+            .await?;
+        let claims = prompts::parse_claims(&reply);
+        for c in &claims {
+            rs.add_claim(&c.claim, c.sub_question.saturating_sub(1), source_id);
+        }
+        if let Some(source) = rs.source_mut(source_id) {
+            source.read = true;
+            source.title = title.clone();
+        }
+        state.store.research_save_state(&run.id, rs)?;
+        emit(
+            app_handle,
+            &run.id,
+            "page_read",
+            serde_json::json!({
+                "source": source_id,
+                "title": title,
+                "url": url,
+                "claims": claims.len(),
+                "pages_read": rs.pages_read(),
+                "page_budget": run.page_budget,
+            }),
+        );
+    }
+    Ok(())
+}
+
+/// File name stem from the question: "why-is-the-sky-blue".
+fn slug(question: &str) -> String {
+    let mut out = String::new();
+    for c in question.chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if !out.ends_with('-') && !out.is_empty() {
+            out.push('-');
+        }
+        if out.len() >= 60 {
+            break;
+        }
+    }
+    let out = out.trim_end_matches('-');
+    if out.is_empty() {
+        "research".into()
+    } else {
+        out.to_string()
+    }
+}
+
+/// Write the report to `research/<slug>-<run>.md` in the agent workspace and
+/// return the workspace-relative path.
+fn save_report(run: &ResearchRun, rs: &ResearchState, report: &str) -> EngineResult<String> {
+    let workspace = crate::engine::tools::ensure_workspace(&run.agent_id)?;
+    let dir = workspace.join("research");
+    std::fs::create_dir_all(&dir)?;
+    let name = format!("{}-{}.md", slug(&run.question), safe_truncate(&run.id, 8));
+    let footer = format!(
+        "\n\n---\n_Research run {} · {} · {} pages read over {} round(s) · {} claims_\n",
+        run.id,
+        chrono::Utc::now().format("%Y-%m-%d"),
+        rs.pages_read(),
+        rs.round,
+        rs.claims.len()
+    );
+    std::fs::write(dir.join(&name), format!("{}{}", report, footer))?;
+    Ok(format!("research/{}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_slugs() {
+        assert_eq!(slug("Why is the sky blue?"), "why-is-the-sky-blue");
+        assert_eq!(slug("???"), "research");
+        assert!(slug(&"word ".repeat(40)).len() <= 61);
+    }
+}
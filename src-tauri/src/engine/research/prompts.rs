@@ -0,0 +1,178 @@
+// research/prompts.rs — prompts for each research step and parsers for
+// the JSON the model answers with.
+
+use serde::Deserialize;
+
+pub(super) const PLAN_SYSTEM: &str = "You are a meticulous research planner. \
+Answer with JSON only.";
+
+pub(super) const EXTRACT_SYSTEM: &str = "You extract facts from web pages for a \
+research report. Only state what the page itself says. Answer with JSON only.";
+
+pub(super) const REFLECT_SYSTEM: &str = "You review research notes, decide what is \
+still unknown and which searches would fill the gaps. Answer with JSON only.";
+
+pub(super) const WRITE_SYSTEM: &str = "You write clear, well-structured research \
+reports in markdown. Every factual sentence cites its sources by number, like [2] \
+or [1, 4], using only the numbers given in the notes.";
+
+pub(super) fn plan_prompt(question: &str) -> String {
+    format!(
+        "Research question: {}\n\n\
+         Break it into 3-6 focused sub-questions that together answer it, and give \
+         1-2 web search queries for each. Reply as:\n\
+         {{\"sub_questions\": [{{\"question\": \"...\", \"queries\": [\"...\"]}}]}}",
+        question
+    )
+}
+
+pub(super) fn extract_prompt(
+    question: &str,
+    sub_questions: &[String],
+    title: &str,
+    url: &str,
+    text: &str,
+) -> String {
+    let numbered: Vec<String> = sub_questions
+        .iter()
+        .enumerate()
+        .map(|(i, q)| format!("{}. {}", i + 1, q))
+        .collect();
+    format!(
+        "Research question: {}\nSub-questions:\n{}\n\n\
+         Page: {}\nURL: {}\n\n{}\n\n\
+         List the facts on this page that help answer a sub-question — specific, \
+         self-contained sentences with numbers, names and dates where given. Skip \
+         anything off-topic; an empty list is fine. Reply as:\n\
+         {{\"claims\": [{{\"sub_question\": 1, \"claim\": \"...\"}}]}}",
+        question,
+        numbered.join("\n"),
+        title,
+        url,
+        text
+    )
+}
+
+pub(super) fn reflect_prompt(question: &str, notes: &str, searched: &[String]) -> String {
+    format!(
+        "Research question: {}\n\nNotes so far (claims with source numbers):\n\n{}\n\
+         Already searched: {}\n\n\
+         Which sub-questions (by number) are now answered well enough? For what is \
+         still missing or contradictory, suggest up to 4 new search queries that \
+         differ from the ones already tried. Reply as:\n\
+         {{\"answered\": [1, 3], \"queries\": [\"...\"]}}\n\
+         Use an empty \"queries\" list when the notes are sufficient.",
+        question,
+        notes,
+        searched.join("; ")
+    )
+}
+
+pub(super) fn write_prompt(question: &str, notes: &str) -> String {
+    format!(
+        "Research question: {}\n\nResearch notes (claims with source numbers):\n\n{}\n\
+         Write the report: a title, a short summary answering the question, then a \
+         section per theme. Cite every claim with the source numbers from the notes. \
+         Point out where sources disagree and what remains uncertain. Do not invent \
+         facts or source numbers, and do not add a sources list — it is appended \
+         automatically.",
+        question, notes
+    )
+}
+
+/// The JSON object in a model reply, tolerating code fences and chatter
+/// around it.
+fn json_object<T: for<'de> Deserialize<'de>>(text: &str) -> Option<T> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct PlannedQuestion {
+    pub question: String,
+    #[serde(default)]
+    pub queries: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Plan {
+    sub_questions: Vec<PlannedQuestion>,
+}
+
+pub(super) fn parse_plan(text: &str) -> Option<Vec<PlannedQuestion>> {
+    let plan: Plan = json_object(text)?;
+    let questions: Vec<PlannedQuestion> = plan
+        .sub_questions
+        .into_iter()
+        .filter(|q| !q.question.trim().is_empty())
+        .take(8)
+        .collect();
+    (!questions.is_empty()).then_some(questions)
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ExtractedClaim {
+    /// 1-based, as shown to the model.
+    #[serde(default = "first_sub_question")]
+    pub sub_question: usize,
+    pub claim: String,
+}
+
+fn first_sub_question() -> usize {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+struct Extraction {
+    #[serde(default)]
+    claims: Vec<ExtractedClaim>,
+}
+
+pub(super) fn parse_claims(text: &str) -> Vec<ExtractedClaim> {
+    json_object::<Extraction>(text)
+        .map(|e| e.claims)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct Reflection {
+    /// 1-based sub-question numbers.
+    #[serde(default)]
+    pub answered: Vec<usize>,
+    #[serde(default)]
+    pub queries: Vec<String>,
+}
+
+pub(super) fn parse_reflection(text: &str) -> Reflection {
+    json_object(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_json_replies() {
+        let plan = parse_plan(
+            "Sure!\n```json\n{\"sub_questions\": [{\"question\": \"What?\", \"queries\": [\"what is it\"]}, {\"question\": \" \"}]}\n```",
+        )
+        .unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].queries, vec!["what is it"]);
+        assert!(parse_plan("no plan here").is_none());
+
+        let claims =
+            parse_claims(r#"{"claims": [{"claim": "X is 5"}, {"sub_question": 2, "claim": "Y"}]}"#);
+        assert_eq!(claims[0].sub_question, 1);
+        assert_eq!(claims[1].sub_question, 2);
+        assert!(parse_claims("[]").is_empty());
+
+        let r = parse_reflection(r#"{"answered": [1], "queries": ["more on y"]}"#);
+        assert_eq!(r.answered, vec![1]);
+        assert!(parse_reflection("done").queries.is_empty());
+    }
+}
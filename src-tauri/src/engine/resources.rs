@@ -0,0 +1,507 @@
+// Paw Agent Engine — Resource Monitor
+//
+// Samples CPU and memory of Paw itself and of the child processes it started
+// (see `processes` — n8n, ollama, the headless browser, MCP servers…), plus
+// an Ollama the user runs themselves, so slowdowns on small machines can be
+// explained: "ollama is holding 5.1 GB of 8 GB".
+//
+// One `ps` call per sample on macOS/Linux; Windows gets memory only, from
+// `tasklist`. Each child counts with everything it spawned (npx → node,
+// ollama serve → model runners). CPU is the share of one core used since the
+// previous sample.
+//
+// A background loop samples every 30 s and emits `resource-warning` when the
+// local model server is thrashing: memory nearly exhausted while it holds a
+// large share of RAM, or the system swapping pages back in heavily.
+
+use crate::engine::processes;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// Don't repeat the same warning more often than this.
+const WARNING_COOLDOWN: Duration = Duration::from_secs(600);
+/// Available memory below this share of RAM counts as exhausted…
+const LOW_AVAILABLE_RATIO: f64 = 0.10;
+/// …when the model server holds at least this share.
+const MODEL_SERVER_RATIO: f64 = 0.25;
+/// Pages swapped in per second that mean the machine is thrashing.
+const SWAP_IN_THRASH_PAGES: f64 = 500.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    /// "paw", or the registry purpose ("ollama", "n8n", "browser", …)
+    pub purpose: String,
+    /// Processes counted: the process and everything it spawned
+    pub processes: usize,
+    pub rss_bytes: u64,
+    /// Percent of one core since the previous sample; None on the first
+    /// sample and on Windows
+    pub cpu_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    pub sampled_at: String,
+    /// Paw's own process
+    pub engine: ProcessUsage,
+    pub children: Vec<ProcessUsage>,
+    /// Paw plus all children
+    pub total_rss_bytes: u64,
+    pub system_memory_bytes: Option<u64>,
+    pub system_available_bytes: Option<u64>,
+    /// Pages read back from swap per second since the previous sample
+    pub swap_in_per_sec: Option<f64>,
+    pub warnings: Vec<String>,
+}
+
+/// One row of the process table.
+#[derive(Debug, Clone, PartialEq)]
+struct Proc {
+    pid: u32,
+    ppid: u32,
+    pgid: u32,
+    rss_bytes: u64,
+    /// Cumulative CPU time
+    cpu_secs: Option<f64>,
+    name: String,
+}
+
+#[derive(Default)]
+struct Previous {
+    at: Option<Instant>,
+    cpu: HashMap<u32, f64>,
+    swap_ins: Option<u64>,
+}
+
+static PREVIOUS: LazyLock<Mutex<Previous>> = LazyLock::new(Default::default);
+
+/// Take a sample now. Blocking (runs `ps`); call from a blocking thread.
+pub fn sample() -> ResourceUsage {
+    let table = process_table();
+    let (total, available) = system_memory();
+    let swap_ins = swap_ins();
+    let now = Instant::now();
+
+    let mut previous = PREVIOUS.lock();
+    let elapsed = previous.at.map(|at| now.duration_since(at).as_secs_f64());
+    let cpu_since = |members: &[&Proc]| -> Option<f64> {
+        let elapsed = elapsed.filter(|e| *e > 0.0)?;
+        let mut used = 0.0;
+        for p in members {
+            let now_secs = p.cpu_secs?;
+            if let Some(before) = previous.cpu.get(&p.pid) {
+                used += (now_secs - before).max(0.0);
+            }
+        }
+        Some(used / elapsed * 100.0)
+    };
+
+    let me = std::process::id();
+    let usage = |pid: u32, purpose: &str, members: Vec<&Proc>| ProcessUsage {
+        pid,
+        purpose: purpose.to_string(),
+        processes: members.len(),
+        rss_bytes: members.iter().map(|p| p.rss_bytes).sum(),
+        cpu_percent: cpu_since(&members),
+    };
+
+    let engine = usage(me, "paw", table.iter().filter(|p| p.pid == me).collect());
+    let mut counted: Vec<u32> = vec![me];
+    let mut children = vec![];
+    for managed in processes::list() {
+        let members = members_of(&table, managed.pid, managed.group);
+        counted.extend(members.iter().map(|p| p.pid));
+        children.push(usage(managed.pid, &managed.purpose, members));
+    }
+    // An Ollama the user started is still the model server that's thrashing
+    let external: Vec<&Proc> = table
+        .iter()
+        .filter(|p| p.name.starts_with("ollama") && !counted.contains(&p.pid))
+        .collect();
+    if let Some(first) = external.first() {
+        children.push(usage(first.pid, "ollama (external)", external.clone()));
+    }
+
+    let swap_in_per_sec = match (swap_ins, previous.swap_ins, elapsed) {
+        (Some(now_count), Some(before), Some(secs)) if secs > 0.0 => {
+            Some(now_count.saturating_sub(before) as f64 / secs)
+        }
+        _ => None,
+    };
+    previous.at = Some(now);
+    previous.cpu = table
+        .iter()
+        .filter_map(|p| Some((p.pid, p.cpu_secs?)))
+        .collect();
+    previous.swap_ins = swap_ins;
+    drop(previous);
+
+    let total_rss_bytes = engine.rss_bytes + children.iter().map(|c| c.rss_bytes).sum::<u64>();
+    let mut usage = ResourceUsage {
+        sampled_at: chrono::Utc::now().to_rfc3339(),
+        engine,
+        children,
+        total_rss_bytes,
+        system_memory_bytes: total,
+        system_available_bytes: available,
+        swap_in_per_sec,
+        warnings: vec![],
+    };
+    usage.warnings = thrashing_warnings(&usage);
+    usage
+}
+
+/// A process with everything it spawned (by parent, and by process group
+/// for group leaders).
+fn members_of(table: &[Proc], root: u32, group: bool) -> Vec<&Proc> {
+    let mut pids = vec![root];
+    let mut i = 0;
+    while i < pids.len() {
+        let parent = pids[i];
+        for p in table {
+            if p.ppid == parent && !pids.contains(&p.pid) {
+                pids.push(p.pid);
+            }
+        }
+        i += 1;
+    }
+    table
+        .iter()
+        .filter(|p| pids.contains(&p.pid) || (group && p.pgid == root))
+        .collect()
+}
+
+fn thrashing_warnings(usage: &ResourceUsage) -> Vec<String> {
+    let model_server: u64 = usage
+        .children
+        .iter()
+        .filter(|c| c.purpose.starts_with("ollama"))
+        .map(|c| c.rss_bytes)
+        .sum();
+    if model_server == 0 {
+        return vec![];
+    }
+    let mut warnings = vec![];
+    if let (Some(total), Some(available)) =
+        (usage.system_memory_bytes, usage.system_available_bytes)
+    {
+        let total_f = total as f64;
+        if (available as f64) < total_f * LOW_AVAILABLE_RATIO
+            && model_server as f64 >= total_f * MODEL_SERVER_RATIO
+        {
+            warnings.push(format!(
+                "Memory is nearly full ({} free of {}) and Ollama holds {}. Responses will be \
+                 slow — try a smaller model or close other apps.",
+                human_bytes(available),
+                human_bytes(total),
+                human_bytes(model_server)
+            ));
+        }
+    }
+    if usage
+        .swap_in_per_sec
+        .is_some_and(|rate| rate >= SWAP_IN_THRASH_PAGES)
+    {
+        warnings.push(format!(
+            "The system is swapping heavily while Ollama holds {} — the model doesn't fit in \
+             memory. A smaller model will be much faster.",
+            human_bytes(model_server)
+        ));
+    }
+    warnings
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= GB {
+        format!("{:.1} GB", bytes as f64 / GB)
+    } else {
+        format!("{:.0} MB", bytes as f64 / MB)
+    }
+}
+
+/// Sample every 30 s and emit `resource-warning` while the model server thrashes.
+pub fn spawn_monitor(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_warned: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let Ok(usage) = tokio::task::spawn_blocking(sample).await else {
+                continue;
+            };
+            if usage.warnings.is_empty()
+                || last_warned.is_some_and(|at| at.elapsed() < WARNING_COOLDOWN)
+            {
+                continue;
+            }
+            for message in &usage.warnings {
+                warn!("[resources] {}", message);
+            }
+            last_warned = Some(Instant::now());
+            let _ = app_handle.emit(
+                "resource-warning",
+                serde_json::json!({
+                    "kind": "model_server_thrashing",
+                    "messages": usage.warnings,
+                    "usage": usage,
+                }),
+            );
+        }
+    });
+    info!("[resources] Resource monitor started");
+}
+
+// ── Platform sampling ──────────────────────────────────────────────────
+
+#[cfg(unix)]
+fn process_table() -> Vec<Proc> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,pgid=,rss=,time=,comm="])
+        .output()
+    else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_ps_line)
+        .collect()
+}
+
+/// `  123     1   123  20480 01:02:03 /usr/bin/ollama`
+fn parse_ps_line(line: &str) -> Option<Proc> {
+    let mut fields = line.split_whitespace();
+    let pid = fields.next()?.parse().ok()?;
+    let ppid = fields.next()?.parse().ok()?;
+    let pgid = fields.next()?.parse().ok()?;
+    let rss_kb: u64 = fields.next()?.parse().ok()?;
+    let cpu_secs = parse_cpu_time(fields.next()?);
+    let command = fields.collect::<Vec<_>>().join(" ");
+    let name = command.rsplit('/').next().unwrap_or_default().to_string();
+    Some(Proc {
+        pid,
+        ppid,
+        pgid,
+        rss_bytes: rss_kb * 1024,
+        cpu_secs,
+        name,
+    })
+}
+
+/// `[dd-][hh:]mm:ss[.ff]` (Linux prints days and hours, macOS fractions).
+fn parse_cpu_time(text: &str) -> Option<f64> {
+    let (days, clock) = match text.split_once('-') {
+        Some((d, rest)) => (d.parse::<f64>().ok()?, rest),
+        None => (0.0, text),
+    };
+    let mut secs = 0.0;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(days * 86_400.0 + secs)
+}
+
+#[cfg(windows)]
+fn process_table() -> Vec<Proc> {
+    let Ok(output) = std::process::Command::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .output()
+    else {
+        return vec![];
+    };
+    // "ollama.exe","1234","Console","1","1,234,567 K"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.trim().trim_matches('"').split("\",\"").collect();
+            let pid = fields.get(1)?.parse().ok()?;
+            let kb: u64 = fields
+                .get(4)?
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .ok()?;
+            Some(Proc {
+                pid,
+                ppid: 0,
+                pgid: 0,
+                rss_bytes: kb * 1024,
+                cpu_secs: None,
+                name: fields[0].trim_end_matches(".exe").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// (total, available) bytes.
+#[cfg(target_os = "linux")]
+fn system_memory() -> (Option<u64>, Option<u64>) {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find(|l| l.starts_with(name))
+            .and_then(|l| l.split_whitespace().nth(1)?.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+    (field("MemTotal:"), field("MemAvailable:"))
+}
+
+#[cfg(target_os = "macos")]
+fn system_memory() -> (Option<u64>, Option<u64>) {
+    let total = std::process::Command::new("sysctl")
+        .args(["-n", "hw.memsize"])
+        .output()
+        .ok()
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok());
+    let stats = vm_stat();
+    let page = stats.get("page size").copied().unwrap_or(4096);
+    let available = ["Pages free", "Pages inactive", "Pages speculative"]
+        .iter()
+        .map(|k| stats.get(*k).copied())
+        .sum::<Option<u64>>()
+        .map(|pages| pages * page);
+    (total, available)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn system_memory() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// Pages swapped in since boot.
+#[cfg(target_os = "linux")]
+fn swap_ins() -> Option<u64> {
+    std::fs::read_to_string("/proc/vmstat")
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("pswpin ")?.trim().parse().ok())
+}
+
+#[cfg(target_os = "macos")]
+fn swap_ins() -> Option<u64> {
+    vm_stat().get("Swapins").copied()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn swap_ins() -> Option<u64> {
+    None
+}
+
+/// `vm_stat` counters, plus "page size" from its header line.
+#[cfg(target_os = "macos")]
+fn vm_stat() -> HashMap<String, u64> {
+    let Ok(output) = std::process::Command::new("vm_stat").output() else {
+        return HashMap::new();
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut stats = HashMap::new();
+    for line in text.lines() {
+        if let Some(rest) = line.split("page size of ").nth(1) {
+            if let Some(size) = rest.split_whitespace().next().and_then(|s| s.parse().ok()) {
+                stats.insert("page size".to_string(), size);
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            if let Ok(n) = value.trim().trim_end_matches('.').parse() {
+                stats.insert(key.trim().to_string(), n);
+            }
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, ppid: u32, pgid: u32, mb: u64) -> Proc {
+        Proc {
+            pid,
+            ppid,
+            pgid,
+            rss_bytes: mb * 1024 * 1024,
+            cpu_secs: Some(1.0),
+            name: "node".into(),
+        }
+    }
+
+    #[test]
+    fn parses_ps_rows_from_linux_and_macos() {
+        let linux = parse_ps_line("  812     1   812 5242880 1-02:03:04 ollama").unwrap();
+        assert_eq!((linux.pid, linux.ppid, linux.pgid), (812, 1, 812));
+        assert_eq!(linux.rss_bytes, 5 * 1024 * 1024 * 1024);
+        assert_eq!(
+            linux.cpu_secs,
+            Some(86_400.0 + 2.0 * 3600.0 + 3.0 * 60.0 + 4.0)
+        );
+        assert_eq!(linux.name, "ollama");
+
+        let mac =
+            parse_ps_line("901 1 901 20480 12:01.50 /Applications/Ollama.app/ollama").unwrap();
+        assert_eq!(mac.cpu_secs, Some(721.5));
+        assert_eq!(mac.name, "ollama");
+        assert!(parse_ps_line("garbage").is_none());
+    }
+
+    #[test]
+    fn children_count_with_what_they_spawned() {
+        let table = vec![
+            proc(100, 1, 100, 50),    // npx (group leader)
+            proc(101, 100, 100, 400), // node n8n
+            proc(102, 1, 100, 10),    // reparented group member
+            proc(200, 1, 200, 30),    // unrelated
+        ];
+        let members: Vec<u32> = members_of(&table, 100, true)
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(members, vec![100, 101, 102]);
+        let members: Vec<u32> = members_of(&table, 100, false)
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(members, vec![100, 101]);
+    }
+
+    #[test]
+    fn warns_only_when_the_model_server_is_squeezed() {
+        let gb = 1024 * 1024 * 1024;
+        let mut usage = ResourceUsage {
+            sampled_at: String::new(),
+            engine: ProcessUsage {
+                pid: 1,
+                purpose: "paw".into(),
+                processes: 1,
+                rss_bytes: gb / 2,
+                cpu_percent: None,
+            },
+            children: vec![ProcessUsage {
+                pid: 2,
+                purpose: "ollama".into(),
+                processes: 2,
+                rss_bytes: 5 * gb,
+                cpu_percent: Some(380.0),
+            }],
+            total_rss_bytes: 0,
+            system_memory_bytes: Some(8 * gb),
+            system_available_bytes: Some(gb / 4),
+            swap_in_per_sec: Some(20.0),
+            warnings: vec![],
+        };
+        assert_eq!(thrashing_warnings(&usage).len(), 1);
+
+        usage.swap_in_per_sec = Some(2_000.0);
+        assert_eq!(thrashing_warnings(&usage).len(), 2);
+
+        usage.children[0].purpose = "n8n".into();
+        assert!(thrashing_warnings(&usage).is_empty());
+    }
+}
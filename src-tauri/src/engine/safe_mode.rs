@@ -0,0 +1,87 @@
+// Paw Agent Engine — Safe mode
+//
+// A read-only mode for demos, screen-sharing, or handing the app to
+// someone else. While `EngineConfig.safe_mode` is on:
+//   - every tool that isn't read-only (file writes, exec, sends, posts,
+//     trades) is refused, by the agent loop before it asks for approval
+//     and by `tools::execute_tool` for every other caller
+//   - channel bridges are stopped and refuse to start
+// The frontend shows a banner from `EngineConfig.safe_mode` and the
+// `safe-mode` event.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::state::EngineState;
+use log::info;
+use openpawz_core::engine::tool_metadata::{self, ToolMutability};
+use tauri::{Emitter, Manager};
+
+/// Emitted with `{ "enabled": bool }` when safe mode is switched.
+pub const EVENT: &str = "safe-mode";
+
+pub fn is_active(app_handle: &tauri::AppHandle) -> bool {
+    app_handle
+        .try_state::<EngineState>()
+        .map(|state| state.config.lock().safe_mode)
+        .unwrap_or(false)
+}
+
+/// Whether safe mode refuses `tool`.
+pub fn blocks(tool: &str) -> bool {
+    tool_metadata::mutability(tool) != ToolMutability::ReadOnly
+}
+
+/// The tool result for a call safe mode refuses, if it does.
+pub fn refusal(app_handle: &tauri::AppHandle, tool: &str) -> Option<String> {
+    (blocks(tool) && is_active(app_handle)).then(|| {
+        format!(
+            "Error: '{}' was not run — Paw is in safe mode, where only read-only tools are \
+             available. Tell the user what you would have done instead.",
+            tool
+        )
+    })
+}
+
+/// Refuse to start a channel bridge in safe mode.
+pub fn check_bridge_start(app_handle: &tauri::AppHandle) -> EngineResult<()> {
+    if is_active(app_handle) {
+        return Err(
+            "Safe mode is on — channel bridges stay disconnected until it's turned off".into(),
+        );
+    }
+    Ok(())
+}
+
+/// Turn safe mode on or off. Turning it on disconnects every bridge.
+pub fn set(app_handle: &tauri::AppHandle, enabled: bool) -> EngineResult<()> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine not initialized")?;
+    {
+        let mut cfg = state.config.lock();
+        cfg.safe_mode = enabled;
+        let json = serde_json::to_string(&*cfg)?;
+        state.store.set_config("engine_config", &json)?;
+    }
+    if enabled {
+        crate::engine::shutdown::stop_bridges();
+    }
+    info!("[safe-mode] {}", if enabled { "On" } else { "Off" });
+    let _ = app_handle.emit(EVENT, serde_json::json!({ "enabled": enabled }));
+    Ok(())
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_read_only_tools_get_through() {
+        assert!(!blocks("read_file"));
+        assert!(!blocks("web_search"));
+        assert!(blocks("write_file"));
+        assert!(blocks("exec"));
+        assert!(blocks("email_send"));
+    }
+}
@@ -0,0 +1,263 @@
+// search/backends.rs — one function per search backend, each returning
+// normalized `SearchResult`s.
+
+use super::SearchResult;
+use crate::atoms::error::EngineResult;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// Error with the response body when the backend answered non-2xx.
+async fn read_json(label: &str, resp: reqwest::Response) -> EngineResult<Value> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!(
+            "{} returned HTTP {}: {}",
+            label,
+            status.as_u16(),
+            crate::engine::types::truncate_utf8(&body, 300)
+        )
+        .into());
+    }
+    Ok(resp.json().await?)
+}
+
+fn text(v: &Value) -> String {
+    v.as_str().unwrap_or_default().trim().to_string()
+}
+
+// ── DuckDuckGo (HTML endpoint, no key) ─────────────────────────────────
+
+pub(super) async fn duckduckgo(
+    client: &reqwest::Client,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchResult>> {
+    let html = client
+        .get("https://html.duckduckgo.com/html/")
+        .query(&[("q", query)])
+        .send()
+        .await?
+        .text()
+        .await?;
+    Ok(parse_duckduckgo(&html, limit))
+}
+
+fn parse_duckduckgo(html: &str, limit: usize) -> Vec<SearchResult> {
+    let document = Html::parse_document(html);
+    let result_selector = Selector::parse(".result").unwrap();
+    let title_selector = Selector::parse(".result__a").unwrap();
+    let snippet_selector = Selector::parse(".result__snippet").unwrap();
+    let url_selector = Selector::parse(".result__url").unwrap();
+    let first_text = |element: &scraper::ElementRef, selector: &Selector| {
+        element
+            .select(selector)
+            .next()
+            .map(|e| e.text().collect::<String>().trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut results: Vec<SearchResult> = document
+        .select(&result_selector)
+        .take(limit)
+        .filter_map(|element| {
+            let title = first_text(&element, &title_selector);
+            (!title.is_empty()).then(|| SearchResult {
+                title,
+                url: first_text(&element, &url_selector),
+                snippet: first_text(&element, &snippet_selector),
+                published: None,
+            })
+        })
+        .collect();
+
+    if results.is_empty() {
+        // Fallback: DuckDuckGo "zero click" or a different layout
+        let link_selector = Selector::parse("a.result__a").unwrap();
+        results = document
+            .select(&link_selector)
+            .take(limit)
+            .filter_map(|element| {
+                let title = element.text().collect::<String>().trim().to_string();
+                let href = element.value().attr("href").unwrap_or("").to_string();
+                (!title.is_empty() && !href.is_empty()).then_some(SearchResult {
+                    title,
+                    url: href,
+                    snippet: String::new(),
+                    published: None,
+                })
+            })
+            .collect();
+    }
+    results
+}
+
+// ── SearxNG (self-hosted, JSON format) ─────────────────────────────────
+
+pub(super) async fn searxng(
+    client: &reqwest::Client,
+    base_url: &str,
+    query: &str,
+) -> EngineResult<Vec<SearchResult>> {
+    let resp = client
+        .get(format!("{}/search", base_url.trim_end_matches('/')))
+        .query(&[("q", query), ("format", "json")])
+        .send()
+        .await?;
+    if resp.status() == reqwest::StatusCode::FORBIDDEN {
+        return Err("SearxNG refused the JSON format — add `json` to search.formats in the instance's settings.yml".into());
+    }
+    Ok(parse_searxng(&read_json("SearxNG", resp).await?))
+}
+
+fn parse_searxng(json: &Value) -> Vec<SearchResult> {
+    json["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| {
+                    let url = text(&r["url"]);
+                    (!url.is_empty()).then(|| SearchResult {
+                        title: text(&r["title"]),
+                        url,
+                        snippet: text(&r["content"]),
+                        published: r["publishedDate"].as_str().map(String::from),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ── Brave Search API ───────────────────────────────────────────────────
+
+pub(super) async fn brave(
+    client: &reqwest::Client,
+    api_key: &str,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchResult>> {
+    let count = limit.clamp(1, 20).to_string();
+    let resp = client
+        .get("https://api.search.brave.com/res/v1/web/search")
+        .header("X-Subscription-Token", api_key)
+        .header("Accept", "application/json")
+        .query(&[("q", query), ("count", &count)])
+        .send()
+        .await?;
+    Ok(parse_brave(&read_json("Brave Search", resp).await?))
+}
+
+fn parse_brave(json: &Value) -> Vec<SearchResult> {
+    json["web"]["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .map(|r| SearchResult {
+                    title: text(&r["title"]),
+                    url: text(&r["url"]),
+                    snippet: strip_tags(&text(&r["description"])),
+                    published: r["age"]
+                        .as_str()
+                        .or(r["page_age"].as_str())
+                        .map(String::from),
+                })
+                .filter(|r| !r.url.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ── Kagi Search API ────────────────────────────────────────────────────
+
+pub(super) async fn kagi(
+    client: &reqwest::Client,
+    api_key: &str,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchResult>> {
+    let limit = limit.to_string();
+    let resp = client
+        .get("https://kagi.com/api/v0/search")
+        .header("Authorization", format!("Bot {}", api_key))
+        .query(&[("q", query), ("limit", &limit)])
+        .send()
+        .await?;
+    Ok(parse_kagi(&read_json("Kagi", resp).await?))
+}
+
+fn parse_kagi(json: &Value) -> Vec<SearchResult> {
+    json["data"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                // t = 0 is a search result; t = 1 is a related-searches block
+                .filter(|r| r["t"].as_u64() == Some(0))
+                .map(|r| SearchResult {
+                    title: text(&r["title"]),
+                    url: text(&r["url"]),
+                    snippet: text(&r["snippet"]),
+                    published: r["published"].as_str().map(String::from),
+                })
+                .filter(|r| !r.url.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Brave marks query terms with <strong>; drop inline tags.
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn normalizes_backend_results() {
+        let searx = parse_searxng(&json!({ "results": [
+            { "title": "Rust", "url": "https://rust-lang.org", "content": "A language", "publishedDate": null },
+            { "title": "no url" }
+        ]}));
+        assert_eq!(searx.len(), 1);
+        assert_eq!(searx[0].snippet, "A language");
+
+        let brave = parse_brave(&json!({ "web": { "results": [
+            { "title": "Rust", "url": "https://rust-lang.org", "description": "The <strong>Rust</strong> language", "age": "2 days ago" }
+        ]}}));
+        assert_eq!(brave[0].snippet, "The Rust language");
+        assert_eq!(brave[0].published.as_deref(), Some("2 days ago"));
+
+        let kagi = parse_kagi(&json!({ "data": [
+            { "t": 0, "url": "https://rust-lang.org", "title": "Rust", "snippet": "Fast" },
+            { "t": 1, "list": ["rust book"] }
+        ]}));
+        assert_eq!(kagi.len(), 1);
+        assert_eq!(kagi[0].title, "Rust");
+    }
+
+    #[test]
+    fn parses_duckduckgo_html() {
+        let html = r#"<div class="result"><a class="result__a" href="/l/?u=x">Rust</a>
+            <a class="result__url">rust-lang.org</a><a class="result__snippet">A language</a></div>"#;
+        let results = parse_duckduckgo(html, 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "rust-lang.org");
+        assert_eq!(results[0].snippet, "A language");
+    }
+}
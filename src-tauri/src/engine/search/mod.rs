@@ -0,0 +1,369 @@
+// Paw Agent Engine — Web search providers
+//
+// `web_search` goes through one configurable backend:
+//
+//   backend      auth                         notes
+//   ───────────  ───────────────────────────  ─────────────────────────────
+//   duckduckgo   none                         HTML endpoint, default
+//   searxng      none (instance URL)          self-hosted; the instance must
+//                                             enable the `json` format
+//   brave        API key (X-Subscription-…)   Brave Search API
+//   kagi         API key (Bot token)          Kagi Search API
+//
+// Settings (active backend, instance URL, per-backend requests/minute) live
+// in the config table; API keys live in the key vault under
+// `search:<backend>`. Each backend has a sliding one-minute rate limit; when
+// the active backend is rate-limited or fails, the search falls back to
+// DuckDuckGo (unless `fallback` is off).
+//
+// Every backend's results are normalized to `SearchResult`.
+
+mod backends;
+
+use crate::atoms::error::EngineResult;
+use crate::engine::key_vault;
+use crate::engine::sessions::SessionStore;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+const SETTINGS_KEY: &str = "search_settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackend {
+    #[default]
+    DuckDuckGo,
+    Searxng,
+    Brave,
+    Kagi,
+}
+
+impl SearchBackend {
+    pub const ALL: [SearchBackend; 4] = [
+        SearchBackend::DuckDuckGo,
+        SearchBackend::Searxng,
+        SearchBackend::Brave,
+        SearchBackend::Kagi,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchBackend::DuckDuckGo => "DuckDuckGo",
+            SearchBackend::Searxng => "SearxNG",
+            SearchBackend::Brave => "Brave Search",
+            SearchBackend::Kagi => "Kagi",
+        }
+    }
+
+    pub fn needs_api_key(self) -> bool {
+        matches!(self, SearchBackend::Brave | SearchBackend::Kagi)
+    }
+
+    /// Requests per minute when the user has not set a limit.
+    fn default_rate_limit(self) -> u32 {
+        match self {
+            // The HTML endpoint starts serving CAPTCHAs to bursty clients
+            SearchBackend::DuckDuckGo => 20,
+            SearchBackend::Searxng => 0,
+            // Brave's free plan allows one request per second
+            SearchBackend::Brave => 60,
+            SearchBackend::Kagi => 30,
+        }
+    }
+
+    fn vault_purpose(self) -> String {
+        let id = match self {
+            SearchBackend::DuckDuckGo => "duckduckgo",
+            SearchBackend::Searxng => "searxng",
+            SearchBackend::Brave => "brave",
+            SearchBackend::Kagi => "kagi",
+        };
+        format!("search:{}", id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchProviderConfig {
+    pub backend: SearchBackend,
+    /// Instance URL — SearxNG only (e.g. `https://searx.example.org`).
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Max requests per minute; 0 = unlimited. `None` = backend default.
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchSettings {
+    #[serde(default)]
+    pub active: SearchBackend,
+    #[serde(default)]
+    pub providers: Vec<SearchProviderConfig>,
+    /// Retry on DuckDuckGo when the active backend fails or is rate-limited.
+    #[serde(default = "default_true")]
+    pub fallback: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        SearchSettings {
+            active: SearchBackend::DuckDuckGo,
+            providers: Vec::new(),
+            fallback: true,
+        }
+    }
+}
+
+impl SearchSettings {
+    pub fn provider(&self, backend: SearchBackend) -> Option<&SearchProviderConfig> {
+        self.providers.iter().find(|p| p.backend == backend)
+    }
+
+    fn rate_limit(&self, backend: SearchBackend) -> u32 {
+        self.provider(backend)
+            .and_then(|p| p.requests_per_minute)
+            .unwrap_or_else(|| backend.default_rate_limit())
+    }
+}
+
+/// One search hit, the same shape for every backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+}
+
+/// Backend status for the settings UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchProviderStatus {
+    pub backend: SearchBackend,
+    pub label: String,
+    pub needs_api_key: bool,
+    pub has_api_key: bool,
+    pub base_url: Option<String>,
+    pub requests_per_minute: u32,
+}
+
+pub fn load_settings(store: &SessionStore) -> SearchSettings {
+    store
+        .get_config(SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(store: &SessionStore, settings: &SearchSettings) -> EngineResult<()> {
+    store.set_config(SETTINGS_KEY, &serde_json::to_string(settings)?)
+}
+
+pub fn api_key(backend: SearchBackend) -> Option<String> {
+    key_vault::get(&backend.vault_purpose())
+        .map(|k| k.to_string())
+        .filter(|k| !k.is_empty())
+}
+
+/// Store (or with an empty key, remove) a backend's API key.
+pub fn set_api_key(backend: SearchBackend, key: &str) {
+    if key.trim().is_empty() {
+        key_vault::remove(&backend.vault_purpose());
+    } else {
+        key_vault::set(&backend.vault_purpose(), key.trim());
+    }
+}
+
+pub fn provider_status(settings: &SearchSettings) -> Vec<SearchProviderStatus> {
+    SearchBackend::ALL
+        .iter()
+        .map(|&backend| SearchProviderStatus {
+            backend,
+            label: backend.label().into(),
+            needs_api_key: backend.needs_api_key(),
+            has_api_key: backend.needs_api_key() && api_key(backend).is_some(),
+            base_url: settings.provider(backend).and_then(|p| p.base_url.clone()),
+            requests_per_minute: settings.rate_limit(backend),
+        })
+        .collect()
+}
+
+/// Whether `backend` has what it needs to run (key or instance URL).
+pub fn check_configured(settings: &SearchSettings, backend: SearchBackend) -> Result<(), String> {
+    if backend.needs_api_key() && api_key(backend).is_none() {
+        return Err(format!("{} needs an API key", backend.label()));
+    }
+    if backend == SearchBackend::Searxng
+        && settings
+            .provider(backend)
+            .and_then(|p| p.base_url.as_deref())
+            .is_none_or(|u| u.trim().is_empty())
+    {
+        return Err("SearxNG needs an instance URL".into());
+    }
+    Ok(())
+}
+
+// ── Rate limiting ──────────────────────────────────────────────────────
+
+static RECENT: OnceLock<Mutex<HashMap<SearchBackend, VecDeque<Instant>>>> = OnceLock::new();
+
+/// Take a slot in `backend`'s one-minute window, or return how long until
+/// one frees up.
+fn acquire(backend: SearchBackend, per_minute: u32) -> Result<(), Duration> {
+    if per_minute == 0 {
+        return Ok(());
+    }
+    let mut recent = RECENT.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    let window = recent.entry(backend).or_default();
+    take_slot(window, per_minute, Instant::now())
+}
+
+fn take_slot(
+    window: &mut VecDeque<Instant>,
+    per_minute: u32,
+    now: Instant,
+) -> Result<(), Duration> {
+    const WINDOW: Duration = Duration::from_secs(60);
+    while window
+        .front()
+        .is_some_and(|t| now.duration_since(*t) >= WINDOW)
+    {
+        window.pop_front();
+    }
+    if window.len() >= per_minute as usize {
+        let oldest = window.front().copied().unwrap_or(now);
+        return Err(WINDOW.saturating_sub(now.duration_since(oldest)));
+    }
+    window.push_back(now);
+    Ok(())
+}
+
+// ── Search ─────────────────────────────────────────────────────────────
+
+async fn search_with(
+    settings: &SearchSettings,
+    backend: SearchBackend,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchResult>> {
+    check_configured(settings, backend)?;
+    if let Err(wait) = acquire(backend, settings.rate_limit(backend)) {
+        return Err(format!(
+            "{} rate limit reached — try again in {}s",
+            backend.label(),
+            wait.as_secs().max(1)
+        )
+        .into());
+    }
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .build()?;
+    let mut results = match backend {
+        SearchBackend::DuckDuckGo => backends::duckduckgo(&client, query, limit).await?,
+        SearchBackend::Searxng => {
+            let base = settings
+                .provider(backend)
+                .and_then(|p| p.base_url.as_deref())
+                .unwrap_or_default();
+            backends::searxng(&client, base, query).await?
+        }
+        SearchBackend::Brave => {
+            let key = api_key(backend).unwrap_or_default();
+            backends::brave(&client, &key, query, limit).await?
+        }
+        SearchBackend::Kagi => {
+            let key = api_key(backend).unwrap_or_default();
+            backends::kagi(&client, &key, query, limit).await?
+        }
+    };
+    results.truncate(limit);
+    Ok(results)
+}
+
+/// Search with the active backend, falling back to DuckDuckGo when allowed.
+/// Returns the results and the backend that produced them.
+pub async fn search(
+    store: &SessionStore,
+    query: &str,
+    limit: usize,
+) -> EngineResult<(Vec<SearchResult>, SearchBackend)> {
+    let settings = load_settings(store);
+    let active = settings.active;
+    info!("[search] {} via {} limit={}", query, active.label(), limit);
+    match search_with(&settings, active, query, limit).await {
+        Ok(results) => Ok((results, active)),
+        Err(e) if settings.fallback && active != SearchBackend::DuckDuckGo => {
+            warn!(
+                "[search] {} failed, falling back to DuckDuckGo: {}",
+                active.label(),
+                e
+            );
+            let results = search_with(&settings, SearchBackend::DuckDuckGo, query, limit).await?;
+            Ok((results, SearchBackend::DuckDuckGo))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Numbered Markdown list for the `web_search` tool result.
+pub fn format_results(query: &str, results: &[SearchResult], backend: SearchBackend) -> String {
+    if results.is_empty() {
+        return format!("No search results found for '{}'.", query);
+    }
+    let mut output = format!("Search results for '{}' ({}):\n\n", query, backend.label());
+    for (i, r) in results.iter().enumerate() {
+        output.push_str(&format!("{}. **{}**\n{}\n", i + 1, r.title, r.url));
+        if let Some(date) = &r.published {
+            output.push_str(&format!("Published: {}\n", date));
+        }
+        if !r.snippet.is_empty() {
+            output.push_str(&r.snippet);
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_window_slides() {
+        let mut window = VecDeque::new();
+        let start = Instant::now();
+        assert!(take_slot(&mut window, 2, start).is_ok());
+        assert!(take_slot(&mut window, 2, start + Duration::from_secs(1)).is_ok());
+        let wait = take_slot(&mut window, 2, start + Duration::from_secs(10)).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(50));
+        assert!(take_slot(&mut window, 2, start + Duration::from_secs(61)).is_ok());
+    }
+
+    #[test]
+    fn settings_defaults_and_keys() {
+        let settings: SearchSettings =
+            serde_json::from_str(r#"{"active":"searxng","providers":[{"backend":"searxng","base_url":"https://searx.local"}]}"#)
+                .unwrap();
+        assert!(settings.fallback);
+        assert_eq!(settings.active, SearchBackend::Searxng);
+        assert_eq!(settings.rate_limit(SearchBackend::Searxng), 0);
+        assert_eq!(settings.rate_limit(SearchBackend::Brave), 60);
+        assert!(check_configured(&settings, SearchBackend::Searxng).is_ok());
+        assert_eq!(
+            SearchBackend::DuckDuckGo.vault_purpose(),
+            "search:duckduckgo"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// Paw Agent Engine — Graceful Shutdown
+//
+// Runs when the app is asked to quit (last window closed, Cmd+Q, tray quit)
+// so agent runs, bridges and child processes are wound down in order instead
+// of being killed mid-write:
+//
+//   1. draining   — refuse new work; ask running agent loops to yield, then
+//                   abort whatever is still running when the drain budget ends
+//   2. bridges    — stop every channel bridge's poll loop
+//   3. flush      — checkpoint the SQLite WAL (inbound journal, memories,
+//                   messages) into the main database file
+//   4. processes  — disconnect MCP servers, stop n8n, the headless browser
+//                   and any `ollama serve` Paw started itself, then anything
+//                   else left in the process registry (engine::processes)
+//
+// Each phase is reported to the frontend as a `shutdown-progress` event.
+// The whole sequence is bounded by a timeout; the app exits regardless.
+
+use crate::engine::state::EngineState;
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// Upper bound for the whole shutdown sequence.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static SHUTDOWN_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+/// True once shutdown has begun. New chat turns, bridge messages and cron
+/// runs check this and refuse to start.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// True once the shutdown sequence has finished and the app may exit.
+pub fn is_complete() -> bool {
+    SHUTDOWN_COMPLETE.load(Ordering::SeqCst)
+}
+
+fn progress(app_handle: &tauri::AppHandle, phase: &str, detail: &str) {
+    info!("[shutdown] {}: {}", phase, detail);
+    let _ = app_handle.emit(
+        "shutdown-progress",
+        serde_json::json!({ "phase": phase, "detail": detail }),
+    );
+}
+
+/// Run the shutdown sequence once. Later calls return immediately.
+pub async fn shutdown(app_handle: &tauri::AppHandle, timeout: Duration) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let deadline = Instant::now() + timeout;
+
+    // Agent runs get most of the budget; the remaining phases are quick.
+    drain_agent_runs(app_handle, timeout * 3 / 5).await;
+
+    progress(app_handle, "bridges", "Stopping channel bridges");
+    stop_bridges();
+
+    progress(app_handle, "flush", "Writing pending data to disk");
+    if let Some(state) = app_handle.try_state::<EngineState>() {
+        if let Err(e) = state.store.flush_deferred() {
+            warn!("[shutdown] Flushing queued writes failed: {}", e);
+        }
+        if let Err(e) = state
+            .store
+            .conn
+            .lock()
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+        {
+            warn!("[shutdown] WAL checkpoint failed: {}", e);
+        }
+    }
+
+    progress(app_handle, "processes", "Stopping child processes");
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if tokio::time::timeout(remaining, stop_processes(app_handle))
+        .await
+        .is_err()
+    {
+        warn!("[shutdown] Timed out stopping child processes");
+    }
+
+    SHUTDOWN_COMPLETE.store(true, Ordering::SeqCst);
+    progress(app_handle, "done", "Shutdown complete");
+}
+
+/// Ask every active agent loop to wrap up (the same YieldSignal a queued
+/// chat message uses) and wait for chat runs and cron tasks to finish.
+/// Anything still running after `budget` is aborted.
+async fn drain_agent_runs(app_handle: &tauri::AppHandle, budget: Duration) {
+    let Some(state) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let running = || state.active_runs.lock().len() + state.inflight_tasks.lock().len();
+    if running() == 0 {
+        return;
+    }
+    progress(
+        app_handle,
+        "draining",
+        &format!("Waiting for {} agent run(s) to finish", running()),
+    );
+    for signal in state.yield_signals.lock().values() {
+        signal.request_yield();
+    }
+
+    let deadline = Instant::now() + budget;
+    while running() > 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let leftover: Vec<(String, tokio::task::AbortHandle)> =
+        state.active_runs.lock().drain().collect();
+    if !leftover.is_empty() {
+        warn!(
+            "[shutdown] Aborting {} agent run(s) that did not finish in time",
+            leftover.len()
+        );
+        for (session_id, handle) in leftover {
+            handle.abort();
+            info!("[shutdown] Aborted run for session {}", session_id);
+        }
+    }
+}
+
+pub(crate) fn stop_bridges() {
+    crate::engine::telegram::stop_bridge();
+    crate::engine::discord::stop_bridge();
+    crate::engine::slack::stop_bridge();
+    crate::engine::matrix::stop_bridge();
+    crate::engine::mattermost::stop_bridge();
+    crate::engine::nextcloud::stop_bridge();
+    crate::engine::irc::stop_bridge();
+    crate::engine::twitch::stop_bridge();
+    crate::engine::nostr::stop_bridge();
+    crate::engine::whatsapp::stop_bridge();
+    crate::engine::email_bridge::stop_bridge();
+    crate::engine::webchat::stop_bridge();
+    crate::engine::webhook::stop_bridge();
+}
+
+async fn stop_processes(app_handle: &tauri::AppHandle) {
+    if let Some(state) = app_handle.try_state::<EngineState>() {
+        state.mcp_registry.lock().await.disconnect_all().await;
+    }
+    crate::engine::n8n_engine::shutdown(app_handle).await;
+    crate::engine::web::close_browser();
+    crate::engine::memory::ollama::stop_spawned_ollama();
+    crate::engine::processes::stop_all();
+}
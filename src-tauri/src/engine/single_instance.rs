@@ -0,0 +1,273 @@
+// Paw Agent Engine — Single-Instance Enforcement
+//
+// Two Paw processes on the same data root would share engine.db and the
+// agent workspaces, and both would connect every channel bridge. The first
+// instance holds an OS advisory lock (flock / LockFileEx) on
+// `{data_root}/paw.lock` for its lifetime, and records its PID and a
+// loopback port in `paw.instance` (kept apart because Windows locks block
+// other processes from reading the locked file). The OS drops the lock when the process dies,
+// so a crash or kill -9 never leaves a lock behind and staleness is never
+// guessed from a timeout. A second launch that finds the lock held asks
+// the holder to focus its window over that port and exits.
+//
+// The port is answered from the moment the lock is taken, well before the
+// window exists (DB open, migrations, bridge start); focus requests that
+// arrive early are queued until `serve` installs the window handler.
+//
+// The lock file itself is never deleted: removing a locked file would let a
+// third launch lock a fresh inode while the holder still runs.
+//
+// Protocol (one line each way over 127.0.0.1):
+//   → "focus"
+//   ← "paw-ok"
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+
+const LOCK_FILE: &str = "paw.lock";
+const INFO_FILE: &str = "paw.instance";
+const PROBE_TIMEOUT: Duration = Duration::from_millis(800);
+/// Handoff attempts against a live holder (it may not have written its
+/// port yet).
+const HANDOFF_ATTEMPTS: u32 = 10;
+const REPLY_OK: &str = "paw-ok";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockInfo {
+    pid: u32,
+    port: u16,
+}
+
+type Handler = Box<dyn Fn(&str) + Send>;
+
+/// Handoff requests seen by the accept thread, held until a handler exists.
+#[derive(Default)]
+struct Requests {
+    handler: Option<Handler>,
+    queued: Vec<String>,
+}
+
+/// Held by the primary instance for its lifetime. Dropping it releases the
+/// lock.
+pub struct InstanceGuard {
+    _lock: File,
+    requests: Arc<Mutex<Requests>>,
+}
+
+pub enum Acquire {
+    /// This is the only instance; keep the guard alive and call `serve`
+    /// once the window exists.
+    Primary(InstanceGuard),
+    /// Another instance holds the lock (and was asked to focus); exit now.
+    Forwarded,
+}
+
+/// Acquire the instance lock for the current data root.
+pub fn acquire() -> std::io::Result<Acquire> {
+    acquire_in(&crate::engine::paths::paw_data_dir())
+}
+
+fn acquire_in(dir: &Path) -> std::io::Result<Acquire> {
+    std::fs::create_dir_all(dir)?;
+    let info_path = dir.join(INFO_FILE);
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(LOCK_FILE))?;
+
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            hand_off(&info_path);
+            return Ok(Acquire::Forwarded);
+        }
+        Err(TryLockError::Error(e)) => return Err(e),
+    }
+
+    // Answer handoffs before anything slow happens in this process
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))?;
+    let info = LockInfo {
+        pid: std::process::id(),
+        port: listener.local_addr()?.port(),
+    };
+    let requests = Arc::new(Mutex::new(Requests::default()));
+    accept_handoffs(listener, Arc::clone(&requests));
+
+    std::fs::write(&info_path, serde_json::to_string(&info)?)?;
+    Ok(Acquire::Primary(InstanceGuard {
+        _lock: file,
+        requests,
+    }))
+}
+
+/// Ask the live lock holder to focus. It is running whether or not it
+/// answers, so this launch exits either way.
+fn hand_off(info_path: &Path) {
+    for _ in 0..HANDOFF_ATTEMPTS {
+        if let Some(existing) = read_instance(info_path) {
+            if forward_focus(existing.port) {
+                info!(
+                    "[instance] Paw is already running (pid {}) — focused it",
+                    existing.pid
+                );
+                return;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    warn!("[instance] Paw is already running but did not answer the focus request");
+}
+
+fn read_instance(path: &Path) -> Option<LockInfo> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Ask the instance listening on `port` to focus. True only if it answered
+/// like Paw (a recycled port owned by another program doesn't count).
+fn forward_focus(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    if stream.write_all(b"focus\n").is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == REPLY_OK
+}
+
+/// Acknowledge every handoff right away; run it now if a handler is
+/// installed, otherwise queue it for `serve`.
+fn accept_handoffs(listener: TcpListener, requests: Arc<Mutex<Requests>>) {
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+            let mut line = String::new();
+            let mut reader = BufReader::new(&stream);
+            if reader.read_line(&mut line).is_err() {
+                continue;
+            }
+            let request = line.trim();
+            info!("[instance] Handoff request from second launch: {}", request);
+            {
+                let mut requests = requests.lock();
+                match &requests.handler {
+                    Some(handler) => handler(request),
+                    None => requests.queued.push(request.to_string()),
+                }
+            }
+            let _ = (&stream).write_all(format!("{}\n", REPLY_OK).as_bytes());
+        }
+    });
+}
+
+impl InstanceGuard {
+    /// Start handling handoff requests from later launches by focusing the
+    /// main window, including any that arrived before the window existed.
+    pub fn serve(&self, app_handle: tauri::AppHandle) {
+        self.serve_with(move |request| {
+            if request == "focus" {
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    let _ = window.unminimize();
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        });
+    }
+
+    fn serve_with(&self, on_request: impl Fn(&str) + Send + 'static) {
+        let mut requests = self.requests.lock();
+        for request in std::mem::take(&mut requests.queued) {
+            on_request(&request);
+        }
+        requests.handler = Some(Box::new(on_request));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_root() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("paw-instance-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn focus_counter(guard: &InstanceGuard) -> Arc<AtomicUsize> {
+        let focused = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&focused);
+        guard.serve_with(move |req| {
+            if req == "focus" {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        focused
+    }
+
+    #[test]
+    fn second_launch_forwards_to_primary() {
+        let dir = temp_root();
+
+        let Acquire::Primary(guard) = acquire_in(&dir).unwrap() else {
+            panic!("first launch should be primary");
+        };
+        let focused = focus_counter(&guard);
+
+        assert!(matches!(acquire_in(&dir).unwrap(), Acquire::Forwarded));
+        assert_eq!(focused.load(Ordering::SeqCst), 1);
+
+        // Releasing the lock frees the data root for the next launch
+        drop(guard);
+        assert!(matches!(acquire_in(&dir).unwrap(), Acquire::Primary(_)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn early_focus_is_queued_until_serve() {
+        let dir = temp_root();
+
+        // Still starting up: no window handler yet
+        let Acquire::Primary(guard) = acquire_in(&dir).unwrap() else {
+            panic!("first launch should be primary");
+        };
+        assert!(matches!(acquire_in(&dir).unwrap(), Acquire::Forwarded));
+
+        let focused = focus_counter(&guard);
+        assert_eq!(focused.load(Ordering::SeqCst), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stale_lock_is_taken_over() {
+        let dir = temp_root();
+        std::fs::create_dir_all(&dir).unwrap();
+        // Left by a crashed instance: files present, nobody holds the lock
+        // and nobody listens on the port
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        std::fs::write(dir.join(LOCK_FILE), "").unwrap();
+        std::fs::write(
+            dir.join(INFO_FILE),
+            serde_json::to_string(&LockInfo { pid: 1, port }).unwrap(),
+        )
+        .unwrap();
+
+        assert!(matches!(acquire_in(&dir).unwrap(), Acquire::Primary(_)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
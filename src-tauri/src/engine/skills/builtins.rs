@@ -402,6 +402,64 @@ Authentication uses Api-Key and Api-Username headers (not Bearer token).
 Forum URL resolves automatically from credentials."#.into(),
             default_enabled: false,
         },
+        SkillDefinition {
+            id: "social".into(),
+            name: "Social Media".into(),
+            description: "Post, reply and search on Mastodon, Bluesky and X — every post is reviewed in the outbox before it goes out".into(),
+            icon: "campaign".into(),
+            category: SkillCategory::Vault,
+            tier: SkillTier::Integration,
+            required_credentials: vec![
+                CredentialField { key: "MASTODON_INSTANCE_URL".into(), label: "Mastodon Instance".into(), description: "Your Mastodon server (e.g. https://mastodon.social)".into(), required: false, placeholder: "https://mastodon.social".into() },
+                CredentialField { key: "MASTODON_ACCESS_TOKEN".into(), label: "Mastodon Access Token".into(), description: "Preferences → Development → New application (scopes: read, write:statuses, write:media)".into(), required: false, placeholder: "abc123...".into() },
+                CredentialField { key: "BLUESKY_HANDLE".into(), label: "Bluesky Handle".into(), description: "Your Bluesky handle (e.g. alice.bsky.social)".into(), required: false, placeholder: "alice.bsky.social".into() },
+                CredentialField { key: "BLUESKY_APP_PASSWORD".into(), label: "Bluesky App Password".into(), description: "Settings → Privacy and security → App passwords. Never use your main password.".into(), required: false, placeholder: "xxxx-xxxx-xxxx-xxxx".into() },
+                CredentialField { key: "BLUESKY_SERVICE_URL".into(), label: "Bluesky PDS (optional)".into(), description: "Only for self-hosted PDS accounts (default https://bsky.social)".into(), required: false, placeholder: "https://bsky.social".into() },
+                CredentialField { key: "X_ACCESS_TOKEN".into(), label: "X User Access Token".into(), description: "OAuth 2.0 user token with tweet.read, tweet.write, users.read and media.write scopes".into(), required: false, placeholder: "...".into() },
+                CredentialField { key: "X_BEARER_TOKEN".into(), label: "X Bearer Token (search)".into(), description: "App-only bearer token from the X developer portal; enough for search".into(), required: false, placeholder: "AAAA...".into() },
+            ],
+            tool_names: vec!["social_post".into(), "social_reply".into(), "social_search".into()],
+            required_binaries: vec![], required_env_vars: vec![], install_hint: "Add credentials for at least one network: a Mastodon access token, a Bluesky app password, or X API tokens from developer.x.com".into(),
+            agent_instructions: r#"You can post to social media (Mastodon, Bluesky, X) for the user.
+
+- social_post: publish a post to one or more networks (default: every configured network)
+- social_reply: reply to a post, given its URL
+- social_search: search recent posts
+
+Posts and replies are NEVER published directly: they are queued in the outbox and the user approves, edits or rejects them. When a tool says the post was queued, tell the user it is waiting for their review — do not call the tool again.
+
+Rules:
+- Write in the user's voice; never invent facts, quotes or statistics.
+- Respect limits: X 280 characters, Bluesky 300, Mastodon 500. Leave out a network rather than posting a truncated thread.
+- Media must be image files in your workspace (png, jpg, gif, webp). Always give alt text.
+- Use social_search to read context before replying to a conversation.
+- NEVER use exec/curl to call the social network APIs — use these tools."#.into(),
+            default_enabled: false,
+        },
+        SkillDefinition {
+            id: "nostr".into(),
+            name: "Nostr".into(),
+            description: "Publish notes, react and look up profiles on Nostr. No extra credentials needed — uses the key and relays configured in the Nostr channel bridge; notes and reactions are reviewed in the outbox.".into(),
+            icon: "bolt".into(),
+            category: SkillCategory::Vault,
+            tier: SkillTier::Integration,
+            required_credentials: vec![],
+            tool_names: vec!["nostr_publish_note".into(), "nostr_react".into(), "nostr_fetch_profile".into()],
+            required_binaries: vec![], required_env_vars: vec![], install_hint: "Set up a key and relays in Channels → Nostr first".into(),
+            agent_instructions: r#"You can keep a presence on Nostr with the bot's own key.
+
+- nostr_publish_note: publish a public note, or reply to one (give its note1… id)
+- nostr_react: like ("+") or react with an emoji to a note
+- nostr_fetch_profile: read someone's profile, including their Lightning address for zaps
+
+Notes and reactions are NEVER published directly: they are queued in the outbox and the user approves, edits or rejects them. When a tool says it was queued, tell the user it is waiting for their review — do not call the tool again.
+
+Rules:
+- Write in the user's voice; never invent facts, quotes or statistics.
+- Private conversations belong in DMs through the Nostr channel, not public notes.
+- You cannot send zaps; report the Lightning address if the user wants to tip someone."#.into(),
+            default_enabled: false,
+        },
         SkillDefinition {
             id: "coinbase".into(),
             name: "Coinbase (CDP Agentic Wallet)".into(),
@@ -791,7 +849,8 @@ Contacts can be phone numbers or email addresses."#.into(),
             required_env_vars: vec![], install_hint: String::new(),
             agent_instructions: r#"You can get weather data without any special tools.
 Use web_search or fetch with: curl wttr.in/<city>?format=j1 (JSON) or curl wttr.in/<city> (text).
-Or use web_read on weather websites. For JSON: curl 'wttr.in/London?format=j1' gives detailed forecasts."#.into(),
+Or use web_read on weather websites. For JSON: curl 'wttr.in/London?format=j1' gives detailed forecasts.
+If the user doesn't name a place, use their home location from the runtime context."#.into(),
             default_enabled: true,
         },
         SkillDefinition {
@@ -18,4 +18,4 @@ pub(crate) mod types;
 pub use installer::{install_toml_skill, uninstall_toml_skill};
 pub use parser::{manifest_to_definition, parse_category, parse_manifest, validate_manifest};
 pub use scanner::{load_manifest_from_path, scan_toml_skills, skills_dir};
-pub use types::{SkillManifest, TomlSkillEntry};
+pub use types::{ManifestCommand, SkillManifest, TomlSkillEntry};
@@ -148,6 +148,23 @@ pub fn validate_manifest(manifest: &SkillManifest) -> Result<(), String> {
             }
         }
     }
+    // Slash command names: lowercase a-z, 0-9, -, _ (typed as `/name`)
+    for cmd in &manifest.commands {
+        if cmd.name.is_empty()
+            || !cmd
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid command name '{}' (use a-z, 0-9, -, _)",
+                cmd.name
+            ));
+        }
+        if cmd.prompt.trim().is_empty() {
+            return Err(format!("Command '{}' has an empty prompt", cmd.name));
+        }
+    }
     Ok(())
 }
 
@@ -295,6 +312,21 @@ text = "GitHub tools are available via MCP."
         assert!(def.tool_names.is_empty());
     }
 
+    #[test]
+    fn parse_and_validate_commands() {
+        let toml_str = format!(
+            "{}\n[[commands]]\nname = \"standup\"\ndescription = \"Daily standup\"\nprompt = \"Summarise {{{{args}}}}\"\n",
+            MINIMAL_MANIFEST
+        );
+        let manifest = parse_manifest(&toml_str).unwrap();
+        assert_eq!(manifest.commands.len(), 1);
+        assert_eq!(manifest.commands[0].name, "standup");
+        assert!(validate_manifest(&manifest).is_ok());
+
+        let bad = toml_str.replace("\"standup\"", "\"Stand Up\"");
+        assert!(validate_manifest(&parse_manifest(&bad).unwrap()).is_err());
+    }
+
     #[test]
     fn validate_valid_manifest() {
         let manifest = parse_manifest(FULL_MANIFEST).unwrap();
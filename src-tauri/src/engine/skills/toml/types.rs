@@ -18,6 +18,8 @@ pub struct SkillManifest {
     pub widget: Option<ManifestWidget>,
     pub mcp: Option<ManifestMcp>,
     pub view: Option<ManifestView>,
+    #[serde(default)]
+    pub commands: Vec<ManifestCommand>,
 }
 
 /// `[skill]` — required metadata section.
@@ -119,6 +121,17 @@ fn default_view_layout() -> String {
     "widget".to_string()
 }
 
+/// `[[commands]]` — slash command contributed by the skill. `prompt` is
+/// sent to the agent in place of the typed command; `{{args}}` is replaced
+/// with whatever followed the command name.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ManifestCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub prompt: String,
+}
+
 // ── Output Struct ──────────────────────────────────────────────────────────
 
 /// A loaded TOML skill with its source path for management purposes.
@@ -145,4 +158,7 @@ pub struct TomlSkillEntry {
     /// View icon for sidebar tab (if has_view is true).
     #[serde(default)]
     pub view_icon: String,
+    /// Slash commands declared in `[[commands]]`.
+    #[serde(default)]
+    pub commands: Vec<ManifestCommand>,
 }
@@ -0,0 +1,204 @@
+// Paw Agent Engine — Slack Block Kit
+//
+// Agent replies go out as Block Kit sections (with the plain text as the
+// notification fallback), and tool approvals as a message with Approve /
+// Deny buttons. Clicks come back as `block_actions` interactive payloads;
+// the button's `value` carries the tool_call_id and tool name as JSON.
+
+use crate::engine::channels;
+use serde_json::{json, Value};
+
+/// Slack rejects section text over 3000 characters.
+const SECTION_MAX: usize = 2900;
+/// And messages with more than 50 blocks.
+const MAX_BLOCKS: usize = 50;
+/// Tool arguments shown on an approval card.
+const ARGS_PREVIEW_MAX: usize = 1500;
+
+pub(crate) const APPROVE_ACTION: &str = "paw_tool_approve";
+pub(crate) const DENY_ACTION: &str = "paw_tool_deny";
+
+fn section(text: &str) -> Value {
+    json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } })
+}
+
+fn context(text: &str) -> Value {
+    json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": text }] })
+}
+
+/// Render an agent reply: a `---` line becomes a divider, everything else
+/// is chunked into mrkdwn sections.
+pub(crate) fn reply_blocks(text: &str) -> Vec<Value> {
+    fn flush(part: &mut String, blocks: &mut Vec<Value>) {
+        let trimmed = part.trim();
+        if !trimmed.is_empty() {
+            blocks.extend(
+                channels::split_message(trimmed, SECTION_MAX)
+                    .iter()
+                    .map(|chunk| section(chunk)),
+            );
+        }
+        part.clear();
+    }
+
+    let mut blocks = Vec::new();
+    let mut part = String::new();
+    for line in text.lines() {
+        if line.trim() == "---" {
+            flush(&mut part, &mut blocks);
+            blocks.push(json!({ "type": "divider" }));
+        } else {
+            part.push_str(line);
+            part.push('\n');
+        }
+    }
+    flush(&mut part, &mut blocks);
+
+    if blocks.len() > MAX_BLOCKS {
+        blocks.truncate(MAX_BLOCKS - 1);
+        blocks.push(context("_Reply truncated — ask for the rest._"));
+    }
+    blocks
+}
+
+/// Approval card for a tool call waiting in `pending_approvals`.
+pub(crate) fn approval_blocks(
+    tool_call_id: &str,
+    tool_name: &str,
+    arguments: &str,
+    session_id: &str,
+    tier: Option<&str>,
+) -> Vec<Value> {
+    let args = serde_json::from_str::<Value>(arguments)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| arguments.to_string());
+    let args = if args.len() > ARGS_PREVIEW_MAX {
+        format!("{}…", &args[..args.floor_char_boundary(ARGS_PREVIEW_MAX)])
+    } else {
+        args
+    };
+    let value = json!({ "id": tool_call_id, "tool": tool_name }).to_string();
+    let mut meta = format!("Session `{}`", session_id);
+    if let Some(tier) = tier {
+        meta.push_str(&format!(" · tier *{}*", tier));
+    }
+    vec![
+        section(&format!(
+            ":raised_hand: *Approval needed* — the agent wants to run `{}`",
+            tool_name
+        )),
+        section(&format!("```{}```", args)),
+        context(&meta),
+        json!({
+            "type": "actions",
+            "block_id": "paw_approval",
+            "elements": [
+                {
+                    "type": "button",
+                    "action_id": APPROVE_ACTION,
+                    "style": "primary",
+                    "text": { "type": "plain_text", "text": "Approve" },
+                    "value": value
+                },
+                {
+                    "type": "button",
+                    "action_id": DENY_ACTION,
+                    "style": "danger",
+                    "text": { "type": "plain_text", "text": "Deny" },
+                    "value": value
+                }
+            ]
+        }),
+    ]
+}
+
+/// What the approval card is replaced with once it has been answered.
+pub(crate) fn decision_blocks(tool_name: &str, outcome: &str) -> Vec<Value> {
+    vec![section(&format!("`{}` — {}", tool_name, outcome))]
+}
+
+/// An Approve / Deny click from a `block_actions` payload.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ApprovalClick {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub approved: bool,
+    pub user_id: String,
+    pub channel_id: String,
+    pub message_ts: String,
+}
+
+pub(crate) fn parse_approval_click(payload: &Value) -> Option<ApprovalClick> {
+    if payload["type"].as_str() != Some("block_actions") {
+        return None;
+    }
+    let action = payload["actions"].as_array()?.iter().find(|a| {
+        matches!(
+            a["action_id"].as_str(),
+            Some(APPROVE_ACTION) | Some(DENY_ACTION)
+        )
+    })?;
+    let value: Value = serde_json::from_str(action["value"].as_str()?).ok()?;
+    Some(ApprovalClick {
+        tool_call_id: value["id"].as_str()?.to_string(),
+        tool_name: value["tool"].as_str().unwrap_or("tool").to_string(),
+        approved: action["action_id"].as_str() == Some(APPROVE_ACTION),
+        user_id: payload["user"]["id"].as_str()?.to_string(),
+        channel_id: payload["channel"]["id"].as_str().unwrap_or("").to_string(),
+        message_ts: payload["message"]["ts"].as_str().unwrap_or("").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_splits_sections_and_dividers() {
+        let blocks = reply_blocks("*Summary*\nall good\n---\nnext part");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["text"]["text"], "*Summary*\nall good");
+        assert_eq!(blocks[1]["type"], "divider");
+        assert_eq!(blocks[2]["text"]["text"], "next part");
+
+        let long = "word ".repeat(1500);
+        let blocks = reply_blocks(&long);
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks
+            .iter()
+            .all(|b| b["text"]["text"].as_str().unwrap().len() <= SECTION_MAX));
+    }
+
+    #[test]
+    fn parses_approval_clicks() {
+        let blocks = approval_blocks("call_1", "exec", r#"{"cmd":"ls"}"#, "s1", Some("dangerous"));
+        let buttons = blocks[3]["elements"].as_array().unwrap();
+        let value = buttons[1]["value"].clone();
+
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": "U1" },
+            "channel": { "id": "C1" },
+            "message": { "ts": "123.456" },
+            "actions": [{ "action_id": DENY_ACTION, "value": value }]
+        });
+        assert_eq!(
+            parse_approval_click(&payload),
+            Some(ApprovalClick {
+                tool_call_id: "call_1".into(),
+                tool_name: "exec".into(),
+                approved: false,
+                user_id: "U1".into(),
+                channel_id: "C1".into(),
+                message_ts: "123.456".into(),
+            })
+        );
+        let other = json!({
+            "type": "block_actions",
+            "user": { "id": "U1" },
+            "actions": [{ "action_id": "something_else", "value": "x" }]
+        });
+        assert_eq!(parse_approval_click(&other), None);
+    }
+}
@@ -0,0 +1,79 @@
+// Paw Agent Engine — Slack file uploads
+//
+// Files shared with the bot (`files` on a message event) are downloaded with
+// the bot token (needs the `files:read` scope) and handed to the shared
+// channel ingestion (`channels::attachments`).
+
+use crate::atoms::error::EngineResult;
+use crate::engine::channels::attachments::{self, MAX_FILES, MAX_INBOUND_BYTES};
+use log::warn;
+use serde_json::Value;
+
+/// Download and ingest the files on a message event. Returns one note per
+/// file for the agent's message; failures become notes too, so the agent
+/// can tell the user what didn't come through.
+pub(crate) async fn ingest_message_files(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    bot_token: &str,
+    files: &[Value],
+    agent_id: &str,
+) -> Vec<String> {
+    let mut notes = Vec::new();
+    for file in files.iter().take(MAX_FILES) {
+        let name = file["name"].as_str().unwrap_or("file");
+        let ingested = match download(client, bot_token, file).await {
+            Ok(bytes) => attachments::ingest_bytes(app_handle, agent_id, name, &bytes).await,
+            Err(e) => Err(e),
+        };
+        match ingested {
+            Ok(note) => notes.push(note),
+            Err(e) => {
+                warn!("[slack] Could not ingest {}: {}", name, e);
+                notes.push(format!("[Shared file {} could not be read: {}]", name, e));
+            }
+        }
+    }
+    if files.len() > MAX_FILES {
+        notes.push(format!(
+            "[{} more files were shared but not read — at most {} per message]",
+            files.len() - MAX_FILES,
+            MAX_FILES
+        ));
+    }
+    notes
+}
+
+async fn download(
+    client: &reqwest::Client,
+    bot_token: &str,
+    file: &Value,
+) -> EngineResult<Vec<u8>> {
+    let url = file["url_private_download"]
+        .as_str()
+        .or_else(|| file["url_private"].as_str())
+        .ok_or("no download URL (is it an external file?)")?;
+    if file["size"].as_u64().unwrap_or(0) > MAX_INBOUND_BYTES {
+        return Err(format!("larger than {} MB", MAX_INBOUND_BYTES / 1024 / 1024).into());
+    }
+
+    let resp = client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", bot_token))
+        .send()
+        .await?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("download failed with HTTP {}", status).into());
+    }
+    // Without `files:read` Slack answers 200 with its HTML login page
+    let is_html = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/html"));
+    if is_html && file["mimetype"].as_str() != Some("text/html") {
+        return Err("Slack returned a login page — add the files:read scope to the app".into());
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
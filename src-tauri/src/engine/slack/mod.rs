@@ -0,0 +1,850 @@
+// Paw Agent Engine — Slack Bridge (Socket Mode)
+//
+// Connects Paw to Slack via Socket Mode — outbound WebSocket, no public URL.
+// The bot opens a WebSocket to Slack's servers and receives events push-style.
+// Replies are sent via the Slack Web API (chat.postMessage) as Block Kit,
+// in a thread under the message that mentioned the bot.
+//
+// Socket Mode also delivers:
+//   - slash commands (declared in the Slack app config) — `/paw <text>`
+//     talks to the agent, any other command is passed through to the
+//     engine's slash-command registry; answered via the `response_url`
+//   - interactive payloads — Approve / Deny clicks on tool approval cards
+//     (see `blocks`), resolved like `engine_approve_tool`
+//
+// Shared files are downloaded and ingested (see `files`).
+//
+// Setup: api.slack.com → Create App → Enable Socket Mode → Bot Token + App Token.
+// Enable Interactivity for approval buttons, add slash commands as needed,
+// and the `files:read` scope for uploads.
+//
+// Security:
+//   - Allowlist by Slack user ID
+//   - Optional pairing mode
+//   - All communication through Slack's TLS API
+//   - Approval clicks only count from allowlisted users
+
+mod blocks;
+mod files;
+
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::channels::{self, ChannelStatus, PendingUser};
+use crate::engine::state::EngineState;
+use futures::{SinkExt, StreamExt};
+use log::{debug, error, info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock};
+use tauri::{Emitter, Listener, Manager};
+use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+
+// ── Slack Config ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// Bot User OAuth Token (xoxb-...)
+    pub bot_token: String,
+    /// App-Level Token (xapp-...) — needed for Socket Mode
+    pub app_token: String,
+    pub enabled: bool,
+    /// "open" | "allowlist" | "pairing"
+    pub dm_policy: String,
+    pub allowed_users: Vec<String>,
+    #[serde(default)]
+    pub pending_users: Vec<PendingUser>,
+    pub agent_id: Option<String>,
+    /// Whether to respond when @mentioned in channels
+    #[serde(default = "default_true")]
+    pub respond_to_mentions: bool,
+    /// Phase C: allow dangerous/side-effect tools for messages from this channel
+    #[serde(default)]
+    pub allow_dangerous_tools: bool,
+    /// Answer channel mentions in a thread under the message
+    #[serde(default = "default_true")]
+    pub reply_in_threads: bool,
+    /// Channel ID that desktop tool approvals are posted to, with Approve /
+    /// Deny buttons. Only `allowed_users` can click them.
+    #[serde(default)]
+    pub approvals_channel: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        SlackConfig {
+            bot_token: String::new(),
+            app_token: String::new(),
+            enabled: false,
+            dm_policy: "pairing".into(),
+            allowed_users: vec![],
+            pending_users: vec![],
+            agent_id: None,
+            respond_to_mentions: true,
+            allow_dangerous_tools: false,
+            reply_in_threads: true,
+            approvals_channel: None,
+        }
+    }
+}
+
+// ── Global State ───────────────────────────────────────────────────────
+
+static BRIDGE_RUNNING: AtomicBool = AtomicBool::new(false);
+static MESSAGE_COUNT: AtomicI64 = AtomicI64::new(0);
+static BOT_USER_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+static STOP_SIGNAL: std::sync::OnceLock<Arc<AtomicBool>> = std::sync::OnceLock::new();
+
+fn get_stop_signal() -> Arc<AtomicBool> {
+    STOP_SIGNAL
+        .get_or_init(|| Arc::new(AtomicBool::new(false)))
+        .clone()
+}
+
+/// One agent turn at a time per Slack user — turns run off the socket loop
+/// so interactive clicks aren't stuck behind a long reply.
+static USER_TURNS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const CONFIG_KEY: &str = "slack_config";
+const CHANNEL_CONTEXT: &str = "You are chatting via Slack. Use Slack-flavored markdown: \
+                               *bold*, _italic_, `code`, ```code blocks```, ~strikethrough~. \
+                               A line with only --- becomes a divider. \
+                               Keep responses concise and workplace-appropriate.";
+
+// ── Bridge Core ────────────────────────────────────────────────────────
+
+pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
+    if BRIDGE_RUNNING.load(Ordering::Relaxed) {
+        return Err("Slack bridge is already running".into());
+    }
+
+    let config: SlackConfig = channels::load_channel_config(&app_handle, CONFIG_KEY)?;
+    if config.bot_token.is_empty() || config.app_token.is_empty() {
+        return Err("Bot token and App token are both required for Socket Mode.".into());
+    }
+    if !config.enabled {
+        return Err("Slack bridge is disabled.".into());
+    }
+
+    let stop = get_stop_signal();
+    stop.store(false, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(true, Ordering::Relaxed);
+
+    info!("[slack] Starting Socket Mode bridge");
+
+    tauri::async_runtime::spawn(async move {
+        let mut reconnect_attempt: u32 = 0;
+        loop {
+            match run_socket_mode(app_handle.clone(), config.clone()).await {
+                Ok(()) => break, // Clean shutdown
+                Err(e) => {
+                    if get_stop_signal().load(Ordering::Relaxed) {
+                        break;
+                    }
+                    error!("[slack] Bridge error: {} — reconnecting", e);
+                    let delay = channels::reconnect_backoff(
+                        &app_handle,
+                        "slack",
+                        reconnect_attempt,
+                        &e.to_string(),
+                    )
+                    .await;
+                    warn!(
+                        "[slack] Reconnecting in {}ms (attempt {})",
+                        delay.as_millis(),
+                        reconnect_attempt + 1
+                    );
+                    reconnect_attempt += 1;
+                    if get_stop_signal().load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            }
+        }
+        BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "slack", None);
+        info!("[slack] Bridge stopped");
+    });
+
+    Ok(())
+}
+
+pub fn stop_bridge() {
+    let stop = get_stop_signal();
+    stop.store(true, Ordering::Relaxed);
+    BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+    info!("[slack] Stop signal sent");
+}
+
+pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
+    let config: SlackConfig =
+        channels::load_channel_config(app_handle, CONFIG_KEY).unwrap_or_default();
+    ChannelStatus {
+        running: BRIDGE_RUNNING.load(Ordering::Relaxed),
+        connected: BRIDGE_RUNNING.load(Ordering::Relaxed),
+        bot_name: Some("Slack Bot".into()),
+        bot_id: BOT_USER_ID.get().cloned(),
+        message_count: MESSAGE_COUNT.load(Ordering::Relaxed) as u64,
+        allowed_users: config.allowed_users,
+        pending_users: config.pending_users,
+        dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("slack"),
+    }
+}
+
+// ── Slack Socket Mode ──────────────────────────────────────────────────
+
+async fn run_socket_mode(app_handle: tauri::AppHandle, config: SlackConfig) -> EngineResult<()> {
+    let stop = get_stop_signal();
+    let http_client = reqwest::Client::new();
+
+    // Get bot user ID via auth.test
+    let auth_resp = http_client
+        .post("https://slack.com/api/auth.test")
+        .header("Authorization", format!("Bearer {}", config.bot_token))
+        .send()
+        .await?;
+    let auth_json: serde_json::Value = auth_resp.json().await?;
+    if !auth_json["ok"].as_bool().unwrap_or(false) {
+        return Err(format!(
+            "auth.test error: {}",
+            auth_json["error"].as_str().unwrap_or("unknown")
+        )
+        .into());
+    }
+    let bot_user_id = auth_json["user_id"].as_str().unwrap_or("").to_string();
+    let _ = BOT_USER_ID.set(bot_user_id.clone());
+    info!("[slack] Authenticated as user_id={}", bot_user_id);
+
+    // Open a Socket Mode connection
+    let ws_url = get_socket_mode_url(&http_client, &config.app_token).await?;
+
+    let (ws_stream, _) = connect_async(&ws_url)
+        .await
+        .map_err(|e| EngineError::Channel {
+            channel: "slack".into(),
+            message: e.to_string(),
+        })?;
+
+    let (mut write, mut read) = ws_stream.split();
+
+    channels::mark_connected(&app_handle, "slack");
+    let _ = app_handle.emit(
+        "slack-status",
+        json!({
+            "kind": "connected",
+            "bot_id": &bot_user_id,
+        }),
+    );
+
+    info!("[slack] Socket Mode connected");
+
+    // Mirror desktop tool approvals into the approvals channel while connected
+    let approvals_listener = {
+        let app = app_handle.clone();
+        let client = http_client.clone();
+        app_handle.listen("engine-event", move |event| {
+            post_approval_card(&app, &client, event.payload());
+        })
+    };
+
+    let mut current_config = config.clone();
+    let mut last_config_reload = std::time::Instant::now();
+
+    while let Some(msg_result) = read.next().await {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let msg = match msg_result {
+            Ok(m) => m,
+            Err(e) => {
+                warn!("[slack] WS read error: {}", e);
+                break;
+            }
+        };
+
+        let text = match msg {
+            WsMessage::Text(t) => t,
+            WsMessage::Close(_) => {
+                info!("[slack] WS closed");
+                break;
+            }
+            WsMessage::Ping(data) => {
+                let _ = write.send(WsMessage::Pong(data)).await;
+                continue;
+            }
+            _ => continue,
+        };
+
+        let envelope: serde_json::Value = match serde_json::from_str(&text) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        let envelope_id = envelope["envelope_id"].as_str().unwrap_or("");
+
+        // Acknowledge every envelope immediately (Slack requires this within 3 seconds)
+        if !envelope_id.is_empty() {
+            let ack = json!({ "envelope_id": envelope_id });
+            let _ = write.send(WsMessage::Text(ack.to_string().into())).await;
+        }
+
+        match envelope["type"].as_str().unwrap_or("") {
+            "events_api" => {
+                handle_message_event(
+                    &app_handle,
+                    &http_client,
+                    &mut current_config,
+                    &bot_user_id,
+                    &envelope["payload"]["event"],
+                )
+                .await;
+            }
+            "slash_commands" => {
+                handle_slash_command(
+                    &app_handle,
+                    &http_client,
+                    &mut current_config,
+                    &envelope["payload"],
+                )
+                .await;
+            }
+            "interactive" => {
+                handle_interaction(
+                    &app_handle,
+                    &http_client,
+                    &current_config,
+                    &envelope["payload"],
+                )
+                .await;
+            }
+            "disconnect" => {
+                info!(
+                    "[slack] Disconnect event received, reason: {}",
+                    envelope["reason"].as_str().unwrap_or("?")
+                );
+                break;
+            }
+            _ => {}
+        }
+
+        // Reload config
+        if last_config_reload.elapsed() > std::time::Duration::from_secs(30) {
+            if let Ok(fresh) = channels::load_channel_config::<SlackConfig>(&app_handle, CONFIG_KEY)
+            {
+                current_config = fresh;
+            }
+            last_config_reload = std::time::Instant::now();
+        }
+    }
+
+    app_handle.unlisten(approvals_listener);
+    let _ = app_handle.emit(
+        "slack-status",
+        json!({
+            "kind": "disconnected",
+        }),
+    );
+
+    Ok(())
+}
+
+// ── Events ─────────────────────────────────────────────────────────────
+
+/// DMs, @mentions and file shares.
+async fn handle_message_event(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    config: &mut SlackConfig,
+    bot_user_id: &str,
+    event: &Value,
+) {
+    let inner_type = event["type"].as_str().unwrap_or("");
+    if inner_type != "message" && inner_type != "app_mention" {
+        return;
+    }
+    // Skip bot messages and subtypes (edits, joins, etc.) — except file shares
+    if event["bot_id"].is_string() {
+        return;
+    }
+    if event["subtype"].is_string() && event["subtype"].as_str() != Some("file_share") {
+        return;
+    }
+
+    let user_id = event["user"].as_str().unwrap_or("").to_string();
+    let text_content = event["text"].as_str().unwrap_or("");
+    let channel_id = event["channel"].as_str().unwrap_or("").to_string();
+    let channel_type = event["channel_type"].as_str().unwrap_or("");
+    let shared_files = event["files"].as_array().cloned().unwrap_or_default();
+
+    if user_id.is_empty() || user_id == bot_user_id {
+        return;
+    }
+
+    let is_dm = channel_type == "im";
+    let is_mention = inner_type == "app_mention";
+
+    // In channels, only respond to mentions
+    if !is_dm && !is_mention {
+        return;
+    }
+    if !is_dm && !config.respond_to_mentions {
+        return;
+    }
+
+    // Strip bot mention from text
+    let content = text_content
+        .replace(&format!("<@{}>", bot_user_id), "")
+        .trim()
+        .to_string();
+    if content.is_empty() && shared_files.is_empty() {
+        return;
+    }
+
+    debug!(
+        "[slack] Message from {} in {}: {}",
+        user_id,
+        channel_id,
+        if content.len() > 50 {
+            format!("{}...", &content[..content.floor_char_boundary(50)])
+        } else {
+            content.clone()
+        }
+    );
+
+    // Stay in the thread the user wrote in; start one under channel mentions
+    let thread_ts = event["thread_ts"]
+        .as_str()
+        .or_else(|| {
+            event["ts"]
+                .as_str()
+                .filter(|_| !is_dm && config.reply_in_threads)
+        })
+        .map(str::to_string);
+    let reply_to = ReplyTo::Channel {
+        channel_id,
+        thread_ts,
+    };
+
+    // Access control (DMs)
+    if is_dm {
+        if let Err(denial) = check_user(app_handle, config, &user_id) {
+            let _ = reply_to.send(client, &config.bot_token, &denial).await;
+            return;
+        }
+    }
+
+    MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+    spawn_turn(
+        app_handle,
+        client,
+        config,
+        AgentTurn {
+            user_id,
+            content,
+            shared_files,
+            reply_to,
+        },
+    );
+}
+
+/// `/paw <text>` goes to the agent as a message; any other command the app
+/// declares is handed to the agent verbatim, so the engine's slash-command
+/// registry (`/remember`, `/model`, …) answers it.
+async fn handle_slash_command(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    config: &mut SlackConfig,
+    payload: &Value,
+) {
+    let command = payload["command"].as_str().unwrap_or("");
+    let text = payload["text"].as_str().unwrap_or("").trim();
+    let user_id = payload["user_id"].as_str().unwrap_or("").to_string();
+    let Some(response_url) = payload["response_url"].as_str().filter(|u| !u.is_empty()) else {
+        return;
+    };
+    let reply_to = ReplyTo::ResponseUrl(response_url.to_string());
+    if user_id.is_empty() {
+        return;
+    }
+    info!("[slack] Slash command {} from {}", command, user_id);
+
+    let content = if command == "/paw" {
+        text.to_string()
+    } else {
+        format!("{} {}", command, text).trim().to_string()
+    };
+    if content.is_empty() {
+        let _ = reply_to
+            .send_ephemeral(client, "Usage: `/paw <message>`")
+            .await;
+        return;
+    }
+
+    if let Err(denial) = check_user(app_handle, config, &user_id) {
+        let _ = reply_to.send_ephemeral(client, &denial).await;
+        return;
+    }
+
+    MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+    spawn_turn(
+        app_handle,
+        client,
+        config,
+        AgentTurn {
+            user_id,
+            content,
+            shared_files: vec![],
+            reply_to,
+        },
+    );
+}
+
+/// Approve / Deny clicks on approval cards.
+async fn handle_interaction(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    config: &SlackConfig,
+    payload: &Value,
+) {
+    let Some(click) = blocks::parse_approval_click(payload) else {
+        return;
+    };
+    if !config.allowed_users.contains(&click.user_id) {
+        warn!(
+            "[slack] Ignoring approval click from non-allowlisted user {}",
+            click.user_id
+        );
+        let _ = slack_api(
+            client,
+            &config.bot_token,
+            "chat.postEphemeral",
+            &json!({
+                "channel": click.channel_id,
+                "user": click.user_id,
+                "text": "Only allowlisted users can answer tool approvals.",
+            }),
+        )
+        .await;
+        return;
+    }
+
+    let resolved = app_handle
+        .try_state::<EngineState>()
+        .is_some_and(|state| state.resolve_tool_approval(&click.tool_call_id, click.approved));
+    let outcome = match (resolved, click.approved) {
+        (true, true) => format!(":white_check_mark: approved by <@{}>", click.user_id),
+        (true, false) => format!(":no_entry: denied by <@{}>", click.user_id),
+        (false, _) => "already answered or timed out".to_string(),
+    };
+    if resolved {
+        let _ = app_handle.emit(
+            "slack-status",
+            json!({
+                "kind": "tool_approval",
+                "tool_call_id": &click.tool_call_id,
+                "approved": click.approved,
+                "user_id": &click.user_id,
+            }),
+        );
+    }
+    let _ = slack_api(
+        client,
+        &config.bot_token,
+        "chat.update",
+        &json!({
+            "channel": click.channel_id,
+            "ts": click.message_ts,
+            "text": format!("{} — {}", click.tool_name, outcome),
+            "blocks": blocks::decision_blocks(&click.tool_name, &outcome),
+        }),
+    )
+    .await;
+}
+
+/// `engine-event` listener: post an approval card for each desktop tool
+/// request. Channel sessions (`eng-…`) have their own approval policy.
+fn post_approval_card(app_handle: &tauri::AppHandle, client: &reqwest::Client, payload: &str) {
+    // engine-event fires for every streamed delta — filter before parsing
+    if !payload.contains("\"tool_request\"") {
+        return;
+    }
+    let Ok(event) = serde_json::from_str::<Value>(payload) else {
+        return;
+    };
+    let session_id = event["session_id"].as_str().unwrap_or("");
+    if event["kind"].as_str() != Some("tool_request") || session_id.starts_with("eng-") {
+        return;
+    }
+    let Ok(config) = load_config(app_handle) else {
+        return;
+    };
+    let Some(channel) = config.approvals_channel.filter(|c| !c.is_empty()) else {
+        return;
+    };
+    let tool_call = &event["tool_call"];
+    let (Some(id), Some(name)) = (
+        tool_call["id"].as_str(),
+        tool_call["function"]["name"].as_str(),
+    ) else {
+        return;
+    };
+    let body = json!({
+        "channel": channel,
+        "text": format!("Approval needed: {}", name),
+        "blocks": blocks::approval_blocks(
+            id,
+            name,
+            tool_call["function"]["arguments"].as_str().unwrap_or("{}"),
+            session_id,
+            event["tool_tier"].as_str(),
+        ),
+    });
+    let client = client.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = slack_api(&client, &config.bot_token, "chat.postMessage", &body).await;
+    });
+}
+
+/// DM-policy check shared by messages and slash commands. On denial the
+/// pending-user list is saved and the text to send back is returned.
+fn check_user(
+    app_handle: &tauri::AppHandle,
+    config: &mut SlackConfig,
+    user_id: &str,
+) -> Result<(), String> {
+    channels::check_access(
+        &config.dm_policy,
+        user_id,
+        user_id,
+        user_id,
+        &config.allowed_users,
+        &mut config.pending_users,
+    )
+    .map_err(|denial| {
+        let _ = channels::save_channel_config(app_handle, CONFIG_KEY, config);
+        let _ = app_handle.emit(
+            "slack-status",
+            json!({
+                "kind": "pairing_request",
+                "user_id": user_id,
+            }),
+        );
+        channels::notify_pairing(app_handle, "Slack", user_id);
+        denial.to_string()
+    })
+}
+
+// ── Agent Turns ────────────────────────────────────────────────────────
+
+/// Where a reply goes: a channel (optionally a thread in it), or a slash
+/// command's `response_url`.
+enum ReplyTo {
+    Channel {
+        channel_id: String,
+        thread_ts: Option<String>,
+    },
+    ResponseUrl(String),
+}
+
+impl ReplyTo {
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        bot_token: &str,
+        text: &str,
+    ) -> EngineResult<()> {
+        match self {
+            ReplyTo::Channel {
+                channel_id,
+                thread_ts,
+            } => {
+                let mut body = json!({
+                    "channel": channel_id,
+                    "text": text,
+                    "blocks": blocks::reply_blocks(text),
+                });
+                if let Some(ts) = thread_ts {
+                    body["thread_ts"] = json!(ts);
+                }
+                slack_api(client, bot_token, "chat.postMessage", &body)
+                    .await
+                    .map(|_| ())
+            }
+            ReplyTo::ResponseUrl(url) => respond(client, url, text, "in_channel").await,
+        }
+    }
+
+    /// Only the invoking user sees it (slash commands); a normal reply otherwise.
+    async fn send_ephemeral(&self, client: &reqwest::Client, text: &str) -> EngineResult<()> {
+        match self {
+            ReplyTo::ResponseUrl(url) => respond(client, url, text, "ephemeral").await,
+            ReplyTo::Channel { .. } => Ok(()),
+        }
+    }
+}
+
+struct AgentTurn {
+    user_id: String,
+    content: String,
+    shared_files: Vec<Value>,
+    reply_to: ReplyTo,
+}
+
+/// Run the agent off the socket loop, one turn at a time per user.
+fn spawn_turn(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    config: &SlackConfig,
+    turn: AgentTurn,
+) {
+    let app_handle = app_handle.clone();
+    let client = client.clone();
+    let bot_token = config.bot_token.clone();
+    let agent_id = config.agent_id.clone().unwrap_or_else(|| "default".into());
+    let allow_dangerous_tools = config.allow_dangerous_tools;
+
+    tauri::async_runtime::spawn(async move {
+        let user_lock = USER_TURNS
+            .lock()
+            .entry(turn.user_id.clone())
+            .or_default()
+            .clone();
+        let _running = user_lock.lock().await;
+
+        let mut content = turn.content;
+        if !turn.shared_files.is_empty() {
+            let notes = files::ingest_message_files(
+                &app_handle,
+                &client,
+                &bot_token,
+                &turn.shared_files,
+                &agent_id,
+            )
+            .await;
+            content = channels::attachments::with_file_notes(&content, &notes);
+        }
+
+        let reply = match channels::run_channel_agent(
+            &app_handle,
+            "slack",
+            CHANNEL_CONTEXT,
+            &content,
+            &turn.user_id,
+            &agent_id,
+            allow_dangerous_tools,
+        )
+        .await
+        {
+            Ok(reply) if reply.is_empty() => return,
+            Ok(reply) => reply,
+            Err(e) => {
+                error!("[slack] Agent error for {}: {}", turn.user_id, e);
+                format!(":warning: Error: {}", e)
+            }
+        };
+        let _ = turn.reply_to.send(&client, &bot_token, &reply).await;
+    });
+}
+
+// ── Slack API Helpers ──────────────────────────────────────────────────
+
+async fn get_socket_mode_url(client: &reqwest::Client, app_token: &str) -> EngineResult<String> {
+    let resp = client
+        .post("https://slack.com/api/apps.connections.open")
+        .header("Authorization", format!("Bearer {}", app_token))
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .await?;
+
+    let body: serde_json::Value = resp.json().await?;
+
+    if !body["ok"].as_bool().unwrap_or(false) {
+        return Err(format!(
+            "connections.open error: {}",
+            body["error"].as_str().unwrap_or("unknown")
+        )
+        .into());
+    }
+
+    body["url"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or("No URL returned from connections.open".into())
+}
+
+/// Call a Web API method with a JSON body. Slack answers 200 with
+/// `ok: false` on failure; that's logged and returned as an error.
+async fn slack_api(
+    client: &reqwest::Client,
+    bot_token: &str,
+    method: &str,
+    body: &Value,
+) -> EngineResult<Value> {
+    let resp = client
+        .post(format!("https://slack.com/api/{}", method))
+        .header("Authorization", format!("Bearer {}", bot_token))
+        .json(body)
+        .send()
+        .await
+        .inspect_err(|e| warn!("[slack] {} failed: {}", method, e))?;
+    let body: Value = resp.json().await.unwrap_or_default();
+    if !body["ok"].as_bool().unwrap_or(false) {
+        let err = body["error"].as_str().unwrap_or("unknown");
+        warn!("[slack] {} error: {}", method, err);
+        return Err(format!("{} error: {}", method, err).into());
+    }
+    Ok(body)
+}
+
+/// Answer a slash command through its `response_url` (valid for 30 minutes).
+async fn respond(
+    client: &reqwest::Client,
+    response_url: &str,
+    text: &str,
+    response_type: &str,
+) -> EngineResult<()> {
+    let resp = client
+        .post(response_url)
+        .json(&json!({
+            "response_type": response_type,
+            "text": text,
+            "blocks": blocks::reply_blocks(text),
+        }))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        warn!("[slack] response_url answered HTTP {}", resp.status());
+        return Err(format!("response_url answered HTTP {}", resp.status()).into());
+    }
+    Ok(())
+}
+
+// ── Config Persistence ─────────────────────────────────────────────────
+
+pub fn load_config(app_handle: &tauri::AppHandle) -> EngineResult<SlackConfig> {
+    channels::load_channel_config(app_handle, CONFIG_KEY)
+}
+
+pub fn save_config(app_handle: &tauri::AppHandle, config: &SlackConfig) -> EngineResult<()> {
+    channels::save_channel_config(app_handle, CONFIG_KEY, config)
+}
+
+pub fn approve_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::approve_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn deny_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::deny_user_generic(app_handle, CONFIG_KEY, user_id)
+}
+
+pub fn remove_user(app_handle: &tauri::AppHandle, user_id: &str) -> EngineResult<()> {
+    channels::remove_user_generic(app_handle, CONFIG_KEY, user_id)
+}
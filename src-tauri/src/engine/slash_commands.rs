@@ -0,0 +1,311 @@
+// Paw Agent Engine — Slash Command Registry
+//
+// `/name args` messages are handled here before the agent loop, by the
+// desktop chat (engine_chat_send) and by every channel bridge and the
+// webchat (channels::run_channel_agent), so they behave the same everywhere.
+//
+// Built-in commands answer directly without a model call. Enabled TOML
+// skills can add their own through `[[commands]]` in `pawz-skill.toml`;
+// those expand into a prompt that is sent to the agent instead of the
+// typed command. Unknown `/words` are left alone (a message may simply
+// start with a path).
+
+use crate::engine::state::EngineState;
+use log::info;
+use serde::Serialize;
+use tauri::Manager;
+
+/// What to do with a message that matched a registered command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlashOutcome {
+    /// Answer the user with this text; the agent loop is skipped.
+    Reply(String),
+    /// Run the agent loop with this message instead of the typed one.
+    Prompt(String),
+}
+
+/// A command as shown by `/help` and the command palette.
+#[derive(Debug, Clone, Serialize)]
+pub struct SlashCommandInfo {
+    pub name: String,
+    pub usage: String,
+    pub description: String,
+    /// "builtin" or the id of the skill that registered it.
+    pub source: String,
+}
+
+struct Builtin {
+    name: &'static str,
+    usage: &'static str,
+    description: &'static str,
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "help",
+        usage: "/help",
+        description: "List available slash commands",
+    },
+    Builtin {
+        name: "remember",
+        usage: "/remember <text>",
+        description: "Store a memory for this agent",
+    },
+    Builtin {
+        name: "search",
+        usage: "/search <query>",
+        description: "Search this agent's memories",
+    },
+    Builtin {
+        name: "task",
+        usage: "/task <title> [-- description]",
+        description: "Add a task to the board for this agent",
+    },
+    Builtin {
+        name: "model",
+        usage: "/model [name|default]",
+        description: "Show or pin the model used in this conversation",
+    },
+    Builtin {
+        name: "clear",
+        usage: "/clear",
+        description: "Clear this conversation's history",
+    },
+];
+
+/// Split `/name args` into a lowercase name and trimmed args. Returns None
+/// for anything that isn't shaped like a command (e.g. `/usr/bin/env`).
+pub fn parse(message: &str) -> Option<(String, &str)> {
+    let rest = message.trim_start().strip_prefix('/')?;
+    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let name = &rest[..end];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    Some((name.to_ascii_lowercase(), rest[end..].trim()))
+}
+
+/// Slash commands from enabled TOML skills, with their prompt templates.
+/// Names already taken by a built-in (or an earlier skill) are skipped.
+fn skill_commands(state: &EngineState) -> Vec<(SlashCommandInfo, String)> {
+    let mut out: Vec<(SlashCommandInfo, String)> = Vec::new();
+    for entry in crate::engine::skills::toml::scan_toml_skills() {
+        if entry.commands.is_empty()
+            || !state
+                .store
+                .is_skill_enabled(&entry.definition.id)
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        for cmd in entry.commands {
+            if BUILTINS.iter().any(|b| b.name == cmd.name)
+                || out.iter().any(|(info, _)| info.name == cmd.name)
+            {
+                log::warn!(
+                    "[slash] Skill '{}' command /{} is already registered — skipped",
+                    entry.definition.id,
+                    cmd.name
+                );
+                continue;
+            }
+            out.push((
+                SlashCommandInfo {
+                    usage: format!("/{} [args]", cmd.name),
+                    name: cmd.name,
+                    description: cmd.description,
+                    source: entry.definition.id.clone(),
+                },
+                cmd.prompt,
+            ));
+        }
+    }
+    out
+}
+
+/// Every registered command: built-ins first, then skill commands.
+pub fn list(state: &EngineState) -> Vec<SlashCommandInfo> {
+    BUILTINS
+        .iter()
+        .map(|b| SlashCommandInfo {
+            name: b.name.into(),
+            usage: b.usage.into(),
+            description: b.description.into(),
+            source: "builtin".into(),
+        })
+        .chain(skill_commands(state).into_iter().map(|(info, _)| info))
+        .collect()
+}
+
+/// Fill a skill command's prompt. Without a `{{args}}` placeholder the
+/// arguments are appended on a new line.
+pub fn expand_prompt(template: &str, args: &str) -> String {
+    if template.contains("{{args}}") {
+        template.replace("{{args}}", args)
+    } else if args.is_empty() {
+        template.to_string()
+    } else {
+        format!("{}\n\n{}", template, args)
+    }
+}
+
+/// Handle `message` if it is a registered slash command. Returns None when
+/// the message should go to the agent unchanged.
+pub async fn dispatch(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    agent_id: &str,
+    message: &str,
+) -> Option<SlashOutcome> {
+    let (name, args) = parse(message)?;
+    let state = app_handle.try_state::<EngineState>()?;
+
+    if !BUILTINS.iter().any(|b| b.name == name) {
+        let (info, template) = skill_commands(&state)
+            .into_iter()
+            .find(|(info, _)| info.name == name)?;
+        info!(
+            "[slash] /{} (skill {}) in session {}",
+            name, info.source, session_id
+        );
+        return Some(SlashOutcome::Prompt(expand_prompt(&template, args)));
+    }
+
+    info!("[slash] /{} in session {}", name, session_id);
+    let reply = match run_builtin(app_handle, &state, &name, args, session_id, agent_id).await {
+        Ok(text) => text,
+        Err(e) => format!("/{}: {}", name, e),
+    };
+    Some(SlashOutcome::Reply(reply))
+}
+
+async fn run_builtin(
+    app_handle: &tauri::AppHandle,
+    state: &EngineState,
+    name: &str,
+    args: &str,
+    session_id: &str,
+    agent_id: &str,
+) -> Result<String, String> {
+    let usage = || {
+        let b = BUILTINS.iter().find(|b| b.name == name);
+        format!("usage: {}", b.map(|b| b.usage).unwrap_or_default())
+    };
+    match name {
+        "help" => Ok(list(state)
+            .iter()
+            .map(|c| format!("{} — {}", c.usage, c.description))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        "remember" => {
+            if args.is_empty() {
+                return Err(usage());
+            }
+            crate::engine::tools::memory::execute(
+                "memory_store",
+                &serde_json::json!({ "content": args }),
+                app_handle,
+                agent_id,
+            )
+            .await
+            .unwrap_or_else(|| Err("memory tools unavailable".into()))
+        }
+        "search" => {
+            if args.is_empty() {
+                return Err(usage());
+            }
+            crate::engine::tools::memory::execute(
+                "memory_search",
+                &serde_json::json!({ "query": args, "limit": 5 }),
+                app_handle,
+                agent_id,
+            )
+            .await
+            .unwrap_or_else(|| Err("memory tools unavailable".into()))
+        }
+        "task" => {
+            let (title, description) = match args.split_once(" -- ") {
+                Some((t, d)) => (t.trim(), d.trim()),
+                None => (args, args),
+            };
+            if title.is_empty() {
+                return Err(usage());
+            }
+            crate::engine::tools::tasks::execute(
+                "create_task",
+                &serde_json::json!({
+                    "title": title,
+                    "description": description,
+                    "agent_id": agent_id,
+                }),
+                app_handle,
+                agent_id,
+            )
+            .await
+            .unwrap_or_else(|| Err("task tools unavailable".into()))
+        }
+        "model" => {
+            if args.is_empty() {
+                let current = state.store.get_session_model(session_id)?;
+                return Ok(match current {
+                    Some(m) => format!("This conversation is pinned to {}.", m),
+                    None => "This conversation uses the default model.".into(),
+                });
+            }
+            if args.eq_ignore_ascii_case("default") {
+                state.store.set_session_model(session_id, None)?;
+                return Ok("Model pin cleared — back to the default model.".into());
+            }
+            let model = crate::engine::state::normalize_model_name(args).to_string();
+            let known = {
+                let cfg = state.config.lock();
+                crate::engine::state::resolve_provider_for_model(&model, &cfg.providers).is_some()
+            };
+            if !known {
+                return Err(format!("no configured provider serves '{}'", model));
+            }
+            state.store.set_session_model(session_id, Some(&model))?;
+            Ok(format!("This conversation now uses {}.", model))
+        }
+        "clear" => {
+            state.store.clear_messages(session_id)?;
+            Ok("Conversation cleared.".into())
+        }
+        _ => Err("unknown command".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse("/model gpt-4o"),
+            Some(("model".to_string(), "gpt-4o"))
+        );
+        assert_eq!(
+            parse("  /Remember  buy milk "),
+            Some(("remember".to_string(), "buy milk"))
+        );
+        assert_eq!(parse("/help"), Some(("help".to_string(), "")));
+        assert_eq!(parse("/usr/bin/env python"), None);
+        assert_eq!(parse("/ nothing"), None);
+        assert_eq!(parse("hello /help"), None);
+    }
+
+    #[test]
+    fn expands_skill_prompts() {
+        assert_eq!(
+            expand_prompt("Review {{args}} now", "PR 12"),
+            "Review PR 12 now"
+        );
+        assert_eq!(expand_prompt("Standup", ""), "Standup");
+        assert_eq!(expand_prompt("Standup", "team a"), "Standup\n\nteam a");
+    }
+}
@@ -0,0 +1,226 @@
+// Paw Agent Engine — SMTP submission
+//
+// Connects and logs in for the account test (engine::mail_check), step by
+// step so each one can be reported, through the account's
+// `message.send.backend` from the himalaya config: implicit TLS,
+// STARTTLS or (with a warning) plaintext, logging in with AUTH PLAIN /
+// LOGIN and the keychain password, or XOAUTH2 for OAuth accounts — the
+// same credentials the IMAP side uses. Mail itself is sent with lettre
+// (engine::mail_client).
+
+use crate::atoms::error::EngineResult;
+use crate::engine::mail_oauth;
+use crate::engine::mail_watch::{self, ImapAccount, MailStream, SmtpServer};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::warn;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Timeout of every reply.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A server reply: its code and the text of every line.
+#[derive(Debug, PartialEq)]
+struct Reply {
+    code: u16,
+    lines: Vec<String>,
+}
+
+impl Reply {
+    /// `Ok` for one of `codes`; otherwise the error names `what` failed.
+    fn expect(self, codes: &[u16], what: &str) -> EngineResult<Self> {
+        if codes.contains(&self.code) {
+            return Ok(self);
+        }
+        Err(format!(
+            "SMTP server refused {}: {} {}",
+            what,
+            self.code,
+            self.lines.join(" ")
+        )
+        .into())
+    }
+}
+
+async fn read_reply<S: AsyncBufRead + Unpin>(
+    stream: &mut S,
+    timeout: Duration,
+) -> EngineResult<Reply> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let n = tokio::time::timeout(timeout, stream.read_line(&mut line))
+            .await
+            .map_err(|_| "The SMTP server stopped responding")?
+            .map_err(|e| format!("Read failed: {}", e))?;
+        if n == 0 {
+            return Err("Connection closed".into());
+        }
+        let line = line.trim_end();
+        let code = line
+            .get(..3)
+            .and_then(|c| c.parse().ok())
+            .ok_or_else(|| format!("Malformed SMTP reply: {}", line))?;
+        lines.push(line.get(4..).unwrap_or("").to_string());
+        // "250-..." continues, "250 ..." is the last line
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(Reply { code, lines });
+        }
+    }
+}
+
+async fn command<S: AsyncBufRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    line: &str,
+) -> EngineResult<Reply> {
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .await
+        .map_err(|e| format!("Write failed: {}", e))?;
+    stream
+        .flush()
+        .await
+        .map_err(|e| format!("Write failed: {}", e))?;
+    read_reply(stream, COMMAND_TIMEOUT).await
+}
+
+/// EHLO; returns the extensions, upper-case (e.g. "AUTH PLAIN LOGIN").
+async fn ehlo<S: AsyncBufRead + AsyncWrite + Unpin>(stream: &mut S) -> EngineResult<Vec<String>> {
+    // An address literal: there's no hostname worth announcing
+    let reply = command(stream, "EHLO [127.0.0.1]")
+        .await?
+        .expect(&[250], "EHLO")?;
+    Ok(reply
+        .lines
+        .iter()
+        .skip(1)
+        .map(|l| l.to_ascii_uppercase())
+        .collect())
+}
+
+pub(crate) struct Smtp {
+    stream: BufReader<Box<dyn MailStream>>,
+}
+
+impl Smtp {
+    /// Open the connection (TLS / STARTTLS) and say EHLO; returns the
+    /// session and the server's extensions.
+    pub(crate) async fn connect(server: &SmtpServer) -> EngineResult<(Self, Vec<String>)> {
+        let addr = format!("{}:{}", server.host, server.port);
+        let tcp = tokio::time::timeout(COMMAND_TIMEOUT, TcpStream::connect(&addr))
+            .await
+            .map_err(|_| format!("Connecting to {} timed out", addr))?
+            .map_err(|e| format!("TCP connect to {} failed: {}", addr, e))?;
+        let stream: Box<dyn MailStream> = match server.encryption.as_str() {
+            "none" => {
+                warn!(
+                    "[smtp] Connecting WITHOUT TLS to {} — the password is sent in plaintext!",
+                    addr
+                );
+                Box::new(tcp)
+            }
+            "start-tls" => {
+                // Nothing follows the 220 until the handshake, so the
+                // buffer is empty when the TCP stream is taken back
+                let mut plain = BufReader::new(tcp);
+                read_reply(&mut plain, COMMAND_TIMEOUT)
+                    .await?
+                    .expect(&[220], "the connection")?;
+                ehlo(&mut plain).await?;
+                command(&mut plain, "STARTTLS")
+                    .await?
+                    .expect(&[220], "STARTTLS")?;
+                mail_watch::tls_upgrade(&server.host, plain.into_inner()).await?
+            }
+            _ => mail_watch::tls_upgrade(&server.host, tcp).await?,
+        };
+        let mut stream = BufReader::new(stream);
+        if server.encryption != "start-tls" {
+            read_reply(&mut stream, COMMAND_TIMEOUT)
+                .await?
+                .expect(&[220], "the connection")?;
+        }
+        let extensions = ehlo(&mut stream).await?;
+        Ok((Smtp { stream }, extensions))
+    }
+
+    /// Log in on a session from `connect`, with the mechanisms among
+    /// `extensions`.
+    pub(crate) async fn authenticate(
+        &mut self,
+        account: &ImapAccount,
+        server: &SmtpServer,
+        extensions: &[String],
+    ) -> EngineResult<()> {
+        let stream = &mut self.stream;
+        match account.oauth {
+            Some(provider) => {
+                let token =
+                    mail_oauth::access_token(&account.name, &account.email, provider).await?;
+                let auth = mail_oauth::xoauth2(&server.login, &token);
+                let mut reply = command(stream, &format!("AUTH XOAUTH2 {}", auth)).await?;
+                // A refusal comes as a 334 challenge carrying the error
+                if reply.code == 334 {
+                    reply = command(stream, "").await?;
+                }
+                reply.expect(&[235], "the login")?;
+            }
+            None => {
+                let pass = match &server.raw_password {
+                    Some(raw) => raw.clone(),
+                    None => mail_watch::password(account)?,
+                };
+                let login_only = extensions
+                    .iter()
+                    .filter_map(|e| e.strip_prefix("AUTH"))
+                    .any(|mechs| {
+                        let mut mechs = mechs.split_whitespace();
+                        !mechs.clone().any(|m| m == "PLAIN") && mechs.any(|m| m == "LOGIN")
+                    });
+                if login_only {
+                    command(stream, "AUTH LOGIN")
+                        .await?
+                        .expect(&[334], "the login")?;
+                    command(stream, &STANDARD.encode(&server.login))
+                        .await?
+                        .expect(&[334], "the login")?;
+                    command(stream, &STANDARD.encode(&pass))
+                        .await?
+                        .expect(&[235], "the login")?;
+                } else {
+                    let plain = STANDARD.encode(format!("\0{}\0{}", server.login, pass));
+                    command(stream, &format!("AUTH PLAIN {}", plain))
+                        .await?
+                        .expect(&[235], "the login")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn multiline_replies_are_read_whole() {
+        let mut input: &[u8] =
+            b"250-smtp.example.com\r\n250-AUTH PLAIN LOGIN\r\n250 SIZE 35882577\r\n535 5.7.8 Bad\r\n";
+        let reply = read_reply(&mut input, COMMAND_TIMEOUT).await.unwrap();
+        assert_eq!(reply.code, 250);
+        assert_eq!(
+            reply.lines,
+            vec!["smtp.example.com", "AUTH PLAIN LOGIN", "SIZE 35882577"]
+        );
+        let refused = read_reply(&mut input, COMMAND_TIMEOUT).await.unwrap();
+        let err = refused.expect(&[235], "the login").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "SMTP server refused the login: 535 5.7.8 Bad"
+        );
+        assert!(read_reply(&mut input, COMMAND_TIMEOUT).await.is_err());
+    }
+}
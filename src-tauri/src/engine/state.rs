@@ -7,7 +7,8 @@ use crate::engine::memory::EmbeddingClient;
 use crate::engine::sessions::SessionStore;
 use crate::engine::speculative::{SpeculationConfig, SpeculativeCache};
 use crate::engine::tool_index::ToolIndex;
-use crate::engine::tool_registry::PersistentToolRegistry;
+use crate::engine::tool_registry::{PersistentToolRegistry, ToolRagConfig};
+use crate::engine::tools::worker_delegate::rules::{WorkerDelegationConfig, WorkerStats};
 use crate::engine::types::*;
 
 use crate::engine::mcp::McpRegistry;
@@ -15,6 +16,7 @@ use crate::engine::mcp::McpRegistry;
 use crate::atoms::engram_types::EngramConfig;
 use crate::atoms::error::EngineResult;
 use log::{info, warn};
+use openpawz_core::engine::cancel::CancelToken;
 use parking_lot::Mutex;
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -191,6 +193,10 @@ pub type RequestQueue = Arc<Mutex<HashMap<String, Vec<QueuedRequest>>>>;
 /// When a steering request is queued, yield is requested on the active run.
 pub type YieldSignals = Arc<Mutex<HashMap<String, YieldSignal>>>;
 
+/// Per-session tool circuit breakers (see engine::tool_errors).
+/// Kept across turns so blocked tools stay blocked until their cooldown.
+pub type ToolBreakers = Arc<Mutex<HashMap<String, crate::engine::tool_errors::ToolFailures>>>;
+
 /// Map retired / renamed / shorthand model IDs to their current API names.
 /// This lets old task configs, agent overrides, and user-entered short names keep working.
 pub fn normalize_model_name(model: &str) -> &str {
@@ -314,6 +320,24 @@ pub fn resolve_provider_for_model(
     }
 }
 
+/// `resolve_provider_for_model`, falling back to a provider whose default
+/// model is `model`, then the default provider, then the first configured one.
+pub fn provider_for_model_or_default(model: &str, cfg: &EngineConfig) -> Option<ProviderConfig> {
+    resolve_provider_for_model(model, &cfg.providers)
+        .or_else(|| {
+            cfg.providers
+                .iter()
+                .find(|p| p.default_model.as_deref() == Some(model))
+                .cloned()
+        })
+        .or_else(|| {
+            cfg.default_provider
+                .as_ref()
+                .and_then(|dp| cfg.providers.iter().find(|p| p.id == *dp).cloned())
+        })
+        .or_else(|| cfg.providers.first().cloned())
+}
+
 /// Engine state managed by Tauri.
 pub struct EngineState {
     pub store: SessionStore,
@@ -330,6 +354,9 @@ pub struct EngineState {
     /// Abort handles for active agent runs, keyed by session_id.
     /// Used by engine_chat_abort to cancel in-flight agent loops.
     pub active_runs: Arc<Mutex<HashMap<String, tokio::task::AbortHandle>>>,
+    /// Cancel tokens of active agent runs: session_id → (run_id, token).
+    /// Used by engine_chat_cancel to stop a run and keep its partial output.
+    pub run_cancels: Arc<Mutex<HashMap<String, (String, CancelToken)>>>,
     /// MCP server registry — manages connected MCP servers and their tools.
     pub mcp_registry: Arc<tokio::sync::Mutex<McpRegistry>>,
     /// Tool RAG index — semantic search over tool definitions ("the librarian").
@@ -340,6 +367,10 @@ pub struct EngineState {
     pub speculation_cache: Arc<Mutex<SpeculativeCache>>,
     /// Speculative execution config.
     pub speculation_config: SpeculationConfig,
+    /// Foreman delegation rules, read on every tool call.
+    pub worker_delegation: Mutex<WorkerDelegationConfig>,
+    /// Foreman delegation counters, keyed by worker name (reset on restart).
+    pub worker_stats: Arc<Mutex<HashMap<String, WorkerStats>>>,
     /// Tools loaded via request_tools in the current chat turn.
     /// Cleared at the start of each new chat message.
     pub loaded_tools: Arc<Mutex<std::collections::HashSet<String>>>,
@@ -357,6 +388,16 @@ pub struct EngineState {
     /// HNSW vector index for approximate nearest-neighbor search on episodic
     /// memory embeddings. Built from DB on startup, updated incrementally.
     pub hnsw_index: crate::engine::engram::hnsw::SharedHnswIndex,
+    /// Sessions where the user allowed `screen_capture` without asking each
+    /// time. Not persisted — consent ends when the app restarts.
+    pub screen_consent: Arc<Mutex<HashSet<String>>>,
+    /// Last focused app/window outside Paw (opt-in, memory only — see
+    /// engine::app_context).
+    pub active_window: Arc<Mutex<Option<crate::engine::app_context::ActiveWindow>>>,
+    /// Files dropped into chat, keyed by ingest handle (see engine::ingest).
+    pub ingested: Arc<Mutex<HashMap<String, crate::engine::ingest::IngestedFile>>>,
+    /// Tool circuit breakers, keyed by session_id. Not persisted.
+    pub tool_breakers: ToolBreakers,
 }
 
 impl EngineState {
@@ -395,6 +436,12 @@ impl EngineState {
             }
         }
 
+        // Install config-driven provider middleware (gateway headers, request logging)
+        crate::engine::providers::middleware::set_config(config.provider_middleware.clone());
+
+        // Keep local models loaded between requests (Ollama keep_alive)
+        crate::engine::local_models::configure(&config);
+
         // Load memory config from DB or use defaults
         let memory_config = match store.get_config("memory_config") {
             Ok(Some(json)) => serde_json::from_str::<MemoryConfig>(&json).unwrap_or_default(),
@@ -410,6 +457,21 @@ impl EngineState {
             _ => SpeculationConfig::default(),
         };
 
+        // Load Tool RAG relevance tuning from DB or use defaults
+        let tool_rag_config = match store.get_config("tool_rag_config") {
+            Ok(Some(json)) => serde_json::from_str::<ToolRagConfig>(&json).unwrap_or_default(),
+            _ => ToolRagConfig::default(),
+        };
+
+        // Load Foreman delegation rules from DB or use defaults
+        let worker_delegation =
+            match store.get_config(crate::engine::tools::worker_delegate::rules::CONFIG_KEY) {
+                Ok(Some(json)) => {
+                    serde_json::from_str::<WorkerDelegationConfig>(&json).unwrap_or_default()
+                }
+                _ => WorkerDelegationConfig::default(),
+            };
+
         // Build HNSW index from existing episodic memory embeddings
         let hnsw_index = {
             let idx = crate::engine::engram::hnsw::new_shared();
@@ -429,18 +491,25 @@ impl EngineState {
             inflight_tasks: Arc::new(Mutex::new(HashSet::new())),
             daily_tokens: Arc::new(DailyTokenTracker::new()),
             active_runs: Arc::new(Mutex::new(HashMap::new())),
+            run_cancels: Arc::new(Mutex::new(HashMap::new())),
             mcp_registry: Arc::new(tokio::sync::Mutex::new(McpRegistry::new())),
             tool_index: Arc::new(tokio::sync::Mutex::new(ToolIndex::new())),
             persistent_tool_registry: Arc::new(tokio::sync::Mutex::new(
-                PersistentToolRegistry::new(),
+                PersistentToolRegistry::with_config(tool_rag_config),
             )),
             speculation_cache: Arc::new(Mutex::new(SpeculativeCache::new(&speculation_config))),
             speculation_config,
+            worker_delegation: Mutex::new(worker_delegation),
+            worker_stats: Arc::new(Mutex::new(HashMap::new())),
             loaded_tools: Arc::new(Mutex::new(HashSet::new())),
             request_queue: Arc::new(Mutex::new(HashMap::new())),
             yield_signals: Arc::new(Mutex::new(HashMap::new())),
             cognitive_states: Arc::new(Mutex::new(HashMap::new())),
             hnsw_index,
+            screen_consent: Arc::new(Mutex::new(HashSet::new())),
+            active_window: Arc::new(Mutex::new(None)),
+            ingested: Arc::new(Mutex::new(HashMap::new())),
+            tool_breakers: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -544,4 +613,67 @@ impl EngineState {
 
         Some(client)
     }
+
+    /// Resolve a pending tool approval. Returns false when nothing is waiting
+    /// on `tool_call_id` any more (timed out, or answered elsewhere first).
+    pub fn resolve_tool_approval(&self, tool_call_id: &str, approved: bool) -> bool {
+        match self.pending_approvals.lock().remove(tool_call_id) {
+            Some(sender) => {
+                info!(
+                    "[engine] Tool approval resolved: {} → {}",
+                    tool_call_id,
+                    if approved { "ALLOWED" } else { "DENIED" }
+                );
+                let _ = sender.send(approved);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Run SessionStore work on the blocking pool. SQLite calls (and waiting
+/// for the writer lock behind a long write) would otherwise stall a runtime
+/// worker that is also driving provider streams and bridges. Calls from one
+/// task still run in order, since each is awaited before the next.
+pub async fn with_store<T, F>(app_handle: &tauri::AppHandle, f: F) -> EngineResult<T>
+where
+    F: FnOnce(&SessionStore) -> EngineResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    use tauri::Manager;
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app
+            .try_state::<EngineState>()
+            .ok_or("Engine not initialized")?;
+        f(&state.store)
+    })
+    .await
+    .map_err(|e| crate::atoms::error::EngineError::Other(format!("Store task failed: {}", e)))?
+}
+
+/// Future handed to `with_store_async`, borrowing the engine state.
+pub type StoreFuture<'a, T> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = EngineResult<T>> + 'a>>;
+
+/// `with_store` for async work that interleaves SQLite with awaits (memory
+/// recall and capture embed over HTTP between queries). The whole future is
+/// driven on a blocking-pool thread, so none of its queries run on a
+/// runtime worker.
+pub async fn with_store_async<T, F>(app_handle: &tauri::AppHandle, f: F) -> EngineResult<T>
+where
+    F: for<'a> FnOnce(&'a EngineState) -> StoreFuture<'a, T> + Send + 'static,
+    T: Send + 'static,
+{
+    use tauri::Manager;
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app
+            .try_state::<EngineState>()
+            .ok_or("Engine not initialized")?;
+        tauri::async_runtime::block_on(f(&state))
+    })
+    .await
+    .map_err(|e| crate::atoms::error::EngineError::Other(format!("Store task failed: {}", e)))?
 }
@@ -6,14 +6,17 @@
 // Contains:
 //   - execute_task:       Multi-agent task dispatch + session management
 //   - run_cron_heartbeat: Background position monitoring + cron execution
+//                         (pre-warms local models of tasks due soon)
+//   - run_due_followups:  Fires follow-ups scheduled via schedule_followup
 //   - check_positions:    SL/TP monitoring for open trading positions
-//   - compute_next_run:   Simple schedule parser
+//   - compute_next_run:   Simple schedule parser (compute_next_run_in: local tz)
 
 use crate::atoms::constants::{CRON_MAX_TOOL_ROUNDS, CRON_SESSION_KEEP_MESSAGES};
 use crate::engine::chat as chat_org;
 use crate::engine::engram;
+use crate::engine::notifications::{self, Notice};
 use crate::engine::providers::AnyProvider;
-use crate::engine::state::{normalize_model_name, resolve_provider_for_model, EngineState};
+use crate::engine::state::{normalize_model_name, provider_for_model_or_default, EngineState};
 use crate::engine::types::*;
 use crate::engine::{agent_loop, sessions, skills, sol_dex, telegram};
 use log::{error, info, warn};
@@ -22,6 +25,44 @@ use tauri::{Emitter, Manager};
 
 // ── Core Organism: execute_task ────────────────────────────────────────
 
+/// The agents a task runs as: its assigned agents, else the single legacy
+/// assignee, else "default".
+pub fn task_agent_ids(task: &Task) -> Vec<String> {
+    if !task.assigned_agents.is_empty() {
+        task.assigned_agents
+            .iter()
+            .map(|a| a.agent_id.clone())
+            .collect()
+    } else if let Some(ref agent) = task.assigned_agent {
+        vec![agent.clone()]
+    } else {
+        vec!["default".to_string()]
+    }
+}
+
+/// The model `agent_id` runs `task` with: the task's own override, else the
+/// agent's worker routing.
+pub fn task_model(
+    task: &Task,
+    agent_id: &str,
+    routing: &ModelRouting,
+    default_model: &str,
+) -> String {
+    match task.model.as_deref() {
+        Some(model) if !model.is_empty() => normalize_model_name(model).to_string(),
+        _ => routing.resolve(agent_id, "worker", "", default_model),
+    }
+}
+
+/// The first of the task's agents whose autonomy level forbids unattended
+/// runs (cron schedules and event triggers), if any.
+pub fn trigger_blocked_by(state: &EngineState, task: &Task) -> Option<String> {
+    task_agent_ids(task).into_iter().find(|agent_id| {
+        crate::engine::autonomy::load(&state.store, agent_id)
+            .is_some_and(|level| !level.policy().allow_triggers)
+    })
+}
+
 /// Standalone task execution — callable from both the Tauri command and the
 /// background cron heartbeat. Handles multi-agent spawning and session
 /// management. This is the core logic for running a task.
@@ -51,16 +92,7 @@ pub async fn execute_task(
         .find(|t| t.id == task_id)
         .ok_or_else(|| format!("Task not found: {}", task_id))?;
 
-    let agent_ids: Vec<String> = if !task.assigned_agents.is_empty() {
-        task.assigned_agents
-            .iter()
-            .map(|a| a.agent_id.clone())
-            .collect()
-    } else if let Some(ref agent) = task.assigned_agent {
-        vec![agent.clone()]
-    } else {
-        vec!["default".to_string()]
-    };
+    let agent_ids = task_agent_ids(&task);
 
     info!(
         "[engine] Running task '{}' with {} agent(s): {:?}",
@@ -76,14 +108,15 @@ pub async fn execute_task(
     }
 
     for agent_id in &agent_ids {
-        let aid = uuid::Uuid::new_v4().to_string();
-        state.store.add_task_activity(
-            &aid,
-            task_id,
-            "agent_started",
-            Some(agent_id),
-            &format!("Agent {} started working on: {}", agent_id, task.title),
-        )?;
+        state
+            .store
+            .defer_write(sessions::DeferredWrite::TaskActivity {
+                id: uuid::Uuid::new_v4().to_string(),
+                task_id: task_id.to_string(),
+                kind: "agent_started".into(),
+                agent: Some(agent_id.clone()),
+                content: format!("Agent {} started working on: {}", agent_id, task.title),
+            });
     }
 
     let task_prompt = if task.description.is_empty() {
@@ -176,26 +209,7 @@ pub async fn execute_task(
             }
         }
 
-        let agent_model = if let Some(ref task_model) = task.model {
-            if !task_model.is_empty() {
-                let normalized = normalize_model_name(task_model).to_string();
-                if normalized != *task_model {
-                    info!(
-                        "[engine] Task '{}' model remapped: {} → {}",
-                        task.title, task_model, normalized
-                    );
-                }
-                info!(
-                    "[engine] Task '{}' has explicit model override: {}",
-                    task.title, normalized
-                );
-                normalized
-            } else {
-                model_routing.resolve(&agent_id, "worker", "", &default_model)
-            }
-        } else {
-            model_routing.resolve(&agent_id, "worker", "", &default_model)
-        };
+        let agent_model = task_model(&task, &agent_id, &model_routing, &default_model);
         info!(
             "[engine] Agent '{}' resolved model: {} (task_override: {:?}, default: {})",
             agent_id, agent_model, task.model, default_model
@@ -204,19 +218,7 @@ pub async fn execute_task(
         let (provider_config, model) = {
             let cfg = state.config.lock();
             let model = agent_model;
-            let provider = resolve_provider_for_model(&model, &cfg.providers)
-                .or_else(|| {
-                    cfg.providers
-                        .iter()
-                        .find(|p| p.default_model.as_deref() == Some(model.as_str()))
-                        .cloned()
-                })
-                .or_else(|| {
-                    cfg.default_provider
-                        .as_ref()
-                        .and_then(|dp| cfg.providers.iter().find(|p| p.id == *dp).cloned())
-                })
-                .or_else(|| cfg.providers.first().cloned());
+            let provider = provider_for_model_or_default(&model, &cfg);
             match provider {
                 Some(p) => (p, model),
                 None => return Err("No AI provider configured".into()),
@@ -253,9 +255,9 @@ pub async fn execute_task(
         let emb_client = state.embedding_client();
         let recall_scope = crate::atoms::engram_types::MemoryScope::agent(&agent_id);
         let provider_name = format!("{:?}", provider_config.kind);
-        let user_tz = {
+        let (user_tz, home_location) = {
             let cfg = state.config.lock();
-            cfg.user_timezone.clone()
+            (cfg.user_timezone.clone(), cfg.home_location.clone())
         };
         let runtime_context = chat_org::build_runtime_context(
             &model,
@@ -263,6 +265,7 @@ pub async fn execute_task(
             &session_id,
             &agent_id,
             &user_tz,
+            home_location.as_ref(),
         );
 
         let agent_count_note = if agent_count > 1 {
@@ -543,6 +546,7 @@ pub async fn execute_task(
     let app_handle_final = app_handle.clone();
     let inflight_clone = inflight.clone();
     let task_id_for_cleanup = task_id.to_string();
+    let task_title = task.title.clone();
     tauri::async_runtime::spawn(async move {
         let mut any_ok = false;
         for handle in handles {
@@ -613,6 +617,16 @@ pub async fn execute_task(
             "task_id": task_id_for_spawn,
             "status": if is_recurring || is_persistent { "in_progress" } else if any_ok { "review" } else { "blocked" },
         })).ok();
+
+        let title = if any_ok {
+            format!("Task finished: {}", task_title)
+        } else {
+            format!("Task failed: {}", task_title)
+        };
+        notifications::notify(
+            &app_handle_final,
+            Notice::new(notifications::TASK, title, "").navigate_to("tasks"),
+        );
     });
 
     Ok(run_id)
@@ -829,11 +843,18 @@ async fn execute_position_sell(
 // ── Background Cron Heartbeat ──────────────────────────────────────────
 
 /// Background cron heartbeat — called every 60 seconds from the Tauri
-/// setup hook. Checks open positions (SL/TP) and executes due cron tasks.
+/// setup hook. Checks open positions (SL/TP), fires due follow-ups and news
+/// watches, and executes due cron tasks.
 pub async fn run_cron_heartbeat(app_handle: &tauri::AppHandle) {
+    if crate::engine::shutdown::is_shutting_down() {
+        return;
+    }
     let state = app_handle.state::<EngineState>();
 
     check_positions(app_handle).await;
+    run_due_followups(app_handle).await;
+    crate::engine::news::run_due_watches(app_handle).await;
+    crate::engine::local_models::prewarm_upcoming_tasks(app_handle);
 
     let due_tasks = match state.store.get_due_cron_tasks() {
         Ok(tasks) => tasks,
@@ -854,7 +875,8 @@ pub async fn run_cron_heartbeat(app_handle: &tauri::AppHandle) {
         let task_title = task.title.clone();
 
         let now = chrono::Utc::now();
-        let next = compute_next_run(&task.cron_schedule, &now);
+        let tz = state.config.lock().timezone();
+        let next = compute_next_run_in(&task.cron_schedule, &now, &tz);
         if let Err(e) =
             state
                 .store
@@ -867,20 +889,26 @@ pub async fn run_cron_heartbeat(app_handle: &tauri::AppHandle) {
             continue;
         }
 
-        let aid = uuid::Uuid::new_v4().to_string();
+        if let Some(agent_id) = trigger_blocked_by(&state, &task) {
+            info!(
+                "[heartbeat] Skipping cron task '{}' — agent '{}' autonomy level disallows triggers",
+                task_title, agent_id
+            );
+            continue;
+        }
+
         state
             .store
-            .add_task_activity(
-                &aid,
-                &task_id,
-                "cron_triggered",
-                None,
-                &format!(
+            .defer_write(sessions::DeferredWrite::TaskActivity {
+                id: uuid::Uuid::new_v4().to_string(),
+                task_id: task_id.clone(),
+                kind: "cron_triggered".into(),
+                agent: None,
+                content: format!(
                     "Cron triggered: {}",
                     task.cron_schedule.as_deref().unwrap_or("unknown")
                 ),
-            )
-            .ok();
+            });
 
         let app = app_handle.clone();
         tauri::async_runtime::spawn(async move {
@@ -919,13 +947,136 @@ pub async fn run_cron_heartbeat(app_handle: &tauri::AppHandle) {
         .ok();
 }
 
+// ── Follow-ups ─────────────────────────────────────────────────────────
+
+/// Fire every due follow-up as a new turn in the session that scheduled it.
+/// Follow-ups are unattended runs, so the agent's autonomy level must allow
+/// triggers and the daily budget must not be exhausted.
+pub async fn run_due_followups(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<EngineState>();
+
+    let due = match state.store.get_due_followups() {
+        Ok(f) => f,
+        Err(e) => {
+            warn!("[heartbeat] Failed to query due follow-ups: {}", e);
+            return;
+        }
+    };
+
+    for followup in due {
+        let policy = crate::engine::autonomy::load(&state.store, &followup.agent_id)
+            .map(|level| level.policy());
+        let skip_reason = if policy.as_ref().is_some_and(|p| !p.allow_triggers) {
+            Some("autonomy level disallows unattended runs")
+        } else if state
+            .store
+            .get_session(&followup.session_id)
+            .ok()
+            .flatten()
+            .is_none()
+        {
+            Some("session no longer exists")
+        } else {
+            let budget = state.config.lock().daily_budget_usd;
+            let budget = policy
+                .as_ref()
+                .map_or(budget, |p| p.effective_budget(budget));
+            if budget > 0.0 && state.daily_tokens.check_budget(budget).is_some() {
+                Some("daily budget exhausted")
+            } else {
+                None
+            }
+        };
+
+        let status = if skip_reason.is_some() {
+            "skipped"
+        } else {
+            "fired"
+        };
+        match state.store.finish_followup(&followup.id, status) {
+            Ok(true) => {}
+            Ok(false) => continue, // cancelled or already handled
+            Err(e) => {
+                error!(
+                    "[heartbeat] Failed to update follow-up {}: {}",
+                    followup.id, e
+                );
+                continue;
+            }
+        }
+        app_handle
+            .emit(
+                "followups-updated",
+                serde_json::json!({ "session_id": followup.session_id }),
+            )
+            .ok();
+
+        if let Some(reason) = skip_reason {
+            info!(
+                "[heartbeat] Skipping follow-up {} for session {}: {}",
+                followup.id, followup.session_id, reason
+            );
+            continue;
+        }
+
+        info!(
+            "[heartbeat] Firing follow-up {} for session {}",
+            followup.id, followup.session_id
+        );
+        let request = ChatRequest {
+            session_id: Some(followup.session_id.clone()),
+            message: format!(
+                "[Scheduled follow-up] {}\n\n(You scheduled this earlier in the conversation. \
+                 No one is necessarily watching — do the check and report what you found.)",
+                followup.note
+            ),
+            model: None,
+            system_prompt: None,
+            temperature: None,
+            provider_id: None,
+            tools_enabled: Some(true),
+            agent_id: Some(followup.agent_id.clone()),
+            tool_filter: None,
+            attachments: Vec::new(),
+            thinking_level: None,
+            auto_approve_all: false,
+            user_approved_tools: Vec::new(),
+            plan_mode: false,
+            ingested: vec![],
+        };
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let st = app.state::<EngineState>();
+            if let Err(e) = crate::commands::chat::engine_chat_send(app.clone(), st, request).await
+            {
+                error!(
+                    "[heartbeat] Follow-up {} failed to start: {}",
+                    followup.id, e
+                );
+            }
+        });
+    }
+}
+
 // ── Schedule helpers ───────────────────────────────────────────────────
 
-/// Simple schedule parser: "every Xm", "every Xh", "daily HH:MM"
+/// Simple schedule parser: "every Xm", "every Xh", "daily HH:MM".
+/// "daily" times are read as UTC; see `compute_next_run_in`.
 pub fn compute_next_run(
     schedule: &Option<String>,
     from: &chrono::DateTime<chrono::Utc>,
 ) -> Option<String> {
+    compute_next_run_in(schedule, from, &chrono_tz::UTC)
+}
+
+/// Like `compute_next_run`, but "daily HH:MM" is wall-clock time in `tz`,
+/// so a 09:00 task keeps firing at 09:00 local across DST changes.
+pub fn compute_next_run_in(
+    schedule: &Option<String>,
+    from: &chrono::DateTime<chrono::Utc>,
+    tz: &chrono_tz::Tz,
+) -> Option<String> {
+    use chrono::TimeZone;
     let s = schedule.as_deref()?;
     let s = s.trim().to_lowercase();
     if s.is_empty() {
@@ -947,16 +1098,20 @@ pub fn compute_next_run(
         if parts.len() == 2 {
             let hour: u32 = parts[0].parse().ok()?;
             let minute: u32 = parts[1].parse().ok()?;
-            let today = from.date_naive();
-            let target_time = today.and_hms_opt(hour, minute, 0)?;
-            let target = target_time.and_utc();
-            if target > *from {
-                return Some(target.to_rfc3339());
-            } else {
-                let tomorrow = today.succ_opt()?;
-                let next = tomorrow.and_hms_opt(hour, minute, 0)?.and_utc();
-                return Some(next.to_rfc3339());
+            let mut day = from.with_timezone(tz).date_naive();
+            // Check today and the next days; a time skipped by a DST jump
+            // simply moves to the following day.
+            for _ in 0..3 {
+                let local = day.and_hms_opt(hour, minute, 0)?;
+                if let Some(target) = tz.from_local_datetime(&local).earliest() {
+                    let target = target.with_timezone(&chrono::Utc);
+                    if target > *from {
+                        return Some(target.to_rfc3339());
+                    }
+                }
+                day = day.succ_opt()?;
             }
+            return None;
         }
     }
 
@@ -1060,6 +1215,28 @@ mod tests {
         assert!(next.contains("23:30"));
     }
 
+    #[test]
+    fn daily_uses_local_timezone() {
+        let tz: chrono_tz::Tz = "America/Chicago".parse().unwrap();
+        // 08:00 UTC = 03:00 CDT → 09:00 CDT is 14:00 UTC the same day
+        let from = utc(2025, 6, 15, 8, 0);
+        let next = compute_next_run_in(&Some("daily 09:00".into()), &from, &tz).unwrap();
+        assert!(next.starts_with("2025-06-15T14:00:00"));
+        // In winter (CST, UTC-6) the same local time is 15:00 UTC
+        let from = utc(2025, 1, 15, 8, 0);
+        let next = compute_next_run_in(&Some("daily 09:00".into()), &from, &tz).unwrap();
+        assert!(next.starts_with("2025-01-15T15:00:00"));
+    }
+
+    #[test]
+    fn daily_skips_nonexistent_dst_time() {
+        let tz: chrono_tz::Tz = "America/Chicago".parse().unwrap();
+        // 02:30 does not exist on 2025-03-09 (clocks jump 02:00 → 03:00)
+        let from = utc(2025, 3, 9, 6, 0);
+        let next = compute_next_run_in(&Some("daily 02:30".into()), &from, &tz).unwrap();
+        assert!(next.starts_with("2025-03-10T07:30:00"));
+    }
+
     // ── Case insensitivity ─────────────────────────────────────────
 
     #[test]
@@ -126,6 +126,8 @@ pub struct TelegramStatus {
     pub allowed_users: Vec<i64>,
     pub pending_users: Vec<PendingUser>,
     pub dm_policy: String,
+    #[serde(default)]
+    pub connectivity: channels::ConnectionMetrics,
 }
 
 // ── Global State ───────────────────────────────────────────────────────
@@ -294,7 +296,13 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
                         break;
                     }
                     error!("[telegram] Bridge error: {} — reconnecting", e);
-                    let delay = crate::engine::http::reconnect_delay(reconnect_attempt).await;
+                    let delay = channels::reconnect_backoff(
+                        &app_handle,
+                        "telegram",
+                        reconnect_attempt,
+                        &e.to_string(),
+                    )
+                    .await;
                     warn!(
                         "[telegram] Reconnecting in {}ms (attempt {})",
                         delay.as_millis(),
@@ -308,6 +316,7 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
             }
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "telegram", None);
         info!("[telegram] Bridge stopped");
     });
 
@@ -335,6 +344,148 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> TelegramStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("telegram"),
+    }
+}
+
+// ── Inbound Journal ────────────────────────────────────────────────────
+
+/// Replay attempts before a journaled message is given up on, so a message
+/// that crashes the engine can't crash it on every startup.
+const MAX_REPLAY_ATTEMPTS: i64 = 3;
+
+/// What the journal keeps to re-run a message and reply to it after a crash.
+#[derive(Debug, Serialize, Deserialize)]
+struct JournaledMessage {
+    chat_id: i64,
+    message_id: i64,
+    user_id: i64,
+    text: String,
+}
+
+/// Route a journaled message through the agent, reply, and mark the journal
+/// entry complete.
+async fn process_journaled(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    token: &str,
+    config: &TelegramConfig,
+    journal_id: &str,
+    inbound: &JournaledMessage,
+) {
+    // Leave it pending — the next startup replays it
+    if crate::engine::shutdown::is_shutting_down() {
+        return;
+    }
+    let engine_state = app_handle.try_state::<EngineState>();
+    if let Some(st) = &engine_state {
+        let _ = st.store.journal_begin_attempt(journal_id);
+    }
+
+    // ── Send "typing" indicator ─────────────────────
+    let _ = tg_send_chat_action(client, token, inbound.chat_id).await;
+
+    // ── Route to agent loop ─────────────────────────
+    // Prune old messages to bound TG session growth
+    let agent_id_str = config.agent_id.as_deref().unwrap_or("default");
+    if let Some(st) = &engine_state {
+        let tg_session_id = format!("eng-telegram-{}-{}", agent_id_str, inbound.user_id);
+        let _ = st.store.prune_session_messages(&tg_session_id, 50);
+    }
+    // Same run id on every attempt: a replay can't repeat side effects
+    let response = channels::with_run_id(
+        format!("inbound:{}", journal_id),
+        channels::run_channel_agent(
+            app_handle,
+            "telegram",
+            "You are chatting via Telegram. The user is messaging you from their phone. \
+             Keep responses concise and mobile-friendly. Use Markdown formatting supported by \
+             Telegram (bold, italic, code, links). Avoid very long responses unless explicitly \
+             asked.",
+            &inbound.text,
+            &inbound.user_id.to_string(),
+            agent_id_str,
+            config.allow_dangerous_tools,
+        ),
+    )
+    .await;
+
+    let status = match response {
+        Ok(reply) => {
+            if !reply.is_empty() {
+                let _ = tg_send_message(
+                    client,
+                    token,
+                    inbound.chat_id,
+                    &reply,
+                    Some(inbound.message_id),
+                )
+                .await;
+            }
+            "done"
+        }
+        Err(e) => {
+            error!("[telegram] Agent error for user {}: {}", inbound.user_id, e);
+            let _ = tg_send_message(
+                client,
+                token,
+                inbound.chat_id,
+                &format!("Error: {}", e),
+                Some(inbound.message_id),
+            )
+            .await;
+            "failed"
+        }
+    };
+    if let Some(st) = &engine_state {
+        let _ = st.store.journal_complete(journal_id, status);
+    }
+}
+
+/// Re-run messages that were journaled but never answered (Paw crashed or
+/// was closed mid-reply), then prune old completed entries.
+async fn replay_journal(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+    token: &str,
+    config: &TelegramConfig,
+) {
+    let Some(st) = app_handle.try_state::<EngineState>() else {
+        return;
+    };
+    let _ = st.store.journal_prune(7);
+    let pending = match st.store.journal_pending("telegram") {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("[telegram] Could not read inbound journal: {}", e);
+            return;
+        }
+    };
+    if pending.is_empty() {
+        return;
+    }
+    info!(
+        "[telegram] Replaying {} unanswered message(s)",
+        pending.len()
+    );
+    for entry in pending {
+        if entry.attempts >= MAX_REPLAY_ATTEMPTS {
+            warn!(
+                "[telegram] Giving up on {} after {} attempts",
+                entry.id, entry.attempts
+            );
+            let _ = st.store.journal_complete(&entry.id, "failed");
+            continue;
+        }
+        match serde_json::from_str::<JournaledMessage>(&entry.payload) {
+            Ok(inbound) => {
+                process_journaled(app_handle, client, token, config, &entry.id, &inbound).await
+            }
+            Err(e) => {
+                warn!("[telegram] Bad journal payload for {}: {}", entry.id, e);
+                let _ = st.store.journal_complete(&entry.id, "failed");
+            }
+        }
     }
 }
 
@@ -354,6 +505,7 @@ async fn run_polling_loop(
     let _ = BOT_NAME.set(name.clone());
 
     // Emit connected event to frontend
+    channels::mark_connected(&app_handle, "telegram");
     let _ = app_handle.emit(
         "telegram-status",
         serde_json::json!({
@@ -363,6 +515,9 @@ async fn run_polling_loop(
         }),
     );
 
+    // Answer anything received before a crash or shutdown
+    replay_journal(&app_handle, &client, &config.bot_token, &config).await;
+
     let stop = get_stop_signal();
     let mut offset: i64 = 0;
     let token = config.bot_token.clone();
@@ -447,6 +602,11 @@ async fn run_polling_loop(
                                                 "username": &username,
                                             }),
                                         );
+                                        channels::notify_pairing(
+                                            &app_handle,
+                                            "Telegram",
+                                            &username,
+                                        );
                                     }
                                     let _ = tg_send_message(
                                         &client,
@@ -476,54 +636,35 @@ async fn run_polling_loop(
                             }
                         }
 
-                        // ── Send "typing" indicator ─────────────────────
-                        let _ = tg_send_chat_action(&client, &token, chat_id).await;
-
-                        // ── Route to agent loop ─────────────────────────
-                        // Prune old messages to bound TG session growth
-                        let agent_id_str = current_config.agent_id.as_deref().unwrap_or("default");
+                        // ── Journal before processing (crash-safe) ──────
+                        let inbound = JournaledMessage {
+                            chat_id,
+                            message_id: msg.message_id,
+                            user_id,
+                            text,
+                        };
+                        let journal_id = format!("telegram:{}", update.update_id);
                         if let Some(st) = app_handle.try_state::<EngineState>() {
-                            let tg_session_id =
-                                format!("eng-telegram-{}-{}", agent_id_str, user_id);
-                            let _ = st.store.prune_session_messages(&tg_session_id, 50);
-                        }
-                        let response = channels::run_channel_agent(
-                            &app_handle,
-                            "telegram",
-                            "You are chatting via Telegram. The user is messaging you from their phone. \
-                             Keep responses concise and mobile-friendly. Use Markdown formatting supported by Telegram \
-                             (bold, italic, code, links). Avoid very long responses unless explicitly asked.",
-                            &text,
-                            &user_id.to_string(),
-                            agent_id_str,
-                            current_config.allow_dangerous_tools,
-                        ).await;
-
-                        match response {
-                            Ok(reply) => {
-                                if !reply.is_empty() {
-                                    let _ = tg_send_message(
-                                        &client,
-                                        &token,
-                                        chat_id,
-                                        &reply,
-                                        Some(msg.message_id),
-                                    )
-                                    .await;
+                            let payload = serde_json::to_string(&inbound).unwrap_or_default();
+                            match st.store.journal_inbound(&journal_id, "telegram", &payload) {
+                                Ok(false) => {
+                                    debug!("[telegram] Skipping redelivered update {}", journal_id);
+                                    continue;
                                 }
-                            }
-                            Err(e) => {
-                                error!("[telegram] Agent error for user {}: {}", user_id, e);
-                                let _ = tg_send_message(
-                                    &client,
-                                    &token,
-                                    chat_id,
-                                    &format!("Error: {}", e),
-                                    Some(msg.message_id),
-                                )
-                                .await;
+                                Ok(true) => {}
+                                Err(e) => warn!("[telegram] Journal write failed: {}", e),
                             }
                         }
+
+                        process_journaled(
+                            &app_handle,
+                            &client,
+                            &token,
+                            &current_config,
+                            &journal_id,
+                            &inbound,
+                        )
+                        .await;
                     }
                 }
             }
@@ -3,7 +3,7 @@
 // without bloating those files.
 
 use super::{RunCollector, TelemetryTurnSummary};
-use crate::engine::sessions::SessionStore;
+use crate::engine::sessions::{DeferredWrite, SessionStore};
 use log::info;
 use std::time::Instant;
 
@@ -35,30 +35,28 @@ impl ToolTimer {
     }
 }
 
-/// Record a turn's telemetry summary into the SessionStore.
+/// Record a turn's telemetry summary into the SessionStore. The row is
+/// batched with other non-critical writes (see sessions::write_queue).
 pub fn persist_summary(store: &SessionStore, summary: &TelemetryTurnSummary) {
-    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
     let model_str = summary.model.as_deref().unwrap_or("unknown");
-    if let Err(e) = store.record_metric(
-        &date,
-        &summary.session_id,
-        model_str,
-        summary.input_tokens,
-        summary.output_tokens,
-        summary.cost_usd,
-        summary.tool_calls,
-        summary.tool_duration_ms,
-        summary.llm_duration_ms,
-        summary.total_duration_ms,
-        summary.rounds,
-    ) {
-        log::warn!("[telemetry] Failed to persist turn metrics: {}", e);
-    } else {
-        info!(
-            "[telemetry] Recorded turn metrics: session={} model={} cost=${:.4}",
-            summary.session_id, model_str, summary.cost_usd
-        );
-    }
+    store.defer_write(DeferredWrite::Metric {
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        session_id: summary.session_id.clone(),
+        run_id: Some(summary.run_id.clone()),
+        model: model_str.to_string(),
+        input_tokens: summary.input_tokens,
+        output_tokens: summary.output_tokens,
+        cost_usd: summary.cost_usd,
+        tool_calls: summary.tool_calls,
+        tool_duration_ms: summary.tool_duration_ms,
+        llm_duration_ms: summary.llm_duration_ms,
+        total_duration_ms: summary.total_duration_ms,
+        rounds: summary.rounds,
+    });
+    info!(
+        "[telemetry] Recorded turn metrics: session={} model={} cost=${:.4}",
+        summary.session_id, model_str, summary.cost_usd
+    );
 }
 
 /// Emit a telemetry summary as a Tauri event for the frontend Inspector.
@@ -78,16 +78,20 @@ pub fn tool_domain(name: &str) -> &'static str {
 pub fn domain_summaries() -> Vec<(&'static str, &'static str, &'static str)> {
     // (domain_id, icon, description)
     vec![
-        ("system", "terminal", "Execute shell commands"),
+        (
+            "system",
+            "terminal",
+            "Execute shell commands, capture the user's screen",
+        ),
         (
             "filesystem",
             "folder",
-            "Read, write, delete, list files in your workspace",
+            "Read, write, delete, list files in your workspace, zip and unzip archives",
         ),
         (
             "web",
             "language",
-            "Search the web, browse pages, take screenshots, fetch URLs",
+            "Search the web, browse pages, take screenshots, fetch URLs, download files",
         ),
         (
             "identity",
@@ -157,6 +161,11 @@ pub fn domain_summaries() -> Vec<(&'static str, &'static str, &'static str)> {
             "forum",
             "Discourse forum management — topics, posts, categories, users, search, tags, badges, groups, site settings, backups",
         ),
+        (
+            "social",
+            "campaign",
+            "Social media — post, reply and search on Mastodon, Bluesky and X; Nostr notes, reactions and profiles (posts are reviewed in the outbox)",
+        ),
         (
             "trello",
             "view_kanban",
@@ -208,7 +217,7 @@ impl ToolIndex {
 
     /// Populate the index by embedding all tool definitions.
     /// Called once on startup (or lazily on first request_tools call).
-    /// Uses the existing Ollama embedding client (~50ms per tool, ~4s total).
+    /// Tool descriptions are embedded in batches (cached across rebuilds).
     pub async fn build(&mut self, all_tools: &[ToolDefinition], client: &EmbeddingClient) {
         info!(
             "[tool-index] Building tool index for {} definitions...",
@@ -218,62 +227,36 @@ impl ToolIndex {
 
         let mut success = 0;
         let mut failed = 0;
-        let mut consecutive_failures = 0;
-        let mut embed_disabled = false;
-
-        for tool in all_tools {
-            // Circuit breaker: if we've had 3+ consecutive failures, skip embedding
-            // for the rest (the provider likely doesn't support embeddings at all).
-            if embed_disabled {
-                self.tools.push(IndexedTool {
-                    definition: tool.clone(),
-                    embedding: Vec::new(),
-                    domain: tool_domain(&tool.function.name).to_string(),
-                });
-                failed += 1;
-                continue;
-            }
 
-            let text = format!("{}: {}", tool.function.name, tool.function.description);
-            match client.embed(&text).await {
+        let texts: Vec<String> = all_tools
+            .iter()
+            .map(|t| format!("{}: {}", t.function.name, t.function.description))
+            .collect();
+        let vectors = client.embed_batch(&texts).await;
+        for (tool, result) in all_tools.iter().zip(vectors) {
+            let embedding = match result {
                 Ok(embedding) => {
-                    self.tools.push(IndexedTool {
-                        definition: tool.clone(),
-                        embedding,
-                        domain: tool_domain(&tool.function.name).to_string(),
-                    });
                     success += 1;
-                    consecutive_failures = 0;
+                    embedding
                 }
                 Err(e) => {
-                    let err_str = e.to_string();
                     if failed == 0 {
                         // Only log the full error once
                         warn!(
                             "[tool-index] Failed to embed tool '{}': {}",
-                            tool.function.name, err_str
+                            tool.function.name, e
                         );
                     }
-                    // Still add the tool without embedding — it can be found by name or domain
-                    self.tools.push(IndexedTool {
-                        definition: tool.clone(),
-                        embedding: Vec::new(),
-                        domain: tool_domain(&tool.function.name).to_string(),
-                    });
                     failed += 1;
-                    consecutive_failures += 1;
-
-                    // After 3 consecutive failures, give up embedding entirely
-                    if consecutive_failures >= 3 {
-                        warn!(
-                            "[tool-index] Embedding unavailable after {} consecutive failures — skipping remaining {} tools",
-                            consecutive_failures,
-                            all_tools.len() - success - failed
-                        );
-                        embed_disabled = true;
-                    }
+                    // Still add the tool without embedding — it can be found by name or domain
+                    Vec::new()
                 }
-            }
+            };
+            self.tools.push(IndexedTool {
+                definition: tool.clone(),
+                embedding,
+                domain: tool_domain(&tool.function.name).to_string(),
+            });
         }
 
         self.ready = true;
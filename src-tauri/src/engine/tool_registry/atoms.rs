@@ -61,7 +61,8 @@ pub struct ToolEmbeddingRecord {
 
 /// Which search tier is currently active for tool discovery.
 /// Lower numeric value = better tier (used in promote_tier_if_available).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum SearchTier {
     /// Tier 1: Local Ollama embeddings (~50ms, zero cost, full privacy)
@@ -88,8 +89,104 @@ pub const DEFAULT_TOP_K: usize = 6;
 /// Maximum number of tools to return after domain expansion.
 pub const MAX_RESULTS: usize = 30;
 
-/// How many consecutive embedding failures before circuit-breaking.
-pub const EMBED_CIRCUIT_BREAKER_THRESHOLD: u32 = 3;
+/// Upper bound for user-configured `top_k` / `max_results`.
+pub const MAX_CONFIGURABLE_RESULTS: usize = 200;
+
+/// Default number of contextually relevant non-core tools kept per round
+/// when automatic pruning is enabled.
+pub const DEFAULT_PRUNE_TOP_K: usize = 12;
+
+// ── Relevance Tuning ───────────────────────────────────────────────────────
+
+/// User-tunable Tool RAG settings, persisted under the `tool_rag_config` key.
+///
+/// Users with hundreds of n8n/MCP tools need control over how many dynamic
+/// tools a single `request_tools` call injects into the next round.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolRagConfig {
+    /// Direct hits taken from the ranked list (before domain expansion).
+    pub top_k: usize,
+    /// Hard cap on tools injected per `request_tools` call (after expansion).
+    pub max_results: usize,
+    /// Minimum cosine similarity for a tool to count as a direct hit.
+    pub min_relevance: f64,
+    /// Best-hit score at which all sibling tools of a domain are included.
+    pub domain_expand_threshold: f64,
+    /// Drop contextually irrelevant tools from each agent round. Off by
+    /// default: it can hide a tool the model would have needed.
+    pub auto_prune: bool,
+    /// Non-core tools kept per round when `auto_prune` is on.
+    pub prune_top_k: usize,
+}
+
+impl Default for ToolRagConfig {
+    fn default() -> Self {
+        ToolRagConfig {
+            top_k: DEFAULT_TOP_K,
+            max_results: MAX_RESULTS,
+            min_relevance: MIN_RELEVANCE,
+            domain_expand_threshold: DOMAIN_EXPAND_STRONG,
+            auto_prune: false,
+            prune_top_k: DEFAULT_PRUNE_TOP_K,
+        }
+    }
+}
+
+impl ToolRagConfig {
+    /// Clamp all fields into a usable range. `max_results` never drops below `top_k`.
+    pub fn sanitized(mut self) -> Self {
+        self.top_k = self.top_k.clamp(1, MAX_CONFIGURABLE_RESULTS);
+        self.max_results = self.max_results.clamp(self.top_k, MAX_CONFIGURABLE_RESULTS);
+        self.min_relevance = self.min_relevance.clamp(0.0, 1.0);
+        self.domain_expand_threshold = self.domain_expand_threshold.clamp(0.0, 1.0);
+        self.prune_top_k = self.prune_top_k.clamp(1, MAX_CONFIGURABLE_RESULTS);
+        self
+    }
+}
+
+// ── Diagnostics ────────────────────────────────────────────────────────────
+
+/// One scored candidate in a `engine_toolrag_query_debug` report.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolRagCandidate {
+    pub tool_name: String,
+    pub domain: String,
+    /// Cosine similarity to the query (None when the tool or query has no embedding).
+    pub vector_score: Option<f64>,
+    /// BM25 keyword score against "{name}: {description}".
+    pub bm25_score: f64,
+    /// Whether the tool is in the set a real `request_tools` call would load.
+    pub selected: bool,
+}
+
+/// Full scoring breakdown for a single query against the tool index.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolRagDebugReport {
+    pub query: String,
+    /// Active search tier at the time of the query.
+    pub tier: SearchTier,
+    /// Domain chosen by the centroid stage of hierarchical search, if any.
+    pub target_domain: Option<String>,
+    pub target_domain_score: Option<f64>,
+    /// Tools in the persistent index / tools that carry an embedding.
+    pub indexed_tools: usize,
+    pub embedded_tools: usize,
+    /// Ranked candidates (vector score first, then BM25).
+    pub candidates: Vec<ToolRagCandidate>,
+    /// Tool names a real `request_tools` call would load, in order.
+    pub selected: Vec<String>,
+    pub config: ToolRagConfig,
+}
+
+/// Outcome of a full index rebuild.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolRagRebuildReport {
+    pub total: usize,
+    pub embedded: usize,
+    pub failed: usize,
+    pub tier: SearchTier,
+}
 
 // ── BM25 Scoring ──────────────────────────────────────────────────────────
 
@@ -178,16 +275,39 @@ pub fn classify_domain_by_keywords(query: &str) -> Vec<&'static str> {
         (&["telegram"], "messaging"),
         (&["discord"], "discord"),
         (&["discourse", "forum"], "discourse"),
+        (
+            &[
+                "mastodon",
+                "bluesky",
+                "tweet",
+                "social media",
+                "toot",
+                "nostr",
+                "zap",
+            ],
+            "social",
+        ),
         (&["trello", "kanban", "board", "card"], "trello"),
         (
             &["github", "issue", "pull request", "pr ", "repo"],
             "github",
         ),
         (
-            &["file", "folder", "directory", "read file", "write file"],
+            &[
+                "file",
+                "folder",
+                "directory",
+                "read file",
+                "write file",
+                "zip",
+                "archive",
+            ],
             "filesystem",
         ),
-        (&["terminal", "shell", "command", "exec", "bash"], "system"),
+        (
+            &["terminal", "shell", "command", "exec", "bash", "my screen"],
+            "system",
+        ),
         (
             &[
                 "web",
@@ -197,6 +317,7 @@ pub fn classify_domain_by_keywords(query: &str) -> Vec<&'static str> {
                 "http",
                 "url",
                 "screenshot",
+                "download",
             ],
             "web",
         ),
@@ -262,6 +383,25 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
     }
 }
 
+// ── Context Pruning ────────────────────────────────────────────────────────
+
+/// Rank tools for per-round pruning and keep the best `top_k`.
+///
+/// Each entry is `(name, vector_score, bm25_score)`. Tools with a vector
+/// score rank ahead of those without; BM25 breaks ties and orders the rest.
+/// Tools with neither signal are never selected.
+pub fn select_top_tools(mut scored: Vec<(String, Option<f64>, f64)>, top_k: usize) -> Vec<String> {
+    scored.retain(|(_, v, b)| v.is_some() || *b > 0.0);
+    scored.sort_by(|a, b| {
+        b.1.unwrap_or(f64::MIN)
+            .partial_cmp(&a.1.unwrap_or(f64::MIN))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    scored.truncate(top_k);
+    scored.into_iter().map(|(name, _, _)| name).collect()
+}
+
 /// Embedding bytes ↔ f32 vec conversion (same as sessions/embedding.rs).
 pub fn f32_vec_to_bytes(vec: &[f32]) -> Vec<u8> {
     vec.iter().flat_map(|f| f.to_le_bytes()).collect()
@@ -295,6 +435,81 @@ mod tests {
         assert_eq!(ToolSource::parse("unknown"), ToolSource::Community);
     }
 
+    // ── ToolRagConfig ──────────────────────────────────────────────
+
+    #[test]
+    fn tool_rag_config_defaults_match_constants() {
+        let cfg = ToolRagConfig::default();
+        assert_eq!(cfg.top_k, DEFAULT_TOP_K);
+        assert_eq!(cfg.max_results, MAX_RESULTS);
+        assert_eq!(cfg.min_relevance, MIN_RELEVANCE);
+        assert_eq!(cfg.domain_expand_threshold, DOMAIN_EXPAND_STRONG);
+        assert!(!cfg.auto_prune);
+        assert_eq!(cfg.prune_top_k, DEFAULT_PRUNE_TOP_K);
+    }
+
+    #[test]
+    fn tool_rag_config_sanitized_clamps() {
+        let cfg = ToolRagConfig {
+            top_k: 0,
+            max_results: 10_000,
+            min_relevance: 1.5,
+            domain_expand_threshold: -0.2,
+            auto_prune: true,
+            prune_top_k: 0,
+        }
+        .sanitized();
+        assert_eq!(cfg.top_k, 1);
+        assert_eq!(cfg.max_results, MAX_CONFIGURABLE_RESULTS);
+        assert_eq!(cfg.min_relevance, 1.0);
+        assert_eq!(cfg.domain_expand_threshold, 0.0);
+        assert_eq!(cfg.prune_top_k, 1);
+    }
+
+    #[test]
+    fn tool_rag_config_max_results_at_least_top_k() {
+        let cfg = ToolRagConfig {
+            top_k: 12,
+            max_results: 4,
+            ..Default::default()
+        }
+        .sanitized();
+        assert_eq!(cfg.max_results, 12);
+    }
+
+    #[test]
+    fn tool_rag_config_partial_json_uses_defaults() {
+        let cfg: ToolRagConfig = serde_json::from_str(r#"{"top_k": 10}"#).unwrap();
+        assert_eq!(cfg.top_k, 10);
+        assert_eq!(cfg.max_results, MAX_RESULTS);
+    }
+
+    // ── Context Pruning ────────────────────────────────────────────
+
+    #[test]
+    fn select_top_tools_prefers_vector_scores() {
+        let scored = vec![
+            ("bm25_only".to_string(), None, 5.0),
+            ("weak".to_string(), Some(0.2), 0.0),
+            ("strong".to_string(), Some(0.9), 0.0),
+            ("unrelated".to_string(), None, 0.0),
+        ];
+        assert_eq!(
+            select_top_tools(scored, 10),
+            vec!["strong", "weak", "bm25_only"]
+        );
+    }
+
+    #[test]
+    fn select_top_tools_truncates() {
+        let scored = vec![
+            ("a".to_string(), None, 1.0),
+            ("b".to_string(), None, 3.0),
+            ("c".to_string(), None, 2.0),
+        ];
+        assert_eq!(select_top_tools(scored, 2), vec!["b", "c"]);
+    }
+
     // ── BM25 ───────────────────────────────────────────────────────
 
     #[test]
@@ -14,6 +14,7 @@ pub mod molecules;
 // Re-export primary types
 pub use atoms::{
     bm25_score, bm25_tokenize, classify_domain_by_keywords, cosine_similarity, SearchTier,
-    ToolEmbeddingRecord, ToolSource,
+    ToolEmbeddingRecord, ToolRagCandidate, ToolRagConfig, ToolRagDebugReport, ToolRagRebuildReport,
+    ToolSource,
 };
 pub use molecules::{PersistentToolRegistry, ToolSearchResult};
@@ -13,8 +13,8 @@
 // ─────────────────────────────────────────────────────────────────────────────
 
 use super::atoms::{
-    self, bytes_to_f32_vec, f32_vec_to_bytes, SearchTier, ToolEmbeddingRecord, ToolSource,
-    DOMAIN_EXPAND_STRONG, MAX_RESULTS, MIN_RELEVANCE,
+    self, bytes_to_f32_vec, f32_vec_to_bytes, SearchTier, ToolEmbeddingRecord, ToolRagCandidate,
+    ToolRagConfig, ToolRagDebugReport, ToolRagRebuildReport, ToolSource,
 };
 use crate::atoms::error::EngineResult;
 use crate::atoms::types::ToolDefinition;
@@ -38,6 +38,8 @@ pub struct PersistentToolRegistry {
     /// Cached domain centroids for Tier 1/2 hierarchical search.
     /// Domain name → centroid embedding.
     domain_centroids: HashMap<String, Vec<f32>>,
+    /// User-tunable relevance thresholds and result caps.
+    config: ToolRagConfig,
 }
 
 impl Default for PersistentToolRegistry {
@@ -48,9 +50,15 @@ impl Default for PersistentToolRegistry {
 
 impl PersistentToolRegistry {
     pub fn new() -> Self {
+        Self::with_config(ToolRagConfig::default())
+    }
+
+    /// Create a registry with user-tuned relevance settings.
+    pub fn with_config(config: ToolRagConfig) -> Self {
         PersistentToolRegistry {
             current_tier: SearchTier::DomainKeyword, // start at lowest, promote on success
             domain_centroids: HashMap::new(),
+            config: config.sanitized(),
         }
     }
 
@@ -59,6 +67,16 @@ impl PersistentToolRegistry {
         self.current_tier
     }
 
+    /// Current relevance tuning.
+    pub fn config(&self) -> &ToolRagConfig {
+        &self.config
+    }
+
+    /// Replace the relevance tuning (takes effect on the next search).
+    pub fn set_config(&mut self, config: ToolRagConfig) {
+        self.config = config.sanitized();
+    }
+
     // ── Save & Load ────────────────────────────────────────────────────
 
     /// Save a tool embedding to the persistent store.
@@ -164,6 +182,47 @@ impl PersistentToolRegistry {
         Ok(count)
     }
 
+    /// Delete every cached embedding. Returns the number of rows removed.
+    pub fn clear(conn: &Connection) -> EngineResult<usize> {
+        let removed = conn.execute("DELETE FROM tool_embeddings", [])?;
+        Ok(removed)
+    }
+
+    /// Register tools that are not yet cached without computing embeddings,
+    /// so BM25 (Tier 3) and domain keyword (Tier 4) search still work.
+    ///
+    /// Returns the number of newly registered tools.
+    pub fn register_without_embeddings(
+        tools: &[ToolDefinition],
+        conn: &Arc<Mutex<Connection>>,
+    ) -> usize {
+        let cached_names = {
+            let db = conn.lock();
+            Self::cached_tool_names(&db).unwrap_or_default()
+        };
+        let now = chrono::Utc::now().timestamp();
+        let mut registered = 0;
+        let db = conn.lock();
+        for tool in tools {
+            let name = &tool.function.name;
+            if cached_names.contains(name) {
+                continue;
+            }
+            let record = ToolEmbeddingRecord {
+                tool_name: name.clone(),
+                description: tool.function.description.clone(),
+                embedding: Vec::new(),
+                domain: tool_domain(name).to_string(),
+                source: classify_tool_source(name),
+                updated_at: now,
+            };
+            if Self::save_embedding(&db, &record).is_ok() {
+                registered += 1;
+            }
+        }
+        registered
+    }
+
     // ── Incremental Indexing ───────────────────────────────────────────
 
     /// Incrementally index tools: only embed those not already cached.
@@ -184,69 +243,48 @@ impl PersistentToolRegistry {
         let mut embedded = 0;
         let mut skipped = 0;
         let mut failed = 0;
-        let mut consecutive_failures: u32 = 0;
 
+        let mut pending: Vec<&ToolDefinition> = Vec::new();
         for tool in tools {
-            let name = &tool.function.name;
-
             // Skip if already cached
-            if cached_names.contains(name) {
+            if cached_names.contains(&tool.function.name) {
                 skipped += 1;
-                continue;
-            }
-
-            // Circuit breaker: too many consecutive failures
-            if consecutive_failures >= atoms::EMBED_CIRCUIT_BREAKER_THRESHOLD {
-                // Save without embedding — still searchable by BM25/keyword
-                let record = ToolEmbeddingRecord {
-                    tool_name: name.clone(),
-                    description: tool.function.description.clone(),
-                    embedding: Vec::new(),
-                    domain: tool_domain(name).to_string(),
-                    source: classify_tool_source(name),
-                    updated_at: now,
-                };
-                let db = conn.lock();
-                Self::save_embedding(&db, &record).ok();
-                failed += 1;
-                continue;
+            } else {
+                pending.push(tool);
             }
+        }
 
-            let text = format!("{}: {}", name, tool.function.description);
-            match client.embed(&text).await {
+        let texts: Vec<String> = pending
+            .iter()
+            .map(|t| format!("{}: {}", t.function.name, t.function.description))
+            .collect();
+        let vectors = client.embed_batch(&texts).await;
+        for (tool, result) in pending.into_iter().zip(vectors) {
+            let name = &tool.function.name;
+            // Failed tools are saved without embedding — still searchable by BM25/keyword
+            let embedding = match result {
                 Ok(embedding) => {
-                    let record = ToolEmbeddingRecord {
-                        tool_name: name.clone(),
-                        description: tool.function.description.clone(),
-                        embedding,
-                        domain: tool_domain(name).to_string(),
-                        source: classify_tool_source(name),
-                        updated_at: now,
-                    };
-                    let db = conn.lock();
-                    Self::save_embedding(&db, &record).ok();
                     embedded += 1;
-                    consecutive_failures = 0;
+                    embedding
                 }
                 Err(e) => {
                     if failed == 0 {
                         warn!("[tool-registry] Embedding failed for '{}': {}", name, e);
                     }
-                    // Save without embedding
-                    let record = ToolEmbeddingRecord {
-                        tool_name: name.clone(),
-                        description: tool.function.description.clone(),
-                        embedding: Vec::new(),
-                        domain: tool_domain(name).to_string(),
-                        source: classify_tool_source(name),
-                        updated_at: now,
-                    };
-                    let db = conn.lock();
-                    Self::save_embedding(&db, &record).ok();
                     failed += 1;
-                    consecutive_failures += 1;
+                    Vec::new()
                 }
-            }
+            };
+            let record = ToolEmbeddingRecord {
+                tool_name: name.clone(),
+                description: tool.function.description.clone(),
+                embedding,
+                domain: tool_domain(name).to_string(),
+                source: classify_tool_source(name),
+                updated_at: now,
+            };
+            let db = conn.lock();
+            Self::save_embedding(&db, &record).ok();
         }
 
         // Update search tier based on results
@@ -271,6 +309,44 @@ impl PersistentToolRegistry {
         (embedded, skipped, failed)
     }
 
+    /// Drop the whole index and re-embed every tool from scratch.
+    ///
+    /// Without an embedding client the tools are re-registered for BM25 and
+    /// domain keyword search only.
+    pub async fn rebuild(
+        &mut self,
+        tools: &[ToolDefinition],
+        client: Option<&EmbeddingClient>,
+        conn: &Arc<Mutex<Connection>>,
+    ) -> EngineResult<ToolRagRebuildReport> {
+        let removed = {
+            let db = conn.lock();
+            Self::clear(&db)?
+        };
+        self.domain_centroids.clear();
+        self.current_tier = SearchTier::DomainKeyword;
+        info!(
+            "[tool-registry] Rebuilding index: cleared {} cached tools, indexing {}",
+            removed,
+            tools.len()
+        );
+
+        let (embedded, failed) = match client {
+            Some(c) => {
+                let (embedded, _skipped, failed) = self.incremental_index(tools, c, conn).await;
+                (embedded, failed)
+            }
+            None => (0, Self::register_without_embeddings(tools, conn)),
+        };
+
+        Ok(ToolRagRebuildReport {
+            total: tools.len(),
+            embedded,
+            failed,
+            tier: self.current_tier,
+        })
+    }
+
     // ── Hierarchical Search ────────────────────────────────────────────
 
     /// Search for tools using the current best-available tier.
@@ -335,7 +411,7 @@ impl PersistentToolRegistry {
                     best_domain = Some(domain.clone());
                 }
             }
-            if best_score >= MIN_RELEVANCE {
+            if best_score >= self.config.min_relevance {
                 info!(
                     "[tool-registry] Hierarchical: top domain '{}' (score={:.3})",
                     best_domain.as_deref().unwrap_or("?"),
@@ -388,7 +464,7 @@ impl PersistentToolRegistry {
         let mut domain_hits: HashMap<String, u32> = HashMap::new();
 
         for (result, score) in scored.iter().take(top_k) {
-            if *score >= MIN_RELEVANCE {
+            if *score >= self.config.min_relevance {
                 results.push(result.clone());
                 let best = domain_best.entry(result.domain.clone()).or_insert(0.0);
                 if *score > *best {
@@ -401,7 +477,7 @@ impl PersistentToolRegistry {
         // Domain expansion: include sibling tools for strong matches
         for (domain, best_score) in &domain_best {
             let hits = domain_hits.get(domain).copied().unwrap_or(0);
-            if *best_score >= DOMAIN_EXPAND_STRONG || hits >= 2 {
+            if *best_score >= self.config.domain_expand_threshold || hits >= 2 {
                 matched_domains.insert(domain.clone());
             }
         }
@@ -426,7 +502,7 @@ impl PersistentToolRegistry {
         }
 
         // Cap results
-        results.truncate(MAX_RESULTS);
+        results.truncate(self.config.max_results);
         Ok(results)
     }
 
@@ -446,37 +522,10 @@ impl PersistentToolRegistry {
             return Vec::new();
         }
 
-        let query_tokens = atoms::bm25_tokenize(query);
-        if query_tokens.is_empty() {
-            return Vec::new();
-        }
-
-        // Build document frequency map
-        let doc_texts: Vec<Vec<String>> = records
-            .iter()
-            .map(|r| atoms::bm25_tokenize(&format!("{}: {}", r.tool_name, r.description)))
-            .collect();
-
-        let avg_doc_len =
-            doc_texts.iter().map(|d| d.len()).sum::<usize>() as f64 / doc_texts.len() as f64;
-
-        let mut doc_freq: HashMap<String, usize> = HashMap::new();
-        for doc in &doc_texts {
-            let unique: HashSet<&String> = doc.iter().collect();
-            for term in unique {
-                *doc_freq.entry(term.clone()).or_insert(0) += 1;
-            }
-        }
-
         // Score each document
-        let mut scored: Vec<(usize, f64)> = doc_texts
-            .iter()
+        let mut scored: Vec<(usize, f64)> = bm25_scores(&records, query)
+            .into_iter()
             .enumerate()
-            .map(|(i, doc)| {
-                let score =
-                    atoms::bm25_score(doc, &query_tokens, avg_doc_len, records.len(), &doc_freq);
-                (i, score)
-            })
             .filter(|(_, score)| *score > 0.0)
             .collect();
 
@@ -528,10 +577,151 @@ impl PersistentToolRegistry {
             }
         }
 
-        results.truncate(MAX_RESULTS);
+        results.truncate(self.config.max_results);
         results
     }
 
+    // ── Diagnostics ────────────────────────────────────────────────────
+
+    /// Score a query against every indexed tool and report how the real
+    /// search would rank and select them. Used by `engine_toolrag_query_debug`.
+    pub async fn debug_search(
+        &self,
+        query: &str,
+        limit: usize,
+        client: Option<&EmbeddingClient>,
+        conn: &Arc<Mutex<Connection>>,
+    ) -> EngineResult<ToolRagDebugReport> {
+        let records = {
+            let db = conn.lock();
+            Self::load_all(&db)?
+        };
+        let bm25 = bm25_scores(&records, query);
+
+        let mut vector: Vec<Option<f64>> = vec![None; records.len()];
+        let mut target_domain = None;
+        let mut target_domain_score = None;
+        if let Some(c) = client {
+            match c.embed(query).await {
+                Ok(query_vec) => {
+                    for (i, r) in records.iter().enumerate() {
+                        if !r.embedding.is_empty() {
+                            vector[i] = Some(atoms::cosine_similarity(&query_vec, &r.embedding));
+                        }
+                    }
+                    for (domain, centroid) in &self.domain_centroids {
+                        let sim = atoms::cosine_similarity(&query_vec, centroid);
+                        if target_domain_score.is_none_or(|best| sim > best) {
+                            target_domain = Some(domain.clone());
+                            target_domain_score = Some(sim);
+                        }
+                    }
+                }
+                Err(e) => warn!("[tool-registry] Debug query embedding failed: {}", e),
+            }
+        }
+
+        let selected: Vec<String> = self
+            .search(query, self.config.top_k, client, conn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.tool_name)
+            .collect();
+
+        let mut candidates: Vec<ToolRagCandidate> = records
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| vector[*i].is_some() || bm25[*i] > 0.0)
+            .map(|(i, r)| ToolRagCandidate {
+                tool_name: r.tool_name.clone(),
+                domain: r.domain.clone(),
+                vector_score: vector[i],
+                bm25_score: bm25[i],
+                selected: selected.contains(&r.tool_name),
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.vector_score
+                .unwrap_or(f64::MIN)
+                .partial_cmp(&a.vector_score.unwrap_or(f64::MIN))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(
+                    b.bm25_score
+                        .partial_cmp(&a.bm25_score)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+        });
+        candidates.truncate(limit);
+
+        Ok(ToolRagDebugReport {
+            query: query.to_string(),
+            tier: self.current_tier,
+            target_domain,
+            target_domain_score,
+            indexed_tools: records.len(),
+            embedded_tools: records.iter().filter(|r| !r.embedding.is_empty()).count(),
+            candidates,
+            selected,
+            config: self.config.clone(),
+        })
+    }
+
+    // ── Context Pruning ────────────────────────────────────────────────
+
+    /// Rank `candidates` against the current conversation context and keep
+    /// the `top_k` most relevant. Uses cached tool embeddings (one embed
+    /// call for the context) and falls back to BM25 when embeddings are
+    /// unavailable. Candidates missing from the index can't be scored and
+    /// are always kept. `index` is the embedding cache from `load_all`,
+    /// which the caller reads off the async runtime; no registry lock is
+    /// needed, so the embed call doesn't hold one.
+    pub async fn rank_for_context(
+        context: &str,
+        candidates: &[String],
+        client: Option<&EmbeddingClient>,
+        index: Vec<ToolEmbeddingRecord>,
+        top_k: usize,
+    ) -> EngineResult<Vec<String>> {
+        let wanted: HashSet<&str> = candidates.iter().map(|s| s.as_str()).collect();
+        let records: Vec<ToolEmbeddingRecord> = index
+            .into_iter()
+            .filter(|r| wanted.contains(r.tool_name.as_str()))
+            .collect();
+        let indexed: HashSet<&str> = records.iter().map(|r| r.tool_name.as_str()).collect();
+        let unindexed: Vec<String> = candidates
+            .iter()
+            .filter(|c| !indexed.contains(c.as_str()))
+            .cloned()
+            .collect();
+        let bm25 = bm25_scores(&records, context);
+
+        let mut context_vec = None;
+        if let Some(c) = client {
+            if records.iter().any(|r| !r.embedding.is_empty()) {
+                match c.embed(context).await {
+                    Ok(v) => context_vec = Some(v),
+                    Err(e) => warn!("[tool-registry] Context embedding failed: {}", e),
+                }
+            }
+        }
+
+        let scored = records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let vector = context_vec
+                    .as_ref()
+                    .filter(|_| !r.embedding.is_empty())
+                    .map(|v| atoms::cosine_similarity(v, &r.embedding));
+                (r.tool_name.clone(), vector, bm25[i])
+            })
+            .collect();
+        let mut kept = atoms::select_top_tools(scored, top_k);
+        kept.extend(unindexed);
+        Ok(kept)
+    }
+
     // ── Domain Centroids ───────────────────────────────────────────────
 
     /// Rebuild domain centroids from cached embeddings.
@@ -605,6 +795,36 @@ pub struct ToolSearchResult {
 
 // ── Helpers ────────────────────────────────────────────────────────────────
 
+/// BM25 score of every record against the query (same order as `records`).
+fn bm25_scores(records: &[ToolEmbeddingRecord], query: &str) -> Vec<f64> {
+    let query_tokens = atoms::bm25_tokenize(query);
+    if records.is_empty() || query_tokens.is_empty() {
+        return vec![0.0; records.len()];
+    }
+
+    let doc_texts: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| atoms::bm25_tokenize(&format!("{}: {}", r.tool_name, r.description)))
+        .collect();
+
+    let avg_doc_len =
+        doc_texts.iter().map(|d| d.len()).sum::<usize>() as f64 / doc_texts.len() as f64;
+
+    // Build document frequency map
+    let mut doc_freq: HashMap<String, usize> = HashMap::new();
+    for doc in &doc_texts {
+        let unique: HashSet<&String> = doc.iter().collect();
+        for term in unique {
+            *doc_freq.entry(term.clone()).or_insert(0) += 1;
+        }
+    }
+
+    doc_texts
+        .iter()
+        .map(|doc| atoms::bm25_score(doc, &query_tokens, avg_doc_len, records.len(), &doc_freq))
+        .collect()
+}
+
 /// Classify a tool name into its source category.
 fn classify_tool_source(name: &str) -> ToolSource {
     if name.starts_with("mcp_") {
@@ -871,4 +1091,159 @@ mod tests {
         let web_centroid = registry.domain_centroids.get("web").unwrap();
         assert_eq!(web_centroid, &vec![1.0f32, 1.0]);
     }
+
+    fn make_tool(name: &str, desc: &str) -> ToolDefinition {
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: crate::atoms::types::FunctionDefinition {
+                name: name.into(),
+                description: desc.into(),
+                parameters: serde_json::json!({"type": "object"}),
+            },
+        }
+    }
+
+    #[test]
+    fn clear_removes_all_embeddings() {
+        let conn = setup_db();
+        let db = conn.lock();
+        PersistentToolRegistry::save_embedding(&db, &make_record("a", "A", "system", vec![1.0]))
+            .unwrap();
+        PersistentToolRegistry::save_embedding(&db, &make_record("b", "B", "email", vec![2.0]))
+            .unwrap();
+
+        assert_eq!(PersistentToolRegistry::clear(&db).unwrap(), 2);
+        assert_eq!(PersistentToolRegistry::count(&db).unwrap(), 0);
+    }
+
+    #[test]
+    fn register_without_embeddings_skips_cached() {
+        let conn = setup_db();
+        {
+            let db = conn.lock();
+            PersistentToolRegistry::save_embedding(
+                &db,
+                &make_record("email_send", "Send an email", "email", vec![1.0]),
+            )
+            .unwrap();
+        }
+
+        let tools = vec![
+            make_tool("email_send", "Send an email"),
+            make_tool("web_search", "Search the web"),
+        ];
+        let registered = PersistentToolRegistry::register_without_embeddings(&tools, &conn);
+        assert_eq!(registered, 1);
+
+        let db = conn.lock();
+        let all = PersistentToolRegistry::load_all(&db).unwrap();
+        let email = all.iter().find(|r| r.tool_name == "email_send").unwrap();
+        assert_eq!(email.embedding, vec![1.0], "cached embedding must be kept");
+        assert!(all.iter().any(|r| r.tool_name == "web_search"));
+    }
+
+    #[tokio::test]
+    async fn rebuild_without_client_reregisters_all() {
+        let conn = setup_db();
+        {
+            let db = conn.lock();
+            PersistentToolRegistry::save_embedding(
+                &db,
+                &make_record("stale_tool", "No longer exists", "other", vec![1.0]),
+            )
+            .unwrap();
+        }
+
+        let tools = vec![make_tool("email_send", "Send an email")];
+        let mut registry = PersistentToolRegistry::new();
+        let report = registry.rebuild(&tools, None, &conn).await.unwrap();
+
+        assert_eq!(report.total, 1);
+        assert_eq!(report.embedded, 0);
+        let db = conn.lock();
+        let names = PersistentToolRegistry::cached_tool_names(&db).unwrap();
+        assert!(names.contains("email_send"));
+        assert!(!names.contains("stale_tool"));
+    }
+
+    #[tokio::test]
+    async fn debug_search_reports_bm25_candidates() {
+        let conn = setup_db();
+        {
+            let db = conn.lock();
+            PersistentToolRegistry::save_embedding(
+                &db,
+                &make_record("email_send", "Send an email message", "email", vec![]),
+            )
+            .unwrap();
+            PersistentToolRegistry::save_embedding(
+                &db,
+                &make_record("web_search", "Search the web", "web", vec![]),
+            )
+            .unwrap();
+        }
+
+        let registry = PersistentToolRegistry::new();
+        let report = registry
+            .debug_search("send email", 10, None, &conn)
+            .await
+            .unwrap();
+
+        assert_eq!(report.indexed_tools, 2);
+        assert_eq!(report.embedded_tools, 0);
+        assert_eq!(report.candidates[0].tool_name, "email_send");
+        assert!(report.candidates[0].vector_score.is_none());
+        assert!(report.candidates[0].selected);
+        assert!(report
+            .candidates
+            .iter()
+            .all(|c| c.tool_name != "web_search"));
+    }
+
+    #[test]
+    fn with_config_applies_max_results() {
+        let registry = PersistentToolRegistry::with_config(ToolRagConfig {
+            top_k: 2,
+            max_results: 3,
+            ..Default::default()
+        });
+        assert_eq!(registry.config().max_results, 3);
+    }
+
+    #[tokio::test]
+    async fn rank_for_context_keeps_relevant_candidates() {
+        let conn = setup_db();
+        {
+            let db = conn.lock();
+            for (name, desc, domain) in [
+                ("email_send", "Send an email message", "email"),
+                ("email_read", "Read email inbox", "email"),
+                ("web_search", "Search the web", "web"),
+            ] {
+                PersistentToolRegistry::save_embedding(
+                    &db,
+                    &make_record(name, desc, domain, vec![]),
+                )
+                .unwrap();
+            }
+        }
+
+        let candidates = vec![
+            "email_send".to_string(),
+            "web_search".to_string(),
+            "not_indexed_yet".to_string(),
+        ];
+        let index = PersistentToolRegistry::load_all(&conn.lock()).unwrap();
+        let kept = PersistentToolRegistry::rank_for_context(
+            "please send an email to bob",
+            &candidates,
+            None,
+            index,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(kept, vec!["email_send", "not_indexed_yet"]);
+    }
 }
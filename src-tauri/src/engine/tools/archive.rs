@@ -0,0 +1,560 @@
+// Paw Agent Engine — Archive tools
+// archive_extract, archive_create
+// Zip and tar(.gz) handled in-process with the zip / tar / flate2 crates —
+// nothing is shelled out. Both tools only touch the agent's workspace:
+// entries that would land outside the destination (zip-slip: absolute
+// paths, `..`, symlinks, hard links) are refused, and entry count and
+// uncompressed size are capped so a zip bomb can't fill the disk.
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use log::{info, warn};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Most entries an archive may contain (extract) or receive (create).
+const MAX_ENTRIES: usize = 20_000;
+/// Most uncompressed bytes written by one call.
+const MAX_TOTAL_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Format::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Format::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Format::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "archive_extract".into(),
+                description: "Extract a .zip, .tar, .tar.gz or .tgz archive inside your workspace. Entries that would escape the destination are refused.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string", "description": "Archive path (relative to your workspace)" },
+                        "dest": { "type": "string", "description": "Folder to extract into (relative to your workspace). Default: next to the archive, named after it." },
+                        "overwrite": { "type": "boolean", "description": "Replace existing files (default false — existing files are skipped)" }
+                    },
+                    "required": ["path"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "archive_create".into(),
+                description: "Pack files and folders from your workspace into a .zip, .tar or .tar.gz archive (format from the dest extension) — e.g. to hand over deliverables.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "paths": { "type": "array", "items": { "type": "string" }, "description": "Files or folders to include (relative to your workspace). Folders are added recursively." },
+                        "dest": { "type": "string", "description": "Archive to write, e.g. 'out/report.zip' (relative to your workspace)" }
+                    },
+                    "required": ["paths", "dest"]
+                }),
+            },
+        },
+    ]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &serde_json::Value,
+    agent_id: &str,
+) -> Option<Result<String, String>> {
+    let result = match name {
+        "archive_extract" => execute_extract(args, agent_id).await,
+        "archive_create" => execute_create(args, agent_id).await,
+        _ => return None,
+    };
+    Some(result.map_err(|e| e.to_string()))
+}
+
+async fn execute_extract(args: &serde_json::Value, agent_id: &str) -> EngineResult<String> {
+    let raw = args["path"]
+        .as_str()
+        .ok_or("archive_extract: missing 'path'")?;
+    let workspace = super::ensure_workspace(agent_id)?;
+    let archive = workspace_path(&workspace, raw, "archive_extract")?;
+    let format = Format::from_path(&archive)
+        .ok_or("archive_extract: unsupported format — use .zip, .tar, .tar.gz or .tgz")?;
+    let dest = match args["dest"].as_str().filter(|d| !d.trim().is_empty()) {
+        Some(d) => workspace_path(&workspace, d, "archive_extract")?,
+        None => default_extract_dir(&archive),
+    };
+    let overwrite = args["overwrite"].as_bool().unwrap_or(false);
+    info!(
+        "[archive] Extracting {} → {} (agent={})",
+        archive.display(),
+        dest.display(),
+        agent_id
+    );
+
+    let report = tokio::task::spawn_blocking(move || extract(&archive, format, &dest, overwrite))
+        .await
+        .map_err(|e| format!("archive_extract: {}", e))??;
+    Ok(report.to_string())
+}
+
+async fn execute_create(args: &serde_json::Value, agent_id: &str) -> EngineResult<String> {
+    let raw_paths: Vec<&str> = args["paths"]
+        .as_array()
+        .ok_or("archive_create: missing 'paths'")?
+        .iter()
+        .filter_map(|p| p.as_str())
+        .collect();
+    if raw_paths.is_empty() {
+        return Err("archive_create: 'paths' is empty".into());
+    }
+    let raw_dest = args["dest"]
+        .as_str()
+        .ok_or("archive_create: missing 'dest'")?;
+    let workspace = super::ensure_workspace(agent_id)?;
+    let dest = workspace_path(&workspace, raw_dest, "archive_create")?;
+    let format = Format::from_path(&dest)
+        .ok_or("archive_create: dest must end in .zip, .tar, .tar.gz or .tgz")?;
+    let sources = raw_paths
+        .iter()
+        .map(|p| workspace_path(&workspace, p, "archive_create"))
+        .collect::<EngineResult<Vec<_>>>()?;
+    info!(
+        "[archive] Creating {} from {} path(s) (agent={})",
+        dest.display(),
+        sources.len(),
+        agent_id
+    );
+
+    let report = tokio::task::spawn_blocking(move || create(&sources, format, &dest))
+        .await
+        .map_err(|e| format!("archive_create: {}", e))??;
+    Ok(report)
+}
+
+/// Resolve `raw` against the workspace and refuse anything outside it.
+/// Absolute paths are allowed only when they point into the workspace.
+fn workspace_path(workspace: &Path, raw: &str, tool: &str) -> EngineResult<PathBuf> {
+    let joined = workspace.join(raw.trim());
+    let canon_ws = workspace.canonicalize()?;
+    // Canonicalize the deepest existing ancestor; the rest must be plain names
+    let mut existing = joined.as_path();
+    let mut rest: Vec<&std::ffi::OsStr> = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = existing.canonicalize()?;
+    for name in rest.into_iter().rev() {
+        resolved.push(name);
+    }
+    if !resolved.starts_with(&canon_ws) || raw.split(['/', '\\']).any(|c| c == "..") {
+        return Err(format!(
+            "{}: '{}' is outside your workspace — archive tools only work inside it",
+            tool, raw
+        )
+        .into());
+    }
+    Ok(resolved)
+}
+
+/// `data/set.tar.gz` → `data/set/`
+fn default_extract_dir(archive: &Path) -> PathBuf {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let lower = name.to_lowercase();
+    let stem_len = [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| name.len() - ext.len())
+        .unwrap_or(name.len());
+    let stem = if stem_len == 0 {
+        "extracted"
+    } else {
+        &name[..stem_len]
+    };
+    archive.with_file_name(stem)
+}
+
+/// An archive entry name as a relative path that stays inside the
+/// destination, or None (absolute, drive prefix, `..`).
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+#[derive(Default)]
+struct ExtractReport {
+    dest: PathBuf,
+    files: usize,
+    dirs: usize,
+    bytes: u64,
+    skipped_existing: usize,
+    refused: Vec<String>,
+}
+
+impl std::fmt::Display for ExtractReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Extracted {} file(s) and {} folder(s), {} bytes, into {}",
+            self.files,
+            self.dirs,
+            self.bytes,
+            self.dest.display()
+        )?;
+        if self.skipped_existing > 0 {
+            write!(
+                f,
+                "\nSkipped {} existing file(s) (pass overwrite: true to replace them)",
+                self.skipped_existing
+            )?;
+        }
+        if !self.refused.is_empty() {
+            let shown: Vec<&str> = self.refused.iter().take(10).map(String::as_str).collect();
+            write!(
+                f,
+                "\nRefused {} unsafe entr{} (outside the destination or links): {}",
+                self.refused.len(),
+                if self.refused.len() == 1 { "y" } else { "ies" },
+                shown.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ExtractReport {
+    /// Write one file entry, counting against the size limit. The reader is
+    /// capped so a lying header can't push past it.
+    fn write_file(
+        &mut self,
+        rel: &Path,
+        reader: &mut dyn Read,
+        overwrite: bool,
+    ) -> EngineResult<()> {
+        let target = self.dest.join(rel);
+        if target.exists() && !overwrite {
+            self.skipped_existing += 1;
+            return Ok(());
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let remaining = MAX_TOTAL_BYTES - self.bytes;
+        let mut out = std::fs::File::create(&target)?;
+        let written = std::io::copy(&mut reader.take(remaining + 1), &mut out)?;
+        if written > remaining {
+            drop(out);
+            let _ = std::fs::remove_file(&target);
+            return Err(format!(
+                "archive_extract: stopped — the archive expands past the {} GB limit",
+                MAX_TOTAL_BYTES / 1024 / 1024 / 1024
+            )
+            .into());
+        }
+        out.flush()?;
+        self.bytes += written;
+        self.files += 1;
+        Ok(())
+    }
+
+    fn make_dir(&mut self, rel: &Path) -> EngineResult<()> {
+        std::fs::create_dir_all(self.dest.join(rel))?;
+        self.dirs += 1;
+        Ok(())
+    }
+}
+
+fn extract(
+    archive: &Path,
+    format: Format,
+    dest: &Path,
+    overwrite: bool,
+) -> EngineResult<ExtractReport> {
+    std::fs::create_dir_all(dest)?;
+    let mut report = ExtractReport {
+        dest: dest.to_path_buf(),
+        ..Default::default()
+    };
+    let file = std::fs::File::open(archive)?;
+    match format {
+        Format::Zip => {
+            let mut zip = zip::ZipArchive::new(file)
+                .map_err(|e| format!("archive_extract: not a valid zip: {}", e))?;
+            if zip.len() > MAX_ENTRIES {
+                return Err(too_many_entries(zip.len()));
+            }
+            for i in 0..zip.len() {
+                let mut entry = zip
+                    .by_index(i)
+                    .map_err(|e| format!("archive_extract: {}", e))?;
+                let name = entry.name().to_string();
+                let rel = match safe_relative(&name) {
+                    Some(rel) if !entry.is_symlink() => rel,
+                    _ => {
+                        report.refused.push(name);
+                        continue;
+                    }
+                };
+                if entry.is_dir() {
+                    report.make_dir(&rel)?;
+                } else {
+                    report.write_file(&rel, &mut entry, overwrite)?;
+                }
+            }
+        }
+        Format::Tar | Format::TarGz => {
+            let reader: Box<dyn Read> = if format == Format::TarGz {
+                Box::new(flate2::read::GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            let mut tar = tar::Archive::new(reader);
+            let entries = tar
+                .entries()
+                .map_err(|e| format!("archive_extract: not a valid tar: {}", e))?;
+            for (i, entry) in entries.enumerate() {
+                if i >= MAX_ENTRIES {
+                    return Err(too_many_entries(i + 1));
+                }
+                let mut entry = entry.map_err(|e| format!("archive_extract: {}", e))?;
+                let name = entry
+                    .path()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let kind = entry.header().entry_type();
+                let Some(rel) = safe_relative(&name) else {
+                    report.refused.push(name);
+                    continue;
+                };
+                if kind.is_dir() {
+                    report.make_dir(&rel)?;
+                } else if kind.is_file() {
+                    report.write_file(&rel, &mut entry, overwrite)?;
+                } else if kind.is_symlink() || kind.is_hard_link() {
+                    report.refused.push(name);
+                }
+                // Other entry types (pax headers, devices, fifos) are ignored
+            }
+        }
+    }
+    if !report.refused.is_empty() {
+        warn!(
+            "[archive] Refused {} unsafe entries in {}",
+            report.refused.len(),
+            archive.display()
+        );
+    }
+    Ok(report)
+}
+
+fn too_many_entries(n: usize) -> crate::atoms::error::EngineError {
+    format!(
+        "archive_extract: the archive has more than {} entries ({}) — refusing to extract",
+        MAX_ENTRIES, n
+    )
+    .into()
+}
+
+/// Files under `sources` as (absolute path, name inside the archive).
+/// Folders keep their own name as the top-level entry; symlinks are skipped.
+fn collect_files(sources: &[PathBuf], dest: &Path) -> EngineResult<Vec<(PathBuf, String)>> {
+    let mut files = Vec::new();
+    let mut total = 0u64;
+    for source in sources {
+        let meta = std::fs::symlink_metadata(source)
+            .map_err(|_| format!("archive_create: '{}' not found", source.display()))?;
+        let base = source.parent().unwrap_or(source);
+        let mut stack = vec![(source.clone(), meta)];
+        while let Some((path, meta)) = stack.pop() {
+            if meta.is_dir() {
+                for entry in std::fs::read_dir(&path)?.flatten() {
+                    if let Ok(m) = entry.path().symlink_metadata() {
+                        stack.push((entry.path(), m));
+                    }
+                }
+            } else if meta.is_file() && path != dest {
+                total += meta.len();
+                if files.len() >= MAX_ENTRIES || total > MAX_TOTAL_BYTES {
+                    return Err(format!(
+                        "archive_create: too much to pack (limit {} files / {} GB)",
+                        MAX_ENTRIES,
+                        MAX_TOTAL_BYTES / 1024 / 1024 / 1024
+                    )
+                    .into());
+                }
+                let name = path
+                    .strip_prefix(base)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                files.push((path, name));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
+}
+
+fn create(sources: &[PathBuf], format: Format, dest: &Path) -> EngineResult<String> {
+    let files = collect_files(sources, dest)?;
+    if files.is_empty() {
+        return Err("archive_create: nothing to pack — the paths contain no files".into());
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let out = std::fs::File::create(dest)?;
+    match format {
+        Format::Zip => {
+            let mut zip = zip::ZipWriter::new(out);
+            let opts = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .large_file(true);
+            let zip_err = |e: zip::result::ZipError| format!("archive_create: {}", e);
+            for (path, name) in &files {
+                zip.start_file(name.as_str(), opts).map_err(zip_err)?;
+                std::io::copy(&mut std::fs::File::open(path)?, &mut zip)?;
+            }
+            zip.finish().map_err(zip_err)?;
+        }
+        Format::Tar => write_tar(out, &files)?.flush()?,
+        Format::TarGz => {
+            let gz = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+            write_tar(gz, &files)?.finish()?.flush()?;
+        }
+    }
+    let size = std::fs::metadata(dest)?.len();
+    Ok(format!(
+        "Created {} — {} file(s), {} bytes",
+        dest.display(),
+        files.len(),
+        size
+    ))
+}
+
+fn write_tar<W: Write>(out: W, files: &[(PathBuf, String)]) -> EngineResult<W> {
+    let mut tar = tar::Builder::new(out);
+    tar.follow_symlinks(false);
+    for (path, name) in files {
+        tar.append_path_with_name(path, name)?;
+    }
+    Ok(tar.into_inner()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("paw-archive-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("report/data")).unwrap();
+        std::fs::write(dir.join("report/summary.md"), "# Summary").unwrap();
+        std::fs::write(dir.join("report/data/rows.csv"), "a,b\n1,2\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn extract_dir_is_named_after_the_archive() {
+        assert_eq!(
+            default_extract_dir(Path::new("/ws/data/set.tar.gz")),
+            Path::new("/ws/data/set")
+        );
+        assert_eq!(
+            default_extract_dir(Path::new("/ws/.zip")),
+            Path::new("/ws/extracted")
+        );
+    }
+
+    #[test]
+    fn refuses_escaping_entry_names() {
+        assert_eq!(safe_relative("a/./b.txt"), Some(PathBuf::from("a/b.txt")));
+        assert_eq!(safe_relative("../etc/passwd"), None);
+        assert_eq!(safe_relative("a/../../b"), None);
+        assert_eq!(safe_relative("/etc/passwd"), None);
+        assert_eq!(safe_relative("..\\windows\\x.dll"), None);
+        assert_eq!(safe_relative("./"), None);
+    }
+
+    #[test]
+    fn round_trips_zip_and_tar_gz() {
+        let dir = scratch();
+        for name in ["out.zip", "out.tar.gz", "out.tar"] {
+            let archive = dir.join(name);
+            let format = Format::from_path(&archive).unwrap();
+            create(&[dir.join("report")], format, &archive).unwrap();
+
+            let dest = dir.join(name.replace('.', "-"));
+            let report = extract(&archive, format, &dest, false).unwrap();
+            assert_eq!(report.files, 2, "{}", name);
+            assert_eq!(
+                std::fs::read_to_string(dest.join("report/data/rows.csv")).unwrap(),
+                "a,b\n1,2\n"
+            );
+            // A second run leaves existing files alone
+            let again = extract(&archive, format, &dest, false).unwrap();
+            assert_eq!(again.skipped_existing, 2);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn zip_slip_entries_are_refused() {
+        let dir = scratch();
+        let archive = dir.join("evil.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let opts = zip::write::SimpleFileOptions::default();
+        zip.start_file("../escaped.txt", opts).unwrap();
+        zip.write_all(b"x").unwrap();
+        zip.start_file("ok.txt", opts).unwrap();
+        zip.write_all(b"y").unwrap();
+        zip.finish().unwrap();
+
+        let dest = dir.join("evil");
+        let report = extract(&archive, Format::Zip, &dest, false).unwrap();
+        assert_eq!(report.files, 1);
+        assert_eq!(report.refused, ["../escaped.txt"]);
+        assert!(!dir.join("escaped.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn paths_stay_in_the_workspace() {
+        let dir = scratch();
+        assert!(workspace_path(&dir, "report/new.zip", "t").is_ok());
+        assert!(workspace_path(&dir, "../outside.zip", "t").is_err());
+        assert!(workspace_path(&dir, "/etc/passwd", "t").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,405 @@
+// Paw Agent Engine — Audio tools
+// audio_summarize
+//
+// Podcast / recording summaries: the audio is downloaded (a direct file,
+// a podcast RSS feed's latest episode, or a page with an og:audio tag) into
+// the agent workspace, transcribed through engine::transcribe, saved as a
+// timestamped transcript artifact, and summarized by the worker model (or
+// the default model when no worker is set).
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{resolve_provider_for_model, EngineState};
+use crate::engine::util::safe_truncate;
+use crate::engine::{transcribe, youtube};
+use log::{info, warn};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Manager;
+
+const MAX_DOWNLOAD_BYTES: u64 = 500 * 1024 * 1024;
+/// Transcript size per summarization call. Longer transcripts are summarized
+/// part by part and the notes merged.
+const SUMMARY_PART_BYTES: usize = 100_000;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize transcripts of podcasts and recordings.\n\n\
+    Lines in the transcript start with [m:ss] or [h:mm:ss] timestamps.\n\n\
+    ## Output (Markdown)\n\
+    ## Overview — 2–4 sentences: what it is, who speaks, the main takeaway.\n\
+    ## Chapters — one bullet per topic: `[timestamp] Title — one-line gist`.\n\
+    ## Key points — the important claims, numbers and arguments, each with its [timestamp].\n\
+    ## Notable quotes — up to 5 short verbatim quotes with [timestamp].\n\
+    ## Mentions — people, books, products and links mentioned (omit if none).\n\n\
+    ## Rules\n\
+    - Use only what is in the transcript; copy timestamps exactly.\n\
+    - Transcription errors happen: fix obvious misspellings of names, never invent content.";
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "audio_summarize".into(),
+            description: "Transcribe and summarize a podcast episode or audio recording. Accepts an audio URL, a podcast RSS feed (latest episode), an episode page with an audio player, or an audio file path in your workspace (mp3, m4a, wav, ogg, flac, webm). Returns a structured summary with timestamped chapters and key points; the full timestamped transcript is saved to your workspace. Long episodes take a few minutes.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "source": { "type": "string", "description": "Audio URL, podcast feed URL, episode page URL, or file path" },
+                    "language": { "type": "string", "description": "Spoken language as an ISO code (e.g. 'en', 'de'); improves accuracy. Auto-detected if omitted." },
+                    "focus": { "type": "string", "description": "Optional: what the user cares about, to emphasize in the summary" }
+                },
+                "required": ["source"]
+            }),
+        },
+    }]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> Option<Result<String, String>> {
+    match name {
+        "audio_summarize" => Some(
+            execute_audio_summarize(args, app_handle, agent_id)
+                .await
+                .map_err(|e| e.to_string()),
+        ),
+        _ => None,
+    }
+}
+
+async fn execute_audio_summarize(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> EngineResult<String> {
+    let source = args["source"]
+        .as_str()
+        .ok_or("audio_summarize: missing 'source'")?
+        .trim();
+    let language = args["language"].as_str().filter(|l| !l.is_empty());
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine state not available")?;
+    let backend = transcribe::pick_backend(&state)?;
+    let workspace = super::ensure_workspace(agent_id)?;
+
+    let path = if source.starts_with("http://") || source.starts_with("https://") {
+        download_audio(source, &workspace.join("audio")).await?
+    } else {
+        let p = Path::new(source);
+        let p = if p.is_absolute() {
+            p.to_path_buf()
+        } else {
+            workspace.join(p)
+        };
+        if !p.is_file() {
+            return Err(format!("Audio file not found: {}", p.display()).into());
+        }
+        p
+    };
+
+    info!(
+        "[audio] Transcribing {} with {}",
+        path.display(),
+        backend.label()
+    );
+    let segments = transcribe::transcribe_file(&backend, &path, language, |done, total| {
+        if total > 1 {
+            info!("[audio] Segment {}/{}", done + 1, total);
+        }
+    })
+    .await?;
+    if segments.is_empty() {
+        return Err("No speech was detected in the recording.".into());
+    }
+
+    let transcript = youtube::format_transcript(&segments);
+    let title = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "recording".into());
+    let duration = segments.last().map(|s| s.start).unwrap_or(0.0);
+    let words = transcript.split_whitespace().count();
+
+    let transcript_dir = workspace.join("transcripts");
+    std::fs::create_dir_all(&transcript_dir)?;
+    let transcript_path = transcript_dir.join(format!("{}.md", title));
+    std::fs::write(
+        &transcript_path,
+        format!(
+            "# {}\n\nSource: {}\nTranscribed with {}\n\n{}\n",
+            title,
+            source,
+            backend.label(),
+            transcript
+        ),
+    )?;
+
+    let header = format!(
+        "# {}\nLength: ~{} · {} words · transcribed with {}\nFull transcript: {}\n",
+        title,
+        youtube::format_timestamp(duration),
+        words,
+        backend.label(),
+        transcript_path.display()
+    );
+    match summarize(&state, &transcript, args["focus"].as_str()).await {
+        Some(summary) => Ok(format!("{}\n{}", header, summary.trim())),
+        None => {
+            // No model for summarizing — hand the agent the transcript itself
+            let first = youtube::split_parts(&transcript, SUMMARY_PART_BYTES / 4);
+            Ok(format!(
+                "{}\nSummarization model unavailable — summarize the transcript below (read the rest from the file above).\n\n{}",
+                header,
+                first.first().map(String::as_str).unwrap_or("")
+            ))
+        }
+    }
+}
+
+/// Download `url` into `dir`. Podcast feeds resolve to their latest
+/// enclosure and HTML pages to their og:audio / first audio link.
+async fn download_audio(url: &str, dir: &Path) -> EngineResult<PathBuf> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(600))
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .build()?;
+    let mut url = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let mut resp = client.get(url.clone()).send().await?.error_for_status()?;
+
+    let content_type = |r: &reqwest::Response| {
+        r.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_lowercase()
+    };
+    let ct = content_type(&resp);
+    if ct.contains("xml") || ct.contains("rss") || ct.contains("html") {
+        let body = resp.text().await?;
+        let found = find_audio_link(&body)
+            .ok_or("No audio found at that URL (expected an audio file, podcast feed, or page with an audio player)")?;
+        url = url
+            .join(&found)
+            .map_err(|e| format!("Invalid audio link {}: {}", found, e))?;
+        info!("[audio] Resolved audio link {}", url);
+        resp = client.get(url.clone()).send().await?.error_for_status()?;
+    }
+    if resp
+        .content_length()
+        .is_some_and(|len| len > MAX_DOWNLOAD_BYTES)
+    {
+        return Err(format!(
+            "Audio file is larger than {} MB",
+            MAX_DOWNLOAD_BYTES / 1024 / 1024
+        )
+        .into());
+    }
+
+    let ext = audio_extension(url.path(), &content_type(&resp));
+    let stem: String = url
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .and_then(|name| name.rsplit_once('.').map(|(s, _)| s).or(Some(name)))
+        .unwrap_or("")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(80)
+        .collect();
+    let stem = if stem.is_empty() {
+        format!("audio-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+    } else {
+        stem
+    };
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", stem, ext));
+
+    let mut file = std::fs::File::create(&path)?;
+    let mut written: u64 = 0;
+    while let Some(chunk) = resp.chunk().await? {
+        written += chunk.len() as u64;
+        if written > MAX_DOWNLOAD_BYTES {
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            return Err(format!(
+                "Audio file is larger than {} MB",
+                MAX_DOWNLOAD_BYTES / 1024 / 1024
+            )
+            .into());
+        }
+        std::io::Write::write_all(&mut file, &chunk)?;
+    }
+    info!(
+        "[audio] Downloaded {} ({} KB)",
+        path.display(),
+        written / 1024
+    );
+    Ok(path)
+}
+
+/// First audio link in a podcast feed or web page.
+fn find_audio_link(body: &str) -> Option<String> {
+    let patterns = [
+        r#"<enclosure[^>]*\surl="([^"]+)""#,
+        r#"<meta[^>]+property="og:audio(?::url|:secure_url)?"[^>]+content="([^"]+)""#,
+        r#"<audio[^>]*\ssrc="([^"]+)""#,
+        r#"<source[^>]*\ssrc="([^"]+\.(?:mp3|m4a|ogg|wav|aac)[^"]*)""#,
+        r#"href="([^"]+\.(?:mp3|m4a|ogg|wav|aac)(?:\?[^"]*)?)""#,
+    ];
+    patterns.iter().find_map(|p| {
+        Regex::new(p)
+            .ok()?
+            .captures(body)
+            .map(|c| c[1].replace("&amp;", "&"))
+    })
+}
+
+fn audio_extension(url_path: &str, content_type: &str) -> &'static str {
+    let from_path = url_path
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_lowercase())
+        .unwrap_or_default();
+    match from_path.as_str() {
+        "mp3" => return "mp3",
+        "m4a" => return "m4a",
+        "ogg" => return "ogg",
+        "wav" => return "wav",
+        "flac" => return "flac",
+        "aac" => return "aac",
+        "opus" => return "opus",
+        "webm" => return "webm",
+        _ => {}
+    }
+    if content_type.contains("mp4") || content_type.contains("m4a") {
+        "m4a"
+    } else if content_type.contains("ogg") {
+        "ogg"
+    } else if content_type.contains("wav") {
+        "wav"
+    } else {
+        "mp3"
+    }
+}
+
+/// Structured summary from the worker model (default model if unset).
+/// `None` when no model is available or every call failed.
+async fn summarize(state: &EngineState, transcript: &str, focus: Option<&str>) -> Option<String> {
+    let (model, provider_config) = {
+        let cfg = state.config.lock();
+        let model = cfg
+            .model_routing
+            .worker_model
+            .clone()
+            .or_else(|| cfg.default_model.clone())?;
+        let provider = resolve_provider_for_model(&model, &cfg.providers)
+            .or_else(|| cfg.providers.first().cloned())?;
+        (model, provider)
+    };
+    let provider = AnyProvider::from_config(&provider_config);
+    let focus = focus
+        .filter(|f| !f.trim().is_empty())
+        .map(|f| format!("\n\nThe user is especially interested in: {}", f))
+        .unwrap_or_default();
+
+    let parts = youtube::split_parts(transcript, SUMMARY_PART_BYTES);
+    let material = if parts.len() == 1 {
+        transcript.to_string()
+    } else {
+        // Long recording: condense each part to timestamped notes first
+        let mut notes = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            let prompt = format!(
+                "Part {} of {} of a transcript. Write detailed notes: one bullet per point, each starting with its [timestamp].\n\n{}",
+                i + 1,
+                parts.len(),
+                part
+            );
+            notes.push(
+                complete(
+                    &provider,
+                    &model,
+                    "You take concise, faithful notes on transcripts.",
+                    &prompt,
+                )
+                .await?,
+            );
+        }
+        notes.join("\n")
+    };
+
+    complete(
+        &provider,
+        &model,
+        SUMMARY_SYSTEM_PROMPT,
+        &format!("{}{}", safe_truncate(&material, SUMMARY_PART_BYTES), focus),
+    )
+    .await
+}
+
+async fn complete(provider: &AnyProvider, model: &str, system: &str, user: &str) -> Option<String> {
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(system.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(user.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+    match provider
+        .chat_stream(&messages, &[], model, Some(0.2), None)
+        .await
+    {
+        Ok(chunks) => {
+            let text: String = chunks
+                .iter()
+                .filter_map(|c| c.delta_text.as_deref())
+                .collect();
+            (!text.trim().is_empty()).then_some(text)
+        }
+        Err(e) => {
+            warn!("[audio] Summarization with {} failed: {}", model, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_audio_links() {
+        let rss = r#"<rss><channel><item><title>Ep 2</title>
+            <enclosure length="1" type="audio/mpeg" url="https://cdn.example/ep2.mp3?a=1&amp;b=2"/>
+            </item><item><enclosure url="https://cdn.example/ep1.mp3"/></item></channel></rss>"#;
+        assert_eq!(
+            find_audio_link(rss).as_deref(),
+            Some("https://cdn.example/ep2.mp3?a=1&b=2")
+        );
+        let html =
+            r#"<html><head><meta property="og:audio" content="/media/show.m4a"></head></html>"#;
+        assert_eq!(find_audio_link(html).as_deref(), Some("/media/show.m4a"));
+        assert!(find_audio_link("<html><p>nothing</p></html>").is_none());
+
+        assert_eq!(audio_extension("/ep/123", "audio/x-m4a"), "m4a");
+        assert_eq!(audio_extension("/ep/123.OGG", "audio/mpeg"), "ogg");
+    }
+}
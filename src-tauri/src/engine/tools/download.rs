@@ -0,0 +1,566 @@
+// Paw Agent Engine — download_file tool
+// Streams large files (datasets, installers, archives) to disk. `fetch` keeps
+// whole responses in memory and truncates them; this writes chunk by chunk
+// to `<dest>.part`, resumes an interrupted download with a Range request
+// (guarded by If-Range on the original ETag), enforces a size limit,
+// optionally verifies a SHA-256, and registers the finished file in the
+// downloads list. Progress is reported as `download-progress` events.
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::http::{is_retryable_status, parse_retry_after, retry_delay, MAX_RETRIES};
+use crate::engine::state::EngineState;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// Default size limit when the agent doesn't pass `max_mb`.
+const DEFAULT_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// `max_mb` can't raise the limit past this.
+const HARD_MAX_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+/// Minimum time between progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "download_file".into(),
+            description: "Download a large file (dataset, installer, archive, media) straight to disk. Use instead of fetch for anything big or binary. Interrupted downloads resume when called again with the same dest. Returns the saved path, size and SHA-256.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "http(s) URL to download" },
+                    "dest": { "type": "string", "description": "Where to save it — relative to your workspace or absolute. Default: downloads/<file name from the URL>" },
+                    "sha256": { "type": "string", "description": "Expected SHA-256 (hex). The download is deleted if it doesn't match." },
+                    "max_mb": { "type": "integer", "description": "Abort if the file is larger than this many MB (default 2048)" },
+                    "headers": { "type": "object", "description": "Extra HTTP headers, e.g. Authorization" }
+                },
+                "required": ["url"]
+            }),
+        },
+    }]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> Option<Result<String, String>> {
+    match name {
+        "download_file" => Some(
+            execute_download(args, app_handle, agent_id)
+                .await
+                .map_err(|e| e.to_string()),
+        ),
+        _ => None,
+    }
+}
+
+async fn execute_download(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> EngineResult<String> {
+    let url = args["url"]
+        .as_str()
+        .map(str::trim)
+        .ok_or("download_file: missing 'url' argument")?;
+    let parsed = url::Url::parse(url).map_err(|e| format!("download_file: invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("download_file: only http(s) URLs are supported".into());
+    }
+    super::fetch::check_url(url, "GET", "download_file", app_handle).await?;
+
+    let max_bytes = args["max_mb"]
+        .as_u64()
+        .map(|mb| mb.saturating_mul(1024 * 1024).min(HARD_MAX_BYTES))
+        .unwrap_or(DEFAULT_MAX_BYTES);
+    let expected_sha = args["sha256"]
+        .as_str()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+    if let Some(sha) = &expected_sha {
+        if sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("download_file: 'sha256' must be 64 hex characters".into());
+        }
+    }
+
+    let raw_dest = match args["dest"].as_str().filter(|d| !d.trim().is_empty()) {
+        Some(d) => d.trim().to_string(),
+        None => format!("downloads/{}", file_name_from_url(&parsed)),
+    };
+    let dest = super::filesystem::resolve_and_validate(&raw_dest, agent_id, "download_file")?;
+    if dest.is_dir() {
+        return Err(format!("download_file: '{}' is a directory", dest.display()).into());
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let part = part_path(&dest);
+
+    info!(
+        "[download] {} → {} (limit {} MB, agent={})",
+        url,
+        dest.display(),
+        max_bytes / 1024 / 1024,
+        agent_id
+    );
+    let progress = |payload: serde_json::Value| {
+        let _ = app_handle.emit("download-progress", payload);
+    };
+    let content_type = stream_to_part(url, &part, args, max_bytes, &progress).await?;
+
+    let sha256 = {
+        let part = part.clone();
+        tokio::task::spawn_blocking(move || sha256_file(&part))
+            .await
+            .map_err(|e| format!("download_file: hashing failed: {}", e))??
+    };
+    if let Some(expected) = &expected_sha {
+        if &sha256 != expected {
+            let _ = std::fs::remove_file(&part);
+            return Err(format!(
+                "download_file: checksum mismatch — expected {}, got {}. The download was deleted.",
+                expected, sha256
+            )
+            .into());
+        }
+    }
+    std::fs::rename(&part, &dest)?;
+    let size = std::fs::metadata(&dest)?.len();
+
+    if let Some(state) = app_handle.try_state::<EngineState>() {
+        if let Err(e) = state.store.download_record(
+            agent_id,
+            url,
+            &dest.to_string_lossy(),
+            size,
+            &sha256,
+            content_type.as_deref(),
+        ) {
+            warn!("[download] Failed to register {}: {}", dest.display(), e);
+        }
+    }
+
+    Ok(format!(
+        "Downloaded {}\nSaved to: {}\nSize: {}\nSHA-256: {}{}\nType: {}",
+        url,
+        dest.display(),
+        format_size(size),
+        sha256,
+        if expected_sha.is_some() {
+            " (verified)"
+        } else {
+            ""
+        },
+        content_type.as_deref().unwrap_or("unknown")
+    ))
+}
+
+/// Download into `part`, resuming from its current length. Transport errors
+/// and retryable statuses are retried from wherever the file got to.
+/// Returns the response Content-Type.
+async fn stream_to_part(
+    url: &str,
+    part: &Path,
+    args: &serde_json::Value,
+    max_bytes: u64,
+    progress: &(dyn Fn(serde_json::Value) + Send + Sync),
+) -> EngineResult<Option<String>> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .read_timeout(Duration::from_secs(60))
+        // Redirects must not lead to internal addresses either
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if super::fetch::is_ssrf_target(attempt.url().as_str()) {
+                attempt.error("redirect to an internal address blocked (SSRF protection)")
+            } else if attempt.previous().len() >= 10 {
+                attempt.error("too many redirects")
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()?;
+
+    let keep_note = |e: String| -> crate::atoms::error::EngineError {
+        format!(
+            "download_file: {} — the partial download was kept at {}; call again with the same dest to resume",
+            e,
+            part.display()
+        )
+        .into()
+    };
+
+    let mut last_err = String::new();
+    for attempt in 0..=MAX_RETRIES {
+        let mut offset = std::fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+        let mut req = client.get(url);
+        if let Some(headers) = args["headers"].as_object() {
+            for (key, value) in headers {
+                if let Some(v) = value.as_str() {
+                    req = req.header(key.as_str(), v);
+                }
+            }
+        }
+        if offset > 0 {
+            req = req.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+            // The server sends the whole file instead if it changed since
+            if let Ok(etag) = std::fs::read_to_string(etag_path(part)) {
+                req = req.header(reqwest::header::IF_RANGE, etag);
+            }
+        }
+
+        let mut resp = match req.send().await {
+            Ok(r) => r,
+            Err(e) if attempt < MAX_RETRIES && (e.is_timeout() || e.is_connect()) => {
+                warn!(
+                    "[download] Transport error on attempt {}: {}",
+                    attempt + 1,
+                    e
+                );
+                last_err = e.to_string();
+                retry_delay(attempt, None).await;
+                continue;
+            }
+            Err(e) => return Err(keep_note(e.to_string())),
+        };
+        let status = resp.status().as_u16();
+        if status == 416 && offset > 0 {
+            // Range starts at the end: the previous run already got everything
+            let _ = std::fs::remove_file(etag_path(part));
+            return Ok(content_type(&resp));
+        }
+        if is_retryable_status(status) && attempt < MAX_RETRIES {
+            let retry_after = resp
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after);
+            warn!("[download] HTTP {} on attempt {}", status, attempt + 1);
+            last_err = format!("HTTP {}", status);
+            retry_delay(attempt, retry_after).await;
+            continue;
+        }
+        if !resp.status().is_success() {
+            return Err(format!("download_file: HTTP {} for {}", status, url).into());
+        }
+
+        let resuming = offset > 0 && status == 206;
+        if resuming {
+            let start = resp
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(content_range_start);
+            if start != Some(offset) {
+                warn!(
+                    "[download] Asked for bytes from {} but got {:?} — starting over",
+                    offset, start
+                );
+                let _ = std::fs::remove_file(part);
+                last_err = "server returned the wrong byte range".into();
+                continue;
+            }
+        } else {
+            if offset > 0 {
+                info!("[download] Server ignored the range request — starting over");
+                offset = 0;
+            }
+            save_etag(part, &resp);
+        }
+        let total = resp.content_length().map(|len| len + offset);
+        if let Some(total) = total.filter(|t| *t > max_bytes) {
+            let _ = std::fs::remove_file(part);
+            return Err(format!(
+                "download_file: file is {} — over the {} limit (raise max_mb to allow it)",
+                format_size(total),
+                format_size(max_bytes)
+            )
+            .into());
+        }
+        let content_type = content_type(&resp);
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resuming)
+            .truncate(!resuming)
+            .open(part)?;
+        let mut last_progress = Instant::now();
+        let mut interrupted = None;
+        loop {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    offset += chunk.len() as u64;
+                    if offset > max_bytes {
+                        drop(file);
+                        let _ = std::fs::remove_file(part);
+                        return Err(format!(
+                            "download_file: aborted — file exceeds the {} limit (raise max_mb to allow it)",
+                            format_size(max_bytes)
+                        )
+                        .into());
+                    }
+                    file.write_all(&chunk)?;
+                    if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                        last_progress = Instant::now();
+                        progress(
+                            serde_json::json!({ "url": url, "downloaded": offset, "total": total }),
+                        );
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    interrupted = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+        file.flush()?;
+        drop(file);
+
+        match interrupted {
+            None if total.is_none_or(|t| offset >= t) => {
+                let _ = std::fs::remove_file(etag_path(part));
+                progress(
+                    serde_json::json!({ "url": url, "downloaded": offset, "total": total, "done": true }),
+                );
+                return Ok(content_type);
+            }
+            None => last_err = format!("connection closed at {} of {:?} bytes", offset, total),
+            Some(e) => last_err = e,
+        }
+        if attempt < MAX_RETRIES {
+            warn!(
+                "[download] Interrupted at {} bytes ({}), resuming",
+                offset, last_err
+            );
+            retry_delay(attempt, None).await;
+        }
+    }
+    Err(keep_note(last_err))
+}
+
+/// Remember the response's ETag next to the part file so a later resume can
+/// send it as If-Range. Weak ETags can't be used for ranges.
+fn save_etag(part: &Path, resp: &reqwest::Response) {
+    let etag = resp
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|e| e.starts_with('"'));
+    let _ = match etag {
+        Some(etag) => std::fs::write(etag_path(part), etag),
+        None => std::fs::remove_file(etag_path(part)),
+    };
+}
+
+fn etag_path(part: &Path) -> PathBuf {
+    let mut name = part.file_name().unwrap_or_default().to_os_string();
+    name.push(".etag");
+    part.with_file_name(name)
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_start(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn content_type(resp: &reqwest::Response) -> Option<String> {
+    resp.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Last path segment of the URL, or "download" when there is none.
+fn file_name_from_url(url: &url::Url) -> String {
+    url.path_segments()
+        .and_then(|mut segs| segs.next_back())
+        .map(|s| {
+            percent_decode(s)
+                .chars()
+                .map(|c| if c == '/' || c == '\\' { '_' } else { c })
+                .collect::<String>()
+        })
+        .filter(|s| !s.is_empty() && s != "." && s != "..")
+        .unwrap_or_else(|| "download".into())
+}
+
+fn percent_decode(s: &str) -> String {
+    url::form_urlencoded::parse(format!("x={}", s.replace('+', "%2B")).as_bytes())
+        .next()
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_else(|| s.to_string())
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+fn sha256_file(path: &Path) -> EngineResult<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.2} GB", bytes as f64 / (1024.0 * MB))
+    } else if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[test]
+    fn names_file_after_url() {
+        let name = |u: &str| file_name_from_url(&url::Url::parse(u).unwrap());
+        assert_eq!(
+            name("https://x.org/data/sales%202024.csv?v=2"),
+            "sales 2024.csv"
+        );
+        assert_eq!(name("https://x.org/"), "download");
+        assert_eq!(name("https://x.org/a/..%2F..%2Fetc"), ".._.._etc");
+    }
+
+    #[test]
+    fn part_file_sits_next_to_dest() {
+        assert_eq!(
+            part_path(Path::new("/ws/downloads/setup.exe")),
+            Path::new("/ws/downloads/setup.exe.part")
+        );
+    }
+
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(content_range_start("bytes 100-199/200"), Some(100));
+        assert_eq!(content_range_start("bytes 0-9/*"), Some(0));
+        assert_eq!(content_range_start("bytes */200"), None);
+        assert_eq!(content_range_start("items 1-2/3"), None);
+    }
+
+    const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    const ETAG: &str = "\"v1\"";
+
+    /// Minimal HTTP server for `BODY`. Honors `Range` when `If-Range` matches
+    /// `ETAG`, answering from `skew` bytes before the requested offset.
+    /// Returns the base URL and the raw requests it received.
+    async fn serve(skew: u64) -> (String, Arc<Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/file.bin", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let n = sock.read(&mut buf).await.unwrap();
+                let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                log.lock().push(req.clone());
+                let range = req
+                    .lines()
+                    .find_map(|l| l.strip_prefix("range: bytes="))
+                    .and_then(|r| r.trim_end_matches('-').parse::<u64>().ok());
+                let fresh = req.lines().any(|l| l == format!("if-range: {}", ETAG));
+                let head = match range.filter(|_| fresh) {
+                    Some(from) => {
+                        let start = from - skew;
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\n",
+                            start,
+                            BODY.len() - 1,
+                            BODY.len(),
+                            BODY.len() as u64 - start
+                        )
+                    }
+                    None => format!(
+                        "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\n",
+                        ETAG,
+                        BODY.len()
+                    ),
+                };
+                let start = range.filter(|_| fresh).map_or(0, |from| from - skew) as usize;
+                let mut out = format!("{}Connection: close\r\n\r\n", head).into_bytes();
+                out.extend_from_slice(&BODY[start..]);
+                let _ = sock.write_all(&out).await;
+            }
+        });
+        (url, seen)
+    }
+
+    #[tokio::test]
+    async fn resumes_from_the_part_file() {
+        let dir = std::env::temp_dir().join(format!("paw-download-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let part = dir.join("file.bin.part");
+        let (url, seen) = serve(0).await;
+
+        // First run: fresh download stores the ETag
+        let args = serde_json::json!({});
+        stream_to_part(&url, &part, &args, u64::MAX, &|_| {})
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&part).unwrap(), BODY);
+        assert!(!etag_path(&part).exists());
+
+        // Interrupted run: half a file plus its ETag
+        std::fs::write(&part, &BODY[..10]).unwrap();
+        std::fs::write(etag_path(&part), ETAG).unwrap();
+        stream_to_part(&url, &part, &args, u64::MAX, &|_| {})
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&part).unwrap(), BODY);
+        let last = seen.lock().last().cloned().unwrap();
+        assert!(last.contains("range: bytes=10-"), "{}", last);
+        assert!(last.contains(&format!("if-range: {}", ETAG)), "{}", last);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn restarts_when_the_range_is_misaligned() {
+        let dir = std::env::temp_dir().join(format!("paw-download-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let part = dir.join("file.bin.part");
+        let (url, seen) = serve(4).await;
+
+        std::fs::write(&part, &BODY[..10]).unwrap();
+        std::fs::write(etag_path(&part), ETAG).unwrap();
+        stream_to_part(&url, &part, &serde_json::json!({}), u64::MAX, &|_| {})
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&part).unwrap(), BODY);
+        // The misaligned 206 was discarded and the file fetched from scratch
+        let seen = seen.lock();
+        assert_eq!(seen.len(), 2);
+        assert!(!seen[1].contains("range:"), "{}", seen[1]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
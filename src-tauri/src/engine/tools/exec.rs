@@ -3,6 +3,7 @@
 
 use crate::atoms::error::EngineResult;
 use crate::atoms::types::*;
+use crate::engine::processes;
 use crate::engine::sandbox;
 use crate::engine::state::EngineState;
 use crate::engine::util::safe_truncate;
@@ -209,31 +210,36 @@ async fn execute_exec(
     use std::time::Duration;
     use tokio::process::Command as TokioCommand;
 
-    let child = if cfg!(target_os = "windows") {
-        TokioCommand::new("cmd")
-            .args(["C", command])
-            .current_dir(&workspace)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = TokioCommand::new("cmd");
+        cmd.args(["C", command]);
+        cmd
     } else {
-        TokioCommand::new("sh")
-            .args(["-c", command])
-            .current_dir(&workspace)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-    }
-    .map_err(|e| {
+        let mut cmd = TokioCommand::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.current_dir(&workspace)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    // Own process group, so a timeout also stops what the command started
+    processes::bind_to_parent(cmd.as_std_mut());
+    let child = cmd.spawn().map_err(|e| {
         crate::atoms::error::EngineError::Other(format!("Failed to spawn process: {}", e))
     })?;
+    let pid = child.id().unwrap_or(0);
+    processes::track(pid, "exec");
 
     let output =
         match tokio::time::timeout(Duration::from_secs(timeout_secs), child.wait_with_output())
             .await
         {
-            Ok(result) => result,
+            Ok(result) => {
+                processes::untrack(pid);
+                result
+            }
             Err(_) => {
+                let _ = processes::kill(pid);
                 return Err(format!("exec: command timed out after {}s", timeout_secs).into());
             }
         };
@@ -9,7 +9,7 @@ use tauri::Manager;
 
 /// §Security: SSRF protection — block access to internal/private network addresses
 /// and cloud metadata endpoints. Applied unconditionally before any network policy.
-fn is_ssrf_target(url: &str) -> bool {
+pub(crate) fn is_ssrf_target(url: &str) -> bool {
     let url_lower = url.to_lowercase();
     // Loopback and special addresses
     const BLOCKED_PREFIXES: &[&str] = &[
@@ -103,6 +103,67 @@ async fn check_dns_rebinding(url: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// SSRF, DNS-rebinding and network-policy checks shared by the HTTP tools.
+/// `tool` prefixes the error messages.
+pub(crate) async fn check_url(
+    url: &str,
+    method: &str,
+    tool: &str,
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<()> {
+    // §Security: SSRF protection — unconditionally block internal/private IPs
+    if is_ssrf_target(url) {
+        warn!("[engine] {}: SSRF blocked — {} {}", tool, method, url);
+        return Err(format!(
+            "{}: access to internal/private network addresses is blocked (SSRF protection). \
+             This includes localhost, RFC-1918 private ranges, link-local, and cloud metadata endpoints.",
+            tool
+        )
+        .into());
+    }
+
+    // §Security: Anti-DNS-rebinding — resolve hostname and verify IPs are public
+    if let Err(msg) = check_dns_rebinding(url).await {
+        warn!(
+            "[engine] {}: DNS rebinding blocked — {} {}: {}",
+            tool, method, url, msg
+        );
+        return Err(format!("{}: {}", tool, msg).into());
+    }
+
+    // Network policy enforcement
+    if let Some(state) = app_handle.try_state::<crate::engine::state::EngineState>() {
+        if let Ok(Some(policy_json)) = state.store.get_config("network_policy") {
+            if let Ok(policy) =
+                serde_json::from_str::<crate::commands::browser::NetworkPolicy>(&policy_json)
+            {
+                let domain = crate::commands::browser::extract_domain_from_url(url);
+                if policy
+                    .blocked_domains
+                    .iter()
+                    .any(|d| crate::commands::browser::domain_matches_pub(&domain, d))
+                {
+                    return Err(format!("Network policy: domain '{}' is blocked", domain).into());
+                }
+                if policy.enabled {
+                    let allowed = policy
+                        .allowed_domains
+                        .iter()
+                        .any(|d| crate::commands::browser::domain_matches_pub(&domain, d));
+                    if !allowed {
+                        return Err(format!(
+                            "Network policy: domain '{}' is not in the allowlist",
+                            domain
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn definitions() -> Vec<ToolDefinition> {
     vec![ToolDefinition {
         tool_type: "function".into(),
@@ -153,55 +214,7 @@ async fn execute_fetch(
 
     info!("[engine] fetch: {} {}", method, url);
 
-    // §Security: SSRF protection — unconditionally block internal/private IPs
-    if is_ssrf_target(url) {
-        warn!("[engine] fetch: SSRF blocked — {} {}", method, url);
-        return Err(
-            "fetch: access to internal/private network addresses is blocked (SSRF protection). \
-             This includes localhost, RFC-1918 private ranges, link-local, and cloud metadata endpoints."
-                .into(),
-        );
-    }
-
-    // §Security: Anti-DNS-rebinding — resolve hostname and verify IPs are public
-    if let Err(msg) = check_dns_rebinding(url).await {
-        warn!(
-            "[engine] fetch: DNS rebinding blocked — {} {}: {}",
-            method, url, msg
-        );
-        return Err(format!("fetch: {}", msg).into());
-    }
-
-    // Network policy enforcement
-    if let Some(state) = app_handle.try_state::<crate::engine::state::EngineState>() {
-        if let Ok(Some(policy_json)) = state.store.get_config("network_policy") {
-            if let Ok(policy) =
-                serde_json::from_str::<crate::commands::browser::NetworkPolicy>(&policy_json)
-            {
-                let domain = crate::commands::browser::extract_domain_from_url(url);
-                if policy
-                    .blocked_domains
-                    .iter()
-                    .any(|d| crate::commands::browser::domain_matches_pub(&domain, d))
-                {
-                    return Err(format!("Network policy: domain '{}' is blocked", domain).into());
-                }
-                if policy.enabled {
-                    let allowed = policy
-                        .allowed_domains
-                        .iter()
-                        .any(|d| crate::commands::browser::domain_matches_pub(&domain, d));
-                    if !allowed {
-                        return Err(format!(
-                            "Network policy: domain '{}' is not in the allowlist",
-                            domain
-                        )
-                        .into());
-                    }
-                }
-            }
-        }
-    }
+    check_url(url, method, "fetch", app_handle).await?;
 
     // ── Auto-inject credentials for known API domains ─────────────────
     // If the agent calls a Discord API URL without an Authorization header,
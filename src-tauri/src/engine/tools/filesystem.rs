@@ -66,7 +66,7 @@ const SENSITIVE_PATHS: &[&str] = &[
 /// workspace via `..` traversal or targets a sensitive location.
 ///
 /// `operation` is used in error messages (e.g. "read_file", "write_file").
-fn resolve_and_validate(
+pub(crate) fn resolve_and_validate(
     raw_path: &str,
     agent_id: &str,
     operation: &str,
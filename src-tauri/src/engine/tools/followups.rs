@@ -0,0 +1,236 @@
+// Paw Agent Engine — Follow-up scheduling tool
+//
+// schedule_followup lets an agent defer work ("I'll check the CI again in
+// 30 minutes"). It needs the calling session, which the generic dispatcher
+// does not carry, so the agent loop routes it to `schedule()` directly.
+// Due follow-ups are fired by the cron heartbeat (tasks::run_due_followups).
+
+use crate::atoms::types::*;
+use crate::engine::state::EngineState;
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use log::info;
+use tauri::{Emitter, Manager};
+
+/// Earliest and latest a follow-up may be scheduled.
+const MIN_DELAY_MINUTES: i64 = 1;
+const MAX_DELAY_DAYS: i64 = 7;
+/// Pending follow-ups allowed per session, so an agent can't fan out.
+const MAX_PENDING_PER_SESSION: usize = 5;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "schedule_followup".into(),
+            description: "Schedule a one-time follow-up in this conversation, e.g. to re-check a build or a deploy later. When it is due, the note is sent back to you in this session as a new turn.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "when": { "type": "string", "description": "Delay such as '30m', '2h', '1d' (or 'in 30 minutes'), a local time such as '17:30' or 'tomorrow 09:00', or an RFC 3339 timestamp. Between 1 minute and 7 days from now." },
+                    "note": { "type": "string", "description": "What to do when the follow-up fires — written as instructions to yourself" }
+                },
+                "required": ["when", "note"]
+            }),
+        },
+    }]
+}
+
+pub async fn execute(
+    name: &str,
+    _args: &serde_json::Value,
+    _app_handle: &tauri::AppHandle,
+    _agent_id: &str,
+) -> Option<Result<String, String>> {
+    match name {
+        // Reached only from callers without a session (plans, workers).
+        "schedule_followup" => Some(Err(
+            "schedule_followup is only available in a chat session".into()
+        )),
+        _ => None,
+    }
+}
+
+/// Schedule a follow-up for `session_id` from the tool's JSON arguments.
+pub fn schedule(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    agent_id: &str,
+    args: &serde_json::Value,
+) -> Result<String, String> {
+    let when = args["when"]
+        .as_str()
+        .ok_or("schedule_followup: missing 'when'")?;
+    let note = args["note"]
+        .as_str()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or("schedule_followup: missing 'note'")?;
+
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine state not available")?;
+    let tz = state.config.lock().timezone();
+
+    let now = Utc::now();
+    let due = parse_when(when, &now, &tz).ok_or_else(|| {
+        format!(
+            "schedule_followup: can't read '{}' — use e.g. '30m', '2h', 'tomorrow 09:00' or an RFC 3339 timestamp",
+            when
+        )
+    })?;
+    if due < now + Duration::minutes(MIN_DELAY_MINUTES)
+        || due > now + Duration::days(MAX_DELAY_DAYS)
+    {
+        return Err(format!(
+            "schedule_followup: '{}' must be between {} minute and {} days from now",
+            when, MIN_DELAY_MINUTES, MAX_DELAY_DAYS
+        ));
+    }
+
+    if let Some(level) = crate::engine::autonomy::load(&state.store, agent_id) {
+        if !level.policy().allow_triggers {
+            return Err(format!(
+                "schedule_followup: the '{}' autonomy level does not allow unattended runs",
+                level.as_str()
+            ));
+        }
+    }
+
+    let pending = state.store.list_followups(Some(session_id))?;
+    if pending.len() >= MAX_PENDING_PER_SESSION {
+        return Err(format!(
+            "schedule_followup: this session already has {} pending follow-ups",
+            pending.len()
+        ));
+    }
+
+    let id = state
+        .store
+        .create_followup(session_id, agent_id, note, &due.to_rfc3339())?;
+    info!(
+        "[engine] schedule_followup: {} session={} agent={} due={}",
+        id,
+        session_id,
+        agent_id,
+        due.to_rfc3339()
+    );
+    app_handle
+        .emit(
+            "followups-updated",
+            serde_json::json!({ "session_id": session_id }),
+        )
+        .ok();
+
+    Ok(format!(
+        "Follow-up scheduled for {} (id {}). You will get the note back in this session then.",
+        due.with_timezone(&tz).format("%Y-%m-%d %H:%M %Z"),
+        id
+    ))
+}
+
+/// Parse a relative delay ("30m", "in 2 hours", "1d"), a wall-clock time in
+/// `tz` ("17:30", "at 9:00", "tomorrow 09:00") or an RFC 3339 timestamp.
+/// A bare time that has already passed today means tomorrow.
+pub fn parse_when(when: &str, now: &DateTime<Utc>, tz: &Tz) -> Option<DateTime<Utc>> {
+    let s = when.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+        return Some(ts.with_timezone(&Utc));
+    }
+
+    let s = s.to_lowercase();
+    if let Some(due) = parse_local_time(&s, now, tz) {
+        return Some(due);
+    }
+    let s = s.strip_prefix("in ").unwrap_or(&s).trim();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let amount: i64 = s[..split].parse().ok()?;
+    let delta = match s[split..].trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(amount),
+        "d" | "day" | "days" => Duration::days(amount),
+        _ => return None,
+    };
+    Some(*now + delta)
+}
+
+fn parse_local_time(s: &str, now: &DateTime<Utc>, tz: &Tz) -> Option<DateTime<Utc>> {
+    let (tomorrow, rest) = match s.strip_prefix("tomorrow") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, s),
+    };
+    let rest = rest.strip_prefix("at ").unwrap_or(rest).trim();
+    let time = NaiveTime::parse_from_str(rest, "%H:%M").ok()?;
+    let mut day = now.with_timezone(tz).date_naive();
+    if tomorrow {
+        day = day.succ_opt()?;
+    }
+    let due = tz
+        .from_local_datetime(&day.and_time(time))
+        .earliest()?
+        .with_timezone(&Utc);
+    if !tomorrow && due <= *now {
+        let next = tz.from_local_datetime(&day.succ_opt()?.and_time(time));
+        return Some(next.earliest()?.with_timezone(&Utc));
+    }
+    Some(due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_relative_delays() {
+        let n = now();
+        assert_eq!(
+            parse_when("30m", &n, &Tz::UTC),
+            Some(n + Duration::minutes(30))
+        );
+        assert_eq!(
+            parse_when("in 2 hours", &n, &Tz::UTC),
+            Some(n + Duration::hours(2))
+        );
+        assert_eq!(parse_when("1d", &n, &Tz::UTC), Some(n + Duration::days(1)));
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        let n = now();
+        assert_eq!(
+            parse_when("2026-03-01T15:00:00Z", &n, &Tz::UTC),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 15, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn parses_local_times() {
+        let n = now(); // 12:00 UTC = 13:00 in Berlin
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        assert_eq!(
+            parse_when("17:30", &n, &berlin),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 16, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_when("at 09:00", &n, &berlin),
+            Some(Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_when("Tomorrow at 14:00", &n, &berlin),
+            Some(Utc.with_ymd_and_hms(2026, 3, 2, 13, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        let n = now();
+        assert_eq!(parse_when("soon", &n, &Tz::UTC), None);
+        assert_eq!(parse_when("5 fortnights", &n, &Tz::UTC), None);
+        assert_eq!(parse_when("m", &n, &Tz::UTC), None);
+    }
+}
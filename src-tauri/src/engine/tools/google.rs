@@ -25,36 +25,17 @@ use std::time::Duration;
 
 // ── Token helper ───────────────────────────────────────────────────────
 
-/// Load the Google OAuth access token from the encrypted vault.
-/// Returns Err if Google is not connected.
-fn load_google_token() -> Result<String, String> {
-    use crate::engine::key_vault;
-    use crate::engine::skills::crypto::{decrypt_credential, get_vault_key};
-
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {e}"))?;
-    // Try 'google-workspace' first (OAuth service_id), fallback to legacy 'google'
-    let encrypted = key_vault::get("oauth:google-workspace")
-        .or_else(|| key_vault::get("oauth:google"))
-        .ok_or("Google is not connected. The user needs to reconnect Google — click the Reconnect button below or go to Integrations → Google → Connect.")?;
-    let json = match decrypt_credential(&encrypted, &vault_key) {
-        Ok(j) => j,
-        Err(_) => {
-            // Token exists but can't be decrypted (vault key changed after rebuild).
-            // Clean up the broken entry.
-            key_vault::remove("oauth:google-workspace");
-            key_vault::remove("oauth:google");
-            return Err("Google OAuth token is corrupted (likely after an app update). The user needs to reconnect Google — click the Reconnect button below or go to Integrations → Google → Connect.".to_string());
-        }
-    };
-
-    #[derive(serde::Deserialize)]
-    struct Tokens {
-        access_token: String,
-    }
-    let tokens: Tokens =
-        serde_json::from_str(&json).map_err(|e| format!("Token parse error: {e}"))?;
-
-    Ok(tokens.access_token)
+/// Load the Google OAuth access token via the shared token manager
+/// (refreshed ahead of expiry). Returns Err if Google is not connected.
+async fn load_google_token() -> Result<String, String> {
+    use crate::engine::oauth_tokens::{self, TokenError};
+
+    oauth_tokens::access_token("google-workspace").await.map_err(|e| match e {
+        TokenError::NotConnected => "Google is not connected. The user needs to reconnect Google — click the Reconnect button below or go to Integrations → Google → Connect.".to_string(),
+        TokenError::Corrupt => "Google OAuth token is corrupted (likely after an app update). The user needs to reconnect Google — click the Reconnect button below or go to Integrations → Google → Connect.".to_string(),
+        TokenError::ReconnectRequired(_) => "Google access has expired or was revoked. The user needs to reconnect Google — click the Reconnect button below or go to Integrations → Google → Connect.".to_string(),
+        TokenError::Other(e) => format!("Google token error: {e}"),
+    })
 }
 
 /// Shared HTTP client with sane timeout.
@@ -126,15 +107,18 @@ pub fn definitions() -> Vec<ToolDefinition> {
             tool_type: "function".into(),
             function: FunctionDefinition {
                 name: "google_gmail_send".into(),
-                description: "Send an email via Gmail. Composes and sends immediately. Always confirm with the user before sending.".into(),
+                description: "Send an email via Gmail. Composes and sends immediately. Supports an HTML version of the body, attachments from your workspace, and replies that stay in the original thread (pass reply_to_message_id). Always confirm with the user before sending.".into(),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
                         "to": { "type": "string", "description": "Recipient email address" },
                         "subject": { "type": "string", "description": "Email subject line" },
                         "body": { "type": "string", "description": "Email body (plain text)" },
+                        "html_body": { "type": "string", "description": "HTML version of the body (optional; `body` stays the plain-text fallback)" },
                         "cc": { "type": "string", "description": "CC recipients (comma-separated, optional)" },
-                        "bcc": { "type": "string", "description": "BCC recipients (comma-separated, optional)" }
+                        "bcc": { "type": "string", "description": "BCC recipients (comma-separated, optional)" },
+                        "attachments": { "type": "array", "items": { "type": "string" }, "description": "Files to attach: paths in your workspace (e.g. 'reports/q3.pdf'), 25 MB total" },
+                        "reply_to_message_id": { "type": "string", "description": "Gmail message ID being replied to (from google_gmail_list/read). Threads the reply under it." }
                     },
                     "required": ["to", "subject", "body"]
                 }),
@@ -316,11 +300,12 @@ pub async fn execute(
     name: &str,
     args: &serde_json::Value,
     _app_handle: &tauri::AppHandle,
+    agent_id: &str,
 ) -> Option<Result<String, String>> {
     match name {
         "google_gmail_list" => Some(gmail_list(args).await),
         "google_gmail_read" => Some(gmail_read(args).await),
-        "google_gmail_send" => Some(gmail_send(args).await),
+        "google_gmail_send" => Some(gmail_send(args, agent_id).await),
         "google_calendar_list" => Some(calendar_list(args).await),
         "google_calendar_create" => Some(calendar_create(args).await),
         "google_drive_list" => Some(drive_list(args).await),
@@ -340,7 +325,7 @@ pub async fn execute(
 // ════════════════════════════════════════════════════════════════════════
 
 async fn gmail_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let query = args["query"].as_str().unwrap_or("");
     let max = args["max_results"].as_u64().unwrap_or(20).min(50);
 
@@ -440,7 +425,7 @@ async fn gmail_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn gmail_read(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let message_id = args["message_id"]
         .as_str()
         .ok_or("message_id is required")?;
@@ -529,31 +514,54 @@ fn decode_base64url(data: &str) -> String {
         .unwrap_or_default()
 }
 
-async fn gmail_send(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+async fn gmail_send(args: &serde_json::Value, agent_id: &str) -> Result<String, String> {
+    use crate::engine::email::OutgoingEmail;
+
+    let token = load_google_token().await?;
     let to = args["to"].as_str().ok_or("'to' is required")?;
     let subject = args["subject"].as_str().ok_or("'subject' is required")?;
     let body_text = args["body"].as_str().ok_or("'body' is required")?;
-    let cc = args["cc"].as_str().unwrap_or("");
-    let bcc = args["bcc"].as_str().unwrap_or("");
 
-    // Build RFC 2822 message
-    let mut raw = format!("To: {to}\r\nSubject: {subject}\r\n");
-    if !cc.is_empty() {
-        raw.push_str(&format!("Cc: {cc}\r\n"));
+    let mut attachments = Vec::new();
+    for raw in args["attachments"].as_array().into_iter().flatten() {
+        let raw = raw
+            .as_str()
+            .ok_or("'attachments' must be a list of paths")?;
+        attachments.push(
+            super::filesystem::resolve_and_validate(raw, agent_id, "google_gmail_send")
+                .map_err(|e| e.to_string())?,
+        );
     }
-    if !bcc.is_empty() {
-        raw.push_str(&format!("Bcc: {bcc}\r\n"));
+    let mut email = OutgoingEmail {
+        to: to.to_string(),
+        cc: args["cc"].as_str().unwrap_or("").to_string(),
+        bcc: args["bcc"].as_str().unwrap_or("").to_string(),
+        subject: subject.to_string(),
+        text: body_text.to_string(),
+        html: args["html_body"]
+            .as_str()
+            .filter(|h| !h.trim().is_empty())
+            .map(str::to_string),
+        attachments,
+        ..Default::default()
+    };
+    email.check_attachments().map_err(|e| e.to_string())?;
+
+    let mut payload = serde_json::json!({});
+    if let Some(parent_id) = args["reply_to_message_id"].as_str() {
+        let parent = gmail_thread_headers(&token, parent_id).await?;
+        email.thread_on(&parent.message_id, &parent.references);
+        if !parent.thread_id.is_empty() {
+            payload["threadId"] = serde_json::json!(parent.thread_id);
+        }
     }
-    raw.push_str("Content-Type: text/plain; charset=\"UTF-8\"\r\n\r\n");
-    raw.push_str(body_text);
 
     // base64url encode the raw message
     use base64::engine::general_purpose::URL_SAFE_NO_PAD;
     use base64::Engine;
-    let encoded = URL_SAFE_NO_PAD.encode(raw.as_bytes());
+    let raw = email.to_mime().map_err(|e| e.to_string())?;
+    payload["raw"] = serde_json::json!(URL_SAFE_NO_PAD.encode(raw.as_bytes()));
 
-    let payload = serde_json::json!({ "raw": encoded });
     let resp = http()
         .post("https://gmail.googleapis.com/gmail/v1/users/me/messages/send")
         .bearer_auth(&token)
@@ -566,19 +574,64 @@ async fn gmail_send(args: &serde_json::Value) -> Result<String, String> {
     let result: serde_json::Value =
         serde_json::from_str(&body).map_err(|e| format!("Parse error: {e}"))?;
 
-    info!("[google] gmail_send to={}", to);
+    info!(
+        "[google] gmail_send to={} attachments={} reply={}",
+        to,
+        email.attachments.len(),
+        email.in_reply_to.is_some()
+    );
     Ok(format!(
         "Email sent successfully. Message ID: {}",
         result["id"].as_str().unwrap_or("unknown")
     ))
 }
 
+/// The headers a reply to a Gmail message needs.
+struct GmailThread {
+    thread_id: String,
+    message_id: String,
+    references: String,
+}
+
+async fn gmail_thread_headers(token: &str, message_id: &str) -> Result<GmailThread, String> {
+    let url = format!(
+        "https://gmail.googleapis.com/gmail/v1/users/me/messages/{}?format=metadata\
+         &metadataHeaders=Message-ID&metadataHeaders=References",
+        urlencoding::encode(message_id)
+    );
+    let resp = http()
+        .get(&url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("Gmail read failed: {e}"))?;
+    let body = check_response(resp, "Gmail read").await?;
+    let msg: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| format!("Parse error: {e}"))?;
+
+    let header = |name: &str| {
+        msg["payload"]["headers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|h| h["name"].as_str().unwrap_or("").eq_ignore_ascii_case(name))
+            .and_then(|h| h["value"].as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    Ok(GmailThread {
+        thread_id: msg["threadId"].as_str().unwrap_or("").to_string(),
+        message_id: header("Message-ID"),
+        references: header("References"),
+    })
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Calendar
 // ════════════════════════════════════════════════════════════════════════
 
 async fn calendar_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let calendar_id = args["calendar_id"].as_str().unwrap_or("primary");
     let max = args["max_results"].as_u64().unwrap_or(25).min(100);
 
@@ -637,7 +690,7 @@ async fn calendar_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn calendar_create(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let calendar_id = args["calendar_id"].as_str().unwrap_or("primary");
     let summary = args["summary"].as_str().ok_or("'summary' is required")?;
     let start = args["start"].as_str().ok_or("'start' is required")?;
@@ -715,7 +768,7 @@ async fn calendar_create(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn drive_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let max = args["max_results"].as_u64().unwrap_or(25).min(100);
 
     let mut url = format!(
@@ -761,7 +814,7 @@ async fn drive_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn drive_read(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let file_id = args["file_id"].as_str().ok_or("'file_id' is required")?;
     let export_format = args["export_format"].as_str().unwrap_or("text/plain");
 
@@ -817,7 +870,7 @@ async fn drive_read(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn drive_upload(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let name = args["name"].as_str().ok_or("'name' is required")?;
     let content = args["content"].as_str().ok_or("'content' is required")?;
     let mime_type = args["mime_type"].as_str().unwrap_or("text/plain");
@@ -865,7 +918,7 @@ async fn drive_upload(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn drive_share(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let file_id = args["file_id"].as_str().ok_or("'file_id' is required")?;
     let email = args["email"].as_str().ok_or("'email' is required")?;
     let role = args["role"].as_str().unwrap_or("reader");
@@ -905,7 +958,7 @@ async fn drive_share(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn sheets_read(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let spreadsheet_id = args["spreadsheet_id"]
         .as_str()
         .ok_or("'spreadsheet_id' is required")?;
@@ -936,7 +989,7 @@ async fn sheets_read(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn sheets_append(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let spreadsheet_id = args["spreadsheet_id"]
         .as_str()
         .ok_or("'spreadsheet_id' is required")?;
@@ -985,7 +1038,7 @@ async fn sheets_append(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn docs_create(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let title = args["title"].as_str().ok_or("'title' is required")?;
     let body_text = args["body"].as_str().unwrap_or("");
 
@@ -1042,7 +1095,7 @@ async fn docs_create(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn generic_api(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_google_token()?;
+    let token = load_google_token().await?;
     let method = args["method"].as_str().ok_or("'method' is required")?;
     let url = args["url"].as_str().ok_or("'url' is required")?;
 
@@ -0,0 +1,40 @@
+// Paw Agent Engine — Agent handoff tool
+//
+// handoff_to_agent lets the acting agent pass a normal chat to a better
+// suited agent, outside orchestrator projects. Like schedule_followup it
+// needs the calling session, so the agent loop routes it to
+// `handoff::request()` directly.
+
+use crate::atoms::types::*;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "handoff_to_agent".into(),
+            description: "Hand this conversation over to another agent whose specialty fits the request better (see agent_list). The other agent continues in this same session with its own instructions and tools, starting from your summary. Use it only when the task is clearly outside your role; after calling it, end your turn.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "agent_id": { "type": "string", "description": "ID of the agent to take over" },
+                    "summary": { "type": "string", "description": "Handoff notes for that agent: what the user wants, what has been done or decided, and what is left to do" }
+                },
+                "required": ["agent_id", "summary"]
+            }),
+        },
+    }]
+}
+
+pub async fn execute(
+    name: &str,
+    _args: &serde_json::Value,
+    _app_handle: &tauri::AppHandle,
+) -> Option<Result<String, String>> {
+    match name {
+        // Reached only from callers without a session (plans, workers).
+        "handoff_to_agent" => Some(Err(
+            "handoff_to_agent is only available in a chat session".into()
+        )),
+        _ => None,
+    }
+}
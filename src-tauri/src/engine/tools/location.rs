@@ -0,0 +1,156 @@
+// Paw Agent Engine — Location tools
+// local_time, nearby_search
+// Both default to the user's home location (see engine::location).
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::location;
+use crate::engine::state::EngineState;
+use tauri::Manager;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "local_time".into(),
+                description: "Current local date and time, at the user's home location or at a named place (e.g. 'Tokyo', 'Portland, OR'). Use it for 'what time is it in …' and before scheduling across timezones.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "location": { "type": "string", "description": "Place name. Omit for the user's home location." }
+                    }
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "nearby_search".into(),
+                description: "Find places near the user's home location (or a named place) from OpenStreetMap — e.g. 'pharmacy', 'thai restaurant', 'EV charging'. Returns names, addresses and distances, nearest first.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "What to look for" },
+                        "location": { "type": "string", "description": "Search around this place instead of the home location" },
+                        "radius_km": { "type": "number", "description": "Search radius in km (default 5, max 50)" },
+                        "limit": { "type": "integer", "description": "Max results (default 10)" }
+                    },
+                    "required": ["query"]
+                }),
+            },
+        },
+    ]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> Option<Result<String, String>> {
+    let result = match name {
+        "local_time" => execute_local_time(args, app_handle).await,
+        "nearby_search" => execute_nearby_search(args, app_handle).await,
+        _ => return None,
+    };
+    Some(result.map_err(|e| e.to_string()))
+}
+
+/// The named place, or the home location when `location` is absent.
+async fn resolve_place(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+) -> EngineResult<HomeLocation> {
+    match args["location"].as_str().filter(|l| !l.trim().is_empty()) {
+        Some(query) => location::geocode(client, query).await,
+        None => app_handle
+            .try_state::<EngineState>()
+            .and_then(|state| location::home(&state))
+            .ok_or_else(|| {
+                "No home location is set. Ask the user where they are (or pass 'location'); they can set it in Settings → Agent Defaults.".into()
+            }),
+    }
+}
+
+async fn execute_local_time(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<String> {
+    let client = location::client()?;
+    let place = resolve_place(args, app_handle, &client).await?;
+    // Fall back to the configured user timezone for a home location
+    // geocoded before timezones were recorded
+    let tz_name = place.timezone.clone().or_else(|| {
+        args["location"].as_str().is_none().then(|| {
+            app_handle
+                .try_state::<EngineState>()
+                .map(|s| s.config.lock().user_timezone.clone())
+        })?
+    });
+    let tz: chrono_tz::Tz = tz_name
+        .as_deref()
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| format!("No timezone known for {}", place.display_name()))?;
+    let now = chrono::Utc::now().with_timezone(&tz);
+    Ok(format!(
+        "{}: {} ({}, UTC{})",
+        place.display_name(),
+        now.format("%A %Y-%m-%d %H:%M"),
+        tz.name(),
+        now.format("%:z")
+    ))
+}
+
+async fn execute_nearby_search(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<String> {
+    let query = args["query"]
+        .as_str()
+        .filter(|q| !q.trim().is_empty())
+        .ok_or("nearby_search: missing 'query'")?;
+    let radius = args["radius_km"].as_f64().unwrap_or(5.0).clamp(0.5, 50.0);
+    let limit = args["limit"].as_u64().unwrap_or(10).clamp(1, 25) as usize;
+    let client = location::client()?;
+    let place = resolve_place(args, app_handle, &client).await?;
+
+    let places = location::nearby(
+        &client,
+        query,
+        place.latitude,
+        place.longitude,
+        radius,
+        limit,
+    )
+    .await?;
+    if places.is_empty() {
+        return Ok(format!(
+            "No '{}' found within {} km of {}. Try a wider radius or a different term.",
+            query,
+            radius,
+            place.display_name()
+        ));
+    }
+    let mut out = format!(
+        "'{}' within {} km of {} (OpenStreetMap):\n\n",
+        query,
+        radius,
+        place.display_name()
+    );
+    for (i, p) in places.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. **{}**{} — {:.1} km\n   {}\n",
+            i + 1,
+            p.name,
+            if p.kind.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", p.kind)
+            },
+            p.distance_km,
+            p.address
+        ));
+    }
+    Ok(out)
+}
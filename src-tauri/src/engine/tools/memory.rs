@@ -1,5 +1,5 @@
 // Paw Agent Engine — Memory tools
-// memory_store, memory_search
+// memory_store, memory_search, memory_timeline, …
 
 use crate::atoms::error::EngineResult;
 use crate::atoms::types::*;
@@ -50,6 +50,22 @@ pub fn definitions() -> Vec<ToolDefinition> {
                 }),
             },
         },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "memory_timeline".into(),
+                description: "Recall what happened at a given time: memories and conversation messages from that window, oldest first. Use for questions like 'what did we decide last Tuesday?' or 'what were we discussing around 3pm yesterday?'. Times are in the user's timezone.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "when": { "type": "string", "description": "Time expression: 'yesterday', 'this morning', 'last night', 'last week', 'tuesday', '3 days ago', 'around 3pm yesterday'" },
+                        "start": { "type": "string", "description": "Explicit window start (RFC 3339). Use with 'end' instead of 'when'." },
+                        "end": { "type": "string", "description": "Explicit window end (RFC 3339)" },
+                        "limit": { "type": "integer", "description": "Maximum entries to return (default: 30)" }
+                    }
+                }),
+            },
+        },
         ToolDefinition {
             tool_type: "function".into(),
             function: FunctionDefinition {
@@ -178,6 +194,9 @@ pub async fn execute(
                 .await
                 .map_err(|e| e.to_string()),
         ),
+        "memory_timeline" => {
+            Some(execute_memory_timeline(args, app_handle, agent_id).map_err(|e| e.to_string()))
+        }
         "memory_knowledge" => Some(
             execute_memory_knowledge(args, app_handle, agent_id)
                 .await
@@ -285,8 +304,40 @@ async fn execute_memory_search(
         .ok_or("Engine state not available")?;
     let emb_client = state.embedding_client();
 
-    // Search via Engram gated search (§55) — intent-aware, quality-gated retrieval
     let scope = crate::atoms::engram_types::MemoryScope::agent(agent_id);
+
+    // "What did we discuss yesterday?" — answer from the time axis, with day
+    // boundaries in the user's timezone.
+    let tz = state.config.lock().timezone();
+    if let Some(tq) = engram::parse_temporal_expression(query, chrono::Utc::now(), tz) {
+        let found = engram::temporal_search(&state.store, &tq, &scope, limit)?;
+        if !found.memories.is_empty() {
+            let mut output = format!(
+                "Found {} memories from that time:\n\n",
+                found.memories.len()
+            );
+            for (i, mem) in found.memories.iter().enumerate() {
+                let when = chrono::DateTime::parse_from_rfc3339(&mem.created_at)
+                    .map(|dt| {
+                        dt.with_timezone(&tz)
+                            .format("%a %Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|_| mem.created_at.clone());
+                output.push_str(&format!(
+                    "{}. [{}] {} — {} (id: {})\n",
+                    i + 1,
+                    when,
+                    mem.category,
+                    mem.content,
+                    safe_truncate(&mem.memory_id, 8),
+                ));
+            }
+            return Ok(output);
+        }
+    }
+
+    // Search via Engram gated search (§55) — intent-aware, quality-gated retrieval
     let search_config = crate::atoms::engram_types::MemorySearchConfig::default();
     // Issue a signed capability token for read-path scope verification (§43.4)
     let read_cap = crate::engine::engram::memory_bus::issue_read_capability(agent_id).ok();
@@ -326,6 +377,7 @@ async fn execute_memory_search(
     }
 
     // Fallback to legacy memory search
+    let tuning = state.memory_config.lock().search.clone();
     let results = memory::search_memories(
         &state.store,
         query,
@@ -333,6 +385,7 @@ async fn execute_memory_search(
         0.1,
         emb_client.as_ref(),
         Some(agent_id),
+        &tuning,
     )
     .await?;
     if results.is_empty() {
@@ -596,6 +649,95 @@ async fn execute_memory_update(
     }
 }
 
+/// Resolve the window for memory_timeline / engine_memory_timeline: an
+/// explicit `start`..`end` pair wins over a `when` expression.
+pub fn resolve_temporal_query(
+    when: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+    tz: chrono_tz::Tz,
+) -> EngineResult<crate::atoms::engram_types::TemporalQuery> {
+    let to_utc = |s: &str| -> EngineResult<String> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Utc)
+                    .format("%Y-%m-%dT%H:%M:%SZ")
+                    .to_string()
+            })
+            .map_err(|_| format!("'{}' is not an RFC 3339 timestamp", s).into())
+    };
+    if let Some(start) = start {
+        let end = match end {
+            Some(e) => to_utc(e)?,
+            None => chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        };
+        return Ok(crate::atoms::engram_types::TemporalQuery::Range {
+            start: to_utc(start)?,
+            end,
+        });
+    }
+    let when = when.ok_or("give 'when' (e.g. 'yesterday') or 'start'/'end'")?;
+    engram::parse_temporal_expression(when, chrono::Utc::now(), tz).ok_or_else(|| {
+        format!(
+            "can't read '{}' as a time — try 'yesterday', 'last week', 'tuesday' or '3 days ago'",
+            when
+        )
+        .into()
+    })
+}
+
+fn execute_memory_timeline(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> EngineResult<String> {
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine state not available")?;
+    let tz = state.config.lock().timezone();
+    let limit = args["limit"].as_u64().unwrap_or(30).clamp(1, 200) as usize;
+    let query = resolve_temporal_query(
+        args["when"].as_str(),
+        args["start"].as_str(),
+        args["end"].as_str(),
+        tz,
+    )
+    .map_err(|e| format!("memory_timeline: {}", e))?;
+    info!(
+        "[engine] memory_timeline: {:?} limit={} agent={}",
+        query, limit, agent_id
+    );
+
+    let scope = crate::atoms::engram_types::MemoryScope::agent(agent_id);
+    let entries = engram::timeline(&state.store, &query, &scope, Some(agent_id), limit)?;
+    if entries.is_empty() {
+        return Ok("Nothing found in that time window.".into());
+    }
+
+    let mut output = format!("{} entries, oldest first:\n\n", entries.len());
+    for e in &entries {
+        let when = chrono::DateTime::parse_from_rfc3339(&e.timestamp)
+            .map(|dt| {
+                dt.with_timezone(&tz)
+                    .format("%a %Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| e.timestamp.clone());
+        let source = if e.kind == "memory" {
+            format!("memory/{}", e.label)
+        } else {
+            e.label.clone()
+        };
+        output.push_str(&format!(
+            "[{}] {}: {}\n",
+            when,
+            source,
+            safe_truncate(e.content.trim(), 400)
+        ));
+    }
+    Ok(output)
+}
+
 fn execute_memory_list(
     args: &serde_json::Value,
     app_handle: &tauri::AppHandle,
@@ -27,32 +27,17 @@ use std::time::Duration;
 
 // ── Token helper ───────────────────────────────────────────────────────
 
-/// Load the Microsoft OAuth access token from the encrypted vault.
-fn load_microsoft_token() -> Result<String, String> {
-    use crate::engine::key_vault;
-    use crate::engine::skills::crypto::{decrypt_credential, get_vault_key};
-
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {e}"))?;
-    let encrypted = key_vault::get("oauth:microsoft")
-        .or_else(|| key_vault::get("oauth:microsoft-365"))
-        .ok_or("Microsoft 365 is not connected. The user needs to connect Microsoft — go to Integrations → Microsoft 365 → Connect.")?;
-    let json = match decrypt_credential(&encrypted, &vault_key) {
-        Ok(j) => j,
-        Err(_) => {
-            key_vault::remove("oauth:microsoft");
-            key_vault::remove("oauth:microsoft-365");
-            return Err("Microsoft OAuth token is corrupted (likely after an app update). The user needs to reconnect Microsoft 365 — go to Integrations → Microsoft 365 → Connect.".to_string());
-        }
-    };
-
-    #[derive(serde::Deserialize)]
-    struct Tokens {
-        access_token: String,
-    }
-    let tokens: Tokens =
-        serde_json::from_str(&json).map_err(|e| format!("Token parse error: {e}"))?;
-
-    Ok(tokens.access_token)
+/// Load the Microsoft OAuth access token via the shared token manager
+/// (refreshed ahead of expiry).
+async fn load_microsoft_token() -> Result<String, String> {
+    use crate::engine::oauth_tokens::{self, TokenError};
+
+    oauth_tokens::access_token("microsoft").await.map_err(|e| match e {
+        TokenError::NotConnected => "Microsoft 365 is not connected. The user needs to connect Microsoft — go to Integrations → Microsoft 365 → Connect.".to_string(),
+        TokenError::Corrupt => "Microsoft OAuth token is corrupted (likely after an app update). The user needs to reconnect Microsoft 365 — go to Integrations → Microsoft 365 → Connect.".to_string(),
+        TokenError::ReconnectRequired(_) => "Microsoft 365 access has expired or was revoked. The user needs to reconnect Microsoft 365 — go to Integrations → Microsoft 365 → Connect.".to_string(),
+        TokenError::Other(e) => format!("Microsoft token error: {e}"),
+    })
 }
 
 /// Shared HTTP client with sane timeout.
@@ -353,7 +338,7 @@ pub async fn execute(
 // ════════════════════════════════════════════════════════════════════════
 
 async fn mail_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let query = args["query"].as_str().unwrap_or("");
     let filter = args["filter"].as_str().unwrap_or("");
     let max = args["max_results"].as_u64().unwrap_or(20).min(50);
@@ -414,7 +399,7 @@ async fn mail_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn mail_read(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let message_id = args["message_id"]
         .as_str()
         .ok_or("message_id is required")?;
@@ -470,7 +455,7 @@ async fn mail_read(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn mail_send(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let to = args["to"].as_str().ok_or("'to' is required")?;
     let subject = args["subject"].as_str().ok_or("'subject' is required")?;
     let body_text = args["body"].as_str().ok_or("'body' is required")?;
@@ -542,7 +527,7 @@ async fn mail_send(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn calendar_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
 
     // Default to today
     let now = chrono::Utc::now();
@@ -616,7 +601,7 @@ async fn calendar_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn calendar_create(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let subject = args["subject"].as_str().ok_or("'subject' is required")?;
     let start = args["start"].as_str().ok_or("'start' is required")?;
     let end = args["end"].as_str().ok_or("'end' is required")?;
@@ -678,7 +663,7 @@ async fn calendar_create(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn drive_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let query = args["query"].as_str().unwrap_or("");
     let path = args["path"].as_str().unwrap_or("");
     let max = args["max_results"].as_u64().unwrap_or(25).min(100);
@@ -747,7 +732,7 @@ async fn drive_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn drive_read(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let item_id = args["item_id"].as_str().unwrap_or("");
     let path = args["path"].as_str().unwrap_or("");
 
@@ -825,7 +810,7 @@ async fn drive_read(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn drive_upload(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let path = args["path"].as_str().ok_or("'path' is required")?;
     let content = args["content"].as_str().ok_or("'content' is required")?;
 
@@ -862,7 +847,7 @@ async fn drive_upload(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn teams_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let include_channels = args["include_channels"].as_bool().unwrap_or(true);
 
     let resp = http()
@@ -925,7 +910,7 @@ async fn teams_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn teams_send(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let body_text = args["body"].as_str().ok_or("'body' is required")?;
     let content_type = args["content_type"].as_str().unwrap_or("text");
 
@@ -979,7 +964,7 @@ async fn teams_send(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn tasks_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let list_id = args["list_id"].as_str().unwrap_or("");
 
     if list_id.is_empty() {
@@ -1057,7 +1042,7 @@ async fn tasks_list(args: &serde_json::Value) -> Result<String, String> {
 }
 
 async fn tasks_create(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let list_id = args["list_id"].as_str().ok_or("'list_id' is required")?;
     let title = args["title"].as_str().ok_or("'title' is required")?;
     let importance = args["importance"].as_str().unwrap_or("normal");
@@ -1109,7 +1094,7 @@ async fn tasks_create(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn onenote_list(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let include_sections = args["include_sections"].as_bool().unwrap_or(true);
 
     let expand = if include_sections {
@@ -1181,7 +1166,7 @@ async fn onenote_list(args: &serde_json::Value) -> Result<String, String> {
 // ════════════════════════════════════════════════════════════════════════
 
 async fn generic_api(args: &serde_json::Value) -> Result<String, String> {
-    let token = load_microsoft_token()?;
+    let token = load_microsoft_token().await?;
     let method = args["method"].as_str().ok_or("'method' is required")?;
     let url = args["url"].as_str().ok_or("'url' is required")?;
 
@@ -9,12 +9,18 @@ use crate::atoms::error::EngineResult;
 use crate::atoms::types::*;
 use crate::engine::skills;
 use crate::engine::state::EngineState;
+use crate::engine::tool_args;
+use crate::engine::tool_errors;
 use crate::engine::util::safe_truncate;
 use log::{debug, info};
+use std::collections::HashMap;
+use std::sync::LazyLock;
 use tauri::Manager;
 
 pub mod agent_comms;
 pub mod agents;
+pub mod archive;
+pub mod audio;
 pub mod canvas;
 pub mod canvas_dashboards;
 pub mod canvas_templates;
@@ -22,24 +28,32 @@ pub mod coinbase;
 pub mod dex;
 pub mod discord;
 pub mod discourse;
+pub mod download;
 pub mod exec;
 pub mod fetch;
 pub mod filesystem;
+pub mod followups;
 pub mod google;
+pub mod handoff;
 pub mod integrations;
+pub mod location;
 pub mod memory;
 pub mod microsoft;
 pub mod n8n;
+pub mod nostr;
 pub mod request_tools;
+pub mod screen;
 pub mod service_api;
 pub mod skill_output;
 pub mod skill_storage;
 pub mod skills_tools;
+pub mod social;
 pub mod solana;
 pub mod soul;
 pub mod squads;
 pub mod tasks;
 pub mod telegram;
+pub mod translate;
 pub mod web;
 pub mod worker_delegate;
 
@@ -106,11 +120,19 @@ pub fn builtin_tools() -> Vec<ToolDefinition> {
     let mut tools = Vec::new();
     tools.extend(exec::definitions());
     tools.extend(fetch::definitions());
+    tools.extend(download::definitions());
     tools.extend(filesystem::definitions());
+    tools.extend(archive::definitions());
     tools.extend(soul::definitions());
     tools.extend(memory::definitions());
     tools.extend(web::definitions());
+    tools.extend(audio::definitions());
+    tools.extend(translate::definitions());
+    tools.extend(location::definitions());
+    tools.extend(screen::definitions());
     tools.extend(tasks::definitions());
+    tools.extend(followups::definitions());
+    tools.extend(handoff::definitions());
     tools.extend(agents::definitions());
     tools.extend(skills_tools::definitions());
     tools.extend(skill_output::definitions());
@@ -126,6 +148,46 @@ pub fn builtin_tools() -> Vec<ToolDefinition> {
     tools
 }
 
+/// Tool groups that can be withheld from an agent (see `group_tools`).
+pub const TOOL_GROUPS: &[&str] = &[
+    "exec",
+    "filesystem",
+    "web",
+    "memory",
+    "comms",
+    "skills",
+    "mcp",
+];
+
+/// Return the tools in one of `TOOL_GROUPS` (empty for an unknown group).
+/// "skills" covers every skill's tools, enabled or not.
+pub fn group_tools(group: &str, app_handle: &tauri::AppHandle) -> Vec<ToolDefinition> {
+    match group {
+        "exec" => exec::definitions(),
+        "filesystem" => [
+            filesystem::definitions(),
+            archive::definitions(),
+            download::definitions(),
+        ]
+        .concat(),
+        "web" => [web::definitions(), fetch::definitions()].concat(),
+        "memory" => [memory::definitions(), soul::definitions()].concat(),
+        "comms" => [
+            agent_comms::definitions(),
+            handoff::definitions(),
+            squads::definitions(),
+            agents::definitions(),
+        ]
+        .concat(),
+        "skills" => {
+            let ids: Vec<String> = skills::builtin_skills().into_iter().map(|s| s.id).collect();
+            skill_tools(&ids)
+        }
+        "mcp" => mcp_tools(app_handle),
+        _ => vec![],
+    }
+}
+
 /// Return tools exposed by all connected MCP servers.
 /// Call this after builtin_tools + skill_tools to merge dynamic tools.
 pub fn mcp_tools(app_handle: &tauri::AppHandle) -> Vec<ToolDefinition> {
@@ -152,6 +214,8 @@ pub fn skill_tools(enabled_skill_ids: &[String]) -> Vec<ToolDefinition> {
             "image_gen" => tools.extend(integrations::definitions_for("image_gen")),
             "discord" => tools.extend(discord::definitions()),
             "discourse" => tools.extend(discourse::definitions()),
+            "social" => tools.extend(social::definitions()),
+            "nostr" => tools.extend(nostr::definitions()),
             "coinbase" => tools.extend(coinbase::definitions()),
             "solana_dex" => tools.extend(solana::definitions()),
             "dex" => tools.extend(dex::definitions()),
@@ -164,6 +228,39 @@ pub fn skill_tools(enabled_skill_ids: &[String]) -> Vec<ToolDefinition> {
     tools
 }
 
+// ── Argument validation ────────────────────────────────────────────────────
+
+/// Parameter schemas of the built-in and skill tools, by name.
+static SCHEMAS: LazyLock<HashMap<String, serde_json::Value>> = LazyLock::new(|| {
+    let skill_ids: Vec<String> = skills::builtin_skills().into_iter().map(|s| s.id).collect();
+    builtin_tools()
+        .into_iter()
+        .chain(skill_tools(&skill_ids))
+        .map(|t| (t.function.name, t.function.parameters))
+        .collect()
+});
+
+/// Check a call's arguments against its tool's schema (MCP tools included)
+/// and count the result. Empty when valid or when the tool has no schema.
+fn check_args(
+    name: &str,
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> Vec<tool_args::ArgError> {
+    let errors = match SCHEMAS.get(name) {
+        Some(schema) => tool_args::validate(schema, args),
+        None => match mcp_tools(app_handle)
+            .into_iter()
+            .find(|t| t.function.name == name)
+        {
+            Some(tool) => tool_args::validate(&tool.function.parameters, args),
+            None => return vec![],
+        },
+    };
+    tool_args::record(name, &errors);
+    errors
+}
+
 // ── Main executor ──────────────────────────────────────────────────────────
 
 /// Execute a single tool call and return the result.
@@ -179,6 +276,15 @@ pub async fn execute_tool(
     // to prevent credential fragments from leaking into logs.
     debug!("[engine] Executing tool: {} agent={}", name, agent_id,);
 
+    if let Some(refusal) = crate::engine::safe_mode::refusal(app_handle, name) {
+        return ToolResult {
+            tool_call_id: tool_call.id.clone(),
+            output: refusal,
+            success: false,
+            error_kind: Some(ToolErrorKind::Permission),
+        };
+    }
+
     // Default empty/whitespace args to {} — models sometimes send no args
     // for tools that take no parameters (e.g. mcp_refresh).
     let args_str = if args_str.trim().is_empty() {
@@ -208,17 +314,39 @@ pub async fn execute_tool(
                     name, parse_err,
                 ),
                 success: false,
+                error_kind: Some(ToolErrorKind::InvalidInput),
             };
         }
     };
 
-    // fetch & exec: When a worker model is configured, delegate these to the
-    // worker (Foreman) so the main model doesn't spend API tokens on
-    // data-fetching rounds. The worker is typically a cheaper model.
-    if (name == "fetch" || name == "exec") && worker_delegate::has_worker(app_handle) {
-        info!("[engine] Delegating {} to Foreman (worker model)", name);
+    // Schema check: tell the model which fields are wrong so it can fix
+    // the call, rather than letting the tool fail on bad input
+    let arg_errors = check_args(name, &args, app_handle);
+    if !arg_errors.is_empty() {
+        log::warn!(
+            "[engine] Invalid tool args for '{}': {} field(s) failed validation",
+            name,
+            arg_errors.len()
+        );
+        return ToolResult {
+            tool_call_id: tool_call.id.clone(),
+            output: tool_args::error_message(name, &arg_errors),
+            success: false,
+            error_kind: Some(ToolErrorKind::InvalidInput),
+        };
+    }
+
+    // Foreman delegation: when a delegation rule matches (default: fetch and
+    // mcp_*), hand the call to the worker model so the main model doesn't
+    // spend API tokens on data-fetching rounds.
+    if let Some(route) = worker_delegate::route_for(app_handle, name, &tool_call.function.arguments)
+    {
+        info!(
+            "[engine] Delegating {} to Foreman worker '{}'",
+            name, route.worker
+        );
         if let Some(worker_result) =
-            worker_delegate::delegate_to_worker(tool_call, app_handle, agent_id).await
+            worker_delegate::delegate_to_worker(tool_call, app_handle, agent_id, &route).await
         {
             return worker_result;
         }
@@ -233,11 +361,19 @@ pub async fn execute_tool(
     let result = None
         .or(exec::execute(name, &args, app_handle, agent_id).await)
         .or(fetch::execute(name, &args, app_handle).await)
+        .or(download::execute(name, &args, app_handle, agent_id).await)
         .or(filesystem::execute(name, &args, agent_id).await)
+        .or(archive::execute(name, &args, agent_id).await)
         .or(soul::execute(name, &args, app_handle, agent_id).await)
         .or(memory::execute(name, &args, app_handle, agent_id).await)
         .or(web::execute(name, &args, app_handle).await)
+        .or(audio::execute(name, &args, app_handle, agent_id).await)
+        .or(translate::execute(name, &args, app_handle).await)
+        .or(location::execute(name, &args, app_handle).await)
+        .or(screen::execute(name, &args).await)
         .or(tasks::execute(name, &args, app_handle, agent_id).await)
+        .or(followups::execute(name, &args, app_handle, agent_id).await)
+        .or(handoff::execute(name, &args, app_handle).await)
         .or(agents::execute(name, &args, app_handle, agent_id).await)
         .or(skills_tools::execute(name, &args, app_handle, agent_id).await)
         .or(skill_output::execute(name, &args, app_handle, agent_id).await)
@@ -256,37 +392,25 @@ pub async fn execute_tool(
         .or(dex::execute(name, &args, app_handle).await)
         .or(discord::execute(name, &args, app_handle).await)
         .or(discourse::execute(name, &args, app_handle).await)
-        .or(google::execute(name, &args, app_handle).await)
+        .or(social::execute(name, &args, app_handle, agent_id).await)
+        .or(nostr::execute(name, &args, app_handle).await)
+        .or(google::execute(name, &args, app_handle, agent_id).await)
         .or(microsoft::execute(name, &args, app_handle).await)
         .or(service_api::execute(name, &args, app_handle).await);
 
     // Try MCP tools (prefixed with `mcp_`) if no built-in handled it.
-    // When a worker_model is configured, delegate MCP calls to the local
-    // Ollama worker instead of executing directly — zero API cost.
+    // Delegation (if any rule matched) already happened above.
     let result = match result {
         Some(r) => r,
         None if name.starts_with("mcp_") => {
-            // Try worker delegation first (local Ollama model)
-            if let Some(worker_result) =
-                worker_delegate::delegate_to_worker(tool_call, app_handle, agent_id).await
-            {
-                if worker_result.success {
-                    Ok(worker_result.output)
-                } else {
-                    Err(worker_result.output)
+            if let Some(state) = app_handle.try_state::<EngineState>() {
+                let reg = state.mcp_registry.lock().await;
+                match reg.execute_tool(name, &args).await {
+                    Some(r) => r,
+                    None => Err(format!("Unknown tool: {}", name)),
                 }
             } else {
-                // No worker configured — fall back to direct MCP execution
-                info!("[engine] No worker model configured, executing MCP tool directly");
-                if let Some(state) = app_handle.try_state::<EngineState>() {
-                    let reg = state.mcp_registry.lock().await;
-                    match reg.execute_tool(name, &args).await {
-                        Some(r) => r,
-                        None => Err(format!("Unknown tool: {}", name)),
-                    }
-                } else {
-                    Err(format!("Unknown tool: {}", name))
-                }
+                Err(format!("Unknown tool: {}", name))
             }
         }
         None => Err(format!("Unknown tool: {}", name)),
@@ -297,9 +421,11 @@ pub async fn execute_tool(
             tool_call_id: tool_call.id.clone(),
             output,
             success: true,
+            error_kind: None,
         },
         Err(err) => ToolResult {
             tool_call_id: tool_call.id.clone(),
+            error_kind: tool_errors::classify(&err),
             output: format!("Error: {}", err),
             success: false,
         },
@@ -309,9 +435,22 @@ pub async fn execute_tool(
 // ── Workspace helpers ──────────────────────────────────────────────────────
 
 /// Get the per-agent workspace directory path.
-/// Each agent gets its own isolated workspace under the Paw data root.
+/// Each agent gets its own isolated workspace under the Paw data root,
+/// unless the call runs inside `with_workspace`.
 pub fn agent_workspace(agent_id: &str) -> std::path::PathBuf {
-    crate::engine::paths::agent_workspace_dir(agent_id)
+    WORKSPACE_OVERRIDE
+        .try_with(|dir| dir.clone())
+        .unwrap_or_else(|_| crate::engine::paths::agent_workspace_dir(agent_id))
+}
+
+tokio::task_local! {
+    static WORKSPACE_OVERRIDE: std::path::PathBuf;
+}
+
+/// Run `fut` with `dir` as the workspace of every agent's tool calls
+/// (orchestrator delegations with isolated workspaces).
+pub async fn with_workspace<F: std::future::Future>(dir: std::path::PathBuf, fut: F) -> F::Output {
+    WORKSPACE_OVERRIDE.scope(dir, fut).await
 }
 
 /// Ensure the agent's workspace directory exists.
@@ -0,0 +1,314 @@
+// Paw Agent Engine — Nostr tools
+// nostr_publish_note, nostr_react, nostr_fetch_profile
+//
+// Sign with the Nostr bridge's key and talk to its configured relays
+// directly, so they work whether or not the bridge is running. Notes and
+// reactions are public, so they always go through the outbox
+// (`tool_metadata::always_reviewed`).
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::nostr;
+use serde_json::{json, Value};
+
+const KIND_METADATA: u64 = 0;
+const KIND_TEXT_NOTE: u64 = 1;
+const KIND_REACTION: u64 = 7;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "nostr_publish_note".into(),
+                description: "Publish a public Nostr text note (kind 1) from the bot's key to its configured relays, optionally as a reply to another note. The note is queued in the outbox and only published after the user approves it.".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "text": { "type": "string", "description": "Note text" },
+                        "reply_to": { "type": "string", "description": "Note to reply to: note1… id, nostr: URI or hex event id" },
+                        "hashtags": { "type": "array", "items": { "type": "string" }, "description": "Hashtags to tag the note with (without #)" }
+                    },
+                    "required": ["text"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "nostr_react".into(),
+                description: "React to a Nostr note (NIP-25): a like (\"+\"), dislike (\"-\") or a single emoji. The reaction is queued in the outbox and only published after the user approves it.".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "event_id": { "type": "string", "description": "Note to react to: note1… id, nostr: URI or hex event id" },
+                        "reaction": { "type": "string", "description": "\"+\" (default), \"-\" or an emoji" }
+                    },
+                    "required": ["event_id"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "nostr_fetch_profile".into(),
+                description: "Look up a Nostr profile (kind-0 metadata): name, about, picture, NIP-05 identifier, and Lightning address for zaps (lud16/lud06). Defaults to the bot's own profile.".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "pubkey": { "type": "string", "description": "npub1…, nostr: URI or hex pubkey (default: the bot's own)" }
+                    }
+                }),
+            },
+        },
+    ]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &Value,
+    app_handle: &tauri::AppHandle,
+) -> Option<Result<String, String>> {
+    let result = match name {
+        "nostr_publish_note" => exec_publish_note(args, app_handle).await,
+        "nostr_react" => exec_react(args, app_handle).await,
+        "nostr_fetch_profile" => exec_fetch_profile(args, app_handle).await,
+        _ => return None,
+    };
+    Some(result.map_err(|e| e.to_string()))
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────
+
+/// Look up one event by id on the configured relays.
+async fn fetch_event(app_handle: &tauri::AppHandle, id: &str) -> EngineResult<Option<Value>> {
+    let events = nostr::query(app_handle, &json!({ "ids": [id], "limit": 1 })).await?;
+    Ok(events.into_iter().find(|e| e["id"].as_str() == Some(id)))
+}
+
+/// NIP-10 tags for a reply: keep the thread root, mark the parent as the
+/// reply target, and mention the parent's author (plus whoever it mentioned).
+fn reply_tags(parent: &Value) -> Vec<Value> {
+    let parent_id = parent["id"].as_str().unwrap_or_default();
+    let parent_author = parent["pubkey"].as_str().unwrap_or_default();
+    let parent_tags = parent["tags"].as_array().cloned().unwrap_or_default();
+
+    let root = parent_tags
+        .iter()
+        .find(|t| t[0] == "e" && t[3] == "root")
+        .and_then(|t| t[1].as_str());
+    let mut tags = match root {
+        Some(root) => vec![
+            json!(["e", root, "", "root"]),
+            json!(["e", parent_id, "", "reply"]),
+        ],
+        None => vec![json!(["e", parent_id, "", "root"])],
+    };
+
+    let mut mentioned = vec![parent_author.to_string()];
+    for t in &parent_tags {
+        if let (Some("p"), Some(pk)) = (t[0].as_str(), t[1].as_str()) {
+            if !mentioned.iter().any(|m| m == pk) {
+                mentioned.push(pk.to_string());
+            }
+        }
+    }
+    tags.extend(mentioned.into_iter().map(|pk| json!(["p", pk])));
+    tags
+}
+
+/// One line per relay for the tool output.
+fn relay_report(outcomes: &[(String, Result<(), String>)]) -> String {
+    outcomes
+        .iter()
+        .map(|(relay, r)| match r {
+            Ok(()) => format!("- {}: accepted", relay),
+            Err(e) => format!("- {}: FAILED — {}", relay, e),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ── Executors ──────────────────────────────────────────────────────────
+
+async fn exec_publish_note(args: &Value, app_handle: &tauri::AppHandle) -> EngineResult<String> {
+    let text = args["text"]
+        .as_str()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("nostr_publish_note: missing 'text'")?;
+
+    let mut tags = match args["reply_to"].as_str().filter(|s| !s.trim().is_empty()) {
+        Some(reply_to) => {
+            let id = nostr::parse_hex_or_bech32(reply_to, "note")?;
+            let parent = fetch_event(app_handle, &id).await?.ok_or_else(|| {
+                format!(
+                    "Note {} was not found on the configured relays, so the reply can't be threaded",
+                    reply_to
+                )
+            })?;
+            reply_tags(&parent)
+        }
+        None => vec![],
+    };
+    if let Some(hashtags) = args["hashtags"].as_array() {
+        for tag in hashtags.iter().filter_map(|h| h.as_str()) {
+            let tag = tag.trim().trim_start_matches('#').to_lowercase();
+            if !tag.is_empty() {
+                tags.push(json!(["t", tag]));
+            }
+        }
+    }
+
+    let (event, outcomes) =
+        nostr::sign_and_publish(app_handle, KIND_TEXT_NOTE, &json!(tags), text).await?;
+    let id = nostr::hex_decode(event["id"].as_str().unwrap_or_default())?;
+    Ok(format!(
+        "Published note {}\n{}",
+        nostr::bech32_encode("note", &id),
+        relay_report(&outcomes)
+    ))
+}
+
+async fn exec_react(args: &Value, app_handle: &tauri::AppHandle) -> EngineResult<String> {
+    let target = args["event_id"]
+        .as_str()
+        .ok_or("nostr_react: missing 'event_id'")?;
+    let id = nostr::parse_hex_or_bech32(target, "note")?;
+    let reaction = args["reaction"]
+        .as_str()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or("+");
+    if reaction.chars().count() > 8 {
+        return Err("nostr_react: 'reaction' must be \"+\", \"-\" or a single emoji".into());
+    }
+
+    // NIP-25: `e` and `p` of the reacted-to note, plus its kind
+    let note = fetch_event(app_handle, &id)
+        .await?
+        .ok_or_else(|| format!("Note {} was not found on the configured relays", target))?;
+    let kind = note["kind"].as_u64().unwrap_or(KIND_TEXT_NOTE);
+    let tags = json!([
+        ["e", id],
+        ["p", note["pubkey"].as_str().unwrap_or_default()],
+        ["k", kind.to_string()],
+    ]);
+
+    let (_, outcomes) = nostr::sign_and_publish(app_handle, KIND_REACTION, &tags, reaction).await?;
+    Ok(format!(
+        "Reacted {} to {}\n{}",
+        reaction,
+        target,
+        relay_report(&outcomes)
+    ))
+}
+
+async fn exec_fetch_profile(args: &Value, app_handle: &tauri::AppHandle) -> EngineResult<String> {
+    let pubkey = match args["pubkey"].as_str().filter(|s| !s.trim().is_empty()) {
+        Some(pk) => nostr::parse_hex_or_bech32(pk, "npub")?,
+        None => nostr::own_pubkey(app_handle)?,
+    };
+    let npub = nostr::bech32_encode("npub", &nostr::hex_decode(&pubkey)?);
+
+    let events = nostr::query(
+        app_handle,
+        &json!({ "kinds": [KIND_METADATA], "authors": [pubkey], "limit": 1 }),
+    )
+    .await?;
+    let Some(event) = events.first() else {
+        return Ok(format!(
+            "No profile (kind 0) found for {} on the configured relays.",
+            npub
+        ));
+    };
+    format_profile(&npub, &pubkey, event)
+}
+
+/// Render kind-0 metadata (a JSON object in `content`) as text.
+fn format_profile(npub: &str, pubkey: &str, event: &Value) -> EngineResult<String> {
+    let meta: Value = serde_json::from_str(event["content"].as_str().unwrap_or("{}"))
+        .map_err(|e| format!("Profile metadata is not valid JSON: {}", e))?;
+
+    let mut out = format!("npub: {}\nhex: {}\n", npub, pubkey);
+    for (label, keys) in [
+        ("Display name", &["display_name", "displayName"][..]),
+        ("Name", &["name"][..]),
+        ("About", &["about"][..]),
+        ("Picture", &["picture"][..]),
+        ("Banner", &["banner"][..]),
+        ("Website", &["website"][..]),
+        ("NIP-05", &["nip05"][..]),
+        ("Lightning address (lud16)", &["lud16"][..]),
+        ("LNURL (lud06)", &["lud06"][..]),
+    ] {
+        if let Some(v) = keys
+            .iter()
+            .find_map(|k| meta[*k].as_str())
+            .filter(|v| !v.trim().is_empty())
+        {
+            out.push_str(&format!("{}: {}\n", label, v.trim()));
+        }
+    }
+    let zappable = ["lud16", "lud06"]
+        .iter()
+        .any(|k| meta[*k].as_str().is_some_and(|v| !v.trim().is_empty()));
+    out.push_str(if zappable {
+        "Zaps: accepted\n"
+    } else {
+        "Zaps: no Lightning address published\n"
+    });
+    if let Some(ts) = event["created_at"]
+        .as_i64()
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+    {
+        out.push_str(&format!("Updated: {}\n", ts.format("%Y-%m-%d %H:%M UTC")));
+    }
+    Ok(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_tags_follow_nip10() {
+        let top = json!({ "id": "aa", "pubkey": "p1", "tags": [] });
+        assert_eq!(
+            reply_tags(&top),
+            vec![json!(["e", "aa", "", "root"]), json!(["p", "p1"])]
+        );
+
+        let nested = json!({
+            "id": "bb",
+            "pubkey": "p2",
+            "tags": [["e", "aa", "", "root"], ["p", "p1"], ["p", "p2"]]
+        });
+        assert_eq!(
+            reply_tags(&nested),
+            vec![
+                json!(["e", "aa", "", "root"]),
+                json!(["e", "bb", "", "reply"]),
+                json!(["p", "p2"]),
+                json!(["p", "p1"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_lists_zap_address() {
+        let event = json!({
+            "created_at": 1_700_000_000,
+            "content": r#"{"name":"paw","about":"hi","lud16":"paw@getalby.com"}"#
+        });
+        let out = format_profile("npub1x", "ab", &event).unwrap();
+        assert!(out.contains("Name: paw"));
+        assert!(out.contains("Lightning address (lud16): paw@getalby.com"));
+        assert!(out.contains("Zaps: accepted"));
+
+        let bare = json!({ "content": "{}" });
+        assert!(format_profile("npub1x", "ab", &bare)
+            .unwrap()
+            .contains("no Lightning address"));
+        assert!(format_profile("npub1x", "ab", &json!({ "content": "nope" })).is_err());
+    }
+}
@@ -142,37 +142,16 @@ async fn execute_request_tools(
         } else {
             // Even without embeddings, ensure all tools are registered for BM25/keyword search.
             // Save tools with empty embeddings so BM25 (Tier 3) and domain keyword (Tier 4) work.
-            let cached_names = {
-                let db = conn.lock();
-                PersistentToolRegistry::cached_tool_names(&db).unwrap_or_default()
-            };
-            let now = chrono::Utc::now().timestamp();
-            for tool in &all_tools {
-                if !cached_names.contains(&tool.function.name) {
-                    let record = crate::engine::tool_registry::ToolEmbeddingRecord {
-                        tool_name: tool.function.name.clone(),
-                        description: tool.function.description.clone(),
-                        embedding: Vec::new(),
-                        domain: tool_index::tool_domain(&tool.function.name).to_string(),
-                        source: if tool.function.name.starts_with("mcp_") {
-                            crate::engine::tool_registry::ToolSource::Mcp
-                        } else {
-                            crate::engine::tool_registry::ToolSource::Builtin
-                        },
-                        updated_at: now,
-                    };
-                    let db = conn.lock();
-                    PersistentToolRegistry::save_embedding(&db, &record).ok();
-                }
-            }
+            PersistentToolRegistry::register_without_embeddings(&all_tools, &conn);
         }
     }
 
     // Search using the persistent registry (four-tier failover: Vector → BM25 → Domain keyword)
     let registry = state.persistent_tool_registry.lock().await;
+    let top_k = registry.config().top_k;
     let conn = state.store.conn();
     let search_results = registry
-        .search(query, 6, emb_client.as_ref(), &conn)
+        .search(query, top_k, emb_client.as_ref(), &conn)
         .await
         .unwrap_or_default();
     drop(registry);
@@ -201,7 +180,7 @@ async fn execute_request_tools(
             }
             let tool_index = state.tool_index.lock().await;
             tool_index
-                .search(query, 6, client)
+                .search(query, top_k, client)
                 .await
                 .unwrap_or_default()
         } else {
@@ -254,7 +233,7 @@ async fn execute_request_tools(
 /// Build the complete list of tools for indexing.
 /// This includes builtins + skill tools + MCP tools (e.g. mcp_n8n_* workflow tools).
 /// MCP tools are passed in because we need them from the registry (separate lock).
-fn build_all_tools_for_index(
+pub(crate) fn build_all_tools_for_index(
     state: &EngineState,
     mcp_tools: &[ToolDefinition],
 ) -> Vec<ToolDefinition> {
@@ -276,6 +255,8 @@ fn build_all_tools_for_index(
         "google_workspace".to_string(),
         "discord".to_string(),
         "discourse".to_string(),
+        "social".to_string(),
+        "nostr".to_string(),
         "trello".to_string(),
     ];
     tools.extend(crate::engine::tools::skill_tools(&all_skill_ids));
@@ -0,0 +1,328 @@
+// Paw Agent Engine — Screen capture tool
+// screen_capture
+// Captures the user's display (or one window) into the screenshot store
+// and shows the image to the model. Never auto-approved: the agent loop
+// asks for every call unless the user consented for the session
+// (`tool_metadata::needs_consent`).
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use log::info;
+use std::path::{Path, PathBuf};
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "screen_capture".into(),
+            description: "Take a screenshot of the user's screen (or one window) so you can see what they see — e.g. 'what's wrong with this dialog on my screen?'. The user approves every capture. The image is attached after the tool result.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "window": { "type": "string", "description": "Capture only the first window whose title or app name contains this text. Omit for the whole display." },
+                    "display": { "type": "integer", "description": "Display number, 1 = main (default 1). Ignored with 'window'." }
+                }
+            }),
+        },
+    }]
+}
+
+pub async fn execute(name: &str, args: &serde_json::Value) -> Option<Result<String, String>> {
+    match name {
+        "screen_capture" => Some(
+            capture(args)
+                .await
+                .map(|c| c.summary())
+                .map_err(|e| e.to_string()),
+        ),
+        _ => None,
+    }
+}
+
+/// A saved screen capture.
+pub struct Capture {
+    pub path: PathBuf,
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// "display 1" or "window matching '…'"
+    pub target: String,
+}
+
+impl Capture {
+    pub fn summary(&self) -> String {
+        format!(
+            "Screen captured ({}): {}\nSize: {} x {}, {} bytes",
+            self.target,
+            self.path.display(),
+            self.width,
+            self.height,
+            self.png.len()
+        )
+    }
+
+    /// The capture as a user message with an image block, so vision models
+    /// can look at it after the tool result.
+    pub fn image_message(&self) -> Message {
+        use base64::Engine as _;
+        let data = base64::engine::general_purpose::STANDARD.encode(&self.png);
+        Message {
+            role: Role::User,
+            content: MessageContent::Blocks(vec![
+                ContentBlock::Text {
+                    text: format!("[Screen capture — {}]", self.target),
+                },
+                ContentBlock::ImageUrl {
+                    image_url: ImageUrlData {
+                        url: format!("data:image/png;base64,{}", data),
+                        detail: Some("high".into()),
+                    },
+                },
+            ]),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        }
+    }
+}
+
+/// Capture the display or a window into `paw-screenshots/`.
+pub async fn capture(args: &serde_json::Value) -> EngineResult<Capture> {
+    let window = args["window"]
+        .as_str()
+        .map(str::trim)
+        .filter(|w| !w.is_empty());
+    let display = args["display"].as_u64().unwrap_or(1).max(1) as u32;
+
+    let dir = std::env::temp_dir().join("paw-screenshots");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "screen-{}.png",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    let target = match window {
+        Some(w) => format!("window matching '{}'", w),
+        None => format!("display {}", display),
+    };
+    info!("[screen] Capturing {}", target);
+    capture_to(&path, window, display).await?;
+
+    let png = std::fs::read(&path)
+        .map_err(|e| format!("screen_capture: no image was written ({})", e))?;
+    let (width, height) = png_size(&png).ok_or("screen_capture: the capture is not a PNG image")?;
+    Ok(Capture {
+        path,
+        png,
+        width,
+        height,
+        target,
+    })
+}
+
+/// Width and height from a PNG's IHDR chunk.
+fn png_size(png: &[u8]) -> Option<(u32, u32)> {
+    if png.len() < 24 || &png[..8] != b"\x89PNG\r\n\x1a\n" || &png[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Run a capture command; `Ok(false)` when the program isn't installed.
+async fn run(program: &str, args: &[&str]) -> EngineResult<bool> {
+    match tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+    {
+        Ok(out) if out.status.success() => Ok(true),
+        Ok(out) => Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&out.stderr).trim()
+        )
+        .into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(format!("Failed to run {}: {}", program, e).into()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn capture_to(path: &Path, window: Option<&str>, display: u32) -> EngineResult<()> {
+    let out = path.to_string_lossy();
+    let args: Vec<String> = match window {
+        Some(w) => {
+            // System Events gives the window's bounds; screencapture grabs that rect
+            let needle = w.replace('\\', "\\\\").replace('"', "\\\"");
+            let script = format!(
+                r#"tell application "System Events"
+  repeat with p in (processes whose visible is true)
+    repeat with w in windows of p
+      if (name of w contains "{0}") or (name of p contains "{0}") then
+        set {{x, y}} to position of w
+        set {{ww, hh}} to size of w
+        return (x as text) & "," & (y as text) & "," & (ww as text) & "," & (hh as text)
+      end if
+    end repeat
+  end repeat
+end tell
+return """#,
+                needle
+            );
+            let output = tokio::process::Command::new("osascript")
+                .args(["-e", &script])
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run osascript: {}", e))?;
+            let rect = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if rect.is_empty() {
+                return Err(format!(
+                    "No visible window matches '{}' (Paw may need Accessibility permission)",
+                    w
+                )
+                .into());
+            }
+            vec!["-x".into(), "-R".into(), rect, out.into_owned()]
+        }
+        None => vec![
+            "-x".into(),
+            "-D".into(),
+            display.to_string(),
+            out.into_owned(),
+        ],
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if run("screencapture", &args).await? {
+        Ok(())
+    } else {
+        Err("screencapture not found".into())
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn capture_to(path: &Path, window: Option<&str>, display: u32) -> EngineResult<()> {
+    let out = path.to_string_lossy();
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    if let Some(w) = window {
+        if wayland {
+            return Err("Capturing a single window isn't supported on Wayland — capture the whole display instead".into());
+        }
+        let found = tokio::process::Command::new("xdotool")
+            .args(["search", "--onlyvisible", "--name", w])
+            .output()
+            .await
+            .map_err(|_| "Window capture needs xdotool and ImageMagick (import)")?;
+        let id = String::from_utf8_lossy(&found.stdout)
+            .lines()
+            .next()
+            .map(str::to_string)
+            .ok_or_else(|| format!("No visible window matches '{}'", w))?;
+        if run("import", &["-window", &id, &out]).await? {
+            return Ok(());
+        }
+        return Err("Window capture needs ImageMagick (import)".into());
+    }
+
+    if display > 1 {
+        info!("[screen] Display selection isn't supported on Linux; capturing everything");
+    }
+    let candidates: &[(&str, &[&str])] = if wayland {
+        &[
+            ("grim", &[]),
+            ("gnome-screenshot", &["-f"]),
+            ("spectacle", &["-b", "-n", "-o"]),
+        ]
+    } else {
+        &[
+            ("import", &["-window", "root"]),
+            ("scrot", &["-o"]),
+            ("gnome-screenshot", &["-f"]),
+            ("spectacle", &["-b", "-n", "-o"]),
+        ]
+    };
+    for (program, flags) in candidates {
+        let mut args = flags.to_vec();
+        args.push(&out);
+        if run(program, &args).await? {
+            return Ok(());
+        }
+    }
+    Err(if wayland {
+        "No screenshot tool found — install grim or gnome-screenshot"
+    } else {
+        "No screenshot tool found — install ImageMagick (import) or scrot"
+    }
+    .into())
+}
+
+#[cfg(target_os = "windows")]
+async fn capture_to(path: &Path, window: Option<&str>, display: u32) -> EngineResult<()> {
+    let out = path.to_string_lossy().replace('\'', "''");
+    let bounds = match window {
+        Some(w) => {
+            let needle: String = w
+                .chars()
+                .filter(|c| !matches!(c, '*' | '?' | '[' | ']'))
+                .collect::<String>()
+                .replace('\'', "''");
+            format!(
+                r#"Add-Type @"
+using System; using System.Runtime.InteropServices;
+public struct PawRect {{ public int Left, Top, Right, Bottom; }}
+public static class PawWin {{ [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr h, out PawRect r); }}
+"@
+$p = Get-Process | Where-Object {{ $_.MainWindowHandle -ne 0 -and ($_.MainWindowTitle -like '*{0}*' -or $_.ProcessName -like '*{0}*') }} | Select-Object -First 1
+if (-not $p) {{ [Console]::Error.WriteLine('No visible window matches ''{0}'''); exit 3 }}
+$r = New-Object PawRect
+[PawWin]::GetWindowRect($p.MainWindowHandle, [ref]$r) | Out-Null
+$b = [System.Drawing.Rectangle]::FromLTRB($r.Left, $r.Top, $r.Right, $r.Bottom)"#,
+                needle
+            )
+        }
+        None => format!(
+            "$s = [System.Windows.Forms.Screen]::AllScreens\n$b = $s[[Math]::Min({}, $s.Length - 1)].Bounds",
+            display - 1
+        ),
+    };
+    let script = format!(
+        r#"Add-Type -AssemblyName System.Windows.Forms, System.Drawing
+{}
+$bmp = New-Object System.Drawing.Bitmap $b.Width, $b.Height
+$g = [System.Drawing.Graphics]::FromImage($bmp)
+$g.CopyFromScreen($b.Location, [System.Drawing.Point]::Empty, $b.Size)
+$bmp.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)"#,
+        bounds, out
+    );
+    if run(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", &script],
+    )
+    .await?
+    {
+        Ok(())
+    } else {
+        Err("PowerShell not found".into())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+async fn capture_to(_path: &Path, _window: Option<&str>, _display: u32) -> EngineResult<()> {
+    Err("Screen capture isn't supported on this platform".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_png_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(1920u32.to_be_bytes());
+        png.extend(1080u32.to_be_bytes());
+        assert_eq!(png_size(&png), Some((1920, 1080)));
+        assert_eq!(png_size(b"GIF89a"), None);
+    }
+}
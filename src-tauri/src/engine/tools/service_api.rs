@@ -21,45 +21,30 @@ use std::time::Duration;
 
 // ── Token helper ───────────────────────────────────────────────────────
 
-/// Load the OAuth access token for any service from the encrypted vault.
-fn load_service_token(service_id: &str) -> Result<String, String> {
-    use crate::engine::key_vault;
-    use crate::engine::skills::crypto::{decrypt_credential, get_vault_key};
-
-    let vault_key = get_vault_key().map_err(|e| format!("Vault key error: {e}"))?;
-
-    // Try the standard key format, then common aliases
-    let key = format!("oauth:{}", service_id);
-    let encrypted = key_vault::get(&key).ok_or_else(|| {
-        let display =
-            provider_registry::display_name(service_id).unwrap_or_else(|| service_id.to_string());
-        format!(
-            "{display} is not connected. The user needs to connect {display} — \
+/// Load the OAuth access token for any service via the shared token
+/// manager (refreshed ahead of expiry).
+async fn load_service_token(service_id: &str) -> Result<String, String> {
+    use crate::engine::oauth_tokens::{self, TokenError};
+
+    let display =
+        provider_registry::display_name(service_id).unwrap_or_else(|| service_id.to_string());
+    oauth_tokens::access_token(service_id)
+        .await
+        .map_err(|e| match e {
+            TokenError::NotConnected => format!(
+                "{display} is not connected. The user needs to connect {display} — \
                  go to Integrations → {display} → Connect."
-        )
-    })?;
-
-    let json = match decrypt_credential(&encrypted, &vault_key) {
-        Ok(j) => j,
-        Err(_) => {
-            key_vault::remove(&key);
-            let display = provider_registry::display_name(service_id)
-                .unwrap_or_else(|| service_id.to_string());
-            return Err(format!(
+            ),
+            TokenError::Corrupt => format!(
                 "{display} OAuth token is corrupted (likely after an app update). \
                  The user needs to reconnect {display} — go to Integrations → {display} → Connect."
-            ));
-        }
-    };
-
-    #[derive(serde::Deserialize)]
-    struct Tokens {
-        access_token: String,
-    }
-    let tokens: Tokens =
-        serde_json::from_str(&json).map_err(|e| format!("Token parse error: {e}"))?;
-
-    Ok(tokens.access_token)
+            ),
+            TokenError::ReconnectRequired(_) => format!(
+                "{display} access has expired or was revoked. \
+                 The user needs to reconnect {display} — go to Integrations → {display} → Connect."
+            ),
+            TokenError::Other(e) => format!("{display} token error: {e}"),
+        })
 }
 
 // ── Tool Definition ────────────────────────────────────────────────────
@@ -142,7 +127,7 @@ async fn execute_service_api(args: &serde_json::Value) -> Result<String, String>
         return Err("Invalid path — must be a relative API path, not a full URL.".into());
     }
 
-    let token = load_service_token(service)?;
+    let token = load_service_token(service).await?;
 
     // Build the full URL
     let base = base_url.trim_end_matches('/');
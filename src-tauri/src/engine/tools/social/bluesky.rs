@@ -0,0 +1,256 @@
+// social/bluesky.rs — Bluesky (AT Protocol)
+//
+// Session:  com.atproto.server.createSession (handle + app password)
+// Posts:    com.atproto.repo.createRecord (app.bsky.feed.post)
+// Media:    com.atproto.repo.uploadBlob → app.bsky.embed.images
+// Search:   app.bsky.feed.searchPosts (proxied through the PDS)
+//
+// Bluesky does not linkify text server-side, so URLs in the post get link
+// facets here. Replies take a bsky.app post URL or an at:// URI.
+
+use super::{
+    client, read_json, Credentials, Media, Network, SearchHit, CRED_BLUESKY_HANDLE,
+    CRED_BLUESKY_PASSWORD, CRED_BLUESKY_SERVICE,
+};
+use crate::atoms::error::EngineResult;
+use serde_json::{json, Value};
+
+const DEFAULT_SERVICE: &str = "https://bsky.social";
+
+struct Session {
+    service: String,
+    did: String,
+    access_jwt: String,
+}
+
+async fn login(http: &reqwest::Client, creds: &Credentials) -> EngineResult<Session> {
+    let service = creds
+        .get(CRED_BLUESKY_SERVICE)
+        .unwrap_or(DEFAULT_SERVICE)
+        .trim_end_matches('/')
+        .to_string();
+    let resp = http
+        .post(format!("{}/xrpc/com.atproto.server.createSession", service))
+        .json(&json!({
+            "identifier": creds.require(CRED_BLUESKY_HANDLE, Network::Bluesky)?.trim_start_matches('@'),
+            "password": creds.require(CRED_BLUESKY_PASSWORD, Network::Bluesky)?,
+        }))
+        .send()
+        .await?;
+    let session = read_json(Network::Bluesky, resp).await?;
+    Ok(Session {
+        service,
+        did: session["did"].as_str().unwrap_or_default().to_string(),
+        access_jwt: session["accessJwt"]
+            .as_str()
+            .ok_or("bluesky: login returned no access token")?
+            .to_string(),
+    })
+}
+
+pub(crate) async fn post(
+    creds: &Credentials,
+    text: &str,
+    reply_to: Option<&str>,
+    media: &[Media],
+) -> EngineResult<String> {
+    let http = client();
+    let session = login(&http, creds).await?;
+
+    let mut record = json!({
+        "$type": "app.bsky.feed.post",
+        "text": text,
+        "createdAt": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+    });
+    let facets = link_facets(text);
+    if !facets.is_empty() {
+        record["facets"] = json!(facets);
+    }
+    if let Some(r) = reply_to {
+        record["reply"] = reply_ref(&http, &session, r).await?;
+    }
+    if !media.is_empty() {
+        let mut images = Vec::new();
+        for m in media {
+            let resp = http
+                .post(format!(
+                    "{}/xrpc/com.atproto.repo.uploadBlob",
+                    session.service
+                ))
+                .bearer_auth(&session.access_jwt)
+                .header(reqwest::header::CONTENT_TYPE, m.mime)
+                .body(m.bytes.clone())
+                .send()
+                .await?;
+            let blob = read_json(Network::Bluesky, resp).await?;
+            images.push(json!({ "alt": m.alt, "image": blob["blob"] }));
+        }
+        record["embed"] = json!({ "$type": "app.bsky.embed.images", "images": images });
+    }
+
+    let resp = http
+        .post(format!(
+            "{}/xrpc/com.atproto.repo.createRecord",
+            session.service
+        ))
+        .bearer_auth(&session.access_jwt)
+        .json(&json!({
+            "repo": session.did,
+            "collection": "app.bsky.feed.post",
+            "record": record,
+        }))
+        .send()
+        .await?;
+    let created = read_json(Network::Bluesky, resp).await?;
+    Ok(web_url(created["uri"].as_str().unwrap_or_default()))
+}
+
+/// Build the `reply` field: the parent post plus the thread root.
+async fn reply_ref(
+    http: &reqwest::Client,
+    session: &Session,
+    reply_to: &str,
+) -> EngineResult<Value> {
+    let (actor, rkey) =
+        parse_post_ref(reply_to).ok_or_else(|| format!("bluesky: not a post URL: {}", reply_to))?;
+    let did = if actor.starts_with("did:") {
+        actor
+    } else {
+        let resp = http
+            .get(format!(
+                "{}/xrpc/com.atproto.identity.resolveHandle",
+                session.service
+            ))
+            .query(&[("handle", actor.as_str())])
+            .send()
+            .await?;
+        read_json(Network::Bluesky, resp).await?["did"]
+            .as_str()
+            .ok_or_else(|| format!("bluesky: unknown handle {}", actor))?
+            .to_string()
+    };
+    let uri = format!("at://{}/app.bsky.feed.post/{}", did, rkey);
+    let resp = http
+        .get(format!("{}/xrpc/app.bsky.feed.getPosts", session.service))
+        .bearer_auth(&session.access_jwt)
+        .query(&[("uris", uri.as_str())])
+        .send()
+        .await?;
+    let found = read_json(Network::Bluesky, resp).await?;
+    let parent = &found["posts"][0];
+    let cid = parent["cid"]
+        .as_str()
+        .ok_or_else(|| format!("bluesky: post not found: {}", reply_to))?;
+    let parent_ref = json!({ "uri": uri, "cid": cid });
+    let root = match &parent["record"]["reply"]["root"] {
+        Value::Object(_) => parent["record"]["reply"]["root"].clone(),
+        _ => parent_ref.clone(),
+    };
+    Ok(json!({ "root": root, "parent": parent_ref }))
+}
+
+pub(crate) async fn search(
+    creds: &Credentials,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchHit>> {
+    let http = client();
+    let session = login(&http, creds).await?;
+    let resp = http
+        .get(format!(
+            "{}/xrpc/app.bsky.feed.searchPosts",
+            session.service
+        ))
+        .bearer_auth(&session.access_jwt)
+        .query(&[("q", query), ("limit", &limit.to_string())])
+        .send()
+        .await?;
+    let found = read_json(Network::Bluesky, resp).await?;
+    Ok(found["posts"]
+        .as_array()
+        .map(|posts| {
+            posts
+                .iter()
+                .map(|p| SearchHit {
+                    author: p["author"]["handle"].as_str().unwrap_or("?").to_string(),
+                    text: p["record"]["text"].as_str().unwrap_or("").to_string(),
+                    url: web_url(p["uri"].as_str().unwrap_or("")),
+                    created_at: p["record"]["createdAt"]
+                        .as_str()
+                        .or(p["indexedAt"].as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// `(handle or DID, record key)` from a bsky.app URL or an at:// URI.
+fn parse_post_ref(s: &str) -> Option<(String, String)> {
+    let s = s.trim().trim_end_matches('/');
+    if let Some(rest) = s.strip_prefix("at://") {
+        let mut parts = rest.split('/');
+        let actor = parts.next()?;
+        let collection = parts.next()?;
+        let rkey = parts.next()?;
+        return (collection == "app.bsky.feed.post").then(|| (actor.into(), rkey.into()));
+    }
+    let (_, path) = s.split_once("/profile/")?;
+    let (actor, rkey) = path.split_once("/post/")?;
+    Some((
+        actor.to_string(),
+        rkey.split(['?', '#']).next()?.to_string(),
+    ))
+}
+
+/// `at://did/app.bsky.feed.post/rkey` → `https://bsky.app/profile/did/post/rkey`.
+fn web_url(uri: &str) -> String {
+    match parse_post_ref(uri) {
+        Some((actor, rkey)) => format!("https://bsky.app/profile/{}/post/{}", actor, rkey),
+        None => uri.to_string(),
+    }
+}
+
+/// Link facets for every http(s) URL in the text. Offsets are UTF-8 byte
+/// positions, as the protocol requires.
+fn link_facets(text: &str) -> Vec<Value> {
+    let mut facets = Vec::new();
+    let mut offset = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        let token = word.trim_end();
+        if token.starts_with("https://") || token.starts_with("http://") {
+            let url = token.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\'']);
+            facets.push(json!({
+                "index": { "byteStart": offset, "byteEnd": offset + url.len() },
+                "features": [{ "$type": "app.bsky.richtext.facet#link", "uri": url }],
+            }));
+        }
+        offset += word.len();
+    }
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn post_refs_and_facets() {
+        assert_eq!(
+            parse_post_ref("https://bsky.app/profile/alice.bsky.social/post/3kabc"),
+            Some(("alice.bsky.social".into(), "3kabc".into()))
+        );
+        assert_eq!(
+            web_url("at://did:plc:xyz/app.bsky.feed.post/3kabc"),
+            "https://bsky.app/profile/did:plc:xyz/post/3kabc"
+        );
+        assert!(parse_post_ref("at://did:plc:xyz/app.bsky.actor.profile/self").is_none());
+
+        let facets = link_facets("Café notes: https://example.com/a.");
+        assert_eq!(facets.len(), 1);
+        assert_eq!(facets[0]["index"]["byteStart"], 13);
+        assert_eq!(facets[0]["index"]["byteEnd"], 34);
+        assert_eq!(facets[0]["features"][0]["uri"], "https://example.com/a");
+    }
+}
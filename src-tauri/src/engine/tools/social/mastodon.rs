@@ -0,0 +1,208 @@
+// social/mastodon.rs — Mastodon (and compatible servers)
+//
+// Statuses:  POST /api/v1/statuses
+// Media:     POST /api/v2/media (polled until processed)
+// Search:    GET  /api/v2/search?type=statuses
+//
+// Replies accept a status ID or any post URL; URLs from other servers are
+// resolved through the user's own instance.
+
+use super::{
+    client, read_json, Credentials, Media, Network, SearchHit, CRED_MASTODON_TOKEN,
+    CRED_MASTODON_URL,
+};
+use crate::atoms::error::EngineResult;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn base(creds: &Credentials) -> EngineResult<(String, String)> {
+    let url = creds.require(CRED_MASTODON_URL, Network::Mastodon)?;
+    let url = if url.starts_with("http") {
+        url.trim_end_matches('/').to_string()
+    } else {
+        format!("https://{}", url.trim_end_matches('/'))
+    };
+    let token = creds.require(CRED_MASTODON_TOKEN, Network::Mastodon)?;
+    Ok((url, token.to_string()))
+}
+
+pub(crate) async fn post(
+    creds: &Credentials,
+    text: &str,
+    reply_to: Option<&str>,
+    media: &[Media],
+    args: &Value,
+) -> EngineResult<String> {
+    let (base, token) = base(creds)?;
+    let http = client();
+
+    let in_reply_to_id = match reply_to {
+        Some(r) => Some(resolve_status_id(&http, &base, &token, r).await?),
+        None => None,
+    };
+    let mut media_ids = Vec::new();
+    for m in media {
+        media_ids.push(upload(&http, &base, &token, m).await?);
+    }
+
+    let mut body = json!({
+        "status": text,
+        "visibility": args["visibility"].as_str().unwrap_or("public"),
+    });
+    if let Some(id) = in_reply_to_id {
+        body["in_reply_to_id"] = json!(id);
+    }
+    if !media_ids.is_empty() {
+        body["media_ids"] = json!(media_ids);
+    }
+    if let Some(cw) = args["content_warning"].as_str().filter(|s| !s.is_empty()) {
+        body["spoiler_text"] = json!(cw);
+    }
+
+    let resp = http
+        .post(format!("{}/api/v1/statuses", base))
+        .bearer_auth(&token)
+        .json(&body)
+        .send()
+        .await?;
+    let status = read_json(Network::Mastodon, resp).await?;
+    Ok(status["url"]
+        .as_str()
+        .or(status["uri"].as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+async fn upload(
+    http: &reqwest::Client,
+    base: &str,
+    token: &str,
+    media: &Media,
+) -> EngineResult<String> {
+    let part = reqwest::multipart::Part::bytes(media.bytes.clone())
+        .file_name(media.file_name.clone())
+        .mime_str(media.mime)?;
+    let mut form = reqwest::multipart::Form::new().part("file", part);
+    if !media.alt.is_empty() {
+        form = form.text("description", media.alt.clone());
+    }
+    let resp = http
+        .post(format!("{}/api/v2/media", base))
+        .bearer_auth(token)
+        .multipart(form)
+        .send()
+        .await?;
+    let processing = resp.status().as_u16() == 202;
+    let attachment = read_json(Network::Mastodon, resp).await?;
+    let id = attachment["id"]
+        .as_str()
+        .ok_or("mastodon: media upload returned no id")?
+        .to_string();
+
+    // 202 means the server is still processing; statuses referencing the
+    // attachment are rejected until its URL is set.
+    if processing {
+        for _ in 0..15 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let resp = http
+                .get(format!("{}/api/v1/media/{}", base, id))
+                .bearer_auth(token)
+                .send()
+                .await?;
+            if resp.status().as_u16() == 200 {
+                let a = read_json(Network::Mastodon, resp).await?;
+                if a["url"].is_string() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(id)
+}
+
+async fn resolve_status_id(
+    http: &reqwest::Client,
+    base: &str,
+    token: &str,
+    reply_to: &str,
+) -> EngineResult<String> {
+    let reply_to = reply_to.trim();
+    if !reply_to.is_empty() && reply_to.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(reply_to.to_string());
+    }
+    let resp = http
+        .get(format!("{}/api/v2/search", base))
+        .bearer_auth(token)
+        .query(&[
+            ("q", reply_to),
+            ("type", "statuses"),
+            ("resolve", "true"),
+            ("limit", "1"),
+        ])
+        .send()
+        .await?;
+    let found = read_json(Network::Mastodon, resp).await?;
+    found["statuses"][0]["id"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| format!("mastodon: could not find the post {}", reply_to).into())
+}
+
+pub(crate) async fn search(
+    creds: &Credentials,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchHit>> {
+    let (base, token) = base(creds)?;
+    let resp = client()
+        .get(format!("{}/api/v2/search", base))
+        .bearer_auth(&token)
+        .query(&[
+            ("q", query),
+            ("type", "statuses"),
+            ("limit", &limit.to_string()),
+        ])
+        .send()
+        .await?;
+    let found = read_json(Network::Mastodon, resp).await?;
+    Ok(found["statuses"]
+        .as_array()
+        .map(|statuses| {
+            statuses
+                .iter()
+                .map(|s| SearchHit {
+                    author: s["account"]["acct"].as_str().unwrap_or("?").to_string(),
+                    text: html_to_text(s["content"].as_str().unwrap_or("")),
+                    url: s["url"].as_str().unwrap_or("").to_string(),
+                    created_at: s["created_at"].as_str().unwrap_or("").to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Statuses come back as HTML; keep paragraph breaks and decode the common
+/// entities.
+fn html_to_text(html: &str) -> String {
+    let html = html
+        .replace("</p>", "\n")
+        .replace("<br>", "\n")
+        .replace("<br />", "\n");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .trim()
+        .to_string()
+}
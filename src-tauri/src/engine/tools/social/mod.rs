@@ -0,0 +1,533 @@
+// Paw Agent Engine — Social Posting Tools (Atomic Module)
+//
+// Post, reply and search on social networks through one set of tools.
+// Each sub-module talks to one network:
+//
+//   mastodon — any Mastodon-compatible instance (access token)
+//   bluesky  — AT Protocol PDS, session from a handle + app password
+//   x        — X API v2 (user access token to post, bearer token to search)
+//
+// A network is usable once its credentials are in the vault. Posts and
+// replies are always held in the outbox for the user to approve
+// (`tool_metadata::always_reviewed`), whatever the outbox mode or agent
+// autonomy — nothing is published without the user seeing it first.
+//
+// Media are files from the agent's workspace (images the agent generated,
+// downloaded or captured), uploaded to the network before posting.
+//
+// Shared helpers (credentials, media loading, response checks) live here.
+
+pub mod bluesky;
+pub mod mastodon;
+pub mod x;
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::util::safe_truncate;
+use log::info;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// ── Public API (called by tools/mod.rs) ────────────────────────────────
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    let media = json!({
+        "type": "array",
+        "description": "Images to attach (max 4): file paths in your workspace, or objects {\"path\": \"...\", \"alt\": \"description\"}. Always give alt text.",
+        "items": {
+            "anyOf": [
+                { "type": "string" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "alt": { "type": "string" }
+                    },
+                    "required": ["path"]
+                }
+            ]
+        }
+    });
+    vec![
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "social_post".into(),
+                description: "Publish a post on Mastodon, Bluesky and/or X. The post is queued in the outbox and only published after the user approves it. Defaults to every configured network. Limits: X 280 characters, Bluesky 300, Mastodon 500 (instance-dependent).".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "text": { "type": "string", "description": "Post text" },
+                        "networks": {
+                            "type": "array",
+                            "items": { "type": "string", "enum": ["mastodon", "bluesky", "x"] },
+                            "description": "Networks to post to (default: all configured)"
+                        },
+                        "media": media.clone(),
+                        "visibility": { "type": "string", "enum": ["public", "unlisted", "private"], "description": "Mastodon visibility (default: public)" },
+                        "content_warning": { "type": "string", "description": "Mastodon content warning / spoiler text" }
+                    },
+                    "required": ["text"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "social_reply".into(),
+                description: "Reply to a post on Mastodon, Bluesky or X. Takes the post URL (or status ID / at:// URI). The reply is queued in the outbox and only published after the user approves it.".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "network": { "type": "string", "enum": ["mastodon", "bluesky", "x"] },
+                        "reply_to": { "type": "string", "description": "URL of the post to reply to, or its ID (Mastodon/X) or at:// URI (Bluesky)" },
+                        "text": { "type": "string", "description": "Reply text" },
+                        "media": media,
+                        "visibility": { "type": "string", "enum": ["public", "unlisted", "private"], "description": "Mastodon visibility (default: public)" }
+                    },
+                    "required": ["network", "reply_to", "text"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "social_search".into(),
+                description: "Search recent posts on Mastodon, Bluesky and/or X. Returns author, text, link and time for each match. Defaults to every configured network.".into(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "Search terms (hashtags and network search operators work)" },
+                        "network": { "type": "string", "enum": ["mastodon", "bluesky", "x"], "description": "Search only this network" },
+                        "limit": { "type": "integer", "description": "Results per network (default 10, max 40)" }
+                    },
+                    "required": ["query"]
+                }),
+            },
+        },
+    ]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> Option<Result<String, String>> {
+    let result = match name {
+        "social_post" => exec_post(args, app_handle, agent_id).await,
+        "social_reply" => exec_reply(args, app_handle, agent_id).await,
+        "social_search" => exec_search(args, app_handle).await,
+        _ => return None,
+    };
+    Some(result.map_err(|e| e.to_string()))
+}
+
+// ── Networks & credentials ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mastodon,
+    Bluesky,
+    X,
+}
+
+impl Network {
+    pub const ALL: [Network; 3] = [Network::Mastodon, Network::Bluesky, Network::X];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Network::Mastodon => "mastodon",
+            Network::Bluesky => "bluesky",
+            Network::X => "x",
+        }
+    }
+
+    pub fn parse(s: &str) -> EngineResult<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "mastodon" => Ok(Network::Mastodon),
+            "bluesky" | "bsky" => Ok(Network::Bluesky),
+            "x" | "twitter" => Ok(Network::X),
+            other => {
+                Err(format!("Unknown network '{}'. Use mastodon, bluesky or x.", other).into())
+            }
+        }
+    }
+
+    /// Character limit checked before anything is sent, so a cross-post
+    /// never half-publishes. Mastodon limits vary per instance and are left
+    /// to the server.
+    fn max_chars(self) -> Option<usize> {
+        match self {
+            Network::Mastodon => None,
+            Network::Bluesky => Some(300),
+            Network::X => Some(280),
+        }
+    }
+}
+
+const CRED_MASTODON_URL: &str = "MASTODON_INSTANCE_URL";
+const CRED_MASTODON_TOKEN: &str = "MASTODON_ACCESS_TOKEN";
+const CRED_BLUESKY_HANDLE: &str = "BLUESKY_HANDLE";
+const CRED_BLUESKY_PASSWORD: &str = "BLUESKY_APP_PASSWORD";
+const CRED_BLUESKY_SERVICE: &str = "BLUESKY_SERVICE_URL";
+const CRED_X_ACCESS_TOKEN: &str = "X_ACCESS_TOKEN";
+const CRED_X_BEARER_TOKEN: &str = "X_BEARER_TOKEN";
+
+/// Vault credentials for the `social` skill.
+pub(crate) struct Credentials(HashMap<String, String>);
+
+impl Credentials {
+    fn load(app_handle: &tauri::AppHandle) -> EngineResult<Self> {
+        Ok(Credentials(super::get_skill_creds("social", app_handle)?))
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|v| v.trim()).filter(|v| !v.is_empty())
+    }
+
+    fn require(&self, key: &str, network: Network) -> EngineResult<&str> {
+        self.get(key).ok_or_else(|| {
+            format!(
+                "{} is not set. Ask the user to add their {} credentials in Skills → Social Media.",
+                key,
+                network.as_str()
+            )
+            .into()
+        })
+    }
+
+    /// Whether the network can post (`search_only` = false) or search.
+    fn configured(&self, network: Network, search_only: bool) -> bool {
+        match network {
+            Network::Mastodon => {
+                self.get(CRED_MASTODON_URL).is_some() && self.get(CRED_MASTODON_TOKEN).is_some()
+            }
+            Network::Bluesky => {
+                self.get(CRED_BLUESKY_HANDLE).is_some() && self.get(CRED_BLUESKY_PASSWORD).is_some()
+            }
+            Network::X => {
+                self.get(CRED_X_ACCESS_TOKEN).is_some()
+                    || (search_only && self.get(CRED_X_BEARER_TOKEN).is_some())
+            }
+        }
+    }
+
+    /// Networks named in `requested`, or every configured one.
+    fn networks(&self, requested: Option<&Value>, search_only: bool) -> EngineResult<Vec<Network>> {
+        let named: Vec<Network> = match requested {
+            Some(Value::String(s)) => vec![Network::parse(s)?],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(Network::parse)
+                .collect::<EngineResult<_>>()?,
+            _ => vec![],
+        };
+        if named.is_empty() {
+            let all: Vec<Network> = Network::ALL
+                .into_iter()
+                .filter(|n| self.configured(*n, search_only))
+                .collect();
+            if all.is_empty() {
+                return Err("No social network is configured. Ask the user to add Mastodon, Bluesky or X credentials in Skills → Social Media.".into());
+            }
+            return Ok(all);
+        }
+        for n in &named {
+            if !self.configured(*n, search_only) {
+                return Err(format!(
+                    "{} is not configured. Ask the user to add its credentials in Skills → Social Media.",
+                    n.as_str()
+                )
+                .into());
+            }
+        }
+        Ok(named)
+    }
+}
+
+// ── Media ──────────────────────────────────────────────────────────────
+
+const MAX_MEDIA: usize = 4;
+const MAX_MEDIA_BYTES: u64 = 5 * 1024 * 1024;
+
+/// An image read from the agent workspace, ready to upload.
+pub(crate) struct Media {
+    pub file_name: String,
+    pub mime: &'static str,
+    pub bytes: Vec<u8>,
+    pub alt: String,
+}
+
+fn image_mime(path: &std::path::Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Read the `media` argument. Paths are relative to the agent workspace and
+/// may not leave it — an agent can attach its own artifacts, not arbitrary
+/// files from the user's disk.
+fn load_media(args: &Value, agent_id: &str) -> EngineResult<Vec<Media>> {
+    let items = match args.get("media").and_then(|m| m.as_array()) {
+        Some(items) if !items.is_empty() => items,
+        _ => return Ok(vec![]),
+    };
+    if items.len() > MAX_MEDIA {
+        return Err(format!("At most {} media files per post", MAX_MEDIA).into());
+    }
+    let workspace = super::ensure_workspace(agent_id)?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace: {}", e))?;
+
+    let mut media = Vec::new();
+    for item in items {
+        let (raw, alt) = match item {
+            Value::String(p) => (p.as_str(), ""),
+            other => (
+                other["path"].as_str().ok_or("media items need a 'path'")?,
+                other["alt"].as_str().unwrap_or(""),
+            ),
+        };
+        let path = workspace
+            .join(raw)
+            .canonicalize()
+            .map_err(|e| format!("Media file '{}' not found: {}", raw, e))?;
+        if !path.starts_with(&workspace) {
+            return Err(format!(
+                "Media file '{}' is outside your workspace. Copy it into the workspace first.",
+                raw
+            )
+            .into());
+        }
+        let mime = image_mime(&path).ok_or_else(|| {
+            format!(
+                "Media file '{}' is not a supported image (png, jpg, gif, webp)",
+                raw
+            )
+        })?;
+        let size = std::fs::metadata(&path)?.len();
+        if size > MAX_MEDIA_BYTES {
+            return Err(format!(
+                "Media file '{}' is {} KB; the limit is {} KB",
+                raw,
+                size / 1024,
+                MAX_MEDIA_BYTES / 1024
+            )
+            .into());
+        }
+        media.push(Media {
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "image".into()),
+            mime,
+            bytes: std::fs::read(&path)?,
+            alt: alt.to_string(),
+        });
+    }
+    Ok(media)
+}
+
+// ── HTTP helpers ───────────────────────────────────────────────────────
+
+pub(crate) fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Parse a JSON response, turning HTTP errors into a readable message.
+pub(crate) async fn read_json(network: Network, resp: reqwest::Response) -> EngineResult<Value> {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!(
+            "{} API {}: {}",
+            network.as_str(),
+            status,
+            safe_truncate(&text, 400)
+        )
+        .into());
+    }
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// One search result, normalized across networks.
+pub(crate) struct SearchHit {
+    pub author: String,
+    pub text: String,
+    pub url: String,
+    pub created_at: String,
+}
+
+// ── Executors ──────────────────────────────────────────────────────────
+
+fn check_length(network: Network, text: &str) -> EngineResult<()> {
+    if let Some(max) = network.max_chars() {
+        let len = text.chars().count();
+        if len > max {
+            return Err(format!(
+                "Text is {} characters; {} allows {}. Shorten it or leave {} out.",
+                len,
+                network.as_str(),
+                max,
+                network.as_str()
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+async fn publish(
+    network: Network,
+    creds: &Credentials,
+    text: &str,
+    reply_to: Option<&str>,
+    media: &[Media],
+    args: &Value,
+) -> EngineResult<String> {
+    match network {
+        Network::Mastodon => mastodon::post(creds, text, reply_to, media, args).await,
+        Network::Bluesky => bluesky::post(creds, text, reply_to, media).await,
+        Network::X => x::post(creds, text, reply_to, media).await,
+    }
+}
+
+async fn exec_post(
+    args: &Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> EngineResult<String> {
+    let text = args["text"].as_str().ok_or("social_post: missing 'text'")?;
+    let creds = Credentials::load(app_handle)?;
+    let networks = creds.networks(args.get("networks"), false)?;
+    for n in &networks {
+        check_length(*n, text)?;
+    }
+    let media = load_media(args, agent_id)?;
+
+    let mut lines = Vec::new();
+    let mut published = 0;
+    for n in networks {
+        match publish(n, &creds, text, None, &media, args).await {
+            Ok(url) => {
+                info!("[social] Posted to {}: {}", n.as_str(), url);
+                published += 1;
+                lines.push(format!("{}: posted {}", n.as_str(), url));
+            }
+            Err(e) => lines.push(format!("{}: FAILED — {}", n.as_str(), e)),
+        }
+    }
+    if published == 0 {
+        return Err(lines.join("\n").into());
+    }
+    Ok(lines.join("\n"))
+}
+
+async fn exec_reply(
+    args: &Value,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+) -> EngineResult<String> {
+    let text = args["text"]
+        .as_str()
+        .ok_or("social_reply: missing 'text'")?;
+    let reply_to = args["reply_to"]
+        .as_str()
+        .ok_or("social_reply: missing 'reply_to'")?;
+    let network = Network::parse(
+        args["network"]
+            .as_str()
+            .ok_or("social_reply: missing 'network'")?,
+    )?;
+    let creds = Credentials::load(app_handle)?;
+    creds.networks(Some(&json!(network.as_str())), false)?;
+    check_length(network, text)?;
+    let media = load_media(args, agent_id)?;
+
+    let url = publish(network, &creds, text, Some(reply_to), &media, args).await?;
+    info!("[social] Replied on {}: {}", network.as_str(), url);
+    Ok(format!("{}: replied {}", network.as_str(), url))
+}
+
+async fn exec_search(args: &Value, app_handle: &tauri::AppHandle) -> EngineResult<String> {
+    let query = args["query"]
+        .as_str()
+        .ok_or("social_search: missing 'query'")?;
+    let limit = args["limit"].as_u64().unwrap_or(10).clamp(1, 40) as usize;
+    let creds = Credentials::load(app_handle)?;
+    let networks = creds.networks(args.get("network"), true)?;
+
+    let mut out = String::new();
+    for n in networks {
+        let hits = match n {
+            Network::Mastodon => mastodon::search(&creds, query, limit).await,
+            Network::Bluesky => bluesky::search(&creds, query, limit).await,
+            Network::X => x::search(&creds, query, limit).await,
+        };
+        match hits {
+            Ok(hits) if hits.is_empty() => {
+                out.push_str(&format!("## {}\nNo results.\n\n", n.as_str()))
+            }
+            Ok(hits) => {
+                out.push_str(&format!("## {} ({} results)\n", n.as_str(), hits.len()));
+                for h in hits {
+                    out.push_str(&format!(
+                        "- @{} ({}): {}\n  {}\n",
+                        h.author,
+                        h.created_at,
+                        h.text.split_whitespace().collect::<Vec<_>>().join(" "),
+                        h.url
+                    ));
+                }
+                out.push('\n');
+            }
+            Err(e) => out.push_str(&format!("## {}\nSearch failed: {}\n\n", n.as_str(), e)),
+        }
+    }
+    Ok(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_names_and_limits() {
+        assert_eq!(Network::parse("Twitter").unwrap(), Network::X);
+        assert_eq!(Network::parse("bsky").unwrap(), Network::Bluesky);
+        assert!(Network::parse("myspace").is_err());
+
+        assert!(check_length(Network::X, &"é".repeat(280)).is_ok());
+        assert!(check_length(Network::X, &"a".repeat(281)).is_err());
+        assert!(check_length(Network::Mastodon, &"a".repeat(1000)).is_ok());
+    }
+
+    #[test]
+    fn configured_networks() {
+        let creds = Credentials(HashMap::from([
+            (CRED_X_BEARER_TOKEN.to_string(), "tok".to_string()),
+            (
+                CRED_MASTODON_URL.to_string(),
+                "https://m.example".to_string(),
+            ),
+            (CRED_MASTODON_TOKEN.to_string(), " ".to_string()),
+        ]));
+        assert_eq!(creds.networks(None, true).unwrap(), vec![Network::X]);
+        assert!(creds.networks(None, false).is_err());
+        assert!(creds.networks(Some(&json!(["mastodon"])), true).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// social/x.rs — X (Twitter) API v2
+//
+// Posts:   POST /2/tweets (OAuth 2.0 user access token with tweet.write)
+// Media:   POST /2/media/upload, alt text via /2/media/metadata
+// Search:  GET  /2/tweets/search/recent (user token or app bearer token)
+//
+// Only images are uploaded; video needs the chunked upload flow.
+
+use super::{
+    client, read_json, Credentials, Media, Network, SearchHit, CRED_X_ACCESS_TOKEN,
+    CRED_X_BEARER_TOKEN,
+};
+use crate::atoms::error::EngineResult;
+use serde_json::json;
+use std::collections::HashMap;
+
+const X_API: &str = "https://api.x.com/2";
+
+pub(crate) async fn post(
+    creds: &Credentials,
+    text: &str,
+    reply_to: Option<&str>,
+    media: &[Media],
+) -> EngineResult<String> {
+    let token = creds.require(CRED_X_ACCESS_TOKEN, Network::X)?;
+    let http = client();
+
+    let mut body = json!({ "text": text });
+    if let Some(r) = reply_to {
+        let id = tweet_id(r).ok_or_else(|| format!("x: not a post URL or ID: {}", r))?;
+        body["reply"] = json!({ "in_reply_to_tweet_id": id });
+    }
+    if !media.is_empty() {
+        let mut ids = Vec::new();
+        for m in media {
+            ids.push(upload(&http, token, m).await?);
+        }
+        body["media"] = json!({ "media_ids": ids });
+    }
+
+    let resp = http
+        .post(format!("{}/tweets", X_API))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await?;
+    let created = read_json(Network::X, resp).await?;
+    let id = created["data"]["id"]
+        .as_str()
+        .ok_or("x: post returned no id")?;
+    Ok(format!("https://x.com/i/web/status/{}", id))
+}
+
+async fn upload(http: &reqwest::Client, token: &str, media: &Media) -> EngineResult<String> {
+    let category = if media.mime == "image/gif" {
+        "tweet_gif"
+    } else {
+        "tweet_image"
+    };
+    let part = reqwest::multipart::Part::bytes(media.bytes.clone())
+        .file_name(media.file_name.clone())
+        .mime_str(media.mime)?;
+    let form = reqwest::multipart::Form::new()
+        .part("media", part)
+        .text("media_category", category);
+    let resp = http
+        .post(format!("{}/media/upload", X_API))
+        .bearer_auth(token)
+        .multipart(form)
+        .send()
+        .await?;
+    let uploaded = read_json(Network::X, resp).await?;
+    let id = uploaded["data"]["id"]
+        .as_str()
+        .ok_or("x: media upload returned no id")?
+        .to_string();
+
+    if !media.alt.is_empty() {
+        let resp = http
+            .post(format!("{}/media/metadata", X_API))
+            .bearer_auth(token)
+            .json(&json!({ "id": id, "metadata": { "alt_text": { "text": media.alt } } }))
+            .send()
+            .await?;
+        read_json(Network::X, resp).await?;
+    }
+    Ok(id)
+}
+
+pub(crate) async fn search(
+    creds: &Credentials,
+    query: &str,
+    limit: usize,
+) -> EngineResult<Vec<SearchHit>> {
+    let token = creds
+        .get(CRED_X_BEARER_TOKEN)
+        .or(creds.get(CRED_X_ACCESS_TOKEN))
+        .ok_or("X_BEARER_TOKEN is not set")?;
+    // The API only accepts 10–100 results per page
+    let max_results = limit.clamp(10, 100).to_string();
+    let resp = client()
+        .get(format!("{}/tweets/search/recent", X_API))
+        .bearer_auth(token)
+        .query(&[
+            ("query", query),
+            ("max_results", &max_results),
+            ("tweet.fields", "created_at,author_id"),
+            ("expansions", "author_id"),
+            ("user.fields", "username"),
+        ])
+        .send()
+        .await?;
+    let found = read_json(Network::X, resp).await?;
+
+    let usernames: HashMap<&str, &str> = found["includes"]["users"]
+        .as_array()
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(|u| Some((u["id"].as_str()?, u["username"].as_str()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(found["data"]
+        .as_array()
+        .map(|tweets| {
+            tweets
+                .iter()
+                .take(limit)
+                .map(|t| {
+                    let id = t["id"].as_str().unwrap_or("");
+                    let author = t["author_id"]
+                        .as_str()
+                        .and_then(|a| usernames.get(a).copied())
+                        .unwrap_or("?");
+                    SearchHit {
+                        author: author.to_string(),
+                        text: t["text"].as_str().unwrap_or("").to_string(),
+                        url: format!("https://x.com/{}/status/{}", author, id),
+                        created_at: t["created_at"].as_str().unwrap_or("").to_string(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Post ID from a bare ID or an x.com / twitter.com status URL.
+fn tweet_id(s: &str) -> Option<String> {
+    let s = s.trim();
+    let id = match s.split_once("/status/") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next()?,
+        None => s,
+    };
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tweet_ids_from_urls() {
+        assert_eq!(
+            tweet_id("1790000000000000000").as_deref(),
+            Some("1790000000000000000")
+        );
+        assert_eq!(
+            tweet_id("https://twitter.com/jack/status/20?s=20").as_deref(),
+            Some("20")
+        );
+        assert_eq!(
+            tweet_id("https://x.com/i/web/status/42/").as_deref(),
+            Some("42")
+        );
+        assert!(tweet_id("https://x.com/jack").is_none());
+    }
+}
@@ -5,7 +5,7 @@ use crate::atoms::error::EngineResult;
 use crate::atoms::types::*;
 use crate::engine::state::EngineState;
 use log::info;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 pub fn definitions() -> Vec<ToolDefinition> {
     vec![
@@ -125,7 +125,23 @@ async fn execute_soul_write(
     let state = app_handle
         .try_state::<EngineState>()
         .ok_or("Engine state not available")?;
-    state.store.set_agent_file(agent_id, file_name, content)?;
+    let version = state
+        .store
+        .set_agent_file_as(agent_id, file_name, content, "agent")?;
+    // Surface self-edits so users notice personality drift
+    if let Some(version) = version {
+        app_handle
+            .emit(
+                "agent-file-changed",
+                serde_json::json!({
+                    "agent_id": agent_id,
+                    "file_name": file_name,
+                    "version": version,
+                    "author": "agent",
+                }),
+            )
+            .ok();
+    }
     Ok(format!(
         "Successfully updated {}. This change will take effect in future conversations.",
         file_name
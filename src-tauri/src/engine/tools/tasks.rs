@@ -21,8 +21,8 @@ pub fn definitions() -> Vec<ToolDefinition> {
                         "description": { "type": "string", "description": "Detailed task instructions for the agent" },
                         "priority": { "type": "string", "enum": ["low", "medium", "high", "urgent"], "description": "Task priority (default: medium)" },
                         "agent_id": { "type": "string", "description": "Agent to assign the task to (default: 'default')" },
-                        "cron_schedule": { "type": "string", "description": "Schedule for recurring tasks: 'every 5m', 'every 1h', 'daily 09:00'. Omit for one-shot tasks." },
-                        "event_trigger": { "type": "string", "description": "JSON event trigger condition. Examples: {\"type\":\"webhook\"} (fires on any inbound webhook), {\"type\":\"webhook\",\"path\":\"/deploy\"} (specific path), {\"type\":\"agent_message\",\"channel\":\"alerts\"} (fires when a message arrives on the alerts channel)" },
+                        "cron_schedule": { "type": "string", "description": "Schedule for recurring tasks: 'every 5m', 'every 1h', 'daily 09:00' (daily times are in the user's timezone). Omit for one-shot tasks." },
+                        "event_trigger": { "type": "string", "description": "JSON event trigger condition. Examples: {\"type\":\"webhook\"} (fires on any inbound webhook), {\"type\":\"webhook\",\"path\":\"/deploy\"} (specific path), {\"type\":\"agent_message\",\"channel\":\"alerts\"} (fires when a message arrives on the alerts channel), {\"type\":\"twitch_redemption\",\"reward\":\"Hydrate\"} (fires when a Twitch viewer redeems that channel point reward), {\"type\":\"new_mail\",\"account\":\"work\",\"folder\":\"INBOX\"} (fires when new mail arrives in a watched folder; account and folder optional)" },
                         "persistent": { "type": "boolean", "description": "If true, the task re-runs continuously after each completion (always-on monitoring mode)" }
                     },
                     "required": ["title", "description"]
@@ -225,12 +225,23 @@ async fn execute_list_tasks(
         return Ok("No tasks found matching the criteria.".into());
     }
 
+    let tz = state.config.lock().timezone();
     let mut output = format!("Found {} task(s):\n\n", filtered.len());
     for t in &filtered {
         let schedule = t.cron_schedule.as_deref().unwrap_or("none");
         let enabled = if t.cron_enabled { "enabled" } else { "paused" };
         let agent = t.assigned_agent.as_deref().unwrap_or("unassigned");
-        let next = t.next_run_at.as_deref().unwrap_or("-");
+        let next = t
+            .next_run_at
+            .as_deref()
+            .map(|n| match chrono::DateTime::parse_from_rfc3339(n) {
+                Ok(dt) => dt
+                    .with_timezone(&tz)
+                    .format("%Y-%m-%d %H:%M %Z")
+                    .to_string(),
+                Err(_) => n.to_string(),
+            })
+            .unwrap_or_else(|| "-".into());
         let trigger = t.event_trigger.as_deref().unwrap_or("none");
         let mode = if t.persistent {
             "persistent"
@@ -0,0 +1,168 @@
+// Paw Agent Engine — Translation tool
+// translate
+//
+// Also used by channel bridges for auto-translate (see engine::translate).
+// A local Ollama model is tried first; the worker / default model is the
+// fallback when there is no local model or the local call fails.
+
+use crate::atoms::error::EngineResult;
+use crate::atoms::types::*;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::{resolve_provider_for_model, EngineState};
+use crate::engine::translate;
+use log::{info, warn};
+use tauri::Manager;
+
+pub fn definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        tool_type: "function".into(),
+        function: FunctionDefinition {
+            name: "translate".into(),
+            description: "Translate text into another language. Runs on a local model when one is configured. Keeps formatting, names, URLs and code unchanged.".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string", "description": "Text to translate" },
+                    "target_lang": { "type": "string", "description": "Target language as an ISO code ('de', 'ja') or name ('Brazilian Portuguese')" },
+                    "source_lang": { "type": "string", "description": "Optional source language; detected if omitted" }
+                },
+                "required": ["text", "target_lang"]
+            }),
+        },
+    }]
+}
+
+pub async fn execute(
+    name: &str,
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> Option<Result<String, String>> {
+    match name {
+        "translate" => Some(
+            execute_translate(args, app_handle)
+                .await
+                .map_err(|e| e.to_string()),
+        ),
+        _ => None,
+    }
+}
+
+async fn execute_translate(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<String> {
+    let text = args["text"].as_str().ok_or("translate: missing 'text'")?;
+    let target = args["target_lang"]
+        .as_str()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("translate: missing 'target_lang'")?;
+    let source = args["source_lang"]
+        .as_str()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| translate::detect_language(text));
+    if text.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let state = app_handle
+        .try_state::<EngineState>()
+        .ok_or("Engine state not available")?;
+    translate_text(&state, text, target, source).await
+}
+
+/// Translate `text` into `target` (code or name). Tries the local Ollama
+/// model first, then the worker / default model.
+pub async fn translate_text(
+    state: &EngineState,
+    text: &str,
+    target: &str,
+    source: Option<&str>,
+) -> EngineResult<String> {
+    if source.is_some_and(|s| translate::same_language(s, target)) {
+        return Ok(text.to_string());
+    }
+    let settings = translate::load_settings(&state.store);
+    let (local, remote) = {
+        let cfg = state.config.lock();
+        let local = cfg
+            .providers
+            .iter()
+            .find(|p| p.kind == ProviderKind::Ollama)
+            .and_then(|p| {
+                let model = settings
+                    .local_model
+                    .clone()
+                    .filter(|m| !m.is_empty())
+                    .or_else(|| p.default_model.clone())?;
+                Some((p.clone(), model))
+            });
+        let remote = cfg
+            .model_routing
+            .worker_model
+            .clone()
+            .or_else(|| cfg.default_model.clone())
+            .and_then(|model| {
+                let provider = resolve_provider_for_model(&model, &cfg.providers)
+                    .or_else(|| cfg.providers.first().cloned())?;
+                Some((provider, model))
+            });
+        (local, remote)
+    };
+
+    let system = translate::translation_prompt(target, source);
+    let mut last_err = String::from("No model available for translation");
+    for (provider_config, model) in local.into_iter().chain(remote) {
+        match complete(&provider_config, &model, &system, text).await {
+            Ok(out) => {
+                info!(
+                    "[translate] {} chars → {} with {}",
+                    text.len(),
+                    translate::language_name(target),
+                    model
+                );
+                return Ok(out);
+            }
+            Err(e) => {
+                warn!("[translate] {} failed: {}", model, e);
+                last_err = e;
+            }
+        }
+    }
+    Err(last_err.into())
+}
+
+async fn complete(
+    provider_config: &ProviderConfig,
+    model: &str,
+    system: &str,
+    text: &str,
+) -> Result<String, String> {
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(system.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(text.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+    let chunks = AnyProvider::from_config(provider_config)
+        .chat_stream(&messages, &[], model, Some(0.2), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let out: String = chunks
+        .iter()
+        .filter_map(|c| c.delta_text.as_deref())
+        .collect();
+    let out = out.trim();
+    if out.is_empty() {
+        return Err(format!("{} returned an empty translation", model));
+    }
+    Ok(out.to_string())
+}
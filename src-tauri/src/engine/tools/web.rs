@@ -1,6 +1,6 @@
 // Paw Agent Engine — Web tools
-// web_search, web_read, web_screenshot, web_browse
-// Execution delegates to crate::engine::web
+// web_search, web_read, web_screenshot, web_browse, youtube_transcript
+// Execution delegates to crate::engine::web and crate::engine::youtube
 
 use crate::atoms::types::*;
 
@@ -10,7 +10,7 @@ pub fn definitions() -> Vec<ToolDefinition> {
             tool_type: "function".into(),
             function: FunctionDefinition {
                 name: "web_search".into(),
-                description: "Search the internet using DuckDuckGo. Returns titles, URLs, and snippets. No API key needed.".into(),
+                description: "Search the internet. Returns titles, URLs, and snippets. Uses the search provider configured in Settings (DuckDuckGo by default; SearxNG, Brave or Kagi).".into(),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -75,6 +75,22 @@ pub fn definitions() -> Vec<ToolDefinition> {
                 }),
             },
         },
+        ToolDefinition {
+            tool_type: "function".into(),
+            function: FunctionDefinition {
+                name: "youtube_transcript".into(),
+                description: "Get a YouTube video's title, channel, duration, description and full timestamped transcript (falls back to auto-generated captions). Use this to summarize or answer questions about a video the user links. Long transcripts come in parts — call again with part=2, 3, … to read on.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "url": { "type": "string", "description": "YouTube video URL (watch, youtu.be, shorts) or video ID" },
+                        "language": { "type": "string", "description": "Preferred caption language code (default: en)" },
+                        "part": { "type": "integer", "description": "Transcript part to return, starting at 1 (default: 1)" }
+                    },
+                    "required": ["url"]
+                }),
+            },
+        },
     ]
 }
 
@@ -85,7 +101,7 @@ pub async fn execute(
 ) -> Option<Result<String, String>> {
     match name {
         "web_search" => Some(
-            crate::engine::web::execute_web_search(args)
+            crate::engine::web::execute_web_search(args, app_handle)
                 .await
                 .map_err(|e| e.to_string()),
         ),
@@ -104,6 +120,11 @@ pub async fn execute(
                 .await
                 .map_err(|e| e.to_string()),
         ),
+        "youtube_transcript" => Some(
+            crate::engine::youtube::execute_youtube_transcript(args)
+                .await
+                .map_err(|e| e.to_string()),
+        ),
         _ => None,
     }
 }
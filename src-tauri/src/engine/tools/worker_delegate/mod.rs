@@ -6,7 +6,11 @@
 //   - Cloud: gemini-2.0-flash, gpt-4o-mini, claude-haiku-4-5, deepseek-chat
 //   - Local: worker-qwen (Ollama), llama3.2:3b, phi3:mini
 //
+// Which calls are delegated, and to which named worker, is decided by the
+// rules in `rules.rs` (tool globs, payload thresholds, result instructions).
+//
 // Flow: Boss decides "call fetch" or "call mcp_n8n_execute_workflow" →
+//       A delegation rule matches → worker model is resolved →
 //       Worker (cheap model) receives task + tool schemas →
 //       Worker executes tools (fetch, MCP) →
 //       Result returned to boss as tool output.
@@ -17,51 +21,86 @@
 // All worker tool results are scanned for prompt injection before being
 // returned to the boss model.
 
+pub mod rules;
+pub mod summarize;
+
 use crate::atoms::types::*;
 use crate::engine::providers::AnyProvider;
 use crate::engine::state::EngineState;
 use crate::engine::tools;
 use crate::engine::util::safe_truncate;
 use log::{info, warn};
+use rules::WorkerDelegationConfig;
 use tauri::Manager;
 
 /// Maximum rounds the worker gets to execute a task.
 const WORKER_MAX_ROUNDS: u32 = 8;
 
-/// Check if a worker model is configured (quick, non-async check).
-pub fn has_worker(app_handle: &tauri::AppHandle) -> bool {
-    if let Some(state) = app_handle.try_state::<EngineState>() {
-        let cfg = state.config.lock();
-        cfg.model_routing
-            .worker_model
-            .as_ref()
-            .is_some_and(|m| !m.is_empty())
-    } else {
-        false
+/// A resolved delegation: which worker runs the call and how it shapes the result.
+#[derive(Debug, Clone)]
+pub struct WorkerRoute {
+    pub worker: String,
+    pub model: String,
+    pub worker_instructions: Option<String>,
+    pub summarize_instructions: Option<String>,
+    pub max_result_chars: usize,
+}
+
+/// The delegation rules, as loaded at startup or last saved.
+pub fn load_config(state: &EngineState) -> WorkerDelegationConfig {
+    state.worker_delegation.lock().clone()
+}
+
+/// Tools the worker itself can execute (see `execute_worker_tool`).
+fn worker_can_execute(name: &str) -> bool {
+    name == "fetch"
+        || name.starts_with("mcp_")
+        || tools::n8n::definitions()
+            .iter()
+            .any(|d| d.function.name == name)
+}
+
+/// Decide whether a tool call should be delegated, and to which worker.
+///
+/// Returns `None` when no rule matches, the tool is not worker-executable,
+/// or the rule's worker can't be resolved to a model.
+pub fn route_for(
+    app_handle: &tauri::AppHandle,
+    tool_name: &str,
+    arguments: &str,
+) -> Option<WorkerRoute> {
+    if !worker_can_execute(tool_name) {
+        return None;
     }
+    let state = app_handle.try_state::<EngineState>()?;
+    let config = load_config(&state);
+    let rule = config.match_rule(tool_name, arguments.len())?;
+    let default_model = state.config.lock().model_routing.worker_model.clone();
+    let (model, worker_instructions) =
+        config.resolve_worker(&rule.worker, default_model.as_deref())?;
+    Some(WorkerRoute {
+        worker: rule.worker.clone(),
+        model,
+        worker_instructions,
+        summarize_instructions: rule.summarize_instructions.clone(),
+        max_result_chars: rule.max_result_chars,
+    })
 }
 
-/// Attempt to delegate a tool call to the local worker model.
+/// Attempt to delegate a tool call to the worker chosen by `route`.
 ///
 /// Returns `Some(ToolResult)` if delegation was performed (success or failure).
-/// Returns `None` if no worker model is configured or the provider can't be resolved,
+/// Returns `None` if the tool is blocked or the provider can't be resolved,
 /// signaling the caller to fall back to direct execution.
 pub async fn delegate_to_worker(
     tool_call: &ToolCall,
     app_handle: &tauri::AppHandle,
     agent_id: &str,
+    route: &WorkerRoute,
 ) -> Option<ToolResult> {
     let state = app_handle.try_state::<EngineState>()?;
-
-    // Read worker model from config
-    let (worker_model, providers) = {
-        let cfg = state.config.lock();
-        let wm = cfg.model_routing.worker_model.clone();
-        let providers = cfg.providers.clone();
-        (wm, providers)
-    };
-
-    let worker_model = worker_model.filter(|m| !m.is_empty())?;
+    let providers = state.config.lock().providers.clone();
+    let worker_model = route.model.clone();
 
     // §Security: Check if the tool is in the blocked list BEFORE sending any
     // arguments to the worker model.  Previously, blocked tool arguments were
@@ -91,8 +130,8 @@ pub async fn delegate_to_worker(
     }
 
     info!(
-        "[worker-delegate] Delegating '{}' to worker model '{}'",
-        tool_call.function.name, worker_model
+        "[worker-delegate] Delegating '{}' to worker '{}' (model '{}')",
+        tool_call.function.name, route.worker, worker_model
     );
 
     // Resolve provider for the worker model
@@ -124,7 +163,7 @@ pub async fn delegate_to_worker(
     );
 
     // Build system prompt for the worker
-    let system_prompt = "You are the FOREMAN (Worker Agent) for OpenPawz.\n\n\
+    let mut system_prompt = "You are the FOREMAN (Worker Agent) for OpenPawz.\n\n\
         Your job is to receive Task Orders and execute them using your available tools.\n\
         You are a silent execution unit — never engage in conversation, never explain your reasoning.\n\n\
         ## Available Tools\n\
@@ -141,6 +180,18 @@ pub async fn delegate_to_worker(
         - Do NOT explain what you're doing. Just execute and return the result.\n\
         - If the task cannot be completed, say ERROR: followed by the reason."
         .to_string();
+    if let Some(ref extra) = route.worker_instructions {
+        system_prompt.push_str(&format!("\n\n## Worker Instructions\n{}", extra));
+    }
+    if let Some(ref instr) = route.summarize_instructions {
+        system_prompt.push_str(&format!("\n\n## Result Summarization\n{}", instr));
+    }
+    if route.max_result_chars > 0 {
+        system_prompt.push_str(&format!(
+            "\n\nKeep your final result under {} characters.",
+            route.max_result_chars
+        ));
+    }
 
     // Build messages
     let mut messages = vec![
@@ -171,26 +222,44 @@ pub async fn delegate_to_worker(
     )
     .await;
 
-    let (output, success) = match result {
-        Ok(text) => {
+    let (output, success, raw_chars) = match result {
+        Ok((text, raw_chars)) => {
             info!(
-                "[worker-delegate] Worker completed '{}': {} chars",
+                "[worker-delegate] Worker completed '{}': {} chars (raw tool output {} chars)",
                 tool_call.function.name,
-                text.len()
+                text.len(),
+                raw_chars
             );
-            (text, true)
+            let text = if route.max_result_chars > 0 {
+                safe_truncate(&text, route.max_result_chars).to_string()
+            } else {
+                text
+            };
+            (text, true, raw_chars)
         }
         Err(e) => {
             warn!(
                 "[worker-delegate] Worker failed on '{}': {}",
                 tool_call.function.name, e
             );
-            (format!("Worker execution failed: {}", e), false)
+            (format!("Worker execution failed: {}", e), false, 0)
         }
     };
 
+    state
+        .worker_stats
+        .lock()
+        .entry(route.worker.clone())
+        .or_default()
+        .record(success, raw_chars, output.len());
+
     Some(ToolResult {
         tool_call_id: tool_call.id.clone(),
+        error_kind: if success {
+            None
+        } else {
+            crate::engine::tool_errors::classify(&output)
+        },
         output,
         success,
     })
@@ -200,6 +269,8 @@ pub async fn delegate_to_worker(
 /// Worker tools are restricted to safe operations (fetch, MCP, n8n).
 /// Dangerous tools (exec, write_file, etc.) are blocked.
 /// All tool results are scanned for prompt injection before being fed back.
+///
+/// Returns the final text and the total chars of raw tool output consumed.
 async fn run_worker_loop(
     app_handle: &tauri::AppHandle,
     provider: &AnyProvider,
@@ -207,7 +278,8 @@ async fn run_worker_loop(
     messages: &mut Vec<Message>,
     tools: &mut [ToolDefinition],
     agent_id: &str,
-) -> Result<String, String> {
+) -> Result<(String, usize), String> {
+    let mut raw_chars = 0;
     for round in 1..=WORKER_MAX_ROUNDS {
         info!(
             "[worker-delegate] Worker round {}/{}",
@@ -252,7 +324,7 @@ async fn run_worker_loop(
             if text_accum.is_empty() {
                 return Err("Worker returned empty response".into());
             }
-            return Ok(text_accum);
+            return Ok((text_accum, raw_chars));
         }
 
         // Build tool calls
@@ -297,6 +369,7 @@ async fn run_worker_loop(
             );
 
             let result = execute_worker_tool(tc, app_handle, agent_id).await;
+            raw_chars += result.output.len();
 
             // §Security: scan worker tool results for prompt injection payloads
             // before feeding them back into the model context.
@@ -402,6 +475,7 @@ async fn execute_worker_tool(
                 name
             ),
             success: false,
+            error_kind: Some(ToolErrorKind::Permission),
         };
     }
 
@@ -431,9 +505,11 @@ async fn execute_worker_tool(
             tool_call_id: tool_call.id.clone(),
             output,
             success: true,
+            error_kind: None,
         },
         Err(err) => ToolResult {
             tool_call_id: tool_call.id.clone(),
+            error_kind: crate::engine::tool_errors::classify(&err),
             output: format!("Error: {}", err),
             success: false,
         },
@@ -0,0 +1,343 @@
+// Worker Delegation — Rules
+//
+// Pure types for rule-driven Foreman delegation: which tools (globs) go to
+// which named worker, payload thresholds, result-shaping instructions, and
+// per-worker savings stats. Persisted under the `worker_delegation_config` key.
+
+use serde::{Deserialize, Serialize};
+
+/// Config key in the session store.
+pub const CONFIG_KEY: &str = "worker_delegation_config";
+
+/// Worker name that resolves to `model_routing.worker_model`.
+pub const DEFAULT_WORKER: &str = "default";
+
+fn default_worker() -> String {
+    DEFAULT_WORKER.to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A named worker model, e.g. a vision worker or a summarizer worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedWorker {
+    pub name: String,
+    pub model: String,
+    /// Extra system-prompt instructions for this worker.
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+/// One delegation rule. The first matching rule wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelegationRule {
+    /// Tool name globs (`*` and `?`), e.g. `["fetch", "mcp_n8n_*"]`.
+    pub tools: Vec<String>,
+    /// Named worker to run the call; `"default"` uses `model_routing.worker_model`.
+    #[serde(default = "default_worker")]
+    pub worker: String,
+    /// Only delegate when the call's argument JSON is at least this long (0 = always).
+    #[serde(default)]
+    pub min_payload_chars: usize,
+    /// Cap on the worker's returned result in chars (0 = no cap).
+    #[serde(default)]
+    pub max_result_chars: usize,
+    /// How the worker should summarize the raw tool output for the boss model.
+    #[serde(default)]
+    pub summarize_instructions: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl DelegationRule {
+    fn new(tools: &[&str]) -> Self {
+        DelegationRule {
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+            worker: default_worker(),
+            min_payload_chars: 0,
+            max_result_chars: 0,
+            summarize_instructions: None,
+            enabled: true,
+        }
+    }
+
+    /// Whether this rule applies to a tool call.
+    pub fn matches(&self, tool_name: &str, payload_chars: usize) -> bool {
+        self.enabled
+            && payload_chars >= self.min_payload_chars
+            && self.tools.iter().any(|p| glob_match(p, tool_name))
+    }
+}
+
+/// Default size (estimated tokens) above which a tool result is summarized.
+pub const DEFAULT_SUMMARIZE_THRESHOLD_TOKENS: usize = 4_000;
+
+/// Worker-model summarization of oversized tool results before they enter
+/// the main model's context. The raw result is kept as a workspace artifact.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultSummarization {
+    pub enabled: bool,
+    /// Results larger than this (chars/4 estimate) are summarized.
+    pub threshold_tokens: usize,
+    /// Named worker that writes the summary (`"default"` = `worker_model`).
+    pub worker: String,
+    /// Tool name globs never summarized (e.g. `read_file` when exact text matters).
+    pub exclude_tools: Vec<String>,
+}
+
+impl Default for ResultSummarization {
+    fn default() -> Self {
+        ResultSummarization {
+            enabled: false,
+            threshold_tokens: DEFAULT_SUMMARIZE_THRESHOLD_TOKENS,
+            worker: default_worker(),
+            exclude_tools: vec!["request_tools".into(), "read_file".into()],
+        }
+    }
+}
+
+impl ResultSummarization {
+    /// Whether a result of `output_len` chars from `tool_name` should be summarized.
+    pub fn applies(&self, tool_name: &str, output_len: usize) -> bool {
+        self.enabled
+            && estimate_tokens(output_len) > self.threshold_tokens as u64
+            && !self.exclude_tools.iter().any(|p| glob_match(p, tool_name))
+    }
+}
+
+/// Rule-driven delegation settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkerDelegationConfig {
+    pub enabled: bool,
+    pub workers: Vec<NamedWorker>,
+    pub rules: Vec<DelegationRule>,
+    pub summarization: ResultSummarization,
+}
+
+impl Default for WorkerDelegationConfig {
+    /// Fetch and MCP calls go to the default worker. The hardcoded routing
+    /// this replaces also named `exec`, but exec is blocked for workers (it
+    /// needs HIL approval) and always fell back to the main agent, so no
+    /// default rule matches it.
+    fn default() -> Self {
+        WorkerDelegationConfig {
+            enabled: true,
+            workers: Vec::new(),
+            rules: vec![DelegationRule::new(&["fetch", "mcp_*"])],
+            summarization: ResultSummarization::default(),
+        }
+    }
+}
+
+impl WorkerDelegationConfig {
+    /// First enabled rule matching the tool call, if any.
+    pub fn match_rule(&self, tool_name: &str, payload_chars: usize) -> Option<&DelegationRule> {
+        if !self.enabled {
+            return None;
+        }
+        self.rules
+            .iter()
+            .find(|r| r.matches(tool_name, payload_chars))
+    }
+
+    /// Resolve a worker name to `(model, instructions)`. `"default"` falls back
+    /// to `default_model` unless a named worker overrides it.
+    pub fn resolve_worker(
+        &self,
+        worker: &str,
+        default_model: Option<&str>,
+    ) -> Option<(String, Option<String>)> {
+        if let Some(w) = self
+            .workers
+            .iter()
+            .find(|w| w.name == worker && !w.model.is_empty())
+        {
+            return Some((w.model.clone(), w.instructions.clone()));
+        }
+        if worker == DEFAULT_WORKER {
+            return default_model
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.to_string(), None));
+        }
+        None
+    }
+}
+
+/// Match a tool name against a glob with `*` (any run) and `?` (one char).
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+// ── Stats ──────────────────────────────────────────────────────────────────
+
+/// Rough token estimate (chars/4), same heuristic as the agent loop.
+pub fn estimate_tokens(text_len: usize) -> u64 {
+    (text_len / 4) as u64
+}
+
+/// Per-worker delegation counters (in-memory, reset on restart).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerStats {
+    pub delegations: u64,
+    pub failures: u64,
+    /// Tokens of raw tool output the worker consumed.
+    pub raw_tokens: u64,
+    /// Tokens the worker returned to the boss model.
+    pub returned_tokens: u64,
+    /// Boss-model context tokens avoided (`raw_tokens - returned_tokens`).
+    pub tokens_saved: u64,
+}
+
+impl WorkerStats {
+    pub fn record(&mut self, success: bool, raw_chars: usize, returned_chars: usize) {
+        self.delegations += 1;
+        if !success {
+            self.failures += 1;
+        }
+        let raw = estimate_tokens(raw_chars);
+        let returned = estimate_tokens(returned_chars);
+        self.raw_tokens += raw;
+        self.returned_tokens += returned;
+        self.tokens_saved += raw.saturating_sub(returned);
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Glob ───────────────────────────────────────────────────────
+
+    #[test]
+    fn glob_exact_and_wildcards() {
+        assert!(glob_match("fetch", "fetch"));
+        assert!(!glob_match("fetch", "fetch_page"));
+        assert!(glob_match("mcp_*", "mcp_n8n_run"));
+        assert!(glob_match("mcp_*_run", "mcp_n8n_run"));
+        assert!(!glob_match("mcp_*", "fetch"));
+        assert!(glob_match("f?tch", "fetch"));
+        assert!(glob_match("*", ""));
+    }
+
+    // ── Rules ──────────────────────────────────────────────────────
+
+    #[test]
+    fn default_config_matches_fetch_and_mcp() {
+        let cfg = WorkerDelegationConfig::default();
+        assert!(cfg.match_rule("fetch", 10).is_some());
+        assert!(cfg.match_rule("mcp_github_search", 10).is_some());
+        assert!(cfg.match_rule("exec", 10).is_none());
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_respects_payload_threshold() {
+        let mut big = DelegationRule::new(&["mcp_*"]);
+        big.worker = "summarizer".into();
+        big.min_payload_chars = 500;
+        let cfg = WorkerDelegationConfig {
+            rules: vec![big, DelegationRule::new(&["mcp_*"])],
+            ..Default::default()
+        };
+        assert_eq!(cfg.match_rule("mcp_x", 1000).unwrap().worker, "summarizer");
+        assert_eq!(cfg.match_rule("mcp_x", 10).unwrap().worker, DEFAULT_WORKER);
+    }
+
+    #[test]
+    fn disabled_config_matches_nothing() {
+        let cfg = WorkerDelegationConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(cfg.match_rule("fetch", 0).is_none());
+    }
+
+    #[test]
+    fn resolve_worker_named_and_default() {
+        let cfg = WorkerDelegationConfig {
+            workers: vec![NamedWorker {
+                name: "vision".into(),
+                model: "gpt-4o-mini".into(),
+                instructions: Some("Describe images".into()),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            cfg.resolve_worker("vision", None),
+            Some(("gpt-4o-mini".into(), Some("Describe images".into())))
+        );
+        assert_eq!(
+            cfg.resolve_worker(DEFAULT_WORKER, Some("qwen2.5:7b")),
+            Some(("qwen2.5:7b".into(), None))
+        );
+        assert_eq!(cfg.resolve_worker(DEFAULT_WORKER, Some("")), None);
+        assert_eq!(cfg.resolve_worker("missing", Some("qwen2.5:7b")), None);
+    }
+
+    #[test]
+    fn partial_json_uses_defaults() {
+        let cfg: WorkerDelegationConfig =
+            serde_json::from_str(r#"{"rules": [{"tools": ["fetch"]}]}"#).unwrap();
+        assert!(cfg.enabled);
+        assert_eq!(cfg.rules[0].worker, DEFAULT_WORKER);
+        assert!(cfg.rules[0].enabled);
+    }
+
+    // ── Summarization ──────────────────────────────────────────────
+
+    #[test]
+    fn summarization_off_by_default() {
+        let cfg = ResultSummarization::default();
+        assert!(!cfg.applies("fetch", 1_000_000));
+    }
+
+    #[test]
+    fn summarization_threshold_and_excludes() {
+        let cfg = ResultSummarization {
+            enabled: true,
+            threshold_tokens: 100,
+            ..Default::default()
+        };
+        assert!(!cfg.applies("fetch", 400));
+        assert!(cfg.applies("fetch", 404));
+        assert!(!cfg.applies("read_file", 10_000));
+    }
+
+    // ── Stats ──────────────────────────────────────────────────────
+
+    #[test]
+    fn stats_record_savings() {
+        let mut s = WorkerStats::default();
+        s.record(true, 4000, 400);
+        s.record(false, 40, 400);
+        assert_eq!(s.delegations, 2);
+        assert_eq!(s.failures, 1);
+        assert_eq!(s.raw_tokens, 1010);
+        assert_eq!(s.returned_tokens, 200);
+        assert_eq!(s.tokens_saved, 900);
+    }
+}
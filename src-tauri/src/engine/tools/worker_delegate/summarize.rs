@@ -0,0 +1,167 @@
+// Worker Delegation — Result Summarization
+//
+// Oversized tool results (scrapes, logs, big JSON) are condensed by the
+// worker model before they enter the main model's context. The raw output
+// is written to the agent workspace as an artifact so nothing is lost —
+// the main model can read it back with read_file when it needs detail.
+
+use super::rules::ResultSummarization;
+use super::{load_config, resolve_worker_provider};
+use crate::atoms::types::*;
+use crate::engine::providers::AnyProvider;
+use crate::engine::state::EngineState;
+use crate::engine::util::safe_truncate;
+use log::{info, warn};
+use tauri::Manager;
+
+/// Maximum raw chars sent to the worker for summarization.
+const MAX_SUMMARY_INPUT_CHARS: usize = 120_000;
+
+/// Workspace subdirectory holding raw tool-result artifacts.
+const ARTIFACT_DIR: &str = "tool-results";
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You condense tool output for another AI model.\n\n\
+    ## Rules\n\
+    1. Preserve EXACTLY: identifiers (IDs, names, URLs, paths, keys), numbers, dates, \
+    amounts, and any error or warning messages.\n\
+    2. Drop boilerplate, markup, navigation text, and repeated records (say how many were omitted).\n\
+    3. Keep the original structure when it helps (lists, key: value lines).\n\
+    4. Never add commentary, advice, or information not in the output.\n\
+    5. Output only the condensed result.";
+
+/// Summarize an oversized tool result with the configured worker model.
+///
+/// Returns the text to insert into the main model's context, or `None` when
+/// summarization is disabled, not needed, or failed (caller uses the raw output).
+pub async fn summarize_oversized_result(
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+    tool_call: &ToolCall,
+    output: &str,
+) -> Option<String> {
+    let state = app_handle.try_state::<EngineState>()?;
+    let config = load_config(&state);
+    let settings: &ResultSummarization = &config.summarization;
+    if !settings.applies(&tool_call.function.name, output.len()) {
+        return None;
+    }
+
+    let (default_model, providers) = {
+        let cfg = state.config.lock();
+        (
+            cfg.model_routing.worker_model.clone(),
+            cfg.providers.clone(),
+        )
+    };
+    let Some((model, _)) = config.resolve_worker(&settings.worker, default_model.as_deref()) else {
+        warn!(
+            "[worker-summarize] Worker '{}' not configured — inserting raw result",
+            settings.worker
+        );
+        return None;
+    };
+    let provider_config = resolve_worker_provider(&model, &providers)?;
+    let provider = AnyProvider::from_config(&provider_config);
+
+    let artifact = save_artifact(agent_id, &tool_call.id, output);
+
+    let messages = vec![
+        Message {
+            role: Role::System,
+            content: MessageContent::Text(SUMMARY_SYSTEM_PROMPT.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+        Message {
+            role: Role::User,
+            content: MessageContent::Text(format!(
+                "Output of tool `{}` (arguments: {}):\n\n{}",
+                tool_call.function.name,
+                safe_truncate(&tool_call.function.arguments, 500),
+                safe_truncate(output, MAX_SUMMARY_INPUT_CHARS)
+            )),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        },
+    ];
+
+    let summary = match provider
+        .chat_stream(&messages, &[], &model, Some(0.0), None)
+        .await
+    {
+        Ok(chunks) => chunks
+            .iter()
+            .filter_map(|c| c.delta_text.as_deref())
+            .collect::<String>(),
+        Err(e) => {
+            warn!(
+                "[worker-summarize] Summarization of '{}' failed: {}",
+                tool_call.function.name, e
+            );
+            return None;
+        }
+    };
+    if summary.trim().is_empty() || summary.len() >= output.len() {
+        return None;
+    }
+
+    let note = match artifact {
+        Some(path) => format!(
+            "[Summarized by worker model — full output ({} chars) saved to {}. \
+             Use read_file on that path if you need exact details.]",
+            output.len(),
+            path
+        ),
+        None => format!(
+            "[Summarized by worker model — full output was {} chars.]",
+            output.len()
+        ),
+    };
+    let condensed = format!("{}\n\n{}", note, summary.trim());
+
+    info!(
+        "[worker-summarize] '{}' result condensed: {} → {} chars (worker '{}')",
+        tool_call.function.name,
+        output.len(),
+        condensed.len(),
+        settings.worker
+    );
+    state
+        .worker_stats
+        .lock()
+        .entry(settings.worker.clone())
+        .or_default()
+        .record(true, output.len(), condensed.len());
+
+    Some(condensed)
+}
+
+/// Write the raw result to `<workspace>/tool-results/<call_id>.txt`.
+fn save_artifact(agent_id: &str, call_id: &str, output: &str) -> Option<String> {
+    let ws = crate::engine::tools::ensure_workspace(agent_id).ok()?;
+    let dir = ws.join(ARTIFACT_DIR);
+    std::fs::create_dir_all(&dir).ok()?;
+    let safe_id: String = call_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = dir.join(format!("{}.txt", safe_id));
+    match std::fs::write(&path, output) {
+        Ok(()) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!(
+                "[worker-summarize] Failed to save raw result artifact: {}",
+                e
+            );
+            None
+        }
+    }
+}
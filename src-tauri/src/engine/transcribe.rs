@@ -0,0 +1,309 @@
+// Paw Agent Engine — Speech-to-Text
+//
+// Transcription shared by Talk Mode (`engine_tts_transcribe`) and the
+// `audio_summarize` tool.
+//
+//   api     OpenAI Whisper (`/audio/transcriptions`) with the configured
+//           OpenAI provider — preferred, same as Talk Mode
+//   local   the `whisper` CLI (openai-whisper), when no OpenAI provider is
+//           configured; runs offline, slower
+//
+// The Whisper API rejects uploads over 25 MB, so long recordings are cut
+// into 10-minute mono MP3 segments with `ffmpeg` and the segment timestamps
+// are shifted back onto the original timeline.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::state::EngineState;
+use crate::engine::types::ProviderKind;
+use crate::engine::youtube::Segment;
+use log::info;
+use std::path::{Path, PathBuf};
+
+/// Whisper API upload limit, with some headroom.
+const MAX_UPLOAD_BYTES: u64 = 24 * 1024 * 1024;
+const SEGMENT_SECS: u64 = 600;
+
+pub enum Backend {
+    WhisperApi { api_key: String, base_url: String },
+    LocalWhisper,
+}
+
+impl Backend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Backend::WhisperApi { .. } => "Whisper API",
+            Backend::LocalWhisper => "local whisper",
+        }
+    }
+}
+
+/// The configured OpenAI provider's key and base URL, if any.
+pub fn openai_credentials(state: &EngineState) -> Option<(String, String)> {
+    let config = state.config.lock();
+    config
+        .providers
+        .iter()
+        .find(|p| p.kind == ProviderKind::OpenAI)
+        .map(|p| {
+            (
+                p.api_key.clone(),
+                p.base_url
+                    .clone()
+                    .unwrap_or_else(|| "https://api.openai.com/v1".into()),
+            )
+        })
+}
+
+fn has_binary(name: &str) -> bool {
+    std::process::Command::new("which")
+        .arg(name)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+pub fn pick_backend(state: &EngineState) -> EngineResult<Backend> {
+    if let Some((api_key, base_url)) = openai_credentials(state) {
+        return Ok(Backend::WhisperApi { api_key, base_url });
+    }
+    if has_binary("whisper") {
+        return Ok(Backend::LocalWhisper);
+    }
+    Err("No transcription backend available. Add an OpenAI provider in Settings → Providers, or install the local whisper CLI (pip install openai-whisper).".into())
+}
+
+pub fn audio_mime(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") | Some("mp4") | Some("aac") => "audio/mp4",
+        Some("wav") => "audio/wav",
+        Some("ogg") | Some("oga") | Some("opus") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        Some("webm") => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// One request to the Whisper API. `verbose` asks for `verbose_json`
+/// (with timestamped segments) instead of plain JSON.
+pub async fn whisper_api(
+    api_key: &str,
+    base_url: &str,
+    audio: Vec<u8>,
+    file_name: &str,
+    mime_type: &str,
+    language: Option<&str>,
+    verbose: bool,
+) -> EngineResult<serde_json::Value> {
+    let file_part = reqwest::multipart::Part::bytes(audio)
+        .file_name(file_name.to_string())
+        .mime_str(mime_type)
+        .map_err(|e| format!("MIME error: {}", e))?;
+
+    let mut form = reqwest::multipart::Form::new()
+        .text("model", "whisper-1")
+        .part("file", file_part);
+    if let Some(lang) = language {
+        form = form.text("language", lang.to_string());
+    }
+    if verbose {
+        form = form.text("response_format", "verbose_json");
+    }
+
+    let resp = reqwest::Client::new()
+        .post(format!(
+            "{}/audio/transcriptions",
+            base_url.trim_end_matches('/')
+        ))
+        .header("Authorization", format!("Bearer {}", api_key))
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Whisper API request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Whisper API error ({}): {}", status, body).into());
+    }
+    Ok(resp
+        .json()
+        .await
+        .map_err(|e| format!("Whisper API JSON parse error: {}", e))?)
+}
+
+/// Timestamped segments from a Whisper JSON result (API `verbose_json` or
+/// the CLI's `.json` output), shifted by `offset` seconds.
+fn whisper_segments(result: &serde_json::Value, offset: f64) -> Vec<Segment> {
+    match result["segments"].as_array() {
+        Some(segments) => segments
+            .iter()
+            .filter_map(|s| {
+                let text = s["text"].as_str()?.trim();
+                (!text.is_empty()).then(|| Segment {
+                    start: offset + s["start"].as_f64().unwrap_or(0.0),
+                    text: text.to_string(),
+                })
+            })
+            .collect(),
+        None => result["text"]
+            .as_str()
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| {
+                vec![Segment {
+                    start: offset,
+                    text: t.trim().to_string(),
+                }]
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Cut `path` into mono 64 kbit/s MP3 segments of `SEGMENT_SECS` in `dir`.
+async fn split_audio(path: &Path, dir: &Path) -> EngineResult<Vec<PathBuf>> {
+    if !has_binary("ffmpeg") {
+        return Err("This recording is larger than the 25 MB Whisper API limit. Install ffmpeg so it can be split into segments.".into());
+    }
+    let pattern = dir.join("segment-%03d.mp3");
+    let output = tokio::process::Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y", "-i"])
+        .arg(path)
+        .args(["-vn", "-ac", "1", "-b:a", "64k", "-f", "segment"])
+        .args(["-segment_time", &SEGMENT_SECS.to_string()])
+        .arg(&pattern)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    let mut segments: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("segment-"))
+        })
+        .collect();
+    segments.sort();
+    Ok(segments)
+}
+
+/// Transcribe an audio file into timestamped segments. `on_progress` is
+/// called with (segments done, total) for long recordings.
+pub async fn transcribe_file(
+    backend: &Backend,
+    path: &Path,
+    language: Option<&str>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> EngineResult<Vec<Segment>> {
+    let work_dir = std::env::temp_dir().join(format!("paw-transcribe-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir)?;
+    let result = async {
+        match backend {
+            Backend::WhisperApi { api_key, base_url } => {
+                let chunks = if std::fs::metadata(path)?.len() <= MAX_UPLOAD_BYTES {
+                    vec![path.to_path_buf()]
+                } else {
+                    split_audio(path, &work_dir).await?
+                };
+                let mut all = Vec::new();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    on_progress(i, chunks.len());
+                    let name = chunk
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| "audio.mp3".into());
+                    let json = whisper_api(
+                        api_key,
+                        base_url,
+                        std::fs::read(chunk)?,
+                        &name,
+                        audio_mime(chunk),
+                        language,
+                        true,
+                    )
+                    .await?;
+                    all.extend(whisper_segments(&json, (i as u64 * SEGMENT_SECS) as f64));
+                }
+                Ok(all)
+            }
+            Backend::LocalWhisper => {
+                on_progress(0, 1);
+                let mut cmd = tokio::process::Command::new("whisper");
+                cmd.arg(path)
+                    .args(["--model", "base", "--output_format", "json"])
+                    .arg("--output_dir")
+                    .arg(&work_dir);
+                if let Some(lang) = language {
+                    cmd.args(["--language", lang]);
+                }
+                let output = cmd
+                    .output()
+                    .await
+                    .map_err(|e| format!("Failed to run whisper: {}", e))?;
+                if !output.status.success() {
+                    return Err(format!(
+                        "whisper failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )
+                    .into());
+                }
+                let stem = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+                    work_dir.join(format!("{}.json", stem)),
+                )?)?;
+                Ok(whisper_segments(&json, 0.0))
+            }
+        }
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    info!(
+        "[transcribe] {} via {}: {} segment(s)",
+        path.display(),
+        backend.label(),
+        result.as_ref().map(|s| s.len()).unwrap_or(0)
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn segments_are_offset() {
+        let verbose = json!({
+            "text": "Hello there. Bye.",
+            "segments": [
+                { "start": 0.0, "end": 2.0, "text": " Hello there." },
+                { "start": 2.5, "end": 3.0, "text": " " },
+                { "start": 4.0, "end": 5.0, "text": " Bye." }
+            ]
+        });
+        let segs = whisper_segments(&verbose, 600.0);
+        assert_eq!(segs.len(), 2);
+        assert_eq!(segs[1].start, 604.0);
+        assert_eq!(segs[1].text, "Bye.");
+
+        let plain = whisper_segments(&json!({ "text": " Just text " }), 0.0);
+        assert_eq!(plain[0].text, "Just text");
+        assert_eq!(audio_mime(Path::new("a/Episode.M4A")), "audio/mp4");
+    }
+}
@@ -0,0 +1,305 @@
+// Paw Agent Engine — Twitch Chat Commands
+//
+// `!name …` commands configured on the bridge. Each command carries:
+//   - a permission level, checked against the sender's IRC badges
+//     (any < subscriber < moderator < broadcaster)
+//   - a global cooldown and a per-viewer cooldown
+//   - a prompt template sent to the agent, with `{user}` and `{input}`
+//     filled in
+//
+// Commands answer whether or not the bot was @mentioned. Denied and
+// cooling-down commands are ignored silently — answering them in a busy
+// chat is spam.
+
+use super::parse_tag;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandPermission {
+    #[default]
+    Any,
+    Subscriber,
+    Moderator,
+    Broadcaster,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwitchCommand {
+    /// Command word without the `!` (e.g. "ask")
+    pub name: String,
+    /// Prompt sent to the agent. `{input}` is the text after the command,
+    /// `{user}` the viewer's display name.
+    pub prompt: String,
+    #[serde(default)]
+    pub permission: CommandPermission,
+    /// Seconds before anyone can use the command again
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// Seconds before the same viewer can use the command again
+    #[serde(default)]
+    pub user_cooldown_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// `!ask` for everyone, `!task` for moderators.
+pub fn default_commands() -> Vec<TwitchCommand> {
+    vec![
+        TwitchCommand {
+            name: "ask".into(),
+            prompt: "{user} asks: {input}".into(),
+            permission: CommandPermission::Any,
+            cooldown_secs: 10,
+            user_cooldown_secs: 60,
+            enabled: true,
+        },
+        TwitchCommand {
+            name: "task".into(),
+            prompt: "{user} (a channel moderator) asked you to do this: {input}\n\
+                     Do it with your tools, then reply with a one-line status for chat."
+                .into(),
+            permission: CommandPermission::Moderator,
+            cooldown_secs: 0,
+            user_cooldown_secs: 30,
+            enabled: true,
+        },
+    ]
+}
+
+/// The sender's highest permission level, from the IRCv3 `badges`,
+/// `mod` and `subscriber` tags.
+pub(crate) fn sender_permission(tags: &str) -> CommandPermission {
+    let badges = parse_tag(tags, "badges").unwrap_or_default();
+    let has_badge = |name: &str| badges.split(',').any(|b| b.split('/').next() == Some(name));
+    if has_badge("broadcaster") {
+        CommandPermission::Broadcaster
+    } else if has_badge("moderator") || parse_tag(tags, "mod").as_deref() == Some("1") {
+        CommandPermission::Moderator
+    } else if has_badge("subscriber")
+        || has_badge("founder")
+        || parse_tag(tags, "subscriber").as_deref() == Some("1")
+    {
+        CommandPermission::Subscriber
+    } else {
+        CommandPermission::Any
+    }
+}
+
+/// Split `!name rest` into the lowercased command word and its input.
+pub(crate) fn parse_command(content: &str) -> Option<(String, &str)> {
+    let body = content.trim().strip_prefix('!')?;
+    let (name, input) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+    if name.is_empty() {
+        return None;
+    }
+    Some((name.to_lowercase(), input.trim()))
+}
+
+/// Fill `{key}` placeholders in a prompt template.
+pub(crate) fn render_prompt(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter().fold(template.to_string(), |acc, (key, value)| {
+        acc.replace(&format!("{{{}}}", key), value)
+    })
+}
+
+/// What to do with a chat message that starts with `!`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum CommandOutcome {
+    /// Run the agent with this prompt.
+    Run(String),
+    /// The sender's badges don't meet the command's permission level.
+    Denied,
+    /// Used too recently; usable again after this long.
+    CoolingDown(Duration),
+}
+
+/// Last-use times for the global and per-viewer cooldowns.
+#[derive(Default)]
+pub(crate) struct Cooldowns {
+    global: HashMap<String, Instant>,
+    per_user: HashMap<(String, String), Instant>,
+}
+
+impl Cooldowns {
+    /// Match `content` against the configured commands. `None` when it
+    /// isn't one of them, so it can be handled as ordinary chat.
+    pub(crate) fn dispatch(
+        &mut self,
+        commands: &[TwitchCommand],
+        content: &str,
+        user: &str,
+        display_name: &str,
+        permission: CommandPermission,
+        now: Instant,
+    ) -> Option<CommandOutcome> {
+        let (name, input) = parse_command(content)?;
+        let command = commands
+            .iter()
+            .find(|c| c.enabled && c.name.trim_start_matches('!').eq_ignore_ascii_case(&name))?;
+
+        if permission < command.permission {
+            return Some(CommandOutcome::Denied);
+        }
+
+        let user_key = (name.clone(), user.to_lowercase());
+        let remaining = |last: Option<&Instant>, secs: u64| {
+            last.map(|t| Duration::from_secs(secs).saturating_sub(now.duration_since(*t)))
+                .filter(|d| !d.is_zero())
+        };
+        // Broadcasters and moderators aren't held to the cooldowns
+        if permission < CommandPermission::Moderator {
+            let wait = remaining(self.global.get(&name), command.cooldown_secs)
+                .into_iter()
+                .chain(remaining(
+                    self.per_user.get(&user_key),
+                    command.user_cooldown_secs,
+                ))
+                .max();
+            if let Some(wait) = wait {
+                return Some(CommandOutcome::CoolingDown(wait));
+            }
+        }
+
+        self.global.insert(name, now);
+        self.per_user.insert(user_key, now);
+        Some(CommandOutcome::Run(render_prompt(
+            &command.prompt,
+            &[("user", display_name), ("input", input)],
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_from_badges() {
+        assert_eq!(
+            sender_permission("badges=broadcaster/1,subscriber/0;mod=0"),
+            CommandPermission::Broadcaster
+        );
+        assert_eq!(
+            sender_permission("badges=;mod=1;subscriber=0"),
+            CommandPermission::Moderator
+        );
+        assert_eq!(
+            sender_permission("badges=founder/0;mod=0"),
+            CommandPermission::Subscriber
+        );
+        assert_eq!(
+            sender_permission("badges=;mod=0;subscriber=0"),
+            CommandPermission::Any
+        );
+        assert_eq!(sender_permission(""), CommandPermission::Any);
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse_command("!ASK what's the song?"),
+            Some(("ask".to_string(), "what's the song?"))
+        );
+        assert_eq!(parse_command("!task"), Some(("task".to_string(), "")));
+        assert_eq!(parse_command("hello !ask"), None);
+        assert_eq!(parse_command("! nope"), None);
+    }
+
+    #[test]
+    fn dispatch_checks_permission_and_cooldowns() {
+        let commands = default_commands();
+        let mut cooldowns = Cooldowns::default();
+        let t0 = Instant::now();
+
+        assert_eq!(
+            cooldowns.dispatch(
+                &commands,
+                "!task clip that",
+                "viewer",
+                "Viewer",
+                CommandPermission::Subscriber,
+                t0
+            ),
+            Some(CommandOutcome::Denied)
+        );
+        assert_eq!(
+            cooldowns.dispatch(
+                &commands,
+                "!lurk",
+                "viewer",
+                "Viewer",
+                CommandPermission::Any,
+                t0
+            ),
+            None
+        );
+        assert_eq!(
+            cooldowns.dispatch(
+                &commands,
+                "!ask hi",
+                "viewer",
+                "Viewer",
+                CommandPermission::Any,
+                t0
+            ),
+            Some(CommandOutcome::Run("Viewer asks: hi".into()))
+        );
+
+        // Global cooldown (10s) for someone else, per-viewer (60s) for the same viewer
+        let t5 = t0 + Duration::from_secs(5);
+        assert_eq!(
+            cooldowns.dispatch(
+                &commands,
+                "!ask yo",
+                "other",
+                "Other",
+                CommandPermission::Any,
+                t5
+            ),
+            Some(CommandOutcome::CoolingDown(Duration::from_secs(5)))
+        );
+        let t20 = t0 + Duration::from_secs(20);
+        assert!(matches!(
+            cooldowns.dispatch(
+                &commands,
+                "!ask yo",
+                "other",
+                "Other",
+                CommandPermission::Any,
+                t20
+            ),
+            Some(CommandOutcome::Run(_))
+        ));
+        assert_eq!(
+            cooldowns.dispatch(
+                &commands,
+                "!ask again",
+                "viewer",
+                "Viewer",
+                CommandPermission::Any,
+                t20
+            ),
+            Some(CommandOutcome::CoolingDown(Duration::from_secs(40)))
+        );
+
+        // Moderators skip cooldowns
+        assert!(matches!(
+            cooldowns.dispatch(
+                &commands,
+                "!ask now",
+                "mod",
+                "Mod",
+                CommandPermission::Moderator,
+                t20
+            ),
+            Some(CommandOutcome::Run(_))
+        ));
+    }
+}
@@ -0,0 +1,423 @@
+// Paw Agent Engine — Twitch EventSub (channel point redemptions)
+//
+// Receives `channel.channel_points_custom_reward_redemption.add` over the
+// EventSub WebSocket transport:
+//   1. Connect to wss://eventsub.wss.twitch.tv/ws; the `session_welcome`
+//      message carries a session id.
+//   2. Within 10s, POST /helix/eventsub/subscriptions once per joined
+//      channel, bound to that session.
+//   3. Redemptions arrive as `notification` messages. `session_reconnect`
+//      hands over a new URL — subscriptions move with the session, so the
+//      new socket is opened before the old one is dropped.
+//
+// The token must belong to the broadcaster (or one of their editors) and
+// carry the `channel:read:redemptions` scope. The client id and user come
+// from /oauth2/validate, so no app registration details are configured.
+
+use super::{get_stop_signal, TwitchConfig};
+use crate::atoms::error::{EngineError, EngineResult};
+use crate::engine::events::{self, EngineEvent};
+use futures::StreamExt;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+
+const EVENTSUB_URL: &str = "wss://eventsub.wss.twitch.tv/ws";
+const HELIX_URL: &str = "https://api.twitch.tv/helix";
+const VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
+const REDEMPTION_TYPE: &str = "channel.channel_points_custom_reward_redemption.add";
+const REQUIRED_SCOPE: &str = "channel:read:redemptions";
+
+/// Slack on top of the session's keepalive interval before giving up.
+const KEEPALIVE_GRACE: Duration = Duration::from_secs(10);
+
+/// A channel point reward that triggers the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedemptionTrigger {
+    /// Reward title as shown on Twitch (case-insensitive)
+    pub reward: String,
+    /// Prompt sent to the agent. `{user}`, `{input}` (the viewer's text,
+    /// for rewards that ask for one) and `{reward}` are filled in.
+    pub prompt: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// One channel point redemption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Redemption {
+    /// Broadcaster login (the chat channel without `#`)
+    pub channel: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub reward: String,
+    pub input: String,
+}
+
+/// The configured trigger for a reward title, if any.
+pub(crate) fn find_trigger<'a>(
+    triggers: &'a [RedemptionTrigger],
+    reward: &str,
+) -> Option<&'a RedemptionTrigger> {
+    triggers
+        .iter()
+        .find(|t| t.enabled && t.reward.trim().eq_ignore_ascii_case(reward.trim()))
+}
+
+// ── Wire format ────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq)]
+enum Message {
+    Welcome {
+        session_id: String,
+        keepalive: Duration,
+    },
+    Keepalive,
+    Redemption(Redemption),
+    Reconnect(String),
+    Revocation(String),
+    Other,
+}
+
+fn parse_message(text: &str) -> Option<Message> {
+    let msg: serde_json::Value = serde_json::from_str(text).ok()?;
+    let payload = &msg["payload"];
+    let message = match msg["metadata"]["message_type"].as_str()? {
+        "session_welcome" => Message::Welcome {
+            session_id: payload["session"]["id"].as_str()?.to_string(),
+            keepalive: Duration::from_secs(
+                payload["session"]["keepalive_timeout_seconds"]
+                    .as_u64()
+                    .unwrap_or(10),
+            ),
+        },
+        "session_keepalive" => Message::Keepalive,
+        "session_reconnect" => {
+            Message::Reconnect(payload["session"]["reconnect_url"].as_str()?.to_string())
+        }
+        "revocation" => Message::Revocation(
+            payload["subscription"]["status"]
+                .as_str()
+                .unwrap_or("revoked")
+                .to_string(),
+        ),
+        "notification" if payload["subscription"]["type"] == REDEMPTION_TYPE => {
+            let event = &payload["event"];
+            let field = |key: &str| event[key].as_str().unwrap_or_default().to_string();
+            Message::Redemption(Redemption {
+                channel: field("broadcaster_user_login"),
+                user_login: field("user_login"),
+                user_name: field("user_name"),
+                reward: event["reward"]["title"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                input: field("user_input"),
+            })
+        }
+        _ => Message::Other,
+    };
+    Some(message)
+}
+
+// ── Helix ──────────────────────────────────────────────────────────────
+
+/// Client id and scopes behind a user token.
+struct TokenInfo {
+    client_id: String,
+    scopes: Vec<String>,
+}
+
+async fn validate_token(http: &reqwest::Client, token: &str) -> EngineResult<TokenInfo> {
+    let resp = http
+        .get(VALIDATE_URL)
+        .header("Authorization", format!("OAuth {}", token))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err("Twitch rejected the EventSub token — it may have expired.".into());
+    }
+    let body: serde_json::Value = resp.json().await?;
+    Ok(TokenInfo {
+        client_id: body["client_id"].as_str().unwrap_or_default().to_string(),
+        scopes: body["scopes"]
+            .as_array()
+            .map(|s| {
+                s.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Subscribe to redemptions in every joined channel; returns how many
+/// subscriptions Twitch accepted.
+async fn subscribe(
+    http: &reqwest::Client,
+    token: &str,
+    client_id: &str,
+    channels: &[String],
+    session_id: &str,
+) -> EngineResult<usize> {
+    let logins: Vec<(&str, &str)> = channels
+        .iter()
+        .map(|c| ("login", c.trim_start_matches('#')))
+        .collect();
+    let users: serde_json::Value = http
+        .get(format!("{}/users", HELIX_URL))
+        .bearer_auth(token)
+        .header("Client-Id", client_id)
+        .query(&logins)
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    let mut accepted = 0;
+    for user in users["data"].as_array().into_iter().flatten() {
+        let login = user["login"].as_str().unwrap_or_default();
+        let body = json!({
+            "type": REDEMPTION_TYPE,
+            "version": "1",
+            "condition": { "broadcaster_user_id": user["id"] },
+            "transport": { "method": "websocket", "session_id": session_id },
+        });
+        let resp = http
+            .post(format!("{}/eventsub/subscriptions", HELIX_URL))
+            .bearer_auth(token)
+            .header("Client-Id", client_id)
+            .json(&body)
+            .send()
+            .await?;
+        let status = resp.status();
+        if status.is_success() {
+            info!(
+                "[twitch] Listening for channel point redemptions in #{}",
+                login
+            );
+            accepted += 1;
+        } else {
+            // 403: the token isn't the broadcaster's (or an editor's)
+            warn!(
+                "[twitch] EventSub subscription for #{} refused: {} {}",
+                login,
+                status,
+                resp.text().await.unwrap_or_default()
+            );
+        }
+    }
+    Ok(accepted)
+}
+
+// ── WebSocket Loop ─────────────────────────────────────────────────────
+
+type Socket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+fn ws_error(e: impl std::fmt::Display) -> EngineError {
+    EngineError::Channel {
+        channel: "twitch".into(),
+        message: format!("EventSub: {}", e),
+    }
+}
+
+/// Open a socket and wait for its welcome message.
+async fn open_session(url: &str) -> EngineResult<(Socket, String, Duration)> {
+    let (mut ws, _) = connect_async(url).await.map_err(ws_error)?;
+    let (session_id, keepalive) =
+        tokio::time::timeout(Duration::from_secs(10), wait_for_welcome(&mut ws))
+            .await
+            .map_err(|_| ws_error("no welcome message"))??;
+    Ok((ws, session_id, keepalive))
+}
+
+async fn wait_for_welcome(ws: &mut Socket) -> EngineResult<(String, Duration)> {
+    while let Some(msg) = ws.next().await {
+        if let WsMessage::Text(text) = msg.map_err(ws_error)? {
+            if let Some(Message::Welcome {
+                session_id,
+                keepalive,
+            }) = parse_message(&text)
+            {
+                return Ok((session_id, keepalive));
+            }
+        }
+    }
+    Err(ws_error("closed before the welcome message"))
+}
+
+/// Run one EventSub session until it drops. Redemptions are dispatched to
+/// event-triggered tasks and forwarded to the chat loop through `tx`.
+pub(crate) async fn run_eventsub_loop(
+    app_handle: &tauri::AppHandle,
+    config: &TwitchConfig,
+    tx: &UnboundedSender<Redemption>,
+) -> EngineResult<()> {
+    let stop = get_stop_signal();
+    let token = if config.broadcaster_token.trim().is_empty() {
+        &config.oauth_token
+    } else {
+        &config.broadcaster_token
+    };
+    let token = token.trim().trim_start_matches("oauth:");
+
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .unwrap_or_default();
+    let info = validate_token(&http, token).await?;
+    if !info.scopes.iter().any(|s| s == REQUIRED_SCOPE) {
+        return Err(format!(
+            "The EventSub token is missing the {} scope — re-authorize it as the broadcaster.",
+            REQUIRED_SCOPE
+        )
+        .into());
+    }
+
+    let (mut ws, session_id, mut keepalive) = open_session(EVENTSUB_URL).await?;
+    let accepted = subscribe(
+        &http,
+        token,
+        &info.client_id,
+        &config.channels_to_join,
+        &session_id,
+    )
+    .await?;
+    if accepted == 0 {
+        return Err(
+            "No channel point subscription was accepted — the EventSub token must belong to the broadcaster."
+                .into(),
+        );
+    }
+
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        let msg = tokio::time::timeout(keepalive + KEEPALIVE_GRACE, ws.next())
+            .await
+            .map_err(|_| ws_error("keepalive timed out"))?;
+        let text = match msg {
+            Some(Ok(WsMessage::Text(t))) => t,
+            Some(Ok(WsMessage::Close(_))) | None => return Err(ws_error("connection closed")),
+            Some(Err(e)) => return Err(ws_error(e)),
+            _ => continue,
+        };
+
+        match parse_message(&text) {
+            Some(Message::Redemption(r)) => {
+                info!(
+                    "[twitch] {} redeemed '{}' in #{}",
+                    r.user_name, r.reward, r.channel
+                );
+                let _ = app_handle.emit(
+                    "twitch-status",
+                    json!({
+                        "kind": "redemption",
+                        "channel": &r.channel,
+                        "username": &r.user_name,
+                        "reward": &r.reward,
+                    }),
+                );
+                events::dispatch_event(
+                    app_handle,
+                    &EngineEvent::TwitchRedemption {
+                        channel: r.channel.clone(),
+                        reward: r.reward.clone(),
+                        user: r.user_login.clone(),
+                        input: r.input.clone(),
+                    },
+                )
+                .await;
+                let _ = tx.send(r);
+            }
+            Some(Message::Reconnect(url)) => {
+                info!("[twitch] EventSub session moving to a new socket");
+                let (new_ws, _, new_keepalive) = open_session(&url).await?;
+                ws = new_ws;
+                keepalive = new_keepalive;
+            }
+            Some(Message::Revocation(status)) => {
+                return Err(ws_error(format!("subscription revoked ({})", status)));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_session_messages() {
+        let welcome = r#"{"metadata":{"message_type":"session_welcome"},
+            "payload":{"session":{"id":"AQoQ","keepalive_timeout_seconds":30}}}"#;
+        assert_eq!(
+            parse_message(welcome),
+            Some(Message::Welcome {
+                session_id: "AQoQ".into(),
+                keepalive: Duration::from_secs(30),
+            })
+        );
+
+        let reconnect = r#"{"metadata":{"message_type":"session_reconnect"},
+            "payload":{"session":{"id":"AQoQ","reconnect_url":"wss://eventsub.wss.twitch.tv/ws?id=x"}}}"#;
+        assert_eq!(
+            parse_message(reconnect),
+            Some(Message::Reconnect(
+                "wss://eventsub.wss.twitch.tv/ws?id=x".into()
+            ))
+        );
+        assert_eq!(parse_message("not json"), None);
+    }
+
+    #[test]
+    fn parses_redemption_notification() {
+        let text = r#"{"metadata":{"message_type":"notification"},
+            "payload":{"subscription":{"type":"channel.channel_points_custom_reward_redemption.add"},
+            "event":{"broadcaster_user_login":"paw_streams","user_login":"viewer1","user_name":"Viewer1",
+            "user_input":"play lofi","reward":{"id":"r1","title":"Song Request","cost":500}}}}"#;
+        assert_eq!(
+            parse_message(text),
+            Some(Message::Redemption(Redemption {
+                channel: "paw_streams".into(),
+                user_login: "viewer1".into(),
+                user_name: "Viewer1".into(),
+                reward: "Song Request".into(),
+                input: "play lofi".into(),
+            }))
+        );
+    }
+
+    #[test]
+    fn trigger_lookup_ignores_case_and_disabled() {
+        let triggers = vec![
+            RedemptionTrigger {
+                reward: "Hydrate".into(),
+                prompt: "{user} says drink water".into(),
+                enabled: false,
+            },
+            RedemptionTrigger {
+                reward: "Song Request ".into(),
+                prompt: "Queue {input} for {user}".into(),
+                enabled: true,
+            },
+        ];
+        assert!(find_trigger(&triggers, "song request").is_some());
+        assert!(find_trigger(&triggers, "Hydrate").is_none());
+        assert!(find_trigger(&triggers, "Other").is_none());
+    }
+}
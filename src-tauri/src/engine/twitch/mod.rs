@@ -6,11 +6,24 @@
 // Setup: Go to dev.twitch.tv → Register App → Get OAuth token.
 //        Or use https://twitchapps.com/tmi/ to generate a quick token.
 //
+// Chat commands (`!ask`, `!task`, … — see commands.rs) answer without an
+// @mention, gated by badge-based permission levels and cooldowns. With
+// `channel_points` on, channel point redemptions arrive over EventSub
+// (eventsub.rs): they fire `twitch_redemption` task triggers, and rewards
+// listed in `redemption_triggers` get an agent reply in chat.
+//
 // Security:
 //   - Allowlist by Twitch username
 //   - Optional pairing mode
+//   - Per-command permission levels (any / subscriber / moderator / broadcaster)
 //   - All communication goes through Twitch's TLS IRC gateway
 
+mod commands;
+mod eventsub;
+
+pub use commands::{CommandPermission, TwitchCommand};
+pub use eventsub::RedemptionTrigger;
+
 use crate::atoms::error::{EngineError, EngineResult};
 use crate::engine::channels::{self, ChannelStatus, PendingUser};
 use futures::{SinkExt, StreamExt};
@@ -20,6 +33,7 @@ use serde_json::json;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::Arc;
 use tauri::Emitter;
+use tokio::sync::mpsc;
 use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
 
 // ── Twitch Config ──────────────────────────────────────────────────────
@@ -45,6 +59,19 @@ pub struct TwitchConfig {
     /// Phase C: allow dangerous/side-effect tools for messages from this channel
     #[serde(default)]
     pub allow_dangerous_tools: bool,
+    /// Chat commands (`!ask`, `!task`, …)
+    #[serde(default = "commands::default_commands")]
+    pub commands: Vec<TwitchCommand>,
+    /// Listen for channel point redemptions via EventSub
+    #[serde(default)]
+    pub channel_points: bool,
+    /// Broadcaster user token for EventSub (needs channel:read:redemptions).
+    /// Empty = use `oauth_token`.
+    #[serde(default)]
+    pub broadcaster_token: String,
+    /// Rewards the agent answers in chat when redeemed
+    #[serde(default)]
+    pub redemption_triggers: Vec<RedemptionTrigger>,
 }
 
 impl Default for TwitchConfig {
@@ -60,6 +87,10 @@ impl Default for TwitchConfig {
             agent_id: None,
             require_mention: true,
             allow_dangerous_tools: false,
+            commands: commands::default_commands(),
+            channel_points: false,
+            broadcaster_token: String::new(),
+            redemption_triggers: vec![],
         }
     }
 }
@@ -102,21 +133,54 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
 
     info!("[twitch] Starting bridge as {}", config.bot_username);
 
+    // Redemptions flow from the EventSub task into the chat loop, which
+    // owns the IRC socket the replies go out on.
+    let (redemption_tx, mut redemption_rx) = mpsc::unbounded_channel();
+    if config.channel_points {
+        let app = app_handle.clone();
+        let cfg = config.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut attempt: u32 = 0;
+            loop {
+                if get_stop_signal().load(Ordering::Relaxed) {
+                    break;
+                }
+                match eventsub::run_eventsub_loop(&app, &cfg, &redemption_tx).await {
+                    Ok(()) => attempt = 0,
+                    Err(e) => warn!("[twitch] EventSub error: {}", e),
+                }
+                if get_stop_signal().load(Ordering::Relaxed) {
+                    break;
+                }
+                tokio::time::sleep(crate::engine::http::reconnect_backoff(attempt)).await;
+                attempt = attempt.saturating_add(1);
+            }
+        });
+    }
+
     tauri::async_runtime::spawn(async move {
         loop {
             if get_stop_signal().load(Ordering::Relaxed) {
                 break;
             }
-            if let Err(e) = run_ws_loop(&app_handle, &config).await {
-                error!("[twitch] WebSocket error: {}", e);
-            }
+            let reason = match run_ws_loop(&app_handle, &config, &mut redemption_rx).await {
+                Ok(()) => "connection closed".to_string(),
+                Err(e) => {
+                    error!("[twitch] WebSocket error: {}", e);
+                    e.to_string()
+                }
+            };
             if get_stop_signal().load(Ordering::Relaxed) {
                 break;
             }
             warn!("[twitch] Reconnecting in 5s...");
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            let attempt = channels::connectivity("twitch").reconnect_attempt + 1;
+            let delay = std::time::Duration::from_secs(5);
+            channels::mark_reconnecting(&app_handle, "twitch", &reason, attempt, delay);
+            tokio::time::sleep(delay).await;
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        channels::mark_stopped(&app_handle, "twitch", None);
         info!("[twitch] Bridge stopped");
     });
 
@@ -142,12 +206,17 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("twitch"),
     }
 }
 
 // ── IRC-over-WebSocket Loop ────────────────────────────────────────────
 
-async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> EngineResult<()> {
+async fn run_ws_loop(
+    app_handle: &tauri::AppHandle,
+    config: &TwitchConfig,
+    redemptions: &mut mpsc::UnboundedReceiver<eventsub::Redemption>,
+) -> EngineResult<()> {
     let stop = get_stop_signal();
 
     // Twitch IRC over WebSocket endpoint
@@ -242,6 +311,7 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
         info!("[twitch] Joined {}", channel);
     }
 
+    channels::mark_connected(app_handle, "twitch");
     let _ = app_handle.emit(
         "twitch-status",
         json!({
@@ -252,6 +322,10 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
 
     let mut current_config = config.clone();
     let mut last_config_reload = std::time::Instant::now();
+    let mut cooldowns = commands::Cooldowns::default();
+    let agent_ctx = "You are chatting via Twitch chat. Keep responses SHORT — Twitch has a 500 character limit \
+                     per message. Use simple text, no markdown. Be casual and fun. \
+                     Twitch users expect quick, concise responses.";
 
     // Message loop
     loop {
@@ -261,6 +335,35 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
 
         let msg = tokio::select! {
             msg = ws_rx.next() => msg,
+            Some(r) = redemptions.recv() => {
+                let triggers = &current_config.redemption_triggers;
+                let Some(trigger) = eventsub::find_trigger(triggers, &r.reward) else {
+                    continue;
+                };
+                let prompt = commands::render_prompt(
+                    &trigger.prompt,
+                    &[
+                        ("user", r.user_name.as_str()),
+                        ("input", r.input.as_str()),
+                        ("reward", r.reward.as_str()),
+                    ],
+                );
+                MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+                let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
+                let response = channels::run_channel_agent(
+                    app_handle,
+                    "twitch",
+                    agent_ctx,
+                    &prompt,
+                    &r.user_login,
+                    agent_id,
+                    current_config.allow_dangerous_tools,
+                )
+                .await;
+                let channel = format!("#{}", r.channel);
+                send_reply(&mut ws_tx, &channel, &r.user_login, response).await;
+                continue;
+            }
             _ = tokio::time::sleep(std::time::Duration::from_secs(300)) => {
                 // Keepalive
                 let _ = ws_tx.send(WsMessage::Text("PING :tmi.twitch.tv".into())).await;
@@ -327,21 +430,42 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
             // Extract display name from tags
             let display_name = parse_tag(tags, "display-name").unwrap_or(sender.to_string());
 
-            // Check if bot is mentioned
-            let mention = format!("@{}", nick);
-            let is_mentioned = msg_content.to_lowercase().contains(&mention);
-
-            // If require_mention is on, only respond to mentions
-            if current_config.require_mention && !is_mentioned {
-                continue;
-            }
+            // Chat commands answer without a mention
+            let command = cooldowns.dispatch(
+                &current_config.commands,
+                msg_content,
+                sender,
+                &display_name,
+                commands::sender_permission(tags),
+                std::time::Instant::now(),
+            );
+            let content = match command {
+                Some(commands::CommandOutcome::Run(prompt)) => prompt,
+                Some(outcome) => {
+                    debug!(
+                        "[twitch] Ignoring command from {}: {:?}",
+                        display_name, outcome
+                    );
+                    continue;
+                }
+                None => {
+                    // Check if bot is mentioned
+                    let mention = format!("@{}", nick);
+                    let is_mentioned = msg_content.to_lowercase().contains(&mention);
+
+                    // If require_mention is on, only respond to mentions
+                    if current_config.require_mention && !is_mentioned {
+                        continue;
+                    }
 
-            // Strip mention from content
-            let content = msg_content
-                .replace(&mention, "")
-                .replace(&format!("@{}", config.bot_username), "")
-                .trim()
-                .to_string();
+                    // Strip mention from content
+                    msg_content
+                        .replace(&mention, "")
+                        .replace(&format!("@{}", config.bot_username), "")
+                        .trim()
+                        .to_string()
+                }
+            };
             if content.is_empty() {
                 continue;
             }
@@ -383,38 +507,17 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
             MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
 
             let agent_id = current_config.agent_id.as_deref().unwrap_or("default");
-            let ctx = "You are chatting via Twitch chat. Keep responses SHORT — Twitch has a 500 character limit \
-                       per message. Use simple text, no markdown. Be casual and fun. \
-                       Twitch users expect quick, concise responses.";
-
             let response = channels::run_channel_agent(
                 app_handle,
                 "twitch",
-                ctx,
+                agent_ctx,
                 &content,
                 &sender_lower,
                 agent_id,
                 current_config.allow_dangerous_tools,
             )
             .await;
-
-            match response {
-                Ok(reply) if !reply.is_empty() => {
-                    // Twitch limit is 500 chars per message
-                    for chunk in channels::split_message(&reply, 490) {
-                        let irc_msg = format!("PRIVMSG {} :{}", channel, chunk);
-                        let _ = ws_tx.send(WsMessage::Text(irc_msg.into())).await;
-                        // Twitch rate limit: ~20 msgs per 30s for regular, ~100 for mods
-                        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
-                    }
-                }
-                Err(e) => {
-                    error!("[twitch] Agent error for {}: {}", sender, e);
-                    let err_msg = format!("PRIVMSG {} :Error processing your message", channel);
-                    let _ = ws_tx.send(WsMessage::Text(err_msg.into())).await;
-                }
-                _ => {}
-            }
+            send_reply(&mut ws_tx, channel, sender, response).await;
         }
 
         // Reload config periodically
@@ -437,6 +540,30 @@ async fn run_ws_loop(app_handle: &tauri::AppHandle, config: &TwitchConfig) -> En
     Ok(())
 }
 
+/// Post an agent reply (or an error notice) to a channel, split to fit
+/// Twitch's 500-character message limit.
+async fn send_reply<S>(ws_tx: &mut S, channel: &str, sender: &str, response: EngineResult<String>)
+where
+    S: futures::Sink<WsMessage> + Unpin,
+{
+    match response {
+        Ok(reply) if !reply.is_empty() => {
+            for chunk in channels::split_message(&reply, 490) {
+                let irc_msg = format!("PRIVMSG {} :{}", channel, chunk);
+                let _ = ws_tx.send(WsMessage::Text(irc_msg.into())).await;
+                // Twitch rate limit: ~20 msgs per 30s for regular, ~100 for mods
+                tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+            }
+        }
+        Err(e) => {
+            error!("[twitch] Agent error for {}: {}", sender, e);
+            let err_msg = format!("PRIVMSG {} :Error processing your message", channel);
+            let _ = ws_tx.send(WsMessage::Text(err_msg.into())).await;
+        }
+        _ => {}
+    }
+}
+
 /// Parse a single IRC tag value from the tags string (key1=val1;key2=val2;...)
 fn parse_tag(tags: &str, key: &str) -> Option<String> {
     for pair in tags.split(';') {
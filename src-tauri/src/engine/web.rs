@@ -1,7 +1,7 @@
 // Paw Agent Engine — Web Browsing & Scraping
 //
 // Gives the agent full internet access:
-//   web_search  — DuckDuckGo search, returns structured results
+//   web_search  — search via the configured provider (engine::search)
 //   web_read    — Fetch any URL and extract readable text (strips HTML)
 //   web_screenshot — Take a full-page screenshot of any URL via headless Chrome
 //   web_browse  — Navigate, click, type, extract in a headless browser session
@@ -60,95 +60,46 @@ fn get_or_launch_browser(profile_dir: Option<std::path::PathBuf>) -> EngineResul
         )
     })?;
 
+    if let Some(pid) = browser.get_process_id() {
+        crate::engine::processes::track(pid, "browser");
+    }
     let arc = Arc::new(browser);
     *guard = Some(Arc::clone(&arc));
     info!("[web] Headless Chrome launched successfully");
     Ok(arc)
 }
 
-// ── web_search: DuckDuckGo search ──────────────────────────────────────
+/// Close the shared headless browser, if one was launched. Dropping the last
+/// handle terminates the Chrome process.
+pub fn close_browser() {
+    if let Some(mutex) = BROWSER.get() {
+        if let Some(browser) = mutex.lock().take() {
+            if let Some(pid) = browser.get_process_id() {
+                crate::engine::processes::untrack(pid);
+            }
+            info!("[web] Closed headless Chrome");
+        }
+    }
+}
+
+// ── web_search: configured search provider (see engine::search) ─────────
 
-pub async fn execute_web_search(args: &serde_json::Value) -> EngineResult<String> {
+pub async fn execute_web_search(
+    args: &serde_json::Value,
+    app_handle: &tauri::AppHandle,
+) -> EngineResult<String> {
     let query = args["query"]
         .as_str()
         .ok_or("web_search: missing 'query' argument")?;
     let limit = args["limit"].as_u64().unwrap_or(8) as usize;
+    let state = app_handle
+        .try_state::<crate::engine::state::EngineState>()
+        .ok_or("Engine state not available")?;
 
-    info!("[web] search: '{}' limit={}", query, limit);
-
-    // Use DuckDuckGo HTML endpoint (no API key required)
-    let encoded = url::form_urlencoded::Serializer::new(String::new())
-        .append_pair("q", query)
-        .finish();
-    let url = format!("https://html.duckduckgo.com/html/?{}", encoded);
-
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(15))
-        .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .build()?;
-
-    let resp = client.get(&url).send().await?;
-    let html = resp.text().await?;
-
-    // Parse DuckDuckGo HTML results
-    let document = Html::parse_document(&html);
-    let result_selector = Selector::parse(".result").unwrap();
-    let title_selector = Selector::parse(".result__a").unwrap();
-    let snippet_selector = Selector::parse(".result__snippet").unwrap();
-    let url_selector = Selector::parse(".result__url").unwrap();
-
-    let mut results = Vec::new();
-    for element in document.select(&result_selector).take(limit) {
-        let title = element
-            .select(&title_selector)
-            .next()
-            .map(|e| e.text().collect::<String>())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
-
-        let snippet = element
-            .select(&snippet_selector)
-            .next()
-            .map(|e| e.text().collect::<String>())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
-
-        let url = element
-            .select(&url_selector)
-            .next()
-            .map(|e| e.text().collect::<String>())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
-
-        if !title.is_empty() {
-            results.push(format!("**{}**\n{}\n{}", title, url, snippet));
-        }
-    }
-
-    if results.is_empty() {
-        // Fallback: try parsing DuckDuckGo "zero click" or different layout
-        let link_selector = Selector::parse("a.result__a").unwrap();
-        for element in document.select(&link_selector).take(limit) {
-            let title = element.text().collect::<String>().trim().to_string();
-            let href = element.value().attr("href").unwrap_or("").to_string();
-            if !title.is_empty() && !href.is_empty() {
-                results.push(format!("**{}**\n{}", title, href));
-            }
-        }
-    }
-
-    if results.is_empty() {
-        return Ok(format!("No search results found for '{}'.", query));
-    }
-
-    let mut output = format!("Search results for '{}':\n\n", query);
-    for (i, r) in results.iter().enumerate() {
-        output.push_str(&format!("{}. {}\n\n", i + 1, r));
-    }
-    Ok(output)
+    let (results, backend) = crate::engine::search::search(&state.store, query, limit).await?;
+    Ok(crate::engine::search::format_results(
+        query, &results, backend,
+    ))
 }
 
 // ── web_read: Fetch URL → readable text ────────────────────────────────
@@ -247,6 +198,32 @@ pub async fn execute_web_read(args: &serde_json::Value) -> EngineResult<String>
     ))
 }
 
+/// Fetch `url` and return its (title, readable text) — the non-tool core of
+/// web_read, used by importers that index pages in bulk.
+pub async fn fetch_readable(client: &reqwest::Client, url: &str) -> EngineResult<(String, String)> {
+    let resp = client.get(url).send().await?.error_for_status()?;
+    let content_type = resp
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let body = resp.text().await?;
+    if content_type.starts_with("text/plain") {
+        return Ok((String::new(), body));
+    }
+    if !content_type.contains("html") {
+        return Err(format!("unsupported content type '{}'", content_type).into());
+    }
+    let document = Html::parse_document(&body);
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .unwrap_or_default();
+    Ok((title, extract_readable_text(&document)))
+}
+
 /// Extract readable text from an HTML element, skipping scripts/styles.
 fn extract_text_from_element(element: &scraper::ElementRef) -> String {
     let mut text = String::new();
@@ -264,7 +241,7 @@ fn extract_text_from_element(element: &scraper::ElementRef) -> String {
 
 /// Extract readable content from a full HTML document.
 /// Tries <article>, <main>, then falls back to <body>, skipping nav/footer/script/style.
-fn extract_readable_text(document: &Html) -> String {
+pub(crate) fn extract_readable_text(document: &Html) -> String {
     // Try content-rich selectors first
     for sel_str in &[
         "article",
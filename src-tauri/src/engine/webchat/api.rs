@@ -0,0 +1,283 @@
+// Paw Agent Engine — Web Chat REST API
+//
+// For scripts and simple integrations that don't want a WebSocket:
+//
+//   POST /api/message   Authorization: Bearer <access token>
+//     {"text": "summarize today's inbox", "user": "ci-bot", "wait": 30}
+//     → 200 {"status":"done","reply":"…","files":[…]} if the agent answers
+//       within `wait` seconds (default 60, at most 300; 0 returns at once)
+//     → 202 {"status":"pending","job_id":"…","poll":"/api/jobs/<id>"} otherwise
+//   GET /api/jobs/<id>  (same auth) → {"status":"pending"|"done"|"error", …}
+//
+// Messages go through the same access policy, agent session and history as
+// the chat page; `user` (default "api") is the user id. File URLs in replies
+// (/files/…) accept the same bearer token. Finished jobs are kept for an hour.
+
+use super::files::ChatFile;
+use super::server::{header, query_param, read_body, respond_json, ChatStream};
+use super::{is_user_allowed, load_config, run_turn, save_config};
+use crate::atoms::error::EngineResult;
+use crate::engine::channels;
+use log::info;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+const DEFAULT_WAIT_SECS: u64 = 60;
+const MAX_WAIT_SECS: u64 = 300;
+/// Largest JSON body accepted.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const JOB_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum JobState {
+    Pending,
+    Done { reply: String, files: Vec<ChatFile> },
+    Error { error: String },
+}
+
+struct Job {
+    state: JobState,
+    created: Instant,
+}
+
+static JOBS: LazyLock<Mutex<HashMap<String, Job>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Constant-time check of `Authorization: Bearer <access token>`.
+pub(super) fn authorized(request_str: &str, access_token: &str) -> bool {
+    use subtle::ConstantTimeEq;
+    let Some(token) = header(request_str, "authorization").and_then(|v| {
+        v.strip_prefix("Bearer ")
+            .or_else(|| v.strip_prefix("bearer "))
+    }) else {
+        return false;
+    };
+    !access_token.is_empty() && bool::from(token.trim().as_bytes().ct_eq(access_token.as_bytes()))
+}
+
+/// Routes everything under /api/.
+pub(super) async fn handle_api(
+    stream: Box<dyn ChatStream>,
+    request: Vec<u8>,
+    peer: std::net::SocketAddr,
+    app_handle: &tauri::AppHandle,
+    path: String,
+) -> EngineResult<()> {
+    let config = load_config(app_handle).unwrap_or_default();
+    let request_str = String::from_utf8_lossy(&request).into_owned();
+    if !config.api_enabled {
+        return respond_json(stream, "404 Not Found", json!({"error": "API disabled"})).await;
+    }
+    if !authorized(&request_str, &config.access_token) {
+        return respond_json(
+            stream,
+            "401 Unauthorized",
+            json!({"error": "missing or invalid bearer token"}),
+        )
+        .await;
+    }
+
+    let route = path.split('?').next().unwrap_or("");
+    if request_str.starts_with("POST") && route == "/api/message" {
+        post_message(stream, &request, &request_str, peer, app_handle, &path).await
+    } else if request_str.starts_with("GET") && route.starts_with("/api/jobs/") {
+        let id = &route["/api/jobs/".len()..];
+        match job_state(id) {
+            Some(state) => respond_json(stream, "200 OK", job_json(id, &state)).await,
+            None => respond_json(stream, "404 Not Found", json!({"error": "unknown job"})).await,
+        }
+    } else {
+        respond_json(stream, "404 Not Found", json!({"error": "not found"})).await
+    }
+}
+
+async fn post_message(
+    mut stream: Box<dyn ChatStream>,
+    request: &[u8],
+    request_str: &str,
+    peer: std::net::SocketAddr,
+    app_handle: &tauri::AppHandle,
+    path: &str,
+) -> EngineResult<()> {
+    let len = header(request_str, "content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    if len > MAX_BODY_BYTES {
+        return respond_json(
+            stream,
+            "413 Payload Too Large",
+            json!({"error": "body too large"}),
+        )
+        .await;
+    }
+    let Some(body) = read_body(&mut stream, request, len).await? else {
+        return respond_json(
+            stream,
+            "400 Bad Request",
+            json!({"error": "body incomplete"}),
+        )
+        .await;
+    };
+    let parsed: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            return respond_json(
+                stream,
+                "400 Bad Request",
+                json!({ "error": format!("invalid JSON: {}", e) }),
+            )
+            .await
+        }
+    };
+
+    let text = parsed["text"].as_str().unwrap_or("").trim().to_string();
+    if text.is_empty() {
+        return respond_json(
+            stream,
+            "400 Bad Request",
+            json!({"error": "text is required"}),
+        )
+        .await;
+    }
+    let user = parsed["user"]
+        .as_str()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .unwrap_or("api")
+        .to_string();
+    let wait = parsed["wait"]
+        .as_u64()
+        .or_else(|| query_param(path, "wait").and_then(|w| w.parse().ok()))
+        .unwrap_or(DEFAULT_WAIT_SECS)
+        .min(MAX_WAIT_SECS);
+
+    // Same access policy as the page
+    let mut config = load_config(app_handle).unwrap_or_default();
+    if !is_user_allowed(&config, &user) {
+        let denial = channels::check_access(
+            &config.dm_policy,
+            &user,
+            &user,
+            &user,
+            &config.allowed_users,
+            &mut config.pending_users,
+        )
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_else(|| "not approved yet".into());
+        let _ = save_config(app_handle, &config);
+        let _ = app_handle.emit(
+            "webchat-status",
+            json!({
+                "kind": "pairing_request",
+                "username": &user,
+                "peer": peer.to_string(),
+            }),
+        );
+        channels::notify_pairing(app_handle, "Webchat", &user);
+        return respond_json(stream, "403 Forbidden", json!({ "error": denial })).await;
+    }
+
+    let job_id = uuid::Uuid::new_v4().simple().to_string();
+    insert_job(&job_id);
+    info!(
+        "[webchat] API message from {} ({}) → job {}",
+        user, peer, job_id
+    );
+
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+    let app = app_handle.clone();
+    let id = job_id.clone();
+    tokio::spawn(async move {
+        let reply = run_turn(&app, &config, &user, &peer, &text).await;
+        let state = if reply.kind == "assistant" {
+            JobState::Done {
+                reply: reply.text,
+                files: reply.files,
+            }
+        } else {
+            JobState::Error { error: reply.text }
+        };
+        finish_job(&id, state);
+        let _ = done_tx.send(());
+    });
+
+    let _ = tokio::time::timeout(Duration::from_secs(wait), done_rx).await;
+    let state = job_state(&job_id).unwrap_or(JobState::Pending);
+    let status = match state {
+        JobState::Pending => "202 Accepted",
+        _ => "200 OK",
+    };
+    respond_json(stream, status, job_json(&job_id, &state)).await
+}
+
+fn job_json(id: &str, state: &JobState) -> serde_json::Value {
+    let mut body = serde_json::to_value(state).unwrap_or_else(|_| json!({}));
+    body["job_id"] = json!(id);
+    if *state == JobState::Pending {
+        body["poll"] = json!(format!("/api/jobs/{}", id));
+    }
+    body
+}
+
+fn insert_job(id: &str) {
+    let mut jobs = JOBS.lock();
+    jobs.retain(|_, job| job.created.elapsed() < JOB_TTL);
+    jobs.insert(
+        id.to_string(),
+        Job {
+            state: JobState::Pending,
+            created: Instant::now(),
+        },
+    );
+}
+
+fn finish_job(id: &str, state: JobState) {
+    if let Some(job) = JOBS.lock().get_mut(id) {
+        job.state = state;
+    }
+}
+
+fn job_state(id: &str) -> Option<JobState> {
+    JOBS.lock().get(id).map(|job| job.state.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_token_must_match() {
+        let req = "POST /api/message HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n{}";
+        assert!(authorized(req, "s3cret"));
+        assert!(!authorized(req, "other"));
+        assert!(!authorized("POST /api/message HTTP/1.1\r\n\r\n", "s3cret"));
+        assert!(!authorized(req, ""));
+    }
+
+    #[test]
+    fn jobs_report_their_state() {
+        insert_job("job-1");
+        assert_eq!(
+            job_json("job-1", &job_state("job-1").unwrap()),
+            json!({ "status": "pending", "job_id": "job-1", "poll": "/api/jobs/job-1" })
+        );
+        finish_job(
+            "job-1",
+            JobState::Done {
+                reply: "hi".into(),
+                files: vec![],
+            },
+        );
+        assert_eq!(
+            job_json("job-1", &job_state("job-1").unwrap()),
+            json!({ "status": "done", "job_id": "job-1", "reply": "hi", "files": [] })
+        );
+        assert!(job_state("missing").is_none());
+    }
+}
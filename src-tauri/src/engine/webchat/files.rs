@@ -0,0 +1,161 @@
+// Paw Agent Engine — Web Chat Files
+//
+// Uploads: POST /upload?name=<file name> with the raw file as the body
+// (session cookie required). The file is checked against the size limit
+// and the type allowlist, run through the shared channel ingestion
+// (`channels::attachments`), and its note is held until the user's next
+// message, which carries it to the agent.
+//
+// Artifacts: files the agent attaches to a reply (`[attach: path]`) are
+// copied to `{data_root}/webchat/files/` under a random id and served back
+// from GET /files/<id>, so images show inline in the chat and stay there
+// when the history is reloaded.
+
+use crate::atoms::error::EngineResult;
+use crate::engine::channels::attachments::{self, OutboundFile};
+use crate::engine::paths;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// File types the page may upload — what the ingestion pipeline can read.
+const ALLOWED_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "csv", "tsv", "json", "yaml", "yml", "toml", "xml", "html", "htm",
+    "log", "pdf", "docx", "png", "jpg", "jpeg", "gif", "webp", "mp3", "wav", "m4a", "aac", "ogg",
+    "opus", "flac", "webm", "py", "rs", "js", "ts", "go", "java", "c", "cpp", "h", "sh", "sql",
+];
+
+/// Uploads (file name, ingest note) waiting for each user's next message.
+type PendingUploads = HashMap<String, Vec<(String, String)>>;
+
+static PENDING_UPLOADS: LazyLock<Mutex<PendingUploads>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A file shown in the chat (an agent artifact).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct ChatFile {
+    pub name: String,
+    pub url: String,
+    pub mime_type: String,
+}
+
+/// Reject uploads that are disabled, too large, empty or of a type ingestion can't read.
+pub(crate) fn check_upload(name: &str, len: usize, max_bytes: u64) -> Result<(), String> {
+    if max_bytes == 0 {
+        return Err("File uploads are disabled".into());
+    }
+    if len as u64 > max_bytes {
+        return Err(format!(
+            "File is larger than {} MB",
+            max_bytes / 1024 / 1024
+        ));
+    }
+    if len == 0 {
+        return Err("File is empty".into());
+    }
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    if !ALLOWED_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!(
+            "Files of type .{} can't be uploaded",
+            if ext.is_empty() { "?" } else { &ext }
+        ));
+    }
+    Ok(())
+}
+
+/// Ingest an uploaded file and queue its note for the user's next message.
+pub(crate) async fn ingest_upload(
+    app_handle: &tauri::AppHandle,
+    username: &str,
+    agent_id: &str,
+    name: &str,
+    bytes: &[u8],
+) -> EngineResult<()> {
+    let note = attachments::ingest_bytes(app_handle, agent_id, name, bytes).await?;
+    let mut pending = PENDING_UPLOADS.lock();
+    let uploads = pending.entry(username.to_string()).or_default();
+    uploads.push((name.to_string(), note));
+    if uploads.len() > attachments::MAX_FILES {
+        uploads.remove(0);
+    }
+    Ok(())
+}
+
+/// Whether the user has uploads waiting for their next message.
+pub(crate) fn has_pending_uploads(username: &str) -> bool {
+    PENDING_UPLOADS.lock().contains_key(username)
+}
+
+/// Uploads (file name, ingest note) since the user's last message.
+pub(crate) fn take_pending_uploads(username: &str) -> Vec<(String, String)> {
+    PENDING_UPLOADS.lock().remove(username).unwrap_or_default()
+}
+
+fn artifacts_dir() -> PathBuf {
+    paths::paw_data_dir().join("webchat").join("files")
+}
+
+/// Store the agent's attached files and return how the page should show them.
+pub(crate) fn store_artifacts(files: Vec<OutboundFile>) -> EngineResult<Vec<ChatFile>> {
+    if files.is_empty() {
+        return Ok(vec![]);
+    }
+    let dir = artifacts_dir();
+    std::fs::create_dir_all(&dir)?;
+    let mut shown = Vec::with_capacity(files.len());
+    for file in files {
+        let id = format!(
+            "{}-{}",
+            uuid::Uuid::new_v4().simple(),
+            attachments::safe_file_name(&file.name)
+        );
+        std::fs::write(dir.join(&id), &file.bytes)?;
+        shown.push(ChatFile {
+            url: format!("/files/{}", urlencoding::encode(&id)),
+            name: file.name,
+            mime_type: file.mime_type,
+        });
+    }
+    Ok(shown)
+}
+
+/// Read a stored artifact for GET /files/<id>: (bytes, mime type).
+pub(crate) fn load_artifact(id: &str) -> Option<(Vec<u8>, &'static str)> {
+    let id = urlencoding::decode(id).ok()?;
+    // Ids are a single path segment written by `store_artifacts`
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return None;
+    }
+    let path = artifacts_dir().join(id.as_ref());
+    let bytes = std::fs::read(&path).ok()?;
+    Some((bytes, crate::engine::ingest::mime_type(&path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_limits() {
+        let mb = 1024 * 1024;
+        assert!(check_upload("notes.md", 10, mb).is_ok());
+        assert!(check_upload("Photo.JPG", 10, mb).is_ok());
+        assert!(check_upload("big.pdf", 2 * mb as usize, mb).is_err());
+        assert!(check_upload("setup.exe", 10, mb).is_err());
+        assert!(check_upload("no_extension", 10, mb).is_err());
+        assert!(check_upload("empty.txt", 0, mb).is_err());
+        assert!(check_upload("notes.md", 10, 0).is_err());
+    }
+
+    #[test]
+    fn artifact_ids_stay_in_the_directory() {
+        assert!(load_artifact("../engine.db").is_none());
+        assert!(load_artifact("..%2Fengine.db").is_none());
+        assert!(load_artifact("").is_none());
+    }
+}
@@ -0,0 +1,101 @@
+// Paw Agent Engine — Web Chat History
+//
+// What each user saw in the chat (their messages, replies, errors and
+// attached artifacts), stored per user in the engine config table so a
+// page reload — or an app restart — shows the conversation again.
+// GET /history returns it for the session's user.
+
+use super::files::ChatFile;
+use crate::atoms::error::EngineResult;
+use crate::engine::channels;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Entries kept per user; older ones are dropped.
+const MAX_ENTRIES: usize = 200;
+
+/// Serializes load-modify-save when one user has several tabs open.
+static HISTORY_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct HistoryEntry {
+    /// "user" | "assistant" | "error"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: String,
+    /// Artifacts attached to a reply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<ChatFile>,
+    /// Names of the files the user uploaded with a message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uploads: Vec<String>,
+    pub at: String,
+}
+
+impl HistoryEntry {
+    pub(crate) fn new(kind: &str, text: &str, files: Vec<ChatFile>) -> Self {
+        HistoryEntry {
+            kind: kind.into(),
+            text: text.into(),
+            files,
+            uploads: vec![],
+            at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+fn history_key(username: &str) -> String {
+    format!("webchat_history:{}", username)
+}
+
+pub(crate) fn load(app_handle: &tauri::AppHandle, username: &str) -> Vec<HistoryEntry> {
+    channels::load_channel_config::<History>(app_handle, &history_key(username))
+        .map(|h| h.entries)
+        .unwrap_or_default()
+}
+
+pub(crate) fn append(
+    app_handle: &tauri::AppHandle,
+    username: &str,
+    new_entries: Vec<HistoryEntry>,
+) -> EngineResult<()> {
+    let _guard = HISTORY_LOCK.lock();
+    let mut entries = load(app_handle, username);
+    push_capped(&mut entries, new_entries);
+    channels::save_channel_config(app_handle, &history_key(username), &History { entries })
+}
+
+fn push_capped(entries: &mut Vec<HistoryEntry>, new_entries: Vec<HistoryEntry>) {
+    entries.extend(new_entries);
+    if entries.len() > MAX_ENTRIES {
+        entries.drain(..entries.len() - MAX_ENTRIES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_entries() {
+        let mut entries = vec![];
+        push_capped(
+            &mut entries,
+            (0..MAX_ENTRIES + 5)
+                .map(|i| HistoryEntry::new("user", &i.to_string(), vec![]))
+                .collect(),
+        );
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].text, "5");
+
+        let json = serde_json::to_value(&entries[0]).unwrap();
+        assert_eq!(json["type"], "user");
+        assert!(json.get("files").is_none());
+    }
+}
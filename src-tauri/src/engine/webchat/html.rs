@@ -1,6 +1,9 @@
 // Paw Agent Engine — Web Chat HTML Template
 //
 // Self-contained HTML/CSS/JS chat page served by the webchat bridge.
+// Replies are rendered from markdown on the client (HTML is escaped first;
+// links and images only for http(s) and /files/ URLs). The page restores
+// the conversation from GET /history when its session cookie is still valid.
 
 /// Build the complete HTML page for the chat interface.
 pub fn build_chat_html(title: &str) -> String {
@@ -25,16 +28,29 @@ body{{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;backgro
 .messages{{flex:1;overflow-y:auto;padding:20px;display:flex;flex-direction:column;gap:10px}}
 .msg{{max-width:80%;padding:10px 14px;border-radius:12px;font-size:14px;line-height:1.5;word-wrap:break-word;white-space:pre-wrap}}
 .msg.user{{align-self:flex-end;background:#2a2d2e;border:1px solid #ff00ff33}}
-.msg.assistant{{align-self:flex-start;background:#252526;border:1px solid #3c3c3c}}
+.msg.assistant{{align-self:flex-start;background:#252526;border:1px solid #3c3c3c;white-space:normal}}
 .msg.system{{align-self:center;color:#888;font-size:12px;font-style:italic}}
 .msg.error{{align-self:center;color:#f44;font-size:13px}}
+.msg p+p,.msg ul,.msg ol,.msg pre,.msg h3,.msg h4,.msg h5,.msg h6{{margin-top:8px}}
+.msg ul,.msg ol{{padding-left:20px}}
+.msg code{{background:#1e1e1e;padding:1px 4px;border-radius:4px;font-family:Menlo,Consolas,monospace;font-size:13px}}
+.msg pre{{background:#1e1e1e;padding:10px;border-radius:6px;overflow-x:auto}}
+.msg pre code{{padding:0}}
+.msg a{{color:#ff66ff}}
+.msg hr{{border:none;border-top:1px solid #3c3c3c;margin:8px 0}}
+.msg img{{display:block;max-width:100%;max-height:360px;border-radius:8px;margin-top:8px}}
+.msg .file{{display:inline-block;margin-top:8px;margin-right:6px}}
+.msg .uploads{{font-size:12px;color:#aaa;margin-bottom:4px}}
 .typing{{align-self:flex-start;color:#888;font-size:13px;padding:4px 14px}}
 .typing::after{{content:'...';animation:dots 1.2s infinite}}
 @keyframes dots{{0%,20%{{content:'.'}}40%{{content:'..'}}60%,100%{{content:'...'}}}}
+.pending{{padding:0 20px 8px;background:#252526;display:flex;gap:6px;flex-wrap:wrap}}
+.pending span{{font-size:12px;padding:3px 8px;border-radius:10px;background:#313131;border:1px solid #3c3c3c}}
 .input-bar{{padding:16px 20px;background:#252526;border-top:1px solid #3c3c3c;display:flex;gap:8px}}
 .input-bar textarea{{flex:1;padding:10px 14px;border:1px solid #3c3c3c;border-radius:8px;background:#313131;color:#cccccc;font-size:14px;font-family:inherit;resize:none;outline:none;max-height:120px}}
 .input-bar textarea:focus{{border-color:#ff00ff}}
 .input-bar button{{padding:10px 20px;background:#ff00ff;color:#fff;border:none;border-radius:8px;font-weight:600;cursor:pointer;white-space:nowrap}}
+.input-bar button.attach{{padding:10px 12px;background:#313131;border:1px solid #3c3c3c}}
 .input-bar button:disabled{{opacity:.4;cursor:not-allowed}}
 </style>
 </head>
@@ -49,15 +65,19 @@ body{{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;backgro
   <button onclick="connect()">Join</button>
 </div>
 <div class="messages" id="messages"></div>
+<div class="pending" id="pending" style="display:none"></div>
 <div class="input-bar" id="inputBar" style="display:none">
+  <button class="attach" id="attachBtn" title="Attach a file" onclick="document.getElementById('fileInput').click()">&#128206;</button>
+  <input type="file" id="fileInput" multiple hidden />
   <textarea id="chatInput" placeholder="Type a message..." rows="1"></textarea>
   <button id="sendBtn" onclick="send()">Send</button>
 </div>
 <script>
-let ws,name="";
+let ws,name="",uploads=[];
 const msgs=document.getElementById("messages");
 const inp=document.getElementById("chatInput");
 const dot=document.getElementById("dot");
+const pending=document.getElementById("pending");
 
 async function connect(){{
   name=document.getElementById("nameInput").value.trim();
@@ -72,6 +92,24 @@ async function connect(){{
     }});
     if(!res.ok){{addMsg("error","Invalid token.");return}}
   }}catch(e){{addMsg("error","Auth failed: "+e.message);return}}
+  if(!await restore())openChat();
+}}
+
+// Resume with the session cookie: replay the saved conversation, then connect
+async function restore(){{
+  try{{
+    const res=await fetch("/history",{{credentials:"same-origin"}});
+    if(!res.ok)return false;
+    const d=await res.json();
+    name=d.user;
+    msgs.innerHTML="";
+    for(const e of d.entries)addMsg(e.type,e.text,e.files,e.uploads);
+    openChat();
+    return true;
+  }}catch(e){{return false}}
+}}
+
+function openChat(){{
   document.getElementById("nameBar").style.display="none";
   document.getElementById("inputBar").style.display="flex";
   const proto=location.protocol==="https:"?"wss:":"ws:";
@@ -83,24 +121,131 @@ async function connect(){{
       const d=JSON.parse(e.data);
       removeTyping();
       if(d.type==="typing"){{addTyping();return}}
-      addMsg(d.type||"assistant",d.text||"");
+      addMsg(d.type||"assistant",d.text||"",d.files);
     }}catch(err){{addMsg("assistant",e.data)}}
   }};
 }}
 
 function send(){{
   const t=inp.value.trim();
-  if(!t||!ws||ws.readyState!==1)return;
-  addMsg("user",t);
+  if((!t&&!uploads.length)||!ws||ws.readyState!==1)return;
+  addMsg("user",t,[],uploads);
   ws.send(JSON.stringify({{type:"message",text:t}}));
   inp.value="";
   inp.style.height="auto";
+  uploads=[];
+  showPending();
+}}
+
+async function upload(file){{
+  try{{
+    const res=await fetch("/upload?name="+encodeURIComponent(file.name),{{
+      method:"POST",
+      body:file,
+      credentials:"same-origin"
+    }});
+    const d=await res.json().catch(()=>({{}}));
+    if(!res.ok){{addMsg("error",file.name+": "+(d.error||"upload failed"));return}}
+    uploads.push(d.name||file.name);
+    showPending();
+  }}catch(e){{addMsg("error",file.name+": "+e.message)}}
 }}
 
-function addMsg(type,text){{
+function showPending(){{
+  pending.innerHTML="";
+  for(const n of uploads){{
+    const s=document.createElement("span");
+    s.textContent="\u{{1F4CE}} "+n;
+    pending.appendChild(s);
+  }}
+  pending.style.display=uploads.length?"flex":"none";
+}}
+
+function esc(s){{
+  return s.replace(/[&<>"']/g,c=>({{"&":"&amp;","<":"&lt;",">":"&gt;",'"':"&quot;","'":"&#39;"}}[c]));
+}}
+
+// Runs on escaped text, so captured URLs are already attribute-safe
+function fmt(s){{
+  const ok=u=>/^(https?:\/\/|\/files\/)/i.test(u);
+  return s
+    .replace(/!\[([^\]]*)\]\(([^)\s]+)\)/g,(m,alt,u)=>ok(u)?`<img src="${{u}}" alt="${{alt}}">`:m)
+    .replace(/\[([^\]]+)\]\(([^)\s]+)\)/g,(m,t,u)=>ok(u)?`<a href="${{u}}" target="_blank" rel="noopener">${{t}}</a>`:m)
+    .replace(/\*\*([^*]+)\*\*/g,"<strong>$1</strong>")
+    .replace(/(^|[^*\w])\*([^*\s][^*]*)\*/g,"$1<em>$2</em>");
+}}
+
+function inline(s){{
+  return s.split(/(`[^`]+`)/).map((p,i)=>i%2?"<code>"+esc(p.slice(1,-1))+"</code>":fmt(esc(p))).join("");
+}}
+
+function renderMarkdown(text){{
+  const lines=text.split("\n"),out=[];
+  const list=/^\s*([-*]|\d+\.)\s+/,heading=/^(#{{1,4}})\s+(.*)/;
+  let i=0;
+  while(i<lines.length){{
+    const line=lines[i];
+    if(line.startsWith("```")){{
+      const code=[];
+      for(i++;i<lines.length&&!lines[i].startsWith("```");i++)code.push(lines[i]);
+      i++;
+      out.push("<pre><code>"+esc(code.join("\n"))+"</code></pre>");
+    }}else if(heading.test(line)){{
+      const h=line.match(heading),n=h[1].length+2;
+      out.push(`<h${{n}}>${{inline(h[2])}}</h${{n}}>`);
+      i++;
+    }}else if(/^\s*(---+|\*\*\*+)\s*$/.test(line)){{
+      out.push("<hr>");
+      i++;
+    }}else if(list.test(line)){{
+      const tag=/^\s*\d+\./.test(line)?"ol":"ul",items=[];
+      for(;i<lines.length&&list.test(lines[i]);i++)items.push("<li>"+inline(lines[i].replace(list,""))+"</li>");
+      out.push(`<${{tag}}>${{items.join("")}}</${{tag}}>`);
+    }}else if(!line.trim()){{
+      i++;
+    }}else{{
+      const para=[];
+      for(;i<lines.length&&lines[i].trim()&&!lines[i].startsWith("```")&&!heading.test(lines[i])&&!list.test(lines[i]);i++)para.push(inline(lines[i]));
+      out.push("<p>"+para.join("<br>")+"</p>");
+    }}
+  }}
+  return out.join("");
+}}
+
+function addMsg(type,text,files,sent){{
+  if(type==="message")type="assistant";
   const d=document.createElement("div");
   d.className="msg "+type;
-  d.textContent=text;
+  if(sent&&sent.length){{
+    const u=document.createElement("div");
+    u.className="uploads";
+    u.textContent="\u{{1F4CE}} "+sent.join(", ");
+    d.appendChild(u);
+  }}
+  if(type==="assistant"){{
+    const body=document.createElement("div");
+    body.innerHTML=renderMarkdown(text);
+    d.appendChild(body);
+  }}else{{
+    d.appendChild(document.createTextNode(text));
+  }}
+  for(const f of files||[]){{
+    const a=document.createElement("a");
+    a.href=f.url;
+    a.target="_blank";
+    a.rel="noopener";
+    if(f.mime_type&&f.mime_type.startsWith("image/")){{
+      const img=document.createElement("img");
+      img.src=f.url;
+      img.alt=f.name;
+      a.appendChild(img);
+    }}else{{
+      a.className="file";
+      a.download=f.name;
+      a.textContent="\u{{1F4CE}} "+f.name;
+    }}
+    d.appendChild(a);
+  }}
   msgs.appendChild(d);
   msgs.scrollTop=msgs.scrollHeight;
 }}
@@ -127,9 +272,21 @@ inp.addEventListener("input",()=>{{
   inp.style.height="auto";
   inp.style.height=Math.min(inp.scrollHeight,120)+"px";
 }});
+document.getElementById("fileInput").addEventListener("change",async(e)=>{{
+  for(const f of e.target.files)await upload(f);
+  e.target.value="";
+}});
 document.getElementById("tokenInput").addEventListener("keydown",(e)=>{{
   if(e.key==="Enter"){{e.preventDefault();connect()}}
 }});
+// Share links carry the token in the fragment (#t=…): fill it in, then drop it from the address bar
+const shared=new URLSearchParams(location.hash.slice(1)).get("t");
+if(shared){{
+  document.getElementById("tokenInput").value=shared;
+  history.replaceState(null,"",location.pathname+location.search);
+  document.getElementById("nameInput").focus();
+}}
+restore();
 </script>
 </body>
 </html>"##,
@@ -8,7 +8,14 @@
 //   - GET /         → serves a self-contained HTML chat page (no secrets embedded)
 //   - POST /auth    → validates access token, returns a session cookie
 //   - GET /ws       → upgrades to WebSocket (session cookie required)
+//   - POST /upload  → file upload, ingested for the next message (see `files`)
+//   - GET /history  → the user's saved conversation (see `history`)
+//   - GET /files/…  → files the agent attached to its replies
+//   - POST /api/message, GET /api/jobs/… → REST API with bearer token (see `api`)
+//   - Share links with a QR code, token rotation and expiring guest tokens (see `share`)
+//   - Optional internet access: router port mapping or a tunnel (see `remote`)
 //   - Optional TLS via rustls for HTTPS/WSS when cert+key paths are set
+//   - One-click self-signed or Let's Encrypt certificates (see `tls`)
 //
 // Security:
 //   - Access token required (auto-generated or user-set)
@@ -19,12 +26,26 @@ use crate::engine::util::safe_truncate;
 //   - Binds to 127.0.0.1 (localhost) by default; set bind_address to "0.0.0.0" for LAN
 //   - Optional TLS for HTTPS/WSS (recommended when binding to 0.0.0.0)
 
+mod api;
+mod files;
+mod history;
 mod html;
+mod qr;
+mod remote;
 mod server;
 mod session;
+mod share;
+mod tls;
+mod upnp;
+
+pub use remote::{set_mode as set_remote_mode, status as remote_status};
+pub use remote::{RemoteAccessStatus, RemoteMode};
+pub use share::{create_guest_token, revoke_guest_token, share_info, WebChatShareInfo};
+pub use tls::{cert_info as tls_cert_info, generate_self_signed, issue_acme};
+pub use tls::{AcmeSettings, TlsCertInfo};
 
 use crate::atoms::error::{EngineError, EngineResult};
-use crate::engine::channels::{self, ChannelStatus, PendingUser};
+use crate::engine::channels::{self, attachments, ChannelStatus, PendingUser};
 use futures::stream::StreamExt;
 use futures::SinkExt;
 use log::{debug, error, info, warn};
@@ -64,14 +85,58 @@ pub struct WebChatConfig {
     /// Phase C: allow dangerous/side-effect tools for messages from this channel
     #[serde(default)]
     pub allow_dangerous_tools: bool,
+    /// Largest file the page may upload, in MB (0 disables uploads)
+    #[serde(default = "default_max_upload_mb")]
+    pub max_upload_mb: u64,
+    /// Accept POST /api/message with the access token as a bearer token
+    #[serde(default = "default_true")]
+    pub api_enabled: bool,
+    /// Extra, expiring tokens handed out with share links (see `share`)
+    #[serde(default)]
+    pub guest_tokens: Vec<GuestToken>,
+    /// Reach the page from the internet while the bridge runs (see `remote`)
+    #[serde(default)]
+    pub remote_access: RemoteMode,
+    /// Let's Encrypt issuance for a public domain (see `tls`)
+    #[serde(default)]
+    pub acme: AcmeSettings,
+}
+
+/// A token for one guest: signs in to the page only (not the REST API),
+/// optionally under a fixed name, until `expires_at`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuestToken {
+    pub id: String,
+    pub token: String,
+    pub label: String,
+    /// Name the guest chats as; they pick one when unset
+    #[serde(default)]
+    pub name: Option<String>,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339
+    pub expires_at: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_upload_mb() -> u64 {
+    20
+}
+
+/// A cryptographically strong random token of `bytes` bytes, hex-encoded.
+pub(crate) fn generate_token(bytes: usize) -> String {
+    let mut token_bytes = vec![0u8; bytes];
+    getrandom::getrandom(&mut token_bytes).expect("OS CSPRNG failed");
+    token_bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 impl Default for WebChatConfig {
     fn default() -> Self {
-        // Generate a cryptographically strong random 32-byte token (64 hex chars)
-        let mut token_bytes = [0u8; 32];
-        getrandom::getrandom(&mut token_bytes).expect("OS CSPRNG failed");
-        let token: String = token_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        // 32 random bytes (64 hex chars)
+        let token = generate_token(32);
         WebChatConfig {
             enabled: false,
             bind_address: "127.0.0.1".into(),
@@ -85,6 +150,11 @@ impl Default for WebChatConfig {
             tls_cert_path: None,
             tls_key_path: None,
             allow_dangerous_tools: false,
+            max_upload_mb: default_max_upload_mb(),
+            api_enabled: true,
+            guest_tokens: vec![],
+            remote_access: RemoteMode::Off,
+            acme: AcmeSettings::default(),
         }
     }
 }
@@ -147,11 +217,25 @@ pub fn start_bridge(app_handle: tauri::AppHandle) -> EngineResult<()> {
         config.bind_address, config.port
     );
 
+    if config.remote_access != RemoteMode::Off {
+        let app = app_handle.clone();
+        let remote_config = config.clone();
+        tauri::async_runtime::spawn(async move {
+            // Failures are in the remote access status; the chat still runs locally
+            let _ = remote::start(&app, &remote_config).await;
+        });
+    }
+
+    if !config.acme.domain.is_empty() {
+        tls::spawn_renewal(app_handle.clone());
+    }
+
     tauri::async_runtime::spawn(async move {
         if let Err(e) = server::run_server(app_handle, config).await {
             error!("[webchat] Server crashed: {}", e);
         }
         BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+        remote::stop().await;
         info!("[webchat] Server stopped");
     });
 
@@ -162,6 +246,7 @@ pub fn stop_bridge() {
     let stop = get_stop_signal();
     stop.store(true, Ordering::Relaxed);
     BRIDGE_RUNNING.store(false, Ordering::Relaxed);
+    tauri::async_runtime::spawn(remote::stop());
     info!("[webchat] Stop signal sent");
 }
 
@@ -176,9 +261,24 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> ChannelStatus {
         allowed_users: config.allowed_users,
         pending_users: config.pending_users,
         dm_policy: config.dm_policy,
+        connectivity: channels::connectivity("webchat"),
     }
 }
 
+/// Whether `username` may chat now — uploads are refused until they may.
+pub(crate) fn is_user_allowed(config: &WebChatConfig, username: &str) -> bool {
+    config.dm_policy == "open" || config.allowed_users.iter().any(|u| u == username)
+}
+
+/// Agent whose workspace takes uploads and serves attachments.
+pub(crate) fn files_agent_id(config: &WebChatConfig) -> String {
+    config
+        .agent_id
+        .clone()
+        .filter(|a| !a.is_empty())
+        .unwrap_or_else(|| "default".into())
+}
+
 // ── WebSocket Chat Handler ─────────────────────────────────────────────
 
 async fn handle_websocket<S: AsyncRead + AsyncWrite + Unpin>(
@@ -221,6 +321,7 @@ async fn handle_websocket<S: AsyncRead + AsyncWrite + Unpin>(
                 "peer": peer.to_string(),
             }),
         );
+        channels::notify_pairing(&app_handle, "Webchat", &username);
 
         let msg = json!({ "type": "system", "text": denial_str });
         let _ = ws_sender
@@ -238,13 +339,6 @@ async fn handle_websocket<S: AsyncRead + AsyncWrite + Unpin>(
         .send(WsMessage::Text(welcome.to_string().into()))
         .await;
 
-    let agent_id = config.agent_id.clone().unwrap_or_default();
-    let channel_context = format!(
-        "User '{}' is chatting via the Paw Web Chat interface from {}. \
-         Keep responses concise but helpful. You can use markdown formatting.",
-        username, peer
-    );
-
     // Message loop
     while let Some(msg) = ws_receiver.next().await {
         let msg = match msg {
@@ -263,39 +357,22 @@ async fn handle_websocket<S: AsyncRead + AsyncWrite + Unpin>(
                     serde_json::from_str(&text).unwrap_or(json!({"text": text}));
                 let user_text = incoming["text"].as_str().unwrap_or("").trim().to_string();
 
-                if user_text.is_empty() {
+                if user_text.is_empty() && !files::has_pending_uploads(&username) {
                     continue;
                 }
 
-                MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
-                debug!(
-                    "[webchat] {} says: {}",
-                    username,
-                    safe_truncate(&user_text, 80)
-                );
-
                 // Send typing indicator
                 let typing = json!({ "type": "typing" });
                 let _ = ws_sender
                     .send(WsMessage::Text(typing.to_string().into()))
                     .await;
 
-                // Route through agent
-                let reply = channels::run_channel_agent(
-                    &app_handle,
-                    "webchat",
-                    &channel_context,
-                    &user_text,
-                    &username,
-                    &agent_id,
-                    config.allow_dangerous_tools,
-                )
-                .await;
-
-                let response = match reply {
-                    Ok(text) => json!({ "type": "message", "text": text }),
-                    Err(e) => json!({ "type": "error", "text": format!("Error: {}", e) }),
-                };
+                let reply = run_turn(&app_handle, &config, &username, &peer, &user_text).await;
+                let response = json!({
+                    "type": if reply.kind == "assistant" { "message" } else { "error" },
+                    "text": reply.text,
+                    "files": reply.files,
+                });
 
                 if ws_sender
                     .send(WsMessage::Text(response.to_string().into()))
@@ -318,3 +395,67 @@ async fn handle_websocket<S: AsyncRead + AsyncWrite + Unpin>(
 
     Ok(())
 }
+
+// ── Chat Turn ──────────────────────────────────────────────────────────
+
+/// One message from `username` to the agent, shared by the page and the
+/// REST API: pending uploads ride along, attached files are stored as
+/// artifacts, and both sides go into the user's history. Returns the reply
+/// entry ("assistant" or "error").
+pub(crate) async fn run_turn(
+    app_handle: &tauri::AppHandle,
+    config: &WebChatConfig,
+    username: &str,
+    peer: &std::net::SocketAddr,
+    user_text: &str,
+) -> history::HistoryEntry {
+    let uploads = files::take_pending_uploads(username);
+    let upload_names: Vec<String> = uploads.iter().map(|(name, _)| name.clone()).collect();
+    let notes: Vec<String> = uploads.into_iter().map(|(_, note)| note).collect();
+
+    MESSAGE_COUNT.fetch_add(1, Ordering::Relaxed);
+    debug!(
+        "[webchat] {} says: {}",
+        username,
+        safe_truncate(user_text, 80)
+    );
+
+    let agent_id = config.agent_id.clone().unwrap_or_default();
+    let channel_context = format!(
+        "User '{}' is chatting via the Paw Web Chat interface from {}. \
+         Keep responses concise but helpful. You can use markdown formatting. {}",
+        username,
+        peer,
+        attachments::ATTACH_HINT
+    );
+
+    // Route through agent
+    let reply = channels::run_channel_agent(
+        app_handle,
+        "webchat",
+        &channel_context,
+        &attachments::with_file_notes(user_text, &notes),
+        username,
+        &agent_id,
+        config.allow_dangerous_tools,
+    )
+    .await;
+
+    let mut user_entry = history::HistoryEntry::new("user", user_text, vec![]);
+    user_entry.uploads = upload_names;
+    let reply_entry = match reply {
+        Ok(text) => {
+            let (text, outbound) = attachments::take_attachments(&text, &files_agent_id(config));
+            let shown = files::store_artifacts(outbound).unwrap_or_else(|e| {
+                warn!("[webchat] Could not store attachments: {}", e);
+                vec![]
+            });
+            history::HistoryEntry::new("assistant", &text, shown)
+        }
+        Err(e) => history::HistoryEntry::new("error", &format!("Error: {}", e), vec![]),
+    };
+    if let Err(e) = history::append(app_handle, username, vec![user_entry, reply_entry.clone()]) {
+        warn!("[webchat] Could not save history for {}: {}", username, e);
+    }
+    reply_entry
+}
@@ -0,0 +1,528 @@
+// Paw Agent Engine — QR codes for webchat share links
+//
+// A small QR encoder — byte mode, error correction level M, versions 1–10
+// (up to 213 bytes, plenty for a share URL) — plus a 1-bit grayscale PNG
+// writer with stored (uncompressed) deflate blocks, so neither needs a
+// dependency. Layout, Reed–Solomon and masking follow ISO/IEC 18004.
+
+/// ECC codewords per block and number of blocks at level M, versions 1–10.
+const ECC_PER_BLOCK: [usize; 10] = [10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+const NUM_BLOCKS: [usize; 10] = [1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+/// Alignment pattern centres, versions 1–10.
+const ALIGNMENT: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+pub(crate) const MAX_VERSION: usize = 10;
+
+/// A QR symbol: `size`×`size` modules, `true` is dark.
+pub(crate) struct QrCode {
+    pub size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data` in the smallest version that fits, with the mask that
+    /// scores the lowest penalty. `None` if it doesn't fit in version 10.
+    pub(crate) fn encode(data: &[u8]) -> Option<QrCode> {
+        let version = (1..=MAX_VERSION)
+            .find(|&v| data_bits_needed(v, data.len()) <= data_codewords(v) * 8)?;
+        let mut best: Option<(u32, QrCode)> = None;
+        for mask in 0..8 {
+            let qr = Self::encode_with_mask(data, version, mask)?;
+            let score = qr.penalty();
+            if best.as_ref().is_none_or(|(s, _)| score < *s) {
+                best = Some((score, qr));
+            }
+        }
+        best.map(|(_, qr)| qr)
+    }
+
+    pub(crate) fn encode_with_mask(data: &[u8], version: usize, mask: u8) -> Option<QrCode> {
+        if !(1..=MAX_VERSION).contains(&version) || mask > 7 {
+            return None;
+        }
+        let capacity = data_codewords(version) * 8;
+        if data_bits_needed(version, data.len()) > capacity {
+            return None;
+        }
+
+        // Mode indicator, character count, data, terminator, padding
+        let mut bits = BitBuffer::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, char_count_bits(version));
+        for &b in data {
+            bits.push(b as u32, 8);
+        }
+        bits.push(0, (capacity - bits.len()).min(4));
+        bits.push(0, (8 - bits.len() % 8) % 8);
+        let mut codewords = bits.into_bytes();
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if codewords.len() * 8 >= capacity {
+                break;
+            }
+            codewords.push(pad);
+        }
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&add_ecc_and_interleave(&codewords, version));
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+        Some(qr)
+    }
+
+    pub(crate) fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+        let centres = ALIGNMENT[version - 1];
+        let last = centres.len().saturating_sub(1);
+        for (i, &cx) in centres.iter().enumerate() {
+            for (j, &cy) in centres.iter().enumerate() {
+                // Skip the three that would overlap finder patterns
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            (cx as i32 + dx) as usize,
+                            (cy as i32 + dy) as usize,
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+        // Reserve the format areas (real bits are drawn after masking)
+        self.draw_format_bits(0);
+        if version >= 7 {
+            let mut rem = version as u32;
+            for _ in 0..12 {
+                rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | rem;
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        // Level M is 0b00, so the data bits are just the mask
+        let data = mask as u32;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Zigzag the data through the non-function modules, two columns at a time.
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = (right - j) as usize;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.is_function[y * size + x] && i < data.len() * 8 {
+                        self.modules[y * size + x] = (data[i >> 3] >> (7 - (i & 7))) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let idx = y * self.size + x;
+                if invert && !self.is_function[idx] {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    /// Mask penalty (N1 runs, N2 blocks, N3 finder look-alikes, N4 balance).
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let mut score = 0;
+        let line = |i: usize, horizontal: bool| -> Vec<bool> {
+            (0..size)
+                .map(|j| {
+                    if horizontal {
+                        self.get(j, i)
+                    } else {
+                        self.get(i, j)
+                    }
+                })
+                .collect()
+        };
+        const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+        for i in 0..size {
+            for horizontal in [true, false] {
+                let cells = line(i, horizontal);
+                let mut run = 1;
+                for j in 1..=size {
+                    if j < size && cells[j] == cells[j - 1] {
+                        run += 1;
+                    } else {
+                        if run >= 5 {
+                            score += 3 + (run - 5) as u32;
+                        }
+                        run = 1;
+                    }
+                }
+                for j in 0..=size - 7 {
+                    if cells[j..j + 7] != FINDER {
+                        continue;
+                    }
+                    let light = |from: i32| {
+                        (from..from + 4).all(|k| k < 0 || k >= size as i32 || !cells[k as usize])
+                    };
+                    if light(j as i32 - 4) || light(j as i32 + 7) {
+                        score += 40;
+                    }
+                }
+            }
+        }
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.get(x, y);
+                if c == self.get(x + 1, y) && c == self.get(x, y + 1) && c == self.get(x + 1, y + 1)
+                {
+                    score += 3;
+                }
+            }
+        }
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let percent = dark * 100 / total;
+        score += (percent.abs_diff(50) / 5) as u32 * 10;
+        score
+    }
+
+    /// Render as a PNG: `scale` pixels per module, `border` modules of quiet zone.
+    pub(crate) fn to_png(&self, scale: usize, border: usize) -> Vec<u8> {
+        let side = (self.size + border * 2) * scale;
+        let row_bytes = side.div_ceil(8);
+        let mut raw = Vec::with_capacity((row_bytes + 1) * side);
+        for py in 0..side {
+            raw.push(0); // filter: none
+            let my = (py / scale) as isize - border as isize;
+            let mut byte = 0u8;
+            for px in 0..side {
+                let mx = (px / scale) as isize - border as isize;
+                let dark = (0..self.size as isize).contains(&mx)
+                    && (0..self.size as isize).contains(&my)
+                    && self.get(mx as usize, my as usize);
+                // 1-bit grayscale: 1 is white
+                byte = byte << 1 | u8::from(!dark);
+                if px % 8 == 7 {
+                    raw.push(byte);
+                    byte = 0;
+                }
+            }
+            if !side.is_multiple_of(8) {
+                raw.push(byte << (8 - side % 8));
+            }
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(side as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(side as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[1, 0, 0, 0, 0]); // depth 1, grayscale
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn char_count_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+fn data_bits_needed(version: usize, len: usize) -> usize {
+    4 + char_count_bits(version) + len * 8
+}
+
+fn raw_codewords(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let align = version / 7 + 2;
+        modules -= (25 * align - 10) * align - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_codewords(version) - ECC_PER_BLOCK[version - 1] * NUM_BLOCKS[version - 1]
+}
+
+fn add_ecc_and_interleave(data: &[u8], version: usize) -> Vec<u8> {
+    let num_blocks = NUM_BLOCKS[version - 1];
+    let ecc_len = ECC_PER_BLOCK[version - 1];
+    let raw = raw_codewords(version);
+    let num_short = num_blocks - raw % num_blocks;
+    let short_len = raw / num_blocks;
+    let divisor = rs_divisor(ecc_len);
+
+    let mut blocks = Vec::with_capacity(num_blocks);
+    let mut k = 0;
+    for i in 0..num_blocks {
+        let len = short_len - ecc_len + usize::from(i >= num_short);
+        let mut block = data[k..k + len].to_vec();
+        k += len;
+        let ecc = rs_remainder(&block, &divisor);
+        if i < num_short {
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            // Short blocks carry a placeholder where long blocks have data
+            if i != short_len - ecc_len || j >= num_short {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z = 0u8;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, len: usize) {
+        for i in (0..len).rev() {
+            self.bits.push((value >> i) & 1 == 1);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|c| c.iter().fold(0u8, |acc, &b| acc << 1 | u8::from(b)))
+            .collect()
+    }
+}
+
+// ── PNG ────────────────────────────────────────────────────────────────
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// zlib stream of stored deflate blocks (a QR PNG is a few KB).
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut chunks = data.chunks(65_535).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        out.push(u8::from(chunks.peek().is_none()));
+        let len = chunk.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    out.extend_from_slice(&(b << 16 | a).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_smallest_version() {
+        assert_eq!(QrCode::encode(b"hi").unwrap().size, 21);
+        assert_eq!(QrCode::encode(&[b'x'; 14]).unwrap().size, 21);
+        assert_eq!(QrCode::encode(&[b'x'; 15]).unwrap().size, 25);
+        assert_eq!(QrCode::encode(&[b'x'; 213]).unwrap().size, 57);
+        assert!(QrCode::encode(&[b'x'; 214]).is_none());
+    }
+
+    #[test]
+    fn reed_solomon_matches_the_spec_example() {
+        // ISO/IEC 18004 Annex I: "01234567" at 1-M
+        let data = [
+            0x10, 0x20, 0x0C, 0x56, 0x61, 0x80, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11,
+            0xEC, 0x11,
+        ];
+        assert_eq!(
+            rs_remainder(&data, &rs_divisor(10)),
+            vec![0xA5, 0x24, 0xD4, 0xC1, 0xED, 0x36, 0xC7, 0x87, 0x2C, 0x55]
+        );
+    }
+
+    #[test]
+    fn png_has_valid_framing() {
+        let png = QrCode::encode(b"https://example.com").unwrap().to_png(4, 4);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+}
@@ -0,0 +1,462 @@
+// Paw Agent Engine — Web Chat Remote Access
+//
+// Optional ways to reach the chat from outside the home network, started
+// with the bridge when `remote_access` is set (or on demand):
+//
+//   upnp              — the router forwards the port (UPnP IGD or NAT-PMP,
+//                       see `upnp`); the lease is renewed while it runs.
+//                       Needs bind_address 0.0.0.0. The page is then
+//                       directly on the internet at the router's address.
+//   cloudflared       — a Cloudflare quick tunnel (`cloudflared tunnel
+//                       --url …`); no router changes, a random
+//                       https://*.trycloudflare.com address, HTTPS included.
+//   tailscale_funnel  — `tailscale funnel` on this machine's tailnet name.
+//
+// Every mode puts the page on the public internet, so status always
+// carries warnings about what that means for this configuration (open
+// policy, plain HTTP, dangerous tools). The share link uses the public URL
+// while remote access is active.
+
+use super::upnp::{self, PortMapping};
+use super::{load_config, save_config, WebChatConfig, BRIDGE_RUNNING};
+use crate::atoms::error::EngineResult;
+use crate::engine::processes;
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Router mappings are leased for an hour and renewed every half hour,
+/// so a crash leaves the port open for an hour at most.
+const LEASE_SECS: u32 = 3600;
+const TUNNEL_START_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteMode {
+    #[default]
+    Off,
+    Upnp,
+    Cloudflared,
+    TailscaleFunnel,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RemoteAccessStatus {
+    pub mode: RemoteMode,
+    pub active: bool,
+    /// Where the page can be reached from the internet
+    pub public_url: Option<String>,
+    /// How it's exposed, e.g. "UPnP port mapping" or "cloudflared quick tunnel"
+    pub detail: String,
+    pub error: Option<String>,
+    /// What exposing the page means for the current settings
+    pub warnings: Vec<String>,
+    pub cloudflared_installed: bool,
+    pub tailscale_installed: bool,
+}
+
+enum RemoteHandle {
+    Mapping(PortMapping),
+    Tunnel(tokio::process::Child),
+    Funnel,
+}
+
+#[derive(Default)]
+struct RemoteState {
+    status: RemoteAccessStatus,
+    handle: Option<RemoteHandle>,
+}
+
+static REMOTE: LazyLock<Mutex<RemoteState>> = LazyLock::new(Default::default);
+/// Bumped on every start/stop so stale renewal loops exit.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Current remote access state, plus which tools are installed.
+pub fn status(app_handle: &tauri::AppHandle) -> RemoteAccessStatus {
+    let config = load_config(app_handle).unwrap_or_default();
+    let mut status = REMOTE.lock().status.clone();
+    if !status.active {
+        status.mode = config.remote_access;
+    }
+    if status.mode != RemoteMode::Off {
+        status.warnings = security_warnings(&config, status.mode);
+    }
+    status.cloudflared_installed = which_program("cloudflared").is_some();
+    status.tailscale_installed = which_program("tailscale").is_some();
+    status
+}
+
+/// The public page URL while remote access is active.
+pub(crate) fn public_url() -> Option<String> {
+    let state = REMOTE.lock();
+    if state.status.active {
+        state.status.public_url.clone()
+    } else {
+        None
+    }
+}
+
+/// Save `mode` as the remote access setting and apply it now if the
+/// bridge is running.
+pub async fn set_mode(
+    app_handle: &tauri::AppHandle,
+    mode: RemoteMode,
+) -> EngineResult<RemoteAccessStatus> {
+    let mut config = load_config(app_handle)?;
+    config.remote_access = mode;
+    save_config(app_handle, &config)?;
+    if mode == RemoteMode::Off || !BRIDGE_RUNNING.load(Ordering::Relaxed) {
+        stop().await;
+    } else {
+        start(app_handle, &config).await?;
+    }
+    Ok(status(app_handle))
+}
+
+/// Expose the page as configured. Replaces whatever was running.
+pub(crate) async fn start(
+    app_handle: &tauri::AppHandle,
+    config: &WebChatConfig,
+) -> EngineResult<()> {
+    stop().await;
+    let mode = config.remote_access;
+    if mode == RemoteMode::Off {
+        return Ok(());
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let result = match mode {
+        RemoteMode::Upnp => start_upnp(config).await,
+        RemoteMode::Cloudflared => start_cloudflared(config).await,
+        RemoteMode::TailscaleFunnel => start_funnel(config).await,
+        RemoteMode::Off => unreachable!(),
+    };
+
+    let mut state = REMOTE.lock();
+    state.status = RemoteAccessStatus {
+        mode,
+        ..Default::default()
+    };
+    let outcome = match result {
+        Ok((handle, public_url, detail)) => {
+            info!("[webchat] Remote access via {}: {}", detail, public_url);
+            state.status.active = true;
+            state.status.public_url = Some(public_url);
+            state.status.detail = detail;
+            if let RemoteHandle::Mapping(mapping) = &handle {
+                spawn_renewal(mapping.clone(), config.port, generation);
+            }
+            state.handle = Some(handle);
+            Ok(())
+        }
+        Err(e) => {
+            warn!("[webchat] Remote access failed: {}", e);
+            state.status.error = Some(e.to_string());
+            Err(e)
+        }
+    };
+    let event = state.status.clone();
+    drop(state);
+    emit_status(app_handle, &event);
+    outcome
+}
+
+/// Close the port mapping or tunnel, if any.
+pub(crate) async fn stop() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    let handle = {
+        let mut state = REMOTE.lock();
+        state.status = RemoteAccessStatus::default();
+        state.handle.take()
+    };
+    match handle {
+        Some(RemoteHandle::Mapping(mapping)) => upnp::unmap_port(&mapping).await,
+        Some(RemoteHandle::Tunnel(mut child)) => {
+            processes::untrack(child.id().unwrap_or(0));
+            let _ = child.start_kill();
+            info!("[webchat] cloudflared tunnel stopped");
+        }
+        Some(RemoteHandle::Funnel) => match run_program("tailscale", &["funnel", "--bg", "off"]) {
+            Ok(_) => info!("[webchat] Tailscale funnel stopped"),
+            Err(e) => warn!("[webchat] Stopping Tailscale funnel failed: {}", e),
+        },
+        None => {}
+    }
+}
+
+fn emit_status(app_handle: &tauri::AppHandle, status: &RemoteAccessStatus) {
+    let _ = app_handle.emit(
+        "webchat-status",
+        json!({
+            "kind": "remote_access",
+            "active": status.active,
+            "public_url": status.public_url,
+            "error": status.error,
+        }),
+    );
+}
+
+/// Risks of putting this configuration on the internet.
+pub(crate) fn security_warnings(config: &WebChatConfig, mode: RemoteMode) -> Vec<String> {
+    let mut warnings = vec![
+        "Anyone on the internet who has the link and a token can open this chat and talk to \
+         your agent."
+            .to_string(),
+    ];
+    if config.dm_policy == "open" {
+        warnings.push(
+            "The access policy is Open — switch to Pairing to approve each new person.".into(),
+        );
+    }
+    if config.allow_dangerous_tools {
+        warnings.push(
+            "Dangerous tools are allowed for Web Chat — visitors could make your agent run \
+             commands or change files."
+                .into(),
+        );
+    }
+    if config.api_enabled {
+        warnings.push("The REST API is reachable too (with the access token).".into());
+    }
+    if mode == RemoteMode::Upnp {
+        if !local_tls(config) {
+            warnings.push(
+                "Without TLS, the token and messages cross the internet unencrypted — set a \
+                 certificate or use a tunnel instead."
+                    .into(),
+            );
+        }
+        if !matches!(config.bind_address.as_str(), "0.0.0.0" | "::") {
+            warnings.push("Port mapping needs the bind address set to 0.0.0.0.".into());
+        }
+    }
+    warnings
+}
+
+// ── Modes ──────────────────────────────────────────────────────────────
+
+type Started = (RemoteHandle, String, String);
+
+async fn start_upnp(config: &WebChatConfig) -> EngineResult<Started> {
+    if !matches!(config.bind_address.as_str(), "0.0.0.0" | "::") {
+        return Err("Port mapping needs the bind address set to 0.0.0.0.".into());
+    }
+    let mapping = upnp::map_port(config.port, LEASE_SECS).await?;
+    let host = mapping
+        .external_ip()
+        .ok_or("The router mapped the port but didn't report its public address")?
+        .to_string();
+    let scheme = if local_tls(config) { "https" } else { "http" };
+    let url = format!("{}://{}:{}/", scheme, host, config.port);
+    let detail = format!("{} port mapping", mapping.method());
+    Ok((RemoteHandle::Mapping(mapping), url, detail))
+}
+
+/// Renew the mapping at half the lease until `stop` or a restart.
+fn spawn_renewal(mapping: PortMapping, port: u16, generation: u64) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(LEASE_SECS as u64 / 2)).await;
+            if GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            match upnp::map_port(port, LEASE_SECS).await {
+                Ok(renewed) if renewed == mapping => debug!("[webchat] Port mapping renewed"),
+                Ok(_) => warn!("[webchat] Port mapping renewed by a different router"),
+                Err(e) => {
+                    warn!("[webchat] Renewing port mapping failed: {}", e);
+                    REMOTE.lock().status.error = Some(e.to_string());
+                }
+            }
+        }
+    });
+}
+
+async fn start_cloudflared(config: &WebChatConfig) -> EngineResult<Started> {
+    let bin = which_program("cloudflared").ok_or(
+        "cloudflared not found. Install it from \
+         https://developers.cloudflare.com/cloudflare-one/connections/connect-networks/downloads/",
+    )?;
+    let target = local_target(config);
+    let mut cmd = tokio::process::Command::new(bin);
+    cmd.args(["tunnel", "--no-autoupdate", "--url", target.as_str()]);
+    if local_tls(config) {
+        // The local certificate is usually self-signed; Cloudflare serves its own
+        cmd.arg("--no-tls-verify");
+    }
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    processes::bind_to_parent(cmd.as_std_mut());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start cloudflared: {}", e))?;
+    let pid = child.id().unwrap_or(0);
+    processes::track(pid, "cloudflared");
+
+    // cloudflared logs the assigned address to stderr
+    let stderr = child.stderr.take().ok_or("cloudflared has no stderr")?;
+    let mut lines = BufReader::new(stderr).lines();
+    let found = tokio::time::timeout(TUNNEL_START_TIMEOUT, async {
+        while let Ok(Some(line)) = lines.next_line().await {
+            debug!("[cloudflared] {}", line);
+            if let Some(url) = quick_tunnel_url(&line) {
+                return Some(url);
+            }
+        }
+        None
+    })
+    .await;
+    let Ok(Some(url)) = found else {
+        processes::untrack(pid);
+        let _ = child.start_kill();
+        return Err("cloudflared didn't report a tunnel address".into());
+    };
+    // Keep draining so the pipe never fills
+    tauri::async_runtime::spawn(async move {
+        while let Ok(Some(line)) = lines.next_line().await {
+            debug!("[cloudflared] {}", line);
+        }
+    });
+    Ok((
+        RemoteHandle::Tunnel(child),
+        format!("{}/", url),
+        "cloudflared quick tunnel".into(),
+    ))
+}
+
+fn quick_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '|')
+        .collect();
+    url.trim_end_matches('/')
+        .ends_with(".trycloudflare.com")
+        .then(|| url.trim_end_matches('/').to_string())
+}
+
+async fn start_funnel(config: &WebChatConfig) -> EngineResult<Started> {
+    let target = if local_tls(config) {
+        format!("https+insecure://localhost:{}", config.port)
+    } else {
+        local_target(config)
+    };
+    tokio::task::spawn_blocking(move || -> EngineResult<Started> {
+        run_program("tailscale", &["funnel", "--bg", &target])?;
+        let status: serde_json::Value =
+            serde_json::from_str(&run_program("tailscale", &["status", "--json"])?)?;
+        let dns_name = status["Self"]["DNSName"]
+            .as_str()
+            .unwrap_or("")
+            .trim_end_matches('.')
+            .to_string();
+        if dns_name.is_empty() {
+            return Err("Tailscale is not connected to a tailnet".into());
+        }
+        Ok((
+            RemoteHandle::Funnel,
+            format!("https://{}/", dns_name),
+            "Tailscale Funnel".into(),
+        ))
+    })
+    .await
+    .map_err(|e| format!("Tailscale funnel task failed: {}", e))?
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────
+
+fn local_tls(config: &WebChatConfig) -> bool {
+    config.tls_cert_path.is_some() && config.tls_key_path.is_some()
+}
+
+/// The page as seen from this machine, for tunnels to forward to.
+fn local_target(config: &WebChatConfig) -> String {
+    let scheme = if local_tls(config) { "https" } else { "http" };
+    format!("{}://localhost:{}", scheme, config.port)
+}
+
+/// Find a CLI in the usual install locations, then on PATH.
+fn which_program(name: &str) -> Option<String> {
+    let home_bin = format!("{}/.local/bin", std::env::var("HOME").unwrap_or_default());
+    for dir in [
+        "/usr/local/bin",
+        "/usr/bin",
+        "/opt/homebrew/bin",
+        home_bin.as_str(),
+    ] {
+        let path = std::path::Path::new(dir).join(name);
+        if path.exists() {
+            return Some(path.to_string_lossy().into_owned());
+        }
+    }
+    let finder = if cfg!(windows) { "where" } else { "which" };
+    std::process::Command::new(finder)
+        .arg(name)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .next()
+                .map(|l| l.trim().to_string())
+        })
+        .filter(|s| !s.is_empty())
+}
+
+fn run_program(name: &str, args: &[&str]) -> EngineResult<String> {
+    let bin = which_program(name).ok_or_else(|| format!("{} not found", name))?;
+    let output = std::process::Command::new(bin).args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "{} {} failed: {}",
+            name,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_quick_tunnel_address() {
+        let line = "2026-01-01T00:00:00Z INF |  https://quiet-lake-1234.trycloudflare.com  |";
+        assert_eq!(
+            quick_tunnel_url(line).as_deref(),
+            Some("https://quiet-lake-1234.trycloudflare.com")
+        );
+        assert!(quick_tunnel_url("INF See https://www.cloudflare.com/website-terms/").is_none());
+    }
+
+    #[test]
+    fn warnings_follow_the_config() {
+        let config = WebChatConfig {
+            bind_address: "0.0.0.0".into(),
+            dm_policy: "pairing".into(),
+            api_enabled: false,
+            ..Default::default()
+        };
+        // Tunnels encrypt; a bare port mapping over HTTP doesn't
+        assert_eq!(security_warnings(&config, RemoteMode::Cloudflared).len(), 1);
+        assert_eq!(security_warnings(&config, RemoteMode::Upnp).len(), 2);
+
+        let risky = WebChatConfig {
+            dm_policy: "open".into(),
+            allow_dangerous_tools: true,
+            ..config
+        };
+        assert_eq!(security_warnings(&risky, RemoteMode::Cloudflared).len(), 3);
+    }
+}
@@ -3,16 +3,22 @@
 // TCP/TLS listener, HTTP routing, auth endpoint, and stream utilities.
 
 use super::html::build_chat_html;
-use super::session::{create_session, extract_cookie, validate_session};
-use super::{get_stop_signal, handle_websocket, WebChatConfig};
+use super::session::{
+    create_session, create_session_until, extract_cookie, session_max_age, validate_session,
+};
+use super::share::{self, TokenGrant};
+use super::{api, files, history};
+use super::{get_stop_signal, handle_websocket, is_user_allowed, load_config, WebChatConfig};
 
 use crate::atoms::error::EngineResult;
+use crate::engine::channels::attachments;
 use log::{info, warn};
+use parking_lot::Mutex;
 use serde_json::json;
 use std::io::BufReader as StdBufReader;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use std::task::{Context, Poll};
 use tauri::Emitter;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
@@ -103,6 +109,27 @@ pub(crate) fn build_tls_acceptor(
     Ok(Some(tokio_rustls::TlsAcceptor::from(Arc::new(tls_config))))
 }
 
+/// The running server's acceptor, read per connection so a new certificate
+/// (see `tls`) takes effect without a restart.
+static TLS_ACCEPTOR: LazyLock<Mutex<Option<Arc<tokio_rustls::TlsAcceptor>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Swap the certificate of a server running with TLS. Returns false when
+/// no TLS server is running (the new paths apply on the next start).
+pub(crate) fn reload_tls(config: &WebChatConfig) -> EngineResult<bool> {
+    let mut current = TLS_ACCEPTOR.lock();
+    if current.is_none() {
+        return Ok(false);
+    }
+    match build_tls_acceptor(config)? {
+        Some(acceptor) => {
+            *current = Some(Arc::new(acceptor));
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 // ── Server Core ────────────────────────────────────────────────────────
 
 pub(crate) async fn run_server(
@@ -147,7 +174,7 @@ pub(crate) async fn run_server(
     );
 
     let config = Arc::new(config);
-    let tls_acceptor = tls_acceptor.map(Arc::new);
+    *TLS_ACCEPTOR.lock() = tls_acceptor.map(Arc::new);
 
     loop {
         if stop.load(Ordering::Relaxed) {
@@ -163,7 +190,7 @@ pub(crate) async fn run_server(
                 let app = app_handle.clone();
                 let cfg = config.clone();
                 let stop_clone = stop.clone();
-                let tls = tls_acceptor.clone();
+                let tls = TLS_ACCEPTOR.lock().clone();
                 tokio::spawn(async move {
                     // Wrap in TLS if configured, then box for type erasure
                     let stream: Box<dyn ChatStream> = if let Some(acceptor) = tls {
@@ -190,6 +217,7 @@ pub(crate) async fn run_server(
         }
     }
 
+    *TLS_ACCEPTOR.lock() = None;
     Ok(())
 }
 
@@ -213,8 +241,9 @@ async fn handle_connection(
     }
     buf.truncate(n);
 
-    let request_str = String::from_utf8_lossy(&buf);
+    let request_str = String::from_utf8_lossy(&buf).into_owned();
     let first_line = request_str.lines().next().unwrap_or("");
+    let path = first_line.split_whitespace().nth(1).unwrap_or("/");
     let is_websocket =
         request_str.contains("Upgrade: websocket") || request_str.contains("upgrade: websocket");
 
@@ -239,7 +268,33 @@ async fn handle_connection(
         let prefixed = PrefixedStream::new(buf, stream);
         handle_websocket(prefixed, peer, app_handle, config, username).await
     } else if first_line.starts_with("POST") && first_line.contains("/auth") {
-        handle_auth(stream, &buf, &config).await
+        handle_auth(stream, &buf, &app_handle).await
+    } else if first_line.starts_with("POST") && path.starts_with("/upload") {
+        match session_user(&request_str) {
+            Some(username) => handle_upload(stream, buf, &app_handle, &username).await,
+            None => {
+                respond_json(stream, "403 Forbidden", json!({"error": "session invalid"})).await
+            }
+        }
+    } else if first_line.starts_with("GET") && path == "/history" {
+        match session_user(&request_str) {
+            Some(username) => {
+                let entries = history::load(&app_handle, &username);
+                respond_json(
+                    stream,
+                    "200 OK",
+                    json!({ "user": username, "entries": entries }),
+                )
+                .await
+            }
+            None => {
+                respond_json(stream, "403 Forbidden", json!({"error": "session invalid"})).await
+            }
+        }
+    } else if first_line.starts_with("GET") && path.starts_with("/files/") {
+        serve_artifact(stream, &request_str, &config, &path["/files/".len()..]).await
+    } else if path.starts_with("/api/") {
+        api::handle_api(stream, buf, peer, &app_handle, path.to_string()).await
     } else if first_line.starts_with("GET /") {
         serve_html(stream, &config).await
     } else {
@@ -249,11 +304,11 @@ async fn handle_connection(
 
 // ── Auth Endpoint ──────────────────────────────────────────────────────
 
-/// POST /auth — validates access token, returns a session cookie.
+/// POST /auth — validates the access token or a guest token, returns a session cookie.
 async fn handle_auth(
     mut stream: Box<dyn ChatStream>,
     request_bytes: &[u8],
-    config: &WebChatConfig,
+    app_handle: &tauri::AppHandle,
 ) -> EngineResult<()> {
     let request_str = String::from_utf8_lossy(request_bytes);
 
@@ -262,12 +317,25 @@ async fn handle_auth(
     let parsed: serde_json::Value = serde_json::from_str(body).unwrap_or(json!({}));
 
     let token = parsed["token"].as_str().unwrap_or("");
-    let name = parsed["name"].as_str().unwrap_or("").trim();
-
-    // Constant-time comparison to prevent timing attacks on access token
-    use subtle::ConstantTimeEq;
-    let token_match = token.as_bytes().ct_eq(config.access_token.as_bytes());
-    if !bool::from(token_match) || name.is_empty() {
+    let requested_name = parsed["name"].as_str().unwrap_or("").trim();
+
+    // Reloaded so rotated and newly created guest tokens apply without a restart
+    let config = load_config(app_handle).unwrap_or_default();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Access token or a live guest token, compared in constant time
+    let (name, expires_at) = match share::resolve_token(&config, token, now) {
+        Some(TokenGrant::Full) => (requested_name.to_string(), None),
+        // A guest token with a name pins it
+        Some(TokenGrant::Guest { name, expires_at }) => (
+            name.unwrap_or_else(|| requested_name.to_string()),
+            Some(expires_at),
+        ),
+        None => (String::new(), None),
+    };
+    if name.is_empty() {
         let resp = "HTTP/1.1 403 Forbidden\r\nContent-Type: application/json\r\nContent-Length: 24\r\nConnection: close\r\n\r\n{\"error\":\"access denied\"}";
         stream
             .write_all(resp.as_bytes())
@@ -276,8 +344,15 @@ async fn handle_auth(
         return Ok(());
     }
 
-    let session_id = create_session(name.to_string());
-    info!("[webchat] Session created for '{}'", name);
+    let session_id = match expires_at {
+        Some(at) => create_session_until(name.clone(), at),
+        None => create_session(name.clone()),
+    };
+    info!(
+        "[webchat] Session created for '{}'{}",
+        name,
+        if expires_at.is_some() { " (guest)" } else { "" }
+    );
 
     let resp_body = json!({"ok": true}).to_string();
     let secure_flag = if config.tls_cert_path.is_some() {
@@ -286,8 +361,10 @@ async fn handle_auth(
         ""
     };
     let cookie = format!(
-        "paw_session={}; HttpOnly; SameSite=Strict; {}Path=/; Max-Age=86400",
-        session_id, secure_flag,
+        "paw_session={}; HttpOnly; SameSite=Strict; {}Path=/; Max-Age={}",
+        session_id,
+        secure_flag,
+        session_max_age(expires_at),
     );
     let response = format!(
         "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nSet-Cookie: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
@@ -317,3 +394,199 @@ async fn serve_html(mut stream: Box<dyn ChatStream>, config: &WebChatConfig) ->
 
     Ok(())
 }
+
+// ── Uploads, History & Artifacts ───────────────────────────────────────
+
+fn session_user(request_str: &str) -> Option<String> {
+    validate_session(extract_cookie(request_str, "paw_session")?)
+}
+
+async fn respond(
+    mut stream: Box<dyn ChatStream>,
+    status: &str,
+    headers: &str,
+    body: &[u8],
+) -> EngineResult<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        headers,
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| format!("Write response: {e}"))?;
+    stream
+        .write_all(body)
+        .await
+        .map_err(|e| format!("Write response: {e}"))?;
+    Ok(())
+}
+
+pub(super) async fn respond_json(
+    stream: Box<dyn ChatStream>,
+    status: &str,
+    body: serde_json::Value,
+) -> EngineResult<()> {
+    respond(
+        stream,
+        status,
+        "Content-Type: application/json\r\n",
+        body.to_string().as_bytes(),
+    )
+    .await
+}
+
+/// A request header's value (case-insensitive name).
+pub(super) fn header<'a>(request_str: &'a str, name: &str) -> Option<&'a str> {
+    request_str
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+fn content_length(request_str: &str) -> Option<usize> {
+    header(request_str, "content-length")?.parse().ok()
+}
+
+/// The request body: what came with the headers plus the rest of
+/// Content-Length, read from the stream. `None` if the client stopped early.
+pub(super) async fn read_body(
+    stream: &mut Box<dyn ChatStream>,
+    request: &[u8],
+    len: usize,
+) -> EngineResult<Option<Vec<u8>>> {
+    let start = request
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map_or(request.len(), |p| p + 4);
+    let mut body = request[start..].to_vec();
+    let mut chunk = vec![0u8; 64 * 1024];
+    while body.len() < len {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Read body: {e}"))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(len);
+    Ok(Some(body))
+}
+
+pub(super) fn query_param(path: &str, key: &str) -> Option<String> {
+    let query = path.split_once('?')?.1;
+    let (_, value) = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)?;
+    urlencoding::decode(value).ok().map(|v| v.into_owned())
+}
+
+/// POST /upload?name=… — the raw file is the request body.
+async fn handle_upload(
+    mut stream: Box<dyn ChatStream>,
+    request: Vec<u8>,
+    app_handle: &tauri::AppHandle,
+    username: &str,
+) -> EngineResult<()> {
+    let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return respond_json(stream, "400 Bad Request", json!({"error": "bad request"})).await;
+    };
+    let head = String::from_utf8_lossy(&request[..header_end]).into_owned();
+    let path = head
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .unwrap_or("");
+    let name = attachments::safe_file_name(&query_param(path, "name").unwrap_or_default());
+    let len = content_length(&head).unwrap_or(0);
+
+    // Checked before reading so oversized bodies are never buffered
+    let current = load_config(app_handle).unwrap_or_default();
+    if let Err(reason) = files::check_upload(&name, len, current.max_upload_mb * 1024 * 1024) {
+        return respond_json(stream, "400 Bad Request", json!({ "error": reason })).await;
+    }
+    if !is_user_allowed(&current, username) {
+        return respond_json(
+            stream,
+            "403 Forbidden",
+            json!({"error": "not approved yet"}),
+        )
+        .await;
+    }
+
+    let Some(body) = read_body(&mut stream, &request, len).await? else {
+        return respond_json(
+            stream,
+            "400 Bad Request",
+            json!({"error": "upload incomplete"}),
+        )
+        .await;
+    };
+
+    let agent_id = super::files_agent_id(&current);
+    match files::ingest_upload(app_handle, username, &agent_id, &name, &body).await {
+        Ok(()) => {
+            info!("[webchat] {} uploaded {} ({} bytes)", username, name, len);
+            respond_json(stream, "200 OK", json!({ "ok": true, "name": name })).await
+        }
+        Err(e) => {
+            warn!("[webchat] Upload {} from {} failed: {}", name, username, e);
+            respond_json(
+                stream,
+                "422 Unprocessable Entity",
+                json!({ "error": e.to_string() }),
+            )
+            .await
+        }
+    }
+}
+
+/// GET /files/<id> — an artifact the agent attached to a reply.
+async fn serve_artifact(
+    stream: Box<dyn ChatStream>,
+    request_str: &str,
+    config: &WebChatConfig,
+    id: &str,
+) -> EngineResult<()> {
+    let api_client = config.api_enabled && api::authorized(request_str, &config.access_token);
+    if session_user(request_str).is_none() && !api_client {
+        return respond(stream, "403 Forbidden", "", b"Session invalid.").await;
+    }
+    let Some((bytes, mime_type)) = files::load_artifact(id) else {
+        return respond(stream, "404 Not Found", "", b"Not found.").await;
+    };
+    // Only images render in the page; anything else downloads, sandboxed
+    let disposition = if mime_type.starts_with("image/") {
+        "inline"
+    } else {
+        "attachment"
+    };
+    let headers = format!(
+        "Content-Type: {}\r\nContent-Disposition: {}\r\nX-Content-Type-Options: nosniff\r\nContent-Security-Policy: sandbox\r\nCache-Control: private, max-age=86400\r\n",
+        mime_type, disposition
+    );
+    respond(stream, "200 OK", &headers, &bytes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_parts() {
+        let req = "POST /upload?name=My%20Notes.md&x=1 HTTP/1.1\r\nHost: a\r\ncontent-length: 42\r\n\r\nContent-Length: 7";
+        assert_eq!(content_length(req), Some(42));
+        assert_eq!(
+            query_param("/upload?name=My%20Notes.md&x=1", "name").as_deref(),
+            Some("My Notes.md")
+        );
+        assert_eq!(query_param("/upload", "name"), None);
+    }
+}
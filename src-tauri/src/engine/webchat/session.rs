@@ -10,6 +10,7 @@
 //   - Constant-time signature comparison (subtle::ConstantTimeEq)
 //   - Survives app restart (no in-memory state)
 //   - 24-hour expiry baked into token — no pruning needed
+//   - Rotating the signing key ends every outstanding session at once
 //   - Forgery requires the 256-bit HMAC key
 
 use base64::Engine;
@@ -37,6 +38,11 @@ fn get_signing_key() -> Result<[u8; 32], String> {
         }
     }
     // Generate on first use
+    new_signing_key()
+}
+
+/// Generate and store a fresh signing key, replacing any existing one.
+fn new_signing_key() -> Result<[u8; 32], String> {
     let mut key = [0u8; 32];
     getrandom::getrandom(&mut key).map_err(|e| format!("OS CSPRNG failed: {}", e))?;
     let b64 = base64::engine::general_purpose::STANDARD.encode(key);
@@ -44,6 +50,14 @@ fn get_signing_key() -> Result<[u8; 32], String> {
     Ok(key)
 }
 
+/// Replace the signing key, invalidating every session handed out so far.
+/// Used when the access token is rotated or a guest token revoked.
+pub(crate) fn rotate_signing_key() -> Result<(), String> {
+    new_signing_key()?;
+    log::info!("[webchat] Session signing key rotated — existing sessions ended");
+    Ok(())
+}
+
 fn now_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -54,7 +68,27 @@ fn now_secs() -> u64 {
 /// Create a signed session token for `username`.
 /// Stateless — no server-side storage needed.
 pub(crate) fn create_session(username: String) -> String {
-    let created_at = now_secs();
+    sign_session(&username, now_secs())
+}
+
+/// Create a session that ends at `expires_at` (unix seconds) or after the
+/// usual 24 hours, whichever is sooner — for guest tokens. The format is
+/// unchanged: `created_at` is backdated so the TTL runs out at `expires_at`.
+pub(crate) fn create_session_until(username: String, expires_at: u64) -> String {
+    let now = now_secs();
+    let created_at = now.min(expires_at.saturating_sub(SESSION_TTL_SECS));
+    sign_session(&username, created_at)
+}
+
+/// Seconds a session created now may last when it has to end at `expires_at`.
+pub(crate) fn session_max_age(expires_at: Option<u64>) -> u64 {
+    match expires_at {
+        Some(at) => at.saturating_sub(now_secs()).min(SESSION_TTL_SECS),
+        None => SESSION_TTL_SECS,
+    }
+}
+
+fn sign_session(username: &str, created_at: u64) -> String {
     let payload = format!("{}|{}", username, created_at);
 
     let key = match get_signing_key() {
@@ -0,0 +1,324 @@
+// Paw Agent Engine — Web Chat Sharing
+//
+// Everything needed to hand the chat to a friend in one step: the page URL
+// (LAN address when bound to 0.0.0.0, https when TLS is set), a share link
+// that carries a token in the URL fragment (`#t=…` — fragments never reach
+// the server or its logs; the page moves it into the sign-in form), and a
+// QR code of that link, written to `{data_root}/webchat/` as a PNG and also
+// returned as base64 for the UI.
+//
+// Guest tokens are short, expiring tokens for one person: they open the
+// page only (not the REST API or artifact downloads by bearer token),
+// optionally under a fixed name, and their sessions end when the token
+// does. Revoking one (or rotating the access token) also rotates the session
+// signing key, so every open session ends and other visitors sign in again
+// with their own token.
+
+use super::qr::QrCode;
+use super::{generate_token, load_config, save_config, session, GuestToken, WebChatConfig};
+use crate::atoms::error::EngineResult;
+use crate::engine::paths;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+/// Guest tokens last at most 30 days.
+const MAX_GUEST_HOURS: u64 = 24 * 30;
+/// Pixels per QR module and quiet-zone width in modules.
+const QR_SCALE: usize = 8;
+const QR_BORDER: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebChatShareInfo {
+    /// The chat page
+    pub url: String,
+    /// The page with the token in the fragment — what to send or scan
+    pub share_url: String,
+    /// QR code of `share_url` as a base64 PNG
+    pub qr_png_base64: String,
+    /// Where the QR PNG was written
+    pub qr_path: String,
+    /// The guest token `share_url` carries, when one was just created
+    pub guest: Option<GuestToken>,
+    /// Guest tokens that haven't expired
+    pub guest_tokens: Vec<GuestToken>,
+    /// SHA-256 of a self-signed certificate, for visitors to compare
+    /// against the browser's warning page
+    #[serde(default)]
+    pub tls_fingerprint: Option<String>,
+    /// Reasons the link may not work for someone else
+    pub warnings: Vec<String>,
+}
+
+/// What a token presented to POST /auth grants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TokenGrant {
+    /// The access token: page and REST API
+    Full,
+    /// A guest token: the page until `expires_at` (unix seconds)
+    Guest {
+        name: Option<String>,
+        expires_at: u64,
+    },
+}
+
+/// Share info for the access token. `rotate` replaces the token first and
+/// ends every open session, locking out everyone who used the old one.
+pub fn share_info(app_handle: &tauri::AppHandle, rotate: bool) -> EngineResult<WebChatShareInfo> {
+    let mut config = load_config(app_handle)?;
+    if rotate || config.access_token.is_empty() {
+        config.access_token = generate_token(32);
+        save_config(app_handle, &config)?;
+        session::rotate_signing_key()?;
+        log::info!("[webchat] Access token rotated");
+    }
+    let token = config.access_token.clone();
+    build_info(&config, &token, None, "share-qr.png")
+}
+
+/// Create a guest token valid for `expires_in_hours` (1 hour to 30 days)
+/// and return its share link. Expired tokens are dropped at the same time.
+pub fn create_guest_token(
+    app_handle: &tauri::AppHandle,
+    label: &str,
+    name: Option<String>,
+    expires_in_hours: u64,
+) -> EngineResult<WebChatShareInfo> {
+    let mut config = load_config(app_handle)?;
+    let now = chrono::Utc::now();
+    let hours = expires_in_hours.clamp(1, MAX_GUEST_HOURS);
+    let guest = GuestToken {
+        id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        token: generate_token(8),
+        label: if label.trim().is_empty() {
+            "Guest".into()
+        } else {
+            label.trim().to_string()
+        },
+        name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        created_at: now.to_rfc3339(),
+        expires_at: (now + chrono::Duration::hours(hours as i64)).to_rfc3339(),
+    };
+    config
+        .guest_tokens
+        .retain(|g| expiry_secs(g).is_some_and(|at| at > now.timestamp() as u64));
+    config.guest_tokens.push(guest.clone());
+    save_config(app_handle, &config)?;
+    log::info!(
+        "[webchat] Guest token '{}' created, expires {}",
+        guest.label,
+        guest.expires_at
+    );
+
+    let token = guest.token.clone();
+    let file_name = format!("share-qr-{}.png", guest.id);
+    build_info(&config, &token, Some(guest), &file_name)
+}
+
+/// Remove a guest token so it can't be used to sign in again, and end the
+/// sessions it already opened (with everyone else's).
+pub fn revoke_guest_token(app_handle: &tauri::AppHandle, id: &str) -> EngineResult<()> {
+    let mut config = load_config(app_handle)?;
+    let before = config.guest_tokens.len();
+    config.guest_tokens.retain(|g| g.id != id);
+    if config.guest_tokens.len() == before {
+        return Err(format!("No guest token with id {}", id).into());
+    }
+    let _ = std::fs::remove_file(share_dir().join(format!("share-qr-{}.png", id)));
+    save_config(app_handle, &config)?;
+    Ok(session::rotate_signing_key()?)
+}
+
+/// Match a token against the access token and the live guest tokens
+/// (constant-time for each).
+pub(crate) fn resolve_token(config: &WebChatConfig, token: &str, now: u64) -> Option<TokenGrant> {
+    if token.is_empty() {
+        return None;
+    }
+    if !config.access_token.is_empty()
+        && bool::from(token.as_bytes().ct_eq(config.access_token.as_bytes()))
+    {
+        return Some(TokenGrant::Full);
+    }
+    config.guest_tokens.iter().find_map(|g| {
+        let expires_at = expiry_secs(g)?;
+        (expires_at > now && bool::from(token.as_bytes().ct_eq(g.token.as_bytes()))).then(|| {
+            TokenGrant::Guest {
+                name: g.name.clone(),
+                expires_at,
+            }
+        })
+    })
+}
+
+fn expiry_secs(guest: &GuestToken) -> Option<u64> {
+    chrono::DateTime::parse_from_rfc3339(&guest.expires_at)
+        .ok()
+        .and_then(|at| u64::try_from(at.timestamp()).ok())
+}
+
+fn share_dir() -> std::path::PathBuf {
+    paths::paw_data_dir().join("webchat")
+}
+
+fn build_info(
+    config: &WebChatConfig,
+    token: &str,
+    guest: Option<GuestToken>,
+    qr_file: &str,
+) -> EngineResult<WebChatShareInfo> {
+    // The public address while remote access is active
+    let (url, mut warnings) = match super::remote::public_url() {
+        Some(url) => (url, vec![]),
+        None => page_url(config, lan_ip()),
+    };
+    let share_url = format!("{}#t={}", url, token);
+    if !super::BRIDGE_RUNNING.load(std::sync::atomic::Ordering::Relaxed) {
+        warnings.push("Web Chat isn't running — start it before sharing the link.".into());
+    }
+
+    let qr = QrCode::encode(share_url.as_bytes())
+        .ok_or("Share link is too long for a QR code")?
+        .to_png(QR_SCALE, QR_BORDER);
+    let dir = share_dir();
+    std::fs::create_dir_all(&dir)?;
+    let qr_path = dir.join(qr_file);
+    std::fs::write(&qr_path, &qr)?;
+
+    let tls_fingerprint = match super::tls::info_for(config) {
+        Ok(Some(cert)) if cert.self_signed => Some(cert.fingerprint_sha256),
+        _ => None,
+    };
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    Ok(WebChatShareInfo {
+        url,
+        share_url,
+        qr_png_base64: base64::engine::general_purpose::STANDARD.encode(&qr),
+        qr_path: qr_path.to_string_lossy().into_owned(),
+        guest,
+        guest_tokens: config
+            .guest_tokens
+            .iter()
+            .filter(|g| expiry_secs(g).is_some_and(|at| at > now))
+            .cloned()
+            .collect(),
+        tls_fingerprint,
+        warnings,
+    })
+}
+
+/// The page URL others should open, and why it might not work for them.
+fn page_url(config: &WebChatConfig, lan_ip: Option<String>) -> (String, Vec<String>) {
+    let tls = config
+        .tls_cert_path
+        .as_deref()
+        .is_some_and(|p| !p.is_empty())
+        && config
+            .tls_key_path
+            .as_deref()
+            .is_some_and(|p| !p.is_empty());
+    let mut warnings = vec![];
+    let host = match config.bind_address.as_str() {
+        "127.0.0.1" | "localhost" | "::1" => {
+            warnings.push(
+                "Web Chat only listens on this computer — set the bind address to 0.0.0.0 \
+                 to share it on your network."
+                    .into(),
+            );
+            "localhost".to_string()
+        }
+        "0.0.0.0" | "::" => lan_ip.unwrap_or_else(|| {
+            warnings.push("Couldn't find this computer's network address.".into());
+            "localhost".into()
+        }),
+        addr => addr.to_string(),
+    };
+    if !tls && host != "localhost" {
+        warnings.push(
+            "The link uses plain HTTP — others on the network could read the token. \
+             Generate a TLS certificate to use HTTPS."
+                .into(),
+        );
+    }
+    let host = if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host
+    };
+    let scheme = if tls { "https" } else { "http" };
+    (format!("{}://{}:{}/", scheme, host, config.port), warnings)
+}
+
+/// The address this machine uses to reach the network. Connecting a UDP
+/// socket sends nothing; it only picks the outgoing interface.
+pub(super) fn lan_ip() -> Option<String> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then(|| ip.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guest(token: &str, expires_at: &str, name: Option<&str>) -> GuestToken {
+        GuestToken {
+            id: "g1".into(),
+            token: token.into(),
+            label: "friend".into(),
+            name: name.map(Into::into),
+            created_at: "2026-01-01T00:00:00Z".into(),
+            expires_at: expires_at.into(),
+        }
+    }
+
+    #[test]
+    fn tokens_resolve_to_their_grant() {
+        let config = WebChatConfig {
+            access_token: "full".into(),
+            guest_tokens: vec![
+                guest("live", "2026-01-02T00:00:00Z", Some("Sam")),
+                guest("old", "2025-12-31T00:00:00Z", None),
+            ],
+            ..Default::default()
+        };
+        let now = 1_767_225_600; // 2026-01-01T00:00:00Z
+        assert_eq!(resolve_token(&config, "full", now), Some(TokenGrant::Full));
+        assert_eq!(
+            resolve_token(&config, "live", now),
+            Some(TokenGrant::Guest {
+                name: Some("Sam".into()),
+                expires_at: now + 86_400,
+            })
+        );
+        assert_eq!(resolve_token(&config, "old", now), None);
+        assert_eq!(resolve_token(&config, "live", now + 86_400), None);
+        assert_eq!(resolve_token(&config, "", now), None);
+        assert_eq!(resolve_token(&config, "nope", now), None);
+    }
+
+    #[test]
+    fn page_url_follows_bind_address_and_tls() {
+        let mut config = WebChatConfig {
+            bind_address: "0.0.0.0".into(),
+            port: 3939,
+            ..Default::default()
+        };
+        let (url, warnings) = page_url(&config, Some("192.168.1.20".into()));
+        assert_eq!(url, "http://192.168.1.20:3939/");
+        assert_eq!(warnings.len(), 1); // plain HTTP
+
+        config.tls_cert_path = Some("cert.pem".into());
+        config.tls_key_path = Some("key.pem".into());
+        let (url, warnings) = page_url(&config, Some("192.168.1.20".into()));
+        assert_eq!(url, "https://192.168.1.20:3939/");
+        assert!(warnings.is_empty());
+
+        config.bind_address = "127.0.0.1".into();
+        let (url, warnings) = page_url(&config, None);
+        assert_eq!(url, "https://localhost:3939/");
+        assert_eq!(warnings.len(), 1);
+    }
+}
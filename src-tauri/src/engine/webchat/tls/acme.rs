@@ -0,0 +1,463 @@
+// Paw Agent Engine — ACME client (RFC 8555) for the web chat certificate
+//
+// Issues a certificate for one domain from Let's Encrypt (or any ACME CA):
+// account (ES256 JWS), order, one challenge per authorization, CSR
+// finalization, certificate download. Challenges:
+//
+//   http-01  — a short-lived listener on `http_port` (default 80) answers
+//              /.well-known/acme-challenge/<token>. Port 80 on the domain
+//              has to reach it (router forward 80 → http_port).
+//   dns-01   — a `_acme-challenge` TXT record created and removed through
+//              the Cloudflare API (a token with Zone.DNS edit).
+
+use super::x509;
+use crate::atoms::error::EngineResult;
+use base64::Engine;
+use log::{debug, info};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub(crate) const LETS_ENCRYPT: &str = "https://acme-v02.api.letsencrypt.org/directory";
+pub(crate) const LETS_ENCRYPT_STAGING: &str =
+    "https://acme-staging-v02.api.letsencrypt.org/directory";
+const CLOUDFLARE_API: &str = "https://api.cloudflare.com/client/v4";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_ATTEMPTS: usize = 60;
+/// Time for a new TXT record to reach the zone's name servers.
+const DNS_PROPAGATION: Duration = Duration::from_secs(15);
+
+pub(crate) enum Challenge {
+    Http01 { port: u16 },
+    Dns01Cloudflare { api_token: String },
+}
+
+/// A certificate chain (PEM) and its private key (PEM).
+pub(crate) struct Issued {
+    pub chain_pem: String,
+    pub key_pem: String,
+}
+
+/// Run a whole issuance for `domain` with the given account key.
+pub(crate) async fn issue(
+    directory_url: &str,
+    account_key: &SigningKey,
+    email: &str,
+    domain: &str,
+    challenge: &Challenge,
+) -> EngineResult<Issued> {
+    let mut client = Client::new(directory_url, account_key.clone()).await?;
+    client.register(email).await?;
+
+    let (order_url, order) = client
+        .post_json(
+            &client.directory("newOrder")?,
+            Some(json!({ "identifiers": [{ "type": "dns", "value": domain }] })),
+        )
+        .await?;
+    let order_url = order_url.ok_or("ACME order has no URL")?;
+
+    for authz_url in order["authorizations"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+    {
+        let authz_url = authz_url.as_str().unwrap_or_default().to_string();
+        client.authorize(&authz_url, domain, challenge).await?;
+    }
+
+    // Finalize with a fresh key for the certificate
+    let cert_key = x509::new_key();
+    let csr = x509::csr(&cert_key, &[domain.to_string()]);
+    let finalize = order["finalize"]
+        .as_str()
+        .ok_or("ACME order has no finalize URL")?;
+    client
+        .post_json(finalize, Some(json!({ "csr": b64(&csr) })))
+        .await?;
+    let order = client.poll(&order_url, "valid", &["invalid"]).await?;
+    let cert_url = order["certificate"]
+        .as_str()
+        .ok_or("ACME order has no certificate")?;
+    let chain = client.post(cert_url, None).await?.2;
+    info!("[webchat] ACME certificate issued for {}", domain);
+    Ok(Issued {
+        chain_pem: String::from_utf8_lossy(&chain).into_owned(),
+        key_pem: x509::key_pem(&cert_key),
+    })
+}
+
+struct Client {
+    http: reqwest::Client,
+    key: SigningKey,
+    directory: Value,
+    nonce: Option<String>,
+    kid: Option<String>,
+}
+
+impl Client {
+    async fn new(directory_url: &str, key: SigningKey) -> EngineResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        let directory = http.get(directory_url).send().await?.json().await?;
+        Ok(Client {
+            http,
+            key,
+            directory,
+            nonce: None,
+            kid: None,
+        })
+    }
+
+    fn directory(&self, name: &str) -> EngineResult<String> {
+        self.directory[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("ACME directory has no {}", name).into())
+    }
+
+    async fn register(&mut self, email: &str) -> EngineResult<()> {
+        let mut account = json!({ "termsOfServiceAgreed": true });
+        if !email.is_empty() {
+            account["contact"] = json!([format!("mailto:{}", email)]);
+        }
+        let (location, _) = self
+            .post_json(&self.directory("newAccount")?, Some(account))
+            .await?;
+        self.kid = Some(location.ok_or("ACME account has no URL")?);
+        Ok(())
+    }
+
+    async fn authorize(
+        &mut self,
+        authz_url: &str,
+        domain: &str,
+        challenge: &Challenge,
+    ) -> EngineResult<()> {
+        let (_, authz) = self.post_json(authz_url, None).await?;
+        if authz["status"] == "valid" {
+            return Ok(());
+        }
+        let kind = match challenge {
+            Challenge::Http01 { .. } => "http-01",
+            Challenge::Dns01Cloudflare { .. } => "dns-01",
+        };
+        let offered = authz["challenges"]
+            .as_array()
+            .and_then(|list| list.iter().find(|c| c["type"] == kind))
+            .ok_or_else(|| format!("The CA didn't offer a {} challenge", kind))?;
+        let token = offered["token"].as_str().ok_or("challenge has no token")?;
+        let challenge_url = offered["url"].as_str().ok_or("challenge has no URL")?;
+        let key_authorization = format!("{}.{}", token, jwk_thumbprint(&self.key));
+        debug!("[webchat] ACME {} challenge for {}", kind, domain);
+
+        let result = match challenge {
+            Challenge::Http01 { port } => {
+                let responder = serve_http01(*port, token, &key_authorization).await?;
+                let result = self.answer(challenge_url, authz_url).await;
+                responder.abort();
+                result
+            }
+            Challenge::Dns01Cloudflare { api_token } => {
+                let value = b64(&Sha256::digest(key_authorization.as_bytes()));
+                let record = cloudflare_add_txt(&self.http, api_token, domain, &value).await?;
+                tokio::time::sleep(DNS_PROPAGATION).await;
+                let result = self.answer(challenge_url, authz_url).await;
+                cloudflare_remove_txt(&self.http, api_token, &record).await;
+                result
+            }
+        };
+        result.map(|_| ())
+    }
+
+    /// Tell the CA the challenge is ready and wait for the authorization.
+    async fn answer(&mut self, challenge_url: &str, authz_url: &str) -> EngineResult<Value> {
+        self.post_json(challenge_url, Some(json!({}))).await?;
+        self.poll(
+            authz_url,
+            "valid",
+            &["invalid", "deactivated", "expired", "revoked"],
+        )
+        .await
+    }
+
+    async fn poll(&mut self, url: &str, done: &str, failed: &[&str]) -> EngineResult<Value> {
+        for _ in 0..POLL_ATTEMPTS {
+            let (_, body) = self.post_json(url, None).await?;
+            let status = body["status"].as_str().unwrap_or("");
+            if status == done {
+                return Ok(body);
+            }
+            if failed.contains(&status) {
+                return Err(format!("ACME {}: {}", status, problem_detail(&body)).into());
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Err("Timed out waiting for the certificate authority".into())
+    }
+
+    /// POST a JSON payload (or POST-as-GET with `None`); returns Location and body.
+    async fn post_json(
+        &mut self,
+        url: &str,
+        payload: Option<Value>,
+    ) -> EngineResult<(Option<String>, Value)> {
+        let (location, _, body) = self.post(url, payload).await?;
+        Ok((
+            location,
+            serde_json::from_slice(&body).unwrap_or(Value::Null),
+        ))
+    }
+
+    async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<Value>,
+    ) -> EngineResult<(Option<String>, u16, Vec<u8>)> {
+        let payload = payload.map(|p| p.to_string()).unwrap_or_default();
+        // One retry for badNonce, which any request may get
+        for attempt in 0..2 {
+            let nonce = self.take_nonce().await?;
+            let body = jws(&self.key, self.kid.as_deref(), url, &nonce, &payload);
+            let response = self
+                .http
+                .post(url)
+                .header("Content-Type", "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await?;
+            let header = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+            };
+            self.nonce = header("replay-nonce");
+            let location = header("location");
+            let status = response.status().as_u16();
+            let bytes = response.bytes().await?.to_vec();
+            if status < 400 {
+                return Ok((location, status, bytes));
+            }
+            let problem: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+            if attempt == 0 && problem["type"] == "urn:ietf:params:acme:error:badNonce" {
+                continue;
+            }
+            return Err(format!("ACME error: {}", problem_detail(&problem)).into());
+        }
+        Err("ACME server kept rejecting the nonce".into())
+    }
+
+    async fn take_nonce(&mut self) -> EngineResult<String> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let response = self.http.head(self.directory("newNonce")?).send().await?;
+        response
+            .headers()
+            .get("replay-nonce")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| "ACME server sent no nonce".into())
+    }
+}
+
+fn problem_detail(problem: &Value) -> String {
+    problem["detail"]
+        .as_str()
+        .or_else(|| problem["error"]["detail"].as_str())
+        .or_else(|| problem["type"].as_str())
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
+fn b64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn jwk(key: &SigningKey) -> Value {
+    let point = key.verifying_key().to_encoded_point(false);
+    json!({
+        "crv": "P-256",
+        "kty": "EC",
+        "x": b64(point.x().map(|x| x.as_slice()).unwrap_or_default()),
+        "y": b64(point.y().map(|y| y.as_slice()).unwrap_or_default()),
+    })
+}
+
+/// RFC 7638 thumbprint: SHA-256 over the JWK's required members in order.
+fn jwk_thumbprint(key: &SigningKey) -> String {
+    let jwk = jwk(key);
+    let canonical = format!(
+        r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+        jwk["x"].as_str().unwrap_or(""),
+        jwk["y"].as_str().unwrap_or("")
+    );
+    b64(&Sha256::digest(canonical.as_bytes()))
+}
+
+/// Flattened JWS: the account URL once registered, the key before that.
+fn jws(key: &SigningKey, kid: Option<&str>, url: &str, nonce: &str, payload: &str) -> Value {
+    let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+    match kid {
+        Some(kid) => protected["kid"] = json!(kid),
+        None => protected["jwk"] = jwk(key),
+    }
+    let protected = b64(protected.to_string().as_bytes());
+    let payload = b64(payload.as_bytes());
+    let signature: Signature = key.sign(format!("{}.{}", protected, payload).as_bytes());
+    json!({
+        "protected": protected,
+        "payload": payload,
+        "signature": b64(&signature.to_bytes()),
+    })
+}
+
+// ── http-01 responder ──────────────────────────────────────────────────
+
+async fn serve_http01(
+    port: u16,
+    token: &str,
+    key_authorization: &str,
+) -> EngineResult<tokio::task::JoinHandle<()>> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .map_err(|e| {
+            format!(
+                "Couldn't listen on port {} for the http-01 challenge ({}). \
+                 Pick another challenge port and forward port 80 to it.",
+                port, e
+            )
+        })?;
+    let path = format!("/.well-known/acme-challenge/{}", token);
+    let answer = key_authorization.to_string();
+    Ok(tokio::spawn(async move {
+        while let Ok((mut stream, peer)) = listener.accept().await {
+            let mut buf = [0u8; 2048];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let requested = request.split_whitespace().nth(1).unwrap_or("");
+            let response = if requested == path {
+                debug!("[webchat] Answered http-01 challenge for {}", peer);
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    answer.len(),
+                    answer
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".into()
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    }))
+}
+
+// ── dns-01 via Cloudflare ──────────────────────────────────────────────
+
+/// (zone id, record id) of a created TXT record.
+type TxtRecord = (String, String);
+
+async fn cloudflare_add_txt(
+    http: &reqwest::Client,
+    api_token: &str,
+    domain: &str,
+    value: &str,
+) -> EngineResult<TxtRecord> {
+    // The zone is the longest suffix of the domain Cloudflare knows
+    let labels: Vec<&str> = domain.split('.').collect();
+    let mut zone_id = None;
+    for start in 0..labels.len().saturating_sub(1) {
+        let candidate = labels[start..].join(".");
+        let found: Value = http
+            .get(format!("{}/zones", CLOUDFLARE_API))
+            .query(&[("name", candidate.as_str())])
+            .bearer_auth(api_token)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(id) = found["result"][0]["id"].as_str() {
+            zone_id = Some(id.to_string());
+            break;
+        }
+    }
+    let zone_id = zone_id.ok_or_else(|| format!("No Cloudflare zone found for {}", domain))?;
+
+    let created: Value = http
+        .post(format!("{}/zones/{}/dns_records", CLOUDFLARE_API, zone_id))
+        .bearer_auth(api_token)
+        .json(&json!({
+            "type": "TXT",
+            "name": format!("_acme-challenge.{}", domain),
+            "content": value,
+            "ttl": 60,
+        }))
+        .send()
+        .await?
+        .json()
+        .await?;
+    let record_id = created["result"]["id"].as_str().ok_or_else(|| {
+        format!(
+            "Cloudflare refused the TXT record: {}",
+            created["errors"][0]["message"]
+                .as_str()
+                .unwrap_or("unknown error")
+        )
+    })?;
+    Ok((zone_id, record_id.to_string()))
+}
+
+async fn cloudflare_remove_txt(http: &reqwest::Client, api_token: &str, record: &TxtRecord) {
+    let (zone_id, record_id) = record;
+    let result = http
+        .delete(format!(
+            "{}/zones/{}/dns_records/{}",
+            CLOUDFLARE_API, zone_id, record_id
+        ))
+        .bearer_auth(api_token)
+        .send()
+        .await;
+    if let Err(e) = result {
+        debug!("[webchat] Removing ACME TXT record failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Verifier;
+
+    #[test]
+    fn jws_signs_protected_and_payload() {
+        let key = x509::new_key();
+        let body = jws(&key, None, "https://ca/new-acct", "n1", r#"{"a":1}"#);
+        let protected = body["protected"].as_str().unwrap();
+        let header: Value = serde_json::from_slice(
+            &base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(protected)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(header["alg"], "ES256");
+        assert_eq!(header["jwk"]["crv"], "P-256");
+
+        let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(body["signature"].as_str().unwrap())
+            .unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        let input = format!("{}.{}", protected, body["payload"].as_str().unwrap());
+        assert!(key
+            .verifying_key()
+            .verify(input.as_bytes(), &signature)
+            .is_ok());
+
+        let with_kid = jws(&key, Some("https://ca/acct/1"), "https://ca/x", "n2", "");
+        assert_eq!(with_kid["payload"], "");
+        assert_eq!(jwk_thumbprint(&key).len(), 43);
+    }
+}
@@ -0,0 +1,182 @@
+// Paw Agent Engine — minimal DER encoding and reading
+//
+// Just enough ASN.1 DER to build certificates and CSRs (`x509`) and to read
+// back the fields Paw shows about a certificate.
+
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const UTF8_STRING: u8 = 0x0c;
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+
+/// Tag-length-value.
+pub(crate) fn tlv(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = body.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+pub(crate) fn seq(parts: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SEQUENCE, &parts.concat())
+}
+
+pub(crate) fn set(parts: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SET, &parts.concat())
+}
+
+/// A non-negative INTEGER from big-endian bytes.
+pub(crate) fn uint(bytes: &[u8]) -> Vec<u8> {
+    let trimmed: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+    let mut body = if trimmed.is_empty() { vec![0] } else { trimmed };
+    if body[0] & 0x80 != 0 {
+        body.insert(0, 0);
+    }
+    tlv(INTEGER, &body)
+}
+
+pub(crate) fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut body = vec![(arcs[0] * 40 + arcs[1]) as u8];
+    for &arc in &arcs[2..] {
+        let mut chunk = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            chunk.insert(0, 0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        body.extend(chunk);
+    }
+    tlv(OID, &body)
+}
+
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    let mut body = vec![0];
+    body.extend_from_slice(bytes);
+    tlv(BIT_STRING, &body)
+}
+
+/// Context-specific constructed tag `[n]` (explicit tagging).
+pub(crate) fn explicit(n: u8, body: &[u8]) -> Vec<u8> {
+    tlv(0xa0 | n, body)
+}
+
+/// UTCTime through 2049, GeneralizedTime after (RFC 5280 §4.1.2.5).
+pub(crate) fn time(at: chrono::DateTime<chrono::Utc>) -> Vec<u8> {
+    use chrono::Datelike;
+    if at.year() < 2050 {
+        tlv(UTC_TIME, at.format("%y%m%d%H%M%SZ").to_string().as_bytes())
+    } else {
+        tlv(
+            GENERALIZED_TIME,
+            at.format("%Y%m%d%H%M%SZ").to_string().as_bytes(),
+        )
+    }
+}
+
+// ── Reading ────────────────────────────────────────────────────────────
+
+/// One element: (tag, content, whole encoding).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Element<'a> {
+    pub tag: u8,
+    pub content: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+/// Read the element at the start of `input`; returns it and the rest.
+pub(crate) fn read(input: &[u8]) -> Option<(Element<'_>, &[u8])> {
+    let tag = *input.first()?;
+    let first = *input.get(1)? as usize;
+    let (len, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let n = first & 0x7f;
+        if n == 0 || n > 4 {
+            return None;
+        }
+        let len = input
+            .get(2..2 + n)?
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + n)
+    };
+    let end = header.checked_add(len)?;
+    let content = input.get(header..end)?;
+    Some((
+        Element {
+            tag,
+            content,
+            raw: &input[..end],
+        },
+        &input[end..],
+    ))
+}
+
+/// All elements inside a constructed element's content.
+pub(crate) fn children(content: &[u8]) -> Option<Vec<Element<'_>>> {
+    let mut out = vec![];
+    let mut rest = content;
+    while !rest.is_empty() {
+        let (element, next) = read(rest)?;
+        out.push(element);
+        rest = next;
+    }
+    Some(out)
+}
+
+pub(crate) fn parse_time(element: &Element<'_>) -> Option<chrono::DateTime<chrono::Utc>> {
+    let text = std::str::from_utf8(element.content).ok()?;
+    let full = match element.tag {
+        UTC_TIME => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if year < 50 { "20" } else { "19" }, text)
+        }
+        GENERALIZED_TIME => text.to_string(),
+        _ => return None,
+    };
+    chrono::NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_and_reads_back() {
+        // ecdsa-with-SHA256
+        assert_eq!(
+            oid(&[1, 2, 840, 10045, 4, 3, 2]),
+            [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]
+        );
+        assert_eq!(uint(&[0x00, 0x80]), [0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(uint(&[0, 0]), [0x02, 0x01, 0x00]);
+
+        let long = tlv(OCTET_STRING, &[7u8; 300]);
+        assert_eq!(&long[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        let (element, rest) = read(&long).unwrap();
+        assert_eq!(element.content.len(), 300);
+        assert!(rest.is_empty());
+
+        let at = chrono::DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+        let encoded = time(at);
+        assert_eq!(&encoded[2..], b"260101000000Z");
+        assert_eq!(parse_time(&read(&encoded).unwrap().0), Some(at));
+    }
+}
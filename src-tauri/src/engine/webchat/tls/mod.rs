@@ -0,0 +1,298 @@
+// Paw Agent Engine — Web Chat Certificates
+//
+// One-click certificates for HTTPS/WSS instead of bringing your own PEMs:
+//
+//   - Self-signed: ECDSA P-256 for localhost, this machine's LAN address
+//     and any extra hosts, valid a year. Browsers warn on first visit, so
+//     the SHA-256 fingerprint is shown for visitors to compare.
+//   - ACME (Let's Encrypt) for a domain that points at this machine, via
+//     http-01 or dns-01 (Cloudflare, see `acme`). Renewed 30 days before
+//     expiry while the bridge runs.
+//
+// Both are written to `{data_root}/webchat/tls/` and set as tls_cert_path /
+// tls_key_path. A server already running with TLS swaps the certificate
+// in place; one running without TLS has to restart to switch to HTTPS.
+
+mod acme;
+mod der;
+mod x509;
+
+use super::{load_config, save_config, server, WebChatConfig, BRIDGE_RUNNING};
+use crate::atoms::error::EngineResult;
+use crate::engine::{key_vault, paths};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const SELF_SIGNED_DAYS: i64 = 365;
+const RENEW_WITHIN_DAYS: i64 = 30;
+const RENEW_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 3600);
+const PURPOSE_ACME_ACCOUNT: &str = "webchat-acme-account";
+
+/// Bumped on each bridge start so only the newest renewal loop runs.
+static RENEW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// ACME issuance settings (see `acme`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AcmeSettings {
+    /// Domain to certify; ACME is off while empty
+    pub domain: String,
+    /// Contact address for expiry notices from the CA
+    pub email: String,
+    /// "http-01" | "dns-01"
+    pub challenge: String,
+    /// Local port that answers http-01 (port 80 on the domain must reach it)
+    pub http_port: u16,
+    /// Cloudflare API token with Zone.DNS edit, for dns-01
+    pub cloudflare_api_token: String,
+    /// Use Let's Encrypt's staging CA (untrusted certificates, high limits)
+    pub staging: bool,
+    pub auto_renew: bool,
+}
+
+impl Default for AcmeSettings {
+    fn default() -> Self {
+        AcmeSettings {
+            domain: String::new(),
+            email: String::new(),
+            challenge: "http-01".into(),
+            http_port: 80,
+            cloudflare_api_token: String::new(),
+            staging: false,
+            auto_renew: true,
+        }
+    }
+}
+
+/// The configured certificate, as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsCertInfo {
+    pub cert_path: String,
+    pub key_path: String,
+    /// "self_signed" | "acme" | "custom"
+    pub source: String,
+    pub subject: String,
+    pub issuer: String,
+    pub hosts: Vec<String>,
+    /// RFC 3339
+    pub not_before: String,
+    /// RFC 3339
+    pub not_after: String,
+    pub days_left: i64,
+    pub self_signed: bool,
+    /// SHA-256 of the certificate, `AB:CD:…` — what browsers show
+    pub fingerprint_sha256: String,
+}
+
+fn tls_dir() -> PathBuf {
+    paths::paw_data_dir().join("webchat").join("tls")
+}
+
+/// The configured certificate, or `None` when TLS is off.
+pub fn cert_info(app_handle: &tauri::AppHandle) -> EngineResult<Option<TlsCertInfo>> {
+    info_for(&load_config(app_handle)?)
+}
+
+pub(crate) fn info_for(config: &WebChatConfig) -> EngineResult<Option<TlsCertInfo>> {
+    let (Some(cert_path), Some(key_path)) = (&config.tls_cert_path, &config.tls_key_path) else {
+        return Ok(None);
+    };
+    let pem =
+        std::fs::read(cert_path).map_err(|e| format!("Read TLS cert {}: {}", cert_path, e))?;
+    let cert = rustls_pemfile::certs(&mut pem.as_slice())
+        .next()
+        .ok_or("No certificate found in PEM file")?
+        .map_err(|e| format!("Parse TLS cert: {}", e))?;
+    let summary = x509::summarize(&cert).ok_or("Unreadable TLS certificate")?;
+
+    let source = match Path::new(cert_path).file_name().and_then(|n| n.to_str()) {
+        Some(name) if Path::new(cert_path).parent() == Some(tls_dir().as_path()) => match name {
+            "self-signed.crt" => "self_signed",
+            "acme.crt" => "acme",
+            _ => "custom",
+        },
+        _ => "custom",
+    };
+    Ok(Some(TlsCertInfo {
+        cert_path: cert_path.clone(),
+        key_path: key_path.clone(),
+        source: source.into(),
+        subject: summary.subject,
+        issuer: summary.issuer,
+        hosts: summary.hosts,
+        not_before: summary.not_before.to_rfc3339(),
+        not_after: summary.not_after.to_rfc3339(),
+        days_left: (summary.not_after - chrono::Utc::now()).num_days(),
+        self_signed: summary.self_signed,
+        fingerprint_sha256: summary.fingerprint,
+    }))
+}
+
+/// Create a self-signed certificate for this machine (plus `extra_hosts`)
+/// and switch the web chat to it.
+pub fn generate_self_signed(
+    app_handle: &tauri::AppHandle,
+    extra_hosts: Vec<String>,
+) -> EngineResult<TlsCertInfo> {
+    let mut config = load_config(app_handle)?;
+    let mut hosts = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+    if let Some(ip) = super::share::lan_ip() {
+        hosts.push(ip);
+    }
+    if !matches!(
+        config.bind_address.as_str(),
+        "0.0.0.0" | "::" | "127.0.0.1" | "localhost"
+    ) {
+        hosts.push(config.bind_address.clone());
+    }
+    for host in extra_hosts {
+        let host = host.trim().to_lowercase();
+        if !host.is_empty() && !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+
+    let key = x509::new_key();
+    let cert = x509::self_signed_cert(&key, "Paw Web Chat", &hosts, SELF_SIGNED_DAYS);
+    install(
+        app_handle,
+        &mut config,
+        "self-signed",
+        &x509::pem("CERTIFICATE", &cert),
+        &x509::key_pem(&key),
+    )
+}
+
+/// Issue (or renew) a certificate for `acme.domain` and switch to it.
+pub async fn issue_acme(app_handle: &tauri::AppHandle) -> EngineResult<TlsCertInfo> {
+    let mut config = load_config(app_handle)?;
+    let settings = config.acme.clone();
+    let domain = settings.domain.trim().to_lowercase();
+    if domain.is_empty() || !domain.contains('.') {
+        return Err("Set the domain that points at this machine first".into());
+    }
+    let challenge = match settings.challenge.as_str() {
+        "dns-01" if settings.cloudflare_api_token.is_empty() => {
+            return Err("dns-01 needs a Cloudflare API token".into())
+        }
+        "dns-01" => acme::Challenge::Dns01Cloudflare {
+            api_token: settings.cloudflare_api_token.clone(),
+        },
+        _ => acme::Challenge::Http01 {
+            port: settings.http_port,
+        },
+    };
+    let directory = if settings.staging {
+        acme::LETS_ENCRYPT_STAGING
+    } else {
+        acme::LETS_ENCRYPT
+    };
+
+    info!("[webchat] Requesting a certificate for {}", domain);
+    let issued = acme::issue(
+        directory,
+        &account_key(),
+        settings.email.trim(),
+        &domain,
+        &challenge,
+    )
+    .await?;
+    install(
+        app_handle,
+        &mut config,
+        "acme",
+        &issued.chain_pem,
+        &issued.key_pem,
+    )
+}
+
+/// The ACME account key, created on first use and kept in the key vault.
+fn account_key() -> p256::ecdsa::SigningKey {
+    if let Some(key) = key_vault::get(PURPOSE_ACME_ACCOUNT).and_then(|pem| x509::key_from_pem(&pem))
+    {
+        return key;
+    }
+    let key = x509::new_key();
+    key_vault::set(PURPOSE_ACME_ACCOUNT, &x509::key_pem(&key));
+    key
+}
+
+/// Write the PEMs, point the config at them, and swap a running server's certificate.
+fn install(
+    app_handle: &tauri::AppHandle,
+    config: &mut WebChatConfig,
+    stem: &str,
+    cert_pem: &str,
+    key_pem: &str,
+) -> EngineResult<TlsCertInfo> {
+    let dir = tls_dir();
+    std::fs::create_dir_all(&dir)?;
+    let cert_path = dir.join(format!("{}.crt", stem));
+    let key_path = dir.join(format!("{}.key", stem));
+    std::fs::write(&cert_path, cert_pem)?;
+    std::fs::write(&key_path, key_pem)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    config.tls_cert_path = Some(cert_path.to_string_lossy().into_owned());
+    config.tls_key_path = Some(key_path.to_string_lossy().into_owned());
+    save_config(app_handle, config)?;
+    if server::reload_tls(config)? {
+        info!("[webchat] TLS certificate replaced in the running server");
+    }
+    info_for(config)?.ok_or_else(|| "Certificate was not saved".into())
+}
+
+/// While the bridge runs, renew an ACME certificate close to expiry.
+pub(crate) fn spawn_renewal(app_handle: tauri::AppHandle) {
+    let generation = RENEW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let config = load_config(&app_handle).unwrap_or_default();
+            if config.acme.auto_renew && !config.acme.domain.is_empty() {
+                match info_for(&config) {
+                    Ok(Some(info))
+                        if info.source == "acme" && info.days_left > RENEW_WITHIN_DAYS => {}
+                    Ok(Some(info)) if info.source != "acme" => {}
+                    _ => {
+                        if let Err(e) = issue_acme(&app_handle).await {
+                            warn!("[webchat] Certificate renewal failed: {}", e);
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(RENEW_CHECK_INTERVAL).await;
+            if RENEW_GENERATION.load(Ordering::SeqCst) != generation
+                || !BRIDGE_RUNNING.load(Ordering::Relaxed)
+            {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acme_settings_fill_missing_fields() {
+        let settings: AcmeSettings =
+            serde_json::from_str(r#"{"domain":"chat.example.com","challenge":"dns-01"}"#).unwrap();
+        assert_eq!(settings.domain, "chat.example.com");
+        assert_eq!(settings.challenge, "dns-01");
+        assert_eq!(settings.http_port, 80);
+        assert!(settings.auto_renew);
+        assert!(!settings.staging);
+    }
+}
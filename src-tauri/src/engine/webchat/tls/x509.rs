@@ -0,0 +1,286 @@
+// Paw Agent Engine — certificates and CSRs for the web chat
+//
+// ECDSA P-256 keys (p256), X.509 v3 certificates and PKCS#10 requests
+// built with `der`, and a reader for the fields shown in the UI.
+
+use super::der::{self, Element};
+use base64::Engine;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+
+const OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+const OID_PRIME256V1: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+const OID_ECDSA_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+const OID_COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+const OID_SUBJECT_ALT_NAME: &[u64] = &[2, 5, 29, 17];
+const OID_BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+const OID_KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+const OID_EXT_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
+const OID_SERVER_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 1];
+const OID_EXTENSION_REQUEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 14];
+
+/// What Paw shows about a certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CertSummary {
+    pub subject: String,
+    pub issuer: String,
+    /// DNS names and IP addresses it is valid for
+    pub hosts: Vec<String>,
+    pub not_before: chrono::DateTime<chrono::Utc>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+    pub self_signed: bool,
+    /// SHA-256 of the DER, as colon-separated hex
+    pub fingerprint: String,
+}
+
+pub(crate) fn new_key() -> SigningKey {
+    loop {
+        let mut bytes = [0u8; 32];
+        getrandom::getrandom(&mut bytes).expect("OS CSPRNG failed");
+        // Out-of-range scalars (zero or ≥ n) are astronomically rare
+        if let Ok(key) = SigningKey::from_slice(&bytes) {
+            return key;
+        }
+    }
+}
+
+/// The key as SEC1 `EC PRIVATE KEY` PEM.
+pub(crate) fn key_pem(key: &SigningKey) -> String {
+    let secret = key.to_bytes();
+    let public = key.verifying_key().to_encoded_point(false);
+    let der = der::seq(&[
+        der::uint(&[1]),
+        der::tlv(der::OCTET_STRING, &secret),
+        der::explicit(0, &der::oid(OID_PRIME256V1)),
+        der::explicit(1, &der::bit_string(public.as_bytes())),
+    ]);
+    pem("EC PRIVATE KEY", &der)
+}
+
+/// Parse a SEC1 or PKCS#8 P-256 key.
+pub(crate) fn key_from_pem(pem: &str) -> Option<SigningKey> {
+    use p256::pkcs8::DecodePrivateKey;
+    p256::SecretKey::from_sec1_pem(pem)
+        .or_else(|_| p256::SecretKey::from_pkcs8_pem(pem))
+        .ok()
+        .map(SigningKey::from)
+}
+
+pub(crate) fn pem(label: &str, der: &[u8]) -> String {
+    let body = base64::engine::general_purpose::STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// A self-signed server certificate for `hosts` (DNS names or IPs), valid
+/// from a day ago for `days`. Returns the certificate DER.
+pub(crate) fn self_signed_cert(
+    key: &SigningKey,
+    common_name: &str,
+    hosts: &[String],
+    days: i64,
+) -> Vec<u8> {
+    let now = chrono::Utc::now();
+    let mut serial = [0u8; 16];
+    getrandom::getrandom(&mut serial).expect("OS CSPRNG failed");
+    serial[0] &= 0x7f;
+
+    let name = name(common_name);
+    let extensions = der::seq(&[
+        extension(OID_BASIC_CONSTRAINTS, true, &der::seq(&[])),
+        // digitalSignature
+        extension(OID_KEY_USAGE, true, &der::tlv(der::BIT_STRING, &[7, 0x80])),
+        extension(
+            OID_EXT_KEY_USAGE,
+            false,
+            &der::seq(&[der::oid(OID_SERVER_AUTH)]),
+        ),
+        extension(OID_SUBJECT_ALT_NAME, false, &subject_alt_names(hosts)),
+    ]);
+    let tbs = der::seq(&[
+        der::explicit(0, &der::uint(&[2])),
+        der::uint(&serial),
+        signature_algorithm(),
+        name.clone(),
+        der::seq(&[
+            der::time(now - chrono::Duration::days(1)),
+            der::time(now + chrono::Duration::days(days)),
+        ]),
+        name,
+        public_key_info(key),
+        der::explicit(3, &extensions),
+    ]);
+    signed(key, tbs)
+}
+
+/// A PKCS#10 request for `hosts`, for ACME finalization. Returns DER.
+pub(crate) fn csr(key: &SigningKey, hosts: &[String]) -> Vec<u8> {
+    let request = der::seq(&[
+        der::uint(&[0]),
+        name(hosts.first().map(String::as_str).unwrap_or("")),
+        public_key_info(key),
+        der::explicit(
+            0,
+            &der::seq(&[
+                der::oid(OID_EXTENSION_REQUEST),
+                der::set(&[der::seq(&[extension(
+                    OID_SUBJECT_ALT_NAME,
+                    false,
+                    &subject_alt_names(hosts),
+                )])]),
+            ]),
+        ),
+    ]);
+    signed(key, request)
+}
+
+fn signed(key: &SigningKey, to_be_signed: Vec<u8>) -> Vec<u8> {
+    let signature: Signature = key.sign(&to_be_signed);
+    let (r, s) = signature.split_bytes();
+    let signature_der = der::seq(&[der::uint(&r), der::uint(&s)]);
+    der::seq(&[
+        to_be_signed,
+        signature_algorithm(),
+        der::bit_string(&signature_der),
+    ])
+}
+
+fn signature_algorithm() -> Vec<u8> {
+    der::seq(&[der::oid(OID_ECDSA_SHA256)])
+}
+
+fn public_key_info(key: &SigningKey) -> Vec<u8> {
+    let point = key.verifying_key().to_encoded_point(false);
+    der::seq(&[
+        der::seq(&[der::oid(OID_EC_PUBLIC_KEY), der::oid(OID_PRIME256V1)]),
+        der::bit_string(point.as_bytes()),
+    ])
+}
+
+fn name(common_name: &str) -> Vec<u8> {
+    der::seq(&[der::set(&[der::seq(&[
+        der::oid(OID_COMMON_NAME),
+        der::tlv(der::UTF8_STRING, common_name.as_bytes()),
+    ])])])
+}
+
+fn extension(id: &[u64], critical: bool, value: &[u8]) -> Vec<u8> {
+    let mut parts = vec![der::oid(id)];
+    if critical {
+        parts.push(der::tlv(0x01, &[0xff]));
+    }
+    parts.push(der::tlv(der::OCTET_STRING, value));
+    der::seq(&parts)
+}
+
+fn subject_alt_names(hosts: &[String]) -> Vec<u8> {
+    let names: Vec<Vec<u8>> = hosts
+        .iter()
+        .map(|host| match host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => der::tlv(0x87, &ip.octets()),
+            Ok(IpAddr::V6(ip)) => der::tlv(0x87, &ip.octets()),
+            Err(_) => der::tlv(0x82, host.as_bytes()),
+        })
+        .collect();
+    der::seq(&names)
+}
+
+/// SHA-256 of a certificate's DER as `AB:CD:…`.
+pub(crate) fn fingerprint(cert_der: &[u8]) -> String {
+    Sha256::digest(cert_der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Read the fields shown in the UI from a certificate's DER.
+pub(crate) fn summarize(cert_der: &[u8]) -> Option<CertSummary> {
+    let (cert, _) = der::read(cert_der)?;
+    let (tbs, _) = der::read(cert.content)?;
+    let mut fields = der::children(tbs.content)?.into_iter().peekable();
+    if fields.peek()?.tag == 0xa0 {
+        fields.next(); // version
+    }
+    let _serial = fields.next()?;
+    let _algorithm = fields.next()?;
+    let issuer = fields.next()?;
+    let validity = der::children(fields.next()?.content)?;
+    let subject = fields.next()?;
+    let _key = fields.next()?;
+    let hosts = fields
+        .find(|f| f.tag == 0xa3)
+        .and_then(|ext| alt_names(&ext))
+        .unwrap_or_default();
+
+    Some(CertSummary {
+        subject: common_name(&subject).unwrap_or_default(),
+        issuer: common_name(&issuer).unwrap_or_default(),
+        hosts,
+        not_before: der::parse_time(validity.first()?)?,
+        not_after: der::parse_time(validity.get(1)?)?,
+        self_signed: issuer.raw == subject.raw,
+        fingerprint: fingerprint(cert_der),
+    })
+}
+
+fn common_name(name: &Element<'_>) -> Option<String> {
+    let wanted = der::oid(OID_COMMON_NAME);
+    der::children(name.content)?.iter().find_map(|rdn| {
+        der::children(rdn.content)?.iter().find_map(|attr| {
+            let parts = der::children(attr.content)?;
+            let value = parts.get(1)?;
+            (parts.first()?.raw == wanted.as_slice())
+                .then(|| String::from_utf8_lossy(value.content).into_owned())
+        })
+    })
+}
+
+fn alt_names(extensions: &Element<'_>) -> Option<Vec<String>> {
+    let (list, _) = der::read(extensions.content)?;
+    let wanted = der::oid(OID_SUBJECT_ALT_NAME);
+    let extension = der::children(list.content)?
+        .into_iter()
+        .find(|e| der::read(e.content).is_some_and(|(id, _)| id.raw == wanted.as_slice()))?;
+    let value = der::children(extension.content)?.last()?.content;
+    let (names, _) = der::read(value)?;
+    Some(
+        der::children(names.content)?
+            .iter()
+            .filter_map(|n| match (n.tag, n.content.len()) {
+                (0x82, _) => Some(String::from_utf8_lossy(n.content).into_owned()),
+                (0x87, 4) => Some(IpAddr::from(<[u8; 4]>::try_from(n.content).ok()?).to_string()),
+                (0x87, 16) => Some(IpAddr::from(<[u8; 16]>::try_from(n.content).ok()?).to_string()),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_signed_round_trip() {
+        let key = new_key();
+        let hosts = vec!["localhost".to_string(), "192.168.1.20".to_string()];
+        let cert = self_signed_cert(&key, "Paw Web Chat", &hosts, 365);
+        let summary = summarize(&cert).unwrap();
+        assert_eq!(summary.subject, "Paw Web Chat");
+        assert!(summary.self_signed);
+        assert_eq!(summary.hosts, hosts);
+        assert_eq!((summary.not_after - summary.not_before).num_days(), 366);
+        assert_eq!(summary.fingerprint.len(), 32 * 3 - 1);
+
+        let parsed = key_from_pem(&key_pem(&key)).unwrap();
+        assert_eq!(parsed.verifying_key(), key.verifying_key());
+    }
+}
@@ -0,0 +1,451 @@
+// Paw Agent Engine — Web Chat Port Mapping
+//
+// Asks the home router to forward the chat port from the internet:
+// UPnP IGD first (SSDP discovery, device description, SOAP
+// AddPortMapping), then NAT-PMP (RFC 6886) on the default gateway.
+// Mappings are leased and renewed by the caller; `unmap_port` removes one.
+// No dependencies beyond reqwest and tokio's UDP socket.
+
+use crate::atoms::error::EngineResult;
+use log::{debug, info};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SSDP_WAIT: Duration = Duration::from_secs(3);
+const NAT_PMP_PORT: u16 = 5351;
+const MAPPING_DESCRIPTION: &str = "Paw Web Chat";
+/// WAN connection services, in order of preference.
+const WAN_SERVICES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// A port forwarded by the router.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PortMapping {
+    Upnp {
+        control_url: String,
+        service_type: String,
+        port: u16,
+        external_ip: Option<String>,
+    },
+    NatPmp {
+        gateway: Ipv4Addr,
+        port: u16,
+        external_ip: Option<String>,
+    },
+}
+
+impl PortMapping {
+    pub(crate) fn method(&self) -> &'static str {
+        match self {
+            PortMapping::Upnp { .. } => "UPnP",
+            PortMapping::NatPmp { .. } => "NAT-PMP",
+        }
+    }
+
+    pub(crate) fn external_ip(&self) -> Option<&str> {
+        match self {
+            PortMapping::Upnp { external_ip, .. } | PortMapping::NatPmp { external_ip, .. } => {
+                external_ip.as_deref()
+            }
+        }
+    }
+}
+
+/// Forward TCP `port` on the router to this machine for `lease_secs`.
+pub(crate) async fn map_port(port: u16, lease_secs: u32) -> EngineResult<PortMapping> {
+    let upnp_error = match map_upnp(port, lease_secs).await {
+        Ok(mapping) => return Ok(mapping),
+        Err(e) => e,
+    };
+    debug!(
+        "[webchat] UPnP mapping failed ({}), trying NAT-PMP",
+        upnp_error
+    );
+    map_nat_pmp(port, lease_secs).await.map_err(|e| {
+        format!(
+            "The router didn't accept a port mapping (UPnP: {}; NAT-PMP: {}). \
+             It may have UPnP turned off.",
+            upnp_error, e
+        )
+        .into()
+    })
+}
+
+/// Remove a mapping made by `map_port`. Best effort.
+pub(crate) async fn unmap_port(mapping: &PortMapping) {
+    let result = match mapping {
+        PortMapping::Upnp {
+            control_url,
+            service_type,
+            port,
+            ..
+        } => {
+            let args = format!(
+                "<NewRemoteHost></NewRemoteHost><NewExternalPort>{}</NewExternalPort>\
+                 <NewProtocol>TCP</NewProtocol>",
+                port
+            );
+            soap_call(control_url, service_type, "DeletePortMapping", &args)
+                .await
+                .map(|_| ())
+        }
+        PortMapping::NatPmp { gateway, port, .. } => {
+            nat_pmp_request(*gateway, &nat_pmp_map_request(*port, 0, 0))
+                .await
+                .map(|_| ())
+        }
+    };
+    match result {
+        Ok(()) => info!("[webchat] Removed {} port mapping", mapping.method()),
+        Err(e) => debug!("[webchat] Removing port mapping failed: {}", e),
+    }
+}
+
+// ── UPnP IGD ───────────────────────────────────────────────────────────
+
+async fn map_upnp(port: u16, lease_secs: u32) -> EngineResult<PortMapping> {
+    let location = discover_gateway().await?;
+    let description = reqwest::Client::new()
+        .get(&location)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?
+        .text()
+        .await?;
+    let (service_type, control_path) =
+        find_wan_service(&description).ok_or("the gateway has no WAN connection service")?;
+    let control_url = resolve_url(&location, &control_path);
+
+    let host: SocketAddr =
+        control_url_addr(&control_url).ok_or_else(|| format!("bad control URL {}", control_url))?;
+    let internal_ip = local_ip_towards(host).await?;
+
+    let args = format!(
+        "<NewRemoteHost></NewRemoteHost><NewExternalPort>{port}</NewExternalPort>\
+         <NewProtocol>TCP</NewProtocol><NewInternalPort>{port}</NewInternalPort>\
+         <NewInternalClient>{internal_ip}</NewInternalClient><NewEnabled>1</NewEnabled>\
+         <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+         <NewLeaseDuration>{lease_secs}</NewLeaseDuration>"
+    );
+    soap_call(&control_url, &service_type, "AddPortMapping", &args).await?;
+
+    let external_ip = soap_call(&control_url, &service_type, "GetExternalIPAddress", "")
+        .await
+        .ok()
+        .and_then(|body| xml_text(&body, "NewExternalIPAddress"))
+        .filter(|ip| !ip.is_empty());
+    info!(
+        "[webchat] UPnP mapped port {} → {} (external {:?})",
+        port, internal_ip, external_ip
+    );
+    Ok(PortMapping::Upnp {
+        control_url,
+        service_type,
+        port,
+        external_ip,
+    })
+}
+
+/// SSDP M-SEARCH for an internet gateway; returns its description URL.
+async fn discover_gateway() -> EngineResult<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    let search = "M-SEARCH * HTTP/1.1\r\n\
+                  HOST: 239.255.255.250:1900\r\n\
+                  MAN: \"ssdp:discover\"\r\n\
+                  MX: 2\r\n\
+                  ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+    socket.send_to(search.as_bytes(), SSDP_ADDR).await?;
+
+    let mut buf = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + SSDP_WAIT;
+    loop {
+        let (n, _) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf))
+            .await
+            .map_err(|_| "no UPnP gateway answered")??;
+        if let Some(location) = ssdp_location(&String::from_utf8_lossy(&buf[..n])) {
+            return Ok(location);
+        }
+    }
+}
+
+fn ssdp_location(response: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim().to_string())
+    })
+}
+
+/// The preferred WAN connection service: (service type, control URL).
+fn find_wan_service(description: &str) -> Option<(String, String)> {
+    let services: Vec<&str> = description
+        .split("<service>")
+        .skip(1)
+        .map(|s| s.split("</service>").next().unwrap_or(s))
+        .collect();
+    WAN_SERVICES.iter().find_map(|wanted| {
+        services.iter().find_map(|service| {
+            let service_type = xml_text(service, "serviceType")?;
+            if service_type != *wanted {
+                return None;
+            }
+            Some((service_type, xml_text(service, "controlURL")?))
+        })
+    })
+}
+
+/// Text of the first `<tag>` (any namespace prefix) in `xml`.
+fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let name = rest[..end].split_whitespace().next().unwrap_or("");
+        let local = name.rsplit(':').next().unwrap_or(name);
+        if local == tag && !name.starts_with('/') {
+            let body = &rest[end + 1..];
+            let close = body.find("</")?;
+            return Some(body[..close].trim().to_string());
+        }
+        rest = &rest[end..];
+    }
+    None
+}
+
+/// Resolve a control path against the description URL's origin.
+fn resolve_url(location: &str, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return path.to_string();
+    }
+    let after_scheme = location.find("://").map(|i| i + 3).unwrap_or(0);
+    let origin_end = location[after_scheme..]
+        .find('/')
+        .map(|i| after_scheme + i)
+        .unwrap_or(location.len());
+    let slash = if path.starts_with('/') { "" } else { "/" };
+    format!("{}{}{}", &location[..origin_end], slash, path)
+}
+
+fn control_url_addr(url: &str) -> Option<SocketAddr> {
+    let host_port = url.split("://").nth(1)?.split('/').next()?;
+    let (host, port) = host_port.rsplit_once(':').unwrap_or((host_port, "80"));
+    Some(SocketAddr::new(host.parse().ok()?, port.parse().ok()?))
+}
+
+async fn soap_call(
+    control_url: &str,
+    service_type: &str,
+    action: &str,
+    args: &str,
+) -> EngineResult<String> {
+    let body = format!(
+        "<?xml version=\"1.0\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+         </s:Envelope>"
+    );
+    let response = reqwest::Client::new()
+        .post(control_url)
+        .timeout(Duration::from_secs(5))
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
+        .body(body)
+        .send()
+        .await?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        let reason = xml_text(&text, "errorDescription").unwrap_or_else(|| status.to_string());
+        return Err(format!("{} failed: {}", action, reason).into());
+    }
+    Ok(text)
+}
+
+/// The local address used to reach `target` — the internal client for the mapping.
+async fn local_ip_towards(target: SocketAddr) -> EngineResult<std::net::IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(target).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+// ── NAT-PMP ────────────────────────────────────────────────────────────
+
+async fn map_nat_pmp(port: u16, lease_secs: u32) -> EngineResult<PortMapping> {
+    let gateway = default_gateway()
+        .await
+        .ok_or("couldn't find the default gateway")?;
+    let response = nat_pmp_request(gateway, &nat_pmp_map_request(port, port, lease_secs)).await?;
+    let mapped = parse_nat_pmp_mapping(&response)?;
+    if mapped != port {
+        // The chat URL has to keep the same port; give this one back
+        let _ = nat_pmp_request(gateway, &nat_pmp_map_request(port, 0, 0)).await;
+        return Err(format!("the router offered port {} instead of {}", mapped, port).into());
+    }
+    let external_ip = nat_pmp_request(gateway, &[0, 0])
+        .await
+        .ok()
+        .and_then(|r| parse_nat_pmp_address(&r));
+    info!(
+        "[webchat] NAT-PMP mapped port {} via {} (external {:?})",
+        port, gateway, external_ip
+    );
+    Ok(PortMapping::NatPmp {
+        gateway,
+        port,
+        external_ip,
+    })
+}
+
+fn nat_pmp_map_request(internal: u16, external: u16, lease_secs: u32) -> Vec<u8> {
+    let mut req = vec![0, 2, 0, 0]; // version 0, opcode 2 (TCP), reserved
+    req.extend_from_slice(&internal.to_be_bytes());
+    req.extend_from_slice(&external.to_be_bytes());
+    req.extend_from_slice(&lease_secs.to_be_bytes());
+    req
+}
+
+/// Send a request, retrying with the RFC's doubling timeout (250ms…1s).
+async fn nat_pmp_request(gateway: Ipv4Addr, request: &[u8]) -> EngineResult<Vec<u8>> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect((gateway, NAT_PMP_PORT)).await?;
+    let mut buf = [0u8; 16];
+    let mut wait = Duration::from_millis(250);
+    for _ in 0..3 {
+        socket.send(request).await?;
+        if let Ok(n) = tokio::time::timeout(wait, socket.recv(&mut buf)).await {
+            return Ok(buf[..n?].to_vec());
+        }
+        wait *= 2;
+    }
+    Err("no NAT-PMP answer from the gateway".into())
+}
+
+fn nat_pmp_result(response: &[u8], opcode: u8) -> EngineResult<()> {
+    if response.len() < 8 || response[0] != 0 || response[1] != 128 + opcode {
+        return Err("malformed NAT-PMP response".into());
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(()),
+        2 => Err("NAT-PMP is disabled on the router".into()),
+        code => Err(format!("NAT-PMP error {}", code).into()),
+    }
+}
+
+/// Mapped external port from a TCP mapping response.
+fn parse_nat_pmp_mapping(response: &[u8]) -> EngineResult<u16> {
+    nat_pmp_result(response, 2)?;
+    if response.len() < 16 {
+        return Err("short NAT-PMP mapping response".into());
+    }
+    Ok(u16::from_be_bytes([response[10], response[11]]))
+}
+
+fn parse_nat_pmp_address(response: &[u8]) -> Option<String> {
+    nat_pmp_result(response, 0).ok()?;
+    let ip = Ipv4Addr::new(
+        *response.get(8)?,
+        *response.get(9)?,
+        *response.get(10)?,
+        *response.get(11)?,
+    );
+    Some(ip.to_string())
+}
+
+/// The IPv4 default gateway: the kernel's routing table on Linux,
+/// otherwise `x.y.z.1` on this machine's subnet (the usual router address).
+async fn default_gateway() -> Option<Ipv4Addr> {
+    if let Ok(routes) = std::fs::read_to_string("/proc/net/route") {
+        if let Some(gateway) = gateway_from_route_table(&routes) {
+            return Some(gateway);
+        }
+    }
+    match local_ip_towards("192.0.2.1:80".parse().ok()?).await.ok()? {
+        std::net::IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => {
+            let [a, b, c, _] = ip.octets();
+            Some(Ipv4Addr::new(a, b, c, 1))
+        }
+        _ => None,
+    }
+}
+
+fn gateway_from_route_table(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        // Iface Destination Gateway … — destination 0 is the default route
+        if fields.len() < 3 || fields[1] != "00000000" {
+            return None;
+        }
+        let raw = u32::from_str_radix(fields[2], 16).ok()?;
+        (raw != 0).then(|| Ipv4Addr::from(raw.to_le_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_wan_service() {
+        let ssdp = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+                    Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        let location = ssdp_location(ssdp).unwrap();
+        assert_eq!(location, "http://192.168.1.1:5000/rootDesc.xml");
+
+        let description = r#"<root><device><serviceList>
+            <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+              <controlURL>/ctl/L3F</controlURL></service>
+            <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+              <controlURL>/ctl/IPConn</controlURL></service>
+        </serviceList></device></root>"#;
+        let (service, path) = find_wan_service(description).unwrap();
+        assert_eq!(service, "urn:schemas-upnp-org:service:WANIPConnection:1");
+        let url = resolve_url(&location, &path);
+        assert_eq!(url, "http://192.168.1.1:5000/ctl/IPConn");
+        assert_eq!(
+            control_url_addr(&url),
+            Some("192.168.1.1:5000".parse().unwrap())
+        );
+
+        let reply = "<s:Envelope><s:Body><u:GetExternalIPAddressResponse>\
+                     <NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>\
+                     </u:GetExternalIPAddressResponse></s:Body></s:Envelope>";
+        assert_eq!(
+            xml_text(reply, "NewExternalIPAddress").as_deref(),
+            Some("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn nat_pmp_packets() {
+        assert_eq!(
+            nat_pmp_map_request(3939, 3939, 3600),
+            [0, 2, 0, 0, 0x0f, 0x63, 0x0f, 0x63, 0, 0, 0x0e, 0x10]
+        );
+        let mapped = [
+            0, 130, 0, 0, 0, 0, 0, 1, 0x0f, 0x63, 0x0f, 0x63, 0, 0, 0x0e, 0x10,
+        ];
+        assert_eq!(parse_nat_pmp_mapping(&mapped).unwrap(), 3939);
+        let refused = [0, 130, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(parse_nat_pmp_mapping(&refused).is_err());
+        let address = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            parse_nat_pmp_address(&address).as_deref(),
+            Some("203.0.113.7")
+        );
+
+        let routes = "Iface\tDestination\tGateway\tFlags\n\
+                      eth0\t0001A8C0\t00000000\t0001\n\
+                      eth0\t00000000\t0101A8C0\t0003\n";
+        assert_eq!(
+            gateway_from_route_table(routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+    }
+}
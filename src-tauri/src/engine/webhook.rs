@@ -212,6 +212,7 @@ pub fn get_status(app_handle: &tauri::AppHandle) -> channels::ChannelStatus {
         allowed_users: vec![],
         pending_users: vec![],
         dm_policy: String::new(),
+        connectivity: channels::connectivity("webhook"),
     }
 }
 
@@ -98,6 +98,7 @@ pub(crate) async fn handle_inbound_message(
                     "user_name": &push_name,
                 }),
             );
+            channels::notify_pairing(&app_handle, "WhatsApp", &push_name);
             // Send denial message back
             let _ = send_whatsapp_message(&app_handle, remote_jid, &denial_str).await;
             continue;
@@ -1,8 +1,10 @@
 // WhatsApp Bridge — Webhook HTTP Listener
 // run_webhook_listener
 
+use super::config::{WhatsAppConfig, CONFIG_KEY};
 use super::messages::handle_inbound_message;
 use crate::atoms::error::EngineResult;
+use crate::engine::channels;
 use log::{info, warn};
 use serde_json::json;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -78,6 +80,9 @@ pub(crate) async fn run_webhook_listener(
                     .or_else(|| payload["data"]["qrcode"].as_str())
                     .unwrap_or("");
                 if !qr.is_empty() {
+                    // QR codes expire every ~20s; keep the saved one current so
+                    // a settings view opened mid-pairing shows a scannable code
+                    update_pairing(&app_handle, |c| c.qr_code = Some(qr.to_string()));
                     let _ = app_handle.emit(
                         "whatsapp-status",
                         json!({
@@ -91,6 +96,10 @@ pub(crate) async fn run_webhook_listener(
             "connection.update" => {
                 let state = payload["data"]["state"].as_str().unwrap_or("");
                 if state == "open" || state == "connected" {
+                    update_pairing(&app_handle, |c| {
+                        c.session_connected = true;
+                        c.qr_code = None;
+                    });
                     let _ = app_handle.emit(
                         "whatsapp-status",
                         json!({
@@ -99,6 +108,18 @@ pub(crate) async fn run_webhook_listener(
                         }),
                     );
                     info!("[whatsapp] Connection confirmed via webhook");
+                } else if state == "close" {
+                    // The phone unlinked the device (or the session expired):
+                    // it has to be paired again with a fresh QR code
+                    update_pairing(&app_handle, |c| c.session_connected = false);
+                    let _ = app_handle.emit(
+                        "whatsapp-status",
+                        json!({
+                            "kind": "disconnected",
+                            "message": "WhatsApp session closed — start the bridge again to re-pair",
+                        }),
+                    );
+                    warn!("[whatsapp] Session closed by WhatsApp — re-pairing needed");
                 }
             }
             "messages.upsert" => {
@@ -117,3 +138,22 @@ pub(crate) async fn run_webhook_listener(
 
     Ok(())
 }
+
+/// Persist a pairing change reported by Evolution API, so status and
+/// get_config reflect it.
+fn update_pairing(app_handle: &tauri::AppHandle, apply: impl FnOnce(&mut WhatsAppConfig)) {
+    let mut config: WhatsAppConfig = match channels::load_channel_config(app_handle, CONFIG_KEY) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(
+                "[whatsapp] Couldn't load config to record pairing state: {}",
+                e
+            );
+            return;
+        }
+    };
+    apply(&mut config);
+    if let Err(e) = channels::save_channel_config(app_handle, CONFIG_KEY, &config) {
+        warn!("[whatsapp] Couldn't save pairing state: {}", e);
+    }
+}
@@ -0,0 +1 @@
+pub use openpawz_core::engine::youtube::*;
@@ -68,6 +68,13 @@ fn startup_housekeeping(state: &commands::state::EngineState) {
         Err(e) => log::warn!("[startup] Session listing for pruning failed: {}", e),
     }
 
+    // 3. Research runs cut off by the last shutdown can be resumed
+    match state.store.research_mark_interrupted() {
+        Ok(n) if n > 0 => log::info!("[startup] {} research run(s) marked interrupted", n),
+        Err(e) => log::warn!("[startup] Research run check failed: {}", e),
+        _ => {}
+    }
+
     log::info!("[startup] DB housekeeping complete");
 
     // ── Session Continuity Certificate ────────────────────────────────
@@ -106,9 +113,36 @@ pub fn run() {
         .install_default()
         .expect("Failed to install rustls CryptoProvider");
 
+    // himalaya asking for a mail OAuth access token — answer and exit.
+    engine::mail_oauth::run_cli();
+
     // Load custom data root from ~/.paw/storage.conf BEFORE opening the DB.
     engine::paths::load_data_root_from_conf();
 
+    // ── Single instance ────────────────────────────────────────────────────
+    // A second launch on the same data root hands off to the running
+    // instance (focuses its window) and exits before touching the DB.
+    let mut instance_guard = match engine::single_instance::acquire() {
+        Ok(engine::single_instance::Acquire::Primary(guard)) => Some(guard),
+        Ok(engine::single_instance::Acquire::Forwarded) => return,
+        Err(e) => {
+            eprintln!(
+                "[instance] Single-instance check failed (continuing): {}",
+                e
+            );
+            None
+        }
+    };
+
+    // ── Child processes ────────────────────────────────────────────────────
+    // Kill what a crashed previous run left behind; n8n and `ollama serve`
+    // are adopted instead, since Paw reconnects to them by port.
+    for adopted in engine::processes::reconcile() {
+        if adopted.purpose == "ollama" {
+            engine::memory::ollama::register_spawned_ollama(adopted.pid);
+        }
+    }
+
     let engine_state =
         commands::state::EngineState::new().expect("Failed to initialize Paw Agent Engine");
 
@@ -195,6 +229,118 @@ pub fn run() {
                 }
             });
 
+            // ── Active-window awareness (opt-in, idle while off) ─────────
+            engine::app_context::spawn_watcher(app.handle().clone());
+
+            // ── New-mail push (IMAP IDLE, opt-in, idle while off) ────────
+            engine::mail_watch::spawn_supervisor(app.handle().clone());
+
+            // ── Resource monitor (warns when the model server thrashes) ──
+            engine::resources::spawn_monitor(app.handle().clone());
+
+            // ── First-run self-test ─────────────────────────────────────
+            // Once per install: lint the config, probe keychain and DB, and
+            // warn the user about misconfigurations.
+            let app_handle_diag = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                engine::diagnostics::first_run_self_test(&app_handle_diag).await;
+            });
+
+            // ── Engine DB maintenance (quick check + vacuum + FTS merge) ───
+            // At most once a day, tracked across restarts. Checked hourly,
+            // starting 10 minutes after launch to stay out of startup's way.
+            let app_handle_db = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(600)).await;
+                loop {
+                    if let Some(state) = app_handle_db.try_state::<commands::state::EngineState>() {
+                        let due = state
+                            .store
+                            .get_config("db_maintenance_last_run")
+                            .ok()
+                            .flatten()
+                            .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+                            .is_none_or(|t| {
+                                chrono::Utc::now() - t.with_timezone(&chrono::Utc)
+                                    > chrono::Duration::hours(24)
+                            });
+                        if due {
+                            // Archive first so the vacuum reclaims the space
+                            let archive_days = state.config.lock().session_archive_days;
+                            if archive_days > 0 {
+                                if let Err(e) = state.store.archive_sessions(
+                                    &engine::paths::session_archive_dir(),
+                                    archive_days,
+                                ) {
+                                    log::warn!("[db] Session archival failed: {}", e);
+                                }
+                            }
+                            match state.store.run_db_maintenance(
+                                engine::sessions::maintenance::DbMaintenanceAction::Scheduled,
+                            ) {
+                                Ok(report) if report.ok => log::info!(
+                                    "[db] Scheduled maintenance done in {}ms ({} → {} bytes)",
+                                    report.duration_ms,
+                                    report.size_before_bytes,
+                                    report.size_after_bytes
+                                ),
+                                Ok(report) => log::warn!(
+                                    "[db] Maintenance found problems: {}",
+                                    report.details.join("; ")
+                                ),
+                                Err(e) => log::warn!("[db] Scheduled maintenance failed: {}", e),
+                            }
+                            let now = chrono::Utc::now().to_rfc3339();
+                            let _ = state.store.set_config("db_maintenance_last_run", &now);
+                        }
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+                }
+            });
+
+            // ── Engine DB writer: batched writes + passive WAL checkpoints ──
+            // Applies `db_write` whenever it changes, flushes queued usage and
+            // activity rows and checkpoints the WAL between runs.
+            let app_handle_writer = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut applied: Option<engine::types::DbWriteConfig> = None;
+                let mut tick: u64 = 0;
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    tick += 1;
+                    let Some(state) = app_handle_writer.try_state::<commands::state::EngineState>()
+                    else {
+                        continue;
+                    };
+                    let cfg = state.config.lock().db_write.clone();
+                    if applied.as_ref() != Some(&cfg) {
+                        if let Err(e) = state.store.tune_writes(&cfg) {
+                            log::warn!("[db] Applying write config failed: {}", e);
+                        }
+                        applied = Some(cfg.clone());
+                    }
+                    if cfg.flush_secs > 0 && tick.is_multiple_of(u64::from(cfg.flush_secs)) {
+                        if let Err(e) = state.store.flush_deferred() {
+                            log::warn!("[db] Flushing queued writes failed: {}", e);
+                        }
+                    }
+                    if cfg.checkpoint_secs > 0
+                        && tick.is_multiple_of(u64::from(cfg.checkpoint_secs))
+                    {
+                        match state.store.checkpoint_wal() {
+                            Ok((wal, done)) if wal > done => log::debug!(
+                                "[db] Checkpointed {}/{} WAL frames (readers hold the rest)",
+                                done,
+                                wal
+                            ),
+                            Ok(_) => {}
+                            Err(e) => log::warn!("[db] WAL checkpoint failed: {}", e),
+                        }
+                    }
+                }
+            });
+
             // ── Engram memory maintenance (consolidation + decay + GC) ─────
             // Runs every 5 minutes in the background. Consolidates episodic
             // memories into semantic triples, applies Ebbinghaus decay, and
@@ -249,6 +395,23 @@ pub fn run() {
                                 log::warn!("[engram] Maintenance failed (non-fatal): {}", e);
                             }
                         }
+
+                        // Retention rules (per-category max age / max count)
+                        let rules = state.memory_config.lock().retention.clone();
+                        if !rules.is_empty() {
+                            match engine::engram::retention::apply_retention(
+                                &state.store,
+                                &rules,
+                                Some(&state.hnsw_index),
+                            ) {
+                                Ok(r) if r.episodic_erased > 0 => log::info!(
+                                    "[engram] Retention erased {} memories",
+                                    r.episodic_erased
+                                ),
+                                Ok(_) => {}
+                                Err(e) => log::warn!("[engram] Retention failed (non-fatal): {}", e),
+                            }
+                        }
                     }
                     tokio::time::sleep(std::time::Duration::from_secs(300)).await;
                 }
@@ -269,8 +432,10 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // ── Mail (himalaya bridge) ──
             commands::mail::write_himalaya_config,
+            commands::mail::mail_oauth_connect,
             commands::mail::read_himalaya_config,
             commands::mail::remove_himalaya_account,
+            commands::mail::test_mail_account,
             commands::mail::fetch_emails,
             commands::mail::fetch_email_content,
             commands::mail::send_email,
@@ -286,6 +451,7 @@ pub fn run() {
             commands::utility::get_db_encryption_key,
             commands::utility::has_db_encryption_key,
             commands::utility::check_keychain_health,
+            commands::utility::key_vault_migrate,
             commands::utility::lock_screen_has_passphrase,
             commands::utility::lock_screen_set_passphrase,
             commands::utility::lock_screen_verify_passphrase,
@@ -295,14 +461,29 @@ pub fn run() {
             // ── Chat & Sessions ──
             commands::chat::engine_chat_send,
             commands::chat::engine_chat_history,
+            commands::chat::engine_chat_citations,
             commands::chat::engine_chat_abort,
+            commands::chat::engine_chat_cancel,
             commands::chat::engine_sessions_list,
             commands::chat::engine_session_rename,
+            commands::chat::engine_session_set_agent,
             commands::chat::engine_session_delete,
             commands::chat::engine_session_clear,
+            commands::chat::engine_session_context_controls_get,
+            commands::chat::engine_session_context_controls_set,
+            commands::chat::engine_context_preview,
+            commands::chat::engine_prompt_render,
+            commands::chat::engine_slash_commands_list,
+            commands::chat::engine_session_instructions_get,
+            commands::chat::engine_session_instructions_set,
             commands::chat::engine_session_cleanup,
+            commands::chat::engine_sessions_archive,
+            commands::chat::engine_sessions_archived_list,
+            commands::chat::engine_sessions_archived_search,
+            commands::chat::engine_session_restore,
             commands::chat::engine_session_compact,
             commands::chat::engine_approve_tool,
+            commands::chat::engine_plan_respond,
             // ── Engine Config & Sandbox ──
             commands::config::engine_sandbox_check,
             commands::config::engine_sandbox_get_config,
@@ -310,6 +491,8 @@ pub fn run() {
             commands::config::engine_get_config,
             commands::config::engine_get_daily_spend,
             commands::config::engine_set_config,
+            commands::config::engine_profile_switch,
+            commands::config::engine_safe_mode_set,
             commands::config::engine_upsert_provider,
             commands::config::engine_remove_provider,
             commands::config::engine_list_provider_models,
@@ -318,17 +501,36 @@ pub fn run() {
             // ── Storage Paths ──
             commands::config::engine_storage_get_paths,
             commands::config::engine_storage_set_data_root,
+            // ── Home Location ──
+            commands::config::engine_location_set,
+            commands::config::engine_location_detect,
+            commands::config::engine_location_clear,
+            commands::config::engine_location_share,
+            commands::config::engine_app_context_set,
+            commands::config::engine_app_context_get,
             // ── Agent Files (Soul / Persona) ──
             commands::agent::engine_agent_file_list,
             commands::agent::engine_agent_file_get,
             commands::agent::engine_agent_file_set,
             commands::agent::engine_agent_file_delete,
+            commands::agent::engine_agent_file_history,
+            commands::agent::engine_agent_file_revert,
             commands::agent::engine_list_all_agents,
             commands::agent::engine_create_agent,
             commands::agent::engine_delete_agent,
+            commands::agent::engine_agent_autonomy_get,
+            commands::agent::engine_agent_autonomy_set,
+            commands::agent::engine_agent_reflection_get,
+            commands::agent::engine_agent_reflection_set,
+            commands::agent::engine_soul_proposals_list,
+            commands::agent::engine_soul_proposal_resolve,
+            commands::agent::engine_agent_grounding_get,
+            commands::agent::engine_agent_grounding_set,
             // ── Memory (Long-term Semantic) ──
             commands::memory::engine_memory_store,
             commands::memory::engine_memory_search,
+            commands::memory::engine_memory_search_debug,
+            commands::memory::engine_memory_timeline,
             commands::memory::engine_memory_stats,
             commands::memory::engine_memory_get,
             commands::memory::engine_memory_update,
@@ -336,6 +538,9 @@ pub fn run() {
             commands::memory::engine_memory_delete_by_session,
             commands::memory::engine_memory_list,
             commands::memory::engine_memory_edges,
+            commands::memory::engine_memory_graph_export,
+            commands::memory::engine_memory_export,
+            commands::memory::engine_memory_import,
             commands::memory::engine_get_memory_config,
             commands::memory::engine_set_memory_config,
             commands::memory::engine_test_embedding,
@@ -346,6 +551,8 @@ pub fn run() {
             commands::memory::engine_working_memory_save,
             commands::memory::engine_working_memory_restore,
             commands::memory::engine_memory_purge_user,
+            commands::memory::engine_memory_forget_matching,
+            commands::memory::engine_memory_retention_run,
             commands::memory::engine_memory_embedding_projection,
             commands::memory::engine_message_feedback,
             // ── Skill Vault ──
@@ -421,6 +628,8 @@ pub fn run() {
             commands::telemetry::engine_get_model_breakdown,
             commands::telemetry::engine_list_session_metrics,
             commands::telemetry::engine_purge_old_metrics,
+            commands::telemetry::engine_stats_timeseries,
+            commands::telemetry::engine_tool_validation_stats,
             // ── Skill Wizard (Phase F.5) ──
             commands::skill_wizard::engine_wizard_generate_toml,
             commands::skill_wizard::engine_wizard_publish_url,
@@ -450,6 +659,8 @@ pub fn run() {
             commands::task::engine_task_set_agents,
             commands::task::engine_task_run,
             commands::task::engine_tasks_cron_tick,
+            commands::task::engine_followups_list,
+            commands::task::engine_followup_cancel,
             // ── Flows (Visual Pipelines) ──
             commands::flows::engine_flows_list,
             commands::flows::engine_flows_get,
@@ -553,6 +764,14 @@ pub fn run() {
             commands::channels::engine_webchat_approve_user,
             commands::channels::engine_webchat_deny_user,
             commands::channels::engine_webchat_remove_user,
+            commands::channels::engine_webchat_share_info,
+            commands::channels::engine_webchat_create_guest_token,
+            commands::channels::engine_webchat_revoke_guest_token,
+            commands::channels::engine_webchat_remote_status,
+            commands::channels::engine_webchat_set_remote_access,
+            commands::channels::engine_webchat_tls_info,
+            commands::channels::engine_webchat_tls_generate_self_signed,
+            commands::channels::engine_webchat_tls_issue_acme,
             // WhatsApp ──
             commands::channels::engine_whatsapp_start,
             commands::channels::engine_whatsapp_stop,
@@ -562,6 +781,16 @@ pub fn run() {
             commands::channels::engine_whatsapp_approve_user,
             commands::channels::engine_whatsapp_deny_user,
             commands::channels::engine_whatsapp_remove_user,
+            commands::channels::engine_email_start,
+            commands::channels::engine_email_stop,
+            commands::channels::engine_email_status,
+            commands::channels::engine_email_get_config,
+            commands::channels::engine_email_set_config,
+            commands::channels::engine_email_approve_user,
+            commands::channels::engine_email_deny_user,
+            commands::channels::engine_email_remove_user,
+            // Connectivity ──
+            commands::channels::engine_channel_ping,
             // ── Orchestrator: Projects ──
             commands::project::engine_projects_list,
             commands::project::engine_project_create,
@@ -570,6 +799,16 @@ pub fn run() {
             commands::project::engine_project_set_agents,
             commands::project::engine_project_messages,
             commands::project::engine_project_run,
+            commands::project::engine_project_pause,
+            commands::project::engine_project_resume,
+            commands::project::engine_project_rerun_step,
+            commands::project::engine_project_postmortem,
+            // ── Prompt Library ──
+            commands::prompts::engine_prompts_list,
+            commands::prompts::engine_prompts_save,
+            commands::prompts::engine_prompts_delete,
+            commands::prompts::engine_prompts_render,
+            commands::prompts::engine_prompts_run_template,
             // ── Browser Profiles & Sandbox ──
             commands::browser::engine_browser_get_config,
             commands::browser::engine_browser_set_config,
@@ -579,10 +818,13 @@ pub fn run() {
             commands::browser::engine_screenshots_list,
             commands::browser::engine_screenshot_get,
             commands::browser::engine_screenshot_delete,
+            commands::browser::engine_screen_capture_consent,
+            commands::browser::engine_screen_capture_consent_get,
             // ── Per-Agent Workspaces ──
             commands::browser::engine_workspaces_list,
             commands::browser::engine_workspace_files,
             commands::browser::engine_workspace_delete,
+            commands::browser::engine_downloads_list,
             // ── Network Policy (Outbound Domain Allowlist) ──
             commands::browser::engine_network_get_policy,
             commands::browser::engine_network_set_policy,
@@ -636,6 +878,7 @@ pub fn run() {
             commands::ollama::engine_ollama_pull_model,
             commands::ollama::engine_ollama_create_model,
             commands::ollama::engine_ollama_setup_worker,
+            commands::ollama::engine_local_model_warm,
             // ── Integration Credentials (Phase 2.5) ──
             commands::n8n::engine_integrations_test_credentials,
             commands::n8n::engine_integrations_save_credentials,
@@ -675,6 +918,40 @@ pub fn run() {
             // ── Compliance Export ──
             commands::export::engine_compliance_export,
             commands::export::engine_compliance_export_to_file,
+            commands::export::engine_export_all,
+            commands::import::engine_import_conversations,
+            commands::import::engine_import_all,
+            commands::import::engine_import_bookmarks,
+            commands::import::engine_ingest_file,
+            commands::outbox::engine_outbox_list,
+            commands::outbox::engine_outbox_approve,
+            commands::outbox::engine_outbox_reject,
+            commands::outbox::engine_outbox_edit,
+            commands::outbox::engine_outbox_get_settings,
+            commands::outbox::engine_outbox_set_settings,
+            commands::translate::engine_translate_get_settings,
+            commands::translate::engine_translate_set_settings,
+            commands::confidence::engine_confidence_get_settings,
+            commands::confidence::engine_confidence_set_settings,
+            commands::feedback::engine_feedback_submit,
+            commands::feedback::engine_feedback_get,
+            commands::feedback::engine_feedback_stats,
+            commands::compare::engine_compare_start,
+            commands::compare::engine_compare_get,
+            commands::compare::engine_compare_pick,
+            commands::search::engine_search_provider_get,
+            commands::search::engine_search_provider_set,
+            commands::news::engine_news_list,
+            commands::news::engine_news_save,
+            commands::news::engine_news_delete,
+            commands::news::engine_news_run,
+            commands::news::engine_news_digests,
+            commands::research::engine_research_start,
+            commands::research::engine_research_list,
+            commands::research::engine_research_get,
+            commands::research::engine_research_cancel,
+            commands::research::engine_research_resume,
+            commands::research::engine_research_delete,
             // ── Tool Bridge & Remapping (Phase 5) ──
             commands::tool_bridge::engine_tools_remap,
             commands::tool_bridge::engine_tools_by_service,
@@ -715,6 +992,29 @@ pub fn run() {
             commands::mcp::engine_mcp_status,
             commands::mcp::engine_mcp_refresh_tools,
             commands::mcp::engine_mcp_connect_all,
+            // ── Tool RAG Index ──
+            commands::tool_rag::engine_toolrag_rebuild,
+            commands::tool_rag::engine_toolrag_query_debug,
+            commands::tool_rag::engine_toolrag_get_config,
+            commands::tool_rag::engine_toolrag_set_config,
+            // ── Credential Import/Export ──
+            commands::credentials::engine_credentials_list,
+            commands::credentials::engine_credentials_export,
+            commands::credentials::engine_credentials_inspect,
+            commands::credentials::engine_credentials_import,
+            // ── Diagnostics ──
+            commands::diagnostics::engine_diagnostics_self_test,
+            commands::diagnostics::engine_diagnostics_export,
+            commands::diagnostics::engine_db_maintenance,
+            // ── Child Processes ──
+            commands::processes::engine_processes_list,
+            commands::processes::engine_processes_kill,
+            commands::processes::engine_resource_usage,
+            // ── Worker Delegation ──
+            commands::worker_delegation::engine_worker_delegation_get_config,
+            commands::worker_delegation::engine_worker_delegation_set_config,
+            commands::worker_delegation::engine_worker_delegation_stats,
+            commands::worker_delegation::engine_worker_delegation_reset_stats,
             // ── Squads ──
             commands::squad::engine_squads_list,
             commands::squad::engine_squad_create,
@@ -724,6 +1024,35 @@ pub fn run() {
             commands::squad::engine_squad_remove_member,
             commands::squad::engine_agent_messages,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |app_handle, event| match event {
+            // Focus the window for handoffs from later launches (those that
+            // arrived during startup were queued by the guard)
+            tauri::RunEvent::Ready => {
+                if let Some(guard) = instance_guard.as_ref() {
+                    guard.serve(app_handle.clone());
+                }
+            }
+            // ── Graceful shutdown ──────────────────────────────────────
+            // Hold the exit until agent runs, bridges and child processes
+            // are wound down (bounded by SHUTDOWN_TIMEOUT), then exit.
+            tauri::RunEvent::ExitRequested { api, .. } => {
+                if engine::shutdown::is_complete() {
+                    return;
+                }
+                api.prevent_exit();
+                if !engine::shutdown::is_shutting_down() {
+                    let handle = app_handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        engine::shutdown::shutdown(&handle, engine::shutdown::SHUTDOWN_TIMEOUT)
+                            .await;
+                        handle.exit(0);
+                    });
+                }
+            }
+            // Release paw.lock
+            tauri::RunEvent::Exit => drop(instance_guard.take()),
+            _ => {}
+        });
 }
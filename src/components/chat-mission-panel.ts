@@ -247,7 +247,14 @@ const APPROVAL_CATEGORIES: ApprovalCategory[] = [
     label: 'Browser',
     icon: 'travel_explore',
     description: 'Web search, read pages, screenshots',
-    tools: ['web_search', 'web_read', 'web_browse', 'web_screenshot', 'fetch'],
+    tools: [
+      'web_search',
+      'web_read',
+      'web_browse',
+      'web_screenshot',
+      'youtube_transcript',
+      'fetch',
+    ],
   },
   {
     id: 'files',
@@ -17,6 +17,52 @@ export function parseDate(raw: string | Date | undefined | null): Date {
   return isNaN(d.getTime()) ? new Date(0) : d;
 }
 
+// ── Timezone-aware formatting ──────────────────────────────────────────────
+// Timestamps are shown in the timezone configured in Agent Defaults (the one
+// the engine schedules in), not necessarily the OS timezone.
+let _userTimezone: string | undefined;
+
+/** Set the IANA timezone used by formatDateTime / formatTimeOfDay. Invalid names are ignored. */
+export function setUserTimezone(tz: string | undefined): void {
+  if (!tz) {
+    _userTimezone = undefined;
+    return;
+  }
+  try {
+    new Intl.DateTimeFormat(undefined, { timeZone: tz });
+    _userTimezone = tz;
+  } catch {
+    console.warn(`[helpers] Unknown timezone '${tz}' — using the system timezone`);
+    _userTimezone = undefined;
+  }
+}
+
+export function getUserTimezone(): string | undefined {
+  return _userTimezone;
+}
+
+/** Date + time in the user's timezone. */
+export function formatDateTime(
+  date: string | Date | undefined | null,
+  opts: Intl.DateTimeFormatOptions = { dateStyle: 'medium', timeStyle: 'short' },
+): string {
+  return parseDate(date).toLocaleString(undefined, { ...opts, timeZone: _userTimezone });
+}
+
+/** Date only, in the user's timezone. */
+export function formatDate(date: string | Date | undefined | null): string {
+  return parseDate(date).toLocaleDateString(undefined, { timeZone: _userTimezone });
+}
+
+/** Time of day (HH:MM), in the user's timezone. */
+export function formatTimeOfDay(date: string | Date | undefined | null): string {
+  return parseDate(date).toLocaleTimeString(undefined, {
+    hour: '2-digit',
+    minute: '2-digit',
+    timeZone: _userTimezone,
+  });
+}
+
 // ── Material Symbols icon helper ───────────────────────────────────────────
 const _iconMap: Record<string, string> = {
   paperclip: 'attach_file',
@@ -400,5 +446,5 @@ export function formatTimeAgo(date: string | Date): string {
   if (seconds < 3600) return `${Math.floor(seconds / 60)}m ago`;
   if (seconds < 86400) return `${Math.floor(seconds / 3600)}h ago`;
   if (seconds < 2592000) return `${Math.floor(seconds / 86400)}d ago`;
-  return d.toLocaleDateString();
+  return formatDate(d);
 }
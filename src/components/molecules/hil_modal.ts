@@ -12,6 +12,8 @@
 //   7. Type-to-confirm for critical-risk tools
 //   8. Network audit info banner
 //   9. OS notification when approval is pending
+//  10. Consent tools (screen capture) — asked every time, or allowed for
+//      one chat session; never covered by "Always Allow" or overrides
 
 import { onEngineToolApproval, resolveEngineToolApproval } from '../../engine-bridge';
 import type { EngineEvent } from '../../engine';
@@ -33,6 +35,10 @@ import { logCredentialActivity, logSecurityEvent } from '../../db';
 import { showToast } from '../toast';
 import { pushNotification } from '../notifications';
 import { escHtml } from '../molecules/markdown';
+import { pawEngine } from '../../engine';
+
+/** Tools that look at the user's machine; see `tool_metadata::needs_consent`. */
+const CONSENT_TOOLS = ['screen_capture'];
 
 // ── Persist "Always Allow" per tool in localStorage ─────────────────
 const ALWAYS_ALLOW_KEY = 'paw-always-allow-tools';
@@ -101,6 +107,7 @@ const TOOL_LABELS: Record<string, string> = {
   dex_transfer: 'DEX transfer',
   coinbase_trade: 'Coinbase trade',
   coinbase_transfer: 'Coinbase transfer',
+  screen_capture: 'Capture your screen',
 };
 
 /** Material icon for the tool tier */
@@ -130,11 +137,13 @@ interface BubbleOptions {
     allTargetsLocal: boolean;
   };
   requireTypeToConfirm: boolean;
+  consentTool: boolean;
   onAllow: () => void;
   onDeny: () => void;
   onAlwaysAllow: () => void;
   onAlwaysPattern: () => void;
   onSessionOverride: (mins: number) => void;
+  onSessionConsent: () => void;
 }
 
 function injectChatBubble(opts: BubbleOptions): HTMLElement | null {
@@ -204,9 +213,14 @@ function injectChatBubble(opts: BubbleOptions): HTMLElement | null {
     : '';
 
   // Session override dropdown items (not for dangerous tools)
-  const sessionItems = isDangerous
-    ? ''
-    : `
+  const sessionItems = opts.consentTool
+    ? `
+          <button class="approval-dropdown-item bubble-consent-btn">
+            <span class="ms" style="font-size:14px">screenshot_monitor</span> Allow for this chat session
+          </button>`
+    : isDangerous
+      ? ''
+      : `
           <button class="approval-dropdown-item bubble-session-btn" data-minutes="480">
             <span class="ms" style="font-size:14px">check_circle</span> Allow all for this session
           </button>
@@ -250,11 +264,15 @@ function injectChatBubble(opts: BubbleOptions): HTMLElement | null {
         </button>
         <div class="approval-dropdown-menu" style="display:none">
           ${sessionItems}
-          ${sessionItems ? '<div class="approval-dropdown-divider"></div>' : ''}
+          ${
+            opts.consentTool
+              ? ''
+              : `${sessionItems ? '<div class="approval-dropdown-divider"></div>' : ''}
           <button class="approval-dropdown-item bubble-always-btn">
             <span class="ms" style="font-size:14px">verified</span> Always allow <strong>${escHtml(toolName)}</strong>
           </button>
-          ${patternItemHtml}
+          ${patternItemHtml}`
+          }
         </div>
       </div>
       <button class="btn btn-ghost btn-sm bubble-deny-btn">Skip</button>
@@ -279,6 +297,7 @@ function injectChatBubble(opts: BubbleOptions): HTMLElement | null {
   const dropdownMenu = bubble.querySelector('.approval-dropdown-menu') as HTMLElement | null;
   const typeInput = bubble.querySelector('.bubble-type-input') as HTMLInputElement | null;
   const sessionBtns = bubble.querySelectorAll('.bubble-session-btn');
+  const consentBtn = bubble.querySelector('.bubble-consent-btn');
 
   const resolve = (approved: boolean) => {
     bubble.classList.add('resolved');
@@ -335,6 +354,11 @@ function injectChatBubble(opts: BubbleOptions): HTMLElement | null {
     resolve(true);
     opts.onAlwaysPattern();
   });
+  consentBtn?.addEventListener('click', () => {
+    closeDropdown();
+    resolve(true);
+    opts.onSessionConsent();
+  });
   sessionBtns.forEach((btn) => {
     btn.addEventListener('click', () => {
       const mins = parseInt((btn as HTMLElement).dataset.minutes ?? '30', 10);
@@ -383,6 +407,7 @@ export function initHILModal(): void {
       args = undefined;
     }
     const sessionKey = event.session_id ?? '';
+    const consentTool = CONSENT_TOOLS.includes(toolName);
 
     const secSettings = loadSecuritySettings();
     const risk: RiskClassification | null = classifyCommandRisk(toolName, args);
@@ -390,7 +415,7 @@ export function initHILModal(): void {
 
     // ── "Always Allow" check: auto-approve if user previously set it ──
     const alwaysAllowed = getAlwaysAllowedTools();
-    if (alwaysAllowed.includes(toolName) && toolTier !== 'dangerous') {
+    if (alwaysAllowed.includes(toolName) && toolTier !== 'dangerous' && !consentTool) {
       resolveEngineToolApproval(toolCallId, true);
       logCredentialActivity({
         action: 'approved',
@@ -427,7 +452,7 @@ export function initHILModal(): void {
 
     // Session override: auto-approve
     const overrideRemaining = getSessionOverrideRemaining();
-    if (overrideRemaining > 0) {
+    if (overrideRemaining > 0 && !consentTool) {
       if (!(secSettings.autoDenyPrivilegeEscalation && isPrivilegeEscalation(toolName, args))) {
         resolveEngineToolApproval(toolCallId, true);
         const minsLeft = Math.ceil(overrideRemaining / 60000);
@@ -507,6 +532,7 @@ export function initHILModal(): void {
     // Auto-approve: allowlist (only if no risk)
     if (
       !risk &&
+      !consentTool &&
       secSettings.commandAllowlist.length > 0 &&
       matchesAllowlist(cmdStr, secSettings.commandAllowlist)
     ) {
@@ -599,6 +625,7 @@ export function initHILModal(): void {
       pattern,
       netAudit,
       requireTypeToConfirm: !!(secSettings.requireTypeToCritical && risk?.level === 'critical'),
+      consentTool,
       onAllow: doAllow,
       onDeny: doDeny,
       onAlwaysAllow: doAlwaysAllow,
@@ -618,6 +645,13 @@ export function initHILModal(): void {
           'info',
         );
       },
+      onSessionConsent: () => {
+        doAllow();
+        pawEngine
+          .screenCaptureConsent(sessionKey, true)
+          .then(() => showToast('Screen capture allowed for this chat session', 'info'))
+          .catch((e) => showToast(`Could not save consent: ${e}`, 'error'));
+      },
     });
 
     // Keep reference for external resolution (if needed)
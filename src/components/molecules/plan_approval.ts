@@ -0,0 +1,138 @@
+// src/components/molecules/plan_approval.ts
+// Plan-then-execute approval — inline chat card.
+// Call initPlanApproval() once at app startup.
+//
+//   1. plan_proposed → card with goal, editable step titles, risks
+//   2. Approve sends the (possibly edited) steps; Cancel rejects the plan
+//   3. plan_step_status → per-step status icon on the same card
+
+import { onEnginePlan, resolveEnginePlan } from '../../engine-bridge';
+import type { EngineEvent, PlanStep, PlanStepState } from '../../engine/atoms/types';
+import { escHtml } from '../molecules/markdown';
+
+const STATUS_ICONS: Record<PlanStepState, string> = {
+  running: 'progress_activity',
+  done: 'check_circle',
+  failed: 'error',
+  skipped: 'block',
+};
+
+function injectPlanCard(event: EngineEvent): void {
+  const chatMessages = document.getElementById('chat-messages');
+  const planId = event.plan_id;
+  if (!chatMessages || !planId) return;
+
+  const steps: PlanStep[] = event.steps ?? [];
+  const risks = event.risks ?? [];
+
+  const card = document.createElement('div');
+  card.className = 'chat-approval-bubble plan-approval-card';
+  card.dataset.planId = planId;
+
+  const stepsHtml = steps
+    .map(
+      (step, i) => `
+      <li class="plan-step" data-step-id="${escHtml(step.id)}">
+        <span class="ms plan-step-status" style="font-size:14px">radio_button_unchecked</span>
+        <input type="text" class="plan-step-title" value="${escHtml(step.title)}" data-index="${i}" />
+        ${step.risk ? `<span class="approval-risk-pill risk-medium" title="${escHtml(step.risk)}">risk</span>` : ''}
+        <button class="btn btn-ghost btn-sm plan-step-remove" data-index="${i}" title="Remove step">
+          <span class="ms" style="font-size:14px">close</span>
+        </button>
+      </li>`,
+    )
+    .join('');
+
+  const risksHtml = risks.length
+    ? `<div class="chat-approval-risk-banner risk-medium">
+        <span class="ms" style="font-size:14px">warning</span>
+        <div>${risks.map((r) => `<div>${escHtml(r)}</div>`).join('')}</div>
+      </div>`
+    : '';
+
+  card.innerHTML = `
+    <div class="chat-approval-title">
+      <span class="ms approval-tier-icon">checklist</span>
+      <span class="approval-title-text">Proposed plan</span>
+    </div>
+    <div class="chat-approval-subtitle">${escHtml(event.goal ?? '')}</div>
+    ${risksHtml}
+    <ol class="plan-steps">${stepsHtml}</ol>
+    <div class="chat-approval-buttons">
+      <button class="btn btn-primary btn-sm plan-approve-btn">Run plan</button>
+      <button class="btn btn-ghost btn-sm plan-reject-btn">Cancel</button>
+    </div>
+    <div class="chat-approval-resolved approved">
+      <span class="ms" style="font-size:14px">check_circle</span> Approved
+    </div>
+    <div class="chat-approval-resolved denied">
+      <span class="ms" style="font-size:14px">cancel</span> Cancelled
+    </div>
+  `;
+
+  chatMessages.appendChild(card);
+  chatMessages.scrollTop = chatMessages.scrollHeight;
+
+  const removed = new Set<number>();
+  card.querySelectorAll<HTMLButtonElement>('.plan-step-remove').forEach((btn) => {
+    btn.addEventListener('click', () => {
+      const index = Number(btn.dataset.index);
+      removed.add(index);
+      btn.closest('.plan-step')?.remove();
+    });
+  });
+
+  const finish = (approved: boolean) => {
+    card.classList.add('resolved');
+    card.querySelectorAll<HTMLInputElement>('.plan-step-title').forEach((input) => {
+      input.readOnly = true;
+    });
+    card.querySelectorAll<HTMLElement>('.plan-step-remove').forEach((btn) => btn.remove());
+    const el = card.querySelector(
+      `.chat-approval-resolved.${approved ? 'approved' : 'denied'}`,
+    ) as HTMLElement | null;
+    if (el) el.style.display = 'flex';
+  };
+
+  card.querySelector('.plan-approve-btn')?.addEventListener('click', () => {
+    const edited: PlanStep[] = [];
+    card.querySelectorAll<HTMLInputElement>('.plan-step-title').forEach((input) => {
+      const index = Number(input.dataset.index);
+      const title = input.value.trim();
+      if (removed.has(index) || !title) return;
+      edited.push({ ...steps[index], title });
+    });
+    if (edited.length === 0) {
+      resolveEnginePlan(planId, false);
+      finish(false);
+      return;
+    }
+    resolveEnginePlan(planId, true, edited);
+    finish(true);
+  });
+
+  card.querySelector('.plan-reject-btn')?.addEventListener('click', () => {
+    resolveEnginePlan(planId, false);
+    finish(false);
+  });
+}
+
+function updateStepStatus(event: EngineEvent): void {
+  if (!event.plan_id || !event.step_id || !event.status) return;
+  const card = document.querySelector(
+    `.plan-approval-card[data-plan-id="${CSS.escape(event.plan_id)}"]`,
+  );
+  const step = card?.querySelector(`.plan-step[data-step-id="${CSS.escape(event.step_id)}"]`);
+  const icon = step?.querySelector('.plan-step-status') as HTMLElement | null;
+  if (!icon) return;
+  icon.textContent = STATUS_ICONS[event.status];
+  icon.className = `ms plan-step-status status-${event.status}`;
+  icon.title = event.summary ?? '';
+}
+
+export function initPlanApproval(): void {
+  onEnginePlan((event: EngineEvent) => {
+    if (event.kind === 'plan_proposed') injectPlanCard(event);
+    else if (event.kind === 'plan_step_status') updateStepStatus(event);
+  });
+}
@@ -0,0 +1,32 @@
+// src/components/molecules/safe_mode.ts
+// Safe mode banner — visible while the engine only runs read-only tools
+// and keeps channel bridges disconnected.
+// Call initSafeModeBanner() once at app startup.
+
+import { listen } from '@tauri-apps/api/event';
+import { pawEngine } from '../../engine';
+import { showToast } from '../toast';
+
+function setBanner(enabled: boolean): void {
+  const banner = document.getElementById('safe-mode-banner');
+  if (banner) banner.style.display = enabled ? 'flex' : 'none';
+  document.body.classList.toggle('safe-mode', enabled);
+}
+
+export async function initSafeModeBanner(): Promise<void> {
+  document.getElementById('safe-mode-exit')?.addEventListener('click', async () => {
+    try {
+      await pawEngine.safeModeSet(false);
+      showToast('Safe mode off — restart your channels to reconnect them', 'success');
+    } catch (e) {
+      showToast(`Could not leave safe mode: ${e instanceof Error ? e.message : e}`, 'error');
+    }
+  });
+  listen<{ enabled: boolean }>('safe-mode', (event) => setBanner(event.payload.enabled));
+  try {
+    const config = await pawEngine.getConfig();
+    setBanner(!!config.safe_mode);
+  } catch (e) {
+    console.warn('[safe-mode] Could not read engine config:', e);
+  }
+}
@@ -5,6 +5,7 @@ import {
   markRead,
   markAllRead,
   createNotificationId,
+  categoryKind,
   type Notification,
 } from './atoms';
 
@@ -101,3 +102,16 @@ describe('createNotificationId', () => {
     expect(ids.size).toBe(20);
   });
 });
+
+describe('categoryKind', () => {
+  it('maps routed categories to drawer kinds', () => {
+    expect(categoryKind('task')).toBe('task');
+    expect(categoryKind('pairing')).toBe('channel');
+    expect(categoryKind('mail')).toBe('message');
+  });
+
+  it('falls back to system', () => {
+    expect(categoryKind('budget')).toBe('system');
+    expect(categoryKind('unknown')).toBe('system');
+  });
+});
@@ -15,6 +15,28 @@ export interface Notification {
   navigateTo?: string;
 }
 
+/** `notification` event from the engine's notification router. */
+export interface RoutedNotification {
+  /** "budget" | "pairing" | "task" | "gateway" | "mail" */
+  category: string;
+  title: string;
+  body: string;
+  navigate_to: string | null;
+  /** The category is routed to OS notifications. */
+  os: boolean;
+}
+
+/** Drawer kind for a routed notification category. */
+export function categoryKind(category: string): NotificationKind {
+  const map: Record<string, NotificationKind> = {
+    task: 'task',
+    pairing: 'channel',
+    gateway: 'channel',
+    mail: 'message',
+  };
+  return map[category] ?? 'system';
+}
+
 /** Map notification kind to Material Symbol icon name. */
 export function notificationIcon(kind: NotificationKind): string {
   const map: Record<NotificationKind, string> = {
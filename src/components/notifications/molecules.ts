@@ -11,6 +11,8 @@ import {
   type Notification,
   type NotificationKind,
   createNotificationId,
+  categoryKind,
+  type RoutedNotification,
 } from './atoms';
 
 const $ = (id: string) => document.getElementById(id);
@@ -75,6 +77,28 @@ export function pushNotification(
   if (badge) badgePulse(badge);
 }
 
+// ── Routed notifications (engine notification router) ────────────────────
+
+/** Show a system notification, asking for permission the first time. */
+async function showOSNotification(title: string, body: string): Promise<void> {
+  if (!('Notification' in window)) return;
+  if (Notification.permission === 'default') await Notification.requestPermission();
+  if (Notification.permission === 'granted') {
+    new Notification(`Open Pawz — ${title}`, { body, icon: '/icons/128x128.png' });
+  }
+}
+
+function onRoutedNotification(n: RoutedNotification) {
+  pushNotification(
+    categoryKind(n.category),
+    n.title,
+    n.body || undefined,
+    undefined,
+    n.navigate_to ?? undefined,
+  );
+  if (n.os) showOSNotification(n.title, n.body).catch(() => {});
+}
+
 // ── Drawer ───────────────────────────────────────────────────────────────
 
 /** Toggle drawer visibility. */
@@ -189,6 +213,18 @@ export function initNotifications() {
   _notifications = loadFromStorage();
   updateBadge();
 
+  // Budget, pairing, task, gateway and mail notices routed by the engine
+  const tauriWindow = window as unknown as {
+    __TAURI__?: {
+      event: {
+        listen: <T>(event: string, handler: (event: { payload: T }) => void) => Promise<() => void>;
+      };
+    };
+  };
+  tauriWindow.__TAURI__?.event
+    ?.listen<RoutedNotification>('notification', (event) => onRoutedNotification(event.payload))
+    .catch((e) => console.warn('[notifications] listen error:', e));
+
   $('notification-bell')?.addEventListener('click', (e) => {
     e.stopPropagation();
     toggleDrawer();
@@ -20,6 +20,14 @@ export interface EngineProviderConfig {
   api_key: string;
   base_url?: string;
   default_model?: string;
+  /** Extra JSON merged into request bodies (OpenAI-compatible only), e.g. OpenRouter `provider` / `models`. */
+  extra_body?: Record<string, unknown>;
+  /** Extra HTTP headers sent with every request (OpenAI-compatible only). */
+  extra_headers?: Record<string, string>;
+  /** Use the Responses API (`/responses`) instead of chat completions, falling back when unsupported. */
+  responses_api?: boolean;
+  /** Extra Responses API tools sent as-is, e.g. `{ type: 'file_search', vector_store_ids: [...] }`. */
+  responses_tools?: Record<string, unknown>[];
 }
 
 export interface EngineConfig {
@@ -38,6 +46,165 @@ export interface EngineConfig {
   context_window_tokens?: number;
   /** Weather location for Today dashboard (e.g. "New York"). Auto-detected via IP if empty. */
   weather_location?: string;
+  /** Geocoded home location — weather, local_time / nearby_search, agent context. */
+  home_location?: HomeLocation;
+  /** Opt-in: chat agents see the last focused app/window title (memory only, never stored). */
+  app_context?: boolean;
+  /** IANA timezone (e.g. "Europe/Berlin"). Schedules, reminders and displayed times use it. */
+  user_timezone?: string;
+  /** Request/response hooks applied to every AI provider call (gateway headers, request logging). */
+  provider_middleware?: ProviderMiddlewareConfig;
+  /** Keep-alive and warm-up for local (Ollama) models. */
+  local_models?: LocalModelConfig;
+  /** Distill a playbook from each finished project and show matching ones to new projects. */
+  project_playbooks?: boolean;
+  /** Caps on what orchestrator workers may spend and use. */
+  worker_limits?: WorkerLimits;
+  /** When the agent loop stops a failing tool. */
+  circuit_breaker?: CircuitBreakerConfig;
+  /** Skip side-effect tool calls a retried run already completed. */
+  idempotency?: IdempotencyConfig;
+  /** Watch IMAP folders for new mail (IDLE) and push `mail-new` events. */
+  mail_watch?: MailWatchConfig;
+  /** How budget, pairing, task, gateway and mail notifications are delivered. */
+  notifications?: NotificationConfig;
+  /** Switchable environments; see `ConfigProfile`. */
+  profiles?: ConfigProfile[];
+  /** Name of the profile last switched to. */
+  active_profile?: string | null;
+  /** Read-only mode: side-effect tools are refused and bridges stay disconnected. */
+  safe_mode?: boolean;
+  /** Days without activity before a session moves to cold storage (0 = never). */
+  session_archive_days?: number;
+  /** Batched writes and WAL checkpoint tuning for the engine DB. */
+  db_write?: DbWriteConfig;
+}
+
+/** A named environment applied by `engine_profile_switch`. Unset fields keep the current value. */
+export interface ConfigProfile {
+  name: string;
+  default_provider?: string | null;
+  default_model?: string | null;
+  /** Agent every channel bridge routes to. */
+  default_agent?: string | null;
+  /** Bridges that run under this profile; every other bridge is stopped. */
+  bridges?: string[] | null;
+  daily_budget_usd?: number | null;
+}
+
+/** Caps for one orchestrator delegation. 0 / empty = no cap. */
+export interface WorkerCaps {
+  /** Tool rounds per task (0 = max_tool_rounds). */
+  max_rounds: number;
+  /** Estimated spend per task in USD. */
+  max_cost_usd: number;
+  /** Withheld tool groups: exec, filesystem, web, memory, comms, skills, mcp. */
+  denied_tool_groups: string[];
+}
+
+export interface WorkerLimits {
+  defaults: WorkerCaps;
+  /** Per-specialty caps (e.g. "researcher"), replacing `defaults`. */
+  specialties: Record<string, WorkerCaps>;
+  /** Give each delegation its own empty workspace directory. */
+  isolate_workspaces: boolean;
+}
+
+/** Tool circuit breaker thresholds. 0 = never nudge / never block / blocked for the session. */
+export interface CircuitBreakerConfig {
+  /** Consecutive failures before the model is told to stop retrying a tool. */
+  nudge_after: number;
+  /** Consecutive failures before the tool is blocked. */
+  block_after: number;
+  /** Minutes a blocked tool stays blocked; one more failure after that blocks it again. */
+  cooldown_minutes: number;
+  /** Per-tool overrides (e.g. "web_fetch"); unset fields keep the values above. */
+  tools: Record<string, Partial<Omit<CircuitBreakerConfig, 'tools'>>>;
+}
+
+export interface IdempotencyConfig {
+  enabled: boolean;
+  /** Hours a completed call's key is remembered. */
+  ttl_hours: number;
+}
+
+export interface DbWriteConfig {
+  /** Seconds between flushes of batched usage records and task activity (0 = write immediately). */
+  flush_secs: number;
+  /** WAL pages after which a commit checkpoints (0 = leave it to the background writer). */
+  wal_autocheckpoint_pages: number;
+  /** Seconds between passive checkpoints by the background writer (0 = none). */
+  checkpoint_secs: number;
+  /** The WAL file is truncated back to this size after a checkpoint. */
+  wal_size_limit_mb: number;
+}
+
+export interface MailWatchConfig {
+  enabled: boolean;
+  /** Folders watched on every account. */
+  folders: string[];
+  /** Per-account folders, replacing `folders` (empty = don't watch). */
+  accounts: Record<string, string[]>;
+}
+
+export interface NotificationRoute {
+  /** "os" (system notification), "bridge" or "log" (drawer and log only). */
+  delivery: 'os' | 'bridge' | 'log';
+  /** Bridge for "bridge" delivery: "telegram", "discord" or "webhook". */
+  bridge: string;
+  /** Bridge chat / channel / username (empty = the bridge's default). */
+  target: string;
+  /** Batch into one digest per `digest_minutes`. */
+  digest: boolean;
+}
+
+export interface NotificationConfig {
+  /** Routes by category; unset categories use the built-in default. */
+  routes: Record<string, NotificationRoute>;
+  digest_minutes: number;
+  /** Do-not-disturb windows: notices are queued until the window ends. */
+  dnd: DndWindow[];
+  /** Categories that break through do-not-disturb (critical notices always do). */
+  breakthrough: string[];
+}
+
+/** A do-not-disturb window in the user's timezone. */
+export interface DndWindow {
+  /** "mon".."sun"; empty = every day. */
+  days: string[];
+  /** "HH:MM". */
+  start: string;
+  /** "HH:MM"; before `start` = the next day, equal = the whole day. */
+  end: string;
+}
+
+export interface LocalModelConfig {
+  /** Ollama duration a model stays loaded after a request ("30m", "-1"). Empty = Ollama default. */
+  keep_alive: string;
+  warm_on_session_open: boolean;
+  prewarm_cron: boolean;
+  /** Seconds before a cron task is due to start loading its model. */
+  prewarm_lead_secs: number;
+}
+
+/** A local model loaded ahead of use. */
+export interface ModelWarmUp {
+  model: string;
+  provider_id: string;
+  duration_ms: number;
+}
+
+/** A header added to outbound provider requests. `{model}` / `{provider}` are substituted. */
+export interface HeaderInjection {
+  name: string;
+  value: string;
+  /** Provider ids or kinds this applies to. Empty = all providers. */
+  providers?: string[];
+}
+
+export interface ProviderMiddlewareConfig {
+  headers: HeaderInjection[];
+  log_requests: boolean;
 }
 
 /** Model routing for multi-agent orchestration.
@@ -78,8 +245,23 @@ export interface EngineChatRequest {
   auto_approve_all?: boolean;
   /** Tool names the user has approved via the sidebar Approvals panel. */
   user_approved_tools?: string[];
+  /** Plan-then-execute: propose a plan and wait for approval before acting. */
+  plan_mode?: boolean;
+  /** Handles from `ingestFile` for files dropped into this turn. */
+  ingested?: string[];
 }
 
+/** One step of a plan proposed in plan-then-execute mode. */
+export interface PlanStep {
+  id: string;
+  title: string;
+  description: string;
+  tools: string[];
+  risk?: string | null;
+}
+
+export type PlanStepState = 'running' | 'done' | 'failed' | 'skipped';
+
 export interface EngineChatResponse {
   run_id: string;
   session_id: string;
@@ -98,6 +280,73 @@ export interface EngineSession {
   agent_id?: string;
 }
 
+/** A session in cold storage (one archive file per month of last activity). */
+export interface ArchivedSession extends EngineSession {
+  /** Month of the archive holding it, e.g. "2026-03". */
+  archive: string;
+  /** Search results only: text around the first matching message. */
+  snippet?: string;
+}
+
+/** Result of an archival pass. */
+export interface SessionArchiveReport {
+  sessions: number;
+  messages: number;
+  /** Months whose archive files received sessions. */
+  archives: string[];
+}
+
+/** Per-session toggles for what the context builder includes. */
+export interface ContextControls {
+  include_memories: boolean;
+  include_soul_files: boolean;
+  /** Skill ids to include; null = all enabled skills. */
+  skill_ids: string[] | null;
+  /** Max past messages; null = limited by token budget only. */
+  max_history_messages: number | null;
+}
+
+/** Token accounting from the context builder. */
+export interface ContextBudgetReport {
+  context_window: number;
+  max_output_tokens: number;
+  system_prompt_tokens: number;
+  history_tokens: number;
+  available_for_reply: number;
+  memories_injected: number;
+  messages_included: number;
+  messages_trimmed: number;
+}
+
+/** Prompt layer a section belongs to — sections are emitted in this order. */
+export type PromptLayer = 'platform' | 'global' | 'agent' | 'skills' | 'session' | 'memory';
+
+export interface ContextSectionReport {
+  name: string;
+  layer: PromptLayer;
+  priority: number;
+  tokens: number;
+  status: 'included' | 'fallback' | 'dropped';
+  content: string;
+}
+
+/** Result of engine_context_preview — what a turn would send. */
+export interface ContextPreview {
+  model: string;
+  system_prompt: string | null;
+  sections: ContextSectionReport[];
+  budget: ContextBudgetReport;
+  recalled_memories: number;
+  messages: { role: string; content: string }[];
+}
+
+/** Result of engine_prompt_render — the merged system prompt on its own. */
+export interface RenderedPrompt {
+  model: string;
+  system_prompt: string | null;
+  sections: ContextSectionReport[];
+}
+
 export interface EngineStoredMessage {
   id: string;
   session_id: string;
@@ -113,6 +362,15 @@ export interface EngineStoredMessage {
 
 // ── Events ───────────────────────────────────────────────────────────
 
+/** Category of a failed tool call (see engine::tool_errors). */
+export type ToolErrorKind =
+  | 'auth'
+  | 'rate_limit'
+  | 'not_found'
+  | 'permission'
+  | 'network'
+  | 'invalid_input';
+
 export interface EngineEvent {
   kind:
     | 'delta'
@@ -120,22 +378,31 @@ export interface EngineEvent {
     | 'tool_result'
     | 'complete'
     | 'error'
+    | 'cancelled'
     | 'thinking_delta'
     | 'tool_auto_approved'
     | 'canvas_push'
-    | 'canvas_update';
+    | 'canvas_update'
+    | 'plan_proposed'
+    | 'plan_step_status'
+    | 'confidence'
+    | 'agent_handoff'
+    | 'code_execution'
+    | 'grounding';
   session_id: string;
   run_id: string;
-  // delta + thinking_delta
+  // delta + thinking_delta (+ complete / cancelled: the answer so far)
   text?: string;
   // tool_request
   tool_call?: { id: string; type: string; function: { name: string; arguments: string } };
   /** Tool tier: "safe" | "reversible" | "external" | "dangerous" | "unknown" */
   tool_tier?: string;
-  // tool_result
+  // tool_result (+ code_execution output)
   tool_call_id?: string;
   output?: string;
   success?: boolean;
+  /** Why the tool failed, when the engine could tell */
+  error_kind?: ToolErrorKind;
   // complete
   tool_calls_count?: number;
   usage?: { input_tokens: number; output_tokens: number; total_tokens: number };
@@ -151,6 +418,16 @@ export interface EngineEvent {
   component?: CanvasComponent;
   // canvas_update
   patch?: CanvasComponentPatch;
+  // plan_proposed + plan_step_status
+  plan_id?: string;
+  goal?: string;
+  steps?: PlanStep[];
+  risks?: string[];
+  step_id?: string;
+  index?: number;
+  status?: PlanStepState;
+  /** plan_step_status, agent_handoff */
+  summary?: string;
   // ── Inspector metadata (Phase 4) ──
   /** Current round in the agent loop (on tool_request) */
   round_number?: number;
@@ -158,12 +435,42 @@ export interface EngineEvent {
   loaded_tools?: string[];
   /** Estimated context token count (on tool_request) */
   context_tokens?: number;
+  /** Agent autonomy level, when set (on tool_request + tool_auto_approved) */
+  autonomy?: AutonomyLevel;
   /** Tool execution duration in ms (on tool_result) */
   duration_ms?: number;
   /** Total rounds executed in this turn (on complete) */
   total_rounds?: number;
   /** Max rounds configured (on complete) */
   max_rounds?: number;
+  /** Web sources the answer drew on (on complete) */
+  citations?: Citation[];
+  /** Claims flagged as uncertain by the judge pass (on confidence) */
+  annotations?: ConfidenceAnnotation[];
+  // agent_handoff
+  from_agent?: string;
+  to_agent?: string;
+  /** Agents that have held the session, oldest first */
+  chain?: string[];
+  // code_execution
+  language?: string;
+  code?: string;
+  /** e.g. "OUTCOME_OK"; absent when no result came back */
+  outcome?: string;
+  // grounding
+  queries?: string[];
+  sources?: Citation[];
+}
+
+/** A web source used in an assistant answer, numbered for footnotes. */
+export interface Citation {
+  index: number;
+  url: string;
+  title: string;
+  /** The passage of the source the answer draws on, when one matched. */
+  quote?: string;
+  /** The tool that fetched it (web_read, web_search, ...). */
+  tool: string;
 }
 
 export interface EngineStatus {
@@ -183,6 +490,80 @@ export interface EngineAgentFile {
   updated_at: string;
 }
 
+export interface EngineAgentFileRevision {
+  agent_id: string;
+  file_name: string;
+  version: number;
+  content: string;
+  author: 'user' | 'agent';
+  created_at: string;
+}
+
+/** A soul-file change proposed by the agent's reflection pass. */
+export interface SoulProposal {
+  id: string;
+  agent_id: string;
+  session_id?: string;
+  file_name: string;
+  proposed_content: string;
+  rationale: string;
+  status: 'pending' | 'applied' | 'rejected';
+  created_at: string;
+}
+
+// ── Slash Commands ───────────────────────────────────────────────────
+
+/** A `/command` handled before the agent loop (built-in or from a skill). */
+export interface SlashCommandInfo {
+  name: string;
+  usage: string;
+  description: string;
+  /** "builtin" or the id of the skill that registered it. */
+  source: string;
+}
+
+// ── Prompt Library ───────────────────────────────────────────────────
+
+export type PromptVariableKind = 'text' | 'number' | 'boolean' | 'choice';
+
+/** A declared `{{name}}` placeholder in a prompt template. */
+export interface PromptVariable {
+  name: string;
+  kind: PromptVariableKind;
+  description?: string;
+  default?: string | null;
+  required?: boolean;
+  /** Allowed values for `choice` variables. */
+  options?: string[];
+}
+
+export interface PromptTemplate {
+  id: string;
+  name: string;
+  description: string;
+  body: string;
+  variables: PromptVariable[];
+  created_at: string;
+  updated_at: string;
+}
+
+export type PromptValues = Record<string, string | number | boolean>;
+
+// ── Agent Autonomy ───────────────────────────────────────────────────
+
+export type AutonomyLevel = 'manual' | 'assisted' | 'autonomous';
+
+export interface AutonomyPolicy {
+  level: AutonomyLevel;
+  auto_approve_all: boolean;
+  auto_approve_reversible: boolean;
+  /** Tool groups the agent may not use (e.g. "coinbase", "solana", "dex"). */
+  blocked_domains: string[];
+  budget_multiplier: number;
+  /** May run unattended from cron schedules and event triggers. */
+  allow_triggers: boolean;
+}
+
 // ── Memory ───────────────────────────────────────────────────────────
 
 export interface EngineMemory {
@@ -195,6 +576,18 @@ export interface EngineMemory {
   agent_id?: string;
 }
 
+/** One entry from engine_memory_timeline, oldest first. */
+export interface MemoryTimelineEntry {
+  kind: 'memory' | 'message';
+  id: string;
+  /** ISO 8601, UTC. */
+  timestamp: string;
+  content: string;
+  /** Memory category, or the message role. */
+  label: string;
+  session_id: string | null;
+}
+
 export type EmbeddingProvider = 'auto' | 'ollama' | 'openai' | 'google' | 'provider';
 
 export interface EngineMemoryConfig {
@@ -206,6 +599,103 @@ export interface EngineMemoryConfig {
   auto_capture: boolean;
   recall_limit: number;
   recall_threshold: number;
+  retention?: RetentionRule[];
+  search?: HybridSearchTuning;
+}
+
+/** Hybrid memory search weights. `decay_half_life_days: 0` disables decay. */
+export interface HybridSearchTuning {
+  bm25_weight: number;
+  vector_weight: number;
+  decay_half_life_days: number;
+  mmr_lambda: number;
+}
+
+/** One hybrid-search candidate with the score after each stage. */
+export interface MemorySearchCandidate {
+  id: string;
+  content: string;
+  created_at: string;
+  bm25_raw: number | null;
+  bm25: number | null;
+  vector: number | null;
+  merged: number;
+  decay: number;
+  decayed: number;
+  mmr: number | null;
+  /** 1-based position in the returned results; null if cut. */
+  rank: number | null;
+}
+
+export interface MemorySearchDebug {
+  query: string;
+  tuning: HybridSearchTuning;
+  bm25_count: number;
+  vector_count: number;
+  embedded: boolean;
+  keyword_fallback: boolean;
+  candidates: MemorySearchCandidate[];
+}
+
+/** Per-category retention limit. `category: "*"` applies to all; counts are per agent. */
+export interface RetentionRule {
+  category: string;
+  max_age_days?: number | null;
+  max_count?: number | null;
+}
+
+export interface ForgetFilter {
+  query?: string;
+  category?: string;
+  agent_id?: string;
+  session_id?: string;
+  /** RFC 3339 — only memories created before this time. */
+  before?: string;
+}
+
+export interface ForgetCandidate {
+  id: string;
+  agent_id: string;
+  category: string;
+  created_at: string;
+  preview: string;
+}
+
+export interface ForgetResult {
+  matched: ForgetCandidate[];
+  /** null for a dry run. */
+  report: {
+    episodic_erased: number;
+    semantic_erased: number;
+    entity_refs_removed: number;
+  } | null;
+}
+
+/** Filter for the JSONL memory export. */
+export interface MemoryExportFilter {
+  agent_id?: string;
+  category?: string;
+  /** Every word must appear in the (decrypted) content. */
+  query?: string;
+  /** RFC 3339 — only memories created at or after this time. */
+  since?: string;
+  /** RFC 3339 — only memories created before this time. */
+  before?: string;
+  /** 0.0–1.0 */
+  min_importance?: number;
+  include_embeddings?: boolean;
+  limit?: number;
+}
+
+export interface MemoryImportReport {
+  imported: number;
+  /** Skipped as near-duplicates of stored memories. */
+  duplicates: number;
+  failed: number;
+  /** Lines embedded during the import. */
+  embedded: number;
+  /** "line N: reason", first few only. */
+  errors: string[];
 }
 
 export interface EngineMemoryStats {
@@ -506,6 +996,29 @@ export interface TelemetryModelBreakdown {
   turn_count: number;
 }
 
+/** Metrics `engine_stats_timeseries` can chart. */
+export type StatsMetric = 'messages' | 'tokens' | 'cost' | 'tool_calls' | 'tool_success_rate';
+
+/** One bucket of a stats timeseries (UTC). */
+export interface StatsPoint {
+  /** Bucket start: "YYYY-MM-DD HH:00" (hour) or "YYYY-MM-DD" (day, week from Monday) */
+  bucket: string;
+  value: number;
+  /** Rows behind `value` — a rate with no samples is a gap, not a zero */
+  samples: number;
+}
+
+/** Tool-call argument validation failures of one tool, since startup. */
+export interface ToolValidationStats {
+  tool: string;
+  checked: number;
+  failed: number;
+  /** Failed field paths (e.g. "path", "nodes[1].tool") → count */
+  fields: Record<string, number>;
+  last_error: string | null;
+  last_failed_at: string | null;
+}
+
 /** Turn summary emitted via telemetry-flush Tauri event. */
 export interface TelemetryTurnSummary {
   session_id: string;
@@ -740,6 +1253,17 @@ export interface EngineTaskActivity {
   created_at: string;
 }
 
+/** A one-shot follow-up an agent scheduled via `schedule_followup`. */
+export interface EngineFollowup {
+  id: string;
+  session_id: string;
+  agent_id: string;
+  note: string;
+  due_at: string;
+  status: string; // 'pending' | 'fired' | 'cancelled' | 'skipped'
+  created_at: string;
+}
+
 // ── Orchestrator: Projects ────────────────────────────────────────────
 
 export interface EngineProject {
@@ -749,6 +1273,8 @@ export interface EngineProject {
   status: string; // planning, running, paused, completed, failed
   boss_agent: string;
   agents: EngineProjectAgent[];
+  /** Hard spending limit for the whole project in USD. 0 = none. */
+  budget_usd?: number;
   created_at: string;
   updated_at: string;
 }
@@ -775,6 +1301,46 @@ export interface EngineProjectMessage {
   created_at: string;
 }
 
+/** What happened in a project's latest run. */
+export interface ProjectPostMortem {
+  project_id: string;
+  title: string;
+  goal: string;
+  status: string;
+  started_at: string;
+  finished_at: string;
+  duration_secs: number;
+  delegations: DelegationRecord[];
+  failures: string[];
+  usage: ProjectAgentUsage[];
+  total_cost_usd: number;
+  /** The boss agent's closing summary */
+  summary: string;
+  /** Procedural memory distilled from the run, when playbooks are on */
+  playbook_id?: string;
+}
+
+export interface DelegationRecord {
+  agent_id: string;
+  task: string;
+  delegated_at: string;
+  finished_at?: string;
+  duration_secs?: number;
+  outcome: 'done' | 'failed' | 'unfinished';
+  /** The agent's result or error message */
+  note: string;
+}
+
+export interface ProjectAgentUsage {
+  agent_id: string;
+  model: string;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number;
+  rounds: number;
+  tool_calls: number;
+}
+
 /** A backend-created agent (from project_agents table). */
 export interface BackendAgent {
   project_id: string;
@@ -817,6 +1383,7 @@ export interface TelegramStatus {
   allowed_users: number[];
   pending_users: TelegramPendingUser[];
   dm_policy: string;
+  connectivity?: ConnectionMetrics;
 }
 
 export interface ChannelPendingUser {
@@ -835,6 +1402,36 @@ export interface ChannelStatus {
   allowed_users: string[];
   pending_users: ChannelPendingUser[];
   dm_policy: string;
+  connectivity?: ConnectionMetrics;
+}
+
+/** Per-bridge connection quality since the app started. */
+export interface ConnectionMetrics {
+  state: 'idle' | 'connected' | 'reconnecting' | 'disconnected';
+  disconnect_count: number;
+  /** 1-based attempt of the pending reconnect (0 when connected). */
+  reconnect_attempt: number;
+  reconnect_delay_ms?: number;
+  last_latency_ms?: number;
+  last_error?: string;
+  last_connected_at?: string;
+  last_disconnected_at?: string;
+}
+
+/** Payload of the `channel-connectivity` event. */
+export interface ChannelConnectivityEvent extends ConnectionMetrics {
+  channel: string;
+  /** Reconnecting, or connected with high latency. */
+  degraded: boolean;
+}
+
+export interface ChannelPing {
+  channel: string;
+  /** URL or `host:port` that was probed. */
+  target: string;
+  reachable: boolean;
+  latency_ms?: number;
+  error?: string;
 }
 
 export interface DiscordConfig {
@@ -853,7 +1450,15 @@ export interface IrcConfig {
   tls: boolean;
   nick: string;
   password?: string;
+  /** Entries may carry a key: "#private s3cret" */
   channels_to_join: string[];
+  sasl_mechanism?: IrcSaslMechanism;
+  sasl_username?: string;
+  sasl_password?: string;
+  /** PEM file with client certificate and key, for SASL EXTERNAL */
+  client_cert_path?: string;
+  nickserv_password?: string;
+  networks?: IrcNetwork[];
   enabled: boolean;
   dm_policy: string;
   allowed_users: string[];
@@ -862,6 +1467,24 @@ export interface IrcConfig {
   respond_in_channels: boolean;
 }
 
+export type IrcSaslMechanism = 'none' | 'plain' | 'external';
+
+export interface IrcNetwork {
+  name: string;
+  server: string;
+  port?: number;
+  tls?: boolean;
+  nick: string;
+  password?: string;
+  channels_to_join?: string[];
+  sasl_mechanism?: IrcSaslMechanism;
+  sasl_username?: string;
+  sasl_password?: string;
+  client_cert_path?: string;
+  nickserv_password?: string;
+  enabled?: boolean;
+}
+
 export interface SlackConfig {
   bot_token: string;
   app_token: string;
@@ -871,6 +1494,9 @@ export interface SlackConfig {
   pending_users: ChannelPendingUser[];
   agent_id?: string;
   respond_to_mentions: boolean;
+  reply_in_threads?: boolean;
+  /** Channel ID that tool approvals are posted to with Approve / Deny buttons */
+  approvals_channel?: string | null;
 }
 
 export interface MatrixConfig {
@@ -927,6 +1553,96 @@ export interface TwitchConfig {
   pending_users: ChannelPendingUser[];
   agent_id?: string;
   require_mention: boolean;
+  commands?: TwitchCommand[];
+  channel_points?: boolean;
+  broadcaster_token?: string;
+  redemption_triggers?: TwitchRedemptionTrigger[];
+}
+
+export type TwitchCommandPermission = 'any' | 'subscriber' | 'moderator' | 'broadcaster';
+
+export interface TwitchCommand {
+  name: string;
+  prompt: string;
+  permission: TwitchCommandPermission;
+  cooldown_secs: number;
+  user_cooldown_secs: number;
+  enabled: boolean;
+}
+
+export interface TwitchRedemptionTrigger {
+  reward: string;
+  prompt: string;
+  enabled: boolean;
+}
+
+/** An expiring token that signs one guest in to the web chat page. */
+export interface WebChatGuestToken {
+  id: string;
+  token: string;
+  label: string;
+  /** Name the guest chats as; they pick one when unset. */
+  name?: string | null;
+  created_at: string;
+  expires_at: string;
+}
+
+export interface WebChatShareInfo {
+  url: string;
+  /** `url` with the token in the fragment (`#t=…`). */
+  share_url: string;
+  /** QR code of `share_url`, base64 PNG. */
+  qr_png_base64: string;
+  qr_path: string;
+  /** Set when a guest token was just created. */
+  guest?: WebChatGuestToken | null;
+  guest_tokens: WebChatGuestToken[];
+  /** SHA-256 of a self-signed certificate, for visitors to compare. */
+  tls_fingerprint?: string | null;
+  warnings: string[];
+}
+
+/** How web chat is reached from the internet. */
+export type WebChatRemoteMode = 'off' | 'upnp' | 'cloudflared' | 'tailscale_funnel';
+
+export interface WebChatRemoteStatus {
+  mode: WebChatRemoteMode;
+  active: boolean;
+  public_url?: string | null;
+  /** e.g. "UPnP port mapping" or "cloudflared quick tunnel". */
+  detail: string;
+  error?: string | null;
+  /** Risks of exposing the page with the current settings. */
+  warnings: string[];
+  cloudflared_installed: boolean;
+  tailscale_installed: boolean;
+}
+
+/** Let's Encrypt settings in the web chat config. */
+export interface WebChatAcmeSettings {
+  domain: string;
+  email: string;
+  challenge: 'http-01' | 'dns-01';
+  http_port: number;
+  cloudflare_api_token: string;
+  staging: boolean;
+  auto_renew: boolean;
+}
+
+export interface WebChatTlsCertInfo {
+  cert_path: string;
+  key_path: string;
+  source: 'self_signed' | 'acme' | 'custom';
+  subject: string;
+  issuer: string;
+  /** DNS names and IPs the certificate covers. */
+  hosts: string[];
+  not_before: string;
+  not_after: string;
+  days_left: number;
+  self_signed: boolean;
+  /** `AB:CD:…` — matches what browsers show. */
+  fingerprint_sha256: string;
 }
 
 export interface WhatsAppConfig {
@@ -946,6 +1662,19 @@ export interface WhatsAppConfig {
   qr_code?: string;
 }
 
+export interface EmailBridgeConfig {
+  enabled: boolean;
+  account: string;
+  folder: string;
+  dm_policy: string;
+  allowed_users: string[];
+  /** Receiving server's Authentication-Results authserv-id (e.g. "mx.google.com"). */
+  authserv_id: string;
+  pending_users: ChannelPendingUser[];
+  agent_id?: string;
+  allow_dangerous_tools: boolean;
+}
+
 export interface DiscourseConfig {
   url: string;
   api_key: string;
@@ -1006,6 +1735,25 @@ export interface WorkspaceFile {
   modified_at: string;
 }
 
+/** A file fetched by the download_file tool. */
+export interface DownloadRecord {
+  id: string;
+  agent_id: string;
+  url: string;
+  path: string;
+  size_bytes: number;
+  sha256: string;
+  content_type?: string | null;
+  created_at: string;
+}
+
+export interface DownloadProgress {
+  url: string;
+  downloaded: number;
+  total?: number | null;
+  done?: boolean;
+}
+
 // ── Network Policy (Outbound Domain Allowlist) ────────────────────────
 
 export interface NetworkPolicy {
@@ -1124,6 +1872,8 @@ export interface N8nEngineStatus {
 export interface N8nStatusEvent {
   kind: 'provisioning' | 'downloading' | 'starting' | 'ready' | 'error' | 'healthy' | 'unhealthy';
   message: string;
+  /** 0–1 fraction while a download is in flight. */
+  progress?: number;
 }
 
 // ── MCP Servers (Phase E) ────────────────────────────────────────────
@@ -1149,6 +1899,529 @@ export interface McpServerStatus {
   tool_count: number;
 }
 
+// ── Tool RAG Index ───────────────────────────────────────────────────
+
+export type ToolRagSearchTier = 'local_embedding' | 'cloud_embedding' | 'bm25' | 'domain_keyword';
+
+export interface ToolRagConfig {
+  top_k: number;
+  max_results: number;
+  min_relevance: number;
+  domain_expand_threshold: number;
+  auto_prune: boolean;
+  prune_top_k: number;
+}
+
+export interface ToolRagCandidate {
+  tool_name: string;
+  domain: string;
+  vector_score: number | null;
+  bm25_score: number;
+  selected: boolean;
+}
+
+export interface ToolRagDebugReport {
+  query: string;
+  tier: ToolRagSearchTier;
+  target_domain: string | null;
+  target_domain_score: number | null;
+  indexed_tools: number;
+  embedded_tools: number;
+  candidates: ToolRagCandidate[];
+  selected: string[];
+  config: ToolRagConfig;
+}
+
+export interface ToolRagRebuildReport {
+  total: number;
+  embedded: number;
+  failed: number;
+  tier: ToolRagSearchTier;
+}
+
+// ── Worker Delegation ──────────────────────────────────────────────────
+
+export interface NamedWorker {
+  name: string;
+  model: string;
+  instructions?: string | null;
+}
+
+export interface DelegationRule {
+  tools: string[];
+  worker: string;
+  min_payload_chars: number;
+  max_result_chars: number;
+  summarize_instructions?: string | null;
+  enabled: boolean;
+}
+
+export interface ResultSummarization {
+  enabled: boolean;
+  threshold_tokens: number;
+  worker: string;
+  exclude_tools: string[];
+}
+
+export interface WorkerDelegationConfig {
+  enabled: boolean;
+  workers: NamedWorker[];
+  rules: DelegationRule[];
+  summarization: ResultSummarization;
+}
+
+export interface WorkerStats {
+  delegations: number;
+  failures: number;
+  raw_tokens: number;
+  returned_tokens: number;
+  tokens_saved: number;
+}
+
+// ── Credential Import/Export ────────────────────────────────────────────
+
+export interface CredentialItem {
+  id: string;
+  kind: 'skill' | 'nostr_key' | 'channel';
+  label: string;
+}
+
+export interface CredentialImportReport {
+  imported: string[];
+  skipped: string[];
+  failed: [string, string][];
+  restart_channels: string[];
+}
+
+// ── Diagnostics ─────────────────────────────────────────────────────────
+
+export type SelfTestStatus = 'ok' | 'warn' | 'fail';
+
+export interface SelfTestCheck {
+  category: 'providers' | 'bridges' | 'keychain' | 'database' | 'config';
+  name: string;
+  status: SelfTestStatus;
+  detail: string;
+}
+
+export type DbMaintenanceAction =
+  | 'integrity_check'
+  | 'incremental_vacuum'
+  | 'reindex_fts'
+  | 'migration_status'
+  | 'scheduled';
+
+export interface MigrationRun {
+  ran_at: string;
+  duration_ms: number;
+  /** e.g. "table followups", "column tasks.event_trigger" */
+  changes: string[];
+}
+
+export interface DbMaintenanceReport {
+  action: DbMaintenanceAction;
+  ok: boolean;
+  details: string[];
+  duration_ms: number;
+  size_before_bytes: number;
+  size_after_bytes: number;
+  migrations?: MigrationRun[];
+}
+
+/** A child process Paw started (see `engine::processes`). */
+export interface ManagedProcess {
+  pid: number;
+  /** e.g. "n8n", "ollama", "mcp:npx", "cloudflared", "browser", "exec" */
+  purpose: string;
+  program: string;
+  started_at: string;
+  owner_pid: number;
+  /** Reconnected to after a restart instead of being killed. */
+  adoptable: boolean;
+  /** Stopped together with everything it started. */
+  group: boolean;
+  /** Boot it was started in (null where the OS has no boot id). */
+  boot_id: string | null;
+  /** OS start time, checked before Paw signals the PID again. */
+  start_time: string | null;
+  alive: boolean;
+}
+
+export interface ProcessUsage {
+  pid: number;
+  /** "paw" for the engine itself, otherwise the ManagedProcess purpose. */
+  purpose: string;
+  /** The process plus everything it spawned. */
+  processes: number;
+  rss_bytes: number;
+  /** Percent of one core since the previous sample; null on the first sample and on Windows. */
+  cpu_percent: number | null;
+}
+
+export interface ResourceUsage {
+  sampled_at: string;
+  engine: ProcessUsage;
+  children: ProcessUsage[];
+  total_rss_bytes: number;
+  system_memory_bytes: number | null;
+  system_available_bytes: number | null;
+  /** Pages read back from swap per second. */
+  swap_in_per_sec: number | null;
+  warnings: string[];
+}
+
+export type ImportSource = 'chatgpt' | 'claude';
+
+export interface ImportOptions {
+  agent_id?: string;
+  model?: string;
+  label_prefix?: string;
+  distill_memories?: boolean;
+  since?: string;
+  limit?: number;
+}
+
+export interface ImportReport {
+  source: ImportSource;
+  conversations_found: number;
+  imported: number;
+  skipped: number;
+  messages_imported: number;
+  memories_captured: number;
+  errors: string[];
+}
+
+export interface ImportProgress {
+  source: ImportSource;
+  done: number;
+  total: number;
+  title?: string;
+}
+
+export type BookmarkBrowser = 'chrome' | 'chromium' | 'brave' | 'edge' | 'firefox';
+
+export interface BookmarkImportOptions {
+  browser: BookmarkBrowser;
+  profile_path?: string;
+  folder?: string;
+  include_history?: boolean;
+  history_min_visits?: number;
+  history_limit?: number;
+  limit?: number;
+  agent_id?: string;
+  titles_only?: boolean;
+}
+
+export interface BookmarkImportReport {
+  browser: string;
+  profile_path: string;
+  found: number;
+  indexed: number;
+  already_indexed: number;
+  fetch_failed: number;
+  errors: string[];
+}
+
+export type IngestKind = 'document' | 'image' | 'sheet' | 'audio';
+
+/** auto = route by type, index = keep in memory, attach = this chat only. */
+export type IngestIntent = 'auto' | 'index' | 'attach';
+
+export interface IngestResult {
+  handle: string;
+  kind: IngestKind;
+  name: string;
+  mime_type: string;
+  size_bytes: number;
+  summary: string;
+  workspace_path?: string | null;
+  memory_ids: string[];
+}
+
+export interface BookmarkImportProgress {
+  done: number;
+  total: number;
+  url?: string;
+}
+
+export type OutboxStatus = 'pending' | 'sending' | 'sent' | 'failed' | 'rejected' | 'expired';
+
+export interface OutboxItem {
+  id: string;
+  agent_id: string;
+  session_id?: string;
+  tool_name: string;
+  /** JSON arguments of the drafted tool call. */
+  arguments: string;
+  summary: string;
+  status: OutboxStatus;
+  result?: string;
+  created_at: string;
+  expires_at?: string;
+  decided_at?: string;
+}
+
+export type OutboxMode = 'off' | 'review' | 'all';
+
+export interface OutboxSettings {
+  mode: OutboxMode;
+  /** 0 = drafts never expire. */
+  expiry_hours: number;
+}
+
+export type AutoTranslateMode = 'off' | 'reply_in_kind' | 'pivot';
+
+export interface TranslateSettings {
+  /** Ollama model for translation; unset = the Ollama provider's default model. */
+  local_model?: string;
+  /** Language the agent works in for `pivot` bridges (ISO 639-1 code). */
+  pivot_language: string;
+  /** Auto-translate mode per bridge, keyed by channel ("telegram", "discord", …). */
+  bridges: Record<string, AutoTranslateMode>;
+}
+
+/** A claim in an assistant answer flagged as uncertain. */
+export interface ConfidenceAnnotation {
+  /** The claim, quoted exactly from the answer. */
+  claim: string;
+  /** Estimated probability the claim is correct, 0–1. */
+  confidence: number;
+  level: 'low' | 'medium';
+  reason: string;
+}
+
+export interface ConfidenceSettings {
+  /** Run the judge pass after every chat answer. */
+  enabled: boolean;
+  /** Ollama model used as judge; unset = the Ollama provider's default model. */
+  local_model?: string;
+}
+
+/** The last focused app outside Paw, as agents see it. */
+export interface ActiveWindow {
+  app: string;
+  title: string;
+  /** RFC 3339 */
+  seen_at: string;
+}
+
+export interface HomeLocation {
+  name: string;
+  region: string;
+  country: string;
+  latitude: number;
+  longitude: number;
+  timezone?: string;
+  /** 'manual' (geocoded) or 'ip' (auto-detected, approximate). */
+  source: string;
+  /** Include the location in agent runtime context. */
+  share_with_agents: boolean;
+}
+
+export type SearchBackend = 'duckduckgo' | 'searxng' | 'brave' | 'kagi';
+
+export interface SearchProviderStatus {
+  backend: SearchBackend;
+  label: string;
+  needs_api_key: boolean;
+  has_api_key: boolean;
+  /** SearxNG instance URL. */
+  base_url?: string;
+  /** 0 = unlimited. */
+  requests_per_minute: number;
+}
+
+export interface SearchProviders {
+  active: SearchBackend;
+  /** Retry on DuckDuckGo when the active backend fails or is rate-limited. */
+  fallback: boolean;
+  providers: SearchProviderStatus[];
+}
+
+export interface SearchProviderUpdate {
+  backend: SearchBackend;
+  /** Omit to keep the stored key; empty string removes it. */
+  apiKey?: string;
+  baseUrl?: string;
+  requestsPerMinute?: number;
+  makeActive?: boolean;
+  fallback?: boolean;
+}
+
+export type NewsDelivery = '' | 'telegram' | 'discord' | 'webhook';
+
+export interface NewsWatch {
+  /** Empty when creating a watch. */
+  id: string;
+  name: string;
+  queries: string[];
+  agent_id: string;
+  /** Task schedule syntax: "every 6h", "daily 08:00". */
+  schedule: string;
+  /** Empty keeps digests in the app only. */
+  deliver_to: NewsDelivery;
+  /** Telegram username / chat ID or Discord channel ID. */
+  deliver_target?: string;
+  enabled: boolean;
+  last_run_at?: string;
+  next_run_at?: string;
+  created_at?: string;
+}
+
+export interface NewsDigest {
+  id: string;
+  watch_id: string;
+  item_count: number;
+  summary: string;
+  status: 'delivered' | 'in_app' | 'failed';
+  error?: string;
+  created_at: string;
+}
+
+export type ResearchStatus = 'running' | 'done' | 'failed' | 'cancelled' | 'interrupted';
+
+export interface ResearchRun {
+  id: string;
+  question: string;
+  agent_id: string;
+  status: ResearchStatus;
+  page_budget: number;
+  max_rounds: number;
+  pages_read: number;
+  /** Workspace-relative path of the finished report. */
+  report_path?: string;
+  error?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ResearchSource {
+  /** Citation number. */
+  id: number;
+  url: string;
+  title: string;
+  read: boolean;
+  error?: string;
+}
+
+export interface ResearchClaim {
+  text: string;
+  /** Index into sub_questions. */
+  sub_question: number;
+  sources: number[];
+}
+
+export interface ResearchState {
+  phase: 'planning' | 'gathering' | 'writing' | 'done';
+  round: number;
+  sub_questions: { question: string; answered: boolean }[];
+  pending_queries: string[];
+  searched: string[];
+  sources: ResearchSource[];
+  claims: ResearchClaim[];
+}
+
+export interface ResearchRunDetail {
+  run: ResearchRun;
+  /** Executing in this process right now. */
+  active: boolean;
+  state: ResearchState;
+  report?: string;
+}
+
+/** Result of switching a session's acting agent. */
+export interface SessionHandoff {
+  session_id: string;
+  from_agent: string;
+  to_agent: string;
+  /** Summary handed to the new agent; absent for empty sessions or if summarizing failed. */
+  summary?: string;
+}
+
+/** A user's rating of one agent run (1 = good, -1 = bad). */
+export interface RunFeedback {
+  run_id: string;
+  session_id?: string;
+  agent_id?: string;
+  model?: string;
+  rating: number;
+  comment: string;
+  created_at: string;
+}
+
+/** Feedback totals for one model, for A/B testing model routing. */
+export interface ModelFeedbackStats {
+  model: string;
+  rated_runs: number;
+  positive: number;
+  negative: number;
+  /** positive / rated_runs, 0–1 */
+  approval_rate: number;
+  avg_cost_usd: number;
+}
+
+/** One side of a compare-mode run; its answer streams as `compare-event`s. */
+export interface CompareRun {
+  run_id: string;
+  model: string;
+  provider_id: string;
+  status: 'running' | 'done' | 'failed';
+  text: string;
+  error?: string;
+  usage?: { input_tokens: number; output_tokens: number; total_tokens: number };
+  cost_usd: number;
+  duration_ms: number;
+}
+
+/** A prompt answered by two models side by side. */
+export interface Comparison {
+  id: string;
+  session_id: string;
+  prompt: string;
+  runs: CompareRun[];
+  /** run_id of the answer the user picked */
+  winner?: string;
+  created_at: string;
+}
+
+export interface SelfTestReport {
+  generated_at: string;
+  app_version: string;
+  os: string;
+  checks: SelfTestCheck[];
+}
+
+export interface DiagnosticsExport {
+  path: string;
+  report: SelfTestReport;
+}
+
+// ── Engine Archive ──────────────────────────────────────────────────────
+
+export interface ArchiveManifest {
+  format: string;
+  version: number;
+  app_version: string;
+  created_at: string;
+  /** Rows per archived table. */
+  tables: Record<string, number>;
+  /** Values still encrypted with the exporting machine's memory key. */
+  encrypted_values: number;
+}
+
+export interface ArchiveExport {
+  path: string;
+  manifest: ArchiveManifest;
+}
+
+export interface ArchiveImport {
+  manifest: ArchiveManifest;
+  /** Rows restored per table. */
+  restored: Record<string, number>;
+  /** Archived tables this database doesn't have. */
+  skipped_tables: string[];
+}
+
 // ── Agent Messages ──────────────────────────────────────────────────────
 
 export interface EngineAgentMessage {
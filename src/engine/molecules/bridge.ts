@@ -3,12 +3,13 @@
 // Extracted from engine-bridge.ts. Import this instead of ../../engine-bridge.
 
 import { pawEngine } from './ipc_client';
-import type { EngineEvent, EngineChatRequest } from '../atoms/types';
+import type { EngineEvent, EngineChatRequest, PlanStep } from '../atoms/types';
 import { getAgentAllowedTools, ALL_TOOLS } from '../../features/agent-policies';
 import { getIntegrationHint } from './auto-discover-bridge';
 import { getUserApprovedTools } from '../../components/chat-mission-panel';
 import { appState } from '../../state';
 import { routeInspectorEvent } from '../../views/inspector/bridge';
+import { showToast } from '../../components/toast';
 
 type AgentEventHandler = (payload: unknown) => void;
 type ToolApprovalHandler = (event: EngineEvent) => void;
@@ -17,6 +18,7 @@ type QueueReadyHandler = (sessionId: string, message: string, model?: string) =>
 let _engineListening = false;
 const _agentHandlers: AgentEventHandler[] = [];
 const _toolApprovalHandlers: ToolApprovalHandler[] = [];
+const _planHandlers: ToolApprovalHandler[] = [];
 let _queueReadyHandler: QueueReadyHandler | null = null;
 
 /** Whether the engine mode is active. */
@@ -43,6 +45,22 @@ export function onEngineToolApproval(handler: ToolApprovalHandler): void {
   _toolApprovalHandlers.push(handler);
 }
 
+/**
+ * Register a handler for plan-then-execute events (plan_proposed, plan_step_status).
+ */
+export function onEnginePlan(handler: ToolApprovalHandler): void {
+  _planHandlers.push(handler);
+}
+
+/**
+ * Approve (optionally with edited steps) or reject a proposed plan.
+ */
+export function resolveEnginePlan(planId: string, approved: boolean, steps?: PlanStep[]): void {
+  pawEngine.planRespond(planId, approved, steps).catch((e) => {
+    console.error('[bridge] Failed to resolve plan:', e);
+  });
+}
+
 /**
  * Register a handler for queue-ready events.
  * The handler is responsible for setting up the streaming UI pipeline
@@ -87,6 +105,18 @@ export async function startEngineBridge(): Promise<void> {
         }
       }
     }
+    if (event.kind === 'plan_proposed' || event.kind === 'plan_step_status') {
+      for (const h of _planHandlers) {
+        try {
+          h(event);
+        } catch (e) {
+          console.error('[bridge] plan handler error:', e);
+        }
+      }
+    }
+    if (event.kind === 'agent_handoff') {
+      showToast(`${event.from_agent} handed this conversation to ${event.to_agent}`, 'info');
+    }
     const agentEvt = translateEngineEvent(event);
     if (agentEvt) {
       for (const h of _agentHandlers) {
@@ -299,12 +329,22 @@ function translateEngineEvent(event: EngineEvent): Record<string, unknown> | nul
               }
             : undefined,
           model: event.model,
+          citations: event.citations,
         },
         runId: event.run_id,
         sessionKey: event.session_id,
         agentId: event.agent_id,
       };
 
+    case 'cancelled':
+      return {
+        stream: 'lifecycle',
+        data: { phase: 'end', text: event.text, cancelled: true },
+        runId: event.run_id,
+        sessionKey: event.session_id,
+        agentId: event.agent_id,
+      };
+
     case 'error':
       return {
         stream: 'error',
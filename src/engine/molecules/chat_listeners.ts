@@ -163,6 +163,8 @@ export function initChatListeners(deps: ChatListenerDeps): void {
     }
     deps.resetTokenMeter();
     deps.loadChatHistory(key);
+    // Local models take a while to load — start before the first message
+    pawEngine.localModelWarm(key, curAgent?.model).catch(() => {});
   });
 
   chatAgentSelect?.addEventListener('change', () => {
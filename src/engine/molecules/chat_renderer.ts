@@ -4,7 +4,7 @@
 // no global DOM lookups. This makes rendering instance-able for mini-hubs.
 
 import { formatMarkdown, wireCodeCopyButtons, escHtml } from '../../components/molecules/markdown';
-import { icon } from '../../components/helpers';
+import { icon, formatTimeOfDay } from '../../components/helpers';
 import { findLastIndex } from '../atoms/chat';
 import { tesseractPlaceholder, activateTesseracts } from '../../components/tesseract';
 import type { Message } from '../../types';
@@ -269,7 +269,7 @@ export function renderSingleMessage(
 
   const time = document.createElement('div');
   time.className = 'message-time';
-  time.textContent = msg.timestamp.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+  time.textContent = formatTimeOfDay(msg.timestamp);
 
   div.appendChild(contentEl);
 
@@ -575,7 +575,7 @@ export function showStreamingMessage(container: HTMLElement, agentName: string):
 
   const time = document.createElement('div');
   time.className = 'message-time';
-  time.textContent = new Date().toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+  time.textContent = formatTimeOfDay(new Date());
 
   div.appendChild(contentEl);
   div.appendChild(time);
@@ -9,12 +9,25 @@ import type {
   EngineChatRequest,
   EngineChatResponse,
   EngineSession,
+  ArchivedSession,
+  SessionArchiveReport,
   EngineStoredMessage,
   EngineEvent,
   EngineStatus,
   EngineAgentFile,
+  EngineAgentFileRevision,
+  SoulProposal,
+  PromptTemplate,
+  PromptVariable,
+  PromptValues,
   EngineMemory,
+  MemoryTimelineEntry,
+  ForgetFilter,
+  MemoryExportFilter,
+  MemoryImportReport,
+  ForgetResult,
   EngineMemoryConfig,
+  MemorySearchDebug,
   EngineMemoryStats,
   OllamaReadyStatus,
   EngineSkillStatus,
@@ -31,14 +44,17 @@ import type {
   TtsConfig,
   EngineTask,
   EngineTaskActivity,
+  EngineFollowup,
   TaskAgent,
   EngineProject,
   EngineProjectAgent,
   EngineProjectMessage,
+  ProjectPostMortem,
   BackendAgent,
   TelegramConfig,
   TelegramStatus,
   ChannelStatus,
+  ChannelPing,
   DiscordConfig,
   IrcConfig,
   SlackConfig,
@@ -47,13 +63,19 @@ import type {
   NextcloudConfig,
   NostrConfig,
   TwitchConfig,
+  WebChatShareInfo,
+  WebChatRemoteMode,
+  WebChatRemoteStatus,
+  WebChatTlsCertInfo,
   WhatsAppConfig,
+  EmailBridgeConfig,
   DiscourseConfig,
   BrowserConfig,
   BrowserProfile,
   ScreenshotEntry,
   WorkspaceInfo,
   WorkspaceFile,
+  DownloadRecord,
   NetworkPolicy,
   TailscaleStatus,
   TailscaleConfig,
@@ -66,6 +88,52 @@ import type {
   N8nEngineStatus,
   McpServerConfig,
   McpServerStatus,
+  ToolRagConfig,
+  ToolRagDebugReport,
+  ToolRagRebuildReport,
+  WorkerDelegationConfig,
+  WorkerStats,
+  SelfTestReport,
+  DiagnosticsExport,
+  DbMaintenanceAction,
+  DbMaintenanceReport,
+  ManagedProcess,
+  ResourceUsage,
+  ImportSource,
+  ImportOptions,
+  ImportReport,
+  BookmarkImportOptions,
+  BookmarkImportReport,
+  IngestIntent,
+  IngestResult,
+  OutboxItem,
+  OutboxStatus,
+  OutboxSettings,
+  TranslateSettings,
+  SearchProviders,
+  SearchProviderUpdate,
+  Citation,
+  ConfidenceSettings,
+  RunFeedback,
+  ModelFeedbackStats,
+  Comparison,
+  SessionHandoff,
+  NewsWatch,
+  NewsDigest,
+  ResearchRun,
+  ResearchRunDetail,
+  HomeLocation,
+  ActiveWindow,
+  CredentialItem,
+  CredentialImportReport,
+  ContextControls,
+  ModelWarmUp,
+  ContextPreview,
+  RenderedPrompt,
+  SlashCommandInfo,
+  PlanStep,
+  AutonomyLevel,
+  AutonomyPolicy,
   SkillOutput,
   CanvasComponentRow,
   DashboardRow,
@@ -75,6 +143,9 @@ import type {
   TelemetryMetricRow,
   TelemetryDailySummary,
   TelemetryModelBreakdown,
+  StatsMetric,
+  StatsPoint,
+  ToolValidationStats,
   EngineSquad,
   EngineSquadMember,
   EngineAgentMessage,
@@ -91,6 +162,9 @@ import type {
   ForgeDomainSummary,
   MemoryEdge,
   EmbeddingProjection,
+  ConfigProfile,
+  ArchiveExport,
+  ArchiveImport,
 } from '../atoms/types';
 
 export class PawEngineClient {
@@ -159,10 +233,20 @@ export class PawEngineClient {
     return invoke<void>('engine_chat_abort', { sessionId });
   }
 
+  /** Stop a run but keep its partial answer (ends with a `cancelled` event). */
+  async chatCancel(sessionId: string, runId: string): Promise<void> {
+    return invoke<void>('engine_chat_cancel', { sessionId, runId });
+  }
+
   async chatHistory(sessionId: string, limit?: number): Promise<EngineStoredMessage[]> {
     return invoke<EngineStoredMessage[]>('engine_chat_history', { sessionId, limit: limit ?? 200 });
   }
 
+  /** Web citations of a session's assistant messages, keyed by message id. */
+  async chatCitations(sessionId: string): Promise<Record<string, Citation[]>> {
+    return invoke<Record<string, Citation[]>>('engine_chat_citations', { sessionId });
+  }
+
   // ── Sessions ─────────────────────────────────────────────────────────
 
   async sessionsList(limit?: number, agentId?: string): Promise<EngineSession[]> {
@@ -176,6 +260,11 @@ export class PawEngineClient {
     return invoke('engine_session_rename', { sessionId, label });
   }
 
+  /** Switch the session's acting agent; the new agent gets a handoff summary. */
+  async sessionSetAgent(sessionId: string, agentId: string): Promise<SessionHandoff> {
+    return invoke<SessionHandoff>('engine_session_set_agent', { sessionId, agentId });
+  }
+
   async sessionDelete(sessionId: string): Promise<void> {
     return invoke('engine_session_delete', { sessionId });
   }
@@ -184,6 +273,50 @@ export class PawEngineClient {
     return invoke('engine_session_clear', { sessionId });
   }
 
+  async sessionContextControlsGet(sessionId: string): Promise<ContextControls> {
+    return invoke<ContextControls>('engine_session_context_controls_get', { sessionId });
+  }
+
+  async sessionContextControlsSet(sessionId: string, controls: ContextControls): Promise<void> {
+    return invoke('engine_session_context_controls_set', { sessionId, controls });
+  }
+
+  async contextPreview(sessionId: string, draftMessage: string): Promise<ContextPreview> {
+    return invoke<ContextPreview>('engine_context_preview', { sessionId, draftMessage });
+  }
+
+  async promptRender(
+    agentId: string,
+    sessionId?: string,
+    agentPrompt?: string,
+  ): Promise<RenderedPrompt> {
+    return invoke<RenderedPrompt>('engine_prompt_render', {
+      agentId,
+      sessionId: sessionId ?? null,
+      agentPrompt: agentPrompt ?? null,
+    });
+  }
+
+  async slashCommandsList(): Promise<SlashCommandInfo[]> {
+    return invoke<SlashCommandInfo[]>('engine_slash_commands_list');
+  }
+
+  async sessionInstructionsGet(sessionId: string): Promise<string | null> {
+    return invoke<string | null>('engine_session_instructions_get', { sessionId });
+  }
+
+  async sessionInstructionsSet(sessionId: string, instructions: string): Promise<void> {
+    return invoke('engine_session_instructions_set', { sessionId, instructions });
+  }
+
+  /** Load the session's model ahead of the first message. Null unless it runs on Ollama. */
+  async localModelWarm(sessionId?: string, model?: string): Promise<ModelWarmUp | null> {
+    return invoke<ModelWarmUp | null>('engine_local_model_warm', {
+      sessionId: sessionId ?? null,
+      model: model ?? null,
+    });
+  }
+
   async sessionCleanup(maxAgeSecs?: number, excludeId?: string): Promise<number> {
     return invoke<number>('engine_session_cleanup', {
       maxAgeSecs: maxAgeSecs ?? 3600,
@@ -191,6 +324,28 @@ export class PawEngineClient {
     });
   }
 
+  /** Move sessions idle for `olderThanDays` (default: config) into cold storage. */
+  async sessionsArchive(olderThanDays?: number): Promise<SessionArchiveReport> {
+    return invoke<SessionArchiveReport>('engine_sessions_archive', {
+      olderThanDays: olderThanDays ?? null,
+    });
+  }
+
+  async sessionsArchivedList(month?: string): Promise<ArchivedSession[]> {
+    return invoke<ArchivedSession[]>('engine_sessions_archived_list', { month: month ?? null });
+  }
+
+  async sessionsArchivedSearch(query: string, limit?: number): Promise<ArchivedSession[]> {
+    return invoke<ArchivedSession[]>('engine_sessions_archived_search', {
+      query,
+      limit: limit ?? null,
+    });
+  }
+
+  async sessionRestore(sessionId: string): Promise<EngineSession> {
+    return invoke<EngineSession>('engine_session_restore', { sessionId });
+  }
+
   async sessionCompact(sessionId: string): Promise<{
     session_id: string;
     messages_before: number;
@@ -212,6 +367,16 @@ export class PawEngineClient {
     return invoke('engine_set_config', { config });
   }
 
+  /** Apply a config profile; bridges it affects are restarted. */
+  async profileSwitch(name: string): Promise<ConfigProfile> {
+    return invoke<ConfigProfile>('engine_profile_switch', { name });
+  }
+
+  /** Turn safe mode on (disconnecting every bridge) or off. */
+  async safeModeSet(enabled: boolean): Promise<void> {
+    return invoke('engine_safe_mode_set', { enabled });
+  }
+
   async upsertProvider(provider: EngineProviderConfig): Promise<void> {
     return invoke('engine_upsert_provider', { provider });
   }
@@ -239,6 +404,11 @@ export class PawEngineClient {
     return invoke('engine_auto_setup');
   }
 
+  /** Approve (optionally with edited steps) or reject a proposed plan. */
+  async planRespond(planId: string, approved: boolean, steps?: PlanStep[]): Promise<void> {
+    return invoke('engine_plan_respond', { planId, approved, steps: steps ?? null });
+  }
+
   async approveTool(toolCallId: string, approved: boolean): Promise<void> {
     return invoke('engine_approve_tool', { toolCallId, approved });
   }
@@ -264,6 +434,111 @@ export class PawEngineClient {
     return invoke('engine_agent_file_delete', { agentId: agentId ?? 'default', fileName });
   }
 
+  async agentFileHistory(fileName: string, agentId?: string): Promise<EngineAgentFileRevision[]> {
+    return invoke<EngineAgentFileRevision[]>('engine_agent_file_history', {
+      agentId: agentId ?? 'default',
+      fileName,
+    });
+  }
+
+  /** Returns the new version, or null if the file already had that content. */
+  async agentFileRevert(
+    fileName: string,
+    version: number,
+    agentId?: string,
+  ): Promise<number | null> {
+    return invoke<number | null>('engine_agent_file_revert', {
+      agentId: agentId ?? 'default',
+      fileName,
+      version,
+    });
+  }
+
+  // ── Agent Autonomy ───────────────────────────────────────────────────
+
+  async agentAutonomyGet(agentId: string): Promise<AutonomyPolicy> {
+    return invoke<AutonomyPolicy>('engine_agent_autonomy_get', { agentId });
+  }
+
+  async agentAutonomySet(agentId: string, level: AutonomyLevel): Promise<AutonomyPolicy> {
+    return invoke<AutonomyPolicy>('engine_agent_autonomy_set', { agentId, level });
+  }
+
+  // ── Agent Self-Reflection ────────────────────────────────────────────
+
+  async agentReflectionGet(agentId: string): Promise<boolean> {
+    return invoke<boolean>('engine_agent_reflection_get', { agentId });
+  }
+
+  async agentReflectionSet(agentId: string, enabled: boolean): Promise<void> {
+    return invoke('engine_agent_reflection_set', { agentId, enabled });
+  }
+
+  // ── Search Grounding ─────────────────────────────────────────────────
+
+  /** Whether the agent lets models use built-in web search (Gemini Google Search, OpenAI web_search). */
+  async agentGroundingGet(agentId: string): Promise<boolean> {
+    return invoke<boolean>('engine_agent_grounding_get', { agentId });
+  }
+
+  async agentGroundingSet(agentId: string, enabled: boolean): Promise<void> {
+    return invoke('engine_agent_grounding_set', { agentId, enabled });
+  }
+
+  async soulProposalsList(agentId?: string): Promise<SoulProposal[]> {
+    return invoke<SoulProposal[]>('engine_soul_proposals_list', { agentId });
+  }
+
+  /** Returns the new file version when the proposal was applied. */
+  async soulProposalResolve(proposalId: string, approve: boolean): Promise<number | null> {
+    return invoke<number | null>('engine_soul_proposal_resolve', { proposalId, approve });
+  }
+
+  // ── Prompt Library ───────────────────────────────────────────────────
+
+  async promptsList(): Promise<PromptTemplate[]> {
+    return invoke<PromptTemplate[]>('engine_prompts_list');
+  }
+
+  async promptsSave(template: {
+    id?: string;
+    name: string;
+    description?: string;
+    body: string;
+    variables: PromptVariable[];
+  }): Promise<PromptTemplate> {
+    return invoke<PromptTemplate>('engine_prompts_save', {
+      id: template.id ?? null,
+      name: template.name,
+      description: template.description ?? null,
+      body: template.body,
+      variables: template.variables,
+    });
+  }
+
+  async promptsDelete(id: string): Promise<boolean> {
+    return invoke<boolean>('engine_prompts_delete', { id });
+  }
+
+  async promptsRender(id: string, values: PromptValues): Promise<string> {
+    return invoke<string>('engine_prompts_render', { id, values });
+  }
+
+  async promptsRunTemplate(
+    id: string,
+    values: PromptValues,
+    opts: { sessionId?: string; agentId?: string; model?: string; systemPrompt?: string } = {},
+  ): Promise<EngineChatResponse> {
+    return invoke<EngineChatResponse>('engine_prompts_run_template', {
+      id,
+      values,
+      sessionId: opts.sessionId ?? null,
+      agentId: opts.agentId ?? null,
+      model: opts.model ?? null,
+      systemPrompt: opts.systemPrompt ?? null,
+    });
+  }
+
   // ── Memory ───────────────────────────────────────────────────────────
 
   async memoryStore(
@@ -279,6 +554,53 @@ export class PawEngineClient {
     return invoke<EngineMemory[]>('engine_memory_search', { query, limit, agentId });
   }
 
+  /** Hybrid search with per-stage scores (BM25, vector, decay, MMR) for tuning. */
+  async memorySearchDebug(
+    query: string,
+    limit?: number,
+    agentId?: string,
+  ): Promise<MemorySearchDebug> {
+    return invoke<MemorySearchDebug>('engine_memory_search_debug', { query, limit, agentId });
+  }
+
+  /** Memories + messages from a time window. `when` is e.g. "last tuesday"; start/end are RFC 3339. */
+  async memoryTimeline(opts: {
+    when?: string;
+    start?: string;
+    end?: string;
+    agentId?: string;
+    limit?: number;
+  }): Promise<MemoryTimelineEntry[]> {
+    return invoke<MemoryTimelineEntry[]>('engine_memory_timeline', opts);
+  }
+
+  /** Knowledge graph as a JSON or GraphML document. */
+  async memoryGraphExport(
+    format: 'json' | 'graphml',
+    agentId?: string,
+    limit?: number,
+  ): Promise<string> {
+    return invoke<string>('engine_memory_graph_export', { format, agentId, limit });
+  }
+
+  /** Memories as JSONL, one per line with decrypted content. */
+  async memoryExport(filter?: MemoryExportFilter): Promise<string> {
+    return invoke<string>('engine_memory_export', { filter });
+  }
+
+  /** Import a JSONL file of memories; `agentId` overrides the file's agents. */
+  async memoryImport(path: string, dedup = true, agentId?: string): Promise<MemoryImportReport> {
+    return invoke<MemoryImportReport>('engine_memory_import', { path, dedup, agentId });
+  }
+
+  async memoryForgetMatching(filter: ForgetFilter, dryRun: boolean): Promise<ForgetResult> {
+    return invoke<ForgetResult>('engine_memory_forget_matching', { filter, dryRun });
+  }
+
+  async memoryRetentionRun(dryRun: boolean): Promise<ForgetResult> {
+    return invoke<ForgetResult>('engine_memory_retention_run', { dryRun });
+  }
+
   async memoryStats(): Promise<EngineMemoryStats> {
     return invoke<EngineMemoryStats>('engine_memory_stats');
   }
@@ -364,6 +686,41 @@ export class PawEngineClient {
     return invoke('engine_message_feedback', { sessionId, messageId, agentId, helpful, context });
   }
 
+  /** Rate an agent run: 1 = good, -1 = bad. Resubmitting replaces the rating. */
+  async feedbackSubmit(runId: string, rating: 1 | -1, comment?: string): Promise<RunFeedback> {
+    return invoke<RunFeedback>('engine_feedback_submit', { runId, rating, comment });
+  }
+
+  async feedbackGet(runId: string): Promise<RunFeedback | null> {
+    return invoke<RunFeedback | null>('engine_feedback_get', { runId });
+  }
+
+  /** Approval rate and cost per model, for comparing routing choices. */
+  async feedbackStats(days?: number): Promise<ModelFeedbackStats[]> {
+    return invoke<ModelFeedbackStats[]>('engine_feedback_stats', { days });
+  }
+
+  // ── Compare mode ─────────────────────────────────────────────────────
+
+  /** Answer `prompt` with both models at once; answers stream as `compare-event`s. */
+  async compareStart(
+    sessionId: string,
+    prompt: string,
+    modelA: string,
+    modelB: string,
+  ): Promise<Comparison> {
+    return invoke<Comparison>('engine_compare_start', { sessionId, prompt, modelA, modelB });
+  }
+
+  async compareGet(compareId: string): Promise<Comparison> {
+    return invoke<Comparison>('engine_compare_get', { compareId });
+  }
+
+  /** Keep the winning answer in the session and rate both runs. */
+  async comparePick(compareId: string, winnerRunId: string, comment?: string): Promise<Comparison> {
+    return invoke<Comparison>('engine_compare_pick', { compareId, winnerRunId, comment });
+  }
+
   // ── Embedding config (legacy Tauri commands) ─────────────────────────
 
   async getEmbeddingProvider(): Promise<string | null> {
@@ -694,6 +1051,15 @@ export class PawEngineClient {
     return invoke<TelemetryModelBreakdown[]>('engine_get_model_breakdown', { date });
   }
 
+  /** A metric over the last `period` ("24h", "7d", "12w"), bucketed by `resolution`. */
+  async statsTimeseries(
+    metric: StatsMetric,
+    period: string,
+    resolution: 'hour' | 'day' | 'week',
+  ): Promise<StatsPoint[]> {
+    return invoke<StatsPoint[]>('engine_stats_timeseries', { metric, period, resolution });
+  }
+
   async listSessionMetrics(sessionId: string): Promise<TelemetryMetricRow[]> {
     return invoke<TelemetryMetricRow[]>('engine_list_session_metrics', { sessionId });
   }
@@ -702,6 +1068,10 @@ export class PawEngineClient {
     return invoke<number>('engine_purge_old_metrics', { cutoffDate });
   }
 
+  async toolValidationStats(): Promise<ToolValidationStats[]> {
+    return invoke<ToolValidationStats[]>('engine_tool_validation_stats');
+  }
+
   // ── PawzHub Registry (Phase F.4) ─────────────────────────────────────
 
   async pawzhubSearch(query: string): Promise<PawzHubEntry[]> {
@@ -822,6 +1192,14 @@ export class PawEngineClient {
     return invoke<string[]>('engine_tasks_cron_tick');
   }
 
+  async followupsList(sessionId?: string): Promise<EngineFollowup[]> {
+    return invoke<EngineFollowup[]>('engine_followups_list', { sessionId });
+  }
+
+  async followupCancel(followupId: string): Promise<boolean> {
+    return invoke<boolean>('engine_followup_cancel', { followupId });
+  }
+
   // ── Flows (Visual Pipelines) ──────────────────────────────────────
 
   async flowsList(): Promise<EngineFlow[]> {
@@ -1136,6 +1514,80 @@ export class PawEngineClient {
     return invoke('engine_whatsapp_remove_user', { userId });
   }
 
+  // ── Email (IMAP IDLE) ────────────────────────────────────────────────
+
+  async emailStart(): Promise<void> {
+    return invoke('engine_email_start');
+  }
+  async emailStop(): Promise<void> {
+    return invoke('engine_email_stop');
+  }
+  async emailStatus(): Promise<ChannelStatus> {
+    return invoke<ChannelStatus>('engine_email_status');
+  }
+  async emailGetConfig(): Promise<EmailBridgeConfig> {
+    return invoke<EmailBridgeConfig>('engine_email_get_config');
+  }
+  async emailSetConfig(config: EmailBridgeConfig): Promise<void> {
+    return invoke('engine_email_set_config', { config });
+  }
+  async emailApproveUser(userId: string): Promise<void> {
+    return invoke('engine_email_approve_user', { userId });
+  }
+  async emailDenyUser(userId: string): Promise<void> {
+    return invoke('engine_email_deny_user', { userId });
+  }
+  async emailRemoveUser(userId: string): Promise<void> {
+    return invoke('engine_email_remove_user', { userId });
+  }
+
+  /** Time a round trip to a channel's platform endpoint. */
+  async channelPing(channel: string): Promise<ChannelPing> {
+    return invoke<ChannelPing>('engine_channel_ping', { channel });
+  }
+
+  // ── Web Chat sharing & remote access ─────────────────────────────────
+
+  /** Share link + QR code for the access token; `rotateToken` replaces it first. */
+  async webchatShareInfo(rotateToken = false): Promise<WebChatShareInfo> {
+    return invoke<WebChatShareInfo>('engine_webchat_share_info', { rotateToken });
+  }
+  async webchatCreateGuestToken(
+    label: string,
+    expiresInHours: number,
+    name?: string,
+  ): Promise<WebChatShareInfo> {
+    return invoke<WebChatShareInfo>('engine_webchat_create_guest_token', {
+      label,
+      name: name ?? null,
+      expiresInHours,
+    });
+  }
+  async webchatRevokeGuestToken(id: string): Promise<void> {
+    return invoke('engine_webchat_revoke_guest_token', { id });
+  }
+  async webchatRemoteStatus(): Promise<WebChatRemoteStatus> {
+    return invoke<WebChatRemoteStatus>('engine_webchat_remote_status');
+  }
+  /** Save the remote access mode; applied at once if Web Chat is running. */
+  async webchatSetRemoteAccess(mode: WebChatRemoteMode): Promise<WebChatRemoteStatus> {
+    return invoke<WebChatRemoteStatus>('engine_webchat_set_remote_access', { mode });
+  }
+  /** The web chat's TLS certificate, or null when TLS is off. */
+  async webchatTlsInfo(): Promise<WebChatTlsCertInfo | null> {
+    return invoke<WebChatTlsCertInfo | null>('engine_webchat_tls_info');
+  }
+  /** Self-signed certificate for localhost, the LAN address and `extraHosts`. */
+  async webchatTlsGenerateSelfSigned(extraHosts?: string[]): Promise<WebChatTlsCertInfo> {
+    return invoke<WebChatTlsCertInfo>('engine_webchat_tls_generate_self_signed', {
+      extraHosts: extraHosts ?? null,
+    });
+  }
+  /** Let's Encrypt certificate for the configured ACME domain. */
+  async webchatTlsIssueAcme(): Promise<WebChatTlsCertInfo> {
+    return invoke<WebChatTlsCertInfo>('engine_webchat_tls_issue_acme');
+  }
+
   // ── Discourse ────────────────────────────────────────────────────────
 
   async discourseStart(): Promise<void> {
@@ -1219,6 +1671,26 @@ export class PawEngineClient {
     return invoke<string>('engine_project_run', { projectId });
   }
 
+  /** Stop delegating; agents already working finish their tasks. */
+  async projectPause(projectId: string): Promise<void> {
+    return invoke('engine_project_pause', { projectId });
+  }
+
+  /** Continue a paused or interrupted project from its message bus. */
+  async projectResume(projectId: string): Promise<void> {
+    return invoke('engine_project_resume', { projectId });
+  }
+
+  /** Hand a past delegation to its agent again; returns the new step's id. */
+  async projectRerunStep(stepId: string): Promise<string> {
+    return invoke<string>('engine_project_rerun_step', { stepId });
+  }
+
+  /** Report of the project's latest finished run, or null before the first one. */
+  async projectPostMortem(projectId: string): Promise<ProjectPostMortem | null> {
+    return invoke<ProjectPostMortem | null>('engine_project_postmortem', { projectId });
+  }
+
   // ── Browser Profiles ─────────────────────────────────────────────────
 
   async browserGetConfig(): Promise<BrowserConfig> {
@@ -1251,6 +1723,15 @@ export class PawEngineClient {
     return invoke('engine_screenshot_delete', { filename });
   }
 
+  /** Allow (or revoke) screen_capture for a chat session without asking each time. */
+  async screenCaptureConsent(sessionId: string, allow: boolean): Promise<void> {
+    return invoke('engine_screen_capture_consent', { sessionId, allow });
+  }
+
+  async screenCaptureConsentGet(sessionId: string): Promise<boolean> {
+    return invoke<boolean>('engine_screen_capture_consent_get', { sessionId });
+  }
+
   // ── Per-Agent Workspaces ─────────────────────────────────────────────
 
   async workspacesList(): Promise<WorkspaceInfo[]> {
@@ -1265,6 +1746,13 @@ export class PawEngineClient {
     return invoke('engine_workspace_delete', { agentId });
   }
 
+  async downloadsList(agentId?: string, limit?: number): Promise<DownloadRecord[]> {
+    return invoke<DownloadRecord[]>('engine_downloads_list', {
+      agentId: agentId ?? null,
+      limit: limit ?? null,
+    });
+  }
+
   // ── Network Policy (Outbound Domain Allowlist) ───────────────────────
 
   async networkGetPolicy(): Promise<NetworkPolicy> {
@@ -1425,6 +1913,262 @@ export class PawEngineClient {
     return invoke<void>('engine_mcp_connect_all');
   }
 
+  // ── Tool RAG Index ─────────────────────────────────────────────────
+
+  async toolRagRebuild(): Promise<ToolRagRebuildReport> {
+    return invoke<ToolRagRebuildReport>('engine_toolrag_rebuild');
+  }
+
+  async toolRagQueryDebug(query: string): Promise<ToolRagDebugReport> {
+    return invoke<ToolRagDebugReport>('engine_toolrag_query_debug', { query });
+  }
+
+  async toolRagGetConfig(): Promise<ToolRagConfig> {
+    return invoke<ToolRagConfig>('engine_toolrag_get_config');
+  }
+
+  async toolRagSetConfig(config: ToolRagConfig): Promise<ToolRagConfig> {
+    return invoke<ToolRagConfig>('engine_toolrag_set_config', { config });
+  }
+
+  // ── Worker Delegation ──────────────────────────────────────────────
+
+  async workerDelegationGetConfig(): Promise<WorkerDelegationConfig> {
+    return invoke<WorkerDelegationConfig>('engine_worker_delegation_get_config');
+  }
+
+  async workerDelegationSetConfig(config: WorkerDelegationConfig): Promise<void> {
+    return invoke<void>('engine_worker_delegation_set_config', { config });
+  }
+
+  async workerDelegationStats(): Promise<Record<string, WorkerStats>> {
+    return invoke<Record<string, WorkerStats>>('engine_worker_delegation_stats');
+  }
+
+  async workerDelegationResetStats(): Promise<void> {
+    return invoke<void>('engine_worker_delegation_reset_stats');
+  }
+
+  // ── Credential Import/Export ───────────────────────────────────────
+
+  async credentialsList(): Promise<CredentialItem[]> {
+    return invoke<CredentialItem[]>('engine_credentials_list');
+  }
+
+  async credentialsExport(passphrase: string, items?: string[]): Promise<string> {
+    return invoke<string>('engine_credentials_export', { passphrase, items: items ?? null });
+  }
+
+  async credentialsInspect(bundle: string, passphrase: string): Promise<CredentialItem[]> {
+    return invoke<CredentialItem[]>('engine_credentials_inspect', { bundle, passphrase });
+  }
+
+  async credentialsImport(
+    bundle: string,
+    passphrase: string,
+    items?: string[],
+  ): Promise<CredentialImportReport> {
+    return invoke<CredentialImportReport>('engine_credentials_import', {
+      bundle,
+      passphrase,
+      items: items ?? null,
+    });
+  }
+
+  // ── Engine Archive ─────────────────────────────────────────────────
+
+  /** Write the whole engine database (credentials excluded) to a portable archive. */
+  async exportAll(path?: string): Promise<ArchiveExport> {
+    return invoke<ArchiveExport>('engine_export_all', { path: path ?? null });
+  }
+
+  /** Restore an archive written by `exportAll` on another machine. */
+  async importAll(path: string): Promise<ArchiveImport> {
+    return invoke<ArchiveImport>('engine_import_all', { path });
+  }
+
+  // ── Diagnostics ────────────────────────────────────────────────────
+
+  async diagnosticsSelfTest(connectivity = true): Promise<SelfTestReport> {
+    return invoke<SelfTestReport>('engine_diagnostics_self_test', { connectivity });
+  }
+
+  async diagnosticsExport(): Promise<DiagnosticsExport> {
+    return invoke<DiagnosticsExport>('engine_diagnostics_export');
+  }
+
+  async dbMaintenance(action: DbMaintenanceAction): Promise<DbMaintenanceReport> {
+    return invoke<DbMaintenanceReport>('engine_db_maintenance', { action });
+  }
+
+  /** Processes Paw started that are still running. */
+  async processesList(): Promise<ManagedProcess[]> {
+    return invoke<ManagedProcess[]>('engine_processes_list');
+  }
+
+  /** Stop a process Paw started, along with whatever it spawned. */
+  async processesKill(pid: number): Promise<void> {
+    return invoke('engine_processes_kill', { pid });
+  }
+
+  /** CPU and memory of Paw and its child processes, plus system memory. */
+  async resourceUsage(): Promise<ResourceUsage> {
+    return invoke<ResourceUsage>('engine_resource_usage');
+  }
+
+  /** Import a ChatGPT or Claude export (zip or conversations.json). Omit `source` to auto-detect. */
+  async importConversations(
+    path: string,
+    source?: ImportSource,
+    options?: ImportOptions,
+  ): Promise<ImportReport> {
+    return invoke<ImportReport>('engine_import_conversations', { path, source, options });
+  }
+
+  /** Fetch and index browser bookmarks (optionally history) into memory. */
+  async importBookmarks(options: BookmarkImportOptions): Promise<BookmarkImportReport> {
+    return invoke<BookmarkImportReport>('engine_import_bookmarks', { options });
+  }
+
+  async ingestFile(path: string, intent?: IngestIntent, agentId?: string): Promise<IngestResult> {
+    return invoke<IngestResult>('engine_ingest_file', { path, intent, agentId });
+  }
+
+  // ── Outbox ───────────────────────────────────────────────────────────
+
+  async outboxList(status?: OutboxStatus): Promise<OutboxItem[]> {
+    return invoke<OutboxItem[]>('engine_outbox_list', { status });
+  }
+
+  async outboxApprove(id: string): Promise<OutboxItem> {
+    return invoke<OutboxItem>('engine_outbox_approve', { id });
+  }
+
+  async outboxReject(id: string, reason?: string): Promise<OutboxItem> {
+    return invoke<OutboxItem>('engine_outbox_reject', { id, reason });
+  }
+
+  async outboxEdit(id: string, args: Record<string, unknown>): Promise<OutboxItem> {
+    return invoke<OutboxItem>('engine_outbox_edit', { id, arguments: args });
+  }
+
+  async outboxGetSettings(): Promise<OutboxSettings> {
+    return invoke<OutboxSettings>('engine_outbox_get_settings');
+  }
+
+  async outboxSetSettings(settings: OutboxSettings): Promise<void> {
+    return invoke<void>('engine_outbox_set_settings', { settings });
+  }
+
+  // ── Translation ──────────────────────────────────────────────────────
+
+  async translateGetSettings(): Promise<TranslateSettings> {
+    return invoke<TranslateSettings>('engine_translate_get_settings');
+  }
+
+  async translateSetSettings(settings: TranslateSettings): Promise<void> {
+    return invoke<void>('engine_translate_set_settings', { settings });
+  }
+
+  async confidenceGetSettings(): Promise<ConfidenceSettings> {
+    return invoke<ConfidenceSettings>('engine_confidence_get_settings');
+  }
+
+  async confidenceSetSettings(settings: ConfidenceSettings): Promise<void> {
+    return invoke<void>('engine_confidence_set_settings', { settings });
+  }
+
+  // ── Home location ────────────────────────────────────────────────────
+
+  async locationSet(query: string): Promise<HomeLocation | null> {
+    return invoke<HomeLocation | null>('engine_location_set', { query });
+  }
+
+  async locationDetect(): Promise<HomeLocation | null> {
+    return invoke<HomeLocation | null>('engine_location_detect');
+  }
+
+  async locationClear(): Promise<void> {
+    return invoke<void>('engine_location_clear');
+  }
+
+  async locationShare(share: boolean): Promise<HomeLocation | null> {
+    return invoke<HomeLocation | null>('engine_location_share', { share });
+  }
+
+  // ── Active-window awareness ──────────────────────────────────────────
+
+  /** Opt in/out of sharing the focused app/window with chat agents. Off clears it. */
+  async appContextSet(enabled: boolean): Promise<void> {
+    return invoke('engine_app_context_set', { enabled });
+  }
+
+  async appContextGet(): Promise<ActiveWindow | null> {
+    return invoke<ActiveWindow | null>('engine_app_context_get');
+  }
+
+  // ── Web search providers ─────────────────────────────────────────────
+
+  async searchProviderGet(): Promise<SearchProviders> {
+    return invoke<SearchProviders>('engine_search_provider_get');
+  }
+
+  async searchProviderSet(update: SearchProviderUpdate): Promise<SearchProviders> {
+    return invoke<SearchProviders>('engine_search_provider_set', { ...update });
+  }
+
+  // ── News watches ─────────────────────────────────────────────────────
+
+  async newsList(): Promise<NewsWatch[]> {
+    return invoke<NewsWatch[]>('engine_news_list');
+  }
+
+  async newsSave(watch: NewsWatch): Promise<NewsWatch> {
+    return invoke<NewsWatch>('engine_news_save', { watch });
+  }
+
+  async newsDelete(id: string): Promise<void> {
+    return invoke<void>('engine_news_delete', { id });
+  }
+
+  /** Run a watch now; null when nothing new was found. */
+  async newsRun(id: string): Promise<NewsDigest | null> {
+    return invoke<NewsDigest | null>('engine_news_run', { id });
+  }
+
+  async newsDigests(watchId?: string, limit?: number): Promise<NewsDigest[]> {
+    return invoke<NewsDigest[]>('engine_news_digests', { watchId, limit });
+  }
+
+  // ── Research runs ────────────────────────────────────────────────────
+
+  async researchStart(
+    question: string,
+    opts: { agentId?: string; pageBudget?: number; maxRounds?: number } = {},
+  ): Promise<ResearchRun> {
+    return invoke<ResearchRun>('engine_research_start', { question, ...opts });
+  }
+
+  async researchList(limit?: number): Promise<ResearchRun[]> {
+    return invoke<ResearchRun[]>('engine_research_list', { limit });
+  }
+
+  async researchGet(id: string): Promise<ResearchRunDetail> {
+    return invoke<ResearchRunDetail>('engine_research_get', { id });
+  }
+
+  async researchCancel(id: string): Promise<boolean> {
+    return invoke<boolean>('engine_research_cancel', { id });
+  }
+
+  async researchResume(id: string): Promise<ResearchRun> {
+    return invoke<ResearchRun>('engine_research_resume', { id });
+  }
+
+  async researchDelete(id: string): Promise<void> {
+    return invoke<void>('engine_research_delete', { id });
+  }
+
   // ── Mail (Himalaya) ────────────────────────────────────────────────
 
   async mailReadConfig(): Promise<string> {
@@ -1440,14 +2184,30 @@ export class PawEngineClient {
     smtpHost: string;
     smtpPort: number;
     password: string;
+    /** XOAUTH2 instead of a password — run `mailOAuthConnect` first. */
+    oauthProvider?: 'google' | 'microsoft' | null;
   }): Promise<void> {
-    return invoke('write_himalaya_config', opts);
+    return invoke('write_himalaya_config', { oauthProvider: null, ...opts });
+  }
+
+  /** Sign in with Google / Microsoft for IMAP + SMTP; the refresh token goes to the keychain. */
+  async mailOAuthConnect(
+    accountName: string,
+    email: string,
+    oauthProvider: 'google' | 'microsoft',
+  ): Promise<void> {
+    return invoke('mail_oauth_connect', { accountName, email, oauthProvider });
   }
 
   async mailRemoveAccount(accountName: string): Promise<void> {
     return invoke('remove_himalaya_account', { accountName });
   }
 
+  /** Test IMAP login + folder list and SMTP login without sending; stops at the failing stage. */
+  async mailTestAccount(accountName: string): Promise<MailAccountTest> {
+    return invoke<MailAccountTest>('test_mail_account', { accountName });
+  }
+
   async mailFetchEmails(account?: string, folder?: string, pageSize?: number): Promise<string> {
     return invoke<string>('fetch_emails', {
       account: account ?? null,
@@ -1469,8 +2229,20 @@ export class PawEngineClient {
     to: string,
     subject: string,
     body: string,
+    options: MailSendOptions = {},
   ): Promise<void> {
-    return invoke('send_email', { account: account ?? null, to, subject, body });
+    return invoke('send_email', {
+      account: account ?? null,
+      to,
+      subject,
+      body,
+      html: options.html ?? null,
+      cc: options.cc ?? null,
+      bcc: options.bcc ?? null,
+      attachments: options.attachments ?? null,
+      replyToId: options.replyToId ?? null,
+      folder: options.folder ?? null,
+    });
   }
 
   async mailMove(account: string | undefined, id: string, folder: string): Promise<void> {
@@ -1566,6 +2338,46 @@ export interface GmailMessage {
   read: boolean;
 }
 
+/** One stage of `mailTestAccount`. */
+export interface MailTestStage {
+  stage: 'dns' | 'tcp' | 'tls' | 'login' | 'folders';
+  ok: boolean;
+  /** What was found, or the error. */
+  detail: string;
+  elapsed_ms: number;
+}
+
+export interface MailServerTest {
+  host: string;
+  port: number;
+  encryption: string;
+  /** Every stage passed. */
+  ok: boolean;
+  stages: MailTestStage[];
+}
+
+export interface MailAccountTest {
+  account: string;
+  imap: MailServerTest;
+  /** null when the account has no SMTP backend. */
+  smtp: MailServerTest | null;
+}
+
+/** Optional parts of an email sent through himalaya. */
+export interface MailSendOptions {
+  /** HTML alternative of the plain-text body. */
+  html?: string;
+  /** Comma-separated recipients. */
+  cc?: string;
+  bcc?: string;
+  /** Absolute file paths. */
+  attachments?: string[];
+  /** Envelope id of the message being replied to (threads the reply). */
+  replyToId?: string;
+  /** Folder of `replyToId`. */
+  folder?: string;
+}
+
 /** Create a new engine client instance — useful for testing or custom wiring. */
 export function createPawEngine(): PawEngineClient {
   return new PawEngineClient();
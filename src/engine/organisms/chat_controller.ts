@@ -165,7 +165,10 @@ function teardownStream(sessionKey: string, reason: string): void {
   console.debug(
     `[chat] Tearing down stream for ${sessionKey.slice(0, 12) || '(empty)'}: ${reason}`,
   );
-  pawEngine.chatAbort(sessionKey).catch(() => {});
+  const stop = stream.runId
+    ? pawEngine.chatCancel(sessionKey, stream.runId)
+    : pawEngine.chatAbort(sessionKey);
+  stop.catch(() => {});
   if (stream.resolve) {
     stream.resolve(stream.content || `(${reason})`);
     stream.resolve = null;
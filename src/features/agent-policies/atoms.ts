@@ -51,16 +51,24 @@ export const ALL_TOOLS = [
   // Core
   'exec',
   'fetch',
+  'download_file',
   'read_file',
   'write_file',
   'list_directory',
   'append_file',
   'delete_file',
+  'archive_extract',
+  'archive_create',
+  'screen_capture',
   // Web
   'web_search',
   'web_read',
   'web_screenshot',
   'web_browse',
+  'youtube_transcript',
+  'audio_summarize',
+  'local_time',
+  'nearby_search',
   // Soul / persona
   'soul_read',
   'soul_write',
@@ -123,6 +131,7 @@ export const ALL_TOOLS = [
   // Inter-agent comms
   'agent_send_message',
   'agent_read_messages',
+  'translate',
   // Squads
   'create_squad',
   'list_squads',
@@ -153,6 +162,9 @@ export const SAFE_TOOLS: readonly string[] = [
   'list_directory',
   'web_search',
   'web_read',
+  'youtube_transcript',
+  'local_time',
+  'nearby_search',
   'memory_search',
   'soul_read',
   'soul_list',
@@ -175,6 +187,7 @@ export const SAFE_TOOLS: readonly string[] = [
   'request_tools',
   'list_tasks',
   'agent_read_messages',
+  'translate',
   'list_squads',
   'skill_search',
   // Canvas (internal UI — zero side effects)
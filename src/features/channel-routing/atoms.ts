@@ -56,6 +56,7 @@ export const ALL_CHANNELS = [
   'nextcloud',
   'nostr',
   'twitch',
+  'email',
   'webchat',
 ] as const;
 
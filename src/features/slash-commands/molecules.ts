@@ -139,8 +139,27 @@ async function executeCommand(cmd: ParsedCommand, ctx: CommandContext): Promise<
       };
 
     case 'agent':
-      // Agent switching — store as override for the next send
+      // Agent switching — store as override for the next send, and hand the
+      // session over so the new agent gets a summary of the conversation
       localStorage.setItem('paw_slash_agent_override', cmd.args);
+      if (ctx.sessionKey) {
+        try {
+          const handoff = await pawEngine.sessionSetAgent(ctx.sessionKey, cmd.args);
+          return {
+            handled: true,
+            systemMessage: handoff.summary
+              ? `Agent switched to **${cmd.args}** with a handoff summary of this conversation.`
+              : `Agent switched to **${cmd.args}**.`,
+            preventDefault: true,
+          };
+        } catch (e) {
+          return {
+            handled: true,
+            systemMessage: `Agent switched to **${cmd.args}** (handoff failed: ${e}).`,
+            preventDefault: true,
+          };
+        }
+      }
       return {
         handled: true,
         systemMessage: `Agent switched to **${cmd.args}**.`,
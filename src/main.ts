@@ -7,10 +7,18 @@ import { initAgentPolicies } from './features/agent-policies/molecules';
 import { initInjectionPolicy } from './features/prompt-injection/molecules';
 import { installErrorBoundary, setErrorHandler } from './error-boundary';
 import { appState, applyModelPricingOverrides } from './state/index';
-import { escHtml, populateModelSelect, promptModal, icon } from './components/helpers';
+import {
+  escHtml,
+  populateModelSelect,
+  promptModal,
+  icon,
+  setUserTimezone,
+} from './components/helpers';
 import { showToast } from './components/toast';
 import { initTheme, getTheme, setTheme } from './components/molecules/theme';
 import { initHILModal } from './components/molecules/hil_modal';
+import { initPlanApproval } from './components/molecules/plan_approval';
+import { initSafeModeBanner } from './components/molecules/safe_mode';
 import {
   initChatListeners,
   switchToAgent,
@@ -159,6 +167,10 @@ async function connectEngine(): Promise<boolean> {
       chatAvatarEl.innerHTML = AgentsModule.spriteAvatar(initAgent.avatar, 32);
 
     refreshModelLabel();
+    pawEngine
+      .getConfig()
+      .then((cfg) => setUserTimezone(cfg.user_timezone))
+      .catch((e) => console.warn('[main] Could not load timezone (non-fatal):', e));
     TasksModule.startCronTimer();
     if (listen) {
       if (unlistenTaskUpdated) {
@@ -170,6 +182,59 @@ async function connectEngine(): Promise<boolean> {
       }).then((fn) => {
         unlistenTaskUpdated = fn;
       });
+      listen<{ service_id: string; error: string }>('oauth-token-failed', (event) => {
+        showToast(
+          `${event.payload.service_id} needs to be reconnected — ${event.payload.error}`,
+          'error',
+        );
+      });
+      listen<{ agent_id: string; file_name: string; version: number }>(
+        'agent-file-changed',
+        (event) => {
+          const { agent_id, file_name, version } = event.payload;
+          showToast(`Agent '${agent_id}' edited its ${file_name} (v${version})`, 'info');
+        },
+      );
+      listen<{ agent_id: string; file_name: string; status: string }>(
+        'soul-proposal',
+        (event) => {
+          const { agent_id, file_name, status } = event.payload;
+          showToast(
+            status === 'applied'
+              ? `Agent '${agent_id}' updated its ${file_name} after reflecting`
+              : `Agent '${agent_id}' proposed a change to its ${file_name} — review it in the agent's settings`,
+            'info',
+          );
+        },
+      );
+      listen<{ phase: string; detail: string }>('shutdown-progress', (event) => {
+        if (event.payload.phase !== 'done') showToast(`${event.payload.detail}…`, 'info');
+      });
+      listen<{ id: string; status: string; summary: string }>('outbox-updated', (event) => {
+        if (event.payload.status === 'pending')
+          showToast(`Drafted for review in the outbox: ${event.payload.summary}`, 'info');
+      });
+      listen<{ name: string; item_count: number; status: string }>('news-digest', (event) => {
+        const { name, item_count, status } = event.payload;
+        showToast(
+          status === 'failed'
+            ? `News digest "${name}" could not be delivered — see its history`
+            : `News digest "${name}": ${item_count} new item${item_count === 1 ? '' : 's'}`,
+          status === 'failed' ? 'warning' : 'info',
+        );
+      });
+      listen<{ kind: string; status?: string; report_path?: string; error?: string }>(
+        'research-event',
+        (event) => {
+          const { kind, status, report_path, error } = event.payload;
+          if (kind !== 'finished') return;
+          if (status === 'done') showToast(`Research report ready: ${report_path}`, 'success');
+          else if (status === 'failed') showToast(`Research run failed: ${error}`, 'error');
+        },
+      );
+      listen<{ kind: string; messages: string[] }>('resource-warning', (event) => {
+        for (const message of event.payload.messages) showToast(message, 'warning');
+      });
     }
 
     pawEngine
@@ -503,6 +568,7 @@ document.addEventListener('DOMContentLoaded', async () => {
     initChatListeners();
     // mountInbox deferred — must run after connectEngine sets wsConnected
     initHILModal();
+    initPlanApproval();
     initCommandPalette({
       getAgents: AgentsModule.getAgents,
       switchView,
@@ -511,6 +577,7 @@ document.addEventListener('DOMContentLoaded', async () => {
     });
     initNotifications();
     initWebhookLog();
+    initSafeModeBanner();
 
     /** Post-setup tasks that run after the wizard (or immediately if returning user). */
     function launchPostSetup() {
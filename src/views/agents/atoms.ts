@@ -39,6 +39,7 @@ export const TOOL_GROUPS: {
     tools: [
       { id: 'exec', name: 'Run Commands', desc: 'Execute shell commands' },
       { id: 'fetch', name: 'HTTP Fetch', desc: 'Make HTTP requests' },
+      { id: 'download_file', name: 'Download File', desc: 'Save large files to disk' },
     ],
   },
   {
@@ -50,6 +51,9 @@ export const TOOL_GROUPS: {
       { id: 'list_directory', name: 'List Directory', desc: 'Browse file listings' },
       { id: 'append_file', name: 'Append File', desc: 'Add content to files' },
       { id: 'delete_file', name: 'Delete File', desc: 'Remove files' },
+      { id: 'archive_extract', name: 'Extract Archive', desc: 'Unpack zip / tar files' },
+      { id: 'archive_create', name: 'Create Archive', desc: 'Pack files into zip / tar' },
+      { id: 'screen_capture', name: 'Screen Capture', desc: 'See your screen (asks first)' },
     ],
   },
   {
@@ -60,6 +64,11 @@ export const TOOL_GROUPS: {
       { id: 'web_read', name: 'Web Read', desc: 'Read web page content' },
       { id: 'web_screenshot', name: 'Web Screenshot', desc: 'Capture screenshots' },
       { id: 'web_browse', name: 'Web Browse', desc: 'Interactive browsing' },
+      { id: 'youtube_transcript', name: 'YouTube Transcript', desc: 'Read video captions' },
+      { id: 'audio_summarize', name: 'Audio Summary', desc: 'Transcribe and summarize podcasts' },
+      { id: 'translate', name: 'Translate', desc: 'Translate text (local model first)' },
+      { id: 'local_time', name: 'Local Time', desc: 'Time at home or any place' },
+      { id: 'nearby_search', name: 'Nearby Search', desc: 'Find places near home' },
     ],
   },
   {
@@ -1,7 +1,7 @@
 // Automations / Cron View — DOM rendering + IPC
 
 import { pawEngine, type EngineTask, type EngineTaskActivity } from '../../engine';
-import { $, escHtml, escAttr, confirmModal, parseDate } from '../../components/helpers';
+import { $, escHtml, escAttr, confirmModal, formatDateTime } from '../../components/helpers';
 import { showToast } from '../../components/toast';
 import { isConnected } from '../../state/connection';
 import { MORNING_BRIEF_PROMPT, isValidSchedule } from './atoms';
@@ -65,8 +65,8 @@ export async function loadCron() {
     let active = 0,
       paused = 0;
     for (const task of cronTasks) {
-      const nextRun = task.next_run_at ? new Date(task.next_run_at).toLocaleString() : '';
-      const lastRun = task.last_run_at ? new Date(task.last_run_at).toLocaleString() : '';
+      const nextRun = task.next_run_at ? formatDateTime(task.next_run_at) : '';
+      const lastRun = task.last_run_at ? formatDateTime(task.last_run_at) : '';
       const agentNames = task.assigned_agents.length
         ? task.assigned_agents.map((a) => a.agent_id).join(', ')
         : (task.assigned_agent ?? 'default');
@@ -138,7 +138,7 @@ function renderActivityCard(activity: EngineTaskActivity, container: HTMLElement
   const statusClass = isFailed ? 'failed' : 'success';
   card.className = `auto-card${isFailed ? ' auto-card-error' : ''}`;
 
-  const timeStr = activity.created_at ? parseDate(activity.created_at).toLocaleString() : '';
+  const timeStr = activity.created_at ? formatDateTime(activity.created_at) : '';
   const kindLabel = activity.kind.replace(/_/g, ' ');
 
   card.innerHTML = `
@@ -35,6 +35,11 @@ describe('isChannelConfigured', () => {
     expect(isChannelConfigured('whatsapp', {})).toBe(false);
   });
 
+  it('email: configured when enabled', () => {
+    expect(isChannelConfigured('email', { enabled: true, account: '' })).toBe(true);
+    expect(isChannelConfigured('email', { account: 'work' })).toBe(false);
+  });
+
   it('discourse: needs url, api_key, and username', () => {
     expect(
       isChannelConfigured('discourse', {
@@ -37,6 +37,7 @@ export const CHANNEL_CLASSES: Record<string, string> = {
   nostr: 'nostr',
   twitch: 'twitch',
   whatsapp: 'whatsapp',
+  email: 'email',
   discourse: 'discourse',
 };
 
@@ -162,7 +163,47 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
         label: 'Channels to Join',
         type: 'text',
         placeholder: '#general, #paw',
-        hint: 'Comma-separated channel names',
+        hint: 'Comma-separated channel names — add a key after a space for keyed channels (#private s3cret)',
+      },
+      {
+        key: 'saslMechanism',
+        label: 'SASL Authentication',
+        type: 'select',
+        options: [
+          { value: 'none', label: 'None' },
+          { value: 'plain', label: 'PLAIN (account + password)' },
+          { value: 'external', label: 'EXTERNAL (TLS client certificate)' },
+        ],
+        defaultValue: 'none',
+      },
+      {
+        key: 'saslUsername',
+        label: 'SASL Account (optional)',
+        type: 'text',
+        placeholder: '',
+        hint: 'Defaults to the nickname',
+      },
+      {
+        key: 'saslPassword',
+        label: 'SASL Password',
+        type: 'password',
+        placeholder: '',
+        sensitive: true,
+      },
+      {
+        key: 'clientCertPath',
+        label: 'Client Certificate (EXTERNAL)',
+        type: 'text',
+        placeholder: '/path/to/paw-bot.pem',
+        hint: 'PEM file containing both the certificate and its private key',
+      },
+      {
+        key: 'nickservPassword',
+        label: 'NickServ Password (optional)',
+        type: 'password',
+        placeholder: '',
+        hint: 'Sent as IDENTIFY when SASL is off or fails',
+        sensitive: true,
       },
     ],
     buildConfig: (v) => ({
@@ -170,6 +211,16 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
       port: parseInt(v.port as string) || 6697,
       tls: v.tls !== false,
       nick: v.nick as string,
+      password: (v.password as string) || null,
+      channels_to_join: ((v.channels as string) || '')
+        .split(',')
+        .map((s) => s.trim())
+        .filter(Boolean),
+      sasl_mechanism: (v.saslMechanism as string) || 'none',
+      sasl_username: ((v.saslUsername as string) || '').trim() || null,
+      sasl_password: (v.saslPassword as string) || null,
+      client_cert_path: ((v.clientCertPath as string) || '').trim() || null,
+      nickserv_password: (v.nickservPassword as string) || null,
       enabled: true,
       dm_policy: 'pairing',
     }),
@@ -179,7 +230,7 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
     name: 'Slack',
     icon: 'SL',
     description:
-      'Connect to Slack via Socket Mode (outbound WebSocket). Create an app at api.slack.com → Enable Socket Mode → get Bot + App tokens.',
+      'Connect to Slack via Socket Mode (outbound WebSocket). Create an app at api.slack.com → Enable Socket Mode → get Bot + App tokens. Add slash commands (e.g. /paw) and the files:read scope in the app config.',
     fields: [
       {
         key: 'botToken',
@@ -216,6 +267,19 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
         type: 'toggle',
         defaultValue: true,
       },
+      {
+        key: 'replyInThreads',
+        label: 'Reply to mentions in a thread',
+        type: 'toggle',
+        defaultValue: true,
+      },
+      {
+        key: 'approvalsChannel',
+        label: 'Approvals Channel ID (optional)',
+        type: 'text',
+        placeholder: 'C0123456789',
+        hint: 'Tool approvals are posted here with Approve / Deny buttons (enable Interactivity). Only allowed users can answer.',
+      },
       { key: 'agentId', label: 'Agent ID (optional)', type: 'text', placeholder: '' },
     ],
     buildConfig: (v) => ({
@@ -224,6 +288,8 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
       enabled: true,
       dm_policy: (v.dmPolicy as string) || 'pairing',
       respond_to_mentions: v.respondToMentions !== false,
+      reply_in_threads: v.replyInThreads !== false,
+      approvals_channel: ((v.approvalsChannel as string) || '').trim() || null,
     }),
   },
   {
@@ -474,6 +540,20 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
         type: 'toggle',
         defaultValue: true,
       },
+      {
+        key: 'channelPoints',
+        label: 'Respond to channel point redemptions',
+        type: 'toggle',
+        defaultValue: false,
+      },
+      {
+        key: 'broadcasterToken',
+        label: 'Broadcaster Token (optional)',
+        type: 'password',
+        placeholder: 'xxxxxxxxxxxxx',
+        hint: 'Needed for channel points when the bot is not the broadcaster — a broadcaster user token with channel:read:redemptions',
+        sensitive: true,
+      },
       { key: 'agentId', label: 'Agent ID (optional)', type: 'text', placeholder: '' },
     ],
     buildConfig: (v) => ({
@@ -486,6 +566,8 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
       enabled: true,
       dm_policy: (v.dmPolicy as string) || 'open',
       require_mention: v.requireMention !== false,
+      channel_points: v.channelPoints === true,
+      broadcaster_token: (v.broadcasterToken as string) || '',
     }),
   },
   {
@@ -569,6 +651,56 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
       respond_in_groups: !!v.respondInGroups,
     }),
   },
+  {
+    id: 'email',
+    name: 'Email',
+    icon: '@',
+    description:
+      'Your agent answers email. It watches a folder of a mail account set up in Mail (IMAP IDLE) and replies in the same thread over SMTP. Automated and list mail is never answered.',
+    fields: [
+      {
+        key: 'account',
+        label: 'Mail account',
+        type: 'text',
+        placeholder: 'Leave blank to use your first IMAP account',
+        hint: 'The account name from Mail → Accounts',
+      },
+      {
+        key: 'folder',
+        label: 'Folder',
+        type: 'text',
+        placeholder: 'INBOX',
+        defaultValue: 'INBOX',
+      },
+      {
+        key: 'dmPolicy',
+        label: 'Who can email your agent?',
+        type: 'select',
+        options: [
+          { value: 'pairing', label: 'New senders need my approval first' },
+          { value: 'allowlist', label: 'Allowlist only' },
+          { value: 'open', label: 'Anyone (not recommended)' },
+        ],
+        defaultValue: 'pairing',
+      },
+      {
+        key: 'authservId',
+        label: 'Mail server authserv-id',
+        type: 'text',
+        placeholder: 'mx.google.com',
+        hint:
+          'The first word of the Authentication-Results header your mail server adds. Only mail it authenticated is answered (not needed for "Anyone").',
+      },
+      { key: 'agentId', label: 'Agent ID (optional)', type: 'text', placeholder: '' },
+    ],
+    buildConfig: (v) => ({
+      account: ((v.account as string) || '').trim(),
+      folder: ((v.folder as string) || '').trim() || 'INBOX',
+      enabled: true,
+      dm_policy: (v.dmPolicy as string) || 'pairing',
+      authserv_id: ((v.authservId as string) || '').trim(),
+    }),
+  },
   {
     id: 'webchat',
     name: 'Web Chat',
@@ -612,6 +744,64 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
         ],
         defaultValue: 'open',
       },
+      {
+        key: 'maxUploadMb',
+        label: 'Max Upload Size (MB)',
+        type: 'text',
+        placeholder: '20',
+        defaultValue: '20',
+        hint: 'Largest file visitors can attach — 0 turns uploads off',
+      },
+      {
+        key: 'apiEnabled',
+        label: 'Enable REST API',
+        type: 'toggle',
+        defaultValue: true,
+        hint: 'POST /api/message with "Authorization: Bearer <access token>" — for scripts and curl',
+      },
+      {
+        key: 'remoteAccess',
+        label: 'Internet Access',
+        type: 'select',
+        options: [
+          { value: 'off', label: 'Off (home network only)' },
+          { value: 'cloudflared', label: 'Cloudflare quick tunnel (needs cloudflared)' },
+          { value: 'tailscale_funnel', label: 'Tailscale Funnel (needs Tailscale)' },
+          { value: 'upnp', label: 'Router port mapping (UPnP / NAT-PMP)' },
+        ],
+        defaultValue: 'off',
+        hint: 'Puts the chat on the public internet — use Pairing, and TLS with port mapping',
+      },
+      {
+        key: 'acmeDomain',
+        label: "Let's Encrypt Domain (optional)",
+        type: 'text',
+        placeholder: 'chat.example.com',
+        hint: 'A domain pointing at this computer — Paw gets and renews an HTTPS certificate for it',
+      },
+      {
+        key: 'acmeEmail',
+        label: "Let's Encrypt Email",
+        type: 'text',
+        placeholder: 'you@example.com',
+      },
+      {
+        key: 'acmeChallenge',
+        label: 'Domain Verification',
+        type: 'select',
+        options: [
+          { value: 'http-01', label: 'HTTP (port 80 must reach this computer)' },
+          { value: 'dns-01', label: 'DNS via Cloudflare (works behind NAT)' },
+        ],
+        defaultValue: 'http-01',
+      },
+      {
+        key: 'acmeCloudflareToken',
+        label: 'Cloudflare API Token',
+        type: 'password',
+        placeholder: '',
+        hint: 'Only for DNS verification — needs Zone.DNS edit permission',
+      },
       { key: 'agentId', label: 'Agent ID (optional)', type: 'text', placeholder: '' },
     ],
     buildConfig: (v) => ({
@@ -619,6 +809,20 @@ export const CHANNEL_SETUPS: ChannelSetupDef[] = [
       bind_address: (v.bindAddress as string) || '0.0.0.0',
       access_token: (v.accessToken as string) || '',
       page_title: (v.pageTitle as string) || 'Paw Chat',
+      max_upload_mb: Math.max(0, parseInt((v.maxUploadMb as string) || '20') || 0),
+      api_enabled: v.apiEnabled !== false,
+      remote_access: (v.remoteAccess as string) || 'off',
+      // Left out when no domain is entered, so saved ACME settings are kept
+      ...(v.acmeDomain
+        ? {
+            acme: {
+              domain: (v.acmeDomain as string).trim(),
+              email: ((v.acmeEmail as string) || '').trim(),
+              challenge: (v.acmeChallenge as string) || 'http-01',
+              cloudflare_api_token: (v.acmeCloudflareToken as string) || '',
+            },
+          }
+        : {}),
       enabled: true,
       dm_policy: (v.dmPolicy as string) || 'open',
     }),
@@ -734,6 +938,8 @@ export function isChannelConfigured(ch: string, config: Record<string, unknown>)
       return !!config.oauth_token && !!config.bot_username;
     case 'whatsapp':
       return !!config.enabled;
+    case 'email':
+      return !!config.enabled;
     case 'discourse':
       return !!config.url && !!config.api_key && !!config.username;
     default:
@@ -786,6 +992,8 @@ export function emptyChannelConfig(ch: string): Record<string, unknown> {
         respond_in_groups: false,
         session_connected: false,
       };
+    case 'email':
+      return { ...base, account: '', folder: 'INBOX', authserv_id: '' };
     case 'discourse':
       return {
         ...base,
@@ -172,6 +172,15 @@ export function initChannels() {
 
 /** Auto-connect all channels that are enabled and have credentials. Called once at startup. */
 export async function autoStartConfiguredChannels(): Promise<void> {
+  try {
+    if ((await pawEngine.getConfig()).safe_mode) {
+      console.debug('[channels] Safe mode is on — not auto-starting bridges');
+      return;
+    }
+  } catch (e) {
+    console.warn('[channels] Could not read engine config:', e);
+  }
+
   try {
     const tgCfg = await pawEngine.telegramGetConfig();
     if (tgCfg.enabled && tgCfg.bot_token) {
@@ -194,6 +203,7 @@ export async function autoStartConfiguredChannels(): Promise<void> {
     'nextcloud',
     'nostr',
     'twitch',
+    'email',
   ] as const;
   for (const ch of channels) {
     try {
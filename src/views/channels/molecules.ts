@@ -42,6 +42,8 @@ export async function getChannelConfig(ch: string): Promise<Record<string, unkno
         return (await pawEngine.twitchGetConfig()) as unknown as Record<string, unknown>;
       case 'whatsapp':
         return (await pawEngine.whatsappGetConfig()) as unknown as Record<string, unknown>;
+      case 'email':
+        return (await pawEngine.emailGetConfig()) as unknown as Record<string, unknown>;
       case 'discourse':
         return (await pawEngine.discourseGetConfig()) as unknown as Record<string, unknown>;
       default:
@@ -72,6 +74,8 @@ export async function setChannelConfig(ch: string, config: Record<string, unknow
       return pawEngine.twitchSetConfig(config as never);
     case 'whatsapp':
       return pawEngine.whatsappSetConfig(config as never);
+    case 'email':
+      return pawEngine.emailSetConfig(config as never);
     case 'discourse':
       return pawEngine.discourseSetConfig(config as never);
   }
@@ -97,6 +101,8 @@ export async function startChannel(ch: string): Promise<void> {
       return pawEngine.twitchStart();
     case 'whatsapp':
       return pawEngine.whatsappStart();
+    case 'email':
+      return pawEngine.emailStart();
     case 'discourse':
       return pawEngine.discourseStart();
   }
@@ -122,6 +128,8 @@ export async function stopChannel(ch: string): Promise<void> {
       return pawEngine.twitchStop();
     case 'whatsapp':
       return pawEngine.whatsappStop();
+    case 'email':
+      return pawEngine.emailStop();
     case 'discourse':
       return pawEngine.discourseStop();
   }
@@ -148,6 +156,8 @@ export async function getChannelStatus(ch: string): Promise<ChannelStatus | null
         return await pawEngine.twitchStatus();
       case 'whatsapp':
         return await pawEngine.whatsappStatus();
+      case 'email':
+        return await pawEngine.emailStatus();
       case 'discourse':
         return await pawEngine.discourseStatus();
       default:
@@ -178,6 +188,8 @@ async function approveChannelUser(ch: string, userId: string): Promise<void> {
       return pawEngine.twitchApproveUser(userId);
     case 'whatsapp':
       return pawEngine.whatsappApproveUser(userId);
+    case 'email':
+      return pawEngine.emailApproveUser(userId);
     case 'discourse':
       return pawEngine.discourseApproveUser(userId);
   }
@@ -203,6 +215,8 @@ async function denyChannelUser(ch: string, userId: string): Promise<void> {
       return pawEngine.twitchDenyUser(userId);
     case 'whatsapp':
       return pawEngine.whatsappDenyUser(userId);
+    case 'email':
+      return pawEngine.emailDenyUser(userId);
     case 'discourse':
       return pawEngine.discourseDenyUser(userId);
   }
@@ -626,6 +640,7 @@ export async function loadChannels() {
       'nostr',
       'twitch',
       'whatsapp',
+      'email',
       'discourse',
     ];
 
@@ -41,6 +41,13 @@ export async function openChannelSetup(channelType: string) {
       if (cfg.dm_policy) existingValues['dmPolicy'] = cfg.dm_policy;
       if (cfg.allowed_users?.length) existingValues['allowFrom'] = cfg.allowed_users.join(', ');
       if (cfg.agent_id) existingValues['agentId'] = cfg.agent_id;
+    } else if (channelType === 'email') {
+      const cfg = await pawEngine.emailGetConfig();
+      if (cfg.account) existingValues['account'] = cfg.account;
+      if (cfg.folder) existingValues['folder'] = cfg.folder;
+      if (cfg.dm_policy) existingValues['dmPolicy'] = cfg.dm_policy;
+      if (cfg.authserv_id) existingValues['authservId'] = cfg.authserv_id;
+      if (cfg.agent_id) existingValues['agentId'] = cfg.agent_id;
     } else if (channelType === 'discord') {
       const cfg = await pawEngine.discordGetConfig();
       if (cfg.bot_token) existingValues['botToken'] = cfg.bot_token;
@@ -54,6 +61,11 @@ export async function openChannelSetup(channelType: string) {
       if (cfg.password) existingValues['password'] = cfg.password;
       if (cfg.channels_to_join?.length)
         existingValues['channels'] = cfg.channels_to_join.join(', ');
+      if (cfg.sasl_mechanism) existingValues['saslMechanism'] = cfg.sasl_mechanism;
+      if (cfg.sasl_username) existingValues['saslUsername'] = cfg.sasl_username;
+      if (cfg.sasl_password) existingValues['saslPassword'] = cfg.sasl_password;
+      if (cfg.client_cert_path) existingValues['clientCertPath'] = cfg.client_cert_path;
+      if (cfg.nickserv_password) existingValues['nickservPassword'] = cfg.nickserv_password;
     } else if (channelType === 'slack') {
       const cfg = await pawEngine.slackGetConfig();
       if (cfg.bot_token) existingValues['botToken'] = cfg.bot_token;
@@ -2,6 +2,8 @@
 // The Inspector is the "Agent X-Ray" panel showing real-time
 // chain-of-thought, tool routing, memory recall, and context utilization.
 
+import type { ToolErrorKind } from '../../engine/atoms/types';
+
 // ── Data Types ────────────────────────────────────────────────────────
 
 /** A single tool call entry in the timeline. */
@@ -26,6 +28,8 @@ export interface InspectorToolEntry {
   tier: string | null;
   /** Whether the tool was auto-approved */
   autoApproved: boolean;
+  /** Why the tool failed, when known */
+  errorKind: ToolErrorKind | null;
 }
 
 /** A thinking trace chunk. */
@@ -180,7 +184,35 @@ export function tierIcon(tier: string | null): string {
 /** Status icon for a tool entry. */
 export function toolStatusIcon(entry: InspectorToolEntry): string {
   if (entry.finishedAt === null) return 'hourglass_top'; // in progress
-  return entry.success ? 'check_circle' : 'error';
+  if (entry.success) return 'check_circle';
+  return entry.errorKind ? ERROR_KIND_ICONS[entry.errorKind] : 'error';
+}
+
+const ERROR_KIND_ICONS: Record<ToolErrorKind, string> = {
+  auth: 'key_off',
+  rate_limit: 'speed',
+  not_found: 'search_off',
+  permission: 'block',
+  network: 'wifi_off',
+  invalid_input: 'rule',
+};
+
+/** Short label for a tool failure kind. */
+export function errorKindLabel(kind: ToolErrorKind): string {
+  switch (kind) {
+    case 'auth':
+      return 'auth failed';
+    case 'rate_limit':
+      return 'rate limited';
+    case 'not_found':
+      return 'not found';
+    case 'permission':
+      return 'denied';
+    case 'network':
+      return 'network';
+    case 'invalid_input':
+      return 'bad input';
+  }
 }
 
 /** Status CSS class for a tool entry. */
@@ -68,7 +68,7 @@ export function routeInspectorEvent(event: EngineEvent): void {
       const callId = event.tool_call_id ?? '';
       const output = event.output ?? '';
       const success = event.success ?? false;
-      inspectorToolResult(callId, output, success, event.duration_ms);
+      inspectorToolResult(callId, output, success, event.duration_ms, event.error_kind);
       break;
     }
 
@@ -2,6 +2,7 @@
 // Renders the collapsible Inspector panel alongside the chat view.
 
 import { $ } from '../../components/helpers';
+import type { ToolErrorKind } from '../../engine/atoms/types';
 import {
   type InspectorState,
   type InspectorToolEntry,
@@ -89,6 +90,7 @@ export function inspectorToolRequest(
     outputPreview: null,
     tier,
     autoApproved,
+    errorKind: null,
   };
   _state.tools.push(entry);
 
@@ -104,11 +106,13 @@ export function inspectorToolResult(
   output: string,
   success: boolean,
   durationMs?: number,
+  errorKind?: ToolErrorKind,
 ): void {
   const entry = _state.tools.find((t) => t.callId === callId);
   if (entry) {
     entry.finishedAt = Date.now();
     entry.success = success;
+    entry.errorKind = success ? null : (errorKind ?? null);
     entry.outputPreview = truncateOutput(output);
     entry.durationMs = durationMs ?? entry.finishedAt - entry.startedAt;
   }
@@ -9,6 +9,7 @@ import {
   toolStatusIcon,
   toolStatusClass,
   tierIcon,
+  errorKindLabel,
 } from './atoms';
 
 // ── Render ────────────────────────────────────────────────────────────
@@ -60,6 +61,7 @@ function renderToolEntry(entry: InspectorToolEntry, totalDuration: number): stri
         <span class="inspector-tool-name">${escHtml(entry.name)}</span>
         <span class="ms ms-xs inspector-tool-tier" title="${entry.tier ?? 'unknown'}">${tIcon}</span>
         ${entry.autoApproved ? '<span class="inspector-tool-auto" title="Auto-approved">auto</span>' : ''}
+        ${entry.errorKind ? `<span class="inspector-tool-error inspector-error-${entry.errorKind}">${errorKindLabel(entry.errorKind)}</span>` : ''}
         <span class="inspector-tool-round">R${entry.round}</span>
         <span class="inspector-tool-dur">${dur}</span>
       </div>
@@ -40,6 +40,8 @@ export const EMAIL_PROVIDERS: Record<
     smtp: string;
     smtpPort: number;
     hint: string;
+    /** Supports "Sign in with …" (XOAUTH2) instead of a password. */
+    oauth?: 'google' | 'microsoft';
   }
 > = {
   gmail: {
@@ -49,7 +51,8 @@ export const EMAIL_PROVIDERS: Record<
     imapPort: 993,
     smtp: 'smtp.gmail.com',
     smtpPort: 465,
-    hint: 'Use an App Password — go to Google Account → Security → App Passwords',
+    hint: 'Sign in with Google, or use an App Password (Google Account → Security → App Passwords)',
+    oauth: 'google',
   },
   outlook: {
     name: 'Outlook / Hotmail',
@@ -58,7 +61,8 @@ export const EMAIL_PROVIDERS: Record<
     imapPort: 993,
     smtp: 'smtp.office365.com',
     smtpPort: 587,
-    hint: 'Use your regular password, or an App Password if 2FA is on',
+    hint: 'Sign in with Microsoft — Outlook is turning off password logins for IMAP/SMTP',
+    oauth: 'microsoft',
   },
   yahoo: {
     name: 'Yahoo Mail',
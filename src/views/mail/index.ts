@@ -1,5 +1,6 @@
 // Mail View — Index (orchestration, state, event wiring, exports)
 
+import { listen } from '@tauri-apps/api/event';
 import { $ } from '../../components/helpers';
 import { showToast } from '../../components/toast';
 import { isConnected } from '../../state/connection';
@@ -143,4 +144,8 @@ export function initMailEvents(): void {
   $('mail-add-account')?.addEventListener('click', () => _openMailAccountSetup());
   $('mail-qa-add-account')?.addEventListener('click', () => _openMailAccountSetup());
   $('mail-qa-refresh')?.addEventListener('click', () => loadMail());
+
+  // New mail pushed by the IMAP watcher (the notification itself comes
+  // through the engine's notification router)
+  listen('mail-new', () => loadMail());
 }
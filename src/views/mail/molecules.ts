@@ -635,7 +635,13 @@ export async function openMailMessage(msgId: string) {
 
 export function openComposeModal(
   mode: 'reply' | 'forward',
-  msg: { from: string; subject: string; body?: string; source?: 'himalaya' | 'google' },
+  msg: {
+    id?: string;
+    from: string;
+    subject: string;
+    body?: string;
+    source?: 'himalaya' | 'google';
+  },
 ) {
   const modal = document.createElement('div');
   modal.className = 'mail-compose-modal';
@@ -647,8 +653,11 @@ export function openComposeModal(
       </div>
       <div class="mail-compose-body">
         <input type="text" class="mail-compose-to" placeholder="To" value="${mode === 'reply' ? escAttr(msg.from) : ''}">
+        <input type="text" class="mail-compose-cc" placeholder="Cc">
+        <input type="text" class="mail-compose-bcc" placeholder="Bcc">
         <input type="text" class="mail-compose-subject" placeholder="Subject" value="${mode === 'reply' ? 'Re: ' : 'Fwd: '}${escAttr(msg.subject)}">
         <textarea class="mail-compose-content" placeholder="Write your message...">${mode === 'forward' ? `\n\n--- Forwarded ---\n${msg.body || ''}` : ''}</textarea>
+        <input type="text" class="mail-compose-attachments" placeholder="Attach files (full paths, comma-separated)">
       </div>
       <div class="mail-compose-footer">
         <button class="btn btn-ghost mail-compose-cancel">Cancel</button>
@@ -669,6 +678,14 @@ export function openComposeModal(
     const to = (modal.querySelector('.mail-compose-to') as HTMLInputElement)?.value;
     const subject = (modal.querySelector('.mail-compose-subject') as HTMLInputElement)?.value;
     const body = (modal.querySelector('.mail-compose-content') as HTMLTextAreaElement)?.value;
+    const field = (cls: string) =>
+      (modal.querySelector(cls) as HTMLInputElement | null)?.value.trim() ?? '';
+    const attachments = field('.mail-compose-attachments')
+      .split(',')
+      .map((p) => p.trim())
+      .filter(Boolean);
+    // Replies to IMAP messages keep their thread (In-Reply-To / References)
+    const threaded = mode === 'reply' && msg.id && msg.source !== 'google';
     if (!to || !subject) {
       showToast('Please fill in To and Subject', 'error');
       return;
@@ -676,7 +693,13 @@ export function openComposeModal(
 
     try {
       const himalayaAccount = _mailAccounts.find((a) => a.name !== '__google__');
-      await pawEngine.mailSend(himalayaAccount?.name, to, subject, body);
+      await pawEngine.mailSend(himalayaAccount?.name, to, subject, body, {
+        cc: field('.mail-compose-cc') || undefined,
+        bcc: field('.mail-compose-bcc') || undefined,
+        attachments: attachments.length ? attachments : undefined,
+        replyToId: threaded ? msg.id : undefined,
+        folder: threaded ? 'INBOX' : undefined,
+      });
       showToast('Email sent!', 'success');
       close();
     } catch (e) {
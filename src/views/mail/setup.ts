@@ -86,6 +86,7 @@ function showMailAccountForm(providerId: string): void {
 
   const isCustom = providerId === 'custom';
   const needsAppPw = providerId === 'gmail' || providerId === 'yahoo' || providerId === 'icloud';
+  const oauthName = provider.oauth === 'google' ? 'Google' : 'Microsoft';
 
   body.innerHTML = `
     <div class="mail-setup-back" id="mail-setup-back">← Choose provider</div>
@@ -99,7 +100,21 @@ function showMailAccountForm(providerId: string): void {
       <input class="form-input" id="ch-field-mail-display" type="text" placeholder="Your Name">
       <div class="form-hint">How your name appears in outgoing emails</div>
     </div>
+    ${
+      provider.oauth
+        ? `
     <div class="form-group">
+      <label class="form-label" for="ch-field-mail-auth">Sign-in Method</label>
+      <select class="form-input" id="ch-field-mail-auth">
+        <option value="oauth" selected>Sign in with ${oauthName} (recommended)</option>
+        <option value="password">${needsAppPw ? 'App Password' : 'Password'}</option>
+      </select>
+      <div class="form-hint">Opens your browser — no password is stored, only a revocable token</div>
+    </div>
+    `
+        : ''
+    }
+    <div class="form-group" id="mail-password-group"${provider.oauth ? ' style="display:none"' : ''}>
       <label class="form-label" for="ch-field-mail-password">${needsAppPw ? 'App Password' : 'Password'} <span class="required">*</span></label>
       <input class="form-input" id="ch-field-mail-password" type="password" placeholder="${providerId === 'gmail' ? '16-character app password' : 'Password'}">
     </div>
@@ -171,7 +186,7 @@ function showMailAccountForm(providerId: string): void {
         How your credentials are stored &amp; used
       </div>
       <ul class="mail-security-list">
-        <li><strong>OS keychain</strong> — your password is stored in the system keychain (macOS Keychain / libsecret on Linux), not in any file.</li>
+        <li><strong>OS keychain</strong> — your password${provider.oauth ? ` or ${oauthName} sign-in token` : ''} is stored in the system keychain (macOS Keychain / libsecret on Linux), not in any file.</li>
         <li><strong>Never sent to frontend</strong> — credential details are redacted before reaching the UI.</li>
         <li><strong>TLS in transit</strong> — connections to ${provider.imap || 'your mail server'} use TLS encryption</li>
         <li><strong>No cloud</strong> — OpenPawz is fully self-hosted, your data never leaves your machine</li>
@@ -183,6 +198,11 @@ function showMailAccountForm(providerId: string): void {
   `;
 
   $('mail-setup-back')?.addEventListener('click', () => openMailAccountSetup());
+  $('ch-field-mail-auth')?.addEventListener('change', (e) => {
+    const usePassword = (e.target as HTMLSelectElement).value === 'password';
+    const group = $('mail-password-group');
+    if (group) group.style.display = usePassword ? '' : 'none';
+  });
 }
 
 // ── Save handler ───────────────────────────────────────────────────────────
@@ -197,12 +217,15 @@ export async function saveMailImapSetup(): Promise<void> {
   const imapPort = parseInt(($('ch-field-mail-imap-port') as HTMLInputElement)?.value ?? '993', 10);
   const smtpHost = ($('ch-field-mail-smtp') as HTMLInputElement)?.value.trim();
   const smtpPort = parseInt(($('ch-field-mail-smtp-port') as HTMLInputElement)?.value ?? '465', 10);
+  const providerId = ($('ch-field-mail-provider') as HTMLInputElement)?.value ?? 'custom';
+  const useOAuth = ($('ch-field-mail-auth') as HTMLSelectElement | null)?.value === 'oauth';
+  const oauthProvider = useOAuth ? (EMAIL_PROVIDERS[providerId]?.oauth ?? null) : null;
 
   if (!email) {
     showToast('Email address is required', 'error');
     return;
   }
-  if (!password) {
+  if (!password && !oauthProvider) {
     showToast('Password is required', 'error');
     return;
   }
@@ -218,12 +241,15 @@ export async function saveMailImapSetup(): Promise<void> {
   const saveBtn = $('channel-setup-save') as HTMLButtonElement | null;
   if (saveBtn) {
     saveBtn.disabled = true;
-    saveBtn.textContent = 'Connecting...';
+    saveBtn.textContent = oauthProvider ? 'Waiting for sign-in...' : 'Connecting...';
   }
 
   try {
     const accountName = email.replace(/[^a-zA-Z0-9]/g, '-').toLowerCase();
 
+    // Runs the browser sign-in; failures must surface, not hit the fallback below
+    if (oauthProvider) await pawEngine.mailOAuthConnect(accountName, email, oauthProvider);
+
     try {
       await pawEngine.mailWriteConfig({
         accountName,
@@ -234,6 +260,7 @@ export async function saveMailImapSetup(): Promise<void> {
         smtpHost,
         smtpPort,
         password,
+        oauthProvider,
       });
     } catch {
       // Fallback: store account info in localStorage when Tauri is not available
@@ -14,6 +14,7 @@ import {
   loadPalaceSidebar,
   palaceRecallById,
   exportMemories,
+  exportKnowledgeGraph,
 } from './molecules';
 import { initPalaceGraph } from './graph';
 
@@ -316,6 +317,9 @@ export function initPalaceEvents(): void {
   $('palace-export')?.addEventListener('click', () => {
     exportMemories();
   });
+  $('palace-export-graph')?.addEventListener('click', () => {
+    exportKnowledgeGraph();
+  });
 
   // Sidebar search filter
   $('palace-search')?.addEventListener('input', () => {
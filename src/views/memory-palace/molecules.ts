@@ -769,6 +769,33 @@ export async function exportMemories(): Promise<void> {
   }
 }
 
+/** Download the knowledge graph as GraphML for Gephi / Cytoscape / yEd.
+ *  Honours the agent filter in the sidebar. */
+export async function exportKnowledgeGraph(): Promise<void> {
+  const btn = $('palace-export-graph') as HTMLButtonElement | null;
+  if (btn) btn.disabled = true;
+
+  try {
+    const agentFilter = ($('palace-agent-filter') as HTMLSelectElement)?.value || undefined;
+    const xml = await pawEngine.memoryGraphExport('graphml', agentFilter);
+    const blob = new Blob([xml], { type: 'application/graphml+xml' });
+    const url = URL.createObjectURL(blob);
+    const a = document.createElement('a');
+    a.href = url;
+    a.download = `paw-knowledge-graph-${new Date().toISOString().slice(0, 10)}.graphml`;
+    document.body.appendChild(a);
+    a.click();
+    document.body.removeChild(a);
+    URL.revokeObjectURL(url);
+
+    showToast('Knowledge graph exported', 'success');
+  } catch (e) {
+    showToast(`Graph export failed: ${e instanceof Error ? e.message : e}`, 'error');
+  } finally {
+    if (btn) btn.disabled = false;
+  }
+}
+
 // ── FORGE Certification View ───────────────────────────────────────────────
 
 export async function renderPalaceForge(): Promise<void> {
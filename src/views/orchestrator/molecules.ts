@@ -84,6 +84,9 @@ const els = {
   get formBoss() {
     return document.getElementById('orch-form-boss') as HTMLInputElement;
   },
+  get formBudget() {
+    return document.getElementById('orch-form-budget') as HTMLInputElement;
+  },
   get agentModal() {
     return document.getElementById('orch-agent-modal')!;
   },
@@ -167,14 +170,7 @@ export async function loadProjectDetail(projectId: string) {
     els.detailStatus.className = `orch-status-badge orch-status-${project.status}`;
     els.detailGoal.textContent = project.goal;
 
-    const runBtn = document.getElementById('orch-run-btn')!;
-    if (project.status === 'running') {
-      runBtn.textContent = '⏳ Running...';
-      (runBtn as HTMLButtonElement).disabled = true;
-    } else {
-      runBtn.textContent = '▶ Run';
-      (runBtn as HTMLButtonElement).disabled = false;
-    }
+    setRunButton(project.status);
 
     renderAgentRoster(project.agents);
     await refreshMessages();
@@ -287,12 +283,17 @@ function renderMessageBus(messages: EngineProjectMessage[]) {
     .map((m) => {
       const kindClass = `orch-msg-${m.kind}`;
       const arrow = m.to_agent ? ` → ${escHtml(m.to_agent)}` : ' (broadcast)';
+      const rerun =
+        m.kind === 'delegation' && m.to_agent
+          ? `<button class="btn btn-ghost btn-xs orch-rerun-step" data-step="${escHtml(m.id)}" title="Give this task to the agent again">↻ Re-run</button>`
+          : '';
       return `
       <div class="orch-message ${kindClass}">
         <div class="orch-msg-header">
           <span class="orch-msg-kind">${messageKindLabel(m.kind)}</span>
           <span class="orch-msg-from">${escHtml(m.from_agent)}${arrow}</span>
           <span class="orch-msg-time">${formatTime(m.created_at)}</span>
+          ${rerun}
         </div>
         <div class="orch-msg-content">${escHtml(m.content)}</div>
       </div>
@@ -300,6 +301,18 @@ function renderMessageBus(messages: EngineProjectMessage[]) {
     })
     .join('');
 
+  els.messageBus.querySelectorAll('.orch-rerun-step').forEach((btn) => {
+    btn.addEventListener('click', async () => {
+      try {
+        await pawEngine.projectRerunStep((btn as HTMLElement).dataset.step!);
+        showToast('Task handed to the agent again');
+        await refreshAgents();
+      } catch (e: unknown) {
+        showToast(`Error: ${e instanceof Error ? e.message : String(e)}`, 'error');
+      }
+    });
+  });
+
   els.messageBus.scrollTop = els.messageBus.scrollHeight;
 }
 
@@ -314,6 +327,7 @@ export function openCreateModal() {
   els.formTitle.value = '';
   els.formGoal.value = '';
   els.formBoss.value = 'default';
+  els.formBudget.value = '0';
   els.modal.style.display = 'flex';
 }
 
@@ -326,6 +340,7 @@ export function editProject() {
   els.formTitle.value = currentProject.title;
   els.formGoal.value = currentProject.goal;
   els.formBoss.value = currentProject.boss_agent;
+  els.formBudget.value = String(currentProject.budget_usd ?? 0);
   els.modal.style.display = 'flex';
 }
 
@@ -338,6 +353,7 @@ export async function saveProject() {
   const title = els.formTitle.value.trim();
   const goal = els.formGoal.value.trim();
   const boss = els.formBoss.value.trim() || 'default';
+  const budget = Math.max(0, parseFloat(els.formBudget.value) || 0);
 
   if (!title) {
     showToast('Project title is required', 'error');
@@ -360,6 +376,7 @@ export async function saveProject() {
           title,
           goal,
           boss_agent: boss,
+          budget_usd: budget,
         };
         await pawEngine.projectUpdate(updated);
         showToast('Project updated');
@@ -383,6 +400,7 @@ export async function saveProject() {
             current_task: undefined,
           },
         ],
+        budget_usd: budget,
         created_at: new Date().toISOString(),
         updated_at: new Date().toISOString(),
       };
@@ -415,11 +433,47 @@ export async function deleteProject() {
   }
 }
 
+/** Run button doubles as Pause while running and Resume while paused. */
+function setRunButton(status: string) {
+  const runBtn = document.getElementById('orch-run-btn') as HTMLButtonElement;
+  runBtn.disabled = false;
+  if (status === 'running') {
+    runBtn.textContent = '⏸ Pause';
+    runBtn.title = 'Stop delegating — agents already working finish their tasks';
+  } else if (status === 'paused') {
+    runBtn.textContent = '▶ Resume';
+    runBtn.title = 'Continue where the project left off';
+  } else {
+    runBtn.textContent = '▶ Run';
+    runBtn.title = '';
+  }
+  els.detailStatus.textContent = status;
+  els.detailStatus.className = `orch-status-badge orch-status-${status}`;
+}
+
 export async function runProject() {
   const currentProject = _state.getCurrentProject();
   if (!currentProject) return;
   if (currentProject.status === 'running') {
-    showToast('Project is already running', 'error');
+    try {
+      await pawEngine.projectPause(currentProject.id);
+      currentProject.status = 'paused';
+      setRunButton('paused');
+      showToast('Project paused. Agents already working will finish their tasks.');
+    } catch (e: unknown) {
+      showToast(`Error: ${e instanceof Error ? e.message : String(e)}`, 'error');
+    }
+    return;
+  }
+  if (currentProject.status === 'paused') {
+    try {
+      await pawEngine.projectResume(currentProject.id);
+      currentProject.status = 'running';
+      setRunButton('running');
+      showToast('Project resumed. The boss agent is catching up from the project log.');
+    } catch (e: unknown) {
+      showToast(`Error: ${e instanceof Error ? e.message : String(e)}`, 'error');
+    }
     return;
   }
   if (currentProject.agents.length === 0) {
@@ -430,12 +484,8 @@ export async function runProject() {
   try {
     await pawEngine.projectRun(currentProject.id);
     showToast('Project started! The boss agent is orchestrating.');
-
-    const runBtn = document.getElementById('orch-run-btn')!;
-    runBtn.textContent = '⏳ Running...';
-    (runBtn as HTMLButtonElement).disabled = true;
-    els.detailStatus.textContent = 'running';
-    els.detailStatus.className = 'orch-status-badge orch-status-running';
+    currentProject.status = 'running';
+    setRunButton('running');
   } catch (e: unknown) {
     showToast(`Error: ${e instanceof Error ? e.message : String(e)}`, 'error');
   }
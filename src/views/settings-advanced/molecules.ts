@@ -1,6 +1,6 @@
 // Settings: Advanced — DOM rendering + IPC
 
-import { pawEngine, type EngineProviderConfig } from '../../engine';
+import { pawEngine, type EngineProviderConfig, type NotificationRoute } from '../../engine';
 import { showToast } from '../../components/toast';
 import { isConnected } from '../../state/connection';
 import {
@@ -9,6 +9,7 @@ import {
   selectInput,
   textInput,
   numberInput,
+  toggleSwitch,
   saveReloadButtons,
 } from '../settings-config';
 import { $ } from '../../components/helpers';
@@ -26,6 +27,93 @@ export async function loadAdvancedSettings() {
     const config = await pawEngine.getConfig();
     container.innerHTML = '';
 
+    // ── Profiles ─────────────────────────────────────────────────────────
+    const profileSection = document.createElement('div');
+    profileSection.className = 'settings-subsection';
+    profileSection.innerHTML =
+      '<h3 class="settings-subsection-title"><span class="ms ms-sm">switch_account</span> Profiles</h3>';
+    const profiles = config.profiles ?? [];
+    if (profiles.length) {
+      const switchRow = formRow(
+        'Active Profile',
+        'Applies the profile’s provider, model, budget, bridge agent and running bridges in one step.',
+      );
+      const profileSel = selectInput(
+        profiles.map((p) => ({ value: p.name, label: p.name })),
+        config.active_profile ?? profiles[0].name,
+      );
+      profileSel.style.maxWidth = '200px';
+      const switchBtn = document.createElement('button');
+      switchBtn.className = 'btn btn-primary btn-sm';
+      switchBtn.textContent = 'Switch';
+      switchBtn.addEventListener('click', async () => {
+        switchBtn.disabled = true;
+        try {
+          await pawEngine.profileSwitch(profileSel.value);
+          showToast(`Switched to profile “${profileSel.value}”`, 'success');
+          loadAdvancedSettings();
+        } catch (e) {
+          showToast(`Switch failed: ${e instanceof Error ? e.message : e}`, 'error');
+          switchBtn.disabled = false;
+        }
+      });
+      switchRow.append(profileSel, switchBtn);
+      profileSection.appendChild(switchRow);
+    }
+    const saveProfileRow = formRow(
+      'Save as Profile',
+      'Saves the current provider, model and budget. Bridges and their agent can be added in the engine config.',
+    );
+    const profileNameInp = textInput('', 'home, work, demo…');
+    profileNameInp.style.maxWidth = '200px';
+    const saveProfileBtn = document.createElement('button');
+    saveProfileBtn.className = 'btn btn-sm';
+    saveProfileBtn.textContent = 'Save';
+    saveProfileBtn.addEventListener('click', async () => {
+      const name = profileNameInp.value.trim();
+      if (!name) return;
+      try {
+        const cfg = await pawEngine.getConfig();
+        const existing = cfg.profiles?.find((p) => p.name === name);
+        const profile = {
+          ...existing,
+          name,
+          default_provider: cfg.default_provider ?? null,
+          default_model: cfg.default_model ?? null,
+          daily_budget_usd: cfg.daily_budget_usd ?? null,
+        };
+        cfg.profiles = [...(cfg.profiles ?? []).filter((p) => p.name !== name), profile];
+        await pawEngine.setConfig(cfg);
+        showToast(`Profile “${name}” saved`, 'success');
+        loadAdvancedSettings();
+      } catch (e) {
+        showToast(`Save failed: ${e instanceof Error ? e.message : e}`, 'error');
+      }
+    });
+    saveProfileRow.append(profileNameInp, saveProfileBtn);
+    profileSection.appendChild(saveProfileRow);
+
+    const safeRow = formRow(
+      'Safe Mode',
+      'Read-only demo mode: writes, sends and trades are refused and channel bridges are disconnected.',
+    );
+    const { container: safeToggle, checkbox: safeCb } = toggleSwitch(
+      !!config.safe_mode,
+      'Safe mode',
+    );
+    safeCb.addEventListener('change', async () => {
+      try {
+        await pawEngine.safeModeSet(safeCb.checked);
+        showToast(safeCb.checked ? 'Safe mode on' : 'Safe mode off', 'success');
+      } catch (e) {
+        safeCb.checked = !safeCb.checked;
+        showToast(`Safe mode failed: ${e instanceof Error ? e.message : e}`, 'error');
+      }
+    });
+    safeRow.appendChild(safeToggle);
+    profileSection.appendChild(safeRow);
+    container.appendChild(profileSection);
+
     // ── Ollama Quick Setup ───────────────────────────────────────────────
     const ollamaSection = document.createElement('div');
     ollamaSection.className = 'settings-subsection';
@@ -468,8 +556,168 @@ export async function loadAdvancedSettings() {
     contextRow.appendChild(contextInp);
     engSection.appendChild(contextRow);
 
+    const localModels = config.local_models;
+    const keepAliveRow = formRow(
+      'Keep Local Models Loaded',
+      'How long Ollama keeps a model in memory after a request, e.g. 30m or 2h (-1 = always). Leave empty for Ollama\'s default of 5 minutes — reloading a model can take 20 seconds.',
+    );
+    const keepAliveInp = textInput(localModels?.keep_alive ?? '30m', '30m');
+    keepAliveInp.style.maxWidth = '120px';
+    keepAliveRow.appendChild(keepAliveInp);
+    engSection.appendChild(keepAliveRow);
+
+    const { container: warmSessionToggle, checkbox: warmSessionCb } = toggleSwitch(
+      localModels?.warm_on_session_open ?? true,
+      'Load the local model when a chat session is opened',
+    );
+    engSection.appendChild(warmSessionToggle);
+    const { container: prewarmCronToggle, checkbox: prewarmCronCb } = toggleSwitch(
+      localModels?.prewarm_cron ?? true,
+      'Load the local model shortly before a scheduled task runs',
+    );
+    engSection.appendChild(prewarmCronToggle);
+
+    const { container: playbooksToggle, checkbox: playbooksCb } = toggleSwitch(
+      config.project_playbooks ?? false,
+      'Learn playbooks from finished projects and reuse them for similar goals',
+    );
+    engSection.appendChild(playbooksToggle);
+
+    const workerCaps = config.worker_limits?.defaults;
+    const workerRow = formRow(
+      'Project Worker Limits',
+      'Caps per delegated task in orchestrator projects: tool rounds (0 = Max Tool Rounds), spend in USD (0 = none) and withheld tool groups (exec, filesystem, web, memory, comms, skills, mcp). A worker that hits a cap stops and its boss is told.',
+    );
+    const workerRoundsInp = numberInput(workerCaps?.max_rounds ?? 0, { min: 0, placeholder: '0' });
+    workerRoundsInp.style.maxWidth = '100px';
+    const workerCostInp = numberInput(workerCaps?.max_cost_usd ?? 0, {
+      min: 0,
+      step: 0.1,
+      placeholder: '0',
+    });
+    workerCostInp.style.maxWidth = '100px';
+    const workerGroupsInp = textInput(
+      (workerCaps?.denied_tool_groups ?? []).join(', '),
+      'e.g. exec, mcp',
+    );
+    workerGroupsInp.style.maxWidth = '200px';
+    workerRow.append(workerRoundsInp, workerCostInp, workerGroupsInp);
+    engSection.appendChild(workerRow);
+
+    const { container: isolateToggle, checkbox: isolateCb } = toggleSwitch(
+      config.worker_limits?.isolate_workspaces ?? false,
+      'Give each delegated task its own empty workspace',
+    );
+    engSection.appendChild(isolateToggle);
+
+    const breaker = config.circuit_breaker;
+    const breakerRow = formRow(
+      'Tool Circuit Breaker',
+      'After how many consecutive failures of a tool the agent is told to stop retrying it, after how many the tool is blocked, and for how many minutes it stays blocked (0 = the rest of the session). Per-tool overrides live in the engine config.',
+    );
+    const nudgeAfterInp = numberInput(breaker?.nudge_after ?? 3, { min: 0, placeholder: '3' });
+    nudgeAfterInp.style.maxWidth = '80px';
+    const blockAfterInp = numberInput(breaker?.block_after ?? 5, { min: 0, placeholder: '5' });
+    blockAfterInp.style.maxWidth = '80px';
+    const cooldownInp = numberInput(breaker?.cooldown_minutes ?? 10, {
+      min: 0,
+      placeholder: '10',
+    });
+    cooldownInp.style.maxWidth = '80px';
+    breakerRow.append(nudgeAfterInp, blockAfterInp, cooldownInp);
+    engSection.appendChild(breakerRow);
+
+    const mailWatch = config.mail_watch;
+    const { container: mailWatchToggle, checkbox: mailWatchCb } = toggleSwitch(
+      mailWatch?.enabled ?? false,
+      'Push new mail from IMAP accounts as it arrives (wakes tasks with a new_mail trigger)',
+    );
+    engSection.appendChild(mailWatchToggle);
+    const mailFoldersRow = formRow(
+      'Watched Mail Folders',
+      'Folders watched on every IMAP account, comma-separated. Per-account folders live in the engine config.',
+    );
+    const mailFoldersInp = textInput((mailWatch?.folders ?? ['INBOX']).join(', '), 'INBOX');
+    mailFoldersInp.style.maxWidth = '240px';
+    mailFoldersRow.appendChild(mailFoldersInp);
+    engSection.appendChild(mailFoldersRow);
+
     container.appendChild(engSection);
 
+    // ── Notifications ────────────────────────────────────────────────────
+    const notifSection = document.createElement('div');
+    notifSection.innerHTML =
+      '<h3 class="settings-subsection-title" style="margin-top:20px">Notifications</h3>';
+    const notif = config.notifications;
+    const deliveryOpts = [
+      { value: 'os', label: 'System notification' },
+      { value: 'bridge:telegram', label: 'Telegram message' },
+      { value: 'bridge:discord', label: 'Discord message' },
+      { value: 'bridge:webhook', label: 'Webhook' },
+      { value: 'log', label: 'Silent (drawer only)' },
+    ];
+    const categories = [
+      { id: 'budget', label: 'Budget Warnings', noisy: false },
+      { id: 'pairing', label: 'Pairing Requests', noisy: false },
+      { id: 'task', label: 'Task Completion', noisy: true },
+      { id: 'gateway', label: 'Bridge Down', noisy: false },
+      { id: 'mail', label: 'New Mail', noisy: true },
+      { id: 'security', label: 'Security Alerts', noisy: false },
+    ];
+    const routeInputs = categories.map((cat) => {
+      const route = notif?.routes?.[cat.id];
+      const current = route
+        ? route.delivery === 'bridge'
+          ? `bridge:${route.bridge}`
+          : route.delivery
+        : 'os';
+      const row = formRow(cat.label);
+      const sel = selectInput(deliveryOpts, current);
+      sel.style.maxWidth = '200px';
+      row.appendChild(sel);
+      const { container: digestToggle, checkbox: digestCb } = toggleSwitch(
+        route?.digest ?? cat.noisy,
+        'Digest',
+      );
+      row.appendChild(digestToggle);
+      notifSection.appendChild(row);
+      return { id: cat.id, sel, digestCb, target: route?.target ?? '' };
+    });
+    const digestRow = formRow(
+      'Digest Window (minutes)',
+      'Digested categories are delivered as one summary per window. Bridge chat / channel targets live in the engine config.',
+    );
+    const digestInp = numberInput(notif?.digest_minutes ?? 15, { min: 1, placeholder: '15' });
+    digestInp.style.maxWidth = '80px';
+    digestRow.appendChild(digestInp);
+    notifSection.appendChild(digestRow);
+
+    // The first do-not-disturb window is editable here; others are kept as-is
+    const quiet = notif?.dnd?.[0];
+    const quietRow = formRow(
+      'Quiet Hours',
+      'Notifications are queued and delivered as a digest when quiet hours end. Leave empty to disable.',
+    );
+    const quietStartInp = textInput(quiet?.start ?? '', '22:00');
+    quietStartInp.style.maxWidth = '80px';
+    const quietEndInp = textInput(quiet?.end ?? '', '07:00');
+    quietEndInp.style.maxWidth = '80px';
+    const quietDaysInp = textInput(quiet?.days.join(', ') ?? '', 'every day (or mon, tue, ...)');
+    quietDaysInp.style.maxWidth = '220px';
+    quietRow.append(quietStartInp, quietEndInp, quietDaysInp);
+    notifSection.appendChild(quietRow);
+
+    const breakthroughRow = formRow(
+      'Break Through Quiet Hours',
+      'Comma-separated categories delivered even during quiet hours. Critical security events always are.',
+    );
+    const breakthroughInp = textInput((notif?.breakthrough ?? ['security']).join(', '), 'security');
+    breakthroughInp.style.maxWidth = '300px';
+    breakthroughRow.appendChild(breakthroughInp);
+    notifSection.appendChild(breakthroughRow);
+
+    container.appendChild(notifSection);
+
     // ── System Prompt ────────────────────────────────────────────────────
     const promptSection = document.createElement('div');
     promptSection.innerHTML =
@@ -498,6 +746,73 @@ export async function loadAdvancedSettings() {
             cfg.max_concurrent_runs = parseInt(concurrencyInp.value) || 4;
             cfg.daily_budget_usd = parseFloat(budgetInp.value) || 0;
             cfg.context_window_tokens = parseInt(contextInp.value) || 32000;
+            cfg.local_models = {
+              keep_alive: keepAliveInp.value.trim(),
+              warm_on_session_open: warmSessionCb.checked,
+              prewarm_cron: prewarmCronCb.checked,
+              prewarm_lead_secs: cfg.local_models?.prewarm_lead_secs ?? 120,
+            };
+            cfg.project_playbooks = playbooksCb.checked;
+            cfg.worker_limits = {
+              defaults: {
+                max_rounds: parseInt(workerRoundsInp.value) || 0,
+                max_cost_usd: parseFloat(workerCostInp.value) || 0,
+                denied_tool_groups: workerGroupsInp.value
+                  .split(',')
+                  .map((g) => g.trim().toLowerCase())
+                  .filter(Boolean),
+              },
+              specialties: cfg.worker_limits?.specialties ?? {},
+              isolate_workspaces: isolateCb.checked,
+            };
+            const intOr = (value: string, fallback: number) => {
+              const n = parseInt(value);
+              return Number.isNaN(n) ? fallback : Math.max(0, n);
+            };
+            cfg.circuit_breaker = {
+              nudge_after: intOr(nudgeAfterInp.value, 3),
+              block_after: intOr(blockAfterInp.value, 5),
+              cooldown_minutes: intOr(cooldownInp.value, 10),
+              tools: cfg.circuit_breaker?.tools ?? {},
+            };
+            cfg.mail_watch = {
+              enabled: mailWatchCb.checked,
+              folders: mailFoldersInp.value
+                .split(',')
+                .map((f) => f.trim())
+                .filter(Boolean),
+              accounts: cfg.mail_watch?.accounts ?? {},
+            };
+            const routes: Record<string, NotificationRoute> = {};
+            for (const r of routeInputs) {
+              const [delivery, bridge = ''] = r.sel.value.split(':');
+              routes[r.id] = {
+                delivery: delivery as NotificationRoute['delivery'],
+                bridge,
+                target: r.target,
+                digest: r.digestCb.checked,
+              };
+            }
+            const list = (value: string) =>
+              value
+                .split(',')
+                .map((v) => v.trim().toLowerCase())
+                .filter(Boolean);
+            const otherWindows = cfg.notifications?.dnd?.slice(1) ?? [];
+            const quietStart = quietStartInp.value.trim();
+            const quietEnd = quietEndInp.value.trim();
+            cfg.notifications = {
+              routes,
+              digest_minutes: Math.max(1, parseInt(digestInp.value) || 15),
+              dnd:
+                quietStart && quietEnd
+                  ? [
+                      { days: list(quietDaysInp.value), start: quietStart, end: quietEnd },
+                      ...otherWindows,
+                    ]
+                  : otherWindows,
+              breakthrough: list(breakthroughInp.value),
+            };
             cfg.default_system_prompt = promptArea.value.trim() || undefined;
             await pawEngine.setConfig(cfg);
             showToast('Engine settings saved', 'success');
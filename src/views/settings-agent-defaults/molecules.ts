@@ -13,7 +13,7 @@ import {
   toggleSwitch,
   saveReloadButtons,
 } from '../settings-config';
-import { $ } from '../../components/helpers';
+import { $, setUserTimezone } from '../../components/helpers';
 
 // ── Render ──────────────────────────────────────────────────────────────────
 
@@ -101,32 +101,69 @@ export async function loadAgentDefaultsSettings() {
 
     const tzRow = formRow(
       'User Timezone',
-      'IANA timezone (e.g. America/Chicago, America/New_York, Europe/London). Used for agent time awareness.',
+      'IANA timezone (e.g. America/Chicago, America/New_York, Europe/London). Scheduled tasks, reminders, "yesterday" in memory search and displayed times all use it.',
     );
     const tzInp = document.createElement('input');
     tzInp.className = 'form-input';
     tzInp.type = 'text';
-    // eslint-disable-next-line @typescript-eslint/no-explicit-any
-    tzInp.value = (config as any).user_timezone ?? 'America/Chicago';
+    tzInp.value = config.user_timezone ?? 'America/Chicago';
     tzInp.placeholder = 'America/Chicago';
     tzInp.style.maxWidth = '240px';
     tzRow.appendChild(tzInp);
     toolSection.appendChild(tzRow);
 
-    // Weather Location
+    // Home Location
+    const home = config.home_location;
+    const homeLabel = home
+      ? [home.name, home.region, home.country].filter(Boolean).join(', ')
+      : (config.weather_location ?? '');
     const weatherRow = formRow(
-      'Weather Location',
-      'City for your dashboard weather (e.g. New York, London). Auto-detected via IP if empty.',
+      'Home Location',
+      'City used for weather, local time and nearby places (e.g. Portland, OR). Leave empty to auto-detect for weather only.',
     );
     const weatherInp = document.createElement('input');
     weatherInp.className = 'form-input';
     weatherInp.type = 'text';
-    // eslint-disable-next-line @typescript-eslint/no-explicit-any
-    weatherInp.value = (config as any).weather_location ?? '';
-    weatherInp.placeholder = 'Auto-detect (leave empty) or enter city';
+    weatherInp.value = homeLabel;
+    weatherInp.placeholder = 'Enter city';
     weatherInp.style.maxWidth = '240px';
+    const detectBtn = document.createElement('button');
+    detectBtn.className = 'btn btn-ghost btn-sm';
+    detectBtn.textContent = 'Detect from IP';
+    detectBtn.style.marginLeft = '8px';
+    detectBtn.addEventListener('click', async () => {
+      detectBtn.disabled = true;
+      try {
+        const found = await pawEngine.locationDetect();
+        if (found) {
+          weatherInp.value = [found.name, found.region, found.country].filter(Boolean).join(', ');
+          showToast(`Home location set to ${weatherInp.value} (approximate)`, 'success');
+        }
+      } catch (e) {
+        showToast(`Detection failed: ${e instanceof Error ? e.message : e}`, 'error');
+      } finally {
+        detectBtn.disabled = false;
+      }
+    });
     weatherRow.appendChild(weatherInp);
+    weatherRow.appendChild(detectBtn);
     toolSection.appendChild(weatherRow);
+    const { container: shareToggle, checkbox: shareLocationCb } = toggleSwitch(
+      home?.share_with_agents ?? true,
+      'Tell agents my home location',
+    );
+    toolSection.appendChild(shareToggle);
+    const { container: appContextToggle, checkbox: appContextCb } = toggleSwitch(
+      config.app_context ?? false,
+      'Tell chat agents which app and window I was just using',
+    );
+    toolSection.appendChild(appContextToggle);
+    const appContextHint = document.createElement('p');
+    appContextHint.className = 'form-hint';
+    appContextHint.style.cssText = 'margin:4px 0 0;font-size:11px;color:var(--text-muted)';
+    appContextHint.textContent =
+      'Lets "summarize this document" find what you were looking at. Only the app name and window title are read; they stay in memory and are never saved.';
+    toolSection.appendChild(appContextHint);
 
     container.appendChild(toolSection);
 
@@ -446,12 +483,20 @@ export async function loadAgentDefaultsSettings() {
             cfg.default_provider = providerSel.value || undefined;
             cfg.max_tool_rounds = parseInt(roundsInp.value) || 20;
             cfg.tool_timeout_secs = parseInt(timeoutInp.value) || 120;
-            // eslint-disable-next-line @typescript-eslint/no-explicit-any
-            (cfg as any).user_timezone = tzInp.value.trim() || 'America/Chicago';
-            // eslint-disable-next-line @typescript-eslint/no-explicit-any
-            (cfg as any).weather_location = weatherInp.value.trim() || undefined;
+            cfg.user_timezone = tzInp.value.trim() || 'America/Chicago';
             cfg.default_system_prompt = promptArea.value.trim() || undefined;
             await pawEngine.setConfig(cfg);
+            setUserTimezone(cfg.user_timezone);
+
+            // Home location — geocoded by the engine when it changed
+            const place = weatherInp.value.trim();
+            if (!place) {
+              await pawEngine.locationClear();
+            } else if (place !== homeLabel) {
+              await pawEngine.locationSet(place);
+            }
+            if (place) await pawEngine.locationShare(shareLocationCb.checked);
+            await pawEngine.appContextSet(appContextCb.checked);
 
             // Save memory config (including embedding settings)
             const mc = await pawEngine.getMemoryConfig();
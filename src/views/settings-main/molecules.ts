@@ -8,7 +8,7 @@ import {
   type SecuritySettings,
 } from '../../security';
 import { getSecurityAuditLog, isEncryptionReady } from '../../db';
-import { $, escHtml } from '../../components/helpers';
+import { $, escHtml, formatDateTime } from '../../components/helpers';
 import { showToast } from '../../components/toast';
 import { isConnected } from '../../state/connection';
 import { getBudgetLimit, setBudgetLimit, downloadFile, type ToolRule } from './atoms';
@@ -512,7 +512,7 @@ export async function loadSecurityAudit() {
 
     tbody.innerHTML = filtered
       .map((e) => {
-        const time = e.timestamp ? new Date(`${e.timestamp}Z`).toLocaleString() : '—';
+        const time = e.timestamp ? formatDateTime(e.timestamp) : '—';
         const riskBadge = e.risk_level
           ? `<span class="audit-risk-badge risk-${escHtml(e.risk_level)}">${escHtml(e.risk_level)}</span>`
           : '<span class="audit-risk-badge">—</span>';
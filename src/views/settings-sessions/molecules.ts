@@ -1,10 +1,10 @@
 // Settings: Sessions — DOM rendering + IPC
 
-import { pawEngine, type EngineSession } from '../../engine';
+import { pawEngine, type ArchivedSession, type EngineSession } from '../../engine';
 import { showToast } from '../../components/toast';
 import { isConnected } from '../../state/connection';
 import { esc } from '../settings-config';
-import { $ } from '../../components/helpers';
+import { $, formatDateTime } from '../../components/helpers';
 
 // ── Internal state ──────────────────────────────────────────────────────────
 
@@ -44,6 +44,7 @@ export async function loadSessionsSettings() {
 
     toolbar.appendChild(acts);
     container.appendChild(toolbar);
+    container.appendChild(await buildArchivePanel());
 
     // ── Session cards ────────────────────────────────────────────────────
     if (_sessions.length === 0) {
@@ -62,6 +63,148 @@ export async function loadSessionsSettings() {
   }
 }
 
+// ── Cold storage ────────────────────────────────────────────────────────────
+
+async function buildArchivePanel(): Promise<HTMLElement> {
+  const panel = document.createElement('details');
+  panel.style.cssText =
+    'margin-bottom:12px; padding:12px; border:1px solid var(--border-color); border-radius:8px';
+  const summary = document.createElement('summary');
+  summary.style.cssText = 'cursor:pointer; font-size:13px';
+  summary.textContent = 'Archived sessions';
+  panel.appendChild(summary);
+
+  const body = document.createElement('div');
+  body.style.cssText = 'margin-top:8px; display:flex; flex-direction:column; gap:8px';
+
+  // Policy: idle days before archival (0 = never) + manual run
+  const config = await pawEngine.getConfig();
+  const policyRow = document.createElement('div');
+  policyRow.style.cssText = 'display:flex; gap:6px; align-items:center; flex-wrap:wrap';
+  const policyLabel = document.createElement('span');
+  policyLabel.style.cssText = 'color:var(--text-muted); font-size:13px';
+  policyLabel.textContent = 'Archive sessions idle for';
+  const daysInp = document.createElement('input');
+  daysInp.type = 'number';
+  daysInp.min = '0';
+  daysInp.className = 'form-input';
+  daysInp.style.cssText = 'width:80px; font-size:13px';
+  daysInp.value = String(config.session_archive_days ?? 0);
+  const daysUnit = document.createElement('span');
+  daysUnit.style.cssText = 'color:var(--text-muted); font-size:13px';
+  daysUnit.textContent = 'days (0 = never)';
+  const saveBtn = document.createElement('button');
+  saveBtn.className = 'btn btn-sm';
+  saveBtn.textContent = 'Save';
+  saveBtn.onclick = async () => {
+    try {
+      const cfg = await pawEngine.getConfig();
+      cfg.session_archive_days = Math.max(0, parseInt(daysInp.value, 10) || 0);
+      await pawEngine.setConfig(cfg);
+      showToast('Archive policy saved', 'success');
+    } catch (e: unknown) {
+      showToast(e instanceof Error ? e.message : String(e), 'error');
+    }
+  };
+  const runBtn = document.createElement('button');
+  runBtn.className = 'btn btn-sm';
+  runBtn.textContent = 'Archive Now';
+  runBtn.onclick = async () => {
+    const days = parseInt(daysInp.value, 10) || 0;
+    if (days <= 0) {
+      showToast('Set how many idle days first', 'error');
+      return;
+    }
+    try {
+      const report = await pawEngine.sessionsArchive(days);
+      showToast(
+        `Archived ${report.sessions} session${report.sessions !== 1 ? 's' : ''}`,
+        'success',
+      );
+      loadSessionsSettings();
+    } catch (e: unknown) {
+      showToast(e instanceof Error ? e.message : String(e), 'error');
+    }
+  };
+  policyRow.append(policyLabel, daysInp, daysUnit, saveBtn, runBtn);
+  body.appendChild(policyRow);
+
+  // Search (empty query lists everything)
+  const searchRow = document.createElement('div');
+  searchRow.style.cssText = 'display:flex; gap:6px; align-items:center';
+  const searchInp = document.createElement('input');
+  searchInp.type = 'text';
+  searchInp.className = 'form-input';
+  searchInp.placeholder = 'Search archived sessions…';
+  searchInp.style.cssText = 'flex:1; max-width:320px; font-size:13px';
+  const searchBtn = document.createElement('button');
+  searchBtn.className = 'btn btn-sm btn-primary';
+  searchBtn.textContent = 'Search';
+  searchRow.append(searchInp, searchBtn);
+  body.appendChild(searchRow);
+
+  const results = document.createElement('div');
+  results.style.cssText = 'display:flex; flex-direction:column; gap:6px';
+  body.appendChild(results);
+
+  const search = async () => {
+    results.innerHTML = '<p style="color:var(--text-muted); font-size:13px">Searching…</p>';
+    try {
+      const query = searchInp.value.trim();
+      const found = query
+        ? await pawEngine.sessionsArchivedSearch(query)
+        : await pawEngine.sessionsArchivedList();
+      results.innerHTML = '';
+      if (found.length === 0) {
+        results.innerHTML = '<p style="color:var(--text-muted); font-size:13px">No archived sessions found.</p>';
+        return;
+      }
+      for (const sess of found) results.appendChild(buildArchivedRow(sess));
+    } catch (e: unknown) {
+      results.innerHTML = `<p style="color:var(--danger)">${esc(e instanceof Error ? e.message : String(e))}</p>`;
+    }
+  };
+  searchBtn.onclick = search;
+  searchInp.onkeydown = (e) => {
+    if (e.key === 'Enter') search();
+  };
+
+  panel.appendChild(body);
+  return panel;
+}
+
+function buildArchivedRow(sess: ArchivedSession): HTMLElement {
+  const row = document.createElement('div');
+  row.style.cssText =
+    'display:flex; justify-content:space-between; align-items:start; gap:8px; padding:8px; border:1px solid var(--border-color); border-radius:6px';
+  const label = sess.label || 'Untitled chat';
+  const info = document.createElement('div');
+  info.style.cssText = 'flex:1; min-width:0';
+  info.innerHTML = `
+    <strong style="font-size:13px">${esc(label)}</strong>
+    <div style="color:var(--text-muted); font-size:12px; margin-top:2px">
+      <span class="badge" style="font-size:11px">${esc(sess.archive)}</span>
+      <span style="margin-left:6px">${sess.message_count} messages</span>
+      <span style="margin-left:6px">${esc(formatDateTime(sess.updated_at))}</span>
+    </div>
+    ${sess.snippet ? `<div style="font-size:12px; margin-top:4px">${esc(sess.snippet)}</div>` : ''}
+  `;
+  const restoreBtn = document.createElement('button');
+  restoreBtn.className = 'btn btn-sm';
+  restoreBtn.textContent = 'Restore';
+  restoreBtn.onclick = async () => {
+    try {
+      await pawEngine.sessionRestore(sess.id);
+      showToast(`Session "${label}" restored`, 'success');
+      loadSessionsSettings();
+    } catch (e: unknown) {
+      showToast(e instanceof Error ? e.message : String(e), 'error');
+    }
+  };
+  row.append(info, restoreBtn);
+  return row;
+}
+
 // ── Session card ────────────────────────────────────────────────────────────
 
 function buildSessionCard(sess: EngineSession): HTMLElement {
@@ -70,7 +213,7 @@ function buildSessionCard(sess: EngineSession): HTMLElement {
   card.style.cssText =
     'margin-bottom:10px; padding:12px; border:1px solid var(--border-color); border-radius:8px';
 
-  const ts = sess.updated_at ? new Date(sess.updated_at).toLocaleString() : '—';
+  const ts = sess.updated_at ? formatDateTime(sess.updated_at) : '—';
   const label = sess.label || (sess.message_count > 0 ? 'Untitled chat' : 'Empty session');
 
   // Header row
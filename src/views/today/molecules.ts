@@ -106,15 +106,14 @@ export function initMoleculesState() {
 
 // ── Weather ───────────────────────────────────────────────────────────
 
-/** Save weather_location to engine config. */
+/** Geocode and save the home location (also used for weather). */
 async function saveWeatherLocation(location: string) {
   try {
     const { pawEngine: pe } = await import('../../engine');
-    const cfg = await pe.getConfig();
-    cfg.weather_location = location;
-    await pe.setConfig(cfg);
+    await pe.locationSet(location);
   } catch (e) {
-    console.warn('[today] Failed to save weather location:', e);
+    console.warn('[today] Failed to save home location:', e);
+    showToast(`Could not find "${location}"`, 'error');
   }
 }
 